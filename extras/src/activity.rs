@@ -0,0 +1,290 @@
+//! Activity counter handler for BRP extras
+//!
+//! Screenshots taken while a level is still loading or an animation is still playing make for
+//! flaky results. A cheap counter runs at the end of every frame and adds up that frame's changes:
+//! entities spawned or despawned, `Transform`s changed and UI nodes laid out again. It keeps the
+//! counts of the last `HISTORY_FRAMES` frames. `brp_extras/activity` reports them along with
+//! `quiet_frames`, the number of most recent frames whose changes stayed at or under
+//! `max_changes`. The app has settled once that reaches the number of frames the client wants.
+//!
+//! Frames stay quiet while an asset is still loading in the background, so the report also
+//! counts `loading_assets`: assets behind handles held by entities or resources that the
+//! `AssetServer` is still loading, dependencies included. They are looked up through
+//! reflection when the report is requested, not every frame.
+
+use std::collections::HashSet;
+use std::collections::VecDeque;
+
+use bevy::asset::RecursiveDependencyLoadState;
+use bevy::asset::ReflectHandle;
+use bevy::asset::UntypedAssetId;
+use bevy::ecs::component::ComponentInfo;
+use bevy::ecs::query::Spawned;
+use bevy::prelude::*;
+use bevy::reflect::ReflectRef;
+use bevy::reflect::TypeRegistry;
+use bevy::remote::BrpError;
+use bevy::remote::BrpResult;
+use bevy::remote::error_codes::INVALID_PARAMS;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+use serde_json::json;
+
+/// Frames of change counts kept - also the most `quiet_frames` can report
+const HISTORY_FRAMES: usize = 600;
+
+/// Request structure for `activity`
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ActivityRequest {
+    /// Most changes a frame may have and still count as quiet (default: 0)
+    #[serde(default)]
+    pub max_changes: u64,
+}
+
+/// Response to `activity`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActivityResponse {
+    /// Frames counted since the plugin was added
+    pub frame:          u64,
+    /// Changes in the last counted frame
+    pub frame_changes:  u64,
+    /// Most recent frames in a row with at most `max_changes` changes
+    pub quiet_frames:   usize,
+    /// Frames of history `quiet_frames` was counted over
+    pub history_frames: usize,
+    /// Entities alive at the end of the last counted frame
+    pub entities:       usize,
+    /// Assets held by entities or resources that are still loading
+    pub loading_assets: usize,
+}
+
+/// Resource holding the change counts of recent frames
+#[derive(Resource, Default)]
+pub struct ActivityCounter {
+    frame:    u64,
+    entities: usize,
+    /// Changes per frame, most recent last
+    history:  VecDeque<u64>,
+}
+
+/// Handler for `activity` requests
+pub fn handler(In(params): In<Option<Value>>, world: &mut World) -> BrpResult {
+    let request: ActivityRequest = params
+        .map(serde_json::from_value)
+        .transpose()
+        .map_err(|e| BrpError {
+            code:    INVALID_PARAMS,
+            message: format!("Invalid request format: {e}"),
+            data:    None,
+        })?
+        .unwrap_or_default();
+
+    let counter = world.resource::<ActivityCounter>();
+    let quiet_frames = counter
+        .history
+        .iter()
+        .rev()
+        .take_while(|&&changes| changes <= request.max_changes)
+        .count();
+    Ok(json!(ActivityResponse {
+        frame: counter.frame,
+        frame_changes: counter.history.back().copied().unwrap_or(0),
+        quiet_frames,
+        history_frames: counter.history.len(),
+        entities: counter.entities,
+        loading_assets: loading_assets(world),
+    }))
+}
+
+/// Assets behind handles held by reflected components and resources that the asset server is
+/// still loading, with their dependencies - assets that failed to load aren't counted
+fn loading_assets(world: &World) -> usize {
+    let (Some(asset_server), Some(registry)) = (
+        world.get_resource::<AssetServer>(),
+        world.get_resource::<AppTypeRegistry>(),
+    ) else {
+        return 0;
+    };
+
+    held_handles(world, &registry.read())
+        .into_iter()
+        .filter(|&id| {
+            matches!(
+                asset_server.get_recursive_dependency_load_state(id),
+                Some(RecursiveDependencyLoadState::Loading)
+            )
+        })
+        .count()
+}
+
+/// IDs of the asset handles in every reflected component and resource
+fn held_handles(world: &World, registry: &TypeRegistry) -> HashSet<UntypedAssetId> {
+    let mut handles = HashSet::new();
+    for archetype in world.archetypes().iter() {
+        for &component_id in archetype.components() {
+            let Some(reflect_component) = world
+                .components()
+                .get_info(component_id)
+                .and_then(ComponentInfo::type_id)
+                .and_then(|type_id| registry.get_type_data::<ReflectComponent>(type_id))
+            else {
+                continue;
+            };
+            for archetype_entity in archetype.entities() {
+                if let Some(component) = world
+                    .get_entity(archetype_entity.id())
+                    .ok()
+                    .and_then(|entity| reflect_component.reflect(entity))
+                {
+                    collect_handles(component.as_partial_reflect(), registry, &mut handles);
+                }
+            }
+        }
+    }
+    for registration in registry.iter() {
+        if let Some(resource) = registration
+            .data::<ReflectResource>()
+            .and_then(|reflect_resource| reflect_resource.reflect(world).ok())
+        {
+            collect_handles(resource.as_partial_reflect(), registry, &mut handles);
+        }
+    }
+    handles
+}
+
+/// Add the IDs of the asset handles in `value` and its fields to `handles`
+// Opaque values hold no fields - and neither do functions, reflected with `reflect_functions`
+#[allow(clippy::match_wildcard_for_single_variants)]
+fn collect_handles(
+    value: &dyn PartialReflect,
+    registry: &TypeRegistry,
+    handles: &mut HashSet<UntypedAssetId>,
+) {
+    if let Some(value) = value.try_as_reflect()
+        && let Some(reflect_handle) = registry.get_type_data::<ReflectHandle>(value.type_id())
+    {
+        if let Some(handle) = reflect_handle.downcast_handle_untyped(value.as_any()) {
+            handles.insert(handle.id());
+        }
+        return;
+    }
+
+    let mut visit = |field: &dyn PartialReflect| collect_handles(field, registry, handles);
+    match value.reflect_ref() {
+        ReflectRef::Struct(value) => value.iter_fields().for_each(&mut visit),
+        ReflectRef::TupleStruct(value) => value.iter_fields().for_each(&mut visit),
+        ReflectRef::Tuple(value) => value.iter_fields().for_each(&mut visit),
+        ReflectRef::List(value) => value.iter().for_each(&mut visit),
+        ReflectRef::Array(value) => value.iter().for_each(&mut visit),
+        ReflectRef::Map(value) => value.iter().for_each(|(_, field)| visit(field)),
+        ReflectRef::Set(value) => value.iter().for_each(&mut visit),
+        ReflectRef::Enum(value) => value.iter_fields().for_each(|field| visit(field.value())),
+        _ => {},
+    }
+}
+
+/// System adding up each frame's changes
+///
+/// Spawns are counted directly and despawns from the entity count they leave, so a frame that
+/// spawns one entity and despawns another counts both.
+pub fn count_activity(
+    mut counter: ResMut<ActivityCounter>,
+    alive: Query<()>,
+    spawned: Query<(), Spawned>,
+    transforms: Query<(), Changed<Transform>>,
+    nodes: Query<(), Changed<ComputedNode>>,
+) {
+    // Counted through a query so internal entities are skipped, as they are by `spawned`
+    let entities = alive.iter().count();
+    // The first frame has nothing to compare with - everything alive counts as spawned
+    let spawned_or_despawned = if counter.frame == 0 {
+        0
+    } else {
+        let spawned = spawned.iter().count();
+        let despawned = (counter.entities + spawned).saturating_sub(entities);
+        spawned + despawned
+    };
+    let changes = transforms.iter().count() + nodes.iter().count() + spawned_or_despawned;
+
+    counter.frame += 1;
+    counter.entities = entities;
+    if counter.history.len() == HISTORY_FRAMES {
+        counter.history.pop_front();
+    }
+    counter
+        .history
+        .push_back(u64::try_from(changes).unwrap_or(u64::MAX));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn count(world: &mut World) {
+        world
+            .run_system_cached(count_activity)
+            .unwrap_or_else(|error| unreachable!("counter should run: {error}"));
+        // Start a new tick so changes made between counts are newer than the last run
+        world.increment_change_tick();
+    }
+
+    fn activity(world: &mut World, max_changes: u64) -> Value {
+        world
+            .run_system_cached_with(handler, Some(json!({ "max_changes": max_changes })))
+            .unwrap_or_else(|error| unreachable!("activity handler should run: {error}"))
+            .unwrap_or_else(|error| unreachable!("activity request should succeed: {error:?}"))
+    }
+
+    #[test]
+    fn quiet_frames_count_back_from_the_latest_busy_frame() {
+        let mut world = World::new();
+        world.init_resource::<ActivityCounter>();
+        count(&mut world);
+        world.spawn(Transform::default());
+        count(&mut world);
+        world.spawn(Transform::default());
+        count(&mut world);
+        count(&mut world);
+        count(&mut world);
+
+        let report = activity(&mut world, 0);
+        assert_eq!(report["frame"], json!(5));
+        assert_eq!(report["frame_changes"], json!(0));
+        assert_eq!(report["quiet_frames"], json!(2));
+        // Each spawn changed a transform and the entity count
+        assert_eq!(activity(&mut world, 2)["quiet_frames"], json!(5));
+    }
+
+    #[test]
+    fn a_spawn_and_a_despawn_in_one_frame_both_count() {
+        let mut world = World::new();
+        world.init_resource::<ActivityCounter>();
+        let doomed = world.spawn_empty().id();
+        count(&mut world);
+        count(&mut world);
+        let entities = activity(&mut world, 0)["entities"].clone();
+
+        world.despawn(doomed);
+        world.spawn_empty();
+        count(&mut world);
+
+        let report = activity(&mut world, 0);
+        assert_eq!(report["frame_changes"], json!(2));
+        assert_eq!(report["entities"], entities);
+    }
+
+    #[test]
+    fn finds_handles_inside_reflected_values() {
+        let mut registry = TypeRegistry::default();
+        registry.register::<Handle<Image>>();
+        registry.register_type_data::<Handle<Image>, ReflectHandle>();
+        let first = Handle::<Image>::default();
+        let value = (vec![first.clone(), first], Some(Handle::<Image>::default()));
+
+        let mut handles = HashSet::new();
+        collect_handles(&value, &registry, &mut handles);
+        assert_eq!(handles.len(), 1);
+    }
+}
@@ -0,0 +1,291 @@
+//! App introspection handler for BRP extras
+//!
+//! `brp_extras/app_info` answers which app is on the other end of the port: its executable and
+//! process, the Bevy plugins it added, how it was built, and the version and features of this
+//! crate. Bevy has no public list of added plugins, so the plugins reported are Bevy's own ones
+//! found with `App::is_plugin_added` once every plugin is built - the app's own plugins aren't
+//! listed.
+//!
+//! This crate can't see which Bevy cargo features the app enabled - `cfg!` only sees its own - so
+//! the app reports them, along with a git commit and build timestamp, through
+//! [`BrpExtrasPlugin::with_build_info`]. Without it the commit and timestamp fall back to
+//! `BRP_EXTRAS_GIT_COMMIT` and `BRP_EXTRAS_BUILD_TIMESTAMP`, when they're set while the app is
+//! compiled, e.g. by a build script or CI.
+//! The app's [`BrpTopology`] says which port and launch instance it is, and which app spawned it
+//! and which children it spawned.
+//!
+//! [`BrpExtrasPlugin::with_build_info`]: crate::BrpExtrasPlugin::with_build_info
+
+use bevy::prelude::*;
+use bevy::remote::BrpError;
+use bevy::remote::BrpResult;
+use bevy::remote::error_codes::INTERNAL_ERROR;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+use serde_json::json;
+
+use crate::topology::BrpTopology;
+
+/// Whether a plugin was added to an app
+type PluginCheck = fn(&App) -> bool;
+
+/// Bevy plugins looked for by `brp_extras/app_info`, by name
+const KNOWN_PLUGINS: &[(&str, PluginCheck)] = &[
+    ("LogPlugin", App::is_plugin_added::<bevy::log::LogPlugin>),
+    (
+        "TaskPoolPlugin",
+        App::is_plugin_added::<bevy::app::TaskPoolPlugin>,
+    ),
+    (
+        "FrameCountPlugin",
+        App::is_plugin_added::<bevy::diagnostic::FrameCountPlugin>,
+    ),
+    ("TimePlugin", App::is_plugin_added::<bevy::time::TimePlugin>),
+    (
+        "TransformPlugin",
+        App::is_plugin_added::<bevy::transform::TransformPlugin>,
+    ),
+    (
+        "DiagnosticsPlugin",
+        App::is_plugin_added::<bevy::diagnostic::DiagnosticsPlugin>,
+    ),
+    (
+        "FrameTimeDiagnosticsPlugin",
+        App::is_plugin_added::<bevy::diagnostic::FrameTimeDiagnosticsPlugin>,
+    ),
+    (
+        "InputPlugin",
+        App::is_plugin_added::<bevy::input::InputPlugin>,
+    ),
+    (
+        "WindowPlugin",
+        App::is_plugin_added::<bevy::window::WindowPlugin>,
+    ),
+    (
+        "AccessibilityPlugin",
+        App::is_plugin_added::<bevy::a11y::AccessibilityPlugin>,
+    ),
+    (
+        "AssetPlugin",
+        App::is_plugin_added::<bevy::asset::AssetPlugin>,
+    ),
+    (
+        "ScenePlugin",
+        App::is_plugin_added::<bevy::scene::ScenePlugin>,
+    ),
+    #[cfg(feature = "window")]
+    (
+        "WinitPlugin",
+        App::is_plugin_added::<bevy::winit::WinitPlugin>,
+    ),
+    (
+        "RenderPlugin",
+        App::is_plugin_added::<bevy::render::RenderPlugin>,
+    ),
+    (
+        "ImagePlugin",
+        App::is_plugin_added::<bevy::image::ImagePlugin>,
+    ),
+    (
+        "CorePipelinePlugin",
+        App::is_plugin_added::<bevy::core_pipeline::CorePipelinePlugin>,
+    ),
+    (
+        "SpritePlugin",
+        App::is_plugin_added::<bevy::sprite::SpritePlugin>,
+    ),
+    ("TextPlugin", App::is_plugin_added::<bevy::text::TextPlugin>),
+    ("UiPlugin", App::is_plugin_added::<bevy::ui::UiPlugin>),
+    ("PbrPlugin", App::is_plugin_added::<bevy::pbr::PbrPlugin>),
+    ("GltfPlugin", App::is_plugin_added::<bevy::gltf::GltfPlugin>),
+    (
+        "AudioPlugin",
+        App::is_plugin_added::<bevy::audio::AudioPlugin>,
+    ),
+    (
+        "GilrsPlugin",
+        App::is_plugin_added::<bevy::gilrs::GilrsPlugin>,
+    ),
+    (
+        "AnimationPlugin",
+        App::is_plugin_added::<bevy::animation::AnimationPlugin>,
+    ),
+    (
+        "GizmoPlugin",
+        App::is_plugin_added::<bevy::gizmos::GizmoPlugin>,
+    ),
+    (
+        "StatesPlugin",
+        App::is_plugin_added::<bevy::state::app::StatesPlugin>,
+    ),
+    (
+        "PickingPlugin",
+        App::is_plugin_added::<bevy::picking::PickingPlugin>,
+    ),
+    (
+        "InputDispatchPlugin",
+        App::is_plugin_added::<bevy::input_focus::InputDispatchPlugin>,
+    ),
+    (
+        "RemotePlugin",
+        App::is_plugin_added::<bevy::remote::RemotePlugin>,
+    ),
+    (
+        "RemoteHttpPlugin",
+        App::is_plugin_added::<bevy::remote::http::RemoteHttpPlugin>,
+    ),
+];
+
+/// This crate's optional features and whether each is enabled
+const EXTRAS_FEATURES: &[(&str, bool)] = &[
+    ("binary-transport", cfg!(feature = "binary-transport")),
+    ("clipboard", cfg!(feature = "clipboard")),
+    ("compression", cfg!(feature = "compression")),
+    ("screenshot", cfg!(feature = "screenshot")),
+    ("window", cfg!(feature = "window")),
+];
+
+/// How the app was built, as the app reports it to `brp_extras/app_info`
+///
+/// ```ignore
+/// BrpExtrasPlugin::default().with_build_info(BuildInfo {
+///     bevy_features: vec!["bevy_pbr".to_string(), "bevy_ui".to_string()],
+///     git_commit: option_env!("GIT_COMMIT").map(String::from),
+///     ..default()
+/// })
+/// ```
+#[derive(Resource, Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuildInfo {
+    /// Bevy cargo features the app enabled
+    pub bevy_features:   Vec<String>,
+    /// Commit the app was built from
+    pub git_commit:      Option<String>,
+    /// When the app was built
+    pub build_timestamp: Option<String>,
+}
+
+/// Bevy plugins found in the app, recorded when `BrpExtrasPlugin` finishes
+#[derive(Resource, Debug, Clone, Default)]
+pub struct AddedPlugins(pub Vec<String>);
+
+impl AddedPlugins {
+    /// Look for the known Bevy plugins in `app`
+    pub fn detect(app: &App) -> Self {
+        Self(
+            KNOWN_PLUGINS
+                .iter()
+                .filter(|(_, is_added)| is_added(app))
+                .map(|(name, _)| (*name).to_string())
+                .collect(),
+        )
+    }
+}
+
+/// The app, as reported by `brp_extras/app_info`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppInfo {
+    /// File name of the running executable
+    pub executable:      Option<String>,
+    /// Process ID
+    pub pid:             u32,
+    /// Bevy plugins added to the app - only Bevy's own plugins are detected
+    pub plugins:         Vec<String>,
+    /// `debug` when built with debug assertions, otherwise `release`
+    pub build_profile:   String,
+    /// Bevy cargo features from `with_build_info` - empty when the app didn't report them
+    pub bevy_features:   Vec<String>,
+    /// Commit from `with_build_info`, else `BRP_EXTRAS_GIT_COMMIT` at compile time
+    pub git_commit:      Option<String>,
+    /// Timestamp from `with_build_info`, else `BRP_EXTRAS_BUILD_TIMESTAMP` at compile time
+    pub build_timestamp: Option<String>,
+    /// Version of `bevy_brp_extras`
+    pub extras_version:  String,
+    /// Enabled features of `bevy_brp_extras`
+    pub extras_features: Vec<String>,
+    /// Port, host, launch instance, parent and children of the app
+    pub topology:        Option<BrpTopology>,
+}
+
+/// Handler for `app_info` requests
+pub fn handler(In(_params): In<Option<Value>>, world: &mut World) -> BrpResult {
+    let plugins = world
+        .get_resource::<AddedPlugins>()
+        .ok_or_else(|| BrpError {
+            code:    INTERNAL_ERROR,
+            message: "Added plugins haven't been recorded - the app hasn't finished building"
+                .to_string(),
+            data:    None,
+        })?
+        .0
+        .clone();
+
+    let build_info = world
+        .get_resource::<BuildInfo>()
+        .cloned()
+        .unwrap_or_default();
+    let info = AppInfo {
+        executable: std::env::current_exe().ok().and_then(|path| {
+            path.file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+        }),
+        pid: std::process::id(),
+        plugins,
+        build_profile: if cfg!(debug_assertions) {
+            "debug"
+        } else {
+            "release"
+        }
+        .to_string(),
+        bevy_features: build_info.bevy_features,
+        git_commit: build_info
+            .git_commit
+            .or_else(|| option_env!("BRP_EXTRAS_GIT_COMMIT").map(String::from)),
+        build_timestamp: build_info
+            .build_timestamp
+            .or_else(|| option_env!("BRP_EXTRAS_BUILD_TIMESTAMP").map(String::from)),
+        extras_version: env!("CARGO_PKG_VERSION").to_string(),
+        extras_features: EXTRAS_FEATURES
+            .iter()
+            .filter(|(_, enabled)| *enabled)
+            .map(|(name, _)| (*name).to_string())
+            .collect(),
+        topology: world.get_resource::<BrpTopology>().cloned(),
+    };
+
+    Ok(json!(info))
+}
+
+#[cfg(test)]
+#[allow(clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    fn app_info(world: &mut World) -> AppInfo {
+        world.insert_resource(AddedPlugins(vec!["RemotePlugin".to_string()]));
+        let info = handler(In(None), world).expect("app info");
+        serde_json::from_value(info).expect("app info shape")
+    }
+
+    #[test]
+    fn reports_the_apps_build_info() {
+        let mut world = World::new();
+        world.insert_resource(BuildInfo {
+            bevy_features:   vec!["bevy_pbr".to_string()],
+            git_commit:      Some("abc1234".to_string()),
+            build_timestamp: None,
+        });
+
+        let info = app_info(&mut world);
+        assert_eq!(info.bevy_features, ["bevy_pbr"]);
+        assert_eq!(info.git_commit.as_deref(), Some("abc1234"));
+        assert_eq!(info.plugins, ["RemotePlugin"]);
+    }
+
+    #[test]
+    fn bevy_features_are_empty_without_build_info() {
+        let info = app_info(&mut World::new());
+        assert!(info.bevy_features.is_empty());
+        assert_eq!(info.extras_version, env!("CARGO_PKG_VERSION"));
+    }
+}
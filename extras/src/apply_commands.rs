@@ -0,0 +1,519 @@
+//! Atomic command batch handler for BRP extras
+//!
+//! Separate BRP calls let the app run frames between them, so systems can observe a half-applied
+//! edit - a spawned entity still missing its components, or a reparent without the matching
+//! insert. `brp_extras/apply_commands` takes a list of spawn, insert, remove, despawn and
+//! reparent commands, checks all of them first, and applies them in a single command flush. Either
+//! every command is applied or, when any is invalid, none is.
+//!
+//! Commands refer to entities spawned earlier in the same batch as `"$N"`, where `N` is the index
+//! of the `spawn` command.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+use bevy::ecs::reflect::ReflectCommandExt;
+use bevy::ecs::reflect::ReflectComponent;
+use bevy::ecs::world::CommandQueue;
+use bevy::prelude::*;
+use bevy::reflect::PartialReflect;
+use bevy::reflect::TypeRegistry;
+use bevy::reflect::serde::TypedReflectDeserializer;
+use bevy::remote::BrpError;
+use bevy::remote::BrpResult;
+use bevy::remote::error_codes::INVALID_PARAMS;
+use serde::Deserialize;
+use serde::de::DeserializeSeed;
+use serde_json::Value;
+use serde_json::json;
+
+/// Commands accepted in one batch at most
+const MAX_BATCH_COMMANDS: usize = 1_000;
+
+/// Request structure for `apply_commands`
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ApplyCommandsRequest {
+    /// Commands to apply, in order
+    pub commands: Vec<EcsCommand>,
+}
+
+/// A command in a batch
+#[derive(Debug, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum EcsCommand {
+    /// Spawn an entity
+    Spawn {
+        /// Components to spawn it with, by full type path
+        #[serde(default)]
+        components: HashMap<String, Value>,
+        /// Parent to spawn it under
+        #[serde(default)]
+        parent:     Option<EntityTarget>,
+    },
+    /// Insert components into an entity, replacing those it already has
+    Insert {
+        /// The entity
+        entity:     EntityTarget,
+        /// Components to insert, by full type path
+        components: HashMap<String, Value>,
+    },
+    /// Remove components from an entity
+    Remove {
+        /// The entity
+        entity:     EntityTarget,
+        /// Full type paths of the components to remove
+        components: Vec<String>,
+    },
+    /// Despawn an entity and its descendants
+    Despawn {
+        /// The entity
+        entity: EntityTarget,
+    },
+    /// Move an entity under a new parent, or make it a root
+    Reparent {
+        /// The entity
+        entity: EntityTarget,
+        /// The new parent - omit or `null` to remove the entity's parent
+        #[serde(default)]
+        parent: Option<EntityTarget>,
+    },
+}
+
+/// An entity a command applies to
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum EntityTarget {
+    /// An entity that exists in the world
+    Existing(Entity),
+    /// `"$N"` - the entity spawned by command `N` of the batch
+    Spawned(String),
+}
+
+/// An entity of a checked command - either existing or spawned by the batch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Target {
+    Existing(Entity),
+    Spawned(usize),
+}
+
+/// A command checked against the world, with its components deserialized
+enum CheckedCommand {
+    Spawn {
+        components: Vec<Box<dyn PartialReflect>>,
+        parent:     Option<Target>,
+    },
+    Insert {
+        entity:     Target,
+        components: Vec<Box<dyn PartialReflect>>,
+    },
+    Remove {
+        entity:     Target,
+        components: Vec<String>,
+    },
+    Despawn {
+        entity: Target,
+    },
+    Reparent {
+        entity: Target,
+        parent: Option<Target>,
+    },
+}
+
+/// Handler for `apply_commands` requests
+pub fn handler(In(params): In<Option<Value>>, world: &mut World) -> BrpResult {
+    let request: ApplyCommandsRequest = params
+        .ok_or_else(|| BrpError {
+            code:    INVALID_PARAMS,
+            message: "Missing request parameters".to_string(),
+            data:    None,
+        })
+        .and_then(|params| {
+            serde_json::from_value(params).map_err(|e| BrpError {
+                code:    INVALID_PARAMS,
+                message: format!("Invalid request format: {e}"),
+                data:    None,
+            })
+        })?;
+    if request.commands.len() > MAX_BATCH_COMMANDS {
+        return Err(BrpError {
+            code:    INVALID_PARAMS,
+            message: format!(
+                "{} commands given - apply at most {MAX_BATCH_COMMANDS} per batch",
+                request.commands.len()
+            ),
+            data:    None,
+        });
+    }
+
+    let checked = {
+        let registry = world.resource::<AppTypeRegistry>().read();
+        check_commands(world, &registry, request.commands)
+    }
+    .map_err(|message| BrpError {
+        code: INVALID_PARAMS,
+        message,
+        data: None,
+    })?;
+
+    let command_count = checked.len();
+    let mut queue = CommandQueue::default();
+    let mut spawned: HashMap<usize, Entity> = HashMap::new();
+    {
+        let mut commands = Commands::new(&mut queue, world);
+        for (index, command) in checked.into_iter().enumerate() {
+            let resolve = |target: Target| match target {
+                Target::Existing(entity) => entity,
+                Target::Spawned(spawn) => spawned[&spawn],
+            };
+            match command {
+                CheckedCommand::Spawn { components, parent } => {
+                    let parent = parent.map(resolve);
+                    let mut entity = commands.spawn_empty();
+                    for component in components {
+                        entity.insert_reflect(component);
+                    }
+                    if let Some(parent) = parent {
+                        entity.insert(ChildOf(parent));
+                    }
+                    spawned.insert(index, entity.id());
+                },
+                CheckedCommand::Insert { entity, components } => {
+                    let mut entity = commands.entity(resolve(entity));
+                    for component in components {
+                        entity.insert_reflect(component);
+                    }
+                },
+                CheckedCommand::Remove { entity, components } => {
+                    let mut entity = commands.entity(resolve(entity));
+                    for component in components {
+                        entity.remove_reflect(component);
+                    }
+                },
+                CheckedCommand::Despawn { entity } => {
+                    commands.entity(resolve(entity)).despawn();
+                },
+                CheckedCommand::Reparent { entity, parent } => {
+                    let parent = parent.map(resolve);
+                    let mut entity = commands.entity(resolve(entity));
+                    match parent {
+                        Some(parent) => entity.insert(ChildOf(parent)),
+                        None => entity.remove::<ChildOf>(),
+                    };
+                },
+            }
+        }
+    }
+    queue.apply(world);
+
+    let mut spawned: Vec<Value> = spawned
+        .into_iter()
+        .map(|(index, entity)| json!({ "index": index, "entity": entity.to_bits() }))
+        .collect();
+    spawned.sort_by_key(|spawn| spawn["index"].as_u64());
+    Ok(json!({
+        "applied": command_count,
+        "spawned": spawned,
+    }))
+}
+
+/// Check every command against the world and deserialize its components - the first problem
+/// found is returned, naming the command
+fn check_commands(
+    world: &World,
+    registry: &TypeRegistry,
+    commands: Vec<EcsCommand>,
+) -> Result<Vec<CheckedCommand>, String> {
+    let mut spawns: HashSet<usize> = HashSet::new();
+    let mut despawned: HashSet<Target> = HashSet::new();
+    let mut hierarchy = Hierarchy {
+        world,
+        parents: HashMap::new(),
+    };
+    let mut checked = Vec::with_capacity(commands.len());
+
+    for (index, command) in commands.into_iter().enumerate() {
+        let in_command = |error: String| format!("Command {index}: {error}");
+        let target = |target: &EntityTarget| -> Result<Target, String> {
+            let target = match target {
+                EntityTarget::Existing(entity) => {
+                    if world.get_entity(*entity).is_err() {
+                        return Err(format!("entity {entity} doesn't exist"));
+                    }
+                    Target::Existing(*entity)
+                },
+                EntityTarget::Spawned(reference) => reference
+                    .strip_prefix('$')
+                    .and_then(|spawn| spawn.parse().ok())
+                    .filter(|spawn| spawns.contains(spawn))
+                    .map(Target::Spawned)
+                    .ok_or_else(|| {
+                        format!("`{reference}` doesn't name an earlier spawn command as `$N`")
+                    })?,
+            };
+            if despawned.contains(&target) {
+                return Err("entity was despawned by an earlier command".to_string());
+            }
+            Ok(target)
+        };
+
+        let command = match command {
+            EcsCommand::Spawn { components, parent } => CheckedCommand::Spawn {
+                components: deserialize_components(registry, &components).map_err(in_command)?,
+                parent:     parent
+                    .as_ref()
+                    .map(target)
+                    .transpose()
+                    .map_err(in_command)?,
+            },
+            EcsCommand::Insert { entity, components } => CheckedCommand::Insert {
+                entity:     target(&entity).map_err(in_command)?,
+                components: deserialize_components(registry, &components).map_err(in_command)?,
+            },
+            EcsCommand::Remove { entity, components } => {
+                for type_path in &components {
+                    component_registration(registry, type_path).map_err(in_command)?;
+                }
+                CheckedCommand::Remove {
+                    entity: target(&entity).map_err(in_command)?,
+                    components,
+                }
+            },
+            EcsCommand::Despawn { entity } => {
+                let entity = target(&entity).map_err(in_command)?;
+                CheckedCommand::Despawn { entity }
+            },
+            EcsCommand::Reparent { entity, parent } => {
+                let entity = target(&entity).map_err(in_command)?;
+                let parent = parent
+                    .as_ref()
+                    .map(target)
+                    .transpose()
+                    .map_err(in_command)?;
+                if let Some(parent) = parent
+                    && (parent == entity || hierarchy.is_ancestor(entity, parent))
+                {
+                    return Err(in_command(
+                        "an entity can't be moved under itself or its own descendant".to_string(),
+                    ));
+                }
+                CheckedCommand::Reparent { entity, parent }
+            },
+        };
+
+        match &command {
+            CheckedCommand::Spawn { parent, .. } => {
+                spawns.insert(index);
+                hierarchy.parents.insert(Target::Spawned(index), *parent);
+            },
+            CheckedCommand::Reparent { entity, parent } => {
+                hierarchy.parents.insert(*entity, *parent);
+            },
+            // Despawning takes the entity's descendants with it, so later commands can't use
+            // them either
+            CheckedCommand::Despawn { entity } => {
+                despawned.extend(hierarchy.descendants(*entity));
+                despawned.insert(*entity);
+            },
+            CheckedCommand::Insert { .. } | CheckedCommand::Remove { .. } => {},
+        }
+        checked.push(command);
+    }
+    Ok(checked)
+}
+
+/// The hierarchy as the batch leaves it so far - the world's `ChildOf` links with the parents
+/// set by earlier spawn and reparent commands on top
+struct Hierarchy<'w> {
+    world:   &'w World,
+    /// Parents set by the batch, by entity
+    parents: HashMap<Target, Option<Target>>,
+}
+
+impl Hierarchy<'_> {
+    fn parent(&self, target: Target) -> Option<Target> {
+        match (self.parents.get(&target), target) {
+            (Some(parent), _) => *parent,
+            (None, Target::Existing(entity)) => self
+                .world
+                .get::<ChildOf>(entity)
+                .map(|child_of| Target::Existing(child_of.parent())),
+            (None, Target::Spawned(_)) => None,
+        }
+    }
+
+    fn is_ancestor(&self, ancestor: Target, target: Target) -> bool {
+        let mut current = self.parent(target);
+        while let Some(parent) = current {
+            if parent == ancestor {
+                return true;
+            }
+            current = self.parent(parent);
+        }
+        false
+    }
+
+    fn descendants(&self, root: Target) -> HashSet<Target> {
+        let mut descendants = HashSet::new();
+        let mut pending = vec![root];
+        while let Some(parent) = pending.pop() {
+            let world_children = match parent {
+                Target::Existing(entity) => self
+                    .world
+                    .get::<Children>(entity)
+                    .map(|children| children.iter().map(Target::Existing).collect())
+                    .unwrap_or_default(),
+                Target::Spawned(_) => Vec::new(),
+            };
+            let batch_children = self
+                .parents
+                .iter()
+                .filter(|(_, child_parent)| **child_parent == Some(parent))
+                .map(|(child, _)| *child);
+            for child in world_children.into_iter().chain(batch_children) {
+                // World children moved elsewhere by the batch no longer belong to `parent`
+                if self.parent(child) == Some(parent) && descendants.insert(child) {
+                    pending.push(child);
+                }
+            }
+        }
+        descendants
+    }
+}
+
+/// Registration of a reflected component by full type path
+fn component_registration<'a>(
+    registry: &'a TypeRegistry,
+    type_path: &str,
+) -> Result<&'a bevy::reflect::TypeRegistration, String> {
+    registry
+        .get_with_type_path(type_path)
+        .filter(|registration| registration.data::<ReflectComponent>().is_some())
+        .ok_or_else(|| format!("`{type_path}` is not a registered, reflected component"))
+}
+
+/// Deserialize component values by full type path
+fn deserialize_components(
+    registry: &TypeRegistry,
+    components: &HashMap<String, Value>,
+) -> Result<Vec<Box<dyn PartialReflect>>, String> {
+    components
+        .iter()
+        .map(|(type_path, value)| {
+            let registration = component_registration(registry, type_path)?;
+            TypedReflectDeserializer::new(registration, registry)
+                .deserialize(value)
+                .map_err(|e| format!("invalid value for `{type_path}`: {e}"))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+#[allow(clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    fn world_with_registry() -> World {
+        let mut world = World::new();
+        let registry = AppTypeRegistry::default();
+        registry.write().register::<Name>();
+        world.insert_resource(registry);
+        world
+    }
+
+    #[test]
+    fn applies_batch_with_references_to_spawned_entities() {
+        let mut world = world_with_registry();
+        let response = handler(
+            In(Some(json!({ "commands": [
+                { "command": "spawn", "components": { "bevy_ecs::name::Name": "Parent" } },
+                { "command": "spawn", "parent": "$0" },
+                { "command": "insert", "entity": "$1",
+                  "components": { "bevy_ecs::name::Name": "Child" } },
+            ]}))),
+            &mut world,
+        )
+        .expect("valid batch");
+
+        assert_eq!(response["applied"], 3);
+        let child = response["spawned"][1]["entity"]
+            .as_u64()
+            .map(Entity::from_bits)
+            .expect("spawned child");
+        assert_eq!(world.get::<Name>(child).map(Name::as_str), Some("Child"));
+        assert!(world.get::<ChildOf>(child).is_some());
+    }
+
+    #[test]
+    fn rejects_whole_batch_when_one_command_is_invalid() {
+        let mut world = world_with_registry();
+        let before = world.entities().len();
+        let error = handler(
+            In(Some(json!({ "commands": [
+                { "command": "spawn", "components": { "bevy_ecs::name::Name": "Kept?" } },
+                { "command": "despawn", "entity": "$3" },
+            ]}))),
+            &mut world,
+        )
+        .expect_err("invalid reference");
+
+        assert!(error.message.starts_with("Command 1:"));
+        assert_eq!(world.entities().len(), before);
+    }
+
+    #[test]
+    fn rejects_commands_on_descendants_of_a_despawned_entity() {
+        let mut world = world_with_registry();
+        let parent = world.spawn_empty().id();
+        let child = world.spawn(ChildOf(parent)).id();
+        let moved = world.spawn(ChildOf(parent)).id();
+
+        let error = handler(
+            In(Some(json!({ "commands": [
+                { "command": "spawn", "parent": child.to_bits() },
+                { "command": "reparent", "entity": moved.to_bits() },
+                { "command": "despawn", "entity": parent.to_bits() },
+                { "command": "insert", "entity": "$0",
+                  "components": { "bevy_ecs::name::Name": "Grandchild" } },
+            ]}))),
+            &mut world,
+        )
+        .expect_err("grandchild was despawned");
+        assert!(error.message.starts_with("Command 3:"));
+
+        // The entity moved out from under the parent survives the despawn
+        handler(
+            In(Some(json!({ "commands": [
+                { "command": "reparent", "entity": moved.to_bits() },
+                { "command": "despawn", "entity": parent.to_bits() },
+                { "command": "insert", "entity": moved.to_bits(),
+                  "components": { "bevy_ecs::name::Name": "Moved" } },
+            ]}))),
+            &mut world,
+        )
+        .expect("moved entity is no longer a descendant");
+        assert!(world.get_entity(child).is_err());
+        assert_eq!(world.get::<Name>(moved).map(Name::as_str), Some("Moved"));
+    }
+
+    #[test]
+    fn rejects_reparent_cycles() {
+        let mut world = world_with_registry();
+        let root = world.spawn_empty().id();
+        let child = world.spawn(ChildOf(root)).id();
+
+        for commands in [
+            json!([{ "command": "reparent", "entity": root.to_bits(), "parent": child.to_bits() }]),
+            json!([{ "command": "reparent", "entity": root.to_bits(), "parent": root.to_bits() }]),
+            json!([
+                { "command": "spawn", "parent": child.to_bits() },
+                { "command": "reparent", "entity": root.to_bits(), "parent": "$0" },
+            ]),
+        ] {
+            let error =
+                handler(In(Some(json!({ "commands": commands }))), &mut world).expect_err("cycle");
+            assert!(error.message.contains("its own descendant"));
+        }
+        assert_eq!(world.get::<ChildOf>(child).map(ChildOf::parent), Some(root));
+    }
+}
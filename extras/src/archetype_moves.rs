@@ -0,0 +1,410 @@
+//! Archetype move tracking handler for BRP extras
+//!
+//! Inserting or removing a component moves an entity to another archetype and copies every
+//! component it has - cheap once, costly when a system toggles a marker on many entities every
+//! frame. Like `collect_frame_stats`, tracking takes two calls: one with `frames` starts comparing
+//! each entity's archetype at the end of every frame, and calls without it poll until the frames
+//! have passed and the entities that moved most are reported with the components that churned.
+//!
+//! Archetypes are compared at frame boundaries, so a component inserted and removed within one
+//! frame isn't seen - the counts are a lower bound on the real churn.
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+
+use bevy::ecs::archetype::ArchetypeId;
+use bevy::ecs::component::ComponentId;
+use bevy::prelude::*;
+use bevy::remote::BrpError;
+use bevy::remote::BrpResult;
+use bevy::remote::error_codes::INVALID_PARAMS;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+use serde_json::json;
+
+/// Longest tracking window in frames
+const MAX_FRAMES: u32 = 10_000;
+
+/// Entities reported when `limit` is omitted
+const DEFAULT_LIMIT: usize = 20;
+
+/// Most entities one report lists
+const MAX_LIMIT: usize = 1_000;
+
+/// Request structure for `track_archetype_moves`
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TrackArchetypeMovesRequest {
+    /// Start tracking over this many frames - omit to poll the current tracking
+    #[serde(default)]
+    pub frames: Option<u32>,
+    /// Entities and components to report, most moves first - read when tracking starts
+    #[serde(default)]
+    pub limit:  Option<usize>,
+}
+
+/// Response to `track_archetype_moves`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum TrackArchetypeMovesResponse {
+    /// Frames are still being compared
+    Tracking {
+        /// Length of the tracking window
+        frames:          u32,
+        /// Frames compared so far
+        frames_recorded: u32,
+        /// Archetype moves seen so far
+        moves_so_far:    usize,
+    },
+    /// The tracking window has passed
+    Complete(ArchetypeMovesReport),
+}
+
+/// Archetype moves over a tracking window
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchetypeMovesReport {
+    /// Length of the tracking window
+    pub frames:          u32,
+    /// Entities that existed at some point in the window
+    pub entities_seen:   usize,
+    /// Archetype moves of all entities
+    pub total_moves:     usize,
+    /// Entities that moved at least once
+    pub moving_entities: usize,
+    /// Entities that moved most, most moves first
+    pub worst_entities:  Vec<EntityArchetypeMoves>,
+    /// Components inserted and removed most across all entities, most changes first
+    pub components:      Vec<ComponentChurn>,
+}
+
+/// Archetype moves of one entity
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntityArchetypeMoves {
+    /// The entity
+    pub entity:          u64,
+    /// Frames in which it changed archetype
+    pub moves:           usize,
+    /// Moves divided by the frames of the window
+    pub moves_per_frame: f64,
+    /// Whether it was despawned before the window ended
+    pub despawned:       bool,
+    /// Components inserted and removed on it, most changes first
+    pub components:      Vec<ComponentChurn>,
+}
+
+/// How often a component was inserted and removed
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ComponentChurn {
+    /// Component name
+    pub component: String,
+    /// Times it was inserted
+    pub inserted:  usize,
+    /// Times it was removed
+    pub removed:   usize,
+}
+
+impl ComponentChurn {
+    const fn changes(&self) -> usize { self.inserted + self.removed }
+}
+
+/// Moves recorded for one entity
+#[derive(Default)]
+struct MoveRecord {
+    moves:      usize,
+    despawned:  bool,
+    components: BTreeMap<String, ComponentChurn>,
+}
+
+/// Resource holding the current tracking - absent until the first tracking starts
+#[derive(Resource)]
+enum ArchetypeMoveTracking {
+    /// Comparing archetypes until `frames_recorded` reaches `frames`
+    Tracking {
+        frames:          u32,
+        limit:           usize,
+        frames_recorded: u32,
+        entities_seen:   usize,
+        /// Each entity's archetype at the end of the previous frame - `None` before the first
+        locations:       Option<HashMap<Entity, ArchetypeId>>,
+        moves:           HashMap<Entity, MoveRecord>,
+    },
+    /// Report of the finished tracking
+    Complete(ArchetypeMovesReport),
+}
+
+/// Handler for `track_archetype_moves` requests
+pub fn handler(In(params): In<Option<Value>>, world: &mut World) -> BrpResult {
+    let request: TrackArchetypeMovesRequest = params
+        .map(serde_json::from_value)
+        .transpose()
+        .map_err(|e| BrpError {
+            code:    INVALID_PARAMS,
+            message: format!("Invalid request format: {e}"),
+            data:    None,
+        })?
+        .unwrap_or_default();
+
+    if let Some(frames) = request.frames {
+        if frames == 0 || frames > MAX_FRAMES {
+            return Err(BrpError {
+                code:    INVALID_PARAMS,
+                message: format!("'frames' must be between 1 and {MAX_FRAMES}, got {frames}"),
+                data:    None,
+            });
+        }
+        let limit = request.limit.unwrap_or(DEFAULT_LIMIT);
+        if limit == 0 || limit > MAX_LIMIT {
+            return Err(BrpError {
+                code:    INVALID_PARAMS,
+                message: format!("'limit' must be between 1 and {MAX_LIMIT}, got {limit}"),
+                data:    None,
+            });
+        }
+        world.insert_resource(ArchetypeMoveTracking::Tracking {
+            frames,
+            limit,
+            frames_recorded: 0,
+            entities_seen: 0,
+            locations: None,
+            moves: HashMap::new(),
+        });
+    }
+
+    let tracking = world
+        .get_resource::<ArchetypeMoveTracking>()
+        .ok_or_else(|| BrpError {
+            code:    INVALID_PARAMS,
+            message: "No archetype move tracking - pass 'frames' to start one".to_string(),
+            data:    None,
+        })?;
+
+    let response = match tracking {
+        ArchetypeMoveTracking::Tracking {
+            frames,
+            frames_recorded,
+            moves,
+            ..
+        } => TrackArchetypeMovesResponse::Tracking {
+            frames:          *frames,
+            frames_recorded: *frames_recorded,
+            moves_so_far:    moves.values().map(|record| record.moves).sum(),
+        },
+        ArchetypeMoveTracking::Complete(report) => {
+            TrackArchetypeMovesResponse::Complete(report.clone())
+        },
+    };
+    Ok(json!(response))
+}
+
+/// System comparing each entity's archetype with the previous frame while tracking is running
+pub fn record_archetype_moves(world: &mut World) {
+    if !matches!(
+        world.get_resource::<ArchetypeMoveTracking>(),
+        Some(ArchetypeMoveTracking::Tracking { .. })
+    ) {
+        return;
+    }
+
+    world.resource_scope(|world, mut tracking: Mut<ArchetypeMoveTracking>| {
+        let ArchetypeMoveTracking::Tracking {
+            frames,
+            limit,
+            frames_recorded,
+            entities_seen,
+            locations,
+            moves,
+        } = tracking.as_mut()
+        else {
+            return;
+        };
+
+        let current = archetype_locations(world);
+        if let Some(previous) = locations.as_ref() {
+            for (entity, &archetype) in &current {
+                match previous.get(entity) {
+                    Some(&before) if before != archetype => {
+                        let record = moves.entry(*entity).or_default();
+                        record.moves += 1;
+                        record_component_changes(world, before, archetype, &mut record.components);
+                    },
+                    Some(_) => {},
+                    None => *entities_seen += 1,
+                }
+            }
+            for entity in previous
+                .keys()
+                .filter(|entity| !current.contains_key(*entity))
+            {
+                if let Some(record) = moves.get_mut(entity) {
+                    record.despawned = true;
+                }
+            }
+            *frames_recorded += 1;
+        } else {
+            *entities_seen = current.len();
+        }
+        *locations = Some(current);
+
+        if *frames_recorded >= *frames {
+            let report = ArchetypeMovesReport::from_moves(*frames, *entities_seen, moves, *limit);
+            debug!(
+                "Archetype moves tracked: {} moves of {} entities over {} frames",
+                report.total_moves, report.moving_entities, report.frames
+            );
+            *tracking = ArchetypeMoveTracking::Complete(report);
+        }
+    });
+}
+
+impl ArchetypeMovesReport {
+    /// Report of the recorded `moves`, listing the `limit` worst entities and components
+    #[allow(clippy::cast_precision_loss)]
+    fn from_moves(
+        frames: u32,
+        entities_seen: usize,
+        moves: &HashMap<Entity, MoveRecord>,
+        limit: usize,
+    ) -> Self {
+        let mut components: BTreeMap<&str, ComponentChurn> = BTreeMap::new();
+        for churn in moves.values().flat_map(|record| record.components.values()) {
+            let total = components
+                .entry(churn.component.as_str())
+                .or_insert_with(|| ComponentChurn {
+                    component: churn.component.clone(),
+                    ..ComponentChurn::default()
+                });
+            total.inserted += churn.inserted;
+            total.removed += churn.removed;
+        }
+
+        let mut worst_entities: Vec<EntityArchetypeMoves> = moves
+            .iter()
+            .map(|(entity, record)| EntityArchetypeMoves {
+                entity:          entity.to_bits(),
+                moves:           record.moves,
+                moves_per_frame: record.moves as f64 / f64::from(frames),
+                despawned:       record.despawned,
+                components:      most_changed(record.components.values().cloned().collect()),
+            })
+            .collect();
+        worst_entities.sort_by(|a, b| b.moves.cmp(&a.moves).then(a.entity.cmp(&b.entity)));
+        worst_entities.truncate(limit);
+
+        let mut components = most_changed(components.into_values().collect());
+        components.truncate(limit);
+
+        Self {
+            frames,
+            entities_seen,
+            total_moves: moves.values().map(|record| record.moves).sum(),
+            moving_entities: moves.len(),
+            worst_entities,
+            components,
+        }
+    }
+}
+
+/// Sort `churn` by changes, most first
+fn most_changed(mut churn: Vec<ComponentChurn>) -> Vec<ComponentChurn> {
+    churn.sort_by(|a, b| {
+        b.changes()
+            .cmp(&a.changes())
+            .then_with(|| a.component.cmp(&b.component))
+    });
+    churn
+}
+
+/// The archetype of every entity in the world
+fn archetype_locations(world: &World) -> HashMap<Entity, ArchetypeId> {
+    world
+        .archetypes()
+        .iter()
+        .flat_map(|archetype| {
+            archetype
+                .entities()
+                .iter()
+                .map(move |archetype_entity| (archetype_entity.id(), archetype.id()))
+        })
+        .collect()
+}
+
+/// Count the components an entity gained and lost moving from archetype `before` to `after`
+fn record_component_changes(
+    world: &World,
+    before: ArchetypeId,
+    after: ArchetypeId,
+    churn: &mut BTreeMap<String, ComponentChurn>,
+) {
+    let archetypes = world.archetypes();
+    let (Some(before), Some(after)) = (archetypes.get(before), archetypes.get(after)) else {
+        return;
+    };
+    for &component_id in after
+        .components()
+        .iter()
+        .filter(|&&id| !before.contains(id))
+    {
+        component_churn(world, component_id, churn).inserted += 1;
+    }
+    for &component_id in before
+        .components()
+        .iter()
+        .filter(|&&id| !after.contains(id))
+    {
+        component_churn(world, component_id, churn).removed += 1;
+    }
+}
+
+/// The churn entry of `component_id`, keyed by its name
+fn component_churn<'a>(
+    world: &World,
+    component_id: ComponentId,
+    churn: &'a mut BTreeMap<String, ComponentChurn>,
+) -> &'a mut ComponentChurn {
+    let component = world.components().get_info(component_id).map_or_else(
+        || format!("{component_id:?}"),
+        |info| info.name().to_string(),
+    );
+    churn
+        .entry(component.clone())
+        .or_insert_with(|| ComponentChurn {
+            component,
+            ..ComponentChurn::default()
+        })
+}
+
+#[cfg(test)]
+#[allow(clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[derive(Component)]
+    struct Highlighted;
+
+    #[test]
+    fn toggled_components_are_counted_per_entity() {
+        let mut world = World::new();
+        let toggled = world.spawn(Highlighted).id();
+        world.spawn_empty();
+
+        let started =
+            handler(In(Some(json!({ "frames": 2 }))), &mut world).expect("tracking should start");
+        assert_eq!(started["status"], "tracking");
+
+        // The first frame records where each entity is, the next two compare against it
+        record_archetype_moves(&mut world);
+        world.entity_mut(toggled).remove::<Highlighted>();
+        record_archetype_moves(&mut world);
+        world.entity_mut(toggled).insert(Highlighted);
+        record_archetype_moves(&mut world);
+
+        let report = handler(In(None), &mut world).expect("tracking should be complete");
+        assert_eq!(report["status"], "complete");
+        assert_eq!(report["total_moves"], 2);
+        assert_eq!(report["moving_entities"], 1);
+        assert_eq!(report["worst_entities"][0]["entity"], toggled.to_bits());
+        assert_eq!(report["components"][0]["inserted"], 1);
+        assert_eq!(report["components"][0]["removed"], 1);
+    }
+}
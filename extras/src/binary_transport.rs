@@ -0,0 +1,262 @@
+//! Binary BRP transport - `MessagePack` and CBOR frames on a second port
+//!
+//! Large world dumps and busy watches spend most of their time in JSON. With the
+//! `binary-transport` feature, [`BrpExtrasPlugin::with_binary_transport`] serves BRP on a second
+//! TCP port as length-prefixed `MessagePack` or CBOR frames. `bevy_brp_mcp` discovers it through
+//! `brp_extras/wire_encodings` and uses it for queries, registry dumps and watches.
+//!
+//! Framing: a request is one byte naming the encoding (`M` or `C`), a big-endian `u32` length
+//! and the encoded JSON-RPC request. Each response is a big-endian `u32` length followed by an
+//! encoded JSON-RPC response in the same encoding - one for a regular method, one per update for
+//! a watch. The connection is closed after the last response.
+//!
+//! [`BrpExtrasPlugin::with_binary_transport`]: crate::BrpExtrasPlugin::with_binary_transport
+
+use std::io::Read;
+use std::io::Write;
+use std::net::TcpListener;
+use std::net::TcpStream;
+use std::sync::Arc;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+
+use bevy::prelude::*;
+use bevy::remote::BrpMessage;
+use bevy::remote::BrpResult;
+use bevy::remote::BrpSender;
+use serde_json::Value;
+use serde_json::json;
+
+/// Encodings the transport understands, by name
+const ENCODINGS: [&str; 2] = ["msgpack", "cbor"];
+
+/// Largest request frame accepted - BRP requests are small
+const MAX_REQUEST_BYTES: usize = 16 * 1024 * 1024;
+
+/// Responses buffered per connection before the app waits on the client
+const RESPONSE_CHANNEL_SIZE: usize = 32;
+
+/// Connections served at once - each holds a thread, and watches hold it until the client leaves
+const MAX_CONNECTIONS: usize = 64;
+
+/// Port the binary transport listens on
+#[derive(Resource, Clone, Copy)]
+pub struct BinaryTransportPort(pub u16);
+
+/// A binary encoding of JSON-RPC messages
+#[derive(Clone, Copy)]
+enum WireEncoding {
+    Msgpack,
+    Cbor,
+}
+
+impl WireEncoding {
+    const fn from_tag(tag: u8) -> Option<Self> {
+        match tag {
+            b'M' => Some(Self::Msgpack),
+            b'C' => Some(Self::Cbor),
+            _ => None,
+        }
+    }
+
+    fn encode(self, value: &Value) -> Result<Vec<u8>, String> {
+        match self {
+            Self::Msgpack => rmp_serde::to_vec_named(value).map_err(|e| e.to_string()),
+            Self::Cbor => {
+                let mut bytes = Vec::new();
+                ciborium::into_writer(value, &mut bytes)
+                    .map(|()| bytes)
+                    .map_err(|e| e.to_string())
+            },
+        }
+    }
+
+    fn decode(self, bytes: &[u8]) -> Result<Value, String> {
+        match self {
+            Self::Msgpack => rmp_serde::from_slice(bytes).map_err(|e| e.to_string()),
+            Self::Cbor => ciborium::from_reader(bytes).map_err(|e| e.to_string()),
+        }
+    }
+}
+
+/// Handler for `brp_extras/wire_encodings`
+///
+/// Reports the binary transport's port and encodings - no encodings when it isn't running
+#[allow(clippy::unnecessary_wraps)]
+pub fn wire_encodings_handler(In(_): In<Option<Value>>, world: &mut World) -> BrpResult {
+    let port = world
+        .get_resource::<BinaryTransportPort>()
+        .map(|port| port.0);
+    let encodings: &[&str] = if port.is_some() { &ENCODINGS } else { &[] };
+    Ok(json!({
+        "port": port,
+        "encodings": encodings,
+    }))
+}
+
+/// Startup system that starts listening for binary requests
+pub fn start_binary_transport(port: Res<BinaryTransportPort>, sender: Res<BrpSender>) {
+    let listener = match TcpListener::bind(("127.0.0.1", port.0)) {
+        Ok(listener) => listener,
+        Err(e) => {
+            warn!(
+                "Binary BRP transport could not listen on port {}: {e}",
+                port.0
+            );
+            return;
+        },
+    };
+    info!("Binary BRP transport listening on port {}", port.0);
+
+    let sender = async_channel::Sender::clone(&sender);
+    std::thread::spawn(move || {
+        let slots = ConnectionSlots::default();
+        for stream in listener.incoming().flatten() {
+            // Dropping the stream closes it, and the client falls back to HTTP
+            let Some(slot) = slots.try_acquire() else {
+                warn!("Binary BRP transport refused a connection - {MAX_CONNECTIONS} are open");
+                continue;
+            };
+            let sender = sender.clone();
+            std::thread::spawn(move || {
+                let _slot = slot;
+                if let Err(e) = serve(stream, &sender) {
+                    debug!("Binary BRP connection closed: {e}");
+                }
+            });
+        }
+    });
+}
+
+/// Count of connections being served, bounded by `MAX_CONNECTIONS`
+#[derive(Default)]
+struct ConnectionSlots(Arc<AtomicUsize>);
+
+impl ConnectionSlots {
+    /// Claim a slot for a new connection - `None` when all are taken
+    fn try_acquire(&self) -> Option<ConnectionSlot> {
+        self.0
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |open| {
+                (open < MAX_CONNECTIONS).then_some(open + 1)
+            })
+            .ok()
+            .map(|_| ConnectionSlot(Arc::clone(&self.0)))
+    }
+}
+
+/// A connection's claim on a slot, released when it's dropped
+struct ConnectionSlot(Arc<AtomicUsize>);
+
+impl Drop for ConnectionSlot {
+    fn drop(&mut self) { self.0.fetch_sub(1, Ordering::AcqRel); }
+}
+
+/// Serve one request, writing responses until the method stops sending them
+fn serve(mut stream: TcpStream, sender: &async_channel::Sender<BrpMessage>) -> Result<(), String> {
+    let mut header = [0_u8; 5];
+    stream.read_exact(&mut header).map_err(|e| e.to_string())?;
+    let encoding = WireEncoding::from_tag(header[0])
+        .ok_or_else(|| format!("unknown encoding tag {:#04x}", header[0]))?;
+    let length = usize::try_from(u32::from_be_bytes([
+        header[1], header[2], header[3], header[4],
+    ]))
+    .unwrap_or(usize::MAX);
+    if length > MAX_REQUEST_BYTES {
+        return Err(format!("request of {length} bytes is too large"));
+    }
+    let mut payload = vec![0; length];
+    stream.read_exact(&mut payload).map_err(|e| e.to_string())?;
+
+    let request = encoding.decode(&payload)?;
+    let id = request.get("id").cloned().unwrap_or(Value::Null);
+    let method = request
+        .get("method")
+        .and_then(Value::as_str)
+        .ok_or("request has no method")?
+        .to_string();
+    let params = request.get("params").cloned();
+
+    let (result_sender, result_receiver) = async_channel::bounded(RESPONSE_CHANNEL_SIZE);
+    sender
+        .send_blocking(BrpMessage {
+            method,
+            params,
+            sender: result_sender,
+        })
+        .map_err(|e| e.to_string())?;
+
+    // Watches keep sending until the client disconnects; other methods send once
+    while let Ok(result) = result_receiver.recv_blocking() {
+        let response = match result {
+            Ok(result) => json!({"jsonrpc": "2.0", "id": id, "result": result}),
+            Err(error) => json!({"jsonrpc": "2.0", "id": id, "error": error}),
+        };
+        let payload = encoding.encode(&response)?;
+        let length = u32::try_from(payload.len()).map_err(|e| e.to_string())?;
+        stream
+            .write_all(&length.to_be_bytes())
+            .and_then(|()| stream.write_all(&payload))
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+#[allow(clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn connections_are_bounded() {
+        let slots = ConnectionSlots::default();
+        let mut held: Vec<ConnectionSlot> = (0..MAX_CONNECTIONS)
+            .map(|_| slots.try_acquire().expect("free slot"))
+            .collect();
+        assert!(slots.try_acquire().is_none());
+
+        held.pop();
+        assert_eq!(held.len(), MAX_CONNECTIONS - 1);
+        assert!(slots.try_acquire().is_some());
+    }
+
+    #[test]
+    fn serves_a_request_as_frames() {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).expect("bind a free port");
+        let port = listener.local_addr().expect("local address").port();
+
+        // Stand in for the app, echoing each request's method
+        let (sender, receiver) = async_channel::unbounded::<BrpMessage>();
+        std::thread::spawn(move || {
+            while let Ok(message) = receiver.recv_blocking() {
+                let _ = message
+                    .sender
+                    .send_blocking(Ok(json!({"method": message.method})));
+            }
+        });
+        std::thread::spawn(move || {
+            let (stream, _) = listener.accept().expect("accept");
+            serve(stream, &sender).expect("serve");
+        });
+
+        let request = json!({"jsonrpc": "2.0", "id": 7, "method": "world.query"});
+        let payload = WireEncoding::Cbor.encode(&request).expect("encode");
+        let length = u32::try_from(payload.len()).expect("small request");
+        let mut stream = TcpStream::connect(("127.0.0.1", port)).expect("connect");
+        stream.write_all(b"C").expect("send tag");
+        stream
+            .write_all(&length.to_be_bytes())
+            .expect("send length");
+        stream.write_all(&payload).expect("send request");
+
+        let mut length = [0_u8; 4];
+        stream.read_exact(&mut length).expect("read length");
+        let mut payload = vec![0; u32::from_be_bytes(length) as usize];
+        stream.read_exact(&mut payload).expect("read response");
+        let response = WireEncoding::Cbor.decode(&payload).expect("decode");
+        assert_eq!(response["id"], 7);
+        assert_eq!(response["result"]["method"], "world.query");
+
+        // The app dropped its sender after answering, so the connection closes
+        assert_eq!(stream.read(&mut length).expect("read to end"), 0);
+    }
+}
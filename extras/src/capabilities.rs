@@ -0,0 +1,133 @@
+//! Optional sections of `BrpExtrasPlugin` and the `brp_extras/capabilities` method
+//!
+//! Screenshots, keyboard input and window control are each a section of the plugin that
+//! `BrpExtrasPlugin::without` leaves out of one app, e.g. a headless server with no window to
+//! capture. The screenshot and window sections also have a cargo feature (both on by default)
+//! that compiles them in along with the Bevy features they need - the PNG encoder and the winit
+//! backend. `brp_extras/capabilities` reports every section and the methods the app actually
+//! serves, so `bevy_brp_mcp` can explain why a call to a missing method failed.
+
+use bevy::prelude::*;
+use bevy::remote::BrpError;
+use bevy::remote::BrpResult;
+use bevy::remote::error_codes::INTERNAL_ERROR;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+use serde_json::json;
+
+/// An optional section of `BrpExtrasPlugin`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Capability {
+    /// `brp_extras/screenshot`, `brp_extras/screenshot_status` and `brp_extras/read_pixels`
+    /// (`screenshot` feature)
+    Screenshot,
+    /// `brp_extras/send_keys` (always compiled in)
+    Keyboard,
+    /// `brp_extras/set_window_title`, `brp_extras/set_window` and `brp_extras/list_monitors`
+    /// (`window` feature)
+    Window,
+}
+
+impl Capability {
+    /// Every section, in the order they are built
+    pub const ALL: [Self; 3] = [Self::Screenshot, Self::Keyboard, Self::Window];
+
+    /// Name of the section, which is also the name of its cargo feature if it has one
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Screenshot => "screenshot",
+            Self::Keyboard => "keyboard",
+            Self::Window => "window",
+        }
+    }
+
+    /// Whether the section is compiled in - its cargo feature is enabled, or it has none
+    #[must_use]
+    pub const fn compiled(self) -> bool {
+        match self {
+            Self::Screenshot => cfg!(feature = "screenshot"),
+            Self::Keyboard => true,
+            Self::Window => cfg!(feature = "window"),
+        }
+    }
+}
+
+/// A section of the plugin as this app built it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CapabilityStatus {
+    /// Name of the section
+    pub name:     String,
+    /// Whether its cargo feature is enabled
+    pub compiled: bool,
+    /// Whether the app built it - compiled in and not left out with `BrpExtrasPlugin::without`
+    pub enabled:  bool,
+    /// Methods it added
+    pub methods:  Vec<String>,
+}
+
+/// What the plugin built, as reported by `brp_extras/capabilities`
+#[derive(Resource, Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExtrasCapabilities {
+    /// Every optional section
+    pub capabilities: Vec<CapabilityStatus>,
+    /// Every `brp_extras/` method the app serves
+    pub methods:      Vec<String>,
+}
+
+/// Handler for `capabilities` requests
+pub fn handler(In(_params): In<Option<Value>>, world: &mut World) -> BrpResult {
+    let capabilities = world
+        .get_resource::<ExtrasCapabilities>()
+        .ok_or_else(|| BrpError {
+            code:    INTERNAL_ERROR,
+            message: "Capabilities haven't been recorded - the plugin isn't built".to_string(),
+            data:    None,
+        })?;
+    Ok(json!(capabilities))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sections_without_a_feature_are_always_compiled() {
+        assert!(Capability::Keyboard.compiled());
+        assert_eq!(
+            Capability::Screenshot.compiled(),
+            cfg!(feature = "screenshot")
+        );
+        assert_eq!(Capability::Window.compiled(), cfg!(feature = "window"));
+    }
+
+    #[test]
+    fn reports_the_recorded_capabilities() {
+        let mut world = World::new();
+        let error = world
+            .run_system_cached_with(handler, None)
+            .unwrap_or_else(|error| unreachable!("handler should run: {error}"))
+            .err()
+            .map(|error| error.code);
+        assert_eq!(error, Some(INTERNAL_ERROR));
+
+        world.insert_resource(ExtrasCapabilities {
+            capabilities: vec![CapabilityStatus {
+                name:     Capability::Keyboard.name().to_string(),
+                compiled: true,
+                enabled:  false,
+                methods:  Vec::new(),
+            }],
+            methods:      vec!["brp_extras/shutdown".to_string()],
+        });
+        let report = world
+            .run_system_cached_with(handler, None)
+            .unwrap_or_else(|error| unreachable!("handler should run: {error}"))
+            .unwrap_or_else(|error| unreachable!("capabilities are recorded: {error:?}"));
+        assert_eq!(report["capabilities"][0]["name"], "keyboard");
+        assert_eq!(report["capabilities"][0]["enabled"], false);
+        assert_eq!(report["methods"], json!(["brp_extras/shutdown"]));
+    }
+}
@@ -0,0 +1,77 @@
+//! System clipboard handler for BRP extras
+//!
+//! With the `clipboard` feature, `brp_extras/clipboard` reads the system clipboard and, given
+//! `text`, replaces its content first - for driving paste-based text entry and checking what a UI
+//! copied. The clipboard is opened on first use and kept for the app's lifetime, since on Linux
+//! the content the app set is only served while the app holds the clipboard.
+
+use arboard::Clipboard;
+use bevy::prelude::*;
+use bevy::remote::BrpError;
+use bevy::remote::BrpResult;
+use bevy::remote::error_codes::INTERNAL_ERROR;
+use bevy::remote::error_codes::INVALID_PARAMS;
+use serde::Deserialize;
+use serde_json::Value;
+use serde_json::json;
+
+/// Request structure for `clipboard`
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ClipboardRequest {
+    /// Replace the clipboard's content with this text before reading it
+    #[serde(default)]
+    pub text: Option<String>,
+}
+
+/// The system clipboard, opened by the first request - platform clipboards are tied to the
+/// thread that opened them
+struct SystemClipboard(Clipboard);
+
+/// Handler for `clipboard` requests
+///
+/// Returns the clipboard's text, or `null` when it holds no text.
+pub fn handler(In(params): In<Option<Value>>, world: &mut World) -> BrpResult {
+    let request: ClipboardRequest = params
+        .map(serde_json::from_value)
+        .transpose()
+        .map_err(|e| BrpError {
+            code:    INVALID_PARAMS,
+            message: format!("Invalid request format: {e}"),
+            data:    None,
+        })?
+        .unwrap_or_default();
+
+    if !world.contains_non_send::<SystemClipboard>() {
+        let clipboard = Clipboard::new().map_err(|e| clipboard_error("open", &e))?;
+        world.insert_non_send_resource(SystemClipboard(clipboard));
+    }
+    let mut clipboard = world.non_send_resource_mut::<SystemClipboard>();
+
+    let set = request.text.is_some();
+    if let Some(text) = request.text {
+        clipboard
+            .0
+            .set_text(text)
+            .map_err(|e| clipboard_error("write", &e))?;
+    }
+    let text = match clipboard.0.get_text() {
+        Ok(text) => Some(text),
+        Err(arboard::Error::ContentNotAvailable) => None,
+        Err(e) => return Err(clipboard_error("read", &e)),
+    };
+
+    Ok(json!({
+        "text": text,
+        "set": set,
+    }))
+}
+
+/// Error for a failed clipboard `action`
+fn clipboard_error(action: &str, error: &arboard::Error) -> BrpError {
+    BrpError {
+        code:    INTERNAL_ERROR,
+        message: format!("Failed to {action} the system clipboard: {error}"),
+        data:    None,
+    }
+}
@@ -0,0 +1,604 @@
+//! BRP HTTP server that compresses large responses
+//!
+//! Bevy's `RemoteHttpPlugin` always answers in plain JSON, and `registry.schema` alone runs to
+//! megabytes in a large app. With the `compression` feature, [`BrpExtrasPlugin::with_compression`]
+//! serves BRP over HTTP from this server instead. It runs on hyper like Bevy's server, so
+//! connections are kept alive and watches stream as chunked server-sent events. Requests are
+//! accepted on `/` and `/jsonrpc`, and responses of at least `MIN_COMPRESSED_BYTES` are gzip or
+//! deflate encoded when the request's `Accept-Encoding` allows it.
+//!
+//! [`BrpExtrasPlugin::with_compression`]: crate::BrpExtrasPlugin::with_compression
+
+use std::convert::Infallible;
+use std::io::Write;
+use std::net::TcpListener;
+use std::net::TcpStream;
+use std::pin::Pin;
+use std::task::Context;
+use std::task::Poll;
+
+use async_io::Async;
+use bevy::prelude::*;
+use bevy::remote::BrpBatch;
+use bevy::remote::BrpError;
+use bevy::remote::BrpMessage;
+use bevy::remote::BrpRequest;
+use bevy::remote::BrpResponse;
+use bevy::remote::BrpResult;
+use bevy::remote::BrpSender;
+use bevy::remote::error_codes::INTERNAL_ERROR;
+use bevy::remote::error_codes::INVALID_REQUEST;
+use bevy::remote::error_codes::PARSE_ERROR;
+use bevy::tasks::IoTaskPool;
+use bevy::tasks::futures_lite::Stream;
+use flate2::Compression;
+use flate2::write::GzEncoder;
+use flate2::write::ZlibEncoder;
+use http_body_util::BodyExt;
+use http_body_util::Full;
+use http_body_util::LengthLimitError;
+use http_body_util::Limited;
+use hyper::Method;
+use hyper::Request;
+use hyper::Response;
+use hyper::StatusCode;
+use hyper::body::Body;
+use hyper::body::Bytes;
+use hyper::body::Frame;
+use hyper::body::Incoming;
+use hyper::body::SizeHint;
+use hyper::header;
+use hyper::header::HeaderName;
+use hyper::header::HeaderValue;
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use serde::Serialize;
+use serde_json::Value;
+use smol_hyper::rt::FuturesIo;
+use smol_hyper::rt::SmolTimer;
+
+/// Paths BRP requests are accepted on - Bevy's clients post to `/`, `bevy_brp_mcp` to `/jsonrpc`
+const BRP_PATHS: [&str; 2] = ["/", "/jsonrpc"];
+
+/// Responses smaller than this are sent uncompressed - compressing them saves nothing
+const MIN_COMPRESSED_BYTES: usize = 1024;
+
+/// Largest request body accepted - BRP requests are small
+const MAX_REQUEST_BYTES: usize = 16 * 1024 * 1024;
+
+/// Watch updates buffered per connection before the app waits on the client
+const WATCH_CHANNEL_SIZE: usize = 32;
+
+/// Port the compressing HTTP server listens on
+#[derive(Resource, Clone, Copy)]
+pub struct CompressedHttpPort(pub u16);
+
+/// Extra headers added to every response, such as CORS headers
+#[derive(Resource, Clone, Default)]
+pub struct CompressedHttpHeaders(pub Vec<(HeaderName, HeaderValue)>);
+
+/// Startup system that starts serving BRP over HTTP
+pub fn start_compressed_http(
+    port: Res<CompressedHttpPort>,
+    headers: Option<Res<CompressedHttpHeaders>>,
+    sender: Res<BrpSender>,
+) {
+    let listener = match Async::<TcpListener>::bind(([127, 0, 0, 1], port.0)) {
+        Ok(listener) => listener,
+        Err(e) => {
+            warn!("BRP HTTP server could not listen on port {}: {e}", port.0);
+            return;
+        },
+    };
+
+    let headers = headers.map(|headers| headers.clone()).unwrap_or_default();
+    let sender = async_channel::Sender::clone(&sender);
+    IoTaskPool::get()
+        .spawn(listen(listener, sender, headers))
+        .detach();
+}
+
+/// Accept connections until the listener fails, serving each on the IO task pool
+async fn listen(
+    listener: Async<TcpListener>,
+    sender: async_channel::Sender<BrpMessage>,
+    headers: CompressedHttpHeaders,
+) {
+    loop {
+        let client = match listener.accept().await {
+            Ok((client, _)) => client,
+            Err(e) => {
+                warn!("BRP HTTP server stopped accepting connections: {e}");
+                return;
+            },
+        };
+        let sender = sender.clone();
+        let headers = headers.clone();
+        IoTaskPool::get()
+            .spawn(async move {
+                if let Err(e) = serve_connection(client, &sender, &headers).await {
+                    debug!("BRP HTTP connection closed: {e}");
+                }
+            })
+            .detach();
+    }
+}
+
+/// Serve requests on a connection until the client closes it
+async fn serve_connection(
+    client: Async<TcpStream>,
+    sender: &async_channel::Sender<BrpMessage>,
+    headers: &CompressedHttpHeaders,
+) -> hyper::Result<()> {
+    http1::Builder::new()
+        .timer(SmolTimer::new())
+        .serve_connection(
+            FuturesIo::new(client),
+            service_fn(|request| async move {
+                let mut response = route(request, sender).await;
+                for (name, value) in &headers.0 {
+                    response.headers_mut().insert(name, value.clone());
+                }
+                Ok::<_, Infallible>(response)
+            }),
+        )
+        .await
+}
+
+/// Answer one HTTP request
+async fn route(
+    request: Request<Incoming>,
+    sender: &async_channel::Sender<BrpMessage>,
+) -> Response<HttpBody> {
+    if !BRP_PATHS.contains(&request.uri().path()) {
+        return status_response(StatusCode::NOT_FOUND);
+    }
+    match *request.method() {
+        Method::POST => {},
+        // CORS preflight - the allowed origins come from the configured headers
+        Method::OPTIONS => return status_response(StatusCode::NO_CONTENT),
+        _ => {
+            let mut response = status_response(StatusCode::METHOD_NOT_ALLOWED);
+            response
+                .headers_mut()
+                .insert(header::ALLOW, HeaderValue::from_static("POST, OPTIONS"));
+            return response;
+        },
+    }
+
+    let encoding = request
+        .headers()
+        .get(header::ACCEPT_ENCODING)
+        .and_then(|value| value.to_str().ok())
+        .and_then(ContentEncoding::negotiate);
+    let body = match Limited::new(request.into_body(), MAX_REQUEST_BYTES)
+        .collect()
+        .await
+    {
+        Ok(body) => body.to_bytes(),
+        Err(e) if e.is::<LengthLimitError>() => {
+            return status_response(StatusCode::PAYLOAD_TOO_LARGE);
+        },
+        Err(_) => return status_response(StatusCode::BAD_REQUEST),
+    };
+
+    match serde_json::from_slice(&body) {
+        Ok(BrpBatch::Single(request)) => match call(request, sender).await {
+            Reply::Complete(response) => json_response(&response, encoding),
+            Reply::Watch(watch) => watch_response(watch),
+        },
+        Ok(BrpBatch::Batch(requests)) => {
+            let mut responses = Vec::with_capacity(requests.len());
+            for request in requests {
+                // Refuse watches before the app starts one nobody can read
+                if request
+                    .get("method")
+                    .and_then(Value::as_str)
+                    .is_some_and(is_watch)
+                {
+                    responses.push(error_response(
+                        request.get("id").cloned(),
+                        INVALID_REQUEST,
+                        "Watches can not be used in batch requests",
+                    ));
+                    continue;
+                }
+                if let Reply::Complete(response) = call(request, sender).await {
+                    responses.push(response);
+                }
+            }
+            json_response(&responses, encoding)
+        },
+        Err(e) => json_response(&error_response(None, PARSE_ERROR, &e.to_string()), None),
+    }
+}
+
+fn is_watch(method: &str) -> bool { method.ends_with("+watch") }
+
+/// What the app answers a request with
+enum Reply {
+    Complete(BrpResponse),
+    Watch(WatchStream),
+}
+
+/// Hand a JSON-RPC request to the app and wait for its response, or for a watch its stream
+async fn call(request: Value, sender: &async_channel::Sender<BrpMessage>) -> Reply {
+    let id = request.get("id").cloned();
+    let request: BrpRequest = match serde_json::from_value(request) {
+        Ok(request) => request,
+        Err(e) => return Reply::Complete(error_response(id, INVALID_REQUEST, &e.to_string())),
+    };
+    if request.jsonrpc != "2.0" {
+        return Reply::Complete(error_response(
+            request.id,
+            INVALID_REQUEST,
+            "JSON-RPC request requires `\"jsonrpc\": \"2.0\"`",
+        ));
+    }
+
+    let watch = is_watch(&request.method);
+    let capacity = if watch { WATCH_CHANNEL_SIZE } else { 1 };
+    let (result_sender, result_receiver) = async_channel::bounded(capacity);
+    let message = BrpMessage {
+        method: request.method,
+        params: request.params,
+        sender: result_sender,
+    };
+    if sender.send(message).await.is_err() {
+        return Reply::Complete(error_response(
+            request.id,
+            INTERNAL_ERROR,
+            "The app is not accepting requests",
+        ));
+    }
+
+    if watch {
+        return Reply::Watch(WatchStream {
+            id:      request.id,
+            results: Box::pin(result_receiver),
+        });
+    }
+    match result_receiver.recv().await {
+        Ok(result) => Reply::Complete(BrpResponse::new(request.id, result)),
+        Err(_) => Reply::Complete(error_response(
+            request.id,
+            INTERNAL_ERROR,
+            "The app dropped the request",
+        )),
+    }
+}
+
+fn error_response(id: Option<Value>, code: i16, message: &str) -> BrpResponse {
+    BrpResponse::new(
+        id,
+        Err(BrpError {
+            code,
+            message: message.to_string(),
+            data: None,
+        }),
+    )
+}
+
+/// A response body encoding the client accepts
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ContentEncoding {
+    Gzip,
+    Deflate,
+}
+
+impl ContentEncoding {
+    /// Preferred encoding allowed by an `Accept-Encoding` header
+    ///
+    /// The encoding with the highest quality wins, gzip on a tie. A quality of zero refuses an
+    /// encoding, and `*` covers encodings the header doesn't name.
+    fn negotiate(accept_encoding: &str) -> Option<Self> {
+        let mut gzip = None;
+        let mut deflate = None;
+        let mut wildcard = None;
+        for coding in accept_encoding.split(',') {
+            let mut parts = coding.split(';');
+            let name = parts.next().unwrap_or_default().trim();
+            let quality = parts.find_map(|param| {
+                let (key, value) = param.split_once('=')?;
+                key.trim().eq_ignore_ascii_case("q").then(|| value.trim())
+            });
+            let quality = match quality.map(str::parse::<f32>) {
+                None => 1.0,
+                Some(Ok(quality)) if (0.0..=1.0).contains(&quality) => quality,
+                // A malformed quality can't be trusted to allow the encoding
+                Some(_) => continue,
+            };
+            if name.eq_ignore_ascii_case("gzip") || name.eq_ignore_ascii_case("x-gzip") {
+                gzip = Some(quality);
+            } else if name.eq_ignore_ascii_case("deflate") {
+                deflate = Some(quality);
+            } else if name == "*" {
+                wildcard = Some(quality);
+            }
+        }
+
+        let gzip = gzip.or(wildcard).unwrap_or_default();
+        let deflate = deflate.or(wildcard).unwrap_or_default();
+        if gzip > 0.0 && gzip >= deflate {
+            Some(Self::Gzip)
+        } else if deflate > 0.0 {
+            Some(Self::Deflate)
+        } else {
+            None
+        }
+    }
+
+    const fn header_value(self) -> &'static str {
+        match self {
+            Self::Gzip => "gzip",
+            Self::Deflate => "deflate",
+        }
+    }
+
+    fn encode(self, body: &[u8]) -> std::io::Result<Vec<u8>> {
+        match self {
+            Self::Gzip => {
+                let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+                encoder.write_all(body)?;
+                encoder.finish()
+            },
+            Self::Deflate => {
+                let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+                encoder.write_all(body)?;
+                encoder.finish()
+            },
+        }
+    }
+}
+
+/// Body of a response - complete, or a watch streamed until the client disconnects
+enum HttpBody {
+    Complete(Full<Bytes>),
+    Watch(WatchStream),
+}
+
+impl Body for HttpBody {
+    type Data = Bytes;
+    type Error = Infallible;
+
+    fn poll_frame(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        match self.get_mut() {
+            Self::Complete(body) => Pin::new(body).poll_frame(cx),
+            Self::Watch(watch) => Pin::new(watch).poll_frame(cx),
+        }
+    }
+
+    // Passed through so complete responses are sent with a `Content-Length`
+    fn is_end_stream(&self) -> bool {
+        match self {
+            Self::Complete(body) => body.is_end_stream(),
+            Self::Watch(watch) => watch.is_end_stream(),
+        }
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        match self {
+            Self::Complete(body) => body.size_hint(),
+            Self::Watch(watch) => watch.size_hint(),
+        }
+    }
+}
+
+/// A watch's results, sent as server-sent events
+struct WatchStream {
+    id:      Option<Value>,
+    results: Pin<Box<async_channel::Receiver<BrpResult>>>,
+}
+
+impl Body for WatchStream {
+    type Data = Bytes;
+    type Error = Infallible;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        let Poll::Ready(result) = self.results.as_mut().poll_next(cx) else {
+            return Poll::Pending;
+        };
+        let event = result.and_then(|result| {
+            let response = BrpResponse::new(self.id.clone(), result);
+            let serialized = serde_json::to_string(&response).ok()?;
+            Some(Ok(Frame::data(Bytes::from(format!(
+                "data: {serialized}\n\n"
+            )))))
+        });
+        Poll::Ready(event)
+    }
+
+    fn is_end_stream(&self) -> bool { self.results.is_closed() && self.results.is_empty() }
+}
+
+fn status_response(status: StatusCode) -> Response<HttpBody> {
+    let mut response = Response::new(HttpBody::Complete(Full::default()));
+    *response.status_mut() = status;
+    response
+}
+
+/// A JSON response, compressed when it's large and the client accepts `encoding`
+fn json_response(value: &impl Serialize, encoding: Option<ContentEncoding>) -> Response<HttpBody> {
+    let Ok(body) = serde_json::to_vec(value) else {
+        return status_response(StatusCode::INTERNAL_SERVER_ERROR);
+    };
+    let compressed = encoding
+        .filter(|_| body.len() >= MIN_COMPRESSED_BYTES)
+        .and_then(|encoding| Some((encoding, encoding.encode(&body).ok()?)));
+
+    let mut response = match compressed {
+        Some((encoding, compressed)) => {
+            let mut response = Response::new(HttpBody::Complete(Full::from(compressed)));
+            response.headers_mut().insert(
+                header::CONTENT_ENCODING,
+                HeaderValue::from_static(encoding.header_value()),
+            );
+            response
+        },
+        None => Response::new(HttpBody::Complete(Full::from(body))),
+    };
+    let headers = response.headers_mut();
+    headers.insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("application/json"),
+    );
+    headers.insert(header::VARY, HeaderValue::from_static("accept-encoding"));
+    response
+}
+
+fn watch_response(watch: WatchStream) -> Response<HttpBody> {
+    let mut response = Response::new(HttpBody::Watch(watch));
+    let headers = response.headers_mut();
+    headers.insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("text/event-stream"),
+    );
+    headers.insert(header::CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+    response
+}
+
+#[cfg(test)]
+#[allow(clippy::expect_used)]
+mod tests {
+    use std::io::BufRead;
+    use std::io::BufReader;
+    use std::io::Read;
+    use std::time::Duration;
+
+    use bevy::tasks::TaskPool;
+    use flate2::read::GzDecoder;
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn negotiate_picks_the_highest_quality_encoding() {
+        assert_eq!(
+            ContentEncoding::negotiate("gzip, deflate"),
+            Some(ContentEncoding::Gzip)
+        );
+        assert_eq!(
+            ContentEncoding::negotiate("gzip;q=0.5, deflate"),
+            Some(ContentEncoding::Deflate)
+        );
+        assert_eq!(
+            ContentEncoding::negotiate("deflate;q=0.5, x-gzip;q=0.5"),
+            Some(ContentEncoding::Gzip)
+        );
+        assert_eq!(
+            ContentEncoding::negotiate("br, *;q=0.1"),
+            Some(ContentEncoding::Gzip)
+        );
+        assert_eq!(ContentEncoding::negotiate("br, identity"), None);
+    }
+
+    #[test]
+    fn negotiate_refuses_zero_quality() {
+        assert_eq!(ContentEncoding::negotiate("gzip;q=0"), None);
+        assert_eq!(
+            ContentEncoding::negotiate("GZIP; Q=0.000, deflate"),
+            Some(ContentEncoding::Deflate)
+        );
+        assert_eq!(
+            ContentEncoding::negotiate("*, gzip;q=0.0"),
+            Some(ContentEncoding::Deflate)
+        );
+        assert_eq!(ContentEncoding::negotiate("gzip;q=bogus"), None);
+        assert_eq!(ContentEncoding::negotiate("*;q=0"), None);
+    }
+
+    /// Read one HTTP/1.1 response, returning its lowercase headers and its body
+    fn read_response(reader: &mut impl BufRead) -> (String, Vec<u8>) {
+        let mut head = String::new();
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).expect("read header");
+            if line == "\r\n" {
+                break;
+            }
+            head.push_str(&line.to_ascii_lowercase());
+        }
+        let length = head
+            .lines()
+            .find_map(|line| line.strip_prefix("content-length: "))
+            .map_or(0, |length| length.trim().parse().expect("content length"));
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body).expect("read body");
+        (head, body)
+    }
+
+    #[test]
+    fn serves_requests_on_a_kept_alive_connection() {
+        IoTaskPool::get_or_init(TaskPool::new);
+        let listener = Async::<TcpListener>::bind(([127, 0, 0, 1], 0)).expect("bind a free port");
+        let port = listener
+            .get_ref()
+            .local_addr()
+            .expect("local address")
+            .port();
+
+        // Stand in for the app, answering every request with a large result
+        let (sender, receiver) = async_channel::unbounded::<BrpMessage>();
+        std::thread::spawn(move || {
+            while let Ok(message) = receiver.recv_blocking() {
+                let result = json!({"method": message.method, "padding": "x".repeat(4096)});
+                let _ = message.sender.send_blocking(Ok(result));
+            }
+        });
+        let headers = CompressedHttpHeaders(vec![(
+            header::ACCESS_CONTROL_ALLOW_ORIGIN,
+            HeaderValue::from_static("*"),
+        )]);
+        IoTaskPool::get()
+            .spawn(listen(listener, sender, headers))
+            .detach();
+
+        let stream = TcpStream::connect(("127.0.0.1", port)).expect("connect");
+        stream
+            .set_read_timeout(Some(Duration::from_secs(10)))
+            .expect("set timeout");
+        let mut writer = stream.try_clone().expect("clone stream");
+        let mut reader = BufReader::new(stream);
+
+        let request = r#"{"jsonrpc":"2.0","id":1,"method":"world.query"}"#;
+        for _ in 0..2 {
+            write!(
+                writer,
+                "POST /jsonrpc HTTP/1.1\r\nHost: localhost\r\nAccept-Encoding: gzip\r\n\
+                 Content-Length: {}\r\n\r\n{request}",
+                request.len()
+            )
+            .expect("send request");
+            let (head, body) = read_response(&mut reader);
+            assert!(head.starts_with("http/1.1 200"), "{head}");
+            assert!(head.contains("content-encoding: gzip"), "{head}");
+            assert!(head.contains("access-control-allow-origin: *"), "{head}");
+            let mut json = String::new();
+            GzDecoder::new(body.as_slice())
+                .read_to_string(&mut json)
+                .expect("gzip body");
+            let response: Value = serde_json::from_str(&json).expect("json body");
+            assert_eq!(response["id"], 1);
+            assert_eq!(response["result"]["method"], "world.query");
+        }
+
+        write!(
+            writer,
+            "POST /elsewhere HTTP/1.1\r\nHost: localhost\r\nContent-Length: 2\r\n\r\n{{}}"
+        )
+        .expect("send request");
+        let (head, _) = read_response(&mut reader);
+        assert!(head.starts_with("http/1.1 404"), "{head}");
+
+        write!(writer, "OPTIONS / HTTP/1.1\r\nHost: localhost\r\n\r\n").expect("send request");
+        let (head, _) = read_response(&mut reader);
+        assert!(head.starts_with("http/1.1 204"), "{head}");
+        assert!(head.contains("access-control-allow-origin: *"), "{head}");
+    }
+}
@@ -0,0 +1,250 @@
+//! Deterministic run mode handler for BRP extras
+//!
+//! Reproducible automated tests need the same random numbers and the same frame deltas on every
+//! run. Bevy has no global RNG, so the seed lives in a `BrpRngSeed` resource the app inserts and
+//! seeds its own RNG from - `brp_extras/determinism` only changes it when the app has one. Fixed
+//! delta time replaces the wall clock with `TimeUpdateStrategy::ManualDuration`, so every frame
+//! advances time by the same amount however long it took. The relative speed scales how fast
+//! virtual time follows it, e.g. to run a test at twice the speed.
+
+use bevy::prelude::*;
+use bevy::remote::BrpError;
+use bevy::remote::BrpResult;
+use bevy::remote::error_codes::INVALID_PARAMS;
+use bevy::remote::error_codes::INVALID_REQUEST;
+use bevy::time::TimeUpdateStrategy;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+use serde_json::json;
+
+use crate::time_config;
+
+/// Seed for the app's random number generators, set by `brp_extras/determinism`
+///
+/// Insert it to opt in, and reseed your RNG when it changes:
+/// ```no_run
+/// use bevy::prelude::*;
+/// use bevy_brp_extras::BrpRngSeed;
+///
+/// fn reseed(seed: Res<BrpRngSeed>) {
+///     if seed.is_changed() {
+///         // e.g. *rng = ChaCha8Rng::seed_from_u64(seed.0);
+///     }
+/// }
+/// ```
+#[derive(Resource, Reflect, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[reflect(Resource)]
+pub struct BrpRngSeed(pub u64);
+
+/// Request structure for `determinism` - omitted fields are left unchanged
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DeterminismRequest {
+    /// Seed to store in the app's `BrpRngSeed` resource
+    #[serde(default)]
+    pub seed:                Option<u64>,
+    /// Seconds every frame advances time by, whatever its real duration
+    #[serde(default)]
+    pub fixed_delta_seconds: Option<f64>,
+    /// `true` returns to advancing time by the real frame duration
+    #[serde(default)]
+    pub real_time:           Option<bool>,
+    /// Rate virtual time advances at relative to real time, e.g. `2.0` for double speed
+    #[serde(default)]
+    pub relative_speed:      Option<f64>,
+}
+
+/// Determinism-relevant settings, as reported by `brp_extras/determinism`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeterminismResponse {
+    /// Current RNG seed - `None` when the app has no `BrpRngSeed` resource
+    pub seed:                   Option<u64>,
+    /// Whether the app has a `BrpRngSeed` resource to seed from
+    pub seed_supported:         bool,
+    /// Seconds every frame advances time by - `None` when time follows the real clock
+    pub fixed_delta_seconds:    Option<f64>,
+    /// Seconds between `FixedUpdate` runs
+    pub fixed_timestep_seconds: f64,
+    /// Largest frame delta, in seconds, virtual time advances by
+    pub max_delta_seconds:      f64,
+    /// Rate virtual time advances at relative to real time
+    pub relative_speed:         f64,
+    /// Whether virtual time is paused
+    pub paused:                 bool,
+    /// Settings changed by this request
+    pub changed:                Vec<String>,
+}
+
+/// Handler for `determinism` requests
+///
+/// Without parameters the current settings are returned unchanged.
+pub fn handler(In(params): In<Option<Value>>, world: &mut World) -> BrpResult {
+    let request: DeterminismRequest = params
+        .map(serde_json::from_value)
+        .transpose()
+        .map_err(|e| BrpError {
+            code:    INVALID_PARAMS,
+            message: format!("Invalid request format: {e}"),
+            data:    None,
+        })?
+        .unwrap_or_default();
+
+    let fixed_delta =
+        time_config::positive_duration("fixed_delta_seconds", request.fixed_delta_seconds)?;
+    let real_time = request.real_time == Some(true);
+    if fixed_delta.is_some() && real_time {
+        return Err(BrpError {
+            code:    INVALID_PARAMS,
+            message: "'fixed_delta_seconds' and 'real_time' can't be combined".to_string(),
+            data:    None,
+        });
+    }
+    if let Some(speed) = request.relative_speed
+        && !(speed.is_finite() && speed >= 0.0)
+    {
+        return Err(BrpError {
+            code:    INVALID_PARAMS,
+            message: format!("'relative_speed' must be a non-negative number, got {speed}"),
+            data:    None,
+        });
+    }
+    if request.seed.is_some() && !world.contains_resource::<BrpRngSeed>() {
+        return Err(BrpError {
+            code:    INVALID_REQUEST,
+            message: "The app has no BrpRngSeed resource - insert it and seed your RNG from it \
+                      to make seeds settable"
+                .to_string(),
+            data:    None,
+        });
+    }
+
+    // Both are read for the response - checked before anything changes, so a request that fails
+    // leaves every setting as it was
+    time_config::time_mut::<Virtual>(world)?;
+    time_config::time_mut::<Fixed>(world)?;
+
+    let mut changed = Vec::new();
+    if let Some(seed) = request.seed
+        && let Some(mut seed_resource) = world.get_resource_mut::<BrpRngSeed>()
+    {
+        // Set even when unchanged, so the app reseeds and replays the same sequence
+        seed_resource.0 = seed;
+        changed.push("seed".to_string());
+    }
+    if let Some(delta) = fixed_delta {
+        world.insert_resource(TimeUpdateStrategy::ManualDuration(delta));
+        changed.push("fixed_delta_seconds".to_string());
+    }
+    if real_time {
+        world.insert_resource(TimeUpdateStrategy::Automatic);
+        changed.push("real_time".to_string());
+    }
+    if let Some(speed) = request.relative_speed {
+        time_config::time_mut::<Virtual>(world)?.set_relative_speed_f64(speed);
+        changed.push("relative_speed".to_string());
+    }
+
+    let seed = world.get_resource::<BrpRngSeed>().map(|seed| seed.0);
+    let fixed_delta_seconds = match world.get_resource::<TimeUpdateStrategy>() {
+        Some(TimeUpdateStrategy::ManualDuration(delta)) => Some(delta.as_secs_f64()),
+        _ => None,
+    };
+    let fixed_timestep_seconds = time_config::time_mut::<Fixed>(world)?
+        .timestep()
+        .as_secs_f64();
+    let virtual_time = time_config::time_mut::<Virtual>(world)?;
+
+    Ok(json!(DeterminismResponse {
+        seed,
+        seed_supported: seed.is_some(),
+        fixed_delta_seconds,
+        fixed_timestep_seconds,
+        max_delta_seconds: virtual_time.max_delta().as_secs_f64(),
+        relative_speed: virtual_time.relative_speed_f64(),
+        paused: virtual_time.is_paused(),
+        changed,
+    }))
+}
+
+#[cfg(test)]
+#[allow(clippy::expect_used)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    fn time_world() -> World {
+        let mut world = World::new();
+        world.insert_resource(Time::<Virtual>::default());
+        world.insert_resource(Time::<Fixed>::default());
+        world
+    }
+
+    fn determinism(world: &mut World, params: Value) -> Result<DeterminismResponse, BrpError> {
+        handler(In(Some(params)), world)
+            .map(|response| serde_json::from_value(response).expect("response should deserialize"))
+    }
+
+    #[test]
+    fn sets_seed_and_fixed_delta() {
+        let mut world = time_world();
+        world.insert_resource(BrpRngSeed(1));
+
+        let response = determinism(
+            &mut world,
+            json!({"seed": 42, "fixed_delta_seconds": 0.015_625}),
+        )
+        .expect("valid request");
+
+        assert_eq!(response.seed, Some(42));
+        assert_eq!(response.fixed_delta_seconds, Some(0.015_625));
+        assert_eq!(response.changed, vec!["seed", "fixed_delta_seconds"]);
+        assert_eq!(world.resource::<BrpRngSeed>(), &BrpRngSeed(42));
+        assert!(matches!(
+            world.resource::<TimeUpdateStrategy>(),
+            TimeUpdateStrategy::ManualDuration(delta) if *delta == Duration::from_micros(15_625)
+        ));
+
+        let response = determinism(&mut world, json!({"real_time": true})).expect("valid request");
+        assert_eq!(response.fixed_delta_seconds, None);
+    }
+
+    #[test]
+    fn sets_relative_speed() {
+        let mut world = time_world();
+
+        let response =
+            determinism(&mut world, json!({"relative_speed": 2.0})).expect("valid request");
+
+        assert!((response.relative_speed - 2.0).abs() < f64::EPSILON);
+        assert_eq!(response.changed, vec!["relative_speed"]);
+        let error =
+            determinism(&mut world, json!({"relative_speed": -1.0})).expect_err("negative speed");
+        assert_eq!(error.code, INVALID_PARAMS);
+    }
+
+    #[test]
+    fn rejects_a_seed_without_a_seed_resource() {
+        let mut world = time_world();
+
+        let error = determinism(&mut world, json!({"seed": 42, "fixed_delta_seconds": 0.01}))
+            .expect_err("no seed resource");
+
+        assert_eq!(error.code, INVALID_REQUEST);
+        assert!(!world.contains_resource::<TimeUpdateStrategy>());
+    }
+
+    #[test]
+    fn changes_nothing_without_time_resources() {
+        let mut world = World::new();
+        world.insert_resource(BrpRngSeed(1));
+
+        let error = determinism(&mut world, json!({"seed": 42, "fixed_delta_seconds": 0.01}))
+            .expect_err("no time resources");
+
+        assert_eq!(error.code, bevy::remote::error_codes::INTERNAL_ERROR);
+        assert_eq!(world.resource::<BrpRngSeed>(), &BrpRngSeed(1));
+        assert!(!world.contains_resource::<TimeUpdateStrategy>());
+    }
+}
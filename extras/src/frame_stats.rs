@@ -0,0 +1,331 @@
+//! Frame-time statistics handler for BRP extras
+//!
+//! A BRP request can't wait for frames to pass, so collection takes two calls: one with
+//! `duration_ms` starts recording real-time frame deltas, and calls without it poll until the
+//! window has passed and the statistics are ready. Percentiles and worst frames show the hitches
+//! an average FPS reading hides.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy::remote::BrpError;
+use bevy::remote::BrpResult;
+use bevy::remote::error_codes::INVALID_PARAMS;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+use serde_json::json;
+
+/// Longest collection window in milliseconds
+const MAX_DURATION_MS: u32 = 300_000;
+
+/// Upper bounds of the histogram buckets in milliseconds - 240, 120, 60, 30, 20 and 10 FPS, with
+/// a final bucket for anything slower
+const HISTOGRAM_BOUNDS_MS: [f64; 6] = [4.17, 8.33, 16.67, 33.33, 50.0, 100.0];
+
+/// Number of slowest frames reported
+const WORST_FRAME_COUNT: usize = 5;
+
+/// Request structure for `collect_frame_stats`
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CollectFrameStatsRequest {
+    /// Start a new collection over this many milliseconds - omit to poll the current one
+    #[serde(default)]
+    pub duration_ms: Option<u32>,
+}
+
+/// Response to `collect_frame_stats`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum CollectFrameStatsResponse {
+    /// Frames are still being recorded
+    Collecting {
+        /// Length of the collection window
+        duration_ms:     u32,
+        /// Time left before the statistics are ready
+        remaining_ms:    u64,
+        /// Frames recorded so far
+        frames_recorded: usize,
+    },
+    /// The collection window has passed
+    Complete(FrameStats),
+}
+
+/// Frame-time statistics over a collection window
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FrameStats {
+    /// Length of the collection window
+    pub duration_ms:  u32,
+    /// Frames recorded
+    pub frame_count:  usize,
+    /// Frames per second over the window
+    pub average_fps:  f64,
+    /// Mean frame time
+    pub mean_ms:      f64,
+    /// Fastest frame time
+    pub min_ms:       f64,
+    /// Slowest frame time
+    pub max_ms:       f64,
+    /// Median frame time
+    pub p50_ms:       f64,
+    /// 90th percentile frame time
+    pub p90_ms:       f64,
+    /// 95th percentile frame time
+    pub p95_ms:       f64,
+    /// 99th percentile frame time
+    pub p99_ms:       f64,
+    /// Frame counts per frame-time bucket, fastest first
+    pub histogram:    Vec<HistogramBucket>,
+    /// Slowest frames, slowest first
+    pub worst_frames: Vec<FrameSample>,
+}
+
+/// Frame count for a range of frame times
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistogramBucket {
+    /// Exclusive lower bound in milliseconds
+    pub min_ms: f64,
+    /// Inclusive upper bound in milliseconds - `None` for the last, unbounded bucket
+    pub max_ms: Option<f64>,
+    /// Frames in the range
+    pub count:  usize,
+}
+
+/// A recorded frame
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct FrameSample {
+    /// Frame index within the collection, from 0
+    pub frame:         usize,
+    /// Milliseconds from the start of the collection to the end of the frame
+    pub timestamp_ms:  f64,
+    /// Frame time in milliseconds
+    pub frame_time_ms: f64,
+}
+
+/// Resource holding the current collection - absent until the first collection starts
+#[derive(Resource)]
+pub enum FrameStatsCollection {
+    /// Recording frames until `elapsed` reaches `duration`
+    Collecting {
+        /// Length of the collection window
+        duration: Duration,
+        /// Real time recorded so far
+        elapsed:  Duration,
+        /// Frame times in milliseconds, in frame order
+        samples:  Vec<f64>,
+    },
+    /// Statistics of the finished collection
+    Complete(FrameStats),
+}
+
+/// Handler for `collect_frame_stats` requests
+pub fn handler(In(params): In<Option<Value>>, world: &mut World) -> BrpResult {
+    let request: CollectFrameStatsRequest = params
+        .map(serde_json::from_value)
+        .transpose()
+        .map_err(|e| BrpError {
+            code:    INVALID_PARAMS,
+            message: format!("Invalid request format: {e}"),
+            data:    None,
+        })?
+        .unwrap_or_default();
+
+    if let Some(duration_ms) = request.duration_ms {
+        if duration_ms == 0 || duration_ms > MAX_DURATION_MS {
+            return Err(BrpError {
+                code:    INVALID_PARAMS,
+                message: format!(
+                    "'duration_ms' must be between 1 and {MAX_DURATION_MS}, got {duration_ms}"
+                ),
+                data:    None,
+            });
+        }
+        world.insert_resource(FrameStatsCollection::Collecting {
+            duration: Duration::from_millis(u64::from(duration_ms)),
+            elapsed:  Duration::ZERO,
+            samples:  Vec::new(),
+        });
+    }
+
+    let collection = world
+        .get_resource::<FrameStatsCollection>()
+        .ok_or_else(|| BrpError {
+            code:    INVALID_PARAMS,
+            message: "No frame stats collection - pass 'duration_ms' to start one".to_string(),
+            data:    None,
+        })?;
+
+    let response = match collection {
+        FrameStatsCollection::Collecting {
+            duration,
+            elapsed,
+            samples,
+        } => CollectFrameStatsResponse::Collecting {
+            duration_ms:     duration_millis(*duration),
+            remaining_ms:    u64::from(duration_millis(duration.saturating_sub(*elapsed))),
+            frames_recorded: samples.len(),
+        },
+        FrameStatsCollection::Complete(stats) => CollectFrameStatsResponse::Complete(stats.clone()),
+    };
+    Ok(json!(response))
+}
+
+/// System recording each frame's real-time delta while a collection is running
+pub fn record_frame_times(collection: Option<ResMut<FrameStatsCollection>>, time: Res<Time<Real>>) {
+    let Some(mut collection) = collection else {
+        return;
+    };
+    let FrameStatsCollection::Collecting {
+        duration,
+        elapsed,
+        samples,
+    } = collection.as_mut()
+    else {
+        return;
+    };
+
+    *elapsed += time.delta();
+    samples.push(time.delta().as_secs_f64() * 1000.0);
+
+    if *elapsed >= *duration {
+        let stats = FrameStats::from_samples(duration_millis(*duration), samples);
+        debug!(
+            "Frame stats collected: {} frames, p99 {:.2}ms",
+            stats.frame_count, stats.p99_ms
+        );
+        *collection = FrameStatsCollection::Complete(stats);
+    }
+}
+
+impl FrameStats {
+    /// Statistics over frame times in milliseconds, given in frame order
+    #[allow(clippy::cast_precision_loss)]
+    fn from_samples(duration_ms: u32, samples: &[f64]) -> Self {
+        let frame_count = samples.len();
+        let total_ms: f64 = samples.iter().sum();
+
+        let mut sorted = samples.to_vec();
+        sorted.sort_by(f64::total_cmp);
+
+        let mut timestamp_ms = 0.0;
+        let mut frames: Vec<FrameSample> = samples
+            .iter()
+            .enumerate()
+            .map(|(frame, &frame_time_ms)| {
+                timestamp_ms += frame_time_ms;
+                FrameSample {
+                    frame,
+                    timestamp_ms,
+                    frame_time_ms,
+                }
+            })
+            .collect();
+        frames.sort_by(|a, b| b.frame_time_ms.total_cmp(&a.frame_time_ms));
+        frames.truncate(WORST_FRAME_COUNT);
+
+        Self {
+            duration_ms,
+            frame_count,
+            average_fps: if total_ms > 0.0 {
+                frame_count as f64 * 1000.0 / total_ms
+            } else {
+                0.0
+            },
+            mean_ms: if frame_count > 0 {
+                total_ms / frame_count as f64
+            } else {
+                0.0
+            },
+            min_ms: sorted.first().copied().unwrap_or_default(),
+            max_ms: sorted.last().copied().unwrap_or_default(),
+            p50_ms: percentile(&sorted, 50),
+            p90_ms: percentile(&sorted, 90),
+            p95_ms: percentile(&sorted, 95),
+            p99_ms: percentile(&sorted, 99),
+            histogram: histogram(&sorted),
+            worst_frames: frames,
+        }
+    }
+}
+
+/// Nearest-rank percentile of sorted frame times
+fn percentile(sorted: &[f64], percent: usize) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let rank = (sorted.len() * percent).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
+
+/// Frame counts per `HISTOGRAM_BOUNDS_MS` bucket of sorted frame times
+fn histogram(sorted: &[f64]) -> Vec<HistogramBucket> {
+    let mut buckets = Vec::with_capacity(HISTOGRAM_BOUNDS_MS.len() + 1);
+    let mut min_ms = 0.0;
+    let mut counted = 0;
+    for max_ms in HISTOGRAM_BOUNDS_MS {
+        let count = sorted.partition_point(|&frame_time| frame_time <= max_ms) - counted;
+        buckets.push(HistogramBucket {
+            min_ms,
+            max_ms: Some(max_ms),
+            count,
+        });
+        counted += count;
+        min_ms = max_ms;
+    }
+    buckets.push(HistogramBucket {
+        min_ms,
+        max_ms: None,
+        count: sorted.len() - counted,
+    });
+    buckets
+}
+
+/// Whole milliseconds in a collection duration, which is capped well below `u32::MAX`
+fn duration_millis(duration: Duration) -> u32 {
+    u32::try_from(duration.as_millis()).unwrap_or(u32::MAX)
+}
+
+#[cfg(test)]
+#[allow(clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stats_report_percentiles_histogram_and_worst_frames() {
+        // 97 smooth frames and three hitches
+        let mut samples = vec![16.0; 97];
+        samples.insert(10, 40.0);
+        samples.insert(50, 120.0);
+        samples.push(60.0);
+
+        let stats = FrameStats::from_samples(2000, &samples);
+
+        assert_eq!(stats.frame_count, 100);
+        assert!((stats.p50_ms - 16.0).abs() < f64::EPSILON);
+        assert!((stats.p99_ms - 60.0).abs() < f64::EPSILON);
+        assert!((stats.max_ms - 120.0).abs() < f64::EPSILON);
+
+        let counts: Vec<usize> = stats.histogram.iter().map(|bucket| bucket.count).collect();
+        assert_eq!(counts, vec![0, 0, 97, 0, 1, 1, 1]);
+        assert_eq!(stats.histogram.last().and_then(|b| b.max_ms), None);
+
+        let worst: Vec<usize> = stats.worst_frames.iter().map(|f| f.frame).collect();
+        assert_eq!(worst[..3], [50, 99, 10]);
+        let hitch = stats.worst_frames[0];
+        assert!((hitch.timestamp_ms - 49.0f64.mul_add(16.0, 40.0 + 120.0)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn polling_without_a_collection_is_an_error() {
+        let mut world = World::new();
+
+        let error = handler(In(None), &mut world).expect_err("no collection started");
+        assert_eq!(error.code, INVALID_PARAMS);
+
+        let started = handler(In(Some(json!({"duration_ms": 1000}))), &mut world)
+            .expect("collection should start");
+        assert_eq!(started["status"], "collecting");
+        assert_eq!(started["remaining_ms"], 1000);
+    }
+}
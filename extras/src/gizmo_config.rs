@@ -0,0 +1,195 @@
+//! Gizmo configuration handler for BRP extras
+//!
+//! Every gizmo config group registered with `init_gizmo_group` has a `GizmoConfig` in the
+//! `GizmoConfigStore`. Groups are addressed by their short type name, e.g.
+//! `DefaultGizmoConfigGroup` or `AabbGizmoConfigGroup`.
+
+use bevy::gizmos::config::GizmoConfigStore;
+use bevy::prelude::*;
+use bevy::remote::BrpError;
+use bevy::remote::BrpResult;
+use bevy::remote::error_codes::INTERNAL_ERROR;
+use bevy::remote::error_codes::INVALID_PARAMS;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+use serde_json::json;
+
+/// Request structure for `gizmo_config` - omitted settings are left unchanged
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GizmoConfigRequest {
+    /// Only read or change this group - `None` applies to every group
+    #[serde(default)]
+    pub group:      Option<String>,
+    /// Draw (`true`) or hide (`false`) the group's gizmos
+    #[serde(default)]
+    pub enabled:    Option<bool>,
+    /// Line width in pixels, or in world units for perspective lines
+    #[serde(default)]
+    pub line_width: Option<f32>,
+    /// Depth bias in `-1.0..=1.0` - `-1.0` draws gizmos on top of everything
+    #[serde(default)]
+    pub depth_bias: Option<f32>,
+}
+
+impl GizmoConfigRequest {
+    const fn changes_config(&self) -> bool {
+        self.enabled.is_some() || self.line_width.is_some() || self.depth_bias.is_some()
+    }
+
+    fn validate(&self) -> Result<(), BrpError> {
+        if let Some(width) = self.line_width
+            && !(width.is_finite() && width > 0.0)
+        {
+            return Err(invalid_params(format!(
+                "'line_width' must be a positive number, got {width}"
+            )));
+        }
+        if let Some(bias) = self.depth_bias
+            && !(-1.0..=1.0).contains(&bias)
+        {
+            return Err(invalid_params(format!(
+                "'depth_bias' must be between -1.0 and 1.0, got {bias}"
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// A gizmo config group's settings, as reported by `brp_extras/gizmo_config`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GizmoGroupInfo {
+    /// Short type name of the config group
+    pub group:      String,
+    /// Whether the group's gizmos are drawn
+    pub enabled:    bool,
+    /// Line width in pixels, or in world units for perspective lines
+    pub line_width: f32,
+    /// Depth bias in `-1.0..=1.0`
+    pub depth_bias: f32,
+}
+
+/// Handler for `gizmo_config` requests
+///
+/// Returns the settings of the selected groups, after applying any changes, sorted by group name.
+pub fn handler(In(params): In<Option<Value>>, world: &mut World) -> BrpResult {
+    let request: GizmoConfigRequest = params
+        .map(serde_json::from_value)
+        .transpose()
+        .map_err(|e| invalid_params(format!("Invalid request format: {e}")))?
+        .unwrap_or_default();
+    request.validate()?;
+
+    let mut store = world
+        .get_resource_mut::<GizmoConfigStore>()
+        .ok_or_else(|| BrpError {
+            code:    INTERNAL_ERROR,
+            message: "GizmoConfigStore not found - is GizmoPlugin installed?".to_string(),
+            data:    None,
+        })?;
+
+    let mut groups = Vec::new();
+    let mut available = Vec::new();
+    for (_, config, group) in store.iter_mut() {
+        let name = (*group).reflect_short_type_path().to_string();
+        available.push(name.clone());
+        if request.group.as_ref().is_some_and(|wanted| *wanted != name) {
+            continue;
+        }
+
+        if let Some(enabled) = request.enabled {
+            config.enabled = enabled;
+        }
+        if let Some(width) = request.line_width {
+            config.line.width = width;
+        }
+        if let Some(bias) = request.depth_bias {
+            config.depth_bias = bias;
+        }
+        groups.push(GizmoGroupInfo {
+            group:      name,
+            enabled:    config.enabled,
+            line_width: config.line.width,
+            depth_bias: config.depth_bias,
+        });
+    }
+
+    if let Some(wanted) = &request.group
+        && groups.is_empty()
+    {
+        available.sort();
+        return Err(invalid_params(format!(
+            "Unknown gizmo config group '{wanted}' - available groups: {}",
+            available.join(", ")
+        )));
+    }
+    groups.sort_by(|a, b| a.group.cmp(&b.group));
+
+    Ok(json!({
+        "groups": groups,
+        "updated": request.changes_config(),
+    }))
+}
+
+const fn invalid_params(message: String) -> BrpError {
+    BrpError {
+        code: INVALID_PARAMS,
+        message,
+        data: None,
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::expect_used)]
+mod tests {
+    use bevy::gizmos::aabb::AabbGizmoConfigGroup;
+
+    use super::*;
+
+    fn gizmo_world() -> World {
+        let mut store = GizmoConfigStore::default();
+        store.insert(GizmoConfig::default(), DefaultGizmoConfigGroup);
+        store.insert(GizmoConfig::default(), AabbGizmoConfigGroup::default());
+        let mut world = World::new();
+        world.insert_resource(store);
+        world
+    }
+
+    #[test]
+    fn updates_only_the_selected_group() {
+        let mut world = gizmo_world();
+
+        let result = handler(
+            In(Some(json!({
+                "group": "AabbGizmoConfigGroup",
+                "enabled": false,
+                "line_width": 4.0
+            }))),
+            &mut world,
+        )
+        .expect("valid request");
+
+        assert_eq!(result["groups"].as_array().map(Vec::len), Some(1));
+        let store = world.resource::<GizmoConfigStore>();
+        let (aabb, _) = store.config::<AabbGizmoConfigGroup>();
+        assert!(!aabb.enabled);
+        assert!((aabb.line.width - 4.0).abs() < f32::EPSILON);
+        let (default, _) = store.config::<DefaultGizmoConfigGroup>();
+        assert!(default.enabled);
+    }
+
+    #[test]
+    fn rejects_unknown_groups_and_out_of_range_values() {
+        let mut world = gizmo_world();
+
+        for params in [
+            json!({"group": "MissingGizmoConfigGroup"}),
+            json!({"depth_bias": 2.0}),
+            json!({"line_width": 0.0}),
+        ] {
+            let error = handler(In(Some(params)), &mut world).expect_err("invalid request");
+            assert_eq!(error.code, INVALID_PARAMS);
+        }
+    }
+}
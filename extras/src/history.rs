@@ -0,0 +1,411 @@
+//! Component change history handlers for BRP extras
+//!
+//! Questions like "what changed on the player in the last 5 seconds?" can't be answered from the
+//! current world alone. `brp_extras/track_entity` opts an entity in: at the end of every frame
+//! its reflected components are compared with the previous frame, and each component that was
+//! added, removed or mutated is recorded with its old and new value. `brp_extras/get_history`
+//! reads the recorded changes.
+//!
+//! Tracking serializes every tracked component each frame, so it is meant for a handful of
+//! entities at a time. Changes are kept in a ring buffer of `MAX_HISTORY_EVENTS`, oldest dropped
+//! first.
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::collections::VecDeque;
+
+use bevy::diagnostic::FrameCount;
+use bevy::ecs::component::ComponentInfo;
+use bevy::ecs::reflect::ReflectComponent;
+use bevy::prelude::*;
+use bevy::reflect::TypeRegistry;
+use bevy::reflect::serde::TypedReflectSerializer;
+use bevy::remote::BrpError;
+use bevy::remote::BrpResult;
+use bevy::remote::error_codes::INVALID_PARAMS;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+use serde_json::json;
+
+/// Changes kept at most across all tracked entities - the oldest are dropped first
+const MAX_HISTORY_EVENTS: usize = 10_000;
+
+/// Entities that can be tracked at once
+const MAX_TRACKED_ENTITIES: usize = 64;
+
+/// Request structure for `track_entity`
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TrackEntityRequest {
+    /// Entity to track
+    pub entity:     Entity,
+    /// Only track these components, by full type path - omit for every reflected component
+    #[serde(default)]
+    pub components: Option<Vec<String>>,
+    /// `true` stops tracking the entity instead - its recorded changes are kept
+    #[serde(default)]
+    pub untrack:    bool,
+}
+
+/// Request structure for `get_history`
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GetHistoryRequest {
+    /// Only return changes of this entity
+    #[serde(default)]
+    pub entity:        Option<Entity>,
+    /// Only return changes of this component, by full type path
+    #[serde(default)]
+    pub component:     Option<String>,
+    /// Only return changes from the last this many seconds
+    #[serde(default)]
+    pub since_seconds: Option<f64>,
+    /// Return at most this many changes, the most recent ones
+    #[serde(default)]
+    pub limit:         Option<usize>,
+}
+
+/// What happened to a component
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    /// The component was inserted
+    Added,
+    /// The component was removed
+    Removed,
+    /// The component's value changed
+    Mutated,
+    /// The entity was despawned - tracking ends with it
+    Despawned,
+}
+
+/// A recorded change of a tracked entity
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChangeEvent {
+    /// `Time<Real>` elapsed seconds at the end of the frame the change was seen in
+    pub time:      f64,
+    /// Frame the change was seen in
+    pub frame:     u32,
+    /// The entity
+    pub entity:    u64,
+    /// Full type path of the component - `None` for a despawn
+    pub component: Option<String>,
+    /// What happened
+    pub change:    ChangeKind,
+    /// Value before the change
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old:       Option<Value>,
+    /// Value after the change
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new:       Option<Value>,
+}
+
+/// An entity being tracked, with its components as of the last frame
+struct TrackedEntity {
+    components: Option<Vec<String>>,
+    snapshot:   BTreeMap<String, Value>,
+}
+
+/// Tracked entities and their recorded changes
+#[derive(Resource, Default)]
+pub struct EntityHistory {
+    tracked: HashMap<Entity, TrackedEntity>,
+    events:  VecDeque<ChangeEvent>,
+    /// Changes dropped because the buffer was full
+    dropped: u64,
+}
+
+impl EntityHistory {
+    /// Keep `events`, dropping the oldest once the buffer is full
+    fn record(&mut self, events: impl IntoIterator<Item = ChangeEvent>) {
+        for event in events {
+            if self.events.len() >= MAX_HISTORY_EVENTS {
+                self.events.pop_front();
+                self.dropped += 1;
+            }
+            self.events.push_back(event);
+        }
+    }
+
+    /// Tracked entities, sorted
+    fn tracked_entities(&self) -> Vec<u64> {
+        let mut entities: Vec<u64> = self.tracked.keys().map(|entity| entity.to_bits()).collect();
+        entities.sort_unstable();
+        entities
+    }
+}
+
+/// Handler for `track_entity` requests
+pub fn track_entity_handler(In(params): In<Option<Value>>, world: &mut World) -> BrpResult {
+    let request: TrackEntityRequest = params
+        .ok_or_else(|| BrpError {
+            code:    INVALID_PARAMS,
+            message: "Missing request parameters".to_string(),
+            data:    None,
+        })
+        .and_then(|params| {
+            serde_json::from_value(params).map_err(|e| BrpError {
+                code:    INVALID_PARAMS,
+                message: format!("Invalid request format: {e}"),
+                data:    None,
+            })
+        })?;
+    let entity = request.entity;
+
+    if request.untrack {
+        let mut history = world.resource_mut::<EntityHistory>();
+        let was_tracked = history.tracked.remove(&entity).is_some();
+        return Ok(json!({
+            "entity": entity.to_bits(),
+            "tracking": false,
+            "tracked_entities": history.tracked_entities(),
+            "message": if was_tracked {
+                format!("Stopped tracking entity {entity}")
+            } else {
+                format!("Entity {entity} was not tracked")
+            },
+        }));
+    }
+
+    let baseline = {
+        let registry = world.resource::<AppTypeRegistry>().read();
+        snapshot(world, &registry, entity, request.components.as_deref())
+    }
+    .ok_or_else(|| BrpError::entity_not_found(entity))?;
+
+    let mut history = world.resource_mut::<EntityHistory>();
+    if !history.tracked.contains_key(&entity) && history.tracked.len() >= MAX_TRACKED_ENTITIES {
+        return Err(BrpError {
+            code:    INVALID_PARAMS,
+            message: format!(
+                "Already tracking {MAX_TRACKED_ENTITIES} entities - untrack one before tracking \
+                 entity {entity}"
+            ),
+            data:    None,
+        });
+    }
+    let components: Vec<String> = baseline.keys().cloned().collect();
+    history.tracked.insert(
+        entity,
+        TrackedEntity {
+            components: request.components,
+            snapshot:   baseline,
+        },
+    );
+
+    Ok(json!({
+        "entity": entity.to_bits(),
+        "tracking": true,
+        "components": components,
+        "tracked_entities": history.tracked_entities(),
+        "message": format!("Tracking {} components of entity {entity}", components.len()),
+    }))
+}
+
+/// Handler for `get_history` requests
+///
+/// Returns the matching changes oldest first.
+pub fn get_history_handler(In(params): In<Option<Value>>, world: &mut World) -> BrpResult {
+    let request: GetHistoryRequest = params
+        .map(serde_json::from_value)
+        .transpose()
+        .map_err(|e| BrpError {
+            code:    INVALID_PARAMS,
+            message: format!("Invalid request format: {e}"),
+            data:    None,
+        })?
+        .unwrap_or_default();
+
+    let now = world
+        .get_resource::<Time<Real>>()
+        .map_or(0.0, Time::elapsed_secs_f64);
+    let history = world.resource::<EntityHistory>();
+    let mut events: Vec<&ChangeEvent> = history
+        .events
+        .iter()
+        .filter(|event| {
+            request
+                .entity
+                .is_none_or(|entity| event.entity == entity.to_bits())
+                && request
+                    .component
+                    .as_ref()
+                    .is_none_or(|component| event.component.as_ref() == Some(component))
+                && request
+                    .since_seconds
+                    .is_none_or(|seconds| event.time >= now - seconds)
+        })
+        .collect();
+    if let Some(limit) = request.limit {
+        events.drain(..events.len().saturating_sub(limit));
+    }
+
+    Ok(json!({
+        "events": events,
+        "now": now,
+        "dropped": history.dropped,
+        "tracked_entities": history.tracked_entities(),
+    }))
+}
+
+/// System recording the changes of tracked entities since the last frame
+pub fn record_entity_changes(world: &mut World) {
+    if world
+        .get_resource::<EntityHistory>()
+        .is_none_or(|history| history.tracked.is_empty())
+    {
+        return;
+    }
+
+    world.resource_scope(|world, mut history: Mut<EntityHistory>| {
+        let time = world
+            .get_resource::<Time<Real>>()
+            .map_or(0.0, Time::elapsed_secs_f64);
+        let frame = world
+            .get_resource::<FrameCount>()
+            .map_or(0, |frame| frame.0);
+        let registry = world.resource::<AppTypeRegistry>().read();
+
+        let mut events = Vec::new();
+        let mut despawned = Vec::new();
+        for (&entity, tracked) in &mut history.tracked {
+            let Some(current) = snapshot(world, &registry, entity, tracked.components.as_deref())
+            else {
+                despawned.push(entity);
+                events.push(ChangeEvent {
+                    time,
+                    frame,
+                    entity: entity.to_bits(),
+                    component: None,
+                    change: ChangeKind::Despawned,
+                    old: None,
+                    new: None,
+                });
+                continue;
+            };
+            events.extend(diff(&tracked.snapshot, &current).into_iter().map(
+                |(component, change, old, new)| ChangeEvent {
+                    time,
+                    frame,
+                    entity: entity.to_bits(),
+                    component: Some(component),
+                    change,
+                    old,
+                    new,
+                },
+            ));
+            tracked.snapshot = current;
+        }
+
+        for entity in despawned {
+            history.tracked.remove(&entity);
+        }
+        history.record(events);
+    });
+}
+
+/// Reflected components of `entity` by type path, limited to `components` when given - `None`
+/// when the entity doesn't exist
+pub fn snapshot(
+    world: &World,
+    registry: &TypeRegistry,
+    entity: Entity,
+    components: Option<&[String]>,
+) -> Option<BTreeMap<String, Value>> {
+    let entity_ref = world.get_entity(entity).ok()?;
+    let mut snapshot = BTreeMap::new();
+    for &component_id in entity_ref.archetype().components() {
+        let Some(registration) = world
+            .components()
+            .get_info(component_id)
+            .and_then(ComponentInfo::type_id)
+            .and_then(|type_id| registry.get(type_id))
+        else {
+            continue;
+        };
+        let type_path = registration.type_info().type_path();
+        if components.is_some_and(|components| !components.iter().any(|path| path == type_path)) {
+            continue;
+        }
+        let Some(reflected) = registration
+            .data::<ReflectComponent>()
+            .and_then(|reflect_component| reflect_component.reflect(entity_ref))
+        else {
+            continue;
+        };
+        let serializer = TypedReflectSerializer::new(reflected.as_partial_reflect(), registry);
+        if let Ok(value) = serde_json::to_value(serializer) {
+            snapshot.insert(type_path.to_string(), value);
+        }
+    }
+    Some(snapshot)
+}
+
+/// Components added, removed or mutated between two snapshots, with their old and new values
+fn diff(
+    before: &BTreeMap<String, Value>,
+    after: &BTreeMap<String, Value>,
+) -> Vec<(String, ChangeKind, Option<Value>, Option<Value>)> {
+    let mut changes = Vec::new();
+    for (component, old) in before {
+        match after.get(component) {
+            None => changes.push((
+                component.clone(),
+                ChangeKind::Removed,
+                Some(old.clone()),
+                None,
+            )),
+            Some(new) if new != old => changes.push((
+                component.clone(),
+                ChangeKind::Mutated,
+                Some(old.clone()),
+                Some(new.clone()),
+            )),
+            Some(_) => {},
+        }
+    }
+    for (component, new) in after {
+        if !before.contains_key(component) {
+            changes.push((
+                component.clone(),
+                ChangeKind::Added,
+                None,
+                Some(new.clone()),
+            ));
+        }
+    }
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diff_reports_added_removed_and_mutated() {
+        let before = BTreeMap::from([
+            ("game::Health".to_string(), json!({ "current": 10 })),
+            ("game::Stunned".to_string(), json!({})),
+            ("game::Name".to_string(), json!("hero")),
+        ]);
+        let after = BTreeMap::from([
+            ("game::Health".to_string(), json!({ "current": 7 })),
+            ("game::Name".to_string(), json!("hero")),
+            ("game::Burning".to_string(), json!({ "seconds": 3 })),
+        ]);
+
+        let changes: Vec<(String, ChangeKind)> = diff(&before, &after)
+            .into_iter()
+            .map(|(component, change, _, _)| (component, change))
+            .collect();
+        assert_eq!(
+            changes,
+            vec![
+                ("game::Health".to_string(), ChangeKind::Mutated),
+                ("game::Stunned".to_string(), ChangeKind::Removed),
+                ("game::Burning".to_string(), ChangeKind::Added),
+            ]
+        );
+    }
+}
@@ -0,0 +1,177 @@
+//! Interaction state handler for BRP extras
+//!
+//! Reports what input is currently aimed at: the entity with keyboard focus, UI nodes whose
+//! `Interaction` is hovered or pressed, and what each picking pointer is hovering in the scene.
+//! When a click doesn't register, this shows whether the pointer was over the expected entity
+//! and whether something else was in front of it.
+
+use bevy::input_focus::InputFocus;
+use bevy::picking::hover::HoverMap;
+use bevy::picking::hover::PickingInteraction;
+use bevy::prelude::*;
+use bevy::remote::BrpResult;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+use serde_json::json;
+
+/// An entity with its `Name`, if it has one
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NamedEntity {
+    /// The entity
+    pub entity: u64,
+    /// Its `Name`
+    pub name:   Option<String>,
+}
+
+impl NamedEntity {
+    fn of(world: &World, entity: Entity) -> Self {
+        Self {
+            entity: entity.to_bits(),
+            name:   world
+                .get::<Name>(entity)
+                .map(|name| name.as_str().to_string()),
+        }
+    }
+}
+
+/// A UI node that is hovered or pressed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UiInteraction {
+    /// The node
+    #[serde(flatten)]
+    pub node:        NamedEntity,
+    /// `hovered` or `pressed`
+    pub interaction: String,
+}
+
+/// What a picking pointer is over
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PointerHover {
+    /// The pointer, e.g. `Mouse` or `Touch(0)`
+    pub pointer: String,
+    /// Entities under the pointer, nearest first
+    pub hovered: Vec<HoveredEntity>,
+}
+
+/// An entity under a picking pointer
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HoveredEntity {
+    /// The entity
+    #[serde(flatten)]
+    pub target:      NamedEntity,
+    /// Camera the hit was found through
+    pub camera:      u64,
+    /// Distance from the camera - lower is nearer
+    pub depth:       f32,
+    /// World-space position of the hit, when the backend reports it
+    pub position:    Option<[f32; 3]>,
+    /// The entity's `PickingInteraction` - `hovered` or `pressed`, when tracked
+    pub interaction: Option<String>,
+}
+
+/// Handler for `get_interaction_state` requests
+pub fn handler(In(_params): In<Option<Value>>, world: &mut World) -> BrpResult {
+    let focus_tracked = world.contains_resource::<InputFocus>();
+    let focus = world
+        .get_resource::<InputFocus>()
+        .and_then(InputFocus::get)
+        .map(|entity| NamedEntity::of(world, entity));
+
+    let mut query = world.query::<(Entity, &Interaction)>();
+    let mut ui: Vec<UiInteraction> = query
+        .iter(world)
+        .filter_map(|(entity, interaction)| {
+            let interaction = match interaction {
+                Interaction::Pressed => "pressed",
+                Interaction::Hovered => "hovered",
+                Interaction::None => return None,
+            };
+            Some(UiInteraction {
+                node:        NamedEntity::of(world, entity),
+                interaction: interaction.to_string(),
+            })
+        })
+        .collect();
+    ui.sort_by_key(|interaction| interaction.node.entity);
+
+    let picking_enabled = world.contains_resource::<HoverMap>();
+    let mut picking: Vec<PointerHover> = world
+        .get_resource::<HoverMap>()
+        .map(|hover_map| {
+            hover_map
+                .iter()
+                .map(|(pointer, hits)| {
+                    let mut hovered: Vec<HoveredEntity> = hits
+                        .iter()
+                        .map(|(&entity, hit)| HoveredEntity {
+                            target:      NamedEntity::of(world, entity),
+                            camera:      hit.camera.to_bits(),
+                            depth:       hit.depth,
+                            position:    hit.position.as_ref().map(Vec3::to_array),
+                            interaction: world
+                                .get::<PickingInteraction>(entity)
+                                .and_then(picking_interaction_name)
+                                .map(String::from),
+                        })
+                        .collect();
+                    hovered.sort_by(|a, b| a.depth.total_cmp(&b.depth));
+                    PointerHover {
+                        pointer: format!("{pointer:?}"),
+                        hovered,
+                    }
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    picking.sort_by(|a, b| a.pointer.cmp(&b.pointer));
+
+    Ok(json!({
+        "focus": focus,
+        "focus_tracked": focus_tracked,
+        "ui": ui,
+        "picking": picking,
+        "picking_enabled": picking_enabled,
+    }))
+}
+
+/// Name of a `PickingInteraction` - `None` when the entity is neither hovered nor pressed
+const fn picking_interaction_name(interaction: &PickingInteraction) -> Option<&'static str> {
+    match interaction {
+        PickingInteraction::Pressed => Some("pressed"),
+        PickingInteraction::Hovered => Some("hovered"),
+        PickingInteraction::None => None,
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_focus_and_active_ui_nodes() {
+        let mut world = World::new();
+        let pressed = world
+            .spawn((Interaction::Pressed, Name::new("PlayButton")))
+            .id();
+        let hovered = world.spawn(Interaction::Hovered).id();
+        world.spawn((Interaction::None, Name::new("QuitButton")));
+        world.insert_resource(InputFocus::from_entity(pressed));
+
+        let state = handler(In(None), &mut world).expect("interaction state");
+
+        assert_eq!(state["focus_tracked"], true);
+        assert_eq!(state["focus"]["entity"], pressed.to_bits());
+        assert_eq!(state["focus"]["name"], "PlayButton");
+        assert_eq!(
+            state["ui"],
+            json!([
+                { "entity": pressed.to_bits(), "name": "PlayButton", "interaction": "pressed" },
+                { "entity": hovered.to_bits(), "name": null, "interaction": "hovered" },
+            ])
+        );
+        assert_eq!(state["picking_enabled"], false);
+        assert_eq!(state["picking"], json!([]));
+    }
+}
@@ -9,6 +9,8 @@ use bevy::prelude::*;
 use bevy::remote::BrpError;
 use bevy::remote::BrpResult;
 use bevy::remote::error_codes::INVALID_PARAMS;
+use bevy::window::Ime;
+use bevy::window::PrimaryWindow;
 use serde::Deserialize;
 use serde::Serialize;
 use serde_json::Value;
@@ -23,13 +25,59 @@ const MAX_KEY_DURATION_MS: u32 = 60_000;
 /// Default duration for holding keys in milliseconds
 const DEFAULT_KEY_DURATION_MS: u32 = 100;
 
+/// Default delay before held keys start repeating, in milliseconds
+const DEFAULT_REPEAT_DELAY_MS: u32 = 500;
+
+/// Default interval between repeat events, in milliseconds
+const DEFAULT_REPEAT_INTERVAL_MS: u32 = 30;
+
 /// Component that tracks keys that need to be released after a duration
 #[derive(Component)]
 pub struct TimedKeyRelease {
     /// The key codes to release
-    pub keys:  Vec<KeyCode>,
+    pub keys:        Vec<KeyCode>,
     /// Timer tracking the remaining duration
-    pub timer: Timer,
+    pub timer:       Timer,
+    /// Key repeat while the keys are held, if requested
+    pub repeat:      Option<KeyRepeat>,
+    /// Delay before the keys are pressed, for keys later in a timed sequence - `timer` starts
+    /// once they are
+    pub press_delay: Option<Timer>,
+}
+
+/// Synthetic key repeat for held keys, like an OS sends for a key held down
+pub struct KeyRepeat {
+    /// Counts down the initial delay, then each interval
+    pub timer:    Timer,
+    /// Time between repeat events once repeating
+    pub interval: Duration,
+}
+
+impl KeyRepeat {
+    /// Repeat after `delay_ms`, then every `interval_ms`
+    fn new(delay_ms: u32, interval_ms: u32) -> Self {
+        Self {
+            timer:    Timer::new(Duration::from_millis(u64::from(delay_ms)), TimerMode::Once),
+            interval: Duration::from_millis(u64::from(interval_ms)),
+        }
+    }
+
+    /// Advance by `delta` and return how many repeat events are due
+    fn tick(&mut self, delta: Duration) -> u32 {
+        if self.timer.mode() == TimerMode::Once {
+            let remaining = self.timer.remaining();
+            self.timer.tick(delta);
+            if !self.timer.is_finished() {
+                return 0;
+            }
+            // Delay is over - carry the overshoot into the repeating interval
+            self.timer = Timer::new(self.interval, TimerMode::Repeating);
+            self.timer.tick(delta.saturating_sub(remaining));
+            return 1 + self.timer.times_finished_this_tick();
+        }
+        self.timer.tick(delta);
+        self.timer.times_finished_this_tick()
+    }
 }
 
 /// Wrapper enum for Bevy's `KeyCode` with strum derives for string conversion
@@ -377,18 +425,64 @@ impl KeyCodeWrapper {
     }
 }
 
+/// A key to send - a key code name, or an object giving the key its own timing
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum KeyEntry {
+    /// Key code name, pressed with the other keys for the request's `duration_ms`
+    Name(String),
+    /// Key code with its own timing
+    Timed {
+        /// Key code name
+        key:         String,
+        /// Duration in milliseconds to hold this key, instead of the request's `duration_ms`
+        #[serde(default)]
+        duration_ms: Option<u32>,
+        /// Delay in milliseconds between the previous key's press and this key's press
+        #[serde(default)]
+        delay_ms:    u32,
+    },
+}
+
+/// A validated key with when to press it and for how long
+struct ScheduledKey {
+    name:        String,
+    key_code:    KeyCode,
+    /// Milliseconds after the request until the key is pressed
+    offset_ms:   u32,
+    /// Hold duration, when it differs from the request's
+    duration_ms: Option<u32>,
+}
+
 /// Request structure for `send_keys`
 #[derive(Debug, Deserialize)]
 pub struct SendKeysRequest {
-    /// Array of key codes to send
-    pub keys:        Vec<String>,
+    /// Keys to send, as key code names or objects with per-key timing
+    pub keys:               Vec<KeyEntry>,
     /// Duration in milliseconds to hold the keys before releasing
     #[serde(default = "default_duration")]
-    pub duration_ms: u32,
+    pub duration_ms:        u32,
+    /// Text to deliver as an `Ime::Commit` event to the primary window, as an input method
+    /// would after composition
+    #[serde(default)]
+    pub ime_text:           Option<String>,
+    /// Send repeated press events (with `repeat: true`) while the keys are held
+    #[serde(default)]
+    pub repeat:             bool,
+    /// Delay in milliseconds before held keys start repeating
+    #[serde(default = "default_repeat_delay")]
+    pub repeat_delay_ms:    u32,
+    /// Interval in milliseconds between repeat events
+    #[serde(default = "default_repeat_interval")]
+    pub repeat_interval_ms: u32,
 }
 
 const fn default_duration() -> u32 { DEFAULT_KEY_DURATION_MS }
 
+const fn default_repeat_delay() -> u32 { DEFAULT_REPEAT_DELAY_MS }
+
+const fn default_repeat_interval() -> u32 { DEFAULT_REPEAT_INTERVAL_MS }
+
 /// Response structure for `send_keys`
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SendKeysResponse {
@@ -398,34 +492,67 @@ pub struct SendKeysResponse {
     pub keys_sent:   Vec<String>,
     /// Duration in milliseconds the keys were held
     pub duration_ms: u32,
+    /// Text committed through an `Ime::Commit` event
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ime_text:    Option<String>,
+    /// Whether held keys repeat
+    #[serde(default)]
+    pub repeat:      bool,
+    /// Milliseconds from the request until the last key is released
+    #[serde(default)]
+    pub total_ms:    u32,
 }
 
-/// Validate key codes and return the parsed key codes
-fn validate_keys(keys: &[String]) -> Result<Vec<(String, KeyCode)>, BrpError> {
-    let mut validated_keys = Vec::new();
-
-    for key_str in keys {
-        match parse_key_code(key_str) {
-            Ok(key_code) => {
-                validated_keys.push((key_str.clone(), key_code));
-            },
-            Err(e) => {
-                return Err(BrpError {
-                    code:    INVALID_PARAMS,
-                    message: format!("Invalid key code '{key_str}': {e}"),
-                    data:    None,
-                });
-            },
+/// Validate key codes and timings and schedule each key relative to the request
+fn validate_keys(keys: &[KeyEntry]) -> Result<Vec<ScheduledKey>, BrpError> {
+    let mut scheduled_keys = Vec::new();
+    let mut offset_ms: u32 = 0;
+
+    for entry in keys {
+        let (key_str, duration_ms, delay_ms) = match entry {
+            KeyEntry::Name(key) => (key, None, 0),
+            KeyEntry::Timed {
+                key,
+                duration_ms,
+                delay_ms,
+            } => (key, *duration_ms, *delay_ms),
+        };
+        let key_code = parse_key_code(key_str).map_err(|e| BrpError {
+            code:    INVALID_PARAMS,
+            message: format!("Invalid key code '{key_str}': {e}"),
+            data:    None,
+        })?;
+
+        offset_ms = offset_ms.saturating_add(delay_ms);
+        if offset_ms > MAX_KEY_DURATION_MS
+            || duration_ms.is_some_and(|duration_ms| duration_ms > MAX_KEY_DURATION_MS)
+        {
+            return Err(BrpError {
+                code:    INVALID_PARAMS,
+                message: format!(
+                    "Timing for key '{key_str}' exceeds maximum allowed duration of \
+                     {MAX_KEY_DURATION_MS}ms (1 minute)"
+                ),
+                data:    None,
+            });
         }
+
+        scheduled_keys.push(ScheduledKey {
+            name: key_str.clone(),
+            key_code,
+            offset_ms,
+            duration_ms,
+        });
     }
 
-    Ok(validated_keys)
+    Ok(scheduled_keys)
 }
 
 /// Create keyboard events from validated key codes
 fn create_keyboard_events(
     key_codes: &[KeyCode],
     press: bool,
+    repeat: bool,
 ) -> Vec<bevy::input::keyboard::KeyboardInput> {
     let state = if press {
         ButtonState::Pressed
@@ -442,7 +569,7 @@ fn create_keyboard_events(
                 bevy::input::keyboard::NativeKey::Unidentified,
             ),
             window: Entity::PLACEHOLDER,
-            repeat: false,
+            repeat,
             text: None,
         })
         .collect()
@@ -474,10 +601,12 @@ pub fn send_keys_handler(In(params): In<Option<Value>>, world: &mut World) -> Br
         });
     };
 
-    // Validate key codes
-    let validated_keys = validate_keys(&request.keys)?;
-    let valid_key_strings: Vec<String> = validated_keys.iter().map(|(s, _)| s.clone()).collect();
-    let key_codes: Vec<KeyCode> = validated_keys.iter().map(|(_, kc)| *kc).collect();
+    // Validate key codes and per-key timing
+    let scheduled_keys = validate_keys(&request.keys)?;
+    let valid_key_strings: Vec<String> = scheduled_keys
+        .iter()
+        .map(|scheduled| scheduled.name.clone())
+        .collect();
 
     // Validate duration doesn't exceed maximum
     if request.duration_ms > MAX_KEY_DURATION_MS {
@@ -491,28 +620,115 @@ pub fn send_keys_handler(In(params): In<Option<Value>>, world: &mut World) -> Br
         });
     }
 
+    if request.repeat
+        && (request.repeat_interval_ms == 0
+            || request.repeat_interval_ms > MAX_KEY_DURATION_MS
+            || request.repeat_delay_ms > MAX_KEY_DURATION_MS)
+    {
+        return Err(BrpError {
+            code:    INVALID_PARAMS,
+            message: format!(
+                "Repeat interval must be between 1 and {MAX_KEY_DURATION_MS}ms and repeat delay \
+                 at most {MAX_KEY_DURATION_MS}ms"
+            ),
+            data:    None,
+        });
+    }
+
+    let total_ms = schedule_keys(world, &scheduled_keys, &request);
+
+    // Committed IME text arrives after the keys that produced it
+    if let Some(text) = &request.ime_text {
+        let window = world
+            .query_filtered::<Entity, With<PrimaryWindow>>()
+            .iter(world)
+            .next()
+            .unwrap_or(Entity::PLACEHOLDER);
+        world.write_message(Ime::Commit {
+            window,
+            value: text.clone(),
+        });
+    }
+
+    Ok(json!(SendKeysResponse {
+        success: true,
+        keys_sent: valid_key_strings,
+        duration_ms: request.duration_ms,
+        ime_text: request.ime_text,
+        repeat: request.repeat,
+        total_ms,
+    }))
+}
+
+/// Press the keys due now and spawn the entities that release them and press later keys
+///
+/// Keys without their own timing are pressed together and share one release, as before per-key
+/// timing existed. Returns the milliseconds until the last release.
+fn schedule_keys(
+    world: &mut World,
+    scheduled_keys: &[ScheduledKey],
+    request: &SendKeysRequest,
+) -> u32 {
+    let repeat = || {
+        request
+            .repeat
+            .then(|| KeyRepeat::new(request.repeat_delay_ms, request.repeat_interval_ms))
+    };
+    let hold_timer = |duration_ms: u32| {
+        Timer::new(
+            Duration::from_millis(u64::from(duration_ms)),
+            TimerMode::Once,
+        )
+    };
+
+    let (together, individually): (Vec<&ScheduledKey>, Vec<&ScheduledKey>) = scheduled_keys
+        .iter()
+        .partition(|scheduled| scheduled.offset_ms == 0 && scheduled.duration_ms.is_none());
+
     // Always send press events first
-    let press_events = create_keyboard_events(&key_codes, true);
-    for event in press_events {
+    let key_codes: Vec<KeyCode> = together
+        .iter()
+        .map(|scheduled| scheduled.key_code)
+        .collect();
+    for event in create_keyboard_events(&key_codes, true, false) {
         world.write_message(event);
     }
 
     // Always spawn an entity to handle the timed release
     if !key_codes.is_empty() {
         world.spawn(TimedKeyRelease {
-            keys:  key_codes,
-            timer: Timer::new(
-                Duration::from_millis(u64::from(request.duration_ms)),
-                TimerMode::Once,
-            ),
+            keys:        key_codes,
+            timer:       hold_timer(request.duration_ms),
+            repeat:      repeat(),
+            press_delay: None,
         });
     }
 
-    Ok(json!(SendKeysResponse {
-        success:     true,
-        keys_sent:   valid_key_strings,
-        duration_ms: request.duration_ms,
-    }))
+    // Keys with their own timing are pressed and released on their own schedule
+    for scheduled in &individually {
+        let press_delay = (scheduled.offset_ms > 0).then(|| hold_timer(scheduled.offset_ms));
+        if press_delay.is_none() {
+            for event in create_keyboard_events(&[scheduled.key_code], true, false) {
+                world.write_message(event);
+            }
+        }
+        world.spawn(TimedKeyRelease {
+            keys: vec![scheduled.key_code],
+            timer: hold_timer(scheduled.duration_ms.unwrap_or(request.duration_ms)),
+            repeat: repeat(),
+            press_delay,
+        });
+    }
+
+    scheduled_keys
+        .iter()
+        .map(|scheduled| {
+            scheduled
+                .offset_ms
+                .saturating_add(scheduled.duration_ms.unwrap_or(request.duration_ms))
+        })
+        .max()
+        .unwrap_or(0)
 }
 
 /// Information about a key code
@@ -539,6 +755,18 @@ pub fn process_timed_key_releases(
     mut keyboard_events: MessageWriter<bevy::input::keyboard::KeyboardInput>,
 ) {
     for (entity, mut timed_release) in &mut query {
+        // Later keys in a sequence wait for their turn to be pressed
+        if let Some(press_delay) = &mut timed_release.press_delay {
+            if !press_delay.tick(time.delta()).is_finished() {
+                continue;
+            }
+            timed_release.press_delay = None;
+            for event in create_keyboard_events(&timed_release.keys, true, false) {
+                keyboard_events.write(event);
+            }
+            continue;
+        }
+
         timed_release.timer.tick(time.delta());
 
         if timed_release.timer.is_finished() {
@@ -559,6 +787,17 @@ pub fn process_timed_key_releases(
 
             // Remove the component after releasing
             commands.entity(entity).despawn();
+        } else {
+            // Still held - send any repeats that came due
+            let due = timed_release
+                .repeat
+                .as_mut()
+                .map_or(0, |repeat| repeat.tick(time.delta()));
+            for _ in 0..due {
+                for event in create_keyboard_events(&timed_release.keys, true, true) {
+                    keyboard_events.write(event);
+                }
+            }
         }
     }
 }
@@ -849,6 +1088,116 @@ mod tests {
         );
     }
 
+    /// Test IME text and key repeat are accepted and reported
+    #[test]
+    #[allow(clippy::expect_used)]
+    fn test_ime_text_and_repeat() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+
+        let params = json!({
+            "keys": ["Backspace"],
+            "duration_ms": 1000,
+            "ime_text": "日本語",
+            "repeat": true
+        });
+
+        let result = send_keys_handler(In(Some(params)), app.world_mut());
+        let response: SendKeysResponse =
+            serde_json::from_value(result.expect("Expected success but got error"))
+                .expect("Failed to deserialize response");
+        assert_eq!(response.ime_text.as_deref(), Some("日本語"));
+        assert!(response.repeat);
+
+        let mut query = app.world_mut().query::<&TimedKeyRelease>();
+        let timed_release = query
+            .iter(app.world())
+            .next()
+            .expect("Expected a TimedKeyRelease component");
+        assert!(timed_release.repeat.is_some());
+    }
+
+    /// Test per-key timing presses later keys on their own schedule
+    #[test]
+    #[allow(clippy::expect_used)]
+    fn test_per_key_timing() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+
+        // Double-tap D, with Space held alongside for the default duration
+        let params = json!({
+            "keys": [
+                "Space",
+                {"key": "KeyD", "duration_ms": 50},
+                {"key": "KeyD", "delay_ms": 120, "duration_ms": 50}
+            ]
+        });
+
+        let result = send_keys_handler(In(Some(params)), app.world_mut());
+        let response: SendKeysResponse =
+            serde_json::from_value(result.expect("Expected success but got error"))
+                .expect("Failed to deserialize response");
+        assert_eq!(response.keys_sent, vec!["Space", "KeyD", "KeyD"]);
+        assert_eq!(response.total_ms, 170);
+
+        let mut query = app.world_mut().query::<&TimedKeyRelease>();
+        let mut releases: Vec<(usize, bool)> = query
+            .iter(app.world())
+            .map(|timed_release| {
+                (
+                    timed_release.keys.len(),
+                    timed_release.press_delay.is_some(),
+                )
+            })
+            .collect();
+        releases.sort_unstable();
+        assert_eq!(releases, vec![(1, false), (1, false), (1, true)]);
+    }
+
+    /// Test a sequence running past the maximum duration is rejected
+    #[test]
+    fn test_per_key_delay_exceeds_maximum() {
+        let mut app = App::new();
+
+        let params = json!({
+            "keys": [
+                {"key": "KeyA", "delay_ms": 40_000},
+                {"key": "KeyB", "delay_ms": 30_000}
+            ]
+        });
+
+        let result = send_keys_handler(In(Some(params)), app.world_mut());
+        assert!(result.is_err());
+    }
+
+    /// Test a zero repeat interval is rejected
+    #[test]
+    fn test_zero_repeat_interval() {
+        let mut app = App::new();
+
+        let params = json!({
+            "keys": ["KeyA"],
+            "repeat": true,
+            "repeat_interval_ms": 0
+        });
+
+        let result = send_keys_handler(In(Some(params)), app.world_mut());
+        assert!(result.is_err());
+    }
+
+    /// Test repeats start after the delay and then follow the interval
+    #[test]
+    fn test_key_repeat_timing() {
+        let mut repeat = KeyRepeat::new(500, 30);
+
+        assert_eq!(repeat.tick(Duration::from_millis(400)), 0);
+        // Delay ends 100ms in, leaving 20ms toward the first interval
+        assert_eq!(repeat.tick(Duration::from_millis(120)), 1);
+        assert_eq!(repeat.tick(Duration::from_millis(10)), 1);
+        assert_eq!(repeat.tick(Duration::from_millis(60)), 2);
+        assert_eq!(repeat.tick(Duration::from_millis(10)), 0);
+    }
+
     /// Test that empty key array does not create `TimedKeyRelease`
     #[test]
     fn test_empty_keys_no_timed_release() {
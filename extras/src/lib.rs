@@ -18,22 +18,159 @@
 //!
 //! This will add the following BRP methods to your app:
 //! - `brp_extras/screenshot`: Capture a screenshot
+//! - `brp_extras/screenshot_status`: Report whether a screenshot has been saved
 //! - `brp_extras/shutdown`: Gracefully shutdown the app
 //! - `brp_extras/send_keys`: Send keyboard input
 //! - `brp_extras/set_window_title`: Change the window title
+//! - `brp_extras/set_window`: Change window decorations, level, focus and minimized state
+//! - `brp_extras/tag_entity`: Attach a `BrpTag` to an entity
+//! - `brp_extras/find_tagged`: Find entities by tag
+//! - `brp_extras/list_monitors`: List connected displays
+//! - `brp_extras/time_config`: Read or change the fixed timestep, max delta and wrap period
+//! - `brp_extras/gizmo_config`: Read or change gizmo line width, depth bias and enabled groups
+//! - `brp_extras/collect_frame_stats`: Collect frame-time statistics over a time window
+//! - `brp_extras/track_archetype_moves`: Count archetype moves per entity over a number of frames
+//! - `brp_extras/activity`: Report per-frame change counts and how long the app has been quiet
+//! - `brp_extras/schedule_report`: Report system ambiguities and execution order
+//! - `brp_extras/determinism`: Set the RNG seed, fixed frame delta and speed of time
+//! - `brp_extras/pin_entity`: Protect an entity from despawning while it is inspected
+//! - `brp_extras/read_pixels`: Sample pixel colors of the primary window
+//! - `brp_extras/get_ui_text`: Collect UI text with screen rects and hierarchy paths
+//! - `brp_extras/get_interaction_state`: Report keyboard focus, UI interactions and picking hovers
+//! - `brp_extras/track_entity`: Record component changes of an entity
+//! - `brp_extras/get_history`: Read recorded component changes
+//! - `brp_extras/apply_commands`: Apply spawn, insert, remove, despawn and reparent commands
+//!   atomically
+//! - `brp_extras/app_info`: Report the app's plugins, build profile and build info
+//! - `brp_extras/ensure_registered`: Report which types are missing reflection registration
+//! - `brp_extras/capabilities`: Report the plugin sections and methods the app serves
+//! - `brp_extras/multiwatch+watch`: Stream many watches over one connection
+//! - `brp_extras/multiwatch_subscribe`: Add and remove the watches of a multiwatch stream
+//! - `brp_extras/query_stream+watch`: Stream the results of a query in chunks
+//!
+//! Screenshot, keyboard and window methods are sections of the plugin behind the default
+//! `screenshot`, `keyboard` and `window` features. Turn a feature off to leave the section out of
+//! the build, or leave it out of one app with `BrpExtrasPlugin::default().without(capability)` -
+//! see [`Capability`].
+//!
+//! With the `binary-transport` feature, `BrpExtrasPlugin::default().with_binary_transport(port)`
+//! also serves BRP as `MessagePack` or CBOR frames on `port` and adds
+//! `brp_extras/wire_encodings` to report it.
+//!
+//! With the `compression` feature, `BrpExtrasPlugin::default().with_compression()` compresses
+//! large HTTP responses with gzip or deflate for clients that accept them.
+//!
+//! With the `clipboard` feature, `brp_extras/clipboard` reads and sets the system clipboard.
+//!
+//! With the `material` feature, `brp_extras/material` reads and changes the `StandardMaterial`
+//! of an entity.
+//!
+//! Apps that spawn their own BRP-enabled processes start them with
+//! [`BrpTopology::child_env`] and record them with [`BrpTopology::register_child`], so
+//! `brp_extras/app_info` reports the ports of the whole process tree.
 
+mod activity;
+mod app_info;
+mod apply_commands;
+mod archetype_moves;
+#[cfg(feature = "binary-transport")]
+mod binary_transport;
+mod capabilities;
+#[cfg(feature = "clipboard")]
+mod clipboard;
+#[cfg(feature = "compression")]
+mod compressed_http;
+mod determinism;
+mod frame_stats;
+mod gizmo_config;
+mod history;
+mod interaction;
 mod keyboard;
+#[cfg(feature = "material")]
+mod material;
+#[cfg(feature = "window")]
+mod monitor;
+mod multiwatch;
+mod pin;
+#[cfg(feature = "screenshot")]
+mod pixels;
 mod plugin;
+mod query_stream;
+mod registration;
+mod schedule_report;
+#[cfg(feature = "screenshot")]
 mod screenshot;
 mod shutdown;
+mod tag;
+mod time_config;
+mod topology;
+mod ui_text;
+#[cfg(feature = "window")]
+mod window;
+#[cfg(feature = "window")]
 mod window_title;
 
+pub use activity::ActivityRequest;
+pub use activity::ActivityResponse;
+pub use app_info::BuildInfo;
+pub use archetype_moves::ArchetypeMovesReport;
+pub use archetype_moves::ComponentChurn;
+pub use archetype_moves::EntityArchetypeMoves;
+pub use archetype_moves::TrackArchetypeMovesRequest;
+pub use archetype_moves::TrackArchetypeMovesResponse;
+pub use capabilities::Capability;
+pub use capabilities::CapabilityStatus;
+pub use capabilities::ExtrasCapabilities;
+pub use determinism::BrpRngSeed;
+pub use determinism::DeterminismRequest;
+pub use determinism::DeterminismResponse;
+pub use frame_stats::CollectFrameStatsRequest;
+pub use frame_stats::CollectFrameStatsResponse;
+pub use frame_stats::FrameSample;
+pub use frame_stats::FrameStats;
+pub use frame_stats::HistogramBucket;
+pub use gizmo_config::GizmoConfigRequest;
+pub use gizmo_config::GizmoGroupInfo;
 pub use keyboard::KeyCodeInfo;
 pub use keyboard::KeyCodeWrapper;
 pub use keyboard::SendKeysRequest;
 pub use keyboard::SendKeysResponse;
 pub use keyboard::TimedKeyRelease;
+#[cfg(feature = "material")]
+pub use material::MaterialInfo;
+#[cfg(feature = "material")]
+pub use material::MaterialRequest;
+#[cfg(feature = "window")]
+pub use monitor::MonitorInfo;
+pub use multiwatch::MultiwatchRequest;
+pub use multiwatch::MultiwatchSubscribeRequest;
+pub use multiwatch::MultiwatchSubscribeResponse;
+pub use multiwatch::WatchSubscription;
+pub use pin::BrpPinned;
+pub use pin::PinEntityRequest;
 pub use plugin::BrpExtrasPlugin;
+pub use query_stream::QueryStreamRequest;
+pub use schedule_report::ScheduleReport;
+pub use schedule_report::ScheduleReportRequest;
+pub use schedule_report::SystemAmbiguity;
+pub use tag::BrpTag;
+pub use tag::FindTaggedRequest;
+pub use tag::TagEntityRequest;
+pub use time_config::TimeConfigRequest;
+pub use time_config::TimeConfigResponse;
+pub use topology::BrpChildInstance;
+pub use topology::BrpTopology;
+pub use topology::HOST_ENV_VAR;
+pub use topology::INSTANCE_COUNT_ENV_VAR;
+pub use topology::INSTANCE_INDEX_ENV_VAR;
+pub use topology::NETWORK_PROXY_ENV_VAR;
+pub use topology::PARENT_PORT_ENV_VAR;
+pub use topology::PORT_ENV_VAR;
+pub use topology::network_proxy_addr;
+#[cfg(feature = "window")]
+pub use window::SetWindowRequest;
+#[cfg(feature = "window")]
+pub use window::WindowLevelSetting;
 
 /// Default port for remote control connections
 ///
@@ -0,0 +1,230 @@
+//! Material handler for BRP extras
+//!
+//! BRP only reaches components and resources, so the `StandardMaterial` asset behind an
+//! entity's `MeshMaterial3d` is out of its reach. `brp_extras/material` reads and changes the
+//! common settings of that material. The asset is shared by every entity holding a handle to it,
+//! so a change shows on all of them.
+
+use bevy::prelude::*;
+use bevy::remote::BrpError;
+use bevy::remote::BrpResult;
+use bevy::remote::error_codes::INTERNAL_ERROR;
+use bevy::remote::error_codes::INVALID_PARAMS;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+use serde_json::json;
+
+/// Request structure for `material` - omitted settings are left unchanged
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MaterialRequest {
+    /// Entity whose `MeshMaterial3d<StandardMaterial>` is read or changed
+    pub entity:               Entity,
+    /// Base color, e.g. `{"Srgba": {"red": 1.0, "green": 0.5, "blue": 0.0, "alpha": 1.0}}`
+    #[serde(default)]
+    pub base_color:           Option<Color>,
+    /// Emitted light, in linear channels that may exceed `1.0`
+    #[serde(default)]
+    pub emissive:             Option<LinearRgba>,
+    /// Roughness in `0.0..=1.0`
+    #[serde(default)]
+    pub perceptual_roughness: Option<f32>,
+    /// Metallic in `0.0..=1.0`
+    #[serde(default)]
+    pub metallic:             Option<f32>,
+    /// Ignore lighting and draw `base_color` as is
+    #[serde(default)]
+    pub unlit:                Option<bool>,
+}
+
+impl MaterialRequest {
+    const fn changes_material(&self) -> bool {
+        self.base_color.is_some()
+            || self.emissive.is_some()
+            || self.perceptual_roughness.is_some()
+            || self.metallic.is_some()
+            || self.unlit.is_some()
+    }
+
+    fn validate(&self) -> Result<(), BrpError> {
+        for (name, value) in [
+            ("perceptual_roughness", self.perceptual_roughness),
+            ("metallic", self.metallic),
+        ] {
+            if let Some(value) = value
+                && !(0.0..=1.0).contains(&value)
+            {
+                return Err(invalid_params(format!(
+                    "'{name}' must be between 0.0 and 1.0, got {value}"
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A material's settings, as reported by `brp_extras/material`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MaterialInfo {
+    /// Base color
+    pub base_color:           Color,
+    /// Emitted light, in linear channels
+    pub emissive:             LinearRgba,
+    /// Roughness in `0.0..=1.0`
+    pub perceptual_roughness: f32,
+    /// Metallic in `0.0..=1.0`
+    pub metallic:             f32,
+    /// Whether lighting is ignored
+    pub unlit:                bool,
+}
+
+impl From<&StandardMaterial> for MaterialInfo {
+    fn from(material: &StandardMaterial) -> Self {
+        Self {
+            base_color:           material.base_color,
+            emissive:             material.emissive,
+            perceptual_roughness: material.perceptual_roughness,
+            metallic:             material.metallic,
+            unlit:                material.unlit,
+        }
+    }
+}
+
+/// Handler for `material` requests
+///
+/// Returns the material's settings after applying any changes.
+pub fn handler(In(params): In<Option<Value>>, world: &mut World) -> BrpResult {
+    let request: MaterialRequest = params
+        .ok_or_else(|| invalid_params("Missing request parameters".to_string()))
+        .and_then(|params| {
+            serde_json::from_value(params)
+                .map_err(|e| invalid_params(format!("Invalid request format: {e}")))
+        })?;
+    request.validate()?;
+
+    let entity = request.entity;
+    let handle = world
+        .get_entity(entity)
+        .map_err(|_| BrpError::entity_not_found(entity))?
+        .get::<MeshMaterial3d<StandardMaterial>>()
+        .map(|material| material.0.clone())
+        .ok_or_else(|| {
+            invalid_params(format!(
+                "Entity {entity} has no MeshMaterial3d<StandardMaterial>"
+            ))
+        })?;
+
+    let mut materials = world
+        .get_resource_mut::<Assets<StandardMaterial>>()
+        .ok_or_else(|| BrpError {
+            code:    INTERNAL_ERROR,
+            message: "Assets<StandardMaterial> not found - is PbrPlugin installed?".to_string(),
+            data:    None,
+        })?;
+    // Reading through `get` leaves the asset unchanged, so it isn't re-uploaded
+    let material = if request.changes_material() {
+        let material = materials
+            .get_mut(&handle)
+            .ok_or_else(|| missing_material(entity))?;
+        if let Some(base_color) = request.base_color {
+            material.base_color = base_color;
+        }
+        if let Some(emissive) = request.emissive {
+            material.emissive = emissive;
+        }
+        if let Some(roughness) = request.perceptual_roughness {
+            material.perceptual_roughness = roughness;
+        }
+        if let Some(metallic) = request.metallic {
+            material.metallic = metallic;
+        }
+        if let Some(unlit) = request.unlit {
+            material.unlit = unlit;
+        }
+        MaterialInfo::from(&*material)
+    } else {
+        MaterialInfo::from(
+            materials
+                .get(&handle)
+                .ok_or_else(|| missing_material(entity))?,
+        )
+    };
+
+    Ok(json!({
+        "entity": entity.to_bits(),
+        "material": material,
+        "updated": request.changes_material(),
+    }))
+}
+
+fn missing_material(entity: Entity) -> BrpError {
+    invalid_params(format!(
+        "The material of entity {entity} isn't loaded or was removed"
+    ))
+}
+
+const fn invalid_params(message: String) -> BrpError {
+    BrpError {
+        code: INVALID_PARAMS,
+        message,
+        data: None,
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    fn material_world() -> (World, Entity, Handle<StandardMaterial>) {
+        let mut world = World::new();
+        let mut materials = Assets::<StandardMaterial>::default();
+        let handle = materials.add(StandardMaterial::default());
+        world.insert_resource(materials);
+        let entity = world.spawn(MeshMaterial3d(handle.clone())).id();
+        (world, entity, handle)
+    }
+
+    #[test]
+    fn changes_only_the_given_settings() {
+        let (mut world, entity, handle) = material_world();
+
+        let result = handler(
+            In(Some(json!({
+                "entity": entity.to_bits(),
+                "base_color": {"Srgba": {"red": 1.0, "green": 0.0, "blue": 0.0, "alpha": 1.0}},
+                "metallic": 0.5
+            }))),
+            &mut world,
+        )
+        .expect("valid request");
+
+        assert_eq!(result["updated"], json!(true));
+        let material = world
+            .resource::<Assets<StandardMaterial>>()
+            .get(&handle)
+            .expect("material is loaded");
+        assert_eq!(material.base_color, Color::srgb(1.0, 0.0, 0.0));
+        assert!((material.metallic - 0.5).abs() < f32::EPSILON);
+        let default = StandardMaterial::default();
+        assert!(
+            (material.perceptual_roughness - default.perceptual_roughness).abs() < f32::EPSILON
+        );
+    }
+
+    #[test]
+    fn rejects_entities_without_a_material_and_out_of_range_values() {
+        let (mut world, entity, _) = material_world();
+        let bare = world.spawn_empty().id();
+
+        for params in [
+            json!({"entity": bare.to_bits()}),
+            json!({"entity": entity.to_bits(), "metallic": 1.5}),
+            json!({"entity": entity.to_bits(), "base_color": "#ff0000"}),
+        ] {
+            let error = handler(In(Some(params)), &mut world).expect_err("invalid request");
+            assert_eq!(error.code, INVALID_PARAMS);
+        }
+    }
+}
@@ -0,0 +1,113 @@
+//! Monitor enumeration handler for BRP extras
+//!
+//! Bevy's windowing backend spawns a `Monitor` entity for each connected display, so listing them
+//! needs no platform code here. Positions and sizes are in physical pixels in the desktop's
+//! coordinate space, the same space window positions use.
+
+use bevy::prelude::*;
+use bevy::remote::BrpResult;
+use bevy::window::Monitor;
+use bevy::window::PrimaryMonitor;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+use serde_json::json;
+
+/// A connected display, as reported by `brp_extras/list_monitors`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MonitorInfo {
+    /// Entity holding the monitor's `Monitor` component
+    pub entity:          u64,
+    /// Name the platform gives the display
+    pub name:            Option<String>,
+    /// Whether this is the primary display
+    pub primary:         bool,
+    /// Width in physical pixels
+    pub physical_width:  u32,
+    /// Height in physical pixels
+    pub physical_height: u32,
+    /// Top-left corner in physical pixels, as `[x, y]`
+    pub position:        [i32; 2],
+    /// Ratio of physical to logical pixels
+    pub scale_factor:    f64,
+    /// Refresh rate in hertz, when the platform reports it
+    pub refresh_rate_hz: Option<f64>,
+}
+
+/// Handler for `list_monitors` requests
+///
+/// Returns the connected displays ordered by position, left to right then top to bottom.
+#[allow(clippy::unnecessary_wraps)]
+pub fn handler(In(_params): In<Option<Value>>, world: &mut World) -> BrpResult {
+    let mut query = world.query::<(Entity, &Monitor, Has<PrimaryMonitor>)>();
+    let mut monitors: Vec<MonitorInfo> = query
+        .iter(world)
+        .map(|(entity, monitor, primary)| MonitorInfo {
+            entity: entity.to_bits(),
+            name: monitor.name.clone(),
+            primary,
+            physical_width: monitor.physical_width,
+            physical_height: monitor.physical_height,
+            position: monitor.physical_position.to_array(),
+            scale_factor: monitor.scale_factor,
+            refresh_rate_hz: monitor
+                .refresh_rate_millihertz
+                .map(|millihertz| f64::from(millihertz) / 1000.0),
+        })
+        .collect();
+    monitors.sort_by_key(|monitor| (monitor.position[0], monitor.position[1]));
+
+    Ok(json!(monitors))
+}
+
+#[cfg(test)]
+#[allow(clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    fn monitor(name: &str, position: IVec2, refresh_rate_millihertz: Option<u32>) -> Monitor {
+        Monitor {
+            name: Some(name.to_string()),
+            physical_height: 1080,
+            physical_width: 1920,
+            physical_position: position,
+            refresh_rate_millihertz,
+            scale_factor: 1.5,
+            video_modes: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn lists_monitors_left_to_right_with_the_primary_flagged() {
+        let mut world = World::new();
+        world.spawn(monitor("right", IVec2::new(1920, 0), None));
+        let primary = world
+            .spawn((
+                monitor("left", IVec2::new(0, 0), Some(59_940)),
+                PrimaryMonitor,
+            ))
+            .id();
+
+        let monitors: Vec<MonitorInfo> =
+            serde_json::from_value(handler(In(None), &mut world).expect("monitors"))
+                .expect("response should deserialize");
+
+        let names: Vec<_> = monitors
+            .iter()
+            .map(|monitor| monitor.name.as_deref())
+            .collect();
+        assert_eq!(names, vec![Some("left"), Some("right")]);
+        assert_eq!(monitors[0].entity, primary.to_bits());
+        assert!(monitors[0].primary);
+        assert!(!monitors[1].primary);
+        assert_eq!(monitors[1].position, [1920, 0]);
+        assert_eq!(monitors[0].refresh_rate_hz, Some(59.94));
+        assert_eq!(monitors[1].refresh_rate_hz, None);
+    }
+
+    #[test]
+    fn lists_nothing_without_a_windowing_backend() {
+        let mut world = World::new();
+        assert_eq!(handler(In(None), &mut world).expect("monitors"), json!([]));
+    }
+}
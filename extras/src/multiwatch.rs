@@ -0,0 +1,588 @@
+//! Watch multiplexing handlers for BRP extras
+//!
+//! Every BRP watch is its own streaming HTTP request, so a client watching a dozen entities holds
+//! a dozen connections to the app. `brp_extras/multiwatch+watch` serves any number of watches over
+//! one: the client opens the stream with a `stream_id`, then adds and removes subscriptions on it
+//! with `brp_extras/multiwatch_subscribe`. Each frame every subscription's watch runs, and the
+//! changes of that frame are sent as one event listing each subscription's `id` with its `result`
+//! or `error`. A subscription whose watch fails is reported once and then dropped.
+//!
+//! A watched entity that is despawned would otherwise leave its watch failing with "not found".
+//! Each poll first checks the entities the subscriptions watch, and reports those watching a
+//! despawned one with `despawned` - the entity - before dropping them.
+//!
+//! The stream method's name ends in `+watch` because Bevy's HTTP server - and this crate's
+//! compressed one - only stream methods named that way. While its client is connected, Bevy polls
+//! an open stream every frame, however far apart frames are. Once a frame goes by without a poll
+//! the connection has closed, and the stream is dropped with its subscriptions. A stream the
+//! client subscribed on but never opened is dropped after `STREAM_OPEN_TIMEOUT`.
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::time::Duration;
+use std::time::Instant;
+
+use bevy::ecs::system::SystemId;
+use bevy::prelude::*;
+use bevy::remote::BrpError;
+use bevy::remote::BrpResult;
+use bevy::remote::builtin_methods::BRP_GET_COMPONENTS_AND_WATCH_METHOD;
+use bevy::remote::builtin_methods::BRP_LIST_COMPONENTS_AND_WATCH_METHOD;
+use bevy::remote::builtin_methods::process_remote_get_components_watching_request;
+use bevy::remote::builtin_methods::process_remote_list_components_watching_request;
+use bevy::remote::error_codes::INTERNAL_ERROR;
+use bevy::remote::error_codes::INVALID_PARAMS;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+use serde_json::json;
+
+/// Subscriptions one stream can hold
+const MAX_SUBSCRIPTIONS_PER_STREAM: usize = 256;
+
+/// How long a stream may go without being opened before it is dropped
+const STREAM_OPEN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Watch methods a subscription can use
+const SUPPORTED_METHODS: &[&str] = &[
+    BRP_GET_COMPONENTS_AND_WATCH_METHOD,
+    BRP_LIST_COMPONENTS_AND_WATCH_METHOD,
+];
+
+/// A registered watching system, run with the subscription's params each frame
+type WatchSystem = SystemId<In<Option<Value>>, BrpResult<Option<Value>>>;
+
+/// Request structure for `multiwatch+watch`
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MultiwatchRequest {
+    /// Client-chosen ID of the stream, shared with `multiwatch_subscribe`
+    pub stream_id: String,
+}
+
+/// Request structure for `multiwatch_subscribe`
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MultiwatchSubscribeRequest {
+    /// ID of the stream to change - created when it doesn't exist yet
+    pub stream_id: String,
+    /// Subscriptions to add, replacing any with the same ID
+    #[serde(default)]
+    pub add:       Vec<WatchSubscription>,
+    /// IDs of subscriptions to remove
+    #[serde(default)]
+    pub remove:    Vec<String>,
+}
+
+/// One watch of a multiplexed stream
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WatchSubscription {
+    /// Client-chosen ID, carried by every event of this watch
+    pub id:     String,
+    /// The watch method, e.g. `world.get_components+watch`
+    pub method: String,
+    /// The params the watch method takes
+    #[serde(default)]
+    pub params: Option<Value>,
+}
+
+/// Response structure for `multiwatch_subscribe`
+#[derive(Debug, Serialize)]
+pub struct MultiwatchSubscribeResponse {
+    /// ID of the stream
+    pub stream_id:     String,
+    /// IDs of the stream's subscriptions after the change
+    pub subscriptions: Vec<String>,
+}
+
+/// One subscription's changes in a frame
+#[derive(Debug, Serialize)]
+struct MultiwatchEvent {
+    /// The subscription's ID
+    id:        String,
+    /// What the watch reported
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result:    Option<Value>,
+    /// Why the watch failed - the subscription is dropped
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error:     Option<BrpError>,
+    /// The watched entity, despawned this frame - the subscription is dropped
+    #[serde(skip_serializing_if = "Option::is_none")]
+    despawned: Option<u64>,
+}
+
+/// A subscription's registered watch and its params
+struct Subscription {
+    params: Option<Value>,
+    system: WatchSystem,
+    /// The entity in `params`, checked before the watch runs
+    entity: Option<Entity>,
+}
+
+/// The subscriptions of one stream
+struct MultiwatchStream {
+    subscriptions: BTreeMap<String, Subscription>,
+    created:       Instant,
+    /// Whether the client has opened the stream
+    opened:        bool,
+    /// Whether the stream was polled since the last pruning
+    polled:        bool,
+}
+
+impl MultiwatchStream {
+    fn new() -> Self {
+        Self {
+            subscriptions: BTreeMap::new(),
+            created:       Instant::now(),
+            opened:        false,
+            polled:        false,
+        }
+    }
+}
+
+/// Multiplexed watch streams by stream ID
+#[derive(Resource, Default)]
+pub struct MultiwatchStreams {
+    streams: HashMap<String, MultiwatchStream>,
+}
+
+/// Handler for `multiwatch+watch` requests
+///
+/// Reports subscriptions whose entity was despawned, runs the others and returns their changes -
+/// nothing when no watch reported any this frame.
+pub fn handler(In(params): In<Option<Value>>, world: &mut World) -> BrpResult<Option<Value>> {
+    let request: MultiwatchRequest = parse_request(params)?;
+
+    let (despawned, subscriptions) =
+        world.resource_scope(|world, mut streams: Mut<MultiwatchStreams>| {
+            let stream = streams
+                .streams
+                .entry(request.stream_id.clone())
+                .or_insert_with(MultiwatchStream::new);
+            stream.opened = true;
+            stream.polled = true;
+            let (despawned, running): (BTreeMap<_, _>, BTreeMap<_, _>) =
+                std::mem::take(&mut stream.subscriptions)
+                    .into_iter()
+                    .partition(|(_, subscription)| {
+                        subscription
+                            .entity
+                            .is_some_and(|entity| world.get_entity(entity).is_err())
+                    });
+            stream.subscriptions = running;
+            let subscriptions: Vec<(String, WatchSystem, Option<Value>)> = stream
+                .subscriptions
+                .iter()
+                .map(|(id, subscription)| {
+                    (id.clone(), subscription.system, subscription.params.clone())
+                })
+                .collect();
+            (despawned, subscriptions)
+        });
+
+    let mut retired = Vec::new();
+    let mut events = Vec::new();
+    for (id, subscription) in despawned {
+        retired.push(subscription.system);
+        events.push(MultiwatchEvent {
+            id,
+            result: None,
+            error: None,
+            despawned: subscription.entity.map(Entity::to_bits),
+        });
+    }
+
+    let mut failed = Vec::new();
+    for (id, system, params) in subscriptions {
+        let error = match world.run_system_with(system, params) {
+            Ok(Ok(None)) => continue,
+            Ok(Ok(Some(result))) => {
+                events.push(MultiwatchEvent {
+                    id,
+                    result: Some(result),
+                    error: None,
+                    despawned: None,
+                });
+                continue;
+            },
+            Ok(Err(error)) => error,
+            Err(error) => BrpError {
+                code:    INTERNAL_ERROR,
+                message: format!("Watch system failed: {error}"),
+                data:    None,
+            },
+        };
+        failed.push(id.clone());
+        events.push(MultiwatchEvent {
+            id,
+            result: None,
+            error: Some(error),
+            despawned: None,
+        });
+    }
+
+    if let Some(stream) = world
+        .resource_mut::<MultiwatchStreams>()
+        .streams
+        .get_mut(&request.stream_id)
+    {
+        retired.extend(
+            failed
+                .iter()
+                .filter_map(|id| stream.subscriptions.remove(id))
+                .map(|subscription| subscription.system),
+        );
+    }
+    unregister_systems(world, retired);
+
+    if events.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(json!({
+        "stream_id": request.stream_id,
+        "events": events,
+    })))
+}
+
+/// Handler for `multiwatch_subscribe` requests
+pub fn subscribe_handler(In(params): In<Option<Value>>, world: &mut World) -> BrpResult {
+    let request: MultiwatchSubscribeRequest = parse_request(params)?;
+
+    if let Some(subscription) = request
+        .add
+        .iter()
+        .find(|subscription| !SUPPORTED_METHODS.contains(&subscription.method.as_str()))
+    {
+        return Err(BrpError {
+            code:    INVALID_PARAMS,
+            message: format!(
+                "Subscription '{}' uses '{}', which can't be multiplexed - supported methods: {}",
+                subscription.id,
+                subscription.method,
+                SUPPORTED_METHODS.join(", ")
+            ),
+            data:    None,
+        });
+    }
+
+    world.resource_scope(|world, mut streams: Mut<MultiwatchStreams>| {
+        let stream = streams
+            .streams
+            .entry(request.stream_id.clone())
+            .or_insert_with(MultiwatchStream::new);
+
+        let kept = stream
+            .subscriptions
+            .keys()
+            .filter(|id| {
+                !request.remove.contains(id)
+                    && !request
+                        .add
+                        .iter()
+                        .any(|subscription| &subscription.id == *id)
+            })
+            .count();
+        if kept + request.add.len() > MAX_SUBSCRIPTIONS_PER_STREAM {
+            return Err(BrpError {
+                code:    INVALID_PARAMS,
+                message: format!(
+                    "A stream holds at most {MAX_SUBSCRIPTIONS_PER_STREAM} subscriptions - remove \
+                     some first"
+                ),
+                data:    None,
+            });
+        }
+
+        let mut retired: Vec<WatchSystem> = request
+            .remove
+            .iter()
+            .filter_map(|id| stream.subscriptions.remove(id))
+            .map(|subscription| subscription.system)
+            .collect();
+        for subscription in request.add {
+            let Some(system) = register_watch(world, &subscription.method) else {
+                continue;
+            };
+            let entity = subscription
+                .params
+                .as_ref()
+                .and_then(|params| params.get("entity"))
+                .and_then(|entity| serde_json::from_value(entity.clone()).ok());
+            let added = Subscription {
+                params: subscription.params,
+                system,
+                entity,
+            };
+            if let Some(replaced) = stream.subscriptions.insert(subscription.id, added) {
+                retired.push(replaced.system);
+            }
+        }
+        let subscriptions = stream.subscriptions.keys().cloned().collect();
+        unregister_systems(world, retired);
+
+        serde_json::to_value(MultiwatchSubscribeResponse {
+            stream_id: request.stream_id,
+            subscriptions,
+        })
+        .map_err(|e| BrpError {
+            code:    INTERNAL_ERROR,
+            message: format!("Failed to serialize response: {e}"),
+            data:    None,
+        })
+    })
+}
+
+/// System dropping streams whose connection closed, run after Bevy polls the open streams
+pub fn prune_closed_streams(world: &mut World) {
+    if world
+        .get_resource::<MultiwatchStreams>()
+        .is_none_or(|streams| streams.streams.is_empty())
+    {
+        return;
+    }
+
+    let now = Instant::now();
+    let mut retired = Vec::new();
+    world
+        .resource_mut::<MultiwatchStreams>()
+        .streams
+        .retain(|_, stream| {
+            let closed = if stream.opened {
+                !stream.polled
+            } else {
+                now.duration_since(stream.created) > STREAM_OPEN_TIMEOUT
+            };
+            stream.polled = false;
+            if closed {
+                retired.extend(
+                    stream
+                        .subscriptions
+                        .values()
+                        .map(|subscription| subscription.system),
+                );
+            }
+            !closed
+        });
+    unregister_systems(world, retired);
+}
+
+/// Register the watching system behind `method` - `None` when it can't be multiplexed
+fn register_watch(world: &mut World, method: &str) -> Option<WatchSystem> {
+    match method {
+        BRP_GET_COMPONENTS_AND_WATCH_METHOD => {
+            Some(world.register_system(process_remote_get_components_watching_request))
+        },
+        BRP_LIST_COMPONENTS_AND_WATCH_METHOD => {
+            Some(world.register_system(process_remote_list_components_watching_request))
+        },
+        _ => None,
+    }
+}
+
+/// Unregister the watching systems of dropped subscriptions
+fn unregister_systems(world: &mut World, systems: Vec<WatchSystem>) {
+    for system in systems {
+        if let Err(error) = world.unregister_system(system) {
+            debug!("Failed to unregister multiwatch system: {error}");
+        }
+    }
+}
+
+/// Deserialize the request params of a multiwatch method
+fn parse_request<T: for<'de> Deserialize<'de>>(params: Option<Value>) -> BrpResult<T> {
+    params
+        .ok_or_else(|| BrpError {
+            code:    INVALID_PARAMS,
+            message: "Missing request parameters".to_string(),
+            data:    None,
+        })
+        .and_then(|params| {
+            serde_json::from_value(params).map_err(|e| BrpError {
+                code:    INVALID_PARAMS,
+                message: format!("Invalid request format: {e}"),
+                data:    None,
+            })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn subscribe(world: &mut World, params: Value) -> BrpResult {
+        world
+            .run_system_cached_with(subscribe_handler, Some(params))
+            .unwrap_or_else(|error| unreachable!("subscribe handler should run: {error}"))
+    }
+
+    #[test]
+    fn subscriptions_are_added_replaced_and_removed_by_id() {
+        let mut world = World::new();
+        world.init_resource::<MultiwatchStreams>();
+
+        let added = subscribe(
+            &mut world,
+            json!({
+                "stream_id": "mcp",
+                "add": [
+                    { "id": "1", "method": BRP_GET_COMPONENTS_AND_WATCH_METHOD, "params": {} },
+                    { "id": "2", "method": BRP_LIST_COMPONENTS_AND_WATCH_METHOD },
+                ],
+            }),
+        );
+        assert_eq!(
+            added.ok(),
+            Some(json!({ "stream_id": "mcp", "subscriptions": ["1", "2"] }))
+        );
+
+        let removed = subscribe(&mut world, json!({ "stream_id": "mcp", "remove": ["1"] }));
+        assert_eq!(
+            removed.ok(),
+            Some(json!({ "stream_id": "mcp", "subscriptions": ["2"] }))
+        );
+
+        let unsupported = subscribe(
+            &mut world,
+            json!({
+                "stream_id": "mcp",
+                "add": [{ "id": "3", "method": "world.query" }],
+            }),
+        );
+        assert_eq!(
+            unsupported.err().map(|error| error.code),
+            Some(INVALID_PARAMS)
+        );
+    }
+
+    #[test]
+    fn despawned_entity_is_reported_and_its_subscription_dropped() {
+        let mut world = World::new();
+        world.init_resource::<AppTypeRegistry>();
+        world.init_resource::<MultiwatchStreams>();
+        let entity = world.spawn(Name::new("watched")).id();
+
+        let params = json!({ "entity": entity, "components": [] });
+        let added = subscribe(
+            &mut world,
+            json!({
+                "stream_id": "mcp",
+                "add": [{
+                    "id": "1",
+                    "method": BRP_GET_COMPONENTS_AND_WATCH_METHOD,
+                    "params": params,
+                }],
+            }),
+        );
+        assert!(added.is_ok());
+
+        world.despawn(entity);
+        let polled = world
+            .run_system_cached_with(handler, Some(json!({ "stream_id": "mcp" })))
+            .unwrap_or_else(|error| unreachable!("multiwatch handler should run: {error}"));
+        assert_eq!(
+            polled.ok().flatten(),
+            Some(json!({
+                "stream_id": "mcp",
+                "events": [{ "id": "1", "despawned": entity.to_bits() }],
+            }))
+        );
+        assert!(
+            world.resource::<MultiwatchStreams>().streams["mcp"]
+                .subscriptions
+                .is_empty()
+        );
+    }
+
+    fn poll(world: &mut World) -> Value {
+        world
+            .run_system_cached_with(handler, Some(json!({ "stream_id": "mcp" })))
+            .unwrap_or_else(|error| unreachable!("multiwatch handler should run: {error}"))
+            .unwrap_or_else(|error| unreachable!("multiwatch poll should succeed: {error:?}"))
+            .unwrap_or(Value::Null)
+    }
+
+    fn prune(world: &mut World) {
+        world
+            .run_system_cached(prune_closed_streams)
+            .unwrap_or_else(|error| unreachable!("pruning should run: {error}"));
+    }
+
+    fn subscription_ids(world: &World) -> Option<Vec<String>> {
+        world
+            .resource::<MultiwatchStreams>()
+            .streams
+            .get("mcp")
+            .map(|stream| stream.subscriptions.keys().cloned().collect())
+    }
+
+    #[test]
+    fn failed_watch_is_reported_once_and_its_subscription_dropped() {
+        let mut world = World::new();
+        world.init_resource::<AppTypeRegistry>();
+        world.init_resource::<MultiwatchStreams>();
+        let entity = world.spawn(Name::new("watched")).id();
+
+        let added = subscribe(
+            &mut world,
+            json!({
+                "stream_id": "mcp",
+                "add": [
+                    { "id": "listed", "method": BRP_LIST_COMPONENTS_AND_WATCH_METHOD,
+                      "params": { "entity": entity } },
+                    // No entity - the watch rejects its params
+                    { "id": "invalid", "method": BRP_LIST_COMPONENTS_AND_WATCH_METHOD,
+                      "params": {} },
+                ],
+            }),
+        );
+        assert!(added.is_ok());
+
+        let first = poll(&mut world);
+        let failed: Vec<&Value> = first["events"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter(|event| event.get("error").is_some())
+            .map(|event| &event["id"])
+            .collect();
+        assert_eq!(failed, vec![&json!("invalid")]);
+        assert_eq!(subscription_ids(&world), Some(vec!["listed".to_string()]));
+
+        // Nothing changed since - the remaining watch reports nothing
+        assert_eq!(poll(&mut world), Value::Null);
+    }
+
+    #[test]
+    fn streams_are_dropped_once_a_frame_goes_by_without_a_poll() {
+        let mut world = World::new();
+        world.init_resource::<MultiwatchStreams>();
+        let entity = world.spawn(Name::new("watched")).id();
+        let added = subscribe(
+            &mut world,
+            json!({
+                "stream_id": "mcp",
+                "add": [{
+                    "id": "1",
+                    "method": BRP_LIST_COMPONENTS_AND_WATCH_METHOD,
+                    "params": { "entity": entity },
+                }],
+            }),
+        );
+        assert!(added.is_ok());
+
+        // Subscribed but not yet opened - kept however many frames go by
+        prune(&mut world);
+        prune(&mut world);
+        assert!(subscription_ids(&world).is_some());
+
+        // Polled each frame while its connection is open
+        poll(&mut world);
+        prune(&mut world);
+        assert!(subscription_ids(&world).is_some());
+
+        // The connection closed, so Bevy stopped polling it
+        prune(&mut world);
+        assert_eq!(subscription_ids(&world), None);
+    }
+}
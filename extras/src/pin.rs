@@ -0,0 +1,186 @@
+//! Entity pinning handler for BRP extras
+//!
+//! Entities often vanish while an agent is still inspecting them. `brp_extras/pin_entity` puts a
+//! `BrpPinned` guard component on an entity for a bounded time. Bevy can't veto a despawn, so
+//! the guard only works when the app's despawn systems skip pinned entities, e.g. with a
+//! `Without<BrpPinned>` filter - a pinned entity that is despawned anyway is logged as a warning.
+
+use bevy::ecs::lifecycle::HookContext;
+use bevy::ecs::world::DeferredWorld;
+use bevy::prelude::*;
+use bevy::remote::BrpError;
+use bevy::remote::BrpResult;
+use bevy::remote::error_codes::INVALID_PARAMS;
+use serde::Deserialize;
+use serde_json::Value;
+use serde_json::json;
+
+use crate::time_config;
+
+/// How long an entity stays pinned without `duration_seconds`
+const DEFAULT_PIN_SECONDS: f64 = 60.0;
+
+/// Longest an entity can be pinned by one request
+const MAX_PIN_SECONDS: f64 = 3600.0;
+
+/// Guard asking the app's despawn systems to leave an entity alone while it is inspected
+///
+/// Added by `brp_extras/pin_entity` and removed once it expires.
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq)]
+#[reflect(Component)]
+#[component(on_despawn = warn_pinned_despawn)]
+pub struct BrpPinned {
+    /// `Time<Real>` elapsed seconds at which the pin expires
+    pub expires_at_seconds: f64,
+}
+
+/// Request structure for `pin_entity`
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PinEntityRequest {
+    /// Entity to pin
+    pub entity:           Entity,
+    /// Seconds to keep the entity pinned - defaults to 60, at most 3600
+    #[serde(default)]
+    pub duration_seconds: Option<f64>,
+    /// `true` removes the entity's pin instead
+    #[serde(default)]
+    pub unpin:            bool,
+}
+
+/// Handler for `pin_entity` requests
+pub fn handler(In(params): In<Option<Value>>, world: &mut World) -> BrpResult {
+    let request: PinEntityRequest = params
+        .ok_or_else(|| BrpError {
+            code:    INVALID_PARAMS,
+            message: "Missing request parameters".to_string(),
+            data:    None,
+        })
+        .and_then(|params| {
+            serde_json::from_value(params).map_err(|e| BrpError {
+                code:    INVALID_PARAMS,
+                message: format!("Invalid request format: {e}"),
+                data:    None,
+            })
+        })?;
+
+    let duration_seconds = request.duration_seconds.unwrap_or(DEFAULT_PIN_SECONDS);
+    if !(duration_seconds > 0.0 && duration_seconds <= MAX_PIN_SECONDS) {
+        return Err(BrpError {
+            code:    INVALID_PARAMS,
+            message: format!(
+                "'duration_seconds' must be greater than 0 and at most {MAX_PIN_SECONDS}, got \
+                 {duration_seconds}"
+            ),
+            data:    None,
+        });
+    }
+
+    let now = time_config::time_mut::<Real>(world)?.elapsed_secs_f64();
+    let entity = request.entity;
+    let mut entity_mut = world
+        .get_entity_mut(entity)
+        .map_err(|_| BrpError::entity_not_found(entity))?;
+
+    if request.unpin {
+        let was_pinned = entity_mut.take::<BrpPinned>().is_some();
+        return Ok(json!({
+            "entity": entity.to_bits(),
+            "pinned": false,
+            "message": if was_pinned {
+                format!("Unpinned entity {entity}")
+            } else {
+                format!("Entity {entity} was not pinned")
+            },
+        }));
+    }
+
+    entity_mut.insert(BrpPinned {
+        expires_at_seconds: now + duration_seconds,
+    });
+    Ok(json!({
+        "entity": entity.to_bits(),
+        "pinned": true,
+        "expires_in_seconds": duration_seconds,
+        "message": format!("Pinned entity {entity} for {duration_seconds} seconds"),
+    }))
+}
+
+/// System that removes pins once they expire
+pub fn expire_pins(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    pins: Query<(Entity, &BrpPinned)>,
+) {
+    let now = time.elapsed_secs_f64();
+    for (entity, pin) in &pins {
+        if pin.expires_at_seconds <= now {
+            commands.entity(entity).remove::<BrpPinned>();
+        }
+    }
+}
+
+/// Warn when a pinned entity is despawned by a system that doesn't respect the pin
+fn warn_pinned_despawn(_world: DeferredWorld, context: HookContext) {
+    warn!(
+        "Pinned entity {} was despawned - filter despawn systems with Without<BrpPinned> to keep \
+         pinned entities alive",
+        context.entity
+    );
+}
+
+#[cfg(test)]
+#[allow(clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    fn pin_world() -> World {
+        let mut world = World::new();
+        world.insert_resource(Time::<Real>::default());
+        world
+    }
+
+    #[test]
+    fn pins_and_unpins_an_entity() {
+        let mut world = pin_world();
+        let entity = world.spawn_empty().id();
+
+        handler(
+            In(Some(
+                json!({"entity": entity.to_bits(), "duration_seconds": 5.0}),
+            )),
+            &mut world,
+        )
+        .expect("valid request");
+        let pin = world
+            .get::<BrpPinned>(entity)
+            .expect("entity should be pinned");
+        assert!((pin.expires_at_seconds - 5.0).abs() < 1.0);
+
+        let response = handler(
+            In(Some(json!({"entity": entity.to_bits(), "unpin": true}))),
+            &mut world,
+        )
+        .expect("valid request");
+        assert_eq!(response["pinned"], json!(false));
+        assert!(world.get::<BrpPinned>(entity).is_none());
+    }
+
+    #[test]
+    fn rejects_unbounded_durations() {
+        let mut world = pin_world();
+        let entity = world.spawn_empty().id();
+
+        for duration_seconds in [0.0, MAX_PIN_SECONDS + 1.0] {
+            let error = handler(
+                In(Some(
+                    json!({"entity": entity.to_bits(), "duration_seconds": duration_seconds}),
+                )),
+                &mut world,
+            )
+            .expect_err("duration out of range");
+            assert_eq!(error.code, INVALID_PARAMS);
+        }
+        assert!(world.get::<BrpPinned>(entity).is_none());
+    }
+}
@@ -0,0 +1,386 @@
+//! Pixel probe handler for BRP extras
+//!
+//! Samples the colors of a few pixels of the primary window without writing a screenshot to
+//! disk, for quick checks like "did the damage flash turn the screen red?". A BRP request can't
+//! wait for a frame to be captured, so probing takes two calls: one with `points` and/or `rect`
+//! captures the next frame and returns a `probe_id`, and calls with that `probe_id` poll until the
+//! colors are ready. Each probe is kept under its own ID, so clients probing at the same time
+//! don't read each other's colors.
+
+use std::collections::BTreeMap;
+
+use bevy::prelude::*;
+use bevy::remote::BrpError;
+use bevy::remote::BrpResult;
+use bevy::remote::error_codes::INTERNAL_ERROR;
+use bevy::remote::error_codes::INVALID_PARAMS;
+use bevy::render::view::screenshot::Screenshot;
+use bevy::render::view::screenshot::ScreenshotCaptured;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+use serde_json::json;
+
+/// Most pixels sampled by one probe - a probe is for spot checks, not for reading the frame
+const MAX_PROBE_PIXELS: usize = 1_024;
+
+/// Most recent probes whose state is kept - older ones are forgotten
+const MAX_TRACKED_PROBES: usize = 16;
+
+/// Request structure for `read_pixels`
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ReadPixelsRequest {
+    /// Pixels to sample as `[x, y]` in physical pixels from the top-left corner
+    #[serde(default)]
+    pub points:   Option<Vec<[u32; 2]>>,
+    /// Rectangle whose every pixel is sampled
+    #[serde(default)]
+    pub rect:     Option<PixelRect>,
+    /// Probe to poll, as returned when it was started
+    #[serde(default)]
+    pub probe_id: Option<u64>,
+}
+
+/// A rectangle of pixels, in physical pixels from the top-left corner
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct PixelRect {
+    /// Left edge
+    pub x:      u32,
+    /// Top edge
+    pub y:      u32,
+    /// Width in pixels
+    pub width:  u32,
+    /// Height in pixels
+    pub height: u32,
+}
+
+impl ReadPixelsRequest {
+    /// Pixels to sample, `points` first - `None` when the request only polls
+    fn coordinates(&self) -> Result<Option<Vec<UVec2>>, String> {
+        if self.points.is_none() && self.rect.is_none() {
+            return Ok(None);
+        }
+
+        let mut coordinates: Vec<UVec2> = self
+            .points
+            .iter()
+            .flatten()
+            .map(|&[x, y]| UVec2::new(x, y))
+            .collect();
+        if let Some(rect) = self.rect {
+            if rect.width == 0 || rect.height == 0 {
+                return Err("'rect' must have a non-zero width and height".to_string());
+            }
+            let area = u64::from(rect.width) * u64::from(rect.height);
+            if usize::try_from(area).map_or(true, |area| area > MAX_PROBE_PIXELS) {
+                return Err(format!(
+                    "'rect' covers {area} pixels - probe at most {MAX_PROBE_PIXELS}"
+                ));
+            }
+            for y in rect.y..rect.y.saturating_add(rect.height) {
+                for x in rect.x..rect.x.saturating_add(rect.width) {
+                    coordinates.push(UVec2::new(x, y));
+                }
+            }
+        }
+
+        if coordinates.is_empty() {
+            return Err("Give at least one pixel in 'points' or a 'rect'".to_string());
+        }
+        if coordinates.len() > MAX_PROBE_PIXELS {
+            return Err(format!(
+                "{} pixels requested - probe at most {MAX_PROBE_PIXELS}",
+                coordinates.len()
+            ));
+        }
+        Ok(Some(coordinates))
+    }
+}
+
+/// Color of a sampled pixel
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PixelSample {
+    /// Column in physical pixels
+    pub x:    u32,
+    /// Row in physical pixels
+    pub y:    u32,
+    /// sRGB red, green, blue and alpha from 0.0 to 1.0
+    pub rgba: [f32; 4],
+    /// The color as `#RRGGBB`, or `#RRGGBBAA` when not opaque
+    pub hex:  String,
+}
+
+/// Colors sampled from a captured frame
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PixelSamples {
+    /// Width of the captured frame
+    pub frame_width:  u32,
+    /// Height of the captured frame
+    pub frame_height: u32,
+    /// Sampled pixels in request order
+    pub pixels:       Vec<PixelSample>,
+    /// Mean color of the sampled pixels
+    pub average:      PixelColor,
+}
+
+/// A color without a position
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PixelColor {
+    /// sRGB red, green, blue and alpha from 0.0 to 1.0
+    pub rgba: [f32; 4],
+    /// The color as `#RRGGBB`, or `#RRGGBBAA` when not opaque
+    pub hex:  String,
+}
+
+impl From<Srgba> for PixelColor {
+    fn from(color: Srgba) -> Self {
+        Self {
+            rgba: color.to_f32_array(),
+            hex:  color.to_hex(),
+        }
+    }
+}
+
+/// State of recent probes, by probe ID
+#[derive(Resource, Default)]
+pub struct PixelProbes {
+    next_id: u64,
+    probes:  BTreeMap<u64, ProbeState>,
+}
+
+impl PixelProbes {
+    /// Track a new probe waiting for its frame - returns its probe ID
+    fn start(&mut self) -> u64 {
+        self.next_id += 1;
+        self.probes.insert(self.next_id, ProbeState::Capturing);
+        while self.probes.len() > MAX_TRACKED_PROBES {
+            self.probes.pop_first();
+        }
+        self.next_id
+    }
+
+    /// Record the outcome of a probe, unless it has already been forgotten
+    fn finish(&mut self, probe_id: u64, state: ProbeState) {
+        if let Some(current) = self.probes.get_mut(&probe_id) {
+            *current = state;
+        }
+    }
+}
+
+/// Progress of a probe
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum ProbeState {
+    /// Waiting for the frame to be captured
+    Capturing,
+    /// The colors have been sampled
+    Complete(PixelSamples),
+    /// The frame couldn't be sampled
+    Failed {
+        /// Why sampling failed
+        error: String,
+    },
+}
+
+/// Handler for `read_pixels` requests
+pub fn handler(In(params): In<Option<Value>>, world: &mut World) -> BrpResult {
+    let request: ReadPixelsRequest = params
+        .map(serde_json::from_value)
+        .transpose()
+        .map_err(|e| BrpError {
+            code:    INVALID_PARAMS,
+            message: format!("Invalid request format: {e}"),
+            data:    None,
+        })?
+        .unwrap_or_default();
+
+    let coordinates = request.coordinates().map_err(|message| BrpError {
+        code: INVALID_PARAMS,
+        message,
+        data: None,
+    })?;
+
+    let probe_id = match (coordinates, request.probe_id) {
+        (Some(_), Some(_)) => {
+            return Err(BrpError {
+                code:    INVALID_PARAMS,
+                message: "Pass 'points' or 'rect' to start a probe, or 'probe_id' to poll one - \
+                          not both"
+                    .to_string(),
+                data:    None,
+            });
+        },
+        (Some(coordinates), None) => start_probe(world, coordinates)?,
+        (None, Some(probe_id)) => probe_id,
+        (None, None) => {
+            return Err(BrpError {
+                code:    INVALID_PARAMS,
+                message: "Pass 'points' or 'rect' to start a probe, or 'probe_id' to poll one"
+                    .to_string(),
+                data:    None,
+            });
+        },
+    };
+
+    let state = world
+        .get_resource::<PixelProbes>()
+        .and_then(|probes| probes.probes.get(&probe_id))
+        .ok_or_else(|| BrpError {
+            code:    INVALID_PARAMS,
+            message: format!(
+                "Unknown pixel probe {probe_id} - only the last {MAX_TRACKED_PROBES} probes are \
+                 kept"
+            ),
+            data:    None,
+        })?;
+    let mut response = json!(state);
+    if let Value::Object(map) = &mut response {
+        map.insert("probe_id".to_string(), json!(probe_id));
+    }
+    Ok(response)
+}
+
+/// Start capturing the next frame of the primary window to sample `coordinates` - returns the
+/// probe ID
+fn start_probe(world: &mut World, coordinates: Vec<UVec2>) -> Result<u64, BrpError> {
+    if world.query::<&Window>().iter(world).next().is_none() {
+        return Err(BrpError {
+            code:    INTERNAL_ERROR,
+            message: "No windows found - cannot read pixels".to_string(),
+            data:    None,
+        });
+    }
+
+    let probe_id = world.get_resource_or_init::<PixelProbes>().start();
+    world
+        .spawn((Screenshot::primary_window(), Name::new("PixelProbe")))
+        .observe(create_sample_observer(probe_id, coordinates));
+    Ok(probe_id)
+}
+
+/// Creates an observer that samples `coordinates` from the captured frame and stores the colors
+/// under probe `probe_id`
+fn create_sample_observer(
+    probe_id: u64,
+    coordinates: Vec<UVec2>,
+) -> impl FnMut(On<ScreenshotCaptured>, ResMut<PixelProbes>) {
+    move |captured: On<ScreenshotCaptured>, mut probes: ResMut<PixelProbes>| {
+        let state = match sample(&captured.event().image, &coordinates) {
+            Ok(samples) => ProbeState::Complete(samples),
+            Err(error) => ProbeState::Failed { error },
+        };
+        probes.finish(probe_id, state);
+    }
+}
+
+/// Colors of `coordinates` in a captured frame
+#[allow(clippy::cast_precision_loss)]
+fn sample(image: &Image, coordinates: &[UVec2]) -> Result<PixelSamples, String> {
+    let size = image.size();
+    let mut pixels = Vec::with_capacity(coordinates.len());
+    let mut sum = Vec4::ZERO;
+    for &UVec2 { x, y } in coordinates {
+        if x >= size.x || y >= size.y {
+            return Err(format!(
+                "Pixel ({x}, {y}) is outside the {}x{} frame",
+                size.x, size.y
+            ));
+        }
+        let color = image
+            .get_color_at(x, y)
+            .map_err(|e| format!("Failed to read pixel ({x}, {y}): {e}"))?
+            .to_srgba();
+        sum += Vec4::from_array(color.to_f32_array());
+        let PixelColor { rgba, hex } = color.into();
+        pixels.push(PixelSample { x, y, rgba, hex });
+    }
+
+    let average = sum / coordinates.len().max(1) as f32;
+    Ok(PixelSamples {
+        frame_width: size.x,
+        frame_height: size.y,
+        pixels,
+        average: Srgba::from_f32_array(average.to_array()).into(),
+    })
+}
+
+#[cfg(test)]
+#[allow(clippy::expect_used)]
+mod tests {
+    use bevy::asset::RenderAssetUsages;
+    use bevy::render::render_resource::Extent3d;
+    use bevy::render::render_resource::TextureDimension;
+    use bevy::render::render_resource::TextureFormat;
+
+    use super::*;
+
+    #[test]
+    fn rect_and_points_are_sampled_in_order() {
+        let request: ReadPixelsRequest = serde_json::from_value(json!({
+            "points": [[5, 5]],
+            "rect": { "x": 0, "y": 0, "width": 2, "height": 1 },
+        }))
+        .expect("valid request");
+        let coordinates = request.coordinates().expect("valid coordinates");
+        assert_eq!(
+            coordinates,
+            Some(vec![UVec2::new(5, 5), UVec2::new(0, 0), UVec2::new(1, 0)])
+        );
+
+        let too_large: ReadPixelsRequest = serde_json::from_value(json!({
+            "rect": { "x": 0, "y": 0, "width": 100, "height": 100 },
+        }))
+        .expect("valid request");
+        assert!(too_large.coordinates().is_err());
+    }
+
+    #[test]
+    fn samples_colors_within_the_frame() {
+        let image = Image::new_fill(
+            Extent3d {
+                width:                 2,
+                height:                2,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            &[255, 0, 0, 255],
+            TextureFormat::Rgba8UnormSrgb,
+            RenderAssetUsages::default(),
+        );
+
+        let samples = sample(&image, &[UVec2::new(0, 0), UVec2::new(1, 1)]).expect("in bounds");
+        assert_eq!(samples.pixels[0].hex, "#FF0000");
+        assert_eq!(samples.average.hex, "#FF0000");
+
+        assert!(sample(&image, &[UVec2::new(2, 0)]).is_err());
+    }
+
+    #[test]
+    fn probes_are_kept_apart_by_id() {
+        let mut probes = PixelProbes::default();
+        let first = probes.start();
+        let second = probes.start();
+        probes.finish(
+            first,
+            ProbeState::Failed {
+                error: "first".to_string(),
+            },
+        );
+        assert!(matches!(
+            probes.probes.get(&first),
+            Some(ProbeState::Failed { error }) if error == "first"
+        ));
+        assert!(matches!(
+            probes.probes.get(&second),
+            Some(ProbeState::Capturing)
+        ));
+
+        for _ in 0..MAX_TRACKED_PROBES {
+            probes.start();
+        }
+        assert!(!probes.probes.contains_key(&first));
+        probes.finish(first, ProbeState::Capturing);
+        assert_eq!(probes.probes.len(), MAX_TRACKED_PROBES);
+    }
+}
@@ -1,31 +1,182 @@
 //! Plugin implementation for extra BRP methods
 
 use bevy::prelude::*;
+use bevy::reflect::GetTypeRegistration;
+use bevy::remote::BrpResult;
+use bevy::remote::RemoteLast;
 use bevy::remote::RemotePlugin;
+use bevy::remote::RemoteSystems;
 use bevy::remote::http::RemoteHttpPlugin;
+use http::HeaderName;
+use http::HeaderValue;
+use serde_json::Value;
 
 use crate::DEFAULT_REMOTE_PORT;
+use crate::activity;
+use crate::app_info;
+use crate::app_info::BuildInfo;
+use crate::apply_commands;
+use crate::archetype_moves;
+#[cfg(feature = "binary-transport")]
+use crate::binary_transport;
+use crate::capabilities;
+use crate::capabilities::Capability;
+use crate::capabilities::CapabilityStatus;
+use crate::capabilities::ExtrasCapabilities;
+#[cfg(feature = "clipboard")]
+use crate::clipboard;
+#[cfg(feature = "compression")]
+use crate::compressed_http;
+use crate::determinism;
+use crate::frame_stats;
+use crate::gizmo_config;
+use crate::history;
+use crate::interaction;
 use crate::keyboard;
+#[cfg(feature = "material")]
+use crate::material;
+#[cfg(feature = "window")]
+use crate::monitor;
+use crate::multiwatch;
+use crate::pin;
+#[cfg(feature = "screenshot")]
+use crate::pixels;
+use crate::query_stream;
+use crate::registration;
+use crate::schedule_report;
+#[cfg(feature = "screenshot")]
 use crate::screenshot;
 use crate::shutdown;
+use crate::tag;
+use crate::time_config;
+use crate::topology;
+use crate::topology::BrpTopology;
+use crate::ui_text;
+#[cfg(feature = "window")]
+use crate::window;
+#[cfg(feature = "window")]
 use crate::window_title;
 
 /// Command prefix for `brp_extras` methods
 const EXTRAS_COMMAND_PREFIX: &str = "brp_extras/";
 
+/// A BRP method of this crate - its name without `brp_extras/`, and its handler
+type ExtrasMethod = (&'static str, fn(In<Option<Value>>, &mut World) -> BrpResult);
+
+/// A streaming BRP method of this crate - its name without `brp_extras/`, and its handler
+type ExtrasWatchingMethod = (
+    &'static str,
+    fn(In<Option<Value>>, &mut World) -> BrpResult<Option<Value>>,
+);
+
+/// Methods every app with the plugin serves
+const CORE_METHODS: &[ExtrasMethod] = &[
+    ("shutdown", shutdown::handler),
+    ("tag_entity", tag::tag_entity_handler),
+    ("find_tagged", tag::find_tagged_handler),
+    ("time_config", time_config::handler),
+    ("gizmo_config", gizmo_config::handler),
+    ("collect_frame_stats", frame_stats::handler),
+    ("track_archetype_moves", archetype_moves::handler),
+    ("activity", activity::handler),
+    ("schedule_report", schedule_report::handler),
+    ("determinism", determinism::handler),
+    ("pin_entity", pin::handler),
+    ("get_ui_text", ui_text::handler),
+    ("get_interaction_state", interaction::handler),
+    ("track_entity", history::track_entity_handler),
+    ("get_history", history::get_history_handler),
+    ("apply_commands", apply_commands::handler),
+    ("app_info", app_info::handler),
+    ("ensure_registered", registration::handler),
+    ("capabilities", capabilities::handler),
+    ("multiwatch_subscribe", multiwatch::subscribe_handler),
+    #[cfg(feature = "binary-transport")]
+    ("wire_encodings", binary_transport::wire_encodings_handler),
+    #[cfg(feature = "clipboard")]
+    ("clipboard", clipboard::handler),
+    #[cfg(feature = "material")]
+    ("material", material::handler),
+];
+
+/// Streaming methods every app with the plugin serves - Bevy only streams names ending in `+watch`
+const WATCHING_METHODS: &[ExtrasWatchingMethod] = &[
+    ("multiwatch+watch", multiwatch::handler),
+    ("query_stream+watch", query_stream::handler),
+];
+
+/// Methods of the `screenshot` section
+#[cfg(feature = "screenshot")]
+const SCREENSHOT_METHODS: &[ExtrasMethod] = &[
+    ("screenshot", screenshot::handler),
+    ("screenshot_status", screenshot::status_handler),
+    ("read_pixels", pixels::handler),
+];
+
+/// Methods of the `keyboard` section
+const KEYBOARD_METHODS: &[ExtrasMethod] = &[("send_keys", keyboard::send_keys_handler)];
+
+/// Methods of the `window` section
+#[cfg(feature = "window")]
+const WINDOW_METHODS: &[ExtrasMethod] = &[
+    ("set_window_title", window_title::handler),
+    ("set_window", window::handler),
+    ("list_monitors", monitor::handler),
+];
+
 /// Plugin that adds extra BRP methods to a Bevy app
 ///
 /// Currently provides:
 /// - `brp_extras/screenshot`: Capture screenshots
+/// - `brp_extras/screenshot_status`: Report whether a screenshot has been saved
 /// - `brp_extras/shutdown`: Gracefully shutdown the app
 /// - `brp_extras/send_keys`: Send keyboard input
 /// - `brp_extras/set_window_title`: Change the window title
+/// - `brp_extras/set_window`: Change window decorations, level, focus and minimized state
+/// - `brp_extras/tag_entity`: Attach a `BrpTag` to an entity
+/// - `brp_extras/find_tagged`: Find entities by tag
+/// - `brp_extras/list_monitors`: List connected displays
+/// - `brp_extras/time_config`: Read or change the fixed timestep, max delta and wrap period
+/// - `brp_extras/gizmo_config`: Read or change gizmo line width, depth bias and enabled groups
+/// - `brp_extras/collect_frame_stats`: Collect frame-time statistics over a time window
+/// - `brp_extras/track_archetype_moves`: Count archetype moves per entity over a number of frames
+/// - `brp_extras/activity`: Report per-frame change counts and how long the app has been quiet
+/// - `brp_extras/schedule_report`: Report system ambiguities and execution order
+/// - `brp_extras/determinism`: Set the RNG seed, fixed frame delta and speed of time
+/// - `brp_extras/pin_entity`: Protect an entity from despawning while it is inspected
+/// - `brp_extras/read_pixels`: Sample pixel colors of the primary window
+/// - `brp_extras/get_ui_text`: Collect UI text with screen rects and hierarchy paths
+/// - `brp_extras/get_interaction_state`: Report keyboard focus, UI interactions and picking hovers
+/// - `brp_extras/track_entity`: Record component changes of an entity
+/// - `brp_extras/get_history`: Read recorded component changes
+/// - `brp_extras/apply_commands`: Apply spawn, insert, remove, despawn and reparent commands
+///   atomically
+/// - `brp_extras/app_info`: Report the app's plugins, build profile and build info
+/// - `brp_extras/ensure_registered`: Report which types are missing reflection registration
+/// - `brp_extras/capabilities`: Report the plugin sections and methods the app serves
+/// - `brp_extras/multiwatch+watch`: Stream many watches over one connection
+/// - `brp_extras/multiwatch_subscribe`: Add and remove the watches of a multiwatch stream
+/// - `brp_extras/query_stream+watch`: Stream the results of a query in chunks
+/// - `brp_extras/wire_encodings`: Report the binary transport's port and encodings
+/// - `brp_extras/clipboard`: Read and set the system clipboard (`clipboard` feature)
+/// - `brp_extras/material`: Read or change an entity's `StandardMaterial` (`material` feature)
+///
+/// Screenshot, keyboard and window methods belong to sections that [`BrpExtrasPlugin::without`]
+/// leaves out, and the screenshot and window sections to cargo features - see [`Capability`].
 #[allow(non_upper_case_globals)]
 pub const BrpExtrasPlugin: BrpExtrasPlugin = BrpExtrasPlugin::new();
 
 /// Plugin type for adding extra BRP methods
 pub struct BrpExtrasPlugin {
-    port: Option<u16>,
+    port:          Option<u16>,
+    auto_register: Vec<fn(&mut App)>,
+    #[cfg(feature = "binary-transport")]
+    binary_port:   Option<u16>,
+    #[cfg(feature = "compression")]
+    compression:   bool,
+    http_headers:  Vec<(String, String)>,
+    build_info:    Option<BuildInfo>,
+    disabled:      Vec<Capability>,
 }
 
 impl Default for BrpExtrasPlugin {
@@ -35,11 +186,106 @@ impl Default for BrpExtrasPlugin {
 impl BrpExtrasPlugin {
     /// Create a new plugin instance with default port
     #[must_use]
-    pub const fn new() -> Self { Self { port: None } }
+    pub const fn new() -> Self {
+        Self {
+            port:                                             None,
+            auto_register:                                    Vec::new(),
+            #[cfg(feature = "binary-transport")]
+            binary_port:                                      None,
+            #[cfg(feature = "compression")]
+            compression:                                      false,
+            http_headers:                                     Vec::new(),
+            build_info:                                       None,
+            disabled:                                         Vec::new(),
+        }
+    }
 
     /// Create plugin with custom port
     #[must_use]
-    pub const fn with_port(port: u16) -> Self { Self { port: Some(port) } }
+    pub const fn with_port(port: u16) -> Self {
+        Self {
+            port:                                             Some(port),
+            auto_register:                                    Vec::new(),
+            #[cfg(feature = "binary-transport")]
+            binary_port:                                      None,
+            #[cfg(feature = "compression")]
+            compression:                                      false,
+            http_headers:                                     Vec::new(),
+            build_info:                                       None,
+            disabled:                                         Vec::new(),
+        }
+    }
+
+    /// Leave the `capability` section out of this app even though its cargo feature is enabled
+    ///
+    /// Its methods aren't served and its systems don't run - e.g. `Capability::Screenshot` for a
+    /// headless server with nothing to capture.
+    #[must_use]
+    pub fn without(mut self, capability: Capability) -> Self {
+        if !self.disabled.contains(&capability) {
+            self.disabled.push(capability);
+        }
+        self
+    }
+
+    /// Whether the `capability` section is compiled in and not left out with `without`
+    #[must_use]
+    pub fn is_enabled(&self, capability: Capability) -> bool {
+        capability.compiled() && !self.disabled.contains(&capability)
+    }
+
+    /// Register `T` for reflection when the plugin is built, so BRP can see it
+    ///
+    /// The same as `app.register_type::<T>()`, chained once per game type that BRP should query,
+    /// insert or mutate. `T` still needs `#[derive(Reflect)]` with `#[reflect(Component)]` or
+    /// `#[reflect(Resource)]` - `brp_extras/ensure_registered` reports what a type is missing.
+    #[must_use]
+    pub fn with_auto_register<T: GetTypeRegistration>(mut self) -> Self {
+        self.auto_register.push(|app| {
+            app.register_type::<T>();
+        });
+        self
+    }
+
+    /// Also serve BRP as `MessagePack` or CBOR frames on `port`
+    ///
+    /// `bevy_brp_mcp` discovers the transport through `brp_extras/wire_encodings` and uses it
+    /// for queries, registry dumps and watches.
+    #[cfg(feature = "binary-transport")]
+    #[must_use]
+    pub const fn with_binary_transport(mut self, port: u16) -> Self {
+        self.binary_port = Some(port);
+        self
+    }
+
+    /// Compress large HTTP responses with gzip or deflate when the client accepts them
+    ///
+    /// BRP is then served over HTTP by this crate's own server in place of Bevy's
+    /// `RemoteHttpPlugin`, on the same port.
+    #[cfg(feature = "compression")]
+    #[must_use]
+    pub const fn with_compression(mut self) -> Self {
+        self.compression = true;
+        self
+    }
+
+    /// Add a header to every BRP HTTP response, such as `Access-Control-Allow-Origin` for a
+    /// browser client
+    ///
+    /// Invalid headers are logged and left out.
+    #[must_use]
+    pub fn with_http_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.http_headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Report how the app was built - its Bevy cargo features, commit and build time - through
+    /// `brp_extras/app_info`
+    #[must_use]
+    pub fn with_build_info(mut self, build_info: BuildInfo) -> Self {
+        self.build_info = Some(build_info);
+        self
+    }
 
     /// Get the effective port, checking environment variable first
     ///
@@ -49,7 +295,7 @@ impl BrpExtrasPlugin {
     /// 3. Default port (15702)
     #[must_use]
     pub fn get_effective_port(&self) -> (u16, String) {
-        let env_port = std::env::var("BRP_EXTRAS_PORT")
+        let env_port = std::env::var(topology::PORT_ENV_VAR)
             .ok()
             .and_then(|s| s.parse::<u16>().ok());
 
@@ -81,48 +327,199 @@ impl Plugin for BrpExtrasPlugin {
             EXTRAS_COMMAND_PREFIX
         );
 
-        let remote_plugin = RemotePlugin::default()
-            .with_method(
-                format!("{EXTRAS_COMMAND_PREFIX}screenshot"),
-                screenshot::handler,
-            )
-            .with_method(
-                format!("{EXTRAS_COMMAND_PREFIX}shutdown"),
-                shutdown::handler,
-            )
-            .with_method(
-                format!("{EXTRAS_COMMAND_PREFIX}send_keys"),
-                keyboard::send_keys_handler,
-            )
-            .with_method(
-                format!("{EXTRAS_COMMAND_PREFIX}set_window_title"),
-                window_title::handler,
+        let mut served = Vec::new();
+        let mut remote_plugin = add_methods(RemotePlugin::default(), CORE_METHODS, &mut served);
+        remote_plugin = add_watching_methods(remote_plugin, WATCHING_METHODS, &mut served);
+        let mut capabilities = Vec::with_capacity(Capability::ALL.len());
+        for capability in Capability::ALL {
+            let enabled = self.is_enabled(capability);
+            let mut methods = Vec::new();
+            if enabled {
+                let section = build_section(capability, app);
+                remote_plugin = add_methods(remote_plugin, section, &mut methods);
+            }
+            served.extend(methods.iter().cloned());
+            capabilities.push(CapabilityStatus {
+                name: capability.name().to_string(),
+                compiled: capability.compiled(),
+                enabled,
+                methods,
+            });
+        }
+        served.sort();
+        app.insert_resource(ExtrasCapabilities {
+            capabilities,
+            methods: served.clone(),
+        });
+
+        #[cfg(feature = "compression")]
+        let compression = self.compression;
+        #[cfg(not(feature = "compression"))]
+        let compression = false;
+
+        let http_headers = parse_http_headers(&self.http_headers);
+        if compression {
+            app.add_plugins(remote_plugin);
+        } else {
+            let http_plugin = http_headers.iter().fold(
+                RemoteHttpPlugin::default().with_port(effective_port),
+                |plugin, (name, value)| plugin.with_header(name.clone(), value.clone()),
             );
+            app.add_plugins((remote_plugin, http_plugin));
+        }
+
+        // Serve HTTP with compression in place of `RemoteHttpPlugin` when configured
+        #[cfg(feature = "compression")]
+        if compression {
+            app.insert_resource(compressed_http::CompressedHttpPort(effective_port));
+            app.insert_resource(compressed_http::CompressedHttpHeaders(http_headers));
+            app.add_systems(Startup, compressed_http::start_compressed_http);
+        }
+
+        if let Some(build_info) = &self.build_info {
+            app.insert_resource(build_info.clone());
+        }
+
+        // Record where the app sits among launched processes for `app_info`
+        app.insert_resource(BrpTopology::from_env(effective_port));
+
+        // Register the game types the app asked for
+        for register in &self.auto_register {
+            register(app);
+        }
+
+        // Register the tag component so BRP queries can see it
+        app.register_type::<tag::BrpTag>();
+
+        // Register the seed resource so BRP can read the seed apps opt in with
+        app.register_type::<determinism::BrpRngSeed>();
+
+        // Register the pin component so BRP queries can see pinned entities
+        app.register_type::<pin::BrpPinned>();
+
+        // Add the system to remove expired entity pins
+        app.add_systems(Update, pin::expire_pins);
 
-        let http_plugin = RemoteHttpPlugin::default().with_port(effective_port);
+        // Add the system to record component changes of tracked entities
+        app.init_resource::<history::EntityHistory>();
+        app.add_systems(Last, history::record_entity_changes);
 
-        app.add_plugins((remote_plugin, http_plugin));
+        // Add the system to drop multiwatch streams whose client disconnected
+        app.init_resource::<multiwatch::MultiwatchStreams>();
+        app.add_systems(
+            RemoteLast,
+            multiwatch::prune_closed_streams.in_set(RemoteSystems::Cleanup),
+        );
+
+        // Add the system to drop query streams whose client disconnected
+        app.init_resource::<query_stream::QueryStreams>();
+        app.add_systems(Last, query_stream::prune_idle_streams);
+
+        // Add the system to record frame times for frame stats collections
+        app.add_systems(Update, frame_stats::record_frame_times);
+
+        // Add the system to compare entity archetypes while archetype moves are tracked
+        app.add_systems(Last, archetype_moves::record_archetype_moves);
 
-        // Add the system to process timed key releases
-        app.add_systems(Update, keyboard::process_timed_key_releases);
+        // Add the system to count each frame's changes for activity reports
+        app.init_resource::<activity::ActivityCounter>();
+        app.add_systems(Last, activity::count_activity);
 
         // Add the system to handle deferred shutdown
         app.add_systems(Update, shutdown::deferred_shutdown_system);
 
-        // Add the system to process pending screenshots (for frame delay feature)
-        app.add_systems(Update, screenshot::process_pending_screenshots);
+        // Serve the binary transport alongside HTTP when configured
+        #[cfg(feature = "binary-transport")]
+        if let Some(binary_port) = self.binary_port {
+            app.insert_resource(binary_transport::BinaryTransportPort(binary_port));
+            app.add_systems(Startup, binary_transport::start_binary_transport);
+        }
 
         app.add_systems(Startup, move |_world: &mut World| {
-            log_initialization(effective_port, &source_description);
+            log_initialization(effective_port, &source_description, &served);
         });
     }
+
+    fn finish(&self, app: &mut App) {
+        // Every plugin is built by now, so record which of Bevy's were added for `app_info`
+        let added_plugins = app_info::AddedPlugins::detect(app);
+        app.insert_resource(added_plugins);
+    }
+}
+
+/// Register `methods` with `remote_plugin`, adding their full names to `served`
+fn add_methods(
+    remote_plugin: RemotePlugin,
+    methods: &[ExtrasMethod],
+    served: &mut Vec<String>,
+) -> RemotePlugin {
+    methods
+        .iter()
+        .fold(remote_plugin, |remote_plugin, &(name, handler)| {
+            let method = format!("{EXTRAS_COMMAND_PREFIX}{name}");
+            served.push(method.clone());
+            remote_plugin.with_method(method, handler)
+        })
+}
+
+/// Register streaming `methods` with `remote_plugin`, adding their full names to `served`
+fn add_watching_methods(
+    remote_plugin: RemotePlugin,
+    methods: &[ExtrasWatchingMethod],
+    served: &mut Vec<String>,
+) -> RemotePlugin {
+    methods
+        .iter()
+        .fold(remote_plugin, |remote_plugin, &(name, handler)| {
+            let method = format!("{EXTRAS_COMMAND_PREFIX}{name}");
+            served.push(method.clone());
+            remote_plugin.with_watching_method(method, handler)
+        })
+}
+
+/// Add the resources and systems of the `capability` section, returning its methods - none when
+/// its cargo feature is disabled
+fn build_section(capability: Capability, app: &mut App) -> &'static [ExtrasMethod] {
+    match capability {
+        #[cfg(feature = "screenshot")]
+        Capability::Screenshot => {
+            // Process pending screenshots (for the frame delay feature)
+            app.init_resource::<screenshot::ScreenshotRequests>();
+            app.add_systems(Update, screenshot::process_pending_screenshots);
+            SCREENSHOT_METHODS
+        },
+        Capability::Keyboard => {
+            // Process timed key releases
+            app.add_systems(Update, keyboard::process_timed_key_releases);
+            KEYBOARD_METHODS
+        },
+        #[cfg(feature = "window")]
+        Capability::Window => WINDOW_METHODS,
+        #[allow(unreachable_patterns)]
+        _ => &[],
+    }
+}
+
+/// Headers from `with_http_header`, leaving out - with a warning - any that aren't valid HTTP
+fn parse_http_headers(headers: &[(String, String)]) -> Vec<(HeaderName, HeaderValue)> {
+    headers
+        .iter()
+        .filter_map(|(name, value)| {
+            if let (Ok(name), Ok(value)) =
+                (HeaderName::try_from(name), HeaderValue::try_from(value))
+            {
+                return Some((name, value));
+            }
+            warn!("Ignoring invalid BRP HTTP header `{name}: {value}`");
+            None
+        })
+        .collect()
 }
 
-fn log_initialization(port: u16, source_description: &str) {
+fn log_initialization(port: u16, source_description: &str, methods: &[String]) {
     info!("BRP extras enabled on http://localhost:{port} ({source_description})");
     trace!("Additional BRP methods available:");
-    trace!("  - brp_extras/screenshot - Take a screenshot");
-    trace!("  - brp_extras/shutdown - Shutdown the app");
-    trace!("  - brp_extras/send_keys - Send keyboard input");
-    trace!("  - brp_extras/set_window_title - Change the window title");
+    for method in methods {
+        trace!("  - {method}");
+    }
 }
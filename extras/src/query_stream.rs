@@ -0,0 +1,279 @@
+//! Streaming query handler for BRP extras
+//!
+//! `world.query` answers in one response, so a query over a world with a hundred thousand
+//! entities serializes every row in one frame and sends it as one body - stalling the app and
+//! exhausting memory on both ends. `brp_extras/query_stream+watch` matches the entities when the
+//! stream opens, then serializes and sends `chunk_size` of them each frame until the last chunk,
+//! marked `done`. Only the matched entity IDs are held between frames.
+//!
+//! Entities are matched by archetype - they have all of `components` and `with` and none of
+//! `without` - and their reflected components are read when their chunk is sent, so an entity
+//! despawned in between is skipped. Like multiwatch streams, a stream that hasn't been polled for
+//! `STREAM_IDLE_TIMEOUT` lost its client and is dropped.
+
+use std::collections::HashMap;
+use std::time::Duration;
+use std::time::Instant;
+
+use bevy::ecs::component::ComponentId;
+use bevy::prelude::*;
+use bevy::reflect::TypeRegistry;
+use bevy::remote::BrpError;
+use bevy::remote::BrpResult;
+use bevy::remote::error_codes::INVALID_PARAMS;
+use serde::Deserialize;
+use serde_json::Value;
+use serde_json::json;
+
+use crate::history;
+
+/// Entities sent per chunk when `chunk_size` is omitted
+const DEFAULT_CHUNK_SIZE: usize = 1_000;
+
+/// Most entities one chunk can hold
+const MAX_CHUNK_SIZE: usize = 10_000;
+
+/// How long a stream may go unpolled before it is dropped
+const STREAM_IDLE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Request structure for `query_stream+watch`
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct QueryStreamRequest {
+    /// Identifies the stream between frames - chosen by the client
+    pub stream_id:  String,
+    /// Components to return, which matched entities must have - all reflected components when
+    /// empty
+    #[serde(default)]
+    pub components: Vec<String>,
+    /// Components matched entities must also have, without returning them
+    #[serde(default)]
+    pub with:       Vec<String>,
+    /// Components matched entities must not have
+    #[serde(default)]
+    pub without:    Vec<String>,
+    /// Entities per chunk (default: 1000, max: 10000)
+    #[serde(default)]
+    pub chunk_size: Option<usize>,
+}
+
+/// One open stream's matched entities and how far it has got
+struct QueryStream {
+    entities:    Vec<Entity>,
+    sent:        usize,
+    chunks:      usize,
+    last_polled: Instant,
+}
+
+/// Resource holding the open query streams by stream ID
+#[derive(Resource, Default)]
+pub struct QueryStreams {
+    streams: HashMap<String, QueryStream>,
+}
+
+/// Handler for `query_stream+watch` requests
+///
+/// Matches the entities on the stream's first frame, then returns the next chunk each frame -
+/// nothing once the `done` chunk has been sent.
+pub fn handler(In(params): In<Option<Value>>, world: &mut World) -> BrpResult<Option<Value>> {
+    let request: QueryStreamRequest = params
+        .ok_or_else(|| BrpError {
+            code:    INVALID_PARAMS,
+            message: "Missing request parameters".to_string(),
+            data:    None,
+        })
+        .and_then(|params| {
+            serde_json::from_value(params).map_err(|e| BrpError {
+                code:    INVALID_PARAMS,
+                message: format!("Invalid request format: {e}"),
+                data:    None,
+            })
+        })?;
+    let chunk_size = request.chunk_size.unwrap_or(DEFAULT_CHUNK_SIZE);
+    if !(1..=MAX_CHUNK_SIZE).contains(&chunk_size) {
+        return Err(BrpError {
+            code:    INVALID_PARAMS,
+            message: format!("chunk_size must be between 1 and {MAX_CHUNK_SIZE}"),
+            data:    None,
+        });
+    }
+
+    if !world
+        .resource::<QueryStreams>()
+        .streams
+        .contains_key(&request.stream_id)
+    {
+        let entities = matching_entities(world, &request)?;
+        world.resource_mut::<QueryStreams>().streams.insert(
+            request.stream_id.clone(),
+            QueryStream {
+                entities,
+                sent: 0,
+                chunks: 0,
+                last_polled: Instant::now(),
+            },
+        );
+    }
+
+    let (chunk, range, total) = {
+        let mut streams = world.resource_mut::<QueryStreams>();
+        let Some(stream) = streams.streams.get_mut(&request.stream_id) else {
+            return Ok(None);
+        };
+        stream.last_polled = Instant::now();
+        let total = stream.entities.len();
+        // The `done` chunk is sent even for an empty match, so the client knows the query ended
+        if stream.sent == total && stream.chunks > 0 {
+            return Ok(None);
+        }
+        let range = stream.sent..total.min(stream.sent + chunk_size);
+        stream.sent = range.end;
+        stream.chunks += 1;
+        (stream.chunks - 1, range, total)
+    };
+
+    let components = (!request.components.is_empty()).then_some(request.components.as_slice());
+    let registry = world.resource::<AppTypeRegistry>().read();
+    let streams = world.resource::<QueryStreams>();
+    let Some(stream) = streams.streams.get(&request.stream_id) else {
+        return Ok(None);
+    };
+    let rows: Vec<Value> = stream.entities[range.clone()]
+        .iter()
+        .filter_map(|&entity| {
+            history::snapshot(world, &registry, entity, components).map(|snapshot| {
+                json!({
+                    "entity": entity.to_bits(),
+                    "components": snapshot,
+                })
+            })
+        })
+        .collect();
+
+    Ok(Some(json!({
+        "stream_id": request.stream_id,
+        "chunk": chunk,
+        "entities": rows,
+        "sent": range.end,
+        "total": total,
+        "done": range.end == total,
+    })))
+}
+
+/// System dropping query streams that haven't been polled for `STREAM_IDLE_TIMEOUT`
+pub fn prune_idle_streams(mut streams: ResMut<QueryStreams>) {
+    if streams.streams.is_empty() {
+        return;
+    }
+    let now = Instant::now();
+    streams
+        .streams
+        .retain(|_, stream| now.duration_since(stream.last_polled) <= STREAM_IDLE_TIMEOUT);
+}
+
+/// Entities in archetypes with every required component and none of `without`
+fn matching_entities(world: &World, request: &QueryStreamRequest) -> BrpResult<Vec<Entity>> {
+    let registry = world.resource::<AppTypeRegistry>().read();
+    let required: Vec<Option<ComponentId>> = request
+        .components
+        .iter()
+        .chain(&request.with)
+        .map(|type_path| component_id(world, &registry, type_path))
+        .collect::<BrpResult<_>>()?;
+    // A component the world has never stored is on no entity, so nothing can match
+    let Some(required) = required.into_iter().collect::<Option<Vec<ComponentId>>>() else {
+        return Ok(Vec::new());
+    };
+    let excluded: Vec<ComponentId> = request
+        .without
+        .iter()
+        .map(|type_path| component_id(world, &registry, type_path))
+        .collect::<BrpResult<Vec<_>>>()?
+        .into_iter()
+        .flatten()
+        .collect();
+
+    Ok(world
+        .archetypes()
+        .iter()
+        .filter(|archetype| {
+            required.iter().all(|&id| archetype.contains(id))
+                && !excluded.iter().any(|&id| archetype.contains(id))
+        })
+        .flat_map(|archetype| {
+            archetype
+                .entities()
+                .iter()
+                .map(|archetype_entity| archetype_entity.id())
+        })
+        .collect())
+}
+
+/// The component ID of a registered type - `None` when the world has never stored it
+fn component_id(
+    world: &World,
+    registry: &TypeRegistry,
+    type_path: &str,
+) -> BrpResult<Option<ComponentId>> {
+    let registration = registry
+        .get_with_type_path(type_path)
+        .ok_or_else(|| BrpError {
+            code:    INVALID_PARAMS,
+            message: format!("Unknown component type: {type_path}"),
+            data:    None,
+        })?;
+    Ok(world.components().get_id(registration.type_id()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Component, Reflect)]
+    #[reflect(Component)]
+    struct Score(u32);
+
+    #[derive(Component, Reflect)]
+    #[reflect(Component)]
+    struct Hidden;
+
+    fn poll(world: &mut World, params: &Value) -> Option<Value> {
+        world
+            .run_system_cached_with(handler, Some(params.clone()))
+            .unwrap_or_else(|error| unreachable!("query stream handler should run: {error}"))
+            .ok()
+            .flatten()
+    }
+
+    #[test]
+    fn matches_are_sent_in_chunks_until_done() {
+        let mut world = World::new();
+        world.init_resource::<AppTypeRegistry>();
+        world.init_resource::<QueryStreams>();
+        {
+            let mut registry = world.resource::<AppTypeRegistry>().write();
+            registry.register::<Score>();
+            registry.register::<Hidden>();
+        }
+        for score in 0..5 {
+            world.spawn(Score(score));
+        }
+        world.spawn((Score(99), Hidden));
+
+        let params = json!({
+            "stream_id": "mcp",
+            "components": [Score::type_path()],
+            "without": [Hidden::type_path()],
+            "chunk_size": 2,
+        });
+        let chunks: Vec<Value> = std::iter::from_fn(|| poll(&mut world, &params)).collect();
+
+        let sizes: Vec<usize> = chunks
+            .iter()
+            .map(|chunk| chunk["entities"].as_array().map_or(0, Vec::len))
+            .collect();
+        assert_eq!(sizes, vec![2, 2, 1]);
+        assert_eq!(chunks[2]["done"], json!(true));
+        assert_eq!(chunks[2]["total"], json!(5));
+    }
+}
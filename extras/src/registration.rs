@@ -0,0 +1,246 @@
+//! Type registration check handler for BRP extras
+//!
+//! BRP only sees types registered for reflection, and a type missing from the registry fails
+//! with the same unhelpful "unknown type" error whatever the cause. `brp_extras/ensure_registered`
+//! takes type paths and says, for each, whether it is registered, what reflection data it has,
+//! and - when it can't be used through BRP - why and how to fix it.
+
+use bevy::prelude::*;
+use bevy::reflect::ReflectDeserialize;
+use bevy::reflect::ReflectSerialize;
+use bevy::reflect::TypeRegistration;
+use bevy::reflect::TypeRegistry;
+use bevy::remote::BrpError;
+use bevy::remote::BrpResult;
+use bevy::remote::error_codes::INVALID_PARAMS;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+use serde_json::json;
+
+/// Registered type paths suggested for a missing type at most
+const MAX_SUGGESTIONS: usize = 5;
+
+/// Request structure for `ensure_registered`
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EnsureRegisteredRequest {
+    /// Full type paths to check, e.g. `my_game::Health`
+    pub types: Vec<String>,
+}
+
+/// Registration of a type, as reported by `brp_extras/ensure_registered`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TypeRegistrationReport {
+    /// The requested type path
+    pub type_path:    String,
+    /// Whether a type with this path is registered
+    pub registered:   bool,
+    /// Reflection data the type is registered with - `Component`, `Resource`, `Default`,
+    /// `Serialize` and `Deserialize`
+    pub capabilities: Vec<String>,
+    /// Why BRP can't use the type, each with how to fix it - empty when it can
+    pub problems:     Vec<String>,
+    /// Registered type paths that look like the requested one
+    pub did_you_mean: Vec<String>,
+}
+
+/// Handler for `ensure_registered` requests
+pub fn handler(In(params): In<Option<Value>>, world: &mut World) -> BrpResult {
+    let request: EnsureRegisteredRequest = params
+        .ok_or_else(|| BrpError {
+            code:    INVALID_PARAMS,
+            message: "Missing request parameters".to_string(),
+            data:    None,
+        })
+        .and_then(|params| {
+            serde_json::from_value(params).map_err(|e| BrpError {
+                code:    INVALID_PARAMS,
+                message: format!("Invalid request format: {e}"),
+                data:    None,
+            })
+        })?;
+
+    let registry = world.resource::<AppTypeRegistry>().read();
+    let reports: Vec<TypeRegistrationReport> = request
+        .types
+        .into_iter()
+        .map(|type_path| match registry.get_with_type_path(&type_path) {
+            Some(registration) => registered_report(type_path, registration),
+            None => missing_report(world, &registry, type_path),
+        })
+        .collect();
+    let missing = reports
+        .iter()
+        .filter(|report| !report.problems.is_empty())
+        .count();
+
+    Ok(json!({
+        "types": reports,
+        "missing": missing,
+    }))
+}
+
+/// Report for a type the registry has
+fn registered_report(type_path: String, registration: &TypeRegistration) -> TypeRegistrationReport {
+    let capabilities: Vec<String> = [
+        (
+            "Component",
+            registration.data::<ReflectComponent>().is_some(),
+        ),
+        ("Resource", registration.data::<ReflectResource>().is_some()),
+        ("Default", registration.data::<ReflectDefault>().is_some()),
+        (
+            "Serialize",
+            registration.data::<ReflectSerialize>().is_some(),
+        ),
+        (
+            "Deserialize",
+            registration.data::<ReflectDeserialize>().is_some(),
+        ),
+    ]
+    .into_iter()
+    .filter(|(_, present)| *present)
+    .map(|(name, _)| name.to_string())
+    .collect();
+
+    let mut problems = Vec::new();
+    if !capabilities
+        .iter()
+        .any(|name| name == "Component" || name == "Resource")
+    {
+        problems.push(format!(
+            "`{type_path}` is registered without `#[reflect(Component)]` or \
+             `#[reflect(Resource)]`, so BRP can't query, insert or mutate it - add the attribute \
+             next to `#[derive(Reflect)]`"
+        ));
+    }
+
+    TypeRegistrationReport {
+        type_path,
+        registered: true,
+        capabilities,
+        problems,
+        did_you_mean: Vec::new(),
+    }
+}
+
+/// Report for a type the registry doesn't have, with the likely reason
+fn missing_report(
+    world: &World,
+    registry: &TypeRegistry,
+    type_path: String,
+) -> TypeRegistrationReport {
+    let in_world = world
+        .components()
+        .iter_registered()
+        .any(|info| info.name().to_string() == type_path);
+    let did_you_mean = similar_type_paths(registry, &type_path);
+
+    let problem = if in_world {
+        format!(
+            "`{type_path}` is used as a component or resource but isn't registered for reflection \
+             - derive `Reflect`, add `#[reflect(Component)]` or `#[reflect(Resource)]`, and \
+             register it with `app.register_type::<T>()` or \
+             `BrpExtrasPlugin::default().with_auto_register::<T>()`"
+        )
+    } else if !did_you_mean.is_empty() {
+        format!("No type is registered as `{type_path}` - check the path against `did_you_mean`")
+    } else if type_path.contains('<') {
+        format!(
+            "No type is registered as `{type_path}` - generic types are registered per set of \
+             type arguments, so register this exact instantiation with \
+             `app.register_type::<T>()`"
+        )
+    } else {
+        format!(
+            "No type is registered as `{type_path}`, and no component or resource of that name \
+             exists yet - check the full path, or derive `Reflect` and register the type with \
+             `app.register_type::<T>()` or `BrpExtrasPlugin::default().with_auto_register::<T>()`"
+        )
+    };
+
+    TypeRegistrationReport {
+        type_path,
+        registered: false,
+        capabilities: Vec::new(),
+        problems: vec![problem],
+        did_you_mean,
+    }
+}
+
+/// Registered type paths with the same short name as `type_path`, ignoring case
+fn similar_type_paths(registry: &TypeRegistry, type_path: &str) -> Vec<String> {
+    let short_name = type_path
+        .rsplit("::")
+        .next()
+        .unwrap_or(type_path)
+        .to_lowercase();
+    let mut similar: Vec<String> = registry
+        .iter()
+        .filter(|registration| {
+            registration
+                .type_info()
+                .type_path_table()
+                .short_path()
+                .to_lowercase()
+                == short_name
+        })
+        .map(|registration| registration.type_info().type_path().to_string())
+        .collect();
+    similar.sort();
+    similar.truncate(MAX_SUGGESTIONS);
+    similar
+}
+
+#[cfg(test)]
+#[allow(clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[derive(Component, Reflect)]
+    #[reflect(Component)]
+    struct Health(u32);
+
+    #[derive(Component, Reflect)]
+    struct Mana(u32);
+
+    #[derive(Component)]
+    struct Stamina;
+
+    #[test]
+    fn explains_why_types_are_unusable() {
+        let mut world = World::new();
+        let registry = AppTypeRegistry::default();
+        registry.write().register::<Health>();
+        registry.write().register::<Mana>();
+        world.insert_resource(registry);
+        world.spawn(Stamina);
+
+        let health = std::any::type_name::<Health>();
+        let mana = std::any::type_name::<Mana>();
+        let stamina = std::any::type_name::<Stamina>();
+        let response = handler(
+            In(Some(
+                json!({ "types": [health, mana, stamina, "other_crate::Health"] }),
+            )),
+            &mut world,
+        )
+        .expect("registration report");
+
+        let types = &response["types"];
+        assert_eq!(types[0]["problems"], json!([]));
+        assert_eq!(types[0]["capabilities"], json!(["Component"]));
+        assert_eq!(types[1]["registered"], true);
+        assert_eq!(types[1]["problems"].as_array().map(Vec::len), Some(1));
+        assert_eq!(types[2]["registered"], false);
+        assert!(
+            types[2]["problems"][0]
+                .as_str()
+                .expect("problem")
+                .contains("isn't registered for reflection")
+        );
+        assert_eq!(types[3]["did_you_mean"], json!([health]));
+        assert_eq!(response["missing"], 3);
+    }
+}
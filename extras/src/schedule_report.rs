@@ -0,0 +1,227 @@
+//! Schedule report handler for BRP extras
+//!
+//! Bevy computes system ambiguities - pairs of systems with conflicting data access and no
+//! ordering between them - whenever it builds a schedule, but only logs them at startup when
+//! ambiguity detection is turned on. This handler reads them back from each built schedule's
+//! graph, along with the order the executor runs its systems in.
+//!
+//! The schedule handling the request is removed from `Schedules` while it runs, so it can't
+//! appear in its own report.
+
+use bevy::ecs::schedule::Schedules;
+use bevy::prelude::*;
+use bevy::remote::BrpError;
+use bevy::remote::BrpResult;
+use bevy::remote::error_codes::INVALID_PARAMS;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+use serde_json::json;
+
+/// Request structure for `schedule_report`
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ScheduleReportRequest {
+    /// Only report this schedule, by label (e.g. `Update`) - `None` reports every schedule
+    #[serde(default)]
+    pub schedule:      Option<String>,
+    /// Include ambiguities between two systems from `bevy` or `bevy_*` crates
+    #[serde(default)]
+    pub include_bevy:  bool,
+    /// Include each schedule's systems in the order the executor runs them
+    #[serde(default)]
+    pub include_order: bool,
+}
+
+/// Ambiguities and ordering of one schedule, as reported by `brp_extras/schedule_report`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduleReport {
+    /// Schedule label
+    pub schedule:     String,
+    /// Number of systems in the schedule
+    pub system_count: usize,
+    /// Unordered system pairs with conflicting data access
+    pub ambiguities:  Vec<SystemAmbiguity>,
+    /// Systems in execution order, when requested
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order:        Option<Vec<String>>,
+}
+
+/// Two systems with conflicting data access and no ordering between them
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SystemAmbiguity {
+    /// First system
+    pub system_a:  String,
+    /// Second system
+    pub system_b:  String,
+    /// Components and resources both systems access, at least one mutably - empty when the
+    /// conflict is on the whole world, e.g. an exclusive system
+    pub conflicts: Vec<String>,
+}
+
+/// Handler for `schedule_report` requests
+///
+/// Returns one report per built schedule, sorted by schedule label.
+pub fn handler(In(params): In<Option<Value>>, world: &mut World) -> BrpResult {
+    let request: ScheduleReportRequest = params
+        .map(serde_json::from_value)
+        .transpose()
+        .map_err(|e| BrpError {
+            code:    INVALID_PARAMS,
+            message: format!("Invalid request format: {e}"),
+            data:    None,
+        })?
+        .unwrap_or_default();
+
+    let Some(schedules) = world.get_resource::<Schedules>() else {
+        return Ok(json!({ "schedules": [], "ambiguity_count": 0 }));
+    };
+    let components = world.components();
+
+    let mut reports = Vec::new();
+    let mut available = Vec::new();
+    for (label, schedule) in schedules.iter() {
+        let name = format!("{label:?}");
+        available.push(name.clone());
+        if request
+            .schedule
+            .as_ref()
+            .is_some_and(|wanted| *wanted != name)
+        {
+            continue;
+        }
+
+        let graph = schedule.graph();
+        let system_name = |key| {
+            graph.systems.get(key).map_or_else(
+                || "<unknown>".to_string(),
+                |system| system.system.name().to_string(),
+            )
+        };
+
+        let ambiguities: Vec<SystemAmbiguity> = graph
+            .conflicting_systems()
+            .iter()
+            .map(|(a, b, conflicts)| SystemAmbiguity {
+                system_a:  system_name(*a),
+                system_b:  system_name(*b),
+                conflicts: conflicts
+                    .iter()
+                    .map(|id| {
+                        components
+                            .get_info(*id)
+                            .map_or_else(|| format!("{id:?}"), |info| info.name().to_string())
+                    })
+                    .collect(),
+            })
+            .filter(|ambiguity| {
+                request.include_bevy
+                    || !(is_bevy_system(&ambiguity.system_a) && is_bevy_system(&ambiguity.system_b))
+            })
+            .collect();
+
+        let order = request.include_order.then(|| {
+            schedule
+                .systems()
+                .map(|systems| {
+                    systems
+                        .map(|(_, system)| system.name().to_string())
+                        .collect()
+                })
+                .unwrap_or_default()
+        });
+
+        reports.push(ScheduleReport {
+            schedule: name,
+            system_count: schedule.systems_len(),
+            ambiguities,
+            order,
+        });
+    }
+
+    if let Some(wanted) = &request.schedule
+        && reports.is_empty()
+    {
+        available.sort();
+        return Err(BrpError {
+            code:    INVALID_PARAMS,
+            message: format!(
+                "Unknown schedule '{wanted}' - available schedules: {}",
+                available.join(", ")
+            ),
+            data:    None,
+        });
+    }
+    reports.sort_by(|a, b| a.schedule.cmp(&b.schedule));
+
+    let ambiguity_count: usize = reports.iter().map(|report| report.ambiguities.len()).sum();
+    Ok(json!({
+        "schedules": reports,
+        "ambiguity_count": ambiguity_count,
+    }))
+}
+
+/// Whether a system comes from `bevy` or a `bevy_*` crate other than this one
+fn is_bevy_system(name: &str) -> bool {
+    let crate_name = name.split("::").next().unwrap_or_default();
+    crate_name == "bevy"
+        || (crate_name.starts_with("bevy_") && crate_name != env!("CARGO_CRATE_NAME"))
+}
+
+#[cfg(test)]
+#[allow(clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[derive(Resource, Default)]
+    struct Score(u32);
+
+    fn add_one(mut score: ResMut<Score>) { score.0 += 1; }
+
+    fn double(mut score: ResMut<Score>) { score.0 *= 2; }
+
+    fn ordered_world(ordered: bool) -> World {
+        let mut world = World::new();
+        world.init_resource::<Score>();
+        let mut schedule = Schedule::new(Update);
+        if ordered {
+            schedule.add_systems((add_one, double).chain());
+        } else {
+            schedule.add_systems((add_one, double));
+        }
+        schedule
+            .initialize(&mut world)
+            .expect("schedule should build");
+        world.add_schedule(schedule);
+        world
+    }
+
+    #[test]
+    fn reports_unordered_conflicting_systems() {
+        let mut world = ordered_world(false);
+
+        let result = handler(In(None), &mut world).expect("report");
+
+        assert_eq!(result["ambiguity_count"], 1);
+        let ambiguity = &result["schedules"][0]["ambiguities"][0];
+        let conflicts = ambiguity["conflicts"].to_string();
+        assert!(conflicts.contains("Score"), "{conflicts}");
+    }
+
+    #[test]
+    fn chained_systems_are_not_ambiguous_and_report_their_order() {
+        let mut world = ordered_world(true);
+
+        let result = handler(
+            In(Some(json!({"schedule": "Update", "include_order": true}))),
+            &mut world,
+        )
+        .expect("report");
+
+        assert_eq!(result["ambiguity_count"], 0);
+        let order = result["schedules"][0]["order"].to_string();
+        let add = order.find("add_one").expect("add_one in order");
+        let double = order.find("double").expect("double in order");
+        assert!(add < double, "{order}");
+    }
+}
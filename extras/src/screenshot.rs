@@ -2,16 +2,40 @@
 //!
 //! This module provides screenshot functionality via the Bevy Remote Protocol.
 //! It addresses common timing issues by supporting frame delays before capture.
+//!
+//! Given a `width` and `height`, the active camera is rendered to an offscreen texture of that
+//! size instead of capturing the window, so captures have the same resolution on every display
+//! and work in headless runs.
+//!
+//! Each request gets a `request_id`. Saving happens asynchronously after the response, so
+//! `brp_extras/screenshot_status` reports whether the file has been written yet, and its size
+//! and dimensions once it has.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::sync::Mutex;
 
+use bevy::camera::Exposure;
+use bevy::camera::RenderTarget;
+use bevy::camera::visibility::RenderLayers;
+use bevy::core_pipeline::tonemapping::DebandDither;
+use bevy::core_pipeline::tonemapping::Tonemapping;
+use bevy::image::BevyDefault;
+use bevy::post_process::bloom::Bloom;
 use bevy::prelude::*;
 use bevy::remote;
 use bevy::remote::BrpError;
 use bevy::remote::BrpResult;
 use bevy::remote::error_codes::INTERNAL_ERROR;
 use bevy::remote::error_codes::INVALID_PARAMS;
+use bevy::render::render_resource::TextureFormat;
+use bevy::render::view::ColorGrading;
+use bevy::render::view::Hdr;
+use bevy::render::view::Msaa;
 use bevy::render::view::screenshot::Screenshot;
 use bevy::render::view::screenshot::ScreenshotCaptured;
 use bevy::tasks::IoTaskPool;
+use serde::Serialize;
 use serde_json::Value;
 use serde_json::json;
 
@@ -19,13 +43,121 @@ use serde_json::json;
 /// This ensures the scene has rendered at least once to avoid white/blank screenshots.
 const DEFAULT_DELAY_FRAMES: u32 = 2;
 
+/// Largest width or height of an offscreen capture
+const MAX_OFFSCREEN_SIZE: u32 = 16_384;
+
+/// Most recent screenshot requests whose status is kept - older ones are forgotten
+const MAX_TRACKED_REQUESTS: usize = 100;
+
+/// Progress of a screenshot request
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum SaveStatus {
+    /// Waiting for the frame delay to pass, the frame to be captured or the file to be written
+    Pending,
+    /// The file has been written
+    Saved {
+        /// Size of the file in bytes
+        file_size: u64,
+        /// Width of the image in pixels
+        width:     u32,
+        /// Height of the image in pixels
+        height:    u32,
+    },
+    /// The capture couldn't be saved
+    Failed {
+        /// Why saving failed
+        error: String,
+    },
+}
+
+/// Path and status of each tracked request, by request ID
+#[derive(Default)]
+struct RequestLog {
+    next_id:  u64,
+    requests: BTreeMap<u64, (String, SaveStatus)>,
+}
+
+/// Status of recent screenshot requests, shared with the tasks that save them
+#[derive(Resource, Clone, Default)]
+pub struct ScreenshotRequests(Arc<Mutex<RequestLog>>);
+
+impl ScreenshotRequests {
+    /// Track a new request saving to `path` - returns its request ID
+    fn start(&self, path: &str) -> u64 {
+        let Ok(mut log) = self.0.lock() else {
+            return 0;
+        };
+        log.next_id += 1;
+        let request_id = log.next_id;
+        log.requests
+            .insert(request_id, (path.to_string(), SaveStatus::Pending));
+        while log.requests.len() > MAX_TRACKED_REQUESTS {
+            log.requests.pop_first();
+        }
+        request_id
+    }
+
+    /// Record how saving a request ended
+    fn finish(&self, request_id: u64, status: SaveStatus) {
+        if let Ok(mut log) = self.0.lock()
+            && let Some((_, current)) = log.requests.get_mut(&request_id)
+        {
+            *current = status;
+        }
+    }
+
+    /// Path and status of a tracked request
+    fn status(&self, request_id: u64) -> Option<(String, SaveStatus)> {
+        self.0.lock().ok()?.requests.get(&request_id).cloned()
+    }
+}
+
+/// What a screenshot captures
+#[derive(Clone)]
+pub enum CaptureTarget {
+    /// The primary window at its current size
+    PrimaryWindow,
+    /// An offscreen copy of the active camera rendering into `image`
+    Offscreen {
+        /// Texture the camera renders into
+        image:  Handle<Image>,
+        /// The offscreen camera, despawned once the capture is saved
+        camera: Entity,
+    },
+}
+
+impl CaptureTarget {
+    fn screenshot(&self) -> Screenshot {
+        match self {
+            Self::PrimaryWindow => Screenshot::primary_window(),
+            Self::Offscreen { image, .. } => Screenshot::image(image.clone()),
+        }
+    }
+
+    const fn camera(&self) -> Option<Entity> {
+        match self {
+            Self::PrimaryWindow => None,
+            Self::Offscreen { camera, .. } => Some(*camera),
+        }
+    }
+}
+
+/// Marks a camera spawned to render an offscreen screenshot
+#[derive(Component)]
+pub struct OffscreenScreenshotCamera;
+
 /// Component for pending screenshots that need to wait for frame delay
 #[derive(Component)]
 pub struct PendingScreenshot {
     /// Path to save the screenshot
-    pub path: String,
+    pub path:             String,
     /// Remaining frames to wait before capture
     pub frames_remaining: u32,
+    /// What to capture once the delay is over
+    pub target:           CaptureTarget,
+    /// ID of the request, for reporting its status
+    pub request_id:       u64,
 }
 
 /// System that processes pending screenshots, counting down frames and triggering capture
@@ -41,10 +173,14 @@ pub fn process_pending_screenshots(
 
             // Remove the pending component and add the actual Screenshot component
             commands.entity(entity).remove::<PendingScreenshot>();
-            commands.entity(entity).insert(Screenshot::primary_window());
+            commands.entity(entity).insert(pending.target.screenshot());
 
             // Add observer for when capture completes
-            commands.entity(entity).observe(create_save_observer(path));
+            commands.entity(entity).observe(create_save_observer(
+                path,
+                pending.target.camera(),
+                pending.request_id,
+            ));
         } else {
             pending.frames_remaining -= 1;
             trace!(
@@ -55,58 +191,191 @@ pub fn process_pending_screenshots(
     }
 }
 
-/// Creates an observer that saves the screenshot when captured
-fn create_save_observer(path: String) -> impl FnMut(On<ScreenshotCaptured>) {
-    move |screenshot_captured: On<ScreenshotCaptured>| {
+/// Creates an observer that saves the screenshot when captured, despawning the offscreen camera
+/// that rendered it if there is one, and records the outcome under `request_id`
+fn create_save_observer(
+    path: String,
+    offscreen_camera: Option<Entity>,
+    request_id: u64,
+) -> impl FnMut(On<ScreenshotCaptured>, Commands, Res<ScreenshotRequests>) {
+    move |screenshot_captured: On<ScreenshotCaptured>,
+          mut commands: Commands,
+          requests: Res<ScreenshotRequests>| {
         info!("Screenshot captured! Starting async save to: {}", path);
+        if let Some(camera) = offscreen_camera {
+            commands.entity(camera).try_despawn();
+        }
         let img = screenshot_captured.event().image.clone();
         let path_clone = path.clone();
+        let requests = requests.clone();
 
         // Move file I/O to background thread to avoid blocking main thread
         IoTaskPool::get()
             .spawn(async move {
-                match img.try_into_dynamic() {
-                    Ok(dyn_img) => {
-                        // Create parent directory if needed
-                        if let Some(parent) = std::path::Path::new(&path_clone).parent()
-                            && let Err(e) = std::fs::create_dir_all(parent)
-                        {
-                            error!(
-                                "Failed to create directory for screenshot {}: {}",
-                                path_clone, e
-                            );
-                            return;
-                        }
-
-                        // Convert to RGB8 to discard alpha channel which stores brightness
-                        // values when HDR is enabled - this matches Bevy's save_to_disk behavior
-                        let rgb_img = dyn_img.to_rgb8();
-
-                        // Save the image
-                        match rgb_img.save(&path_clone) {
-                            Ok(()) => {
-                                info!("Screenshot successfully saved to: {}", path_clone);
-                            }
-                            Err(e) => {
-                                error!("Failed to save screenshot to {}: {}", path_clone, e);
-                            }
+                let status = match save_image(img, &path_clone) {
+                    Ok(size) => {
+                        info!("Screenshot successfully saved to: {}", path_clone);
+                        SaveStatus::Saved {
+                            file_size: std::fs::metadata(&path_clone).map_or(0, |m| m.len()),
+                            width:     size.x,
+                            height:    size.y,
                         }
-                    }
-                    Err(e) => error!("Failed to convert screenshot to dynamic image: {}", e),
-                }
+                    },
+                    Err(e) => {
+                        error!("{}", e);
+                        SaveStatus::Failed { error: e }
+                    },
+                };
+                requests.finish(request_id, status);
             })
             .detach();
     }
 }
 
+/// Save a captured image to `path` as RGB8 - returns its dimensions
+fn save_image(img: Image, path: &str) -> Result<UVec2, String> {
+    let dyn_img = img
+        .try_into_dynamic()
+        .map_err(|e| format!("Failed to convert screenshot to dynamic image: {e}"))?;
+
+    // Create parent directory if needed
+    if let Some(parent) = std::path::Path::new(path).parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create directory for screenshot {path}: {e}"))?;
+    }
+
+    // Convert to RGB8 to discard alpha channel which stores brightness
+    // values when HDR is enabled - this matches Bevy's save_to_disk behavior
+    let rgb_img = dyn_img.to_rgb8();
+
+    // Save the image
+    rgb_img
+        .save(path)
+        .map_err(|e| format!("Failed to save screenshot to {path}: {e}"))?;
+    Ok(UVec2::new(rgb_img.width(), rgb_img.height()))
+}
+
+/// Read the optional `width` and `height` of an offscreen capture - both or neither
+fn parse_resolution(params: Option<&Value>) -> Result<Option<UVec2>, BrpError> {
+    let dimension = |name: &str| -> Result<Option<u32>, BrpError> {
+        let Some(value) = params.and_then(|v| v.get(name)) else {
+            return Ok(None);
+        };
+        value
+            .as_u64()
+            .and_then(|v| u32::try_from(v).ok())
+            .filter(|v| (1..=MAX_OFFSCREEN_SIZE).contains(v))
+            .map(Some)
+            .ok_or_else(|| BrpError {
+                code:    INVALID_PARAMS,
+                message: format!("'{name}' must be a whole number from 1 to {MAX_OFFSCREEN_SIZE}"),
+                data:    None,
+            })
+    };
+
+    match (dimension("width")?, dimension("height")?) {
+        (Some(width), Some(height)) => Ok(Some(UVec2::new(width, height))),
+        (None, None) => Ok(None),
+        _ => Err(BrpError {
+            code:    INVALID_PARAMS,
+            message: "Pass both 'width' and 'height' for an offscreen capture, or neither"
+                .to_string(),
+            data:    None,
+        }),
+    }
+}
+
+/// Spawn a copy of the active camera that renders into a `size` texture - it follows the source
+/// camera as its child, so the capture shows what the window shows
+fn spawn_offscreen_camera(world: &mut World, size: UVec2) -> Result<CaptureTarget, BrpError> {
+    let (source, order, clear_color) = world
+        .query::<(Entity, &Camera)>()
+        .iter(world)
+        .filter(|(_, camera)| camera.is_active)
+        .max_by_key(|(_, camera)| camera.order)
+        .map(|(entity, camera)| (entity, camera.order, camera.clear_color))
+        .ok_or_else(|| BrpError {
+            code:    INTERNAL_ERROR,
+            message: "No active camera found - cannot render an offscreen screenshot".to_string(),
+            data:    None,
+        })?;
+
+    let image = Image::new_target_texture(size.x, size.y, TextureFormat::bevy_default());
+    let image = world.resource_mut::<Assets<Image>>().add(image);
+
+    let camera = world
+        .spawn((
+            Camera {
+                target: RenderTarget::Image(image.clone().into()),
+                order,
+                clear_color,
+                ..default()
+            },
+            Transform::default(),
+            OffscreenScreenshotCamera,
+            ChildOf(source),
+            Name::new("OffscreenScreenshotCamera"),
+        ))
+        .id();
+
+    // The camera kind goes first - its required components are then replaced by the source's
+    if world.get::<Camera3d>(source).is_some() {
+        copy_component::<Camera3d>(world, source, camera);
+    } else {
+        copy_component::<Camera2d>(world, source, camera);
+    }
+    // What the source sees and how it post-processes it, so the capture matches the window
+    copy_component::<Projection>(world, source, camera);
+    copy_component::<RenderLayers>(world, source, camera);
+    copy_component::<Msaa>(world, source, camera);
+    copy_component::<Hdr>(world, source, camera);
+    copy_component::<Tonemapping>(world, source, camera);
+    copy_component::<DebandDither>(world, source, camera);
+    copy_component::<Exposure>(world, source, camera);
+    copy_component::<ColorGrading>(world, source, camera);
+    copy_component::<Bloom>(world, source, camera);
+
+    Ok(CaptureTarget::Offscreen { image, camera })
+}
+
+/// Give `target` a copy of `source`'s `T`, if it has one
+fn copy_component<T: Component + Clone>(world: &mut World, source: Entity, target: Entity) {
+    if let Some(component) = world.get::<T>(source).cloned() {
+        world.entity_mut(target).insert(component);
+    }
+}
+
+/// Fail unless there is a window to capture
+fn ensure_window_exists(world: &mut World) -> Result<(), BrpError> {
+    let window_exists = world.query::<&Window>().iter(world).any(|w| {
+        info!(
+            "Found window - resolution: {:?}, visible: {:?}",
+            w.resolution, w.visible
+        );
+        true
+    });
+
+    if window_exists {
+        Ok(())
+    } else {
+        Err(BrpError {
+            code:    INTERNAL_ERROR,
+            message: "No windows found - cannot take screenshot".to_string(),
+            data:    None,
+        })
+    }
+}
+
 /// Handler for screenshot requests
 ///
 /// Takes a screenshot of the primary window and saves it to the specified path.
 ///
 /// # Parameters
 /// - `path` (required): The file path to save the screenshot
-/// - `delay_frames` (optional): Number of frames to wait before capturing (default: 2)
-///   This helps avoid white/blank screenshots by ensuring the scene has rendered.
+/// - `delay_frames` (optional): Number of frames to wait before capturing (default: 2) This helps
+///   avoid white/blank screenshots by ensuring the scene has rendered.
+/// - `width`, `height` (optional): Render the active camera offscreen at this resolution instead of
+///   capturing the window - works without a window, e.g. in headless CI
 ///
 /// # Notes
 /// - File I/O is performed asynchronously to avoid blocking the main thread
@@ -141,6 +410,8 @@ pub fn handler(In(params): In<Option<Value>>, world: &mut World) -> BrpResult {
         .and_then(|v| v.as_u64())
         .map_or(DEFAULT_DELAY_FRAMES, |v| v as u32);
 
+    let resolution = parse_resolution(params.as_ref())?;
+
     // Convert to absolute path
     let path_buf = std::path::Path::new(path);
     let absolute_path = if path_buf.is_absolute() {
@@ -163,22 +434,17 @@ pub fn handler(In(params): In<Option<Value>>, world: &mut World) -> BrpResult {
         absolute_path_str, delay_frames
     );
 
-    // Check if we have a primary window
-    let window_exists = world.query::<&Window>().iter(world).any(|w| {
-        info!(
-            "Found window - resolution: {:?}, visible: {:?}",
-            w.resolution, w.visible
-        );
-        true
-    });
+    let (target, delay_frames) = if let Some(size) = resolution {
+        // The offscreen camera has to render a frame before there is anything to capture
+        (spawn_offscreen_camera(world, size)?, delay_frames.max(1))
+    } else {
+        ensure_window_exists(world)?;
+        (CaptureTarget::PrimaryWindow, delay_frames)
+    };
 
-    if !window_exists {
-        return Err(BrpError {
-            code:    INTERNAL_ERROR,
-            message: "No windows found - cannot take screenshot".to_string(),
-            data:    None,
-        });
-    }
+    let request_id = world
+        .resource::<ScreenshotRequests>()
+        .start(&absolute_path_str);
 
     // Spawn entity based on delay setting
     let entity = if delay_frames == 0 {
@@ -186,18 +452,24 @@ pub fn handler(In(params): In<Option<Value>>, world: &mut World) -> BrpResult {
         let path_for_observer = absolute_path_str.clone();
         world
             .spawn((
-                Screenshot::primary_window(),
+                target.screenshot(),
                 Name::new(format!("Screenshot_{absolute_path_str}")),
             ))
-            .observe(create_save_observer(path_for_observer))
+            .observe(create_save_observer(
+                path_for_observer,
+                target.camera(),
+                request_id,
+            ))
             .id()
     } else {
         // Delayed capture - spawn with PendingScreenshot component
         world
             .spawn((
                 PendingScreenshot {
-                    path:             absolute_path_str.clone(),
+                    path: absolute_path_str.clone(),
                     frames_remaining: delay_frames,
+                    target,
+                    request_id,
                 },
                 Name::new(format!("PendingScreenshot_{absolute_path_str}")),
             ))
@@ -211,8 +483,12 @@ pub fn handler(In(params): In<Option<Value>>, world: &mut World) -> BrpResult {
 
     Ok(json!({
         "success": true,
+        "request_id": request_id,
         "path": absolute_path_str,
         "delay_frames": delay_frames,
+        "mode": if resolution.is_some() { "offscreen" } else { "window" },
+        "width": resolution.map(|size| size.x),
+        "height": resolution.map(|size| size.y),
         "working_directory": std::env::current_dir()
             .unwrap_or_else(|_| std::path::PathBuf::from("unknown"))
             .to_string_lossy(),
@@ -223,3 +499,127 @@ pub fn handler(In(params): In<Option<Value>>, world: &mut World) -> BrpResult {
         }
     }))
 }
+
+/// Handler for screenshot status requests
+///
+/// Reports whether the screenshot started with `request_id` has been saved - `pending`, `saved`
+/// with the file size and image dimensions, or `failed` with the error.
+///
+/// # Parameters
+/// - `request_id` (required): The `request_id` returned by `brp_extras/screenshot`
+pub fn status_handler(In(params): In<Option<Value>>, world: &mut World) -> BrpResult {
+    let request_id = params
+        .as_ref()
+        .and_then(|v| v.get("request_id"))
+        .and_then(Value::as_u64)
+        .ok_or_else(|| BrpError {
+            code:    INVALID_PARAMS,
+            message: "Missing 'request_id' parameter".to_string(),
+            data:    None,
+        })?;
+
+    let (path, status) = world
+        .resource::<ScreenshotRequests>()
+        .status(request_id)
+        .ok_or_else(|| BrpError {
+            code:    INVALID_PARAMS,
+            message: format!(
+                "Unknown screenshot request {request_id} - only the last {MAX_TRACKED_REQUESTS} \
+                 requests are tracked"
+            ),
+            data:    None,
+        })?;
+
+    let mut response = serde_json::to_value(&status).map_err(|e| BrpError {
+        code:    INTERNAL_ERROR,
+        message: format!("Failed to serialize screenshot status: {e}"),
+        data:    None,
+    })?;
+    if let Value::Object(map) = &mut response {
+        map.insert("request_id".to_string(), json!(request_id));
+        map.insert("path".to_string(), json!(path));
+    }
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_resolution_given_together() {
+        let size = parse_resolution(Some(&json!({ "width": 1920, "height": 1080 })));
+        assert_eq!(size.ok().flatten(), Some(UVec2::new(1920, 1080)));
+        assert!(matches!(parse_resolution(Some(&json!({}))), Ok(None)));
+        assert!(parse_resolution(Some(&json!({ "width": 1920 }))).is_err());
+        assert!(parse_resolution(Some(&json!({ "width": 0, "height": 1080 }))).is_err());
+    }
+
+    #[test]
+    #[allow(clippy::expect_used)]
+    fn offscreen_camera_matches_the_source_camera() {
+        let mut world = World::new();
+        world.init_resource::<Assets<Image>>();
+        let source = world
+            .spawn((
+                Camera {
+                    order: 3,
+                    ..default()
+                },
+                Camera3d::default(),
+                RenderLayers::layer(2),
+                Msaa::Off,
+                Hdr,
+                Tonemapping::AgX,
+                Bloom::NATURAL,
+            ))
+            .id();
+
+        let CaptureTarget::Offscreen { camera, .. } =
+            spawn_offscreen_camera(&mut world, UVec2::new(64, 32)).expect("offscreen camera")
+        else {
+            unreachable!("a resolution always gives an offscreen capture target");
+        };
+
+        assert_eq!(world.get::<Camera>(camera).map(|c| c.order), Some(3));
+        assert!(world.get::<Camera3d>(camera).is_some());
+        assert_eq!(
+            world.get::<RenderLayers>(camera),
+            Some(&RenderLayers::layer(2))
+        );
+        assert_eq!(world.get::<Msaa>(camera), Some(&Msaa::Off));
+        assert!(world.get::<Hdr>(camera).is_some());
+        assert_eq!(world.get::<Tonemapping>(camera), Some(&Tonemapping::AgX));
+        assert!(world.get::<Bloom>(camera).is_some());
+        assert_eq!(
+            world.get::<ChildOf>(camera).map(ChildOf::parent),
+            Some(source)
+        );
+    }
+
+    #[test]
+    fn tracks_request_status_until_evicted() {
+        let requests = ScreenshotRequests::default();
+        let first = requests.start("/tmp/first.png");
+        assert_eq!(
+            requests.status(first),
+            Some(("/tmp/first.png".to_string(), SaveStatus::Pending))
+        );
+
+        let saved = SaveStatus::Saved {
+            file_size: 1024,
+            width:     640,
+            height:    480,
+        };
+        requests.finish(first, saved.clone());
+        assert_eq!(
+            requests.status(first).map(|(_, status)| status),
+            Some(saved)
+        );
+
+        for _ in 0..MAX_TRACKED_REQUESTS {
+            requests.start("/tmp/later.png");
+        }
+        assert_eq!(requests.status(first), None);
+    }
+}
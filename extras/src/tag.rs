@@ -0,0 +1,116 @@
+//! Entity tag handlers for BRP extras
+//!
+//! A tag is a plain string stored in a `BrpTag` component on the entity, so it lives in the app:
+//! it survives reconnecting tools and the game can query it like any other component.
+
+use bevy::prelude::*;
+use bevy::remote::BrpError;
+use bevy::remote::BrpResult;
+use bevy::remote::error_codes::INVALID_PARAMS;
+use serde::Deserialize;
+use serde_json::Value;
+use serde_json::json;
+
+/// Bookmark attached to an entity by `brp_extras/tag_entity`
+#[derive(Component, Reflect, Debug, Clone, PartialEq, Eq)]
+#[reflect(Component)]
+pub struct BrpTag(pub String);
+
+/// Request structure for `tag_entity`
+#[derive(Debug, Deserialize)]
+pub struct TagEntityRequest {
+    /// Entity to tag
+    pub entity: Entity,
+    /// Tag to attach - `None` removes the entity's tag
+    #[serde(default)]
+    pub tag:    Option<String>,
+}
+
+/// Request structure for `find_tagged`
+#[derive(Debug, Default, Deserialize)]
+pub struct FindTaggedRequest {
+    /// Only return entities with this tag - `None` returns every tagged entity
+    #[serde(default)]
+    pub tag: Option<String>,
+}
+
+/// Handler for `tag_entity` requests
+pub fn tag_entity_handler(In(params): In<Option<Value>>, world: &mut World) -> BrpResult {
+    let request: TagEntityRequest = params
+        .ok_or_else(|| BrpError {
+            code:    INVALID_PARAMS,
+            message: "Missing request parameters".to_string(),
+            data:    None,
+        })
+        .and_then(|params| {
+            serde_json::from_value(params).map_err(|e| BrpError {
+                code:    INVALID_PARAMS,
+                message: format!("Invalid request format: {e}"),
+                data:    None,
+            })
+        })?;
+
+    if request.tag.as_ref().is_some_and(String::is_empty) {
+        return Err(BrpError {
+            code:    INVALID_PARAMS,
+            message: "Tag must not be empty - omit 'tag' to remove an entity's tag".to_string(),
+            data:    None,
+        });
+    }
+
+    let entity = request.entity;
+    let mut entity_mut = world
+        .get_entity_mut(entity)
+        .map_err(|_| BrpError::entity_not_found(entity))?;
+
+    let previous_tag = entity_mut.get::<BrpTag>().map(|BrpTag(tag)| tag.clone());
+    match &request.tag {
+        Some(tag) => {
+            entity_mut.insert(BrpTag(tag.clone()));
+        },
+        None => {
+            entity_mut.remove::<BrpTag>();
+        },
+    }
+
+    let message = match (&request.tag, &previous_tag) {
+        (Some(tag), _) => format!("Tagged entity {entity} as '{tag}'"),
+        (None, Some(previous)) => format!("Removed tag '{previous}' from entity {entity}"),
+        (None, None) => format!("Entity {entity} had no tag"),
+    };
+
+    Ok(json!({
+        "entity": entity.to_bits(),
+        "tag": request.tag,
+        "previous_tag": previous_tag,
+        "message": message,
+    }))
+}
+
+/// Handler for `find_tagged` requests
+pub fn find_tagged_handler(In(params): In<Option<Value>>, world: &mut World) -> BrpResult {
+    let request: FindTaggedRequest = params
+        .map(serde_json::from_value)
+        .transpose()
+        .map_err(|e| BrpError {
+            code:    INVALID_PARAMS,
+            message: format!("Invalid request format: {e}"),
+            data:    None,
+        })?
+        .unwrap_or_default();
+
+    let mut query = world.query::<(Entity, &BrpTag)>();
+    let mut entities: Vec<(Entity, String)> = query
+        .iter(world)
+        .filter(|(_, BrpTag(tag))| request.tag.as_ref().is_none_or(|wanted| wanted == tag))
+        .map(|(entity, BrpTag(tag))| (entity, tag.clone()))
+        .collect();
+    entities.sort_by(|(a_entity, a_tag), (b_entity, b_tag)| {
+        a_tag.cmp(b_tag).then(a_entity.cmp(b_entity))
+    });
+
+    Ok(entities
+        .into_iter()
+        .map(|(entity, tag)| json!({ "entity": entity.to_bits(), "tag": tag }))
+        .collect())
+}
@@ -0,0 +1,204 @@
+//! Time configuration handler for BRP extras
+//!
+//! Reads and changes the fixed timestep (`Time<Fixed>`), the maximum frame delta (`Time<Virtual>`)
+//! and the wrap period used by `elapsed_wrapped`. The wrap period is set on every clock so the
+//! generic `Time` the schedules copy from each frame agrees with them.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy::remote::BrpError;
+use bevy::remote::BrpResult;
+use bevy::remote::error_codes::INTERNAL_ERROR;
+use bevy::remote::error_codes::INVALID_PARAMS;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+use serde_json::json;
+
+/// Shortest duration a setting accepts - anything shorter would have `FixedUpdate` run
+/// thousands of times a frame, or the clocks wrap or clamp every frame
+const MIN_DURATION: Duration = Duration::from_millis(1);
+
+/// Request structure for `time_config` - omitted fields are left unchanged
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TimeConfigRequest {
+    /// Seconds between `FixedUpdate` runs
+    #[serde(default)]
+    pub fixed_timestep_seconds: Option<f64>,
+    /// Largest frame delta, in seconds, virtual time advances by
+    #[serde(default)]
+    pub max_delta_seconds:      Option<f64>,
+    /// Seconds after which `elapsed_wrapped` wraps to zero
+    #[serde(default)]
+    pub wrap_period_seconds:    Option<f64>,
+}
+
+/// Time configuration, as reported by `brp_extras/time_config`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimeConfigResponse {
+    /// Seconds between `FixedUpdate` runs
+    pub fixed_timestep_seconds: f64,
+    /// `FixedUpdate` runs per second
+    pub fixed_timestep_hz:      f64,
+    /// Largest frame delta, in seconds, virtual time advances by
+    pub max_delta_seconds:      f64,
+    /// Seconds after which `elapsed_wrapped` wraps to zero
+    pub wrap_period_seconds:    f64,
+    /// Settings changed by this request
+    pub changed:                Vec<String>,
+}
+
+/// Handler for `time_config` requests
+///
+/// Without parameters the current configuration is returned unchanged.
+pub fn handler(In(params): In<Option<Value>>, world: &mut World) -> BrpResult {
+    let request: TimeConfigRequest = params
+        .map(serde_json::from_value)
+        .transpose()
+        .map_err(|e| BrpError {
+            code:    INVALID_PARAMS,
+            message: format!("Invalid request format: {e}"),
+            data:    None,
+        })?
+        .unwrap_or_default();
+
+    let fixed_timestep =
+        positive_duration("fixed_timestep_seconds", request.fixed_timestep_seconds)?;
+    let max_delta = positive_duration("max_delta_seconds", request.max_delta_seconds)?;
+    let wrap_period = positive_duration("wrap_period_seconds", request.wrap_period_seconds)?;
+
+    let mut changed = Vec::new();
+    if let Some(timestep) = fixed_timestep {
+        time_mut::<Fixed>(world)?.set_timestep(timestep);
+        changed.push("fixed_timestep_seconds".to_string());
+    }
+    if let Some(max_delta) = max_delta {
+        time_mut::<Virtual>(world)?.set_max_delta(max_delta);
+        changed.push("max_delta_seconds".to_string());
+    }
+    if let Some(wrap_period) = wrap_period {
+        time_mut::<Real>(world)?.set_wrap_period(wrap_period);
+        time_mut::<Virtual>(world)?.set_wrap_period(wrap_period);
+        time_mut::<Fixed>(world)?.set_wrap_period(wrap_period);
+        time_mut::<()>(world)?.set_wrap_period(wrap_period);
+        changed.push("wrap_period_seconds".to_string());
+    }
+
+    let fixed = time_mut::<Fixed>(world)?;
+    let fixed_timestep_seconds = fixed.timestep().as_secs_f64();
+    let wrap_period_seconds = fixed.wrap_period().as_secs_f64();
+    let max_delta_seconds = time_mut::<Virtual>(world)?.max_delta().as_secs_f64();
+
+    Ok(json!(TimeConfigResponse {
+        fixed_timestep_seconds,
+        fixed_timestep_hz: fixed_timestep_seconds.recip(),
+        max_delta_seconds,
+        wrap_period_seconds,
+        changed,
+    }))
+}
+
+/// Validate an optional setting as a finite number of seconds of at least `MIN_DURATION`
+pub fn positive_duration(name: &str, seconds: Option<f64>) -> Result<Option<Duration>, BrpError> {
+    seconds
+        .map(|seconds| {
+            Duration::try_from_secs_f64(seconds)
+                .ok()
+                .filter(|duration| *duration >= MIN_DURATION)
+                .ok_or_else(|| BrpError {
+                    code:    INVALID_PARAMS,
+                    message: format!(
+                        "'{name}' must be at least {} seconds, got {seconds}",
+                        MIN_DURATION.as_secs_f64()
+                    ),
+                    data:    None,
+                })
+        })
+        .transpose()
+}
+
+/// The `Time<T>` resource, or an error when `TimePlugin` isn't installed
+pub fn time_mut<T: Default + Send + Sync + 'static>(
+    world: &mut World,
+) -> Result<Mut<'_, Time<T>>, BrpError> {
+    world.get_resource_mut::<Time<T>>().ok_or_else(|| BrpError {
+        code:    INTERNAL_ERROR,
+        message: "Time resources not found - is TimePlugin installed?".to_string(),
+        data:    None,
+    })
+}
+
+#[cfg(test)]
+#[allow(clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    fn time_world() -> World {
+        let mut world = World::new();
+        world.insert_resource(Time::<()>::default());
+        world.insert_resource(Time::<Real>::default());
+        world.insert_resource(Time::<Virtual>::default());
+        world.insert_resource(Time::<Fixed>::default());
+        world
+    }
+
+    #[test]
+    fn updates_only_the_given_settings() {
+        let mut world = time_world();
+        let default_max_delta = world.resource::<Time<Virtual>>().max_delta();
+
+        let response: TimeConfigResponse = serde_json::from_value(
+            handler(
+                In(Some(
+                    json!({"fixed_timestep_seconds": 0.01, "wrap_period_seconds": 60.0}),
+                )),
+                &mut world,
+            )
+            .expect("valid request"),
+        )
+        .expect("response should deserialize");
+
+        assert!((response.fixed_timestep_hz - 100.0).abs() < 1e-9);
+        assert!((response.wrap_period_seconds - 60.0).abs() < f64::EPSILON);
+        assert_eq!(
+            response.changed,
+            vec!["fixed_timestep_seconds", "wrap_period_seconds"]
+        );
+        assert_eq!(
+            world.resource::<Time<Fixed>>().timestep(),
+            Duration::from_millis(10)
+        );
+        assert_eq!(
+            world.resource::<Time<Virtual>>().max_delta(),
+            default_max_delta
+        );
+        assert_eq!(
+            world.resource::<Time<Real>>().wrap_period(),
+            Duration::from_mins(1)
+        );
+    }
+
+    #[test]
+    fn rejects_durations_under_a_millisecond() {
+        let mut world = time_world();
+
+        for params in [
+            json!({"fixed_timestep_seconds": 0.0}),
+            json!({"max_delta_seconds": -1.0}),
+            json!({"wrap_period_seconds": 1e-9}),
+            json!({"fixed_timestep_seconds": 0.000_999}),
+        ] {
+            let error = handler(In(Some(params)), &mut world).expect_err("invalid duration");
+            assert_eq!(error.code, INVALID_PARAMS);
+        }
+        assert_eq!(
+            world.resource::<Time<Fixed>>().timestep(),
+            Time::<Fixed>::default().timestep()
+        );
+
+        let shortest = json!({"fixed_timestep_seconds": 0.001});
+        assert!(handler(In(Some(shortest)), &mut world).is_ok());
+    }
+}
@@ -0,0 +1,166 @@
+//! Port topology of an app and the child processes it spawns
+//!
+//! `bevy_brp_mcp` launches each instance with a set of environment variables describing where
+//! it sits: its BRP port, the host it is reached on and its index among the instances of one
+//! launch. Apps that spawn their own processes - a dedicated server started by a client, say -
+//! pass the variables from [`BrpTopology::child_env`] to each child so it picks its own port and
+//! knows its parent, and record the child with [`BrpTopology::register_child`]. Each app then
+//! reports its place in the tree through `brp_extras/app_info`, letting the agent find ports it
+//! never launched.
+
+use std::net::SocketAddr;
+
+use bevy::prelude::*;
+use serde::Deserialize;
+use serde::Serialize;
+
+/// Environment variable holding the app's BRP port
+pub const PORT_ENV_VAR: &str = "BRP_EXTRAS_PORT";
+
+/// Environment variable holding the host the app's BRP port is reached on
+pub const HOST_ENV_VAR: &str = "BRP_EXTRAS_HOST";
+
+/// Environment variable holding the app's 0-based index among the instances of one launch
+pub const INSTANCE_INDEX_ENV_VAR: &str = "BRP_EXTRAS_INSTANCE_INDEX";
+
+/// Environment variable holding the number of instances started by the launch
+pub const INSTANCE_COUNT_ENV_VAR: &str = "BRP_EXTRAS_INSTANCE_COUNT";
+
+/// Environment variable holding the BRP port of the app that spawned this one
+pub const PARENT_PORT_ENV_VAR: &str = "BRP_EXTRAS_PARENT_PORT";
+
+/// Environment variable holding the address of the network condition proxy between instances
+pub const NETWORK_PROXY_ENV_VAR: &str = "BRP_EXTRAS_NETWORK_PROXY";
+
+/// Host reported when `BRP_EXTRAS_HOST` isn't set
+const DEFAULT_HOST: &str = "localhost";
+
+/// A child process the app spawned, as recorded with [`BrpTopology::register_child`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BrpChildInstance {
+    /// What the child is, e.g. `dedicated_server`
+    pub label: String,
+    /// The child's BRP port
+    pub port:  u16,
+    /// The child's process ID, when known
+    pub pid:   Option<u32>,
+}
+
+/// Where the app sits in the tree of launched processes, reported by `brp_extras/app_info`
+#[derive(Resource, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BrpTopology {
+    /// Host the app's BRP port is reached on
+    pub host:           String,
+    /// The app's BRP port
+    pub port:           u16,
+    /// 0-based index among the instances of the launch, when launched by `bevy_brp_mcp`
+    pub instance_index: Option<usize>,
+    /// Number of instances started by the launch
+    pub instance_count: Option<usize>,
+    /// BRP port of the app that spawned this one
+    pub parent_port:    Option<u16>,
+    /// Child processes the app recorded
+    pub children:       Vec<BrpChildInstance>,
+}
+
+impl BrpTopology {
+    /// Read the topology variables of this process, for an app serving BRP on `port`
+    #[must_use]
+    pub fn from_env(port: u16) -> Self {
+        Self {
+            host: std::env::var(HOST_ENV_VAR).unwrap_or_else(|_| DEFAULT_HOST.to_string()),
+            port,
+            instance_index: env_number(INSTANCE_INDEX_ENV_VAR),
+            instance_count: env_number(INSTANCE_COUNT_ENV_VAR),
+            parent_port: env_number(PARENT_PORT_ENV_VAR),
+            children: Vec::new(),
+        }
+    }
+
+    /// Environment variables to start a child process serving BRP on `child_port` with
+    ///
+    /// The child inherits the host and instance index and records this app's port as its
+    /// parent.
+    #[must_use]
+    pub fn child_env(&self, child_port: u16) -> Vec<(&'static str, String)> {
+        let mut env = vec![
+            (PORT_ENV_VAR, child_port.to_string()),
+            (HOST_ENV_VAR, self.host.clone()),
+            (PARENT_PORT_ENV_VAR, self.port.to_string()),
+        ];
+        if let Some(instance_index) = self.instance_index {
+            env.push((INSTANCE_INDEX_ENV_VAR, instance_index.to_string()));
+        }
+        if let Some(instance_count) = self.instance_count {
+            env.push((INSTANCE_COUNT_ENV_VAR, instance_count.to_string()));
+        }
+        env
+    }
+
+    /// Record a child process serving BRP on `port`, replacing any child on the same port
+    pub fn register_child(&mut self, label: impl Into<String>, port: u16, pid: Option<u32>) {
+        self.children.retain(|child| child.port != port);
+        self.children.push(BrpChildInstance {
+            label: label.into(),
+            port,
+            pid,
+        });
+    }
+
+    /// Forget the child serving BRP on `port`, e.g. after it exited
+    pub fn unregister_child(&mut self, port: u16) {
+        self.children.retain(|child| child.port != port);
+    }
+}
+
+/// Address to connect to the server through, when launched behind a network condition proxy
+///
+/// `bevy_brp_mcp` launches with `network_proxy` start a proxy that adds latency, jitter and packet
+/// loss on its way to the server instance's game port. Clients that connect here instead of to the
+/// server directly play over the simulated connection; `None` means connect as usual.
+#[must_use]
+pub fn network_proxy_addr() -> Option<SocketAddr> {
+    std::env::var(NETWORK_PROXY_ENV_VAR)
+        .ok()
+        .and_then(|addr| addr.trim().parse().ok())
+}
+
+/// Parse a numeric environment variable, ignoring it when unset or malformed
+fn env_number<T: std::str::FromStr>(name: &str) -> Option<T> {
+    std::env::var(name)
+        .ok()
+        .and_then(|value| value.trim().parse().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn children_inherit_host_and_instance_and_point_at_their_parent() {
+        let mut topology = BrpTopology {
+            host:           "10.0.0.5".to_string(),
+            port:           15702,
+            instance_index: Some(1),
+            instance_count: None,
+            parent_port:    None,
+            children:       Vec::new(),
+        };
+        assert_eq!(
+            topology.child_env(15800),
+            vec![
+                (PORT_ENV_VAR, "15800".to_string()),
+                (HOST_ENV_VAR, "10.0.0.5".to_string()),
+                (PARENT_PORT_ENV_VAR, "15702".to_string()),
+                (INSTANCE_INDEX_ENV_VAR, "1".to_string()),
+            ]
+        );
+
+        topology.register_child("server", 15800, None);
+        topology.register_child("dedicated_server", 15800, Some(42));
+        assert_eq!(topology.children.len(), 1);
+        assert_eq!(topology.children[0].label, "dedicated_server");
+        topology.unregister_child(15800);
+        assert!(topology.children.is_empty());
+    }
+}
@@ -0,0 +1,201 @@
+//! UI text extraction handler for BRP extras
+//!
+//! Collects the content of every UI `Text` node, spans included, with its rectangle on screen
+//! and the names of its ancestors, so the copy shown to the player can be checked without OCR on
+//! a screenshot or a hand-written chain of component queries.
+
+use bevy::prelude::*;
+use bevy::remote::BrpError;
+use bevy::remote::BrpResult;
+use bevy::remote::error_codes::INVALID_PARAMS;
+use bevy::ui::UiGlobalTransform;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+use serde_json::json;
+
+/// Request structure for `get_ui_text`
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GetUiTextRequest {
+    /// Only return text containing this, ignoring case
+    #[serde(default)]
+    pub contains:       Option<String>,
+    /// Also return text that isn't visible
+    #[serde(default)]
+    pub include_hidden: bool,
+}
+
+/// A UI text node, as reported by `brp_extras/get_ui_text`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UiText {
+    /// Entity holding the `Text` component
+    pub entity:  u64,
+    /// The text with its spans, in order
+    pub text:    String,
+    /// Where the node is on screen
+    pub rect:    TextRect,
+    /// Names of the node's ancestors and itself, root first, joined with `/` - entities without
+    /// a `Name` appear as their ID
+    pub path:    String,
+    /// Whether the node is visible
+    pub visible: bool,
+}
+
+/// A node's rectangle in logical pixels from the top-left corner of the window
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct TextRect {
+    /// Left edge
+    pub x:      f32,
+    /// Top edge
+    pub y:      f32,
+    /// Width
+    pub width:  f32,
+    /// Height
+    pub height: f32,
+}
+
+impl TextRect {
+    fn of(node: &ComputedNode, transform: &UiGlobalTransform) -> Self {
+        // Layout is in physical pixels, with the transform at the node's center
+        let scale = node.inverse_scale_factor();
+        let size = node.size() * scale;
+        let center = transform.translation * scale;
+        Self {
+            x:      center.x - size.x / 2.0,
+            y:      center.y - size.y / 2.0,
+            width:  size.x,
+            height: size.y,
+        }
+    }
+}
+
+/// Handler for `get_ui_text` requests
+///
+/// Returns the matching text nodes in reading order, top to bottom then left to right.
+pub fn handler(In(params): In<Option<Value>>, world: &mut World) -> BrpResult {
+    let request: GetUiTextRequest = params
+        .map(serde_json::from_value)
+        .transpose()
+        .map_err(|e| BrpError {
+            code:    INVALID_PARAMS,
+            message: format!("Invalid request format: {e}"),
+            data:    None,
+        })?
+        .unwrap_or_default();
+    let needle = request.contains.as_deref().map(str::to_lowercase);
+
+    let mut query = world.query::<(
+        Entity,
+        &Text,
+        &ComputedNode,
+        &UiGlobalTransform,
+        Option<&InheritedVisibility>,
+    )>();
+    let nodes: Vec<(Entity, String, TextRect, bool)> = query
+        .iter(world)
+        .map(|(entity, text, node, transform, visibility)| {
+            (
+                entity,
+                text.0.clone(),
+                TextRect::of(node, transform),
+                visibility.is_none_or(|visibility| visibility.get()),
+            )
+        })
+        .collect();
+
+    let mut texts = Vec::new();
+    for (entity, mut text, rect, visible) in nodes {
+        if !visible && !request.include_hidden {
+            continue;
+        }
+        append_spans(world, entity, &mut text);
+        if let Some(needle) = &needle
+            && !text.to_lowercase().contains(needle)
+        {
+            continue;
+        }
+        texts.push(UiText {
+            entity: entity.to_bits(),
+            text,
+            rect,
+            path: hierarchy_path(world, entity),
+            visible,
+        });
+    }
+    texts.sort_by(|a, b| {
+        a.rect
+            .y
+            .total_cmp(&b.rect.y)
+            .then(a.rect.x.total_cmp(&b.rect.x))
+    });
+
+    Ok(json!(texts))
+}
+
+/// Append the `TextSpan` descendants of `entity` to `text`, depth first as Bevy lays them out
+fn append_spans(world: &World, entity: Entity, text: &mut String) {
+    let Some(children) = world.get::<Children>(entity) else {
+        return;
+    };
+    for child in children.iter() {
+        if let Some(span) = world.get::<TextSpan>(child) {
+            text.push_str(&span.0);
+            append_spans(world, child, text);
+        }
+    }
+}
+
+/// `Root/Panel/Label` path of names from the root of `entity`'s hierarchy down to it
+fn hierarchy_path(world: &World, entity: Entity) -> String {
+    let mut segments = Vec::new();
+    let mut current = Some(entity);
+    while let Some(entity) = current {
+        segments.push(
+            world
+                .get::<Name>(entity)
+                .map_or_else(|| entity.to_string(), |name| name.as_str().to_string()),
+        );
+        current = world.get::<ChildOf>(entity).map(ChildOf::parent);
+    }
+    segments.reverse();
+    segments.join("/")
+}
+
+#[cfg(test)]
+#[allow(clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn collects_spans_paths_and_filters() {
+        let mut world = World::new();
+        let panel = world.spawn(Name::new("Panel")).id();
+        let label = world
+            .spawn((
+                Text::new("Health: "),
+                InheritedVisibility::VISIBLE,
+                Name::new("HealthLabel"),
+                ChildOf(panel),
+            ))
+            .id();
+        world.spawn((TextSpan::new("42"), ChildOf(label)));
+        world.spawn((Text::new("Paused"), InheritedVisibility::HIDDEN));
+
+        let texts = handler(In(None), &mut world).expect("ui text");
+        let texts = texts.as_array().expect("array of texts");
+        assert_eq!(texts.len(), 1);
+        assert_eq!(texts[0]["text"], "Health: 42");
+        assert_eq!(texts[0]["path"], "Panel/HealthLabel");
+
+        let hidden = handler(
+            In(Some(
+                json!({ "contains": "PAUSED", "include_hidden": true }),
+            )),
+            &mut world,
+        )
+        .expect("ui text");
+        assert_eq!(hidden.as_array().map(Vec::len), Some(1));
+        assert_eq!(hidden[0]["visible"], false);
+    }
+}
@@ -0,0 +1,240 @@
+//! Window state handler for BRP extras
+//!
+//! Changes are written to the primary `Window` component and applied by the windowing backend on
+//! its next update, so the response reflects what was requested rather than what the platform
+//! ended up doing - some window managers ignore focus requests or window levels. `Window` doesn't
+//! expose whether it is minimized, so the last minimized state requested here is kept on the
+//! window entity and reported.
+
+use bevy::prelude::*;
+use bevy::remote::BrpError;
+use bevy::remote::BrpResult;
+use bevy::remote::error_codes::INTERNAL_ERROR;
+use bevy::remote::error_codes::INVALID_PARAMS;
+use bevy::window::PrimaryWindow;
+use bevy::window::WindowLevel;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+use serde_json::json;
+
+/// Stacking level of a window relative to other windows
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WindowLevelSetting {
+    /// Always below other windows
+    AlwaysOnBottom,
+    /// Stacked like any other window
+    Normal,
+    /// Always above other windows
+    AlwaysOnTop,
+}
+
+impl From<WindowLevelSetting> for WindowLevel {
+    fn from(level: WindowLevelSetting) -> Self {
+        match level {
+            WindowLevelSetting::AlwaysOnBottom => Self::AlwaysOnBottom,
+            WindowLevelSetting::Normal => Self::Normal,
+            WindowLevelSetting::AlwaysOnTop => Self::AlwaysOnTop,
+        }
+    }
+}
+
+impl From<WindowLevel> for WindowLevelSetting {
+    fn from(level: WindowLevel) -> Self {
+        match level {
+            WindowLevel::AlwaysOnBottom => Self::AlwaysOnBottom,
+            WindowLevel::Normal => Self::Normal,
+            WindowLevel::AlwaysOnTop => Self::AlwaysOnTop,
+        }
+    }
+}
+
+/// Minimized state last requested through `set_window`
+#[derive(Component, Debug, Clone, Copy)]
+struct RequestedMinimized(bool);
+
+/// Request structure for `set_window`
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SetWindowRequest {
+    /// Show (`true`) or hide (`false`) the title bar and borders
+    #[serde(default)]
+    pub decorations:  Option<bool>,
+    /// Stacking level of the window
+    #[serde(default)]
+    pub window_level: Option<WindowLevelSetting>,
+    /// Ask the platform to give the window keyboard focus - only `true`, since platforms have no
+    /// request to give focus up
+    #[serde(default)]
+    pub focus:        Option<bool>,
+    /// Minimize (`true`) or restore (`false`) the window
+    #[serde(default)]
+    pub minimized:    Option<bool>,
+}
+
+impl SetWindowRequest {
+    const fn is_empty(&self) -> bool {
+        self.decorations.is_none()
+            && self.window_level.is_none()
+            && self.focus.is_none()
+            && self.minimized.is_none()
+    }
+}
+
+/// Handler for `set_window` requests
+pub fn handler(In(params): In<Option<Value>>, world: &mut World) -> BrpResult {
+    let request: SetWindowRequest = params
+        .map(serde_json::from_value)
+        .transpose()
+        .map_err(|e| BrpError {
+            code:    INVALID_PARAMS,
+            message: format!("Invalid request format: {e}"),
+            data:    None,
+        })?
+        .unwrap_or_default();
+
+    if request.is_empty() {
+        return Err(BrpError {
+            code:    INVALID_PARAMS,
+            message: "Nothing to change - pass at least one of 'decorations', 'window_level', \
+                      'focus' or 'minimized'"
+                .to_string(),
+            data:    None,
+        });
+    }
+
+    if request.focus == Some(false) {
+        return Err(BrpError {
+            code:    INVALID_PARAMS,
+            message: "'focus' can only be true - to move focus away, focus another window or \
+                      minimize this one"
+                .to_string(),
+            data:    None,
+        });
+    }
+
+    let mut query = world.query_filtered::<(Entity, &mut Window), With<PrimaryWindow>>();
+    let (entity, mut window) = query.single_mut(world).map_err(|_| BrpError {
+        code:    INTERNAL_ERROR,
+        message: "No primary window found".to_string(),
+        data:    None,
+    })?;
+
+    let mut changes = Vec::new();
+    if let Some(decorations) = request.decorations {
+        window.decorations = decorations;
+        changes.push(if decorations {
+            "decorations shown"
+        } else {
+            "decorations hidden"
+        });
+    }
+    if let Some(level) = request.window_level {
+        window.window_level = level.into();
+        changes.push(match level {
+            WindowLevelSetting::AlwaysOnBottom => "level set to always on bottom",
+            WindowLevelSetting::Normal => "level set to normal",
+            WindowLevelSetting::AlwaysOnTop => "level set to always on top",
+        });
+    }
+    if request.focus == Some(true) {
+        window.focused = true;
+        changes.push("focus requested");
+    }
+    if let Some(minimized) = request.minimized {
+        window.set_minimized(minimized);
+        changes.push(if minimized { "minimized" } else { "restored" });
+    }
+
+    let message = format!("Window updated: {}", changes.join(", "));
+    let mut response = json!({
+        "decorations": window.decorations,
+        "window_level": WindowLevelSetting::from(window.window_level),
+        "focused": window.focused,
+        "changes": changes,
+        "message": message
+    });
+
+    if let Some(minimized) = request.minimized {
+        world
+            .entity_mut(entity)
+            .insert(RequestedMinimized(minimized));
+    }
+    response["minimized"] = json!(
+        world
+            .get::<RequestedMinimized>(entity)
+            .map(|requested| requested.0)
+    );
+
+    Ok(response)
+}
+
+#[cfg(test)]
+#[allow(clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    fn window_world() -> World {
+        let mut world = World::new();
+        world.spawn((Window::default(), PrimaryWindow));
+        world
+    }
+
+    fn set_window(world: &mut World, params: Value) -> BrpResult {
+        handler(In(Some(params)), world)
+    }
+
+    #[test]
+    fn applies_the_requested_changes_and_reports_them() {
+        let mut world = window_world();
+
+        let response = set_window(
+            &mut world,
+            json!({"decorations": false, "window_level": "always_on_top", "focus": true}),
+        )
+        .expect("valid request");
+
+        assert_eq!(response["decorations"], json!(false));
+        assert_eq!(response["window_level"], json!("always_on_top"));
+        assert_eq!(response["focused"], json!(true));
+        assert_eq!(
+            response["changes"],
+            json!([
+                "decorations hidden",
+                "level set to always on top",
+                "focus requested"
+            ])
+        );
+        assert_eq!(response["minimized"], Value::Null);
+    }
+
+    #[test]
+    fn minimized_state_is_kept_between_requests() {
+        let mut world = window_world();
+
+        let minimized = set_window(&mut world, json!({"minimized": true})).expect("minimize");
+        assert_eq!(minimized["minimized"], json!(true));
+        assert_eq!(minimized["changes"], json!(["minimized"]));
+
+        let unrelated = set_window(&mut world, json!({"decorations": true})).expect("decorate");
+        assert_eq!(unrelated["minimized"], json!(true));
+
+        let restored = set_window(&mut world, json!({"minimized": false})).expect("restore");
+        assert_eq!(restored["minimized"], json!(false));
+    }
+
+    #[test]
+    fn rejects_focus_false_and_empty_requests() {
+        let mut world = window_world();
+
+        let error = set_window(&mut world, json!({"focus": false})).expect_err("focus false");
+        assert_eq!(error.code, INVALID_PARAMS);
+
+        let error = set_window(&mut world, json!({})).expect_err("empty request");
+        assert_eq!(error.code, INVALID_PARAMS);
+
+        let error = set_window(&mut World::new(), json!({"focus": true})).expect_err("no window");
+        assert_eq!(error.code, INTERNAL_ERROR);
+    }
+}
@@ -0,0 +1,482 @@
+//! `brp_bootstrap_session` tool - Launch, wait, load, seed and snapshot in one call
+//!
+//! An agent session against a game usually starts the same way: launch the usual preset, wait
+//! until BRP answers, load a test scene, make the run reproducible, name the entities it will
+//! talk about and take a baseline to diff against - six or more calls before the real work. This
+//! tool runs that sequence through the same tools a client would call and returns one session
+//! descriptor. Seed and time scale go to every instance; the scene, aliases and baseline to the
+//! first. A failing stage stops the sequence and leaves the instances running, with the error
+//! naming their ports.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use bevy_brp_mcp_macros::ParamStruct;
+use bevy_brp_mcp_macros::ResultStruct;
+use bevy_brp_mcp_macros::ToolFn;
+use futures::future::join_all;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Map;
+use serde_json::Value;
+use serde_json::json;
+use tokio::time::Instant;
+
+use super::constants::DEFAULT_READY_TIMEOUT_MS;
+use super::support::answers_brp_by;
+use crate::brp_tools::BrpQuery;
+use crate::brp_tools::BrpQueryFilter;
+use crate::brp_tools::ComponentSelector;
+use crate::brp_tools::Port;
+use crate::brp_tools::fetch_scene;
+use crate::error::Error;
+use crate::error::Result;
+use crate::tool::HandlerContext;
+use crate::tool::HandlerResult;
+use crate::tool::ParameterName;
+use crate::tool::ToolFn;
+use crate::tool::ToolName;
+use crate::tool::ToolResult;
+
+/// `Name` component type path - the default baseline tracks named entities
+const NAME_TYPE: &str = "bevy_ecs::name::Name";
+
+/// Scene file field holding the commands, when the file is an object
+const SCENE_COMMANDS_FIELD: &str = "commands";
+
+/// Stages reported as progress: launch, readiness, scene, determinism, aliases, baseline
+const STAGE_COUNT: usize = 6;
+
+/// Which entities and components the baseline checkpoint tracks, as for `world_changes_since`
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
+pub struct SessionBaseline {
+    /// Components to track: {components: string[], option: "all" | string[]}
+    pub data:   BrpQuery,
+    /// Which entities to track: {with: string[], without: string[]}
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filter: Option<BrpQueryFilter>,
+}
+
+impl Default for SessionBaseline {
+    /// Every component of every named entity
+    fn default() -> Self {
+        Self {
+            data:   BrpQuery {
+                option: ComponentSelector::All,
+                ..BrpQuery::default()
+            },
+            filter: Some(BrpQueryFilter {
+                with:    vec![NAME_TYPE.to_string()],
+                without: Vec::new(),
+            }),
+        }
+    }
+}
+
+#[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
+pub struct BootstrapSessionParams {
+    /// Launch preset to start, from the presets file `brp_launch_bevy_app` reads
+    pub preset:           String,
+    /// Launch the preset's target as an example rather than an app (default: false)
+    #[serde(default)]
+    pub example:          bool,
+    /// Launch parameters that win over the preset's, e.g. `{"port": 15720, "profile":
+    /// "release"}`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub launch:           Option<Map<String, Value>>,
+    /// Milliseconds to wait for every instance to answer BRP (default: 60000, max: 600000)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[param(max = 600_000)]
+    #[schemars(extend("default" = DEFAULT_READY_TIMEOUT_MS))]
+    pub ready_timeout_ms: Option<u32>,
+    /// JSON file of `brp_extras_apply_commands` commands to load as the scene - an array, or an
+    /// object with `commands` - absolute or relative to the first workspace root
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scene:            Option<String>,
+    /// Seed for every instance's `BrpRngSeed` resource
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed:             Option<u64>,
+    /// Rate every instance's virtual time advances at, e.g. `2.0` for double speed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub relative_speed:   Option<f64>,
+    /// Aliases to bind, each to the entity with that `Name`, e.g. `{"player": "Player"}`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aliases:          Option<BTreeMap<String, String>>,
+    /// What the baseline checkpoint tracks (default: every component of every named entity)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub baseline:         Option<SessionBaseline>,
+}
+
+/// Everything a session needs to know about the apps it bootstrapped
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SessionDescriptor {
+    /// Name of the launched target
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_name:         Option<String>,
+    /// Port of the first instance - the scene, aliases and baseline are on it
+    pub port:                u16,
+    /// Ports of every instance
+    pub ports:               Vec<u16>,
+    /// The launched instances, as `brp_launch_bevy_app` reports them
+    pub instances:           Value,
+    /// Commands of the scene applied
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scene_commands:      Option<usize>,
+    /// Determinism settings of the first instance after seeding
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub determinism:         Option<Value>,
+    /// Entity aliases bound on the first instance's port
+    pub aliases:             BTreeMap<String, u64>,
+    /// `world_changes_since` checkpoint to diff the world against
+    pub baseline_checkpoint: String,
+    /// Entities the baseline tracks
+    pub baseline_entities:   u64,
+}
+
+/// Result from bootstrapping a session
+#[derive(Debug, Clone, Serialize, Deserialize, ResultStruct)]
+pub struct BootstrapSessionResult {
+    /// The session descriptor
+    #[to_result]
+    session:          SessionDescriptor,
+    /// Port of the first instance
+    #[to_metadata]
+    port:             u16,
+    /// Number of instances launched
+    #[to_metadata]
+    instance_count:   usize,
+    /// Total time the bootstrap took, in milliseconds
+    #[to_metadata]
+    duration_ms:      u64,
+    /// Message template for formatting responses
+    #[to_message(
+        message_template = "Bootstrapped {instance_count} instance(s) from port {port} in {duration_ms}ms"
+    )]
+    message_template: String,
+}
+
+#[derive(ToolFn)]
+#[tool_fn(
+    params = "BootstrapSessionParams",
+    output = "BootstrapSessionResult",
+    with_context
+)]
+pub struct BootstrapSession;
+
+async fn handle_impl(
+    ctx: HandlerContext,
+    params: BootstrapSessionParams,
+) -> Result<BootstrapSessionResult> {
+    let started = Instant::now();
+    // Read the scene before launching, so a bad file doesn't leave instances behind
+    let scene = params
+        .scene
+        .as_deref()
+        .map(|scene| read_scene(scene, &ctx.roots))
+        .transpose()?;
+
+    report_progress(&ctx, 0, "Launching").await;
+    let launch_tool = if params.example {
+        ToolName::BrpLaunchBevyExample
+    } else {
+        ToolName::BrpLaunchBevyApp
+    };
+    let mut arguments = params.launch.clone().unwrap_or_default();
+    arguments.insert(ParameterName::Preset.to_string(), json!(params.preset));
+    let launch = call(&ctx, launch_tool, arguments).await?;
+    let instances = launch.get("result").cloned().unwrap_or(Value::Null);
+    let ports: Vec<u16> = instances
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|instance| instance.get(ParameterName::Port.as_ref())?.as_u64())
+        .filter_map(|port| u16::try_from(port).ok())
+        .collect();
+    let Some(&first) = ports.first() else {
+        return Err(Error::tool_call_failed("The launch reported no instance ports").into());
+    };
+    let port = Port(first);
+
+    report_progress(&ctx, 1, "Waiting for BRP").await;
+    let timeout = params.ready_timeout_ms.unwrap_or(DEFAULT_READY_TIMEOUT_MS);
+    let deadline = Instant::now() + Duration::from_millis(u64::from(timeout));
+    let ready = join_all(
+        ports
+            .iter()
+            .map(|&port| answers_brp_by(Port(port), deadline)),
+    )
+    .await;
+    if ready.contains(&false) {
+        return Err(stage_failed(
+            &ports,
+            &format!("not every instance answered BRP within {timeout}ms"),
+        )
+        .into());
+    }
+
+    let session = prepare_session(&ctx, &params, scene, &ports)
+        .await
+        .map_err(|report| stage_failed(&ports, &report.current_context().to_string()))?;
+    let duration_ms = u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX);
+    Ok(BootstrapSessionResult::new(
+        SessionDescriptor {
+            target_name: launch
+                .get("metadata")
+                .and_then(|metadata| metadata.get("target_name"))
+                .and_then(Value::as_str)
+                .map(String::from),
+            port: *port,
+            ports: ports.clone(),
+            instances,
+            ..session
+        },
+        *port,
+        ports.len(),
+        duration_ms,
+    ))
+}
+
+/// Load the scene, seed every instance, bind the aliases and take the baseline - the stages
+/// after the instances answer BRP
+async fn prepare_session(
+    ctx: &HandlerContext,
+    params: &BootstrapSessionParams,
+    scene: Option<Vec<Value>>,
+    ports: &[u16],
+) -> Result<SessionDescriptor> {
+    let port = ports.first().copied().unwrap_or_default();
+
+    report_progress(ctx, 2, "Loading the scene").await;
+    let scene_commands = scene.as_ref().map(Vec::len);
+    if let Some(commands) = scene {
+        let arguments = object(json!({ "commands": commands, "port": port }));
+        call(ctx, ToolName::BrpExtrasApplyCommands, arguments).await?;
+    }
+
+    report_progress(ctx, 3, "Seeding").await;
+    let mut determinism = None;
+    if params.seed.is_some() || params.relative_speed.is_some() {
+        for &instance_port in ports {
+            let mut arguments = object(json!({ "port": instance_port }));
+            if let Some(seed) = params.seed {
+                arguments.insert("seed".to_string(), json!(seed));
+            }
+            if let Some(speed) = params.relative_speed {
+                arguments.insert("relative_speed".to_string(), json!(speed));
+            }
+            let settings = call(ctx, ToolName::BrpExtrasDeterminism, arguments).await?;
+            determinism.get_or_insert_with(|| settings.get("result").cloned().unwrap_or_default());
+        }
+    }
+
+    report_progress(ctx, 4, "Binding entity aliases").await;
+    let aliases = bind_aliases(ctx, params.aliases.as_ref(), port).await?;
+
+    report_progress(ctx, 5, "Taking the baseline").await;
+    let baseline = params.baseline.clone().unwrap_or_default();
+    let mut arguments = object(json!({ "data": baseline.data, "port": port }));
+    if let Some(filter) = baseline.filter {
+        arguments.insert(ParameterName::Filter.to_string(), json!(filter));
+    }
+    // The baseline's rows aren't returned, so they mustn't be saved to a file either
+    arguments.insert(ParameterName::MaxResponseTokens.to_string(), json!(0));
+    let changes = call(ctx, ToolName::WorldChangesSince, arguments).await?;
+    let metadata = changes.get("metadata");
+    let field = |name: &str| metadata.and_then(|metadata| metadata.get(name));
+
+    Ok(SessionDescriptor {
+        target_name: None,
+        port,
+        ports: ports.to_vec(),
+        instances: Value::Null,
+        scene_commands,
+        determinism,
+        aliases,
+        baseline_checkpoint: field("checkpoint")
+            .and_then(Value::as_str)
+            .map(String::from)
+            .unwrap_or_default(),
+        baseline_entities: field("entity_count").and_then(Value::as_u64).unwrap_or(0),
+    })
+}
+
+/// Bind each alias to the one entity with its `Name`, returning every alias on the port
+async fn bind_aliases(
+    ctx: &HandlerContext,
+    aliases: Option<&BTreeMap<String, String>>,
+    port: u16,
+) -> Result<BTreeMap<String, u64>> {
+    let mut bound = BTreeMap::new();
+    let Some(aliases) = aliases.filter(|aliases| !aliases.is_empty()) else {
+        return Ok(bound);
+    };
+
+    let scene = fetch_scene(Port(port), None, &[]).await?;
+    for (alias, name) in aliases {
+        let named: Vec<u64> = scene
+            .names
+            .iter()
+            .filter(|(_, label)| *label == name)
+            .map(|(&entity, _)| entity)
+            .collect();
+        let [entity] = named[..] else {
+            return Err(Error::invalid(
+                "aliases",
+                format!(
+                    "'{alias}' needs exactly one entity named '{name}', found {}",
+                    named.len()
+                ),
+            )
+            .into());
+        };
+        let arguments = object(json!({ "alias": alias, "entity": entity, "port": port }));
+        let response = call(ctx, ToolName::BrpAliasEntity, arguments).await?;
+        bound = response
+            .get("result")
+            .cloned()
+            .and_then(|aliases| serde_json::from_value(aliases).ok())
+            .unwrap_or_default();
+    }
+    Ok(bound)
+}
+
+/// Read the scene file's commands - a relative `scene` path is resolved against the first root
+fn read_scene(scene: &str, roots: &[PathBuf]) -> Result<Vec<Value>> {
+    let path = roots
+        .first()
+        .map_or_else(|| Path::new(scene).to_path_buf(), |root| root.join(scene));
+    let text = fs::read_to_string(&path).map_err(|e| Error::io_failed("read scene", &path, &e))?;
+    let scene: Value = serde_json::from_str(&text)
+        .map_err(|e| Error::invalid("scene", format!("{} is not JSON: {e}", path.display())))?;
+    match scene {
+        Value::Array(commands) => Ok(commands),
+        Value::Object(mut scene) => match scene.remove(SCENE_COMMANDS_FIELD) {
+            Some(Value::Array(commands)) => Ok(commands),
+            _ => Err(Error::invalid(
+                "scene",
+                format!("{} has no `commands` array", path.display()),
+            )
+            .into()),
+        },
+        _ => Err(Error::invalid(
+            "scene",
+            format!(
+                "{} is neither a command array nor an object",
+                path.display()
+            ),
+        )
+        .into()),
+    }
+}
+
+/// Call `tool_name` the way a client would, returning its structured content
+async fn call(
+    ctx: &HandlerContext,
+    tool_name: ToolName,
+    arguments: Map<String, Value>,
+) -> Result<Value> {
+    let mut request = ctx.request.clone();
+    request.name = <&'static str>::from(tool_name).into();
+    request.arguments = Some(arguments);
+    let response = tool_name
+        .to_tool_def()
+        .call_tool(request, ctx.roots.clone(), ctx.roots_support(), None)
+        .await
+        .map_err(|e| Error::tool_call_failed(format!("{tool_name} failed: {}", e.message)))?;
+    let content = response.structured_content.unwrap_or_default();
+    if response.is_error == Some(true) {
+        let message = content
+            .get("message")
+            .and_then(Value::as_str)
+            .unwrap_or("no message");
+        return Err(Error::tool_call_failed(format!("{tool_name} failed: {message}")).into());
+    }
+    Ok(content)
+}
+
+/// The arguments object of a `json!` literal
+fn object(value: Value) -> Map<String, Value> {
+    match value {
+        Value::Object(map) => map,
+        _ => Map::new(),
+    }
+}
+
+/// Error for a stage that failed after the instances were launched
+fn stage_failed(ports: &[u16], reason: &str) -> Error {
+    let ports = ports
+        .iter()
+        .map(u16::to_string)
+        .collect::<Vec<_>>()
+        .join(", ");
+    Error::tool_call_failed(format!(
+        "Session bootstrap stopped: {reason} - the instances on ports {ports} are still running"
+    ))
+}
+
+async fn report_progress(ctx: &HandlerContext, completed: usize, stage: &str) {
+    if let Some(progress) = ctx.progress() {
+        progress.report(completed, STAGE_COUNT, stage).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scene_in(contents: &str) -> (tempfile::TempDir, Vec<PathBuf>) {
+        let dir = tempfile::tempdir().unwrap_or_else(|e| unreachable!("temp dir: {e}"));
+        fs::write(dir.path().join("scene.json"), contents)
+            .unwrap_or_else(|e| unreachable!("temp dir is writable: {e}"));
+        let roots = vec![dir.path().to_path_buf()];
+        (dir, roots)
+    }
+
+    #[test]
+    fn reads_commands_from_an_array_or_an_object() {
+        let command = json!({"tool": "world_spawn_entity", "arguments": {}});
+
+        let (_dir, roots) = scene_in(&json!([command]).to_string());
+        assert_eq!(
+            read_scene("scene.json", &roots).ok(),
+            Some(vec![command.clone()])
+        );
+
+        let (_dir, roots) = scene_in(&json!({"commands": [command]}).to_string());
+        assert_eq!(read_scene("scene.json", &roots).ok(), Some(vec![command]));
+    }
+
+    #[test]
+    fn rejects_scenes_without_commands() {
+        for contents in ["not json", r#"{"steps": []}"#, r#"{"commands": {}}"#, "42"] {
+            let (_dir, roots) = scene_in(contents);
+            assert!(read_scene("scene.json", &roots).is_err(), "{contents}");
+        }
+        assert!(read_scene("missing.json", &[]).is_err());
+    }
+
+    #[test]
+    fn default_baseline_tracks_every_component_of_named_entities() {
+        let baseline = SessionBaseline::default();
+        assert!(matches!(baseline.data.option, ComponentSelector::All));
+        assert!(baseline.data.components.is_empty());
+        assert_eq!(
+            baseline.filter.map(|filter| filter.with),
+            Some(vec![NAME_TYPE.to_string()])
+        );
+    }
+
+    #[test]
+    fn stage_failure_names_the_ports_left_running() {
+        let error = stage_failed(&[15702, 15703], "scene failed");
+        assert_eq!(
+            error.to_string(),
+            "Tool call error: Session bootstrap stopped: scene failed - the instances on ports \
+             15702, 15703 are still running"
+        );
+    }
+}
@@ -0,0 +1,544 @@
+//! `brp_diagnose` tool - Staged connection checklist for a BRP port
+//!
+//! "Connection refused" on its own doesn't say whether nothing is running, something else owns
+//! the port, the app lacks `RemotePlugin`, or the app is listening on a different port than the
+//! one being queried. This tool walks through each layer in order and reports the first stage
+//! that fails along with a hint for fixing it.
+
+use std::time::Duration;
+
+use bevy_brp_mcp_macros::ParamStruct;
+use bevy_brp_mcp_macros::ResultStruct;
+use bevy_brp_mcp_macros::ToolFn;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+use serde_json::json;
+use strum::Display;
+use sysinfo::ProcessRefreshKind;
+use sysinfo::ProcessesToUpdate;
+use sysinfo::System;
+use tokio::net::TcpStream;
+
+use crate::app_tools::support::get_pid_for_port;
+use crate::brp_tools::BRP_DEFAULT_HOST;
+use crate::brp_tools::BRP_EXTRAS_PORT_ENV_VAR;
+use crate::brp_tools::BRP_HTTP_PROTOCOL;
+use crate::brp_tools::BRP_JSONRPC_PATH;
+use crate::brp_tools::Port;
+use crate::error::Result;
+use crate::tool::BrpMethod;
+use crate::tool::HandlerContext;
+use crate::tool::HandlerResult;
+use crate::tool::ToolFn;
+use crate::tool::ToolResult;
+
+/// Timeout for each network stage
+const STAGE_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
+pub struct DiagnoseParams {
+    /// The BRP port
+    #[serde(default)]
+    pub port: Port,
+}
+
+/// A single stage of the connection checklist
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Display)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum DiagnosticStage {
+    /// Something accepts TCP connections on the port
+    TcpConnect,
+    /// The listener answers HTTP requests
+    HttpReachable,
+    /// The HTTP endpoint answers with JSON-RPC
+    JsonRpcResponse,
+    /// The app exposes Bevy's `RemotePlugin` methods
+    RemotePlugin,
+    /// The app exposes `bevy_brp_extras` methods
+    BrpExtras,
+    /// The app's `BRP_EXTRAS_PORT` matches the queried port
+    PortEnvironment,
+}
+
+/// Outcome of a single stage
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum StageStatus {
+    Passed,
+    Failed,
+    /// Not fatal, but some tools will be unavailable
+    Warning,
+    /// Not run because an earlier stage failed
+    Skipped,
+}
+
+/// Report for a single stage
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct StageReport {
+    stage:  DiagnosticStage,
+    status: StageStatus,
+    detail: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hint:   Option<String>,
+}
+
+impl StageReport {
+    fn passed(stage: DiagnosticStage, detail: impl Into<String>) -> Self {
+        Self {
+            stage,
+            status: StageStatus::Passed,
+            detail: detail.into(),
+            hint: None,
+        }
+    }
+
+    fn failed(stage: DiagnosticStage, detail: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            stage,
+            status: StageStatus::Failed,
+            detail: detail.into(),
+            hint: Some(hint.into()),
+        }
+    }
+
+    fn warning(stage: DiagnosticStage, detail: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            stage,
+            status: StageStatus::Warning,
+            detail: detail.into(),
+            hint: Some(hint.into()),
+        }
+    }
+
+    fn skipped(stage: DiagnosticStage) -> Self {
+        Self {
+            stage,
+            status: StageStatus::Skipped,
+            detail: "not run because an earlier stage failed".to_string(),
+            hint: None,
+        }
+    }
+}
+
+/// Result from diagnosing a BRP connection
+#[derive(Debug, Clone, Serialize, Deserialize, ResultStruct)]
+pub struct DiagnoseResult {
+    /// Port that was diagnosed
+    #[to_metadata]
+    port:                u16,
+    /// True if every required stage passed
+    #[to_metadata]
+    healthy:             bool,
+    /// The first stage that failed, if any
+    #[to_metadata(skip_if_none)]
+    first_failing_stage: Option<DiagnosticStage>,
+    /// Per-stage reports in the order they were run
+    #[to_result]
+    stages:              Vec<StageReport>,
+    /// Message template for formatting responses
+    #[to_message]
+    message_template:    Option<String>,
+}
+
+#[derive(ToolFn)]
+#[tool_fn(params = "DiagnoseParams", output = "DiagnoseResult")]
+pub struct Diagnose;
+
+async fn handle_impl(params: DiagnoseParams) -> Result<DiagnoseResult> {
+    let port = params.port;
+    let mut stages = run_network_stages(port).await;
+    // Reading the environment of every process blocks
+    let port_environment = tokio::task::spawn_blocking(move || check_port_environment(port))
+        .await
+        .unwrap_or_else(|e| {
+            StageReport::warning(
+                DiagnosticStage::PortEnvironment,
+                format!("could not inspect process environments: {e}"),
+                "Run brp_diagnose again",
+            )
+        });
+    stages.push(port_environment);
+
+    let first_failure = stages
+        .iter()
+        .find(|report| report.status == StageStatus::Failed);
+    let first_failing_stage = first_failure.map(|report| report.stage);
+
+    let message = first_failure.map_or_else(
+        || format!("BRP connection on port {port} is healthy"),
+        |report| {
+            format!(
+                "BRP diagnosis for port {port} failed at stage '{}': {}",
+                report.stage, report.detail
+            )
+        },
+    );
+
+    Ok(DiagnoseResult::new(
+        port.0,
+        first_failing_stage.is_none(),
+        first_failing_stage,
+        stages,
+    )
+    .with_message_template(message))
+}
+
+/// Run the network stages in order, skipping everything after the first failure
+async fn run_network_stages(port: Port) -> Vec<StageReport> {
+    let remaining = [
+        DiagnosticStage::HttpReachable,
+        DiagnosticStage::JsonRpcResponse,
+        DiagnosticStage::RemotePlugin,
+        DiagnosticStage::BrpExtras,
+    ];
+    // Everything after the stage that just failed is skipped
+    let skip_remaining = |mut stages: Vec<StageReport>| {
+        let done = stages.len() - 1;
+        stages.extend(
+            remaining
+                .iter()
+                .skip(done)
+                .map(|stage| StageReport::skipped(*stage)),
+        );
+        stages
+    };
+
+    let mut stages = vec![check_tcp_connect(port).await];
+    if stages[0].status == StageStatus::Failed {
+        return skip_remaining(stages);
+    }
+
+    let response = match send_discover(port).await {
+        Ok(response) => {
+            stages.push(StageReport::passed(
+                DiagnosticStage::HttpReachable,
+                format!("HTTP {} from {}", response.status(), brp_url(port)),
+            ));
+            response
+        },
+        Err(e) => {
+            stages.push(StageReport::failed(
+                DiagnosticStage::HttpReachable,
+                format!("TCP connection succeeded but the HTTP request failed: {e}"),
+                "Another (non-HTTP) service may be using this port - try a different port for \
+                 your Bevy app",
+            ));
+            return skip_remaining(stages);
+        },
+    };
+
+    let body: Value = match response.json().await {
+        Ok(body @ Value::Object(_)) if body.get("jsonrpc").is_some() => body,
+        Ok(_) | Err(_) => {
+            stages.push(StageReport::failed(
+                DiagnosticStage::JsonRpcResponse,
+                format!("{} did not answer with a JSON-RPC response", brp_url(port)),
+                "An HTTP server that isn't Bevy is using this port - stop it or run your Bevy \
+                 app on a different port",
+            ));
+            return skip_remaining(stages);
+        },
+    };
+    stages.push(StageReport::passed(
+        DiagnosticStage::JsonRpcResponse,
+        "endpoint speaks JSON-RPC 2.0",
+    ));
+
+    stages.extend(check_discovered_methods(&body));
+    stages
+}
+
+/// Inspect the `rpc.discover` response for `RemotePlugin` and `bevy_brp_extras` methods
+fn check_discovered_methods(body: &Value) -> Vec<StageReport> {
+    let method_names: Vec<&str> = body
+        .get("result")
+        .and_then(|result| result.get("methods"))
+        .and_then(Value::as_array)
+        .map(|methods| {
+            methods
+                .iter()
+                .filter_map(|m| m.get("name").and_then(Value::as_str))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let has_remote_plugin = method_names
+        .iter()
+        .any(|name| name.starts_with("world.") || name.starts_with("bevy/"));
+    if !has_remote_plugin {
+        let detail = body.get("error").map_or_else(
+            || "rpc.discover returned no Bevy world methods".to_string(),
+            |error| format!("rpc.discover returned an error: {error}"),
+        );
+        return vec![
+            StageReport::failed(
+                DiagnosticStage::RemotePlugin,
+                detail,
+                "Add `RemotePlugin::default()` and `RemoteHttpPlugin::default()` (or \
+                 `BrpExtrasPlugin`) to your Bevy app",
+            ),
+            StageReport::skipped(DiagnosticStage::BrpExtras),
+        ];
+    }
+    let remote_plugin = StageReport::passed(
+        DiagnosticStage::RemotePlugin,
+        format!("{} BRP methods available", method_names.len()),
+    );
+
+    let extras_count = method_names
+        .iter()
+        .filter(|name| name.starts_with("brp_extras/"))
+        .count();
+    let brp_extras = if extras_count == 0 {
+        StageReport::warning(
+            DiagnosticStage::BrpExtras,
+            "bevy_brp_extras methods not found",
+            "Add `BrpExtrasPlugin` to enable screenshots, key input, window title and clean \
+             shutdown",
+        )
+    } else {
+        StageReport::passed(
+            DiagnosticStage::BrpExtras,
+            format!("{extras_count} brp_extras methods available"),
+        )
+    };
+
+    vec![remote_plugin, brp_extras]
+}
+
+async fn check_tcp_connect(port: Port) -> StageReport {
+    let address = format!("{BRP_DEFAULT_HOST}:{port}");
+    match tokio::time::timeout(STAGE_TIMEOUT, TcpStream::connect(&address)).await {
+        Ok(Ok(_)) => StageReport::passed(
+            DiagnosticStage::TcpConnect,
+            format!("connected to {address}"),
+        ),
+        Ok(Err(e)) => StageReport::failed(
+            DiagnosticStage::TcpConnect,
+            format!("could not connect to {address}: {e}"),
+            "Nothing is listening on this port - make sure your Bevy app is running and uses \
+             the same port (see the port_environment stage)",
+        ),
+        Err(_) => StageReport::failed(
+            DiagnosticStage::TcpConnect,
+            format!(
+                "timed out after {}s connecting to {address}",
+                STAGE_TIMEOUT.as_secs()
+            ),
+            "A firewall may be blocking local connections, or the app is hung",
+        ),
+    }
+}
+
+/// Send a raw `rpc.discover` request so each layer of the response can be inspected separately
+async fn send_discover(port: Port) -> reqwest::Result<reqwest::Response> {
+    reqwest::Client::new()
+        .post(brp_url(port))
+        .timeout(STAGE_TIMEOUT)
+        .json(&json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": BrpMethod::RpcDiscover.as_str(),
+        }))
+        .send()
+        .await
+}
+
+fn brp_url(port: Port) -> String {
+    format!("{BRP_HTTP_PROTOCOL}://{BRP_DEFAULT_HOST}:{port}{BRP_JSONRPC_PATH}")
+}
+
+/// Compare `BRP_EXTRAS_PORT` in the environment of running processes against the queried port
+fn check_port_environment(port: Port) -> StageReport {
+    let mut system = System::new();
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::All,
+        true,
+        ProcessRefreshKind::nothing().with_environ(sysinfo::UpdateKind::Always),
+    );
+
+    let env_port_of = |process: &sysinfo::Process| {
+        process.environ().iter().find_map(|entry| {
+            entry
+                .to_string_lossy()
+                .strip_prefix(BRP_EXTRAS_PORT_ENV_VAR)
+                .and_then(|rest| rest.strip_prefix('='))
+                .map(str::to_string)
+        })
+    };
+
+    // The process that owns the port should agree with it
+    if let Some(pid) = get_pid_for_port(port) {
+        let process = system.process(sysinfo::Pid::from_u32(pid));
+        return match process.and_then(env_port_of) {
+            Some(env_port) if env_port != port.to_string() => StageReport::failed(
+                DiagnosticStage::PortEnvironment,
+                format!(
+                    "PID {pid} owns port {port} but was launched with \
+                     {BRP_EXTRAS_PORT_ENV_VAR}={env_port}"
+                ),
+                format!(
+                    "Relaunch the app with {BRP_EXTRAS_PORT_ENV_VAR}={port}, or query port \
+                     {env_port}"
+                ),
+            ),
+            Some(_) => StageReport::passed(
+                DiagnosticStage::PortEnvironment,
+                format!("PID {pid} owns port {port} and {BRP_EXTRAS_PORT_ENV_VAR} matches"),
+            ),
+            None => StageReport::passed(
+                DiagnosticStage::PortEnvironment,
+                format!("PID {pid} owns port {port} ({BRP_EXTRAS_PORT_ENV_VAR} not set)"),
+            ),
+        };
+    }
+
+    // Nothing owns the port - look for apps that were told to listen somewhere else
+    let elsewhere: Vec<String> = system
+        .processes()
+        .values()
+        .filter_map(|process| {
+            env_port_of(process).map(|env_port| {
+                format!(
+                    "'{}' (PID {}) has {BRP_EXTRAS_PORT_ENV_VAR}={env_port}",
+                    process.name().to_string_lossy(),
+                    process.pid().as_u32()
+                )
+            })
+        })
+        .collect();
+
+    if elsewhere.is_empty() {
+        StageReport::passed(
+            DiagnosticStage::PortEnvironment,
+            format!("no process owns port {port} and none sets {BRP_EXTRAS_PORT_ENV_VAR}"),
+        )
+    } else {
+        StageReport::failed(
+            DiagnosticStage::PortEnvironment,
+            format!("no process owns port {port}, but {}", elsewhere.join(", ")),
+            "Query the port the app was launched with, or relaunch it on this port",
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::net::TcpListener;
+
+    use super::*;
+
+    fn discover(methods: &[&str]) -> Value {
+        let methods: Vec<Value> = methods.iter().map(|name| json!({ "name": name })).collect();
+        json!({ "jsonrpc": "2.0", "id": 1, "result": { "methods": methods } })
+    }
+
+    fn statuses(stages: &[StageReport]) -> Vec<(DiagnosticStage, StageStatus)> {
+        stages
+            .iter()
+            .map(|report| (report.stage, report.status))
+            .collect()
+    }
+
+    #[test]
+    fn remote_plugin_alone_warns_about_missing_extras() {
+        let stages = check_discovered_methods(&discover(&["rpc.discover", "world.query"]));
+        assert_eq!(
+            statuses(&stages),
+            vec![
+                (DiagnosticStage::RemotePlugin, StageStatus::Passed),
+                (DiagnosticStage::BrpExtras, StageStatus::Warning),
+            ]
+        );
+    }
+
+    #[test]
+    fn extras_methods_pass_the_extras_stage() {
+        let stages = check_discovered_methods(&discover(&[
+            "world.query",
+            "brp_extras/screenshot",
+            "brp_extras/shutdown",
+        ]));
+        assert_eq!(
+            statuses(&stages),
+            vec![
+                (DiagnosticStage::RemotePlugin, StageStatus::Passed),
+                (DiagnosticStage::BrpExtras, StageStatus::Passed),
+            ]
+        );
+        assert_eq!(stages[1].detail, "2 brp_extras methods available");
+    }
+
+    #[test]
+    fn legacy_method_names_count_as_remote_plugin() {
+        let stages = check_discovered_methods(&discover(&["bevy/query", "bevy/get"]));
+        assert_eq!(stages[0].status, StageStatus::Passed);
+
+        let stages = check_discovered_methods(&discover(&["rpc.discover"]));
+        assert_eq!(
+            statuses(&stages),
+            vec![
+                (DiagnosticStage::RemotePlugin, StageStatus::Failed),
+                (DiagnosticStage::BrpExtras, StageStatus::Skipped),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn stages_after_a_failed_tcp_connect_are_skipped() {
+        // A port nothing listens on once the listener is dropped
+        let port = std::net::TcpListener::bind((BRP_DEFAULT_HOST, 0))
+            .and_then(|listener| listener.local_addr())
+            .map_or_else(
+                |e| unreachable!("bind a free port: {e}"),
+                |address| Port(address.port()),
+            );
+
+        let stages = run_network_stages(port).await;
+
+        assert_eq!(
+            statuses(&stages),
+            vec![
+                (DiagnosticStage::TcpConnect, StageStatus::Failed),
+                (DiagnosticStage::HttpReachable, StageStatus::Skipped),
+                (DiagnosticStage::JsonRpcResponse, StageStatus::Skipped),
+                (DiagnosticStage::RemotePlugin, StageStatus::Skipped),
+                (DiagnosticStage::BrpExtras, StageStatus::Skipped),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn stages_after_a_failed_http_request_are_skipped() {
+        // Accepts connections and closes them without answering
+        let listener = TcpListener::bind((BRP_DEFAULT_HOST, 0))
+            .await
+            .unwrap_or_else(|e| unreachable!("bind a free port: {e}"));
+        let port = listener.local_addr().map_or_else(
+            |e| unreachable!("bound address: {e}"),
+            |address| address.port(),
+        );
+        let server = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                drop(stream);
+            }
+        });
+
+        let stages = run_network_stages(Port(port)).await;
+        server.abort();
+
+        assert_eq!(
+            statuses(&stages),
+            vec![
+                (DiagnosticStage::TcpConnect, StageStatus::Passed),
+                (DiagnosticStage::HttpReachable, StageStatus::Failed),
+                (DiagnosticStage::JsonRpcResponse, StageStatus::Skipped),
+                (DiagnosticStage::RemotePlugin, StageStatus::Skipped),
+                (DiagnosticStage::BrpExtras, StageStatus::Skipped),
+            ]
+        );
+    }
+}
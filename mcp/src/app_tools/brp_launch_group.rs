@@ -0,0 +1,388 @@
+//! `brp_launch_group` tool - Launch several different targets together
+//!
+//! `instance_count` starts copies of one target; a dedicated server app plus a client example
+//! needs two. A group launches each of its targets on its own ports - a target's `port`, or else
+//! the port after the previous target's last instance - builds and starts them one after another,
+//! since parallel cargo builds would only wait on each other's build directory lock, and waits
+//! until every instance answers BRP.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use bevy_brp_mcp_macros::ParamStruct;
+use bevy_brp_mcp_macros::ResultStruct;
+use bevy_brp_mcp_macros::ToolFn;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+use tokio::time::Instant;
+
+use super::constants::DEFAULT_PROFILE;
+use super::constants::DEFAULT_READY_TIMEOUT_MS;
+use super::instance_count::InstanceCount;
+use super::instance_override::InstanceOverride;
+use super::launch_params::BuildProfile;
+use super::support::App;
+use super::support::Example;
+use super::support::FromLaunchParams;
+use super::support::LaunchConfig;
+use super::support::LaunchParams;
+use super::support::LaunchResult;
+use super::support::OutputCapture;
+use super::support::TargetSpec;
+use super::support::TargetType;
+use super::support::answers_brp_by;
+use super::support::check_ports_available;
+use super::support::launch_target;
+use crate::brp_tools::MAX_VALID_PORT;
+use crate::brp_tools::Port;
+use crate::error::Error;
+use crate::error::Result;
+use crate::tool::HandlerContext;
+use crate::tool::HandlerResult;
+use crate::tool::ToolFn;
+use crate::tool::ToolResult;
+
+/// Most targets one group may launch
+const MAX_GROUP_TARGETS: usize = 16;
+
+/// Time kept back from the call's timeout for reporting which instances weren't ready
+const RESULT_MARGIN: Duration = Duration::from_secs(1);
+
+/// One target of a launch group
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct GroupTarget {
+    /// Name of the app (or example, with `example: true`), or a target spec naming its package:
+    /// `my_pkg#demo`, `my_pkg/examples/demo` or `my_pkg/bin/demo`
+    pub target_name:    String,
+    /// Launch an example rather than an app (default: false)
+    #[serde(default)]
+    pub example:        bool,
+    /// BRP port of the target's first instance (default: the port after the previous target's
+    /// last instance)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub port:           Option<Port>,
+    /// Number of instances of this target to launch (default: 1)
+    #[serde(default)]
+    pub instance_count: InstanceCount,
+    /// Per-instance overrides - entry `i` adds `args`, `env` and a `label` to instance `i`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instances:      Option<Vec<InstanceOverride>>,
+    /// Build profile to use (debug or release)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(extend("default" = DEFAULT_PROFILE))]
+    pub profile:        Option<BuildProfile>,
+    /// Path to use when multiple targets with the same name exist
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path:           Option<String>,
+    /// Package containing the target - an alternative to `path`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub package:        Option<String>,
+    /// Cargo features to enable when building and running
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub features:       Option<Vec<String>>,
+    /// Build debug builds with Bevy's `dynamic_linking` feature when available (default: false)
+    #[serde(default)]
+    pub fast_build:     bool,
+    /// How the target's output is captured
+    #[serde(flatten)]
+    pub output:         OutputCapture,
+}
+
+#[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
+pub struct LaunchGroupParams {
+    /// Targets to launch, e.g. `[{"target_name": "server"}, {"target_name": "client", "example":
+    /// true, "instance_count": 2}]`
+    pub targets:          Vec<GroupTarget>,
+    /// Wait until every instance answers BRP before returning (default: true)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(extend("default" = true))]
+    pub wait_ready:       Option<bool>,
+    /// Milliseconds to wait for the instances to answer BRP (default: 60000, max: 600000) - never
+    /// past the call's `timeout_ms`, so the result still reports which instances weren't ready
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[param(max = 600_000)]
+    #[schemars(extend("default" = DEFAULT_READY_TIMEOUT_MS))]
+    pub ready_timeout_ms: Option<u32>,
+    /// Port of the first target without its own `port`
+    #[serde(default)]
+    pub port:             Port,
+}
+
+/// How one target of the group launched
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GroupMember {
+    /// Name of the target
+    pub target_name: String,
+    /// Whether it is an app or an example
+    pub target_type: String,
+    /// Ports of its instances
+    pub ports:       Vec<u16>,
+    /// The launch result, as `brp_launch_bevy_app` or `brp_launch_bevy_example` report it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub launch:      Option<Value>,
+    /// Why the target didn't launch
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error:       Option<String>,
+    /// Ports of instances that didn't answer BRP in time
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub not_ready:   Vec<u16>,
+}
+
+/// Result from launching a group of targets
+#[derive(Debug, Clone, Serialize, Deserialize, ResultStruct)]
+pub struct LaunchGroupResult {
+    /// Each target of the group, in the order given
+    #[to_result]
+    targets:          Vec<GroupMember>,
+    /// Number of targets in the group
+    #[to_metadata]
+    target_count:     usize,
+    /// Number of targets that launched
+    #[to_metadata]
+    launched_count:   usize,
+    /// Number of launched instances
+    #[to_metadata]
+    instance_count:   usize,
+    /// Number of launched instances answering BRP - `None` when readiness wasn't awaited
+    #[to_metadata(skip_if_none)]
+    ready_count:      Option<usize>,
+    /// Message template for formatting responses
+    #[to_message(
+        message_template = "Launched {launched_count} of {target_count} targets ({instance_count} instances)"
+    )]
+    message_template: String,
+}
+
+#[derive(ToolFn)]
+#[tool_fn(
+    params = "LaunchGroupParams",
+    output = "LaunchGroupResult",
+    with_context
+)]
+pub struct LaunchGroup;
+
+/// A group target with its ports decided
+struct PlannedTarget {
+    target_type: TargetType,
+    params:      LaunchParams,
+    ports:       Vec<u16>,
+}
+
+async fn handle_impl(ctx: HandlerContext, params: LaunchGroupParams) -> Result<LaunchGroupResult> {
+    let call_deadline = ctx
+        .timeout()
+        .map(|timeout| Instant::now() + timeout.saturating_sub(RESULT_MARGIN));
+    if params.targets.is_empty() {
+        return Err(Error::invalid("targets", "the group has no targets").into());
+    }
+    if params.targets.len() > MAX_GROUP_TARGETS {
+        return Err(Error::invalid(
+            "targets",
+            format!("a group launches at most {MAX_GROUP_TARGETS} targets"),
+        )
+        .into());
+    }
+
+    let planned = plan(&params.targets, params.port)?;
+    // Fail before building anything if another process holds one of the group's ports
+    for target in &planned {
+        check_ports_available(target.params.port, *target.params.instance_count).await?;
+    }
+
+    let mut members = Vec::with_capacity(planned.len());
+    for target in planned {
+        let (launch, error) = match launch(&target, &ctx.roots).await {
+            Ok(launch) => (Some(launch), None),
+            Err(report) => (None, Some(report.current_context().to_string())),
+        };
+        members.push(GroupMember {
+            target_name: target.params.target_name,
+            target_type: target.target_type.to_string(),
+            ports: launch.as_ref().map_or(target.ports, |launch| {
+                launch.instances().iter().map(|i| i.port).collect()
+            }),
+            launch: launch.map(|launch| serde_json::to_value(launch).unwrap_or(Value::Null)),
+            error,
+            not_ready: Vec::new(),
+        });
+    }
+
+    let launched: Vec<&GroupMember> = members.iter().filter(|m| m.error.is_none()).collect();
+    let launched_count = launched.len();
+    let instance_count = launched.iter().map(|m| m.ports.len()).sum();
+
+    let ready_count = if params.wait_ready.unwrap_or(true) && launched_count > 0 {
+        let timeout = params.ready_timeout_ms.unwrap_or(DEFAULT_READY_TIMEOUT_MS);
+        let requested = Instant::now() + Duration::from_millis(u64::from(timeout));
+        let deadline =
+            call_deadline.map_or(requested, |call_deadline| requested.min(call_deadline));
+        let not_ready = wait_until_ready(&members, deadline).await;
+        for (member, not_ready) in members.iter_mut().zip(not_ready) {
+            member.not_ready = not_ready;
+        }
+        let not_ready_count: usize = members.iter().map(|m| m.not_ready.len()).sum();
+        Some(instance_count - not_ready_count)
+    } else {
+        None
+    };
+
+    Ok(LaunchGroupResult::new(
+        members,
+        params.targets.len(),
+        launched_count,
+        instance_count,
+        ready_count,
+    ))
+}
+
+/// Decide every target's ports, failing if two targets would share one
+fn plan(targets: &[GroupTarget], base_port: Port) -> Result<Vec<PlannedTarget>> {
+    let mut next_port = u32::from(*base_port);
+    let mut owners: BTreeMap<u16, &str> = BTreeMap::new();
+    let mut planned = Vec::with_capacity(targets.len());
+
+    for target in targets {
+        let spec = TargetSpec::parse(&target.target_name);
+        // A target spec naming examples or binaries decides the target type itself
+        let target_type = spec.target_type.unwrap_or(if target.example {
+            TargetType::Example
+        } else {
+            TargetType::App
+        });
+
+        let first = target.port.map_or(next_port, |port| u32::from(*port));
+        let count = u32::try_from(*target.instance_count).unwrap_or(u32::MAX);
+        let last = first.saturating_add(count) - 1;
+        if last > u32::from(MAX_VALID_PORT) {
+            return Err(Error::invalid(
+                "targets",
+                format!(
+                    "`{}` needs ports {first}-{last}, past the highest valid port \
+                     {MAX_VALID_PORT}",
+                    target.target_name
+                ),
+            )
+            .into());
+        }
+        next_port = last + 1;
+
+        let ports: Vec<u16> = (first..=last)
+            .filter_map(|p| u16::try_from(p).ok())
+            .collect();
+        for &port in &ports {
+            if let Some(owner) = owners.insert(port, &target.target_name) {
+                return Err(Error::invalid(
+                    "targets",
+                    format!(
+                        "`{}` and `{owner}` would both use port {port}",
+                        target.target_name
+                    ),
+                )
+                .into());
+            }
+        }
+
+        let params = LaunchParams {
+            target_name:    spec.name,
+            target_type:    spec.target_type,
+            profile:        target
+                .profile
+                .as_ref()
+                .map_or(DEFAULT_PROFILE, BuildProfile::as_str)
+                .to_string(),
+            path:           target.path.clone(),
+            package:        target.package.clone().or(spec.package),
+            port:           Port(ports.first().copied().unwrap_or(*base_port)),
+            instance_count: target.instance_count,
+            stagger_ms:     0,
+            instances:      target.instances.clone().unwrap_or_default(),
+            features:       target.features.clone(),
+            fast_build:     target.fast_build,
+            output:         target.output,
+            remote_host:    None,
+            debugger:       None,
+            network_proxy:  None,
+        };
+        planned.push(PlannedTarget {
+            target_type,
+            params,
+            ports,
+        });
+    }
+    Ok(planned)
+}
+
+/// Build and launch `target`
+async fn launch(target: &PlannedTarget, roots: &[PathBuf]) -> Result<LaunchResult> {
+    match target.target_type {
+        TargetType::Example => {
+            let config = LaunchConfig::<Example>::from_params(&target.params);
+            launch_target(&config, roots).await
+        },
+        TargetType::App => {
+            let config = LaunchConfig::<App>::from_params(&target.params);
+            launch_target(&config, roots).await
+        },
+    }
+}
+
+/// Wait for every launched instance to answer BRP, returning each target's ports that didn't
+async fn wait_until_ready(members: &[GroupMember], deadline: Instant) -> Vec<Vec<u16>> {
+    let probes = members
+        .iter()
+        .enumerate()
+        .filter(|(_, member)| member.error.is_none())
+        .flat_map(|(index, member)| {
+            member.ports.iter().map(move |&port| async move {
+                (index, port, answers_brp_by(Port(port), deadline).await)
+            })
+        });
+    let mut not_ready = vec![Vec::new(); members.len()];
+    for (index, port, ready) in futures::future::join_all(probes).await {
+        if !ready && let Some(ports) = not_ready.get_mut(index) {
+            ports.push(port);
+        }
+    }
+    not_ready
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn targets(value: Value) -> Vec<GroupTarget> {
+        serde_json::from_value(value).unwrap_or_default()
+    }
+
+    #[test]
+    fn targets_without_a_port_follow_the_previous_target() {
+        let targets = targets(json!([
+            {"target_name": "server", "port": 15710},
+            {"target_name": "client", "example": true, "instance_count": 2},
+            {"target_name": "my_pkg/examples/bot"},
+        ]));
+        let planned = plan(&targets, Port(15702)).unwrap_or_default();
+
+        let ports: Vec<&[u16]> = planned.iter().map(|t| t.ports.as_slice()).collect();
+        assert_eq!(ports, [&[15710][..], &[15711, 15712], &[15713]]);
+        let types: Vec<TargetType> = planned.iter().map(|t| t.target_type).collect();
+        assert_eq!(
+            types,
+            [TargetType::App, TargetType::Example, TargetType::Example]
+        );
+    }
+
+    #[test]
+    fn rejects_targets_sharing_a_port() {
+        let targets = targets(json!([
+            {"target_name": "server", "instance_count": 2},
+            {"target_name": "client", "port": 15703},
+        ]));
+        assert!(plan(&targets, Port(15702)).is_err());
+    }
+}
@@ -3,12 +3,12 @@ use bevy_brp_mcp_macros::ToolFn;
 use serde::Deserialize;
 use serde::Serialize;
 
+use super::list_params::ListTargetsParams;
 use super::support;
 use super::support::BevyAppsStrategy;
 use crate::error::Result;
 use crate::tool::HandlerContext;
 use crate::tool::HandlerResult;
-use crate::tool::NoParams;
 use crate::tool::ToolFn;
 use crate::tool::ToolResult;
 
@@ -27,12 +27,16 @@ pub struct ListBevyAppsResult {
 }
 
 #[derive(ToolFn)]
-#[tool_fn(params = "NoParams", output = "ListBevyAppsResult", with_context)]
+#[tool_fn(
+    params = "ListTargetsParams",
+    output = "ListBevyAppsResult",
+    with_context
+)]
 pub struct ListBevyApps;
 
 #[allow(clippy::unused_async)]
-async fn handle_impl(ctx: HandlerContext, _params: NoParams) -> Result<ListBevyAppsResult> {
+async fn handle_impl(ctx: HandlerContext, params: ListTargetsParams) -> Result<ListBevyAppsResult> {
     let search_paths = &ctx.roots;
-    let items = support::collect_all_items(search_paths, &BevyAppsStrategy);
+    let items = support::collect_all_items(search_paths, &BevyAppsStrategy, &params);
     Ok(ListBevyAppsResult::new(items.len(), items))
 }
@@ -3,12 +3,12 @@ use bevy_brp_mcp_macros::ToolFn;
 use serde::Deserialize;
 use serde::Serialize;
 
+use super::list_params::ListTargetsParams;
 use super::support;
 use super::support::BevyExamplesStrategy;
 use crate::error::Result;
 use crate::tool::HandlerContext;
 use crate::tool::HandlerResult;
-use crate::tool::NoParams;
 use crate::tool::ToolFn;
 use crate::tool::ToolResult;
 
@@ -27,12 +27,19 @@ pub struct ListBevyExamplesResult {
 }
 
 #[derive(ToolFn)]
-#[tool_fn(params = "NoParams", output = "ListBevyExamplesResult", with_context)]
+#[tool_fn(
+    params = "ListTargetsParams",
+    output = "ListBevyExamplesResult",
+    with_context
+)]
 pub struct ListBevyExamples;
 
 #[allow(clippy::unused_async)]
-async fn handle_impl(ctx: HandlerContext, _params: NoParams) -> Result<ListBevyExamplesResult> {
+async fn handle_impl(
+    ctx: HandlerContext,
+    params: ListTargetsParams,
+) -> Result<ListBevyExamplesResult> {
     let search_paths = &ctx.roots;
-    let items = support::collect_all_items(search_paths, &BevyExamplesStrategy);
+    let items = support::collect_all_items(search_paths, &BevyExamplesStrategy, &params);
     Ok(ListBevyExamplesResult::new(items.len(), items))
 }
@@ -0,0 +1,39 @@
+use bevy_brp_mcp_macros::ResultStruct;
+use bevy_brp_mcp_macros::ToolFn;
+use serde::Deserialize;
+use serde::Serialize;
+
+use super::support::instance_registry;
+use super::support::instance_registry::RegisteredInstance;
+use crate::error::Result;
+use crate::session_tools::control_lock;
+use crate::tool::HandlerContext;
+use crate::tool::HandlerResult;
+use crate::tool::NoParams;
+use crate::tool::ToolFn;
+use crate::tool::ToolResult;
+
+/// Result from listing launched instances
+#[derive(Debug, Clone, Serialize, Deserialize, ResultStruct)]
+pub struct ListInstancesResult {
+    /// Count of running instances
+    #[to_metadata]
+    count:            usize,
+    /// Running instances launched by this server, including before a restart
+    #[to_result]
+    instances:        Vec<RegisteredInstance>,
+    /// Message template for formatting responses
+    #[to_message(message_template = "Found {count} running launched instances")]
+    message_template: String,
+}
+
+#[derive(ToolFn)]
+#[tool_fn(params = "NoParams", output = "ListInstancesResult")]
+pub struct ListInstances;
+
+#[allow(clippy::unused_async)]
+async fn handle_impl(_params: NoParams) -> Result<ListInstancesResult> {
+    control_lock::prune_expired();
+    let instances = instance_registry::live_instances();
+    Ok(ListInstancesResult::new(instances.len(), instances))
+}
@@ -0,0 +1,74 @@
+//! `brp_network_proxy_configure` tool - Change a running proxy's network conditions
+//!
+//! Conditions apply to the next packet forwarded, so a test can start on a clean connection,
+//! degrade it mid-session and watch the game recover. Conditions left out keep their values; a
+//! call without any reports the proxy's current conditions and counts.
+
+use bevy_brp_mcp_macros::ParamStruct;
+use bevy_brp_mcp_macros::ResultStruct;
+use bevy_brp_mcp_macros::ToolFn;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+
+use super::support::network_proxy;
+use super::support::network_proxy::ProxyInfo;
+use crate::error::Result;
+use crate::tool::HandlerContext;
+use crate::tool::HandlerResult;
+use crate::tool::ToolFn;
+use crate::tool::ToolResult;
+
+#[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
+pub struct NetworkProxyConfigureParams {
+    /// Port the proxy listens on, as reported when it was started
+    pub listen_port:  u16,
+    /// Delay added to every packet in milliseconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latency_ms:   Option<u32>,
+    /// Further random delay of up to this many milliseconds per packet
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jitter_ms:    Option<u32>,
+    /// Percentage of UDP packets dropped, 0-100
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub loss_percent: Option<f64>,
+}
+
+/// Result from configuring a network proxy
+#[derive(Debug, Clone, Serialize, Deserialize, ResultStruct)]
+pub struct NetworkProxyConfigureResult {
+    /// The proxy with its new conditions
+    #[to_result]
+    proxy:            ProxyInfo,
+    /// Port the proxy listens on
+    #[to_metadata]
+    listen_port:      u16,
+    /// Message template for formatting responses
+    #[to_message(
+        message_template = "Updated network conditions of the proxy on port {listen_port}"
+    )]
+    message_template: String,
+}
+
+#[derive(ToolFn)]
+#[tool_fn(
+    params = "NetworkProxyConfigureParams",
+    output = "NetworkProxyConfigureResult"
+)]
+pub struct NetworkProxyConfigure;
+
+#[allow(clippy::unused_async)]
+async fn handle_impl(params: NetworkProxyConfigureParams) -> Result<NetworkProxyConfigureResult> {
+    let proxy = network_proxy::configure_proxy(params.listen_port, |conditions| {
+        if let Some(latency_ms) = params.latency_ms {
+            conditions.latency_ms = latency_ms;
+        }
+        if let Some(jitter_ms) = params.jitter_ms {
+            conditions.jitter_ms = jitter_ms;
+        }
+        if let Some(loss_percent) = params.loss_percent {
+            conditions.loss_percent = loss_percent;
+        }
+    })?;
+    Ok(NetworkProxyConfigureResult::new(proxy, params.listen_port))
+}
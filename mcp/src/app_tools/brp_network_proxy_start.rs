@@ -0,0 +1,79 @@
+//! `brp_network_proxy_start` tool - Simulate a bad network between already running instances
+//!
+//! The launch tools' `network_proxy` parameter starts a proxy with the instances, so they are
+//! launched knowing its address. This tool starts one on its own, for instances launched earlier
+//! or by hand - they must be told to connect to its `listen_addr` some other way, e.g. through a
+//! `--connect` argument on relaunch.
+
+use bevy_brp_mcp_macros::ParamStruct;
+use bevy_brp_mcp_macros::ResultStruct;
+use bevy_brp_mcp_macros::ToolFn;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+
+use super::support::network_proxy;
+use super::support::network_proxy::NetworkConditions;
+use super::support::network_proxy::NetworkProxyOptions;
+use super::support::network_proxy::ProxyInfo;
+use super::support::network_proxy::ProxyProtocol;
+use crate::error::Result;
+use crate::tool::HandlerContext;
+use crate::tool::HandlerResult;
+use crate::tool::ToolFn;
+use crate::tool::ToolResult;
+
+#[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
+pub struct NetworkProxyStartParams {
+    /// Port the server instance's game networking listens on - the proxy forwards to it
+    pub target_port:  u16,
+    /// Port for the proxy to listen on (default: any free port)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub listen_port:  Option<u16>,
+    /// Transport to forward: `udp` or `tcp` (default: udp)
+    #[serde(default)]
+    pub protocol:     ProxyProtocol,
+    /// Delay added to every packet in milliseconds (default: 0)
+    #[serde(default)]
+    pub latency_ms:   u32,
+    /// Further random delay of up to this many milliseconds per packet (default: 0)
+    #[serde(default)]
+    pub jitter_ms:    u32,
+    /// Percentage of UDP packets dropped, 0-100 (default: 0)
+    #[serde(default)]
+    pub loss_percent: f64,
+}
+
+/// Result from starting a network proxy
+#[derive(Debug, Clone, Serialize, Deserialize, ResultStruct)]
+pub struct NetworkProxyStartResult {
+    /// The started proxy
+    #[to_result]
+    proxy:            ProxyInfo,
+    /// Address clients connect to instead of the server
+    #[to_metadata]
+    listen_addr:      String,
+    /// Message template for formatting responses
+    #[to_message(message_template = "Network proxy listening on {listen_addr}")]
+    message_template: String,
+}
+
+#[derive(ToolFn)]
+#[tool_fn(params = "NetworkProxyStartParams", output = "NetworkProxyStartResult")]
+pub struct NetworkProxyStart;
+
+async fn handle_impl(params: NetworkProxyStartParams) -> Result<NetworkProxyStartResult> {
+    let proxy = network_proxy::start_proxy(&NetworkProxyOptions {
+        target_port: params.target_port,
+        listen_port: params.listen_port,
+        protocol:    params.protocol,
+        conditions:  NetworkConditions {
+            latency_ms:   params.latency_ms,
+            jitter_ms:    params.jitter_ms,
+            loss_percent: params.loss_percent,
+        },
+    })
+    .await?;
+    let listen_addr = proxy.listen_addr.clone();
+    Ok(NetworkProxyStartResult::new(proxy, listen_addr))
+}
@@ -0,0 +1,51 @@
+//! `brp_network_proxy_stop` tool - Stop network proxies and report what they forwarded
+//!
+//! Proxies outlive the instances they were started for, so a test tears them down here. The
+//! final counts tell a test how many packets its instances actually lost.
+
+use bevy_brp_mcp_macros::ParamStruct;
+use bevy_brp_mcp_macros::ResultStruct;
+use bevy_brp_mcp_macros::ToolFn;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+
+use super::support::network_proxy;
+use super::support::network_proxy::ProxyInfo;
+use crate::error::Result;
+use crate::tool::HandlerContext;
+use crate::tool::HandlerResult;
+use crate::tool::ToolFn;
+use crate::tool::ToolResult;
+
+#[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
+pub struct NetworkProxyStopParams {
+    /// Port of the proxy to stop (default: stop every proxy)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub listen_port: Option<u16>,
+}
+
+/// Result from stopping network proxies
+#[derive(Debug, Clone, Serialize, Deserialize, ResultStruct)]
+pub struct NetworkProxyStopResult {
+    /// The stopped proxies with their final counts
+    #[to_result]
+    proxies:          Vec<ProxyInfo>,
+    /// Number of proxies stopped
+    #[to_metadata]
+    #[computed(from = "proxies", operation = "count")]
+    count:            usize,
+    /// Message template for formatting responses
+    #[to_message(message_template = "Stopped {count} network proxies")]
+    message_template: String,
+}
+
+#[derive(ToolFn)]
+#[tool_fn(params = "NetworkProxyStopParams", output = "NetworkProxyStopResult")]
+pub struct NetworkProxyStop;
+
+#[allow(clippy::unused_async)]
+async fn handle_impl(params: NetworkProxyStopParams) -> Result<NetworkProxyStopResult> {
+    let proxies = network_proxy::stop_proxies(params.listen_port)?;
+    Ok(NetworkProxyStopResult::new(proxies))
+}
@@ -0,0 +1,286 @@
+//! `brp_new_bevy_example` tool - Scaffold a minimal Bevy app with `BrpExtrasPlugin`
+//!
+//! When the user's own app doesn't build or doesn't exist yet, an agent still needs a live world
+//! to try BRP calls against. This tool writes a small standalone package - a camera, a light and
+//! a spinning cube with a reflected `Spin` component - into the first workspace root, builds it,
+//! and checks that the launcher finds it, so `brp_launch_bevy_app` can start it right away.
+
+use std::fs;
+use std::path::Component;
+use std::path::Path;
+use std::path::PathBuf;
+
+use bevy_brp_mcp_macros::ParamStruct;
+use bevy_brp_mcp_macros::ResultStruct;
+use bevy_brp_mcp_macros::ToolFn;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+use serde_json::json;
+
+use super::constants::DEFAULT_PROFILE;
+use super::launch_params::BuildProfile;
+use super::support::TargetType;
+use super::support::find_all_targets_by_name;
+use super::support::run_cargo_build;
+use crate::error::Error;
+use crate::error::Result;
+use crate::tool::HandlerContext;
+use crate::tool::HandlerResult;
+use crate::tool::ToolFn;
+use crate::tool::ToolResult;
+
+/// Version requirement for `bevy` and `bevy_brp_extras` in the scaffold - this server's minor
+/// version follows Bevy's, as `bevy_brp_extras` does
+const BEVY_VERSION: &str = concat!("0.", env!("CARGO_PKG_VERSION_MINOR"));
+
+/// `Cargo.toml` of the scaffold - `{name}` and `{bevy_version}` are filled in
+///
+/// The empty `[workspace]` keeps the package out of any workspace it is created inside.
+const CARGO_TOML_TEMPLATE: &str = r#"[package]
+edition = "2024"
+name    = "{name}"
+publish = false
+version = "0.1.0"
+
+# Standalone - not a member of an enclosing workspace
+[workspace]
+
+[dependencies]
+bevy            = { version = "{bevy_version}", features = ["bevy_remote"] }
+bevy_brp_extras = "{bevy_version}"
+"#;
+
+/// `src/main.rs` of the scaffold
+const MAIN_RS: &str = r#"//! Minimal Bevy app with `BrpExtrasPlugin` serving the Bevy Remote Protocol
+
+use bevy::prelude::*;
+use bevy_brp_extras::BrpExtrasPlugin;
+
+/// Spins its entity around the Y axis - a reflected component to inspect and mutate over BRP
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct Spin {
+    /// Radians per second
+    speed: f32,
+}
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, BrpExtrasPlugin))
+        .register_type::<Spin>()
+        .add_systems(Startup, setup)
+        .add_systems(Update, spin)
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.spawn((
+        Name::new("Camera"),
+        Camera3d::default(),
+        Transform::from_xyz(0.0, 2.5, 6.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+    commands.spawn((
+        Name::new("Light"),
+        DirectionalLight::default(),
+        Transform::from_xyz(3.0, 6.0, 3.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+    commands.spawn((
+        Name::new("Cube"),
+        Mesh3d(meshes.add(Cuboid::default())),
+        MeshMaterial3d(materials.add(Color::srgb(0.3, 0.5, 0.9))),
+        Transform::default(),
+        Spin { speed: 1.0 },
+    ));
+}
+
+fn spin(time: Res<Time>, mut spinners: Query<(&Spin, &mut Transform)>) {
+    for (spin, mut transform) in &mut spinners {
+        transform.rotate_y(spin.speed * time.delta_secs());
+    }
+}
+"#;
+
+/// `.gitignore` of the scaffold
+const GITIGNORE: &str = "/target\n";
+
+#[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
+pub struct NewBevyExampleParams {
+    /// Package and binary name - letters, digits, `-` and `_`, starting with a letter
+    #[param(regex = r"^[A-Za-z][A-Za-z0-9_-]*$")]
+    pub name:    String,
+    /// Directory to create, relative to the first workspace root (default: `name`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path:    Option<String>,
+    /// Build the app after creating it (default: true)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(extend("default" = true))]
+    pub build:   Option<bool>,
+    /// Build profile to use (debug or release)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(extend("default" = DEFAULT_PROFILE))]
+    pub profile: Option<BuildProfile>,
+}
+
+/// Result from scaffolding an app
+#[derive(Debug, Clone, Serialize, Deserialize, ResultStruct)]
+pub struct NewBevyExampleResult {
+    /// Parameters for `brp_launch_bevy_app` that start the new app
+    #[to_result]
+    launch:           Value,
+    /// Name of the new app
+    #[to_metadata]
+    target_name:      String,
+    /// Directory the app was created in
+    #[to_metadata]
+    directory:        String,
+    /// Files written, relative to `directory`
+    #[to_metadata]
+    files:            Vec<String>,
+    /// Whether the app was built
+    #[to_metadata]
+    built:            bool,
+    /// Whether the launcher finds the app in the workspace roots
+    #[to_metadata]
+    registered:       bool,
+    /// Message template for formatting responses
+    #[to_message(
+        message_template = "Created Bevy app {target_name} in {directory} - start it with brp_launch_bevy_app"
+    )]
+    message_template: String,
+}
+
+#[derive(ToolFn)]
+#[tool_fn(
+    params = "NewBevyExampleParams",
+    output = "NewBevyExampleResult",
+    with_context
+)]
+pub struct NewBevyExample;
+
+async fn handle_impl(
+    ctx: HandlerContext,
+    params: NewBevyExampleParams,
+) -> Result<NewBevyExampleResult> {
+    let Some(root) = ctx.roots.first() else {
+        return Err(Error::InvalidState(
+            "No workspace root to create the app in - the MCP client provided no roots".to_string(),
+        )
+        .into());
+    };
+    let relative = params.path.clone().unwrap_or_else(|| params.name.clone());
+    validate_relative_path(&relative)?;
+    let directory = root.join(&relative);
+    if fs::read_dir(&directory).is_ok_and(|mut entries| entries.next().is_some()) {
+        return Err(Error::invalid(
+            "path",
+            format!("'{}' already exists and isn't empty", directory.display()),
+        )
+        .into());
+    }
+
+    let files = write_scaffold(&directory, &params.name)?;
+
+    let build = params.build.unwrap_or(true);
+    let profile = params
+        .profile
+        .as_ref()
+        .map_or(DEFAULT_PROFILE, BuildProfile::as_str);
+    if build {
+        run_cargo_build(
+            &params.name,
+            TargetType::App,
+            profile,
+            &directory,
+            None,
+            None,
+            false,
+        )
+        .await?;
+    }
+    let name = params.name.clone();
+    let roots = ctx.roots.clone();
+    let registered = tokio::task::spawn_blocking(move || {
+        !find_all_targets_by_name(&name, Some(TargetType::App), &roots).is_empty()
+    })
+    .await
+    .map_err(|e| Error::ProcessManagement(format!("Build task failed: {e}")))?;
+
+    let launch = json!({ "target_name": params.name, "path": relative });
+    Ok(NewBevyExampleResult::new(
+        launch,
+        params.name,
+        directory.display().to_string(),
+        files,
+        build,
+        registered,
+    ))
+}
+
+/// Write the scaffold's files into `directory`, returning their relative paths
+#[allow(clippy::literal_string_with_formatting_args)]
+fn write_scaffold(directory: &Path, name: &str) -> Result<Vec<String>> {
+    let cargo_toml = CARGO_TOML_TEMPLATE
+        .replace("{name}", name)
+        .replace("{bevy_version}", BEVY_VERSION);
+    let files = [
+        ("Cargo.toml", cargo_toml.as_str()),
+        (".gitignore", GITIGNORE),
+        ("src/main.rs", MAIN_RS),
+    ];
+
+    fs::create_dir_all(directory.join("src")).map_err(|e| {
+        Error::FileOperation(format!("Failed to create '{}': {e}", directory.display()))
+    })?;
+    for (file, contents) in files {
+        let path = directory.join(file);
+        fs::write(&path, contents).map_err(|e| {
+            Error::FileOperation(format!("Failed to write '{}': {e}", path.display()))
+        })?;
+    }
+    Ok(files.iter().map(|(file, _)| (*file).to_string()).collect())
+}
+
+/// The app must land inside the workspace root - no absolute paths or `..`
+fn validate_relative_path(path: &str) -> Result<()> {
+    let inside = PathBuf::from(path)
+        .components()
+        .all(|component| matches!(component, Component::Normal(_) | Component::CurDir));
+    if inside && !path.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::invalid(
+            "path",
+            format!("'{path}' must be a relative path inside the workspace root"),
+        )
+        .into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tool::ParamStruct as _;
+
+    fn named(name: &str) -> NewBevyExampleParams {
+        serde_json::from_value(json!({ "name": name }))
+            .unwrap_or_else(|e| unreachable!("params should deserialize: {e}"))
+    }
+
+    #[test]
+    fn rejects_names_and_paths_cargo_or_the_workspace_would_not_take() {
+        assert!(named("brp_sandbox-2").validate().is_ok());
+        assert!(named("2d_sandbox").validate().is_err());
+        assert!(named("my sandbox").validate().is_err());
+
+        assert!(validate_relative_path("sandboxes/first").is_ok());
+        assert!(validate_relative_path("../outside").is_err());
+        assert!(validate_relative_path("/tmp/sandbox").is_err());
+        assert!(validate_relative_path("").is_err());
+    }
+}
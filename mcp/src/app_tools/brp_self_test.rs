@@ -0,0 +1,352 @@
+//! `brp_self_test` tool - Readiness report for the MCP server's own subsystems
+//!
+//! Setup problems - an unwritable temp directory, `cargo` missing from the server's `PATH`, a
+//! sandbox that hides the socket table, a client without roots - otherwise surface halfway
+//! through the first launch or watch as an error about something else. This tool checks each
+//! subsystem directly and reports what failed and how to fix it, without touching any app.
+
+use std::fs;
+use std::net::Ipv4Addr;
+use std::net::TcpListener;
+use std::path::Path;
+use std::process::Command;
+use std::time::Duration;
+
+use bevy_brp_mcp_macros::ResultStruct;
+use bevy_brp_mcp_macros::ToolFn;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+use strum::Display;
+
+use crate::app_tools::support::get_pid_for_port;
+use crate::brp_tools::Port;
+use crate::error::Result;
+use crate::log_tools::get_log_directory;
+use crate::mcp_service::RootsSupport;
+use crate::tool::HandlerContext;
+use crate::tool::HandlerResult;
+use crate::tool::NoParams;
+use crate::tool::ToolFn;
+use crate::tool::ToolResult;
+
+/// How long `cargo --version` may take before cargo is reported as unavailable
+const CARGO_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Hint for checks that fail because local networking is unavailable
+const NETWORKING_HINT: &str = "The MCP server needs local networking to reach BRP - check \
+                               sandbox or firewall rules for the process running it";
+
+/// A subsystem the self-test checks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Display)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum SelfTestCheck {
+    /// Files can be written to the temp directory (large responses, archives, caches)
+    TempDir,
+    /// The log directory exists or can be created, and is writable
+    LogDir,
+    /// `cargo` runs, for builds and launches
+    Cargo,
+    /// Local ports can be bound and their owning process found, for port checks and PID lookups
+    PortScanning,
+    /// The client supports MCP roots, which bound app and example discovery
+    Roots,
+}
+
+/// Outcome of a single check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Passed,
+    Failed,
+    /// Not fatal, but some tools will be limited
+    Warning,
+}
+
+/// Report for a single check
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CheckReport {
+    check:  SelfTestCheck,
+    status: CheckStatus,
+    detail: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hint:   Option<String>,
+}
+
+impl CheckReport {
+    fn passed(check: SelfTestCheck, detail: impl Into<String>) -> Self {
+        Self {
+            check,
+            status: CheckStatus::Passed,
+            detail: detail.into(),
+            hint: None,
+        }
+    }
+
+    fn failed(check: SelfTestCheck, detail: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            check,
+            status: CheckStatus::Failed,
+            detail: detail.into(),
+            hint: Some(hint.into()),
+        }
+    }
+
+    fn warning(check: SelfTestCheck, detail: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            check,
+            status: CheckStatus::Warning,
+            detail: detail.into(),
+            hint: Some(hint.into()),
+        }
+    }
+}
+
+/// Result from running the self-test
+#[derive(Debug, Clone, Serialize, Deserialize, ResultStruct)]
+pub struct SelfTestResult {
+    /// True if no check failed
+    #[to_metadata]
+    ready:            bool,
+    /// Checks that failed
+    #[to_metadata]
+    failed:           Vec<SelfTestCheck>,
+    /// Checks that passed with a warning
+    #[to_metadata]
+    warnings:         Vec<SelfTestCheck>,
+    /// Per-check reports in the order they were run
+    #[to_result]
+    checks:           Vec<CheckReport>,
+    /// Message template for formatting responses
+    #[to_message]
+    message_template: Option<String>,
+}
+
+#[derive(ToolFn)]
+#[tool_fn(params = "NoParams", output = "SelfTestResult", with_context)]
+pub struct SelfTest;
+
+async fn handle_impl(ctx: HandlerContext, _params: NoParams) -> Result<SelfTestResult> {
+    let checks = vec![
+        check_temp_dir(),
+        check_log_dir(),
+        check_cargo().await,
+        check_port_scanning(),
+        check_roots(ctx.roots_support()),
+    ];
+
+    let with_status = |status| {
+        checks
+            .iter()
+            .filter(|report| report.status == status)
+            .map(|report| report.check)
+            .collect::<Vec<_>>()
+    };
+    let failed = with_status(CheckStatus::Failed);
+    let warnings = with_status(CheckStatus::Warning);
+
+    let message = match (failed.as_slice(), warnings.len()) {
+        ([], 0) => "MCP server is ready: all checks passed".to_string(),
+        ([], warnings) => format!("MCP server is ready with {warnings} warning(s)"),
+        (failed, _) => format!(
+            "MCP server is not ready: {} failed",
+            failed
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    };
+
+    Ok(
+        SelfTestResult::new(failed.is_empty(), failed, warnings, checks)
+            .with_message_template(message),
+    )
+}
+
+/// Write, read back and remove a probe file in `dir`
+fn probe_writable(dir: &Path) -> std::io::Result<()> {
+    let probe = dir.join(format!("bevy_brp_mcp_self_test_{}.tmp", std::process::id()));
+    let contents = b"bevy_brp_mcp self-test";
+    fs::write(&probe, contents)?;
+    let read_back = fs::read(&probe);
+    fs::remove_file(&probe)?;
+    if read_back? == contents {
+        Ok(())
+    } else {
+        Err(std::io::Error::other(
+            "probe file read back different contents",
+        ))
+    }
+}
+
+fn check_temp_dir() -> CheckReport {
+    let dir = std::env::temp_dir();
+    match probe_writable(&dir) {
+        Ok(()) => CheckReport::passed(
+            SelfTestCheck::TempDir,
+            format!("{} is writable", dir.display()),
+        ),
+        Err(e) => CheckReport::failed(
+            SelfTestCheck::TempDir,
+            format!("cannot write to {}: {e}", dir.display()),
+            "Point TMPDIR (TEMP on Windows) at a writable directory in the environment that starts \
+             the MCP server",
+        ),
+    }
+}
+
+fn check_log_dir() -> CheckReport {
+    let dir = get_log_directory();
+    match fs::create_dir_all(&dir).and_then(|()| probe_writable(&dir)) {
+        Ok(()) => CheckReport::passed(
+            SelfTestCheck::LogDir,
+            format!("{} exists and is writable", dir.display()),
+        ),
+        Err(e) => CheckReport::failed(
+            SelfTestCheck::LogDir,
+            format!("cannot create or write to {}: {e}", dir.display()),
+            "Launch logs, watch logs and traces are written here - make the directory writable \
+             for the user running the MCP server",
+        ),
+    }
+}
+
+async fn check_cargo() -> CheckReport {
+    let run = tokio::task::spawn_blocking(|| Command::new("cargo").arg("--version").output());
+    let hint = "Install Rust with rustup, and make sure `cargo` is on the PATH of the process that \
+                starts the MCP server (GUI clients often don't see the shell's PATH)";
+    match tokio::time::timeout(CARGO_TIMEOUT, run).await {
+        Ok(Ok(Ok(output))) if output.status.success() => CheckReport::passed(
+            SelfTestCheck::Cargo,
+            String::from_utf8_lossy(&output.stdout).trim().to_string(),
+        ),
+        Ok(Ok(Ok(output))) => CheckReport::failed(
+            SelfTestCheck::Cargo,
+            format!(
+                "`cargo --version` exited with {}: {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ),
+            hint,
+        ),
+        Ok(Ok(Err(e))) => CheckReport::failed(
+            SelfTestCheck::Cargo,
+            format!("cannot run `cargo`: {e}"),
+            hint,
+        ),
+        Ok(Err(e)) => CheckReport::failed(
+            SelfTestCheck::Cargo,
+            format!("`cargo --version` task failed: {e}"),
+            hint,
+        ),
+        Err(_) => CheckReport::failed(
+            SelfTestCheck::Cargo,
+            format!(
+                "`cargo --version` didn't finish within {}s",
+                CARGO_TIMEOUT.as_secs()
+            ),
+            hint,
+        ),
+    }
+}
+
+/// Bind a loopback port and look it up in the socket table, the way port-conflict checks and
+/// `brp_shutdown` find the process behind a port
+fn check_port_scanning() -> CheckReport {
+    let listener = match TcpListener::bind((Ipv4Addr::LOCALHOST, 0)) {
+        Ok(listener) => listener,
+        Err(e) => {
+            return CheckReport::failed(
+                SelfTestCheck::PortScanning,
+                format!("cannot bind a loopback port: {e}"),
+                NETWORKING_HINT,
+            );
+        },
+    };
+    let port = match listener.local_addr() {
+        Ok(address) => Port(address.port()),
+        Err(e) => {
+            return CheckReport::failed(
+                SelfTestCheck::PortScanning,
+                format!("cannot read the bound loopback port: {e}"),
+                NETWORKING_HINT,
+            );
+        },
+    };
+
+    let own_pid = std::process::id();
+    match get_pid_for_port(port) {
+        Some(pid) if pid == own_pid => CheckReport::passed(
+            SelfTestCheck::PortScanning,
+            format!("bound port {port} and found this process (PID {own_pid}) listening on it"),
+        ),
+        Some(pid) => CheckReport::warning(
+            SelfTestCheck::PortScanning,
+            format!(
+                "bound port {port}, but the socket table reports PID {pid} instead of {own_pid}"
+            ),
+            "PIDs behind ports may be misreported, so port conflicts may name the wrong process",
+        ),
+        None => CheckReport::warning(
+            SelfTestCheck::PortScanning,
+            format!("bound port {port}, but it wasn't found in the socket table"),
+            "Port conflicts can still be detected, but not which process holds a port - on macOS \
+             and in containers, the server may lack permission to list sockets",
+        ),
+    }
+}
+
+fn check_roots(support: RootsSupport) -> CheckReport {
+    match support {
+        RootsSupport::Supported { roots } if roots > 0 => CheckReport::passed(
+            SelfTestCheck::Roots,
+            format!("the client lists {roots} root(s) to search for apps and examples"),
+        ),
+        RootsSupport::Supported { .. } => CheckReport::warning(
+            SelfTestCheck::Roots,
+            "the client supports roots but lists none",
+            "Apps and examples are only found under the client's roots - open a project folder or \
+             add one as a root in the client",
+        ),
+        RootsSupport::Unsupported => CheckReport::warning(
+            SelfTestCheck::Roots,
+            "the client doesn't support roots, so the server's current directory is searched",
+            "Start the MCP server from the project directory, or use a client that supports MCP \
+             roots",
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn probe_writable_leaves_nothing_behind() {
+        let dir = std::env::temp_dir().join(format!("bevy_brp_self_test_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap_or_else(|e| unreachable!("temp dir is writable: {e}"));
+
+        assert!(probe_writable(&dir).is_ok());
+        let leftovers = fs::read_dir(&dir).map(Iterator::count).unwrap_or_default();
+        assert_eq!(leftovers, 0);
+
+        fs::remove_dir(&dir).unwrap_or_else(|e| unreachable!("probe dir is empty: {e}"));
+        assert!(probe_writable(&dir).is_err());
+    }
+
+    #[test]
+    fn roots_warn_unless_the_client_lists_some() {
+        let status = |support| check_roots(support).status;
+        assert_eq!(
+            status(RootsSupport::Supported { roots: 2 }),
+            CheckStatus::Passed
+        );
+        assert_eq!(
+            status(RootsSupport::Supported { roots: 0 }),
+            CheckStatus::Warning
+        );
+        assert_eq!(status(RootsSupport::Unsupported), CheckStatus::Warning);
+    }
+}
@@ -0,0 +1,435 @@
+//! `brp_setup_check` tool - Check whether a target is set up for BRP and say how to fix it
+//!
+//! First-time setup is where most BRP sessions fail: the app runs, but nothing answers on the
+//! port because `RemotePlugin` was never added, `RemoteHttpPlugin` is missing, or
+//! `bevy_brp_extras` isn't a dependency. This tool reads the target's manifest through
+//! `cargo metadata` and scans its sources for the plugins - a heuristic that looks for the type
+//! names outside comments - then returns what is missing with the `cargo add` commands and code
+//! to paste. With `apply_dependencies: true` it runs the `cargo add` commands itself; source
+//! changes are always left to the caller.
+
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::Command;
+
+use bevy_brp_mcp_macros::ParamStruct;
+use bevy_brp_mcp_macros::ResultStruct;
+use bevy_brp_mcp_macros::ToolFn;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+
+use super::support::BevyTarget;
+use super::support::BrpManifestSetup;
+use super::support::CargoDetector;
+use super::support::TargetSpec;
+use super::support::TargetType;
+use super::support::find_required_target_with_path;
+use crate::error::Error;
+use crate::error::Result;
+use crate::tool::HandlerContext;
+use crate::tool::HandlerResult;
+use crate::tool::ToolFn;
+use crate::tool::ToolResult;
+
+/// `bevy_brp_extras` version used when the target's Bevy version is unknown - this server's
+/// minor version follows Bevy's, as `bevy_brp_extras` does
+const DEFAULT_EXTRAS_VERSION: &str = concat!("0.", env!("CARGO_PKG_VERSION_MINOR"));
+
+/// Plugin that adds `RemotePlugin`, `RemoteHttpPlugin` and the `brp_extras/` methods
+const EXTRAS_PLUGIN: &str = "BrpExtrasPlugin";
+
+/// Bevy's plugin serving BRP methods
+const REMOTE_PLUGIN: &str = "RemotePlugin";
+
+/// Bevy's plugin serving BRP over HTTP
+const REMOTE_HTTP_PLUGIN: &str = "RemoteHttpPlugin";
+
+/// Code adding `BrpExtrasPlugin` to an app
+const EXTRAS_PLUGIN_SNIPPET: &str = "use bevy_brp_extras::BrpExtrasPlugin;
+
+// In `main`, after `DefaultPlugins` - it adds `RemotePlugin` and `RemoteHttpPlugin` itself
+app.add_plugins(BrpExtrasPlugin);";
+
+/// Code adding the HTTP transport next to an existing `RemotePlugin`
+const REMOTE_HTTP_PLUGIN_SNIPPET: &str = "use bevy::remote::http::RemoteHttpPlugin;
+
+// Next to `RemotePlugin` - or replace both with `BrpExtrasPlugin`
+app.add_plugins(RemoteHttpPlugin::default());";
+
+#[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
+pub struct SetupCheckParams {
+    /// Name of the app (or example, with `example: true`) to check, or a target spec naming its
+    /// package: `my_pkg#demo`, `my_pkg/examples/demo` or `my_pkg/bin/demo`
+    pub target_name:        String,
+    /// Check an example rather than an app (default: false)
+    #[serde(default)]
+    pub example:            bool,
+    /// Path to use when multiple targets with the same name exist
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path:               Option<String>,
+    /// Package containing the target - an alternative to `path` when multiple targets with the
+    /// same name exist
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub package:            Option<String>,
+    /// Run the suggested `cargo add` commands, editing the target's `Cargo.toml` - nothing is
+    /// changed unless true (default: false)
+    #[serde(default)]
+    pub apply_dependencies: bool,
+}
+
+/// Where each BRP piece was found
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct SetupChecks {
+    /// The `bevy` dependency enables `bevy_remote` - `bevy_brp_extras` enables it too
+    pub bevy_remote_feature: bool,
+    /// The package depends on `bevy_brp_extras`
+    pub extras_dependency:   bool,
+    /// Source file using `BrpExtrasPlugin`
+    pub extras_plugin:       Option<String>,
+    /// Source file using `RemotePlugin`
+    pub remote_plugin:       Option<String>,
+    /// Source file using `RemoteHttpPlugin`
+    pub remote_http_plugin:  Option<String>,
+}
+
+/// What is missing and how to add it
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct SetupAdvice {
+    /// Problems keeping the app from serving BRP
+    issues:    Vec<String>,
+    /// Arguments of the `cargo add` runs that add missing dependencies
+    cargo_add: Vec<Vec<String>>,
+    /// Code to add to the app
+    snippets:  Vec<String>,
+}
+
+/// Result from checking a target's BRP setup
+#[derive(Debug, Clone, Serialize, Deserialize, ResultStruct)]
+pub struct SetupCheckResult {
+    /// Where each BRP piece was found
+    #[to_result]
+    checks:             SetupChecks,
+    /// Name of the checked target
+    #[to_metadata]
+    target_name:        String,
+    /// The target's `Cargo.toml`
+    #[to_metadata]
+    manifest_path:      String,
+    /// Whether the target looks ready to serve BRP
+    #[to_metadata]
+    ready:              bool,
+    /// Problems keeping the app from serving BRP
+    #[to_metadata]
+    issues:             Vec<String>,
+    /// Commands adding the missing dependencies
+    #[to_metadata]
+    cargo_add_commands: Vec<String>,
+    /// Code to add to the app's sources
+    #[to_metadata]
+    snippets:           Vec<String>,
+    /// Commands run because of `apply_dependencies`
+    #[to_metadata]
+    applied:            Vec<String>,
+    /// Message template for formatting responses
+    #[to_message]
+    message_template:   Option<String>,
+}
+
+#[derive(ToolFn)]
+#[tool_fn(params = "SetupCheckParams", output = "SetupCheckResult", with_context)]
+pub struct SetupCheck;
+
+async fn handle_impl(ctx: HandlerContext, params: SetupCheckParams) -> Result<SetupCheckResult> {
+    let roots = ctx.roots.clone();
+    tokio::task::spawn_blocking(move || check_setup(&params, &roots))
+        .await
+        .map_err(|e| Error::ProcessManagement(format!("Setup check task failed: {e}")))?
+}
+
+/// Find the target, inspect it, and run the `cargo add` commands when asked to
+fn check_setup(params: &SetupCheckParams, roots: &[PathBuf]) -> Result<SetupCheckResult> {
+    let spec = TargetSpec::parse(&params.target_name);
+    // A target spec naming examples or binaries decides the target type itself
+    let target_type = spec.target_type.unwrap_or(if params.example {
+        TargetType::Example
+    } else {
+        TargetType::App
+    });
+    let package = params.package.as_deref().or(spec.package.as_deref());
+    let target = find_required_target_with_path(
+        &spec.name,
+        target_type,
+        params.path.as_deref(),
+        package,
+        roots,
+        None,
+    )?;
+    let manifest_path = target.manifest_path.display().to_string();
+    let extras_version = target
+        .bevy_version
+        .as_deref()
+        .and_then(minor_version)
+        .unwrap_or_else(|| DEFAULT_EXTRAS_VERSION.to_string());
+
+    let (mut checks, mut advice) = inspect(&target, &extras_version)?;
+    let mut applied = Vec::new();
+    if params.apply_dependencies && !advice.cargo_add.is_empty() {
+        for args in &advice.cargo_add {
+            run_cargo_add(args)?;
+            applied.push(command_line(args));
+        }
+        // Check again so the report reflects the edited manifest
+        (checks, advice) = inspect(&target, &extras_version)?;
+    }
+
+    let ready = advice.issues.is_empty();
+    let message = if ready {
+        format!("{} is set up to serve BRP", spec.name)
+    } else {
+        format!(
+            "{} is not set up to serve BRP: {}",
+            spec.name,
+            advice.issues.join("; ")
+        )
+    };
+    Ok(SetupCheckResult::new(
+        checks,
+        spec.name,
+        manifest_path,
+        ready,
+        advice.issues,
+        advice
+            .cargo_add
+            .iter()
+            .map(|args| command_line(args))
+            .collect(),
+        advice.snippets,
+        applied,
+    )
+    .with_message_template(message))
+}
+
+/// Read the target's manifest and sources, and work out what is missing
+fn inspect(target: &BevyTarget, extras_version: &str) -> Result<(SetupChecks, SetupAdvice)> {
+    let manifest_dir = target
+        .manifest_path
+        .parent()
+        .unwrap_or(&target.manifest_path);
+    let detector = CargoDetector::from_path(manifest_dir)
+        .map_err(|e| Error::ProcessManagement(format!("cargo metadata failed: {e}")))?;
+    let Some(BrpManifestSetup {
+        bevy_remote_feature,
+        extras_dependency,
+        target_source,
+    }) = detector.brp_manifest_setup(target)
+    else {
+        return Err(Error::InvalidState(format!(
+            "'{}' is not in the package at {}",
+            target.name,
+            target.manifest_path.display()
+        ))
+        .into());
+    };
+
+    let mut sources = Vec::new();
+    collect_sources(&manifest_dir.join("src"), &mut sources);
+    if !sources.contains(&target_source) {
+        sources.push(target_source);
+    }
+    let checks = SetupChecks {
+        bevy_remote_feature: bevy_remote_feature || extras_dependency,
+        extras_dependency,
+        extras_plugin: find_plugin(&sources, EXTRAS_PLUGIN),
+        remote_plugin: find_plugin(&sources, REMOTE_PLUGIN),
+        remote_http_plugin: find_plugin(&sources, REMOTE_HTTP_PLUGIN),
+    };
+    let advice = advise(&checks, &target.manifest_path, extras_version);
+    Ok((checks, advice))
+}
+
+/// The issues, `cargo add` arguments and snippets for what `checks` found
+fn advise(checks: &SetupChecks, manifest_path: &Path, extras_version: &str) -> SetupAdvice {
+    let mut advice = SetupAdvice::default();
+    let manifest_args = [
+        "--manifest-path".to_string(),
+        manifest_path.display().to_string(),
+    ];
+    let add_extras = || {
+        let mut args = vec![
+            "add".to_string(),
+            format!("bevy_brp_extras@{extras_version}"),
+        ];
+        args.extend(manifest_args.iter().cloned());
+        args
+    };
+
+    match (
+        &checks.extras_plugin,
+        &checks.remote_plugin,
+        &checks.remote_http_plugin,
+    ) {
+        (Some(file), remote, http) if remote.is_some() || http.is_some() => {
+            advice.issues.push(format!(
+                "{EXTRAS_PLUGIN} (in {file}) adds {REMOTE_PLUGIN} and {REMOTE_HTTP_PLUGIN} \
+                 itself - adding them again panics at startup, so remove them"
+            ));
+        },
+        // Both plugins, or the extras plugin alone, serve BRP
+        (Some(_), _, _) | (None, Some(_), Some(_)) => {},
+        (None, None, None) => {
+            advice.issues.push(format!(
+                "no {EXTRAS_PLUGIN} or {REMOTE_PLUGIN} found in the sources"
+            ));
+            advice.snippets.push(EXTRAS_PLUGIN_SNIPPET.to_string());
+            if !checks.extras_dependency {
+                advice.cargo_add.push(add_extras());
+            }
+        },
+        (None, Some(file), None) => {
+            advice.issues.push(format!(
+                "{REMOTE_PLUGIN} (in {file}) without {REMOTE_HTTP_PLUGIN} serves nothing on the \
+                 port"
+            ));
+            advice.snippets.push(REMOTE_HTTP_PLUGIN_SNIPPET.to_string());
+        },
+        (None, None, Some(file)) => {
+            advice.issues.push(format!(
+                "{REMOTE_HTTP_PLUGIN} (in {file}) needs {REMOTE_PLUGIN} or {EXTRAS_PLUGIN}"
+            ));
+            advice.snippets.push(EXTRAS_PLUGIN_SNIPPET.to_string());
+        },
+    }
+
+    if checks.extras_plugin.is_some() && !checks.extras_dependency {
+        advice.issues.push(format!(
+            "{EXTRAS_PLUGIN} is used but bevy_brp_extras isn't a dependency"
+        ));
+        advice.cargo_add.push(add_extras());
+    }
+    if checks.extras_plugin.is_none()
+        && (checks.remote_plugin.is_some() || checks.remote_http_plugin.is_some())
+        && !checks.bevy_remote_feature
+    {
+        advice
+            .issues
+            .push("the bevy dependency doesn't enable the bevy_remote feature".to_string());
+        let mut args = vec![
+            "add".to_string(),
+            "bevy".to_string(),
+            "--features".to_string(),
+            "bevy_remote".to_string(),
+        ];
+        args.extend(manifest_args.iter().cloned());
+        advice.cargo_add.push(args);
+    }
+    advice
+}
+
+/// `major.minor` of a version, e.g. `0.17` for `0.17.2`
+fn minor_version(version: &str) -> Option<String> {
+    let mut parts = version.split('.');
+    Some(format!("{}.{}", parts.next()?, parts.next()?))
+}
+
+/// Collect the `.rs` files under `dir`
+fn collect_sources(dir: &Path, sources: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for path in entries.flatten().map(|entry| entry.path()) {
+        if path.is_dir() {
+            collect_sources(&path, sources);
+        } else if path.extension().is_some_and(|ext| ext == "rs") {
+            sources.push(path);
+        }
+    }
+}
+
+/// First source file naming `plugin` outside a comment
+fn find_plugin(sources: &[PathBuf], plugin: &str) -> Option<String> {
+    sources
+        .iter()
+        .find(|path| fs::read_to_string(path).is_ok_and(|content| mentions(&content, plugin)))
+        .map(|path| path.display().to_string())
+}
+
+/// Whether `content` uses the identifier `name` on a line that isn't a comment
+fn mentions(content: &str, name: &str) -> bool {
+    content
+        .lines()
+        .filter(|line| !line.trim_start().starts_with("//"))
+        .any(|line| {
+            line.split(|c: char| !(c.is_alphanumeric() || c == '_'))
+                .any(|word| word == name)
+        })
+}
+
+/// Run `cargo` with `args`, failing with its stderr
+fn run_cargo_add(args: &[String]) -> Result<()> {
+    let output = Command::new("cargo")
+        .args(args)
+        .output()
+        .map_err(|e| Error::ProcessManagement(format!("Failed to run cargo: {e}")))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(Error::ProcessManagement(format!(
+            "{} failed: {}",
+            command_line(args),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+        .into())
+    }
+}
+
+/// `args` as a `cargo` command line
+fn command_line(args: &[String]) -> String { format!("cargo {}", args.join(" ")) }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_plugins_outside_comments_only() {
+        let source = "// RemotePlugin is added by BrpExtrasPlugin\n\
+                      app.add_plugins((DefaultPlugins, BrpExtrasPlugin));";
+        assert!(mentions(source, EXTRAS_PLUGIN));
+        assert!(!mentions(source, REMOTE_PLUGIN));
+        assert!(!mentions("RemoteHttpPlugin::default()", REMOTE_PLUGIN));
+    }
+
+    #[test]
+    fn advises_extras_when_no_plugin_is_found() {
+        let advice = advise(
+            &SetupChecks::default(),
+            Path::new("/game/Cargo.toml"),
+            "0.17",
+        );
+        assert_eq!(advice.issues.len(), 1);
+        assert_eq!(advice.snippets, vec![EXTRAS_PLUGIN_SNIPPET.to_string()]);
+        assert_eq!(
+            advice
+                .cargo_add
+                .iter()
+                .map(|args| command_line(args))
+                .collect::<Vec<_>>(),
+            vec!["cargo add bevy_brp_extras@0.17 --manifest-path /game/Cargo.toml".to_string()]
+        );
+
+        let doubled = advise(
+            &SetupChecks {
+                extras_dependency: true,
+                bevy_remote_feature: true,
+                extras_plugin: Some("src/main.rs".to_string()),
+                remote_plugin: Some("src/main.rs".to_string()),
+                ..SetupChecks::default()
+            },
+            Path::new("/game/Cargo.toml"),
+            "0.17",
+        );
+        assert!(doubled.issues[0].contains("panics at startup"));
+        assert!(doubled.cargo_add.is_empty());
+    }
+}
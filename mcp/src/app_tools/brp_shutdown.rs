@@ -9,10 +9,15 @@ use sysinfo::System;
 use tracing::debug;
 
 use crate::app_tools::support::get_pid_for_port;
+use crate::app_tools::support::instance_registry;
+use crate::app_tools::support::kill_remote_process;
 use crate::brp_tools::BrpClient;
 use crate::brp_tools::JSON_RPC_ERROR_METHOD_NOT_FOUND;
 use crate::brp_tools::Port;
+use crate::brp_tools::RemoteHost;
 use crate::brp_tools::ResponseStatus;
+use crate::brp_tools::forget_remote_host;
+use crate::brp_tools::remote_host_for;
 use crate::error::Error;
 use crate::error::Result;
 use crate::tool::BrpMethod;
@@ -25,7 +30,7 @@ use crate::tool::ToolResult;
 pub struct ShutdownParams {
     /// Name of the Bevy app to shutdown
     pub app_name: String,
-    /// The BRP port (default: 15702)
+    /// The BRP port
     #[serde(default)]
     pub port:     Port,
 }
@@ -55,7 +60,7 @@ pub struct ShutdownResult {
 }
 
 /// Result of a shutdown operation
-enum ShutdownOutcome {
+pub(super) enum ShutdownOutcome {
     /// Graceful shutdown via `bevy_brp_extras` succeeded
     CleanShutdown { pid: u32 },
     /// Process was killed using system signal - typically when extras plugin is not available
@@ -71,13 +76,13 @@ enum ShutdownOutcome {
 pub struct Shutdown;
 
 /// Attempt to shutdown a Bevy app, first trying graceful shutdown then falling back to kill
-async fn shutdown_app(app_name: &str, port: Port) -> ShutdownOutcome {
+pub(super) async fn shutdown_app(app_name: &str, port: Port) -> ShutdownOutcome {
     debug!("Starting shutdown process for app '{app_name}' on port {port}");
 
     // Try graceful shutdown via bevy_brp_extras
     // Extraction shouldn't return 0 with the udpated data extras but it's possible we could be
     // running against an older version
-    match try_graceful_shutdown(port).await {
+    let outcome = match try_graceful_shutdown(port).await {
         Ok(Some(result)) => {
             debug!("Graceful shutdown succeeded");
             // Extract PID from the BRP response
@@ -101,7 +106,13 @@ async fn shutdown_app(app_name: &str, port: Port) -> ShutdownOutcome {
             // BRP not responsive - fall back to kill
             handle_kill_process_fallback(app_name, port, Some(e.to_string()))
         },
+    };
+
+    if !matches!(outcome, ShutdownOutcome::Error { .. }) {
+        instance_registry::unregister(port);
+        forget_remote_host(port);
     }
+    outcome
 }
 
 /// Handle the fallback to kill process when graceful shutdown fails
@@ -222,6 +233,11 @@ fn kill_process(app_name: &str, port: Port) -> Result<Option<u32>> {
     let mut system = System::new_all();
     system.refresh_processes(sysinfo::ProcessesToUpdate::All, true);
 
+    // Remote instances are killed on their machine; locally only the SSH tunnel listens
+    if let Some(remote_host) = remote_host_for(port) {
+        return kill_remote_instance(&system, port, &remote_host);
+    }
+
     // First try: Get PID from port for more reliable process identification
     let target_pid = get_pid_for_port(port).map_or_else(
         || {
@@ -275,6 +291,29 @@ fn kill_process(app_name: &str, port: Port) -> Result<Option<u32>> {
     Ok(None)
 }
 
+/// Kill a remote instance by its remote PID and close the SSH session forwarding its port
+fn kill_remote_instance(
+    system: &System,
+    port: Port,
+    remote_host: &RemoteHost,
+) -> Result<Option<u32>> {
+    if let Some(remote_pid) = remote_host.remote_pid {
+        kill_remote_process(&remote_host.profile, remote_pid)?;
+        debug!(
+            "Killed remote process {remote_pid} on {}",
+            remote_host.destination
+        );
+    }
+
+    let ssh_pid = get_pid_for_port(port);
+    if let Some(pid) = ssh_pid
+        && let Some(process) = system.process(sysinfo::Pid::from_u32(pid))
+    {
+        process.kill_with(Signal::Term);
+    }
+    Ok(remote_host.remote_pid.or(ssh_pid))
+}
+
 /// Error when process is not running
 #[derive(Debug, Clone, Serialize, Deserialize, ResultStruct)]
 pub struct ProcessNotRunningError {
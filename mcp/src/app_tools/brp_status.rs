@@ -22,7 +22,7 @@ use crate::tool::ToolResult;
 pub struct StatusParams {
     /// Name of the process to check for
     pub app_name: String,
-    /// The BRP port (default: 15702)
+    /// The BRP port
     #[serde(default)]
     pub port:     Port,
 }
@@ -323,6 +323,9 @@ async fn check_brp_for_app(app_name: &str, port: Port) -> Result<StatusResult> {
 
 /// Check if BRP is responding on the given port
 async fn check_brp_on_port(port: Port) -> Result<bool> {
+    // Probe for real even if earlier calls tripped the circuit for this port
+    brp_tools::reset_circuit(Some(port));
+
     // Try up to 5 times with 500ms delays to account for BRP initialization timing
     for _attempt in 0..5 {
         let client = brp_tools::BrpClient::new(BrpMethod::WorldListComponents, port, None);
@@ -0,0 +1,163 @@
+//! `brp_watch_and_relaunch` tool - Rebuild and relaunch an app whenever its sources change
+//!
+//! The relaunch watcher runs under the watch manager: its rebuild status is streamed to a watch
+//! log, and it is listed by `brp_list_active_watches` and stopped with `brp_stop_watch`.
+
+use std::time::Duration;
+
+use bevy_brp_mcp_macros::ParamStruct;
+use bevy_brp_mcp_macros::ResultStruct;
+use bevy_brp_mcp_macros::ToolFn;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+
+use super::constants::DEFAULT_PROFILE;
+use super::instance_count::InstanceCount;
+use super::launch_params::BuildProfile;
+use super::support::App;
+use super::support::Example;
+use super::support::LaunchConfig;
+use super::support::LaunchParams;
+use super::support::OutputCapture;
+use super::support::RelaunchWatchStarted;
+use super::support::TargetSpec;
+use super::support::TargetType;
+use super::support::start_relaunch_watch;
+use crate::brp_tools::Port;
+use crate::error::Result;
+use crate::tool::HandlerContext;
+use crate::tool::HandlerResult;
+use crate::tool::ToolFn;
+use crate::tool::ToolResult;
+
+/// Quiet period after the last change before rebuilding
+const DEFAULT_DEBOUNCE_MS: u32 = 500;
+
+#[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
+pub struct WatchAndRelaunchParams {
+    /// Name of the app (or example, with `example: true`) to watch and relaunch, or a target
+    /// spec naming its package: `my_pkg#demo`, `my_pkg/examples/demo` or `my_pkg/bin/demo`
+    pub target_name: String,
+    /// Watch an example rather than an app (default: false)
+    #[serde(default)]
+    pub example:     bool,
+    /// Build profile to use (debug or release)
+    #[to_metadata(skip_if_none)]
+    #[schemars(extend("default" = DEFAULT_PROFILE))]
+    pub profile:     Option<BuildProfile>,
+    /// Path to use when multiple targets with the same name exist
+    #[to_metadata(skip_if_none)]
+    pub path:        Option<String>,
+    /// Package containing the target - an alternative to `path` when multiple targets with the
+    /// same name exist
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[to_metadata(skip_if_none)]
+    pub package:     Option<String>,
+    /// The BRP port
+    #[serde(default)]
+    pub port:        Port,
+    /// Cargo features to enable when building and running
+    #[serde(default)]
+    #[to_metadata(skip_if_none)]
+    pub features:    Option<Vec<String>>,
+    /// Build debug builds with Bevy's `dynamic_linking` feature when available (default: false)
+    #[serde(default)]
+    pub fast_build:  bool,
+    /// How the app's output is captured
+    #[serde(flatten)]
+    pub output:      OutputCapture,
+    /// Milliseconds without further changes before rebuilding (default: 500, max: 60000)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[param(max = 60_000)]
+    #[schemars(extend("default" = DEFAULT_DEBOUNCE_MS))]
+    pub debounce_ms: Option<u32>,
+}
+
+/// Result from starting a relaunch watch
+#[derive(Debug, Clone, Serialize, Deserialize, ResultStruct)]
+pub struct WatchAndRelaunchResult {
+    /// Name of the watched target
+    #[to_metadata]
+    target_name:      String,
+    /// Watch ID - stop relaunching with `brp_stop_watch`
+    #[to_metadata]
+    watch_id:         u32,
+    /// Log file receiving rebuild and relaunch status
+    #[to_metadata]
+    log_path:         String,
+    /// Package directory being watched
+    #[to_metadata]
+    watched_path:     String,
+    /// Result of the initial launch
+    #[to_result]
+    launch:           Value,
+    /// Message template for formatting responses
+    #[to_message(
+        message_template = "Launched {target_name} and watching {watched_path} - it is rebuilt and relaunched on change (watch {watch_id})"
+    )]
+    message_template: String,
+}
+
+#[derive(ToolFn)]
+#[tool_fn(
+    params = "WatchAndRelaunchParams",
+    output = "WatchAndRelaunchResult",
+    with_context
+)]
+pub struct WatchAndRelaunch;
+
+async fn handle_impl(
+    ctx: HandlerContext,
+    params: WatchAndRelaunchParams,
+) -> Result<WatchAndRelaunchResult> {
+    let spec = TargetSpec::parse(&params.target_name);
+    // A target spec naming examples or binaries decides the target type itself
+    let example = spec.target_type.map_or(params.example, |target_type| {
+        target_type == TargetType::Example
+    });
+    let launch_params = LaunchParams {
+        target_name:    spec.name,
+        target_type:    spec.target_type,
+        profile:        params
+            .profile
+            .as_ref()
+            .map_or(DEFAULT_PROFILE, BuildProfile::as_str)
+            .to_string(),
+        path:           params.path,
+        package:        params.package.or(spec.package),
+        port:           params.port,
+        instance_count: InstanceCount::default(),
+        stagger_ms:     0,
+        instances:      Vec::new(),
+        features:       params.features,
+        fast_build:     params.fast_build,
+        output:         params.output,
+        remote_host:    None,
+        debugger:       None,
+        network_proxy:  None,
+    };
+    let debounce =
+        Duration::from_millis(u64::from(params.debounce_ms.unwrap_or(DEFAULT_DEBOUNCE_MS)));
+
+    let started = if example {
+        start_relaunch_watch::<LaunchConfig<Example>>(&launch_params, ctx.roots, debounce).await?
+    } else {
+        start_relaunch_watch::<LaunchConfig<App>>(&launch_params, ctx.roots, debounce).await?
+    };
+    let RelaunchWatchStarted {
+        watch_id,
+        log_path,
+        watched_path,
+        launch,
+    } = started;
+
+    Ok(WatchAndRelaunchResult::new(
+        params.target_name,
+        watch_id,
+        log_path.to_string_lossy().to_string(),
+        watched_path.to_string_lossy().to_string(),
+        serde_json::to_value(launch).unwrap_or(Value::Null),
+    ))
+}
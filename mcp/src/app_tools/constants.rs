@@ -2,3 +2,6 @@
 pub const PROFILE_DEBUG: &str = "debug";
 pub const PROFILE_RELEASE: &str = "release";
 pub const DEFAULT_PROFILE: &str = PROFILE_DEBUG;
+
+/// Time for launched instances to answer BRP, used by launch group and session bootstrap
+pub const DEFAULT_READY_TIMEOUT_MS: u32 = 60_000;
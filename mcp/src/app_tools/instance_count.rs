@@ -3,10 +3,14 @@
 //! Provides a type-safe wrapper around the number of instances to launch
 //! with built-in validation and default values for parallel testing.
 
+use std::borrow::Cow;
 use std::ops::Deref;
 use std::ops::RangeInclusive;
 
 use schemars::JsonSchema;
+use schemars::Schema;
+use schemars::SchemaGenerator;
+use schemars::json_schema;
 use serde::Deserialize;
 use serde::Deserializer;
 use serde::Serialize;
@@ -20,9 +24,23 @@ pub const VALID_INSTANCE_RANGE: RangeInclusive<usize> = MIN_INSTANCE_COUNT..=MAX
 
 /// Count of instances to launch in sequence
 /// Validates count is within 1-100 - defaults to 1
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, JsonSchema, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
 pub struct InstanceCount(pub usize);
 
+/// Manual impl so the schema carries the same range that `deserialize_instance_count` enforces
+impl JsonSchema for InstanceCount {
+    fn schema_name() -> Cow<'static, str> { "InstanceCount".into() }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "description": "Count of instances to launch in sequence - defaults to 1",
+            "type": "integer",
+            "minimum": MIN_INSTANCE_COUNT,
+            "maximum": MAX_INSTANCE_COUNT,
+        })
+    }
+}
+
 impl<'de> Deserialize<'de> for InstanceCount {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
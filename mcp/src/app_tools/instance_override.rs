@@ -0,0 +1,25 @@
+//! Per-instance overrides for multi-instance launches
+//!
+//! Multiplayer testing rarely wants identical instances - instance 0 runs as `--server` and the
+//! rest as `--client`. Entry `i` of the launch tools' `instances` parameter is applied to the
+//! command for instance `i`; instances without an entry launch unchanged.
+
+use std::collections::HashMap;
+
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+
+/// Arguments, environment variables and a label for one launched instance
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema)]
+pub struct InstanceOverride {
+    /// Extra command-line arguments passed to this instance (after `--` for examples)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub args:  Vec<String>,
+    /// Environment variables set for this instance, overriding inherited ones
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub env:   HashMap<String, String>,
+    /// Name reported for this instance in the launch result (e.g. "server")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+}
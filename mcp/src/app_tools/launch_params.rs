@@ -1,48 +1,318 @@
+use std::borrow::Cow;
+use std::fmt;
+use std::str::FromStr;
+
 use bevy_brp_mcp_macros::ParamStruct;
 use schemars::JsonSchema;
+use schemars::Schema;
+use schemars::SchemaGenerator;
+use schemars::json_schema;
 use serde::Deserialize;
+use serde::Deserializer;
 use serde::Serialize;
+use serde::Serializer;
 
+use super::constants::DEFAULT_PROFILE;
+use super::constants::PROFILE_DEBUG;
+use super::constants::PROFILE_RELEASE;
 use super::instance_count::InstanceCount;
+use super::instance_override::InstanceOverride;
+use super::support::Debugger;
 use super::support::LaunchParams;
+use super::support::OutputCapture;
+use super::support::TargetSpec;
 use super::support::ToLaunchParams;
+use super::support::network_proxy::NetworkProxyOptions;
 use crate::brp_tools::Port;
 
+/// Cargo's name for the profile it builds without `--release` - its output goes to `debug`
+const CARGO_DEV_PROFILE: &str = "dev";
+
+/// Cargo's built-in profile for tests, which writes to `debug`
+const CARGO_TEST_PROFILE: &str = "test";
+
+/// Cargo's built-in profile for benchmarks, which writes to `release`
+const CARGO_BENCH_PROFILE: &str = "bench";
+
+/// Cargo build profile accepted by the launch tools
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BuildProfile {
+    /// Unoptimized build with debug assertions (`target/debug`)
+    Debug,
+    /// Optimized build (`target/release`)
+    Release,
+    /// A `[profile.<name>]` from the workspace's `Cargo.toml` (`target/<name>`)
+    Custom(String),
+}
+
+impl BuildProfile {
+    /// Profile for a name known to be valid - unknown names are taken as custom profiles
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            PROFILE_DEBUG | CARGO_DEV_PROFILE => Self::Debug,
+            PROFILE_RELEASE => Self::Release,
+            custom => Self::Custom(custom.to_string()),
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Debug => PROFILE_DEBUG,
+            Self::Release => PROFILE_RELEASE,
+            Self::Custom(name) => name,
+        }
+    }
+
+    /// Arguments selecting this profile on a `cargo build` or `cargo run` command
+    pub fn cargo_args(&self) -> Vec<&str> {
+        match self {
+            Self::Debug => Vec::new(),
+            Self::Release => vec!["--release"],
+            Self::Custom(name) => vec!["--profile", name],
+        }
+    }
+
+    /// Cargo environment variable overriding this profile's `debug` setting - cargo names the
+    /// profile it builds without `--release` `dev`, and spells `-` as `_` in variable names
+    pub fn debug_env_var(&self) -> String {
+        let name = match self {
+            Self::Debug => CARGO_DEV_PROFILE,
+            profile => profile.as_str(),
+        };
+        format!(
+            "CARGO_PROFILE_{}_DEBUG",
+            name.to_ascii_uppercase().replace('-', "_")
+        )
+    }
+
+    /// Directory under `target/` that cargo writes this profile's output to - cargo's built-in
+    /// `test` and `bench` profiles share the `debug` and `release` directories
+    pub fn output_dir(&self) -> &str {
+        match self {
+            Self::Custom(name) if name == CARGO_TEST_PROFILE => PROFILE_DEBUG,
+            Self::Custom(name) if name == CARGO_BENCH_PROFILE => PROFILE_RELEASE,
+            profile => profile.as_str(),
+        }
+    }
+}
+
+impl FromStr for BuildProfile {
+    type Err = String;
+
+    /// Case-insensitive for `debug` and `release` - custom profile names are kept as given
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim();
+        if name.eq_ignore_ascii_case(PROFILE_DEBUG) || name.eq_ignore_ascii_case(CARGO_DEV_PROFILE)
+        {
+            return Ok(Self::Debug);
+        }
+        if name.eq_ignore_ascii_case(PROFILE_RELEASE) {
+            return Ok(Self::Release);
+        }
+        // The characters cargo allows in profile names
+        let valid = !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if valid {
+            Ok(Self::Custom(name.to_string()))
+        } else {
+            Err(format!(
+                "Invalid build profile '{s}' - use debug, release or the name of a [profile.<name>] \
+                 in Cargo.toml"
+            ))
+        }
+    }
+}
+
+impl fmt::Display for BuildProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { f.write_str(self.as_str()) }
+}
+
+/// Manual impl so the schema suggests `debug` and `release` without ruling out custom profiles
+impl JsonSchema for BuildProfile {
+    fn schema_name() -> Cow<'static, str> { "BuildProfile".into() }
+
+    fn inline_schema() -> bool { true }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "description": "Cargo build profile - debug, release or a custom [profile.<name>] from Cargo.toml",
+            "type": "string",
+            "examples": [PROFILE_DEBUG, PROFILE_RELEASE],
+        })
+    }
+}
+
+impl Serialize for BuildProfile {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for BuildProfile {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let name = String::deserialize(deserializer)?;
+        name.parse().map_err(serde::de::Error::custom)
+    }
+}
+
 /// Shared parameters for launching Bevy binaries (apps or examples)
 #[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
 pub struct LaunchBevyBinaryParams {
-    /// Name of the Bevy target to launch (app or example)
-    pub target_name:    String,
-    /// Build profile to use (debug or release)
+    /// Name of the Bevy target to launch (app or example), or a target spec naming its package:
+    /// `my_pkg#demo`, `my_pkg/examples/demo` or `my_pkg/bin/demo` - required unless `preset` sets
+    /// it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_name:    Option<String>,
+    /// Named launch preset from `BRP_MCP_LAUNCH_PRESETS` or the workspace's
+    /// `brp_launch_presets.json` - fills in every parameter this call doesn't give
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preset:         Option<String>,
+    /// Build profile to use - debug, release or a custom `[profile.<name>]` from `Cargo.toml`
     #[to_metadata(skip_if_none)]
-    pub profile:        Option<String>,
+    #[schemars(extend("default" = DEFAULT_PROFILE))]
+    pub profile:        Option<BuildProfile>,
     /// Path to use when multiple targets with the same name exist
     #[to_metadata(skip_if_none)]
     pub path:           Option<String>,
-    /// The BRP port (default: 15702)
+    /// Package containing the target - an alternative to `path` when multiple targets with the
+    /// same name exist
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[to_metadata(skip_if_none)]
+    pub package:        Option<String>,
+    /// The BRP port
     #[serde(default)]
     pub port:           Port,
-    /// Number of instances to launch (default: 1)
+    /// Number of instances to launch
     #[serde(default)]
     pub instance_count: InstanceCount,
+    /// Milliseconds to wait between starting consecutive instances (default: 0, max: 60000)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[param(max = 60_000)]
+    #[to_metadata(skip_if_none)]
+    pub stagger_ms:     Option<u32>,
+    /// Per-instance overrides - entry `i` adds `args`, `env` and a `label` to instance `i` (e.g.
+    /// `[{"args": ["--server"]}, {"args": ["--client"]}]`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instances:      Option<Vec<InstanceOverride>>,
     /// Cargo features to enable when building and running
     #[serde(default)]
     #[to_metadata(skip_if_none)]
     pub features:       Option<Vec<String>>,
+    /// Build debug builds with Bevy's `dynamic_linking` feature for faster iteration, when the
+    /// package's `bevy` dependency offers it (default: false)
+    #[serde(default)]
+    pub fast_build:     bool,
+    /// Write stderr to its own log file instead of interleaving it with stdout (default: false)
+    #[serde(default)]
+    pub split_stderr:   bool,
+    /// Prefix every captured output line with the time it was written (default: false)
+    #[serde(default)]
+    pub timestamps:     bool,
+    /// Remote host profile to build for and run on over SSH, from the file named by
+    /// `BRP_MCP_REMOTE_HOSTS`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[to_metadata(skip_if_none)]
+    pub remote_host:    Option<String>,
+    /// Run each instance under a debugger: `lldb` or `gdb` serve it on a debug port and wait for
+    /// a debugger to attach, `rr` records it for replay
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[to_metadata(skip_if_none)]
+    pub debugger:       Option<Debugger>,
+    /// Start a proxy adding latency, jitter and packet loss between the instances - clients
+    /// connect to the address in `BRP_EXTRAS_NETWORK_PROXY` instead of the server's `target_port`
+    /// (e.g. `{"target_port": 5000, "latency_ms": 80, "jitter_ms": 20, "loss_percent": 2}`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub network_proxy:  Option<NetworkProxyOptions>,
 }
 
 impl ToLaunchParams for LaunchBevyBinaryParams {
     fn to_launch_params(&self, default_profile: &str) -> LaunchParams {
+        let spec = TargetSpec::parse(self.target_name.as_deref().unwrap_or_default());
         LaunchParams {
-            target_name:    self.target_name.clone(),
-            profile:        self
-                .profile
-                .clone()
-                .unwrap_or_else(|| default_profile.to_string()),
+            target_name:    spec.name,
+            target_type:    spec.target_type,
+            profile:        self.profile.as_ref().map_or_else(
+                || default_profile.to_string(),
+                |profile| profile.as_str().to_string(),
+            ),
             path:           self.path.clone(),
+            package:        self.package.clone().or(spec.package),
             port:           self.port,
             instance_count: self.instance_count,
+            stagger_ms:     self.stagger_ms.unwrap_or_default(),
+            instances:      self.instances.clone().unwrap_or_default(),
             features:       self.features.clone(),
+            fast_build:     self.fast_build,
+            output:         OutputCapture {
+                split_stderr: self.split_stderr,
+                timestamps:   self.timestamps,
+            },
+            remote_host:    self.remote_host.clone(),
+            debugger:       self.debugger,
+            network_proxy:  self.network_proxy.clone(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn custom_profiles_are_accepted() {
+        assert_eq!("Release".parse::<BuildProfile>(), Ok(BuildProfile::Release));
+        assert_eq!("dev".parse::<BuildProfile>(), Ok(BuildProfile::Debug));
+
+        let profiling = BuildProfile::Custom("profiling".to_string());
+        assert_eq!(
+            serde_json::from_value::<BuildProfile>(json!("profiling")).ok(),
+            Some(profiling.clone())
+        );
+        assert_eq!(profiling.cargo_args(), vec!["--profile", "profiling"]);
+        assert_eq!(profiling.output_dir(), "profiling");
+        assert_eq!(
+            serde_json::to_value(&profiling).ok(),
+            Some(json!("profiling"))
+        );
+
+        assert!("release; rm -rf /".parse::<BuildProfile>().is_err());
+        assert!("".parse::<BuildProfile>().is_err());
+    }
+
+    #[test]
+    fn profiles_select_cargo_output_directories() {
+        assert!(BuildProfile::Debug.cargo_args().is_empty());
+        assert_eq!(BuildProfile::Release.cargo_args(), vec!["--release"]);
+        assert_eq!(
+            BuildProfile::from_name("bench").output_dir(),
+            PROFILE_RELEASE
+        );
+        assert_eq!(BuildProfile::from_name("test").output_dir(), PROFILE_DEBUG);
+    }
+
+    #[test]
+    fn debug_env_vars_name_cargo_profiles() {
+        assert_eq!(
+            BuildProfile::Debug.debug_env_var(),
+            "CARGO_PROFILE_DEV_DEBUG"
+        );
+        assert_eq!(
+            BuildProfile::Release.debug_env_var(),
+            "CARGO_PROFILE_RELEASE_DEBUG"
+        );
+        assert_eq!(
+            BuildProfile::from_name("release-lto").debug_env_var(),
+            "CARGO_PROFILE_RELEASE_LTO_DEBUG"
+        );
+    }
+}
@@ -0,0 +1,45 @@
+use bevy_brp_mcp_macros::ParamStruct;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+
+/// Shared parameters for listing Bevy targets (apps or examples)
+#[derive(Clone, Default, Deserialize, Serialize, JsonSchema, ParamStruct)]
+pub struct ListTargetsParams {
+    /// Only list targets whose name or package name contains this text (case-insensitive)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[to_metadata(skip_if_none)]
+    pub keyword:          Option<String>,
+    /// Include each target's cargo features, default features and required features (default:
+    /// false)
+    #[serde(default)]
+    pub include_features: bool,
+}
+
+impl ListTargetsParams {
+    /// Whether `name` or `package_name` contains the keyword, if one is given
+    pub fn matches(&self, name: &str, package_name: &str) -> bool {
+        self.keyword.as_deref().is_none_or(|keyword| {
+            let keyword = keyword.to_lowercase();
+            name.to_lowercase().contains(&keyword) || package_name.to_lowercase().contains(&keyword)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keyword_matches_names_or_packages_ignoring_case() {
+        let params = ListTargetsParams {
+            keyword: Some("Space".to_string()),
+            ..ListTargetsParams::default()
+        };
+        assert!(params.matches("space_game", "games"));
+        assert!(params.matches("client", "SPACE_SHOOTER"));
+        assert!(!params.matches("client", "games"));
+
+        assert!(ListTargetsParams::default().matches("anything", "at_all"));
+    }
+}
@@ -2,24 +2,62 @@
 
 mod constants;
 mod instance_count;
+mod instance_override;
 mod launch_params;
+mod list_params;
 
+mod brp_bootstrap_session;
+mod brp_diagnose;
 mod brp_launch_bevy_app;
 mod brp_launch_bevy_example;
+mod brp_launch_group;
 mod brp_list_bevy_apps;
 mod brp_list_bevy_examples;
 mod brp_list_brp_apps;
+mod brp_list_instances;
+mod brp_network_proxy_configure;
+mod brp_network_proxy_start;
+mod brp_network_proxy_stop;
+mod brp_new_bevy_example;
+mod brp_self_test;
+mod brp_setup_check;
 mod brp_shutdown;
 mod brp_status;
+mod brp_watch_and_relaunch;
 mod support;
 
+pub use brp_bootstrap_session::BootstrapSession;
+pub use brp_bootstrap_session::BootstrapSessionParams;
+pub use brp_diagnose::Diagnose;
+pub use brp_diagnose::DiagnoseParams;
 pub use brp_launch_bevy_app::create_launch_bevy_app_handler;
 pub use brp_launch_bevy_example::create_launch_bevy_example_handler;
+pub use brp_launch_group::LaunchGroup;
+pub use brp_launch_group::LaunchGroupParams;
 pub use brp_list_bevy_apps::ListBevyApps;
 pub use brp_list_bevy_examples::ListBevyExamples;
 pub use brp_list_brp_apps::ListBrpApps;
+pub use brp_list_instances::ListInstances;
+pub use brp_network_proxy_configure::NetworkProxyConfigure;
+pub use brp_network_proxy_configure::NetworkProxyConfigureParams;
+pub use brp_network_proxy_start::NetworkProxyStart;
+pub use brp_network_proxy_start::NetworkProxyStartParams;
+pub use brp_network_proxy_stop::NetworkProxyStop;
+pub use brp_network_proxy_stop::NetworkProxyStopParams;
+pub use brp_new_bevy_example::NewBevyExample;
+pub use brp_new_bevy_example::NewBevyExampleParams;
+pub use brp_self_test::SelfTest;
+pub use brp_setup_check::SetupCheck;
+pub use brp_setup_check::SetupCheckParams;
 pub use brp_shutdown::Shutdown;
 pub use brp_shutdown::ShutdownParams;
 pub use brp_status::Status;
 pub use brp_status::StatusParams;
+pub use brp_watch_and_relaunch::WatchAndRelaunch;
+pub use brp_watch_and_relaunch::WatchAndRelaunchParams;
 pub use launch_params::LaunchBevyBinaryParams;
+pub use list_params::ListTargetsParams;
+pub use support::NotificationLevel;
+pub use support::get_pid_for_port;
+pub use support::instance_registry;
+pub use support::start_watchdog;
@@ -0,0 +1,171 @@
+//! Compiler errors from the `--message-format=json` output of a failed cargo build
+//!
+//! Cargo prints one JSON object per line; `compiler-message` lines carry rustc's diagnostics.
+//! Only errors are kept - warnings don't explain why a build failed.
+
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+
+/// Lines of stderr kept when the build failed without any compiler errors
+const STDERR_TAIL_LINES: usize = 40;
+
+/// A compiler error from a failed build
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct CompilerError {
+    /// Source file of the primary span, relative to the workspace root
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file:     Option<String>,
+    /// 1-based line of the primary span
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line:     Option<u64>,
+    /// 1-based column of the primary span
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub column:   Option<u64>,
+    /// Error code, e.g. `E0425`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code:     Option<String>,
+    /// Short error message
+    pub message:  String,
+    /// The full message as rustc prints it, with source snippet and help
+    pub rendered: String,
+}
+
+/// A line of cargo's JSON output - only the fields needed here
+#[derive(Deserialize)]
+struct CargoMessage {
+    reason:  String,
+    message: Option<Diagnostic>,
+}
+
+#[derive(Deserialize)]
+struct Diagnostic {
+    message:  String,
+    level:    String,
+    code:     Option<DiagnosticCode>,
+    #[serde(default)]
+    spans:    Vec<DiagnosticSpan>,
+    rendered: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct DiagnosticCode {
+    code: String,
+}
+
+#[derive(Deserialize)]
+struct DiagnosticSpan {
+    file_name:    String,
+    line_start:   u64,
+    column_start: u64,
+    is_primary:   bool,
+}
+
+/// Collect the compiler errors from cargo's JSON output
+pub fn parse_compiler_errors(stdout: &[u8]) -> Vec<CompilerError> {
+    String::from_utf8_lossy(stdout)
+        .lines()
+        .filter_map(|line| serde_json::from_str::<CargoMessage>(line).ok())
+        .filter(|message| message.reason == "compiler-message")
+        .filter_map(|message| message.message)
+        .filter(is_reported_error)
+        .map(|diagnostic| {
+            let primary = diagnostic.spans.iter().find(|span| span.is_primary);
+            CompilerError {
+                file:     primary.map(|span| span.file_name.clone()),
+                line:     primary.map(|span| span.line_start),
+                column:   primary.map(|span| span.column_start),
+                code:     diagnostic.code.map(|code| code.code),
+                rendered: diagnostic.rendered.map_or_else(
+                    || diagnostic.message.clone(),
+                    |rendered| rendered.trim_end().to_string(),
+                ),
+                message:  diagnostic.message,
+            }
+        })
+        .collect()
+}
+
+/// Errors worth reporting - rustc's closing "aborting due to N previous errors" summary isn't one
+fn is_reported_error(diagnostic: &Diagnostic) -> bool {
+    diagnostic.level.starts_with("error")
+        && !(diagnostic.spans.is_empty() && diagnostic.message.starts_with("aborting due to"))
+}
+
+/// The last lines of stderr, for failures that produce no compiler errors (e.g. a broken
+/// `Cargo.toml` or a failed dependency download)
+pub fn stderr_tail(stderr: &[u8]) -> String {
+    let stderr = String::from_utf8_lossy(stderr);
+    let lines: Vec<&str> = stderr.lines().collect();
+    lines[lines.len().saturating_sub(STDERR_TAIL_LINES)..].join("\n")
+}
+
+/// One line per error, `file:line:column: message`, for plain-text reports
+pub fn summarize(errors: &[CompilerError]) -> String {
+    errors
+        .iter()
+        .map(|error| {
+            let location = match (&error.file, error.line, error.column) {
+                (Some(file), Some(line), Some(column)) => format!("{file}:{line}:{column}: "),
+                (Some(file), ..) => format!("{file}: "),
+                _ => String::new(),
+            };
+            let code = error
+                .code
+                .as_ref()
+                .map_or_else(String::new, |code| format!("[{code}] "));
+            format!("{location}{code}{}", error.message)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OUTPUT: &str = concat!(
+        r#"{"reason":"compiler-artifact","target":{"name":"serde"},"fresh":true}"#,
+        "\n",
+        r#"{"reason":"compiler-message","message":{"message":"unused variable: `y`","level":"warning","code":null,"spans":[],"rendered":"warning: unused variable"}}"#,
+        "\n",
+        r#"{"reason":"compiler-message","message":{"message":"cannot find value `x` in this scope","level":"error","code":{"code":"E0425","explanation":null},"spans":[{"file_name":"src/other.rs","line_start":9,"column_start":1,"is_primary":false},{"file_name":"src/main.rs","line_start":2,"column_start":5,"is_primary":true}],"rendered":"error[E0425]: cannot find value `x` in this scope\n"}}"#,
+        "\n",
+        r#"{"reason":"compiler-message","message":{"message":"aborting due to 1 previous error","level":"error","code":null,"spans":[],"rendered":"error: aborting due to 1 previous error\n"}}"#,
+        "\n",
+        r#"{"reason":"build-finished","success":false}"#,
+    );
+
+    #[test]
+    fn errors_are_parsed_from_the_primary_span() {
+        let errors = parse_compiler_errors(OUTPUT.as_bytes());
+
+        assert_eq!(
+            errors,
+            vec![CompilerError {
+                file:     Some("src/main.rs".to_string()),
+                line:     Some(2),
+                column:   Some(5),
+                code:     Some("E0425".to_string()),
+                message:  "cannot find value `x` in this scope".to_string(),
+                rendered: "error[E0425]: cannot find value `x` in this scope".to_string(),
+            }]
+        );
+        assert_eq!(
+            summarize(&errors),
+            "src/main.rs:2:5: [E0425] cannot find value `x` in this scope"
+        );
+    }
+
+    #[test]
+    fn stderr_tail_keeps_the_last_lines() {
+        let stderr = (1..=50)
+            .map(|n| format!("{n}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let tail = stderr_tail(stderr.as_bytes());
+
+        assert!(tail.starts_with("11\n"));
+        assert!(tail.ends_with("\n50"));
+    }
+}
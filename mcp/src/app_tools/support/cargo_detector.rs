@@ -14,6 +14,8 @@ use strum::AsRefStr;
 use strum::Display;
 use strum::EnumString;
 
+use crate::app_tools::launch_params::BuildProfile;
+
 /// Type of Bevy target
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Display, AsRefStr, EnumString, Serialize)]
 #[strum(serialize_all = "lowercase")]
@@ -43,34 +45,57 @@ impl TargetType {
 #[derive(Debug, Clone)]
 pub struct BevyTarget {
     /// Name of the target
-    pub name:           String,
+    pub name:              String,
     /// Type of target (App or Example)
-    pub target_type:    TargetType,
+    pub target_type:       TargetType,
     /// Package name (for examples, this is the package containing the example)
-    pub package_name:   String,
+    pub package_name:      String,
     /// Workspace root (for apps)
-    pub workspace_root: PathBuf,
+    pub workspace_root:    PathBuf,
     /// Path to the package's Cargo.toml
-    pub manifest_path:  PathBuf,
+    pub manifest_path:     PathBuf,
     /// Relative path from scan root to this item
-    pub relative_path:  PathBuf,
+    pub relative_path:     PathBuf,
+    /// Cargo feature that builds Bevy as a dynamic library, if the package can enable it
+    pub dylib_feature:     Option<String>,
+    /// Version of the `bevy` crate the package builds against, as resolved by cargo
+    pub bevy_version:      Option<String>,
+    /// Cargo features the package declares, excluding `default`
+    pub features:          Vec<String>,
+    /// Features the package enables by default
+    pub default_features:  Vec<String>,
+    /// Features that must be enabled to build this target
+    pub required_features: Vec<String>,
 }
 
 impl BevyTarget {
     /// Get the path to the binary for a given profile
     pub fn get_binary_path(&self, profile: &str) -> PathBuf {
-        match self.target_type {
-            TargetType::App => self
+        let profile = BuildProfile::from_name(profile);
+        self.binary_path_in(
+            &self
                 .workspace_root
                 .join("target")
-                .join(profile)
-                .join(&self.name),
-            TargetType::Example => self
+                .join(profile.output_dir()),
+        )
+    }
+
+    /// Get the path to the binary for a given profile when cross-compiled for `target_triple`
+    pub fn get_cross_binary_path(&self, profile: &str, target_triple: &str) -> PathBuf {
+        self.binary_path_in(
+            &self
                 .workspace_root
                 .join("target")
-                .join(profile)
-                .join("examples")
-                .join(&self.name),
+                .join(target_triple)
+                .join(BuildProfile::from_name(profile).output_dir()),
+        )
+    }
+
+    /// Path of the binary within a profile's output directory
+    fn binary_path_in(&self, profile_dir: &Path) -> PathBuf {
+        match self.target_type {
+            TargetType::App => profile_dir.join(&self.name),
+            TargetType::Example => profile_dir.join("examples").join(&self.name),
         }
     }
 
@@ -81,6 +106,17 @@ impl BevyTarget {
     pub fn is_example(&self) -> bool { self.target_type == TargetType::Example }
 }
 
+/// What a target's package declares for BRP, as `brp_setup_check` reports it
+#[derive(Debug, Clone)]
+pub struct BrpManifestSetup {
+    /// The `bevy` dependency enables the `bevy_remote` feature
+    pub bevy_remote_feature: bool,
+    /// The package depends on `bevy_brp_extras`
+    pub extras_dependency:   bool,
+    /// Source file of the target's `main`
+    pub target_source:       PathBuf,
+}
+
 /// Detects binary targets in a project or workspace
 pub struct CargoDetector {
     metadata: Metadata,
@@ -119,30 +155,49 @@ impl CargoDetector {
         let workspace_root: PathBuf = self.metadata.workspace_root.clone().into();
         let package_name = package.name.to_string();
         let manifest_path: PathBuf = package.manifest_path.clone().into();
+        let dylib_feature = self.dylib_feature(package);
+        let bevy_version = self.bevy_version(package);
+        let features: Vec<String> = package
+            .features
+            .keys()
+            .filter(|feature| feature.as_str() != "default")
+            .cloned()
+            .collect();
+        let default_features = package.features.get("default").cloned().unwrap_or_default();
 
         let mut targets = Vec::new();
 
         // Extract apps
         for target in package.targets.iter().filter(|t| t.is_bin()) {
             targets.push(BevyTarget {
-                name:           target.name.clone(),
-                target_type:    TargetType::App,
-                package_name:   package_name.clone(),
-                workspace_root: workspace_root.clone(),
-                manifest_path:  manifest_path.clone(),
-                relative_path:  PathBuf::new(), // Will be set by scanning logic
+                name:              target.name.clone(),
+                target_type:       TargetType::App,
+                package_name:      package_name.clone(),
+                workspace_root:    workspace_root.clone(),
+                manifest_path:     manifest_path.clone(),
+                relative_path:     PathBuf::new(), // Will be set by scanning logic
+                dylib_feature:     dylib_feature.clone(),
+                bevy_version:      bevy_version.clone(),
+                features:          features.clone(),
+                default_features:  default_features.clone(),
+                required_features: target.required_features.clone(),
             });
         }
 
         // Extract examples
         for target in package.targets.iter().filter(|t| t.is_example()) {
             targets.push(BevyTarget {
-                name:           target.name.clone(),
-                target_type:    TargetType::Example,
-                package_name:   package_name.clone(),
-                workspace_root: workspace_root.clone(),
-                manifest_path:  manifest_path.clone(),
-                relative_path:  PathBuf::new(), // Will be set by scanning logic
+                name:              target.name.clone(),
+                target_type:       TargetType::Example,
+                package_name:      package_name.clone(),
+                workspace_root:    workspace_root.clone(),
+                manifest_path:     manifest_path.clone(),
+                relative_path:     PathBuf::new(), // Will be set by scanning logic
+                dylib_feature:     dylib_feature.clone(),
+                bevy_version:      bevy_version.clone(),
+                features:          features.clone(),
+                default_features:  default_features.clone(),
+                required_features: target.required_features.clone(),
             });
         }
 
@@ -175,6 +230,73 @@ impl CargoDetector {
             .collect()
     }
 
+    /// BRP dependencies of `target`'s package and the source file of its `main` - `None` when
+    /// the package isn't in this workspace
+    pub fn brp_manifest_setup(&self, target: &BevyTarget) -> Option<BrpManifestSetup> {
+        let package = self
+            .metadata
+            .packages
+            .iter()
+            .find(|package| package.manifest_path.as_std_path() == target.manifest_path)?;
+        let source = package.targets.iter().find(|candidate| {
+            candidate.name == target.name
+                && match target.target_type {
+                    TargetType::App => candidate.is_bin(),
+                    TargetType::Example => candidate.is_example(),
+                }
+        })?;
+
+        Some(BrpManifestSetup {
+            bevy_remote_feature: package.dependencies.iter().any(|dep| {
+                dep.name == "bevy" && dep.features.iter().any(|feature| feature == "bevy_remote")
+            }),
+            extras_dependency:   package
+                .dependencies
+                .iter()
+                .any(|dep| dep.name == "bevy_brp_extras"),
+            target_source:       source.src_path.clone().into(),
+        })
+    }
+
+    /// The feature that enables Bevy's `dynamic_linking` for `package` - `bevy/dynamic_linking`
+    /// when it depends on a `bevy` that has the feature, or `dynamic_linking` for `bevy` itself
+    fn dylib_feature(&self, package: &Package) -> Option<String> {
+        const DYNAMIC_LINKING: &str = "dynamic_linking";
+
+        if package.name.as_str() == "bevy" {
+            return package
+                .features
+                .contains_key(DYNAMIC_LINKING)
+                .then(|| DYNAMIC_LINKING.to_string());
+        }
+
+        let bevy_has_feature = self.metadata.packages.iter().any(|candidate| {
+            candidate.name.as_str() == "bevy" && candidate.features.contains_key(DYNAMIC_LINKING)
+        });
+        (Self::package_depends_on_bevy(package) && bevy_has_feature)
+            .then(|| format!("bevy/{DYNAMIC_LINKING}"))
+    }
+
+    /// Version of `bevy` that `package` builds against - the package's own version for `bevy`
+    /// itself, otherwise the resolved `bevy` package its dependency requirement matches
+    fn bevy_version(&self, package: &Package) -> Option<String> {
+        if package.name.as_str() == "bevy" {
+            return Some(package.version.to_string());
+        }
+
+        let requirement = package
+            .dependencies
+            .iter()
+            .find(|dep| dep.name == "bevy")
+            .map(|dep| &dep.req)?;
+        self.metadata
+            .packages
+            .iter()
+            .filter(|candidate| candidate.name.as_str() == "bevy")
+            .find(|candidate| requirement.matches(&candidate.version))
+            .map(|candidate| candidate.version.to_string())
+    }
+
     fn package_depends_on_bevy(package: &Package) -> bool {
         // Check direct dependencies (including workspace dependencies)
         package.dependencies.iter().any(|dep| dep.name == "bevy")
@@ -4,6 +4,7 @@ use serde_json::json;
 
 use super::cargo_detector::BevyTarget;
 use super::cargo_detector::CargoDetector;
+use super::target_spec::TargetSpec;
 use crate::app_tools::constants::PROFILE_DEBUG;
 use crate::app_tools::constants::PROFILE_RELEASE;
 
@@ -66,6 +67,8 @@ impl CollectionStrategy for BevyAppsStrategy {
     fn serialize_item(&self, item: &Self::Item, relative_path: String) -> serde_json::Value {
         json!({
             "name": item.name,
+            // A target spec that launches exactly this target when passed as target_name
+            "target": TargetSpec::format(item),
             "workspace_root": item.workspace_root.display().to_string(),
             "manifest_path": item.manifest_path.display().to_string(),
             // The relative_path field is designed for round-trip compatibility with launch functions.
@@ -105,6 +108,8 @@ impl CollectionStrategy for BrpAppsStrategy {
     fn serialize_item(&self, item: &Self::Item, _relative_path: String) -> serde_json::Value {
         json!({
             "name": item.name,
+            // A target spec that launches exactly this target when passed as target_name
+            "target": TargetSpec::format(item),
             "workspace_root": item.workspace_root.display().to_string(),
             "manifest_path": item.manifest_path.display().to_string(),
             "builds": create_builds_json(item),
@@ -141,6 +146,8 @@ impl CollectionStrategy for BevyExamplesStrategy {
     fn serialize_item(&self, item: &Self::Item, relative_path: String) -> serde_json::Value {
         json!({
             "name": item.name,
+            // A target spec that launches exactly this target when passed as target_name
+            "target": TargetSpec::format(item),
             "package_name": item.package_name,
             "manifest_path": item.manifest_path.display().to_string(),
             // The relative_path field is designed for round-trip compatibility with launch functions.
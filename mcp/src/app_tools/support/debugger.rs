@@ -0,0 +1,282 @@
+//! `debugger` launch option - running instances under lldb, gdb or rr
+//!
+//! Launched instances have no terminal, so an interactive debugger can't be started in front of
+//! them. `lldb` and `gdb` instead run the binary under their remote debugging server, stopped
+//! before `main` until a debugger attaches to the reported port; `rr` records the run for replay
+//! after it exits. Either way the instance keeps its log file and BRP port.
+
+use std::fs::File;
+use std::io::Read;
+use std::net::Ipv4Addr;
+use std::net::TcpListener;
+use std::path::Path;
+use std::process::Command;
+
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::error::Error;
+use crate::error::Result;
+
+/// Debugger to run launched instances under
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Debugger {
+    /// Serve the instance with `lldb-server` and attach with `lldb`
+    Lldb,
+    /// Serve the instance with `gdbserver` and attach with `gdb`
+    Gdb,
+    /// Record the instance with `rr` and replay it after it exits
+    Rr,
+}
+
+/// A wrapped instance command and how to debug it
+pub struct DebugSession {
+    /// Command running the binary under the debugger
+    pub command: Command,
+    /// How to debug the instance
+    pub attach:  DebugAttach,
+}
+
+/// How to debug an instance running under a debugger
+#[derive(Debug, Clone)]
+pub struct DebugAttach {
+    /// Port the debugging server listens on, for debuggers that attach
+    pub debug_port: Option<u16>,
+    /// Command to run to debug the instance
+    pub command:    String,
+}
+
+impl Debugger {
+    /// Executable that runs the target
+    const fn program(self) -> &'static str {
+        match self {
+            Self::Lldb => "lldb-server",
+            Self::Gdb => "gdbserver",
+            Self::Rr => "rr",
+        }
+    }
+
+    /// Whether instances wait for a debugger to attach before starting
+    pub const fn waits_for_attach(self) -> bool { !matches!(self, Self::Rr) }
+
+    /// Fail unless the debugger's executable is on `PATH`
+    pub fn ensure_installed(self) -> Result<()> {
+        let program = self.program();
+        let installed = std::env::var_os("PATH").is_some_and(|path| {
+            std::env::split_paths(&path).any(|dir| dir.join(program).is_file())
+        });
+        if installed {
+            Ok(())
+        } else {
+            Err(Error::missing(&format!("debugger executable '{program}' on PATH")).into())
+        }
+    }
+
+    /// Command running `binary` with `args` under the debugger
+    pub fn wrap(self, binary: &Path, args: &[String]) -> Result<DebugSession> {
+        let mut command = Command::new(self.program());
+        let mut session = match self {
+            Self::Lldb => {
+                let port = free_port()?;
+                command
+                    .arg("gdbserver")
+                    .arg(format!("127.0.0.1:{port}"))
+                    .arg("--")
+                    .arg(binary);
+                DebugSession {
+                    command,
+                    attach: DebugAttach {
+                        debug_port: Some(port),
+                        command:    format!(
+                            "lldb {} -o 'gdb-remote 127.0.0.1:{port}'",
+                            binary.display()
+                        ),
+                    },
+                }
+            },
+            Self::Gdb => {
+                let port = free_port()?;
+                command.arg(format!("127.0.0.1:{port}")).arg(binary);
+                DebugSession {
+                    command,
+                    attach: DebugAttach {
+                        debug_port: Some(port),
+                        command:    format!(
+                            "gdb {} -ex 'target remote 127.0.0.1:{port}'",
+                            binary.display()
+                        ),
+                    },
+                }
+            },
+            Self::Rr => {
+                command.arg("record").arg(binary);
+                DebugSession {
+                    command,
+                    attach: DebugAttach {
+                        debug_port: None,
+                        command:    "rr replay".to_string(),
+                    },
+                }
+            },
+        };
+        session.command.args(args);
+        Ok(session)
+    }
+}
+
+impl std::fmt::Display for Debugger {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Lldb => write!(f, "lldb"),
+            Self::Gdb => write!(f, "gdb"),
+            Self::Rr => write!(f, "rr"),
+        }
+    }
+}
+
+/// Marker found in binaries carrying DWARF - the ELF `.debug_info` (or compressed
+/// `.zdebug_info`) section name and the Mach-O `__debug_info` one
+const DWARF_SECTION_MARKER: &[u8] = b"debug_info";
+
+/// Marker of a Mach-O debug map, which points at the object files holding the DWARF when cargo
+/// leaves debug info unpacked on macOS
+const DEBUG_MAP_MARKER: &[u8] = b".rcgu.o";
+
+/// Whether `binary` carries debug info a debugger can use - embedded DWARF, a debug map, or a
+/// `.dSYM` bundle or `.pdb` file next to it
+///
+/// The binary is scanned in chunks, so a large one is never read into memory at once.
+pub fn has_debug_info(binary: &Path) -> bool {
+    let beside = |extension: &str| {
+        let mut path = binary.as_os_str().to_owned();
+        path.push(extension);
+        Path::new(&path).exists()
+    };
+    if beside(".dSYM") || binary.with_extension("pdb").exists() {
+        return true;
+    }
+    File::open(binary)
+        .is_ok_and(|file| contains_any(file, &[DWARF_SECTION_MARKER, DEBUG_MAP_MARKER]))
+}
+
+/// Whether `reader` contains any of `markers`, also across chunk boundaries
+fn contains_any(mut reader: impl Read, markers: &[&[u8]]) -> bool {
+    const CHUNK_SIZE: usize = 1 << 20;
+    let overlap = markers.iter().map(|marker| marker.len()).max().unwrap_or(0);
+    let mut window = Vec::with_capacity(CHUNK_SIZE + overlap);
+    let mut chunk = vec![0; CHUNK_SIZE];
+    loop {
+        let read = match reader.read(&mut chunk) {
+            Ok(0) | Err(_) => return false,
+            Ok(read) => read,
+        };
+        window.extend_from_slice(&chunk[..read]);
+        if markers.iter().any(|marker| {
+            window
+                .windows(marker.len())
+                .any(|candidate| candidate == *marker)
+        }) {
+            return true;
+        }
+        // Keep the tail, in case a marker straddles this chunk and the next
+        let keep = window.len().saturating_sub(overlap);
+        window.drain(..keep);
+    }
+}
+
+/// A port nothing listens on right now, for a debugging server
+fn free_port() -> Result<u16> {
+    TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+        .and_then(|listener| listener.local_addr())
+        .map(|address| address.port())
+        .map_err(|e| Error::failed_to("find a free port for the debugging server", e).into())
+}
+
+#[cfg(test)]
+#[allow(clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    fn args(command: &Command) -> Vec<String> {
+        command
+            .get_args()
+            .map(|arg| arg.to_string_lossy().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn servers_listen_on_the_reported_port_and_pass_instance_args() {
+        let binary = Path::new("/game/target/debug/game");
+        let instance_args = vec!["--server".to_string()];
+
+        let lldb = Debugger::Lldb
+            .wrap(binary, &instance_args)
+            .expect("lldb session");
+        let port = lldb.attach.debug_port.expect("lldb port");
+        assert_eq!(
+            args(&lldb.command),
+            vec![
+                "gdbserver".to_string(),
+                format!("127.0.0.1:{port}"),
+                "--".to_string(),
+                "/game/target/debug/game".to_string(),
+                "--server".to_string(),
+            ]
+        );
+        assert!(
+            lldb.attach
+                .command
+                .contains(&format!("gdb-remote 127.0.0.1:{port}"))
+        );
+
+        let gdb = Debugger::Gdb
+            .wrap(binary, &instance_args)
+            .expect("gdb session");
+        let port = gdb.attach.debug_port.expect("gdb port");
+        assert_eq!(
+            args(&gdb.command),
+            vec![
+                format!("127.0.0.1:{port}"),
+                "/game/target/debug/game".to_string(),
+                "--server".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn rr_records_without_a_port() {
+        let rr = Debugger::Rr
+            .wrap(Path::new("game"), &[])
+            .expect("rr session");
+        assert_eq!(args(&rr.command), vec!["record", "game"]);
+        assert_eq!(rr.attach.debug_port, None);
+        assert!(!Debugger::Rr.waits_for_attach());
+    }
+
+    #[test]
+    fn debug_info_is_found_in_the_binary_or_beside_it() {
+        let dir = tempfile::TempDir::new().expect("temp dir");
+        let stripped = dir.path().join("stripped");
+        std::fs::write(&stripped, b"\x7fELF.text.rodata").expect("write binary");
+        assert!(!has_debug_info(&stripped));
+
+        let with_dwarf = dir.path().join("with_dwarf");
+        std::fs::write(&with_dwarf, b"\x7fELF.text.debug_info.debug_line").expect("write binary");
+        assert!(has_debug_info(&with_dwarf));
+
+        std::fs::create_dir(dir.path().join("stripped.dSYM")).expect("dSYM bundle");
+        assert!(has_debug_info(&stripped));
+
+        assert!(!has_debug_info(&dir.path().join("missing")));
+    }
+
+    #[test]
+    fn markers_are_found_across_chunk_boundaries() {
+        let mut bytes = vec![0; (1 << 20) - 4];
+        bytes.extend_from_slice(b"debug_info");
+        assert!(contains_any(bytes.as_slice(), &[DWARF_SECTION_MARKER]));
+        assert!(!contains_any(&b"debug_inf"[..], &[DWARF_SECTION_MARKER]));
+    }
+}
@@ -2,12 +2,19 @@ use bevy_brp_mcp_macros::ResultStruct;
 use serde::Deserialize;
 use serde::Serialize;
 
+use super::build_diagnostics::CompilerError;
+use super::port_check::PortConflict;
+use super::scanning::TargetCandidate;
+
 /// Error when multiple targets with the same name exist
 #[derive(Debug, Clone, Serialize, Deserialize, ResultStruct)]
 pub struct PathDisambiguationError {
     #[to_error_info]
     available_paths: Vec<String>,
 
+    #[to_error_info]
+    candidates: Vec<TargetCandidate>,
+
     #[to_error_info]
     target_name: String,
 
@@ -15,7 +22,28 @@ pub struct PathDisambiguationError {
     target_type: String,
 
     #[to_message(
-        message_template = "Found multiple {target_type}s named `{target_name}`. Please specify which path to use."
+        message_template = "Found multiple {target_type}s named `{target_name}`. Please specify which path or package to use."
+    )]
+    message_template: String,
+}
+
+/// Error when no target with the name is in the package given for disambiguation
+#[derive(Debug, Clone, Serialize, Deserialize, ResultStruct)]
+pub struct TargetNotInPackage {
+    #[to_error_info]
+    target_name: String,
+
+    #[to_error_info]
+    target_type: String,
+
+    #[to_error_info]
+    package: String,
+
+    #[to_error_info]
+    candidates: Vec<TargetCandidate>,
+
+    #[to_message(
+        message_template = "No {target_type} named `{target_name}` in package `{package}`"
     )]
     message_template: String,
 }
@@ -53,3 +81,43 @@ pub struct NoTargetsFoundError {
     #[to_message(message_template = "No {target_type} named `{target_name}` found in workspace")]
     message_template: String,
 }
+
+/// Error when cargo fails to build the target
+#[derive(Debug, Clone, Serialize, Deserialize, ResultStruct)]
+pub struct BuildFailedError {
+    #[to_error_info]
+    target_name: String,
+
+    #[to_error_info]
+    target_type: String,
+
+    #[to_error_info]
+    profile: String,
+
+    #[to_error_info]
+    error_count: usize,
+
+    #[to_error_info]
+    errors: Vec<CompilerError>,
+
+    #[to_error_info(skip_if_none)]
+    stderr: Option<String>,
+
+    #[to_message(
+        message_template = "Cargo build failed for {target_type} `{target_name}` (profile: {profile}) with {error_count} compiler errors"
+    )]
+    message_template: String,
+}
+
+/// Error when something already listens on a port the launch would use
+#[derive(Debug, Clone, Serialize, Deserialize, ResultStruct)]
+pub struct PortInUseError {
+    #[to_error_info]
+    conflicts: Vec<PortConflict>,
+
+    #[to_error_info]
+    suggested_ports: Vec<u16>,
+
+    #[to_message]
+    message_template: Option<String>,
+}
@@ -0,0 +1,103 @@
+//! `fast_build` launch option - Bevy dynamic linking for debug builds
+//!
+//! Linking Bevy as a dynamic library cuts incremental debug builds from minutes to seconds. The
+//! feature only applies to debug builds of packages whose `bevy` dependency offers
+//! `dynamic_linking`; anything else builds normally and the launch log says why.
+
+use std::ffi::OsString;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::Command;
+
+use super::cargo_detector::BevyTarget;
+use crate::app_tools::constants::PROFILE_DEBUG;
+
+/// Environment variable the platform's loader searches for dynamic libraries
+#[cfg(target_os = "macos")]
+const LIBRARY_PATH_ENV_VAR: &str = "DYLD_FALLBACK_LIBRARY_PATH";
+/// Environment variable the platform's loader searches for dynamic libraries
+#[cfg(target_os = "windows")]
+const LIBRARY_PATH_ENV_VAR: &str = "PATH";
+/// Environment variable the platform's loader searches for dynamic libraries
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+const LIBRARY_PATH_ENV_VAR: &str = "LD_LIBRARY_PATH";
+
+/// Whether a launch builds with Bevy dynamic linking, and why not when it doesn't
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FastBuild {
+    /// `fast_build` wasn't requested
+    NotRequested,
+    /// Building with this Cargo feature
+    Enabled(String),
+    /// Requested but not applied, for this reason
+    Unavailable(&'static str),
+}
+
+impl FastBuild {
+    /// Decide whether a launch of `target` with `profile` can use dynamic linking
+    pub fn decide(requested: bool, profile: &str, target: &BevyTarget) -> Self {
+        if !requested {
+            return Self::NotRequested;
+        }
+        if profile != PROFILE_DEBUG {
+            return Self::Unavailable("dynamic linking is only used for debug builds");
+        }
+        target.dylib_feature.clone().map_or(
+            Self::Unavailable("bevy has no dynamic_linking feature here"),
+            Self::Enabled,
+        )
+    }
+
+    /// The Cargo feature to add to the build, if any
+    pub fn feature(&self) -> Option<&str> {
+        match self {
+            Self::Enabled(feature) => Some(feature),
+            Self::NotRequested | Self::Unavailable(_) => None,
+        }
+    }
+
+    /// Line recording the decision in the launch log
+    pub fn log_note(&self) -> Option<String> {
+        match self {
+            Self::NotRequested => None,
+            Self::Enabled(feature) => Some(format!("Fast build: enabled ({feature})")),
+            Self::Unavailable(reason) => Some(format!(
+                "Fast build: not applied - {reason}, building normally"
+            )),
+        }
+    }
+}
+
+/// Let a binary built with dynamic linking find `libbevy_dylib` and the Rust standard library
+/// when it is run directly rather than through `cargo run`
+pub fn set_dynamic_library_path(cmd: &mut Command, binary_path: &Path) {
+    let mut paths: Vec<PathBuf> = Vec::new();
+    if let Some(profile_dir) = binary_path.parent() {
+        paths.push(profile_dir.join("deps"));
+        paths.push(profile_dir.to_path_buf());
+    }
+    if let Some(sysroot) = rust_sysroot() {
+        paths.push(sysroot.join("lib"));
+    }
+    if let Some(existing) = std::env::var_os(LIBRARY_PATH_ENV_VAR) {
+        paths.extend(std::env::split_paths(&existing));
+    }
+
+    if let Ok(joined) = std::env::join_paths(paths) {
+        cmd.env(LIBRARY_PATH_ENV_VAR, joined);
+    }
+}
+
+/// Sysroot of the active Rust toolchain, which holds the `std` dynamic library
+fn rust_sysroot() -> Option<PathBuf> {
+    let output = Command::new("rustc")
+        .arg("--print")
+        .arg("sysroot")
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let sysroot = String::from_utf8(output.stdout).ok()?;
+    Some(PathBuf::from(OsString::from(sysroot.trim())))
+}
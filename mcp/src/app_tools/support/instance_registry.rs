@@ -0,0 +1,391 @@
+//! Registry of the instances this server launched, persisted across restarts
+//!
+//! Every launch records its instances here, and each server writes its own registry file,
+//! `bevy_brp_mcp_instances_<pid>_<start time>.json` in the log directory - servers running side by
+//! side never overwrite each other's instances. A restarted server adopts the files of servers
+//! that are no longer running, claiming each by renaming it so no two servers adopt the same
+//! instances, and keeps only the instances whose process is still the one that was launched - a
+//! PID is only trusted when the process also has the recorded start time, since PIDs are reused.
+
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::LazyLock;
+use std::sync::Mutex;
+
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+use sysinfo::ProcessesToUpdate;
+use sysinfo::System;
+use tracing::debug;
+use tracing::warn;
+
+use super::cargo_detector::TargetType;
+use super::launch_common::LaunchedInstance;
+use super::remote;
+use crate::brp_tools::Port;
+use crate::brp_tools::RemoteHost;
+use crate::brp_tools::register_remote_host;
+use crate::log_tools::get_log_directory;
+
+/// Start of each server's registry file name, inside the log directory
+const REGISTRY_FILE_PREFIX: &str = "bevy_brp_mcp_instances_";
+
+/// Extension of registry files
+const REGISTRY_FILE_EXTENSION: &str = "json";
+
+/// A launched instance, with what is needed to find, stop and tail it later
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct RegisteredInstance {
+    /// Name of the app or example
+    pub target_name:        String,
+    /// `app` or `example`
+    pub target_type:        String,
+    /// Build profile it was launched with
+    pub profile:            String,
+    /// Process ID
+    pub pid:                u32,
+    /// BRP port
+    pub port:               u16,
+    /// Log file receiving the instance's output
+    pub log_file:           String,
+    /// Separate stderr log, when stderr is split from `log_file`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stderr_log_file:    Option<String>,
+    /// Label given with the instance's launch override
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label:              Option<String>,
+    /// Remote host profile the instance runs under; `pid` is then the local SSH process
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remote_host:        Option<String>,
+    /// PID of the instance on the remote machine
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remote_pid:         Option<u32>,
+    /// Process start time in seconds since the epoch, to tell a reused PID apart
+    pub started_at:         u64,
+    /// When the watchdog found the instance no longer answering BRP, while it still doesn't
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unresponsive_since: Option<String>,
+    /// Who holds the control lock on `port`, while a lock is held
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub control_owner:      Option<String>,
+}
+
+static INSTANCES: LazyLock<Mutex<Vec<RegisteredInstance>>> =
+    LazyLock::new(|| Mutex::new(Vec::new()));
+
+/// This server's registry file
+static REGISTRY_PATH: LazyLock<PathBuf> = LazyLock::new(|| {
+    let pid = std::process::id();
+    let mut system = System::new();
+    system.refresh_processes(
+        ProcessesToUpdate::Some(&[sysinfo::Pid::from_u32(pid)]),
+        true,
+    );
+    let started_at = start_time(&system, pid).unwrap_or_default();
+    get_log_directory().join(registry_file_name(pid, started_at))
+});
+
+/// Record freshly launched instances, replacing any earlier instance on the same ports
+pub fn register(
+    target_name: &str,
+    target_type: TargetType,
+    profile: &str,
+    instances: &[LaunchedInstance],
+) {
+    let mut system = System::new();
+    system.refresh_processes(ProcessesToUpdate::All, true);
+
+    let Ok(mut registered) = INSTANCES.lock() else {
+        return;
+    };
+    for instance in instances {
+        let Some(started_at) = start_time(&system, instance.pid) else {
+            // Exited already - nothing to track
+            continue;
+        };
+        registered.retain(|existing| existing.port != instance.port);
+        registered.push(RegisteredInstance {
+            target_name: target_name.to_string(),
+            target_type: target_type.to_string(),
+            profile: profile.to_string(),
+            pid: instance.pid,
+            port: instance.port,
+            log_file: instance.log_file.clone(),
+            stderr_log_file: instance.stderr_log_file.clone(),
+            label: instance.label.clone(),
+            remote_host: instance.remote_host.clone(),
+            remote_pid: instance.remote_pid,
+            started_at,
+            unresponsive_since: None,
+            control_owner: None,
+        });
+    }
+    persist(&registered);
+}
+
+/// Forget the instance on `port`, after it was shut down
+pub fn unregister(port: Port) {
+    let Ok(mut registered) = INSTANCES.lock() else {
+        return;
+    };
+    let count = registered.len();
+    registered.retain(|instance| instance.port != *port);
+    if registered.len() != count {
+        persist(&registered);
+    }
+}
+
+/// Flag the instance on `port` as not answering BRP since `since`, or clear the flag with `None`
+pub fn set_unresponsive(port: u16, since: Option<String>) {
+    let Ok(mut registered) = INSTANCES.lock() else {
+        return;
+    };
+    if let Some(instance) = registered.iter_mut().find(|instance| instance.port == port)
+        && instance.unresponsive_since != since
+    {
+        instance.unresponsive_since = since;
+        persist(&registered);
+    }
+}
+
+/// Record `owner` as holding the control lock on `port`, or clear it with `None`
+pub fn set_control_owner(port: u16, owner: Option<String>) {
+    let Ok(mut registered) = INSTANCES.lock() else {
+        return;
+    };
+    if let Some(instance) = registered.iter_mut().find(|instance| instance.port == port)
+        && instance.control_owner != owner
+    {
+        instance.control_owner = owner;
+        persist(&registered);
+    }
+}
+
+/// The registered instances that are still running, sorted by port
+pub fn live_instances() -> Vec<RegisteredInstance> {
+    let Ok(mut registered) = INSTANCES.lock() else {
+        return Vec::new();
+    };
+    let count = registered.len();
+    retain_live(&mut registered);
+    if registered.len() != count {
+        persist(&registered);
+    }
+
+    let mut instances = registered.clone();
+    instances.sort_by_key(|instance| instance.port);
+    instances
+}
+
+/// Adopt the instances of servers that are no longer running, dropping those that have exited
+pub fn reload() {
+    let mut instances = adopt_orphaned(&get_log_directory(), &REGISTRY_PATH);
+    retain_live(&mut instances);
+    restore_remote_hosts(&instances);
+    debug!(
+        "Reloaded {} running instances from the registry",
+        instances.len()
+    );
+
+    if let Ok(mut registered) = INSTANCES.lock() {
+        for instance in instances {
+            registered.retain(|existing| existing.port != instance.port);
+            registered.push(instance);
+        }
+        persist(&registered);
+    }
+}
+
+/// The instances in the registry files in `dir` whose server has exited - each adopted file is
+/// claimed and removed, `own` is skipped
+fn adopt_orphaned(dir: &Path, own: &Path) -> Vec<RegisteredInstance> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut system = System::new();
+    system.refresh_processes(ProcessesToUpdate::All, true);
+
+    let mut instances = Vec::new();
+    for path in entries.filter_map(|entry| entry.ok().map(|entry| entry.path())) {
+        let Some((server_pid, server_started_at)) = parse_registry_file_name(&path) else {
+            continue;
+        };
+        if path == own || start_time(&system, server_pid) == Some(server_started_at) {
+            continue;
+        }
+        // Another restarted server may be adopting the same file - only one rename succeeds
+        let claimed = path.with_extension(format!("adopted_{}", std::process::id()));
+        if fs::rename(&path, &claimed).is_err() {
+            continue;
+        }
+        match fs::read_to_string(&claimed)
+            .map_err(|e| e.to_string())
+            .and_then(|contents| {
+                serde_json::from_str::<Vec<RegisteredInstance>>(&contents)
+                    .map_err(|e| e.to_string())
+            }) {
+            Ok(adopted) => instances.extend(adopted),
+            Err(e) => warn!(
+                "Ignoring unreadable instance registry {}: {e}",
+                path.display()
+            ),
+        }
+        let _ = fs::remove_file(&claimed);
+    }
+    instances
+}
+
+/// Registry file name of the server with process `pid`, started at `started_at`
+fn registry_file_name(pid: u32, started_at: u64) -> String {
+    format!("{REGISTRY_FILE_PREFIX}{pid}_{started_at}.{REGISTRY_FILE_EXTENSION}")
+}
+
+/// Process ID and start time of the server a registry file belongs to
+fn parse_registry_file_name(path: &Path) -> Option<(u32, u64)> {
+    let (pid, started_at) = path
+        .file_name()?
+        .to_str()?
+        .strip_prefix(REGISTRY_FILE_PREFIX)?
+        .strip_suffix(REGISTRY_FILE_EXTENSION)?
+        .strip_suffix('.')?
+        .split_once('_')?;
+    Some((pid.parse().ok()?, started_at.parse().ok()?))
+}
+
+/// Record the forwarded ports of reloaded remote instances again
+fn restore_remote_hosts(instances: &[RegisteredInstance]) {
+    for instance in instances {
+        let Some(profile) = &instance.remote_host else {
+            continue;
+        };
+        // The profile file may have changed since; the profile name still identifies the host
+        let destination = remote::load_profile(profile)
+            .map_or_else(|_| profile.clone(), |profile| profile.ssh_destination);
+        register_remote_host(
+            Port(instance.port),
+            RemoteHost {
+                profile: profile.clone(),
+                destination,
+                remote_pid: instance.remote_pid,
+            },
+        );
+    }
+}
+
+/// Drop instances whose process has exited or whose PID now belongs to another process
+fn retain_live(instances: &mut Vec<RegisteredInstance>) {
+    if instances.is_empty() {
+        return;
+    }
+    let mut system = System::new();
+    system.refresh_processes(ProcessesToUpdate::All, true);
+    instances.retain(|instance| start_time(&system, instance.pid) == Some(instance.started_at));
+}
+
+/// Start time of the running process `pid`
+fn start_time(system: &System, pid: u32) -> Option<u64> {
+    system
+        .process(sysinfo::Pid::from_u32(pid))
+        .filter(|process| !matches!(process.status(), sysinfo::ProcessStatus::Zombie))
+        .map(sysinfo::Process::start_time)
+}
+
+/// Write this server's registry, replacing the file in one step so a crash never leaves it half
+/// written
+fn persist(instances: &[RegisteredInstance]) {
+    let path: &Path = &REGISTRY_PATH;
+    let temp_path = path.with_extension("json.tmp");
+    let result = serde_json::to_vec_pretty(instances)
+        .map_err(std::io::Error::other)
+        .and_then(|contents| fs::write(&temp_path, contents))
+        .and_then(|()| fs::rename(&temp_path, path));
+    if let Err(e) = result {
+        warn!(
+            "Failed to persist instance registry to {}: {e}",
+            path.display()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn instance(pid: u32, started_at: u64, port: u16) -> RegisteredInstance {
+        RegisteredInstance {
+            target_name: "game".to_string(),
+            target_type: "app".to_string(),
+            profile: "debug".to_string(),
+            pid,
+            port,
+            log_file: String::new(),
+            stderr_log_file: None,
+            label: None,
+            remote_host: None,
+            remote_pid: None,
+            started_at,
+            unresponsive_since: None,
+            control_owner: None,
+        }
+    }
+
+    fn write_registry(dir: &Path, pid: u32, started_at: u64, instances: &[RegisteredInstance]) {
+        let contents = serde_json::to_vec(instances)
+            .unwrap_or_else(|e| unreachable!("instances serialize: {e}"));
+        fs::write(dir.join(registry_file_name(pid, started_at)), contents)
+            .unwrap_or_else(|e| unreachable!("temp dir is writable: {e}"));
+    }
+
+    #[test]
+    fn registry_file_names_round_trip() {
+        let name = registry_file_name(4321, 1_760_000_000);
+        assert_eq!(
+            parse_registry_file_name(Path::new(&name)),
+            Some((4321, 1_760_000_000))
+        );
+        assert_eq!(
+            parse_registry_file_name(Path::new("bevy_brp_mcp_instances_4321_1.json.tmp")),
+            None
+        );
+        assert_eq!(
+            parse_registry_file_name(Path::new("bevy_brp_mcp_game_port15702_1.log")),
+            None
+        );
+    }
+
+    #[test]
+    fn only_files_of_exited_servers_are_adopted() {
+        let dir = TempDir::new().unwrap_or_else(|e| unreachable!("temp dir: {e}"));
+        let mut system = System::new();
+        system.refresh_processes(ProcessesToUpdate::All, true);
+        let pid = std::process::id();
+        let started_at =
+            start_time(&system, pid).unwrap_or_else(|| unreachable!("the test process runs"));
+
+        // A server that exited long ago, and one that is still running - this test process
+        write_registry(dir.path(), u32::MAX - 1, 1, &[instance(11, 1, 15_702)]);
+        write_registry(dir.path(), pid, started_at, &[instance(12, 1, 15_703)]);
+        let own = dir.path().join(registry_file_name(1, 1));
+        write_registry(dir.path(), 1, 1, &[instance(13, 1, 15_704)]);
+
+        let adopted = adopt_orphaned(dir.path(), &own);
+
+        assert_eq!(adopted, vec![instance(11, 1, 15_702)]);
+        let mut remaining: Vec<String> = fs::read_dir(dir.path())
+            .unwrap_or_else(|e| unreachable!("temp dir is readable: {e}"))
+            .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+            .collect();
+        remaining.sort();
+        let mut expected = vec![
+            registry_file_name(1, 1),
+            registry_file_name(pid, started_at),
+        ];
+        expected.sort();
+        assert_eq!(remaining, expected);
+        assert!(adopt_orphaned(dir.path(), &own).is_empty());
+    }
+}
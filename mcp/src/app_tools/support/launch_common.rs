@@ -1,17 +1,38 @@
+use std::fmt::Write;
 use std::marker::PhantomData;
 use std::path::Path;
 use std::path::PathBuf;
 use std::process::Command;
+use std::time::Duration;
 
 use bevy_brp_mcp_macros::ResultStruct;
 use error_stack::Report;
+use schemars::JsonSchema;
 use serde::Deserialize;
 use serde::Serialize;
 
+use super::build_diagnostics;
+use super::debugger::DebugAttach;
+use super::debugger::DebugSession;
+use super::debugger::Debugger;
+use super::debugger::has_debug_info;
+use super::errors::BuildFailedError;
 use super::errors::NoTargetsFoundError;
-use super::errors::PathDisambiguationError;
 use super::errors::TargetNotFoundAtSpecifiedPath;
+use super::fast_build::FastBuild;
+use super::fast_build::set_dynamic_library_path;
+use super::instance_registry;
+use super::launch_presets;
+use super::logging::LaunchLogs;
+use super::logging::OutputCapture;
+use super::network_proxy;
+use super::network_proxy::NetworkProxyOptions;
+use super::network_proxy::ProxyInfo;
+use super::port_check;
 use super::process;
+use super::remote;
+use super::remote::RemoteLaunch;
+use crate::app_tools::launch_params::BuildProfile;
 use crate::app_tools::support::cargo_detector::BevyTarget;
 use crate::error::Error;
 use crate::error::Result;
@@ -35,40 +56,89 @@ pub struct LaunchConfig<T> {
     pub target_name:    String,
     pub profile:        String,
     pub path:           Option<String>,
+    pub package:        Option<String>,
     pub port:           Port,
     pub instance_count: InstanceCount,
+    pub stagger_ms:     u32,
+    pub instances:      Vec<InstanceOverride>,
     pub features:       Option<Vec<String>>,
+    pub fast_build:     bool,
+    pub output:         OutputCapture,
+    pub remote_host:    Option<String>,
+    pub debugger:       Option<Debugger>,
     _phantom:           PhantomData<T>,
 }
 
 impl<T> LaunchConfig<T> {
     /// Create a new launch configuration
+    #[allow(clippy::too_many_arguments)]
     pub const fn new(
         target_name: String,
         profile: String,
         path: Option<String>,
+        package: Option<String>,
         port: Port,
         instance_count: InstanceCount,
+        stagger_ms: u32,
+        instances: Vec<InstanceOverride>,
         features: Option<Vec<String>>,
+        fast_build: bool,
+        output: OutputCapture,
+        remote_host: Option<String>,
+        debugger: Option<Debugger>,
     ) -> Self {
         Self {
             target_name,
             profile,
             path,
+            package,
             port,
             instance_count,
+            stagger_ms,
+            instances,
             features,
+            fast_build,
+            output,
+            remote_host,
+            debugger,
             _phantom: PhantomData,
         }
     }
 }
 
 /// Represents a single launched instance
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct LaunchedInstance {
-    pub pid:      u32,
-    pub log_file: String,
-    pub port:     u16,
+    pub pid:             u32,
+    pub log_file:        String,
+    /// Separate stderr log, when stderr is split from `log_file`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stderr_log_file: Option<String>,
+    pub port:            u16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label:           Option<String>,
+    /// Remote host profile the instance runs under; `pid` is then the local SSH process
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remote_host:     Option<String>,
+    /// PID of the instance on the remote machine
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remote_pid:      Option<u32>,
+    /// Port the debugging server listens on, when launched under `lldb` or `gdb`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub debug_port:      Option<u16>,
+    /// Command to debug the instance with, when launched under a debugger
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub debugger_attach: Option<String>,
+}
+
+/// An instance process that was started, before it is reported
+struct SpawnedInstance {
+    pid:             u32,
+    log_file:        PathBuf,
+    stderr_log_file: Option<PathBuf>,
+    port:            u16,
+    remote_pid:      Option<u32>,
+    debug_attach:    Option<DebugAttach>,
 }
 
 /// Unified result type for launching Bevy apps and examples
@@ -81,47 +151,86 @@ pub struct LaunchResult {
     /// Array of launched instances (1 or more)
     #[to_result]
     instances:          Vec<LaunchedInstance>,
+    /// Number of instances launched
+    #[to_metadata]
+    #[computed(from = "instances", operation = "count")]
+    instance_count:     usize,
     /// Working directory used for launch
-    #[to_metadata(skip_if_none)]
+    #[to_metadata(skip_if_none, group = "build")]
     working_directory:  Option<String>,
     /// Build profile used (debug/release)
-    #[to_metadata(skip_if_none)]
+    #[to_metadata(skip_if_none, group = "build")]
     profile:            Option<String>,
     /// Binary path of the launched app (only for apps, not examples)
-    #[to_metadata(skip_if_none)]
+    #[to_metadata(skip_if_none, group = "build")]
     binary_path:        Option<String>,
+    /// Time taken to launch all instances
+    launch_duration:    Option<Duration>,
     /// Launch duration in milliseconds
-    #[to_metadata(skip_if_none)]
+    #[to_metadata(skip_if_none, group = "timing")]
+    #[computed(from = "launch_duration", operation = "duration_ms")]
     launch_duration_ms: Option<u128>,
     /// Launch timestamp
-    #[to_metadata(skip_if_none)]
+    #[to_metadata(skip_if_none, group = "timing")]
     launch_timestamp:   Option<String>,
     /// Workspace information
-    #[to_metadata(skip_if_none)]
+    #[to_metadata(skip_if_none, group = "build")]
     workspace:          Option<String>,
     /// Package name containing the example (only for examples)
-    #[to_metadata(skip_if_none)]
+    #[to_metadata(skip_if_none, group = "build")]
     package_name:       Option<String>,
     /// Available duplicate paths (for disambiguation errors)
     #[to_metadata(skip_if_none)]
     duplicate_paths:    Option<Vec<String>>,
+    /// Network proxy started for the instances to talk through
+    #[to_metadata(skip_if_none)]
+    network_proxy:      Option<ProxyInfo>,
     /// Message template for formatting responses
     #[to_message]
     message_template:   Option<String>,
 }
 
+impl LaunchResult {
+    /// The launched instances
+    pub fn instances(&self) -> &[LaunchedInstance] { &self.instances }
+
+    /// Report the network proxy the instances were launched behind
+    pub fn with_network_proxy(mut self, network_proxy: ProxyInfo) -> Self {
+        self.network_proxy = Some(network_proxy);
+        self
+    }
+}
+
 use crate::app_tools::instance_count::InstanceCount;
+use crate::app_tools::instance_override::InstanceOverride;
+use crate::brp_tools::BRP_DEFAULT_HOST;
+use crate::brp_tools::BRP_EXTRAS_HOST_ENV_VAR;
+use crate::brp_tools::BRP_EXTRAS_INSTANCE_COUNT_ENV_VAR;
+use crate::brp_tools::BRP_EXTRAS_INSTANCE_INDEX_ENV_VAR;
 use crate::brp_tools::BRP_EXTRAS_PORT_ENV_VAR;
 use crate::brp_tools::Port;
+use crate::brp_tools::RemoteHost;
+use crate::brp_tools::register_remote_host;
+use crate::brp_tools::reset_circuit;
 
 /// Parameters extracted from launch requests
 pub struct LaunchParams {
     pub target_name:    String,
+    /// Target type named by a combined target spec, checked against the launching tool
+    pub target_type:    Option<TargetType>,
     pub profile:        String,
     pub path:           Option<String>,
+    pub package:        Option<String>,
     pub port:           Port,
     pub instance_count: InstanceCount,
+    pub stagger_ms:     u32,
+    pub instances:      Vec<InstanceOverride>,
     pub features:       Option<Vec<String>>,
+    pub fast_build:     bool,
+    pub output:         OutputCapture,
+    pub remote_host:    Option<String>,
+    pub debugger:       Option<Debugger>,
+    pub network_proxy:  Option<NetworkProxyOptions>,
 }
 
 /// Generic launch handler that can work with any `LaunchConfig` type
@@ -154,21 +263,78 @@ impl<T: FromLaunchParams, P: ToLaunchParams + ParamStruct + for<'de> serde::Dese
     ) -> HandlerResult<'_, ToolResult<Self::Output, Self::Params>> {
         let default_profile = self.default_profile;
         Box::pin(async move {
+            // A named preset fills in the parameters the call leaves out
+            let mut ctx = ctx;
+            let input_schema = ctx.input_schema().unwrap_or_default();
+            if let Err(report) = launch_presets::apply_preset(
+                ctx.request.arguments.as_mut(),
+                &ctx.roots,
+                &input_schema,
+            ) {
+                return Ok(ToolResult {
+                    result: Err(report),
+                    params: None,
+                });
+            }
+
             // Extract typed parameters - this returns framework error on failure
             let typed_params: P = ctx.extract_parameter_values()?;
 
             // Convert to LaunchParams
             let params = typed_params.to_launch_params(default_profile);
+            if params.target_name.is_empty() {
+                return Ok(ToolResult {
+                    result: Err(Report::new(Error::missing(
+                        "target_name - pass it, or a `preset` that sets it",
+                    ))),
+                    params: Some(typed_params),
+                });
+            }
             // Port is available in params but not needed for launch
 
             // Get search paths
             let search_paths = ctx.roots;
 
+            // A target spec naming the other target type can't be launched by this tool
+            if let Some(target_type) = params.target_type
+                && target_type != T::TARGET_TYPE
+            {
+                return Ok(ToolResult {
+                    result: Err(Report::new(Error::tool_call_failed(format!(
+                        "`{}` names an {target_type} - launch it with brp_launch_bevy_{target_type}",
+                        params.target_name
+                    )))),
+                    params: Some(typed_params),
+                });
+            }
+
+            // Start the network proxy first, so the instances are launched with its address
+            let mut params = params;
+            let proxy = match start_network_proxy(&mut params).await {
+                Ok(proxy) => proxy,
+                Err(report) => {
+                    return Ok(ToolResult {
+                        result: Err(report),
+                        params: Some(typed_params),
+                    });
+                },
+            };
+
             // Create config from params
             let config = T::from_params(&params);
 
+            // Fail before building if another process holds one of the ports
+            if let Err(report) =
+                port_check::check_ports_available(config.port(), *config.instance_count()).await
+            {
+                return Ok(ToolResult {
+                    result: attach_network_proxy(Err(report), proxy),
+                    params: Some(typed_params),
+                });
+            }
+
             // Launch the target
-            let result = launch_target(&config, &search_paths);
+            let result = attach_network_proxy(launch_target(&config, &search_paths).await, proxy);
 
             Ok(ToolResult {
                 result,
@@ -178,6 +344,38 @@ impl<T: FromLaunchParams, P: ToLaunchParams + ParamStruct + for<'de> serde::Dese
     }
 }
 
+/// Start the network proxy a launch asks for, pointing its instances at it
+async fn start_network_proxy(params: &mut LaunchParams) -> Result<Option<ProxyInfo>> {
+    let Some(options) = &params.network_proxy else {
+        return Ok(None);
+    };
+    // The proxy listens on this machine's loopback, out of reach of a remote machine
+    if params.remote_host.is_some() {
+        return Err(Error::invalid(
+            "network_proxy",
+            "a network proxy can't be used with remote_host launches",
+        )
+        .into());
+    }
+    network_proxy::start_launch_proxy(options, &mut params.instances, *params.instance_count)
+        .await
+        .map(Some)
+}
+
+/// Report the network proxy in a launch's result - or stop it, when the launch failed
+fn attach_network_proxy(
+    result: Result<LaunchResult>,
+    proxy: Option<ProxyInfo>,
+) -> Result<LaunchResult> {
+    let Some(proxy) = proxy else {
+        return result;
+    };
+    if result.is_err() {
+        let _ = network_proxy::stop_proxies(Some(proxy.listen_port));
+    }
+    result.map(|launch| launch.with_network_proxy(proxy))
+}
+
 /// Trait for converting typed parameters to `LaunchParams`
 pub trait ToLaunchParams: Send + Sync {
     /// Convert to `LaunchParams` with the given default profile
@@ -191,28 +389,49 @@ pub trait FromLaunchParams: LaunchConfigTrait + Sized + Send + Sync {
 }
 
 /// Trait for configuring launch behavior for different target types (app vs example)
-pub trait LaunchConfigTrait: Clone {
+pub trait LaunchConfigTrait: Clone + Send + Sync {
     /// The target type constant (App or Example)
     const TARGET_TYPE: TargetType;
 
     /// Get the name of the target being launched
     fn target_name(&self) -> &str;
 
-    /// Get the build profile ("debug" or "release")
+    /// Get the build profile ("debug", "release" or a custom profile name)
     fn profile(&self) -> &str;
 
     /// Get the optional path for disambiguation
     fn path(&self) -> Option<&str>;
 
+    /// Get the optional package for disambiguation
+    fn package(&self) -> Option<&str>;
+
     /// Get the BRP port
     fn port(&self) -> Port;
 
     /// Get the instance count for launching multiple instances
     fn instance_count(&self) -> InstanceCount;
 
+    /// Get the delay in milliseconds between starting consecutive instances
+    fn stagger_ms(&self) -> u32;
+
+    /// Get the per-instance overrides, indexed by instance
+    fn instance_overrides(&self) -> &[InstanceOverride];
+
     /// Get the features to enable
     fn features(&self) -> Option<&Vec<String>>;
 
+    /// Whether Bevy dynamic linking was requested for debug builds
+    fn fast_build(&self) -> bool;
+
+    /// Get how the launched process's output is captured
+    fn output_capture(&self) -> OutputCapture;
+
+    /// Get the remote host profile to launch on, if any
+    fn remote_host(&self) -> Option<&str>;
+
+    /// Get the debugger to run instances under, if any
+    fn debugger(&self) -> Option<Debugger>;
+
     /// Set the port (needed for multi-instance launches)
     fn set_port(&mut self, port: Port);
 
@@ -222,18 +441,39 @@ pub trait LaunchConfigTrait: Clone {
     /// Get any extra log info specific to this target type
     fn extra_log_info(&self, target: &BevyTarget) -> Option<String>;
 
-    /// Ensure the target is built, blocking until compilation completes if needed
-    /// Returns the build state indicating whether it was fresh, rebuilt, or not found
-    fn ensure_built(&self, target: &BevyTarget) -> Result<BuildState> {
-        let manifest_dir = validate_manifest_directory(&target.manifest_path)?;
-        run_cargo_build(
-            self.target_name(),
-            Self::TARGET_TYPE,
-            self.profile(),
-            manifest_dir,
-            self.features(),
-        )
+    /// Decide whether this launch builds with Bevy dynamic linking
+    fn fast_build_decision(&self, target: &BevyTarget) -> FastBuild {
+        FastBuild::decide(self.fast_build(), self.profile(), target)
     }
+
+    /// Get the features to build and run with - the requested ones plus Bevy dynamic linking
+    /// when `fast_build` applies
+    fn build_features(&self, target: &BevyTarget) -> Option<Vec<String>> {
+        let mut features = self.features().cloned().unwrap_or_default();
+        if let Some(feature) = self.fast_build_decision(target).feature() {
+            features.push(feature.to_string());
+        }
+        (!features.is_empty()).then_some(features)
+    }
+}
+
+/// Ensure the target is built, waiting until compilation completes if needed
+/// Returns the build state indicating whether it was fresh, rebuilt, or not found
+pub async fn ensure_built<T: LaunchConfigTrait>(
+    config: &T,
+    target: &BevyTarget,
+) -> Result<BuildState> {
+    let manifest_dir = validate_manifest_directory(&target.manifest_path)?;
+    run_cargo_build(
+        config.target_name(),
+        T::TARGET_TYPE,
+        config.profile(),
+        manifest_dir,
+        config.build_features(target).as_ref(),
+        None,
+        config.debugger().is_some(),
+    )
+    .await
 }
 
 /// Validates and extracts the manifest directory from a manifest path
@@ -247,18 +487,52 @@ pub fn validate_manifest_directory(manifest_path: &Path) -> Result<&Path> {
     })
 }
 
+/// Where an instance sits among the instances of one launch
+#[derive(Debug, Clone, Copy)]
+pub struct InstancePosition {
+    /// 0-based index of the instance
+    pub index: usize,
+    /// Number of instances launched
+    pub count: usize,
+}
+
+impl InstancePosition {
+    /// Environment variables telling the instance its position
+    pub fn env_vars(self) -> [(&'static str, String); 2] {
+        [
+            (BRP_EXTRAS_INSTANCE_INDEX_ENV_VAR, self.index.to_string()),
+            (BRP_EXTRAS_INSTANCE_COUNT_ENV_VAR, self.count.to_string()),
+        ]
+    }
+}
+
+/// Environment variables telling an app where it serves BRP
+pub fn brp_env_vars(port: Port) -> [(&'static str, String); 2] {
+    [
+        (BRP_EXTRAS_PORT_ENV_VAR, port.to_string()),
+        (BRP_EXTRAS_HOST_ENV_VAR, BRP_DEFAULT_HOST.to_string()),
+    ]
+}
+
 /// Sets BRP-related environment variables on a command
 ///
-/// Currently sets:
-/// - `BRP_PORT`: When a port is provided, sets this environment variable for `bevy_brp_extras` to
-///   read
-pub fn set_brp_env_vars(cmd: &mut Command, port: Option<Port>) {
+/// `bevy_brp_extras` reads them, reports them through `brp_extras/app_info`, and hands them on
+/// to child processes the app spawns with its own port as their parent's. Currently sets:
+/// - `BRP_EXTRAS_PORT`: When a port is provided, the port to serve BRP on
+/// - `BRP_EXTRAS_HOST`: When a port is provided, the host the port is reached on
+/// - `BRP_EXTRAS_INSTANCE_INDEX` and `BRP_EXTRAS_INSTANCE_COUNT`: When an instance position is
+///   provided, the instance's 0-based index and the number of instances launched with it
+pub fn set_brp_env_vars(cmd: &mut Command, port: Option<Port>, instance: Option<InstancePosition>) {
     if let Some(port) = port {
-        cmd.env(BRP_EXTRAS_PORT_ENV_VAR, port.to_string());
+        cmd.envs(brp_env_vars(port));
+    }
+    if let Some(instance) = instance {
+        cmd.envs(instance.env_vars());
     }
 }
 
 /// Setup logging for launch operations and return log file handles
+#[allow(clippy::too_many_arguments)]
 pub fn setup_launch_logging(
     name: &str,
     target_type: TargetType,
@@ -267,13 +541,21 @@ pub fn setup_launch_logging(
     manifest_dir: &Path,
     port: Port,
     extra_log_info: Option<&str>,
-) -> Result<(PathBuf, std::fs::File)> {
+    split_stderr: bool,
+) -> Result<LaunchLogs> {
     use super::logging;
 
-    // Create log file
-    let (log_file_path, _) =
-        logging::create_log_file(name, target_type, profile, binary_path, manifest_dir, port)
-            .map_err(|e| Error::tool_call_failed(format!("Failed to create log file: {e}")))?;
+    // Create log file, and the stderr log when stderr is split
+    let (log_file_path, stderr_file_path) = logging::create_log_file(
+        name,
+        target_type,
+        profile,
+        binary_path,
+        manifest_dir,
+        port,
+        split_stderr,
+    )
+    .map_err(|e| Error::tool_call_failed(format!("Failed to create log file: {e}")))?;
 
     // Add extra info to log file if provided
     if let Some(extra_info) = extra_log_info {
@@ -281,13 +563,21 @@ pub fn setup_launch_logging(
             .map_err(|e| Error::tool_call_failed(format!("Failed to append to log file: {e}")))?;
     }
 
-    // Open log file for stdout/stderr redirection
-    let log_file_for_redirect =
-        logging::open_log_file_for_redirect(&log_file_path).map_err(|e| {
+    // Open log files for stdout/stderr redirection
+    let open_for_redirect = |path: &Path| {
+        logging::open_log_file_for_redirect(path).map_err(|e| {
             Error::tool_call_failed(format!("Failed to open log file for redirect: {e}"))
-        })?;
+        })
+    };
+    let stdout = open_for_redirect(&log_file_path)?;
+    let stderr = open_for_redirect(stderr_file_path.as_deref().unwrap_or(&log_file_path))?;
 
-    Ok((log_file_path, log_file_for_redirect))
+    Ok(LaunchLogs {
+        log_file_path,
+        stderr_file_path,
+        stdout,
+        stderr,
+    })
 }
 
 /// Build cargo command for running examples
@@ -301,28 +591,43 @@ pub fn build_cargo_example_command(
     cmd.arg("run").arg("--example").arg(example_name);
 
     // Add features flag if provided
-    if let Some(features_list) = features {
-        if !features_list.is_empty() {
-            let features_str = features_list.join(",");
-            cmd.arg("--features").arg(features_str);
-        }
+    if let Some(features_list) = features
+        && !features_list.is_empty()
+    {
+        let features_str = features_list.join(",");
+        cmd.arg("--features").arg(features_str);
     }
 
-    // Add profile flag if release
-    if profile == "release" {
-        cmd.arg("--release");
-    }
+    // Select the profile - `--release`, or `--profile` for a custom one
+    cmd.args(BuildProfile::from_name(profile).cargo_args());
 
     // Set BRP-related environment variables
-    set_brp_env_vars(&mut cmd, port);
+    set_brp_env_vars(&mut cmd, port, None);
 
     cmd
 }
 
+/// Add an instance's extra arguments and environment variables to its launch command
+///
+/// `cargo run` passes arguments after `--` through to the example.
+fn apply_instance_override(
+    cmd: &mut Command,
+    target_type: TargetType,
+    instance_override: &InstanceOverride,
+) {
+    if !instance_override.args.is_empty() {
+        if target_type == TargetType::Example {
+            cmd.arg("--");
+        }
+        cmd.args(&instance_override.args);
+    }
+    cmd.envs(&instance_override.env);
+}
+
 /// Build command for running app binaries
 pub fn build_app_command(binary_path: &Path, port: Option<Port>) -> Command {
     let mut cmd = Command::new(binary_path);
-    set_brp_env_vars(&mut cmd, port);
+    set_brp_env_vars(&mut cmd, port, None);
     cmd
 }
 
@@ -343,6 +648,8 @@ fn build_cargo_command(
     profile: &str,
     manifest_dir: &Path,
     features: Option<&Vec<String>>,
+    target_triple: Option<&str>,
+    debug_info: bool,
 ) -> Command {
     let mut cmd = Command::new("cargo");
     cmd.current_dir(manifest_dir);
@@ -352,16 +659,25 @@ fn build_cargo_command(
     target_type.add_cargo_args(&mut cmd, target_name);
 
     // Add features flag if provided
-    if let Some(features_list) = features {
-        if !features_list.is_empty() {
-            let features_str = features_list.join(",");
-            cmd.arg("--features").arg(features_str);
-        }
+    if let Some(features_list) = features
+        && !features_list.is_empty()
+    {
+        let features_str = features_list.join(",");
+        cmd.arg("--features").arg(features_str);
     }
 
-    // Add profile flag if release
-    if profile == "release" {
-        cmd.arg("--release");
+    // Select the profile - `--release`, or `--profile` for a custom one
+    let build_profile = BuildProfile::from_name(profile);
+    cmd.args(build_profile.cargo_args());
+
+    // Debuggers need symbols, which release and most custom profiles leave out
+    if debug_info {
+        cmd.env(build_profile.debug_env_var(), "true");
+    }
+
+    // Cross-compile when building for another machine
+    if let Some(target_triple) = target_triple {
+        cmd.arg("--target").arg(target_triple);
     }
 
     // Use JSON output to track freshness
@@ -371,8 +687,11 @@ fn build_cargo_command(
 }
 
 /// Execute cargo build command and validate output
-fn execute_build_command(
-    cmd: &mut Command,
+///
+/// cargo is killed when the returned future is dropped, so a call that times out doesn't leave
+/// the build running - or launch the app once it finishes.
+async fn execute_build_command(
+    cmd: Command,
     target_name: &str,
     target_type: TargetType,
     profile: &str,
@@ -385,7 +704,11 @@ fn execute_build_command(
         target_type, target_name, cmd
     );
 
-    let output = cmd.output().map_err(|e| {
+    let output = tokio::process::Command::from(cmd)
+        .kill_on_drop(true)
+        .output()
+        .await
+        .map_err(|e| {
         Error::ProcessManagement(format!(
             "Failed to run cargo build for {target_type} '{target_name}' (profile: {profile}, dir: {}): {e}",
             manifest_dir.display()
@@ -393,17 +716,49 @@ fn execute_build_command(
     })?;
 
     if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(Error::ProcessManagement(format!(
-            "Cargo build failed for {target_type} '{target_name}' (profile: {profile}, dir: {}): {stderr}",
-            manifest_dir.display()
-        ))
-        .into());
+        return Err(build_failed_report(
+            &output,
+            target_name,
+            target_type,
+            profile,
+            &manifest_dir.display().to_string(),
+        ));
     }
 
     Ok(output)
 }
 
+/// Report a failed cargo build with the compiler errors in its JSON output
+pub fn build_failed_report(
+    output: &std::process::Output,
+    target_name: &str,
+    target_type: TargetType,
+    profile: &str,
+    build_dir: &str,
+) -> Report<Error> {
+    let errors = build_diagnostics::parse_compiler_errors(&output.stdout);
+    // Without compiler errors the cause is only in cargo's own output
+    let stderr = errors
+        .is_empty()
+        .then(|| build_diagnostics::stderr_tail(&output.stderr));
+    let summary = stderr
+        .clone()
+        .unwrap_or_else(|| build_diagnostics::summarize(&errors));
+    let build_failed_error = BuildFailedError::new(
+        target_name.to_string(),
+        target_type.to_string(),
+        profile.to_string(),
+        errors.len(),
+        errors,
+        stderr,
+    );
+    Report::new(Error::Structured {
+        result: Box::new(build_failed_error),
+    })
+    .attach(format!("Build directory: {build_dir}"))
+    .attach(summary)
+}
+
 /// Parse cargo build JSON output to determine build state
 fn parse_build_output(stdout: &[u8], target_name: &str) -> BuildState {
     use serde_json::Value;
@@ -453,42 +808,67 @@ fn log_build_result(build_state: BuildState, target_name: &str, target_type: Tar
     }
 }
 
-/// Run cargo build for a target and block until completion
-pub fn run_cargo_build(
+/// Run cargo build for a target and wait until completion
+pub async fn run_cargo_build(
     target_name: &str,
     target_type: TargetType,
     profile: &str,
     manifest_dir: &Path,
     features: Option<&Vec<String>>,
+    target_triple: Option<&str>,
+    debug_info: bool,
 ) -> Result<BuildState> {
-    let mut cmd = build_cargo_command(target_name, target_type, profile, manifest_dir, features);
-    let output = execute_build_command(&mut cmd, target_name, target_type, profile, manifest_dir)?;
+    let cmd = build_cargo_command(
+        target_name,
+        target_type,
+        profile,
+        manifest_dir,
+        features,
+        target_triple,
+        debug_info,
+    );
+    let output =
+        execute_build_command(cmd, target_name, target_type, profile, manifest_dir).await?;
     let build_state = parse_build_output(&output.stdout, target_name);
     log_build_result(build_state, target_name, target_type);
 
     Ok(build_state)
 }
 
-/// Build unified result from collected vectors
+/// Build unified result from the spawned instances
 fn build_launch_result<T: LaunchConfigTrait>(
-    all_pids: Vec<u32>,
-    all_log_files: Vec<PathBuf>,
-    all_ports: Vec<u16>,
+    spawned: Vec<SpawnedInstance>,
     config: &T,
     target: &BevyTarget,
+    remote: Option<&RemoteLaunch>,
     launch_start: std::time::Instant,
 ) -> LaunchResult {
     let launch_duration = launch_start.elapsed();
+    let all_ports: Vec<u16> = spawned.iter().map(|instance| instance.port).collect();
 
     // Build instances array
-    let instances: Vec<LaunchedInstance> = all_pids
+    let instances: Vec<LaunchedInstance> = spawned
         .into_iter()
-        .zip(all_log_files.iter())
-        .zip(all_ports.iter())
-        .map(|((pid, log_file), port)| LaunchedInstance {
-            pid,
-            log_file: log_file.display().to_string(),
-            port: *port,
+        .enumerate()
+        .map(|(i, instance)| LaunchedInstance {
+            pid:             instance.pid,
+            log_file:        instance.log_file.display().to_string(),
+            stderr_log_file: instance
+                .stderr_log_file
+                .as_ref()
+                .map(|path| path.display().to_string()),
+            port:            instance.port,
+            label:           config
+                .instance_overrides()
+                .get(i)
+                .and_then(|instance_override| instance_override.label.clone()),
+            remote_host:     remote.map(|remote| remote.profile.name.clone()),
+            remote_pid:      instance.remote_pid,
+            debug_port:      instance
+                .debug_attach
+                .as_ref()
+                .and_then(|attach| attach.debug_port),
+            debugger_attach: instance.debug_attach.map(|attach| attach.command),
         })
         .collect();
 
@@ -507,68 +887,157 @@ fn build_launch_result<T: LaunchConfigTrait>(
 
     let instance_count = all_ports.len();
     let target_name_str = config.target_name();
-    let message = format!(
+    let mut message = format!(
         "Successfully launched {instance_count} instance(s) of {target_name_str} on ports {port_range}"
     );
+    if let Some(debugger) = config.debugger() {
+        if debugger.waits_for_attach() {
+            let _ = write!(
+                message,
+                " under {debugger} - each instance waits for a debugger to attach and continue \
+                 (see debugger_attach)"
+            );
+        } else {
+            let _ = write!(
+                message,
+                " under {debugger} - replay the recording after it exits (see debugger_attach)"
+            );
+        }
+        let binary = target.get_binary_path(config.profile());
+        if remote.is_none() && !has_debug_info(&binary) {
+            let _ = write!(
+                message,
+                ". Warning: no debug info found in {} - check that the profile doesn't set \
+                 `strip`",
+                binary.display()
+            );
+        }
+    }
+
+    let package_name = if T::TARGET_TYPE == TargetType::Example {
+        Some(target.package_name.clone())
+    } else {
+        None
+    };
+    let binary_path = match remote {
+        Some(remote) => Some(format!(
+            "{}:{}",
+            remote.profile.ssh_destination,
+            remote.binary()
+        )),
+        None if T::TARGET_TYPE == TargetType::App => Some(
+            target
+                .get_binary_path(config.profile())
+                .display()
+                .to_string(),
+        ),
+        None => None,
+    };
 
-    LaunchResult {
-        target_name: Some(config.target_name().to_string()),
+    LaunchResult::new(
+        Some(config.target_name().to_string()),
         instances,
-        working_directory: std::env::current_dir()
+        std::env::current_dir()
             .ok()
             .map(|dir| dir.display().to_string()),
-        profile: Some(config.profile().to_string()),
-        launch_duration_ms: Some(launch_duration.as_millis()),
-        launch_timestamp: Some(chrono::Utc::now().to_rfc3339()),
+        Some(config.profile().to_string()),
+        binary_path,
+        Some(launch_duration),
+        Some(chrono::Utc::now().to_rfc3339()),
         workspace,
-        package_name: if T::TARGET_TYPE == TargetType::Example {
-            Some(target.package_name.clone())
-        } else {
-            None
-        },
-        binary_path: if T::TARGET_TYPE == TargetType::App {
-            Some(
-                target
-                    .get_binary_path(config.profile())
-                    .display()
-                    .to_string(),
-            )
-        } else {
-            None
-        },
-        duplicate_paths: None,
-        message_template: Some(message),
-    }
+        package_name,
+        None,
+        None,
+    )
+    .with_message_template(message)
 }
 
 /// Prepare the launch environment including command, logging, and directory setup
 fn prepare_launch_environment<T: LaunchConfigTrait>(
     config: &T,
     target: &BevyTarget,
-) -> Result<(Command, PathBuf, PathBuf, std::fs::File)> {
+    instance: InstancePosition,
+    instance_override: Option<&InstanceOverride>,
+    remote: Option<&RemoteLaunch>,
+) -> Result<(Command, PathBuf, LaunchLogs, Option<DebugAttach>)> {
     // Get manifest directory
     let manifest_dir = validate_manifest_directory(&target.manifest_path)?;
 
-    // Build command
-    let cmd = config.build_command(target);
+    // Build command - remote instances are started through ssh, debugged ones by the debugger
+    let mut debug_attach = None;
+    let cmd = if let Some(remote) = remote {
+        remote::instance_command(remote, config.port(), instance, instance_override)
+    } else if let Some(debugger) = config.debugger() {
+        let session =
+            build_debugger_command(config, target, debugger, instance, instance_override)?;
+        debug_attach = Some(session.attach);
+        session.command
+    } else {
+        let mut cmd = config.build_command(target);
+        set_brp_env_vars(&mut cmd, None, Some(instance));
+        if let Some(instance_override) = instance_override {
+            apply_instance_override(&mut cmd, T::TARGET_TYPE, instance_override);
+        }
+        cmd
+    };
 
-    // Setup logging
-    let (log_file_path, log_file_for_redirect) = setup_launch_logging(
+    // Setup logging, recording whether fast build applied and where the instance runs
+    let extra_log_info: Vec<String> = [
+        config.extra_log_info(target),
+        remote
+            .is_none()
+            .then(|| config.fast_build_decision(target).log_note())
+            .flatten(),
+        remote.map(|remote| {
+            format!(
+                "Remote host: {} ({})",
+                remote.profile.name, remote.profile.ssh_destination
+            )
+        }),
+        debug_attach
+            .as_ref()
+            .map(|attach| format!("Debug with: {}", attach.command)),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    let extra_log_info = extra_log_info.join("\n");
+    let logs = setup_launch_logging(
         config.target_name(),
         T::TARGET_TYPE,
         config.profile(),
         &PathBuf::from(format!("{cmd:?}")), // Convert command to path for logging
         manifest_dir,
         config.port(),
-        config.extra_log_info(target).as_deref(),
+        (!extra_log_info.is_empty()).then_some(extra_log_info.as_str()),
+        config.output_capture().split_stderr,
     )?;
 
-    Ok((
-        cmd,
-        manifest_dir.to_path_buf(),
-        log_file_path,
-        log_file_for_redirect,
-    ))
+    Ok((cmd, manifest_dir.to_path_buf(), logs, debug_attach))
+}
+
+/// Command running the built binary under `debugger` - examples too, since `cargo run` can't
+/// be wrapped
+fn build_debugger_command<T: LaunchConfigTrait>(
+    config: &T,
+    target: &BevyTarget,
+    debugger: Debugger,
+    instance: InstancePosition,
+    instance_override: Option<&InstanceOverride>,
+) -> Result<DebugSession> {
+    let binary_path = target.get_binary_path(config.profile());
+    let args = instance_override.map_or(&[][..], |instance_override| {
+        instance_override.args.as_slice()
+    });
+    let mut session = debugger.wrap(&binary_path, args)?;
+    set_brp_env_vars(&mut session.command, Some(config.port()), Some(instance));
+    if let Some(instance_override) = instance_override {
+        session.command.envs(&instance_override.env);
+    }
+    if config.fast_build_decision(target).feature().is_some() {
+        set_dynamic_library_path(&mut session.command, &binary_path);
+    }
+    Ok(session)
 }
 
 /// Create error details for `ToolError` with common fields populated
@@ -581,6 +1050,7 @@ fn create_error_details<T: LaunchConfigTrait>(
         "target_type": T::TARGET_TYPE,
         "profile": config.profile(),
         "path": config.path(),
+        "package": config.package(),
         "port": config.port(),
         "duplicate_paths": duplicate_paths
     })
@@ -600,41 +1070,31 @@ fn find_and_validate_target<T: LaunchConfigTrait>(
     let all_targets =
         scanning::find_all_targets_by_name(config.target_name(), Some(target_type), search_paths);
 
-    // If multiple targets exist, we always want to include their paths
-    let duplicate_paths = if all_targets.len() > 1 {
-        Some(
-            all_targets
-                .iter()
-                .map(|target| target.relative_path.to_string_lossy().to_string())
-                .collect(),
-        )
-    } else {
-        None
-    };
-
-    // Find the specific target with path disambiguation (reuse all_targets to avoid duplicate scan)
+    // Find the specific target with path/package disambiguation (reuse all_targets to avoid
+    // duplicate scan)
     let target = match scanning::find_required_target_with_path(
         config.target_name(),
         target_type,
         config.path(),
+        config.package(),
         search_paths,
         Some(all_targets.clone()),
     ) {
         Ok(target) => target,
         Err(err) => {
-            use crate::error::Error;
-
-            // For any other error when duplicates exist, return disambiguation error with paths
-            if let Some(available_paths) = duplicate_paths {
-                let path_disambiguation_error = PathDisambiguationError::new(
-                    available_paths,
-                    config.target_name().to_string(),
-                    T::TARGET_TYPE.to_string(),
-                );
+            // A package narrows the duplicates itself - its error already lists the candidates
+            if config.package().is_some() {
+                return Err(Report::new(err));
+            }
 
-                Err(Error::Structured {
-                    result: Box::new(path_disambiguation_error),
-                })?;
+            // For any other error when duplicates exist, return disambiguation error with
+            // candidates
+            if all_targets.len() > 1 {
+                Err(scanning::disambiguation_error(
+                    &all_targets,
+                    config.target_name(),
+                    T::TARGET_TYPE.as_ref(),
+                ))?;
             }
 
             // For non-duplicate errors, determine appropriate structured error
@@ -666,7 +1126,7 @@ fn find_and_validate_target<T: LaunchConfigTrait>(
                     })?;
                 },
                 _ => {
-                    // This should not happen due to duplicate_paths logic above, but fallback
+                    // This should not happen due to the duplicates check above, but fallback
                     return Err(Report::new(Error::tool_call_failed_with_details(
                         err.to_string(),
                         create_error_details(config, None),
@@ -706,43 +1166,88 @@ fn validate_port_range(base_port: u16, instance_count: usize) -> Result<()> {
 }
 
 /// Launch multiple instances of a target
-fn launch_instances<T: LaunchConfigTrait>(
+async fn launch_instances<T: LaunchConfigTrait>(
     config: &T,
     target: &BevyTarget,
     instance_count: usize,
     base_port: u16,
-) -> Result<(Vec<u32>, Vec<PathBuf>, Vec<u16>)> {
-    let mut all_pids = Vec::new();
-    let mut all_log_files = Vec::new();
-    let mut all_ports = Vec::new();
+    remote: Option<&RemoteLaunch>,
+) -> Result<Vec<SpawnedInstance>> {
+    let mut spawned = Vec::with_capacity(instance_count);
 
     for i in 0..instance_count {
         // Use saturating conversion - validated in validate_port_range that this won't overflow
         let i_u16 = u16::try_from(i).unwrap_or(u16::MAX);
         let port = Port(base_port.saturating_add(i_u16));
 
+        // Give the previous instance a head start (asset/file locks, servers before clients)
+        if i > 0 && config.stagger_ms() > 0 {
+            tokio::time::sleep(Duration::from_millis(u64::from(config.stagger_ms()))).await;
+        }
+
         // Create a modified config with the updated port for this instance
         let mut instance_config = config.clone();
         instance_config.set_port(port);
 
         // Prepare launch environment with the instance-specific config
-        let (cmd, manifest_dir, log_file_path, log_file_for_redirect) =
-            prepare_launch_environment(&instance_config, target)?;
+        let (cmd, manifest_dir, logs, debug_attach) = prepare_launch_environment(
+            &instance_config,
+            target,
+            InstancePosition {
+                index: i,
+                count: instance_count,
+            },
+            config.instance_overrides().get(i),
+            remote,
+        )?;
 
         // Use launch_detached_process for proper zombie prevention and process group isolation
         let pid = process::launch_detached_process(
             &cmd,
             &manifest_dir,
-            log_file_for_redirect,
+            logs.stdout,
+            logs.stderr,
+            config.output_capture().timestamps,
             config.target_name(),
         )?;
 
-        all_pids.push(pid);
-        all_log_files.push(log_file_path);
-        all_ports.push(port.0);
+        // Failures from an earlier app on this port say nothing about the new one
+        reset_circuit(Some(port));
+
+        // Remote instances report their remote PID once the SSH session is up
+        let remote_pid = if let Some(remote) = remote {
+            let remote_pid = remote::wait_for_remote_pid(&logs.log_file_path).await;
+            if remote_pid.is_none() {
+                tracing::warn!(
+                    "Instance on port {port} did not report its PID on {} - see {}",
+                    remote.profile.ssh_destination,
+                    logs.log_file_path.display()
+                );
+            }
+            register_remote_host(
+                port,
+                RemoteHost {
+                    profile: remote.profile.name.clone(),
+                    destination: remote.profile.ssh_destination.clone(),
+                    remote_pid,
+                },
+            );
+            remote_pid
+        } else {
+            None
+        };
+
+        spawned.push(SpawnedInstance {
+            pid,
+            log_file: logs.log_file_path,
+            stderr_log_file: logs.stderr_file_path,
+            port: port.0,
+            remote_pid,
+            debug_attach,
+        });
     }
 
-    Ok((all_pids, all_log_files, all_ports))
+    Ok(spawned)
 }
 
 /// Handle target discovery errors and convert to appropriate error types
@@ -762,8 +1267,16 @@ fn handle_target_discovery_error(error: Report<Error>) -> Report<Error> {
     Error::tool_call_failed_with_details(error_message, details).into()
 }
 
+/// Find the target a launch configuration names, reporting the same errors a launch would
+pub(super) fn find_launch_target<T: LaunchConfigTrait>(
+    config: &T,
+    search_paths: &[PathBuf],
+) -> Result<BevyTarget> {
+    find_and_validate_target(config, search_paths).map_err(handle_target_discovery_error)
+}
+
 /// Generic function to launch a Bevy target (app or example)
-pub fn launch_target<T: LaunchConfigTrait>(
+pub async fn launch_target<T: LaunchConfigTrait>(
     config: &T,
     search_paths: &[PathBuf],
 ) -> Result<LaunchResult> {
@@ -777,19 +1290,7 @@ pub fn launch_target<T: LaunchConfigTrait>(
     debug!("Environment variable: BRP_EXTRAS_PORT={}", config.port());
 
     // Find and validate the target
-    let target =
-        find_and_validate_target(config, search_paths).map_err(handle_target_discovery_error)?;
-
-    // Ensure the target is built (blocks until compilation completes if needed)
-    let build_state = config.ensure_built(&target)?;
-    match build_state {
-        BuildState::Fresh => debug!("Target was already up to date, launching immediately"),
-        BuildState::Rebuilt => debug!("Target was rebuilt before launch"),
-        BuildState::NotFound => {
-            use tracing::warn;
-            warn!("Target not found in build output but build succeeded");
-        },
-    }
+    let target = find_launch_target(config, search_paths)?;
 
     let instance_count = *config.instance_count();
     let base_port = *config.port();
@@ -797,19 +1298,74 @@ pub fn launch_target<T: LaunchConfigTrait>(
     // Validate entire port range fits within valid bounds
     validate_port_range(base_port, instance_count)?;
 
+    let override_count = config.instance_overrides().len();
+    if override_count > instance_count {
+        return Err(Error::invalid(
+            "instances",
+            format!(
+                "{override_count} per-instance overrides given but instance_count is \
+                 {instance_count}"
+            ),
+        )
+        .into());
+    }
+
+    if let Some(debugger) = config.debugger() {
+        if config.remote_host().is_some() {
+            return Err(Error::invalid(
+                "debugger",
+                "debugging is not supported for remote_host launches",
+            )
+            .into());
+        }
+        debugger.ensure_installed()?;
+    }
+
+    // Remote launches build for and copy to the remote machine instead
+    let remote_launch = if let Some(remote_host) = config.remote_host() {
+        let profile = remote::load_profile(remote_host)?;
+        Some(remote::prepare(config, &target, profile).await?)
+    } else {
+        // Ensure the target is built (waits until compilation completes if needed)
+        match ensure_built(config, &target).await? {
+            BuildState::Fresh => debug!("Target was already up to date, launching immediately"),
+            BuildState::Rebuilt => debug!("Target was rebuilt before launch"),
+            BuildState::NotFound => {
+                use tracing::warn;
+                warn!("Target not found in build output but build succeeded");
+            },
+        }
+        None
+    };
+
     // Launch all instances
-    let (all_pids, all_log_files, all_ports) =
-        launch_instances(config, &target, instance_count, base_port)?;
+    let spawned = launch_instances(
+        config,
+        &target,
+        instance_count,
+        base_port,
+        remote_launch.as_ref(),
+    )
+    .await?;
 
     // Build unified result (works for both single and multi)
-    Ok(build_launch_result(
-        all_pids,
-        all_log_files,
-        all_ports,
+    let launch_result = build_launch_result(
+        spawned,
         config,
         &target,
+        remote_launch.as_ref(),
         launch_start,
-    ))
+    );
+
+    // Remember the instances so a restarted server can still find them
+    instance_registry::register(
+        config.target_name(),
+        T::TARGET_TYPE,
+        config.profile(),
+        launch_result.instances(),
+    );
+
+    Ok(launch_result)
 }
 
 impl FromLaunchParams for LaunchConfig<App> {
@@ -818,9 +1374,16 @@ impl FromLaunchParams for LaunchConfig<App> {
             params.target_name.clone(),
             params.profile.clone(),
             params.path.clone(),
+            params.package.clone(),
             params.port,
             params.instance_count,
+            params.stagger_ms,
+            params.instances.clone(),
             params.features.clone(),
+            params.fast_build,
+            params.output,
+            params.remote_host.clone(),
+            params.debugger,
         )
     }
 }
@@ -834,16 +1397,35 @@ impl LaunchConfigTrait for LaunchConfig<App> {
 
     fn path(&self) -> Option<&str> { self.path.as_deref() }
 
+    fn package(&self) -> Option<&str> { self.package.as_deref() }
+
     fn port(&self) -> Port { self.port }
 
     fn instance_count(&self) -> InstanceCount { self.instance_count }
 
+    fn stagger_ms(&self) -> u32 { self.stagger_ms }
+
+    fn instance_overrides(&self) -> &[InstanceOverride] { &self.instances }
+
     fn features(&self) -> Option<&Vec<String>> { self.features.as_ref() }
 
+    fn fast_build(&self) -> bool { self.fast_build }
+
+    fn output_capture(&self) -> OutputCapture { self.output }
+
+    fn remote_host(&self) -> Option<&str> { self.remote_host.as_deref() }
+
+    fn debugger(&self) -> Option<Debugger> { self.debugger }
+
     fn set_port(&mut self, port: Port) { self.port = port; }
 
     fn build_command(&self, target: &BevyTarget) -> Command {
-        build_app_command(&target.get_binary_path(self.profile()), Some(self.port))
+        let binary_path = target.get_binary_path(self.profile());
+        let mut cmd = build_app_command(&binary_path, Some(self.port));
+        if self.fast_build_decision(target).feature().is_some() {
+            set_dynamic_library_path(&mut cmd, &binary_path);
+        }
+        cmd
     }
 
     fn extra_log_info(&self, _target: &BevyTarget) -> Option<String> { None }
@@ -855,9 +1437,16 @@ impl FromLaunchParams for LaunchConfig<Example> {
             params.target_name.clone(),
             params.profile.clone(),
             params.path.clone(),
+            params.package.clone(),
             params.port,
             params.instance_count,
+            params.stagger_ms,
+            params.instances.clone(),
             params.features.clone(),
+            params.fast_build,
+            params.output,
+            params.remote_host.clone(),
+            params.debugger,
         )
     }
 }
@@ -871,19 +1460,121 @@ impl LaunchConfigTrait for LaunchConfig<Example> {
 
     fn path(&self) -> Option<&str> { self.path.as_deref() }
 
+    fn package(&self) -> Option<&str> { self.package.as_deref() }
+
     fn port(&self) -> Port { self.port }
 
     fn instance_count(&self) -> InstanceCount { self.instance_count }
 
+    fn stagger_ms(&self) -> u32 { self.stagger_ms }
+
+    fn instance_overrides(&self) -> &[InstanceOverride] { &self.instances }
+
     fn features(&self) -> Option<&Vec<String>> { self.features.as_ref() }
 
+    fn fast_build(&self) -> bool { self.fast_build }
+
+    fn output_capture(&self) -> OutputCapture { self.output }
+
+    fn remote_host(&self) -> Option<&str> { self.remote_host.as_deref() }
+
+    fn debugger(&self) -> Option<Debugger> { self.debugger }
+
     fn set_port(&mut self, port: Port) { self.port = port; }
 
-    fn build_command(&self, _target: &BevyTarget) -> Command {
-        build_cargo_example_command(&self.target_name, self.profile(), Some(self.port), self.features.as_ref())
+    fn build_command(&self, target: &BevyTarget) -> Command {
+        build_cargo_example_command(
+            &self.target_name,
+            self.profile(),
+            Some(self.port),
+            self.build_features(target).as_ref(),
+        )
     }
 
     fn extra_log_info(&self, target: &BevyTarget) -> Option<String> {
         Some(format!("Package: {}", target.package_name))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+    use serde_json::json;
+
+    use super::*;
+    use crate::tool::ResponseBuilder;
+    use crate::tool::ResultStruct;
+    use crate::tool::ToolName;
+
+    fn launch_result() -> LaunchResult {
+        let instances = [15702, 15703]
+            .into_iter()
+            .map(|port| {
+                serde_json::from_value(json!({ "pid": 1, "log_file": "app.log", "port": port }))
+                    .unwrap_or_else(|e| unreachable!("instance should deserialize: {e}"))
+            })
+            .collect();
+        LaunchResult::new(
+            Some("app".to_string()),
+            instances,
+            Some("/work".to_string()),
+            Some("debug".to_string()),
+            Some("/work/target/debug/app".to_string()),
+            Some(Duration::from_millis(1500)),
+            Some("2026-01-01T00:00:00Z".to_string()),
+            Some("work".to_string()),
+            None,
+            None,
+            None,
+        )
+        .with_message_template("Launched app")
+    }
+
+    #[test]
+    fn metadata_is_grouped_into_build_and_timing() {
+        let builder = ResponseBuilder::success(ToolName::BrpLaunchBevyApp.get_call_info());
+        let response = launch_result()
+            .add_response_fields(builder)
+            .unwrap_or_else(|error| unreachable!("fields should serialize: {error}"))
+            .build();
+        let metadata = serde_json::to_value(response.metadata).unwrap_or_default();
+
+        assert_eq!(metadata["target_name"], json!("app"));
+        assert_eq!(metadata["instance_count"], json!(2));
+        assert_eq!(
+            metadata["build"],
+            json!({
+                "working_directory": "/work",
+                "profile": "debug",
+                "binary_path": "/work/target/debug/app",
+                "workspace": "work",
+            })
+        );
+        assert_eq!(
+            metadata["timing"],
+            json!({ "launch_duration_ms": 1500, "launch_timestamp": "2026-01-01T00:00:00Z" })
+        );
+        // Grouped fields aren't repeated at the top level, and the source duration isn't reported
+        for field in [
+            "profile",
+            "binary_path",
+            "launch_duration_ms",
+            "launch_duration",
+        ] {
+            assert_eq!(metadata.get(field), None::<&Value>, "{field}");
+        }
+    }
+
+    #[test]
+    fn output_schema_places_fields_in_their_groups() {
+        let mut generator = schemars::SchemaGenerator::default();
+        let groups: Vec<(&str, Option<&str>)> = LaunchResult::output_schema_fields(&mut generator)
+            .into_iter()
+            .map(|field| (field.field_name, field.group))
+            .collect();
+
+        assert!(groups.contains(&("profile", Some("build"))));
+        assert!(groups.contains(&("launch_duration_ms", Some("timing"))));
+        assert!(groups.contains(&("instance_count", None)));
+    }
+}
@@ -0,0 +1,316 @@
+//! Named launch presets
+//!
+//! Setups launched over and over - "the usual two-client netcode session" - otherwise need the
+//! same long parameter list on every call. A preset names a set of launch parameters in a JSON
+//! file, and `preset: "<name>"` on `brp_launch_bevy_app` or `brp_launch_bevy_example` fills in
+//! every parameter the call doesn't give itself:
+//!
+//! ```json
+//! {
+//!   "netcode": {
+//!     "target_name": "my_game",
+//!     "profile": "release",
+//!     "features": ["netcode"],
+//!     "instance_count": 3,
+//!     "port": 15710,
+//!     "env": {"RUST_LOG": "info"},
+//!     "instances": [{"args": ["--server"], "label": "server"}]
+//!   }
+//! }
+//! ```
+//!
+//! `env` is set for every instance, under any variables an instance's own `env` sets. The file
+//! is the one named by `BRP_MCP_LAUNCH_PRESETS`, or else `brp_launch_presets.json` in the first
+//! workspace root that has one.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+
+use serde_json::Map;
+use serde_json::Value;
+
+use crate::error::Error;
+use crate::error::Result;
+use crate::tool::ParameterName;
+
+/// Environment variable naming the JSON file of launch presets
+pub const LAUNCH_PRESETS_ENV_VAR: &str = "BRP_MCP_LAUNCH_PRESETS";
+
+/// Presets file looked for in the workspace roots when `BRP_MCP_LAUNCH_PRESETS` isn't set
+const PRESETS_FILE_NAME: &str = "brp_launch_presets.json";
+
+/// Preset field holding environment variables for every instance
+const PRESET_ENV_FIELD: &str = "env";
+
+/// Launch parameters a preset may set, besides `env`
+const PRESET_PARAMETERS: &[&str] = &[
+    "target_name",
+    "profile",
+    "path",
+    "package",
+    "port",
+    "instance_count",
+    "stagger_ms",
+    "instances",
+    "features",
+    "fast_build",
+    "split_stderr",
+    "timestamps",
+    "remote_host",
+    "debugger",
+    "network_proxy",
+];
+
+/// Replace `preset` in the launch arguments with the parameters of the named preset
+///
+/// Parameters given in the call win over the preset's. A parameter that is `null`, `""` or the
+/// default `input_schema` advertises counts as not given, since clients may prefill every
+/// parameter with its advertised default. Arguments without `preset` are left unchanged.
+pub fn apply_preset(
+    arguments: Option<&mut Map<String, Value>>,
+    roots: &[PathBuf],
+    input_schema: &Map<String, Value>,
+) -> Result<()> {
+    let Some(arguments) = arguments else {
+        return Ok(());
+    };
+    let Some(name) = arguments.remove(ParameterName::Preset.as_ref()) else {
+        return Ok(());
+    };
+    let Some(name) = name.as_str() else {
+        return Err(Error::invalid("preset", "expected a preset name").into());
+    };
+
+    merge_preset(arguments, load_preset(name, roots)?, input_schema);
+    Ok(())
+}
+
+/// Fill in the preset's parameters the call doesn't give itself
+fn merge_preset(
+    arguments: &mut Map<String, Value>,
+    mut preset: Map<String, Value>,
+    input_schema: &Map<String, Value>,
+) {
+    let env = preset.remove(PRESET_ENV_FIELD);
+    for (parameter, value) in preset {
+        let given = arguments
+            .get(&parameter)
+            .is_some_and(|given| !is_unset(given, advertised_default(input_schema, &parameter)));
+        if !given {
+            arguments.insert(parameter, value);
+        }
+    }
+    if let Some(Value::Object(env)) = env {
+        apply_env(arguments, &env);
+    }
+}
+
+/// Whether a call's `value` for a parameter leaves it to the preset
+fn is_unset(value: &Value, default: Option<&Value>) -> bool {
+    value.is_null() || value.as_str() == Some("") || default == Some(value)
+}
+
+/// The default `input_schema` advertises for `parameter`
+fn advertised_default<'a>(
+    input_schema: &'a Map<String, Value>,
+    parameter: &str,
+) -> Option<&'a Value> {
+    input_schema
+        .get("properties")
+        .and_then(|properties| properties.get(parameter))
+        .and_then(|property| property.get("default"))
+}
+
+/// Set the preset's `env` on every instance, under each instance's own variables
+fn apply_env(arguments: &mut Map<String, Value>, env: &Map<String, Value>) {
+    let instance_count = arguments
+        .get(ParameterName::InstanceCount.as_ref())
+        .and_then(Value::as_u64)
+        .map_or(1, |count| usize::try_from(count).unwrap_or(usize::MAX));
+    let instances = arguments
+        .entry(ParameterName::Instances.as_ref())
+        .or_insert_with(|| Value::Array(Vec::new()));
+    let Value::Array(instances) = instances else {
+        return;
+    };
+    while instances.len() < instance_count {
+        instances.push(Value::Object(Map::new()));
+    }
+    for instance in instances {
+        let Value::Object(instance) = instance else {
+            continue;
+        };
+        let Value::Object(instance_env) = instance
+            .entry(PRESET_ENV_FIELD)
+            .or_insert_with(|| Value::Object(Map::new()))
+        else {
+            continue;
+        };
+        for (variable, value) in env {
+            instance_env
+                .entry(variable.clone())
+                .or_insert_with(|| value.clone());
+        }
+    }
+}
+
+/// Load the preset named `name`
+fn load_preset(name: &str, roots: &[PathBuf]) -> Result<Map<String, Value>> {
+    let Some(path) = presets_path(roots) else {
+        return Err(Error::invalid(
+            "preset",
+            format!(
+                "no launch presets are configured - set {LAUNCH_PRESETS_ENV_VAR} to a JSON file \
+                 of presets, or add {PRESETS_FILE_NAME} to the workspace root"
+            ),
+        )
+        .into());
+    };
+    let contents = fs::read_to_string(&path)
+        .map_err(|e| Error::io_failed("read launch presets", &path, &e))?;
+    let mut presets = parse_presets(&contents)
+        .map_err(|e| Error::invalid("launch presets", format!("{}: {e}", path.display())))?;
+
+    let available = presets.keys().cloned().collect::<Vec<_>>().join(", ");
+    presets.remove(name).ok_or_else(|| {
+        Error::invalid(
+            "preset",
+            format!(
+                "no preset named '{name}' in {} (available: {available})",
+                path.display()
+            ),
+        )
+        .into()
+    })
+}
+
+/// The presets file - from `BRP_MCP_LAUNCH_PRESETS`, or the first workspace root that has one
+fn presets_path(roots: &[PathBuf]) -> Option<PathBuf> {
+    if let Ok(path) = std::env::var(LAUNCH_PRESETS_ENV_VAR) {
+        return Some(PathBuf::from(path));
+    }
+    roots
+        .iter()
+        .map(|root| root.join(PRESETS_FILE_NAME))
+        .find(|path| Path::is_file(path))
+}
+
+/// Parse the presets file, a JSON object of presets keyed by name
+fn parse_presets(
+    contents: &str,
+) -> std::result::Result<BTreeMap<String, Map<String, Value>>, String> {
+    let presets: BTreeMap<String, Map<String, Value>> =
+        serde_json::from_str(contents).map_err(|e| e.to_string())?;
+    for (name, preset) in &presets {
+        if let Some(unknown) = preset
+            .keys()
+            .find(|key| *key != PRESET_ENV_FIELD && !PRESET_PARAMETERS.contains(&key.as_str()))
+        {
+            return Err(format!(
+                "preset '{name}' sets unknown parameter '{unknown}'"
+            ));
+        }
+        if preset
+            .get(PRESET_ENV_FIELD)
+            .is_some_and(|env| !env.is_object())
+        {
+            return Err(format!("preset '{name}' has an `env` that isn't an object"));
+        }
+    }
+    Ok(presets)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::app_tools::LaunchBevyBinaryParams;
+    use crate::tool::build_parameters_from;
+
+    /// Input schema of the launch tools
+    fn launch_schema() -> Map<String, Value> {
+        build_parameters_from::<LaunchBevyBinaryParams>()
+            .build()
+            .as_ref()
+            .clone()
+    }
+
+    #[test]
+    fn call_parameters_win_and_env_reaches_every_instance() {
+        let presets = parse_presets(
+            r#"{"netcode": {
+                "target_name": "my_game",
+                "profile": "release",
+                "instance_count": 3,
+                "env": {"RUST_LOG": "info", "MODE": "test"},
+                "instances": [{"args": ["--server"], "env": {"MODE": "server"}}]
+            }}"#,
+        );
+        let preset = presets
+            .unwrap_or_default()
+            .remove("netcode")
+            .unwrap_or_default();
+        let mut arguments = Map::new();
+        arguments.insert("profile".to_string(), json!("profiling"));
+        merge_preset(&mut arguments, preset, &launch_schema());
+
+        assert_eq!(arguments["profile"], "profiling");
+        assert_eq!(arguments["target_name"], "my_game");
+        assert_eq!(
+            arguments["instances"],
+            json!([
+                {"args": ["--server"], "env": {"MODE": "server", "RUST_LOG": "info"}},
+                {"env": {"RUST_LOG": "info", "MODE": "test"}},
+                {"env": {"RUST_LOG": "info", "MODE": "test"}}
+            ])
+        );
+    }
+
+    #[test]
+    fn prefilled_schema_defaults_leave_parameters_to_the_preset() {
+        let schema = launch_schema();
+        let mut arguments: Map<String, Value> = schema
+            .get("properties")
+            .and_then(Value::as_object)
+            .into_iter()
+            .flatten()
+            .filter_map(|(name, property)| Some((name.clone(), property.get("default")?.clone())))
+            .collect();
+        assert!(
+            !arguments.is_empty(),
+            "the launch schema advertises defaults"
+        );
+        arguments.insert("target_name".to_string(), json!(""));
+        arguments.insert("port".to_string(), Value::Null);
+        let preset = json!({
+            "target_name": "my_game",
+            "profile": "release",
+            "port": 15710,
+            "instance_count": 3,
+            "fast_build": true
+        });
+        let Value::Object(preset) = preset else {
+            unreachable!("preset is an object");
+        };
+
+        merge_preset(&mut arguments, preset, &schema);
+
+        assert_eq!(arguments["target_name"], "my_game");
+        assert_eq!(arguments["profile"], "release");
+        assert_eq!(arguments["port"], 15710);
+        assert_eq!(arguments["instance_count"], 3);
+        assert_eq!(arguments["fast_build"], true);
+    }
+
+    #[test]
+    fn rejects_unknown_preset_parameters() {
+        let presets = parse_presets(r#"{"broken": {"target": "my_game"}}"#);
+        assert_eq!(
+            presets.err().as_deref(),
+            Some("preset 'broken' sets unknown parameter 'target'")
+        );
+    }
+}
@@ -2,14 +2,19 @@
 
 use std::collections::HashSet;
 
+use serde_json::json;
+
+use super::cargo_detector::BevyTarget;
 use super::cargo_detector::CargoDetector;
 use super::collection_strategy::CollectionStrategy;
 use super::scanning;
+use crate::app_tools::list_params::ListTargetsParams;
 
-/// Collect all items using the provided strategy
-pub fn collect_all_items<S: CollectionStrategy>(
+/// Collect all items using the provided strategy, narrowed and extended as `params` asks
+pub fn collect_all_items<S: CollectionStrategy<Item = BevyTarget>>(
     search_paths: &[std::path::PathBuf],
     strategy: &S,
+    params: &ListTargetsParams,
 ) -> Vec<serde_json::Value> {
     let mut all_items = Vec::new();
     let mut seen_items = HashSet::new();
@@ -18,7 +23,10 @@ pub fn collect_all_items<S: CollectionStrategy>(
     for path in scanning::iter_cargo_project_paths(search_paths) {
         if let Ok(detector) = CargoDetector::from_path(&path) {
             let items = strategy.collect_items(&detector);
-            for item in items {
+            for item in items
+                .into_iter()
+                .filter(|item| params.matches(&item.name, &item.package_name))
+            {
                 // Create a unique key using the strategy
                 let key = strategy.create_unique_key(&item);
                 if seen_items.insert(key) {
@@ -27,8 +35,13 @@ pub fn collect_all_items<S: CollectionStrategy>(
                     let item_path = strategy.get_path_for_relative(&item);
                     let relative_path = scanning::compute_relative_path(&item_path, search_paths);
 
-                    let serialized_item =
+                    let mut serialized_item =
                         strategy.serialize_item(&item, relative_path.display().to_string());
+                    if params.include_features {
+                        serialized_item["features"] = json!(item.features);
+                        serialized_item["default_features"] = json!(item.default_features);
+                        serialized_item["required_features"] = json!(item.required_features);
+                    }
                     all_items.push(serialized_item);
                 }
             }
@@ -4,13 +4,42 @@ use std::path::Path;
 use std::path::PathBuf;
 
 use error_stack::ResultExt;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
 
-/// Create a log file for a Bevy app launch
 use super::cargo_detector::TargetType;
 use crate::brp_tools::Port;
 use crate::error::Error;
 use crate::error::Result;
 
+/// How a launched process's output is captured
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, JsonSchema)]
+pub struct OutputCapture {
+    /// Write stderr to its own log file instead of interleaving it with stdout (default: false)
+    #[serde(default)]
+    pub split_stderr: bool,
+    /// Prefix every captured output line with the time it was written (default: false)
+    #[serde(default)]
+    pub timestamps:   bool,
+}
+
+/// Log files a launched process writes to
+pub struct LaunchLogs {
+    /// Launch log - header, stdout, and stderr unless it is split out
+    pub log_file_path:    PathBuf,
+    /// Separate stderr log, when stderr is split
+    pub stderr_file_path: Option<PathBuf>,
+    /// Handle stdout is redirected to
+    pub stdout:           File,
+    /// Handle stderr is redirected to
+    pub stderr:           File,
+}
+
+/// Create a log file for a Bevy app launch
+///
+/// Returns the launch log's path and, when `split_stderr` is set, the path of a separate stderr
+/// log created alongside it
 pub fn create_log_file(
     name: &str,
     target_type: TargetType,
@@ -18,7 +47,8 @@ pub fn create_log_file(
     binary_path: &Path,
     manifest_dir: &Path,
     port: Port,
-) -> Result<(PathBuf, File)> {
+    split_stderr: bool,
+) -> Result<(PathBuf, Option<PathBuf>)> {
     // Generate unique log file name in temp directory
     let timestamp = std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
@@ -29,6 +59,11 @@ pub fn create_log_file(
     // Port provides uniqueness for multiple instances
     let log_file_path =
         std::env::temp_dir().join(format!("bevy_brp_mcp_{name}_port{port}_{timestamp}.log"));
+    let stderr_file_path = split_stderr.then(|| {
+        std::env::temp_dir().join(format!(
+            "bevy_brp_mcp_{name}_stderr_port{port}_{timestamp}.log"
+        ))
+    });
 
     // Create log file
     let mut log_file = File::create(&log_file_path)
@@ -54,14 +89,26 @@ pub fn create_log_file(
     writeln!(log_file, "Working directory: {}", manifest_dir.display()).change_context(
         Error::LogOperation("Failed to write to log file".to_string()),
     )?;
+    if let Some(stderr_file_path) = &stderr_file_path {
+        writeln!(log_file, "stderr: {}", stderr_file_path.display()).change_context(
+            Error::LogOperation("Failed to write to log file".to_string()),
+        )?;
+    }
     writeln!(log_file, "============================================\n").change_context(
         Error::LogOperation("Failed to write to log file".to_string()),
     )?;
+    if let Some(stderr_file_path) = &stderr_file_path {
+        File::create(stderr_file_path)
+            .change_context(Error::LogOperation(
+                "Failed to create stderr log file".to_string(),
+            ))
+            .attach(format!("Path: {}", stderr_file_path.display()))?;
+    }
     log_file
         .sync_all()
         .change_context(Error::LogOperation("Failed to sync log file".to_string()))?;
 
-    Ok((log_file_path, log_file))
+    Ok((log_file_path, stderr_file_path))
 }
 
 /// Open an existing log file for appending (for stdout/stderr redirection)
@@ -1,21 +1,52 @@
 // Local support modules for app_tools
+mod build_diagnostics;
 mod cargo_detector;
 mod collection_strategy;
+mod debugger;
 pub mod errors;
+mod fast_build;
+pub mod instance_registry;
 mod launch_common;
+mod launch_presets;
 mod list_common;
 mod logging;
+pub mod network_proxy;
+mod port_check;
 mod process;
+mod relaunch;
+mod remote;
 mod scanning;
+mod target_spec;
+mod watchdog;
 
+pub use cargo_detector::BevyTarget;
+pub use cargo_detector::BrpManifestSetup;
+pub use cargo_detector::CargoDetector;
+pub use cargo_detector::TargetType;
 pub use collection_strategy::BevyAppsStrategy;
 pub use collection_strategy::BevyExamplesStrategy;
 pub use collection_strategy::BrpAppsStrategy;
+pub use debugger::Debugger;
 pub use launch_common::App;
 pub use launch_common::Example;
+pub use launch_common::FromLaunchParams;
 pub use launch_common::GenericLaunchHandler;
 pub use launch_common::LaunchConfig;
 pub use launch_common::LaunchParams;
+pub use launch_common::LaunchResult;
 pub use launch_common::ToLaunchParams;
+pub use launch_common::launch_target;
+pub use launch_common::run_cargo_build;
 pub use list_common::collect_all_items;
+pub use logging::OutputCapture;
+pub use port_check::answers_brp_by;
+pub use port_check::check_ports_available;
 pub use process::get_pid_for_port;
+pub use relaunch::RelaunchWatchStarted;
+pub use relaunch::start_relaunch_watch;
+pub use remote::kill_remote_process;
+pub use scanning::find_all_targets_by_name;
+pub use scanning::find_required_target_with_path;
+pub use target_spec::TargetSpec;
+pub use watchdog::NotificationLevel;
+pub use watchdog::start_watchdog;
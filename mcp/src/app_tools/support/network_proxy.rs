@@ -0,0 +1,696 @@
+//! Network condition proxies for multi-instance testing
+//!
+//! Instances of a game using a Bevy networking crate talk to each other over localhost, where
+//! nothing is ever late or lost - so lag compensation, interpolation and reconnect logic go
+//! untested. A proxy sits between the instances: it listens on a local port, forwards everything
+//! to the port the server instance's game networking listens on, and delays each packet by
+//! `latency_ms` plus up to `jitter_ms`, dropping `loss_percent` of UDP packets. Clients connect to
+//! the proxy instead of the server - instances launched with a proxy find its address in
+//! `BRP_EXTRAS_NETWORK_PROXY`. Conditions can be changed while the proxy runs, so a test can
+//! degrade a connection mid-session.
+//!
+//! TCP streams can't lose bytes, so TCP proxies only add latency and jitter - and keep each
+//! stream in order, as TCP would.
+
+use std::collections::HashMap;
+use std::net::Ipv4Addr;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::sync::LazyLock;
+use std::sync::Mutex;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+use strum::Display;
+use tokio::io::AsyncReadExt;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpListener;
+use tokio::net::TcpStream;
+use tokio::net::UdpSocket;
+use tokio::net::tcp::OwnedReadHalf;
+use tokio::net::tcp::OwnedWriteHalf;
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+use tokio_util::sync::CancellationToken;
+use tracing::debug;
+
+use crate::app_tools::instance_override::InstanceOverride;
+use crate::brp_tools::BRP_EXTRAS_NETWORK_PROXY_ENV_VAR;
+use crate::error::Error;
+use crate::error::Result;
+
+/// Largest UDP datagram the proxy forwards
+const MAX_DATAGRAM_SIZE: usize = 65_535;
+
+/// Bytes read from a TCP stream at a time
+const TCP_CHUNK_SIZE: usize = 16 * 1024;
+
+/// A UDP client's upstream socket is closed after this long without a packet either way
+const UDP_IDLE_TIMEOUT: Duration = Duration::from_mins(1);
+
+/// Running proxies by the port they listen on
+static PROXIES: LazyLock<Mutex<HashMap<u16, Proxy>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Transport a proxy forwards
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Display, Deserialize, Serialize, JsonSchema,
+)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum ProxyProtocol {
+    /// Forward datagrams - latency, jitter and packet loss
+    #[default]
+    Udp,
+    /// Forward streams - latency and jitter only
+    Tcp,
+}
+
+/// Conditions a proxy applies to every packet it forwards
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize, Serialize, JsonSchema)]
+pub struct NetworkConditions {
+    /// Delay added to every packet in milliseconds (default: 0)
+    #[serde(default)]
+    pub latency_ms:   u32,
+    /// Further random delay of up to this many milliseconds per packet (default: 0)
+    #[serde(default)]
+    pub jitter_ms:    u32,
+    /// Percentage of UDP packets dropped, 0-100 (default: 0)
+    #[serde(default)]
+    pub loss_percent: f64,
+}
+
+impl NetworkConditions {
+    /// Reject a loss percentage outside 0-100
+    pub fn validate(&self) -> Result<()> {
+        if (0.0..=100.0).contains(&self.loss_percent) {
+            Ok(())
+        } else {
+            Err(Error::invalid(
+                "loss_percent",
+                format!("{} is not between 0 and 100", self.loss_percent),
+            )
+            .into())
+        }
+    }
+}
+
+/// A proxy to start, as given to the launch tools' `network_proxy` parameter
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct NetworkProxyOptions {
+    /// Port the server instance's game networking listens on - the proxy forwards to it
+    pub target_port: u16,
+    /// Port for the proxy to listen on (default: any free port)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub listen_port: Option<u16>,
+    /// Transport to forward: `udp` or `tcp` (default: udp)
+    #[serde(default)]
+    pub protocol:    ProxyProtocol,
+    /// Conditions to apply to the forwarded packets
+    #[serde(flatten)]
+    pub conditions:  NetworkConditions,
+}
+
+/// A running proxy as reported by the proxy tools
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ProxyInfo {
+    /// Port the proxy listens on
+    pub listen_port:       u16,
+    /// Address clients connect to instead of the server
+    pub listen_addr:       String,
+    /// Port the proxy forwards to
+    pub target_port:       u16,
+    /// Transport the proxy forwards
+    pub protocol:          ProxyProtocol,
+    /// Conditions currently applied
+    pub conditions:        NetworkConditions,
+    /// Packets (UDP) or reads (TCP) forwarded
+    pub forwarded_packets: u64,
+    /// UDP packets dropped
+    pub dropped_packets:   u64,
+}
+
+/// A proxy in the registry
+struct Proxy {
+    target_port: u16,
+    protocol:    ProxyProtocol,
+    shared:      Arc<ProxyShared>,
+    cancel:      CancellationToken,
+}
+
+impl Proxy {
+    fn info(&self, listen_port: u16) -> ProxyInfo {
+        ProxyInfo {
+            listen_port,
+            listen_addr: SocketAddr::from((Ipv4Addr::LOCALHOST, listen_port)).to_string(),
+            target_port: self.target_port,
+            protocol: self.protocol,
+            conditions: self.shared.conditions(),
+            forwarded_packets: self.shared.forwarded.load(Ordering::Relaxed),
+            dropped_packets: self.shared.dropped.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// State shared by a proxy's forwarding tasks
+struct ProxyShared {
+    conditions: Mutex<NetworkConditions>,
+    forwarded:  AtomicU64,
+    dropped:    AtomicU64,
+    rng:        AtomicU64,
+}
+
+impl ProxyShared {
+    fn new(conditions: NetworkConditions) -> Self {
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(1, |elapsed| {
+                (elapsed.as_secs() ^ u64::from(elapsed.subsec_nanos())) | 1
+            });
+        Self {
+            conditions: Mutex::new(conditions),
+            forwarded:  AtomicU64::new(0),
+            dropped:    AtomicU64::new(0),
+            rng:        AtomicU64::new(seed),
+        }
+    }
+
+    fn conditions(&self) -> NetworkConditions {
+        self.conditions
+            .lock()
+            .map(|conditions| *conditions)
+            .unwrap_or_default()
+    }
+
+    /// A xorshift step - the proxies only need noise, not a dependency on `rand`
+    fn next_random(&self) -> u64 {
+        let mut state = self.rng.load(Ordering::Relaxed);
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        self.rng.store(state, Ordering::Relaxed);
+        state
+    }
+
+    /// How long to hold the next packet - `None` when it is dropped, which only datagrams can be
+    #[allow(clippy::cast_precision_loss)] // only the top bits matter for a percentage
+    fn schedule(&self, droppable: bool) -> Option<Duration> {
+        let conditions = self.conditions();
+        if droppable
+            && conditions.loss_percent > 0.0
+            && (self.next_random() as f64 / u64::MAX as f64) * 100.0 < conditions.loss_percent
+        {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+            return None;
+        }
+        self.forwarded.fetch_add(1, Ordering::Relaxed);
+        let jitter = if conditions.jitter_ms == 0 {
+            0
+        } else {
+            self.next_random() % (u64::from(conditions.jitter_ms) + 1)
+        };
+        Some(Duration::from_millis(
+            u64::from(conditions.latency_ms) + jitter,
+        ))
+    }
+}
+
+/// Start a proxy and register it under the port it listens on
+pub async fn start_proxy(options: &NetworkProxyOptions) -> Result<ProxyInfo> {
+    options.conditions.validate()?;
+    let listen_addr = SocketAddr::from((Ipv4Addr::LOCALHOST, options.listen_port.unwrap_or(0)));
+    let target_addr = SocketAddr::from((Ipv4Addr::LOCALHOST, options.target_port));
+    let shared = Arc::new(ProxyShared::new(options.conditions));
+    let cancel = CancellationToken::new();
+
+    let listen_port = match options.protocol {
+        ProxyProtocol::Udp => {
+            let socket = UdpSocket::bind(listen_addr)
+                .await
+                .map_err(|e| Error::failed_to(&format!("bind UDP proxy on {listen_addr}"), e))?;
+            let port = local_port(socket.local_addr())?;
+            tokio::spawn(run_udp_proxy(
+                Arc::new(socket),
+                target_addr,
+                Arc::clone(&shared),
+                cancel.clone(),
+            ));
+            port
+        },
+        ProxyProtocol::Tcp => {
+            let listener = TcpListener::bind(listen_addr)
+                .await
+                .map_err(|e| Error::failed_to(&format!("bind TCP proxy on {listen_addr}"), e))?;
+            let port = local_port(listener.local_addr())?;
+            tokio::spawn(run_tcp_proxy(
+                listener,
+                target_addr,
+                Arc::clone(&shared),
+                cancel.clone(),
+            ));
+            port
+        },
+    };
+
+    let proxy = Proxy {
+        target_port: options.target_port,
+        protocol: options.protocol,
+        shared,
+        cancel,
+    };
+    let info = proxy.info(listen_port);
+    debug!(
+        "Started {} network proxy {} -> {target_addr}",
+        info.protocol, info.listen_addr
+    );
+    PROXIES
+        .lock()
+        .map_err(|_| Error::General("Network proxy registry is unavailable".to_string()))?
+        .insert(listen_port, proxy);
+    Ok(info)
+}
+
+/// Start a proxy for a launch and point every instance at it through
+/// `BRP_EXTRAS_NETWORK_PROXY`
+pub async fn start_launch_proxy(
+    options: &NetworkProxyOptions,
+    instances: &mut Vec<InstanceOverride>,
+    instance_count: usize,
+) -> Result<ProxyInfo> {
+    let info = start_proxy(options).await?;
+    if instances.len() < instance_count {
+        instances.resize_with(instance_count, InstanceOverride::default);
+    }
+    for instance in instances {
+        instance.env.insert(
+            BRP_EXTRAS_NETWORK_PROXY_ENV_VAR.to_string(),
+            info.listen_addr.clone(),
+        );
+    }
+    Ok(info)
+}
+
+/// Change the conditions of the proxy listening on `listen_port`
+pub fn configure_proxy(
+    listen_port: u16,
+    change: impl FnOnce(&mut NetworkConditions),
+) -> Result<ProxyInfo> {
+    let proxies = PROXIES
+        .lock()
+        .map_err(|_| Error::General("Network proxy registry is unavailable".to_string()))?;
+    let proxy = proxies.get(&listen_port).ok_or_else(|| {
+        Error::tool_call_failed(format!(
+            "No network proxy is listening on port {listen_port}"
+        ))
+    })?;
+    let mut conditions = proxy.shared.conditions();
+    change(&mut conditions);
+    conditions.validate()?;
+    if let Ok(mut current) = proxy.shared.conditions.lock() {
+        *current = conditions;
+    }
+    let info = proxy.info(listen_port);
+    drop(proxies);
+    Ok(info)
+}
+
+/// Stop the proxy listening on `listen_port`, or every proxy when `None` - returns the stopped
+/// proxies with their final counts
+pub fn stop_proxies(listen_port: Option<u16>) -> Result<Vec<ProxyInfo>> {
+    let mut proxies = PROXIES
+        .lock()
+        .map_err(|_| Error::General("Network proxy registry is unavailable".to_string()))?;
+    let ports: Vec<u16> = match listen_port {
+        Some(port) if proxies.contains_key(&port) => vec![port],
+        Some(port) => {
+            return Err(Error::tool_call_failed(format!(
+                "No network proxy is listening on port {port}"
+            ))
+            .into());
+        },
+        None => proxies.keys().copied().collect(),
+    };
+    let mut stopped: Vec<ProxyInfo> = ports
+        .into_iter()
+        .filter_map(|port| {
+            let proxy = proxies.remove(&port)?;
+            proxy.cancel.cancel();
+            Some(proxy.info(port))
+        })
+        .collect();
+    drop(proxies);
+    stopped.sort_by_key(|info| info.listen_port);
+    Ok(stopped)
+}
+
+fn local_port(addr: std::io::Result<SocketAddr>) -> Result<u16> {
+    addr.map(|addr| addr.port())
+        .map_err(|e| Error::failed_to("read the proxy's listen address", e).into())
+}
+
+/// A UDP client's socket to the target, and the task returning the target's replies
+struct Upstream {
+    socket:      Arc<UdpSocket>,
+    /// Milliseconds after the proxy started that a packet last went either way
+    last_active: Arc<AtomicU64>,
+    cancel:      CancellationToken,
+}
+
+/// Forward datagrams between clients and `target`, one upstream socket per client so replies
+/// find their way back - closed again once the client goes quiet
+async fn run_udp_proxy(
+    socket: Arc<UdpSocket>,
+    target: SocketAddr,
+    shared: Arc<ProxyShared>,
+    cancel: CancellationToken,
+) {
+    let started = Instant::now();
+    let mut upstreams: HashMap<SocketAddr, Upstream> = HashMap::new();
+    let mut sweep = tokio::time::interval(UDP_IDLE_TIMEOUT);
+    let mut buffer = vec![0; MAX_DATAGRAM_SIZE];
+    loop {
+        let (len, client) = tokio::select! {
+            () = cancel.cancelled() => break,
+            _ = sweep.tick() => {
+                close_idle_upstreams(&mut upstreams, started);
+                continue;
+            },
+            received = socket.recv_from(&mut buffer) => match received {
+                Ok(received) => received,
+                // Windows reports an ICMP "port unreachable" for an earlier send as a failed
+                // receive - the socket itself is still fine
+                Err(e) => {
+                    debug!("Network proxy receive failed: {e}");
+                    continue;
+                },
+            },
+        };
+
+        let upstream = if let Some(upstream) = upstreams.get(&client) {
+            upstream
+        } else {
+            let Ok(upstream_socket) = connect_upstream(target).await else {
+                continue;
+            };
+            let upstream = Upstream {
+                socket:      upstream_socket,
+                last_active: Arc::new(AtomicU64::new(0)),
+                cancel:      cancel.child_token(),
+            };
+            tokio::spawn(return_to_client(
+                Arc::clone(&upstream.socket),
+                Arc::clone(&socket),
+                client,
+                Arc::clone(&shared),
+                started,
+                Arc::clone(&upstream.last_active),
+                upstream.cancel.clone(),
+            ));
+            upstreams.entry(client).or_insert(upstream)
+        };
+        upstream
+            .last_active
+            .store(millis_since(started), Ordering::Relaxed);
+        let upstream = Arc::clone(&upstream.socket);
+
+        if let Some(delay) = shared.schedule(true) {
+            let packet = buffer[..len].to_vec();
+            tokio::spawn(async move {
+                tokio::time::sleep(delay).await;
+                let _ = upstream.send(&packet).await;
+            });
+        }
+    }
+}
+
+/// Close the upstreams of clients that went quiet, ending their reply tasks
+fn close_idle_upstreams(upstreams: &mut HashMap<SocketAddr, Upstream>, started: Instant) {
+    let now = millis_since(started);
+    let idle_ms = millis(UDP_IDLE_TIMEOUT);
+    upstreams.retain(|client, upstream| {
+        let active = now.saturating_sub(upstream.last_active.load(Ordering::Relaxed)) < idle_ms;
+        if !active {
+            debug!("Network proxy closing idle upstream of {client}");
+            upstream.cancel.cancel();
+        }
+        active
+    });
+}
+
+fn millis_since(started: Instant) -> u64 { millis(started.elapsed()) }
+
+fn millis(duration: Duration) -> u64 { u64::try_from(duration.as_millis()).unwrap_or(u64::MAX) }
+
+async fn connect_upstream(target: SocketAddr) -> std::io::Result<Arc<UdpSocket>> {
+    let upstream = UdpSocket::bind((Ipv4Addr::LOCALHOST, 0)).await?;
+    upstream.connect(target).await?;
+    Ok(Arc::new(upstream))
+}
+
+/// Forward the target's replies on `upstream` back to `client`
+async fn return_to_client(
+    upstream: Arc<UdpSocket>,
+    socket: Arc<UdpSocket>,
+    client: SocketAddr,
+    shared: Arc<ProxyShared>,
+    started: Instant,
+    last_active: Arc<AtomicU64>,
+    cancel: CancellationToken,
+) {
+    let mut buffer = vec![0; MAX_DATAGRAM_SIZE];
+    loop {
+        let len = tokio::select! {
+            () = cancel.cancelled() => break,
+            received = upstream.recv(&mut buffer) => match received {
+                Ok(len) => len,
+                Err(_) => continue,
+            },
+        };
+        last_active.store(millis_since(started), Ordering::Relaxed);
+        if let Some(delay) = shared.schedule(true) {
+            let packet = buffer[..len].to_vec();
+            let socket = Arc::clone(&socket);
+            tokio::spawn(async move {
+                tokio::time::sleep(delay).await;
+                let _ = socket.send_to(&packet, client).await;
+            });
+        }
+    }
+}
+
+/// Accept connections and forward each to `target` in both directions
+async fn run_tcp_proxy(
+    listener: TcpListener,
+    target: SocketAddr,
+    shared: Arc<ProxyShared>,
+    cancel: CancellationToken,
+) {
+    loop {
+        let client = tokio::select! {
+            () = cancel.cancelled() => break,
+            accepted = listener.accept() => match accepted {
+                Ok((client, _)) => client,
+                Err(_) => continue,
+            },
+        };
+        let Ok(server) = TcpStream::connect(target).await else {
+            debug!("Network proxy could not connect to {target}");
+            continue;
+        };
+        let (client_read, client_write) = client.into_split();
+        let (server_read, server_write) = server.into_split();
+        for (read, write) in [(client_read, server_write), (server_read, client_write)] {
+            tokio::spawn(forward_stream(
+                read,
+                write,
+                Arc::clone(&shared),
+                cancel.clone(),
+            ));
+        }
+    }
+}
+
+/// Forward one direction of a TCP connection, delaying each read without reordering them
+async fn forward_stream(
+    mut read: OwnedReadHalf,
+    mut write: OwnedWriteHalf,
+    shared: Arc<ProxyShared>,
+    cancel: CancellationToken,
+) {
+    let (sender, mut receiver) = mpsc::unbounded_channel::<(Instant, Vec<u8>)>();
+    let writer = tokio::spawn(async move {
+        while let Some((deliver_at, bytes)) = receiver.recv().await {
+            tokio::time::sleep_until(deliver_at).await;
+            if write.write_all(&bytes).await.is_err() {
+                break;
+            }
+        }
+        let _ = write.shutdown().await;
+    });
+
+    let mut buffer = vec![0; TCP_CHUNK_SIZE];
+    let mut last_delivery = Instant::now();
+    loop {
+        let len = tokio::select! {
+            () = cancel.cancelled() => break,
+            read = read.read(&mut buffer) => match read {
+                Ok(0) | Err(_) => break,
+                Ok(len) => len,
+            },
+        };
+        let delay = shared.schedule(false).unwrap_or_default();
+        // Jitter may not reorder a stream, so a read is never delivered before the one before it
+        last_delivery = last_delivery.max(Instant::now() + delay);
+        if sender
+            .send((last_delivery, buffer[..len].to_vec()))
+            .is_err()
+        {
+            break;
+        }
+    }
+    drop(sender);
+    if cancel.is_cancelled() {
+        writer.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn udp_proxy_forwards_both_ways_and_drops_at_full_loss() {
+        let server = UdpSocket::bind((Ipv4Addr::LOCALHOST, 0))
+            .await
+            .unwrap_or_else(|e| unreachable!("bind server: {e}"));
+        let target_port = server
+            .local_addr()
+            .unwrap_or_else(|e| unreachable!("server address: {e}"))
+            .port();
+        let info = start_proxy(&NetworkProxyOptions {
+            target_port,
+            listen_port: None,
+            protocol: ProxyProtocol::Udp,
+            conditions: NetworkConditions::default(),
+        })
+        .await
+        .unwrap_or_else(|e| unreachable!("start proxy: {e:?}"));
+
+        let client = UdpSocket::bind((Ipv4Addr::LOCALHOST, 0))
+            .await
+            .unwrap_or_else(|e| unreachable!("bind client: {e}"));
+        let _ = client.send_to(b"ping", &info.listen_addr).await;
+        let mut buffer = [0; 16];
+        let (len, from) = server
+            .recv_from(&mut buffer)
+            .await
+            .unwrap_or_else(|e| unreachable!("server receive: {e}"));
+        assert_eq!(&buffer[..len], b"ping");
+        let _ = server.send_to(b"pong", from).await;
+        let (len, _) = client
+            .recv_from(&mut buffer)
+            .await
+            .unwrap_or_else(|e| unreachable!("client receive: {e}"));
+        assert_eq!(&buffer[..len], b"pong");
+
+        let configured = configure_proxy(info.listen_port, |conditions| {
+            conditions.loss_percent = 100.0;
+        })
+        .unwrap_or_else(|e| unreachable!("configure proxy: {e:?}"));
+        assert!((configured.conditions.loss_percent - 100.0).abs() < f64::EPSILON);
+        let _ = client.send_to(b"lost", &info.listen_addr).await;
+        let received =
+            tokio::time::timeout(Duration::from_millis(200), server.recv_from(&mut buffer)).await;
+        assert!(received.is_err());
+
+        let stopped = stop_proxies(Some(info.listen_port))
+            .unwrap_or_else(|e| unreachable!("stop proxy: {e:?}"));
+        assert_eq!(stopped[0].forwarded_packets, 2);
+        assert_eq!(stopped[0].dropped_packets, 1);
+        assert!(configure_proxy(info.listen_port, |_| {}).is_err());
+    }
+
+    #[tokio::test]
+    async fn tcp_proxy_delays_a_stream_without_reordering_it() {
+        let server = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+            .await
+            .unwrap_or_else(|e| unreachable!("bind server: {e}"));
+        let target_port = local_port(server.local_addr())
+            .unwrap_or_else(|e| unreachable!("server address: {e:?}"));
+        let info = start_proxy(&NetworkProxyOptions {
+            target_port,
+            listen_port: None,
+            protocol: ProxyProtocol::Tcp,
+            conditions: NetworkConditions {
+                latency_ms:   20,
+                jitter_ms:    20,
+                loss_percent: 100.0,
+            },
+        })
+        .await
+        .unwrap_or_else(|e| unreachable!("start proxy: {e:?}"));
+
+        let sent_at = Instant::now();
+        let mut client = TcpStream::connect(&info.listen_addr)
+            .await
+            .unwrap_or_else(|e| unreachable!("connect client: {e}"));
+        let (mut accepted, _) = server
+            .accept()
+            .await
+            .unwrap_or_else(|e| unreachable!("accept: {e}"));
+        for chunk in [&b"one "[..], b"two ", b"three"] {
+            let _ = client.write_all(chunk).await;
+        }
+        client
+            .shutdown()
+            .await
+            .unwrap_or_else(|e| unreachable!("close client: {e}"));
+        let mut received = Vec::new();
+        accepted
+            .read_to_end(&mut received)
+            .await
+            .unwrap_or_else(|e| unreachable!("server read: {e}"));
+
+        // Loss never applies to a stream, and jitter never reorders it
+        assert_eq!(received, b"one two three");
+        assert!(sent_at.elapsed() >= Duration::from_millis(20));
+        let stopped = stop_proxies(Some(info.listen_port))
+            .unwrap_or_else(|e| unreachable!("stop proxy: {e:?}"));
+        assert_eq!(stopped[0].dropped_packets, 0);
+    }
+
+    #[tokio::test]
+    async fn launch_proxy_address_reaches_every_instance() {
+        let mut instances = vec![InstanceOverride {
+            label: Some("server".to_string()),
+            ..InstanceOverride::default()
+        }];
+        let info = start_launch_proxy(
+            &NetworkProxyOptions {
+                target_port: 1,
+                listen_port: None,
+                protocol:    ProxyProtocol::Udp,
+                conditions:  NetworkConditions::default(),
+            },
+            &mut instances,
+            3,
+        )
+        .await
+        .unwrap_or_else(|e| unreachable!("start proxy: {e:?}"));
+        let _ = stop_proxies(Some(info.listen_port));
+
+        assert_eq!(instances.len(), 3);
+        assert_eq!(instances[0].label.as_deref(), Some("server"));
+        for instance in &instances {
+            assert_eq!(
+                instance.env.get(BRP_EXTRAS_NETWORK_PROXY_ENV_VAR),
+                Some(&info.listen_addr)
+            );
+        }
+    }
+}
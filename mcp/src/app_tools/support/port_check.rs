@@ -0,0 +1,267 @@
+//! Port-conflict check run before a launch spawns any instance
+//!
+//! An instance started on a port something else already listens on can't bind BRP, and BRP
+//! tools then talk to whatever holds the port instead. Checking first turns that into an error
+//! naming the occupant - and, when this server launched it, which target it is - and the
+//! nearest free ports. Once launched, `answers_brp_by` waits for
+//! the instance on a port to answer.
+
+use std::net::Ipv4Addr;
+use std::net::TcpListener;
+use std::time::Duration;
+
+use serde::Deserialize;
+use serde::Serialize;
+use sysinfo::ProcessesToUpdate;
+use sysinfo::System;
+use tokio::time::Instant;
+
+use super::errors::PortInUseError;
+use super::instance_registry;
+use super::instance_registry::RegisteredInstance;
+use super::process::get_pid_for_port;
+use crate::brp_tools;
+use crate::brp_tools::BrpClient;
+use crate::brp_tools::MAX_VALID_PORT;
+use crate::brp_tools::Port;
+use crate::error::Error;
+use crate::error::Result;
+use crate::tool::BrpMethod;
+
+/// How long an occupant gets to answer `rpc.discover` before it's taken not to be a BRP app
+const BRP_PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How often a launched instance that doesn't answer yet is probed
+const READY_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// How many free base ports to suggest
+const SUGGESTED_PORT_COUNT: usize = 3;
+
+/// Something already listening on a port a launch wants
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortConflict {
+    /// The occupied port
+    pub port:         u16,
+    /// PID of the process listening on it, if it could be found
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pid:          Option<u32>,
+    /// Name of that process
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub process_name: Option<String>,
+    /// Whether it answers BRP requests
+    pub brp_app:      bool,
+    /// Target of the instance this server launched on the port, from the instance registry
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_name:  Option<String>,
+}
+
+/// Fail with a `PortInUseError` if anything listens on the ports `instance_count` instances
+/// starting at `base_port` would use
+pub async fn check_ports_available(base_port: Port, instance_count: usize) -> Result<()> {
+    let ports = port_range(*base_port, instance_count);
+    let occupied: Vec<u16> = ports.clone().filter(|&port| !is_port_free(port)).collect();
+    if occupied.is_empty() {
+        return Ok(());
+    }
+
+    let mut system = System::new();
+    system.refresh_processes(ProcessesToUpdate::All, true);
+    let instances = instance_registry::live_instances();
+    let mut conflicts = Vec::with_capacity(occupied.len());
+    for port in occupied {
+        let pid = get_pid_for_port(Port(port));
+        let process_name = pid.and_then(|pid| {
+            system
+                .process(sysinfo::Pid::from_u32(pid))
+                .map(|process| process.name().to_string_lossy().to_string())
+        });
+        conflicts.push(PortConflict {
+            port,
+            pid,
+            process_name,
+            brp_app: is_brp_app(Port(port)).await,
+            target_name: registered_target(&instances, port),
+        });
+    }
+
+    let suggested_ports = suggest_free_base_ports(*ports.end(), instance_count, is_port_free);
+    let message = describe(&conflicts, &suggested_ports);
+    Err(Error::Structured {
+        result: Box::new(
+            PortInUseError::new(conflicts, suggested_ports).with_message_template(message),
+        ),
+    })?
+}
+
+/// The ports used by `instance_count` instances starting at `base_port`
+fn port_range(base_port: u16, instance_count: usize) -> std::ops::RangeInclusive<u16> {
+    let count = u16::try_from(instance_count.max(1)).unwrap_or(u16::MAX);
+    base_port..=base_port.saturating_add(count - 1).min(MAX_VALID_PORT)
+}
+
+/// Target of the registered instance on `port`
+fn registered_target(instances: &[RegisteredInstance], port: u16) -> Option<String> {
+    instances
+        .iter()
+        .find(|instance| instance.port == port)
+        .map(|instance| instance.target_name.clone())
+}
+
+/// Whether nothing listens on `port` on the loopback interface BRP binds to
+fn is_port_free(port: u16) -> bool { TcpListener::bind((Ipv4Addr::LOCALHOST, port)).is_ok() }
+
+/// Whether the process on `port` answers a JSON-RPC request
+async fn is_brp_app(port: Port) -> bool {
+    let client = BrpClient::new(BrpMethod::RpcDiscover, port, None);
+    matches!(
+        tokio::time::timeout(BRP_PROBE_TIMEOUT, client.execute_untouched()).await,
+        Ok(Ok(response)) if response.get("jsonrpc").is_some()
+    )
+}
+
+/// Probe `port` with `rpc.discover` until it answers or `deadline` passes
+pub async fn answers_brp_by(port: Port, deadline: Instant) -> bool {
+    loop {
+        // Failed probes of a starting app mustn't leave its circuit open
+        brp_tools::reset_circuit(Some(port));
+        let client = BrpClient::new(BrpMethod::RpcDiscover, port, None);
+        if let Ok(Ok(response)) =
+            tokio::time::timeout(BRP_PROBE_TIMEOUT, client.execute_untouched()).await
+            && response.get("result").is_some()
+        {
+            return true;
+        }
+        if Instant::now() + READY_POLL_INTERVAL > deadline {
+            return false;
+        }
+        tokio::time::sleep(READY_POLL_INTERVAL).await;
+    }
+}
+
+/// Base ports above `after` whose whole range of `instance_count` ports is free
+fn suggest_free_base_ports(
+    after: u16,
+    instance_count: usize,
+    is_free: impl Fn(u16) -> bool,
+) -> Vec<u16> {
+    let count = u16::try_from(instance_count.max(1)).unwrap_or(u16::MAX);
+    let mut suggestions = Vec::new();
+    let mut base = after.saturating_add(1);
+    while suggestions.len() < SUGGESTED_PORT_COUNT
+        && let Some(last) = base.checked_add(count - 1)
+        && last <= MAX_VALID_PORT
+    {
+        if let Some(occupied) = (base..=last).find(|&port| !is_free(port)) {
+            // Skip past the occupied port - no range containing it can be free
+            base = occupied.saturating_add(1);
+        } else {
+            suggestions.push(base);
+            base = last.saturating_add(1);
+        }
+    }
+    suggestions
+}
+
+/// Summary of the conflicts and suggestions for the error message
+fn describe(conflicts: &[PortConflict], suggested_ports: &[u16]) -> String {
+    let occupants = conflicts
+        .iter()
+        .map(|conflict| {
+            let owner = match (&conflict.process_name, conflict.pid) {
+                (Some(name), Some(pid)) => format!("'{name}' (PID {pid})"),
+                (None, Some(pid)) => format!("PID {pid}"),
+                _ => "an unknown process".to_string(),
+            };
+            let kind = match (&conflict.target_name, conflict.brp_app) {
+                (Some(target), _) => format!(", the '{target}' instance launched by this server"),
+                (None, true) => ", a BRP app".to_string(),
+                (None, false) => String::new(),
+            };
+            format!("port {} is in use by {owner}{kind}", conflict.port)
+        })
+        .collect::<Vec<_>>()
+        .join("; ");
+
+    if suggested_ports.is_empty() {
+        format!("Cannot launch: {occupants}")
+    } else {
+        let suggestions = suggested_ports
+            .iter()
+            .map(u16::to_string)
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!("Cannot launch: {occupants}. Free ports to use instead: {suggestions}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn suggestions_skip_ranges_with_an_occupied_port() {
+        let busy = [15705, 15708];
+        let is_free = |port: u16| !busy.contains(&port);
+
+        assert_eq!(
+            suggest_free_base_ports(15702, 2, is_free),
+            vec![15703, 15706, 15709]
+        );
+        assert_eq!(
+            suggest_free_base_ports(15702, 1, is_free),
+            vec![15703, 15704, 15706]
+        );
+    }
+
+    #[test]
+    fn conflicts_name_the_registered_target() {
+        let instance: RegisteredInstance = serde_json::from_value(serde_json::json!({
+            "target_name": "space_game",
+            "target_type": "app",
+            "profile": "debug",
+            "pid": 4242,
+            "port": 15702,
+            "log_file": "/tmp/space_game.log",
+            "started_at": 0
+        }))
+        .unwrap_or_else(|e| unreachable!("instance should deserialize: {e}"));
+        let instances = [instance];
+        assert_eq!(
+            registered_target(&instances, 15702).as_deref(),
+            Some("space_game")
+        );
+        assert_eq!(registered_target(&instances, 15703), None);
+
+        let conflicts = [
+            PortConflict {
+                port:         15702,
+                pid:          Some(4242),
+                process_name: Some("space_game".to_string()),
+                brp_app:      true,
+                target_name:  registered_target(&instances, 15702),
+            },
+            PortConflict {
+                port:         15703,
+                pid:          None,
+                process_name: None,
+                brp_app:      false,
+                target_name:  None,
+            },
+        ];
+        assert_eq!(
+            describe(&conflicts, &[15704]),
+            "Cannot launch: port 15702 is in use by 'space_game' (PID 4242), the 'space_game' \
+             instance launched by this server; port 15703 is in use by an unknown process. Free \
+             ports to use instead: 15704"
+        );
+    }
+
+    #[test]
+    fn suggestions_stop_at_the_highest_valid_port() {
+        assert_eq!(
+            suggest_free_base_ports(MAX_VALID_PORT - 2, 2, |_| true),
+            vec![MAX_VALID_PORT - 1]
+        );
+        assert!(suggest_free_base_ports(MAX_VALID_PORT, 1, |_| true).is_empty());
+    }
+}
@@ -1,11 +1,14 @@
 use std::fs::File;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::Read;
+use std::io::Write;
 #[cfg(unix)]
 use std::os::unix::process::CommandExt;
 use std::path::Path;
 use std::process::Stdio;
 
 use error_stack::Report;
-use error_stack::ResultExt;
 use netstat2::AddressFamilyFlags;
 use netstat2::ProtocolFlags;
 use netstat2::ProtocolSocketInfo;
@@ -15,21 +18,21 @@ use crate::brp_tools::Port;
 use crate::error::Error;
 use crate::error::Result;
 
+/// Format of the time prefixed to captured output lines
+const LINE_TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S%.3f";
+
 /// Launch a detached process with proper setup
+///
+/// With `timestamps`, output is piped through relay threads that prefix each line with the time
+/// it was written; otherwise the process writes to the log files directly.
 pub fn launch_detached_process(
     cmd: &std::process::Command,
     working_dir: &Path,
-    log_file: File,
+    stdout_file: File,
+    stderr_file: File,
+    timestamps: bool,
     process_name: &str,
 ) -> Result<u32> {
-    // Clone the log file handle for stderr
-    let log_file_for_stderr = log_file
-        .try_clone()
-        .change_context(Error::ProcessManagement(
-            "Failed to clone log file handle".to_string(),
-        ))
-        .attach(format!("Process: {process_name}, Operation: launch"))?;
-
     // Create a new command from the provided one
     let mut new_cmd = std::process::Command::new(cmd.get_program());
 
@@ -51,10 +54,16 @@ pub fn launch_detached_process(
     }
 
     // Set stdio
-    new_cmd
-        .stdin(Stdio::null())
-        .stdout(Stdio::from(log_file))
-        .stderr(Stdio::from(log_file_for_stderr));
+    new_cmd.stdin(Stdio::null());
+    let relay_files = if timestamps {
+        new_cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
+        Some((stdout_file, stderr_file))
+    } else {
+        new_cmd
+            .stdout(Stdio::from(stdout_file))
+            .stderr(Stdio::from(stderr_file));
+        None
+    };
 
     // Create new process group for true detachment (Unix only)
     #[cfg(unix)]
@@ -72,6 +81,15 @@ pub fn launch_detached_process(
 
             tracing::debug!("Process spawned successfully: {process_name} (PID: {pid})");
 
+            if let Some((stdout_file, stderr_file)) = relay_files {
+                if let Some(stdout) = child.stdout.take() {
+                    let _ = relay_with_timestamps(stdout, stdout_file);
+                }
+                if let Some(stderr) = child.stderr.take() {
+                    let _ = relay_with_timestamps(stderr, stderr_file);
+                }
+            }
+
             // Spawn a background thread to reap the child when it exits
             // This prevents zombie processes
             std::thread::spawn(move || match child.wait() {
@@ -97,6 +115,39 @@ pub fn launch_detached_process(
     }
 }
 
+/// Copy `source` into `sink` on a background thread, prefixing each line with the current time
+///
+/// A failed write stops the copying but not the reading: closing the pipe would make the app's
+/// next write to it fail, and `println!` panics when it does.
+fn relay_with_timestamps(
+    source: impl Read + Send + 'static,
+    mut sink: impl Write + Send + 'static,
+) -> std::thread::JoinHandle<()> {
+    std::thread::spawn(move || {
+        let mut reader = BufReader::new(source);
+        let mut line = Vec::new();
+        let mut writable = true;
+        loop {
+            line.clear();
+            match reader.read_until(b'\n', &mut line) {
+                Ok(0) | Err(_) => break,
+                Ok(_) if !writable => {},
+                Ok(_) => {
+                    // One write per line keeps stdout and stderr lines whole in a shared file
+                    let mut stamped =
+                        format!("[{}] ", chrono::Local::now().format(LINE_TIMESTAMP_FORMAT))
+                            .into_bytes();
+                    stamped.extend_from_slice(&line);
+                    if let Err(e) = sink.write_all(&stamped) {
+                        tracing::warn!("Stopped capturing output, the log file write failed: {e}");
+                        writable = false;
+                    }
+                },
+            }
+        }
+    })
+}
+
 /// Get the PID for a process listening on the specified port
 pub fn get_pid_for_port(port: Port) -> Option<u32> {
     let af_flags = AddressFamilyFlags::IPV4 | AddressFamilyFlags::IPV6;
@@ -114,3 +165,86 @@ pub fn get_pid_for_port(port: Port) -> Option<u32> {
             None
         })
 }
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+    use std::sync::Arc;
+    use std::sync::Mutex;
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::atomic::Ordering;
+
+    use super::*;
+
+    /// A log file the test can read back
+    #[derive(Clone, Default)]
+    struct SharedSink(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedSink {
+        fn write(&mut self, bytes: &[u8]) -> std::io::Result<usize> {
+            self.0
+                .lock()
+                .map_err(|_| std::io::Error::other("poisoned"))?
+                .extend_from_slice(bytes);
+            Ok(bytes.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> { Ok(()) }
+    }
+
+    /// A log file on a full disk
+    struct FailingSink;
+
+    impl Write for FailingSink {
+        fn write(&mut self, _: &[u8]) -> std::io::Result<usize> {
+            Err(std::io::Error::other("disk full"))
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> { Ok(()) }
+    }
+
+    /// App output that counts how much of it was read
+    struct CountingSource {
+        output: Cursor<Vec<u8>>,
+        read:   Arc<AtomicUsize>,
+    }
+
+    impl Read for CountingSource {
+        fn read(&mut self, buffer: &mut [u8]) -> std::io::Result<usize> {
+            let read = self.output.read(buffer)?;
+            self.read.fetch_add(read, Ordering::Relaxed);
+            Ok(read)
+        }
+    }
+
+    #[test]
+    fn every_line_is_stamped_with_the_time() {
+        let sink = SharedSink::default();
+        let relay =
+            relay_with_timestamps(Cursor::new(b"one\ntwo\nno newline".to_vec()), sink.clone());
+        assert!(relay.join().is_ok());
+
+        let written = sink.0.lock().map(|bytes| bytes.clone()).unwrap_or_default();
+        let written = String::from_utf8_lossy(&written);
+        let lines: Vec<&str> = written.lines().collect();
+        assert_eq!(lines.len(), 3);
+        for (line, text) in lines.iter().zip(["one", "two", "no newline"]) {
+            // `[2026-01-01 12:00:00.000] ` is 26 characters
+            assert!(line.starts_with('['), "{line}");
+            assert_eq!(line.get(24..26), Some("] "), "{line}");
+            assert_eq!(line.get(26..), Some(text));
+        }
+    }
+
+    #[test]
+    fn a_failed_write_keeps_draining_the_app_output() {
+        let output = b"line\n".repeat(1000);
+        let read = Arc::new(AtomicUsize::new(0));
+        let source = CountingSource {
+            output: Cursor::new(output.clone()),
+            read:   Arc::clone(&read),
+        };
+        assert!(relay_with_timestamps(source, FailingSink).join().is_ok());
+        assert_eq!(read.load(Ordering::Relaxed), output.len());
+    }
+}
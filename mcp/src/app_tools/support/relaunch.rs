@@ -0,0 +1,348 @@
+//! Auto-relaunch engine behind `brp_watch_and_relaunch`
+//!
+//! Watches a target's package sources and, once a burst of changes has settled, rebuilds the
+//! target. When the build succeeds the running instance is shut down and launched again; when it
+//! fails the old instance keeps running. Every step is written to a watch log, and the task is
+//! registered with the watch manager so `brp_list_active_watches` and `brp_stop_watch` control it
+//! like any other watch.
+
+use std::collections::BTreeSet;
+use std::path::Component;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use notify::Event;
+use notify::RecommendedWatcher;
+use notify::RecursiveMode;
+use notify::Watcher;
+use serde_json::Value;
+use serde_json::json;
+use tokio::sync::mpsc;
+use tracing::debug;
+use tracing::error;
+
+use super::cargo_detector::BevyTarget;
+use super::launch_common::FromLaunchParams;
+use super::launch_common::LaunchConfigTrait;
+use super::launch_common::LaunchParams;
+use super::launch_common::LaunchResult;
+use super::launch_common::ensure_built;
+use super::launch_common::find_launch_target;
+use super::launch_common::launch_target;
+use super::launch_common::validate_manifest_directory;
+use super::port_check::check_ports_available;
+use super::process::get_pid_for_port;
+use crate::app_tools::brp_shutdown::ShutdownOutcome;
+use crate::app_tools::brp_shutdown::shutdown_app;
+use crate::brp_tools::BufferedWatchLogger;
+use crate::brp_tools::Port;
+use crate::brp_tools::start_background_watch_task;
+use crate::error::Error;
+use crate::error::Result;
+
+/// Watch type recorded with the watch manager and in the log file name
+const RELAUNCH_WATCH_TYPE: &str = "relaunch";
+
+/// Environment variable moving cargo's build directory out of the workspace's `target`
+const CARGO_TARGET_DIR_ENV_VAR: &str = "CARGO_TARGET_DIR";
+
+/// How long to wait for a stopped instance to release its port
+const PORT_RELEASE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How often to check whether the port has been released
+const PORT_RELEASE_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// A started relaunch watch
+pub struct RelaunchWatchStarted {
+    /// Watch ID to pass to `brp_stop_watch`
+    pub watch_id:     u32,
+    /// Log file the rebuild status is written to
+    pub log_path:     PathBuf,
+    /// Package directory being watched
+    pub watched_path: PathBuf,
+    /// Result of the initial launch
+    pub launch:       LaunchResult,
+}
+
+/// Everything the relaunch loop needs to rebuild and relaunch its target
+struct RelaunchContext<T> {
+    config:       T,
+    target:       BevyTarget,
+    search_paths: Vec<PathBuf>,
+    debounce:     Duration,
+}
+
+/// Launch the target `params` names, then watch its package and relaunch it on every change
+pub async fn start_relaunch_watch<T: FromLaunchParams + 'static>(
+    params: &LaunchParams,
+    search_paths: Vec<PathBuf>,
+    debounce: Duration,
+) -> Result<RelaunchWatchStarted> {
+    let config = T::from_params(params);
+    let target = find_launch_target(&config, &search_paths)?;
+    let watched_path = validate_manifest_directory(&target.manifest_path)?.to_path_buf();
+
+    // Start watching before the initial launch so edits made while it builds aren't missed
+    let (tx, rx) = mpsc::unbounded_channel();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<Event>| {
+        if let Ok(event) = event {
+            let _ = tx.send(event);
+        }
+    })
+    .map_err(|e| Error::failed_to("start file watcher", e))?;
+    watcher
+        .watch(&watched_path, RecursiveMode::Recursive)
+        .map_err(|e| Error::failed_to(&format!("watch {}", watched_path.display()), e))?;
+
+    check_ports_available(config.port(), *config.instance_count()).await?;
+    let launch = launch_target(&config, &search_paths).await?;
+
+    let start_data = json!({
+        "target_name": config.target_name(),
+        "target_type": T::TARGET_TYPE,
+        "profile": config.profile(),
+        "watched_path": watched_path,
+        "port": config.port(),
+        "debounce_ms": debounce.as_millis(),
+        "timestamp": chrono::Local::now().to_rfc3339(),
+    });
+    let context = RelaunchContext {
+        config,
+        target,
+        search_paths,
+        debounce,
+    };
+    let port = context.config.port();
+    let (watch_id, log_path) =
+        start_background_watch_task(RELAUNCH_WATCH_TYPE, port, start_data, move |_, logger| {
+            run_relaunch_loop(context, watcher, rx, logger)
+        })
+        .await?;
+
+    Ok(RelaunchWatchStarted {
+        watch_id,
+        log_path,
+        watched_path,
+        launch,
+    })
+}
+
+/// Rebuild and relaunch on every settled burst of source changes until the watch is stopped
+async fn run_relaunch_loop<T: LaunchConfigTrait + Send + Sync + 'static>(
+    context: RelaunchContext<T>,
+    watcher: RecommendedWatcher,
+    mut events: mpsc::UnboundedReceiver<Event>,
+    logger: BufferedWatchLogger,
+) {
+    // Dropping the watcher - when the watch is stopped and this task aborted - ends the events
+    let _watcher = watcher;
+    let root = &context.target.workspace_root;
+    let target_dir = build_dir(
+        root,
+        std::env::var_os(CARGO_TARGET_DIR_ENV_VAR).map(PathBuf::from),
+    );
+
+    while let Some(event) = events.recv().await {
+        let mut changed = relevant_paths(&event, root, &target_dir);
+        if changed.is_empty() {
+            continue;
+        }
+
+        // Let a burst of saves settle before building
+        while let Ok(Some(event)) = tokio::time::timeout(context.debounce, events.recv()).await {
+            changed.extend(relevant_paths(&event, root, &target_dir));
+        }
+
+        log(&logger, "REBUILD_STARTED", json!({ "changed": changed })).await;
+        if let Err(message) = rebuild(&context).await {
+            log(&logger, "BUILD_FAILED", json!({ "error": message })).await;
+            continue;
+        }
+
+        let stopped = shutdown_app(context.config.target_name(), context.config.port()).await;
+        log(&logger, "INSTANCE_STOPPED", describe_shutdown(&stopped)).await;
+        wait_for_port_release(context.config.port()).await;
+
+        match relaunch(&context).await {
+            Ok(launch) => {
+                let data = serde_json::to_value(&launch).unwrap_or(Value::Null);
+                log(&logger, "RELAUNCHED", data).await;
+            },
+            Err(message) => log(&logger, "RELAUNCH_FAILED", json!({ "error": message })).await,
+        }
+    }
+
+    debug!("Relaunch watch for {} ended", context.config.target_name());
+}
+
+/// Build the target, returning the error message on failure
+async fn rebuild<T: LaunchConfigTrait + Send + Sync + 'static>(
+    context: &RelaunchContext<T>,
+) -> std::result::Result<(), String> {
+    ensure_built(&context.config, &context.target)
+        .await
+        .map(|_| ())
+        // The full report carries the compiler output attached by the build
+        .map_err(|report| format!("{report:?}"))
+}
+
+/// Launch the target again, returning the error message on failure
+async fn relaunch<T: LaunchConfigTrait + Send + Sync + 'static>(
+    context: &RelaunchContext<T>,
+) -> std::result::Result<LaunchResult, String> {
+    launch_target(&context.config, &context.search_paths)
+        .await
+        .map_err(|report| report.current_context().to_string())
+}
+
+/// Wait until nothing listens on `port`, so the relaunched instance can bind it
+async fn wait_for_port_release(port: Port) {
+    let deadline = tokio::time::Instant::now() + PORT_RELEASE_TIMEOUT;
+    while get_pid_for_port(port).is_some() && tokio::time::Instant::now() < deadline {
+        tokio::time::sleep(PORT_RELEASE_POLL_INTERVAL).await;
+    }
+}
+
+/// Describe how the previous instance was stopped for the log
+fn describe_shutdown(outcome: &ShutdownOutcome) -> Value {
+    match outcome {
+        ShutdownOutcome::CleanShutdown { pid } => json!({ "method": "clean_shutdown", "pid": pid }),
+        ShutdownOutcome::ProcessKilled { pid } => json!({ "method": "process_kill", "pid": pid }),
+        ShutdownOutcome::NotRunning => json!({ "method": "not_running" }),
+        ShutdownOutcome::Error { message } => json!({ "method": "failed", "error": message }),
+    }
+}
+
+/// Write a status update, reporting logger failures to the server log
+async fn log(logger: &BufferedWatchLogger, update_type: &str, data: Value) {
+    if let Err(e) = logger.write_update(update_type, data).await {
+        error!("Failed to write relaunch update to log: {e}");
+    }
+}
+
+/// Directory cargo builds into for the workspace at `root` - `CARGO_TARGET_DIR` when set,
+/// relative to the workspace like cargo takes it
+fn build_dir(root: &Path, cargo_target_dir: Option<PathBuf>) -> PathBuf {
+    cargo_target_dir.map_or_else(|| root.join("target"), |dir| root.join(dir))
+}
+
+/// Source files in `event` that should trigger a rebuild - Rust sources and manifests outside
+/// the build directory and hidden directories under the watched `root`
+fn relevant_paths(event: &Event, root: &Path, target_dir: &Path) -> BTreeSet<PathBuf> {
+    if event.kind.is_access() {
+        return BTreeSet::new();
+    }
+
+    event
+        .paths
+        .iter()
+        .filter(|path| !path.starts_with(target_dir))
+        .filter(|path| {
+            // Hidden directories above the root, like a `~/.projects` checkout, don't count
+            let within_root = path.strip_prefix(root).unwrap_or(path);
+            !within_root.components().any(|component| {
+                matches!(component, Component::Normal(name)
+                    if name.to_str().is_some_and(|name| name.starts_with('.')))
+            })
+        })
+        .filter(|path| {
+            path.extension().is_some_and(|extension| extension == "rs")
+                || path
+                    .file_name()
+                    .is_some_and(|name| name == "Cargo.toml" || name == "Cargo.lock")
+        })
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use notify::EventKind;
+    use notify::event::AccessKind;
+    use notify::event::ModifyKind;
+
+    use super::*;
+
+    fn event(kind: EventKind, paths: &[&str]) -> Event {
+        paths.iter().fold(Event::new(kind), |event, path| {
+            event.add_path(PathBuf::from(path))
+        })
+    }
+
+    #[test]
+    fn only_sources_and_manifests_trigger_rebuilds() {
+        let target_dir = Path::new("/game/target");
+        let modified = event(
+            EventKind::Modify(ModifyKind::Any),
+            &[
+                "/game/src/main.rs",
+                "/game/Cargo.toml",
+                "/game/assets/player.png",
+                "/game/target/debug/build/out.rs",
+                "/game/.git/index.rs",
+            ],
+        );
+
+        let changed = relevant_paths(&modified, Path::new("/game"), target_dir);
+        assert_eq!(
+            changed.into_iter().collect::<Vec<_>>(),
+            vec![
+                PathBuf::from("/game/Cargo.toml"),
+                PathBuf::from("/game/src/main.rs")
+            ]
+        );
+    }
+
+    #[test]
+    fn access_events_are_ignored() {
+        let accessed = event(EventKind::Access(AccessKind::Any), &["/game/src/main.rs"]);
+        assert!(
+            relevant_paths(&accessed, Path::new("/game"), Path::new("/game/target")).is_empty()
+        );
+    }
+
+    #[test]
+    fn hidden_directories_only_count_below_the_root() {
+        let root = Path::new("/home/dev/.projects/game");
+        let modified = event(
+            EventKind::Modify(ModifyKind::Any),
+            &[
+                "/home/dev/.projects/game/src/main.rs",
+                "/home/dev/.projects/game/.cargo/config.rs",
+            ],
+        );
+
+        let changed = relevant_paths(&modified, root, &root.join("target"));
+        assert_eq!(
+            changed.into_iter().collect::<Vec<_>>(),
+            vec![PathBuf::from("/home/dev/.projects/game/src/main.rs")]
+        );
+    }
+
+    #[test]
+    fn cargo_target_dir_moves_the_ignored_build_directory() {
+        let root = Path::new("/game");
+        assert_eq!(build_dir(root, None), Path::new("/game/target"));
+        assert_eq!(
+            build_dir(root, Some(PathBuf::from("/tmp/build"))),
+            Path::new("/tmp/build")
+        );
+        assert_eq!(
+            build_dir(root, Some(PathBuf::from("out"))),
+            Path::new("/game/out")
+        );
+
+        let target_dir = build_dir(root, Some(PathBuf::from("/tmp/build")));
+        let modified = event(
+            EventKind::Modify(ModifyKind::Any),
+            &["/tmp/build/debug/build/out.rs", "/game/target/main.rs"],
+        );
+        assert_eq!(
+            relevant_paths(&modified, root, &target_dir)
+                .into_iter()
+                .collect::<Vec<_>>(),
+            vec![PathBuf::from("/game/target/main.rs")]
+        );
+    }
+}
@@ -0,0 +1,445 @@
+//! Remote launches over SSH
+//!
+//! Testing on target hardware (a Steam Deck, an ARM board) means running the game on another
+//! machine. A launch with `remote_host` names a profile from the JSON file in
+//! `BRP_MCP_REMOTE_HOSTS`; the target is built locally (optionally cross-compiled) and copied to
+//! the remote machine, or built there from a checkout of the project. Each instance is then
+//! started through `ssh`, which also forwards the instance's BRP port to the same local port -
+//! `bevy_brp_extras` only listens on the remote machine's loopback interface, and every BRP tool
+//! keeps talking to `127.0.0.1`.
+//!
+//! The local SSH process is the instance's `pid` and its output is the instance's log. The
+//! remote PID is printed by the remote shell before it `exec`s the binary and read back from
+//! the log.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use std::process::Output;
+use std::time::Duration;
+use std::time::Instant;
+
+use serde::Deserialize;
+
+use super::cargo_detector::BevyTarget;
+use super::cargo_detector::TargetType;
+use super::launch_common::InstancePosition;
+use super::launch_common::LaunchConfigTrait;
+use super::launch_common::brp_env_vars;
+use super::launch_common::build_failed_report;
+use super::launch_common::run_cargo_build;
+use super::launch_common::validate_manifest_directory;
+use crate::app_tools::instance_override::InstanceOverride;
+use crate::app_tools::launch_params::BuildProfile;
+use crate::brp_tools::BRP_DEFAULT_HOST;
+use crate::brp_tools::Port;
+use crate::error::Error;
+use crate::error::Result;
+
+/// Environment variable naming the JSON file of remote host profiles
+pub const REMOTE_HOSTS_ENV_VAR: &str = "BRP_MCP_REMOTE_HOSTS";
+
+/// Printed by the remote shell, followed by its PID, right before it `exec`s the binary
+const REMOTE_PID_MARKER: &str = "BRP_MCP_REMOTE_PID=";
+
+/// How long to wait for an instance to report its remote PID
+const REMOTE_PID_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// How often to check the log for the remote PID
+const REMOTE_PID_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Bevy's asset directory override - binaries run outside cargo look next to themselves
+const BEVY_ASSET_ROOT_ENV_VAR: &str = "BEVY_ASSET_ROOT";
+
+/// Where a remote launch builds the target
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RemoteBuild {
+    /// Build on this machine and copy the binary over
+    #[default]
+    Local,
+    /// Build on the remote machine in `remote_project_dir`
+    Remote,
+}
+
+/// An SSH host profile from the remote hosts file
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RemoteHostProfile {
+    /// Profile name - its key in the remote hosts file
+    #[serde(skip)]
+    pub name:               String,
+    /// `ssh` destination, e.g. `deck@steamdeck.local`
+    pub ssh_destination:    String,
+    /// Extra options passed to both `ssh` and `scp`, e.g. `["-o", "IdentityFile=~/.ssh/deck"]`
+    #[serde(default)]
+    pub ssh_options:        Vec<String>,
+    /// Directory on the remote machine that locally built binaries are copied to and run from
+    pub remote_dir:         String,
+    /// Where to build the target
+    #[serde(default)]
+    pub build:              RemoteBuild,
+    /// Target triple to cross-compile for when building locally, e.g.
+    /// `aarch64-unknown-linux-gnu`
+    pub target_triple:      Option<String>,
+    /// Project checkout on the remote machine, required when building remotely
+    pub remote_project_dir: Option<String>,
+}
+
+/// A target built for a remote machine, ready to start instances of
+pub struct RemoteLaunch {
+    /// Profile of the machine the target runs on
+    pub profile: RemoteHostProfile,
+    /// Path of the binary on the remote machine
+    binary:      String,
+    /// Directory the binary is run from, which also holds its `assets`
+    working_dir: String,
+}
+
+impl RemoteLaunch {
+    /// Path of the binary on the remote machine
+    pub fn binary(&self) -> &str { &self.binary }
+}
+
+/// Load the remote host profile named `name`
+pub fn load_profile(name: &str) -> Result<RemoteHostProfile> {
+    let Ok(path) = std::env::var(REMOTE_HOSTS_ENV_VAR) else {
+        return Err(Error::invalid(
+            "remote_host",
+            format!(
+                "no remote host profiles are configured - set {REMOTE_HOSTS_ENV_VAR} to a JSON \
+                 file of profiles"
+            ),
+        )
+        .into());
+    };
+    let contents = fs::read_to_string(&path)
+        .map_err(|e| Error::io_failed("read remote host profiles", Path::new(&path), &e))?;
+    let mut profiles = parse_profiles(&contents)
+        .map_err(|e| Error::invalid("remote host profiles", format!("{path}: {e}")))?;
+
+    let available = profiles.keys().cloned().collect::<Vec<_>>().join(", ");
+    let profile = profiles.remove(name).ok_or_else(|| {
+        Error::invalid(
+            "remote_host",
+            format!("no profile named '{name}' in {path} (available: {available})"),
+        )
+    })?;
+    if profile.build == RemoteBuild::Remote && profile.remote_project_dir.is_none() {
+        return Err(Error::invalid(
+            "remote host profile",
+            format!("'{name}' builds remotely but has no remote_project_dir"),
+        )
+        .into());
+    }
+    Ok(profile)
+}
+
+/// Parse the remote hosts file, a JSON object of profiles keyed by name
+fn parse_profiles(contents: &str) -> serde_json::Result<BTreeMap<String, RemoteHostProfile>> {
+    let mut profiles: BTreeMap<String, RemoteHostProfile> = serde_json::from_str(contents)?;
+    for (name, profile) in &mut profiles {
+        profile.name.clone_from(name);
+    }
+    Ok(profiles)
+}
+
+/// Build the target for the remote machine and make its binary available there
+pub async fn prepare<T: LaunchConfigTrait>(
+    config: &T,
+    target: &BevyTarget,
+    profile: RemoteHostProfile,
+) -> Result<RemoteLaunch> {
+    if let (RemoteBuild::Remote, Some(project_dir)) =
+        (profile.build, profile.remote_project_dir.clone())
+    {
+        build_remotely(config, target, &profile, &project_dir).await?;
+        let binary = remote_binary_path(&project_dir, config.profile(), target);
+        Ok(RemoteLaunch {
+            profile,
+            binary,
+            working_dir: project_dir,
+        })
+    } else {
+        let manifest_dir = validate_manifest_directory(&target.manifest_path)?;
+        run_cargo_build(
+            config.target_name(),
+            T::TARGET_TYPE,
+            config.profile(),
+            manifest_dir,
+            config.features(),
+            profile.target_triple.as_deref(),
+            false,
+        )
+        .await?;
+        let local_binary = profile.target_triple.as_deref().map_or_else(
+            || target.get_binary_path(config.profile()),
+            |triple| target.get_cross_binary_path(config.profile(), triple),
+        );
+
+        let mut mkdir = ssh(&profile);
+        mkdir
+            .arg(&profile.ssh_destination)
+            .arg(format!("mkdir -p {}", shell_quote(&profile.remote_dir)));
+        let output = run_killable(mkdir, "ssh").await?;
+        check_ssh(&profile, &output, "create the remote directory")?;
+        let binary = format!(
+            "{}/{}",
+            profile.remote_dir.trim_end_matches('/'),
+            target.name
+        );
+        let mut scp = Command::new("scp");
+        scp.args(&profile.ssh_options)
+            .arg(&local_binary)
+            .arg(format!("{}:{binary}", profile.ssh_destination));
+        let output = run_killable(scp, "scp").await?;
+        if !output.status.success() {
+            return Err(Error::process_failed(
+                "copy the binary with",
+                "scp",
+                String::from_utf8_lossy(&output.stderr).trim(),
+            )
+            .into());
+        }
+
+        let working_dir = profile.remote_dir.clone();
+        Ok(RemoteLaunch {
+            profile,
+            binary,
+            working_dir,
+        })
+    }
+}
+
+/// Run `cargo build` in the project checkout on the remote machine
+async fn build_remotely<T: LaunchConfigTrait>(
+    config: &T,
+    target: &BevyTarget,
+    profile: &RemoteHostProfile,
+    project_dir: &str,
+) -> Result<()> {
+    let target_flag = match T::TARGET_TYPE {
+        TargetType::App => "--bin",
+        TargetType::Example => "--example",
+    };
+    let mut build_command = format!(
+        "cd {} && cargo build {target_flag} {} --message-format=json",
+        shell_quote(project_dir),
+        shell_quote(&target.name)
+    );
+    if let Some(features) = config.features().filter(|features| !features.is_empty()) {
+        let _ = write!(
+            build_command,
+            " --features {}",
+            shell_quote(&features.join(","))
+        );
+    }
+    for arg in BuildProfile::from_name(config.profile()).cargo_args() {
+        let _ = write!(build_command, " {}", shell_quote(arg));
+    }
+
+    let mut cmd = ssh(profile);
+    cmd.arg(&profile.ssh_destination).arg(build_command);
+    let output = run_killable(cmd, "ssh").await?;
+    if !output.status.success() {
+        return Err(build_failed_report(
+            &output,
+            config.target_name(),
+            T::TARGET_TYPE,
+            config.profile(),
+            &format!("{}:{project_dir}", profile.ssh_destination),
+        ));
+    }
+    Ok(())
+}
+
+/// Where cargo puts the target's binary in a remote project checkout
+fn remote_binary_path(project_dir: &str, build_profile: &str, target: &BevyTarget) -> String {
+    let profile_dir = format!(
+        "{}/target/{}",
+        project_dir.trim_end_matches('/'),
+        BuildProfile::from_name(build_profile).output_dir()
+    );
+    match target.target_type {
+        TargetType::App => format!("{profile_dir}/{}", target.name),
+        TargetType::Example => format!("{profile_dir}/examples/{}", target.name),
+    }
+}
+
+/// `ssh` command that starts one instance on the remote machine and forwards its BRP port
+pub fn instance_command(
+    launch: &RemoteLaunch,
+    port: Port,
+    instance: InstancePosition,
+    instance_override: Option<&InstanceOverride>,
+) -> Command {
+    let mut env: Vec<String> = brp_env_vars(port)
+        .into_iter()
+        .chain(instance.env_vars())
+        .map(|(key, value)| format!("{key}={value}"))
+        .collect();
+    env.push(format!("{BEVY_ASSET_ROOT_ENV_VAR}={}", launch.working_dir));
+    let mut args = Vec::new();
+    if let Some(instance_override) = instance_override {
+        env.extend(
+            instance_override
+                .env
+                .iter()
+                .map(|(key, value)| format!("{key}={value}")),
+        );
+        args.extend(instance_override.args.iter().cloned());
+    }
+
+    let command_line = env
+        .iter()
+        .chain(std::iter::once(&launch.binary))
+        .chain(&args)
+        .map(|word| shell_quote(word))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let remote_command = format!(
+        "cd {} && echo {REMOTE_PID_MARKER}$$ && exec env {command_line}",
+        shell_quote(&launch.working_dir)
+    );
+
+    let mut cmd = ssh(&launch.profile);
+    cmd.arg("-o")
+        .arg("ExitOnForwardFailure=yes")
+        .arg("-L")
+        .arg(format!("{port}:{BRP_DEFAULT_HOST}:{port}"))
+        .arg(&launch.profile.ssh_destination)
+        .arg(remote_command);
+    cmd
+}
+
+/// Wait for an instance to print its remote PID to `log_file`
+///
+/// `None` if it doesn't within the timeout - the SSH connection may have failed, which the log
+/// shows.
+pub async fn wait_for_remote_pid(log_file: &Path) -> Option<u32> {
+    let deadline = Instant::now() + REMOTE_PID_TIMEOUT;
+    loop {
+        let pid = tokio::fs::read_to_string(log_file)
+            .await
+            .ok()
+            .and_then(|log| parse_remote_pid(&log));
+        if pid.is_some() || Instant::now() >= deadline {
+            return pid;
+        }
+        tokio::time::sleep(REMOTE_PID_POLL_INTERVAL).await;
+    }
+}
+
+/// The remote PID in an instance's log, wherever the marker line is (it may be timestamped)
+fn parse_remote_pid(log: &str) -> Option<u32> {
+    log.lines()
+        .find_map(|line| line.split_once(REMOTE_PID_MARKER))
+        .and_then(|(_, pid)| pid.trim().parse().ok())
+}
+
+/// Kill process `pid` on the machine of the remote host profile `profile_name`
+pub fn kill_remote_process(profile_name: &str, pid: u32) -> Result<()> {
+    let profile = load_profile(profile_name)?;
+    run_ssh(&profile, &format!("kill {pid}"), "kill the remote process")
+}
+
+/// Run `remote_command` on the profile's machine, failing with its stderr
+fn run_ssh(profile: &RemoteHostProfile, remote_command: &str, action: &str) -> Result<()> {
+    let output = ssh(profile)
+        .arg(&profile.ssh_destination)
+        .arg(remote_command)
+        .output()
+        .map_err(|e| Error::process_failed("run", "ssh", e))?;
+    check_ssh(profile, &output, action)
+}
+
+/// Fail with the stderr of an `ssh` run that didn't succeed
+fn check_ssh(profile: &RemoteHostProfile, output: &Output, action: &str) -> Result<()> {
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(Error::failed_to(
+            &format!("{action} on {}", profile.ssh_destination),
+            String::from_utf8_lossy(&output.stderr).trim(),
+        )
+        .into())
+    }
+}
+
+/// Run `cmd` to completion, killing it if the launch is dropped - when its call times out
+async fn run_killable(cmd: Command, program: &str) -> Result<Output> {
+    tokio::process::Command::from(cmd)
+        .kill_on_drop(true)
+        .output()
+        .await
+        .map_err(|e| Error::process_failed("run", program, e).into())
+}
+
+/// `ssh` with the profile's options; never prompts, since nobody could answer
+fn ssh(profile: &RemoteHostProfile) -> Command {
+    let mut cmd = Command::new("ssh");
+    cmd.arg("-o")
+        .arg("BatchMode=yes")
+        .args(&profile.ssh_options);
+    cmd
+}
+
+/// Quote `word` for a POSIX shell
+fn shell_quote(word: &str) -> String { format!("'{}'", word.replace('\'', r"'\''")) }
+
+#[cfg(test)]
+#[allow(clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn profiles_are_keyed_by_name_with_local_build_by_default() {
+        let profiles = parse_profiles(
+            r#"{
+                "deck": {
+                    "ssh_destination": "deck@steamdeck.local",
+                    "remote_dir": "/home/deck/games",
+                    "target_triple": "x86_64-unknown-linux-gnu"
+                },
+                "pi": {
+                    "ssh_destination": "pi@raspberrypi",
+                    "ssh_options": ["-i", "/keys/pi"],
+                    "remote_dir": "/tmp",
+                    "build": "remote",
+                    "remote_project_dir": "/home/pi/game"
+                }
+            }"#,
+        )
+        .expect("valid profiles");
+
+        assert_eq!(profiles["deck"].name, "deck");
+        assert_eq!(profiles["deck"].build, RemoteBuild::Local);
+        assert_eq!(profiles["pi"].build, RemoteBuild::Remote);
+        assert_eq!(profiles["pi"].ssh_options, vec!["-i", "/keys/pi"]);
+        assert!(
+            parse_profiles(r#"{"x": {"ssh_destination": "a", "remote_dir": "/", "typo": 1}}"#)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn remote_pid_is_read_from_a_plain_or_timestamped_line() {
+        assert_eq!(
+            parse_remote_pid("header\nBRP_MCP_REMOTE_PID=4242\n"),
+            Some(4242)
+        );
+        assert_eq!(
+            parse_remote_pid("[2026-01-01 10:00:00.000] BRP_MCP_REMOTE_PID=17\r\nrest"),
+            Some(17)
+        );
+        assert_eq!(parse_remote_pid("ssh: connect to host refused"), None);
+    }
+
+    #[test]
+    fn words_are_quoted_for_the_remote_shell() {
+        assert_eq!(shell_quote("my game"), "'my game'");
+        assert_eq!(shell_quote("it's"), r"'it'\''s'");
+    }
+}
@@ -3,6 +3,10 @@ use std::collections::HashSet;
 use std::path::Path;
 use std::path::PathBuf;
 
+use chrono::DateTime;
+use chrono::Local;
+use serde::Deserialize;
+use serde::Serialize;
 use tracing::debug;
 
 use super::cargo_detector::BevyTarget;
@@ -10,6 +14,7 @@ use super::cargo_detector::CargoDetector;
 use super::cargo_detector::TargetType;
 use super::errors::NoTargetsFoundError;
 use super::errors::PathDisambiguationError;
+use super::errors::TargetNotInPackage;
 use crate::error::Error;
 
 /// Helper function to safely canonicalize a path
@@ -410,14 +415,64 @@ pub fn find_all_targets_by_name(
     targets
 }
 
-/// Find a required target by name with path parameter handling
-/// Returns an error with enhanced path error messages if duplicates found and no path specified
+/// A target offered for disambiguation, with what tells it apart from the others
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TargetCandidate {
+    /// Relative path to pass as `path`
+    pub path:          String,
+    /// Package name to pass as `package`
+    pub package:       String,
+    /// Version of `bevy` the package builds against
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bevy_version:  Option<String>,
+    /// When the package's `Cargo.toml` was last modified (RFC 3339)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_modified: Option<String>,
+}
+
+impl From<&BevyTarget> for TargetCandidate {
+    fn from(target: &BevyTarget) -> Self {
+        let last_modified = std::fs::metadata(&target.manifest_path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .map(|modified| DateTime::<Local>::from(modified).to_rfc3339());
+        Self {
+            path: target.relative_path.to_string_lossy().to_string(),
+            package: target.package_name.clone(),
+            bevy_version: target.bevy_version.clone(),
+            last_modified,
+        }
+    }
+}
+
+/// Error asking which of several targets with the same name to use
+pub fn disambiguation_error(targets: &[BevyTarget], target_name: &str, target_type: &str) -> Error {
+    let available_paths = targets
+        .iter()
+        .map(|target| target.relative_path.to_string_lossy().to_string())
+        .filter(|path| !path.is_empty())
+        .collect();
+    let path_disambiguation_error = PathDisambiguationError::new(
+        available_paths,
+        targets.iter().map(TargetCandidate::from).collect(),
+        target_name.to_string(),
+        target_type.to_string(),
+    );
+    Error::Structured {
+        result: Box::new(path_disambiguation_error),
+    }
+}
+
+/// Find a required target by name with path and package parameter handling
+/// Returns an error with enhanced path error messages if duplicates found and neither a path nor
+/// a package is specified
 ///
 /// If `cached_targets` is provided, uses those instead of scanning again (performance optimization)
 pub fn find_required_target_with_path(
     target_name: &str,
     target_type: TargetType,
     path: Option<&str>,
+    package: Option<&str>,
     search_paths: &[PathBuf],
     cached_targets: Option<Vec<BevyTarget>>,
 ) -> Result<BevyTarget, Error> {
@@ -431,12 +486,31 @@ pub fn find_required_target_with_path(
         debug!("With path filter: {p}");
     }
 
-    let all_targets = cached_targets.unwrap_or_else(|| {
+    let mut all_targets = cached_targets.unwrap_or_else(|| {
         debug!("No cached targets provided, scanning filesystem");
         find_all_targets_by_name(target_name, Some(target_type), search_paths)
     });
     debug!("Found {} matching {target_type_str}(s)", all_targets.len());
 
+    if let Some(package) = package {
+        debug!("With package filter: {package}");
+        let (in_package, others): (Vec<_>, Vec<_>) = all_targets
+            .into_iter()
+            .partition(|target| target.package_name == package);
+        if in_package.is_empty() && !others.is_empty() {
+            let target_not_in_package = TargetNotInPackage::new(
+                target_name.to_string(),
+                target_type_str.to_string(),
+                package.to_string(),
+                others.iter().map(TargetCandidate::from).collect(),
+            );
+            return Err(Error::Structured {
+                result: Box::new(target_not_in_package),
+            });
+        }
+        all_targets = in_package;
+    }
+
     // If a path is provided and we found multiple targets, check for ambiguity
     if let Some(path_str) = path
         && all_targets.len() > 1
@@ -448,61 +522,36 @@ pub fn find_required_target_with_path(
         // check if the path could have been ambiguous
         if filtered_targets.is_empty() {
             // Check if the path partially matches multiple targets
-            let partial_matches: Vec<_> = all_targets
+            let partial_matches: Vec<BevyTarget> = all_targets
                 .iter()
-                .filter(|target| {
-                    let relative_path = &target.relative_path;
-                    partial_path_match(relative_path, path_str)
-                })
+                .filter(|target| partial_path_match(&target.relative_path, path_str))
+                .cloned()
                 .collect();
 
             if partial_matches.len() > 1 {
                 // This is an ambiguous partial path
-                let paths: Vec<String> = partial_matches
-                    .iter()
-                    .map(|target| target.relative_path.to_string_lossy().to_string())
-                    .collect();
-
-                let path_disambiguation_error = PathDisambiguationError::new(
-                    paths,
-                    target_name.to_string(),
-                    target_type_str.to_string(),
-                );
-                return Err(Error::Structured {
-                    result: Box::new(path_disambiguation_error),
-                });
+                return Err(disambiguation_error(
+                    &partial_matches,
+                    target_name,
+                    target_type_str,
+                ));
             }
 
             // Enhanced error message for path not found
-            let available_paths: Vec<String> = all_targets
-                .iter()
-                .map(|target| target.relative_path.to_string_lossy().to_string())
-                .collect();
-
-            let path_disambiguation_error = PathDisambiguationError::new(
-                available_paths,
-                target_name.to_string(),
-                target_type_str.to_string(),
-            );
-            return Err(Error::Structured {
-                result: Box::new(path_disambiguation_error),
-            });
+            return Err(disambiguation_error(
+                &all_targets,
+                target_name,
+                target_type_str,
+            ));
         }
 
-        return validate_single_result_or_error(
-            filtered_targets,
-            target_name,
-            target_type_str,
-            |target| &target.relative_path,
-        );
+        return validate_single_result_or_error(filtered_targets, target_name, target_type_str);
     }
 
     let filtered_targets =
         find_and_filter_by_path(all_targets, path, |target| &target.relative_path);
 
-    validate_single_result_or_error(filtered_targets, target_name, target_type_str, |target| {
-        &target.relative_path
-    })
+    validate_single_result_or_error(filtered_targets, target_name, target_type_str)
 }
 
 /// Check if the relative path exactly matches the provided path string
@@ -563,59 +612,35 @@ fn find_and_filter_by_path<T>(
     }
 }
 
-/// Validate that exactly one item was found, or return helpful error
-fn validate_single_result_or_error<T>(
-    items: Vec<T>,
-    item_name: &str,
-    item_type: &str,
-    get_relative_path: impl Fn(&T) -> &PathBuf,
-) -> Result<T, Error> {
-    match items.len() {
+/// Validate that exactly one target was found, or return helpful error
+fn validate_single_result_or_error(
+    targets: Vec<BevyTarget>,
+    target_name: &str,
+    target_type: &str,
+) -> Result<BevyTarget, Error> {
+    match targets.len() {
         0 => {
             let no_targets_error =
-                NoTargetsFoundError::new(item_name.to_string(), item_type.to_string());
+                NoTargetsFoundError::new(target_name.to_string(), target_type.to_string());
             Err(Error::Structured {
                 result: Box::new(no_targets_error),
             })
         },
         1 => {
-            // We know exactly one item exists
-            let mut iter = items.into_iter();
+            // We know exactly one target exists
+            let mut iter = targets.into_iter();
             iter.next().map_or_else(
                 || {
                     let no_targets_error =
-                        NoTargetsFoundError::new(item_name.to_string(), item_type.to_string());
+                        NoTargetsFoundError::new(target_name.to_string(), target_type.to_string());
                     Err(Error::Structured {
                         result: Box::new(no_targets_error),
                     })
                 },
-                |item| Ok(item),
+                Ok,
             )
         },
-        _ => {
-            let all_paths: Vec<String> = items
-                .iter()
-                .map(|item| {
-                    let relative_path = get_relative_path(item);
-                    relative_path.to_string_lossy().to_string()
-                })
-                .collect();
-
-            let non_empty_paths: Vec<String> = all_paths
-                .iter()
-                .filter(|path| !path.is_empty())
-                .cloned()
-                .collect();
-
-            let path_disambiguation_error = PathDisambiguationError::new(
-                non_empty_paths,
-                item_name.to_string(),
-                item_type.to_string(),
-            );
-            Err(Error::Structured {
-                result: Box::new(path_disambiguation_error),
-            })
-        },
+        _ => Err(disambiguation_error(&targets, target_name, target_type)),
     }
 }
 
@@ -1136,4 +1161,55 @@ path = "src/main.rs"
             "Should find at least 1 standalone project"
         );
     }
+
+    fn target_in(package_name: &str, relative_path: &str) -> BevyTarget {
+        BevyTarget {
+            name:              "game".to_string(),
+            target_type:       TargetType::App,
+            package_name:      package_name.to_string(),
+            workspace_root:    PathBuf::from(relative_path),
+            manifest_path:     PathBuf::from(relative_path).join("Cargo.toml"),
+            relative_path:     PathBuf::from(relative_path),
+            dylib_feature:     None,
+            bevy_version:      Some("0.17.2".to_string()),
+            features:          Vec::new(),
+            default_features:  Vec::new(),
+            required_features: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_find_required_target_by_package() {
+        let targets = vec![
+            target_in("client", "repo1/game"),
+            target_in("server", "repo2/game"),
+        ];
+
+        let target = find_required_target_with_path(
+            "game",
+            TargetType::App,
+            None,
+            Some("server"),
+            &[],
+            Some(targets.clone()),
+        )
+        .expect("package should disambiguate");
+        assert_eq!(target.relative_path, PathBuf::from("repo2/game"));
+
+        assert!(
+            find_required_target_with_path(
+                "game",
+                TargetType::App,
+                None,
+                Some("editor"),
+                &[],
+                Some(targets.clone()),
+            )
+            .is_err()
+        );
+        assert!(
+            find_required_target_with_path("game", TargetType::App, None, None, &[], Some(targets))
+                .is_err()
+        );
+    }
 }
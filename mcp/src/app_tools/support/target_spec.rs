@@ -0,0 +1,116 @@
+//! Combined target specs naming a package and a target in one string
+//!
+//! In larger workspaces a bare target name is often ambiguous. A target spec carries the package
+//! along with the name, so `target_name` alone picks the target:
+//! - `my_pkg#demo` - target `demo` in package `my_pkg`
+//! - `my_pkg/examples/demo` - example `demo` in package `my_pkg`
+//! - `my_pkg/bin/demo` - app `demo` in package `my_pkg`
+//!
+//! Anything else is a bare target name.
+
+use super::cargo_detector::BevyTarget;
+use super::cargo_detector::TargetType;
+
+/// Separator between the package and the target name
+const PACKAGE_SEPARATOR: char = '#';
+
+/// Path segment naming example targets
+const EXAMPLES_SEGMENT: &str = "examples";
+
+/// Path segment naming binary targets
+const BIN_SEGMENT: &str = "bin";
+
+/// A target name with the package and target type a spec may carry
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TargetSpec {
+    /// Package containing the target
+    pub package:     Option<String>,
+    /// Name of the target
+    pub name:        String,
+    /// Target type, when the spec names one
+    pub target_type: Option<TargetType>,
+}
+
+impl TargetSpec {
+    /// Parse a bare target name or a combined target spec
+    pub fn parse(spec: &str) -> Self {
+        if let Some((package, name)) = spec.split_once(PACKAGE_SEPARATOR)
+            && !package.is_empty()
+            && !name.is_empty()
+        {
+            return Self::in_package(package, name, None);
+        }
+
+        let segments: Vec<&str> = spec.split('/').collect();
+        if let [package, kind, name] = segments.as_slice()
+            && !package.is_empty()
+            && !name.is_empty()
+        {
+            match *kind {
+                EXAMPLES_SEGMENT => {
+                    return Self::in_package(package, name, Some(TargetType::Example));
+                },
+                BIN_SEGMENT => return Self::in_package(package, name, Some(TargetType::App)),
+                _ => {},
+            }
+        }
+
+        Self {
+            package:     None,
+            name:        spec.to_string(),
+            target_type: None,
+        }
+    }
+
+    /// Spec that names `target` unambiguously, as listed by the list tools
+    pub fn format(target: &BevyTarget) -> String {
+        match target.target_type {
+            TargetType::App => format!("{}{PACKAGE_SEPARATOR}{}", target.package_name, target.name),
+            TargetType::Example => {
+                format!("{}/{EXAMPLES_SEGMENT}/{}", target.package_name, target.name)
+            },
+        }
+    }
+
+    fn in_package(package: &str, name: &str, target_type: Option<TargetType>) -> Self {
+        Self {
+            package: Some(package.to_string()),
+            name: name.to_string(),
+            target_type,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_package_and_name() {
+        let spec = TargetSpec::parse("my_pkg#demo");
+        assert_eq!(spec.package.as_deref(), Some("my_pkg"));
+        assert_eq!(spec.name, "demo");
+        assert_eq!(spec.target_type, None);
+    }
+
+    #[test]
+    fn test_parse_package_path() {
+        let example = TargetSpec::parse("my_pkg/examples/demo");
+        assert_eq!(example.package.as_deref(), Some("my_pkg"));
+        assert_eq!(example.name, "demo");
+        assert_eq!(example.target_type, Some(TargetType::Example));
+
+        let app = TargetSpec::parse("my_pkg/bin/server");
+        assert_eq!(app.name, "server");
+        assert_eq!(app.target_type, Some(TargetType::App));
+    }
+
+    #[test]
+    fn test_parse_bare_names() {
+        for spec in ["demo", "my-game", "#demo", "my_pkg#", "my_pkg/src/demo"] {
+            let parsed = TargetSpec::parse(spec);
+            assert_eq!(parsed.package, None);
+            assert_eq!(parsed.name, spec);
+        }
+    }
+}
@@ -0,0 +1,395 @@
+//! Health watchdog for launched instances
+//!
+//! An instance that hangs - a deadlock, an infinite loop in a system - keeps its process alive,
+//! so nothing notices until the next tool call times out. The watchdog pings every instance in
+//! the registry with `rpc.discover` on an interval. After `FAILURES_BEFORE_UNRESPONSIVE` missed
+//! pings in a row it flags the instance's `unresponsive_since` in the registry and sends the
+//! client an MCP logging notification carrying the tail of the instance's log; a notification
+//! follows when it answers again. Instances are pinged concurrently, so one hung instance doesn't
+//! delay the checks of the others, and a process stopped by a debugger or a signal isn't counted
+//! as missing its pings. Notifications below the level the client sets with `logging/setLevel`
+//! are not sent.
+//!
+//! `BRP_MCP_WATCHDOG_SECS` sets the interval in seconds - `0` turns the watchdog off.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+use std::io::Seek;
+use std::io::SeekFrom;
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::LazyLock;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use futures::future::join_all;
+use rmcp::Peer;
+use rmcp::RoleServer;
+use rmcp::model::LoggingLevel;
+use rmcp::model::LoggingMessageNotificationParam;
+use serde_json::Value;
+use serde_json::json;
+use sysinfo::ProcessStatus;
+use sysinfo::ProcessesToUpdate;
+use sysinfo::System;
+use tracing::debug;
+use tracing::warn;
+
+use super::instance_registry;
+use super::instance_registry::RegisteredInstance;
+use crate::brp_tools::BrpClient;
+use crate::brp_tools::Port;
+use crate::tool::BrpMethod;
+
+/// Environment variable setting the ping interval in seconds - `0` turns the watchdog off
+pub const WATCHDOG_SECS_ENV_VAR: &str = "BRP_MCP_WATCHDOG_SECS";
+
+/// Ping interval without `BRP_MCP_WATCHDOG_SECS`
+const DEFAULT_INTERVAL: Duration = Duration::from_secs(15);
+
+/// How long an instance gets to answer a ping
+const PING_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Missed pings in a row before an instance is reported unresponsive
+const FAILURES_BEFORE_UNRESPONSIVE: u32 = 2;
+
+/// Log lines included in an unresponsive notification
+const LOG_TAIL_LINES: usize = 20;
+
+/// Bytes read from the end of a log to find its last lines
+const LOG_TAIL_BYTES: u64 = 16 * 1024;
+
+/// Logger name the notifications are sent under
+const NOTIFICATION_LOGGER: &str = "brp_watchdog";
+
+/// Ping interval - read once at first use, `None` when the watchdog is off
+static INTERVAL: LazyLock<Option<Duration>> = LazyLock::new(|| {
+    let Ok(secs) = std::env::var(WATCHDOG_SECS_ENV_VAR) else {
+        return Some(DEFAULT_INTERVAL);
+    };
+    match secs.trim().parse::<u64>() {
+        Ok(0) => None,
+        Ok(secs) => Some(Duration::from_secs(secs)),
+        Err(_) => {
+            debug!("Invalid {WATCHDOG_SECS_ENV_VAR} value '{secs}' - using the default");
+            Some(DEFAULT_INTERVAL)
+        },
+    }
+});
+
+/// Least severe level of notification the client wants, set with `logging/setLevel` - shared
+/// by the server, which sets it, and the watchdog, which reads it
+#[derive(Clone)]
+pub struct NotificationLevel(Arc<Mutex<LoggingLevel>>);
+
+impl Default for NotificationLevel {
+    /// Every notification is sent until the client sets a level
+    fn default() -> Self { Self(Arc::new(Mutex::new(LoggingLevel::Debug))) }
+}
+
+impl NotificationLevel {
+    /// Send only notifications at `level` or above from now on
+    pub fn set(&self, level: LoggingLevel) {
+        if let Ok(mut current) = self.0.lock() {
+            *current = level;
+        }
+    }
+
+    /// Whether a notification at `level` should be sent
+    fn allows(&self, level: LoggingLevel) -> bool {
+        self.0
+            .lock()
+            .map_or(true, |current| severity(level) >= severity(*current))
+    }
+}
+
+/// Rank of a logging level, least severe first - `LoggingLevel` has no ordering of its own
+const fn severity(level: LoggingLevel) -> u8 {
+    match level {
+        LoggingLevel::Debug => 0,
+        LoggingLevel::Info => 1,
+        LoggingLevel::Notice => 2,
+        LoggingLevel::Warning => 3,
+        LoggingLevel::Error => 4,
+        LoggingLevel::Critical => 5,
+        LoggingLevel::Alert => 6,
+        LoggingLevel::Emergency => 7,
+    }
+}
+
+/// How an instance fared in one round of pings
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Health {
+    /// It answered the ping
+    Answers,
+    /// It didn't answer, but its process is stopped - by a debugger at a breakpoint, or a signal
+    Paused,
+    /// It didn't answer while running
+    Silent,
+}
+
+/// A change in an instance's reported state
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Transition {
+    /// It just missed `FAILURES_BEFORE_UNRESPONSIVE` pings in a row
+    Unresponsive,
+    /// It answered after being reported unresponsive
+    Recovered,
+}
+
+/// Start pinging launched instances, notifying `peer` when one stops or resumes answering, at
+/// the levels `level` lets through
+pub fn start_watchdog(peer: Peer<RoleServer>, level: NotificationLevel) {
+    let Some(interval) = *INTERVAL else {
+        debug!("Instance watchdog is off");
+        return;
+    };
+    tokio::spawn(async move {
+        // Missed pings in a row, by port and the PID the port belonged to
+        let mut failures: HashMap<(u16, u32), u32> = HashMap::new();
+        loop {
+            tokio::time::sleep(interval).await;
+            let instances = instance_registry::live_instances();
+            failures.retain(|(port, pid), _| {
+                instances.iter().any(|i| i.port == *port && i.pid == *pid)
+            });
+            let checks = join_all(instances.into_iter().map(|instance| async move {
+                let health = check(&instance).await;
+                (instance, health)
+            }))
+            .await;
+            for (instance, health) in checks {
+                let key = (instance.port, instance.pid);
+                match record(&mut failures, key, health) {
+                    Some(Transition::Unresponsive) => {
+                        let since = chrono::Local::now().to_rfc3339();
+                        instance_registry::set_unresponsive(instance.port, Some(since.clone()));
+                        let data = unresponsive(&instance, &since);
+                        notify(&peer, &level, LoggingLevel::Warning, data).await;
+                    },
+                    Some(Transition::Recovered) => {
+                        instance_registry::set_unresponsive(instance.port, None);
+                        notify(&peer, &level, LoggingLevel::Info, recovered(&instance)).await;
+                    },
+                    None => {},
+                }
+            }
+        }
+    });
+}
+
+/// Count a ping round for the instance `key` in `failures`, returning the change to report
+fn record(
+    failures: &mut HashMap<(u16, u32), u32>,
+    key: (u16, u32),
+    health: Health,
+) -> Option<Transition> {
+    match health {
+        Health::Answers => (failures.remove(&key).unwrap_or(0) >= FAILURES_BEFORE_UNRESPONSIVE)
+            .then_some(Transition::Recovered),
+        // A stopped process can't answer, but isn't hung - its count waits until it runs again
+        Health::Paused => None,
+        Health::Silent => {
+            let missed = failures.entry(key).or_insert(0);
+            *missed += 1;
+            (*missed == FAILURES_BEFORE_UNRESPONSIVE).then_some(Transition::Unresponsive)
+        },
+    }
+}
+
+/// Ping an instance, telling a stopped process apart from a hung one when it doesn't answer
+async fn check(instance: &RegisteredInstance) -> Health {
+    if answers(Port(instance.port)).await {
+        return Health::Answers;
+    }
+    let pid = instance.pid;
+    let paused = tokio::task::spawn_blocking(move || is_stopped(pid))
+        .await
+        .unwrap_or(false);
+    if paused {
+        Health::Paused
+    } else {
+        Health::Silent
+    }
+}
+
+/// Whether the instance on `port` answers `rpc.discover` in time
+async fn answers(port: Port) -> bool {
+    let client = BrpClient::new(BrpMethod::RpcDiscover, port, None);
+    matches!(
+        tokio::time::timeout(PING_TIMEOUT, client.execute_untouched()).await,
+        Ok(Ok(response)) if response.get("result").is_some()
+    )
+}
+
+/// Whether process `pid` is stopped - by a debugger at a breakpoint, or by a signal
+fn is_stopped(pid: u32) -> bool {
+    let pid = sysinfo::Pid::from_u32(pid);
+    let mut system = System::new();
+    system.refresh_processes(ProcessesToUpdate::Some(&[pid]), true);
+    system.process(pid).is_some_and(|process| {
+        matches!(
+            process.status(),
+            ProcessStatus::Stop | ProcessStatus::Tracing
+        )
+    })
+}
+
+/// Notification data for an instance that stopped answering
+fn unresponsive(instance: &RegisteredInstance, since: &str) -> Value {
+    let mut log_tail = tail(Path::new(&instance.log_file));
+    if let Some(stderr_log_file) = &instance.stderr_log_file {
+        log_tail.extend(tail(Path::new(stderr_log_file)));
+    }
+    json!({
+        "event": "instance_unresponsive",
+        "message": format!(
+            "{} on port {} (PID {}) stopped answering BRP",
+            instance.target_name, instance.port, instance.pid
+        ),
+        "target_name": instance.target_name,
+        "port": instance.port,
+        "pid": instance.pid,
+        "unresponsive_since": since,
+        "log_file": instance.log_file,
+        "log_tail": log_tail,
+    })
+}
+
+/// Notification data for an instance that answers again
+fn recovered(instance: &RegisteredInstance) -> Value {
+    json!({
+        "event": "instance_recovered",
+        "message": format!(
+            "{} on port {} (PID {}) answers BRP again",
+            instance.target_name, instance.port, instance.pid
+        ),
+        "target_name": instance.target_name,
+        "port": instance.port,
+        "pid": instance.pid,
+    })
+}
+
+/// Send a logging notification unless `threshold` filters it out - failures are only logged,
+/// the client may not listen
+async fn notify(
+    peer: &Peer<RoleServer>,
+    threshold: &NotificationLevel,
+    level: LoggingLevel,
+    data: Value,
+) {
+    warn!("Instance watchdog: {data}");
+    if !threshold.allows(level) {
+        return;
+    }
+    let notification = LoggingMessageNotificationParam {
+        level,
+        logger: Some(NOTIFICATION_LOGGER.to_string()),
+        data,
+    };
+    if let Err(e) = peer.notify_logging_message(notification).await {
+        debug!("Failed to send watchdog notification: {e}");
+    }
+}
+
+/// The last `LOG_TAIL_LINES` lines of the log at `path`
+fn tail(path: &Path) -> Vec<String> {
+    let Ok(mut file) = File::open(path) else {
+        return Vec::new();
+    };
+    let length = file.metadata().map_or(0, |metadata| metadata.len());
+    let start = length.saturating_sub(LOG_TAIL_BYTES);
+    let mut bytes = Vec::new();
+    if file.seek(SeekFrom::Start(start)).is_err() || file.read_to_end(&mut bytes).is_err() {
+        return Vec::new();
+    }
+    last_lines(&String::from_utf8_lossy(&bytes), start > 0)
+}
+
+/// The last `LOG_TAIL_LINES` lines of `text`, without the first when it may be cut off
+fn last_lines(text: &str, cut_off: bool) -> Vec<String> {
+    let lines: Vec<&str> = text.lines().skip(usize::from(cut_off)).collect();
+    lines[lines.len().saturating_sub(LOG_TAIL_LINES)..]
+        .iter()
+        .map(|line| (*line).to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fmt::Write as _;
+
+    use super::*;
+
+    #[test]
+    fn tail_drops_a_cut_off_first_line() {
+        let text = (1..=30).fold(String::new(), |mut text, n| {
+            let _ = writeln!(text, "line {n}");
+            text
+        });
+        let lines = last_lines(&text, false);
+        assert_eq!(lines.len(), LOG_TAIL_LINES);
+        assert_eq!(lines.first().map(String::as_str), Some("line 11"));
+
+        assert_eq!(last_lines("ine 1\nline 2\n", true), vec!["line 2"]);
+    }
+
+    #[test]
+    fn reports_each_change_once() {
+        let mut failures = HashMap::new();
+        let key = (15702, 42);
+        assert_eq!(record(&mut failures, key, Health::Silent), None);
+        assert_eq!(
+            record(&mut failures, key, Health::Silent),
+            Some(Transition::Unresponsive)
+        );
+        assert_eq!(record(&mut failures, key, Health::Silent), None);
+        assert_eq!(
+            record(&mut failures, key, Health::Answers),
+            Some(Transition::Recovered)
+        );
+        assert_eq!(record(&mut failures, key, Health::Answers), None);
+
+        // One missed ping followed by an answer isn't worth reporting
+        assert_eq!(record(&mut failures, key, Health::Silent), None);
+        assert_eq!(record(&mut failures, key, Health::Answers), None);
+        assert!(failures.is_empty());
+    }
+
+    #[test]
+    fn a_stopped_process_is_not_counted_as_hung() {
+        let mut failures = HashMap::new();
+        let key = (15702, 42);
+        assert_eq!(record(&mut failures, key, Health::Silent), None);
+        for _ in 0..5 {
+            assert_eq!(record(&mut failures, key, Health::Paused), None);
+        }
+        assert_eq!(failures.get(&key), Some(&1));
+        assert_eq!(
+            record(&mut failures, key, Health::Silent),
+            Some(Transition::Unresponsive)
+        );
+    }
+
+    #[test]
+    fn the_client_level_filters_notifications() {
+        let level = NotificationLevel::default();
+        assert!(level.allows(LoggingLevel::Info));
+
+        level.set(LoggingLevel::Warning);
+        assert!(!level.allows(LoggingLevel::Info));
+        assert!(level.allows(LoggingLevel::Warning));
+        assert!(level.allows(LoggingLevel::Error));
+
+        // Clones share the level, so the server's setting reaches the watchdog
+        let shared = level.clone();
+        shared.set(LoggingLevel::Error);
+        assert!(!level.allows(LoggingLevel::Warning));
+    }
+
+    #[test]
+    fn this_process_is_not_stopped() {
+        assert!(!is_stopped(std::process::id()));
+    }
+}
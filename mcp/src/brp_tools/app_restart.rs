@@ -0,0 +1,215 @@
+//! Detect app restarts and forget what was learned about the previous run
+//!
+//! Entity IDs, aliases, change checkpoints, the short-name index, type guides and negotiated
+//! transports all describe one run of the app. When the app on a port restarts - a hot-reload
+//! rebuild, a crash and a relaunch outside the MCP - they silently give wrong answers. Before a
+//! tool call's BRP request the app is identified by the process listening on the port (its PID and
+//! start time) or, for apps on a remote host, by a fingerprint of its `rpc.discover` document. When
+//! the identity changes, everything cached for the port is dropped, watches on it are marked stale,
+//! and the tool response carries an `app_restarted` notice.
+
+use std::collections::HashMap;
+use std::hash::DefaultHasher;
+use std::hash::Hash;
+use std::hash::Hasher;
+use std::sync::LazyLock;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::Instant;
+
+use sysinfo::ProcessesToUpdate;
+use sysinfo::System;
+use tracing::info;
+
+use super::BrpClient;
+use super::Port;
+use super::ResponseStatus;
+use super::brp_client::AppRestart;
+use super::brp_client::clear_short_name_index;
+use super::brp_client::forget_cached_reads;
+use super::brp_client::forget_method_naming;
+use super::brp_client::forget_wire_encoding;
+use super::brp_client::record_app_restart;
+use super::brp_client::remote_host_for;
+use super::brp_type_guide::clear_type_guide_memory;
+use super::change_checkpoints::forget_port_checkpoints;
+use super::entity_alias::forget_port_aliases;
+use super::watch_tools::forget_multiwatch;
+use super::watch_tools::mark_watches_stale;
+use crate::app_tools::get_pid_for_port;
+use crate::tool::BrpMethod;
+
+/// How long an identity is trusted before the app is identified again
+const CHECK_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Which run of an app answers on a port
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AppIdentity {
+    /// Local process listening on the port - the start time tells a reused PID apart
+    Process { pid: u32, start_time: u64 },
+    /// Hash of the app's `rpc.discover` document, when the process can't be seen
+    Discover(u64),
+}
+
+/// Identities last seen on a port - one baseline per kind, since a process identity and a
+/// discover fingerprint can't be compared
+#[derive(Debug, Default)]
+struct SeenIdentities {
+    checked:  Option<Instant>,
+    process:  Option<AppIdentity>,
+    discover: Option<AppIdentity>,
+}
+
+impl SeenIdentities {
+    /// Record `identity`, returning whether it shows the app restarted - a different identity
+    /// of the same kind
+    fn record(&mut self, identity: AppIdentity, now: Instant) -> bool {
+        self.checked = Some(now);
+        let (baseline, other) = match identity {
+            AppIdentity::Process { .. } => (&mut self.process, &mut self.discover),
+            AppIdentity::Discover(_) => (&mut self.discover, &mut self.process),
+        };
+        let restarted = baseline
+            .replace(identity)
+            .is_some_and(|previous| previous != identity);
+        if restarted {
+            // The other kind's baseline describes the previous run
+            *other = None;
+        }
+        restarted
+    }
+}
+
+/// Identities seen per port and when they were last checked
+static IDENTITIES: LazyLock<Mutex<HashMap<Port, SeenIdentities>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Identify the app on `port` and, if it restarted since the last call, forget the previous run
+pub async fn detect_restart(port: Port) {
+    let checked_recently = IDENTITIES.lock().ok().is_some_and(|identities| {
+        identities
+            .get(&port)
+            .and_then(|seen| seen.checked)
+            .is_some_and(|checked| checked.elapsed() < CHECK_INTERVAL)
+    });
+    if checked_recently {
+        return;
+    }
+    let Some(identity) = identify(port).await else {
+        return;
+    };
+
+    let restarted = IDENTITIES.lock().ok().is_some_and(|mut identities| {
+        identities
+            .entry(port)
+            .or_default()
+            .record(identity, Instant::now())
+    });
+    if restarted {
+        info!("App on port {port} restarted - forgetting its previous run");
+        let restart = forget_previous_run(port).await;
+        record_app_restart(restart);
+    }
+}
+
+/// Drop everything cached about the previous run of the app on `port`
+async fn forget_previous_run(port: Port) -> AppRestart {
+    clear_short_name_index(Some(port));
+    forget_cached_reads(port);
+    forget_method_naming(port);
+    forget_wire_encoding(port);
+    forget_port_checkpoints(port);
+    clear_type_guide_memory(Some(port));
+    forget_multiwatch(port).await;
+    AppRestart {
+        port,
+        aliases_dropped: forget_port_aliases(port),
+        stale_watches: mark_watches_stale(port).await,
+    }
+}
+
+async fn identify(port: Port) -> Option<AppIdentity> {
+    if remote_host_for(port).is_none() {
+        // Reading sockets and processes blocks
+        let process = tokio::task::spawn_blocking(move || identify_process(port))
+            .await
+            .ok()
+            .flatten();
+        if process.is_some() {
+            return process;
+        }
+    }
+
+    let client = BrpClient::new(BrpMethod::RpcDiscover, port, None);
+    match client.execute_direct_internal_no_enhancement().await {
+        Ok(ResponseStatus::Success(Some(discover))) => {
+            let mut hasher = DefaultHasher::new();
+            discover.to_string().hash(&mut hasher);
+            Some(AppIdentity::Discover(hasher.finish()))
+        },
+        _ => None,
+    }
+}
+
+/// Identity of the local process listening on `port`
+fn identify_process(port: Port) -> Option<AppIdentity> {
+    let pid = get_pid_for_port(port)?;
+    let mut system = System::new();
+    system.refresh_processes(
+        ProcessesToUpdate::Some(&[sysinfo::Pid::from_u32(pid)]),
+        true,
+    );
+    let process = system.process(sysinfo::Pid::from_u32(pid))?;
+    Some(AppIdentity::Process {
+        pid,
+        start_time: process.start_time(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROCESS: AppIdentity = AppIdentity::Process {
+        pid:        42,
+        start_time: 1_000,
+    };
+
+    #[test]
+    fn a_new_process_is_a_restart() {
+        let mut seen = SeenIdentities::default();
+        let now = Instant::now();
+
+        assert!(!seen.record(PROCESS, now));
+        assert!(!seen.record(PROCESS, now));
+        let relaunched = AppIdentity::Process {
+            pid:        42,
+            start_time: 2_000,
+        };
+        assert!(seen.record(relaunched, now));
+        assert_eq!(seen.checked, Some(now));
+    }
+
+    #[test]
+    fn switching_kinds_keeps_each_baseline() {
+        let mut seen = SeenIdentities::default();
+        let now = Instant::now();
+
+        assert!(!seen.record(PROCESS, now));
+        // The process couldn't be seen for one check
+        assert!(!seen.record(AppIdentity::Discover(7), now));
+        assert!(!seen.record(PROCESS, now));
+        assert!(!seen.record(AppIdentity::Discover(7), now));
+
+        assert!(seen.record(AppIdentity::Discover(8), now));
+        // The process baseline belonged to the previous run
+        assert_eq!(seen.process, None);
+        assert!(!seen.record(
+            AppIdentity::Process {
+                pid:        43,
+                start_time: 3_000,
+            },
+            now
+        ));
+    }
+}
@@ -0,0 +1,181 @@
+//! `auto_name` for `world.spawn_entity`
+//!
+//! Test entities spawned by an agent are only known by their entity IDs, which are hard to talk
+//! about and change from run to run. With `auto_name`, the spawn request gets a generated
+//! `bevy_ecs::name::Name` component and the spawn result reports the name next to the entity ID:
+//!
+//! - `{"style": "counter", "prefix": "enemy"}` - `enemy_1`, `enemy_2`, ... counted per port and
+//!   prefix for the lifetime of the MCP server
+//! - `{"style": "words", "seed": 7}` - `amber-falcon-1`, `quiet-otter-2`, ... picked from word
+//!   lists by a generator seeded with `seed`, so the same seed names entities the same way in every
+//!   run
+
+use std::collections::HashMap;
+use std::sync::LazyLock;
+use std::sync::Mutex;
+
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::brp_tools::Port;
+use crate::error::Error;
+use crate::error::Result;
+use crate::tool::BrpMethod;
+use crate::tool::ParameterName;
+
+/// Full type path of Bevy's `Name` component
+const NAME_COMPONENT: &str = "bevy_ecs::name::Name";
+
+/// Prefix of counter names when the request doesn't give one
+const DEFAULT_PREFIX: &str = "entity";
+
+/// Field of the spawn result carrying the generated name
+pub const NAME_RESULT_FIELD: &str = "name";
+
+/// First word of `words` names
+const ADJECTIVES: &[&str] = &[
+    "amber", "brave", "calm", "dusty", "eager", "fuzzy", "gentle", "hollow", "icy", "jolly",
+    "keen", "lively", "mossy", "nimble", "quiet", "rusty",
+];
+
+/// Second word of `words` names
+const NOUNS: &[&str] = &[
+    "badger", "comet", "falcon", "fern", "glacier", "harbor", "lantern", "maple", "otter",
+    "pebble", "raven", "river", "thistle", "tiger", "willow", "zephyr",
+];
+
+/// How `auto_name` names a spawned entity
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "style", rename_all = "snake_case")]
+pub enum AutoName {
+    /// `{prefix}_{n}`, counting up per port and prefix
+    Counter {
+        /// Name prefix (default: "entity")
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        prefix: Option<String>,
+    },
+    /// `{adjective}-{noun}-{n}`, with the words picked by a generator seeded with `seed`
+    Words {
+        /// Generator seed - the same seed gives the same names in every run (default: 0)
+        #[serde(default)]
+        seed: u64,
+    },
+}
+
+/// Names generated so far, per port and prefix or seed
+static COUNTERS: LazyLock<Mutex<HashMap<(Port, String), u64>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Replace `auto_name` in a spawn request with a generated `Name` component
+///
+/// Returns the request to send and the generated name, or `None` when the request doesn't ask
+/// for a name and can be sent unchanged.
+pub fn apply_auto_name(
+    method: BrpMethod,
+    port: Port,
+    params: Option<&Value>,
+) -> Result<Option<(Value, String)>> {
+    if method != BrpMethod::WorldSpawnEntity {
+        return Ok(None);
+    }
+    let Some(mut params) = params.and_then(Value::as_object).cloned() else {
+        return Ok(None);
+    };
+    let Some(auto_name) = params.remove(ParameterName::AutoName.as_ref()) else {
+        return Ok(None);
+    };
+    let auto_name: AutoName = serde_json::from_value(auto_name)
+        .map_err(|e| Error::invalid(ParameterName::AutoName.as_ref(), e.to_string()))?;
+
+    let Some(Value::Object(components)) = params.get_mut(ParameterName::Components.as_ref()) else {
+        return Ok(None);
+    };
+    if components.contains_key(NAME_COMPONENT) {
+        return Err(Error::invalid(
+            ParameterName::AutoName.as_ref(),
+            format!("the spawn already sets `{NAME_COMPONENT}` - drop it or `auto_name`"),
+        )
+        .into());
+    }
+    let name = next_name(port, &auto_name);
+    components.insert(NAME_COMPONENT.to_string(), Value::String(name.clone()));
+    Ok(Some((Value::Object(params), name)))
+}
+
+/// Generate the next name for `auto_name` on `port`
+fn next_name(port: Port, auto_name: &AutoName) -> String {
+    let key = match auto_name {
+        AutoName::Counter { prefix } => prefix.as_deref().unwrap_or(DEFAULT_PREFIX).to_string(),
+        AutoName::Words { seed } => format!("#words:{seed}"),
+    };
+    let mut counters = COUNTERS
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    let counter = counters.entry((port, key.clone())).or_default();
+    *counter += 1;
+    let index = *counter;
+    drop(counters);
+    match auto_name {
+        AutoName::Counter { .. } => format!("{key}_{index}"),
+        AutoName::Words { seed } => words_name(*seed, index),
+    }
+}
+
+/// The `index`th name of the word sequence for `seed`
+#[allow(clippy::cast_possible_truncation)]
+fn words_name(seed: u64, index: u64) -> String {
+    let hash = split_mix(seed ^ split_mix(index));
+    let adjective = ADJECTIVES[(hash % ADJECTIVES.len() as u64) as usize];
+    let noun = NOUNS[((hash >> 32) % NOUNS.len() as u64) as usize];
+    format!("{adjective}-{noun}-{index}")
+}
+
+/// `SplitMix64` step - a small, stable mixing function, so seeded names never change between
+/// releases the way a library generator's output might
+pub const fn split_mix(value: u64) -> u64 {
+    let mut z = value.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn counter_names_count_up_per_prefix() {
+        let port = Port(45_001);
+        let params = json!({
+            "components": {"my_game::Health": 3},
+            "auto_name": {"style": "counter", "prefix": "enemy"}
+        });
+        let first = apply_auto_name(BrpMethod::WorldSpawnEntity, port, Some(&params));
+        let second = apply_auto_name(BrpMethod::WorldSpawnEntity, port, Some(&params));
+        assert_eq!(
+            first.ok().flatten(),
+            Some((
+                json!({"components": {"my_game::Health": 3, "bevy_ecs::name::Name": "enemy_1"}}),
+                "enemy_1".to_string()
+            ))
+        );
+        assert_eq!(
+            second.ok().flatten().map(|(_, name)| name).as_deref(),
+            Some("enemy_2")
+        );
+    }
+
+    #[test]
+    fn word_names_depend_only_on_seed_and_index() {
+        assert_eq!(words_name(7, 1), words_name(7, 1));
+        let name = words_name(7, 2);
+        let parts: Vec<&str> = name.split('-').collect();
+        assert!(ADJECTIVES.contains(&parts[0]));
+        assert!(NOUNS.contains(&parts[1]));
+        assert_eq!(parts[2], "2");
+    }
+}
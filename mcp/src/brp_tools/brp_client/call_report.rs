@@ -0,0 +1,66 @@
+//! What the BRP client did for one tool call, reported in the response metadata
+//!
+//! Transport details a tool's result struct knows nothing about - the sizes of compressed
+//! responses, answers served from the read cache, retries of transient errors, app restarts - are
+//! recorded here while the call runs and added to the metadata when its response is formatted.
+
+use std::cell::Cell;
+use std::future::Future;
+use std::time::Duration;
+
+use super::compression::TransferSizes;
+use crate::brp_tools::Port;
+
+/// Transport details of one tool call
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CallReport {
+    /// Sizes of the compressed responses received
+    pub transfer_sizes:    Option<TransferSizes>,
+    /// Age of the cached response the call was answered with, if it was
+    pub cache_age:         Option<Duration>,
+    /// Requests sent again after a transient error
+    pub transient_retries: u32,
+    /// Restart of the app noticed before the call's request was sent
+    pub app_restart:       Option<AppRestart>,
+}
+
+/// An app restart and what was dropped because of it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AppRestart {
+    /// Port of the restarted app
+    pub port:            Port,
+    /// Entity aliases dropped - their entity IDs belonged to the previous run
+    pub aliases_dropped: usize,
+    /// Watches on the port marked stale
+    pub stale_watches:   usize,
+}
+
+tokio::task_local! {
+    /// Report of the tool call running on this task
+    static CALL_REPORT: Cell<CallReport>;
+}
+
+/// Run a tool call, returning its output and what the BRP client did for it
+pub async fn with_call_report<F: Future>(future: F) -> (F::Output, CallReport) {
+    CALL_REPORT
+        .scope(Cell::new(CallReport::default()), async move {
+            let output = future.await;
+            (output, CALL_REPORT.with(Cell::get))
+        })
+        .await
+}
+
+/// Update the report of the running tool call
+pub fn record(update: impl FnOnce(&mut CallReport)) {
+    // Outside a tool call - e.g. a background watch - there is nothing to report to
+    let _ = CALL_REPORT.try_with(|report| {
+        let mut current = report.get();
+        update(&mut current);
+        report.set(current);
+    });
+}
+
+/// Report that the app restarted to the running tool call
+pub fn record_app_restart(restart: AppRestart) {
+    record(|report| report.app_restart = Some(restart));
+}
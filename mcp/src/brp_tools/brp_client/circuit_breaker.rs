@@ -0,0 +1,223 @@
+//! Per-port circuit breaker for apps that stopped answering
+//!
+//! While an app is down every request waits for a refused connection or, when the app hangs or
+//! its tunnel dropped, for the full request timeout - and agents keep retrying. After
+//! `FAILURE_THRESHOLD` consecutive connection failures the circuit for the port trips: requests
+//! fail at once with an "app appears down" error. Once `PROBE_INTERVAL` has passed, one request
+//! is let through to probe the app; a success closes the circuit and a failure keeps it open.
+//! `brp_reset_circuit` closes it by hand, and launching or checking an app on the port closes it
+//! too.
+
+use std::collections::HashMap;
+use std::sync::LazyLock;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::Instant;
+
+use chrono::DateTime;
+use chrono::Local;
+use serde_json::json;
+use tracing::warn;
+
+use crate::brp_tools::Port;
+use crate::error::Error;
+use crate::error::Result;
+use crate::tool::ToolName;
+
+/// Consecutive connection failures that trip the circuit
+pub const FAILURE_THRESHOLD: u32 = 3;
+
+/// How long an open circuit fails fast before letting a probe request through
+pub const PROBE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Connection health of one port
+#[derive(Debug, Clone, Copy, Default)]
+struct Circuit {
+    consecutive_failures: u32,
+    tripped:              Option<Trip>,
+}
+
+/// When an open circuit tripped and when it next lets a request through
+#[derive(Debug, Clone, Copy)]
+struct Trip {
+    tripped_at:  DateTime<Local>,
+    probe_after: Instant,
+}
+
+/// Circuits of ports that failed since their last success
+static CIRCUITS: LazyLock<Mutex<HashMap<Port, Circuit>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Fail fast when the circuit for `port` is open
+pub fn check_circuit(port: Port) -> Result<()> {
+    let Ok(mut circuits) = CIRCUITS.lock() else {
+        return Ok(());
+    };
+    let Some(circuit) = circuits.get_mut(&port) else {
+        return Ok(());
+    };
+    let Some(trip) = circuit.tripped.as_mut() else {
+        return Ok(());
+    };
+
+    let now = Instant::now();
+    if now >= trip.probe_after {
+        // Let this request probe the app; others keep failing fast until it answers
+        trip.probe_after = now + PROBE_INTERVAL;
+        return Ok(());
+    }
+
+    let retry_after = trip.probe_after.saturating_duration_since(now);
+    Err(Error::tool_call_failed_with_details(
+        format!(
+            "App on port {port} appears down (tripped at {})",
+            trip.tripped_at.format("%H:%M:%S")
+        ),
+        json!({
+            "port": port.0,
+            "circuit": "open",
+            "tripped_at": trip.tripped_at.to_rfc3339(),
+            "consecutive_failures": circuit.consecutive_failures,
+            "next_probe_in_secs": retry_after.as_secs(),
+            "hint": format!(
+                "Relaunch or check the app, or call `{}` once it is running again",
+                ToolName::BrpResetCircuit
+            ),
+        }),
+    )
+    .into())
+}
+
+/// Record that the app on `port` answered
+pub fn record_success(port: Port) {
+    if let Ok(mut circuits) = CIRCUITS.lock() {
+        circuits.remove(&port);
+    }
+}
+
+/// Record a failed connection to `port`, tripping its circuit after `FAILURE_THRESHOLD` in a row
+pub fn record_failure(port: Port) {
+    let Ok(mut circuits) = CIRCUITS.lock() else {
+        return;
+    };
+    let circuit = circuits.entry(port).or_default();
+    circuit.consecutive_failures += 1;
+    if circuit.consecutive_failures < FAILURE_THRESHOLD {
+        return;
+    }
+
+    let probe_after = Instant::now() + PROBE_INTERVAL;
+    if let Some(trip) = circuit.tripped.as_mut() {
+        trip.probe_after = probe_after;
+    } else {
+        warn!(
+            "Circuit for port {port} tripped after {} connection failures",
+            circuit.consecutive_failures
+        );
+        circuit.tripped = Some(Trip {
+            tripped_at: Local::now(),
+            probe_after,
+        });
+    }
+}
+
+/// Close the circuit for `port`, or for every port - returns the ports whose circuit was open
+pub fn reset_circuit(port: Option<Port>) -> Vec<Port> {
+    let Ok(mut circuits) = CIRCUITS.lock() else {
+        return Vec::new();
+    };
+    let mut reset: Vec<Port> = match port {
+        Some(port) => circuits
+            .remove(&port)
+            .filter(|circuit| circuit.tripped.is_some())
+            .map(|_| port)
+            .into_iter()
+            .collect(),
+        None => circuits
+            .drain()
+            .filter(|(_, circuit)| circuit.tripped.is_some())
+            .map(|(port, _)| port)
+            .collect(),
+    };
+    reset.sort_by_key(|port| port.0);
+    reset
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trips_after_consecutive_failures_and_closes_on_success() {
+        let port = Port(47_301);
+
+        record_failure(port);
+        record_failure(port);
+        assert!(check_circuit(port).is_ok());
+
+        record_failure(port);
+        assert!(check_circuit(port).is_err());
+
+        record_success(port);
+        assert!(check_circuit(port).is_ok());
+
+        for _ in 0..FAILURE_THRESHOLD {
+            record_failure(port);
+        }
+        assert_eq!(reset_circuit(Some(port)), vec![port]);
+        assert!(check_circuit(port).is_ok());
+    }
+
+    /// Make the open circuit for `port` due for its probe
+    fn expire_probe_interval(port: Port) {
+        if let Ok(mut circuits) = CIRCUITS.lock()
+            && let Some(trip) = circuits
+                .get_mut(&port)
+                .and_then(|circuit| circuit.tripped.as_mut())
+        {
+            trip.probe_after = Instant::now();
+        }
+    }
+
+    fn trip(port: Port) {
+        for _ in 0..FAILURE_THRESHOLD {
+            record_failure(port);
+        }
+    }
+
+    #[test]
+    fn half_open_circuit_lets_one_probe_through() {
+        let port = Port(47_302);
+        trip(port);
+        assert!(check_circuit(port).is_err());
+
+        // The probe goes through, the requests behind it still fail fast
+        expire_probe_interval(port);
+        assert!(check_circuit(port).is_ok());
+        assert!(check_circuit(port).is_err());
+
+        // A failed probe keeps the circuit open until the next interval
+        record_failure(port);
+        assert!(check_circuit(port).is_err());
+
+        // A successful probe closes it
+        expire_probe_interval(port);
+        assert!(check_circuit(port).is_ok());
+        record_success(port);
+        assert!(check_circuit(port).is_ok());
+        assert!(check_circuit(port).is_ok());
+    }
+
+    #[test]
+    fn resets_report_only_open_circuits() {
+        let open = Port(47_303);
+        let failing = Port(47_304);
+        trip(open);
+        record_failure(failing);
+
+        assert!(reset_circuit(Some(failing)).is_empty());
+        assert_eq!(reset_circuit(Some(open)), vec![open]);
+        assert!(reset_circuit(Some(open)).is_empty());
+        assert!(check_circuit(open).is_ok());
+    }
+}
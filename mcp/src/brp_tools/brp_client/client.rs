@@ -6,44 +6,105 @@
 //! - `execute_raw()`: Low-level API for debugging and format discovery engine
 //! - `execute_streaming()`: Specialized API for watch operations with streaming responses
 
+use std::sync::LazyLock;
+
+use regex::Regex;
+use serde_json::Map;
 use serde_json::Value;
 use tracing::warn;
 
 use super::super::Port;
+use super::auto_name;
+use super::color_correction;
+use super::compact_query;
+use super::compression;
 use super::constants::BRP_EXTRAS_PREFIX;
 use super::constants::JSON_RPC_ERROR_METHOD_NOT_FOUND;
+use super::correction_log::record_corrections;
+use super::enum_variant;
+use super::format_correction;
+use super::format_correction::CorrectionContext;
+use super::format_correction::Corrector;
 use super::http_client::BrpHttpClient;
+use super::json_rpc_builder::RequestId;
+use super::method_compat::resolve_method_name;
+use super::preflight;
+use super::read_cache;
+use super::request_queue;
+use super::request_queue::RequestPriority;
+use super::schema_validation;
+use super::transient_retry;
 use super::types::BrpClientCallJsonResponse;
 use super::types::BrpClientError;
 use super::types::BrpToolConfig;
+use super::types::JsonRpcError;
 use super::types::Operation;
 use super::types::ResponseStatus;
 use super::types::ResultStructBrpExt;
+use super::wire_encoding;
+use super::wire_encoding::BinaryResponseStream;
 use crate::brp_tools::FormatCorrectionStatus;
+use crate::brp_tools::app_restart::detect_restart;
 use crate::brp_tools::brp_type_guide::TypeGuideEngine;
+use crate::brp_tools::despawn_archive;
+use crate::brp_tools::entity_alias;
 use crate::error::Error;
 use crate::error::Result;
 use crate::tool::BrpMethod;
 use crate::tool::ParameterName;
+use crate::tool::without_format_field;
+
+/// Patterns of BRP error messages that name the type they failed on, compiled once
+static ERROR_TYPE_PATTERNS: LazyLock<Vec<Regex>> = LazyLock::new(|| {
+    [
+        r"Unknown component type: `([^`]+)`",
+        r"([a-zA-Z0-9_:]+) is invalid:",
+    ]
+    .into_iter()
+    .filter_map(|pattern| Regex::new(pattern).ok())
+    .collect()
+});
 
 /// Client for executing a BRP operation
 pub struct BrpClient {
-    method: BrpMethod,
-    port:   Port,
-    params: Option<Value>,
+    method:     BrpMethod,
+    port:       Port,
+    params:     Option<Value>,
+    request_id: RequestId,
+    priority:   RequestPriority,
 }
 
 impl BrpClient {
     /// Create a new BRP client for the given method, port, and parameters
+    #[must_use]
     pub const fn new(method: BrpMethod, port: Port, params: Option<Value>) -> Self {
         Self {
             method,
             port,
             params,
+            request_id: RequestId::Default,
+            priority: RequestPriority::Interactive,
         }
     }
 
+    /// Override the JSON-RPC id sent with the request
+    #[must_use]
+    pub fn with_request_id(mut self, request_id: RequestId) -> Self {
+        self.request_id = request_id;
+        self
+    }
+
+    /// Queue the request behind interactive ones on its port (see `request_queue`)
+    #[must_use]
+    pub const fn with_priority(mut self, priority: RequestPriority) -> Self {
+        self.priority = priority;
+        self
+    }
+
     /// Prepare parameters for BRP calls by filtering nulls and Port parameter
+    ///
+    /// # Errors
+    /// Returns an error if `params` cannot be serialized to JSON.
     pub fn prepare_params<T: serde::Serialize>(params: T) -> Result<Option<Value>> {
         let mut params_json = serde_json::to_value(params)
             .map_err(|e| Error::InvalidArgument(format!("Failed to serialize parameters: {e}")))?;
@@ -74,12 +135,167 @@ impl BrpClient {
     /// Appending the type guide to an error is only attempted for result types with
     /// `BrpToolConfig::ADD_TYPE_GUIDE_TO_ERROR = true`. Result types with `ADD_TYPE_GUIDE_TO_ERROR
     /// = false` will return errors immediately without added `TypeGuide` .
+    ///
+    /// Mutate and insert requests with `strict: true` are first checked against the entity's
+    /// components, and mutate requests naming a `variant` are expanded into a full variant value.
+    /// Spawn, insert and mutate requests with `validate: true` have their values checked against
+    /// `registry.schema` before they are sent, spawn requests with `auto_name` get a generated
+    /// `Name` component, despawn requests with `archive: true` archive the entity first, and
+    /// color shorthand in material requests is converted to reflected colors.
+    /// Under strict formats (see `tool::strict_formats`) no format correction is attempted.
+    ///
+    /// # Errors
+    /// Returns an error if the app cannot be reached, answers with an error that format
+    /// correction cannot fix, or sends a result that doesn't deserialize into `R`.
     pub async fn execute<R>(&self) -> Result<R>
     where
         R: ResultStructBrpExt<
                 Args = (
                     Option<Value>,
-                    Option<Vec<Value>>,
+                    Option<Vec<(Corrector, Value)>>,
+                    Option<FormatCorrectionStatus>,
+                ),
+            > + BrpToolConfig
+            + Send
+            + 'static,
+    {
+        let mut params = self.params.clone();
+        if let Some(checked) =
+            preflight::check_strict(self.method, self.port, params.as_ref()).await?
+        {
+            params = Some(checked);
+        }
+        if let Some(expanded) =
+            enum_variant::expand_variant(self.method, self.port, params.as_ref()).await?
+        {
+            params = Some(expanded);
+        }
+        let validate = match schema_validation::take_validate_flag(self.method, params.as_ref()) {
+            Some((without_flag, requested)) => {
+                params = Some(without_flag);
+                requested
+            },
+            None => false,
+        };
+        // Fields the MCP side adds to a successful result
+        let mut added_fields = Map::new();
+        if let Some((named, name)) =
+            auto_name::apply_auto_name(self.method, self.port, params.as_ref())?
+        {
+            params = Some(named);
+            added_fields.insert(
+                auto_name::NAME_RESULT_FIELD.to_string(),
+                Value::String(name),
+            );
+        }
+        if let Some(converted) =
+            color_correction::convert_material_colors(self.method, params.as_ref())
+        {
+            params = Some(converted);
+        }
+        if let Some((archived, fields)) =
+            despawn_archive::archive_before_despawn(self.method, self.port, params.as_ref()).await?
+        {
+            params = Some(archived);
+            added_fields.extend(fields);
+        }
+        if let Some(without_flag) =
+            compact_query::without_compact_flag(self.method, params.as_ref())
+        {
+            params = Some(without_flag);
+        }
+        if let Some(without_field) = without_format_field(self.method, params.as_ref()) {
+            params = Some(without_field);
+        }
+        let no_cache = read_cache::bypasses_cache(params.as_ref());
+        if let Some(without_flag) = read_cache::without_no_cache_flag(params.as_ref()) {
+            params = Some(without_flag);
+        }
+        let correctors = format_correction::requested_correctors(params.as_ref());
+        if let Some(without_field) = format_correction::without_correctors_field(params.as_ref()) {
+            params = Some(without_field);
+        }
+
+        let mut client = Self {
+            params,
+            request_id: self.request_id.clone(),
+            ..*self
+        };
+        // Values are validated as they will be sent - after any correction they need
+        let applied = if validate {
+            client.validate_with_corrections(&correctors).await?
+        } else {
+            Vec::new()
+        };
+        client
+            .execute_prepared(no_cache, &correctors, &added_fields, applied)
+            .await
+    }
+
+    /// Check the request's values against `registry.schema`, correcting them with `correctors`
+    /// when they fail - the params become the first correction that passes
+    ///
+    /// Returns the corrections applied, or the original mismatches when no correction passes.
+    async fn validate_with_corrections(
+        &mut self,
+        correctors: &[Corrector],
+    ) -> Result<Vec<(Corrector, Value)>> {
+        let Some(mut params) = self.params.clone() else {
+            return Ok(Vec::new());
+        };
+        let errors = schema_validation::schema_errors(self.method, self.port, &params).await?;
+        if errors.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut context = CorrectionContext::new(self.method, self.port);
+        let mut corrections: Vec<(Corrector, Value)> = Vec::new();
+        for &corrector in correctors {
+            let candidates = corrector.candidates(&mut context, &params).await?;
+            let Some(best_guess) = candidates.first().cloned() else {
+                continue;
+            };
+            for candidate in candidates {
+                if schema_validation::schema_errors(self.method, self.port, &candidate.params)
+                    .await?
+                    .is_empty()
+                {
+                    corrections.extend(
+                        candidate
+                            .corrections
+                            .into_iter()
+                            .map(|correction| (corrector, correction)),
+                    );
+                    self.params = Some(candidate.params);
+                    return Ok(corrections);
+                }
+            }
+            params = best_guess.params;
+            corrections.extend(
+                best_guess
+                    .corrections
+                    .into_iter()
+                    .map(|correction| (corrector, correction)),
+            );
+        }
+        Err(schema_validation::validation_failure(errors))
+    }
+
+    /// `execute` once the request's parameters have been checked and expanded - `added_fields`
+    /// are added to a successful result, and the corrections `applied` during validation are
+    /// reported with it
+    async fn execute_prepared<R>(
+        &self,
+        no_cache: bool,
+        correctors: &[Corrector],
+        added_fields: &Map<String, Value>,
+        applied: Vec<(Corrector, Value)>,
+    ) -> Result<R>
+    where
+        R: ResultStructBrpExt<
+                Args = (
+                    Option<Value>,
+                    Option<Vec<(Corrector, Value)>>,
                     Option<FormatCorrectionStatus>,
                 ),
             > + BrpToolConfig
@@ -87,18 +303,46 @@ impl BrpClient {
             + 'static,
     {
         // ALWAYS execute direct first
-        let direct_result = self.execute_direct_internal().await?;
+        let direct_result = self.execute_cached(no_cache).await?;
 
         match direct_result {
-            ResponseStatus::Success(data) => {
+            ResponseStatus::Success(data) if applied.is_empty() => {
                 // Success - no format discovery needed
                 R::from_brp_client_response((
-                    data,
+                    with_added_fields(data, added_fields),
                     None,
                     Some(FormatCorrectionStatus::NotAttempted),
                 ))
             },
+            ResponseStatus::Success(data) => {
+                // Success with the corrections validation needed
+                record_corrections(
+                    self.port,
+                    self.method,
+                    applied.iter().map(|(rule, correction)| (*rule, correction)),
+                );
+                R::from_brp_client_response((
+                    with_added_fields(data, added_fields),
+                    Some(applied),
+                    Some(FormatCorrectionStatus::Succeeded),
+                ))
+            },
             ResponseStatus::Error(err) => {
+                // Run the format-correction pipeline
+                if err.has_format_error_code()
+                    && let Some((data, corrections)) =
+                        self.retry_with_corrections(correctors).await?
+                {
+                    // Report the corrections validation applied first, as they were made first
+                    let mut all_corrections = applied;
+                    all_corrections.extend(corrections);
+                    return R::from_brp_client_response((
+                        with_added_fields(data, added_fields),
+                        Some(all_corrections),
+                        Some(FormatCorrectionStatus::Succeeded),
+                    ));
+                }
+
                 // Check if this result type supports adding the `TypeGuide`
                 if R::ADD_TYPE_GUIDE_TO_ERROR && err.has_format_error_code() {
                     // embed type_guide information
@@ -128,23 +372,121 @@ impl BrpClient {
     /// - Debugging tools that need raw BRP responses (`brp_execute`)
     /// - Format discovery engine internal operations
     /// - Testing and diagnostic scenarios
+    ///
+    /// # Errors
+    /// Returns an error if the app cannot be reached or its response cannot be parsed - an error
+    /// the app answers with is returned as `ResponseStatus::Error`.
     pub async fn execute_raw(&self) -> Result<ResponseStatus> {
         self.execute_direct_internal().await
     }
 
+    /// `execute_raw()` for tool calls - idempotent reads are answered from the short-lived read
+    /// cache when a fresh response is there, unless `no_cache` is set (see `read_cache`), and
+    /// transient errors are retried briefly (see `transient_retry`)
+    ///
+    /// # Errors
+    /// Returns an error if the app cannot be reached or its response cannot be parsed.
+    pub async fn execute_cached(&self, no_cache: bool) -> Result<ResponseStatus> {
+        // A restarted app must not be answered with what was cached about its previous run
+        detect_restart(self.port).await;
+        if !no_cache
+            && let Some(cached) = read_cache::lookup(self.method, self.port, self.params.as_ref())
+        {
+            return Ok(cached);
+        }
+        let status = self.execute_retrying_transient().await?;
+        read_cache::store(self.method, self.port, self.params.as_ref(), &status);
+        Ok(status)
+    }
+
+    /// Send the request, sending it again with backoff while it fails with a transient error
+    async fn execute_retrying_transient(&self) -> Result<ResponseStatus> {
+        let mut retries = 0;
+        loop {
+            let status = self.execute_direct_internal().await?;
+            let delay = match &status {
+                ResponseStatus::Error(err) if transient_retry::is_transient(err) => {
+                    transient_retry::next_delay(self.port, retries)
+                },
+                _ => None,
+            };
+            let Some(delay) = delay else {
+                transient_retry::record_retries(retries);
+                return Ok(status);
+            };
+            tokio::time::sleep(delay).await;
+            retries += 1;
+        }
+    }
+
+    /// Send the request and return the JSON-RPC response exactly as the app sent it
+    ///
+    /// Unlike `execute_raw()` nothing is interpreted - no error enhancement, no `bevy_brp_extras`
+    /// handling and no format correction. Used by `brp_execute` to debug protocol issues. The
+    /// response still updates the entity aliases and cached reads of the port.
+    ///
+    /// # Errors
+    /// Returns an error if the app cannot be reached or its response body isn't JSON.
+    pub async fn execute_untouched(&self) -> Result<Value> {
+        let _slot = request_queue::acquire(self.port, self.priority).await;
+        let response = self.http_client().await.send_request().await?;
+        let body = response.text().await.map_err(|e| {
+            error_stack::Report::new(Error::JsonRpc("Failed to read response body".to_string()))
+                .attach(format!(
+                    "Method: {}, Port: {}",
+                    self.method.as_str(),
+                    self.port
+                ))
+                .attach(format!("Error: {e}"))
+        })?;
+
+        if body.trim().is_empty() {
+            return Ok(Value::Null);
+        }
+        let response: Value = serde_json::from_str(&body).map_err(|e| {
+            error_stack::Report::new(Error::JsonRpc("JSON parsing failed".to_string()))
+                .attach(format!(
+                    "Method: {}, Port: {}",
+                    self.method.as_str(),
+                    self.port
+                ))
+                .attach(format!("Body: {body}"))
+                .attach(format!("Error: {e}"))
+        })?;
+
+        if let Some(status) = status_of_envelope(&response) {
+            self.observe_response(&status);
+        }
+        Ok(response)
+    }
+
+    /// Send the request as a JSON-RPC notification without waiting for a result
+    ///
+    /// Returns once the app has accepted the request - any response body is ignored. Its outcome
+    /// is unknown, so the cached reads of the port are dropped as after any write.
+    ///
+    /// # Errors
+    /// Returns an error if the app cannot be reached.
+    pub async fn send_notification(&self) -> Result<()> {
+        let _slot = request_queue::acquire(self.port, self.priority).await;
+        self.http_client()
+            .await
+            .with_request_id(RequestId::Notification)
+            .send_request()
+            .await?;
+        read_cache::forget_cached_reads(self.port);
+        Ok(())
+    }
+
     /// Raw BRP execution without any error enhancement (used internally to prevent recursion)
     ///
     /// This method is identical to `execute_direct_internal()` but bypasses all error enhancement
     /// to prevent recursion when `TypeSchemaEngine` needs to fetch registry data.
+    ///
+    /// # Errors
+    /// Returns an error if the app cannot be reached or its response cannot be parsed.
     pub async fn execute_direct_internal_no_enhancement(&self) -> Result<ResponseStatus> {
-        // Create HTTP client with our data
-        let http_client = BrpHttpClient::new(self.method, self.port, self.params.clone());
-
-        // Send HTTP request (includes status check)
-        let response = http_client.send_request().await?;
-
-        // Parse JSON-RPC response
-        let brp_response = self.parse_json_response(response).await?;
+        let brp_response = self.send_and_parse().await?;
 
         // Convert to BrpClientResult with special handling for bevy_brp_extras
         // NO ERROR ENHANCEMENT - return directly
@@ -158,9 +500,12 @@ impl BrpClient {
     /// - Uses no timeout (streaming connections stay open)
     /// - Returns the raw response for the caller to process
     /// - Provides the same rich error context as other `BrpClient` methods
+    ///
+    /// # Errors
+    /// Returns an error if the app cannot be reached or answers with a non-success status.
     pub async fn execute_streaming(&self) -> Result<reqwest::Response> {
         // Create HTTP client with our data
-        let http_client = BrpHttpClient::new(self.method, self.port, self.params.clone());
+        let http_client = self.http_client().await;
 
         // Send HTTP request using streaming version (no timeout, includes status check)
         let response = http_client.send_streaming_request().await?;
@@ -168,22 +513,137 @@ impl BrpClient {
         Ok(response)
     }
 
+    /// Start a streaming request over the binary transport, when the app offers one
+    ///
+    /// Returns `None` when the method isn't sent over the binary transport, the app only speaks
+    /// JSON or its binary transport can't be reached, so the caller can fall back to
+    /// `execute_streaming()`.
+    pub async fn execute_binary_streaming(&self) -> Option<BinaryResponseStream> {
+        let method_name = resolve_method_name(self.method, self.port).await;
+        if !wire_encoding::is_high_volume(method_name) {
+            return None;
+        }
+        wire_encoding::open_stream(
+            self.port,
+            method_name,
+            self.params.as_ref(),
+            &self.request_id,
+        )
+        .await
+    }
+
+    /// Start a streaming request for a method this server has no `BrpMethod` for, such as a
+    /// game's own streaming endpoint - the name is sent as-is, with the same error context as
+    /// `execute_streaming()`
+    ///
+    /// # Errors
+    /// Returns an error if the app cannot be reached or answers with a non-success status.
+    pub async fn execute_streaming_custom(
+        method_name: String,
+        port: Port,
+        params: Option<Value>,
+    ) -> Result<reqwest::Response> {
+        BrpHttpClient::for_method_name(method_name, port, params)
+            .send_streaming_request()
+            .await
+    }
+
+    /// Send a request for a method this server has no `BrpMethod` for and return its status -
+    /// the name is sent as-is, without the corrections and retries of `execute_raw()`. Such a
+    /// method is no known read, so success drops the cached reads of the port.
+    ///
+    /// # Errors
+    /// Returns an error if the app cannot be reached or its response cannot be parsed.
+    pub async fn execute_custom(
+        method_name: String,
+        port: Port,
+        params: Option<Value>,
+    ) -> Result<ResponseStatus> {
+        let response = BrpHttpClient::for_method_name(method_name.clone(), port, params)
+            .send_request()
+            .await?;
+        let response: BrpClientCallJsonResponse = response.json().await.map_err(|e| {
+            error_stack::Report::new(Error::JsonRpc("JSON parsing failed".to_string()))
+                .attach(format!("Method: {method_name}, Port: {port}"))
+                .attach(format!("Error: {e}"))
+        })?;
+        let status = response
+            .error
+            .map_or(ResponseStatus::Success(response.result), |error| {
+                ResponseStatus::Error(BrpClientError {
+                    code:    error.code,
+                    message: error.message,
+                    data:    error.data,
+                })
+            });
+        if matches!(status, ResponseStatus::Success(_)) {
+            read_cache::forget_cached_reads(port);
+        }
+        Ok(status)
+    }
+
     /// Internal direct execution - does the actual http call - we wanted the internal version so we
     /// can distinguish a canned call generated for a `ToolFn` by our macro, and the `execute_raw()`
     /// version we still allow to be called by bespoke tools like `brp_shutdown` and `brp_status`
     /// and the like.
     async fn execute_direct_internal(&self) -> Result<ResponseStatus> {
-        // Create HTTP client with our data
-        let http_client = BrpHttpClient::new(self.method, self.port, self.params.clone());
+        let brp_response = self.send_and_parse().await?;
+
+        // Convert to BrpClientResult with special handling for bevy_brp_extras
+        let status = self.to_response_status(brp_response);
+        self.observe_response(&status);
+
+        Ok(status)
+    }
+
+    /// Update the bookkeeping that depends on what a call did to the app
+    fn observe_response(&self, status: &ResponseStatus) {
+        // Aliases of entities this call showed to be gone are no longer valid
+        entity_alias::observe_response(self.method, self.port, self.params.as_ref(), status);
+        // Cached component lists may be stale once the world changed
+        read_cache::observe_response(self.method, self.port, status);
+    }
+
+    /// Send the request and parse the JSON-RPC response - over the binary transport for
+    /// high-volume methods when the app offers one, otherwise as JSON over HTTP
+    async fn send_and_parse(&self) -> Result<BrpClientCallJsonResponse> {
+        // Hold a slot in the port's queue until the response is read
+        let _slot = request_queue::acquire(self.port, self.priority).await;
+        let method_name = resolve_method_name(self.method, self.port).await;
+        if wire_encoding::is_high_volume(method_name)
+            && let Some(transport) = wire_encoding::transport_for(self.port).await
+        {
+            match transport
+                .request(method_name, self.params.as_ref(), &self.request_id)
+                .await
+            {
+                Ok(brp_response) => return Ok(brp_response),
+                Err(e) => {
+                    warn!("Binary BRP transport failed, falling back to JSON: {e}");
+                    wire_encoding::forget_wire_encoding(self.port);
+                },
+            }
+        }
 
         // Send HTTP request (includes status check)
-        let response = http_client.send_request().await?;
+        let response = self
+            .http_client()
+            .await
+            .with_compression()
+            .send_request()
+            .await?;
 
         // Parse JSON-RPC response
-        let brp_response = self.parse_json_response(response).await?;
+        self.parse_json_response(response).await
+    }
 
-        // Convert to BrpClientResult with special handling for bevy_brp_extras
-        Ok(self.to_response_status(brp_response))
+    /// Create the HTTP client for this call, translating the method name for apps that still
+    /// use the pre-0.17 `bevy/*` method names
+    async fn http_client(&self) -> BrpHttpClient {
+        let method_name = resolve_method_name(self.method, self.port).await;
+        BrpHttpClient::new(self.method, self.port, self.params.clone())
+            .with_method_name(method_name)
+            .with_request_id(self.request_id.clone())
     }
 
     /// Parse the JSON response from the BRP call to a running bevy app
@@ -191,7 +651,8 @@ impl BrpClient {
         &self,
         response: reqwest::Response,
     ) -> Result<BrpClientCallJsonResponse> {
-        match response.json().await {
+        let body = compression::read_body(response).await?;
+        match serde_json::from_slice(&body) {
             Ok(json_resp) => Ok(json_resp),
             Err(e) => {
                 warn!("BRP execute_brp_method: JSON parsing failed - error={}", e);
@@ -209,21 +670,70 @@ impl BrpClient {
         }
     }
 
+    /// Retry a request that failed with a format error through the format-correction pipeline
+    ///
+    /// Each corrector's candidates are retried in turn. When none of them succeeds, the first -
+    /// its best guess - is kept for the correctors after it, so corrections accumulate. Returns
+    /// the response data and each correction with the corrector that made it, or `None` if no
+    /// corrected request succeeded. The corrections of a successful retry are
+    /// recorded in the session's correction log.
+    async fn retry_with_corrections(
+        &self,
+        correctors: &[Corrector],
+    ) -> Result<Option<(Option<Value>, Vec<(Corrector, Value)>)>> {
+        let Some(mut params) = self.params.clone() else {
+            return Ok(None);
+        };
+
+        let mut context = CorrectionContext::new(self.method, self.port);
+        let mut corrections: Vec<(Corrector, Value)> = Vec::new();
+        for &corrector in correctors {
+            let candidates = corrector.candidates(&mut context, &params).await?;
+            let Some(best_guess) = candidates.first().cloned() else {
+                continue;
+            };
+
+            for candidate in candidates {
+                let client = Self::new(self.method, self.port, Some(candidate.params));
+                if let ResponseStatus::Success(data) = client.execute_direct_internal().await? {
+                    corrections.extend(
+                        candidate
+                            .corrections
+                            .into_iter()
+                            .map(|correction| (corrector, correction)),
+                    );
+                    record_corrections(
+                        self.port,
+                        self.method,
+                        corrections
+                            .iter()
+                            .map(|(rule, correction)| (*rule, correction)),
+                    );
+                    return Ok(Some((data, corrections)));
+                }
+            }
+
+            params = best_guess.params;
+            corrections.extend(
+                best_guess
+                    .corrections
+                    .into_iter()
+                    .map(|correction| (corrector, correction)),
+            );
+        }
+
+        Ok(None)
+    }
+
     /// Extract type names from BRP error messages using regex patterns
     fn extract_types_from_error_message(error_msg: &str) -> Vec<String> {
-        const ERROR_PATTERNS: &[&str] = &[
-            r"Unknown component type: `([^`]+)`",
-            r"([a-zA-Z0-9_:]+) is invalid:",
-        ];
-
-        ERROR_PATTERNS
+        ERROR_TYPE_PATTERNS
             .iter()
-            .filter_map(|pattern| {
-                regex::Regex::new(pattern)
-                    .ok()
-                    .and_then(|regex| regex.captures(error_msg))
+            .filter_map(|regex| {
+                regex
+                    .captures(error_msg)
                     .and_then(|caps| caps.get(1))
-                    .map(|m| (*m.as_str()).to_string())
+                    .map(|m| m.as_str().to_string())
             })
             .collect()
     }
@@ -321,8 +831,11 @@ impl BrpClient {
                 && self.method.as_str().starts_with(BRP_EXTRAS_PREFIX)
             {
                 format!(
-                    "{}. This method requires the bevy_brp_extras crate to be added to your Bevy app with the BrpExtrasPlugin",
-                    error.message
+                    "{}. bevy_brp_extras is not present on port {}, or the app leaves out the \
+                     plugin section serving this method (see brp_extras_capabilities). This \
+                     method requires the bevy_brp_extras crate to be added to your Bevy app with \
+                     the BrpExtrasPlugin",
+                    error.message, self.port
                 )
             } else {
                 error.message
@@ -338,3 +851,35 @@ impl BrpClient {
         }
     }
 }
+
+/// Add `fields` to a successful result - an empty result becomes an object of just the fields
+fn with_added_fields(result: Option<Value>, fields: &Map<String, Value>) -> Option<Value> {
+    if fields.is_empty() {
+        return result;
+    }
+    match result {
+        Some(Value::Object(mut map)) => {
+            map.extend(fields.clone());
+            Some(Value::Object(map))
+        },
+        None | Some(Value::Null) => Some(Value::Object(fields.clone())),
+        other => other,
+    }
+}
+
+/// The status a JSON-RPC response envelope reports, or `None` when it isn't one
+///
+/// Unlike `BrpClientCallJsonResponse` this accepts any id, since raw requests may carry a custom
+/// one.
+fn status_of_envelope(response: &Value) -> Option<ResponseStatus> {
+    let envelope = response.as_object()?;
+    let Some(error) = envelope.get("error") else {
+        return Some(ResponseStatus::Success(envelope.get("result").cloned()));
+    };
+    let error: JsonRpcError = serde_json::from_value(error.clone()).ok()?;
+    Some(ResponseStatus::Error(BrpClientError {
+        code:    error.code,
+        message: error.message,
+        data:    error.data,
+    }))
+}
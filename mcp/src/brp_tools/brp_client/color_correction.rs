@@ -0,0 +1,363 @@
+//! Color shorthand conversion for BRP requests
+//!
+//! Agents often write colors the way they would in CSS - `"#ff8800"`, `"rgb(255, 136, 0)"` or
+//! `"orange"` - instead of the reflected form Bevy expects. When a spawn, insert or mutate request
+//! fails with a format error, these helpers find such strings in fields the type guide says are
+//! colors, and rewrite them into the representation of that field's type so the client can retry.
+//! This is the `color_formats` step of the format-correction pipeline.
+
+use serde_json::Value;
+use serde_json::json;
+
+use super::typed_values;
+use super::typed_values::Rewrite;
+use crate::brp_tools::BrpTypeName;
+use crate::brp_tools::brp_type_guide::TypeGuideEngine;
+use crate::tool::BrpMethod;
+
+/// `bevy_color::Color` - an enum, written as `{"Srgba": {...}}`
+const COLOR_TYPE: &str = "bevy_color::color::Color";
+/// `bevy_color::Srgba` - a struct of gamma-encoded channels
+const SRGBA_TYPE: &str = "bevy_color::srgba::Srgba";
+/// `bevy_color::LinearRgba` - a struct of linear channels
+const LINEAR_RGBA_TYPE: &str = "bevy_color::linear_rgba::LinearRgba";
+
+/// Note appended to the description of every tool whose color fields accept shorthand
+pub const COLOR_SHORTHAND_NOTE: &str = include_str!("../../../help_text/notes/color_shorthand.txt");
+
+/// Fields of a `brp_extras/material` request that hold colors, with their types
+const MATERIAL_COLOR_FIELDS: &[(&str, &str)] =
+    &[("base_color", COLOR_TYPE), ("emissive", LINEAR_RGBA_TYPE)];
+
+/// CSS color names accepted in place of a color value, as sRGB bytes
+const NAMED_COLORS: &[(&str, [u8; 3])] = &[
+    ("aqua", [0, 255, 255]),
+    ("black", [0, 0, 0]),
+    ("blue", [0, 0, 255]),
+    ("brown", [165, 42, 42]),
+    ("cyan", [0, 255, 255]),
+    ("fuchsia", [255, 0, 255]),
+    ("gold", [255, 215, 0]),
+    ("gray", [128, 128, 128]),
+    ("green", [0, 128, 0]),
+    ("grey", [128, 128, 128]),
+    ("lime", [0, 255, 0]),
+    ("magenta", [255, 0, 255]),
+    ("maroon", [128, 0, 0]),
+    ("navy", [0, 0, 128]),
+    ("olive", [128, 128, 0]),
+    ("orange", [255, 165, 0]),
+    ("pink", [255, 192, 203]),
+    ("purple", [128, 0, 128]),
+    ("red", [255, 0, 0]),
+    ("silver", [192, 192, 192]),
+    ("teal", [0, 128, 128]),
+    ("white", [255, 255, 255]),
+    ("yellow", [255, 255, 0]),
+];
+
+/// A color parsed from shorthand, with gamma-encoded (sRGB) channels in `0.0..=1.0`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ShorthandColor {
+    red:   f64,
+    green: f64,
+    blue:  f64,
+    alpha: f64,
+}
+
+impl ShorthandColor {
+    /// Parse `#RGB`, `#RGBA`, `#RRGGBB`, `#RRGGBBAA`, `rgb(r, g, b)`, `rgba(r, g, b, a)` or a CSS
+    /// color name (`transparent` included)
+    pub fn parse(input: &str) -> Option<Self> {
+        let input = input.trim();
+        if let Some(hex) = input.strip_prefix('#') {
+            return Self::parse_hex(hex);
+        }
+
+        let lower = input.to_ascii_lowercase();
+        if let Some(args) = lower
+            .strip_prefix("rgba(")
+            .or_else(|| lower.strip_prefix("rgb("))
+            .and_then(|rest| rest.strip_suffix(')'))
+        {
+            return Self::parse_rgb_function(args);
+        }
+
+        if lower == "transparent" {
+            return Some(Self {
+                red:   0.0,
+                green: 0.0,
+                blue:  0.0,
+                alpha: 0.0,
+            });
+        }
+        NAMED_COLORS
+            .iter()
+            .find(|(name, _)| *name == lower)
+            .map(|(_, [red, green, blue])| Self::from_bytes(*red, *green, *blue, u8::MAX))
+    }
+
+    fn from_bytes(red: u8, green: u8, blue: u8, alpha: u8) -> Self {
+        Self {
+            red:   f64::from(red) / 255.0,
+            green: f64::from(green) / 255.0,
+            blue:  f64::from(blue) / 255.0,
+            alpha: f64::from(alpha) / 255.0,
+        }
+    }
+
+    fn parse_hex(hex: &str) -> Option<Self> {
+        if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+        let digits: Vec<u8> = match hex.len() {
+            // Short forms repeat each digit: `#f80` is `#ff8800`
+            3 | 4 => hex
+                .chars()
+                .map(|c| u8::from_str_radix(&format!("{c}{c}"), 16).ok())
+                .collect::<Option<_>>()?,
+            6 | 8 => (0..hex.len())
+                .step_by(2)
+                .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+                .collect::<Option<_>>()?,
+            _ => return None,
+        };
+        match digits.as_slice() {
+            [red, green, blue] => Some(Self::from_bytes(*red, *green, *blue, u8::MAX)),
+            [red, green, blue, alpha] => Some(Self::from_bytes(*red, *green, *blue, *alpha)),
+            _ => None,
+        }
+    }
+
+    /// Channels are `0-255` or percentages, alpha is `0.0-1.0` or a percentage
+    fn parse_rgb_function(args: &str) -> Option<Self> {
+        let args: Vec<&str> = args.split(',').map(str::trim).collect();
+        let channel = |arg: &str| -> Option<f64> {
+            let value = match arg.strip_suffix('%') {
+                Some(percent) => percent.trim().parse::<f64>().ok()? / 100.0,
+                None => arg.parse::<f64>().ok()? / 255.0,
+            };
+            (0.0..=1.0).contains(&value).then_some(value)
+        };
+        let alpha = |arg: &str| -> Option<f64> {
+            let value = match arg.strip_suffix('%') {
+                Some(percent) => percent.trim().parse::<f64>().ok()? / 100.0,
+                None => arg.parse::<f64>().ok()?,
+            };
+            (0.0..=1.0).contains(&value).then_some(value)
+        };
+
+        match args.as_slice() {
+            [red, green, blue] => Some(Self {
+                red:   channel(red)?,
+                green: channel(green)?,
+                blue:  channel(blue)?,
+                alpha: 1.0,
+            }),
+            [red, green, blue, alpha_arg] => Some(Self {
+                red:   channel(red)?,
+                green: channel(green)?,
+                blue:  channel(blue)?,
+                alpha: alpha(alpha_arg)?,
+            }),
+            _ => None,
+        }
+    }
+
+    /// The value of this color for a field of type `type_name`, or `None` if it isn't a color type
+    fn to_reflected(self, type_name: &BrpTypeName) -> Option<Value> {
+        match type_name.as_str() {
+            COLOR_TYPE => Some(json!({ "Srgba": self.channels() })),
+            SRGBA_TYPE => Some(self.channels()),
+            LINEAR_RGBA_TYPE => Some(self.linear().channels()),
+            _ => None,
+        }
+    }
+
+    fn channels(self) -> Value {
+        json!({
+            "red": self.red,
+            "green": self.green,
+            "blue": self.blue,
+            "alpha": self.alpha,
+        })
+    }
+
+    /// Convert gamma-encoded channels to linear ones (alpha is already linear)
+    fn linear(self) -> Self {
+        let decode = |channel: f64| {
+            if channel <= 0.040_45 {
+                channel / 12.92
+            } else {
+                ((channel + 0.055) / 1.055).powf(2.4)
+            }
+        };
+        Self {
+            red:   decode(self.red),
+            green: decode(self.green),
+            blue:  decode(self.blue),
+            alpha: self.alpha,
+        }
+    }
+}
+
+/// Whether `params` holds any string that parses as a color shorthand - checked before fetching
+/// the registry so requests without shorthand don't pay for it
+pub fn contains_color_shorthand(params: &Value) -> bool {
+    match params {
+        Value::String(s) => ShorthandColor::parse(s).is_some(),
+        Value::Array(items) => items.iter().any(contains_color_shorthand),
+        Value::Object(map) => map.values().any(contains_color_shorthand),
+        _ => false,
+    }
+}
+
+/// Whether color shorthand in the values of `method` is converted on a format error
+pub const fn accepts_color_shorthand(method: BrpMethod) -> bool {
+    typed_values::has_typed_values(method)
+}
+
+/// Rewrite color shorthand in the component or resource values of a spawn, insert or mutate
+/// request, returning the corrected parameters and what was converted
+pub fn convert_colors(
+    method: BrpMethod,
+    params: &Value,
+    engine: &TypeGuideEngine,
+) -> Option<(Value, Vec<Rewrite>)> {
+    typed_values::rewrite_typed_values(method, params, engine, |typed| {
+        let color = ShorthandColor::parse(typed.value.as_str()?)?;
+        typed
+            .candidates
+            .iter()
+            .find_map(|(_, type_name)| color.to_reflected(type_name))
+    })
+}
+
+/// Rewrite color shorthand in the color fields of a `brp_extras/material` request
+///
+/// The types of those fields are fixed, so unlike [`convert_colors`] this needs no type guide and
+/// runs before the request is sent. Returns `None` when there is nothing to convert.
+pub fn convert_material_colors(method: BrpMethod, params: Option<&Value>) -> Option<Value> {
+    if method != BrpMethod::BrpExtrasMaterial {
+        return None;
+    }
+    let mut params = params?.as_object()?.clone();
+    let mut converted = false;
+    for (field, type_name) in MATERIAL_COLOR_FIELDS {
+        if let Some(value) = params.get_mut(*field)
+            && let Some(color) = value.as_str().and_then(ShorthandColor::parse)
+            && let Some(reflected) = color.to_reflected(&BrpTypeName::from(*type_name))
+        {
+            *value = reflected;
+            converted = true;
+        }
+    }
+    converted.then_some(Value::Object(params))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::brp_tools::brp_client::typed_values::tint_engine;
+
+    #[test]
+    fn parses_hex_forms() {
+        let orange = ShorthandColor::from_bytes(255, 136, 0, 255);
+        assert_eq!(ShorthandColor::parse("#ff8800"), Some(orange));
+        assert_eq!(ShorthandColor::parse("#F80"), Some(orange));
+        assert_eq!(
+            ShorthandColor::parse("#ff880080"),
+            Some(ShorthandColor::from_bytes(255, 136, 0, 128))
+        );
+        assert_eq!(
+            ShorthandColor::parse("#ff88"),
+            Some(ShorthandColor::from_bytes(255, 255, 136, 136))
+        );
+        assert_eq!(ShorthandColor::parse("#ff88000"), None);
+        assert_eq!(ShorthandColor::parse("#gg8800"), None);
+    }
+
+    #[test]
+    fn parses_rgb_functions_and_names() {
+        assert_eq!(
+            ShorthandColor::parse("rgb(255, 136, 0)"),
+            Some(ShorthandColor::from_bytes(255, 136, 0, 255))
+        );
+        assert_eq!(
+            ShorthandColor::parse("rgba(100%, 0%, 0%, 0.5)"),
+            Some(ShorthandColor {
+                red:   1.0,
+                green: 0.0,
+                blue:  0.0,
+                alpha: 0.5,
+            })
+        );
+        assert_eq!(ShorthandColor::parse("rgb(300, 0, 0)"), None);
+        assert_eq!(
+            ShorthandColor::parse("Red"),
+            Some(ShorthandColor::from_bytes(255, 0, 0, 255))
+        );
+        assert_eq!(ShorthandColor::parse("player"), None);
+    }
+
+    #[test]
+    fn reflects_into_color_types() {
+        let red = ShorthandColor::from_bytes(255, 0, 0, 255);
+        let channels = json!({ "red": 1.0, "green": 0.0, "blue": 0.0, "alpha": 1.0 });
+        assert_eq!(
+            red.to_reflected(&BrpTypeName::from(COLOR_TYPE)),
+            Some(json!({ "Srgba": &channels }))
+        );
+        assert_eq!(
+            red.to_reflected(&BrpTypeName::from(SRGBA_TYPE)),
+            Some(channels)
+        );
+        assert_eq!(red.to_reflected(&BrpTypeName::from("f32")), None);
+    }
+
+    #[test]
+    fn converts_shorthand_in_color_fields_only() {
+        let engine = tint_engine();
+        let params = json!({
+            "components": { "test::Tint": { "color": "red", "label": "orange" } }
+        });
+        let (corrected, rewrites) = convert_colors(BrpMethod::WorldSpawnEntity, &params, &engine)
+            .unwrap_or_else(|| unreachable!("the color field holds shorthand"));
+        let red = json!({ "Srgba": { "red": 1.0, "green": 0.0, "blue": 0.0, "alpha": 1.0 } });
+        assert_eq!(corrected["components"]["test::Tint"]["color"], red);
+        assert_eq!(
+            corrected["components"]["test::Tint"]["label"],
+            json!("orange")
+        );
+        assert_eq!(rewrites.len(), 1);
+        assert_eq!(rewrites[0].path, ".color");
+
+        let params = json!({ "component": "test::Tint", "path": ".color", "value": "#ff0000" });
+        let (corrected, _) = convert_colors(BrpMethod::WorldMutateComponents, &params, &engine)
+            .unwrap_or_else(|| unreachable!("the mutated path is a color"));
+        assert_eq!(corrected["value"], red);
+
+        let params =
+            json!({ "components": { "test::Tint": { "color": { "Srgba": red["Srgba"] } } } });
+        assert!(convert_colors(BrpMethod::WorldSpawnEntity, &params, &engine).is_none());
+    }
+
+    #[test]
+    fn converts_material_colors_to_their_field_types() {
+        let params = json!({ "entity": 1, "base_color": "white", "emissive": "#000000" });
+        let corrected = convert_material_colors(BrpMethod::BrpExtrasMaterial, Some(&params))
+            .unwrap_or_else(|| unreachable!("both colors are shorthand"));
+        assert_eq!(
+            corrected["base_color"],
+            json!({ "Srgba": { "red": 1.0, "green": 1.0, "blue": 1.0, "alpha": 1.0 } })
+        );
+        assert_eq!(
+            corrected["emissive"],
+            json!({ "red": 0.0, "green": 0.0, "blue": 0.0, "alpha": 1.0 })
+        );
+
+        assert!(convert_material_colors(BrpMethod::WorldSpawnEntity, Some(&params)).is_none());
+        let reflected = json!({ "entity": 1, "base_color": corrected["base_color"] });
+        assert!(convert_material_colors(BrpMethod::BrpExtrasMaterial, Some(&reflected)).is_none());
+    }
+}
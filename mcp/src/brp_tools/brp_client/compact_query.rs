@@ -0,0 +1,137 @@
+//! `compact` output mode for `world.query`
+//!
+//! Uniform scenes - hundreds of tiles with the same `Sprite`, particles sharing a material -
+//! repeat the same component value once per entity. With `compact: true`, each component value
+//! shared by several entities is listed once in a `shared_values` table and the entities refer
+//! to it by index as `{"$shared": index}`.
+
+use std::collections::HashMap;
+
+use serde_json::Map;
+use serde_json::Value;
+use serde_json::json;
+
+use crate::tool::BrpMethod;
+use crate::tool::ParameterName;
+
+/// Key of a reference to a `shared_values` entry
+const SHARED_REF_KEY: &str = "$shared";
+
+/// Values this short (serialized) are left inline - a reference wouldn't be any shorter
+const MIN_SHARED_VALUE_CHARS: usize = 16;
+
+/// Remove `compact` from a query request - BRP doesn't know it
+///
+/// Returns `None` when the request isn't a query or has no `compact` flag, so it can be sent
+/// unchanged.
+pub fn without_compact_flag(method: BrpMethod, params: Option<&Value>) -> Option<Value> {
+    if method != BrpMethod::WorldQuery {
+        return None;
+    }
+    let mut params = params.and_then(Value::as_object).cloned()?;
+    params.remove(ParameterName::Compact.as_ref())?;
+    Some(Value::Object(params))
+}
+
+/// Factor component values shared by several entities out of a query result
+///
+/// Returns `{"entities": [...], "shared_values": [{"component", "value", "entity_count"}]}` and
+/// the number of shared values, or `None` when `result` isn't a list of entities.
+pub fn compact_query_result(result: &Value) -> Option<(Value, usize)> {
+    let entities = result.as_array()?;
+
+    let mut usage: HashMap<(&str, String), usize> = HashMap::new();
+    for (component, value) in entities.iter().flat_map(entity_components) {
+        let serialized = value.to_string();
+        if serialized.len() >= MIN_SHARED_VALUE_CHARS {
+            *usage.entry((component, serialized)).or_default() += 1;
+        }
+    }
+
+    let mut shared_values: Vec<Value> = Vec::new();
+    let mut shared_index: HashMap<(&str, String), usize> = HashMap::new();
+    let mut compacted = entities.clone();
+    for (entity, original) in compacted.iter_mut().zip(entities) {
+        let Some(Value::Object(components)) = entity.get_mut("components") else {
+            continue;
+        };
+        for (component, value) in entity_components(original) {
+            let key = (component, value.to_string());
+            if usage.get(&key).is_none_or(|&count| count < 2) {
+                continue;
+            }
+            let entity_count = usage[&key];
+            let index = *shared_index.entry(key).or_insert_with(|| {
+                shared_values.push(json!({
+                    "component": component,
+                    "value": value,
+                    "entity_count": entity_count,
+                }));
+                shared_values.len() - 1
+            });
+            components.insert(component.to_string(), json!({ SHARED_REF_KEY: index }));
+        }
+    }
+
+    let shared_count = shared_values.len();
+    Some((
+        json!({
+            "entities": compacted,
+            "shared_values": shared_values,
+        }),
+        shared_count,
+    ))
+}
+
+/// `(component type path, value)` pairs of a query result entity
+fn entity_components(entity: &Value) -> impl Iterator<Item = (&str, &Value)> {
+    entity
+        .get("components")
+        .and_then(Value::as_object)
+        .into_iter()
+        .flat_map(Map::iter)
+        .map(|(component, value)| (component.as_str(), value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shares_repeated_values_and_keeps_unique_ones_inline() {
+        let sprite = json!({"color": {"Srgba": {"red": 1.0, "green": 1.0, "blue": 1.0}}});
+        let result = json!([
+            {"entity": 1, "components": {"Sprite": sprite, "Transform": {"translation": [0, 0, 0]}}},
+            {"entity": 2, "components": {"Sprite": sprite, "Transform": {"translation": [1, 0, 0]}}},
+            {"entity": 3, "components": {"Sprite": sprite, "Visibility": "Inherited"}},
+        ]);
+
+        let Some((compacted, shared_count)) = compact_query_result(&result) else {
+            unreachable!("a query result is a list of entities");
+        };
+
+        assert_eq!(shared_count, 1);
+        assert_eq!(compacted["shared_values"][0]["value"], sprite);
+        assert_eq!(compacted["shared_values"][0]["entity_count"], 3);
+        let entities = &compacted["entities"];
+        assert_eq!(entities[2]["components"]["Sprite"], json!({"$shared": 0}));
+        assert_eq!(
+            entities[1]["components"]["Transform"],
+            json!({"translation": [1, 0, 0]})
+        );
+        assert_eq!(entities[2]["components"]["Visibility"], "Inherited");
+    }
+
+    #[test]
+    fn strips_the_compact_flag_from_query_requests_only() {
+        let params = json!({"data": {"components": []}, "compact": true});
+
+        let stripped = without_compact_flag(BrpMethod::WorldQuery, Some(&params));
+
+        assert_eq!(stripped, Some(json!({"data": {"components": []}})));
+        assert_eq!(
+            without_compact_flag(BrpMethod::WorldGetComponents, Some(&params)),
+            None
+        );
+    }
+}
@@ -0,0 +1,104 @@
+//! Compressed BRP responses
+//!
+//! `registry.schema` and large query results are mostly repeated type paths and field names, and
+//! shrink several times under gzip. Requests ask for `gzip` or `deflate`; servers that don't
+//! compress - Bevy's own `RemoteHttpPlugin` - ignore the header and answer in plain JSON.
+//! Compressed bodies are inflated here, and their sizes on the wire and after decompression are
+//! recorded for the tool call's response metadata.
+
+use std::io::Read;
+
+use flate2::read::GzDecoder;
+use flate2::read::ZlibDecoder;
+use reqwest::header::CONTENT_ENCODING;
+use serde::Serialize;
+
+use super::call_report;
+use crate::error::Error;
+use crate::error::Result;
+
+/// `Accept-Encoding` sent with requests whose responses may be large
+pub const ACCEPT_ENCODING: &str = "gzip, deflate";
+
+/// Bytes received compressed during a tool call, and their size once decompressed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct TransferSizes {
+    /// Bytes received on the wire
+    pub compressed_bytes:   usize,
+    /// Bytes after decompression
+    pub uncompressed_bytes: usize,
+}
+
+fn record(compressed_bytes: usize, uncompressed_bytes: usize) {
+    call_report::record(|report| {
+        let total = report.transfer_sizes.get_or_insert_default();
+        total.compressed_bytes += compressed_bytes;
+        total.uncompressed_bytes += uncompressed_bytes;
+    });
+}
+
+/// Read a response body, decompressing it according to its `Content-Encoding`
+pub async fn read_body(response: reqwest::Response) -> Result<Vec<u8>> {
+    let encoding = response
+        .headers()
+        .get(CONTENT_ENCODING)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.trim().to_ascii_lowercase());
+    let body = response
+        .bytes()
+        .await
+        .map_err(|e| Error::failed_to("read the BRP response body", &e))?;
+
+    let decoded = decompress(encoding.as_deref(), &body)?;
+    if encoding.is_some_and(|encoding| encoding != "identity") {
+        record(body.len(), decoded.len());
+    }
+    Ok(decoded)
+}
+
+fn decompress(encoding: Option<&str>, body: &[u8]) -> Result<Vec<u8>> {
+    let mut decoded = Vec::new();
+    let inflated = match encoding {
+        None | Some("identity") => return Ok(body.to_vec()),
+        Some("gzip") => GzDecoder::new(body).read_to_end(&mut decoded),
+        Some("deflate") => ZlibDecoder::new(body).read_to_end(&mut decoded),
+        Some(other) => {
+            return Err(Error::JsonRpc(format!("Unsupported response encoding '{other}'")).into());
+        },
+    };
+    inflated.map_err(|e| Error::failed_to("decompress the BRP response", &e))?;
+    Ok(decoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use flate2::Compression;
+    use flate2::write::GzEncoder;
+    use flate2::write::ZlibEncoder;
+
+    use super::*;
+
+    #[test]
+    fn inflates_gzip_and_deflate_bodies() {
+        let body = br#"{"jsonrpc":"2.0","id":1,"result":{"bevy_transform::components::transform::Transform":{}}}"#;
+
+        let mut gzip = GzEncoder::new(Vec::new(), Compression::default());
+        let mut deflate = ZlibEncoder::new(Vec::new(), Compression::default());
+        let gzip = gzip.write_all(body).and_then(|()| gzip.finish());
+        let deflate = deflate.write_all(body).and_then(|()| deflate.finish());
+
+        for (encoding, compressed) in [("gzip", gzip), ("deflate", deflate)] {
+            let decoded = compressed
+                .map_err(|e| Error::failed_to("compress", &e).into())
+                .and_then(|compressed| decompress(Some(encoding), &compressed));
+            assert!(
+                decoded.as_ref().is_ok_and(|decoded| decoded == body),
+                "{encoding}: {decoded:?}"
+            );
+        }
+        assert!(decompress(Some("br"), body).is_err());
+        assert!(decompress(None, body).is_ok_and(|decoded| decoded == body));
+    }
+}
@@ -24,6 +24,8 @@ pub const BRP_EXTRAS_PREFIX: &str = "brp_extras/";
 // ERROR CONSTANTS
 // ============================================================================
 
+/// BRP error code for an entity that doesn't exist - "Entity ... not found"
+pub const BRP_ERROR_CODE_ENTITY_NOT_FOUND: i32 = -23_401;
 /// BRP error code for invalid request - can occur under multiple circumstances including
 /// The underlying error is generally something like "Unknown component type" which our code will
 /// turn into one of the following depending on what is happening:
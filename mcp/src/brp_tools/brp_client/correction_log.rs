@@ -0,0 +1,254 @@
+//! Session log of the format corrections `BrpClient` applied
+//!
+//! A corrected request succeeds without the caller ever seeing what it sent wrong, so the same
+//! mistake repeats - and a correction that changed the meaning of a value (a string read as a
+//! number, an enum variant matched case-insensitively) goes unnoticed. Every correction in a
+//! request that succeeded after correction is recorded here, with the value as sent, the value
+//! that was accepted and the rule that rewrote it. `brp_list_corrections` reads the log.
+
+use std::collections::HashSet;
+use std::collections::VecDeque;
+use std::sync::LazyLock;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use std::sync::PoisonError;
+
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+
+use super::format_correction::Corrector;
+use crate::brp_tools::Port;
+use crate::tool::BrpMethod;
+
+/// Most recent corrections kept - older ones are dropped first
+pub const MAX_CORRECTION_EVENTS: usize = 1000;
+
+/// Fields of a `format_corrections` entry holding the value as sent, by entry kind
+const ORIGINAL_FIELDS: &[&str] = &["original_value", "original_type"];
+
+/// Fields of a `format_corrections` entry holding the corrected value, by entry kind
+const CORRECTED_FIELDS: &[&str] = &["corrected_value", "corrected_type"];
+
+/// Corrections recorded this session, oldest first
+static CORRECTION_LOG: LazyLock<Mutex<CorrectionLog>> =
+    LazyLock::new(|| Mutex::new(CorrectionLog::default()));
+
+/// One correction applied to a request that then succeeded
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct CorrectionEvent {
+    /// Position in the session's log, counting every correction ever recorded
+    pub sequence:  u64,
+    /// When the corrected request succeeded
+    pub timestamp: String,
+    /// Port of the app the request went to
+    pub port:      u16,
+    /// BRP method of the request
+    pub method:    String,
+    /// Rule that rewrote the value
+    pub rule:      Corrector,
+    /// Component or resource the value belongs to, when the rule rewrote a value
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub type_name: Option<String>,
+    /// Path of the value within the component or resource
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path:      Option<String>,
+    /// The value or type path as sent
+    pub original:  Value,
+    /// The value or type path the app accepted
+    pub corrected: Value,
+    /// Why the rule rewrote it
+    pub reason:    String,
+}
+
+/// The corrections a listing returned
+#[derive(Debug, Default)]
+pub struct CorrectionListing {
+    /// The most recent matching corrections, oldest first
+    pub listed: Vec<CorrectionEvent>,
+    /// Number of matching corrections, before the limit
+    pub total:  usize,
+}
+
+/// The log behind `CORRECTION_LOG`
+#[derive(Debug, Default)]
+struct CorrectionLog {
+    events:        VecDeque<CorrectionEvent>,
+    next_sequence: u64,
+}
+
+impl CorrectionLog {
+    fn record(&mut self, port: Port, method: BrpMethod, rule: Corrector, correction: &Value) {
+        let field = |names: &[&str]| {
+            names
+                .iter()
+                .find_map(|name| correction.get(*name))
+                .cloned()
+                .unwrap_or(Value::Null)
+        };
+        let text = |name: &str| {
+            correction
+                .get(name)
+                .and_then(Value::as_str)
+                .map(ToString::to_string)
+        };
+
+        self.events.push_back(CorrectionEvent {
+            sequence: self.next_sequence,
+            timestamp: chrono::Local::now().to_rfc3339(),
+            port: port.0,
+            method: method.as_str().to_string(),
+            rule,
+            type_name: text("type"),
+            path: text("path"),
+            original: field(ORIGINAL_FIELDS),
+            corrected: field(CORRECTED_FIELDS),
+            reason: text("reason").unwrap_or_default(),
+        });
+        self.next_sequence += 1;
+        while self.events.len() > MAX_CORRECTION_EVENTS {
+            self.events.pop_front();
+        }
+    }
+
+    fn list(
+        &mut self,
+        port: Option<Port>,
+        rule: Option<Corrector>,
+        limit: usize,
+        drain: bool,
+    ) -> CorrectionListing {
+        let mut listed: Vec<CorrectionEvent> = self
+            .events
+            .iter()
+            .filter(|event| port.is_none_or(|port| event.port == port.0))
+            .filter(|event| rule.is_none_or(|rule| event.rule == rule))
+            .cloned()
+            .collect();
+        let total = listed.len();
+        let listed = listed.split_off(total.saturating_sub(limit));
+        if drain {
+            let drained: HashSet<u64> = listed.iter().map(|event| event.sequence).collect();
+            self.events
+                .retain(|event| !drained.contains(&event.sequence));
+        }
+        CorrectionListing { listed, total }
+    }
+}
+
+/// Record the `format_corrections` entries of a request to `port` that succeeded after
+/// correction, each with the rule that produced it
+pub fn record_corrections<'a>(
+    port: Port,
+    method: BrpMethod,
+    corrections: impl IntoIterator<Item = (Corrector, &'a Value)>,
+) {
+    let mut log = correction_log();
+    for (rule, correction) in corrections {
+        log.record(port, method, rule, correction);
+    }
+}
+
+/// The most recent `limit` recorded corrections, optionally only those for `port` or by `rule` -
+/// removed from the log when `drain` is set, leaving every correction that wasn't returned
+pub fn corrections(
+    port: Option<Port>,
+    rule: Option<Corrector>,
+    limit: usize,
+    drain: bool,
+) -> CorrectionListing {
+    correction_log().list(port, rule, limit, drain)
+}
+
+/// The log - a panic while it was held leaves at most one event half-built, so poisoning is
+/// ignored
+fn correction_log() -> MutexGuard<'static, CorrectionLog> {
+    CORRECTION_LOG
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn records_value_and_type_corrections_and_drops_the_oldest() {
+        let mut log = CorrectionLog::default();
+        let port = Port(15702);
+        log.record(
+            port,
+            BrpMethod::WorldInsertComponents,
+            Corrector::NumericStrings,
+            &json!({
+                "type": "my_game::Health",
+                "path": ".current",
+                "original_value": "42",
+                "corrected_value": 42,
+                "reason": "numeric string converted for a number field",
+            }),
+        );
+        log.record(
+            port,
+            BrpMethod::WorldGetComponents,
+            Corrector::ShortNames,
+            &json!({
+                "original_type": "Transform",
+                "corrected_type": "bevy_transform::components::transform::Transform",
+                "reason": "short type name resolved",
+            }),
+        );
+
+        let [value, type_path] = [&log.events[0], &log.events[1]];
+        assert_eq!(value.original, json!("42"));
+        assert_eq!(value.corrected, json!(42));
+        assert_eq!(value.path.as_deref(), Some(".current"));
+        assert_eq!(type_path.original, json!("Transform"));
+        assert_eq!(type_path.type_name, None);
+        assert_eq!(type_path.sequence, 1);
+
+        for _ in 0..MAX_CORRECTION_EVENTS {
+            log.record(
+                port,
+                BrpMethod::WorldQuery,
+                Corrector::TypeAliases,
+                &json!({}),
+            );
+        }
+        assert_eq!(log.events.len(), MAX_CORRECTION_EVENTS);
+        assert_eq!(log.events.front().map(|event| event.sequence), Some(2));
+    }
+
+    #[test]
+    fn listing_filters_limits_and_drains_only_what_it_returned() {
+        let mut log = CorrectionLog::default();
+        for (port, rule) in [
+            (15702, Corrector::NumericStrings),
+            (15702, Corrector::ShortNames),
+            (15703, Corrector::NumericStrings),
+            (15702, Corrector::NumericStrings),
+        ] {
+            log.record(Port(port), BrpMethod::WorldQuery, rule, &json!({}));
+        }
+        let sequences = |listing: &CorrectionListing| {
+            listing
+                .listed
+                .iter()
+                .map(|event| event.sequence)
+                .collect::<Vec<_>>()
+        };
+
+        let by_port = log.list(Some(Port(15702)), None, 10, false);
+        assert_eq!((sequences(&by_port), by_port.total), (vec![0, 1, 3], 3));
+        let by_rule = log.list(None, Some(Corrector::NumericStrings), 10, false);
+        assert_eq!(sequences(&by_rule), vec![0, 2, 3]);
+
+        let latest = log.list(None, Some(Corrector::NumericStrings), 2, true);
+        assert_eq!((sequences(&latest), latest.total), (vec![2, 3], 3));
+        let left: Vec<u64> = log.events.iter().map(|event| event.sequence).collect();
+        assert_eq!(left, vec![0, 1]);
+    }
+}
@@ -0,0 +1,116 @@
+//! Enum variant casing correction for BRP requests
+//!
+//! Reflected enum variants must be written exactly as declared (`"Visible"`, `{"Srgba": ..}`), but
+//! agents often write them in another case - `"visible"`, `"not_found"`, `{"srgba": ..}`. When a
+//! spawn, insert or mutate request fails with a format error, the `enum_casing` step of the
+//! format-correction pipeline renames variants that match exactly one declared variant when case,
+//! `_` and `-` are ignored.
+
+use std::collections::HashMap;
+
+use serde_json::Map;
+use serde_json::Value;
+
+use super::enum_variant::short_name;
+use super::typed_values;
+use super::typed_values::Rewrite;
+use crate::brp_tools::brp_type_guide::TypeGuideEngine;
+use crate::tool::BrpMethod;
+
+/// Rewrite miscased enum variant names in the component or resource values of a spawn, insert or
+/// mutate request, returning the corrected parameters and what was renamed
+pub fn correct_enum_casing(
+    method: BrpMethod,
+    params: &Value,
+    engine: &TypeGuideEngine,
+) -> Option<(Value, Vec<Rewrite>)> {
+    // Building a guide is not free - look up each enum's variants once per request
+    let mut variants_by_path: HashMap<(String, String), Vec<String>> = HashMap::new();
+
+    typed_values::rewrite_typed_values(method, params, engine, |typed| {
+        let written = match typed.value {
+            Value::String(name) => name.as_str(),
+            Value::Object(map) if map.len() == 1 => map.keys().next()?.as_str(),
+            _ => return None,
+        };
+
+        typed.candidates.iter().find_map(|(path, _)| {
+            let variants = variants_by_path
+                .entry((typed.root.as_str().to_string(), (*path).to_string()))
+                .or_insert_with(|| variant_names(engine, typed.root.as_str(), path));
+            let declared = match_variant(written, variants)?;
+            Some(match typed.value {
+                Value::Object(map) => {
+                    let mut renamed = Map::new();
+                    renamed.insert(declared.to_string(), map.values().next()?.clone());
+                    Value::Object(renamed)
+                },
+                _ => Value::String(declared.to_string()),
+            })
+        })
+    })
+}
+
+/// Short names of the variants of the enum at `path` of `type_name` - empty if it isn't an enum
+fn variant_names(engine: &TypeGuideEngine, type_name: &str, path: &str) -> Vec<String> {
+    engine
+        .variant_examples(type_name, path)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|group| group.get("applicable_variants")?.as_array())
+        .flatten()
+        .filter_map(Value::as_str)
+        .map(|name| short_name(name).to_string())
+        .collect()
+}
+
+/// The declared variant `written` refers to, when it isn't already written exactly and matches
+/// one variant unambiguously once case, `_` and `-` are ignored
+fn match_variant<'a>(written: &str, variants: &'a [String]) -> Option<&'a str> {
+    if variants.iter().any(|variant| variant == written) {
+        return None;
+    }
+    let fold = |name: &str| -> String {
+        name.chars()
+            .filter(|c| !matches!(c, '_' | '-'))
+            .map(|c| c.to_ascii_lowercase())
+            .collect()
+    };
+    let folded = fold(written);
+    let mut matches = variants
+        .iter()
+        .filter(|variant| fold(variant.as_str()) == folded);
+    let declared = matches.next()?;
+    matches.next().is_none().then_some(declared.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn variants() -> Vec<String> {
+        ["Visible", "Hidden", "NotFound", "Inherited"]
+            .into_iter()
+            .map(String::from)
+            .collect()
+    }
+
+    #[test]
+    fn matches_variants_ignoring_case_and_separators() {
+        assert_eq!(match_variant("visible", &variants()), Some("Visible"));
+        assert_eq!(match_variant("not_found", &variants()), Some("NotFound"));
+        assert_eq!(match_variant("NOT-FOUND", &variants()), Some("NotFound"));
+    }
+
+    #[test]
+    fn leaves_exact_and_unknown_names_alone() {
+        assert_eq!(match_variant("Hidden", &variants()), None);
+        assert_eq!(match_variant("invisible", &variants()), None);
+    }
+
+    #[test]
+    fn skips_ambiguous_names() {
+        let variants = vec!["Foo".to_string(), "FOO".to_string()];
+        assert_eq!(match_variant("foo", &variants), None);
+    }
+}
@@ -0,0 +1,237 @@
+//! `set_enum_variant` mode for the mutate tools
+//!
+//! Switching an enum field to another variant means writing the variant's whole payload, which
+//! agents rarely get right from memory. When a `world.mutate_components` or
+//! `world.mutate_resources` call names a `variant` instead of (or alongside) a `value`, the type
+//! guide's example for that variant is used as the payload, with any fields given in `value`
+//! written over it, and the request is sent as an ordinary mutation.
+
+use serde_json::Map;
+use serde_json::Value;
+
+use crate::brp_tools::Port;
+use crate::brp_tools::brp_type_guide::TypeGuideEngine;
+use crate::error::Error;
+use crate::error::Result;
+use crate::tool::BrpMethod;
+use crate::tool::ParameterName;
+
+/// Rewrite a mutate request that names a `variant` into one carrying the full variant `value`
+///
+/// Returns `None` when the request isn't a mutation or doesn't name a variant, so it can be sent
+/// unchanged.
+pub async fn expand_variant(
+    method: BrpMethod,
+    port: Port,
+    params: Option<&Value>,
+) -> Result<Option<Value>> {
+    let type_field = match method {
+        BrpMethod::WorldMutateComponents => ParameterName::Component,
+        BrpMethod::WorldMutateResources => ParameterName::Resource,
+        _ => return Ok(None),
+    };
+    let Some(mut params) = params.and_then(Value::as_object).cloned() else {
+        return Ok(None);
+    };
+    let Some(variant) = params.remove(ParameterName::Variant.as_ref()) else {
+        return Ok(None);
+    };
+    let Some(variant) = variant.as_str() else {
+        return Err(Error::invalid("variant", "expected a variant name string").into());
+    };
+
+    let type_name = params
+        .get(type_field.as_ref())
+        .and_then(Value::as_str)
+        .ok_or_else(|| Error::missing(type_field.as_ref()))?;
+    let path = params
+        .get(ParameterName::Path.as_ref())
+        .and_then(Value::as_str)
+        .map(normalize_path)
+        .unwrap_or_default();
+
+    let engine = TypeGuideEngine::new(port).await?;
+    let Some(groups) = engine.variant_examples(type_name, &path) else {
+        return Err(Error::invalid(
+            "path",
+            format!("'{path}' of {type_name} is not an enum - `variant` needs a path to an enum"),
+        )
+        .into());
+    };
+
+    let value = build_variant_value(variant, &groups, params.get(ParameterName::Value.as_ref()))?;
+    params.insert(ParameterName::Value.as_ref().to_string(), value);
+
+    Ok(Some(Value::Object(params)))
+}
+
+/// Mutation paths in the type guide start with `.` or `[`, so `translation.x` is looked up as
+/// `.translation.x`
+pub fn normalize_path(path: &str) -> String {
+    if path.is_empty() || path.starts_with('.') || path.starts_with('[') {
+        path.to_string()
+    } else {
+        format!(".{path}")
+    }
+}
+
+/// `Color::Srgba` → `Srgba`
+pub fn short_name(variant: &str) -> &str {
+    variant.rsplit_once("::").map_or(variant, |(_, name)| name)
+}
+
+/// Build the value for `variant` from the type guide's variant `groups`, with `overrides` from the
+/// request written over the example payload
+fn build_variant_value(
+    variant: &str,
+    groups: &[Value],
+    overrides: Option<&Value>,
+) -> Result<Value> {
+    let wanted = short_name(variant);
+    let variant_names = |group: &Value| -> Vec<String> {
+        group
+            .get("applicable_variants")
+            .and_then(Value::as_array)
+            .map(|names| {
+                names
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .map(|name| short_name(name).to_string())
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+
+    let Some(group) = groups
+        .iter()
+        .find(|group| variant_names(group).iter().any(|name| name == wanted))
+    else {
+        let known: Vec<String> = groups.iter().flat_map(variant_names).collect();
+        return Err(Error::invalid(
+            "variant",
+            format!(
+                "'{wanted}' is not a variant of this enum - expected one of: {}",
+                known.join(", ")
+            ),
+        )
+        .into());
+    };
+
+    let Some(example) = group.get("example") else {
+        return Err(Error::invalid(
+            "variant",
+            format!("'{wanted}' can't be built through BRP - its payload isn't mutable"),
+        )
+        .into());
+    };
+
+    // Variants sharing a signature share one example, written with the group's first variant
+    let payload = match example {
+        Value::Object(object) if object.len() == 1 => object.values().next().cloned(),
+        _ => None,
+    };
+
+    match (payload, overrides) {
+        (None, None) => Ok(Value::String(wanted.to_string())),
+        (None, Some(_)) => Err(Error::invalid(
+            "value",
+            format!("'{wanted}' is a unit variant and takes no value"),
+        )
+        .into()),
+        (Some(payload), None) => Ok(wrap(wanted, payload)),
+        (Some(Value::Object(mut fields)), Some(Value::Object(overrides))) => {
+            if let Some(unknown) = overrides.keys().find(|key| !fields.contains_key(*key)) {
+                let known: Vec<&str> = fields.keys().map(String::as_str).collect();
+                return Err(Error::invalid(
+                    "value",
+                    format!(
+                        "'{wanted}' has no field '{unknown}' - expected fields: {}",
+                        known.join(", ")
+                    ),
+                )
+                .into());
+            }
+            fields.extend(overrides.clone());
+            Ok(wrap(wanted, Value::Object(fields)))
+        },
+        (Some(_), Some(value)) => Ok(wrap(wanted, value.clone())),
+    }
+}
+
+/// `{"<variant>": <payload>}`
+fn wrap(variant: &str, payload: Value) -> Value {
+    let mut object = Map::new();
+    object.insert(variant.to_string(), payload);
+    Value::Object(object)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn groups() -> Vec<Value> {
+        vec![
+            json!({
+                "applicable_variants": ["Shape::Empty"],
+                "example": "Empty",
+                "signature": "unit",
+                "mutability": "mutable",
+            }),
+            json!({
+                "applicable_variants": ["Shape::Circle", "Shape::Square"],
+                "example": { "Circle": 1.0 },
+                "signature": { "tuple": ["f32"] },
+                "mutability": "mutable",
+            }),
+            json!({
+                "applicable_variants": ["Shape::Rect"],
+                "example": { "Rect": { "width": 1.0, "height": 2.0 } },
+                "signature": { "struct": { "width": "f32", "height": "f32" } },
+                "mutability": "mutable",
+            }),
+            json!({
+                "applicable_variants": ["Shape::Mesh"],
+                "signature": { "tuple": ["Handle<Mesh>"] },
+                "mutability": "not_mutable",
+            }),
+        ]
+    }
+
+    #[test]
+    fn fills_payload_from_example() {
+        let value = build_variant_value("Square", &groups(), None);
+        assert_eq!(value.ok(), Some(json!({ "Square": 1.0 })));
+
+        let value = build_variant_value("Shape::Empty", &groups(), None);
+        assert_eq!(value.ok(), Some(json!("Empty")));
+    }
+
+    #[test]
+    fn merges_struct_fields() {
+        let value = build_variant_value("Rect", &groups(), Some(&json!({ "height": 5.0 })));
+        assert_eq!(
+            value.ok(),
+            Some(json!({ "Rect": { "width": 1.0, "height": 5.0 } }))
+        );
+
+        let value = build_variant_value("Rect", &groups(), Some(&json!({ "depth": 5.0 })));
+        assert!(value.is_err());
+    }
+
+    #[test]
+    fn rejects_unknown_unmutable_and_unit_with_value() {
+        assert!(build_variant_value("Triangle", &groups(), None).is_err());
+        assert!(build_variant_value("Mesh", &groups(), None).is_err());
+        assert!(build_variant_value("Empty", &groups(), Some(&json!(1))).is_err());
+    }
+
+    #[test]
+    fn normalizes_paths() {
+        assert_eq!(normalize_path(""), "");
+        assert_eq!(normalize_path("mode"), ".mode");
+        assert_eq!(normalize_path(".mode"), ".mode");
+        assert_eq!(normalize_path("[0]"), "[0]");
+    }
+}
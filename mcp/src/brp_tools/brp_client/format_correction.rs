@@ -0,0 +1,299 @@
+//! Format-correction pipeline
+//!
+//! When a BRP request fails with a format error, the client runs a pipeline of named correctors
+//! over the request parameters. Each corrector proposes corrected requests; the client retries
+//! them in turn and stops at the first that succeeds. A corrector whose proposals all fail still
+//! hands its best guess on to the next one, so a request with both a short type name and a color
+//! shorthand gets both fixed.
+//!
+//! Callers choose the correctors per call with `correctors` (default: all, in pipeline order);
+//! the ones that fired are reported in `format_correctors`.
+
+use std::str::FromStr;
+
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+use strum::Display;
+use strum::EnumIter;
+use strum::EnumString;
+use strum::IntoEnumIterator;
+
+use super::color_correction;
+use super::enum_casing;
+use super::math_shorthand;
+use super::numeric_strings;
+use super::short_name_cache;
+use super::type_alias_correction;
+use super::typed_values;
+use crate::brp_tools::Port;
+use crate::brp_tools::brp_type_guide::TypeGuideEngine;
+use crate::error::Result;
+use crate::tool::BrpMethod;
+use crate::tool::ParameterName;
+use crate::tool::strict_formats;
+
+/// Note appended to the description of every tool that accepts `correctors`
+pub const FORMAT_CORRECTION_NOTE: &str =
+    include_str!("../../../help_text/notes/format_correction.txt");
+
+/// A named step of the format-correction pipeline, listed in the order the pipeline runs them
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+    Display,
+    EnumIter,
+    EnumString,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum Corrector {
+    /// Type paths from other Bevy releases replaced with the path the app registers
+    TypeAliases,
+    /// Short type names (`Transform`) resolved to the one full type path registered under them
+    ShortNames,
+    /// `{"x": .., "y": .., "z": ..}` written as an array for vector and quaternion fields
+    MathShorthand,
+    /// Enum variants written in another case (`"visible"`) renamed to the declared variant
+    EnumCasing,
+    /// CSS-style colors (`"#ff8800"`, `"rgb(..)"`, `"orange"`) converted to reflected colors
+    ColorFormats,
+    /// Numbers and booleans sent as strings converted for number and `bool` fields
+    NumericStrings,
+}
+
+/// A corrected request and the `format_corrections` entries describing what was changed
+#[derive(Debug, Clone)]
+pub struct Candidate {
+    pub params:      Value,
+    pub corrections: Vec<Value>,
+}
+
+/// State shared by the correctors of one pipeline run - the registry is fetched at most once
+pub struct CorrectionContext {
+    method: BrpMethod,
+    port:   Port,
+    engine: Option<TypeGuideEngine>,
+}
+
+impl CorrectionContext {
+    pub const fn new(method: BrpMethod, port: Port) -> Self {
+        Self {
+            method,
+            port,
+            engine: None,
+        }
+    }
+
+    async fn engine(&mut self) -> Result<&TypeGuideEngine> {
+        let engine = match self.engine.take() {
+            Some(engine) => engine,
+            None => TypeGuideEngine::new(self.port).await?,
+        };
+        Ok(self.engine.insert(engine))
+    }
+}
+
+impl Corrector {
+    /// Corrected versions of `params`, best guess first - empty when there is nothing to correct
+    ///
+    /// # Errors
+    /// Returns an error if the schema the correction needs cannot be fetched from the app.
+    pub async fn candidates(
+        self,
+        context: &mut CorrectionContext,
+        params: &Value,
+    ) -> Result<Vec<Candidate>> {
+        match self {
+            Self::TypeAliases => Ok(type_alias_candidates(params)),
+            Self::ShortNames => short_name_candidates(context.port, params).await,
+            Self::MathShorthand | Self::EnumCasing | Self::ColorFormats | Self::NumericStrings => {
+                self.value_candidates(context, params).await
+            },
+        }
+    }
+
+    /// Candidates of the correctors that rewrite component and resource values
+    async fn value_candidates(
+        self,
+        context: &mut CorrectionContext,
+        params: &Value,
+    ) -> Result<Vec<Candidate>> {
+        // Check for something to correct before paying for the registry
+        let applies = typed_values::has_typed_values(context.method)
+            && match self {
+                Self::MathShorthand => math_shorthand::contains_math_shorthand(params),
+                Self::ColorFormats => color_correction::contains_color_shorthand(params),
+                Self::NumericStrings => numeric_strings::contains_numeric_string(params),
+                _ => true,
+            };
+        if !applies {
+            return Ok(Vec::new());
+        }
+
+        let method = context.method;
+        let engine = context.engine().await?;
+        let (reason, rewritten) = match self {
+            Self::MathShorthand => (
+                "vector or quaternion object written as the reflected array",
+                math_shorthand::convert_math_shorthand(method, params, engine),
+            ),
+            Self::EnumCasing => (
+                "enum variant renamed to the declared variant",
+                enum_casing::correct_enum_casing(method, params, engine),
+            ),
+            Self::ColorFormats => (
+                "color shorthand converted to the reflected color representation",
+                color_correction::convert_colors(method, params, engine),
+            ),
+            Self::NumericStrings => (
+                "string converted to the number or boolean the field expects",
+                numeric_strings::convert_numeric_strings(method, params, engine),
+            ),
+            Self::TypeAliases | Self::ShortNames => return Ok(Vec::new()),
+        };
+
+        Ok(rewritten
+            .map(|(params, rewrites)| Candidate {
+                params,
+                corrections: rewrites
+                    .iter()
+                    .map(|rewrite| rewrite.to_correction(reason))
+                    .collect(),
+            })
+            .into_iter()
+            .collect())
+    }
+}
+
+/// One candidate per alias combination - each aliased name tries its aliases in turn
+fn type_alias_candidates(params: &Value) -> Vec<Candidate> {
+    let aliased = type_alias_correction::find_aliased_type_names(params);
+    let attempts = aliased
+        .iter()
+        .map(|(_, aliases)| aliases.len())
+        .max()
+        .unwrap_or(0);
+
+    (0..attempts)
+        .map(|attempt| {
+            let substitutions = type_alias_correction::substitutions_for_attempt(&aliased, attempt);
+            Candidate {
+                params:      type_alias_correction::apply_substitutions(params, &substitutions),
+                corrections: substitutions
+                    .iter()
+                    .map(type_alias_correction::TypeAliasSubstitution::to_correction)
+                    .collect(),
+            }
+        })
+        .collect()
+}
+
+/// Short type names replaced by the full type paths registered under them
+async fn short_name_candidates(port: Port, params: &Value) -> Result<Vec<Candidate>> {
+    if !short_name_cache::has_short_names(params) {
+        return Ok(Vec::new());
+    }
+
+    let index = short_name_cache::index_for_port(port).await?;
+    let resolutions = short_name_cache::resolutions(params, &index);
+    if resolutions.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    Ok(vec![Candidate {
+        params:      short_name_cache::apply_resolutions(params, &resolutions),
+        corrections: resolutions
+            .iter()
+            .map(short_name_cache::ShortNameResolution::to_correction)
+            .collect(),
+    }])
+}
+
+/// The correctors a request asks for with `correctors` - all of them when it doesn't say
+///
+/// Unknown names are ignored; an empty list turns format correction off, as do strict formats,
+/// which return format errors as BRP reported them.
+pub fn requested_correctors(params: Option<&Value>) -> Vec<Corrector> {
+    if strict_formats() {
+        return Vec::new();
+    }
+    let Some(requested) = params
+        .and_then(|params| params.get(ParameterName::Correctors.as_ref()))
+        .and_then(Value::as_array)
+    else {
+        return Corrector::iter().collect();
+    };
+    // Run in pipeline order, whatever order they were listed in
+    let requested: Vec<Corrector> = requested
+        .iter()
+        .filter_map(Value::as_str)
+        .filter_map(|name| Corrector::from_str(name).ok())
+        .collect();
+    Corrector::iter()
+        .filter(|corrector| requested.contains(corrector))
+        .collect()
+}
+
+/// Remove `correctors` from a request - BRP doesn't know it
+///
+/// Returns `None` when the request has no `correctors` field, so it can be sent unchanged.
+pub fn without_correctors_field(params: Option<&Value>) -> Option<Value> {
+    let mut params = params.and_then(Value::as_object).cloned()?;
+    params.remove(ParameterName::Correctors.as_ref())?;
+    Some(Value::Object(params))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::tool::with_strict_formats;
+
+    #[test]
+    fn runs_all_correctors_by_default() {
+        let params = json!({ "entity": 42 });
+        assert_eq!(
+            requested_correctors(Some(&params)),
+            Corrector::iter().collect::<Vec<_>>()
+        );
+        assert_eq!(without_correctors_field(Some(&params)), None);
+    }
+
+    #[test]
+    fn runs_requested_correctors_in_pipeline_order() {
+        let params = json!({
+            "entity": 42,
+            "correctors": ["numeric_strings", "short_names", "not_a_corrector"],
+        });
+        assert_eq!(
+            requested_correctors(Some(&params)),
+            vec![Corrector::ShortNames, Corrector::NumericStrings]
+        );
+        assert_eq!(
+            without_correctors_field(Some(&params)),
+            Some(json!({ "entity": 42 }))
+        );
+        assert!(requested_correctors(Some(&json!({ "correctors": [] }))).is_empty());
+    }
+
+    #[tokio::test]
+    async fn strict_formats_run_no_correctors() {
+        let params = json!({ "entity": 42, "correctors": ["short_names"] });
+        let strict =
+            with_strict_formats(Some(true), async { requested_correctors(Some(&params)) }).await;
+        assert!(strict.is_empty());
+        let lenient =
+            with_strict_formats(Some(false), async { requested_correctors(Some(&params)) }).await;
+        assert_eq!(lenient, vec![Corrector::ShortNames]);
+    }
+}
@@ -4,16 +4,26 @@
 //! It encapsulates all HTTP-related operations including URL building, request sending,
 //! status checking, and response parsing.
 
+use std::borrow::Cow;
 use std::time::Duration;
 
 use serde_json::Value;
 use tracing::debug;
 use tracing::warn;
 
+use super::circuit_breaker::check_circuit;
+use super::circuit_breaker::record_failure;
+use super::circuit_breaker::record_success;
+use super::compression::ACCEPT_ENCODING;
 use super::constants::BRP_DEFAULT_HOST;
 use super::constants::BRP_HTTP_PROTOCOL;
 use super::constants::BRP_JSONRPC_PATH;
 use super::json_rpc_builder::BrpJsonRpcBuilder;
+use super::json_rpc_builder::RequestId;
+use super::method_compat::forget_method_naming;
+use super::read_cache::forget_cached_reads;
+use super::remote_host::remote_host_for;
+use super::wire_encoding::forget_wire_encoding;
 use crate::brp_tools::Port;
 use crate::error::Error;
 use crate::error::Result;
@@ -23,21 +33,63 @@ use crate::tool::ParameterName;
 
 /// HTTP client for BRP communication
 pub struct BrpHttpClient {
-    method: BrpMethod,
-    port:   Port,
-    params: Option<Value>,
+    /// Method name sent on the wire - for known methods this can differ from the `BrpMethod`
+    /// name for apps using legacy names
+    method_name: Cow<'static, str>,
+    port:        Port,
+    params:      Option<Value>,
+    request_id:  RequestId,
+    /// Whether to ask for a compressed response - only for callers that read the body with
+    /// `compression::read_body`
+    compressed:  bool,
 }
 
 impl BrpHttpClient {
     /// Create a new BRP HTTP client
     pub const fn new(method: BrpMethod, port: Port, params: Option<Value>) -> Self {
         Self {
-            method,
+            method_name: Cow::Borrowed(method.as_str()),
             port,
             params,
+            request_id: RequestId::Default,
+            compressed: false,
         }
     }
 
+    /// Create a client for a method this server has no `BrpMethod` for, such as a game's own
+    /// streaming endpoint - the name is sent as-is
+    pub fn for_method_name(
+        method_name: impl Into<Cow<'static, str>>,
+        port: Port,
+        params: Option<Value>,
+    ) -> Self {
+        Self {
+            method_name: method_name.into(),
+            port,
+            params,
+            request_id: RequestId::Default,
+            compressed: false,
+        }
+    }
+
+    /// Override the JSON-RPC id sent with the request
+    pub fn with_request_id(mut self, request_id: RequestId) -> Self {
+        self.request_id = request_id;
+        self
+    }
+
+    /// Ask for a gzip or deflate compressed response (see `compression`)
+    pub const fn with_compression(mut self) -> Self {
+        self.compressed = true;
+        self
+    }
+
+    /// Override the method name sent on the wire (see `method_compat`)
+    pub fn with_method_name(mut self, method_name: &'static str) -> Self {
+        self.method_name = Cow::Borrowed(method_name);
+        self
+    }
+
     /// Build the BRP URL for this client's port
     fn build_url(&self) -> String {
         format!(
@@ -48,8 +100,8 @@ impl BrpHttpClient {
 
     /// Build the JSON-RPC request body for this client
     fn build_request_body(&self) -> String {
-        let method_str = self.method.as_str();
-        let mut builder = BrpJsonRpcBuilder::new(method_str);
+        let mut builder =
+            BrpJsonRpcBuilder::new(self.method_name.as_ref()).id(self.request_id.clone());
         if let Some(ref params) = self.params {
             debug!(
                 "BRP execute_brp_method: Added params - {}",
@@ -63,17 +115,20 @@ impl BrpHttpClient {
 
     /// Send an HTTP request with timeout
     pub async fn send_request(&self) -> Result<reqwest::Response> {
+        check_circuit(self.port)?;
         let url = self.build_url();
         let body = self.build_request_body();
         let client = reqwest::Client::new();
 
-        let response = client
+        let mut request = client
             .post(&url)
             .header("Content-Type", "application/json")
             .body(body.clone())
-            .timeout(Duration::from_secs(30))
-            .send()
-            .await;
+            .timeout(Duration::from_secs(30));
+        if self.compressed {
+            request = request.header("Accept-Encoding", ACCEPT_ENCODING);
+        }
+        let response = request.send().await;
 
         let response = match response {
             Ok(resp) => resp,
@@ -87,6 +142,7 @@ impl BrpHttpClient {
 
     /// Send an HTTP request for streaming (no timeout)
     pub async fn send_streaming_request(&self) -> Result<reqwest::Response> {
+        check_circuit(self.port)?;
         let url = self.build_url();
         let body = self.build_request_body();
         // Create client with no timeout for streaming
@@ -111,6 +167,8 @@ impl BrpHttpClient {
 
     /// Check if the HTTP response status is successful
     fn check_status(&self, response: &reqwest::Response) -> Result<()> {
+        // Any answer - even an HTTP error - shows the app is up
+        record_success(self.port);
         if !response.status().is_success() {
             warn!(
                 "BRP execute_brp_method: HTTP status error - status={}",
@@ -126,11 +184,7 @@ impl BrpHttpClient {
                             .canonical_reason()
                             .unwrap_or("Unknown error")
                     ))
-                    .attach(format!(
-                        "Method: {}, Port: {}",
-                        self.method.as_str(),
-                        self.port
-                    )),
+                    .attach(format!("Method: {}, Port: {}", self.method_name, self.port)),
             );
         }
 
@@ -147,13 +201,22 @@ impl BrpHttpClient {
         // Always log HTTP errors to help debug intermittent failures
         warn!("BRP execute_brp_method: HTTP request failed - error={}", e);
 
+        // The app may have gone away - re-detect its method naming when it comes back
+        if e.is_connect() {
+            forget_method_naming(self.port);
+            forget_wire_encoding(self.port);
+            forget_cached_reads(self.port);
+        }
+        if e.is_connect() || e.is_timeout() {
+            record_failure(self.port);
+        }
+
         let error_details = format!(
             "HTTP Error at {}\nMethod: {}\nPort: {}\nURL: {}\nError: {:?}\n",
             std::time::SystemTime::now()
                 .duration_since(std::time::UNIX_EPOCH)
-                .map(|d| d.as_secs())
-                .unwrap_or(0),
-            self.method.as_str(),
+                .map_or(0, |d| d.as_secs()),
+            self.method_name,
             self.port,
             url,
             e
@@ -170,7 +233,7 @@ impl BrpHttpClient {
 
         // Extract additional context from the request body for better error reporting
         let mut context_info = vec![
-            format!("Method: {}", self.method.as_str()),
+            format!("Method: {}", self.method_name),
             format!("Port: {}", self.port),
             format!("URL: {url}"),
         ];
@@ -207,12 +270,23 @@ impl BrpHttpClient {
 
         context_info.push(format!("Error type: {error_type}"));
 
+        // A remote instance is only reachable while its SSH tunnel is up
+        if e.is_connect()
+            && let Some(remote_host) = remote_host_for(self.port)
+        {
+            context_info.push(format!(
+                "Port is forwarded over SSH to '{}' (remote host profile '{}') - the SSH \
+                 connection may have dropped",
+                remote_host.destination, remote_host.profile
+            ));
+        }
+
         // Add port info
         context_info.push(format!("Port: ({})", self.port));
 
         let error_msg = format!(
             "HTTP request failed for {} operation - {error_type}: {e}",
-            self.method.as_str()
+            self.method_name
         );
 
         Err(error_stack::Report::new(Error::JsonRpc(error_msg))
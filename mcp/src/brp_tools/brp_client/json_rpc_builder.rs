@@ -8,6 +8,18 @@ use crate::brp_tools::brp_client::constants::JSONRPC_FIELD_ID;
 use crate::brp_tools::brp_client::constants::JSONRPC_FIELD_METHOD;
 use crate::brp_tools::brp_client::constants::JSONRPC_VERSION;
 
+/// JSON-RPC `id` sent with a request
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum RequestId {
+    /// The fixed id used for regular calls
+    #[default]
+    Default,
+    /// A caller-chosen id (string or number)
+    Custom(Value),
+    /// No id - the request is a notification
+    Notification,
+}
+
 /// Builder for constructing raw JSON-RPC 2.0 requests
 ///
 /// This builder is used for direct HTTP communication with BRP,
@@ -15,7 +27,7 @@ use crate::brp_tools::brp_client::constants::JSONRPC_VERSION;
 pub struct BrpJsonRpcBuilder {
     method: String,
     params: Option<Value>,
-    id:     u64,
+    id:     RequestId,
 }
 
 impl BrpJsonRpcBuilder {
@@ -24,7 +36,7 @@ impl BrpJsonRpcBuilder {
         Self {
             method: method.into(),
             params: None,
-            id:     JSONRPC_DEFAULT_ID,
+            id:     RequestId::Default,
         }
     }
 
@@ -34,14 +46,25 @@ impl BrpJsonRpcBuilder {
         self
     }
 
+    /// Set the request id
+    pub fn id(mut self, id: RequestId) -> Self {
+        self.id = id;
+        self
+    }
+
     /// Build the final JSON-RPC request
     pub fn build(self) -> Value {
         let mut request = json!({
             JSONRPC_FIELD: JSONRPC_VERSION,
             JSONRPC_FIELD_METHOD: self.method,
-            JSONRPC_FIELD_ID: self.id
         });
 
+        match self.id {
+            RequestId::Default => request[JSONRPC_FIELD_ID] = json!(JSONRPC_DEFAULT_ID),
+            RequestId::Custom(id) => request[JSONRPC_FIELD_ID] = id,
+            RequestId::Notification => {},
+        }
+
         if let Some(params) = self.params {
             request[JSONRPC_FIELD_PARAMS] = params;
         } else {
@@ -51,3 +74,26 @@ impl BrpJsonRpcBuilder {
         request
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ids_follow_the_request_id() {
+        let request = |id| BrpJsonRpcBuilder::new("world.query").id(id).build();
+
+        for id in [json!("trace-1"), json!(42)] {
+            assert_eq!(request(RequestId::Custom(id.clone()))[JSONRPC_FIELD_ID], id);
+        }
+        assert_eq!(
+            request(RequestId::Default)[JSONRPC_FIELD_ID],
+            json!(JSONRPC_DEFAULT_ID)
+        );
+        assert!(
+            request(RequestId::Notification)
+                .get(JSONRPC_FIELD_ID)
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,124 @@
+//! Math shorthand conversion for BRP requests
+//!
+//! Bevy reflects vectors and quaternions as plain arrays (`[1.0, 2.0, 3.0]`), but agents often
+//! write them the way they read in Rust - `{"x": 1.0, "y": 2.0, "z": 3.0}`. When a spawn, insert
+//! or mutate request fails with a format error, the `math_shorthand` step of the format-correction
+//! pipeline rewrites such objects into arrays for fields the type guide says are vectors or
+//! quaternions.
+
+use serde_json::Value;
+
+use super::typed_values;
+use super::typed_values::Rewrite;
+use crate::brp_tools::BrpTypeName;
+use crate::brp_tools::brp_type_guide::TypeGuideEngine;
+use crate::tool::BrpMethod;
+
+/// Crates that define the reflected vector and quaternion types
+const MATH_CRATES: &[&str] = &["glam", "bevy_math"];
+
+/// Component names in array order
+pub const AXES: [&str; 4] = ["x", "y", "z", "w"];
+
+/// Whether `params` holds any object of numeric `x`/`y`(/`z`/`w`) fields - checked before
+/// fetching the registry so requests without shorthand don't pay for it
+pub fn contains_math_shorthand(params: &Value) -> bool {
+    match params {
+        Value::Object(map) => {
+            axes_object_len(params).is_some() || map.values().any(contains_math_shorthand)
+        },
+        Value::Array(items) => items.iter().any(contains_math_shorthand),
+        _ => false,
+    }
+}
+
+/// Rewrite `{"x": .., "y": ..}` objects written for vector and quaternion fields into arrays,
+/// returning the corrected parameters and what was converted
+pub fn convert_math_shorthand(
+    method: BrpMethod,
+    params: &Value,
+    engine: &TypeGuideEngine,
+) -> Option<(Value, Vec<Rewrite>)> {
+    typed_values::rewrite_typed_values(method, params, engine, |typed| {
+        let len = axes_object_len(typed.value)?;
+        let is_vector = typed
+            .candidates
+            .iter()
+            .any(|(_, type_name)| vector_len(type_name) == Some(len));
+        if is_vector {
+            to_array(typed.value, len)
+        } else {
+            None
+        }
+    })
+}
+
+/// Number of axes of a vector or quaternion type - `glam::Vec3` has 3, `glam::Quat` 4
+pub fn vector_len(type_name: &BrpTypeName) -> Option<usize> {
+    let (crate_name, name) = type_name.as_str().split_once("::")?;
+    if !MATH_CRATES.contains(&crate_name) {
+        return None;
+    }
+    let name = name.rsplit("::").next()?;
+    if name == "Quat" || name == "DQuat" {
+        return Some(4);
+    }
+    let digits = name.strip_suffix('A').unwrap_or(name);
+    let len = digits
+        .strip_prefix("Vec")
+        .or_else(|| digits.strip_prefix("IVec"))
+        .or_else(|| digits.strip_prefix("UVec"))
+        .or_else(|| digits.strip_prefix("DVec"))?
+        .parse()
+        .ok()?;
+    (2..=4).contains(&len).then_some(len)
+}
+
+/// Number of axes if `value` is an object of exactly the leading axes with numeric values
+fn axes_object_len(value: &Value) -> Option<usize> {
+    let map = value.as_object()?;
+    let len = map.len();
+    ((2..=4).contains(&len)
+        && AXES[..len]
+            .iter()
+            .all(|axis| map.get(*axis).is_some_and(Value::is_number)))
+    .then_some(len)
+}
+
+fn to_array(value: &Value, len: usize) -> Option<Value> {
+    AXES[..len]
+        .iter()
+        .map(|axis| value.get(*axis).cloned())
+        .collect::<Option<Vec<_>>>()
+        .map(Value::Array)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn recognizes_vector_types() {
+        assert_eq!(vector_len(&BrpTypeName::from("glam::Vec3")), Some(3));
+        assert_eq!(vector_len(&BrpTypeName::from("glam::Vec3A")), Some(3));
+        assert_eq!(vector_len(&BrpTypeName::from("glam::UVec2")), Some(2));
+        assert_eq!(vector_len(&BrpTypeName::from("glam::Quat")), Some(4));
+        assert_eq!(vector_len(&BrpTypeName::from("glam::Mat3")), None);
+        assert_eq!(vector_len(&BrpTypeName::from("my_game::Vec3")), None);
+    }
+
+    #[test]
+    fn converts_axes_objects() {
+        let value = json!({ "x": 1.0, "y": 2.0, "z": 3.0 });
+        assert_eq!(axes_object_len(&value), Some(3));
+        assert_eq!(to_array(&value, 3), Some(json!([1.0, 2.0, 3.0])));
+
+        assert_eq!(axes_object_len(&json!({ "x": 1.0, "z": 3.0 })), None);
+        assert_eq!(axes_object_len(&json!({ "x": "1", "y": 2.0 })), None);
+        assert!(contains_math_shorthand(
+            &json!({ "components": { "Transform": { "translation": value } } })
+        ));
+    }
+}
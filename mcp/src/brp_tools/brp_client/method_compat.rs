@@ -0,0 +1,174 @@
+//! BRP method-name compatibility across Bevy versions
+//!
+//! Bevy 0.17 renamed the built-in BRP methods (`bevy/get` became `world.get_components`,
+//! `bevy/query` became `world.query`, and so on). Our `BrpMethod` enum always uses the current
+//! names. Before a call goes out we look up which naming scheme the app on that port speaks -
+//! detected once via `rpc.discover` and cached per port - and translate the wire name if needed.
+
+use std::collections::HashMap;
+use std::sync::LazyLock;
+use std::sync::Mutex;
+
+use serde_json::Value;
+use tracing::debug;
+
+use super::http_client::BrpHttpClient;
+use super::types::BrpClientCallJsonResponse;
+use crate::brp_tools::Port;
+use crate::tool::BrpMethod;
+
+/// Which generation of BRP method names an app understands
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MethodNaming {
+    /// `world.*` / `registry.*` names (Bevy 0.17+)
+    Current,
+    /// `bevy/*` names (Bevy 0.15 - 0.16)
+    Legacy,
+}
+
+/// Detected method naming per port
+static METHOD_NAMING: LazyLock<Mutex<HashMap<Port, MethodNaming>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Legacy equivalent of a current BRP method name, if the method was renamed
+pub const fn legacy_method_name(method: BrpMethod) -> Option<&'static str> {
+    match method {
+        BrpMethod::WorldGetComponents => Some("bevy/get"),
+        BrpMethod::WorldQuery => Some("bevy/query"),
+        BrpMethod::WorldSpawnEntity => Some("bevy/spawn"),
+        BrpMethod::WorldDespawnEntity => Some("bevy/destroy"),
+        BrpMethod::WorldRemoveComponents => Some("bevy/remove"),
+        BrpMethod::WorldInsertComponents => Some("bevy/insert"),
+        BrpMethod::WorldMutateComponents => Some("bevy/mutate_component"),
+        BrpMethod::WorldReparentEntities => Some("bevy/reparent"),
+        BrpMethod::WorldListComponents => Some("bevy/list"),
+        BrpMethod::WorldGetResources => Some("bevy/get_resource"),
+        BrpMethod::WorldInsertResources => Some("bevy/insert_resource"),
+        BrpMethod::WorldRemoveResources => Some("bevy/remove_resource"),
+        BrpMethod::WorldMutateResources => Some("bevy/mutate_resource"),
+        BrpMethod::WorldListResources => Some("bevy/list_resources"),
+        BrpMethod::WorldGetComponentsWatch => Some("bevy/get+watch"),
+        BrpMethod::WorldListComponentsWatch => Some("bevy/list+watch"),
+        BrpMethod::RegistrySchema => Some("bevy/registry/schema"),
+        _ => None,
+    }
+}
+
+/// Resolve the method name to put on the wire for `method` when talking to `port`
+///
+/// Methods that were never renamed skip detection entirely, so `rpc.discover` and the
+/// `brp_extras/*` methods never trigger an extra round trip.
+pub async fn resolve_method_name(method: BrpMethod, port: Port) -> &'static str {
+    let Some(legacy_name) = legacy_method_name(method) else {
+        return method.as_str();
+    };
+
+    match method_naming_for_port(port).await {
+        MethodNaming::Current => method.as_str(),
+        MethodNaming::Legacy => {
+            debug!(
+                "Mapping BRP method {} to legacy name {legacy_name} for port {port}",
+                method.as_str()
+            );
+            legacy_name
+        },
+    }
+}
+
+/// Get the cached naming for `port`, detecting it via `rpc.discover` on first use
+pub async fn method_naming_for_port(port: Port) -> MethodNaming {
+    if let Some(naming) = cached_naming(port) {
+        return naming;
+    }
+
+    let Some(naming) = detect_method_naming(port).await else {
+        // Detection failed (app not up yet, or no discover support) - assume current names and
+        // try again on the next call rather than caching a guess
+        return MethodNaming::Current;
+    };
+
+    if let Ok(mut cache) = METHOD_NAMING.lock() {
+        cache.insert(port, naming);
+    }
+    naming
+}
+
+/// Forget the detected naming for `port` - called when the connection drops so a restarted app
+/// running a different Bevy version is re-detected
+pub fn forget_method_naming(port: Port) {
+    if let Ok(mut cache) = METHOD_NAMING.lock() {
+        cache.remove(&port);
+    }
+}
+
+fn cached_naming(port: Port) -> Option<MethodNaming> {
+    METHOD_NAMING
+        .lock()
+        .ok()
+        .and_then(|cache| cache.get(&port).copied())
+}
+
+/// Ask the app which methods it supports and classify the naming scheme
+async fn detect_method_naming(port: Port) -> Option<MethodNaming> {
+    let http_client = BrpHttpClient::new(BrpMethod::RpcDiscover, port, None);
+    let response = http_client.send_request().await.ok()?;
+    let discover: BrpClientCallJsonResponse = response.json().await.ok()?;
+    let methods = discover.result?;
+
+    let naming = classify_discovered_methods(&methods);
+    debug!("Detected BRP method naming {naming:?} on port {port}");
+    Some(naming)
+}
+
+/// Classify an `rpc.discover` result - legacy apps advertise `bevy/*` names
+fn classify_discovered_methods(discover_result: &Value) -> MethodNaming {
+    let is_legacy = discover_result
+        .get("methods")
+        .and_then(Value::as_array)
+        .is_some_and(|methods| {
+            let names: Vec<&str> = methods
+                .iter()
+                .filter_map(|m| m.get("name").and_then(Value::as_str))
+                .collect();
+            names.iter().any(|name| name.starts_with("bevy/"))
+                && !names.iter().any(|name| name.starts_with("world."))
+        });
+
+    if is_legacy {
+        MethodNaming::Legacy
+    } else {
+        MethodNaming::Current
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn classifies_legacy_discover_result() {
+        let result = json!({
+            "methods": [{"name": "bevy/get"}, {"name": "bevy/query"}, {"name": "rpc.discover"}]
+        });
+        assert_eq!(classify_discovered_methods(&result), MethodNaming::Legacy);
+    }
+
+    #[test]
+    fn classifies_current_discover_result() {
+        let result = json!({
+            "methods": [{"name": "world.get_components"}, {"name": "rpc.discover"}]
+        });
+        assert_eq!(classify_discovered_methods(&result), MethodNaming::Current);
+    }
+
+    #[test]
+    fn unrenamed_methods_have_no_legacy_name() {
+        assert_eq!(legacy_method_name(BrpMethod::RpcDiscover), None);
+        assert_eq!(
+            legacy_method_name(BrpMethod::WorldGetComponents),
+            Some("bevy/get")
+        );
+    }
+}
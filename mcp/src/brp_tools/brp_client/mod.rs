@@ -1,12 +1,78 @@
+mod auto_name;
+mod call_report;
+mod circuit_breaker;
 mod client;
+mod color_correction;
+mod compact_query;
+mod compression;
 mod constants;
+mod correction_log;
+mod enum_casing;
+mod enum_variant;
+mod format_correction;
 mod http_client;
 mod json_rpc_builder;
+mod math_shorthand;
+mod method_compat;
+mod numeric_strings;
+mod preflight;
+mod read_cache;
+mod remote_host;
+mod request_queue;
+mod schema_validation;
+mod screenshot_path;
+mod short_name_cache;
+mod transient_retry;
+mod type_alias_correction;
+mod typed_values;
 mod types;
+mod wire_encoding;
 
 // Re-export public items
+pub use auto_name::AutoName;
+pub use auto_name::split_mix;
+pub use call_report::AppRestart;
+pub use call_report::CallReport;
+pub use call_report::record_app_restart;
+pub use call_report::with_call_report;
+pub use circuit_breaker::reset_circuit;
 pub use client::BrpClient;
+pub use color_correction::COLOR_SHORTHAND_NOTE;
+pub use color_correction::accepts_color_shorthand;
+pub use compact_query::compact_query_result;
+// Re-export network constants used to probe the BRP endpoint directly
+pub use constants::BRP_DEFAULT_HOST;
+#[cfg(test)]
+pub use constants::BRP_ERROR_CODE_ENTITY_NOT_FOUND;
+pub use constants::BRP_HTTP_PROTOCOL;
+pub use constants::BRP_JSONRPC_PATH;
 // Re-export error constant needed by external modules
 pub use constants::JSON_RPC_ERROR_METHOD_NOT_FOUND;
+pub use correction_log::CorrectionEvent;
+pub use correction_log::corrections;
+#[cfg(test)]
+pub use correction_log::record_corrections;
+pub use format_correction::Corrector;
+pub use format_correction::FORMAT_CORRECTION_NOTE;
+pub use json_rpc_builder::RequestId;
+pub use method_compat::forget_method_naming;
+pub use read_cache::forget_cached_reads;
+pub use remote_host::RemoteHost;
+pub use remote_host::forget_remote_host;
+pub use remote_host::register_remote_host;
+pub use remote_host::remote_host_for;
+pub use request_queue::RequestPriority;
+pub use schema_validation::registry_schemas;
+pub use screenshot_path::with_managed_path as with_managed_screenshot_path;
+pub use short_name_cache::clear as clear_short_name_index;
+pub use short_name_cache::refresh as refresh_short_name_index;
 // Re-export types needed by result_struct macro and client operations
-pub use types::{BrpToolConfig, FormatCorrectionStatus, ResponseStatus, ResultStructBrpExt};
+#[cfg(test)]
+pub use types::BrpClientError;
+pub use types::BrpToolConfig;
+pub use types::FormatCorrectionStatus;
+pub use types::FormatCorrections;
+pub use types::ResponseStatus;
+pub use types::ResultStructBrpExt;
+pub use wire_encoding::BinaryResponseStream;
+pub use wire_encoding::forget_wire_encoding;
@@ -0,0 +1,106 @@
+//! Numeric-string coercion for BRP requests
+//!
+//! Some MCP clients send numbers and booleans inside component values as strings - `"1.5"`,
+//! `"true"`. When a spawn, insert or mutate request fails with a format error, the
+//! `numeric_strings` step of the format-correction pipeline converts such strings for fields the
+//! type guide says are numbers or booleans, leaving string fields that merely look numeric alone.
+
+use serde_json::Number;
+use serde_json::Value;
+
+use super::typed_values;
+use super::typed_values::Rewrite;
+use crate::brp_tools::brp_type_guide::TypeGuideEngine;
+use crate::tool::BrpMethod;
+
+/// Integer primitive type names
+const INTEGER_TYPES: &[&str] = &[
+    "i8", "i16", "i32", "i64", "i128", "isize", "u8", "u16", "u32", "u64", "u128", "usize",
+];
+
+/// Float primitive type names
+const FLOAT_TYPES: &[&str] = &["f32", "f64"];
+
+/// Boolean type name
+const BOOL_TYPE: &str = "bool";
+
+/// Whether `params` holds any string that parses as a number or boolean - checked before fetching
+/// the registry so requests without one don't pay for it
+pub fn contains_numeric_string(params: &Value) -> bool {
+    match params {
+        // Integers parse as floats too
+        Value::String(s) => parse_as(s, "f64").is_some() || parse_as(s, BOOL_TYPE).is_some(),
+        Value::Array(items) => items.iter().any(contains_numeric_string),
+        Value::Object(map) => map.values().any(contains_numeric_string),
+        _ => false,
+    }
+}
+
+/// Convert numeric and boolean strings written for number and `bool` fields in the component or
+/// resource values of a spawn, insert or mutate request, returning the corrected parameters and
+/// what was converted
+pub fn convert_numeric_strings(
+    method: BrpMethod,
+    params: &Value,
+    engine: &TypeGuideEngine,
+) -> Option<(Value, Vec<Rewrite>)> {
+    typed_values::rewrite_typed_values(method, params, engine, |typed| {
+        let s = typed.value.as_str()?;
+        typed
+            .candidates
+            .iter()
+            .find_map(|(_, type_name)| parse_as(s, type_name.as_str()))
+    })
+}
+
+/// `s` as a value of primitive type `type_name`, or `None` if it isn't one or doesn't parse
+fn parse_as(s: &str, type_name: &str) -> Option<Value> {
+    let s = s.trim();
+    if INTEGER_TYPES.contains(&type_name) {
+        if let Ok(n) = s.parse::<i64>() {
+            return Some(Value::Number(n.into()));
+        }
+        return s.parse::<u64>().ok().map(|n| Value::Number(n.into()));
+    }
+    if FLOAT_TYPES.contains(&type_name) {
+        return s
+            .parse::<f64>()
+            .ok()
+            .and_then(Number::from_f64)
+            .map(Value::Number);
+    }
+    if type_name == BOOL_TYPE {
+        return match s {
+            "true" => Some(Value::Bool(true)),
+            "false" => Some(Value::Bool(false)),
+            _ => None,
+        };
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn parses_by_field_type() {
+        assert_eq!(parse_as("42", "u32"), Some(json!(42)));
+        assert_eq!(parse_as("1.5", "f32"), Some(json!(1.5)));
+        assert_eq!(parse_as("true", "bool"), Some(json!(true)));
+        assert_eq!(parse_as("1.5", "u32"), None);
+        assert_eq!(parse_as("123", "alloc::string::String"), None);
+    }
+
+    #[test]
+    fn finds_numeric_strings() {
+        assert!(contains_numeric_string(
+            &json!({ "value": { "speed": "2.5" } })
+        ));
+        assert!(!contains_numeric_string(
+            &json!({ "value": { "name": "player" } })
+        ));
+    }
+}
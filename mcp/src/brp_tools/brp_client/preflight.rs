@@ -0,0 +1,245 @@
+//! `strict` pre-flight checks for the mutate and insert tools
+//!
+//! A failed mutation usually comes back from BRP as an opaque reflection error, and the agent
+//! spends a round trip finding out that the entity was despawned or never had the component.
+//! With `strict: true`, `world.mutate_components`, `world.insert_components` and
+//! `world.mutate_resources` first check the target against `world.list_components` /
+//! `world.list_resources` and fail with an error naming what is actually missing.
+
+use serde_json::Value;
+use serde_json::json;
+
+use super::client::BrpClient;
+use super::types::ResponseStatus;
+use crate::brp_tools::BrpTypeName;
+use crate::brp_tools::Port;
+use crate::error::Error;
+use crate::error::Result;
+use crate::tool::BrpMethod;
+use crate::tool::ParameterName;
+
+/// Run the pre-flight checks for a request carrying `strict: true`
+///
+/// Returns the params with `strict` removed (BRP doesn't know it for these methods), or `None`
+/// when the request doesn't ask for strict checking and can be sent unchanged.
+pub async fn check_strict(
+    method: BrpMethod,
+    port: Port,
+    params: Option<&Value>,
+) -> Result<Option<Value>> {
+    if !matches!(
+        method,
+        BrpMethod::WorldMutateComponents
+            | BrpMethod::WorldInsertComponents
+            | BrpMethod::WorldMutateResources
+    ) {
+        return Ok(None);
+    }
+    let Some(mut params) = params.and_then(Value::as_object).cloned() else {
+        return Ok(None);
+    };
+    let Some(strict) = params.remove(ParameterName::Strict.as_ref()) else {
+        return Ok(None);
+    };
+    if strict.as_bool() != Some(true) {
+        return Ok(Some(Value::Object(params)));
+    }
+
+    let params = Value::Object(params);
+    match method {
+        BrpMethod::WorldMutateResources => check_resource(port, &params).await?,
+        _ => check_entity_components(method, port, &params).await?,
+    }
+    Ok(Some(params))
+}
+
+/// The entity must exist; a mutated component must be on it, an inserted one registered
+async fn check_entity_components(method: BrpMethod, port: Port, params: &Value) -> Result<()> {
+    let entity = params
+        .get(ParameterName::Entity.as_ref())
+        .and_then(Value::as_u64)
+        .ok_or_else(|| Error::missing(ParameterName::Entity.as_ref()))?;
+
+    let present = match list(
+        BrpMethod::WorldListComponents,
+        port,
+        Some(json!({ "entity": entity })),
+    )
+    .await?
+    {
+        Listing::Names(names) => names,
+        Listing::EntityNotFound => {
+            return Err(Error::tool_call_failed(format!(
+                "Entity {entity} does not exist - it was despawned or never spawned"
+            ))
+            .into());
+        },
+    };
+
+    let requested: Vec<String> = if method == BrpMethod::WorldMutateComponents {
+        params
+            .get(ParameterName::Component.as_ref())
+            .and_then(Value::as_str)
+            .map(|component| vec![component.to_string()])
+            .unwrap_or_default()
+    } else {
+        params
+            .get(ParameterName::Components.as_ref())
+            .and_then(Value::as_object)
+            .map(|components| components.keys().cloned().collect())
+            .unwrap_or_default()
+    };
+
+    let not_present: Vec<&String> = requested
+        .iter()
+        .filter(|component| !is_listed(component, &present))
+        .collect();
+    if not_present.is_empty() {
+        return Ok(());
+    }
+
+    let Listing::Names(registered) = list(BrpMethod::WorldListComponents, port, None).await? else {
+        return Ok(());
+    };
+    let (unregistered, missing): (Vec<&String>, Vec<&String>) = not_present
+        .into_iter()
+        .partition(|component| !is_listed(component, &registered));
+    // Insertion only needs the type to be registered
+    if unregistered.is_empty() && method == BrpMethod::WorldInsertComponents {
+        return Ok(());
+    }
+
+    let mut problems = Vec::new();
+    if !unregistered.is_empty() {
+        problems.push(format!(
+            "{} not registered for reflection (missing `#[reflect(Component)]` or \
+             `register_type`) - `brp_extras_ensure_registered` reports the cause and fix",
+            short_names(&unregistered)
+        ));
+    }
+    if method == BrpMethod::WorldMutateComponents && !missing.is_empty() {
+        let has = if present.is_empty() {
+            "no reflected components".to_string()
+        } else {
+            short_names(&present.iter().collect::<Vec<_>>())
+        };
+        problems.push(format!(
+            "entity {entity} has {has} but not {}",
+            short_names(&missing)
+        ));
+    }
+
+    Err(Error::tool_call_failed(format!("Pre-flight check failed: {}", problems.join("; "))).into())
+}
+
+/// The resource must be registered and present in the world
+async fn check_resource(port: Port, params: &Value) -> Result<()> {
+    let resource = params
+        .get(ParameterName::Resource.as_ref())
+        .and_then(Value::as_str)
+        .ok_or_else(|| Error::missing(ParameterName::Resource.as_ref()))?;
+
+    let Listing::Names(resources) = list(BrpMethod::WorldListResources, port, None).await? else {
+        return Ok(());
+    };
+    if is_listed(resource, &resources) {
+        return Ok(());
+    }
+
+    Err(Error::tool_call_failed(format!(
+        "Pre-flight check failed: resource {} is not in the world or not registered for \
+         reflection (missing `#[reflect(Resource)]` or `register_type`) - \
+         `brp_extras_ensure_registered` reports the cause and fix",
+        BrpTypeName::from(resource).short_name()
+    ))
+    .into())
+}
+
+/// Result of a listing call made for a pre-flight check
+enum Listing {
+    /// Type names returned by the listing
+    Names(Vec<String>),
+    /// The listed entity doesn't exist
+    EntityNotFound,
+}
+
+/// Call a `world.list_*` method and collect the type names it returns
+async fn list(method: BrpMethod, port: Port, params: Option<Value>) -> Result<Listing> {
+    match BrpClient::new(method, port, params).execute_raw().await? {
+        ResponseStatus::Success(data) => Ok(Listing::Names(
+            data.as_ref()
+                .and_then(Value::as_array)
+                .map(|names| {
+                    names
+                        .iter()
+                        .filter_map(Value::as_str)
+                        .map(String::from)
+                        .collect()
+                })
+                .unwrap_or_default(),
+        )),
+        ResponseStatus::Error(err) if err.is_entity_not_found() => Ok(Listing::EntityNotFound),
+        ResponseStatus::Error(err) => Err(Error::tool_call_failed(format!(
+            "Pre-flight check could not run {method}: {}",
+            err.get_message()
+        ))
+        .into()),
+    }
+}
+
+/// Whether `type_name`, or the path another Bevy release uses for it, is in `listed` - a short
+/// name (`Transform`) matches any listed path it is the short name of, since format correction
+/// resolves it to that path when BRP rejects it
+fn is_listed(type_name: &str, listed: &[String]) -> bool {
+    if !type_name.contains("::") {
+        return listed
+            .iter()
+            .any(|entry| BrpTypeName::from(entry.as_str()).short_name() == type_name);
+    }
+    let is_present = |name: &BrpTypeName| listed.iter().any(|entry| entry == name.as_str());
+    let type_name = BrpTypeName::from(type_name);
+    is_present(&type_name) || type_name.normalize_against(is_present).is_some()
+}
+
+/// `Transform, Health` - short names for error messages
+fn short_names(type_names: &[&String]) -> String {
+    type_names
+        .iter()
+        .map(|name| BrpTypeName::from(name.as_str()).short_name())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn listing_matches_exact_paths() {
+        let listed = vec![
+            "bevy_transform::components::transform::Transform".to_string(),
+            "my_game::Health".to_string(),
+        ];
+        assert!(is_listed("my_game::Health", &listed));
+        assert!(!is_listed("my_game::Mana", &listed));
+    }
+
+    #[test]
+    fn listing_matches_short_names() {
+        let listed = vec![
+            "bevy_transform::components::transform::Transform".to_string(),
+            "my_game::Health".to_string(),
+        ];
+        assert!(is_listed("Transform", &listed));
+        assert!(is_listed("Health", &listed));
+        assert!(!is_listed("GlobalTransform", &listed));
+        assert!(!is_listed("Mana", &listed));
+    }
+
+    #[test]
+    fn short_names_join() {
+        let transform = "bevy_transform::components::transform::Transform".to_string();
+        let health = "my_game::Health".to_string();
+        assert_eq!(short_names(&[&transform, &health]), "Transform, Health");
+    }
+}
@@ -0,0 +1,250 @@
+//! Short-lived cache of idempotent reads
+//!
+//! Agents often repeat the same read within seconds - the schema before each of several
+//! mutations, `rpc.discover` while exploring, the component list of one entity. Successful
+//! responses to `registry.schema`, `rpc.discover` and `world.list_components` are kept per port
+//! and parameters for `BRP_MCP_READ_CACHE_SECS` seconds (default 5, `0` turns the cache off).
+//! Calls with `no_cache: true` always go to the app, and refresh the cached response. Any
+//! successful call to a method that isn't a known read - including writes sent through
+//! `brp_execute`, raw or not, and methods this server has no `BrpMethod` for - drops every cached
+//! read of its port, since the component lists it cached may have changed. So does every
+//! notification, whose outcome is never reported.
+
+use std::collections::HashMap;
+use std::sync::LazyLock;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::Instant;
+
+use serde_json::Value;
+use tracing::debug;
+
+use super::call_report;
+use super::types::ResponseStatus;
+use crate::brp_tools::Port;
+use crate::tool::BrpMethod;
+use crate::tool::ParameterName;
+
+/// Environment variable setting how long reads are cached, in seconds - `0` turns caching off
+pub const READ_CACHE_SECS_ENV_VAR: &str = "BRP_MCP_READ_CACHE_SECS";
+
+/// How long reads are cached without `BRP_MCP_READ_CACHE_SECS`
+const DEFAULT_TTL: Duration = Duration::from_secs(5);
+
+/// Entries kept at most - expired entries are dropped first
+const MAX_ENTRIES: usize = 256;
+
+/// How long reads are cached - read once at first use
+static TTL: LazyLock<Duration> = LazyLock::new(|| {
+    std::env::var(READ_CACHE_SECS_ENV_VAR).map_or(DEFAULT_TTL, |secs| {
+        secs.trim().parse().map_or_else(
+            |_| {
+                debug!("Invalid {READ_CACHE_SECS_ENV_VAR} value '{secs}' - using the default");
+                DEFAULT_TTL
+            },
+            Duration::from_secs,
+        )
+    })
+});
+
+/// Cache key - port, method name and the request parameters as sent
+type CacheKey = (Port, &'static str, String);
+
+/// Cached responses and when they were received
+static CACHE: LazyLock<Mutex<HashMap<CacheKey, (Instant, ResponseStatus)>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Whether responses to `method` may be served from the cache
+const fn is_cacheable(method: BrpMethod) -> bool {
+    matches!(
+        method,
+        BrpMethod::RegistrySchema | BrpMethod::RpcDiscover | BrpMethod::WorldListComponents
+    )
+}
+
+/// Whether `method` only reads - a successful call to any other method may change what the cached
+/// reads of its port return, since it can add or remove components (`world.reparent_entities`
+/// adds `ChildOf`, `brp_extras/apply_commands` edits many entities at once) or register types
+const fn is_known_read(method: BrpMethod) -> bool {
+    matches!(
+        method,
+        BrpMethod::RegistrySchema
+            | BrpMethod::RpcDiscover
+            | BrpMethod::WorldGetComponents
+            | BrpMethod::WorldGetComponentsWatch
+            | BrpMethod::WorldGetResources
+            | BrpMethod::WorldListComponents
+            | BrpMethod::WorldListComponentsWatch
+            | BrpMethod::WorldListResources
+            | BrpMethod::WorldQuery
+            | BrpMethod::BrpExtrasAppInfo
+            | BrpMethod::BrpExtrasCapabilities
+            | BrpMethod::BrpExtrasCollectFrameStats
+            | BrpMethod::BrpExtrasFindTagged
+            | BrpMethod::BrpExtrasGetHistory
+            | BrpMethod::BrpExtrasGetInteractionState
+            | BrpMethod::BrpExtrasGetUiText
+            | BrpMethod::BrpExtrasListMonitors
+            | BrpMethod::BrpExtrasQueryStream
+            | BrpMethod::BrpExtrasReadPixels
+            | BrpMethod::BrpExtrasScheduleReport
+            | BrpMethod::BrpExtrasScreenshotStatus
+            | BrpMethod::BrpWaitForIdle
+    )
+}
+
+/// Drop the cached reads of `port` once a call changed what they return
+pub fn observe_response(method: BrpMethod, port: Port, status: &ResponseStatus) {
+    if !is_known_read(method) && matches!(status, ResponseStatus::Success(_)) {
+        forget_cached_reads(port);
+    }
+}
+
+/// Whether the request asks to bypass the cache with `no_cache: true`
+pub fn bypasses_cache(params: Option<&Value>) -> bool {
+    params
+        .and_then(|params| params.get(ParameterName::NoCache.as_ref()))
+        .and_then(Value::as_bool)
+        == Some(true)
+}
+
+/// Remove `no_cache` from a request - BRP doesn't know it
+///
+/// Returns `None` when the request has no `no_cache` flag, so it can be sent unchanged.
+pub fn without_no_cache_flag(params: Option<&Value>) -> Option<Value> {
+    let mut params = params.and_then(Value::as_object).cloned()?;
+    params.remove(ParameterName::NoCache.as_ref())?;
+    Some(Value::Object(params))
+}
+
+fn cache_key(method: BrpMethod, port: Port, params: Option<&Value>) -> CacheKey {
+    let params = params.map_or_else(String::new, Value::to_string);
+    (port, method.as_str(), params)
+}
+
+/// The cached response to this request, if one is still fresh - recorded in the call report
+pub fn lookup(method: BrpMethod, port: Port, params: Option<&Value>) -> Option<ResponseStatus> {
+    if !is_cacheable(method) || TTL.is_zero() {
+        return None;
+    }
+    let (received, status) = CACHE
+        .lock()
+        .ok()?
+        .get(&cache_key(method, port, params))
+        .cloned()?;
+    let age = received.elapsed();
+    if age >= *TTL {
+        return None;
+    }
+
+    call_report::record(|report| report.cache_age = Some(age));
+    Some(status)
+}
+
+/// Cache a successful response to this request
+pub fn store(method: BrpMethod, port: Port, params: Option<&Value>, status: &ResponseStatus) {
+    if !is_cacheable(method) || TTL.is_zero() || !matches!(status, ResponseStatus::Success(_)) {
+        return;
+    }
+    let Ok(mut cache) = CACHE.lock() else {
+        return;
+    };
+    cache.retain(|_, (received, _)| received.elapsed() < *TTL);
+    if cache.len() >= MAX_ENTRIES {
+        return;
+    }
+    cache.insert(
+        cache_key(method, port, params),
+        (Instant::now(), status.clone()),
+    );
+}
+
+/// Drop every cached response from `port` - the app behind it went away
+pub fn forget_cached_reads(port: Port) {
+    if let Ok(mut cache) = CACHE.lock() {
+        cache.retain(|(cached_port, _, _), _| *cached_port != port);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn serves_fresh_reads_per_port_and_params() {
+        let port = Port(47_311);
+        let entity = json!({"entity": 42});
+        let components = ResponseStatus::Success(Some(json!(["my_game::Health"])));
+
+        store(
+            BrpMethod::WorldListComponents,
+            port,
+            Some(&entity),
+            &components,
+        );
+
+        assert!(lookup(BrpMethod::WorldListComponents, port, Some(&entity)).is_some());
+        assert!(lookup(BrpMethod::WorldListComponents, port, None).is_none());
+        assert!(lookup(BrpMethod::WorldListComponents, Port(47_312), Some(&entity)).is_none());
+
+        store(BrpMethod::WorldQuery, port, None, &components);
+        assert!(lookup(BrpMethod::WorldQuery, port, None).is_none());
+
+        forget_cached_reads(port);
+        assert!(lookup(BrpMethod::WorldListComponents, port, Some(&entity)).is_none());
+    }
+
+    #[test]
+    fn successful_writes_drop_the_ports_reads() {
+        let port = Port(47_313);
+        let entity = json!({"entity": 42});
+        let components = ResponseStatus::Success(Some(json!(["my_game::Health"])));
+        let cache = || {
+            store(
+                BrpMethod::WorldListComponents,
+                port,
+                Some(&entity),
+                &components,
+            );
+        };
+        let cached = || lookup(BrpMethod::WorldListComponents, port, Some(&entity)).is_some();
+
+        cache();
+        observe_response(BrpMethod::WorldQuery, port, &components);
+        observe_response(
+            BrpMethod::WorldInsertComponents,
+            Port(47_314),
+            &ResponseStatus::Success(None),
+        );
+        assert!(cached(), "reads and other ports' writes keep the cache");
+
+        for write in [
+            BrpMethod::WorldInsertComponents,
+            BrpMethod::WorldRemoveComponents,
+            BrpMethod::WorldSpawnEntity,
+            BrpMethod::WorldReparentEntities,
+            BrpMethod::BrpExtrasApplyCommands,
+        ] {
+            cache();
+            observe_response(write, port, &ResponseStatus::Success(None));
+            assert!(
+                !cached(),
+                "{write:?} should drop the cached component lists"
+            );
+        }
+    }
+
+    #[test]
+    fn strips_the_no_cache_flag() {
+        let params = json!({"entity": 42, "no_cache": true});
+
+        assert!(bypasses_cache(Some(&params)));
+        assert_eq!(
+            without_no_cache_flag(Some(&params)),
+            Some(json!({"entity": 42}))
+        );
+        assert_eq!(without_no_cache_flag(Some(&json!({"entity": 42}))), None);
+    }
+}
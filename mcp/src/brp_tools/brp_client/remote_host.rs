@@ -0,0 +1,48 @@
+//! Ports forwarded to instances running on remote machines
+//!
+//! A remote launch starts the app over SSH and forwards its BRP port to the same local port, so
+//! requests still go to `127.0.0.1`. What differs is what a failed connection means: the SSH
+//! tunnel may have dropped while the app kept running. The launch records each forwarded port
+//! here so connection errors can say so, and so shutdown can reach the remote process.
+
+use std::collections::HashMap;
+use std::sync::LazyLock;
+use std::sync::Mutex;
+
+use crate::brp_tools::Port;
+
+/// An instance running on a remote machine, reached through a forwarded port
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteHost {
+    /// Name of the remote host profile the instance was launched with
+    pub profile:     String,
+    /// SSH destination the instance runs on
+    pub destination: String,
+    /// PID of the instance on the remote machine, when it was reported
+    pub remote_pid:  Option<u32>,
+}
+
+static REMOTE_HOSTS: LazyLock<Mutex<HashMap<Port, RemoteHost>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Record that `port` is forwarded to an instance on `remote_host`
+pub fn register_remote_host(port: Port, remote_host: RemoteHost) {
+    if let Ok(mut remote_hosts) = REMOTE_HOSTS.lock() {
+        remote_hosts.insert(port, remote_host);
+    }
+}
+
+/// Forget the remote instance on `port`, after it was shut down
+pub fn forget_remote_host(port: Port) {
+    if let Ok(mut remote_hosts) = REMOTE_HOSTS.lock() {
+        remote_hosts.remove(&port);
+    }
+}
+
+/// The remote instance `port` is forwarded to, if any
+pub fn remote_host_for(port: Port) -> Option<RemoteHost> {
+    REMOTE_HOSTS
+        .lock()
+        .ok()
+        .and_then(|remote_hosts| remote_hosts.get(&port).cloned())
+}
@@ -0,0 +1,229 @@
+//! Per-port request queue that lets interactive calls go ahead of background work
+//!
+//! Bevy answers BRP requests a few per frame, so a bulk operation - all type guides, a resource
+//! dump - that fires dozens of requests at once leaves a simple query waiting behind all of them.
+//! Each port admits at most `BRP_MCP_MAX_IN_FLIGHT` (default 4) requests at once and queues the
+//! rest, first come first served within a priority: each waiting request holds a ticket in its
+//! priority's line and only the front of the line may start. Background requests may only fill all
+//! but `INTERACTIVE_RESERVED_SLOTS` of the slots and never start while an interactive request is
+//! waiting, so an interactive request waits for at most one request to finish. Streaming requests
+//! (watches) stay open for long periods and bypass the queue.
+
+use std::collections::HashMap;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::sync::LazyLock;
+use std::sync::Mutex;
+
+use tokio::sync::Notify;
+
+use crate::brp_tools::Port;
+
+/// Environment variable setting how many requests each port has in flight at once
+pub const MAX_IN_FLIGHT_ENV_VAR: &str = "BRP_MCP_MAX_IN_FLIGHT";
+
+/// Requests each port has in flight at once unless `MAX_IN_FLIGHT_ENV_VAR` says otherwise
+const DEFAULT_MAX_IN_FLIGHT: usize = 4;
+
+/// Slots only interactive requests may use
+const INTERACTIVE_RESERVED_SLOTS: usize = 1;
+
+/// How urgent a BRP request is
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RequestPriority {
+    /// A tool call an agent is waiting on - queries, screenshots, mutations
+    #[default]
+    Interactive,
+    /// Bulk work that can yield - all type guides, resource dumps
+    Background,
+}
+
+/// Requests in flight and waiting on one port
+#[derive(Debug, Default)]
+struct QueueState {
+    in_flight:   usize,
+    /// Ticket the next waiting request gets
+    next_ticket: u64,
+    /// Tickets of the waiting interactive requests, in arrival order
+    interactive: VecDeque<u64>,
+    /// Tickets of the waiting background requests, in arrival order
+    background:  VecDeque<u64>,
+}
+
+impl QueueState {
+    const fn line(&mut self, priority: RequestPriority) -> &mut VecDeque<u64> {
+        match priority {
+            RequestPriority::Interactive => &mut self.interactive,
+            RequestPriority::Background => &mut self.background,
+        }
+    }
+}
+
+/// Queue of one port
+#[derive(Debug, Default)]
+struct PortQueue {
+    state:    Mutex<QueueState>,
+    released: Notify,
+}
+
+/// Queues of the ports requests were sent to
+static QUEUES: LazyLock<Mutex<HashMap<Port, Arc<PortQueue>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Requests each port has in flight at once - at least one more than the reserved slots
+static MAX_IN_FLIGHT: LazyLock<usize> = LazyLock::new(|| {
+    std::env::var(MAX_IN_FLIGHT_ENV_VAR)
+        .ok()
+        .and_then(|value| value.trim().parse::<usize>().ok())
+        .unwrap_or(DEFAULT_MAX_IN_FLIGHT)
+        .max(INTERACTIVE_RESERVED_SLOTS + 1)
+});
+
+/// A slot in a port's queue - the request keeps it until it is dropped
+pub struct QueueSlot {
+    queue: Arc<PortQueue>,
+}
+
+impl Drop for QueueSlot {
+    fn drop(&mut self) {
+        if let Ok(mut state) = self.queue.state.lock() {
+            state.in_flight = state.in_flight.saturating_sub(1);
+        }
+        self.queue.released.notify_waiters();
+    }
+}
+
+/// Keeps a request's ticket in its priority's line until it gets a slot or gives up
+struct Waiting<'a> {
+    queue:    &'a PortQueue,
+    priority: RequestPriority,
+    ticket:   u64,
+}
+
+impl Drop for Waiting<'_> {
+    fn drop(&mut self) {
+        if let Ok(mut state) = self.queue.state.lock() {
+            state
+                .line(self.priority)
+                .retain(|&ticket| ticket != self.ticket);
+        }
+        // The next request in line, or background requests held back for this one, may start now
+        self.queue.released.notify_waiters();
+    }
+}
+
+/// Wait for a slot in `port`'s queue
+pub async fn acquire(port: Port, priority: RequestPriority) -> QueueSlot {
+    let queue = queue_for(port);
+    let mut waiting = None;
+    loop {
+        let released = queue.released.notified();
+        tokio::pin!(released);
+        // Register for the next release before checking, so a release in between isn't missed
+        released.as_mut().enable();
+        {
+            let Ok(mut state) = queue.state.lock() else {
+                break;
+            };
+            let waiting = waiting.get_or_insert_with(|| {
+                let ticket = state.next_ticket;
+                state.next_ticket += 1;
+                state.line(priority).push_back(ticket);
+                Waiting {
+                    queue: &queue,
+                    priority,
+                    ticket,
+                }
+            });
+            if state.line(priority).front() == Some(&waiting.ticket)
+                && admits(&state, priority, *MAX_IN_FLIGHT)
+            {
+                state.line(priority).pop_front();
+                state.in_flight += 1;
+                break;
+            }
+        }
+        released.await;
+    }
+    drop(waiting);
+    QueueSlot { queue }
+}
+
+/// Whether a request of `priority` may start now
+fn admits(state: &QueueState, priority: RequestPriority, max_in_flight: usize) -> bool {
+    match priority {
+        RequestPriority::Interactive => state.in_flight < max_in_flight,
+        RequestPriority::Background => {
+            state.interactive.is_empty()
+                && state.in_flight < max_in_flight - INTERACTIVE_RESERVED_SLOTS
+        },
+    }
+}
+
+fn queue_for(port: Port) -> Arc<PortQueue> {
+    let Ok(mut queues) = QUEUES.lock() else {
+        return Arc::new(PortQueue::default());
+    };
+    Arc::clone(queues.entry(port).or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn background_requests_leave_a_slot_and_yield_to_waiting_interactive_ones() {
+        let state = |in_flight, waiting_interactive| QueueState {
+            in_flight,
+            interactive: (0..waiting_interactive).collect(),
+            ..QueueState::default()
+        };
+        let background = RequestPriority::Background;
+        let interactive = RequestPriority::Interactive;
+
+        assert!(admits(&state(2, 0), background, 4));
+        assert!(!admits(&state(3, 0), background, 4));
+        assert!(admits(&state(3, 0), interactive, 4));
+        assert!(!admits(&state(4, 0), interactive, 4));
+        assert!(!admits(&state(0, 1), background, 4));
+    }
+
+    #[tokio::test]
+    async fn waiting_requests_start_in_arrival_order() {
+        let port = Port(47_401);
+        let queue = queue_for(port);
+        let background_slots = *MAX_IN_FLIGHT - INTERACTIVE_RESERVED_SLOTS;
+        let waiting = || queue.state.lock().map_or(0, |state| state.background.len());
+        let mut held = Vec::new();
+        for _ in 0..background_slots {
+            held.push(acquire(port, RequestPriority::Background).await);
+        }
+        assert_eq!(held.len(), background_slots);
+
+        let first = tokio::spawn(acquire(port, RequestPriority::Background));
+        while waiting() < 1 {
+            tokio::task::yield_now().await;
+        }
+        let second = tokio::spawn(acquire(port, RequestPriority::Background));
+        while waiting() < 2 {
+            tokio::task::yield_now().await;
+        }
+
+        held.pop();
+        let first = first.await;
+        assert!(
+            first.is_ok(),
+            "the first request in line gets the freed slot"
+        );
+        for _ in 0..10 {
+            tokio::task::yield_now().await;
+        }
+        assert!(
+            !second.is_finished(),
+            "the second request waits for the next slot"
+        );
+
+        drop(first);
+        assert!(second.await.is_ok());
+    }
+}
@@ -0,0 +1,584 @@
+//! `validate` schema checks for the spawn, insert and mutate tools
+//!
+//! BRP rejects a malformed component value with a reflection error that rarely says which field
+//! was wrong, or why. With `validate: true`, `world.spawn_entity`, `world.insert_components` and
+//! `world.mutate_components` first check each value against the type's `registry.schema` entry
+//! and fail with every mismatch found, by field path - a missing required field, an unknown
+//! field, a wrong JSON type or an unknown enum variant. Values are checked as they will be sent:
+//! when the request asks for format correction, a value that fails is corrected first, and only
+//! a value no correction fixes is rejected.
+//!
+//! Checking is deliberately lenient: a part of a value whose schema can't be resolved is assumed
+//! to be fine and left for BRP to judge.
+
+use error_stack::Report;
+use serde_json::Map;
+use serde_json::Value;
+use serde_json::json;
+
+use super::client::BrpClient;
+use super::enum_variant::normalize_path;
+use super::math_shorthand;
+use super::math_shorthand::AXES;
+use super::types::ResponseStatus;
+use crate::brp_tools::BrpTypeName;
+use crate::brp_tools::Port;
+use crate::brp_tools::brp_type_guide::TypeKind;
+use crate::error::Error;
+use crate::error::Result;
+use crate::json_object::JsonObjectAccess;
+use crate::json_schema::SchemaField;
+use crate::tool::BrpMethod;
+use crate::tool::ParameterName;
+
+/// Nesting depth past which values aren't checked
+const MAX_DEPTH: usize = 32;
+
+/// Mismatches reported at most - the rest are counted
+const MAX_ERRORS: usize = 20;
+
+/// Remove `validate` from the params of a spawn, insert or mutate request
+///
+/// Returns the params without it (BRP doesn't know it) and whether it asked for validation, or
+/// `None` when the request carries no `validate` and can be sent unchanged.
+pub fn take_validate_flag(method: BrpMethod, params: Option<&Value>) -> Option<(Value, bool)> {
+    if !matches!(
+        method,
+        BrpMethod::WorldSpawnEntity
+            | BrpMethod::WorldInsertComponents
+            | BrpMethod::WorldMutateComponents
+    ) {
+        return None;
+    }
+    let mut params = params.and_then(Value::as_object).cloned()?;
+    let validate = params.remove(ParameterName::Validate.as_ref())?;
+    Some((Value::Object(params), validate.as_bool() == Some(true)))
+}
+
+/// Check the values of a spawn, insert or mutate request against `registry.schema`, returning
+/// every mismatch found - empty when the values are fine
+pub async fn schema_errors(method: BrpMethod, port: Port, params: &Value) -> Result<Vec<String>> {
+    let Some(params) = params.as_object() else {
+        return Ok(Vec::new());
+    };
+    let schemas = registry_schemas(port).await?;
+    let mut errors = Vec::new();
+    if method == BrpMethod::WorldMutateComponents {
+        validate_mutation(&schemas, params, &mut errors);
+    } else if let Some(components) = params
+        .get(ParameterName::Components.as_ref())
+        .and_then(Value::as_object)
+    {
+        for (type_path, value) in components {
+            validate_component(&schemas, type_path, "", value, &mut errors);
+        }
+    }
+    Ok(errors)
+}
+
+/// The error of a request whose values failed validation with `errors`
+pub fn validation_failure(mut errors: Vec<String>) -> Report<Error> {
+    let found = errors.len();
+    errors.truncate(MAX_ERRORS);
+    let more = found
+        .checked_sub(MAX_ERRORS)
+        .filter(|more| *more > 0)
+        .map(|more| format!(" (and {more} more)"))
+        .unwrap_or_default();
+    Report::new(Error::tool_call_failed_with_details(
+        format!("Schema validation failed: {}{more}", errors.join("; ")),
+        json!({ "validation_errors": errors }),
+    ))
+}
+
+/// `registry.schema` for `port`, keyed by type path - served from the read cache when fresh
+pub async fn registry_schemas(port: Port) -> Result<Map<String, Value>> {
+    let client = BrpClient::new(BrpMethod::RegistrySchema, port, None);
+    let schemas = match client.execute_cached(false).await? {
+        ResponseStatus::Success(data) => data.unwrap_or(Value::Null),
+        ResponseStatus::Error(err) => {
+            return Err(Error::tool_call_failed(format!(
+                "Schema validation could not read the type registry: {}",
+                err.get_message()
+            ))
+            .into());
+        },
+    };
+    Ok(match schemas {
+        Value::Object(schemas) => schemas,
+        // Older releases return a list of schemas, each carrying its type path
+        Value::Array(schemas) => schemas
+            .into_iter()
+            .filter_map(|schema| {
+                let type_path = schema.get_field_string(SchemaField::TypePath)?;
+                Some((type_path, schema))
+            })
+            .collect(),
+        _ => Map::new(),
+    })
+}
+
+/// Check a mutation's `value` against the schema of the field at its `path`
+fn validate_mutation(
+    schemas: &Map<String, Value>,
+    params: &Map<String, Value>,
+    errors: &mut Vec<String>,
+) {
+    let (Some(type_path), Some(value)) = (
+        params
+            .get(ParameterName::Component.as_ref())
+            .and_then(Value::as_str),
+        params.get(ParameterName::Value.as_ref()),
+    ) else {
+        return;
+    };
+    let path = params
+        .get(ParameterName::Path.as_ref())
+        .and_then(Value::as_str)
+        .map(normalize_path)
+        .unwrap_or_default();
+    validate_component(schemas, type_path, &path, value, errors);
+}
+
+/// Check `value` against the schema of the field at `path` of component `type_path`
+fn validate_component(
+    schemas: &Map<String, Value>,
+    type_path: &str,
+    path: &str,
+    value: &Value,
+    errors: &mut Vec<String>,
+) {
+    let type_name = BrpTypeName::from(type_path);
+    let type_name = type_name
+        .normalize_against(|name| schemas.contains_key(name.as_str()))
+        .unwrap_or(type_name);
+    let Some(schema) = schemas.get(type_name.as_str()) else {
+        errors.push(format!("{}: not a registered type", type_name.short_name()));
+        return;
+    };
+    let Some(schema) = schema_at_path(schemas, schema, path) else {
+        return;
+    };
+    let mut validator = Validator { schemas, errors };
+    validator.check(
+        schema,
+        value,
+        &format!("{}{path}", type_name.short_name()),
+        0,
+    );
+}
+
+/// Schema of the field at a mutation `path` like `.translation.x` - `None` when the path goes
+/// through something other than struct fields and tuple elements
+fn schema_at_path<'a>(
+    schemas: &'a Map<String, Value>,
+    schema: &'a Value,
+    path: &str,
+) -> Option<&'a Value> {
+    let mut current = schema;
+    for segment in path.split('.').filter(|segment| !segment.is_empty()) {
+        let field = match TypeKind::from_schema(current) {
+            TypeKind::Struct => current.get_properties()?.get(segment)?,
+            TypeKind::Tuple | TypeKind::TupleStruct => current
+                .get_field_array(SchemaField::PrefixItems)?
+                .get(segment.parse::<usize>().ok()?)?,
+            _ => return None,
+        };
+        current = resolve(schemas, field)?;
+    }
+    Some(current)
+}
+
+/// The type definition a field schema refers to through `type.$ref`
+fn resolve<'a>(schemas: &'a Map<String, Value>, field: &Value) -> Option<&'a Value> {
+    let type_name = field.extract_field_type()?;
+    schemas.get(type_name.as_str())
+}
+
+/// Checks values against type definitions, collecting the mismatches
+struct Validator<'a> {
+    schemas: &'a Map<String, Value>,
+    errors:  &'a mut Vec<String>,
+}
+
+impl Validator<'_> {
+    /// Check `value` against the type definition `schema`, with `path` naming it in errors
+    fn check(&mut self, schema: &Value, value: &Value, path: &str, depth: usize) {
+        if depth > MAX_DEPTH {
+            return;
+        }
+        let schemas = self.schemas;
+        match TypeKind::from_schema(schema) {
+            TypeKind::Struct => self.check_struct(schema, value, path, depth),
+            TypeKind::TupleStruct | TypeKind::Tuple => self.check_tuple(schema, value, path, depth),
+            TypeKind::Array | TypeKind::List | TypeKind::Set => {
+                let Some(elements) = value.as_array() else {
+                    self.mismatch(path, "an array", value);
+                    return;
+                };
+                let Some(element_schema) = schema
+                    .get_field(SchemaField::Items)
+                    .and_then(|items| resolve(schemas, items))
+                else {
+                    return;
+                };
+                for (index, element) in elements.iter().enumerate() {
+                    self.check(
+                        element_schema,
+                        element,
+                        &format!("{path}[{index}]"),
+                        depth + 1,
+                    );
+                }
+            },
+            TypeKind::Map => {
+                let Some(entries) = value.as_object() else {
+                    self.mismatch(path, "an object", value);
+                    return;
+                };
+                let Some(value_schema) = schema
+                    .get_field(SchemaField::ValueType)
+                    .and_then(|value_type| resolve(schemas, value_type))
+                else {
+                    return;
+                };
+                for (key, entry) in entries {
+                    self.check(
+                        value_schema,
+                        entry,
+                        &format!("{path}[\"{key}\"]"),
+                        depth + 1,
+                    );
+                }
+            },
+            TypeKind::Enum => self.check_enum(schema, value, path, depth),
+            TypeKind::Value => self.check_primitive(schema, value, path),
+        }
+    }
+
+    fn check_struct(&mut self, schema: &Value, value: &Value, path: &str, depth: usize) {
+        if let Some(len) = schema
+            .get_field_str(SchemaField::TypePath)
+            .and_then(|type_path| math_shorthand::vector_len(&BrpTypeName::from(type_path)))
+        {
+            self.check_vector(schema, value, len, path, depth);
+            return;
+        }
+        let Some(fields) = value.as_object() else {
+            self.mismatch(path, "an object", value);
+            return;
+        };
+        let Some(properties) = schema.get_properties() else {
+            return;
+        };
+        for required in schema
+            .get_field_array(SchemaField::Required)
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+        {
+            if !fields.contains_key(required) {
+                self.errors
+                    .push(format!("{path}: missing required field `{required}`"));
+            }
+        }
+        for (name, field_value) in fields {
+            let Some(field) = properties.get(name) else {
+                let expected: Vec<&str> = properties.keys().map(String::as_str).collect();
+                self.errors.push(format!(
+                    "{path}: unknown field `{name}` - expected one of: {}",
+                    expected.join(", ")
+                ));
+                continue;
+            };
+            if let Some(field_schema) = resolve(self.schemas, field) {
+                self.check(
+                    field_schema,
+                    field_value,
+                    &format!("{path}.{name}"),
+                    depth + 1,
+                );
+            }
+        }
+    }
+
+    /// Vectors and quaternions are reflected as arrays of their axes, not as the structs their
+    /// schemas describe
+    fn check_vector(
+        &mut self,
+        schema: &Value,
+        value: &Value,
+        len: usize,
+        path: &str,
+        depth: usize,
+    ) {
+        let Some(elements) = value.as_array().filter(|elements| elements.len() == len) else {
+            self.mismatch(path, &format!("an array of {len} numbers"), value);
+            return;
+        };
+        let Some(properties) = schema.get_properties() else {
+            return;
+        };
+        for (axis, element) in AXES.iter().zip(elements) {
+            if let Some(axis_schema) = properties
+                .get(*axis)
+                .and_then(|field| resolve(self.schemas, field))
+            {
+                self.check(axis_schema, element, &format!("{path}.{axis}"), depth + 1);
+            }
+        }
+    }
+
+    /// Tuples are arrays, except single-element ones, which are written as their element
+    fn check_tuple(&mut self, schema: &Value, value: &Value, path: &str, depth: usize) {
+        let Some(items) = schema.get_field_array(SchemaField::PrefixItems) else {
+            return;
+        };
+        if let [item] = items.as_slice() {
+            if let Some(item_schema) = resolve(self.schemas, item) {
+                self.check(item_schema, value, path, depth + 1);
+            }
+            return;
+        }
+        let Some(elements) = value.as_array() else {
+            self.mismatch(
+                path,
+                &format!("an array of {} elements", items.len()),
+                value,
+            );
+            return;
+        };
+        if elements.len() != items.len() {
+            self.errors.push(format!(
+                "{path}: expected {} elements, got {}",
+                items.len(),
+                elements.len()
+            ));
+            return;
+        }
+        for (index, (item, element)) in items.iter().zip(elements).enumerate() {
+            if let Some(item_schema) = resolve(self.schemas, item) {
+                self.check(item_schema, element, &format!("{path}.{index}"), depth + 1);
+            }
+        }
+    }
+
+    /// Unit variants are written as their name, others as `{"Variant": payload}` - `Option` is
+    /// written as `null` or its inner value
+    fn check_enum(&mut self, schema: &Value, value: &Value, path: &str, depth: usize) {
+        let Some(variants) = schema.get_field_array(SchemaField::OneOf) else {
+            return;
+        };
+        let variant_name = |variant: &Value| {
+            variant
+                .as_str()
+                .or_else(|| variant.get_field_str(SchemaField::ShortPath))
+                .map(String::from)
+        };
+        let find_variant = |name: &str| {
+            variants
+                .iter()
+                .find(|variant| variant_name(variant).as_deref() == Some(name))
+        };
+
+        let is_option = schema
+            .get_field_str(SchemaField::TypePath)
+            .is_some_and(|type_path| type_path.starts_with("core::option::Option<"));
+        if is_option {
+            if let Some(some) = find_variant("Some")
+                && !value.is_null()
+            {
+                self.check_tuple(some, value, path, depth);
+            }
+            return;
+        }
+
+        let (name, payload) = match value {
+            Value::String(name) => (name.as_str(), None),
+            Value::Object(tagged) if tagged.len() == 1 => {
+                let Some((name, payload)) = tagged.iter().next() else {
+                    return;
+                };
+                (name.as_str(), Some(payload))
+            },
+            _ => {
+                self.mismatch(path, "a variant name or {\"Variant\": payload}", value);
+                return;
+            },
+        };
+        let Some(variant) = find_variant(name) else {
+            let expected: Vec<String> = variants.iter().filter_map(variant_name).collect();
+            self.errors.push(format!(
+                "{path}: unknown variant `{name}` - expected one of: {}",
+                expected.join(", ")
+            ));
+            return;
+        };
+
+        let is_struct = variant.get_properties().is_some();
+        let is_tuple = variant.get_field(SchemaField::PrefixItems).is_some();
+        match payload {
+            None if is_struct || is_tuple => self.errors.push(format!(
+                "{path}: variant `{name}` has fields - write it as {{\"{name}\": ...}}"
+            )),
+            Some(_) if !is_struct && !is_tuple => self.errors.push(format!(
+                "{path}: variant `{name}` has no fields - write it as \"{name}\""
+            )),
+            Some(payload) if is_struct => {
+                self.check_struct(variant, payload, &format!("{path}::{name}"), depth + 1);
+            },
+            Some(payload) => {
+                self.check_tuple(variant, payload, &format!("{path}::{name}"), depth + 1);
+            },
+            None => {},
+        }
+    }
+
+    /// Leaf values - only clear JSON type mismatches are reported
+    fn check_primitive(&mut self, schema: &Value, value: &Value, path: &str) {
+        let expected = match schema.get_field_str(SchemaField::Type) {
+            Some("string") if !value.is_string() => "a string",
+            Some("boolean") if !value.is_boolean() => "a boolean",
+            Some("float" | "number") if !value.is_number() => "a number",
+            Some("uint") if !value.is_u64() => "a non-negative integer",
+            Some("int" | "integer") if !(value.is_i64() || value.is_u64()) => "an integer",
+            _ => return,
+        };
+        self.mismatch(path, expected, value);
+    }
+
+    fn mismatch(&mut self, path: &str, expected: &str, value: &Value) {
+        let got = match value {
+            Value::Null => "null",
+            Value::Bool(_) => "a boolean",
+            Value::Number(_) => "a number",
+            Value::String(_) => "a string",
+            Value::Array(_) => "an array",
+            Value::Object(_) => "an object",
+        };
+        self.errors
+            .push(format!("{path}: expected {expected}, got {got} `{value}`"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schemas() -> Map<String, Value> {
+        let schemas = json!({
+            "f32": {"kind": "Value", "type": "float"},
+            "u32": {"kind": "Value", "type": "uint"},
+            "glam::Vec3": {
+                "kind": "Struct",
+                "typePath": "glam::Vec3",
+                "properties": {
+                    "x": {"type": {"$ref": "#/$defs/f32"}},
+                    "y": {"type": {"$ref": "#/$defs/f32"}},
+                    "z": {"type": {"$ref": "#/$defs/f32"}}
+                },
+                "required": ["x", "y", "z"]
+            },
+            "my_game::Mode": {
+                "kind": "Enum",
+                "typePath": "my_game::Mode",
+                "oneOf": [
+                    "Idle",
+                    {
+                        "shortPath": "Moving",
+                        "prefixItems": [{"type": {"$ref": "#/$defs/glam::Vec3"}}]
+                    }
+                ]
+            },
+            "my_game::Unit": {
+                "kind": "Struct",
+                "typePath": "my_game::Unit",
+                "properties": {
+                    "health": {"type": {"$ref": "#/$defs/u32"}},
+                    "mode": {"type": {"$ref": "#/$defs/my_game::Mode"}}
+                },
+                "required": ["health", "mode"]
+            }
+        });
+        schemas.as_object().cloned().unwrap_or_default()
+    }
+
+    fn errors_for(type_path: &str, path: &str, value: &Value) -> Vec<String> {
+        let mut errors = Vec::new();
+        validate_component(&schemas(), type_path, path, value, &mut errors);
+        errors
+    }
+
+    #[test]
+    fn valid_values_pass() {
+        let unit = json!({"health": 10, "mode": {"Moving": [1.0, 0.0, 2.5]}});
+        assert!(errors_for("my_game::Unit", "", &unit).is_empty());
+        assert!(errors_for("my_game::Unit", "", &json!({"health": 1, "mode": "Idle"})).is_empty());
+    }
+
+    #[test]
+    fn reports_mismatches_by_field_path() {
+        let unit = json!({"health": -3, "mode": {"Moving": ["fast", 0.0, 1.0]}, "mana": 1});
+        let errors = errors_for("my_game::Unit", "", &unit);
+        let reported = |start: &str| errors.iter().any(|error| error.starts_with(start));
+        assert!(reported(
+            "Unit.health: expected a non-negative integer, got a number `-3`"
+        ));
+        assert!(reported(
+            "Unit.mode::Moving.x: expected a number, got a string"
+        ));
+        assert!(reported("Unit: unknown field `mana`"));
+    }
+
+    #[test]
+    fn checks_unknown_variants_and_mutation_paths() {
+        let errors = errors_for("my_game::Unit", ".mode", &json!("Flying"));
+        assert_eq!(
+            errors,
+            vec!["Unit.mode: unknown variant `Flying` - expected one of: Idle, Moving"]
+        );
+        assert_eq!(
+            errors_for("my_game::Unit", ".health", &json!("ten")),
+            vec!["Unit.health: expected a non-negative integer, got a string `\"ten\"`"]
+        );
+    }
+
+    #[test]
+    fn vectors_are_checked_as_arrays_of_their_axes() {
+        assert!(errors_for("glam::Vec3", "", &json!([1.0, 2.0, 3.0])).is_empty());
+        assert_eq!(
+            errors_for("glam::Vec3", "", &json!({"x": 1.0, "y": 2.0, "z": 3.0})),
+            vec![
+                "Vec3: expected an array of 3 numbers, got an object `{\"x\":1.0,\"y\":2.0,\"z\":3.0}`"
+            ]
+        );
+        assert_eq!(
+            errors_for("glam::Vec3", "", &json!([1.0, 2.0])),
+            vec!["Vec3: expected an array of 3 numbers, got an array `[1.0,2.0]`"]
+        );
+    }
+
+    #[test]
+    fn the_validate_flag_is_removed_from_checked_methods_only() {
+        let params = json!({"components": {}, "validate": true});
+        assert_eq!(
+            take_validate_flag(BrpMethod::WorldSpawnEntity, Some(&params)),
+            Some((json!({"components": {}}), true))
+        );
+        assert_eq!(
+            take_validate_flag(
+                BrpMethod::WorldInsertComponents,
+                Some(&json!({"validate": false}))
+            ),
+            Some((json!({}), false))
+        );
+        assert_eq!(
+            take_validate_flag(BrpMethod::WorldQuery, Some(&params)),
+            None
+        );
+        assert_eq!(
+            take_validate_flag(BrpMethod::WorldSpawnEntity, Some(&json!({}))),
+            None
+        );
+    }
+}
@@ -0,0 +1,98 @@
+//! Managed screenshot paths for `brp_extras/screenshot`
+//!
+//! Agents routinely lose track of where they saved captures. A screenshot request without a
+//! `path` is saved to the managed screenshot directory next to the logs, named
+//! `{target}_port{port}_{timestamp}.png`, where `brp_list_screenshots` finds it again.
+
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+use serde_json::Value;
+use serde_json::json;
+use sysinfo::ProcessesToUpdate;
+use sysinfo::System;
+
+use crate::app_tools::get_pid_for_port;
+use crate::brp_tools::Port;
+use crate::log_tools::screenshot_file_path;
+use crate::tool::BrpMethod;
+use crate::tool::ParameterName;
+
+/// Target name used when the process on the port can't be identified
+const UNKNOWN_TARGET: &str = "app";
+
+/// Fill in a managed `path` for a screenshot request that doesn't give one
+///
+/// Returns `None` when the request isn't a screenshot or already has a path, so it can be sent
+/// unchanged.
+pub fn with_managed_path(method: BrpMethod, port: Port, params: Option<&Value>) -> Option<Value> {
+    if method != BrpMethod::BrpExtrasScreenshot {
+        return None;
+    }
+    let mut params = match params {
+        Some(Value::Object(params)) => params.clone(),
+        Some(_) => return None,
+        None => serde_json::Map::new(),
+    };
+    if params.contains_key(ParameterName::Path.as_ref()) {
+        return None;
+    }
+
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_millis());
+    let path = screenshot_file_path(&target_name_for_port(port), *port, timestamp);
+    params.insert(ParameterName::Path.as_ref().to_string(), json!(path));
+    Some(Value::Object(params))
+}
+
+/// Name of the process listening on `port`, made safe for a filename
+fn target_name_for_port(port: Port) -> String {
+    let name = get_pid_for_port(port).and_then(|pid| {
+        let pid = sysinfo::Pid::from_u32(pid);
+        let mut system = System::new();
+        system.refresh_processes(ProcessesToUpdate::Some(&[pid]), true);
+        system
+            .process(pid)
+            .map(|process| process.name().to_string_lossy().to_string())
+    });
+    name.as_deref()
+        .map(sanitize_target_name)
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| UNKNOWN_TARGET.to_string())
+}
+
+/// `my-game.exe` -> `my-game`, with anything that isn't safe in a filename replaced
+fn sanitize_target_name(process_name: &str) -> String {
+    process_name
+        .trim_end_matches(".exe")
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn process_names_become_safe_target_names() {
+        assert_eq!(sanitize_target_name("my-game.exe"), "my-game");
+        assert_eq!(sanitize_target_name("space game"), "space_game");
+    }
+
+    #[test]
+    fn explicit_paths_and_other_methods_are_left_alone() {
+        let params = json!({ "path": "/tmp/shot.png" });
+        assert!(
+            with_managed_path(BrpMethod::BrpExtrasScreenshot, Port(1), Some(&params)).is_none()
+        );
+        assert!(with_managed_path(BrpMethod::WorldQuery, Port(1), None).is_none());
+    }
+}
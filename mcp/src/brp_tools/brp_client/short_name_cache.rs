@@ -0,0 +1,347 @@
+//! Short type names resolved to full type paths, per port
+//!
+//! Requests often name components and resources by their short name (`Transform`) rather than
+//! the full type path the app registers (`bevy_transform::components::transform::Transform`).
+//! The index mapping one to the other is built from `registry.schema` the first time a failed
+//! request needs it and kept for the rest of the session. `brp_refresh_type_cache` rebuilds it
+//! after the app's type set changes - a hot reload or a restart with new code.
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::iter;
+use std::sync::LazyLock;
+use std::sync::Mutex;
+
+use serde_json::Map;
+use serde_json::Value;
+use serde_json::json;
+use tracing::debug;
+
+use super::client::BrpClient;
+use super::types::ResponseStatus;
+use crate::brp_tools::BrpTypeName;
+use crate::brp_tools::Port;
+use crate::error::Error;
+use crate::error::Result;
+use crate::json_object::JsonObjectAccess;
+use crate::json_schema::SchemaField;
+use crate::tool::BrpMethod;
+
+/// Reflect traits whose types can be named in a request
+const NAMEABLE_REFLECT_TYPES: [&str; 2] = ["Component", "Resource"];
+
+/// Request fields holding a single type path
+const TYPE_FIELDS: [&str; 2] = ["component", "resource"];
+
+/// Query fields holding type paths, under `data` and `filter`
+const QUERY_TYPE_FIELDS: [&str; 5] = ["components", "option", "has", "with", "without"];
+
+/// Full type paths of the app's components and resources, by short name
+#[derive(Debug, Clone, Default)]
+pub struct ShortNameIndex {
+    by_short_name: BTreeMap<String, Vec<String>>,
+}
+
+impl ShortNameIndex {
+    /// Index the components and resources in a `registry.schema` response - an object keyed by
+    /// type path, or an array from older Bevy releases
+    fn from_schemas(schemas: &Value) -> Self {
+        let entries: Vec<(Option<&str>, &Value)> = match schemas {
+            Value::Object(map) => map
+                .iter()
+                .map(|(type_path, schema)| (Some(type_path.as_str()), schema))
+                .collect(),
+            Value::Array(items) => items.iter().map(|schema| (None, schema)).collect(),
+            _ => Vec::new(),
+        };
+
+        let mut by_short_name: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for (key, schema) in entries {
+            let Some(type_path) = key.or_else(|| schema.get_field_str(SchemaField::TypePath))
+            else {
+                continue;
+            };
+            let nameable = schema
+                .get_field_array(SchemaField::ReflectTypes)
+                .is_some_and(|reflect_types| {
+                    reflect_types
+                        .iter()
+                        .filter_map(Value::as_str)
+                        .any(|reflect_type| NAMEABLE_REFLECT_TYPES.contains(&reflect_type))
+                });
+            if !nameable {
+                continue;
+            }
+
+            let short_name = schema
+                .get_field_string(SchemaField::ShortPath)
+                .unwrap_or_else(|| BrpTypeName::from(type_path).short_name());
+            by_short_name
+                .entry(short_name)
+                .or_default()
+                .push(type_path.to_string());
+        }
+        for type_paths in by_short_name.values_mut() {
+            type_paths.sort();
+        }
+
+        Self { by_short_name }
+    }
+
+    /// Full type paths registered under `short_name`
+    pub fn resolve(&self, short_name: &str) -> &[String] {
+        self.by_short_name
+            .get(short_name)
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Number of indexed types
+    pub fn type_count(&self) -> usize { self.by_short_name.values().map(Vec::len).sum() }
+
+    /// Number of distinct short names
+    pub fn short_name_count(&self) -> usize { self.by_short_name.len() }
+
+    /// Short names shared by more than one type, with the types sharing them
+    pub fn ambiguous(&self) -> BTreeMap<&str, &[String]> {
+        self.by_short_name
+            .iter()
+            .filter(|(_, type_paths)| type_paths.len() > 1)
+            .map(|(short_name, type_paths)| (short_name.as_str(), type_paths.as_slice()))
+            .collect()
+    }
+}
+
+/// Index per port, kept for the session
+static INDEXES: LazyLock<Mutex<HashMap<Port, ShortNameIndex>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Index for `port`, built from the app's registry on first use
+pub async fn index_for_port(port: Port) -> Result<ShortNameIndex> {
+    let cached = INDEXES
+        .lock()
+        .ok()
+        .and_then(|indexes| indexes.get(&port).cloned());
+    match cached {
+        Some(index) => Ok(index),
+        None => refresh(port).await,
+    }
+}
+
+/// Rebuild the index for `port` from the app's registry
+pub async fn refresh(port: Port) -> Result<ShortNameIndex> {
+    let client = BrpClient::new(BrpMethod::RegistrySchema, port, None);
+    let schemas = match client.execute_direct_internal_no_enhancement().await? {
+        ResponseStatus::Success(data) => data.unwrap_or(Value::Null),
+        ResponseStatus::Error(err) => {
+            return Err(Error::tool_call_failed(format!(
+                "Could not read the type registry: {}",
+                err.get_message()
+            ))
+            .into());
+        },
+    };
+
+    let index = ShortNameIndex::from_schemas(&schemas);
+    debug!(
+        "Indexed {} component and resource types for port {port}",
+        index.type_count()
+    );
+    if let Ok(mut indexes) = INDEXES.lock() {
+        indexes.insert(port, index.clone());
+    }
+    Ok(index)
+}
+
+/// Drop the index for `port`, or for every port - returns the number of indexes dropped
+pub fn clear(port: Option<Port>) -> usize {
+    let Ok(mut indexes) = INDEXES.lock() else {
+        return 0;
+    };
+    if let Some(port) = port {
+        usize::from(indexes.remove(&port).is_some())
+    } else {
+        let cleared = indexes.len();
+        indexes.clear();
+        cleared
+    }
+}
+
+/// A short type name replaced by the one type path registered under it
+#[derive(Debug, Clone)]
+pub struct ShortNameResolution {
+    /// Name as written in the request
+    pub short_name: String,
+    /// Full type path it resolved to
+    pub type_path:  String,
+}
+
+impl ShortNameResolution {
+    /// Describe this resolution for the `format_corrections` metadata
+    pub fn to_correction(&self) -> Value {
+        json!({
+            "original_type": self.short_name,
+            "corrected_type": self.type_path,
+            "reason": "short type name resolved to the only registered type with that name",
+        })
+    }
+}
+
+/// Whether `params` names any type by its short name
+pub fn has_short_names(params: &Value) -> bool {
+    let mut found = false;
+    visit_type_names(params, &mut |name| found |= !name.contains("::"));
+    found
+}
+
+/// Short names used as type paths in `params` that resolve to exactly one registered type
+pub fn resolutions(params: &Value, index: &ShortNameIndex) -> Vec<ShortNameResolution> {
+    let mut found: Vec<ShortNameResolution> = Vec::new();
+    visit_type_names(params, &mut |name| {
+        if name.contains("::") || found.iter().any(|resolved| resolved.short_name == name) {
+            return;
+        }
+        if let [type_path] = index.resolve(name) {
+            found.push(ShortNameResolution {
+                short_name: name.to_string(),
+                type_path:  type_path.clone(),
+            });
+        }
+    });
+    found
+}
+
+/// Rewrite resolved short names where `params` holds type paths
+pub fn apply_resolutions(params: &Value, resolutions: &[ShortNameResolution]) -> Value {
+    let resolve = |name: &str| {
+        resolutions
+            .iter()
+            .find(|resolved| resolved.short_name == name)
+            .map_or_else(|| name.to_string(), |resolved| resolved.type_path.clone())
+    };
+    let resolve_value = |value: &Value| match value {
+        Value::String(name) => Value::String(resolve(name.as_str())),
+        Value::Array(names) => Value::Array(
+            names
+                .iter()
+                .map(|name| {
+                    name.as_str()
+                        .map_or_else(|| name.clone(), |name| Value::String(resolve(name)))
+                })
+                .collect(),
+        ),
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(name, value)| (resolve(name.as_str()), value.clone()))
+                .collect::<Map<String, Value>>(),
+        ),
+        other => other.clone(),
+    };
+
+    let Value::Object(map) = params else {
+        return params.clone();
+    };
+    let mut resolved = map.clone();
+    for field in TYPE_FIELDS.iter().chain(iter::once(&"components")) {
+        if let Some(value) = map.get(*field) {
+            resolved.insert((*field).to_string(), resolve_value(value));
+        }
+    }
+    for section in ["data", "filter"] {
+        if let Some(Value::Object(query)) = map.get(section) {
+            let mut query = query.clone();
+            for field in QUERY_TYPE_FIELDS {
+                if let Some(value) = query.get(field) {
+                    let value = resolve_value(value);
+                    query.insert(field.to_string(), value);
+                }
+            }
+            resolved.insert(section.to_string(), Value::Object(query));
+        }
+    }
+    Value::Object(resolved)
+}
+
+/// Visit every type name in the request fields that hold type paths - `component`,
+/// `resource`, `components` (an array or an object keyed by type path) and the query's `data`
+/// and `filter` fields
+fn visit_type_names(params: &Value, visit: &mut impl FnMut(&str)) {
+    let mut visit_value = |value: &Value| match value {
+        Value::String(name) => visit(name.as_str()),
+        Value::Array(names) => names.iter().filter_map(Value::as_str).for_each(&mut *visit),
+        Value::Object(map) => map.keys().for_each(|name| visit(name)),
+        _ => {},
+    };
+
+    for field in TYPE_FIELDS.iter().chain(iter::once(&"components")) {
+        if let Some(value) = params.get(*field) {
+            visit_value(value);
+        }
+    }
+    for section in ["data", "filter"] {
+        if let Some(query) = params.get(section) {
+            for field in QUERY_TYPE_FIELDS {
+                if let Some(value) = query.get(field) {
+                    visit_value(value);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn index() -> ShortNameIndex {
+        ShortNameIndex::from_schemas(&json!({
+            "bevy_transform::components::transform::Transform": {
+                "shortPath": "Transform",
+                "reflectTypes": ["Component", "Default"]
+            },
+            "my_game::Health": {"shortPath": "Health", "reflectTypes": ["Component"]},
+            "my_game::ui::Health": {"shortPath": "Health", "reflectTypes": ["Component"]},
+            "glam::Vec3": {"shortPath": "Vec3", "reflectTypes": ["Default"]},
+            "my_game::Score": {"reflectTypes": ["Resource"]}
+        }))
+    }
+
+    #[test]
+    fn indexes_components_and_resources_by_short_name() {
+        let index = index();
+
+        assert_eq!(index.type_count(), 4);
+        assert_eq!(index.resolve("Score"), ["my_game::Score"]);
+        assert!(index.resolve("Vec3").is_empty());
+        assert_eq!(
+            index.ambiguous().get("Health").map(|paths| paths.len()),
+            Some(2)
+        );
+    }
+
+    #[test]
+    fn resolves_only_unambiguous_short_names_in_type_positions() {
+        let index = index();
+        let params = json!({
+            "entity": 42,
+            "components": {"Transform": {"translation": [0.0, 1.0, 0.0]}, "Health": 10},
+            "data": {"components": ["Transform"], "with": ["Score"]},
+            "name": "Transform"
+        });
+
+        let resolutions = resolutions(&params, &index);
+        let resolved = apply_resolutions(&params, &resolutions);
+
+        assert_eq!(resolutions.len(), 2);
+        assert_eq!(
+            resolved["components"]["bevy_transform::components::transform::Transform"]["translation"],
+            json!([0.0, 1.0, 0.0])
+        );
+        assert_eq!(resolved["components"]["Health"], 10);
+        assert_eq!(
+            resolved["data"]["components"],
+            json!(["bevy_transform::components::transform::Transform"])
+        );
+        assert_eq!(resolved["data"]["with"], json!(["my_game::Score"]));
+        assert_eq!(resolved["name"], "Transform");
+    }
+}
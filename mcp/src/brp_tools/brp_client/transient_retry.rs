@@ -0,0 +1,132 @@
+//! Brief retries of requests that failed while the app was still catching up
+//!
+//! Some BRP errors only mean the app hasn't caught up yet: an entity a system just spawned whose
+//! commands haven't been applied, or an asset whose handle exists but which hasn't finished
+//! loading. Such failures are retried up to `MAX_RETRIES` times with doubling delays before they
+//! are surfaced. Retries are rate limited per port - at most `MAX_RETRIES_PER_WINDOW` within
+//! `RETRY_WINDOW` - so an entity that really is gone doesn't slow every call down. How often a
+//! call was retried is reported in its response metadata.
+
+use std::collections::HashMap;
+use std::collections::VecDeque;
+use std::sync::LazyLock;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::Instant;
+
+use tracing::debug;
+
+use super::call_report;
+use super::types::BrpClientError;
+use crate::brp_tools::Port;
+
+/// Retries of one request at most
+pub const MAX_RETRIES: u32 = 3;
+
+/// Delay before the first retry - each further retry waits twice as long
+const INITIAL_DELAY: Duration = Duration::from_millis(25);
+
+/// Retries of requests to one port at most within `RETRY_WINDOW`
+const MAX_RETRIES_PER_WINDOW: usize = 10;
+
+/// Window over which retries are rate limited
+const RETRY_WINDOW: Duration = Duration::from_secs(5);
+
+/// Lowercase message fragments of errors about assets that haven't finished loading
+const ASSET_LOADING_FRAGMENTS: &[&str] = &[
+    "not loaded",
+    "not yet loaded",
+    "still loading",
+    "is loading",
+    "not finished loading",
+];
+
+/// When each recent retry on a port happened, oldest first
+static RECENT_RETRIES: LazyLock<Mutex<HashMap<Port, VecDeque<Instant>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Whether `error` is likely to go away if the request is sent again shortly
+pub fn is_transient(error: &BrpClientError) -> bool {
+    if error.is_entity_not_found() {
+        return true;
+    }
+    let message = error.get_message().to_lowercase();
+    message.contains("asset")
+        && ASSET_LOADING_FRAGMENTS
+            .iter()
+            .any(|fragment| message.contains(fragment))
+}
+
+/// Delay before retry number `attempt` (starting at 0), or `None` when the request shouldn't be
+/// retried again
+///
+/// Each granted retry counts against the port's rate limit.
+pub fn next_delay(port: Port, attempt: u32) -> Option<Duration> {
+    if attempt >= MAX_RETRIES {
+        return None;
+    }
+    let mut recent = RECENT_RETRIES.lock().ok()?;
+    let acquired = try_acquire(recent.entry(port).or_default(), Instant::now());
+    drop(recent);
+    if !acquired {
+        debug!("Transient error retries on port {port} are rate limited - not retrying");
+        return None;
+    }
+    Some(INITIAL_DELAY * 2_u32.pow(attempt))
+}
+
+/// Report how often the running tool call's request was retried
+pub fn record_retries(retries: u32) {
+    if retries > 0 {
+        call_report::record(|report| report.transient_retries += retries);
+    }
+}
+
+/// Take a retry from a port's budget, forgetting retries older than `RETRY_WINDOW`
+fn try_acquire(retries: &mut VecDeque<Instant>, now: Instant) -> bool {
+    while retries
+        .front()
+        .is_some_and(|retry| now.saturating_duration_since(*retry) >= RETRY_WINDOW)
+    {
+        retries.pop_front();
+    }
+    if retries.len() >= MAX_RETRIES_PER_WINDOW {
+        return false;
+    }
+    retries.push_back(now);
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(code: i32, message: &str) -> BrpClientError {
+        BrpClientError {
+            code,
+            message: message.to_string(),
+            data: None,
+        }
+    }
+
+    #[test]
+    fn recognizes_transient_errors() {
+        assert!(is_transient(&error(-23_401, "Entity 4294967301 not found")));
+        assert!(is_transient(&error(
+            -23_402,
+            "Asset 'textures/player.png' is not loaded yet"
+        )));
+        assert!(!is_transient(&error(-23_402, "Unknown component type")));
+    }
+
+    #[test]
+    fn rate_limits_retries_per_window() {
+        let start = Instant::now();
+        let mut retries = VecDeque::new();
+        for _ in 0..MAX_RETRIES_PER_WINDOW {
+            assert!(try_acquire(&mut retries, start));
+        }
+        assert!(!try_acquire(&mut retries, start));
+        assert!(try_acquire(&mut retries, start + RETRY_WINDOW));
+    }
+}
@@ -0,0 +1,106 @@
+//! Type-path alias correction for BRP requests
+//!
+//! When a request fails because a type path isn't registered, the path may simply come from a
+//! different Bevy release (see `BrpTypeName::aliases`). These helpers find aliased type paths in
+//! request parameters and rewrite them so the client can retry with the path the app knows.
+
+use serde_json::Map;
+use serde_json::Value;
+use serde_json::json;
+
+use crate::brp_tools::BrpTypeName;
+
+/// A single type-path substitution applied to a request
+#[derive(Debug, Clone)]
+pub struct TypeAliasSubstitution {
+    pub original:  BrpTypeName,
+    pub corrected: BrpTypeName,
+}
+
+impl TypeAliasSubstitution {
+    /// Describe this substitution for the `format_corrections` metadata
+    pub fn to_correction(&self) -> Value {
+        json!({
+            "original_type": self.original,
+            "corrected_type": self.corrected,
+            "reason": "type path normalized to the path registered by the connected Bevy version",
+        })
+    }
+}
+
+/// Find every type path in `params` (object keys or string values) that has known aliases
+pub fn find_aliased_type_names(params: &Value) -> Vec<(BrpTypeName, Vec<BrpTypeName>)> {
+    let mut found: Vec<(BrpTypeName, Vec<BrpTypeName>)> = Vec::new();
+    let mut record = |candidate: &str| {
+        let type_name = BrpTypeName::from(candidate);
+        if found.iter().any(|(existing, _)| *existing == type_name) {
+            return;
+        }
+        let aliases = type_name.aliases();
+        if !aliases.is_empty() {
+            found.push((type_name, aliases));
+        }
+    };
+    visit_strings(params, &mut record);
+    found
+}
+
+/// Build the substitutions to try on attempt `attempt` - each aliased name uses its
+/// `attempt`-th alias (or its last one when it has fewer)
+pub fn substitutions_for_attempt(
+    aliased: &[(BrpTypeName, Vec<BrpTypeName>)],
+    attempt: usize,
+) -> Vec<TypeAliasSubstitution> {
+    aliased
+        .iter()
+        .filter_map(|(original, aliases)| {
+            aliases
+                .get(attempt)
+                .or_else(|| aliases.last())
+                .map(|corrected| TypeAliasSubstitution {
+                    original:  original.clone(),
+                    corrected: corrected.clone(),
+                })
+        })
+        .collect()
+}
+
+/// Rewrite every exact occurrence of a substituted type path in `params`
+pub fn apply_substitutions(params: &Value, substitutions: &[TypeAliasSubstitution]) -> Value {
+    let replace = |s: &str| {
+        substitutions
+            .iter()
+            .find(|sub| sub.original.as_str() == s)
+            .map_or_else(|| s.to_string(), |sub| sub.corrected.to_string())
+    };
+
+    match params {
+        Value::String(s) => Value::String(replace(s)),
+        Value::Array(items) => Value::Array(
+            items
+                .iter()
+                .map(|item| apply_substitutions(item, substitutions))
+                .collect(),
+        ),
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(key, value)| (replace(key), apply_substitutions(value, substitutions)))
+                .collect::<Map<String, Value>>(),
+        ),
+        other => other.clone(),
+    }
+}
+
+fn visit_strings(value: &Value, visit: &mut impl FnMut(&str)) {
+    match value {
+        Value::String(s) => visit(s),
+        Value::Array(items) => items.iter().for_each(|item| visit_strings(item, visit)),
+        Value::Object(map) => {
+            for (key, value) in map {
+                visit(key);
+                visit_strings(value, visit);
+            }
+        },
+        _ => {},
+    }
+}
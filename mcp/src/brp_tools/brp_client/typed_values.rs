@@ -0,0 +1,380 @@
+//! Typed walk over the values of spawn, insert and mutate requests
+//!
+//! The value correctors - color formats, math shorthand, enum casing and numeric strings - all
+//! need to know which type a value is written for. `rewrite_typed_values` walks each component or
+//! resource value alongside the type guide's mutation paths, offers every value with the types
+//! found for its path to the corrector, and replaces the values the corrector rewrites.
+
+use std::collections::HashMap;
+
+use serde_json::Value;
+use serde_json::json;
+
+use super::enum_variant::normalize_path;
+use crate::brp_tools::BrpTypeName;
+use crate::brp_tools::brp_type_guide::TypeGuideEngine;
+use crate::tool::BrpMethod;
+use crate::tool::ParameterName;
+
+/// A value in a request, with the types the type guide gives for where it sits
+pub struct TypedValue<'a> {
+    /// Component or resource the value belongs to
+    pub root:       &'a BrpTypeName,
+    /// Mutation paths and their types that describe the value - its own path first, then `.0`
+    /// below it, since a single-field tuple struct such as `BackgroundColor(Color)` is written as
+    /// its field
+    pub candidates: Vec<(&'a str, &'a BrpTypeName)>,
+    /// The value as written in the request
+    pub value:      &'a Value,
+}
+
+/// A single value rewritten in a request
+#[derive(Debug, Clone)]
+pub struct Rewrite {
+    pub type_name: BrpTypeName,
+    pub path:      String,
+    pub original:  Value,
+    pub corrected: Value,
+}
+
+impl Rewrite {
+    /// Describe this rewrite for the `format_corrections` metadata
+    pub fn to_correction(&self, reason: &str) -> Value {
+        json!({
+            "type": self.type_name,
+            "path": self.path,
+            "original_value": self.original,
+            "corrected_value": self.corrected,
+            "reason": reason,
+        })
+    }
+}
+
+/// Whether `method` carries component or resource values the value correctors can rewrite
+pub const fn has_typed_values(method: BrpMethod) -> bool {
+    matches!(
+        method,
+        BrpMethod::WorldSpawnEntity
+            | BrpMethod::WorldInsertComponents
+            | BrpMethod::WorldInsertResources
+            | BrpMethod::WorldMutateComponents
+            | BrpMethod::WorldMutateResources
+    )
+}
+
+/// Offer every component or resource value of a spawn, insert or mutate request to `rewrite`,
+/// returning the corrected parameters and what was rewritten
+///
+/// A value `rewrite` replaces is not walked into. Returns `None` when nothing was rewritten.
+pub fn rewrite_typed_values(
+    method: BrpMethod,
+    params: &Value,
+    engine: &TypeGuideEngine,
+    mut rewrite: impl FnMut(&TypedValue) -> Option<Value>,
+) -> Option<(Value, Vec<Rewrite>)> {
+    let mut corrected = params.clone();
+    let mut rewrites = Vec::new();
+
+    match method {
+        BrpMethod::WorldSpawnEntity | BrpMethod::WorldInsertComponents => {
+            let components = corrected
+                .get_mut(ParameterName::Components.as_ref())
+                .and_then(Value::as_object_mut)?;
+            for (type_name, value) in components.iter_mut() {
+                let mut walker = Walker::new(engine, type_name, &mut rewrite, &mut rewrites);
+                walker.walk(value, String::new());
+            }
+        },
+        BrpMethod::WorldInsertResources
+        | BrpMethod::WorldMutateComponents
+        | BrpMethod::WorldMutateResources => {
+            let type_field = if method == BrpMethod::WorldMutateComponents {
+                ParameterName::Component
+            } else {
+                ParameterName::Resource
+            };
+            let type_name = corrected.get(type_field.as_ref())?.as_str()?.to_string();
+            let path = corrected
+                .get(ParameterName::Path.as_ref())
+                .and_then(Value::as_str)
+                .map(normalize_path)
+                .unwrap_or_default();
+            let value = corrected.get_mut(ParameterName::Value.as_ref())?;
+            let mut walker = Walker::new(engine, &type_name, &mut rewrite, &mut rewrites);
+            walker.walk(value, path);
+        },
+        _ => return None,
+    }
+
+    (!rewrites.is_empty()).then_some((corrected, rewrites))
+}
+
+/// Walks one component or resource value alongside its mutation paths
+struct Walker<'a, F> {
+    type_name:  BrpTypeName,
+    path_types: HashMap<String, BrpTypeName>,
+    rewrite:    &'a mut F,
+    rewrites:   &'a mut Vec<Rewrite>,
+}
+
+impl<'a, F> Walker<'a, F>
+where
+    F: FnMut(&TypedValue) -> Option<Value>,
+{
+    fn new(
+        engine: &TypeGuideEngine,
+        type_name: &str,
+        rewrite: &'a mut F,
+        rewrites: &'a mut Vec<Rewrite>,
+    ) -> Self {
+        Self {
+            type_name: BrpTypeName::from(type_name),
+            path_types: engine.mutation_path_types(type_name),
+            rewrite,
+            rewrites,
+        }
+    }
+
+    /// Offer `value`, which sits at mutation path `path`, to the corrector and walk into it if
+    /// it was left alone
+    fn walk(&mut self, value: &mut Value, path: String) {
+        let newtype_path = format!("{path}.0");
+        let candidates = [path.as_str(), newtype_path.as_str()]
+            .into_iter()
+            .filter_map(|candidate| {
+                self.path_types
+                    .get_key_value(candidate)
+                    .map(|(path, type_name)| (path.as_str(), type_name))
+            })
+            .collect();
+        let typed = TypedValue {
+            root: &self.type_name,
+            candidates,
+            value,
+        };
+        if let Some(corrected) = (self.rewrite)(&typed) {
+            self.rewrites.push(Rewrite {
+                type_name: self.type_name.clone(),
+                path,
+                original: value.clone(),
+                corrected: corrected.clone(),
+            });
+            *value = corrected;
+            return;
+        }
+
+        match value {
+            Value::Object(map) => {
+                for (key, child) in map.iter_mut() {
+                    // Keys that aren't fields are enum variant names, which add nothing to the
+                    // path
+                    let field_path = format!("{path}.{key}");
+                    let child_path = if self.path_types.contains_key(&field_path) {
+                        field_path
+                    } else {
+                        path.clone()
+                    };
+                    self.walk(child, child_path);
+                }
+            },
+            Value::Array(items) => {
+                for (index, child) in items.iter_mut().enumerate() {
+                    // Tuples use `.0`, lists and arrays `[0]` - and guides only describe the
+                    // first element of a list
+                    let child_path = [
+                        format!("{path}.{index}"),
+                        format!("{path}[{index}]"),
+                        format!("{path}[0]"),
+                    ]
+                    .into_iter()
+                    .find(|candidate| self.path_types.contains_key(candidate))
+                    .unwrap_or_else(|| format!("{path}[{index}]"));
+                    self.walk(child, child_path);
+                }
+            },
+            _ => {},
+        }
+    }
+}
+
+/// An engine whose registry holds `test::Tint`, a component with a `color: Color` field and a
+/// `label: String` field
+#[cfg(test)]
+pub fn tint_engine() -> TypeGuideEngine {
+    let field = |type_name: &str| json!({ "type": { "$ref": format!("#/$defs/{type_name}") } });
+    let f32_field = field("f32");
+    let registry = [
+        (
+            "f32",
+            json!({ "typePath": "f32", "shortPath": "f32", "kind": "Value", "type": "float" }),
+        ),
+        (
+            "alloc::string::String",
+            json!({
+                "typePath": "alloc::string::String",
+                "shortPath": "String",
+                "kind": "Value",
+                "type": "string"
+            }),
+        ),
+        (
+            "bevy_color::srgba::Srgba",
+            json!({
+                "typePath": "bevy_color::srgba::Srgba",
+                "shortPath": "Srgba",
+                "kind": "Struct",
+                "type": "object",
+                "properties": {
+                    "red": f32_field,
+                    "green": f32_field,
+                    "blue": f32_field,
+                    "alpha": f32_field
+                },
+                "required": ["red", "green", "blue", "alpha"],
+                "reflectTypes": ["Serialize", "Deserialize", "Default"]
+            }),
+        ),
+        (
+            "bevy_color::color::Color",
+            json!({
+                "typePath": "bevy_color::color::Color",
+                "shortPath": "Color",
+                "kind": "Enum",
+                "type": "object",
+                "oneOf": [{
+                    "typePath": "bevy_color::color::Color::Srgba",
+                    "shortPath": "Srgba",
+                    "kind": "Tuple",
+                    "type": "array",
+                    "prefixItems": [field("bevy_color::srgba::Srgba")]
+                }],
+                "reflectTypes": ["Serialize", "Deserialize", "Default"]
+            }),
+        ),
+        (
+            "test::Tint",
+            json!({
+                "typePath": "test::Tint",
+                "shortPath": "Tint",
+                "kind": "Struct",
+                "type": "object",
+                "properties": {
+                    "color": field("bevy_color::color::Color"),
+                    "label": field("alloc::string::String")
+                },
+                "required": ["color", "label"],
+                "reflectTypes": ["Component", "Serialize", "Deserialize"]
+            }),
+        ),
+    ]
+    .into_iter()
+    .map(|(type_name, schema)| (BrpTypeName::from(type_name), schema))
+    .collect();
+    TypeGuideEngine::from_registry(registry)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COLOR: &str = "bevy_color::color::Color";
+
+    fn red() -> Value {
+        json!({ "Srgba": { "red": 1.0, "green": 0.0, "blue": 0.0, "alpha": 1.0 } })
+    }
+
+    /// Replace every `Color` value with `"replaced"`
+    fn replace_colors(typed: &TypedValue) -> Option<Value> {
+        typed
+            .candidates
+            .iter()
+            .any(|(_, type_name)| type_name.as_str() == COLOR)
+            .then(|| json!("replaced"))
+    }
+
+    #[test]
+    fn walks_into_values_and_offers_them_with_their_path_types() {
+        let engine = tint_engine();
+        let params = json!({ "components": { "test::Tint": { "color": red(), "label": "lamp" } } });
+
+        let (corrected, rewrites) =
+            rewrite_typed_values(BrpMethod::WorldSpawnEntity, &params, &engine, |typed| {
+                (typed.value == &json!("lamp")).then(|| json!("LAMP"))
+            })
+            .unwrap_or_else(|| unreachable!("the label should be rewritten"));
+
+        assert_eq!(
+            corrected["components"]["test::Tint"]["label"],
+            json!("LAMP")
+        );
+        assert_eq!(corrected["components"]["test::Tint"]["color"], red());
+        assert_eq!(rewrites.len(), 1);
+        assert_eq!(rewrites[0].type_name.as_str(), "test::Tint");
+        assert_eq!(rewrites[0].path, ".label");
+        assert_eq!(rewrites[0].original, json!("lamp"));
+    }
+
+    #[test]
+    fn does_not_walk_into_a_replaced_value() {
+        let engine = tint_engine();
+        let params = json!({ "components": { "test::Tint": { "color": red(), "label": "lamp" } } });
+
+        let mut offered = 0;
+        let (corrected, rewrites) = rewrite_typed_values(
+            BrpMethod::WorldInsertComponents,
+            &params,
+            &engine,
+            |typed| {
+                offered += 1;
+                replace_colors(typed)
+            },
+        )
+        .unwrap_or_else(|| unreachable!("the color should be rewritten"));
+
+        assert_eq!(
+            corrected["components"]["test::Tint"]["color"],
+            json!("replaced")
+        );
+        assert_eq!(rewrites.len(), 1);
+        assert_eq!(rewrites[0].path, ".color");
+        // The component, its color and its label - none of the color's channels
+        assert_eq!(offered, 3);
+    }
+
+    #[test]
+    fn mutate_values_start_at_the_request_path() {
+        let engine = tint_engine();
+        let params =
+            json!({ "entity": 1, "component": "test::Tint", "path": "color", "value": red() });
+
+        let (corrected, rewrites) = rewrite_typed_values(
+            BrpMethod::WorldMutateComponents,
+            &params,
+            &engine,
+            replace_colors,
+        )
+        .unwrap_or_else(|| unreachable!("the color should be rewritten"));
+
+        assert_eq!(corrected["value"], json!("replaced"));
+        assert_eq!(corrected["path"], json!("color"));
+        assert_eq!(rewrites[0].path, ".color");
+    }
+
+    #[test]
+    fn nothing_rewritten_or_no_typed_values_gives_none() {
+        let engine = tint_engine();
+        let params = json!({ "components": { "test::Tint": { "color": red(), "label": "lamp" } } });
+        assert!(
+            rewrite_typed_values(BrpMethod::WorldSpawnEntity, &params, &engine, |_| None).is_none()
+        );
+        assert!(
+            rewrite_typed_values(
+                BrpMethod::WorldGetComponents,
+                &params,
+                &engine,
+                replace_colors
+            )
+            .is_none()
+        );
+    }
+}
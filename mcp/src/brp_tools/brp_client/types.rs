@@ -1,17 +1,24 @@
 //! Common types for BRP tools
 
+use schemars::SchemaGenerator;
 use serde::Deserialize;
 use serde::Serialize;
 use serde_json::Value;
 
 use super::constants::BRP_ERROR_ACCESS_ERROR;
+use super::constants::BRP_ERROR_CODE_ENTITY_NOT_FOUND;
 use super::constants::BRP_ERROR_CODE_UNKNOWN_COMPONENT_TYPE;
 use super::constants::JSON_RPC_ERROR_INTERNAL_ERROR;
 use super::constants::JSON_RPC_ERROR_INVALID_PARAMS;
+use super::format_correction::Corrector;
 use crate::error::Error;
 use crate::error::Result;
 use crate::tool::BrpMethod;
+use crate::tool::FieldPlacement;
+use crate::tool::FlattenedMetadata;
+use crate::tool::OutputSchemaField;
 use crate::tool::ParameterName;
+use crate::tool::ResponseBuilder;
 
 /// Configuration trait for BRP tools to control enhanced error handling
 pub trait BrpToolConfig {
@@ -42,6 +49,9 @@ impl BrpClientError {
     /// Get the error message
     pub fn get_message(&self) -> &str { &self.message }
 
+    /// Check if the app rejected the request because an entity doesn't exist
+    pub const fn is_entity_not_found(&self) -> bool { self.code == BRP_ERROR_CODE_ENTITY_NOT_FOUND }
+
     /// Check if this error indicates a format issue that can be recovered
     /// This function was constructed through trial and error via vibe coding with claude
     /// There is a bug in `bevy_remote` right now that we get a spurious "Unknown component type"
@@ -97,7 +107,9 @@ pub enum ResponseStatus {
 }
 
 /// Status of format correction attempts
-#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[derive(
+    Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize, schemars::JsonSchema,
+)]
 #[serde(rename_all = "snake_case")]
 pub enum FormatCorrectionStatus {
     /// Format discovery was not enabled for this request
@@ -108,6 +120,100 @@ pub enum FormatCorrectionStatus {
     Succeeded,
 }
 
+/// How a request was corrected to succeed - flattened into the metadata of tools that correct
+/// formats with `#[to_metadata(flatten)]`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, schemars::JsonSchema)]
+pub struct FormatCorrections {
+    /// Corrections applied so the request matches what the app expects, such as type path
+    /// substitutions for the connected Bevy version
+    #[serde(rename = "format_corrections", skip_serializing_if = "Option::is_none")]
+    pub corrections: Option<Vec<Value>>,
+    /// Whether a format correction was applied to make the request succeed
+    #[serde(rename = "format_corrected", skip_serializing_if = "Option::is_none")]
+    pub status:      Option<FormatCorrectionStatus>,
+    /// Correctors whose changes made the request succeed, in the order they fired
+    #[serde(rename = "format_correctors", skip_serializing_if = "Option::is_none")]
+    pub correctors:  Option<Vec<Corrector>>,
+}
+
+impl FormatCorrections {
+    /// Keep only what is worth reporting - no empty correction list, no status for a request
+    /// that needed no correction
+    ///
+    /// Each correction comes with the corrector that made it, and `format_correctors` lists
+    /// those correctors once each.
+    pub fn new(
+        corrections: Option<Vec<(Corrector, Value)>>,
+        status: Option<FormatCorrectionStatus>,
+    ) -> Self {
+        let corrections = corrections.filter(|corrections| !corrections.is_empty());
+        let correctors = corrections.as_ref().map(|corrections| {
+            corrections
+                .iter()
+                .fold(Vec::new(), |mut correctors, (corrector, _)| {
+                    if !correctors.contains(corrector) {
+                        correctors.push(*corrector);
+                    }
+                    correctors
+                })
+        });
+        Self {
+            corrections: corrections.map(|corrections| {
+                corrections
+                    .into_iter()
+                    .map(|(_, correction)| correction)
+                    .collect()
+            }),
+            status: status.filter(|status| *status != FormatCorrectionStatus::NotAttempted),
+            correctors,
+        }
+    }
+}
+
+impl FlattenedMetadata for FormatCorrections {
+    fn add_metadata(&self, builder: ResponseBuilder) -> Result<ResponseBuilder> {
+        let mut builder = builder;
+        if let Some(corrections) = &self.corrections {
+            builder = builder.add_field_to(
+                "format_corrections",
+                corrections,
+                FieldPlacement::Metadata,
+            )?;
+        }
+        if let Some(status) = &self.status {
+            builder = builder.add_field_to("format_corrected", status, FieldPlacement::Metadata)?;
+        }
+        if let Some(correctors) = &self.correctors {
+            builder =
+                builder.add_field_to("format_correctors", correctors, FieldPlacement::Metadata)?;
+        }
+        Ok(builder)
+    }
+
+    fn output_schema_fields(generator: &mut SchemaGenerator) -> Vec<OutputSchemaField> {
+        vec![
+            OutputSchemaField {
+                field_name: "format_corrections",
+                placement:  FieldPlacement::Metadata,
+                group:      None,
+                schema:     generator.subschema_for::<Option<Vec<Value>>>(),
+            },
+            OutputSchemaField {
+                field_name: "format_corrected",
+                placement:  FieldPlacement::Metadata,
+                group:      None,
+                schema:     generator.subschema_for::<Option<FormatCorrectionStatus>>(),
+            },
+            OutputSchemaField {
+                field_name: "format_correctors",
+                placement:  FieldPlacement::Metadata,
+                group:      None,
+                schema:     generator.subschema_for::<Option<Vec<Corrector>>>(),
+            },
+        ]
+    }
+}
+
 /// Type of BRP operation being performed
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize)]
 #[serde(rename_all = "snake_case")]
@@ -245,6 +351,31 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn format_corrections_report_only_what_happened() {
+        assert_eq!(
+            FormatCorrections::new(Some(Vec::new()), Some(FormatCorrectionStatus::NotAttempted)),
+            FormatCorrections::default()
+        );
+
+        let corrections = FormatCorrections::new(
+            Some(vec![
+                (Corrector::ShortNames, json!({ "from": "Foo" })),
+                (Corrector::NumericStrings, json!({ "from": "1" })),
+                (Corrector::ShortNames, json!({ "from": "Bar" })),
+            ]),
+            Some(FormatCorrectionStatus::Succeeded),
+        );
+        assert_eq!(
+            serde_json::to_value(&corrections).ok(),
+            Some(json!({
+                "format_corrections": [{ "from": "Foo" }, { "from": "1" }, { "from": "Bar" }],
+                "format_corrected": "succeeded",
+                "format_correctors": ["short_names", "numeric_strings"],
+            }))
+        );
+    }
+
     #[test]
     fn test_extract_from_components_object() {
         // Test with valid components object
@@ -0,0 +1,355 @@
+//! Binary wire encodings for high-volume BRP methods
+//!
+//! JSON over HTTP is the bottleneck for large world dumps and busy watches. Apps built with the
+//! `binary-transport` feature of `bevy_brp_extras` serve BRP on a second TCP port as
+//! length-prefixed `MessagePack` or CBOR frames, and advertise it through
+//! `brp_extras/wire_encodings`. The encoding is negotiated once per port; queries, component
+//! reads, registry dumps and watches then go over the binary transport and are decoded back to
+//! JSON here, so nothing past the client sees the difference. Apps without it keep using JSON.
+//!
+//! Framing: a request is one byte naming the encoding (`M` or `C`), a big-endian `u32` length
+//! and the encoded JSON-RPC request. Each response is a big-endian `u32` length followed by an
+//! encoded JSON-RPC response, in the request's encoding - one for a regular method, one per
+//! update for a watch. The app closes the connection after the last response.
+
+use std::collections::HashMap;
+use std::sync::LazyLock;
+use std::sync::Mutex;
+
+use serde_json::Value;
+use strum::Display;
+use strum::EnumString;
+use tokio::io::AsyncReadExt;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+use tracing::debug;
+use tracing::warn;
+
+use super::constants::BRP_DEFAULT_HOST;
+use super::constants::BRP_EXTRAS_PREFIX;
+use super::http_client::BrpHttpClient;
+use super::json_rpc_builder::BrpJsonRpcBuilder;
+use super::json_rpc_builder::RequestId;
+use super::remote_host::remote_host_for;
+use super::types::BrpClientCallJsonResponse;
+use crate::brp_tools::Port;
+use crate::error::Error;
+use crate::error::Result;
+
+/// Environment variable choosing the wire encoding - `auto` (the default) prefers `MessagePack`,
+/// then CBOR, when the app offers them; `json` never uses the binary transport
+pub const WIRE_ENCODING_ENV_VAR: &str = "BRP_MCP_WIRE_ENCODING";
+
+/// Largest response frame accepted - guards against reading garbage as a length
+const MAX_FRAME_BYTES: usize = 256 * 1024 * 1024;
+
+/// A binary encoding of JSON-RPC messages
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display, EnumString)]
+#[strum(serialize_all = "lowercase")]
+pub enum WireEncoding {
+    /// `MessagePack`
+    Msgpack,
+    /// CBOR
+    Cbor,
+}
+
+impl WireEncoding {
+    /// Byte naming the encoding at the start of a request frame
+    const fn tag(self) -> u8 {
+        match self {
+            Self::Msgpack => b'M',
+            Self::Cbor => b'C',
+        }
+    }
+
+    fn encode(self, value: &Value) -> Result<Vec<u8>> {
+        let encoded = match self {
+            Self::Msgpack => rmp_serde::to_vec_named(value).map_err(|e| e.to_string()),
+            Self::Cbor => {
+                let mut bytes = Vec::new();
+                ciborium::into_writer(value, &mut bytes)
+                    .map(|()| bytes)
+                    .map_err(|e| e.to_string())
+            },
+        };
+        encoded.map_err(|e| Error::JsonRpc(format!("Failed to encode {self} request: {e}")).into())
+    }
+
+    fn decode(self, bytes: &[u8]) -> Result<Value> {
+        let decoded = match self {
+            Self::Msgpack => rmp_serde::from_slice(bytes).map_err(|e| e.to_string()),
+            Self::Cbor => ciborium::from_reader(bytes).map_err(|e| e.to_string()),
+        };
+        decoded.map_err(|e| Error::JsonRpc(format!("Failed to decode {self} response: {e}")).into())
+    }
+}
+
+/// The binary transport an app offers, with the encoding chosen for it
+#[derive(Debug, Clone, Copy)]
+pub struct BinaryTransport {
+    /// TCP port the app serves binary frames on
+    port:     u16,
+    /// Encoding both sides support
+    encoding: WireEncoding,
+}
+
+/// Negotiated transport per BRP port - `None` when the app only speaks JSON
+static NEGOTIATED: LazyLock<Mutex<HashMap<Port, Option<BinaryTransport>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Whether `method_name` is worth sending over the binary transport
+pub fn is_high_volume(method_name: &str) -> bool {
+    method_name.ends_with("+watch")
+        || matches!(
+            method_name,
+            "world.query" | "world.get_components" | "registry.schema"
+        )
+}
+
+/// Encodings to offer, most preferred first, from `BRP_MCP_WIRE_ENCODING`
+fn preferred_encodings() -> Vec<WireEncoding> {
+    let setting = std::env::var(WIRE_ENCODING_ENV_VAR).unwrap_or_default();
+    match setting.trim().to_ascii_lowercase().as_str() {
+        "json" => Vec::new(),
+        "" | "auto" => vec![WireEncoding::Msgpack, WireEncoding::Cbor],
+        other => other.parse().map_or_else(
+            |_| {
+                debug!("Unknown {WIRE_ENCODING_ENV_VAR} value '{other}' - using JSON");
+                Vec::new()
+            },
+            |encoding| vec![encoding],
+        ),
+    }
+}
+
+/// Binary transport for `port`, negotiated with the app on first use
+pub async fn transport_for(port: Port) -> Option<BinaryTransport> {
+    if remote_host_for(port).is_some() {
+        // Only the BRP port is forwarded to remote instances
+        return None;
+    }
+    if let Some(negotiated) = NEGOTIATED
+        .lock()
+        .ok()
+        .and_then(|negotiated| negotiated.get(&port).copied())
+    {
+        return negotiated;
+    }
+
+    let transport = negotiate(port).await;
+    if let Ok(mut negotiated) = NEGOTIATED.lock() {
+        negotiated.insert(port, transport);
+    }
+    transport
+}
+
+/// Forget the negotiated transport for `port` - the app may restart with different settings
+pub fn forget_wire_encoding(port: Port) {
+    if let Ok(mut negotiated) = NEGOTIATED.lock() {
+        negotiated.remove(&port);
+    }
+}
+
+/// Open a binary response stream for `method_name` on `port`, or `None` to use HTTP instead
+///
+/// A transport that was negotiated but can't be reached is forgotten, so the next request
+/// negotiates again rather than failing the same way.
+pub async fn open_stream(
+    port: Port,
+    method_name: &str,
+    params: Option<&Value>,
+    request_id: &RequestId,
+) -> Option<BinaryResponseStream> {
+    let transport = transport_for(port).await?;
+    match transport.open(method_name, params, request_id).await {
+        Ok(stream) => Some(stream),
+        Err(e) => {
+            warn!("Binary BRP transport failed, falling back to JSON: {e}");
+            forget_wire_encoding(port);
+            None
+        },
+    }
+}
+
+async fn negotiate(port: Port) -> Option<BinaryTransport> {
+    let preferred = preferred_encodings();
+    if preferred.is_empty() {
+        return None;
+    }
+
+    let response =
+        BrpHttpClient::for_method_name(format!("{BRP_EXTRAS_PREFIX}wire_encodings"), port, None)
+            .send_request()
+            .await
+            .ok()?;
+    let response: BrpClientCallJsonResponse = response.json().await.ok()?;
+    let offer = response.result?;
+
+    let binary_port = offer
+        .get("port")
+        .and_then(Value::as_u64)
+        .and_then(|binary_port| u16::try_from(binary_port).ok())?;
+    let offered: Vec<WireEncoding> = offer
+        .get("encodings")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|encoding| encoding.as_str()?.parse().ok())
+        .collect();
+    let encoding = preferred
+        .into_iter()
+        .find(|encoding| offered.contains(encoding))?;
+
+    debug!("Using {encoding} wire encoding on port {binary_port} for BRP port {port}");
+    Some(BinaryTransport {
+        port: binary_port,
+        encoding,
+    })
+}
+
+impl BinaryTransport {
+    /// Send a request and return its JSON-RPC response
+    pub(super) async fn request(
+        self,
+        method_name: &str,
+        params: Option<&Value>,
+        request_id: &RequestId,
+    ) -> Result<BrpClientCallJsonResponse> {
+        let mut stream = self.open(method_name, params, request_id).await?;
+        let response = stream.next_response().await?.ok_or_else(|| {
+            Error::JsonRpc(format!("No {} response to {method_name}", self.encoding))
+        })?;
+        serde_json::from_value(response).map_err(|e| {
+            Error::JsonRpc(format!("Unexpected {} response: {e}", self.encoding)).into()
+        })
+    }
+
+    /// Send a request and return the stream of its JSON-RPC responses
+    pub async fn open(
+        self,
+        method_name: &str,
+        params: Option<&Value>,
+        request_id: &RequestId,
+    ) -> Result<BinaryResponseStream> {
+        let mut builder = BrpJsonRpcBuilder::new(method_name).id(request_id.clone());
+        if let Some(params) = params {
+            builder = builder.params(params.clone());
+        }
+        let payload = self.encoding.encode(&builder.build())?;
+        let length = u32::try_from(payload.len())
+            .map_err(|_| Error::JsonRpc(format!("{method_name} request is too large")))?;
+
+        let mut stream = TcpStream::connect((BRP_DEFAULT_HOST, self.port))
+            .await
+            .map_err(|e| Error::failed_to("connect to the binary BRP transport", &e))?;
+        let mut frame = Vec::with_capacity(payload.len() + 5);
+        frame.push(self.encoding.tag());
+        frame.extend_from_slice(&length.to_be_bytes());
+        frame.extend_from_slice(&payload);
+        stream
+            .write_all(&frame)
+            .await
+            .map_err(|e| Error::failed_to("send a binary BRP request", &e))?;
+
+        Ok(BinaryResponseStream {
+            stream,
+            encoding: self.encoding,
+        })
+    }
+}
+
+/// JSON-RPC responses arriving over the binary transport
+pub struct BinaryResponseStream {
+    stream:   TcpStream,
+    encoding: WireEncoding,
+}
+
+impl BinaryResponseStream {
+    /// The next response, decoded to JSON - `None` once the app closes the connection
+    pub async fn next_response(&mut self) -> Result<Option<Value>> {
+        let mut length = [0_u8; 4];
+        match self.stream.read_exact(&mut length).await {
+            Ok(_) => {},
+            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(e) => return Err(Error::failed_to("read a binary BRP response", &e).into()),
+        }
+        let length = usize::try_from(u32::from_be_bytes(length)).unwrap_or(usize::MAX);
+        if length > MAX_FRAME_BYTES {
+            return Err(Error::JsonRpc(format!(
+                "Binary BRP response of {length} bytes exceeds the {MAX_FRAME_BYTES} byte limit"
+            ))
+            .into());
+        }
+
+        let mut payload = vec![0; length];
+        self.stream
+            .read_exact(&mut payload)
+            .await
+            .map_err(|e| Error::failed_to("read a binary BRP response", &e))?;
+        self.encoding.decode(&payload).map(Some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn encodings_round_trip_json_rpc_responses() {
+        let response = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "result": [{"entity": 4_294_967_298_u64, "components": {"Transform": [0.5, -1.0]}}]
+        });
+
+        for encoding in [WireEncoding::Msgpack, WireEncoding::Cbor] {
+            let decoded = encoding
+                .encode(&response)
+                .and_then(|bytes| encoding.decode(&bytes));
+            assert!(
+                decoded.as_ref().is_ok_and(|decoded| *decoded == response),
+                "{encoding}: {decoded:?}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn unreachable_transport_falls_back_to_http() {
+        // A port nothing listens on once the listener is dropped
+        let binary_port = std::net::TcpListener::bind((BRP_DEFAULT_HOST, 0))
+            .and_then(|listener| listener.local_addr())
+            .map_or_else(
+                |e| unreachable!("bind a free port: {e}"),
+                |address| address.port(),
+            );
+        let port = Port(binary_port);
+        if let Ok(mut negotiated) = NEGOTIATED.lock() {
+            negotiated.insert(
+                port,
+                Some(BinaryTransport {
+                    port:     binary_port,
+                    encoding: WireEncoding::Msgpack,
+                }),
+            );
+        }
+
+        let stream = open_stream(port, "world.query", None, &RequestId::default()).await;
+
+        assert!(stream.is_none());
+        let remembered = NEGOTIATED
+            .lock()
+            .ok()
+            .and_then(|negotiated| negotiated.get(&port).copied());
+        assert!(remembered.is_none(), "{remembered:?}");
+    }
+
+    #[test]
+    fn only_bulk_methods_use_the_binary_transport() {
+        assert!(is_high_volume("world.query"));
+        assert!(is_high_volume("world.get_components+watch"));
+        assert!(!is_high_volume("world.mutate_components"));
+        assert!(!is_high_volume("brp_extras/screenshot"));
+    }
+}
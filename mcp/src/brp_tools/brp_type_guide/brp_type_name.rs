@@ -10,6 +10,72 @@ use serde_json::Value;
 
 use super::type_knowledge::TypeKnowledge;
 
+/// Groups of type paths that name the same type in different Bevy releases, oldest first
+///
+/// Bevy periodically moves core types between crates (hierarchy into `bevy_ecs` in 0.16,
+/// camera/light types out of `bevy_render`/`bevy_pbr` in 0.17). A script written against one
+/// release uses paths the app from another release doesn't register, so we resolve through these
+/// groups to whichever member the connected app actually knows about.
+const TYPE_PATH_ALIASES: &[&[&str]] = &[
+    &[
+        "bevy_hierarchy::components::parent::Parent",
+        "bevy_ecs::hierarchy::ChildOf",
+    ],
+    &[
+        "bevy_hierarchy::components::children::Children",
+        "bevy_ecs::hierarchy::Children",
+    ],
+    &["bevy_core::name::Name", "bevy_ecs::name::Name"],
+    &[
+        "bevy_render::camera::camera::Camera",
+        "bevy_camera::camera::Camera",
+    ],
+    &[
+        "bevy_render::camera::projection::Projection",
+        "bevy_camera::projection::Projection",
+    ],
+    &[
+        "bevy_render::view::visibility::Visibility",
+        "bevy_camera::visibility::Visibility",
+    ],
+    &[
+        "bevy_render::view::visibility::InheritedVisibility",
+        "bevy_camera::visibility::InheritedVisibility",
+    ],
+    &[
+        "bevy_render::view::visibility::ViewVisibility",
+        "bevy_camera::visibility::ViewVisibility",
+    ],
+    &[
+        "bevy_render::mesh::components::Mesh3d",
+        "bevy_mesh::components::Mesh3d",
+    ],
+    &[
+        "bevy_render::mesh::components::Mesh2d",
+        "bevy_mesh::components::Mesh2d",
+    ],
+    &[
+        "bevy_pbr::light::point_light::PointLight",
+        "bevy_light::point_light::PointLight",
+    ],
+    &[
+        "bevy_pbr::light::spot_light::SpotLight",
+        "bevy_light::spot_light::SpotLight",
+    ],
+    &[
+        "bevy_pbr::light::directional_light::DirectionalLight",
+        "bevy_light::directional_light::DirectionalLight",
+    ],
+    &[
+        "bevy_pbr::light::ambient_light::AmbientLight",
+        "bevy_light::ambient_light::AmbientLight",
+    ],
+    &[
+        "bevy_render::camera::clear_color::ClearColor",
+        "bevy_camera::clear_color::ClearColor",
+    ],
+];
+
 /// A newtype wrapper for BRP type names used as `HashMap` keys
 ///
 /// This type provides documentation and type safety for strings that represent
@@ -67,6 +133,34 @@ impl BrpTypeName {
         self.0.rsplit("::").next().unwrap_or(&self.0).to_string()
     }
 
+    /// Other paths this type has been known by across Bevy releases (excluding itself)
+    pub fn aliases(&self) -> Vec<Self> {
+        TYPE_PATH_ALIASES
+            .iter()
+            .find(|group| group.contains(&self.as_str()))
+            .map(|group| {
+                group
+                    .iter()
+                    .filter(|alias| **alias != self.as_str())
+                    .map(|alias| Self::from(*alias))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Normalize this type path against the set of types the connected app registers
+    ///
+    /// Returns `None` if the name is already registered (or no alias is), otherwise the alias
+    /// that the app actually knows about.
+    pub fn normalize_against(&self, is_registered: impl Fn(&Self) -> bool) -> Option<Self> {
+        if is_registered(self) {
+            return None;
+        }
+        self.aliases()
+            .into_iter()
+            .find(|alias| is_registered(alias))
+    }
+
     /// Get the display name for this type, using simplified name from knowledge if available
     pub fn display_name(&self) -> Self {
         TypeKnowledge::get_simplified_name(self).unwrap_or_else(|| self.clone())
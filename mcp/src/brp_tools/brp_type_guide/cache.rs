@@ -0,0 +1,313 @@
+//! Type guide caches for `brp_all_type_guides`
+//!
+//! Finished responses are kept in memory per port for as long as the same process owns the port,
+//! and on disk per app binary so a freshly restarted app with unchanged code skips the rebuild.
+//! A disk entry is only reused when the binary's size and modification time, the app's Bevy
+//! version, and this server's version all match the ones it was built with.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::LazyLock;
+use std::sync::Mutex;
+use std::time::UNIX_EPOCH;
+
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+use sysinfo::ProcessRefreshKind;
+use sysinfo::ProcessesToUpdate;
+use sysinfo::System;
+use sysinfo::UpdateKind;
+use tracing::debug;
+
+use super::constants::TYPE_GUIDE_CACHE_DIR_NAME;
+use super::response_types::TypeGuideResponse;
+use crate::brp_tools::BrpClient;
+use crate::brp_tools::Port;
+use crate::brp_tools::ResponseStatus;
+use crate::error::Error;
+use crate::error::Result;
+use crate::tool::BrpMethod;
+
+/// Identifies the build of a running app that a cached response was computed for
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AppFingerprint {
+    binary_path:        PathBuf,
+    binary_size:        u64,
+    binary_modified_ms: u128,
+    bevy_version:       Option<String>,
+    server_version:     String,
+}
+
+impl AppFingerprint {
+    /// Fingerprint the app with process id `pid`, or `None` if its binary can't be inspected
+    pub async fn detect(pid: u32, port: Port) -> Option<Self> {
+        let binary_path = binary_path_for_pid(pid)?;
+        let metadata = fs::metadata(&binary_path).ok()?;
+        let binary_modified_ms = metadata
+            .modified()
+            .ok()?
+            .duration_since(UNIX_EPOCH)
+            .ok()?
+            .as_millis();
+
+        Some(Self {
+            binary_path,
+            binary_size: metadata.len(),
+            binary_modified_ms,
+            bevy_version: bevy_version(port).await,
+            server_version: env!("CARGO_PKG_VERSION").to_string(),
+        })
+    }
+
+    /// Path of the app binary this fingerprint describes
+    pub fn binary_path(&self) -> &Path { &self.binary_path }
+}
+
+/// A finished response and the process it was built for
+struct MemoryEntry {
+    pid:      u32,
+    response: TypeGuideResponse,
+}
+
+/// Finished responses per port - only reused while the same process owns the port
+static MEMORY_CACHE: LazyLock<Mutex<HashMap<Port, MemoryEntry>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Cached response for `port` if it was built for the process that owns the port now
+pub fn memory_lookup(port: Port, pid: u32) -> Option<TypeGuideResponse> {
+    MEMORY_CACHE
+        .lock()
+        .ok()?
+        .get(&port)
+        .filter(|entry| entry.pid == pid)
+        .map(|entry| entry.response.clone())
+}
+
+/// Remember `response` as built for process `pid` on `port`
+pub fn memory_store(port: Port, pid: u32, response: &TypeGuideResponse) {
+    if let Ok(mut cache) = MEMORY_CACHE.lock() {
+        cache.insert(
+            port,
+            MemoryEntry {
+                pid,
+                response: response.clone(),
+            },
+        );
+    }
+}
+
+/// Forget in-memory responses - only the one for `port` when given, otherwise all of them
+pub fn memory_clear(port: Option<Port>) -> usize {
+    let Ok(mut cache) = MEMORY_CACHE.lock() else {
+        return 0;
+    };
+    if let Some(port) = port {
+        usize::from(cache.remove(&port).is_some())
+    } else {
+        let removed = cache.len();
+        cache.clear();
+        removed
+    }
+}
+
+/// On-disk form of a cached response
+#[derive(Deserialize)]
+struct DiskEntry {
+    fingerprint: AppFingerprint,
+    response:    TypeGuideResponse,
+}
+
+/// Borrowed form of `DiskEntry` so storing doesn't clone the response
+#[derive(Serialize)]
+struct DiskEntryRef<'a> {
+    fingerprint: &'a AppFingerprint,
+    response:    &'a TypeGuideResponse,
+}
+
+/// Cached response for the binary in `fingerprint` if it is still the same build
+pub fn disk_lookup(fingerprint: &AppFingerprint) -> Option<TypeGuideResponse> {
+    let path = disk_entry_path(fingerprint.binary_path());
+    let bytes = fs::read(&path).ok()?;
+    match serde_json::from_slice::<DiskEntry>(&bytes) {
+        Ok(entry) if entry.fingerprint == *fingerprint => Some(entry.response),
+        Ok(_) => {
+            debug!("Type guide cache {} is stale", path.display());
+            None
+        },
+        Err(e) => {
+            debug!(
+                "Ignoring unreadable type guide cache {}: {e}",
+                path.display()
+            );
+            None
+        },
+    }
+}
+
+/// Write `response` to disk for the binary in `fingerprint`
+///
+/// Failures are only logged - the cache is an optimization and must never fail the tool call.
+pub fn disk_store(fingerprint: &AppFingerprint, response: &TypeGuideResponse) {
+    let path = disk_entry_path(fingerprint.binary_path());
+    let entry = DiskEntryRef {
+        fingerprint,
+        response,
+    };
+    let result = fs::create_dir_all(cache_dir())
+        .map_err(|e| e.to_string())
+        .and_then(|()| serde_json::to_vec(&entry).map_err(|e| e.to_string()))
+        .and_then(|bytes| fs::write(&path, bytes).map_err(|e| e.to_string()));
+    if let Err(e) = result {
+        debug!("Failed to write type guide cache {}: {e}", path.display());
+    }
+}
+
+/// Remove disk entries - only the one for `binary_path` when given, otherwise all of them
+pub fn disk_clear(binary_path: Option<&Path>) -> Result<usize> {
+    if let Some(binary_path) = binary_path {
+        return remove_entry(&disk_entry_path(binary_path));
+    }
+
+    let entries = match fs::read_dir(cache_dir()) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(0),
+        Err(e) => return Err(Error::failed_to("read type guide cache directory", e).into()),
+    };
+
+    let mut removed = 0;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().is_some_and(|ext| ext == "json") {
+            removed += remove_entry(&path)?;
+        }
+    }
+    Ok(removed)
+}
+
+fn remove_entry(path: &Path) -> Result<usize> {
+    match fs::remove_file(path) {
+        Ok(()) => Ok(1),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(0),
+        Err(e) => Err(Error::failed_to(&format!("remove {}", path.display()), e).into()),
+    }
+}
+
+fn cache_dir() -> PathBuf { std::env::temp_dir().join(TYPE_GUIDE_CACHE_DIR_NAME) }
+
+/// One file per binary path - the hash keeps apps with the same name in different workspaces
+/// apart, and the stem keeps the directory readable
+fn disk_entry_path(binary_path: &Path) -> PathBuf {
+    let stem = binary_path
+        .file_stem()
+        .map_or_else(|| "app".into(), |stem| stem.to_string_lossy());
+    cache_dir().join(format!("{stem}-{:016x}.json", path_hash(binary_path)))
+}
+
+/// 64-bit FNV-1a hash of `path`
+///
+/// Unlike `DefaultHasher`, whose output may change between Rust releases, this names the same
+/// file for a binary after the server is rebuilt, so its entry is found again.
+fn path_hash(path: &Path) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01b3;
+    path.as_os_str()
+        .as_encoded_bytes()
+        .iter()
+        .fold(OFFSET_BASIS, |hash, &byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(PRIME)
+        })
+}
+
+/// Path of the executable for process `pid`
+fn binary_path_for_pid(pid: u32) -> Option<PathBuf> {
+    let pid = sysinfo::Pid::from_u32(pid);
+    let mut system = System::new();
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::Some(&[pid]),
+        true,
+        ProcessRefreshKind::nothing().with_exe(UpdateKind::OnlyIfNotSet),
+    );
+    system.process(pid)?.exe().map(Path::to_path_buf)
+}
+
+/// Bevy version reported by the app's `rpc.discover` document
+async fn bevy_version(port: Port) -> Option<String> {
+    let client = BrpClient::new(BrpMethod::RpcDiscover, port, None);
+    match client.execute_direct_internal_no_enhancement().await {
+        Ok(ResponseStatus::Success(Some(discover))) => discover
+            .pointer("/info/version")
+            .and_then(Value::as_str)
+            .map(String::from),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn fingerprint(binary_path: PathBuf, binary_size: u64) -> AppFingerprint {
+        AppFingerprint {
+            binary_path,
+            binary_size,
+            binary_modified_ms: 1_700_000_000_000,
+            bevy_version: Some("0.17.3".to_string()),
+            server_version: env!("CARGO_PKG_VERSION").to_string(),
+        }
+    }
+
+    fn response() -> TypeGuideResponse {
+        serde_json::from_value(json!({
+            "discovered_count": 1,
+            "requested_types": ["test::Tint"],
+            "summary": {
+                "failed_discoveries": 0,
+                "successful_discoveries": 1,
+                "total_requested": 1
+            },
+            "type_guide": { "test::Tint": {} }
+        }))
+        .unwrap_or_else(|e| unreachable!("response should deserialize: {e}"))
+    }
+
+    #[test]
+    fn entry_paths_are_stable_and_keep_workspaces_apart() {
+        // FNV-1a of the empty input is its offset basis, and of "a" a published test vector
+        assert_eq!(path_hash(Path::new("")), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(path_hash(Path::new("a")), 0xaf63_dc4c_8601_ec8c);
+
+        let first = disk_entry_path(Path::new("/work/first/target/debug/game"));
+        let second = disk_entry_path(Path::new("/work/second/target/debug/game"));
+        assert_ne!(first, second);
+        assert_eq!(
+            first,
+            disk_entry_path(Path::new("/work/first/target/debug/game"))
+        );
+        assert!(
+            first
+                .file_name()
+                .is_some_and(|name| name.to_string_lossy().starts_with("game-"))
+        );
+    }
+
+    #[test]
+    fn disk_entries_are_reused_only_for_the_same_build() {
+        let dir = TempDir::new().unwrap_or_else(|e| unreachable!("temp dir: {e}"));
+        let binary = dir.path().join("cache_test_app");
+        let built = fingerprint(binary.clone(), 1024);
+
+        disk_store(&built, &response());
+        assert!(disk_lookup(&built).is_some());
+        assert!(disk_lookup(&fingerprint(binary.clone(), 2048)).is_none());
+
+        assert_eq!(disk_clear(Some(&binary)).ok(), Some(1));
+        assert!(disk_lookup(&built).is_none());
+    }
+}
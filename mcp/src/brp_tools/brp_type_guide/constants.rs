@@ -5,6 +5,19 @@
 /// Maximum recursion depth for type example generation to prevent stack overflow
 pub const MAX_TYPE_RECURSION_DEPTH: usize = 10;
 
+// ============================================================================
+// ALL TYPE GUIDES CONSTANTS
+// ============================================================================
+
+/// Number of types built per blocking task by `brp_all_type_guides`
+pub const TYPE_GUIDE_BATCH_SIZE: usize = 32;
+
+/// Parallelism used when the number of available cores can't be determined
+pub const DEFAULT_TYPE_GUIDE_PARALLELISM: usize = 4;
+
+/// Directory (under the system temp dir) holding type guides cached per app binary
+pub const TYPE_GUIDE_CACHE_DIR_NAME: &str = "bevy_brp_mcp_type_guides";
+
 // ============================================================================
 // TYPE NAME CONSTANTS
 // ============================================================================
@@ -28,6 +28,8 @@ use super::mutation_path_builder::MutationPathExternal;
 use super::mutation_path_builder::{self};
 use super::response_types::BrpTypeName;
 use super::response_types::SchemaInfo;
+use super::type_docs;
+use super::type_docs::TypeDocs;
 use super::type_kind::TypeKind;
 use super::type_knowledge::TypeKnowledge;
 use crate::error::Result;
@@ -41,24 +43,31 @@ use crate::json_schema::SchemaField;
 #[derive(Debug, Clone, Serialize)]
 pub struct TypeGuide {
     /// Guidance for AI agents about using mutation paths
-    pub agent_guidance: String,
+    pub agent_guidance:  String,
     /// Fully-qualified type name
-    pub type_name:      BrpTypeName,
+    pub type_name:       BrpTypeName,
     /// Whether the type is registered in the Bevy registry
-    pub in_registry:    bool,
+    pub in_registry:     bool,
+    /// The requested type path when it was normalized to the path this app registers
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub normalized_from: Option<BrpTypeName>,
     /// Mutation paths available for this type - using same format as V1
     #[serde(skip_serializing_if = "Vec::is_empty")]
-    pub mutation_paths: Vec<MutationPathExternal>,
+    pub mutation_paths:  Vec<MutationPathExternal>,
     /// Example format for spawn/insert operations when supported
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub spawn_format:   Option<Value>,
+    pub spawn_format:    Option<Value>,
     /// Schema information from the registry
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub schema_info:    Option<SchemaInfo>,
+    pub schema_info:     Option<SchemaInfo>,
+    /// Doc comments for the type and its fields, from the registry or the `BRP_MCP_TYPE_DOCS`
+    /// file
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub docs:            Option<TypeDocs>,
     /// Type information for direct fields (struct fields only, one level deep)
     /// Error message if discovery failed
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub error:          Option<String>,
+    pub error:           Option<String>,
 }
 
 impl TypeGuide {
@@ -93,6 +102,9 @@ impl TypeGuide {
         // Extract schema info from registry
         let schema_info = Some(Self::extract_schema_info(registry_schema));
 
+        // Doc comments give agents the meaning of the type and its fields, not just their shape
+        let docs = type_docs::docs_for(&brp_type_name, registry_schema);
+
         // Generate agent guidance (with Entity warning and spawn format guidance)
         let agent_guidance =
             Self::generate_agent_guidance(&mutation_paths, spawn_format.as_ref(), &reflect_traits)?;
@@ -100,9 +112,11 @@ impl TypeGuide {
         Ok(Self {
             type_name: brp_type_name,
             in_registry: true,
+            normalized_from: None,
             mutation_paths,
             spawn_format,
             schema_info,
+            docs,
             agent_guidance,
             error: None,
         })
@@ -113,9 +127,11 @@ impl TypeGuide {
         Self {
             type_name,
             in_registry: false,
+            normalized_from: None,
             mutation_paths: Vec::new(),
             spawn_format: None,
             schema_info: None,
+            docs: None,
             agent_guidance: AGENT_GUIDANCE.to_string(),
             error: Some(error_msg),
         }
@@ -129,9 +145,11 @@ impl TypeGuide {
         Self {
             type_name,
             in_registry: true, // Type WAS found in registry
+            normalized_from: None,
             mutation_paths: Vec::new(),
             spawn_format: None,
             schema_info: None,
+            docs: None,
             agent_guidance: ERROR_GUIDANCE.to_string(),
             error: Some(error_msg),
         }
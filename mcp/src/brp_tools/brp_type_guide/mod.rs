@@ -5,20 +5,27 @@
 //! serialization knowledge to provide accurate format discovery for BRP operations.
 
 mod brp_type_name;
+mod cache;
 mod constants;
 mod guide;
 mod mutation_path_builder;
 mod response_types;
 mod tool_all_types;
+mod tool_clear_cache;
 mod tool_type_guide;
+mod type_docs;
 mod type_kind;
 mod type_knowledge;
 
 // Re-export public API
 // Internal use for format discovery
 pub use brp_type_name::BrpTypeName;
+pub use cache::memory_clear as clear_type_guide_memory;
 pub use tool_all_types::AllTypeGuidesParams;
 pub use tool_all_types::BrpAllTypeGuides;
+pub use tool_clear_cache::BrpClearTypeGuideCache;
+pub use tool_clear_cache::ClearTypeGuideCacheParams;
 pub use tool_type_guide::BrpTypeGuide;
 pub use tool_type_guide::TypeGuideEngine;
 pub use tool_type_guide::TypeGuideParams;
+pub use type_kind::TypeKind;
@@ -6,6 +6,7 @@
 
 use std::collections::HashMap;
 
+use schemars::JsonSchema;
 use serde::Deserialize;
 use serde::Serialize;
 use serde_json::Value;
@@ -13,7 +14,6 @@ use strum::AsRefStr;
 use strum::Display;
 
 pub use super::brp_type_name::BrpTypeName;
-use super::guide::TypeGuide;
 use super::type_kind::TypeKind;
 
 /// Enum for BRP supported operations
@@ -68,7 +68,9 @@ pub struct SchemaInfo {
 }
 
 /// response structure
-#[derive(Debug, Clone, Serialize)]
+///
+/// Guides are stored serialized so a finished response can be cached on disk and read back.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct TypeGuideResponse {
     /// Number of types successfully discovered
     pub discovered_count: usize,
@@ -77,11 +79,12 @@ pub struct TypeGuideResponse {
     /// Summary statistics for the discovery operation
     pub summary:          TypeGuideSummary,
     /// Detailed information for each type, keyed by type name
-    pub type_guide:       HashMap<BrpTypeName, TypeGuide>,
+    #[schemars(with = "HashMap<String, Value>")]
+    pub type_guide:       HashMap<BrpTypeName, Value>,
 }
 
 /// Summary statistics for the discovery operation
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct TypeGuideSummary {
     /// Number of types that failed discovery
     pub failed_discoveries:     usize,
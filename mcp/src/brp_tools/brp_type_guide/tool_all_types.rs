@@ -3,66 +3,133 @@
 //! This tool fetches all registered component and resource types from the Bevy app and returns
 //! their type schema information in a single call. It combines `world.list_components`,
 //! `world.list_resources`, and `brp_type_guide` functionality for convenience.
+//!
+//! Large games register thousands of types, so the registry is fetched once (alongside the type
+//! lists), guides are built in batches on blocking threads with bounded parallelism, and the
+//! finished response is cached per app instance (port + process id) and on disk per app binary,
+//! so a restarted app with unchanged code gets its guides instantly. Clients that send a
+//! `progressToken` receive a progress notification as each batch completes.
+
+use std::collections::HashMap;
+use std::num::NonZeroUsize;
 
 use bevy_brp_mcp_macros::ParamStruct;
 use bevy_brp_mcp_macros::ToolFn;
 use schemars::JsonSchema;
 use serde::Deserialize;
 use serde::Serialize;
+use tokio::task::JoinSet;
 
+use super::brp_type_name::BrpTypeName;
+use super::cache;
+use super::cache::AppFingerprint;
+use super::constants::DEFAULT_TYPE_GUIDE_PARALLELISM;
+use super::constants::TYPE_GUIDE_BATCH_SIZE;
+use super::guide::TypeGuide;
 use super::tool_type_guide::TypeGuideEngine;
 use super::tool_type_guide::TypeGuideResult;
+use crate::app_tools::get_pid_for_port;
 use crate::brp_tools::BrpClient;
 use crate::brp_tools::Port;
+use crate::brp_tools::RequestPriority;
 use crate::brp_tools::ResponseStatus;
 use crate::error::Error;
 use crate::error::Result;
 use crate::tool::BrpMethod;
 use crate::tool::HandlerContext;
 use crate::tool::HandlerResult;
+use crate::tool::ProgressReporter;
 use crate::tool::ToolFn;
 use crate::tool::ToolResult;
 
 /// Parameters for the `brp_all_type_guides` tool
 #[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
 pub struct AllTypeGuidesParams {
-    /// The BRP port (default: 15702)
+    /// Rebuild the guides even if they are cached for this app instance or binary (default:
+    /// false)
+    #[serde(default)]
+    pub refresh: bool,
+
+    /// The BRP port
     #[serde(default)]
     pub port: Port,
 }
 
 /// The main tool struct for getting all type guides
 #[derive(ToolFn)]
-#[tool_fn(params = "AllTypeGuidesParams", output = "TypeGuideResult")]
+#[tool_fn(
+    params = "AllTypeGuidesParams",
+    output = "TypeGuideResult",
+    with_context
+)]
 pub struct BrpAllTypeGuides;
 
 /// Implementation that fetches all types then gets their guides
-async fn handle_impl(params: AllTypeGuidesParams) -> Result<TypeGuideResult> {
-    // Fetch component types
-    let component_types = fetch_type_list(
-        BrpMethod::WorldListComponents,
-        params.port,
-        "world.list_components",
-    )
-    .await?;
+async fn handle_impl(ctx: HandlerContext, params: AllTypeGuidesParams) -> Result<TypeGuideResult> {
+    let pid = get_pid_for_port(params.port);
 
-    // Fetch resource types
-    let resource_types = fetch_type_list(
-        BrpMethod::WorldListResources,
-        params.port,
-        "world.list_resources",
-    )
-    .await?;
+    if !params.refresh
+        && let Some(pid) = pid
+        && let Some(response) = cache::memory_lookup(params.port, pid)
+    {
+        let type_count = response.discovered_count;
+        return Ok(
+            TypeGuideResult::new(response, type_count).with_message_template(format!(
+                "Discovered schemas for all {type_count} registered type(s) (cached)"
+            )),
+        );
+    }
+
+    let fingerprint = match pid {
+        Some(pid) => AppFingerprint::detect(pid, params.port).await,
+        None => None,
+    };
+
+    if !params.refresh
+        && let Some(pid) = pid
+        && let Some(fingerprint) = &fingerprint
+        && let Some(response) = cache::disk_lookup(fingerprint)
+    {
+        cache::memory_store(params.port, pid, &response);
+        let type_count = response.discovered_count;
+        return Ok(
+            TypeGuideResult::new(response, type_count).with_message_template(format!(
+                "Discovered schemas for all {type_count} registered type(s) (cached on disk)"
+            )),
+        );
+    }
+
+    // Type lists and the registry are independent, so fetch them concurrently - in the
+    // background, so interactive calls to the app don't wait behind them
+    let (component_types, resource_types, engine) = tokio::try_join!(
+        fetch_type_list(
+            BrpMethod::WorldListComponents,
+            params.port,
+            "world.list_components",
+        ),
+        fetch_type_list(
+            BrpMethod::WorldListResources,
+            params.port,
+            "world.list_resources",
+        ),
+        TypeGuideEngine::with_priority(params.port, RequestPriority::Background),
+    )?;
 
     // Merge both lists
     let mut all_types = component_types;
     all_types.extend(resource_types);
 
-    // Construct TypeSchemaEngine and generate response for all types
-    let engine = TypeGuideEngine::new(params.port).await?;
-    let response = engine.generate_response(&all_types);
+    let type_guide = build_guides_concurrently(&engine, &all_types, ctx.progress()).await?;
+    let response = TypeGuideEngine::summarize(&all_types, type_guide);
     let type_count = response.discovered_count;
 
+    if let Some(pid) = pid {
+        cache::memory_store(params.port, pid, &response);
+    }
+    if let Some(fingerprint) = &fingerprint {
+        cache::disk_store(fingerprint, &response);
+    }
+
     Ok(
         TypeGuideResult::new(response, type_count).with_message_template(format!(
             "Discovered schemas for all {type_count} registered type(s)"
@@ -70,9 +137,60 @@ async fn handle_impl(params: AllTypeGuidesParams) -> Result<TypeGuideResult> {
     )
 }
 
+/// Build guides for `types` in batches on blocking threads, keeping at most one batch per core
+/// in flight and reporting progress as each batch finishes
+async fn build_guides_concurrently(
+    engine: &TypeGuideEngine,
+    types: &[String],
+    progress: Option<&ProgressReporter>,
+) -> Result<HashMap<BrpTypeName, TypeGuide>> {
+    let max_parallel = std::thread::available_parallelism()
+        .map_or(DEFAULT_TYPE_GUIDE_PARALLELISM, NonZeroUsize::get);
+    let total = types.len();
+
+    let mut batches = types.chunks(TYPE_GUIDE_BATCH_SIZE);
+    let mut tasks = JoinSet::new();
+    let mut type_guide = HashMap::with_capacity(total);
+    let mut completed = 0;
+
+    loop {
+        while tasks.len() < max_parallel
+            && let Some(batch) = batches.next()
+        {
+            let engine = engine.clone();
+            let batch = batch.to_vec();
+            tasks.spawn_blocking(move || {
+                batch
+                    .iter()
+                    .map(|type_name| engine.build_guide(type_name))
+                    .collect::<Vec<_>>()
+            });
+        }
+
+        let Some(joined) = tasks.join_next().await else {
+            break;
+        };
+        let guides = joined.map_err(|e| Error::failed_to("build type guides", e))?;
+        completed += guides.len();
+        type_guide.extend(guides);
+
+        if let Some(progress) = progress {
+            progress
+                .report(
+                    completed,
+                    total,
+                    format!("Built type guides for {completed} of {total} types"),
+                )
+                .await;
+        }
+    }
+
+    Ok(type_guide)
+}
+
 /// Helper function to fetch a list of type names from a BRP method
 async fn fetch_type_list(method: BrpMethod, port: Port, method_name: &str) -> Result<Vec<String>> {
-    let client = BrpClient::new(method, port, None);
+    let client = BrpClient::new(method, port, None).with_priority(RequestPriority::Background);
 
     match client.execute_direct_internal_no_enhancement().await {
         Ok(ResponseStatus::Success(Some(types_data))) => types_data.as_array().map_or_else(
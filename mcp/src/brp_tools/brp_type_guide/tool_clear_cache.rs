@@ -0,0 +1,79 @@
+//! `brp_clear_type_guide_cache` tool - Invalidate cached type guides
+//!
+//! `brp_all_type_guides` caches its results in memory and on disk. This tool drops them - either
+//! for the app running on one port, or everything - so the next call rebuilds the guides.
+
+use bevy_brp_mcp_macros::ParamStruct;
+use bevy_brp_mcp_macros::ResultStruct;
+use bevy_brp_mcp_macros::ToolFn;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+
+use super::cache;
+use super::cache::AppFingerprint;
+use crate::app_tools::get_pid_for_port;
+use crate::brp_tools::Port;
+use crate::error::Result;
+use crate::tool::HandlerContext;
+use crate::tool::HandlerResult;
+use crate::tool::ToolFn;
+use crate::tool::ToolResult;
+
+/// Parameters for the `brp_clear_type_guide_cache` tool
+#[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
+pub struct ClearTypeGuideCacheParams {
+    /// Only clear the cache for the app running on this port - omit to clear every cached entry
+    pub port: Option<Port>,
+}
+
+/// Result for the `brp_clear_type_guide_cache` tool
+#[derive(Debug, Clone, Serialize, Deserialize, ResultStruct)]
+pub struct ClearTypeGuideCacheResult {
+    /// Number of in-memory entries removed
+    #[to_metadata]
+    memory_entries_removed: usize,
+    /// Number of on-disk entries removed
+    #[to_metadata]
+    disk_entries_removed:   usize,
+    /// Message template for formatting responses
+    #[to_message(
+        message_template = "Cleared {memory_entries_removed} in-memory and {disk_entries_removed} on-disk type guide cache entries"
+    )]
+    message_template:       String,
+}
+
+#[derive(ToolFn)]
+#[tool_fn(
+    params = "ClearTypeGuideCacheParams",
+    output = "ClearTypeGuideCacheResult"
+)]
+pub struct BrpClearTypeGuideCache;
+
+async fn handle_impl(params: ClearTypeGuideCacheParams) -> Result<ClearTypeGuideCacheResult> {
+    let Some(port) = params.port else {
+        let memory_entries_removed = cache::memory_clear(None);
+        let disk_entries_removed = cache::disk_clear(None)?;
+        return Ok(ClearTypeGuideCacheResult::new(
+            memory_entries_removed,
+            disk_entries_removed,
+        ));
+    };
+
+    let memory_entries_removed = cache::memory_clear(Some(port));
+
+    // The disk entry belongs to the app's binary, so the app must be running to find it
+    let fingerprint = match get_pid_for_port(port) {
+        Some(pid) => AppFingerprint::detect(pid, port).await,
+        None => None,
+    };
+    let disk_entries_removed = match &fingerprint {
+        Some(fingerprint) => cache::disk_clear(Some(fingerprint.binary_path()))?,
+        None => 0,
+    };
+
+    Ok(ClearTypeGuideCacheResult::new(
+        memory_entries_removed,
+        disk_entries_removed,
+    ))
+}
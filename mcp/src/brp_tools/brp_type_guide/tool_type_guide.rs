@@ -22,6 +22,7 @@ use super::response_types::TypeGuideResponse;
 use super::response_types::TypeGuideSummary;
 use crate::brp_tools::BrpClient;
 use crate::brp_tools::Port;
+use crate::brp_tools::RequestPriority;
 use crate::brp_tools::ResponseStatus;
 use crate::error::Error;
 use crate::error::Result;
@@ -37,7 +38,7 @@ pub struct TypeGuideParams {
     /// Array of fully-qualified component type names to discover formats for
     pub types: Vec<String>,
 
-    /// The BRP port (default: 15702)
+    /// The BRP port
     #[serde(default)]
     pub port: Port,
 }
@@ -77,6 +78,9 @@ async fn handle_impl(params: TypeGuideParams) -> Result<TypeGuideResult> {
 }
 
 /// orchestrates type schema generation using a single call to get the complete registry
+///
+/// Cloning is cheap - clones share the fetched registry.
+#[derive(Clone)]
 pub struct TypeGuideEngine {
     registry: Arc<HashMap<BrpTypeName, Value>>,
 }
@@ -84,16 +88,33 @@ pub struct TypeGuideEngine {
 impl TypeGuideEngine {
     /// Create a new engine instance by fetching the complete registry
     pub async fn new(port: Port) -> Result<Self> {
-        let registry = Arc::new(Self::get_full_registry(port).await?);
+        Self::with_priority(port, RequestPriority::Interactive).await
+    }
+
+    /// Create a new engine instance, fetching the registry with the given request priority
+    pub async fn with_priority(port: Port, priority: RequestPriority) -> Result<Self> {
+        let registry = Arc::new(Self::get_full_registry(port, priority).await?);
         Ok(Self { registry })
     }
 
+    /// Create an engine over an already fetched registry
+    #[cfg(test)]
+    pub fn from_registry(registry: HashMap<BrpTypeName, Value>) -> Self {
+        Self {
+            registry: Arc::new(registry),
+        }
+    }
+
     /// Get the complete registry
     ///
     /// Fetches fresh registry data from the BRP server on each call.
-    async fn get_full_registry(port: Port) -> Result<HashMap<BrpTypeName, Value>> {
+    async fn get_full_registry(
+        port: Port,
+        priority: RequestPriority,
+    ) -> Result<HashMap<BrpTypeName, Value>> {
         // Fetch full registry from BRP
-        let client = BrpClient::new(BrpMethod::RegistrySchema, port, Some(json!({})));
+        let client = BrpClient::new(BrpMethod::RegistrySchema, port, Some(json!({})))
+            .with_priority(priority);
 
         match client.execute_direct_internal_no_enhancement().await {
             Ok(ResponseStatus::Success(Some(registry_data))) => {
@@ -118,23 +139,64 @@ impl TypeGuideEngine {
 
     /// Generate response for requested types
     pub fn generate_response(&self, requested_types: &[String]) -> TypeGuideResponse {
-        // Build the type_guide HashMap functionally
         let type_guide: HashMap<BrpTypeName, TypeGuide> = requested_types
             .iter()
-            .map(BrpTypeName::from)
-            .map(|brp_type_name| {
-                let type_info = TypeGuide::build(brp_type_name.clone(), Arc::clone(&self.registry))
-                    .unwrap_or_else(|e| {
-                        // Processing failed - type was found but building failed
-                        TypeGuide::processing_failed(
-                            brp_type_name.clone(),
-                            format!("Failed to process type: {e}"),
-                        )
-                    });
-                (brp_type_name, type_info)
-            })
+            .map(|type_name| self.build_guide(type_name))
             .collect();
 
+        Self::summarize(requested_types, type_guide)
+    }
+
+    /// Build the guide for a single requested type
+    pub fn build_guide(&self, type_name: &str) -> (BrpTypeName, TypeGuide) {
+        let brp_type_name = BrpTypeName::from(type_name);
+
+        // Resolve paths from other Bevy releases to the one this app registers
+        let normalized = brp_type_name.normalize_against(|name| self.registry.contains_key(name));
+        let lookup_name = normalized.clone().unwrap_or_else(|| brp_type_name.clone());
+
+        let mut type_info = TypeGuide::build(lookup_name.clone(), Arc::clone(&self.registry))
+            .unwrap_or_else(|e| {
+                // Processing failed - type was found but building failed
+                TypeGuide::processing_failed(lookup_name, format!("Failed to process type: {e}"))
+            });
+        if normalized.is_some() {
+            type_info.normalized_from = Some(brp_type_name.clone());
+        }
+        (brp_type_name, type_info)
+    }
+
+    /// The type at each mutation path of `type_name`, keyed by path (`""` is the type itself)
+    pub fn mutation_path_types(&self, type_name: &str) -> HashMap<String, BrpTypeName> {
+        let (_, guide) = self.build_guide(type_name);
+        guide
+            .mutation_paths
+            .into_iter()
+            .map(|path| (path.path.to_string(), path.path_info.type_name))
+            .collect()
+    }
+
+    /// Variant groups of the enum at mutation path `path` of `type_name` - each with its
+    /// `applicable_variants` and, when it can be built through BRP, an `example` - or `None` if
+    /// the path doesn't hold an enum
+    pub fn variant_examples(&self, type_name: &str, path: &str) -> Option<Vec<Value>> {
+        let (_, guide) = self.build_guide(type_name);
+        let mutation_path = guide
+            .mutation_paths
+            .into_iter()
+            .find(|mutation_path| mutation_path.path.as_str() == path)?;
+        serde_json::to_value(mutation_path)
+            .ok()?
+            .get("examples")?
+            .as_array()
+            .cloned()
+    }
+
+    /// Assemble the response from guides built for `requested_types`
+    pub fn summarize(
+        requested_types: &[String],
+        type_guide: HashMap<BrpTypeName, TypeGuide>,
+    ) -> TypeGuideResponse {
         // Calculate summary statistics from the results
         let successful_discoveries = type_guide
             .values()
@@ -145,6 +207,16 @@ impl TypeGuideEngine {
             .filter(|tg| !tg.in_registry || tg.error.is_some())
             .count();
 
+        let type_guide = type_guide
+            .into_iter()
+            .map(|(type_name, guide)| {
+                let guide = serde_json::to_value(guide).unwrap_or_else(
+                    |e| json!({ "error": format!("Failed to serialize type guide: {e}") }),
+                );
+                (type_name, guide)
+            })
+            .collect();
+
         TypeGuideResponse {
             discovered_count: successful_discoveries,
             requested_types: requested_types.to_vec(),
@@ -0,0 +1,170 @@
+//! Documentation for types and their fields in type guides
+//!
+//! Apps built with `bevy_reflect`'s `documentation` feature export doc comments as `description`
+//! on each type and property of `registry.schema`. Types without them - or apps built without the
+//! feature - can be documented in the JSON file named by `BRP_MCP_TYPE_DOCS`, keyed by full type
+//! path or short name:
+//!
+//! ```json
+//! { "my_game::Player": { "description": "The player", "fields": { "speed": "Speed in m/s" } } }
+//! ```
+//!
+//! Docs from the registry win; the file fills in whatever the registry leaves out.
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::fs;
+use std::sync::LazyLock;
+
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+use tracing::warn;
+
+use super::brp_type_name::BrpTypeName;
+use crate::json_object::JsonObjectAccess;
+use crate::json_schema::SchemaField;
+
+/// Environment variable naming the JSON file of type documentation
+pub const TYPE_DOCS_ENV_VAR: &str = "BRP_MCP_TYPE_DOCS";
+
+/// Documentation of a type and its fields
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TypeDocs {
+    /// What the type represents
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// What each field means, by field name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub fields:      BTreeMap<String, String>,
+}
+
+impl TypeDocs {
+    fn is_empty(&self) -> bool { self.description.is_none() && self.fields.is_empty() }
+
+    /// Fill in whatever `self` leaves out from `fallback`
+    fn or(mut self, fallback: &Self) -> Self {
+        if self.description.is_none() {
+            self.description.clone_from(&fallback.description);
+        }
+        for (field, doc) in &fallback.fields {
+            self.fields
+                .entry(field.clone())
+                .or_insert_with(|| doc.clone());
+        }
+        self
+    }
+}
+
+/// Docs from the docs file - read once at first use, empty when the variable is unset
+static CONFIGURED_DOCS: LazyLock<HashMap<String, TypeDocs>> = LazyLock::new(|| {
+    let Ok(path) = std::env::var(TYPE_DOCS_ENV_VAR) else {
+        return HashMap::new();
+    };
+    let docs = fs::read_to_string(&path)
+        .map_err(|e| e.to_string())
+        .and_then(|contents| serde_json::from_str(&contents).map_err(|e| e.to_string()));
+    docs.unwrap_or_else(|e| {
+        warn!("Ignoring type docs in {path}: {e}");
+        HashMap::new()
+    })
+});
+
+/// Documentation for `type_name` from its registry schema and the docs file, if there is any
+pub fn docs_for(type_name: &BrpTypeName, registry_schema: &Value) -> Option<TypeDocs> {
+    docs_with_config(type_name, registry_schema, &CONFIGURED_DOCS)
+}
+
+fn docs_with_config(
+    type_name: &BrpTypeName,
+    registry_schema: &Value,
+    configured: &HashMap<String, TypeDocs>,
+) -> Option<TypeDocs> {
+    let docs = registry_docs(registry_schema);
+    let docs = match configured_docs(type_name, configured) {
+        Some(fallback) => docs.or(fallback),
+        None => docs,
+    };
+    (!docs.is_empty()).then_some(docs)
+}
+
+/// `description` of the type and of each of its properties
+fn registry_docs(registry_schema: &Value) -> TypeDocs {
+    let description = registry_schema.get_field_string(SchemaField::Description);
+    let fields = registry_schema
+        .get_field(SchemaField::Properties)
+        .and_then(Value::as_object)
+        .map(|properties| {
+            properties
+                .iter()
+                .filter_map(|(field, schema)| {
+                    let doc = schema.get_field_str(SchemaField::Description)?;
+                    Some((field.clone(), doc.to_string()))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    TypeDocs {
+        description,
+        fields,
+    }
+}
+
+/// Docs configured under the full type path, or else under its short name
+fn configured_docs<'a>(
+    type_name: &BrpTypeName,
+    configured: &'a HashMap<String, TypeDocs>,
+) -> Option<&'a TypeDocs> {
+    configured
+        .get(type_name.as_str())
+        .or_else(|| configured.get(&type_name.short_name()))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn prefers_registry_docs_and_fills_gaps_from_config() {
+        let schema = json!({
+            "description": "The controlled character",
+            "properties": {
+                "speed": { "type": { "$ref": "#/$defs/f32" }, "description": "From the registry" },
+                "jump": { "type": { "$ref": "#/$defs/f32" } },
+            },
+        });
+        let configured: HashMap<String, TypeDocs> = serde_json::from_value(json!({
+            "Player": {
+                "description": "Ignored - the registry has one",
+                "fields": { "speed": "Ignored", "jump": "Jump height in m" },
+            },
+        }))
+        .unwrap_or_default();
+
+        let docs = docs_with_config(&BrpTypeName::from("my_game::Player"), &schema, &configured);
+
+        assert_eq!(
+            docs,
+            Some(TypeDocs {
+                description: Some("The controlled character".to_string()),
+                fields:      BTreeMap::from([
+                    ("jump".to_string(), "Jump height in m".to_string()),
+                    ("speed".to_string(), "From the registry".to_string()),
+                ]),
+            })
+        );
+    }
+
+    #[test]
+    fn omits_docs_when_there_are_none() {
+        let docs = docs_with_config(
+            &BrpTypeName::from("my_game::Enemy"),
+            &json!({ "properties": {} }),
+            &HashMap::new(),
+        );
+        assert_eq!(docs, None);
+    }
+}
@@ -0,0 +1,123 @@
+//! Checkpoints of queried component values for `world_changes_since`
+//!
+//! A checkpoint fingerprints the rows of one query - a hash of each component value, per entity -
+//! so the next call can report only the entities whose values differ without the server keeping
+//! the values themselves. Checkpoints belong to one port and one query; the oldest are dropped
+//! beyond `MAX_CHECKPOINTS`, and all of a port's are dropped when its app restarts, since entity
+//! IDs then describe a different run.
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::collections::VecDeque;
+use std::hash::DefaultHasher;
+use std::hash::Hash;
+use std::hash::Hasher;
+use std::sync::LazyLock;
+use std::sync::Mutex;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+
+use serde_json::Map;
+use serde_json::Value;
+
+use super::Port;
+use crate::error::Error;
+use crate::error::Result;
+
+/// Checkpoints kept at most, across all ports
+const MAX_CHECKPOINTS: usize = 64;
+
+/// Component value hashes by type path, per entity
+pub type Fingerprint = HashMap<u64, BTreeMap<String, u64>>;
+
+/// The fingerprint of one query's rows on one port
+struct Checkpoint {
+    token:       String,
+    port:        Port,
+    query:       String,
+    fingerprint: Fingerprint,
+}
+
+/// Checkpoints, oldest first
+static CHECKPOINTS: LazyLock<Mutex<VecDeque<Checkpoint>>> =
+    LazyLock::new(|| Mutex::new(VecDeque::new()));
+
+/// Source of checkpoint token numbers
+static NEXT_CHECKPOINT: AtomicU64 = AtomicU64::new(1);
+
+/// Hash of every component value in `components`
+pub fn fingerprint_components(components: &Map<String, Value>) -> BTreeMap<String, u64> {
+    components
+        .iter()
+        .map(|(type_path, value)| {
+            let mut hasher = DefaultHasher::new();
+            value.to_string().hash(&mut hasher);
+            (type_path.clone(), hasher.finish())
+        })
+        .collect()
+}
+
+/// Store `fingerprint` of `query` on `port`, returning its token
+pub fn save(port: Port, query: String, fingerprint: Fingerprint) -> String {
+    let token = format!(
+        "{}-{}",
+        port.0,
+        NEXT_CHECKPOINT.fetch_add(1, Ordering::Relaxed)
+    );
+    if let Ok(mut checkpoints) = CHECKPOINTS.lock() {
+        while checkpoints.len() >= MAX_CHECKPOINTS {
+            checkpoints.pop_front();
+        }
+        checkpoints.push_back(Checkpoint {
+            token: token.clone(),
+            port,
+            query,
+            fingerprint,
+        });
+    }
+    token
+}
+
+/// The fingerprint saved as `token`, which must have been taken of `query` on `port`
+pub fn load(token: &str, port: Port, query: &str) -> Result<Fingerprint> {
+    let checkpoints = CHECKPOINTS
+        .lock()
+        .map_err(|_| Error::General("Checkpoint store is unavailable".to_string()))?;
+    let Some(checkpoint) = checkpoints.iter().find(|c| c.token == token) else {
+        return Err(Error::invalid(
+            "checkpoint",
+            format!(
+                "'{token}' is unknown - it expired, or the app restarted since; call without \
+                 `checkpoint` for a new one"
+            ),
+        )
+        .into());
+    };
+    if checkpoint.port != port {
+        return Err(Error::invalid(
+            "checkpoint",
+            format!(
+                "'{token}' was taken on port {}, not {port}",
+                checkpoint.port
+            ),
+        )
+        .into());
+    }
+    if checkpoint.query != query {
+        return Err(Error::invalid(
+            "checkpoint",
+            format!("'{token}' was taken of a different `data` and `filter`"),
+        )
+        .into());
+    }
+    let fingerprint = checkpoint.fingerprint.clone();
+    drop(checkpoints);
+    Ok(fingerprint)
+}
+
+/// Drop the checkpoints of `port`, after its app restarted
+pub fn forget_port_checkpoints(port: Port) {
+    if let Ok(mut checkpoints) = CHECKPOINTS.lock() {
+        checkpoints.retain(|checkpoint| checkpoint.port != port);
+    }
+}
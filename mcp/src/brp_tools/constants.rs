@@ -10,6 +10,21 @@ pub const DEFAULT_BRP_EXTRAS_PORT: u16 = 15702;
 /// Environment variable name for BRP port
 pub const BRP_EXTRAS_PORT_ENV_VAR: &str = "BRP_EXTRAS_PORT";
 
+/// Environment variable name for the host an app's BRP port is reached on
+pub const BRP_EXTRAS_HOST_ENV_VAR: &str = "BRP_EXTRAS_HOST";
+
+/// Environment variable name for an app's 0-based index among the instances of its launch
+pub const BRP_EXTRAS_INSTANCE_INDEX_ENV_VAR: &str = "BRP_EXTRAS_INSTANCE_INDEX";
+
+/// Environment variable name for the number of instances started by an app's launch
+pub const BRP_EXTRAS_INSTANCE_COUNT_ENV_VAR: &str = "BRP_EXTRAS_INSTANCE_COUNT";
+
+/// Environment variable name for the address of the network proxy an app's instances talk through
+pub const BRP_EXTRAS_NETWORK_PROXY_ENV_VAR: &str = "BRP_EXTRAS_NETWORK_PROXY";
+
+/// Environment variable that sets the MCP server's session default port at startup
+pub const DEFAULT_PORT_ENV_VAR: &str = "BRP_MCP_DEFAULT_PORT";
+
 /// valid ports
 pub const MIN_VALID_PORT: u16 = 1024; // Non-privileged ports start here
 pub const MAX_VALID_PORT: u16 = 65534; // Leave room for calculations
@@ -0,0 +1,751 @@
+//! Archives of despawned entities for `world_undo_despawn`
+//!
+//! A despawn can't be taken back through BRP. With `archive: true`, `world_despawn_entity` first
+//! reads the entity and its whole subtree - every serializable component of each entity, and
+//! which entity is whose child - and writes it to a JSON file in the temp directory. The despawn
+//! result carries a `restore_token` naming the file, and `world_undo_despawn` spawns the subtree
+//! again from it.
+//!
+//! Restored entities get new IDs, and component fields the registry schema types as `Entity` are
+//! rewritten from the archived IDs to the new ones. Restoring claims the archive first, so it
+//! can't be restored twice. Components the app can't serialize aren't archived - they are
+//! counted in the despawn result and listed in the archive.
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Map;
+use serde_json::Value;
+use serde_json::json;
+
+use super::BrpClient;
+use super::Port;
+use super::ResponseStatus;
+use super::brp_client::registry_schemas;
+use super::brp_type_guide::TypeKind;
+use super::tools::world_export_graph::QUERY_COMPONENTS_FIELD;
+use super::tools::world_export_graph::TYPE_CHILD_OF;
+use super::tools::world_export_graph::first_entity;
+use super::tools::world_export_graph::with_aliases;
+use super::tools::world_query::BrpQuery;
+use super::tools::world_query::BrpQueryFilter;
+use super::tools::world_query::ComponentSelector;
+use crate::error::Error;
+use crate::error::Result;
+use crate::json_object::JsonObjectAccess;
+use crate::json_schema::SchemaField;
+use crate::tool::BrpMethod;
+use crate::tool::ParameterName;
+
+/// Component listing an entity's children - rebuilt by Bevy from `ChildOf`, so never archived
+const TYPE_CHILDREN: &str = "bevy_ecs::hierarchy::Children";
+
+/// Prefix of archive file names in the temp directory
+const ARCHIVE_FILE_PREFIX: &str = "bevy_brp_mcp_despawn_";
+
+/// Entities archived at most - a larger subtree fails the despawn instead
+const MAX_ARCHIVED_ENTITIES: usize = 1000;
+
+/// Field of a `world.get_components` response holding components that couldn't be read
+const GET_ERRORS_FIELD: &str = "errors";
+
+/// Suffix of an archive being restored
+const CLAIMED_SUFFIX: &str = ".restoring";
+
+/// Type path of entity references in component values
+const TYPE_ENTITY: &str = "bevy_ecs::entity::Entity";
+
+/// Nesting depth past which component values aren't searched for entity references
+const MAX_REMAP_DEPTH: usize = 32;
+
+/// An entity and its subtree as they were before the despawn
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DespawnArchive {
+    /// Port of the app the entities were despawned from
+    pub port:     u16,
+    /// The despawned entity
+    pub root:     u64,
+    /// Parent of the despawned entity, if it had one
+    pub parent:   Option<u64>,
+    /// The subtree, parents before their children
+    pub entities: Vec<ArchivedEntity>,
+}
+
+/// One archived entity
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedEntity {
+    /// Entity ID before the despawn
+    pub entity:     u64,
+    /// Parent within the archived subtree - `None` for the root
+    pub parent:     Option<u64>,
+    /// Component values by type path, without hierarchy components
+    pub components: Map<String, Value>,
+    /// Components the app couldn't serialize, with its error
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub skipped:    BTreeMap<String, String>,
+}
+
+/// Archive the entity of a despawn request carrying `archive: true`
+///
+/// Returns the request without `archive` (BRP doesn't know it) and the fields to add to the
+/// despawn result, or `None` when the request can be sent unchanged.
+pub async fn archive_before_despawn(
+    method: BrpMethod,
+    port: Port,
+    params: Option<&Value>,
+) -> Result<Option<(Value, Map<String, Value>)>> {
+    if method != BrpMethod::WorldDespawnEntity {
+        return Ok(None);
+    }
+    let Some(mut params) = params.and_then(Value::as_object).cloned() else {
+        return Ok(None);
+    };
+    let Some(archive) = params.remove(ParameterName::Archive.as_ref()) else {
+        return Ok(None);
+    };
+    if archive.as_bool() != Some(true) {
+        return Ok(Some((Value::Object(params), Map::new())));
+    }
+    let Some(entity) = params
+        .get(ParameterName::Entity.as_ref())
+        .and_then(Value::as_u64)
+    else {
+        return Err(Error::missing("entity to archive").into());
+    };
+
+    let archive = capture(port, entity).await?;
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_millis());
+    let token = format!("{}_{entity}_{timestamp}", port.0);
+    let path = archive_path(&token);
+    let contents = serde_json::to_string_pretty(&archive)
+        .map_err(|e| Error::General(format!("Failed to serialize despawn archive: {e}")))?;
+    tokio::fs::write(&path, contents).await.map_err(|e| {
+        Error::General(format!(
+            "Failed to write despawn archive {}: {e}",
+            path.display()
+        ))
+    })?;
+
+    let skipped: usize = archive.entities.iter().map(|e| e.skipped.len()).sum();
+    let mut fields = Map::new();
+    fields.insert("restore_token".to_string(), json!(token));
+    fields.insert(
+        "archived_entities".to_string(),
+        json!(archive.entities.len()),
+    );
+    fields.insert("skipped_components".to_string(), json!(skipped));
+    fields.insert(
+        "archive_path".to_string(),
+        json!(path.display().to_string()),
+    );
+    Ok(Some((Value::Object(params), fields)))
+}
+
+/// An archive claimed for restoring - no other restore can claim it meanwhile
+pub struct ClaimedArchive {
+    /// The archived entities
+    pub archive: DespawnArchive,
+    token:       String,
+}
+
+impl ClaimedArchive {
+    /// Give the archive back, for a restore that failed before it spawned anything
+    pub async fn put_back(self) {
+        // A failed rename leaves the claimed file, which only costs disk space
+        let _ = tokio::fs::rename(claimed_path(&self.token), archive_path(&self.token)).await;
+    }
+
+    /// Delete the archive, once it has been restored or partly restored
+    pub async fn discard(self) {
+        // A leftover archive only costs disk space in the temp directory
+        let _ = tokio::fs::remove_file(claimed_path(&self.token)).await;
+    }
+}
+
+/// Claim and read the archive named by `token`
+///
+/// Claiming renames the archive, so a second restore of the same token fails instead of
+/// spawning the entities twice.
+pub async fn claim(token: &str) -> Result<ClaimedArchive> {
+    if token.is_empty() || !token.chars().all(|c| c.is_ascii_digit() || c == '_') {
+        return Err(
+            Error::invalid("restore_token", format!("'{token}' is not a restore token")).into(),
+        );
+    }
+    let path = archive_path(token);
+    let claimed = claimed_path(token);
+    tokio::fs::rename(&path, &claimed).await.map_err(|e| {
+        Error::invalid(
+            "restore_token",
+            format!(
+                "no archive at {} ({e}) - it may have been restored already",
+                path.display()
+            ),
+        )
+    })?;
+    let archive = tokio::fs::read_to_string(&claimed)
+        .await
+        .map_err(|e| e.to_string())
+        .and_then(|contents| serde_json::from_str(&contents).map_err(|e| e.to_string()));
+    match archive {
+        Ok(archive) => Ok(ClaimedArchive {
+            archive,
+            token: token.to_string(),
+        }),
+        Err(e) => {
+            // Leave an unreadable archive where it was found
+            let _ = tokio::fs::rename(&claimed, &path).await;
+            Err(Error::General(format!(
+                "Despawn archive {} is unreadable: {e}",
+                path.display()
+            ))
+            .into())
+        },
+    }
+}
+
+/// File holding the archive named by `token`
+fn archive_path(token: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("{ARCHIVE_FILE_PREFIX}{token}.json"))
+}
+
+/// File holding the archive named by `token` while it is restored
+fn claimed_path(token: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("{ARCHIVE_FILE_PREFIX}{token}.json{CLAIMED_SUFFIX}"))
+}
+
+/// The components of restored entities that refer to other restored entities, rewritten to
+/// their new IDs - by archived entity, only the components that changed
+///
+/// `restored` maps archived entity IDs to the IDs they were restored as. Entity fields are found
+/// through the app's registry schema, so numbers that merely equal an archived ID are left alone.
+pub async fn remap_entity_references(
+    port: Port,
+    archive: &DespawnArchive,
+    restored: &HashMap<u64, u64>,
+) -> Result<Vec<(u64, Map<String, Value>)>> {
+    // Skip the registry when no value could hold an archived ID
+    let mentions_archived = |value: &Value| mentions_any(value, restored);
+    if !archive
+        .entities
+        .iter()
+        .any(|entity| entity.components.values().any(mentions_archived))
+    {
+        return Ok(Vec::new());
+    }
+
+    let schemas = registry_schemas(port).await?;
+    Ok(remap_with_schemas(&schemas, archive, restored))
+}
+
+/// `remap_entity_references` once the registry schema has been read
+fn remap_with_schemas(
+    schemas: &Map<String, Value>,
+    archive: &DespawnArchive,
+    restored: &HashMap<u64, u64>,
+) -> Vec<(u64, Map<String, Value>)> {
+    let mut remapped = Vec::new();
+    for entity in &archive.entities {
+        let changed: Map<String, Value> = entity
+            .components
+            .iter()
+            .filter_map(|(type_path, value)| {
+                let schema = schemas.get(type_path)?;
+                let mut value = value.clone();
+                (remap_value(schemas, schema, &mut value, restored, 0) > 0)
+                    .then(|| (type_path.clone(), value))
+            })
+            .collect();
+        if !changed.is_empty() {
+            remapped.push((entity.entity, changed));
+        }
+    }
+    remapped
+}
+
+/// Rewrite the entity fields of `value`, typed by `schema`, returning how many changed
+fn remap_value(
+    schemas: &Map<String, Value>,
+    schema: &Value,
+    value: &mut Value,
+    restored: &HashMap<u64, u64>,
+    depth: usize,
+) -> usize {
+    if depth > MAX_REMAP_DEPTH {
+        return 0;
+    }
+    if schema.get_field_str(SchemaField::TypePath) == Some(TYPE_ENTITY) {
+        return match value.as_u64().and_then(|entity| restored.get(&entity)) {
+            Some(&entity) => {
+                *value = json!(entity);
+                1
+            },
+            None => 0,
+        };
+    }
+    match TypeKind::from_schema(schema) {
+        TypeKind::Struct => remap_struct(schemas, schema, value, restored, depth),
+        TypeKind::Tuple | TypeKind::TupleStruct => {
+            remap_tuple(schemas, schema, value, restored, depth)
+        },
+        TypeKind::Array | TypeKind::List | TypeKind::Set => {
+            let (Some(items), Some(elements)) =
+                (schema.get_field(SchemaField::Items), value.as_array_mut())
+            else {
+                return 0;
+            };
+            elements
+                .iter_mut()
+                .map(|element| remap_field(schemas, items, element, restored, depth))
+                .sum()
+        },
+        TypeKind::Map => {
+            let (Some(value_type), Some(entries)) = (
+                schema.get_field(SchemaField::ValueType),
+                value.as_object_mut(),
+            ) else {
+                return 0;
+            };
+            entries
+                .values_mut()
+                .map(|entry| remap_field(schemas, value_type, entry, restored, depth))
+                .sum()
+        },
+        TypeKind::Enum => remap_enum(schemas, schema, value, restored, depth),
+        TypeKind::Value => 0,
+    }
+}
+
+/// Rewrite the entity fields of a value typed by the field schema `field`
+fn remap_field(
+    schemas: &Map<String, Value>,
+    field: &Value,
+    value: &mut Value,
+    restored: &HashMap<u64, u64>,
+    depth: usize,
+) -> usize {
+    resolve(schemas, field).map_or(0, |schema| {
+        remap_value(schemas, schema, value, restored, depth + 1)
+    })
+}
+
+/// Rewrite the entity fields of a struct, or a struct variant's payload
+fn remap_struct(
+    schemas: &Map<String, Value>,
+    schema: &Value,
+    value: &mut Value,
+    restored: &HashMap<u64, u64>,
+    depth: usize,
+) -> usize {
+    let (Some(properties), Some(fields)) = (schema.get_properties(), value.as_object_mut()) else {
+        return 0;
+    };
+    fields
+        .iter_mut()
+        .filter_map(|(name, value)| Some((properties.get(name)?, value)))
+        .map(|(field, value)| remap_field(schemas, field, value, restored, depth))
+        .sum()
+}
+
+/// Rewrite the entity fields of a tuple, or a tuple variant's payload - single-element tuples
+/// are written as their element
+fn remap_tuple(
+    schemas: &Map<String, Value>,
+    schema: &Value,
+    value: &mut Value,
+    restored: &HashMap<u64, u64>,
+    depth: usize,
+) -> usize {
+    let Some(items) = schema.get_field_array(SchemaField::PrefixItems) else {
+        return 0;
+    };
+    if let [item] = items.as_slice() {
+        return remap_field(schemas, item, value, restored, depth);
+    }
+    let Some(elements) = value.as_array_mut() else {
+        return 0;
+    };
+    items
+        .iter()
+        .zip(elements)
+        .map(|(item, element)| remap_field(schemas, item, element, restored, depth))
+        .sum()
+}
+
+/// Rewrite the entity fields of an enum value - `{"Variant": payload}`, or an `Option`'s inner
+/// value
+fn remap_enum(
+    schemas: &Map<String, Value>,
+    schema: &Value,
+    value: &mut Value,
+    restored: &HashMap<u64, u64>,
+    depth: usize,
+) -> usize {
+    let Some(variants) = schema.get_field_array(SchemaField::OneOf) else {
+        return 0;
+    };
+    let find_variant = |name: &str| {
+        variants
+            .iter()
+            .find(|variant| variant.get_field_str(SchemaField::ShortPath) == Some(name))
+    };
+    let is_option = schema
+        .get_field_str(SchemaField::TypePath)
+        .is_some_and(|type_path| type_path.starts_with("core::option::Option<"));
+    if is_option {
+        return match find_variant("Some") {
+            Some(some) if !value.is_null() => remap_tuple(schemas, some, value, restored, depth),
+            _ => 0,
+        };
+    }
+    let Some((name, payload)) = value
+        .as_object_mut()
+        .filter(|tagged| tagged.len() == 1)
+        .and_then(|tagged| tagged.iter_mut().next())
+    else {
+        return 0;
+    };
+    match find_variant(name) {
+        Some(variant) if variant.get_properties().is_some() => {
+            remap_struct(schemas, variant, payload, restored, depth)
+        },
+        Some(variant) => remap_tuple(schemas, variant, payload, restored, depth),
+        None => 0,
+    }
+}
+
+/// The type definition a field schema refers to through `type.$ref`
+fn resolve<'a>(schemas: &'a Map<String, Value>, field: &Value) -> Option<&'a Value> {
+    let type_name = field.extract_field_type()?;
+    schemas.get(type_name.as_str())
+}
+
+/// Whether `value` holds a number equal to an archived entity ID
+fn mentions_any(value: &Value, restored: &HashMap<u64, u64>) -> bool {
+    match value {
+        Value::Number(number) => number
+            .as_u64()
+            .is_some_and(|number| restored.contains_key(&number)),
+        Value::Array(items) => items.iter().any(|item| mentions_any(item, restored)),
+        Value::Object(fields) => fields.values().any(|field| mentions_any(field, restored)),
+        _ => false,
+    }
+}
+
+/// Read `root` and its subtree from the app
+async fn capture(port: Port, root: u64) -> Result<DespawnArchive> {
+    let parents = parents_by_child(port).await?;
+    let mut children: HashMap<u64, Vec<u64>> = HashMap::new();
+    for (&child, &parent) in &parents {
+        children.entry(parent).or_default().push(child);
+    }
+
+    // Breadth-first, so every parent is listed (and later restored) before its children
+    let mut order = vec![root];
+    let mut next = 0;
+    while let Some(&entity) = order.get(next) {
+        let mut below = children.remove(&entity).unwrap_or_default();
+        below.sort_unstable();
+        order.extend(below);
+        if order.len() > MAX_ARCHIVED_ENTITIES {
+            return Err(Error::tool_call_failed(format!(
+                "The subtree of entity {root} has more than {MAX_ARCHIVED_ENTITIES} entities - too \
+                 many to archive, despawn it without `archive`"
+            ))
+            .into());
+        }
+        next += 1;
+    }
+
+    let mut entities = Vec::with_capacity(order.len());
+    for entity in order {
+        let (components, skipped) = entity_components(port, entity).await?;
+        entities.push(ArchivedEntity {
+            entity,
+            parent: (entity != root)
+                .then(|| parents.get(&entity).copied())
+                .flatten(),
+            components,
+            skipped,
+        });
+    }
+
+    Ok(DespawnArchive {
+        port: port.0,
+        root,
+        parent: parents.get(&root).copied(),
+        entities,
+    })
+}
+
+/// Parent of every entity that has one
+async fn parents_by_child(port: Port) -> Result<HashMap<u64, u64>> {
+    let child_of = with_aliases(TYPE_CHILD_OF);
+    let query = BrpQuery {
+        option: ComponentSelector::Paths(child_of.clone()),
+        ..BrpQuery::default()
+    };
+    let params = json!({
+        ParameterName::Data: query,
+        ParameterName::Filter: BrpQueryFilter::default(),
+    });
+    let rows = match BrpClient::new(BrpMethod::WorldQuery, port, Some(params))
+        .execute_raw()
+        .await?
+    {
+        ResponseStatus::Success(Some(Value::Array(rows))) => rows,
+        ResponseStatus::Success(_) => Vec::new(),
+        ResponseStatus::Error(err) => return Err(Error::tool_call_failed(err.get_message()).into()),
+    };
+
+    let mut parents = HashMap::new();
+    for row in &rows {
+        let Some(entity) = row.get_field(ParameterName::Entity).and_then(Value::as_u64) else {
+            continue;
+        };
+        let components = row.get(QUERY_COMPONENTS_FIELD);
+        if let Some(parent) = child_of
+            .iter()
+            .find_map(|type_path| first_entity(components?.get(type_path.as_str())?))
+        {
+            parents.insert(entity, parent);
+        }
+    }
+    Ok(parents)
+}
+
+/// Serializable components of `entity` without hierarchy components, and those that failed
+async fn entity_components(
+    port: Port,
+    entity: u64,
+) -> Result<(Map<String, Value>, BTreeMap<String, String>)> {
+    let listed = BrpClient::new(
+        BrpMethod::WorldListComponents,
+        port,
+        Some(json!({ ParameterName::Entity: entity })),
+    )
+    .execute_raw()
+    .await?;
+    let hierarchy: Vec<String> = with_aliases(TYPE_CHILD_OF)
+        .into_iter()
+        .chain(with_aliases(TYPE_CHILDREN))
+        .collect();
+    let type_paths: Vec<String> = match listed {
+        ResponseStatus::Success(Some(Value::Array(names))) => names
+            .iter()
+            .filter_map(Value::as_str)
+            .filter(|name| !hierarchy.iter().any(|h| h == name))
+            .map(String::from)
+            .collect(),
+        ResponseStatus::Success(_) => Vec::new(),
+        ResponseStatus::Error(err) => return Err(Error::tool_call_failed(err.get_message()).into()),
+    };
+    if type_paths.is_empty() {
+        return Ok((Map::new(), BTreeMap::new()));
+    }
+
+    let fetched = BrpClient::new(
+        BrpMethod::WorldGetComponents,
+        port,
+        Some(json!({
+            ParameterName::Entity: entity,
+            ParameterName::Components: type_paths,
+            ParameterName::Strict: false,
+        })),
+    )
+    .execute_raw()
+    .await?;
+    let response = match fetched {
+        ResponseStatus::Success(Some(Value::Object(response))) => response,
+        ResponseStatus::Success(_) => Map::new(),
+        ResponseStatus::Error(err) => return Err(Error::tool_call_failed(err.get_message()).into()),
+    };
+
+    let components = response
+        .get(QUERY_COMPONENTS_FIELD)
+        .and_then(Value::as_object)
+        .cloned()
+        .unwrap_or_default();
+    let skipped = response
+        .get(GET_ERRORS_FIELD)
+        .and_then(Value::as_object)
+        .map(|errors| {
+            errors
+                .iter()
+                .map(|(type_path, error)| {
+                    let message = error
+                        .get("message")
+                        .and_then(Value::as_str)
+                        .map_or_else(|| error.to_string(), String::from);
+                    (type_path.clone(), message)
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    Ok((components, skipped))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field(type_path: &str) -> Value {
+        json!({ "type": { "$ref": format!("#/$defs/{type_path}") } })
+    }
+
+    fn schemas() -> Map<String, Value> {
+        let schemas = json!({
+            "u64": {"kind": "Value", "typePath": "u64", "type": "uint"},
+            TYPE_ENTITY: {"kind": "Value", "typePath": TYPE_ENTITY, "type": "uint"},
+            "Target": {
+                "kind": "Struct",
+                "typePath": "Target",
+                "properties": {
+                    "entity": field(TYPE_ENTITY),
+                    "count": field("u64"),
+                    "others": field("Vec<Entity>"),
+                    "maybe": field("Option<Entity>"),
+                    "goal": field("Goal"),
+                },
+            },
+            "Vec<Entity>": {"kind": "List", "typePath": "Vec<Entity>", "items": field(TYPE_ENTITY)},
+            "Option<Entity>": {
+                "kind": "Enum",
+                "typePath": "core::option::Option<Entity>",
+                "oneOf": [
+                    "None",
+                    {"shortPath": "Some", "prefixItems": [field(TYPE_ENTITY)]},
+                ],
+            },
+            "Goal": {
+                "kind": "Enum",
+                "typePath": "Goal",
+                "oneOf": [
+                    "Idle",
+                    {"shortPath": "Follow", "prefixItems": [field(TYPE_ENTITY)]},
+                    {"shortPath": "Pair", "prefixItems": [field(TYPE_ENTITY), field("u64")]},
+                ],
+            },
+        });
+        match schemas {
+            Value::Object(schemas) => schemas,
+            _ => unreachable!("fixture is an object"),
+        }
+    }
+
+    fn archived(entity: u64, components: Value) -> ArchivedEntity {
+        ArchivedEntity {
+            entity,
+            parent: None,
+            components: match components {
+                Value::Object(components) => components,
+                _ => unreachable!("fixture components are an object"),
+            },
+            skipped: BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn entity_fields_are_rewritten_and_other_numbers_left_alone() {
+        let archive = DespawnArchive {
+            port:     15702,
+            root:     7,
+            parent:   None,
+            entities: vec![
+                archived(
+                    7,
+                    json!({
+                        "Target": {
+                            "entity": 8,
+                            "count": 8,
+                            "others": [8, 99],
+                            "maybe": 7,
+                            "goal": {"Pair": [8, 7]},
+                        },
+                    }),
+                ),
+                archived(8, json!({ "Target": { "entity": 99, "count": 7 } })),
+                archived(9, json!({ "Unknown": { "entity": 7 } })),
+            ],
+        };
+        let restored = HashMap::from([(7, 107), (8, 108), (9, 109)]);
+
+        let remapped = remap_with_schemas(&schemas(), &archive, &restored);
+
+        // Only entity 7 refers to restored entities through `Entity` fields
+        assert_eq!(remapped.len(), 1);
+        let (entity, components) = &remapped[0];
+        assert_eq!(*entity, 7);
+        assert_eq!(
+            components["Target"],
+            json!({
+                "entity": 108,
+                "count": 8,
+                "others": [108, 99],
+                "maybe": 107,
+                "goal": {"Pair": [108, 7]},
+            })
+        );
+    }
+
+    #[test]
+    fn single_element_variants_are_written_as_their_element() {
+        let archive = DespawnArchive {
+            port:     15702,
+            root:     7,
+            parent:   None,
+            entities: vec![archived(7, json!({ "Target": { "goal": {"Follow": 7} } }))],
+        };
+        let restored = HashMap::from([(7, 107)]);
+
+        let remapped = remap_with_schemas(&schemas(), &archive, &restored);
+
+        assert_eq!(remapped[0].1["Target"], json!({ "goal": {"Follow": 107} }));
+    }
+
+    #[tokio::test]
+    async fn an_archive_is_claimed_once_unless_put_back() {
+        let token = format!("0_0_{}", std::process::id());
+        let archive = DespawnArchive {
+            port:     45_811,
+            root:     7,
+            parent:   None,
+            entities: vec![archived(7, json!({}))],
+        };
+        let contents =
+            serde_json::to_string(&archive).unwrap_or_else(|e| unreachable!("serializes: {e}"));
+        tokio::fs::write(archive_path(&token), contents)
+            .await
+            .unwrap_or_else(|e| unreachable!("temp dir should be writable: {e}"));
+
+        let claimed = claim(&token)
+            .await
+            .unwrap_or_else(|e| unreachable!("archive should be claimable: {e}"));
+        assert_eq!(claimed.archive.port, 45_811);
+        assert!(
+            claim(&token).await.is_err(),
+            "a claimed archive can't be claimed again"
+        );
+
+        claimed.put_back().await;
+        let claimed = claim(&token)
+            .await
+            .unwrap_or_else(|e| unreachable!("a put back archive should be claimable: {e}"));
+        claimed.discard().await;
+        assert!(claim(&token).await.is_err(), "a discarded archive is gone");
+        assert!(!claimed_path(&token).exists());
+    }
+
+    #[tokio::test]
+    async fn tokens_that_could_escape_the_temp_dir_are_rejected() {
+        assert!(claim("../etc/passwd").await.is_err());
+        assert!(claim("").await.is_err());
+    }
+}
@@ -0,0 +1,182 @@
+//! What a despawn would take with it, reported before anything is despawned
+//!
+//! Despawning an entity also despawns all of its descendants, which in a nested scene can be far
+//! more than the caller meant to remove. With `preview: true`, `world_despawn_entity` and
+//! `brp_extras_apply_commands` send nothing to the app's despawn path: they read the hierarchy
+//! the way `world_export_graph` does and report, for every entity that would be despawned, its
+//! descendants and the relationships that cross the edge of its subtree - parents losing a child,
+//! and entities whose relationship components point into or out of the removed entities.
+
+use std::collections::BTreeSet;
+
+use serde::Serialize;
+use serde_json::Value;
+use serde_json::json;
+
+use super::Port;
+use super::tools::world_export_graph::Scene;
+use super::tools::world_export_graph::fetch_scene;
+use crate::error::Error;
+use crate::error::Result;
+
+/// Field of an `apply_commands` command naming its kind
+const COMMAND_FIELD: &str = "command";
+
+/// Kind of an `apply_commands` command that despawns its entity
+const DESPAWN_COMMAND: &str = "despawn";
+
+/// Field of an `apply_commands` command naming its entity
+const ENTITY_FIELD: &str = "entity";
+
+/// A relationship that would lose one of its ends
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AffectedRelationship {
+    /// Entity holding the relationship component
+    pub source:        u64,
+    /// Short name of the relationship component
+    pub relationship:  String,
+    /// Entity the component points to
+    pub target:        u64,
+    /// Which end is despawned - `source` or `target`
+    pub despawned_end: &'static str,
+}
+
+/// What despawning one entity would take with it
+#[derive(Debug, Clone, Serialize)]
+pub struct DespawnPreview {
+    /// The entity to despawn
+    pub entity:        u64,
+    /// Its label - `Name`, or `Entity {id}`
+    pub name:          String,
+    /// Its parent, which would lose a child
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent:        Option<u64>,
+    /// Number of descendants despawned with it
+    pub descendants:   usize,
+    /// The descendants, parents before their children
+    pub despawned:     Vec<u64>,
+    /// Relationships between the subtree and entities outside it
+    pub relationships: Vec<AffectedRelationship>,
+}
+
+/// Preview despawning each of `roots` - `relationships` are the relationship components to check,
+/// by full type path
+pub async fn preview(port: Port, roots: &[u64], relationships: &[String]) -> Result<Value> {
+    let scene = fetch_scene(port, None, relationships).await?;
+    let previews = roots
+        .iter()
+        .map(|&root| preview_in(&scene, root))
+        .collect::<Result<Vec<_>>>()?;
+    let total: BTreeSet<u64> = previews
+        .iter()
+        .flat_map(|preview| {
+            std::iter::once(preview.entity).chain(preview.despawned.iter().copied())
+        })
+        .collect();
+    Ok(json!({
+        "preview": true,
+        "total_despawned": total.len(),
+        "entities": previews,
+    }))
+}
+
+/// Entities an `apply_commands` batch despawns - despawns of entities spawned by the batch
+/// itself (`"$N"`) remove nothing that exists yet and are left out
+pub fn despawned_by_commands(commands: &[Value]) -> Vec<u64> {
+    commands
+        .iter()
+        .filter(|command| {
+            command.get(COMMAND_FIELD).and_then(Value::as_str) == Some(DESPAWN_COMMAND)
+        })
+        .filter_map(|command| command.get(ENTITY_FIELD).and_then(Value::as_u64))
+        .collect()
+}
+
+/// What despawning `root` would take with it in `scene`
+fn preview_in(scene: &Scene, root: u64) -> Result<DespawnPreview> {
+    let Some(name) = scene.names.get(&root) else {
+        return Err(Error::invalid("entity", format!("entity {root} was not found")).into());
+    };
+    let despawned = scene.descendants(root);
+    let subtree: BTreeSet<u64> = std::iter::once(root)
+        .chain(despawned.iter().copied())
+        .collect();
+    let relationships = scene
+        .relationships
+        .iter()
+        .filter_map(|(source, relationship, target)| {
+            let despawned_end = match (subtree.contains(source), subtree.contains(target)) {
+                (true, false) => "source",
+                (false, true) => "target",
+                _ => return None,
+            };
+            Some(AffectedRelationship {
+                source: *source,
+                relationship: relationship.clone(),
+                target: *target,
+                despawned_end,
+            })
+        })
+        .collect();
+
+    Ok(DespawnPreview {
+        entity: root,
+        name: name.clone(),
+        parent: scene.parents.get(&root).copied(),
+        descendants: despawned.len(),
+        despawned,
+        relationships,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+
+    #[test]
+    fn reports_descendants_and_relationships_crossing_the_subtree() {
+        let scene = Scene {
+            names:         BTreeMap::from([
+                (1, "Level".to_string()),
+                (2, "Squad".to_string()),
+                (3, "Soldier".to_string()),
+                (4, "Rifle".to_string()),
+                (5, "Enemy".to_string()),
+            ]),
+            parents:       BTreeMap::from([(2, 1), (3, 2), (4, 3)]),
+            relationships: vec![
+                (3, "AimAt".to_string(), 5),
+                (5, "Targets".to_string(), 4),
+                (3, "Holds".to_string(), 4),
+            ],
+        };
+
+        let Ok(preview) = preview_in(&scene, 2) else {
+            unreachable!("entity 2 is in the scene");
+        };
+        assert_eq!(preview.parent, Some(1));
+        assert_eq!(preview.despawned, vec![3, 4]);
+        assert_eq!(
+            preview
+                .relationships
+                .iter()
+                .map(|r| (r.source, r.target, r.despawned_end))
+                .collect::<Vec<_>>(),
+            vec![(3, 5, "source"), (5, 4, "target")]
+        );
+        assert!(preview_in(&scene, 9).is_err());
+    }
+
+    #[test]
+    fn picks_existing_entities_despawned_by_a_batch() {
+        let commands = vec![
+            json!({ "command": "spawn", "components": {} }),
+            json!({ "command": "despawn", "entity": "$0" }),
+            json!({ "command": "despawn", "entity": 42 }),
+            json!({ "command": "remove", "entity": 7, "components": [] }),
+        ];
+        assert_eq!(despawned_by_commands(&commands), vec![42]);
+    }
+}
@@ -0,0 +1,302 @@
+//! Session-scoped entity aliases
+//!
+//! `brp_alias_entity` binds human-readable names such as "player" to entity IDs, per port since
+//! IDs only mean something within one app. Every tool parameter named `entity`, `entities` or
+//! `parent` accepts an alias in place of the ID - aliases are replaced with their IDs before the
+//! parameters are deserialized, so individual tools never see them.
+//!
+//! An alias is dropped when its entity is despawned through `world_despawn_entity`, or as soon as
+//! the app reports the entity no longer exists (it may have been despawned by the game itself).
+//! All aliases on a port are dropped when the app behind it restarts.
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+use std::sync::Mutex;
+
+use serde_json::Value;
+use tracing::debug;
+
+use super::Port;
+use super::ResponseStatus;
+use crate::error::Error;
+use crate::error::Result;
+use crate::json_object::coerce_string;
+use crate::tool::BrpMethod;
+use crate::tool::ParameterName;
+
+/// Tool parameters that hold entity IDs and therefore accept aliases
+const ENTITY_PARAMETERS: [ParameterName; 3] = [
+    ParameterName::Entity,
+    ParameterName::Entities,
+    ParameterName::Parent,
+];
+
+/// Aliases per port, sorted by name
+static ENTITY_ALIASES: LazyLock<Mutex<HashMap<Port, BTreeMap<String, u64>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Bind `alias` to `entity` on `port`, returning the entity it was bound to before
+pub fn bind(port: Port, alias: &str, entity: u64) -> Result<Option<u64>> {
+    // Anything `coerce_string` would turn into a number or boolean could never reach the resolver
+    // as a string
+    if coerce_string(alias).is_some() {
+        return Err(Error::invalid(
+            "alias",
+            format!("'{alias}' would be read as a value, not a name"),
+        )
+        .into());
+    }
+
+    let mut aliases = ENTITY_ALIASES
+        .lock()
+        .map_err(|_| Error::General("Entity alias registry is unavailable".to_string()))?;
+    Ok(aliases
+        .entry(port)
+        .or_default()
+        .insert(alias.to_string(), entity))
+}
+
+/// Remove `alias` from `port`, returning the entity it was bound to
+pub fn unbind(port: Port, alias: &str) -> Option<u64> {
+    ENTITY_ALIASES
+        .lock()
+        .ok()?
+        .get_mut(&port)
+        .and_then(|aliases| aliases.remove(alias))
+}
+
+/// All aliases bound on `port`
+pub fn aliases_for(port: Port) -> BTreeMap<String, u64> {
+    ENTITY_ALIASES
+        .lock()
+        .ok()
+        .and_then(|aliases| aliases.get(&port).cloned())
+        .unwrap_or_default()
+}
+
+/// Drop every alias bound on `port` - returns the number of aliases dropped
+pub fn forget_port_aliases(port: Port) -> usize {
+    ENTITY_ALIASES
+        .lock()
+        .ok()
+        .and_then(|mut aliases| aliases.remove(&port))
+        .map_or(0, |port_aliases| port_aliases.len())
+}
+
+/// Drop every alias bound to `entity` on `port`
+fn forget_entity(port: Port, entity: u64) {
+    let Ok(mut aliases) = ENTITY_ALIASES.lock() else {
+        return;
+    };
+    if let Some(port_aliases) = aliases.get_mut(&port) {
+        port_aliases.retain(|alias, bound| {
+            let keep = *bound != entity;
+            if !keep {
+                debug!("Dropping alias '{alias}' for despawned entity {entity} on port {port}");
+            }
+            keep
+        });
+    }
+}
+
+/// Replace aliases in the entity parameters of a tool call's arguments with their entity IDs
+///
+/// Aliases are looked up on the call's `port`, or the session default port when it's omitted.
+/// A string that isn't an alias on that port is an error rather than being left for
+/// deserialization to reject with a less helpful message.
+pub fn resolve_entity_aliases(mut arguments: Value) -> Result<Value> {
+    let Some(object) = arguments.as_object_mut() else {
+        return Ok(arguments);
+    };
+
+    let port = object
+        .get(ParameterName::Port.as_ref())
+        .and_then(Value::as_u64)
+        .and_then(|port| u16::try_from(port).ok())
+        .map_or_else(Port::session_default, Port);
+
+    for parameter in ENTITY_PARAMETERS {
+        match object.get_mut(parameter.as_ref()) {
+            Some(Value::Array(values)) => {
+                for value in values {
+                    resolve_value(port, value)?;
+                }
+            },
+            Some(value) => resolve_value(port, value)?,
+            None => {},
+        }
+    }
+
+    Ok(arguments)
+}
+
+/// Replace `value` with the entity ID it names if it's an alias
+fn resolve_value(port: Port, value: &mut Value) -> Result<()> {
+    let Value::String(alias) = value else {
+        return Ok(());
+    };
+
+    match aliases_for(port).get(alias.as_str()) {
+        Some(&entity) => {
+            *value = Value::from(entity);
+            Ok(())
+        },
+        None => Err(Error::invalid(
+            "entity",
+            format!(
+                "'{alias}' is neither an entity ID nor an alias on port {port} - bind it with \
+                 brp_alias_entity first"
+            ),
+        )
+        .into()),
+    }
+}
+
+/// Drop aliases for entities a BRP call showed to be gone
+///
+/// A successful `world.despawn_entity` removes its entity's aliases. A call the app rejects
+/// because an entity doesn't exist removes the aliases of the entities the error names - a call
+/// about several entities may have failed on just one of them.
+pub fn observe_response(
+    method: BrpMethod,
+    port: Port,
+    params: Option<&Value>,
+    status: &ResponseStatus,
+) {
+    let missing: Option<&str> = match status {
+        ResponseStatus::Success(_) if method == BrpMethod::WorldDespawnEntity => None,
+        ResponseStatus::Error(err) if err.is_entity_not_found() => Some(err.get_message()),
+        _ => return,
+    };
+
+    let Some(params) = params else {
+        return;
+    };
+    let gone = |entity: &u64| missing.is_none_or(|message| names_entity(message, *entity));
+    for parameter in ENTITY_PARAMETERS {
+        match params.get(parameter.as_ref()) {
+            Some(Value::Array(values)) => values
+                .iter()
+                .filter_map(Value::as_u64)
+                .filter(gone)
+                .for_each(|entity| forget_entity(port, entity)),
+            Some(value) => {
+                if let Some(entity) = value.as_u64().filter(gone) {
+                    forget_entity(port, entity);
+                }
+            },
+            None => {},
+        }
+    }
+}
+
+/// Whether an error `message` names `entity` - the app writes entities as `<index>v<generation>`
+fn names_entity(message: &str, entity: u64) -> bool {
+    // The low 32 bits hold the index stored as its complement, the high 32 bits the generation
+    let index = u32::try_from(entity & u64::from(u32::MAX)).unwrap_or_default() ^ u32::MAX;
+    let generation = entity >> 32;
+    let name = format!("{index}v{generation}");
+    message.match_indices(&name).any(|(start, _)| {
+        let before = message[..start].chars().next_back();
+        let after = message[start + name.len()..].chars().next();
+        !before.is_some_and(|c| c.is_ascii_alphanumeric())
+            && !after.is_some_and(|c| c.is_ascii_alphanumeric())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::brp_tools::brp_client::BRP_ERROR_CODE_ENTITY_NOT_FOUND;
+    use crate::brp_tools::brp_client::BrpClientError;
+
+    #[test]
+    fn resolves_aliases_in_entity_parameters() {
+        let port = Port(25_701);
+        assert!(bind(port, "player", 42).is_ok());
+        assert!(bind(port, "boss", 7).is_ok());
+
+        let resolved = resolve_entity_aliases(json!({
+            "port": 25_701,
+            "entity": "player",
+            "entities": ["boss", 3],
+            "components": ["player"],
+        }));
+        assert_eq!(
+            resolved.ok(),
+            Some(json!({
+                "port": 25_701,
+                "entity": 42,
+                "entities": [7, 3],
+                "components": ["player"],
+            }))
+        );
+    }
+
+    #[test]
+    fn unknown_alias_is_an_error() {
+        let resolved = resolve_entity_aliases(json!({ "port": 25_702, "entity": "nobody" }));
+        assert!(resolved.is_err());
+    }
+
+    #[test]
+    fn rejects_names_that_read_as_values() {
+        let port = Port(25_703);
+        assert!(bind(port, "12", 1).is_err());
+        assert!(bind(port, "true", 1).is_err());
+        assert!(bind(port, "enemy_12", 1).is_ok());
+    }
+
+    #[test]
+    fn despawn_drops_aliases() {
+        let port = Port(25_704);
+        assert!(bind(port, "player", 42).is_ok());
+        observe_response(
+            BrpMethod::WorldDespawnEntity,
+            port,
+            Some(&json!({ "entity": 42 })),
+            &ResponseStatus::Success(None),
+        );
+        assert!(aliases_for(port).is_empty());
+    }
+
+    #[test]
+    fn entity_not_found_drops_only_the_named_entity() {
+        let port = Port(25_707);
+        // Entities 3v0 and 30v1 as the app serializes them
+        let gone = u64::from(u32::MAX ^ 0b11);
+        let alive = (1 << 32) | u64::from(u32::MAX ^ 0b1_1110);
+        assert!(bind(port, "gone", gone).is_ok());
+        assert!(bind(port, "alive", alive).is_ok());
+        let error = BrpClientError {
+            code:    BRP_ERROR_CODE_ENTITY_NOT_FOUND,
+            message: "Entity 3v0 not found".to_string(),
+            data:    None,
+        };
+        observe_response(
+            BrpMethod::WorldReparentEntities,
+            port,
+            Some(&json!({ "entities": [gone, alive] })),
+            &ResponseStatus::Error(error),
+        );
+        assert_eq!(aliases_for(port).into_keys().collect::<Vec<_>>(), ["alive"]);
+        // `30v1` isn't `3v0`, nor is `13v0`
+        assert!(names_entity("Entity 30v1 not found", alive));
+        assert!(!names_entity("Entity 13v0 not found", gone));
+    }
+
+    #[test]
+    fn restart_drops_every_alias_on_the_port() {
+        let port = Port(25_705);
+        assert!(bind(port, "player", 42).is_ok());
+        assert!(bind(port, "boss", 7).is_ok());
+        assert!(bind(Port(25_706), "player", 42).is_ok());
+
+        assert_eq!(forget_port_aliases(port), 2);
+        assert!(aliases_for(port).is_empty());
+        assert_eq!(aliases_for(Port(25_706)).len(), 1);
+    }
+}
@@ -1,6 +1,11 @@
+mod app_restart;
 mod brp_client;
 mod brp_type_guide;
+mod change_checkpoints;
 mod constants;
+mod despawn_archive;
+mod despawn_preview;
+mod entity_alias;
 mod port;
 mod tools;
 mod watch_tools;
@@ -9,38 +14,157 @@ mod watch_tools;
 //
 // We export `JSON_RPC_ERROR_METHOD_NOT_FOUND` so that the `brp_shutdown` tool can determine if
 // `brp_mcp_extras` is available
+pub use brp_client::AutoName;
+pub use brp_client::BRP_DEFAULT_HOST;
+pub use brp_client::BRP_HTTP_PROTOCOL;
+pub use brp_client::BRP_JSONRPC_PATH;
+pub use brp_client::BinaryResponseStream;
 pub use brp_client::BrpClient;
 pub use brp_client::BrpToolConfig;
+pub use brp_client::COLOR_SHORTHAND_NOTE;
+pub use brp_client::CallReport;
+pub use brp_client::CorrectionEvent;
+pub use brp_client::Corrector;
+pub use brp_client::FORMAT_CORRECTION_NOTE;
 pub use brp_client::FormatCorrectionStatus;
+pub use brp_client::FormatCorrections;
 pub use brp_client::JSON_RPC_ERROR_METHOD_NOT_FOUND;
+pub use brp_client::RemoteHost;
+pub use brp_client::RequestId;
+pub use brp_client::RequestPriority;
 pub use brp_client::ResponseStatus;
 pub use brp_client::ResultStructBrpExt;
+pub use brp_client::accepts_color_shorthand;
+pub use brp_client::compact_query_result;
+pub use brp_client::corrections;
+pub use brp_client::forget_remote_host;
+pub use brp_client::refresh_short_name_index;
+pub use brp_client::register_remote_host;
+pub use brp_client::remote_host_for;
+pub use brp_client::reset_circuit;
+pub use brp_client::with_call_report;
+pub use brp_client::with_managed_screenshot_path;
 //
 // Export brp_type_guide tools
 pub use brp_type_guide::{
-    AllTypeGuidesParams, BrpAllTypeGuides, BrpTypeGuide, BrpTypeName, TypeGuideParams,
+    AllTypeGuidesParams, BrpAllTypeGuides, BrpClearTypeGuideCache, BrpTypeGuide, BrpTypeName,
+    ClearTypeGuideCacheParams, TypeGuideParams,
 };
+pub use constants::BRP_EXTRAS_HOST_ENV_VAR;
+pub use constants::BRP_EXTRAS_INSTANCE_COUNT_ENV_VAR;
+pub use constants::BRP_EXTRAS_INSTANCE_INDEX_ENV_VAR;
+pub use constants::BRP_EXTRAS_NETWORK_PROXY_ENV_VAR;
 pub use constants::BRP_EXTRAS_PORT_ENV_VAR;
 pub use constants::MAX_VALID_PORT;
+pub use entity_alias::resolve_entity_aliases;
 pub use port::Port;
+pub use port::configured_default_port;
 //
 // Export special case tools that don't follow the standard pattern
-pub use tools::brp_execute::{BrpExecute, ExecuteParams};
+pub use tools::brp_alias_entity::{AliasEntityParams, BrpAliasEntity};
+pub use tools::brp_assert::AssertParams;
+pub use tools::brp_assert::BrpAssert;
+pub use tools::brp_execute::BrpExecute;
+pub use tools::brp_execute::ExecuteParams;
+pub use tools::brp_extras_app_info::AppInfoParams;
+pub use tools::brp_extras_app_info::AppInfoResult;
+pub use tools::brp_extras_apply_commands::ApplyCommandsParams;
+pub use tools::brp_extras_apply_commands::BrpExtrasApplyCommands;
+pub use tools::brp_extras_capabilities::CapabilitiesParams;
+pub use tools::brp_extras_capabilities::CapabilitiesResult;
+pub use tools::brp_extras_clipboard::ClipboardParams;
+pub use tools::brp_extras_clipboard::ClipboardResult;
+pub use tools::brp_extras_collect_frame_stats::BrpExtrasCollectFrameStats;
+pub use tools::brp_extras_collect_frame_stats::CollectFrameStatsParams;
+pub use tools::brp_extras_determinism::DeterminismParams;
+pub use tools::brp_extras_determinism::DeterminismResult;
+pub use tools::brp_extras_ensure_registered::EnsureRegisteredParams;
+pub use tools::brp_extras_ensure_registered::EnsureRegisteredResult;
+pub use tools::brp_extras_find_tagged::FindTaggedParams;
+pub use tools::brp_extras_find_tagged::FindTaggedResult;
+pub use tools::brp_extras_get_history::GetHistoryParams;
+pub use tools::brp_extras_get_history::GetHistoryResult;
+pub use tools::brp_extras_get_interaction_state::GetInteractionStateParams;
+pub use tools::brp_extras_get_interaction_state::GetInteractionStateResult;
+pub use tools::brp_extras_get_ui_text::GetUiTextParams;
+pub use tools::brp_extras_get_ui_text::GetUiTextResult;
+pub use tools::brp_extras_gizmo_config::GizmoConfigParams;
+pub use tools::brp_extras_gizmo_config::GizmoConfigResult;
+pub use tools::brp_extras_list_monitors::ListMonitorsParams;
+pub use tools::brp_extras_list_monitors::ListMonitorsResult;
+pub use tools::brp_extras_material::MaterialParams;
+pub use tools::brp_extras_material::MaterialResult;
+pub use tools::brp_extras_pin_entity::PinEntityParams;
+pub use tools::brp_extras_pin_entity::PinEntityResult;
+pub use tools::brp_extras_query_stream::BrpExtrasQueryStream;
+pub use tools::brp_extras_query_stream::QueryStreamParams;
+pub use tools::brp_extras_read_pixels::BrpExtrasReadPixels;
+pub use tools::brp_extras_read_pixels::ReadPixelsParams;
+pub use tools::brp_extras_schedule_report::ScheduleReportParams;
+pub use tools::brp_extras_schedule_report::ScheduleReportResult;
+pub use tools::brp_extras_screenshot::BrpExtrasScreenshot;
 pub use tools::brp_extras_screenshot::ScreenshotParams;
-pub use tools::brp_extras_screenshot::ScreenshotResult;
+pub use tools::brp_extras_screenshot_status::ScreenshotStatusParams;
+pub use tools::brp_extras_screenshot_status::ScreenshotStatusResult;
 pub use tools::brp_extras_send_keys::SendKeysParams;
 pub use tools::brp_extras_send_keys::SendKeysResult;
+pub use tools::brp_extras_set_window::SetWindowParams;
+pub use tools::brp_extras_set_window::SetWindowResult;
 pub use tools::brp_extras_set_window_title::SetWindowTitleParams;
 pub use tools::brp_extras_set_window_title::SetWindowTitleResult;
+pub use tools::brp_extras_tag_entity::TagEntityParams;
+pub use tools::brp_extras_tag_entity::TagEntityResult;
+pub use tools::brp_extras_time_config::TimeConfigParams;
+pub use tools::brp_extras_time_config::TimeConfigResult;
+pub use tools::brp_extras_track_archetype_moves::BrpExtrasTrackArchetypeMoves;
+pub use tools::brp_extras_track_archetype_moves::TrackArchetypeMovesParams;
+pub use tools::brp_extras_track_entity::TrackEntityParams;
+pub use tools::brp_extras_track_entity::TrackEntityResult;
+pub use tools::brp_fuzz_component::BrpFuzzComponent;
+pub use tools::brp_fuzz_component::FuzzComponentParams;
+pub use tools::brp_list_corrections::BrpListCorrections;
+pub use tools::brp_list_corrections::ListCorrectionsParams;
+pub use tools::brp_map_entities::BrpMapEntities;
+pub use tools::brp_map_entities::MapEntitiesParams;
+pub use tools::brp_refresh_type_cache::BrpRefreshTypeCache;
+pub use tools::brp_refresh_type_cache::RefreshTypeCacheParams;
+pub use tools::brp_request_confirmation::BrpRequestConfirmation;
+pub use tools::brp_request_confirmation::RequestConfirmationParams;
+pub use tools::brp_reset_circuit::BrpResetCircuit;
+pub use tools::brp_reset_circuit::ResetCircuitParams;
+pub use tools::brp_set_default_port::BrpSetDefaultPort;
+pub use tools::brp_set_default_port::SetDefaultPortParams;
+pub use tools::brp_set_strict_formats::BrpSetStrictFormats;
+pub use tools::brp_set_strict_formats::SetStrictFormatsParams;
+pub use tools::brp_wait_for::BrpWaitFor;
+pub use tools::brp_wait_for::WaitForParams;
+pub use tools::brp_wait_for_idle::BrpWaitForIdle;
+pub use tools::brp_wait_for_idle::WaitForIdleParams;
 #[allow(unused_imports)]
-pub use tools::grab_selection::{GrabSelection, GrabSelectionParams, GrabSelectionResult};
+pub use tools::grab_selection::GrabSelection;
+#[allow(unused_imports)]
+pub use tools::grab_selection::GrabSelectionParams;
+#[allow(unused_imports)]
+pub use tools::grab_selection::GrabSelectionResult;
 //
 // Export all parameter and result structs by name
-pub use tools::registry_schema::{RegistrySchemaParams, RegistrySchemaResult};
+pub use tools::registry_schema::{RegistrySchema, RegistrySchemaParams};
 pub use tools::rpc_discover::RpcDiscoverParams;
 pub use tools::rpc_discover::RpcDiscoverResult;
+pub use tools::world_changes_since::ChangesSinceParams;
+pub use tools::world_changes_since::WorldChangesSince;
+pub use tools::world_component_matrix::ComponentMatrixParams;
+pub use tools::world_component_matrix::WorldComponentMatrix;
 pub use tools::world_despawn_entity::DespawnEntityParams;
 pub use tools::world_despawn_entity::DespawnEntityResult;
+pub use tools::world_despawn_entity::WorldDespawnEntity;
+pub use tools::world_dump_resources::DumpResourcesParams;
+pub use tools::world_dump_resources::WorldDumpResources;
+pub use tools::world_export_graph::ExportGraphParams;
+pub use tools::world_export_graph::WorldExportGraph;
+pub use tools::world_export_graph::fetch_scene;
+pub use tools::world_field_histogram::FieldHistogramParams;
+pub use tools::world_field_histogram::WorldFieldHistogram;
 pub use tools::world_get_components::GetComponentsParams;
 pub use tools::world_get_components::GetComponentsResult;
 pub use tools::world_get_resources::GetResourcesParams;
@@ -49,6 +173,8 @@ pub use tools::world_insert_components::InsertComponentsParams;
 pub use tools::world_insert_components::InsertComponentsResult;
 pub use tools::world_insert_resources::InsertResourcesParams;
 pub use tools::world_insert_resources::InsertResourcesResult;
+pub use tools::world_join_query::JoinQueryParams;
+pub use tools::world_join_query::WorldJoinQuery;
 pub use tools::world_list_components::ListComponentsParams;
 pub use tools::world_list_components::ListComponentsResult;
 pub use tools::world_list_resources::ListResourcesParams;
@@ -57,6 +183,9 @@ pub use tools::world_mutate_components::MutateComponentsParams;
 pub use tools::world_mutate_components::MutateComponentsResult;
 pub use tools::world_mutate_resources::MutateResourcesParams;
 pub use tools::world_mutate_resources::MutateResourcesResult;
+pub use tools::world_query::BrpQuery;
+pub use tools::world_query::BrpQueryFilter;
+pub use tools::world_query::ComponentSelector;
 pub use tools::world_query::QueryParams;
 pub use tools::world_query::QueryResult;
 pub use tools::world_remove_components::RemoveComponentsParams;
@@ -67,11 +196,24 @@ pub use tools::world_reparent_entities::ReparentEntitiesParams;
 pub use tools::world_reparent_entities::ReparentEntitiesResult;
 pub use tools::world_spawn_entity::SpawnEntityParams;
 pub use tools::world_spawn_entity::SpawnEntityResult;
+pub use tools::world_undo_despawn::UndoDespawnParams;
+pub use tools::world_undo_despawn::WorldUndoDespawn;
+pub use watch_tools::Aggregation;
 pub use watch_tools::GetComponentsWatchParams;
+pub use watch_tools::StopCondition;
+pub use watch_tools::WatchInfo;
+pub use watch_tools::WatchLabels;
+pub use watch_tools::WatchOptions;
 pub use watch_tools::WorldGetComponentsWatch;
+pub use watch_tools::active_watches;
+pub use watch_tools::stop_watch;
+pub use watch_tools::watch_component_list;
+pub use watch_tools::watch_entity;
+pub use watch_tools::watch_method;
 //
 // Export watch tools
 pub use watch_tools::{
-    BevyListWatch, BrpListActiveWatches, BrpStopWatch, ListComponentsWatchParams, StopWatchParams,
-    WatchManager,
+    BevyListWatch, BrpExecuteWatch, BrpGetWatchBuffer, BrpListActiveWatches, BrpStopWatch,
+    BufferedWatchLogger, ExecuteWatchParams, GetWatchBufferParams, ListComponentsWatchParams,
+    StopWatchParams, WatchManager, start_background_watch_task,
 };
@@ -2,21 +2,107 @@
 //!
 //! Provides a type-safe wrapper around port numbers with built-in validation
 //! and default values for BRP connections.
+//!
+//! The default is a session-wide setting rather than a constant: it starts as
+//! `BRP_MCP_DEFAULT_PORT` (or 15702) and can be changed with `brp_set_default_port`, so
+//! tool calls that omit `port` go to whichever app the session is working with. The session
+//! default is filled into a call's arguments before they are deserialized, so `Port::default()`
+//! stays 15702 and parsing parameters never reads session state.
 
+use std::borrow::Cow;
 use std::ops::Deref;
+use std::sync::LazyLock;
+use std::sync::atomic::AtomicU16;
+use std::sync::atomic::Ordering;
 
 use schemars::JsonSchema;
+use schemars::Schema;
+use schemars::SchemaGenerator;
+use schemars::json_schema;
 use serde::Deserialize;
 use serde::Deserializer;
 use serde::Serialize;
+use serde_json::Map;
+use serde_json::Value;
+use serde_json::json;
 
 use crate::brp_tools::constants::DEFAULT_BRP_EXTRAS_PORT;
+use crate::brp_tools::constants::DEFAULT_PORT_ENV_VAR;
+use crate::brp_tools::constants::MAX_VALID_PORT;
+use crate::brp_tools::constants::MIN_VALID_PORT;
 use crate::brp_tools::constants::VALID_PORT_RANGE;
+use crate::tool::ParameterName;
 
-/// Port number for BRP - defaults to 15702
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, JsonSchema, Serialize)]
+/// Port number for BRP - 15702 by default, though tool calls that omit `port` get the session
+/// default port
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
 pub struct Port(pub u16);
 
+/// Port used when a tool call omits `port`
+static SESSION_DEFAULT_PORT: LazyLock<AtomicU16> =
+    LazyLock::new(|| AtomicU16::new(configured_default_port().0));
+
+/// The startup default port - `BRP_MCP_DEFAULT_PORT` if it holds a valid port, otherwise 15702
+pub fn configured_default_port() -> Port {
+    std::env::var(DEFAULT_PORT_ENV_VAR)
+        .ok()
+        .and_then(|value| value.trim().parse::<u16>().ok())
+        .filter(|port| VALID_PORT_RANGE.contains(port))
+        .map_or(Port(DEFAULT_BRP_EXTRAS_PORT), Port)
+}
+
+impl Port {
+    /// The session default port used when a tool call omits `port`
+    pub fn session_default() -> Self { Self(SESSION_DEFAULT_PORT.load(Ordering::Relaxed)) }
+
+    /// Change the session default port, returning the previous default
+    pub fn set_session_default(port: Self) -> Self {
+        Self(SESSION_DEFAULT_PORT.swap(port.0, Ordering::Relaxed))
+    }
+
+    /// Give a tool call's arguments the session default port when they omit `port`
+    ///
+    /// Only tools whose `port` is a plain port in `input_schema` get one - an omitted optional
+    /// port means every port.
+    #[must_use]
+    pub fn with_session_default(arguments: Value, input_schema: &Map<String, Value>) -> Value {
+        Self::with_default(arguments, input_schema, Self::session_default())
+    }
+
+    /// `with_session_default` with the default passed in
+    fn with_default(arguments: Value, input_schema: &Map<String, Value>, default: Self) -> Value {
+        let Value::Object(mut args) = arguments else {
+            return arguments;
+        };
+        let takes_port = input_schema
+            .get("properties")
+            .and_then(|properties| properties.get(ParameterName::Port.as_ref()))
+            .and_then(|port| port.get("type"))
+            .is_some_and(|port_type| port_type == "integer");
+        let omitted = args
+            .get(ParameterName::Port.as_ref())
+            .is_none_or(Value::is_null);
+        if takes_port && omitted {
+            args.insert(ParameterName::Port.to_string(), json!(default));
+        }
+        Value::Object(args)
+    }
+}
+
+/// Manual impl so the schema carries the same range that `deserialize_port` enforces
+impl JsonSchema for Port {
+    fn schema_name() -> Cow<'static, str> { "Port".into() }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "description": "Port number for BRP - defaults to the session default port (15702 unless changed with brp_set_default_port)",
+            "type": "integer",
+            "minimum": MIN_VALID_PORT,
+            "maximum": MAX_VALID_PORT,
+        })
+    }
+}
+
 impl<'de> Deserialize<'de> for Port {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -101,3 +187,53 @@ where
         )))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schema(port: Value) -> Map<String, Value> {
+        let Value::Object(schema) = json!({ "properties": { "port": port } }) else {
+            unreachable!("literal is an object");
+        };
+        schema
+    }
+
+    #[test]
+    fn default_is_fixed_whatever_the_session_uses() {
+        assert_eq!(Port::default(), Port(DEFAULT_BRP_EXTRAS_PORT));
+        let parsed: Port = serde_json::from_value(json!("16000"))
+            .unwrap_or_else(|error| unreachable!("valid port: {error}"));
+        assert_eq!(parsed, Port(16_000));
+        assert!(serde_json::from_value::<Port>(json!(80)).is_err());
+    }
+
+    #[test]
+    fn fills_the_default_into_calls_that_omit_port() {
+        let plain = schema(json!({ "type": "integer" }));
+        let filled = Port::with_default(json!({ "entity": 1 }), &plain, Port(16_000));
+        assert_eq!(filled, json!({ "entity": 1, "port": 16_000 }));
+        let filled = Port::with_default(json!({ "port": null }), &plain, Port(16_000));
+        assert_eq!(filled, json!({ "port": 16_000 }));
+
+        let explicit = json!({ "port": 15_800 });
+        assert_eq!(
+            Port::with_default(explicit.clone(), &plain, Port(16_000)),
+            explicit
+        );
+    }
+
+    #[test]
+    fn leaves_optional_ports_and_portless_tools_alone() {
+        let args = json!({ "entity": 1 });
+        let optional = schema(json!({ "type": "object" }));
+        assert_eq!(
+            Port::with_default(args.clone(), &optional, Port(16_000)),
+            args
+        );
+        assert_eq!(
+            Port::with_default(args.clone(), &Map::new(), Port(16_000)),
+            args
+        );
+    }
+}
@@ -0,0 +1,81 @@
+//! `brp_alias_entity` tool - Name entities for the rest of the session
+//!
+//! Entity IDs change from run to run and are easy to mix up. Binding "player" or "boss" once lets
+//! every later call use the name wherever it takes an `entity`, `entities` or `parent`.
+
+use std::collections::BTreeMap;
+
+use bevy_brp_mcp_macros::ParamStruct;
+use bevy_brp_mcp_macros::ResultStruct;
+use bevy_brp_mcp_macros::ToolFn;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::brp_tools::Port;
+use crate::brp_tools::entity_alias;
+use crate::error::Error;
+use crate::error::Result;
+use crate::tool::HandlerContext;
+use crate::tool::HandlerResult;
+use crate::tool::ToolFn;
+use crate::tool::ToolResult;
+
+/// Parameters for the `brp_alias_entity` tool
+#[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
+pub struct AliasEntityParams {
+    /// Name to bind or remove, e.g. "player" - omit to only list this port's aliases
+    #[param(regex = r"^[A-Za-z_][A-Za-z0-9_.:-]*$")]
+    pub alias: Option<String>,
+
+    /// Entity to bind `alias` to - omit to remove `alias`
+    pub entity: Option<u64>,
+
+    /// The BRP port
+    #[serde(default)]
+    pub port: Port,
+}
+
+/// Result for the `brp_alias_entity` tool
+#[derive(Debug, Clone, Serialize, Deserialize, ResultStruct)]
+pub struct AliasEntityResult {
+    /// Every alias bound on the port after this call
+    #[to_result]
+    aliases:          BTreeMap<String, u64>,
+    /// Entity the alias was bound to before this call
+    #[to_metadata(skip_if_none)]
+    previous_entity:  Option<u64>,
+    /// Message template for formatting responses
+    #[to_message]
+    message_template: Option<String>,
+}
+
+#[derive(ToolFn)]
+#[tool_fn(params = "AliasEntityParams", output = "AliasEntityResult")]
+pub struct BrpAliasEntity;
+
+#[allow(clippy::unused_async)]
+async fn handle_impl(params: AliasEntityParams) -> Result<AliasEntityResult> {
+    let port = params.port;
+    let (previous_entity, message) = match (params.alias, params.entity) {
+        (Some(alias), Some(entity)) => {
+            let previous = entity_alias::bind(port, &alias, entity)?;
+            (previous, format!("Bound '{alias}' to entity {entity}"))
+        },
+        (Some(alias), None) => {
+            let previous = entity_alias::unbind(port, &alias).ok_or_else(|| {
+                Error::invalid("alias", format!("'{alias}' is not bound on port {port}"))
+            })?;
+            (Some(previous), format!("Removed alias '{alias}'"))
+        },
+        (None, Some(_)) => {
+            return Err(Error::missing("alias to bind the entity to").into());
+        },
+        (None, None) => (None, format!("Entity aliases on port {port}")),
+    };
+
+    Ok(
+        AliasEntityResult::new(entity_alias::aliases_for(port), previous_entity)
+            .with_message_template(message),
+    )
+}
@@ -0,0 +1,479 @@
+//! `brp_assert` tool - Check declarative assertions against the live world
+//!
+//! Each assertion is evaluated independently with fresh (uncached) BRP reads and reported as
+//! passed or failed together with the actual value, so one call can verify the state an
+//! integration test expects. An assertion that cannot be evaluated - an unknown component, a path
+//! that doesn't exist - fails with the reason instead of failing the whole call.
+
+use bevy_brp_mcp_macros::ParamStruct;
+use bevy_brp_mcp_macros::ResultStruct;
+use bevy_brp_mcp_macros::ToolFn;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+use serde_json::json;
+
+use super::world_query::BrpQuery;
+use super::world_query::BrpQueryFilter;
+use crate::brp_tools::BrpClient;
+use crate::brp_tools::Port;
+use crate::brp_tools::ResponseStatus;
+use crate::error::Error;
+use crate::error::Result;
+use crate::tool::BrpMethod;
+use crate::tool::HandlerContext;
+use crate::tool::HandlerResult;
+use crate::tool::ParameterName;
+use crate::tool::ToolFn;
+use crate::tool::ToolResult;
+
+/// Field of a `world.get_components` response holding the component values
+const COMPONENTS_FIELD: &str = "components";
+
+/// Field of a `world.get_components` response holding per-component errors
+const ERRORS_FIELD: &str = "errors";
+
+const fn default_true() -> bool { true }
+
+/// A declarative check on the world, selected by `kind`
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Assertion {
+    /// The entity exists - or, with `exists: false`, that it doesn't
+    EntityExists {
+        /// Entity ID
+        entity: u64,
+        /// Whether the entity is expected to exist (default: true)
+        #[serde(default = "default_true")]
+        exists: bool,
+    },
+    /// A component, or a field of it, equals `expected` (numbers compare by value, so `1` equals
+    /// `1.0`)
+    ComponentEquals {
+        /// Entity ID
+        entity:    u64,
+        /// Fully-qualified component type
+        component: String,
+        /// Reflection path into the component (e.g. `.translation.x`); empty for the whole value
+        #[serde(default)]
+        path:      String,
+        /// Expected value
+        expected:  Value,
+    },
+    /// A numeric component field lies within `min`..=`max` (either bound may be omitted)
+    ComponentInRange {
+        /// Entity ID
+        entity:    u64,
+        /// Fully-qualified component type
+        component: String,
+        /// Reflection path to a numeric field (e.g. `.translation.y`)
+        #[serde(default)]
+        path:      String,
+        /// Inclusive lower bound
+        #[serde(skip_serializing_if = "Option::is_none")]
+        min:       Option<f64>,
+        /// Inclusive upper bound
+        #[serde(skip_serializing_if = "Option::is_none")]
+        max:       Option<f64>,
+    },
+    /// The number of entities matching `filter` is `equals`, or within `min`..=`max`
+    Count {
+        /// Which entities to count. Structure: {with: string[], without: string[]}
+        #[serde(default)]
+        filter: BrpQueryFilter,
+        /// Exact expected count
+        #[serde(skip_serializing_if = "Option::is_none")]
+        equals: Option<usize>,
+        /// Inclusive lower bound
+        #[serde(skip_serializing_if = "Option::is_none")]
+        min:    Option<usize>,
+        /// Inclusive upper bound
+        #[serde(skip_serializing_if = "Option::is_none")]
+        max:    Option<usize>,
+    },
+}
+
+impl Assertion {
+    const fn kind(&self) -> &'static str {
+        match self {
+            Self::EntityExists { .. } => "entity_exists",
+            Self::ComponentEquals { .. } => "component_equals",
+            Self::ComponentInRange { .. } => "component_in_range",
+            Self::Count { .. } => "count",
+        }
+    }
+}
+
+/// Parameters for the `brp_assert` tool
+#[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
+pub struct AssertParams {
+    /// Assertions to evaluate, each selected by `kind`: `entity_exists`, `component_equals`,
+    /// `component_in_range` or `count`
+    pub assertions: Vec<Assertion>,
+
+    /// The BRP port
+    #[serde(default)]
+    pub port: Port,
+}
+
+/// Outcome of one assertion
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct AssertionOutcome {
+    /// Position of the assertion in `assertions`
+    pub index:  usize,
+    /// The assertion's `kind`
+    pub kind:   String,
+    /// Whether the assertion holds
+    pub passed: bool,
+    /// The value found in the world - absent when it could not be read
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub actual: Option<Value>,
+    /// Why the assertion failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+impl AssertionOutcome {
+    fn new(index: usize, assertion: &Assertion, actual: Value, passed: bool) -> Self {
+        Self {
+            index,
+            kind: assertion.kind().to_string(),
+            passed,
+            actual: Some(actual),
+            reason: None,
+        }
+    }
+
+    fn failed(index: usize, assertion: &Assertion, reason: impl Into<String>) -> Self {
+        Self {
+            index,
+            kind: assertion.kind().to_string(),
+            passed: false,
+            actual: None,
+            reason: Some(reason.into()),
+        }
+    }
+
+    fn with_reason(mut self, reason: impl Into<String>) -> Self {
+        if !self.passed {
+            self.reason = Some(reason.into());
+        }
+        self
+    }
+}
+
+/// Result for the `brp_assert` tool
+#[derive(Debug, Clone, Serialize, Deserialize, ResultStruct)]
+pub struct AssertResult {
+    /// Outcome of each assertion, in the order given
+    #[to_result]
+    outcomes:         Vec<AssertionOutcome>,
+    /// Number of assertions evaluated
+    #[to_metadata]
+    assertion_count:  usize,
+    /// Number of assertions that passed
+    #[to_metadata]
+    passed:           usize,
+    /// Number of assertions that failed
+    #[to_metadata]
+    failed:           usize,
+    /// Whether every assertion passed
+    #[to_metadata]
+    all_passed:       bool,
+    /// Message template for formatting responses
+    #[to_message(message_template = "{passed} of {assertion_count} assertions passed")]
+    message_template: String,
+}
+
+#[derive(ToolFn)]
+#[tool_fn(params = "AssertParams", output = "AssertResult")]
+pub struct BrpAssert;
+
+async fn handle_impl(params: AssertParams) -> Result<AssertResult> {
+    if params.assertions.is_empty() {
+        return Err(Error::invalid("assertions", "provide at least one assertion").into());
+    }
+
+    let mut outcomes = Vec::with_capacity(params.assertions.len());
+    for (index, assertion) in params.assertions.iter().enumerate() {
+        outcomes.push(evaluate(index, assertion, params.port).await?);
+    }
+
+    let assertion_count = outcomes.len();
+    let passed = outcomes.iter().filter(|outcome| outcome.passed).count();
+    let failed = assertion_count - passed;
+
+    Ok(AssertResult::new(
+        outcomes,
+        assertion_count,
+        passed,
+        failed,
+        failed == 0,
+    ))
+}
+
+/// Evaluate one assertion - connection failures, and app errors that leave an entity's existence
+/// unknown, are errors; everything else is a failed outcome
+pub async fn evaluate(index: usize, assertion: &Assertion, port: Port) -> Result<AssertionOutcome> {
+    match assertion {
+        Assertion::EntityExists { entity, exists } => {
+            let found = entity_exists(*entity, port).await?;
+            Ok(
+                AssertionOutcome::new(index, assertion, json!(found), found == *exists)
+                    .with_reason(if *exists {
+                        format!("entity {entity} does not exist")
+                    } else {
+                        format!("entity {entity} exists")
+                    }),
+            )
+        },
+        Assertion::ComponentEquals {
+            entity,
+            component,
+            path,
+            expected,
+        } => Ok(
+            match component_field(*entity, component, path, port).await? {
+                Ok(actual) => {
+                    let passed = values_equal(&actual, expected);
+                    AssertionOutcome::new(index, assertion, actual, passed)
+                        .with_reason(format!("expected {expected}"))
+                },
+                Err(reason) => AssertionOutcome::failed(index, assertion, reason),
+            },
+        ),
+        Assertion::ComponentInRange {
+            entity,
+            component,
+            path,
+            min,
+            max,
+        } => Ok(
+            match component_field(*entity, component, path, port).await? {
+                Ok(actual) => match actual.as_f64() {
+                    Some(number) => {
+                        let passed = in_range(number, *min, *max);
+                        let expected = describe_range(*min, *max);
+                        AssertionOutcome::new(index, assertion, actual, passed)
+                            .with_reason(format!("expected a value in {expected}"))
+                    },
+                    None => AssertionOutcome::new(index, assertion, actual, false)
+                        .with_reason("value is not a number"),
+                },
+                Err(reason) => AssertionOutcome::failed(index, assertion, reason),
+            },
+        ),
+        Assertion::Count {
+            filter,
+            equals,
+            min,
+            max,
+        } => Ok(match count_matching(filter, port).await? {
+            Ok(count) => {
+                let passed = equals.is_none_or(|equals| count == equals)
+                    && min.is_none_or(|min| count >= min)
+                    && max.is_none_or(|max| count <= max);
+                let expected = equals.map_or_else(
+                    || describe_range(min.map(to_f64), max.map(to_f64)),
+                    |equals| equals.to_string(),
+                );
+                AssertionOutcome::new(index, assertion, json!(count), passed)
+                    .with_reason(format!("expected a count of {expected}"))
+            },
+            Err(reason) => AssertionOutcome::failed(index, assertion, reason),
+        }),
+    }
+}
+
+/// Whether `entity` exists, judged by whether its components can be listed
+pub async fn entity_exists(entity: u64, port: Port) -> Result<bool> {
+    let client = BrpClient::new(
+        BrpMethod::WorldListComponents,
+        port,
+        Some(json!({ ParameterName::Entity: entity })),
+    );
+    existence(&client.execute_raw().await?)
+}
+
+/// Whether a `world.list_components` response shows its entity exists - only the app's "entity
+/// not found" error means it doesn't, any other error can't tell
+fn existence(status: &ResponseStatus) -> Result<bool> {
+    match status {
+        ResponseStatus::Success(_) => Ok(true),
+        ResponseStatus::Error(err) if err.is_entity_not_found() => Ok(false),
+        ResponseStatus::Error(err) => Err(Error::tool_call_failed(format!(
+            "Could not check whether the entity exists: {}",
+            err.get_message()
+        ))
+        .into()),
+    }
+}
+
+/// The value at `path` of `entity`'s `component`, or why it couldn't be read
+pub async fn component_field(
+    entity: u64,
+    component: &str,
+    path: &str,
+    port: Port,
+) -> Result<std::result::Result<Value, String>> {
+    let client = BrpClient::new(
+        BrpMethod::WorldGetComponents,
+        port,
+        Some(json!({
+            ParameterName::Entity: entity,
+            ParameterName::Components: [component],
+            ParameterName::Strict: false,
+        })),
+    );
+    let response = match client.execute_raw().await? {
+        ResponseStatus::Success(Some(response)) => response,
+        ResponseStatus::Success(None) => return Ok(Err("empty response".to_string())),
+        ResponseStatus::Error(err) => return Ok(Err(err.get_message().to_string())),
+    };
+
+    if let Some(error) = response
+        .get(ERRORS_FIELD)
+        .and_then(|errors| errors.get(component))
+    {
+        return Ok(Err(error
+            .get("message")
+            .and_then(Value::as_str)
+            .map_or_else(|| error.to_string(), String::from)));
+    }
+    let Some(value) = response
+        .get(COMPONENTS_FIELD)
+        .and_then(|components| components.get(component))
+    else {
+        return Ok(Err(format!("entity {entity} has no {component}")));
+    };
+
+    Ok(value_at_path(value, path)
+        .cloned()
+        .ok_or_else(|| format!("path `{path}` not found in {component}")))
+}
+
+/// Number of entities matching `filter`, or why the query failed
+pub async fn count_matching(
+    filter: &BrpQueryFilter,
+    port: Port,
+) -> Result<std::result::Result<usize, String>> {
+    let client = BrpClient::new(
+        BrpMethod::WorldQuery,
+        port,
+        Some(json!({
+            ParameterName::Data: BrpQuery::default(),
+            ParameterName::Filter: filter,
+        })),
+    );
+    Ok(match client.execute_raw().await? {
+        ResponseStatus::Success(Some(Value::Array(rows))) => Ok(rows.len()),
+        ResponseStatus::Success(_) => Ok(0),
+        ResponseStatus::Error(err) => Err(err.get_message().to_string()),
+    })
+}
+
+/// The value at a reflection path such as `.translation.x`, `.0` or `.points[2]`
+pub fn value_at_path<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    let mut current = value;
+    for segment in path.split('.').filter(|segment| !segment.is_empty()) {
+        let (field, indices) = segment.split_once('[').unwrap_or((segment, ""));
+        if !field.is_empty() {
+            current = match current {
+                Value::Object(map) => map.get(field)?,
+                // Tuple structs reflect as arrays, addressed as `.0`, `.1`, ...
+                Value::Array(items) => items.get(field.parse::<usize>().ok()?)?,
+                _ => return None,
+            };
+        }
+        for index in indices.split('[').filter(|index| !index.is_empty()) {
+            let index = index.strip_suffix(']')?.parse::<usize>().ok()?;
+            current = current.as_array()?.get(index)?;
+        }
+    }
+    Some(current)
+}
+
+/// JSON equality that compares numbers by value, so `1` equals `1.0`
+pub fn values_equal(actual: &Value, expected: &Value) -> bool {
+    match (actual, expected) {
+        (Value::Number(a), Value::Number(b)) => a.as_f64() == b.as_f64(),
+        (Value::Array(a), Value::Array(b)) => {
+            a.len() == b.len() && a.iter().zip(b).all(|(a, b)| values_equal(a, b))
+        },
+        (Value::Object(a), Value::Object(b)) => {
+            a.len() == b.len()
+                && a.iter()
+                    .all(|(key, a)| b.get(key).is_some_and(|b| values_equal(a, b)))
+        },
+        _ => actual == expected,
+    }
+}
+
+/// Whether `number` lies within the inclusive bounds
+pub fn in_range(number: f64, min: Option<f64>, max: Option<f64>) -> bool {
+    min.is_none_or(|min| number >= min) && max.is_none_or(|max| number <= max)
+}
+
+/// `[min, max]` with missing bounds written as infinite
+fn describe_range(min: Option<f64>, max: Option<f64>) -> String {
+    let min = min.map_or_else(|| "-inf".to_string(), |min| min.to_string());
+    let max = max.map_or_else(|| "inf".to_string(), |max| max.to_string());
+    format!("[{min}, {max}]")
+}
+
+#[allow(clippy::cast_precision_loss)]
+const fn to_f64(count: usize) -> f64 { count as f64 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::brp_tools::brp_client::BRP_ERROR_CODE_ENTITY_NOT_FOUND;
+    use crate::brp_tools::brp_client::BrpClientError;
+
+    #[test]
+    fn resolves_reflection_paths() {
+        let value = json!({
+            "translation": [1.0, 2.0, 3.0],
+            "inner": { "points": [{ "x": 4 }, { "x": 5 }] },
+        });
+        assert_eq!(value_at_path(&value, ""), Some(&value));
+        assert_eq!(value_at_path(&value, ".translation.1"), Some(&json!(2.0)));
+        assert_eq!(value_at_path(&value, ".translation[2]"), Some(&json!(3.0)));
+        assert_eq!(value_at_path(&value, ".inner.points[1].x"), Some(&json!(5)));
+        assert_eq!(value_at_path(&value, ".missing"), None);
+    }
+
+    #[test]
+    fn only_entity_not_found_means_an_entity_is_gone() {
+        let error = |code| {
+            ResponseStatus::Error(BrpClientError {
+                code,
+                message: "Entity 3v0 not found".to_string(),
+                data: None,
+            })
+        };
+        assert!(matches!(
+            existence(&ResponseStatus::Success(None)),
+            Ok(true)
+        ));
+        assert!(matches!(
+            existence(&error(BRP_ERROR_CODE_ENTITY_NOT_FOUND)),
+            Ok(false)
+        ));
+        // An internal error or a missing method says nothing about the entity
+        assert!(existence(&error(-32_603)).is_err());
+        assert!(existence(&error(-32_601)).is_err());
+    }
+
+    #[test]
+    fn compares_numbers_by_value() {
+        assert!(values_equal(&json!([1, 2.5]), &json!([1.0, 2.5])));
+        assert!(!values_equal(
+            &json!({ "a": 1 }),
+            &json!({ "a": 1, "b": 2 })
+        ));
+        assert!(in_range(2.0, Some(1.0), None));
+        assert!(!in_range(2.0, None, Some(1.5)));
+    }
+}
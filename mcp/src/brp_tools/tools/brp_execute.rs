@@ -1,6 +1,9 @@
 //! `brp_execute` allows for executing an arbitrary BRP method - generally this is used as a
 //! debugging tool for his MCP server but can also be used if (for example) a new brp method is
 //! added before it's been implemented in this server code.
+//!
+//! For debugging the protocol itself the request can be sent as a notification, with a custom
+//! request id, or with `raw` set to get the JSON-RPC response back exactly as the app sent it.
 use async_trait::async_trait;
 use bevy_brp_mcp_macros::ParamStruct;
 use bevy_brp_mcp_macros::ResultStruct;
@@ -11,6 +14,7 @@ use serde_json::Value;
 
 use crate::brp_tools::BrpClient;
 use crate::brp_tools::Port;
+use crate::brp_tools::RequestId;
 use crate::brp_tools::ResponseStatus;
 use crate::error::Error;
 use crate::tool::BrpMethod;
@@ -19,13 +23,23 @@ use crate::tool::ToolFn;
 #[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
 pub struct ExecuteParams {
     /// The BRP method to execute (e.g., `rpc.discover`, `world.get_components`, `world.query`)
-    pub method: BrpMethod,
+    pub method:       BrpMethod,
     /// Optional parameters for the method
     #[to_metadata(skip_if_none)]
-    pub params: Option<serde_json::Value>,
-    /// The BRP port (default: 15702)
+    pub params:       Option<serde_json::Value>,
+    /// Custom JSON-RPC request id (string or number) - cannot be combined with `notification`
+    #[to_metadata(skip_if_none)]
+    pub id:           Option<serde_json::Value>,
+    /// Send the request as a JSON-RPC notification (no id) and return without a result
+    #[serde(default)]
+    pub notification: bool,
+    /// Return the JSON-RPC response envelope exactly as received - BRP errors are returned as
+    /// data and no error enhancement or format correction is applied
     #[serde(default)]
-    pub port:   Port,
+    pub raw:          bool,
+    /// The BRP port
+    #[serde(default)]
+    pub port:         Port,
 }
 
 /// Result type for the dynamic BRP execute tool
@@ -50,11 +64,25 @@ impl ToolFn for BrpExecute {
     type Params = ExecuteParams;
 
     async fn handle_impl(&self, params: ExecuteParams) -> crate::error::Result<ExecuteResult> {
+        let request_id = request_id_of(&params)?;
+
         let client = BrpClient::new(
             params.method,         // Direct use of typed BRP method
             params.port,           // Use typed port parameter
             params.params.clone(), // User-provided params (already Option<Value>)
-        );
+        )
+        .with_request_id(request_id);
+
+        if params.notification {
+            client.send_notification().await?;
+            return Ok(ExecuteResult::new(None).with_message_template("Sent notification {method}"));
+        }
+
+        if params.raw {
+            let response = client.execute_untouched().await?;
+            return Ok(ExecuteResult::new(Some(response))
+                .with_message_template("Executed method {method} (raw response)"));
+        }
 
         let brp_result = client.execute_raw().await?;
 
@@ -65,3 +93,58 @@ impl ToolFn for BrpExecute {
         }
     }
 }
+
+/// The JSON-RPC id the request is sent with - a notification has none, so it can't take `id`
+fn request_id_of(params: &ExecuteParams) -> crate::error::Result<RequestId> {
+    match (params.notification, params.id.clone()) {
+        (true, Some(_)) => Err(Error::invalid(
+            "parameters",
+            "`id` cannot be combined with `notification` - notifications have no id",
+        )
+        .into()),
+        (true, None) => Ok(RequestId::Notification),
+        (false, Some(id)) => Ok(RequestId::Custom(id)),
+        (false, None) => Ok(RequestId::Default),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn params(notification: bool, id: Option<Value>) -> ExecuteParams {
+        ExecuteParams {
+            method: BrpMethod::WorldQuery,
+            params: None,
+            id,
+            notification,
+            raw: false,
+            port: Port::default(),
+        }
+    }
+
+    #[test]
+    fn notifications_cannot_take_an_id() {
+        assert!(request_id_of(&params(true, Some(json!(7)))).is_err());
+        assert_eq!(
+            request_id_of(&params(true, None)).ok(),
+            Some(RequestId::Notification)
+        );
+    }
+
+    #[test]
+    fn custom_ids_are_sent_as_given() {
+        for id in [json!("trace-1"), json!(42)] {
+            assert_eq!(
+                request_id_of(&params(false, Some(id.clone()))).ok(),
+                Some(RequestId::Custom(id))
+            );
+        }
+        assert_eq!(
+            request_id_of(&params(false, None)).ok(),
+            Some(RequestId::Default)
+        );
+    }
+}
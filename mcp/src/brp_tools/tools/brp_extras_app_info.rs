@@ -0,0 +1,32 @@
+//! `brp_extras/app_info` tool - Report plugins and build info
+
+use bevy_brp_mcp_macros::ParamStruct;
+use bevy_brp_mcp_macros::ResultStruct;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::brp_tools::Port;
+
+/// Parameters for the `brp_extras/app_info` tool
+#[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
+pub struct AppInfoParams {
+    /// The BRP port
+    #[serde(default)]
+    pub port: Port,
+}
+
+/// Result for the `brp_extras/app_info` tool
+#[derive(Serialize, ResultStruct)]
+#[brp_result]
+pub struct AppInfoResult {
+    /// The raw BRP response - executable, plugins, build info and port topology
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[to_result(skip_if_none)]
+    pub result: Option<Value>,
+
+    /// Message template for formatting responses
+    #[to_message(message_template = "Retrieved app info")]
+    pub message_template: String,
+}
@@ -0,0 +1,81 @@
+//! `brp_extras/apply_commands` tool - Apply a batch of ECS commands atomically
+//!
+//! With `preview: true` nothing is applied - the result reports what the batch's `despawn`
+//! commands would take with them.
+
+use bevy_brp_mcp_macros::ParamStruct;
+use bevy_brp_mcp_macros::ResultStruct;
+use bevy_brp_mcp_macros::ToolFn;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::brp_tools::BrpClient;
+use crate::brp_tools::Port;
+use crate::brp_tools::despawn_preview;
+use crate::error::Result;
+use crate::tool::BrpMethod;
+use crate::tool::HandlerContext;
+use crate::tool::HandlerResult;
+use crate::tool::ToolFn;
+use crate::tool::ToolResult;
+
+/// Parameters for the `brp_extras/apply_commands` tool
+#[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
+pub struct ApplyCommandsParams {
+    /// Commands to apply in order, each an object with a `command` field of `spawn`, `insert`,
+    /// `remove`, `despawn` or `reparent`. Entities are entity IDs or `"$N"` for the entity spawned
+    /// by command `N` of this batch
+    pub commands: Vec<Value>,
+
+    /// If true, apply nothing and report the descendants and relationships the batch's `despawn`
+    /// commands would affect (default: false)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preview: Option<bool>,
+
+    /// Relationship components (fully-qualified type paths) whose links `preview` reports
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub relationships: Option<Vec<String>>,
+
+    /// The BRP port
+    #[serde(default)]
+    pub port: Port,
+}
+
+/// Result for the `brp_extras/apply_commands` tool
+#[derive(Serialize, ResultStruct)]
+#[brp_result]
+pub struct ApplyCommandsResult {
+    /// The raw BRP response - the number of commands applied and the spawned entities, or what
+    /// the despawns would affect when `preview` was set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[to_result(skip_if_none)]
+    pub result: Option<Value>,
+
+    /// Message template for formatting responses
+    #[to_message(message_template = "Applied ECS commands in a single flush")]
+    pub message_template: String,
+}
+
+#[derive(ToolFn)]
+#[tool_fn(params = "ApplyCommandsParams", output = "ApplyCommandsResult")]
+pub struct BrpExtrasApplyCommands;
+
+async fn handle_impl(mut params: ApplyCommandsParams) -> Result<ApplyCommandsResult> {
+    let relationships = params.relationships.take().unwrap_or_default();
+    if params.preview.take() == Some(true) {
+        let despawned = despawn_preview::despawned_by_commands(&params.commands);
+        let preview = despawn_preview::preview(params.port, &despawned, &relationships).await?;
+        return Ok(
+            ApplyCommandsResult::new(Some(preview)).with_message_template(
+                "Previewed the despawns of the batch - no command was applied",
+            ),
+        );
+    }
+
+    let brp_params = BrpClient::prepare_params(&params)?;
+    BrpClient::new(BrpMethod::BrpExtrasApplyCommands, params.port, brp_params)
+        .execute::<ApplyCommandsResult>()
+        .await
+}
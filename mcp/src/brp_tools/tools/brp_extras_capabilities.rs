@@ -0,0 +1,32 @@
+//! `brp_extras/capabilities` tool - Report the plugin sections and methods an app serves
+
+use bevy_brp_mcp_macros::ParamStruct;
+use bevy_brp_mcp_macros::ResultStruct;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::brp_tools::Port;
+
+/// Parameters for the `brp_extras/capabilities` tool
+#[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
+pub struct CapabilitiesParams {
+    /// The BRP port
+    #[serde(default)]
+    pub port: Port,
+}
+
+/// Result for the `brp_extras/capabilities` tool
+#[derive(Serialize, ResultStruct)]
+#[brp_result]
+pub struct CapabilitiesResult {
+    /// The raw BRP response - each plugin section and every `brp_extras/` method served
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[to_result(skip_if_none)]
+    pub result: Option<Value>,
+
+    /// Message template for formatting responses
+    #[to_message(message_template = "Retrieved extras capabilities")]
+    pub message_template: String,
+}
@@ -0,0 +1,54 @@
+//! `brp_extras/clipboard` tool - Read and set the system clipboard
+
+use bevy_brp_mcp_macros::ParamStruct;
+use bevy_brp_mcp_macros::ResultStruct;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::brp_tools::Port;
+
+/// Parameters for the `brp_extras/clipboard` tool
+#[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
+pub struct ClipboardParams {
+    /// Replace the clipboard's content with this text - omit to only read the clipboard
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+
+    /// The BRP port
+    #[serde(default)]
+    pub port: Port,
+}
+
+/// Result for the `brp_extras/clipboard` tool
+#[derive(Serialize, ResultStruct)]
+#[brp_result]
+pub struct ClipboardResult {
+    /// The raw BRP response - the clipboard's `text` and whether it was `set`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[to_result(skip_if_none)]
+    pub result: Option<Value>,
+
+    /// Message template for formatting responses
+    #[to_message(message_template = "Accessed the system clipboard")]
+    pub message_template: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn reading_sends_no_text() {
+        let read: ClipboardParams = serde_json::from_value(json!({"port": 15702}))
+            .unwrap_or_else(|e| unreachable!("valid params: {e}"));
+        assert!(read.text.is_none());
+        assert_eq!(
+            serde_json::to_value(&read).ok(),
+            Some(json!({"port": 15702}))
+        );
+    }
+}
@@ -0,0 +1,113 @@
+//! `brp_extras/collect_frame_stats` tool - Frame-time histogram over a time window
+//!
+//! The BRP method only starts a collection or reports on it, since a request can't wait for
+//! frames to pass. This tool starts one, sleeps for the window, and polls until the app reports
+//! the statistics, so callers get the histogram from a single tool call.
+
+use std::time::Duration;
+
+use bevy_brp_mcp_macros::ParamStruct;
+use bevy_brp_mcp_macros::ResultStruct;
+use bevy_brp_mcp_macros::ToolFn;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+use serde_json::json;
+
+use crate::brp_tools::BrpClient;
+use crate::brp_tools::Port;
+use crate::brp_tools::ResponseStatus;
+use crate::error::Error;
+use crate::error::Result;
+use crate::tool::BrpMethod;
+use crate::tool::HandlerContext;
+use crate::tool::HandlerResult;
+use crate::tool::ToolFn;
+use crate::tool::ToolResult;
+
+/// Default collection window in milliseconds
+const DEFAULT_DURATION_MS: u32 = 5_000;
+
+/// Interval between polls once the window should have passed
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// How long past the window to keep polling before giving up - frames only advance the
+/// collection while the app is running its `Update` schedule
+const POLL_GRACE: Duration = Duration::from_secs(10);
+
+/// Parameters for the `brp_extras/collect_frame_stats` tool
+#[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
+pub struct CollectFrameStatsParams {
+    /// Milliseconds to record frame times for (default: 5000ms, max: 300000ms)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[param(min = 1, max = 300_000)]
+    #[schemars(extend("default" = 5000))]
+    pub duration_ms: Option<u32>,
+
+    /// The BRP port
+    #[serde(default)]
+    pub port: Port,
+}
+
+/// Result for the `brp_extras/collect_frame_stats` tool
+#[derive(Serialize, ResultStruct)]
+pub struct CollectFrameStatsResult {
+    /// The frame statistics - percentiles, histogram and worst frames
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[to_result(skip_if_none)]
+    pub result: Option<Value>,
+
+    /// Count of frames recorded
+    #[to_metadata]
+    pub frame_count: u64,
+
+    /// Message template for formatting responses
+    #[to_message(message_template = "Collected frame stats over {frame_count} frames")]
+    pub message_template: String,
+}
+
+#[derive(ToolFn)]
+#[tool_fn(params = "CollectFrameStatsParams", output = "CollectFrameStatsResult")]
+pub struct BrpExtrasCollectFrameStats;
+
+async fn handle_impl(params: CollectFrameStatsParams) -> Result<CollectFrameStatsResult> {
+    let duration_ms = params.duration_ms.unwrap_or(DEFAULT_DURATION_MS);
+    let started = request(params.port, Some(json!({ "duration_ms": duration_ms }))).await?;
+
+    let timeout = Duration::from_millis(u64::from(duration_ms)) + POLL_GRACE;
+    let deadline = tokio::time::Instant::now() + timeout;
+    let mut remaining_ms = started.get("remaining_ms").and_then(Value::as_u64);
+    loop {
+        let wait = remaining_ms.map_or(POLL_INTERVAL, |ms| {
+            Duration::from_millis(ms).max(POLL_INTERVAL)
+        });
+        if tokio::time::Instant::now() + wait > deadline {
+            return Err(Error::tool_call_failed(format!(
+                "Frame stats collection did not finish within {}ms - is the app running frames?",
+                timeout.as_millis()
+            ))
+            .into());
+        }
+        tokio::time::sleep(wait).await;
+
+        let status = request(params.port, None).await?;
+        if status.get("status").and_then(Value::as_str) == Some("complete") {
+            let frame_count = status
+                .get("frame_count")
+                .and_then(Value::as_u64)
+                .unwrap_or(0);
+            return Ok(CollectFrameStatsResult::new(Some(status), frame_count));
+        }
+        remaining_ms = status.get("remaining_ms").and_then(Value::as_u64);
+    }
+}
+
+/// Send a `collect_frame_stats` request - `Some` params start a collection, `None` polls it
+async fn request(port: Port, params: Option<Value>) -> Result<Value> {
+    let client = BrpClient::new(BrpMethod::BrpExtrasCollectFrameStats, port, params);
+    match client.execute_raw().await? {
+        ResponseStatus::Success(data) => Ok(data.unwrap_or(Value::Null)),
+        ResponseStatus::Error(err) => Err(Error::tool_call_failed(err.get_message()).into()),
+    }
+}
@@ -0,0 +1,51 @@
+//! `brp_extras/determinism` tool - Set the RNG seed and fixed frame delta for reproducible runs
+
+use bevy_brp_mcp_macros::ParamStruct;
+use bevy_brp_mcp_macros::ResultStruct;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::brp_tools::Port;
+
+/// Parameters for the `brp_extras/determinism` tool - omitted settings are left unchanged
+#[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
+pub struct DeterminismParams {
+    /// Seed to store in the app's `BrpRngSeed` resource - the app must insert it and seed its RNG
+    /// from it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+
+    /// Seconds every frame advances time by, whatever its real duration - e.g. the fixed timestep
+    /// to run `FixedUpdate` exactly once per frame
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fixed_delta_seconds: Option<f64>,
+
+    /// If true, return to advancing time by the real frame duration
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub real_time: Option<bool>,
+
+    /// Rate virtual time advances at relative to real time, e.g. `2.0` for double speed or `0.0`
+    /// to freeze it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub relative_speed: Option<f64>,
+
+    /// The BRP port
+    #[serde(default)]
+    pub port: Port,
+}
+
+/// Result for the `brp_extras/determinism` tool
+#[derive(Serialize, ResultStruct)]
+#[brp_result]
+pub struct DeterminismResult {
+    /// The raw BRP response - the determinism-relevant settings and the settings changed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[to_result(skip_if_none)]
+    pub result: Option<Value>,
+
+    /// Message template for formatting responses
+    #[to_message(message_template = "Retrieved determinism settings")]
+    pub message_template: String,
+}
@@ -0,0 +1,35 @@
+//! `brp_extras/ensure_registered` tool - Report missing type registrations
+
+use bevy_brp_mcp_macros::ParamStruct;
+use bevy_brp_mcp_macros::ResultStruct;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::brp_tools::Port;
+
+/// Parameters for the `brp_extras/ensure_registered` tool
+#[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
+pub struct EnsureRegisteredParams {
+    /// Full type paths to check, e.g. `["my_game::Health"]`
+    pub types: Vec<String>,
+
+    /// The BRP port
+    #[serde(default)]
+    pub port: Port,
+}
+
+/// Result for the `brp_extras/ensure_registered` tool
+#[derive(Serialize, ResultStruct)]
+#[brp_result]
+pub struct EnsureRegisteredResult {
+    /// The raw BRP response - registration status, problems and fixes per type
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[to_result(skip_if_none)]
+    pub result: Option<Value>,
+
+    /// Message template for formatting responses
+    #[to_message(message_template = "Checked type registrations")]
+    pub message_template: String,
+}
@@ -0,0 +1,40 @@
+//! `brp_extras/find_tagged` tool - Find entities by `BrpTag`
+
+use bevy_brp_mcp_macros::ParamStruct;
+use bevy_brp_mcp_macros::ResultStruct;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::brp_tools::Port;
+
+/// Parameters for the `brp_extras/find_tagged` tool
+#[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
+pub struct FindTaggedParams {
+    /// Only return entities with this tag - omit to return every tagged entity
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+
+    /// The BRP port
+    #[serde(default)]
+    pub port: Port,
+}
+
+/// Result for the `brp_extras/find_tagged` tool
+#[derive(Serialize, ResultStruct)]
+#[brp_result]
+pub struct FindTaggedResult {
+    /// The raw BRP response - an array of `{entity, tag}` objects sorted by tag
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[to_result(skip_if_none)]
+    pub result: Option<Value>,
+
+    /// Count of tagged entities found
+    #[to_metadata(result_operation = "count")]
+    pub entity_count: usize,
+
+    /// Message template for formatting responses
+    #[to_message(message_template = "Found {entity_count} tagged entities")]
+    pub message_template: String,
+}
@@ -0,0 +1,49 @@
+//! `brp_extras/get_history` tool - Read recorded component changes of tracked entities
+
+use bevy_brp_mcp_macros::ParamStruct;
+use bevy_brp_mcp_macros::ResultStruct;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::brp_tools::Port;
+
+/// Parameters for the `brp_extras/get_history` tool
+#[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
+pub struct GetHistoryParams {
+    /// Only return changes of this entity
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entity: Option<u64>,
+
+    /// Only return changes of this component, by full type path
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub component: Option<String>,
+
+    /// Only return changes from the last this many seconds, e.g. 5
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub since_seconds: Option<f64>,
+
+    /// Return at most this many changes, the most recent ones
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[param(min = 1)]
+    pub limit: Option<usize>,
+
+    /// The BRP port
+    #[serde(default)]
+    pub port: Port,
+}
+
+/// Result for the `brp_extras/get_history` tool
+#[derive(Serialize, ResultStruct)]
+#[brp_result]
+pub struct GetHistoryResult {
+    /// The raw BRP response - `events` oldest first, `now`, `dropped` and `tracked_entities`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[to_result(skip_if_none)]
+    pub result: Option<Value>,
+
+    /// Message template for formatting responses
+    #[to_message(message_template = "Retrieved component change history")]
+    pub message_template: String,
+}
@@ -0,0 +1,32 @@
+//! `brp_extras/get_interaction_state` tool - Report keyboard focus and hover state
+
+use bevy_brp_mcp_macros::ParamStruct;
+use bevy_brp_mcp_macros::ResultStruct;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::brp_tools::Port;
+
+/// Parameters for the `brp_extras/get_interaction_state` tool
+#[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
+pub struct GetInteractionStateParams {
+    /// The BRP port
+    #[serde(default)]
+    pub port: Port,
+}
+
+/// Result for the `brp_extras/get_interaction_state` tool
+#[derive(Serialize, ResultStruct)]
+#[brp_result]
+pub struct GetInteractionStateResult {
+    /// The raw BRP response - `focus`, `ui` interactions and `picking` hovers per pointer
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[to_result(skip_if_none)]
+    pub result: Option<Value>,
+
+    /// Message template for formatting responses
+    #[to_message(message_template = "Retrieved interaction state")]
+    pub message_template: String,
+}
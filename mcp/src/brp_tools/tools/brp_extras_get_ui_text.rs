@@ -0,0 +1,44 @@
+//! `brp_extras/get_ui_text` tool - Collect the text shown in the UI
+
+use bevy_brp_mcp_macros::ParamStruct;
+use bevy_brp_mcp_macros::ResultStruct;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::brp_tools::Port;
+
+/// Parameters for the `brp_extras/get_ui_text` tool
+#[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
+pub struct GetUiTextParams {
+    /// Only return text containing this substring, ignoring case
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contains: Option<String>,
+
+    /// Also return text nodes that aren't visible (default: false)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub include_hidden: bool,
+
+    /// The BRP port
+    #[serde(default)]
+    pub port: Port,
+}
+
+/// Result for the `brp_extras/get_ui_text` tool
+#[derive(Serialize, ResultStruct)]
+#[brp_result]
+pub struct GetUiTextResult {
+    /// The raw BRP response - an array of text nodes in reading order
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[to_result(skip_if_none)]
+    pub result: Option<Value>,
+
+    /// Count of text nodes returned
+    #[to_metadata(result_operation = "count")]
+    pub text_count: usize,
+
+    /// Message template for formatting responses
+    #[to_message(message_template = "Found {text_count} UI text nodes")]
+    pub message_template: String,
+}
@@ -0,0 +1,49 @@
+//! `brp_extras/gizmo_config` tool - Read or change gizmo configuration
+
+use bevy_brp_mcp_macros::ParamStruct;
+use bevy_brp_mcp_macros::ResultStruct;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::brp_tools::Port;
+
+/// Parameters for the `brp_extras/gizmo_config` tool - omitted settings are left unchanged
+#[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
+pub struct GizmoConfigParams {
+    /// Gizmo config group to read or change, by short type name (e.g. `DefaultGizmoConfigGroup`,
+    /// `AabbGizmoConfigGroup`) - omit to apply to every group
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+
+    /// Draw (`true`) or hide (`false`) the group's gizmos
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+
+    /// Line width in pixels, or in world units for perspective lines (must be positive)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line_width: Option<f32>,
+
+    /// Depth bias from -1.0 to 1.0 - -1.0 draws gizmos on top of everything
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub depth_bias: Option<f32>,
+
+    /// The BRP port
+    #[serde(default)]
+    pub port: Port,
+}
+
+/// Result for the `brp_extras/gizmo_config` tool
+#[derive(Serialize, ResultStruct)]
+#[brp_result]
+pub struct GizmoConfigResult {
+    /// The raw BRP response - the settings of each selected gizmo config group
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[to_result(skip_if_none)]
+    pub result: Option<Value>,
+
+    /// Message template for formatting responses
+    #[to_message(message_template = "Retrieved gizmo configuration")]
+    pub message_template: String,
+}
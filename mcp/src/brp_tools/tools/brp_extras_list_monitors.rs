@@ -0,0 +1,36 @@
+//! `brp_extras/list_monitors` tool - List connected displays
+
+use bevy_brp_mcp_macros::ParamStruct;
+use bevy_brp_mcp_macros::ResultStruct;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::brp_tools::Port;
+
+/// Parameters for the `brp_extras/list_monitors` tool
+#[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
+pub struct ListMonitorsParams {
+    /// The BRP port
+    #[serde(default)]
+    pub port: Port,
+}
+
+/// Result for the `brp_extras/list_monitors` tool
+#[derive(Serialize, ResultStruct)]
+#[brp_result]
+pub struct ListMonitorsResult {
+    /// The raw BRP response - an array of displays ordered by position
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[to_result(skip_if_none)]
+    pub result: Option<Value>,
+
+    /// Count of connected displays
+    #[to_metadata(result_operation = "count")]
+    pub monitor_count: usize,
+
+    /// Message template for formatting responses
+    #[to_message(message_template = "Found {monitor_count} monitors")]
+    pub message_template: String,
+}
@@ -0,0 +1,56 @@
+//! `brp_extras/material` tool - Read or change an entity's material
+
+use bevy_brp_mcp_macros::ParamStruct;
+use bevy_brp_mcp_macros::ResultStruct;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::brp_tools::Port;
+
+/// Parameters for the `brp_extras/material` tool - omitted settings are left unchanged
+#[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
+pub struct MaterialParams {
+    /// Entity with a `MeshMaterial3d<StandardMaterial>`
+    pub entity: u64,
+
+    /// New base color - `"#RRGGBB"`, `"rgb(r, g, b)"`, a CSS color name or a reflected `Color`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base_color: Option<Value>,
+
+    /// New emitted light - `"#RRGGBB"`, `"rgb(r, g, b)"`, a CSS color name or a reflected
+    /// `LinearRgba`, whose channels may exceed 1.0
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub emissive: Option<Value>,
+
+    /// New roughness, from 0.0 to 1.0
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub perceptual_roughness: Option<f32>,
+
+    /// New metallic, from 0.0 to 1.0
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metallic: Option<f32>,
+
+    /// Ignore lighting and draw the base color as is
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unlit: Option<bool>,
+
+    /// The BRP port
+    #[serde(default)]
+    pub port: Port,
+}
+
+/// Result for the `brp_extras/material` tool
+#[derive(Serialize, ResultStruct)]
+#[brp_result]
+pub struct MaterialResult {
+    /// The raw BRP response - the material's settings after any change
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[to_result(skip_if_none)]
+    pub result: Option<Value>,
+
+    /// Message template for formatting responses
+    #[to_message(message_template = "Retrieved material of entity {entity}")]
+    pub message_template: String,
+}
@@ -0,0 +1,43 @@
+//! `brp_extras/pin_entity` tool - Protect an entity from despawning while it is inspected
+
+use bevy_brp_mcp_macros::ParamStruct;
+use bevy_brp_mcp_macros::ResultStruct;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::brp_tools::Port;
+
+/// Parameters for the `brp_extras/pin_entity` tool
+#[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
+pub struct PinEntityParams {
+    /// The entity ID to pin
+    pub entity: u64,
+
+    /// Seconds to keep the entity pinned (default: 60, at most 3600)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration_seconds: Option<f64>,
+
+    /// If true, remove the entity's pin instead
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unpin: Option<bool>,
+
+    /// The BRP port
+    #[serde(default)]
+    pub port: Port,
+}
+
+/// Result for the `brp_extras/pin_entity` tool
+#[derive(Serialize, ResultStruct)]
+#[brp_result]
+pub struct PinEntityResult {
+    /// The raw BRP response - the entity, whether it is pinned and when the pin expires
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[to_result(skip_if_none)]
+    pub result: Option<Value>,
+
+    /// Message template for formatting responses
+    #[to_message(message_template = "Updated pin of entity {entity}")]
+    pub message_template: String,
+}
@@ -0,0 +1,408 @@
+//! `brp_extras/query_stream+watch` tool - Query results streamed in chunks
+//!
+//! `world.query` returns every row in one response, which stalls or runs out of memory over
+//! worlds with a hundred thousand entities. The streaming method sends `chunk_size` entities per
+//! frame instead; this tool reads the chunks until the one marked `done` and either assembles the
+//! rows - up to `limit` of them - or appends each row to `output_path` as it arrives, so a query
+//! of any size never sits in memory whole. Clients that send a `progressToken` receive a
+//! progress notification per chunk.
+
+use std::fs;
+use std::fs::File;
+use std::io::BufWriter;
+use std::io::Write as _;
+use std::path::Path;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+use bevy_brp_mcp_macros::ParamStruct;
+use bevy_brp_mcp_macros::ResultStruct;
+use bevy_brp_mcp_macros::ToolFn;
+use error_stack::ResultExt;
+use futures::StreamExt;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+use serde_json::json;
+
+use super::world_query::BrpQueryFilter;
+use crate::brp_tools::BrpClient;
+use crate::brp_tools::Port;
+use crate::error::Error;
+use crate::error::Result;
+use crate::tool::BrpMethod;
+use crate::tool::HandlerContext;
+use crate::tool::HandlerResult;
+use crate::tool::ToolFn;
+use crate::tool::ToolResult;
+
+/// Rows returned inline when `limit` is omitted
+const DEFAULT_LIMIT: usize = 10_000;
+
+/// How long to wait for the next chunk before giving up - chunks are only sent while the app is
+/// running frames
+const STALL_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Streams opened by this server, numbered so concurrent queries never share an ID
+static NEXT_STREAM: AtomicU64 = AtomicU64::new(0);
+
+/// Parameters for the `brp_extras/query_stream+watch` tool
+#[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
+pub struct QueryStreamParams {
+    /// Components to return, which matched entities must have (fully-qualified type paths) -
+    /// all reflected components when empty
+    #[serde(default)]
+    pub components: Vec<String>,
+
+    /// Which entities to match. Structure: {with: string[], without: string[]}
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter: Option<BrpQueryFilter>,
+
+    /// Entities the app sends per frame (default: 1000, max: 10000)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[param(min = 1, max = 10_000)]
+    #[schemars(extend("default" = 1000))]
+    pub chunk_size: Option<usize>,
+
+    /// Most rows to return inline - the stream is closed once reached (default: 10000, ignored
+    /// with `output_path`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[param(min = 1)]
+    #[schemars(extend("default" = 10_000))]
+    pub limit: Option<usize>,
+
+    /// Write every row to this file as JSON Lines, one entity per line, instead of returning
+    /// them (parent directories are created as needed)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_path: Option<String>,
+
+    /// The BRP port
+    #[serde(default)]
+    pub port: Port,
+}
+
+/// Result for the `brp_extras/query_stream+watch` tool
+#[derive(Serialize, ResultStruct)]
+pub struct QueryStreamResult {
+    /// The rows, each `{entity, components}` - omitted when written to `output_path`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[to_result(skip_if_none)]
+    pub result: Option<Value>,
+
+    /// Rows received
+    #[to_metadata]
+    pub entity_count: usize,
+
+    /// Entities the query matched
+    #[to_metadata]
+    pub total: usize,
+
+    /// Chunks received
+    #[to_metadata]
+    pub chunk_count: usize,
+
+    /// Whether `limit` closed the stream before the last chunk
+    #[to_metadata]
+    pub truncated: bool,
+
+    /// File the rows were written to
+    #[to_metadata(skip_if_none)]
+    pub output_path: Option<String>,
+
+    /// Size of the written file in bytes
+    #[to_metadata(skip_if_none)]
+    pub bytes_written: Option<usize>,
+
+    /// Message template for formatting responses
+    #[to_message]
+    pub message_template: Option<String>,
+}
+
+#[derive(ToolFn)]
+#[tool_fn(
+    params = "QueryStreamParams",
+    output = "QueryStreamResult",
+    with_context
+)]
+pub struct BrpExtrasQueryStream;
+
+/// Where received rows go
+enum RowSink {
+    /// Kept for the response, up to `limit`
+    Inline { rows: Vec<Value>, limit: usize },
+    /// Appended to a JSON Lines file
+    File {
+        writer:        BufWriter<File>,
+        bytes_written: usize,
+    },
+}
+
+impl RowSink {
+    /// Take `rows`, returning whether the sink wants more
+    fn accept(&mut self, rows: Vec<Value>, path: Option<&str>) -> Result<bool> {
+        match self {
+            Self::Inline { rows: kept, limit } => {
+                let room = limit.saturating_sub(kept.len());
+                let more = rows.len() < room;
+                kept.extend(rows.into_iter().take(room));
+                Ok(more)
+            },
+            Self::File {
+                writer,
+                bytes_written,
+            } => {
+                for row in rows {
+                    let line = format!("{row}\n");
+                    writer
+                        .write_all(line.as_bytes())
+                        .change_context(Error::FileOperation(format!(
+                            "Failed to write query rows to {}",
+                            path.unwrap_or_default()
+                        )))?;
+                    *bytes_written += line.len();
+                }
+                Ok(true)
+            },
+        }
+    }
+}
+
+#[allow(clippy::literal_string_with_formatting_args)]
+async fn handle_impl(ctx: HandlerContext, params: QueryStreamParams) -> Result<QueryStreamResult> {
+    let mut sink = match &params.output_path {
+        Some(path) => RowSink::File {
+            writer:        create_output(Path::new(path))?,
+            bytes_written: 0,
+        },
+        None => RowSink::Inline {
+            rows:  Vec::new(),
+            limit: params.limit.unwrap_or(DEFAULT_LIMIT),
+        },
+    };
+
+    let filter = params.filter.unwrap_or_default();
+    let stream_id = format!(
+        "bevy_brp_mcp-{}-{}",
+        std::process::id(),
+        NEXT_STREAM.fetch_add(1, Ordering::Relaxed)
+    );
+    let brp_params = json!({
+        "stream_id": stream_id,
+        "components": params.components,
+        "with": filter.with,
+        "without": filter.without,
+        "chunk_size": params.chunk_size,
+    });
+    let response = BrpClient::new(
+        BrpMethod::BrpExtrasQueryStream,
+        params.port,
+        Some(brp_params),
+    )
+    .execute_streaming()
+    .await?;
+
+    let StreamTotals {
+        entity_count,
+        total,
+        chunk_count,
+        truncated,
+    } = read_chunks(&ctx, response, &mut sink, params.output_path.as_deref()).await?;
+    match sink {
+        RowSink::Inline { rows, .. } => {
+            let entity_count = rows.len();
+            let message = if truncated {
+                "Returned the first {entity_count} of {total} entities - pass output_path for all"
+            } else {
+                "Streamed {entity_count} of {total} entities in {chunk_count} chunks"
+            };
+            Ok(QueryStreamResult::new(
+                Some(Value::Array(rows)),
+                entity_count,
+                total,
+                chunk_count,
+                truncated,
+                None,
+                None,
+            )
+            .with_message_template(message))
+        },
+        RowSink::File {
+            mut writer,
+            bytes_written,
+        } => {
+            writer.flush().change_context(Error::FileOperation(format!(
+                "Failed to write query rows to {}",
+                params.output_path.as_deref().unwrap_or_default()
+            )))?;
+            Ok(QueryStreamResult::new(
+                None,
+                entity_count,
+                total,
+                chunk_count,
+                false,
+                params.output_path,
+                Some(bytes_written),
+            )
+            .with_message_template("Streamed {entity_count} entities to {output_path}"))
+        },
+    }
+}
+
+/// Counts of a read stream
+struct StreamTotals {
+    entity_count: usize,
+    total:        usize,
+    chunk_count:  usize,
+    truncated:    bool,
+}
+
+/// Hand the stream's rows to `sink` until the `done` chunk, or until the sink is full
+async fn read_chunks(
+    ctx: &HandlerContext,
+    response: reqwest::Response,
+    sink: &mut RowSink,
+    output_path: Option<&str>,
+) -> Result<StreamTotals> {
+    let mut stream = response.bytes_stream();
+    let mut line_buffer = String::new();
+    let mut totals = StreamTotals {
+        entity_count: 0,
+        total:        0,
+        chunk_count:  0,
+        truncated:    false,
+    };
+    loop {
+        let bytes = match tokio::time::timeout(STALL_TIMEOUT, stream.next()).await {
+            Ok(Some(Ok(bytes))) => bytes,
+            Ok(Some(Err(e))) => {
+                return Err(Error::BrpCommunication(format!("Query stream failed: {e}")).into());
+            },
+            Ok(None) => {
+                return Err(Error::BrpCommunication(
+                    "Query stream ended before its last chunk".to_string(),
+                )
+                .into());
+            },
+            Err(_) => {
+                return Err(Error::tool_call_failed(format!(
+                    "Query stream sent no chunk for {}s - is the app running frames?",
+                    STALL_TIMEOUT.as_secs()
+                ))
+                .into());
+            },
+        };
+        line_buffer.push_str(&String::from_utf8_lossy(&bytes));
+        while let Some(newline_pos) = line_buffer.find('\n') {
+            let line: String = line_buffer.drain(..=newline_pos).collect();
+            let Some(mut chunk) = parse_chunk(line.trim())? else {
+                continue;
+            };
+            let count = |field: &str| {
+                chunk
+                    .get(field)
+                    .and_then(Value::as_u64)
+                    .map_or(0, |count| usize::try_from(count).unwrap_or(usize::MAX))
+            };
+            let sent = count("sent");
+            totals.total = count("total");
+            totals.chunk_count += 1;
+            let done = chunk.get("done").and_then(Value::as_bool).unwrap_or(false);
+            let rows = match chunk.get_mut("entities").map(Value::take) {
+                Some(Value::Array(rows)) => rows,
+                _ => Vec::new(),
+            };
+            totals.entity_count += rows.len();
+            let wants_more = sink.accept(rows, output_path)?;
+
+            if let Some(progress) = ctx.progress() {
+                progress
+                    .report(
+                        sent,
+                        totals.total,
+                        format!("Received {sent} of {} entities", totals.total),
+                    )
+                    .await;
+            }
+            if done {
+                return Ok(totals);
+            }
+            if !wants_more {
+                totals.truncated = true;
+                return Ok(totals);
+            }
+        }
+    }
+}
+
+/// Create `path` for writing, with its parent directories
+fn create_output(path: &Path) -> Result<BufWriter<File>> {
+    if let Some(parent) = path.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        fs::create_dir_all(parent).change_context(Error::FileOperation(format!(
+            "Failed to create directory {}",
+            parent.display()
+        )))?;
+    }
+    let file = File::create(path).change_context(Error::FileOperation(format!(
+        "Failed to create {}",
+        path.display()
+    )))?;
+    Ok(BufWriter::new(file))
+}
+
+/// The chunk of one server-sent events line - `None` for keep-alives and other non-data lines
+fn parse_chunk(line: &str) -> Result<Option<Value>> {
+    let Some(mut data) = line
+        .strip_prefix("data: ")
+        .and_then(|json| serde_json::from_str::<Value>(json).ok())
+    else {
+        return Ok(None);
+    };
+    if let Some(error) = data.get("error") {
+        let message = error
+            .get("message")
+            .and_then(Value::as_str)
+            .unwrap_or("unknown error");
+        return Err(Error::tool_call_failed(format!("Query stream failed: {message}")).into());
+    }
+    Ok(data.get_mut("result").map(Value::take))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inline_rows_stop_at_the_limit() {
+        let mut sink = RowSink::Inline {
+            rows:  Vec::new(),
+            limit: 3,
+        };
+        let rows = |count: u64| -> Vec<Value> {
+            (0..count)
+                .map(|entity| json!({ "entity": entity }))
+                .collect()
+        };
+
+        assert!(matches!(sink.accept(rows(2), None), Ok(true)));
+        assert!(matches!(sink.accept(rows(2), None), Ok(false)));
+        let RowSink::Inline { rows: kept, .. } = sink else {
+            unreachable!("sink was created inline");
+        };
+        assert_eq!(kept.len(), 3);
+    }
+
+    #[test]
+    fn chunks_are_read_from_data_lines() {
+        let data = json!({ "jsonrpc": "2.0", "id": 1, "result": { "chunk": 0, "done": true } });
+        let chunk = parse_chunk(&format!("data: {data}")).ok().flatten();
+        assert_eq!(chunk, Some(json!({ "chunk": 0, "done": true })));
+        assert!(matches!(parse_chunk(": keep-alive"), Ok(None)));
+
+        let error = json!({ "jsonrpc": "2.0", "id": 1, "error": { "code": -1, "message": "x" } });
+        assert!(parse_chunk(&format!("data: {error}")).is_err());
+    }
+}
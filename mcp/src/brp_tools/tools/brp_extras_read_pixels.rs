@@ -0,0 +1,139 @@
+//! `brp_extras/read_pixels` tool - Sample pixel colors of the rendered frame
+//!
+//! The BRP method only starts a probe or reports on it, since a request can't wait for a frame
+//! to be captured. This tool starts one and polls until the app reports the colors, so callers
+//! get them from a single tool call.
+
+use std::time::Duration;
+
+use bevy_brp_mcp_macros::ParamStruct;
+use bevy_brp_mcp_macros::ResultStruct;
+use bevy_brp_mcp_macros::ToolFn;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+use serde_json::json;
+
+use crate::brp_tools::BrpClient;
+use crate::brp_tools::Port;
+use crate::brp_tools::ResponseStatus;
+use crate::error::Error;
+use crate::error::Result;
+use crate::tool::BrpMethod;
+use crate::tool::HandlerContext;
+use crate::tool::HandlerResult;
+use crate::tool::ToolFn;
+use crate::tool::ToolResult;
+
+/// Interval between polls while the frame is being captured
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// How long to wait for the capture before giving up - frames are only captured while the app is
+/// running
+const CAPTURE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A rectangle of pixels, in physical pixels from the top-left corner
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
+pub struct PixelRect {
+    /// Left edge
+    pub x:      u32,
+    /// Top edge
+    pub y:      u32,
+    /// Width in pixels
+    pub width:  u32,
+    /// Height in pixels
+    pub height: u32,
+}
+
+/// Parameters for the `brp_extras/read_pixels` tool
+#[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
+pub struct ReadPixelsParams {
+    /// Pixels to sample as `[x, y]` in physical pixels from the top-left corner of the window
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub points: Option<Vec<[u32; 2]>>,
+
+    /// Rectangle whose every pixel is sampled - at most 1024 pixels in total
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rect: Option<PixelRect>,
+
+    /// The BRP port
+    #[serde(default)]
+    pub port: Port,
+}
+
+/// Result for the `brp_extras/read_pixels` tool
+#[derive(Serialize, ResultStruct)]
+pub struct ReadPixelsResult {
+    /// The sampled colors - frame size, each pixel and their average
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[to_result(skip_if_none)]
+    pub result: Option<Value>,
+
+    /// Count of pixels sampled
+    #[to_metadata]
+    pub pixel_count: usize,
+
+    /// Message template for formatting responses
+    #[to_message(message_template = "Read {pixel_count} pixels")]
+    pub message_template: String,
+}
+
+#[derive(ToolFn)]
+#[tool_fn(params = "ReadPixelsParams", output = "ReadPixelsResult")]
+pub struct BrpExtrasReadPixels;
+
+async fn handle_impl(params: ReadPixelsParams) -> Result<ReadPixelsResult> {
+    if params.points.is_none() && params.rect.is_none() {
+        return Err(Error::missing("pixels to sample in `points` or `rect`").into());
+    }
+    let port = params.port;
+    let brp_params = BrpClient::prepare_params(params)?;
+    let mut status = request(port, brp_params).await?;
+    let probe_id = status
+        .get("probe_id")
+        .and_then(Value::as_u64)
+        .ok_or_else(|| Error::tool_call_failed("The app did not return a `probe_id` to poll"))?;
+
+    let deadline = tokio::time::Instant::now() + CAPTURE_TIMEOUT;
+    loop {
+        match status.get("status").and_then(Value::as_str) {
+            Some("complete") => {
+                let pixel_count = status
+                    .get("pixels")
+                    .and_then(Value::as_array)
+                    .map_or(0, Vec::len);
+                return Ok(ReadPixelsResult::new(Some(status), pixel_count));
+            },
+            Some("failed") => {
+                let error = status
+                    .get("error")
+                    .and_then(Value::as_str)
+                    .unwrap_or("unknown error");
+                return Err(
+                    Error::tool_call_failed(format!("Failed to read pixels: {error}")).into(),
+                );
+            },
+            _ => {},
+        }
+
+        if tokio::time::Instant::now() + POLL_INTERVAL > deadline {
+            return Err(Error::tool_call_failed(format!(
+                "Frame was not captured within {}ms - is the app running frames?",
+                CAPTURE_TIMEOUT.as_millis()
+            ))
+            .into());
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+        status = request(port, Some(json!({ "probe_id": probe_id }))).await?;
+    }
+}
+
+/// Send a `read_pixels` request - `points` or `rect` start a probe, `probe_id` polls it
+async fn request(port: Port, params: Option<Value>) -> Result<Value> {
+    let client = BrpClient::new(BrpMethod::BrpExtrasReadPixels, port, params);
+    match client.execute_raw().await? {
+        ResponseStatus::Success(data) => Ok(data.unwrap_or(Value::Null)),
+        ResponseStatus::Error(err) => Err(Error::tool_call_failed(err.get_message()).into()),
+    }
+}
@@ -0,0 +1,45 @@
+//! `brp_extras/schedule_report` tool - Report system ambiguities and execution order
+
+use bevy_brp_mcp_macros::ParamStruct;
+use bevy_brp_mcp_macros::ResultStruct;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::brp_tools::Port;
+
+/// Parameters for the `brp_extras/schedule_report` tool
+#[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
+pub struct ScheduleReportParams {
+    /// Only report this schedule, by label (e.g. `Update`, `FixedUpdate`) - omit to report every
+    /// schedule
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schedule: Option<String>,
+
+    /// Include ambiguities between two systems from `bevy` or `bevy_*` crates (default: false)
+    #[serde(default)]
+    pub include_bevy: bool,
+
+    /// Include each schedule's systems in the order the executor runs them (default: false)
+    #[serde(default)]
+    pub include_order: bool,
+
+    /// The BRP port
+    #[serde(default)]
+    pub port: Port,
+}
+
+/// Result for the `brp_extras/schedule_report` tool
+#[derive(Serialize, ResultStruct)]
+#[brp_result]
+pub struct ScheduleReportResult {
+    /// The raw BRP response - per-schedule ambiguities and the total `ambiguity_count`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[to_result(skip_if_none)]
+    pub result: Option<Value>,
+
+    /// Message template for formatting responses
+    #[to_message(message_template = "Retrieved schedule report")]
+    pub message_template: String,
+}
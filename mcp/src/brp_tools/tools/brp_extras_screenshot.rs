@@ -1,34 +1,183 @@
 //! `brp_extras/screenshot` tool - Capture screenshots
+//!
+//! The app saves a screenshot asynchronously after answering the request. With
+//! `wait_for_completion`, this tool polls `brp_extras/screenshot_status` until the file is on
+//! disk, so callers never read a half-written or missing file.
+
+use std::time::Duration;
 
 use bevy_brp_mcp_macros::ParamStruct;
 use bevy_brp_mcp_macros::ResultStruct;
+use bevy_brp_mcp_macros::ToolFn;
 use schemars::JsonSchema;
 use serde::Deserialize;
 use serde::Serialize;
 use serde_json::Value;
+use serde_json::json;
 
+use crate::brp_tools::BrpClient;
 use crate::brp_tools::Port;
+use crate::brp_tools::ResponseStatus;
+use crate::brp_tools::with_managed_screenshot_path;
+use crate::error::Error;
+use crate::error::Result;
+use crate::tool::BrpMethod;
+use crate::tool::HandlerContext;
+use crate::tool::HandlerResult;
+use crate::tool::ToolFn;
+use crate::tool::ToolResult;
+
+/// Interval between status polls while waiting for the file
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// How long to wait for the file before giving up - the frame delay and encoding both take
+/// frames, which only pass while the app is running
+const SAVE_TIMEOUT: Duration = Duration::from_secs(10);
 
 /// Parameters for the `brp_extras/screenshot` tool
 #[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
 pub struct ScreenshotParams {
-    /// File path where the screenshot should be saved
-    pub path: String,
-    /// The BRP port (default: 15702)
+    /// File path where the screenshot should be saved - omit to save to the managed screenshot
+    /// directory as `{target}_port{port}_{timestamp}.png`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path:                Option<String>,
+    /// Render the active camera offscreen at this width instead of capturing the window - pass
+    /// together with `height`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[param(min = 1, max = 16_384)]
+    pub width:               Option<u32>,
+    /// Height of an offscreen capture - pass together with `width`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[param(min = 1, max = 16_384)]
+    pub height:              Option<u32>,
+    /// Frames to wait before capturing so the scene has rendered (default: 2, max: 600) - 0
+    /// captures at once, which may give a blank image on early frames
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[param(max = 600)]
+    pub delay_frames:        Option<u32>,
+    /// Return only once the file is on disk, with its size and dimensions (default: false)
+    #[serde(default)]
+    pub wait_for_completion: bool,
+    /// The BRP port
     #[serde(default)]
-    pub port: Port,
+    pub port:                Port,
 }
 
 /// Result for the `brp_extras/screenshot` tool
-#[derive(Serialize, ResultStruct)]
-#[brp_result]
+#[derive(Debug, Clone, Serialize, Deserialize, ResultStruct)]
 pub struct ScreenshotResult {
     /// The raw BRP response
-    #[serde(skip_serializing_if = "Option::is_none")]
     #[to_result(skip_if_none)]
-    pub result: Option<Value>,
+    result:    Option<Value>,
+    /// Absolute path the screenshot is saved to
+    #[to_metadata]
+    path:      String,
+    /// Size of the saved file in bytes - reported with `wait_for_completion`
+    #[to_metadata(skip_if_none)]
+    file_size: Option<u64>,
+    /// Width of the saved image in pixels - reported with `wait_for_completion`
+    #[to_metadata(skip_if_none)]
+    width:     Option<u64>,
+    /// Height of the saved image in pixels - reported with `wait_for_completion`
+    #[to_metadata(skip_if_none)]
+    height:    Option<u64>,
 
     /// Message template for formatting responses
-    #[to_message(message_template = "Screenshot saved to {path}")]
-    pub message_template: String,
+    #[to_message]
+    message_template: Option<String>,
+}
+
+#[derive(ToolFn)]
+#[tool_fn(params = "ScreenshotParams", output = "ScreenshotResult")]
+pub struct BrpExtrasScreenshot;
+
+#[allow(clippy::literal_string_with_formatting_args)]
+async fn handle_impl(params: ScreenshotParams) -> Result<ScreenshotResult> {
+    let port = params.port;
+    let wait_for_completion = params.wait_for_completion;
+    let mut brp_params = BrpClient::prepare_params(params)?;
+    if let Some(Value::Object(map)) = &mut brp_params {
+        // The app doesn't know `wait_for_completion` - waiting happens here
+        map.remove("wait_for_completion");
+    }
+    if let Some(with_path) =
+        with_managed_screenshot_path(BrpMethod::BrpExtrasScreenshot, port, brp_params.as_ref())
+    {
+        brp_params = Some(with_path);
+    }
+
+    let response = request(BrpMethod::BrpExtrasScreenshot, port, brp_params).await?;
+    let path = response
+        .get("path")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string();
+    if !wait_for_completion {
+        return Ok(
+            ScreenshotResult::new(Some(response), path, None, None, None)
+                .with_message_template("Screenshot saving to {path}"),
+        );
+    }
+
+    let request_id = response
+        .get("request_id")
+        .and_then(Value::as_u64)
+        .ok_or_else(|| {
+            Error::tool_call_failed(
+                "The app's bevy_brp_extras doesn't report screenshot progress - update it to use \
+                 `wait_for_completion`",
+            )
+        })?;
+
+    let deadline = tokio::time::Instant::now() + SAVE_TIMEOUT;
+    loop {
+        let status = request(
+            BrpMethod::BrpExtrasScreenshotStatus,
+            port,
+            Some(json!({ "request_id": request_id })),
+        )
+        .await?;
+        match status.get("status").and_then(Value::as_str) {
+            Some("saved") => {
+                let field = |name: &str| status.get(name).and_then(Value::as_u64);
+                return Ok(ScreenshotResult::new(
+                    Some(response),
+                    path,
+                    field("file_size"),
+                    field("width"),
+                    field("height"),
+                )
+                .with_message_template("Screenshot saved to {path} ({width}x{height})"));
+            },
+            Some("failed") => {
+                let error = status
+                    .get("error")
+                    .and_then(Value::as_str)
+                    .unwrap_or("unknown error");
+                return Err(Error::tool_call_failed(format!(
+                    "Screenshot could not be saved to {path}: {error}"
+                ))
+                .into());
+            },
+            _ => {},
+        }
+
+        if tokio::time::Instant::now() + POLL_INTERVAL > deadline {
+            return Err(Error::tool_call_failed(format!(
+                "Screenshot was not saved to {path} within {}ms - is the app running frames?",
+                SAVE_TIMEOUT.as_millis()
+            ))
+            .into());
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+/// Send a request to the app and return its result
+async fn request(method: BrpMethod, port: Port, params: Option<Value>) -> Result<Value> {
+    let client = BrpClient::new(method, port, params);
+    match client.execute_raw().await? {
+        ResponseStatus::Success(data) => Ok(data.unwrap_or(Value::Null)),
+        ResponseStatus::Error(err) => Err(Error::tool_call_failed(err.get_message()).into()),
+    }
 }
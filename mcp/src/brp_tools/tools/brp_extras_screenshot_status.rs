@@ -0,0 +1,35 @@
+//! `brp_extras/screenshot_status` tool - Check whether a screenshot has been saved
+
+use bevy_brp_mcp_macros::ParamStruct;
+use bevy_brp_mcp_macros::ResultStruct;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::brp_tools::Port;
+
+/// Parameters for the `brp_extras/screenshot_status` tool
+#[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
+pub struct ScreenshotStatusParams {
+    /// The `request_id` returned by `brp_extras_screenshot`
+    pub request_id: u64,
+    /// The BRP port
+    #[serde(default)]
+    pub port:       Port,
+}
+
+/// Result for the `brp_extras/screenshot_status` tool
+#[derive(Serialize, ResultStruct)]
+#[brp_result]
+pub struct ScreenshotStatusResult {
+    /// The raw BRP response - `status` is `pending`, `saved` (with `file_size`, `width` and
+    /// `height`) or `failed` (with `error`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[to_result(skip_if_none)]
+    pub result: Option<Value>,
+
+    /// Message template for formatting responses
+    #[to_message(message_template = "Retrieved screenshot status")]
+    pub message_template: String,
+}
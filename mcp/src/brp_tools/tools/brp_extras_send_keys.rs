@@ -9,17 +9,61 @@ use serde_json::Value;
 
 use crate::brp_tools::Port;
 
+/// A key to send - a key code name, or an object giving the key its own timing
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
+#[serde(untagged)]
+pub enum KeyEntry {
+    /// Key code name, pressed together with the other plain keys for `duration_ms`
+    Name(String),
+    /// Key code with its own timing
+    Timed {
+        /// Key code name
+        key:         String,
+        /// Milliseconds to hold this key (default: the request's `duration_ms`)
+        #[serde(skip_serializing_if = "Option::is_none")]
+        duration_ms: Option<u32>,
+        /// Milliseconds between the previous key's press and this key's press (default: 0)
+        #[serde(skip_serializing_if = "Option::is_none")]
+        delay_ms:    Option<u32>,
+    },
+}
+
 /// Parameters for the `brp_extras/send_keys` tool
 #[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
 pub struct SendKeysParams {
-    /// Array of key code names to send
-    pub keys: Vec<String>,
+    /// Keys to send - key code names, or `{"key", "duration_ms", "delay_ms"}` objects for
+    /// per-key hold durations and delays between presses
+    pub keys: Vec<KeyEntry>,
 
     /// Duration in milliseconds to hold the keys before releasing (default: 100ms, max: 60000ms)
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[param(max = 60_000)]
+    #[schemars(extend("default" = 100))]
     pub duration_ms: Option<u32>,
 
-    /// The BRP port (default: 15702)
+    /// Text to deliver as an IME commit (`Ime::Commit`) to the primary window, for text fields
+    /// that read input-method events
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ime_text: Option<String>,
+
+    /// Send repeated press events while the keys are held, as an OS does for a held key
+    /// (default: false)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repeat: Option<bool>,
+
+    /// Delay in milliseconds before held keys start repeating (default: 500ms, max: 60000ms)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[param(max = 60_000)]
+    #[schemars(extend("default" = 500))]
+    pub repeat_delay_ms: Option<u32>,
+
+    /// Interval in milliseconds between repeat events (default: 30ms, min: 1ms, max: 60000ms)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[param(min = 1, max = 60_000)]
+    #[schemars(extend("default" = 30))]
+    pub repeat_interval_ms: Option<u32>,
+
+    /// The BRP port
     #[serde(default)]
     pub port: Port,
 }
@@ -0,0 +1,60 @@
+//! `brp_extras/set_window` tool - Change window decorations, level, focus and minimized state
+
+use bevy_brp_mcp_macros::ParamStruct;
+use bevy_brp_mcp_macros::ResultStruct;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::brp_tools::Port;
+
+/// Stacking level of a window relative to other windows
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum WindowLevel {
+    /// Always below other windows
+    AlwaysOnBottom,
+    /// Stacked like any other window
+    Normal,
+    /// Always above other windows
+    AlwaysOnTop,
+}
+
+/// Parameters for the `brp_extras/set_window` tool
+#[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
+pub struct SetWindowParams {
+    /// Show (`true`) or hide (`false`) the title bar and borders
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub decorations: Option<bool>,
+
+    /// Stacking level - `always_on_top`, `normal` or `always_on_bottom`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_level: Option<WindowLevel>,
+
+    /// Ask the platform to give the window keyboard focus - only `true` is accepted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub focus: Option<bool>,
+
+    /// Minimize (`true`) or restore (`false`) the window
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub minimized: Option<bool>,
+
+    /// The BRP port
+    #[serde(default)]
+    pub port: Port,
+}
+
+/// Result for the `brp_extras/set_window` tool
+#[derive(Serialize, ResultStruct)]
+#[brp_result]
+pub struct SetWindowResult {
+    /// The raw BRP response - the resulting window state and the changes made
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[to_result(skip_if_none)]
+    pub result: Option<Value>,
+
+    /// Message template for formatting responses
+    #[to_message(message_template = "Window state updated")]
+    pub message_template: String,
+}
@@ -15,7 +15,7 @@ pub struct SetWindowTitleParams {
     /// The new title to set for the window
     pub title: String,
 
-    /// The BRP port (default: 15702)
+    /// The BRP port
     #[serde(default)]
     pub port: Port,
 }
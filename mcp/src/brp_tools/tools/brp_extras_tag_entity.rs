@@ -0,0 +1,39 @@
+//! `brp_extras/tag_entity` tool - Bookmark an entity with a `BrpTag`
+
+use bevy_brp_mcp_macros::ParamStruct;
+use bevy_brp_mcp_macros::ResultStruct;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::brp_tools::Port;
+
+/// Parameters for the `brp_extras/tag_entity` tool
+#[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
+pub struct TagEntityParams {
+    /// The entity ID to tag
+    pub entity: u64,
+
+    /// Tag to attach, replacing any existing tag - omit to remove the entity's tag
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+
+    /// The BRP port
+    #[serde(default)]
+    pub port: Port,
+}
+
+/// Result for the `brp_extras/tag_entity` tool
+#[derive(Serialize, ResultStruct)]
+#[brp_result]
+pub struct TagEntityResult {
+    /// The raw BRP response - the entity with its new and previous tag
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[to_result(skip_if_none)]
+    pub result: Option<Value>,
+
+    /// Message template for formatting responses
+    #[to_message(message_template = "Updated tag of entity {entity}")]
+    pub message_template: String,
+}
@@ -0,0 +1,44 @@
+//! `brp_extras/time_config` tool - Read or change fixed timestep and time configuration
+
+use bevy_brp_mcp_macros::ParamStruct;
+use bevy_brp_mcp_macros::ResultStruct;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::brp_tools::Port;
+
+/// Parameters for the `brp_extras/time_config` tool - omitted settings are left unchanged
+#[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
+pub struct TimeConfigParams {
+    /// Seconds between `FixedUpdate` runs (`Time<Fixed>` timestep, Bevy default: 1/64)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fixed_timestep_seconds: Option<f64>,
+
+    /// Largest frame delta in seconds that virtual time advances by (Bevy default: 0.25)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_delta_seconds: Option<f64>,
+
+    /// Seconds after which `elapsed_wrapped` wraps to zero (Bevy default: 3600)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wrap_period_seconds: Option<f64>,
+
+    /// The BRP port
+    #[serde(default)]
+    pub port: Port,
+}
+
+/// Result for the `brp_extras/time_config` tool
+#[derive(Serialize, ResultStruct)]
+#[brp_result]
+pub struct TimeConfigResult {
+    /// The raw BRP response - the current time configuration and the settings changed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[to_result(skip_if_none)]
+    pub result: Option<Value>,
+
+    /// Message template for formatting responses
+    #[to_message(message_template = "Retrieved time configuration")]
+    pub message_template: String,
+}
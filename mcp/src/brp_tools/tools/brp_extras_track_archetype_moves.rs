@@ -0,0 +1,137 @@
+//! `brp_extras/track_archetype_moves` tool - Entities that change archetype most often
+//!
+//! Like `brp_extras_collect_frame_stats`, the BRP method only starts a tracking or reports on
+//! it. This tool starts one and polls until the app has compared the requested frames, so callers
+//! get the worst offenders from a single tool call.
+
+use std::time::Duration;
+
+use bevy_brp_mcp_macros::ParamStruct;
+use bevy_brp_mcp_macros::ResultStruct;
+use bevy_brp_mcp_macros::ToolFn;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+use serde_json::json;
+
+use crate::brp_tools::BrpClient;
+use crate::brp_tools::Port;
+use crate::brp_tools::ResponseStatus;
+use crate::error::Error;
+use crate::error::Result;
+use crate::tool::BrpMethod;
+use crate::tool::HandlerContext;
+use crate::tool::HandlerResult;
+use crate::tool::ToolFn;
+use crate::tool::ToolResult;
+
+/// Default tracking window in frames
+const DEFAULT_FRAMES: u32 = 120;
+
+/// Interval between polls
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// How long to keep polling without a newly compared frame before giving up - frames only
+/// advance the tracking while the app is running its schedules
+const STALL_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Parameters for the `brp_extras/track_archetype_moves` tool
+#[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
+pub struct TrackArchetypeMovesParams {
+    /// Frames to compare entity archetypes over (default: 120, max: 10000)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[param(min = 1, max = 10_000)]
+    #[schemars(extend("default" = 120))]
+    pub frames: Option<u32>,
+
+    /// Entities and components to report, most moves first (default: 20, max: 1000)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[param(min = 1, max = 1_000)]
+    #[schemars(extend("default" = 20))]
+    pub limit: Option<usize>,
+
+    /// The BRP port
+    #[serde(default)]
+    pub port: Port,
+}
+
+/// Result for the `brp_extras/track_archetype_moves` tool
+#[derive(Serialize, ResultStruct)]
+pub struct TrackArchetypeMovesResult {
+    /// The report - worst entities and the components they churned
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[to_result(skip_if_none)]
+    pub result: Option<Value>,
+
+    /// Archetype moves of all entities
+    #[to_metadata]
+    pub total_moves: u64,
+
+    /// Entities that moved at least once
+    #[to_metadata]
+    pub moving_entities: u64,
+
+    /// Frames compared
+    #[to_metadata]
+    pub frames: u64,
+
+    /// Message template for formatting responses
+    #[to_message(message_template = "Tracked {total_moves} archetype moves over {frames} frames")]
+    pub message_template: String,
+}
+
+#[derive(ToolFn)]
+#[tool_fn(
+    params = "TrackArchetypeMovesParams",
+    output = "TrackArchetypeMovesResult"
+)]
+pub struct BrpExtrasTrackArchetypeMoves;
+
+async fn handle_impl(params: TrackArchetypeMovesParams) -> Result<TrackArchetypeMovesResult> {
+    let frames = params.frames.unwrap_or(DEFAULT_FRAMES);
+    let started = request(
+        params.port,
+        Some(json!({ "frames": frames, "limit": params.limit })),
+    )
+    .await?;
+
+    let mut frames_recorded = started.get("frames_recorded").and_then(Value::as_u64);
+    let mut last_progress = tokio::time::Instant::now();
+    loop {
+        tokio::time::sleep(POLL_INTERVAL).await;
+
+        let status = request(params.port, None).await?;
+        if status.get("status").and_then(Value::as_str) == Some("complete") {
+            let count = |field: &str| status.get(field).and_then(Value::as_u64).unwrap_or(0);
+            let (total_moves, moving_entities) = (count("total_moves"), count("moving_entities"));
+            return Ok(TrackArchetypeMovesResult::new(
+                Some(status),
+                total_moves,
+                moving_entities,
+                u64::from(frames),
+            ));
+        }
+
+        let recorded = status.get("frames_recorded").and_then(Value::as_u64);
+        if recorded != frames_recorded {
+            frames_recorded = recorded;
+            last_progress = tokio::time::Instant::now();
+        } else if last_progress.elapsed() > STALL_TIMEOUT {
+            return Err(Error::tool_call_failed(format!(
+                "Archetype move tracking made no progress for {}s - is the app running frames?",
+                STALL_TIMEOUT.as_secs()
+            ))
+            .into());
+        }
+    }
+}
+
+/// Send a `track_archetype_moves` request - `Some` params start a tracking, `None` polls it
+async fn request(port: Port, params: Option<Value>) -> Result<Value> {
+    let client = BrpClient::new(BrpMethod::BrpExtrasTrackArchetypeMoves, port, params);
+    match client.execute_raw().await? {
+        ResponseStatus::Success(data) => Ok(data.unwrap_or(Value::Null)),
+        ResponseStatus::Error(err) => Err(Error::tool_call_failed(err.get_message()).into()),
+    }
+}
@@ -0,0 +1,43 @@
+//! `brp_extras/track_entity` tool - Record component changes of an entity
+
+use bevy_brp_mcp_macros::ParamStruct;
+use bevy_brp_mcp_macros::ResultStruct;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::brp_tools::Port;
+
+/// Parameters for the `brp_extras/track_entity` tool
+#[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
+pub struct TrackEntityParams {
+    /// The entity ID to track
+    pub entity: u64,
+
+    /// Only track these components, by full type path - omit for every reflected component
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub components: Option<Vec<String>>,
+
+    /// If true, stop tracking the entity instead - its recorded changes are kept
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub untrack: Option<bool>,
+
+    /// The BRP port
+    #[serde(default)]
+    pub port: Port,
+}
+
+/// Result for the `brp_extras/track_entity` tool
+#[derive(Serialize, ResultStruct)]
+#[brp_result]
+pub struct TrackEntityResult {
+    /// The raw BRP response - the entity, whether it is tracked and the tracked components
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[to_result(skip_if_none)]
+    pub result: Option<Value>,
+
+    /// Message template for formatting responses
+    #[to_message(message_template = "Updated tracking of entity {entity}")]
+    pub message_template: String,
+}
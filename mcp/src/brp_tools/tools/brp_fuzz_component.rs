@@ -0,0 +1,528 @@
+//! `brp_fuzz_component` tool - Randomized values for a component type, optionally applied
+//!
+//! Values are generated from the type guide: the `spawn_format` example gives the shape, each
+//! leaf is replaced by a random value of its mutation path's type - integers within their type's
+//! range, floats including edge cases, booleans and strings - and each enum is set to a random
+//! variant from its examples. Generation is seeded, so a failing value can be reproduced.
+//!
+//! With `entity`, the values are inserted onto it one after another. BRP errors are reported per
+//! value, and when the app was launched by this server its log is scanned after each insert for
+//! panics the value caused.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+use std::io::Seek;
+use std::io::SeekFrom;
+use std::path::Path;
+use std::time::Duration;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+use bevy_brp_mcp_macros::ParamStruct;
+use bevy_brp_mcp_macros::ResultStruct;
+use bevy_brp_mcp_macros::ToolFn;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Map;
+use serde_json::Value;
+use serde_json::json;
+
+use crate::app_tools::instance_registry;
+use crate::brp_tools::BrpClient;
+use crate::brp_tools::Port;
+use crate::brp_tools::ResponseStatus;
+use crate::brp_tools::brp_client::split_mix;
+use crate::brp_tools::brp_type_guide::TypeGuideEngine;
+use crate::error::Error;
+use crate::error::Result;
+use crate::tool::BrpMethod;
+use crate::tool::HandlerContext;
+use crate::tool::HandlerResult;
+use crate::tool::ParameterName;
+use crate::tool::ToolFn;
+use crate::tool::ToolResult;
+
+/// Values generated without `count`
+const DEFAULT_COUNT: u32 = 10;
+
+/// Milliseconds waited after each insert without `settle_ms`
+const DEFAULT_SETTLE_MS: u32 = 100;
+
+/// Text of a Rust panic message in the app's log
+const PANIC_MARKER: &str = "panicked at";
+
+/// `SplitMix64` increment between generator states
+const GOLDEN_GAMMA: u64 = 0x9E37_79B9_7F4A_7C15;
+
+/// Float values tried alongside uniform ones - where arithmetic and normalization tend to break
+const FLOAT_EDGES: [f64; 8] = [0.0, 1.0, -1.0, 0.5, 1e-6, 1e6, -1e6, f64::EPSILON];
+
+/// Characters random strings are built from
+const STRING_CHARS: &[char] = &['a', 'Z', '0', '9', ' ', '_', '-', '/', 'é', '✓', '"', '\\'];
+
+/// Longest random string in characters
+const MAX_STRING_LENGTH: usize = 16;
+
+/// Parameters for the `brp_fuzz_component` tool
+#[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
+pub struct FuzzComponentParams {
+    /// Fully-qualified component type to generate values for
+    pub type_name: String,
+
+    /// Number of values to generate (default: 10, max: 100)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[param(min = 1, max = 100)]
+    #[schemars(extend("default" = 10))]
+    pub count: Option<u32>,
+
+    /// Seed for the generator - pass the `seed` of an earlier result to get the same values again
+    /// (default: random)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+
+    /// Entity to insert each value onto, one after another - omit it to only generate the values
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entity: Option<u64>,
+
+    /// Milliseconds to wait after each insert before checking the log for panics, so systems
+    /// reading the component get to run (default: 100ms)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[param(max = 10_000)]
+    #[schemars(extend("default" = 100))]
+    pub settle_ms: Option<u32>,
+
+    /// The BRP port
+    #[serde(default)]
+    pub port: Port,
+}
+
+/// One generated value and what applying it caused
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FuzzCase {
+    /// Position in the generated sequence
+    pub index:  usize,
+    /// The generated component value
+    pub value:  Value,
+    /// BRP error returned when inserting the value
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error:  Option<String>,
+    /// Panic lines the app logged after the value was inserted
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub panics: Vec<String>,
+}
+
+/// Result for the `brp_fuzz_component` tool
+#[derive(Debug, Clone, Serialize, Deserialize, ResultStruct)]
+pub struct FuzzComponentResult {
+    /// Generated values, with any error or panic each caused
+    #[to_result]
+    cases:            Vec<FuzzCase>,
+    /// The type the values were generated for
+    #[to_metadata]
+    type_name:        String,
+    /// Seed the values were generated from
+    #[to_metadata]
+    seed:             u64,
+    /// Entity the values were inserted onto
+    #[to_metadata(skip_if_none)]
+    entity:           Option<u64>,
+    /// Number of values generated
+    #[to_metadata]
+    generated_count:  usize,
+    /// Number of values inserted
+    #[to_metadata]
+    applied_count:    usize,
+    /// Number of inserts that returned a BRP error
+    #[to_metadata]
+    error_count:      usize,
+    /// Number of inserts followed by a panic in the app's log
+    #[to_metadata]
+    panic_count:      usize,
+    /// Log scanned for panics - absent when the app wasn't launched by this server
+    #[to_metadata(skip_if_none)]
+    log_file:         Option<String>,
+    /// Message template for formatting responses
+    #[to_message(
+        message_template = "Generated {generated_count} values for {type_name} (seed {seed}), applied {applied_count}: {error_count} BRP errors, {panic_count} panics"
+    )]
+    message_template: String,
+}
+
+#[derive(ToolFn)]
+#[tool_fn(params = "FuzzComponentParams", output = "FuzzComponentResult")]
+pub struct BrpFuzzComponent;
+
+async fn handle_impl(params: FuzzComponentParams) -> Result<FuzzComponentResult> {
+    let port = params.port;
+    let engine = TypeGuideEngine::new(port).await?;
+    let (type_name, guide) = engine.build_guide(&params.type_name);
+    let Some(base) = guide.spawn_format.clone() else {
+        return Err(Error::invalid(
+            "type_name",
+            format!(
+                "{} has no spawn format in the type guide - only types that can be inserted \
+                 whole can be fuzzed",
+                type_name.as_str()
+            ),
+        )
+        .into());
+    };
+
+    let mut shape = Shape::default();
+    for mutation_path in &guide.mutation_paths {
+        let path = mutation_path.path.to_string();
+        let examples = serde_json::to_value(mutation_path)
+            .ok()
+            .and_then(|value| value.get("examples").and_then(Value::as_array).cloned());
+        if let Some(groups) = examples {
+            let variants: Vec<Value> = groups
+                .iter()
+                .filter_map(|group| group.get("example").cloned())
+                .collect();
+            if !variants.is_empty() {
+                shape.variants.insert(path.clone(), variants);
+            }
+        }
+        shape
+            .leaf_types
+            .insert(path, mutation_path.path_info.type_name.as_str().to_string());
+    }
+
+    let seed = params.seed.unwrap_or_else(random_seed);
+    let count = params.count.unwrap_or(DEFAULT_COUNT);
+    let mut fuzzer = Fuzzer::new(&shape, seed);
+    let mut cases: Vec<FuzzCase> = (0..count as usize)
+        .map(|index| FuzzCase {
+            index,
+            value: fuzzer.value("", &base),
+            error: None,
+            panics: Vec::new(),
+        })
+        .collect();
+
+    let log_file = instance_registry::live_instances()
+        .into_iter()
+        .find(|instance| instance.port == port.0)
+        .map(|instance| instance.log_file);
+    let mut applied_count = 0;
+    if let Some(entity) = params.entity {
+        let settle =
+            Duration::from_millis(u64::from(params.settle_ms.unwrap_or(DEFAULT_SETTLE_MS)));
+        let mut log_offset = log_file
+            .as_deref()
+            .map_or(0, |path| log_length(Path::new(path)));
+        for case in &mut cases {
+            let request = json!({
+                ParameterName::Entity: entity,
+                ParameterName::Components: { type_name.as_str(): case.value },
+            });
+            let outcome = BrpClient::new(BrpMethod::WorldInsertComponents, port, Some(request))
+                .execute_raw()
+                .await;
+            applied_count += 1;
+            let stopped = match outcome {
+                Ok(ResponseStatus::Success(_)) => false,
+                Ok(ResponseStatus::Error(err)) => {
+                    case.error = Some(err.get_message().to_string());
+                    false
+                },
+                // The app no longer answers - most likely the previous value crashed it
+                Err(e) => {
+                    case.error = Some(e.to_string());
+                    true
+                },
+            };
+            tokio::time::sleep(settle).await;
+            if let Some(path) = &log_file {
+                case.panics = panics_since(Path::new(path), &mut log_offset);
+            }
+            if stopped {
+                break;
+            }
+        }
+    }
+
+    let error_count = cases.iter().filter(|case| case.error.is_some()).count();
+    let panic_count = cases.iter().filter(|case| !case.panics.is_empty()).count();
+    let generated_count = cases.len();
+    Ok(FuzzComponentResult::new(
+        cases,
+        type_name.as_str().to_string(),
+        seed,
+        params.entity,
+        generated_count,
+        applied_count,
+        error_count,
+        panic_count,
+        log_file.filter(|_| params.entity.is_some()),
+    ))
+}
+
+/// A seed from the clock, for calls without `seed`
+fn random_seed() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| {
+            split_mix(elapsed.as_secs() ^ u64::from(elapsed.subsec_nanos()))
+        })
+}
+
+/// What the type guide says about each mutation path of the fuzzed type
+#[derive(Default)]
+struct Shape {
+    /// Type name at each mutation path
+    leaf_types: HashMap<String, String>,
+    /// Buildable variant examples of each enum, by mutation path
+    variants:   HashMap<String, Vec<Value>>,
+}
+
+/// Seeded generator of values shaped like an example
+struct Fuzzer<'a> {
+    shape: &'a Shape,
+    state: u64,
+}
+
+impl<'a> Fuzzer<'a> {
+    const fn new(shape: &'a Shape, seed: u64) -> Self { Self { shape, state: seed } }
+
+    const fn next(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(GOLDEN_GAMMA);
+        split_mix(self.state)
+    }
+
+    /// A random index below `len`, which must not be 0
+    #[allow(clippy::cast_possible_truncation)] // the result is below `len`
+    const fn below(&mut self, len: usize) -> usize { (self.next() % len as u64) as usize }
+
+    /// A random value shaped like `example`, which sits at mutation path `path`
+    fn value(&mut self, path: &str, example: &Value) -> Value {
+        match self.shape.variants.get(path) {
+            Some(variants) if !variants.is_empty() => {
+                let index = self.below(variants.len());
+                let variant = variants.get(index).unwrap_or(example).clone();
+                self.shaped(path, variant, true)
+            },
+            _ => self.shaped(path, example.clone(), false),
+        }
+    }
+
+    /// Randomize the leaves of `example` - `is_variant` when it is an enum variant picked for
+    /// `path`, whose `{"Variant": payload}` wrapper isn't part of the mutation paths
+    fn shaped(&mut self, path: &str, example: Value, is_variant: bool) -> Value {
+        match example {
+            Value::Object(map) if is_variant && map.len() == 1 => {
+                let mut randomized = Map::new();
+                for (variant, payload) in map {
+                    let payload = self.variant_payload(path, payload);
+                    randomized.insert(variant, payload);
+                }
+                Value::Object(randomized)
+            },
+            Value::Object(map) => {
+                let mut randomized = Map::new();
+                for (field, value) in map {
+                    let value = self.value(&format!("{path}.{field}"), &value);
+                    randomized.insert(field, value);
+                }
+                Value::Object(randomized)
+            },
+            Value::Array(items) => {
+                let mut randomized = Vec::with_capacity(items.len());
+                for (index, item) in items.iter().enumerate() {
+                    let tuple_path = format!("{path}.{index}");
+                    let element_path = if self.shape.leaf_types.contains_key(&tuple_path) {
+                        tuple_path
+                    } else {
+                        format!("{path}[{index}]")
+                    };
+                    randomized.push(self.value(&element_path, item));
+                }
+                Value::Array(randomized)
+            },
+            Value::Number(number) => self.number(path, &number),
+            Value::Bool(_) => Value::Bool(self.next() & 1 == 1),
+            Value::String(text) => match self.leaf_type(path) {
+                Some("alloc::string::String") => Value::String(self.string()),
+                Some("char") => Value::String(self.character().to_string()),
+                // Unit variants and other strings keep their meaning
+                _ => Value::String(text),
+            },
+            Value::Null => Value::Null,
+        }
+    }
+
+    /// A random payload for a variant of the enum at `path` - a single-field tuple variant's
+    /// payload is at `path.0`, the fields or elements of other payloads hang off `path` itself
+    fn variant_payload(&mut self, path: &str, payload: Value) -> Value {
+        let newtype_path = format!("{path}.0");
+        if !payload.is_array() && self.shape.leaf_types.contains_key(&newtype_path) {
+            self.value(&newtype_path, &payload)
+        } else {
+            self.shaped(path, payload, false)
+        }
+    }
+
+    /// The short type name at `path`, e.g. `u8` or `f32`, when the type guide lists the path
+    fn leaf_type(&self, path: &str) -> Option<&str> {
+        self.shape.leaf_types.get(path).map(String::as_str)
+    }
+
+    /// A random number of the type at `path` - or, for paths the guide doesn't list, of the
+    /// same kind as `example`
+    fn number(&mut self, path: &str, example: &serde_json::Number) -> Value {
+        let range = match self.leaf_type(path) {
+            Some(type_name) => integer_range(type_name),
+            None if example.is_u64() => Some((0, i128::from(u8::MAX))),
+            None if example.is_i64() => Some((i128::from(i8::MIN), i128::from(i8::MAX))),
+            None => None,
+        };
+        let Some((min, max)) = range else {
+            return json!(self.float(self.leaf_type(path) == Some("f32")));
+        };
+        let value = match self.next() % 8 {
+            0 => min,
+            1 => max,
+            2 => 0.clamp(min, max),
+            _ => {
+                let span = u128::try_from(max - min).unwrap_or(0) + 1;
+                min + i128::try_from(u128::from(self.next()) % span).unwrap_or(0)
+            },
+        };
+        u64::try_from(value).map_or_else(|_| json!(i64::try_from(value).unwrap_or(0)), Value::from)
+    }
+
+    /// A random float - an edge case a third of the time, otherwise uniform in ±1000
+    #[allow(clippy::cast_precision_loss)] // 53 random bits fit an f64 mantissa exactly
+    fn float(&mut self, single_precision: bool) -> f64 {
+        if self.next().is_multiple_of(3) {
+            let index = self.below(FLOAT_EDGES.len() + 2);
+            return match FLOAT_EDGES.get(index) {
+                Some(edge) => *edge,
+                None if index == FLOAT_EDGES.len() && single_precision => f64::from(f32::MAX),
+                None if index == FLOAT_EDGES.len() => f64::MAX,
+                None if single_precision => f64::from(f32::MIN),
+                None => f64::MIN,
+            };
+        }
+        let unit = (self.next() >> 11) as f64 / (1_u64 << 53) as f64;
+        unit.mul_add(2000.0, -1000.0)
+    }
+
+    /// A random string of up to `MAX_STRING_LENGTH` characters, empty included
+    fn string(&mut self) -> String {
+        let length = self.below(MAX_STRING_LENGTH + 1);
+        (0..length).map(|_| self.character()).collect()
+    }
+
+    fn character(&mut self) -> char {
+        let index = self.below(STRING_CHARS.len());
+        STRING_CHARS.get(index).copied().unwrap_or('a')
+    }
+}
+
+/// Bounds of an integer type, by its type name
+fn integer_range(type_name: &str) -> Option<(i128, i128)> {
+    let range = match type_name {
+        "u8" => (0, i128::from(u8::MAX)),
+        "u16" => (0, i128::from(u16::MAX)),
+        "u32" => (0, i128::from(u32::MAX)),
+        "u64" | "usize" => (0, i128::from(u64::MAX)),
+        "i8" => (i128::from(i8::MIN), i128::from(i8::MAX)),
+        "i16" => (i128::from(i16::MIN), i128::from(i16::MAX)),
+        "i32" => (i128::from(i32::MIN), i128::from(i32::MAX)),
+        "i64" | "isize" => (i128::from(i64::MIN), i128::from(i64::MAX)),
+        _ => return None,
+    };
+    Some(range)
+}
+
+/// Current length of the log at `path`, 0 when it can't be read
+fn log_length(path: &Path) -> u64 { std::fs::metadata(path).map_or(0, |metadata| metadata.len()) }
+
+/// Panic lines logged at `path` since `offset`, which moves to the end of the log
+fn panics_since(path: &Path, offset: &mut u64) -> Vec<String> {
+    let Ok(mut file) = File::open(path) else {
+        return Vec::new();
+    };
+    let mut bytes = Vec::new();
+    if file.seek(SeekFrom::Start(*offset)).is_err() || file.read_to_end(&mut bytes).is_err() {
+        return Vec::new();
+    }
+    *offset += bytes.len() as u64;
+    panic_lines(&String::from_utf8_lossy(&bytes))
+}
+
+/// Each panic line of `text` with the line after it, which holds the panic message
+fn panic_lines(text: &str) -> Vec<String> {
+    let lines: Vec<&str> = text.lines().collect();
+    lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| line.contains(PANIC_MARKER))
+        .map(|(index, line)| match lines.get(index + 1) {
+            Some(message) if !message.contains(PANIC_MARKER) => format!("{line}\n{message}"),
+            _ => (*line).to_string(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shape() -> Shape {
+        Shape {
+            leaf_types: [
+                ("", "my_game::Stats"),
+                (".level", "u8"),
+                (".speed", "f32"),
+                (".name", "alloc::string::String"),
+                (".mode", "my_game::Mode"),
+            ]
+            .into_iter()
+            .map(|(path, type_name)| (path.to_string(), type_name.to_string()))
+            .collect(),
+            variants:   HashMap::from([(".mode".to_string(), vec![json!("Idle"), json!("Run")])]),
+        }
+    }
+
+    #[test]
+    fn values_follow_the_example_shape_and_repeat_per_seed() {
+        let shape = shape();
+        let example = json!({"level": 1, "speed": 1.0, "name": "a", "mode": "Idle"});
+        let generate = |seed| {
+            let mut fuzzer = Fuzzer::new(&shape, seed);
+            (0..50)
+                .map(|_| fuzzer.value("", &example))
+                .collect::<Vec<_>>()
+        };
+
+        let values = generate(7);
+        assert_eq!(values, generate(7));
+        assert_ne!(values, generate(8));
+        for value in &values {
+            let level = value.get("level").and_then(Value::as_u64);
+            assert!(level.is_some_and(|level| u8::try_from(level).is_ok()));
+            assert!(value.get("speed").is_some_and(Value::is_f64));
+            assert!(value.get("name").is_some_and(Value::is_string));
+            let mode = value.get("mode").and_then(Value::as_str);
+            assert!(matches!(mode, Some("Idle" | "Run")));
+        }
+        assert!(
+            values
+                .iter()
+                .any(|value| value.get("mode") == Some(&json!("Run")))
+        );
+    }
+
+    #[test]
+    fn panic_lines_include_the_message() {
+        let log = "INFO frame\nthread 'main' panicked at src/main.rs:4:5:\nbad speed\nok\n";
+        assert_eq!(
+            panic_lines(log),
+            vec!["thread 'main' panicked at src/main.rs:4:5:\nbad speed"]
+        );
+    }
+}
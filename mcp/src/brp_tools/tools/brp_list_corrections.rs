@@ -0,0 +1,139 @@
+//! `brp_list_corrections` tool - List the format corrections applied this session
+//!
+//! Reads the session's correction log: every value or type path the format-correction pipeline
+//! rewrote in a request that then succeeded, with the rule that rewrote it. Agents learn the
+//! canonical formats from it, and reviewers check it for corrections that changed what a value
+//! meant.
+
+use bevy_brp_mcp_macros::ParamStruct;
+use bevy_brp_mcp_macros::ResultStruct;
+use bevy_brp_mcp_macros::ToolFn;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::brp_tools::CorrectionEvent;
+use crate::brp_tools::Corrector;
+use crate::brp_tools::Port;
+use crate::brp_tools::corrections;
+use crate::error::Result;
+use crate::tool::HandlerContext;
+use crate::tool::HandlerResult;
+use crate::tool::ToolFn;
+use crate::tool::ToolResult;
+
+/// Corrections returned when `limit` is omitted
+const DEFAULT_LIMIT: usize = 50;
+
+/// Parameters for the `brp_list_corrections` tool
+#[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
+pub struct ListCorrectionsParams {
+    /// Only list corrections of requests to this port - omit for every port
+    #[to_metadata(skip_if_none)]
+    pub port:  Option<Port>,
+    /// Only list corrections made by this rule
+    #[to_metadata(skip_if_none)]
+    pub rule:  Option<Corrector>,
+    /// Return at most this many of the most recent matching corrections (default: 50)
+    #[param(min = 1, max = 1000)]
+    #[schemars(extend("default" = 50))]
+    pub limit: Option<usize>,
+    /// Remove the listed corrections from the log - the others stay (default: false)
+    #[serde(default)]
+    pub clear: bool,
+}
+
+/// Result for the `brp_list_corrections` tool
+#[derive(Debug, Clone, Serialize, Deserialize, ResultStruct)]
+pub struct ListCorrectionsResult {
+    /// Matching corrections, oldest first
+    #[to_result]
+    corrections:      Vec<CorrectionEvent>,
+    /// Number of matching corrections in the log, before `limit`
+    #[to_metadata]
+    total:            usize,
+    /// Number of corrections removed by `clear` - the listed ones
+    #[to_metadata(skip_if_none)]
+    cleared:          Option<usize>,
+    /// Message template for formatting responses
+    #[to_message]
+    message_template: Option<String>,
+}
+
+#[derive(ToolFn)]
+#[tool_fn(params = "ListCorrectionsParams", output = "ListCorrectionsResult")]
+pub struct BrpListCorrections;
+
+#[allow(clippy::unused_async)]
+async fn handle_impl(params: ListCorrectionsParams) -> Result<ListCorrectionsResult> {
+    let limit = params.limit.unwrap_or(DEFAULT_LIMIT);
+    let listing = corrections(params.port, params.rule, limit, params.clear);
+    let (listed, total) = (listing.listed, listing.total);
+    let cleared = params.clear.then_some(listed.len());
+
+    let cleared_note = cleared.map_or_else(String::new, |cleared| {
+        format!(", cleared {cleared} from the log")
+    });
+    let message = if total == listed.len() {
+        format!("{total} format corrections recorded{cleared_note}")
+    } else {
+        format!(
+            "Showing the latest {} of {total} format corrections{cleared_note}",
+            listed.len()
+        )
+    };
+
+    Ok(ListCorrectionsResult::new(listed, total, cleared).with_message_template(message))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::brp_tools::brp_client::record_corrections;
+    use crate::tool::BrpMethod;
+
+    fn params(
+        port: u16,
+        rule: Option<Corrector>,
+        limit: usize,
+        clear: bool,
+    ) -> ListCorrectionsParams {
+        ListCorrectionsParams {
+            port: Some(Port(port)),
+            rule,
+            limit: Some(limit),
+            clear,
+        }
+    }
+
+    #[tokio::test]
+    async fn clear_removes_only_the_listed_corrections() {
+        // A port no other test records on, as the log is shared by the whole process
+        let port = 45_801;
+        let correction = json!({});
+        for rule in [
+            Corrector::NumericStrings,
+            Corrector::ShortNames,
+            Corrector::NumericStrings,
+            Corrector::NumericStrings,
+        ] {
+            record_corrections(Port(port), BrpMethod::WorldQuery, [(rule, &correction)]);
+        }
+
+        let listed = handle_impl(params(port, Some(Corrector::NumericStrings), 2, true)).await;
+        let listed = listed.unwrap_or_else(|e| unreachable!("listing should succeed: {e:?}"));
+        assert_eq!((listed.corrections.len(), listed.total), (2, 3));
+        assert_eq!(listed.cleared, Some(2));
+
+        let left = handle_impl(params(port, None, 10, false)).await;
+        let left = left.unwrap_or_else(|e| unreachable!("listing should succeed: {e:?}"));
+        let rules: Vec<Corrector> = left.corrections.iter().map(|event| event.rule).collect();
+        assert_eq!(
+            rules,
+            vec![Corrector::NumericStrings, Corrector::ShortNames]
+        );
+        assert_eq!(left.cleared, None);
+    }
+}
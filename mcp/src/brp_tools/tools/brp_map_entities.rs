@@ -0,0 +1,503 @@
+//! `brp_map_entities` tool - Match entity IDs from before an app restart to the IDs after it
+//!
+//! Entity IDs are allocated anew on every run, so every ID an agent holds goes stale when the app
+//! restarts. `snapshot`, taken before the restart, records each entity's `Name`, the names of its
+//! ancestors and the set of components it has. `map`, after the restart, fingerprints the new
+//! world the same way and pairs each snapshot entity with its most similar successor, giving a
+//! confidence for every pair. Snapshots are kept per port in memory and survive the app's
+//! restart detection, which forgets everything else about the previous run.
+
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::sync::LazyLock;
+use std::sync::Mutex;
+
+use bevy_brp_mcp_macros::ParamStruct;
+use bevy_brp_mcp_macros::ResultStruct;
+use bevy_brp_mcp_macros::ToolFn;
+use chrono::Local;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+use serde_json::json;
+use strum::Display;
+
+use super::world_export_graph::QUERY_COMPONENTS_FIELD;
+use super::world_export_graph::TYPE_CHILD_OF;
+use super::world_export_graph::TYPE_NAME;
+use super::world_export_graph::first_entity;
+use super::world_export_graph::name_text;
+use super::world_export_graph::with_aliases;
+use super::world_query::BrpQuery;
+use super::world_query::BrpQueryFilter;
+use super::world_query::ComponentSelector;
+use crate::brp_tools::BrpClient;
+use crate::brp_tools::Port;
+use crate::brp_tools::ResponseStatus;
+use crate::error::Error;
+use crate::error::Result;
+use crate::json_object::JsonObjectAccess;
+use crate::tool::BrpMethod;
+use crate::tool::HandlerContext;
+use crate::tool::HandlerResult;
+use crate::tool::ParameterName;
+use crate::tool::ToolFn;
+use crate::tool::ToolResult;
+
+/// Share of the confidence earned by an equal `Name`
+const NAME_WEIGHT: f64 = 0.4;
+
+/// Share of the confidence earned by the same ancestor names
+const HIERARCHY_WEIGHT: f64 = 0.3;
+
+/// Share of the confidence earned by the same set of components
+const COMPONENTS_WEIGHT: f64 = 0.3;
+
+/// Default minimum confidence for a pair to be mapped, in percent
+const DEFAULT_MIN_CONFIDENCE: u32 = 50;
+
+/// What `brp_map_entities` does
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema, Display)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum MapEntitiesAction {
+    /// Record the current world, replacing any earlier snapshot for the port
+    Snapshot,
+    /// Match the current world against the port's snapshot
+    Map,
+}
+
+/// Parameters for the `brp_map_entities` tool
+#[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
+pub struct MapEntitiesParams {
+    /// `snapshot` before the restart records the world; `map` after it matches the new world
+    /// against that snapshot
+    pub action: MapEntitiesAction,
+
+    /// Minimum confidence (0-100) for a pair to be mapped - `map` only (default: 50)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[param(max = 100)]
+    #[schemars(extend("default" = 50))]
+    pub min_confidence: Option<u32>,
+
+    /// Which entities to record or match. Structure: {with: string[], without: string[]}
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter: Option<BrpQueryFilter>,
+
+    /// The BRP port
+    #[serde(default)]
+    pub port: Port,
+}
+
+/// A snapshot entity and the current entity it most likely became
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct EntityMapping {
+    /// Entity ID before the restart
+    pub old:        u64,
+    /// Entity ID after the restart
+    pub new:        u64,
+    /// How alike the two entities are, 0-100
+    pub confidence: u32,
+    /// Another current entity matched the snapshot entity just as well - the pick is arbitrary
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub ambiguous:  bool,
+    /// `Name` of the entity, if it has one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name:       Option<String>,
+}
+
+/// Result for the `brp_map_entities` tool
+#[derive(Debug, Clone, Serialize, Deserialize, ResultStruct)]
+pub struct MapEntitiesResult {
+    /// Old to new entity pairs in old ID order - `map` only
+    #[to_result(skip_if_none)]
+    mappings:          Option<Vec<EntityMapping>>,
+    /// Number of entities in the snapshot
+    #[to_metadata]
+    snapshot_entities: usize,
+    /// When the snapshot was taken
+    #[to_metadata]
+    snapshot_taken_at: String,
+    /// Snapshot entities without a match of at least `min_confidence`
+    #[to_metadata(skip_if_none)]
+    unmatched_old:     Option<Vec<u64>>,
+    /// Current entities no snapshot entity was matched to
+    #[to_metadata(skip_if_none)]
+    unmatched_new:     Option<Vec<u64>>,
+    /// Message template for formatting responses
+    #[to_message]
+    message_template:  Option<String>,
+}
+
+#[derive(ToolFn)]
+#[tool_fn(params = "MapEntitiesParams", output = "MapEntitiesResult")]
+pub struct BrpMapEntities;
+
+/// What is known about one entity
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct EntityRecord {
+    entity:     u64,
+    name:       Option<String>,
+    /// Names of the entity's ancestors, root first - `None` for unnamed ones
+    ancestors:  Vec<Option<String>>,
+    /// Type paths of the entity's components
+    components: BTreeSet<String>,
+}
+
+/// The world as recorded by `snapshot`
+#[derive(Debug, Clone)]
+struct Snapshot {
+    taken_at: String,
+    entities: Vec<EntityRecord>,
+}
+
+/// Latest snapshot per port
+static SNAPSHOTS: LazyLock<Mutex<HashMap<Port, Snapshot>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+async fn handle_impl(params: MapEntitiesParams) -> Result<MapEntitiesResult> {
+    let port = params.port;
+
+    match params.action {
+        MapEntitiesAction::Snapshot => {
+            let snapshot = Snapshot {
+                taken_at: Local::now().to_rfc3339(),
+                entities: fetch_records(params.filter, port).await?,
+            };
+            let message = format!(
+                "Recorded {} entities on port {port} - call with action `map` after the restart",
+                snapshot.entities.len()
+            );
+            let result = MapEntitiesResult::new(
+                None,
+                snapshot.entities.len(),
+                snapshot.taken_at.clone(),
+                None,
+                None,
+            );
+            if let Ok(mut snapshots) = SNAPSHOTS.lock() {
+                snapshots.insert(port, snapshot);
+            }
+            Ok(result.with_message_template(message))
+        },
+        MapEntitiesAction::Map => {
+            let snapshot = SNAPSHOTS
+                .lock()
+                .ok()
+                .and_then(|snapshots| snapshots.get(&port).cloned())
+                .ok_or_else(|| {
+                    Error::invalid(
+                        "action",
+                        format!(
+                            "no snapshot for port {port} - call with action `snapshot` before the \
+                             restart"
+                        ),
+                    )
+                })?;
+            let current = fetch_records(params.filter, port).await?;
+            let min_confidence = params.min_confidence.unwrap_or(DEFAULT_MIN_CONFIDENCE);
+            let mappings = match_records(&snapshot.entities, &current, min_confidence);
+
+            let mapped_old: HashSet<u64> = mappings.iter().map(|mapping| mapping.old).collect();
+            let mapped_new: HashSet<u64> = mappings.iter().map(|mapping| mapping.new).collect();
+            let unmatched_old: Vec<u64> = snapshot
+                .entities
+                .iter()
+                .map(|record| record.entity)
+                .filter(|entity| !mapped_old.contains(entity))
+                .collect();
+            let unmatched_new: Vec<u64> = current
+                .iter()
+                .map(|record| record.entity)
+                .filter(|entity| !mapped_new.contains(entity))
+                .collect();
+
+            let message = format!(
+                "Mapped {} of {} snapshot entities to current entities",
+                mappings.len(),
+                snapshot.entities.len()
+            );
+            Ok(MapEntitiesResult::new(
+                Some(mappings),
+                snapshot.entities.len(),
+                snapshot.taken_at,
+                (!unmatched_old.is_empty()).then_some(unmatched_old),
+                (!unmatched_new.is_empty()).then_some(unmatched_new),
+            )
+            .with_message_template(message))
+        },
+    }
+}
+
+/// Fingerprint every entity matching `filter` with one `world.query`
+async fn fetch_records(filter: Option<BrpQueryFilter>, port: Port) -> Result<Vec<EntityRecord>> {
+    let query = BrpQuery {
+        option: ComponentSelector::All,
+        ..BrpQuery::default()
+    };
+    let brp_params = json!({
+        ParameterName::Data: query,
+        ParameterName::Filter: filter.unwrap_or_default(),
+    });
+
+    let client = BrpClient::new(BrpMethod::WorldQuery, port, Some(brp_params));
+    let rows = match client.execute_raw().await? {
+        ResponseStatus::Success(Some(Value::Array(rows))) => rows,
+        ResponseStatus::Success(_) => Vec::new(),
+        ResponseStatus::Error(err) => return Err(Error::tool_call_failed(err.get_message()).into()),
+    };
+
+    let child_of = with_aliases(TYPE_CHILD_OF);
+    let name = with_aliases(TYPE_NAME);
+    let mut records = Vec::with_capacity(rows.len());
+    let mut parents = BTreeMap::new();
+    for row in &rows {
+        let Some(entity) = row.get_field(ParameterName::Entity).and_then(Value::as_u64) else {
+            continue;
+        };
+        let components = row.get(QUERY_COMPONENTS_FIELD).and_then(Value::as_object);
+        let component = |type_path: &String| components.and_then(|c| c.get(type_path.as_str()));
+
+        if let Some(parent) = child_of
+            .iter()
+            .find_map(|type_path| first_entity(component(type_path)?))
+        {
+            parents.insert(entity, parent);
+        }
+        records.push(EntityRecord {
+            entity,
+            name: name
+                .iter()
+                .find_map(|type_path| component(type_path).and_then(name_text)),
+            ancestors: Vec::new(),
+            components: components
+                .map(|components| components.keys().cloned().collect())
+                .unwrap_or_default(),
+        });
+    }
+
+    let names: HashMap<u64, Option<String>> = records
+        .iter()
+        .map(|record| (record.entity, record.name.clone()))
+        .collect();
+    for record in &mut records {
+        record.ancestors = ancestors(record.entity, &parents, &names);
+    }
+    Ok(records)
+}
+
+/// Names of `entity`'s ancestors, root first, stopping at the first one outside the query
+fn ancestors(
+    entity: u64,
+    parents: &BTreeMap<u64, u64>,
+    names: &HashMap<u64, Option<String>>,
+) -> Vec<Option<String>> {
+    let mut ancestors = Vec::new();
+    let mut current = entity;
+    // A well-formed hierarchy is never deeper than the number of entities - this guards cycles
+    while ancestors.len() < names.len()
+        && let Some(parent) = parents.get(&current)
+        && let Some(name) = names.get(parent)
+    {
+        ancestors.push(name.clone());
+        current = *parent;
+    }
+    ancestors.reverse();
+    ancestors
+}
+
+/// Pair snapshot entities with current ones, most confident pairs first, each entity used once
+///
+/// Only entities with the same `Name` are compared; unnamed entities are compared with unnamed
+/// entities under the same ancestor names.
+fn match_records(
+    old: &[EntityRecord],
+    new: &[EntityRecord],
+    min_confidence: u32,
+) -> Vec<EntityMapping> {
+    let mut candidates_by_key: HashMap<MatchKey<'_>, Vec<&EntityRecord>> = HashMap::new();
+    for record in new {
+        candidates_by_key
+            .entry(match_key(record))
+            .or_default()
+            .push(record);
+    }
+
+    // `(confidence, old, new, ambiguous)`
+    let mut pairs: Vec<(u32, u64, u64, bool)> = Vec::new();
+    for record in old {
+        let Some(candidates) = candidates_by_key.get(&match_key(record)) else {
+            continue;
+        };
+        let scored: Vec<(u32, u64)> = candidates
+            .iter()
+            .map(|candidate| (confidence(record, candidate), candidate.entity))
+            .filter(|(confidence, _)| *confidence >= min_confidence)
+            .collect();
+        let best = scored.iter().map(|(confidence, _)| *confidence).max();
+        let tied = scored
+            .iter()
+            .filter(|(confidence, _)| Some(*confidence) == best)
+            .count();
+        pairs.extend(scored.into_iter().map(|(confidence, entity)| {
+            (
+                confidence,
+                record.entity,
+                entity,
+                Some(confidence) == best && tied > 1,
+            )
+        }));
+    }
+    pairs.sort_unstable_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)).then(a.2.cmp(&b.2)));
+
+    let names: HashMap<u64, &Option<String>> = old
+        .iter()
+        .map(|record| (record.entity, &record.name))
+        .collect();
+    let mut used_old = HashSet::new();
+    let mut used_new = HashSet::new();
+    let mut mappings = Vec::new();
+    for (confidence, old, new, ambiguous) in pairs {
+        if used_old.contains(&old) || used_new.contains(&new) {
+            continue;
+        }
+        used_old.insert(old);
+        used_new.insert(new);
+        mappings.push(EntityMapping {
+            old,
+            new,
+            confidence,
+            ambiguous,
+            name: names.get(&old).and_then(|name| (*name).clone()),
+        });
+    }
+    mappings.sort_unstable_by_key(|mapping| mapping.old);
+    mappings
+}
+
+/// An entity's `Name`, or its ancestors' names when it has none
+type MatchKey<'a> = (Option<&'a str>, &'a [Option<String>]);
+
+/// Entities are only compared within the same key
+fn match_key(record: &EntityRecord) -> MatchKey<'_> {
+    record.name.as_deref().map_or_else(
+        || (None, record.ancestors.as_slice()),
+        |name| (Some(name), &[][..]),
+    )
+}
+
+/// How alike two entities are, 0-100
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)] // the score is within 0.0..=1.0
+fn confidence(old: &EntityRecord, new: &EntityRecord) -> u32 {
+    let name = if old.name.is_some() && old.name == new.name {
+        1.0
+    } else {
+        0.0
+    };
+    let score = component_similarity(&old.components, &new.components).mul_add(
+        COMPONENTS_WEIGHT,
+        hierarchy_similarity(&old.ancestors, &new.ancestors)
+            .mul_add(HIERARCHY_WEIGHT, name * NAME_WEIGHT),
+    );
+    (score * 100.0).round() as u32
+}
+
+/// Share of ancestor names that agree, counting up from the parent
+#[allow(clippy::cast_precision_loss)] // hierarchy depths are far below f64's exact integer range
+fn hierarchy_similarity(old: &[Option<String>], new: &[Option<String>]) -> f64 {
+    let depth = old.len().max(new.len());
+    if depth == 0 {
+        return 1.0;
+    }
+    let agreeing = old
+        .iter()
+        .rev()
+        .zip(new.iter().rev())
+        .take_while(|(old, new)| old == new)
+        .count();
+    agreeing as f64 / depth as f64
+}
+
+/// Jaccard similarity of two component sets
+#[allow(clippy::cast_precision_loss)] // component counts are far below f64's exact integer range
+fn component_similarity(old: &BTreeSet<String>, new: &BTreeSet<String>) -> f64 {
+    let union = old.union(new).count();
+    if union == 0 {
+        return 1.0;
+    }
+    old.intersection(new).count() as f64 / union as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(
+        entity: u64,
+        name: Option<&str>,
+        ancestors: &[&str],
+        components: &[&str],
+    ) -> EntityRecord {
+        EntityRecord {
+            entity,
+            name: name.map(String::from),
+            ancestors: ancestors.iter().map(|a| Some((*a).to_string())).collect(),
+            components: components.iter().map(|c| (*c).to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn maps_by_name_hierarchy_and_components() {
+        let old = vec![
+            record(10, Some("Player"), &[], &["Transform", "Name", "Health"]),
+            record(11, Some("Sword"), &["Player"], &["Transform", "Name"]),
+            record(12, None, &["Player"], &["Transform"]),
+            record(13, Some("Boss"), &[], &["Transform", "Name"]),
+        ];
+        let new = vec![
+            record(20, Some("Sword"), &["Player"], &["Transform", "Name"]),
+            record(21, Some("Player"), &[], &["Transform", "Name", "Health"]),
+            record(22, None, &["Player"], &["Transform"]),
+        ];
+
+        let mappings = match_records(&old, &new, DEFAULT_MIN_CONFIDENCE);
+
+        let pairs: Vec<(u64, u64, u32)> = mappings
+            .iter()
+            .map(|mapping| (mapping.old, mapping.new, mapping.confidence))
+            .collect();
+        assert_eq!(pairs, vec![(10, 21, 100), (11, 20, 100), (12, 22, 60)]);
+        assert!(mappings.iter().all(|mapping| !mapping.ambiguous));
+    }
+
+    #[test]
+    fn flags_ties_and_uses_each_entity_once() {
+        let old = vec![
+            record(1, Some("Enemy"), &[], &["Transform"]),
+            record(2, Some("Enemy"), &[], &["Transform"]),
+        ];
+        let new = vec![
+            record(5, Some("Enemy"), &[], &["Transform"]),
+            record(6, Some("Enemy"), &[], &["Transform"]),
+        ];
+
+        let mappings = match_records(&old, &new, DEFAULT_MIN_CONFIDENCE);
+
+        assert_eq!(mappings.len(), 2);
+        assert!(mappings.iter().all(|mapping| mapping.ambiguous));
+        assert_ne!(mappings[0].new, mappings[1].new);
+    }
+
+    #[test]
+    fn scores_partial_similarity() {
+        let old = [None, Some("Arm".to_string())];
+        let new = [Some("Arm".to_string())];
+        assert!((hierarchy_similarity(&old, &new) - 0.5).abs() < f64::EPSILON);
+        let old = BTreeSet::from(["A".to_string(), "B".to_string()]);
+        let new = BTreeSet::from(["B".to_string(), "C".to_string()]);
+        assert!((component_similarity(&old, &new) - 1.0 / 3.0).abs() < f64::EPSILON);
+    }
+}
@@ -0,0 +1,75 @@
+//! `brp_refresh_type_cache` tool - Rebuild the short type name index for an app
+//!
+//! Requests naming a component or resource by its short name are retried with the full type
+//! path registered under it. The index behind that lookup is built once per port; this tool
+//! rebuilds it after a hot reload or a restart changes the app's types.
+
+use std::collections::BTreeMap;
+
+use bevy_brp_mcp_macros::ParamStruct;
+use bevy_brp_mcp_macros::ResultStruct;
+use bevy_brp_mcp_macros::ToolFn;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+use serde_json::json;
+
+use crate::brp_tools::Port;
+use crate::brp_tools::refresh_short_name_index;
+use crate::error::Result;
+use crate::tool::HandlerContext;
+use crate::tool::HandlerResult;
+use crate::tool::ToolFn;
+use crate::tool::ToolResult;
+
+/// Parameters for the `brp_refresh_type_cache` tool
+#[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
+pub struct RefreshTypeCacheParams {
+    /// The BRP port
+    #[serde(default)]
+    pub port: Port,
+}
+
+/// Result for the `brp_refresh_type_cache` tool
+#[derive(Serialize, ResultStruct)]
+pub struct RefreshTypeCacheResult {
+    /// Short names shared by several types, which are never resolved automatically
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[to_result(skip_if_none)]
+    pub result: Option<Value>,
+
+    /// Count of component and resource types indexed
+    #[to_metadata]
+    pub type_count: usize,
+
+    /// Count of distinct short names
+    #[to_metadata]
+    pub short_name_count: usize,
+
+    /// Count of short names shared by several types
+    #[to_metadata]
+    pub ambiguous_count: usize,
+
+    /// Message template for formatting responses
+    #[to_message(
+        message_template = "Indexed {type_count} types under {short_name_count} short names ({ambiguous_count} ambiguous)"
+    )]
+    pub message_template: String,
+}
+
+#[derive(ToolFn)]
+#[tool_fn(params = "RefreshTypeCacheParams", output = "RefreshTypeCacheResult")]
+pub struct BrpRefreshTypeCache;
+
+async fn handle_impl(params: RefreshTypeCacheParams) -> Result<RefreshTypeCacheResult> {
+    let index = refresh_short_name_index(params.port).await?;
+    let ambiguous: BTreeMap<&str, &[String]> = index.ambiguous();
+
+    Ok(RefreshTypeCacheResult::new(
+        (!ambiguous.is_empty()).then(|| json!({ "ambiguous": ambiguous })),
+        index.type_count(),
+        index.short_name_count(),
+        ambiguous.len(),
+    ))
+}
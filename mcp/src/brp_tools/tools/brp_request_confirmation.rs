@@ -0,0 +1,73 @@
+//! `brp_request_confirmation` tool - Obtain a single-use token that unlocks a destructive tool
+
+use std::str::FromStr;
+
+use bevy_brp_mcp_macros::ParamStruct;
+use bevy_brp_mcp_macros::ResultStruct;
+use bevy_brp_mcp_macros::ToolFn;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::error::Error;
+use crate::error::Result;
+use crate::tool::CONFIRMATION_TOKEN_TTL;
+use crate::tool::HandlerContext;
+use crate::tool::HandlerResult;
+use crate::tool::ToolFn;
+use crate::tool::ToolName;
+use crate::tool::ToolResult;
+use crate::tool::issue_confirmation_token;
+
+/// Parameters for the `brp_request_confirmation` tool
+#[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
+pub struct RequestConfirmationParams {
+    /// Name of the destructive tool to confirm (e.g. `world_despawn_entity`)
+    pub tool: String,
+}
+
+/// Result for the `brp_request_confirmation` tool
+#[derive(Debug, Clone, Serialize, Deserialize, ResultStruct)]
+pub struct RequestConfirmationResult {
+    /// The tool this token unlocks
+    #[to_metadata]
+    tool:               String,
+    /// Single-use token to pass as `confirmation_token`
+    #[to_metadata]
+    confirmation_token: String,
+    /// Seconds until the token expires
+    #[to_metadata]
+    expires_in_seconds: u64,
+    /// Message template for formatting responses
+    #[to_message(
+        message_template = "Confirmation token issued for {tool} - valid for {expires_in_seconds} seconds"
+    )]
+    message_template:   String,
+}
+
+#[derive(ToolFn)]
+#[tool_fn(
+    params = "RequestConfirmationParams",
+    output = "RequestConfirmationResult"
+)]
+pub struct BrpRequestConfirmation;
+
+#[allow(clippy::unused_async)]
+async fn handle_impl(params: RequestConfirmationParams) -> Result<RequestConfirmationResult> {
+    let tool_name = ToolName::from_str(&params.tool)
+        .map_err(|_| Error::invalid("tool", format!("unknown tool '{}'", params.tool)))?;
+
+    if !tool_name.requires_confirmation() {
+        return Err(Error::invalid(
+            "tool",
+            format!("'{tool_name}' does not require confirmation"),
+        )
+        .into());
+    }
+
+    Ok(RequestConfirmationResult::new(
+        tool_name.to_string(),
+        issue_confirmation_token(tool_name),
+        CONFIRMATION_TOKEN_TTL.as_secs(),
+    ))
+}
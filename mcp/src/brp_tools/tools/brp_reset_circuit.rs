@@ -0,0 +1,56 @@
+//! `brp_reset_circuit` tool - Close the circuit breaker for an app that is running again
+//!
+//! After repeated connection failures to a port, BRP calls to it fail fast with an "app appears
+//! down" error instead of waiting on timeouts. This tool closes the circuit so the next call
+//! goes through straight away, rather than after the next probe.
+
+use bevy_brp_mcp_macros::ParamStruct;
+use bevy_brp_mcp_macros::ResultStruct;
+use bevy_brp_mcp_macros::ToolFn;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::brp_tools::Port;
+use crate::brp_tools::reset_circuit;
+use crate::error::Result;
+use crate::tool::HandlerContext;
+use crate::tool::HandlerResult;
+use crate::tool::ToolFn;
+use crate::tool::ToolResult;
+
+/// Parameters for the `brp_reset_circuit` tool
+#[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
+pub struct ResetCircuitParams {
+    /// Only reset the circuit for this port - omit to reset every port
+    pub port: Option<Port>,
+}
+
+/// Result for the `brp_reset_circuit` tool
+#[derive(Debug, Clone, Serialize, Deserialize, ResultStruct)]
+pub struct ResetCircuitResult {
+    /// Ports whose open circuit was closed
+    #[to_result]
+    ports:            Vec<u16>,
+    /// Count of circuits closed
+    #[to_metadata]
+    reset_count:      usize,
+    /// Message template for formatting responses
+    #[to_message(message_template = "Closed {reset_count} open circuits")]
+    message_template: String,
+}
+
+#[derive(ToolFn)]
+#[tool_fn(params = "ResetCircuitParams", output = "ResetCircuitResult")]
+pub struct BrpResetCircuit;
+
+#[allow(clippy::unused_async)]
+async fn handle_impl(params: ResetCircuitParams) -> Result<ResetCircuitResult> {
+    let ports: Vec<u16> = reset_circuit(params.port)
+        .into_iter()
+        .map(|port| port.0)
+        .collect();
+    let reset_count = ports.len();
+
+    Ok(ResetCircuitResult::new(ports, reset_count))
+}
@@ -0,0 +1,51 @@
+//! `brp_set_default_port` tool - Set the port used when BRP tool calls omit `port`
+
+use bevy_brp_mcp_macros::ParamStruct;
+use bevy_brp_mcp_macros::ResultStruct;
+use bevy_brp_mcp_macros::ToolFn;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::brp_tools::Port;
+use crate::brp_tools::configured_default_port;
+use crate::error::Result;
+use crate::tool::HandlerContext;
+use crate::tool::HandlerResult;
+use crate::tool::ToolFn;
+use crate::tool::ToolResult;
+
+/// Parameters for the `brp_set_default_port` tool
+#[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
+pub struct SetDefaultPortParams {
+    /// Port to use for tool calls that omit `port` - omit to restore the startup default
+    pub port: Option<Port>,
+}
+
+/// Result for the `brp_set_default_port` tool
+#[derive(Debug, Clone, Serialize, Deserialize, ResultStruct)]
+pub struct SetDefaultPortResult {
+    /// The session default port now in effect
+    #[to_metadata]
+    default_port:     u16,
+    /// The session default port before this call
+    #[to_metadata]
+    previous_port:    u16,
+    /// Message template for formatting responses
+    #[to_message(
+        message_template = "Default BRP port set to {default_port} (was {previous_port})"
+    )]
+    message_template: String,
+}
+
+#[derive(ToolFn)]
+#[tool_fn(params = "SetDefaultPortParams", output = "SetDefaultPortResult")]
+pub struct BrpSetDefaultPort;
+
+#[allow(clippy::unused_async)]
+async fn handle_impl(params: SetDefaultPortParams) -> Result<SetDefaultPortResult> {
+    let port = params.port.unwrap_or_else(configured_default_port);
+    let previous_port = Port::set_session_default(port);
+
+    Ok(SetDefaultPortResult::new(port.0, previous_port.0))
+}
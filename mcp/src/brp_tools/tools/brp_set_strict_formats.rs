@@ -0,0 +1,51 @@
+//! `brp_set_strict_formats` tool - Turn silent coercion and format correction off or on for the
+//! session
+
+use bevy_brp_mcp_macros::ParamStruct;
+use bevy_brp_mcp_macros::ResultStruct;
+use bevy_brp_mcp_macros::ToolFn;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::error::Result;
+use crate::tool::HandlerContext;
+use crate::tool::HandlerResult;
+use crate::tool::ToolFn;
+use crate::tool::ToolResult;
+use crate::tool::configured_strict_formats;
+use crate::tool::set_session_strict_formats;
+
+/// Parameters for the `brp_set_strict_formats` tool
+#[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
+pub struct SetStrictFormatsParams {
+    /// Whether tool calls that omit `strict_formats` use strict formats - omit to restore the
+    /// startup setting
+    pub enabled: Option<bool>,
+}
+
+/// Result for the `brp_set_strict_formats` tool
+#[derive(Debug, Clone, Serialize, Deserialize, ResultStruct)]
+pub struct SetStrictFormatsResult {
+    /// The session setting now in effect
+    #[to_metadata]
+    strict_formats:   bool,
+    /// The session setting before this call
+    #[to_metadata]
+    previous:         bool,
+    /// Message template for formatting responses
+    #[to_message(message_template = "Strict formats set to {strict_formats} (was {previous})")]
+    message_template: String,
+}
+
+#[derive(ToolFn)]
+#[tool_fn(params = "SetStrictFormatsParams", output = "SetStrictFormatsResult")]
+pub struct BrpSetStrictFormats;
+
+#[allow(clippy::unused_async)]
+async fn handle_impl(params: SetStrictFormatsParams) -> Result<SetStrictFormatsResult> {
+    let strict_formats = params.enabled.unwrap_or_else(configured_strict_formats);
+    let previous = set_session_strict_formats(strict_formats);
+
+    Ok(SetStrictFormatsResult::new(strict_formats, previous))
+}
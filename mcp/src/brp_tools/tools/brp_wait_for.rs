@@ -0,0 +1,192 @@
+//! `brp_wait_for` tool - Poll the world until a condition holds
+//!
+//! The condition is any `brp_assert` assertion. It is evaluated every `interval_ms` until it
+//! passes or `max_wait_ms` elapses, so an agent waiting for a spawn, a state change or a count to
+//! settle makes one call instead of a sleep-and-retry loop.
+
+use std::future::Future;
+use std::time::Duration;
+
+use bevy_brp_mcp_macros::ParamStruct;
+use bevy_brp_mcp_macros::ResultStruct;
+use bevy_brp_mcp_macros::ToolFn;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+use tokio::time::Instant;
+
+use super::brp_assert;
+use super::brp_assert::Assertion;
+use super::brp_assert::AssertionOutcome;
+use crate::brp_tools::Port;
+use crate::error::Error;
+use crate::error::Result;
+use crate::tool::HandlerContext;
+use crate::tool::HandlerResult;
+use crate::tool::ToolFn;
+use crate::tool::ToolResult;
+
+/// Default time between evaluations in milliseconds
+const DEFAULT_INTERVAL_MS: u32 = 250;
+
+/// Default time to wait for the condition in milliseconds
+const DEFAULT_MAX_WAIT_MS: u32 = 10_000;
+
+/// Parameters for the `brp_wait_for` tool
+#[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
+pub struct WaitForParams {
+    /// Condition to wait for - any `brp_assert` assertion, selected by `kind`: `entity_exists`,
+    /// `component_equals`, `component_in_range` or `count`
+    pub condition: Assertion,
+
+    /// Milliseconds between evaluations (default: 250ms)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[param(min = 10, max = 60_000)]
+    #[schemars(extend("default" = 250))]
+    pub interval_ms: Option<u32>,
+
+    /// Milliseconds to wait for the condition before giving up (default: 10000ms, max: 600000ms)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[param(max = 600_000)]
+    #[schemars(extend("default" = 10000))]
+    pub max_wait_ms: Option<u32>,
+
+    /// The BRP port
+    #[serde(default)]
+    pub port: Port,
+}
+
+/// Result for the `brp_wait_for` tool
+#[derive(Debug, Clone, Serialize, Deserialize, ResultStruct)]
+pub struct WaitForResult {
+    /// The value that satisfied the condition
+    #[to_result(skip_if_none)]
+    state:            Option<Value>,
+    /// Milliseconds until the condition held
+    #[to_metadata]
+    elapsed_ms:       u64,
+    /// Number of times the condition was evaluated
+    #[to_metadata]
+    attempts:         u32,
+    /// Message template for formatting responses
+    #[to_message(
+        message_template = "Condition held after {elapsed_ms}ms ({attempts} evaluations)"
+    )]
+    message_template: String,
+}
+
+#[derive(ToolFn)]
+#[tool_fn(params = "WaitForParams", output = "WaitForResult")]
+pub struct BrpWaitFor;
+
+async fn handle_impl(params: WaitForParams) -> Result<WaitForResult> {
+    let interval =
+        Duration::from_millis(u64::from(params.interval_ms.unwrap_or(DEFAULT_INTERVAL_MS)));
+    let max_wait =
+        Duration::from_millis(u64::from(params.max_wait_ms.unwrap_or(DEFAULT_MAX_WAIT_MS)));
+
+    wait_until(interval, max_wait, || {
+        brp_assert::evaluate(0, &params.condition, params.port)
+    })
+    .await
+}
+
+/// Evaluate the condition every `interval` until it passes or `max_wait` elapses
+async fn wait_until<F, Fut>(
+    interval: Duration,
+    max_wait: Duration,
+    mut evaluate: F,
+) -> Result<WaitForResult>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<AssertionOutcome>>,
+{
+    let started = Instant::now();
+    let deadline = started + max_wait;
+    let mut attempts = 0;
+    loop {
+        attempts += 1;
+        let outcome = evaluate().await?;
+        let elapsed_ms = u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX);
+        if outcome.passed {
+            return Ok(WaitForResult::new(outcome.actual, elapsed_ms, attempts));
+        }
+
+        if Instant::now() + interval > deadline {
+            let last_seen = outcome
+                .actual
+                .map_or_else(|| "nothing".to_string(), |actual| actual.to_string());
+            let reason = outcome.reason.unwrap_or_default();
+            return Err(Error::tool_call_failed(format!(
+                "Condition did not hold within {}ms after {attempts} evaluations - last saw \
+                 {last_seen} ({reason})",
+                max_wait.as_millis()
+            ))
+            .into());
+        }
+        tokio::time::sleep(interval).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::tool::ParamStruct;
+
+    fn outcome(passed: bool, actual: u64) -> AssertionOutcome {
+        AssertionOutcome {
+            index: 0,
+            kind: "count".to_string(),
+            passed,
+            actual: Some(json!(actual)),
+            reason: (!passed).then(|| "expected 3".to_string()),
+        }
+    }
+
+    #[tokio::test]
+    async fn returns_the_value_once_the_condition_holds() {
+        let mut seen = 0;
+        let result = wait_until(Duration::from_millis(10), Duration::from_secs(5), || {
+            seen += 1;
+            let current = seen;
+            async move { Ok(outcome(current == 3, current)) }
+        })
+        .await
+        .unwrap_or_else(|e| unreachable!("the condition holds on the third try: {e:?}"));
+
+        assert_eq!(result.attempts, 3);
+        assert_eq!(result.state, Some(json!(3)));
+    }
+
+    #[tokio::test]
+    async fn times_out_with_the_last_value_seen() {
+        let error = wait_until(
+            Duration::from_millis(10),
+            Duration::from_millis(35),
+            || async { Ok(outcome(false, 1)) },
+        )
+        .await
+        .err()
+        .map(|e| e.to_string())
+        .unwrap_or_default();
+
+        assert!(error.contains("did not hold within 35ms"), "{error}");
+        assert!(error.contains("last saw 1 (expected 3)"), "{error}");
+    }
+
+    #[test]
+    fn interval_is_bounded() {
+        let params = |interval_ms: u32| -> WaitForParams {
+            serde_json::from_value(json!({
+                "condition": { "kind": "entity_exists", "entity": 42 },
+                "interval_ms": interval_ms
+            }))
+            .unwrap_or_else(|e| unreachable!("params should deserialize: {e}"))
+        };
+        assert!(params(10).validate().is_ok());
+        assert!(params(5).validate().is_err());
+    }
+}
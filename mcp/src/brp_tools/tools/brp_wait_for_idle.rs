@@ -0,0 +1,235 @@
+//! `brp_wait_for_idle` tool - Wait until the app stops changing
+//!
+//! Polls `brp_extras/activity`, which counts each frame's spawns, despawns, changed transforms
+//! and re-laid-out UI nodes, until `settle_frames` frames in a row stayed at or under
+//! `max_changes_per_frame` and no asset held by the world is still loading - so screenshots and
+//! assertions see a stable world. Running out of `max_wait_ms` isn't an error: the result says
+//! the app never settled and how quiet it was.
+
+use std::time::Duration;
+
+use bevy_brp_mcp_macros::ParamStruct;
+use bevy_brp_mcp_macros::ResultStruct;
+use bevy_brp_mcp_macros::ToolFn;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+use serde_json::json;
+use tokio::time::Instant;
+
+use crate::brp_tools::BrpClient;
+use crate::brp_tools::Port;
+use crate::brp_tools::ResponseStatus;
+use crate::error::Error;
+use crate::error::Result;
+use crate::tool::BrpMethod;
+use crate::tool::HandlerContext;
+use crate::tool::HandlerResult;
+use crate::tool::ToolFn;
+use crate::tool::ToolResult;
+
+/// Default quiet frames in a row that count as idle
+const DEFAULT_SETTLE_FRAMES: u32 = 30;
+
+/// Default time between polls in milliseconds
+const DEFAULT_INTERVAL_MS: u32 = 100;
+
+/// Default time to wait for the app to settle in milliseconds
+const DEFAULT_MAX_WAIT_MS: u32 = 10_000;
+
+/// Parameters for the `brp_wait_for_idle` tool
+#[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
+pub struct WaitForIdleParams {
+    /// Frames in a row that must stay quiet (default: 30, max: 600 - the frames the app keeps)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[param(min = 1, max = 600)]
+    #[schemars(extend("default" = 30))]
+    pub settle_frames: Option<u32>,
+
+    /// Most changes a frame may have and still count as quiet (default: 0) - raise it for apps
+    /// with something always moving, like a spinning loading icon
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schemars(extend("default" = 0))]
+    pub max_changes_per_frame: Option<u64>,
+
+    /// Milliseconds between polls (default: 100ms)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[param(min = 10, max = 60_000)]
+    #[schemars(extend("default" = 100))]
+    pub interval_ms: Option<u32>,
+
+    /// Milliseconds to wait for the app to settle (default: 10000ms, max: 600000ms)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[param(max = 600_000)]
+    #[schemars(extend("default" = 10000))]
+    pub max_wait_ms: Option<u32>,
+
+    /// The BRP port
+    #[serde(default)]
+    pub port: Port,
+}
+
+/// Result for the `brp_wait_for_idle` tool
+#[derive(Debug, Clone, Serialize, Deserialize, ResultStruct)]
+pub struct WaitForIdleResult {
+    /// The last activity report
+    #[to_result(skip_if_none)]
+    activity:         Option<Value>,
+    /// Whether the app settled before `max_wait_ms` elapsed
+    #[to_metadata]
+    idle:             bool,
+    /// Milliseconds waited
+    #[to_metadata]
+    waited_ms:        u64,
+    /// Most recent quiet frames in a row when the wait ended
+    #[to_metadata]
+    quiet_frames:     u64,
+    /// Frames the app had counted when the wait ended
+    #[to_metadata]
+    frame:            u64,
+    /// Assets still loading when the wait ended
+    #[to_metadata]
+    loading_assets:   u64,
+    /// Message template for formatting responses
+    #[to_message]
+    message_template: Option<String>,
+}
+
+#[derive(ToolFn)]
+#[tool_fn(params = "WaitForIdleParams", output = "WaitForIdleResult")]
+pub struct BrpWaitForIdle;
+
+async fn handle_impl(params: WaitForIdleParams) -> Result<WaitForIdleResult> {
+    let settle_frames = u64::from(params.settle_frames.unwrap_or(DEFAULT_SETTLE_FRAMES));
+    let interval =
+        Duration::from_millis(u64::from(params.interval_ms.unwrap_or(DEFAULT_INTERVAL_MS)));
+    let max_wait =
+        Duration::from_millis(u64::from(params.max_wait_ms.unwrap_or(DEFAULT_MAX_WAIT_MS)));
+    let request = json!({ "max_changes": params.max_changes_per_frame.unwrap_or(0) });
+
+    wait_until_idle(settle_frames, interval, max_wait, || {
+        activity(params.port, request.clone())
+    })
+    .await
+}
+
+/// Poll activity reports until `settle_frames` quiet frames in a row with nothing loading, or
+/// until the next poll would pass `max_wait`
+#[allow(clippy::literal_string_with_formatting_args)]
+async fn wait_until_idle<F, Fut>(
+    settle_frames: u64,
+    interval: Duration,
+    max_wait: Duration,
+    mut poll: F,
+) -> Result<WaitForIdleResult>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<Value>>,
+{
+    let started = Instant::now();
+    let deadline = started + max_wait;
+    loop {
+        let activity = poll().await?;
+        let field = |name: &str| activity.get(name).and_then(Value::as_u64).unwrap_or(0);
+        let (quiet_frames, frame) = (field("quiet_frames"), field("frame"));
+        let loading_assets = field("loading_assets");
+        let idle = quiet_frames >= settle_frames && loading_assets == 0;
+        if idle || Instant::now() + interval > deadline {
+            let waited_ms = u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX);
+            let message = if idle {
+                "App idle after {waited_ms}ms ({quiet_frames} quiet frames)"
+            } else if loading_assets > 0 {
+                "App not idle after {waited_ms}ms - {loading_assets} assets still loading"
+            } else {
+                "App not idle after {waited_ms}ms - only {quiet_frames} quiet frames in a row"
+            };
+            return Ok(WaitForIdleResult::new(
+                Some(activity),
+                idle,
+                waited_ms,
+                quiet_frames,
+                frame,
+                loading_assets,
+            )
+            .with_message_template(message));
+        }
+        tokio::time::sleep(interval).await;
+    }
+}
+
+/// Read the app's activity report
+async fn activity(port: Port, params: Value) -> Result<Value> {
+    let client = BrpClient::new(BrpMethod::BrpWaitForIdle, port, Some(params));
+    match client.execute_raw().await? {
+        ResponseStatus::Success(data) => Ok(data.unwrap_or(Value::Null)),
+        ResponseStatus::Error(err) => Err(Error::tool_call_failed(err.get_message()).into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicU64;
+    use std::sync::atomic::Ordering;
+
+    use super::*;
+
+    const INTERVAL: Duration = Duration::from_millis(1);
+
+    /// Poll reports that gain 10 quiet frames each poll, with `loading` assets until poll 3
+    async fn wait(settle_frames: u64, max_wait: Duration, loading: u64) -> WaitForIdleResult {
+        let polls = AtomicU64::new(0);
+        wait_until_idle(settle_frames, INTERVAL, max_wait, || {
+            let poll = polls.fetch_add(1, Ordering::Relaxed) + 1;
+            let loading_assets = if poll < 3 { loading } else { 0 };
+            async move {
+                Ok(json!({
+                    "frame": poll * 10,
+                    "quiet_frames": poll * 10,
+                    "loading_assets": loading_assets,
+                }))
+            }
+        })
+        .await
+        .unwrap_or_else(|error| unreachable!("polling should succeed: {error}"))
+    }
+
+    #[tokio::test]
+    async fn becomes_idle_once_enough_frames_are_quiet() {
+        let result = wait(30, Duration::from_secs(5), 0).await;
+        assert!(result.idle);
+        assert_eq!(result.quiet_frames, 30);
+        assert_eq!(result.frame, 30);
+    }
+
+    #[tokio::test]
+    async fn loading_assets_keep_the_app_busy() {
+        let result = wait(10, Duration::from_secs(5), 2).await;
+        assert!(result.idle);
+        assert_eq!(result.quiet_frames, 30);
+        assert_eq!(result.loading_assets, 0);
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_max_wait() {
+        let result = wait(1_000_000, Duration::from_millis(20), 0).await;
+        assert!(!result.idle);
+        assert!(result.quiet_frames > 0);
+    }
+
+    #[tokio::test]
+    async fn reports_assets_still_loading_when_time_runs_out() {
+        let result = wait(1, Duration::ZERO, 4).await;
+        assert!(!result.idle);
+        assert_eq!(result.loading_assets, 4);
+    }
+
+    #[tokio::test]
+    async fn a_failed_poll_ends_the_wait() {
+        let result = wait_until_idle(1, INTERVAL, Duration::from_secs(5), || async {
+            Err(Error::tool_call_failed("no activity").into())
+        })
+        .await;
+        assert!(result.is_err());
+    }
+}
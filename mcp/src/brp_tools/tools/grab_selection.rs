@@ -2,22 +2,29 @@ use std::env;
 use std::fs;
 use std::path::PathBuf;
 
-use bevy_brp_mcp_macros::{ParamStruct, ResultStruct, ToolFn};
+use bevy_brp_mcp_macros::ParamStruct;
+use bevy_brp_mcp_macros::ResultStruct;
+use bevy_brp_mcp_macros::ToolFn;
 use schemars::JsonSchema;
-use serde::{Deserialize, Serialize};
+use serde::Deserialize;
+use serde::Serialize;
 
 use crate::error::Error;
-use crate::tool::{HandlerContext, HandlerResult, ToolFn, ToolResult};
+use crate::tool::HandlerContext;
+use crate::tool::HandlerResult;
+use crate::tool::ToolFn;
+use crate::tool::ToolResult;
 
 const DEFAULT_SELECTION_PATH: &str = "target/ai-selection/selection.json";
 const ENV_SELECTION_PATH: &str = "BRP_GRAB_SELECTION_PATH";
 
-fn default_true() -> bool { true }
+const fn default_true() -> bool { true }
 
 /// Parameters for the `grab.selection` tool
 #[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
 pub struct GrabSelectionParams {
-    /// Optional override path to the selection JSON (defaults to `target/ai-selection/selection.json` or `BRP_GRAB_SELECTION_PATH` env var)
+    /// Optional override path to the selection JSON (defaults to
+    /// `target/ai-selection/selection.json` or `BRP_GRAB_SELECTION_PATH` env var)
     #[serde(default)]
     pub path: Option<String>,
 
@@ -52,16 +59,16 @@ pub struct GrabSelectionResult {
 
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct SelectionData {
-    pub entity: EntitySummary,
+    pub entity:    EntitySummary,
     #[serde(default)]
     pub hierarchy: Vec<String>,
-    pub cursor: Option<CursorSummary>,
-    pub target: SelectionTargetSummary,
+    pub cursor:    Option<CursorSummary>,
+    pub target:    SelectionTargetSummary,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct EntitySummary {
-    pub id: u32,
+    pub id:   u32,
     pub name: Option<String>,
 }
 
@@ -80,8 +87,8 @@ pub enum SelectionTargetSummary {
     },
     World {
         position: [f32; 3],
-        bounds: Option<BoundsSummary>,
-        mesh: Option<String>,
+        bounds:   Option<BoundsSummary>,
+        mesh:     Option<String>,
     },
 }
 
@@ -89,7 +96,7 @@ pub enum SelectionTargetSummary {
 pub struct RectSummary {
     pub min: [f32; 2],
     pub max: [f32; 2],
-    pub z: f32,
+    pub z:   f32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
@@ -100,7 +107,7 @@ pub struct BoundsSummary {
 
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 struct SelectionSummaryFile {
-    pub enabled: bool,
+    pub enabled:   bool,
     pub selection: Option<SelectionData>,
 }
 
@@ -114,21 +121,23 @@ async fn handle_impl(params: GrabSelectionParams) -> crate::error::Result<GrabSe
 
     if !path.exists() {
         if params.fail_if_absent {
-            return Err(Error::missing(&format!(
-                "grab selection file at {}",
-                path.display()
-            ))
-            .into());
+            return Err(
+                Error::missing(&format!("grab selection file at {}", path.display())).into(),
+            );
         }
 
-        return Ok(GrabSelectionResult::new(path.display().to_string(), false, None));
+        return Ok(GrabSelectionResult::new(
+            path.display().to_string(),
+            false,
+            None,
+        ));
     }
 
     let contents = fs::read_to_string(&path)
         .map_err(|e| Error::io_failed("read grab selection file", &path, &e))?;
 
-    let summary: SelectionSummaryFile = serde_json::from_str(&contents)
-        .map_err(|e| Error::failed_to("parse grab selection", e))?;
+    let summary: SelectionSummaryFile =
+        serde_json::from_str(&contents).map_err(|e| Error::failed_to("parse grab selection", e))?;
 
     if params.require_enabled && !summary.enabled {
         return Err(Error::invalid("enabled", "selection capture is disabled").into());
@@ -146,25 +155,27 @@ fn resolve_path(arg: Option<&str>) -> PathBuf {
         return PathBuf::from(p);
     }
 
-    if let Ok(env_path) = env::var(ENV_SELECTION_PATH) {
-        if !env_path.is_empty() {
-            return PathBuf::from(env_path);
-        }
+    if let Ok(env_path) = env::var(ENV_SELECTION_PATH)
+        && !env_path.is_empty()
+    {
+        return PathBuf::from(env_path);
     }
 
     PathBuf::from(DEFAULT_SELECTION_PATH)
 }
 
 #[cfg(test)]
+#[allow(clippy::expect_used, clippy::float_cmp)]
 mod tests {
-    use super::*;
     use futures::executor::block_on;
     use tempfile::TempDir;
 
+    use super::*;
+
     fn write_file(dir: &TempDir, name: &str, body: &str) -> PathBuf {
         let path = dir.path().join(name);
-        fs::create_dir_all(path.parent().unwrap()).unwrap();
-        fs::write(&path, body).unwrap();
+        fs::create_dir_all(path.parent().expect("file has a parent")).expect("create parent dirs");
+        fs::write(&path, body).expect("write selection file");
         path
     }
 
@@ -176,7 +187,7 @@ mod tests {
 
     #[test]
     fn parses_ui_selection() {
-        let dir = TempDir::new().unwrap();
+        let dir = TempDir::new().expect("temp dir");
         let json = r#"{
   "enabled": true,
   "selection": {
@@ -193,30 +204,28 @@ mod tests {
         let path = write_file(&dir, "target/ai-selection/selection.json", json);
 
         let result = block_on(handle_impl(GrabSelectionParams {
-            path: Some(path.to_string_lossy().to_string()),
+            path:            Some(path.to_string_lossy().to_string()),
             require_enabled: false,
-            fail_if_absent: true,
+            fail_if_absent:  true,
         }))
-        .unwrap();
+        .expect("selection result");
 
         assert!(result.enabled);
-        let sel = result.selection.unwrap();
+        let sel = result.selection.expect("selection present");
         assert_eq!(sel.entity.id, 1);
         assert_eq!(sel.hierarchy, vec!["Root", "Button"]);
-        match sel.target {
-            SelectionTargetSummary::Ui { rect, text } => {
-                assert_eq!(rect.min, [0.0, 0.0]);
-                assert_eq!(rect.max, [100.0, 50.0]);
-                assert_eq!(rect.z, 1.0);
-                assert_eq!(text.as_deref(), Some("Click"));
-            },
-            _ => panic!("expected ui target"),
-        }
+        let SelectionTargetSummary::Ui { rect, text } = sel.target else {
+            unreachable!("expected ui target");
+        };
+        assert_eq!(rect.min, [0.0, 0.0]);
+        assert_eq!(rect.max, [100.0, 50.0]);
+        assert_eq!(rect.z, 1.0);
+        assert_eq!(text.as_deref(), Some("Click"));
     }
 
     #[test]
     fn world_selection_bounds_optional() {
-        let dir = TempDir::new().unwrap();
+        let dir = TempDir::new().expect("temp dir");
         let json = r#"{
   "enabled": false,
   "selection": {
@@ -234,35 +243,38 @@ mod tests {
         let path = write_file(&dir, "sel.json", json);
 
         let result = block_on(handle_impl(GrabSelectionParams {
-            path: Some(path.to_string_lossy().to_string()),
+            path:            Some(path.to_string_lossy().to_string()),
             require_enabled: false,
-            fail_if_absent: true,
+            fail_if_absent:  true,
         }))
-        .unwrap();
+        .expect("selection result");
 
         assert!(!result.enabled);
-        let sel = result.selection.unwrap();
-        match sel.target {
-            SelectionTargetSummary::World { position, bounds, mesh } => {
-                assert_eq!(position, [1.0, 2.0, 3.0]);
-                assert!(bounds.is_none());
-                assert_eq!(mesh.as_deref(), Some("mesh-123"));
-            },
-            _ => panic!("expected world target"),
-        }
+        let sel = result.selection.expect("selection present");
+        let SelectionTargetSummary::World {
+            position,
+            bounds,
+            mesh,
+        } = sel.target
+        else {
+            unreachable!("expected world target");
+        };
+        assert_eq!(position, [1.0, 2.0, 3.0]);
+        assert!(bounds.is_none());
+        assert_eq!(mesh.as_deref(), Some("mesh-123"));
     }
 
     #[test]
     fn missing_file_allowed_when_flag_false() {
-        let dir = TempDir::new().unwrap();
+        let dir = TempDir::new().expect("temp dir");
         let missing = dir.path().join("nope.json");
 
         let result = block_on(handle_impl(GrabSelectionParams {
-            path: Some(missing.to_string_lossy().to_string()),
+            path:            Some(missing.to_string_lossy().to_string()),
             require_enabled: false,
-            fail_if_absent: false,
+            fail_if_absent:  false,
         }))
-        .unwrap();
+        .expect("selection result");
 
         assert!(!result.enabled);
         assert!(result.selection.is_none());
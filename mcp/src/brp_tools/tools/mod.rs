@@ -1,17 +1,59 @@
 //! Individual tool modules containing parameter and result structs for each BRP tool
 
+pub mod brp_alias_entity;
+pub mod brp_assert;
 pub mod brp_execute;
+pub mod brp_extras_app_info;
+pub mod brp_extras_apply_commands;
+pub mod brp_extras_capabilities;
+pub mod brp_extras_clipboard;
+pub mod brp_extras_collect_frame_stats;
+pub mod brp_extras_determinism;
+pub mod brp_extras_ensure_registered;
+pub mod brp_extras_find_tagged;
+pub mod brp_extras_get_history;
+pub mod brp_extras_get_interaction_state;
+pub mod brp_extras_get_ui_text;
+pub mod brp_extras_gizmo_config;
+pub mod brp_extras_list_monitors;
+pub mod brp_extras_material;
+pub mod brp_extras_pin_entity;
+pub mod brp_extras_query_stream;
+pub mod brp_extras_read_pixels;
+pub mod brp_extras_schedule_report;
 pub mod brp_extras_screenshot;
+pub mod brp_extras_screenshot_status;
 pub mod brp_extras_send_keys;
+pub mod brp_extras_set_window;
 pub mod brp_extras_set_window_title;
+pub mod brp_extras_tag_entity;
+pub mod brp_extras_time_config;
+pub mod brp_extras_track_archetype_moves;
+pub mod brp_extras_track_entity;
+pub mod brp_fuzz_component;
+pub mod brp_list_corrections;
+pub mod brp_map_entities;
+pub mod brp_refresh_type_cache;
+pub mod brp_request_confirmation;
+pub mod brp_reset_circuit;
+pub mod brp_set_default_port;
+pub mod brp_set_strict_formats;
+pub mod brp_wait_for;
+pub mod brp_wait_for_idle;
 pub mod grab_selection;
 pub mod registry_schema;
 pub mod rpc_discover;
+pub mod world_changes_since;
+pub mod world_component_matrix;
 pub mod world_despawn_entity;
+pub mod world_dump_resources;
+pub mod world_export_graph;
+pub mod world_field_histogram;
 pub mod world_get_components;
 pub mod world_get_resources;
 pub mod world_insert_components;
 pub mod world_insert_resources;
+pub mod world_join_query;
 pub mod world_list_components;
 pub mod world_list_resources;
 pub mod world_mutate_components;
@@ -21,3 +63,4 @@ pub mod world_remove_components;
 pub mod world_remove_resources;
 pub mod world_reparent_entities;
 pub mod world_spawn_entity;
+pub mod world_undo_despawn;
@@ -1,13 +1,45 @@
 //! `registry.schema` tool - Get type schemas
+//!
+//! With `output_path` the full schema is always written to that file and only summary counts are
+//! returned inline, regardless of the response size that would otherwise trigger the generic
+//! large-response handling.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
 
 use bevy_brp_mcp_macros::ParamStruct;
 use bevy_brp_mcp_macros::ResultStruct;
+use bevy_brp_mcp_macros::ToolFn;
+use error_stack::ResultExt;
 use schemars::JsonSchema;
 use serde::Deserialize;
 use serde::Serialize;
 use serde_json::Value;
 
+use crate::brp_tools::BrpClient;
 use crate::brp_tools::Port;
+use crate::brp_tools::ResponseStatus;
+use crate::error::Error;
+use crate::error::Result;
+use crate::json_object::JsonObjectAccess;
+use crate::json_schema::SchemaField;
+use crate::tool::BrpMethod;
+use crate::tool::HandlerContext;
+use crate::tool::HandlerResult;
+use crate::tool::ToolFn;
+use crate::tool::ToolResult;
+
+/// How the schema file written to `output_path` is formatted
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    /// Indented JSON, easy to read and diff
+    #[default]
+    Pretty,
+    /// Single-line JSON, smallest file
+    Compact,
+}
 
 /// Parameters for the `registry.schema` tool
 #[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
@@ -28,41 +60,171 @@ pub struct RegistrySchemaParams {
     #[serde(default)]
     pub without_types: Vec<String>,
 
-    /// The BRP port (default: 15702)
+    /// Write the full schema to this file and return only summary counts (parent directories
+    /// are created as needed)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_path: Option<String>,
+
+    /// Formatting of the file written to `output_path` (default: pretty)
+    #[serde(default)]
+    pub output_format: ExportFormat,
+
+    /// If true, always ask the app instead of answering from the short-lived read cache
+    /// (default: false)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub no_cache: Option<bool>,
+
+    /// The BRP port
     #[serde(default)]
     pub port: Port,
 }
 
+impl RegistrySchemaParams {
+    /// Filters forwarded to `registry.schema` - the port, cache and export options stay local
+    fn brp_params(&self) -> Result<Option<Value>> {
+        let mut brp_params = BrpClient::prepare_params(self)?;
+        if let Some(Value::Object(map)) = &mut brp_params {
+            map.remove("output_path");
+            map.remove("output_format");
+            map.remove("no_cache");
+        }
+        Ok(brp_params)
+    }
+}
+
 /// Result for the `registry.schema` tool
 #[derive(Serialize, ResultStruct)]
-#[brp_result]
 pub struct RegistrySchemaResult {
-    /// The raw BRP response - array of type schemas
+    /// The raw BRP response - omitted when the schema was written to `output_path`
     #[serde(skip_serializing_if = "Option::is_none")]
     #[to_result(skip_if_none)]
     pub result: Option<Value>,
 
     /// Count of types returned
-    #[to_metadata(result_operation = "count")]
+    #[to_metadata]
     pub type_count: usize,
 
+    /// Count of types per schema kind (`Struct`, `Enum`, ...) when exported to a file
+    #[to_metadata(skip_if_none)]
+    pub kind_counts: Option<BTreeMap<String, usize>>,
+
+    /// File the full schema was written to
+    #[to_metadata(skip_if_none)]
+    pub output_path: Option<String>,
+
+    /// Size of the written file in bytes
+    #[to_metadata(skip_if_none)]
+    pub bytes_written: Option<usize>,
+
     /// Message template for formatting responses
-    #[to_message(message_template = "Retrieved {type_count} schemas")]
-    pub message_template: String,
+    #[to_message]
+    pub message_template: Option<String>,
+}
+
+#[derive(ToolFn)]
+#[tool_fn(params = "RegistrySchemaParams", output = "RegistrySchemaResult")]
+pub struct RegistrySchema;
+
+#[allow(clippy::literal_string_with_formatting_args)]
+async fn handle_impl(params: RegistrySchemaParams) -> Result<RegistrySchemaResult> {
+    let client = BrpClient::new(BrpMethod::RegistrySchema, params.port, params.brp_params()?);
+
+    let schemas = match client
+        .execute_cached(params.no_cache.unwrap_or_default())
+        .await?
+    {
+        ResponseStatus::Success(data) => data,
+        ResponseStatus::Error(err) => {
+            return Err(Error::tool_call_failed(err.get_message()).into());
+        },
+    };
+    let type_count = count_types(schemas.as_ref());
+
+    let Some(output_path) = params.output_path else {
+        return Ok(
+            RegistrySchemaResult::new(schemas, type_count, None, None, None)
+                .with_message_template("Retrieved {type_count} schemas"),
+        );
+    };
+
+    let schemas = schemas.unwrap_or(Value::Null);
+    let bytes_written = write_schemas(Path::new(&output_path), &schemas, params.output_format)?;
+
+    Ok(RegistrySchemaResult::new(
+        None,
+        type_count,
+        Some(count_kinds(&schemas)),
+        Some(output_path),
+        Some(bytes_written),
+    )
+    .with_message_template("Exported {type_count} schemas to {output_path}"))
+}
+
+/// Number of types in a `registry.schema` response (an object keyed by type path, or an array
+/// from older Bevy releases)
+fn count_types(schemas: Option<&Value>) -> usize {
+    match schemas {
+        Some(Value::Object(map)) => map.len(),
+        Some(Value::Array(items)) => items.len(),
+        _ => 0,
+    }
+}
+
+/// Number of types per schema `kind`
+fn count_kinds(schemas: &Value) -> BTreeMap<String, usize> {
+    let entries: Vec<&Value> = match schemas {
+        Value::Object(map) => map.values().collect(),
+        Value::Array(items) => items.iter().collect(),
+        _ => Vec::new(),
+    };
+
+    let mut kind_counts = BTreeMap::new();
+    for entry in entries {
+        let kind = entry.get_field_str(SchemaField::Kind).unwrap_or("Unknown");
+        *kind_counts.entry(kind.to_string()).or_insert(0) += 1;
+    }
+    kind_counts
+}
+
+/// Write `schemas` to `path`, returning the number of bytes written
+fn write_schemas(path: &Path, schemas: &Value, format: ExportFormat) -> Result<usize> {
+    let json = match format {
+        ExportFormat::Pretty => serde_json::to_string_pretty(schemas),
+        ExportFormat::Compact => serde_json::to_string(schemas),
+    }
+    .change_context(Error::General(
+        "Failed to serialize registry schema".to_string(),
+    ))?;
+
+    if let Some(parent) = path.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        fs::create_dir_all(parent).change_context(Error::FileOperation(format!(
+            "Failed to create directory {}",
+            parent.display()
+        )))?;
+    }
+
+    fs::write(path, &json).change_context(Error::FileOperation(format!(
+        "Failed to write registry schema to {}",
+        path.display()
+    )))?;
+
+    Ok(json.len())
 }
 
 #[cfg(test)]
+#[allow(clippy::expect_used)]
 mod tests {
-    use super::RegistrySchemaParams;
     use schemars::schema_for;
 
+    use super::RegistrySchemaParams;
+
     #[test]
     fn registry_schema_params_arrays_are_non_nullable() {
         let schema = schema_for!(RegistrySchemaParams);
         let value = serde_json::to_value(&schema).expect("serialize schema");
 
-        println!("{}", serde_json::to_string_pretty(&value).unwrap());
-
         let props = value
             .get("properties")
             .and_then(|p| p.as_object())
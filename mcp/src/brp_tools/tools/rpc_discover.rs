@@ -12,7 +12,12 @@ use crate::brp_tools::Port;
 /// Parameters for the `rpc.discover` tool
 #[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
 pub struct RpcDiscoverParams {
-    /// The BRP port (default: 15702)
+    /// If true, always ask the app instead of answering from the short-lived read cache
+    /// (default: false)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub no_cache: Option<bool>,
+
+    /// The BRP port
     #[serde(default)]
     pub port: Port,
 }
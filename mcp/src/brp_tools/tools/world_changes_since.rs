@@ -0,0 +1,274 @@
+//! `world_changes_since` tool - Entities whose queried components changed since a checkpoint
+//!
+//! Runs a `world.query` and compares each row with the fingerprint saved by an earlier call,
+//! returning only entities that appeared, disappeared, or have a component value that differs.
+//! Every call saves a new checkpoint of the rows it saw, so an agent polling the world passes
+//! the previous call's `checkpoint` and gets just the delta.
+
+use std::collections::BTreeMap;
+
+use bevy_brp_mcp_macros::ParamStruct;
+use bevy_brp_mcp_macros::ResultStruct;
+use bevy_brp_mcp_macros::ToolFn;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Map;
+use serde_json::Value;
+use serde_json::json;
+
+use super::world_export_graph::QUERY_COMPONENTS_FIELD;
+use super::world_query::BrpQuery;
+use super::world_query::BrpQueryFilter;
+use crate::brp_tools::BrpClient;
+use crate::brp_tools::Port;
+use crate::brp_tools::ResponseStatus;
+use crate::brp_tools::change_checkpoints;
+use crate::brp_tools::change_checkpoints::Fingerprint;
+use crate::error::Error;
+use crate::error::Result;
+use crate::json_object::JsonObjectAccess;
+use crate::tool::BrpMethod;
+use crate::tool::HandlerContext;
+use crate::tool::HandlerResult;
+use crate::tool::ParameterName;
+use crate::tool::ToolFn;
+use crate::tool::ToolResult;
+
+/// Parameters for the `world_changes_since` tool
+#[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
+pub struct ChangesSinceParams {
+    /// Components to track, as for `world_query`: {components: string[], option: "all" |
+    /// string[]}. Every returned component value is compared.
+    pub data:       BrpQuery,
+    /// Which entities to track, as for `world_query`: {with: string[], without: string[]}
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filter:     Option<BrpQueryFilter>,
+    /// `checkpoint` from an earlier call with the same `data` and `filter` - omit it to get every
+    /// entity as added and a first checkpoint
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checkpoint: Option<String>,
+    /// The BRP port
+    #[serde(default)]
+    pub port:       Port,
+}
+
+/// An entity that appeared or changed
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ChangedEntity {
+    /// Entity ID
+    pub entity:             u64,
+    /// Current values of the components that are new or differ - all of them for added entities
+    pub components:         Map<String, Value>,
+    /// Tracked components the entity no longer has
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub removed_components: Vec<String>,
+}
+
+/// What changed among the queried entities
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct Changes {
+    /// Entities the query didn't return at the checkpoint
+    pub added:   Vec<ChangedEntity>,
+    /// Entities with component values that differ from the checkpoint
+    pub changed: Vec<ChangedEntity>,
+    /// Entities the query returned at the checkpoint but no longer does - despawned, or no
+    /// longer matching
+    pub removed: Vec<u64>,
+}
+
+/// Result for the `world_changes_since` tool
+#[derive(Debug, Clone, Serialize, Deserialize, ResultStruct)]
+pub struct ChangesSinceResult {
+    /// Added, changed and removed entities
+    #[to_result]
+    changes:          Changes,
+    /// Checkpoint of the rows seen now - pass it as `checkpoint` to the next call
+    #[to_metadata]
+    checkpoint:       String,
+    /// The checkpoint compared against
+    #[to_metadata(skip_if_none)]
+    since:            Option<String>,
+    /// Entities the query returns now
+    #[to_metadata]
+    entity_count:     usize,
+    /// Number of added entities
+    #[to_metadata]
+    added_count:      usize,
+    /// Number of changed entities
+    #[to_metadata]
+    changed_count:    usize,
+    /// Number of removed entities
+    #[to_metadata]
+    removed_count:    usize,
+    /// Message template for formatting responses
+    #[to_message(
+        message_template = "{changed_count} changed, {added_count} added and {removed_count} removed of {entity_count} entities - next checkpoint {checkpoint}"
+    )]
+    message_template: String,
+}
+
+#[derive(ToolFn)]
+#[tool_fn(params = "ChangesSinceParams", output = "ChangesSinceResult")]
+pub struct WorldChangesSince;
+
+async fn handle_impl(params: ChangesSinceParams) -> Result<ChangesSinceResult> {
+    let port = params.port;
+    let filter = params.filter.unwrap_or_default();
+    // Checkpoints are only comparable between calls tracking the same rows
+    let query = json!({ "data": params.data, "filter": filter }).to_string();
+    let previous = match &params.checkpoint {
+        Some(token) => Some(change_checkpoints::load(token, port, &query)?),
+        None => None,
+    };
+
+    let request = json!({
+        ParameterName::Data: params.data,
+        ParameterName::Filter: filter,
+    });
+    let rows = match BrpClient::new(BrpMethod::WorldQuery, port, Some(request))
+        .execute_raw()
+        .await?
+    {
+        ResponseStatus::Success(Some(Value::Array(rows))) => rows,
+        ResponseStatus::Success(_) => Vec::new(),
+        ResponseStatus::Error(err) => return Err(Error::tool_call_failed(err.get_message()).into()),
+    };
+
+    let current = current_rows(&rows);
+    let fingerprint: Fingerprint = current
+        .iter()
+        .map(|(&entity, components)| {
+            (
+                entity,
+                change_checkpoints::fingerprint_components(components),
+            )
+        })
+        .collect();
+    let changes = diff(previous.as_ref(), current);
+
+    let entity_count = fingerprint.len();
+    let checkpoint = change_checkpoints::save(port, query, fingerprint);
+    let (added_count, changed_count, removed_count) = (
+        changes.added.len(),
+        changes.changed.len(),
+        changes.removed.len(),
+    );
+    Ok(ChangesSinceResult::new(
+        changes,
+        checkpoint,
+        params.checkpoint,
+        entity_count,
+        added_count,
+        changed_count,
+        removed_count,
+    ))
+}
+
+/// Component values of every returned entity, by entity ID
+fn current_rows(rows: &[Value]) -> BTreeMap<u64, Map<String, Value>> {
+    rows.iter()
+        .filter_map(|row| {
+            let entity = row
+                .get_field(ParameterName::Entity)
+                .and_then(Value::as_u64)?;
+            let components = row
+                .get(QUERY_COMPONENTS_FIELD)
+                .and_then(Value::as_object)
+                .cloned()
+                .unwrap_or_default();
+            Some((entity, components))
+        })
+        .collect()
+}
+
+/// Compare the current rows with the checkpoint - every row is added without one
+fn diff(previous: Option<&Fingerprint>, current: BTreeMap<u64, Map<String, Value>>) -> Changes {
+    let mut changes = Changes::default();
+    for (entity, components) in &current {
+        let Some(before) = previous.and_then(|previous| previous.get(entity)) else {
+            changes.added.push(ChangedEntity {
+                entity:             *entity,
+                components:         components.clone(),
+                removed_components: Vec::new(),
+            });
+            continue;
+        };
+
+        let now = change_checkpoints::fingerprint_components(components);
+        let changed_components: Map<String, Value> = components
+            .iter()
+            .filter(|(type_path, _)| before.get(*type_path) != now.get(*type_path))
+            .map(|(type_path, value)| (type_path.clone(), value.clone()))
+            .collect();
+        let removed_components: Vec<String> = before
+            .keys()
+            .filter(|type_path| !components.contains_key(*type_path))
+            .cloned()
+            .collect();
+        if !changed_components.is_empty() || !removed_components.is_empty() {
+            changes.changed.push(ChangedEntity {
+                entity: *entity,
+                components: changed_components,
+                removed_components,
+            });
+        }
+    }
+
+    if let Some(previous) = previous {
+        changes.removed = previous
+            .keys()
+            .filter(|entity| !current.contains_key(*entity))
+            .copied()
+            .collect();
+        changes.removed.sort_unstable();
+    }
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rows(value: Value) -> BTreeMap<u64, Map<String, Value>> {
+        current_rows(value.as_array().map_or(&[][..], Vec::as_slice))
+    }
+
+    #[test]
+    fn reports_only_entities_that_differ_from_the_checkpoint() {
+        let before = rows(json!([
+            {"entity": 1, "components": {"Health": 10, "Name": "a"}},
+            {"entity": 2, "components": {"Health": 5}},
+            {"entity": 3, "components": {"Health": 7, "Shield": 1}},
+        ]));
+        let fingerprint: Fingerprint = before
+            .iter()
+            .map(|(&e, c)| (e, change_checkpoints::fingerprint_components(c)))
+            .collect();
+
+        let changes = diff(
+            Some(&fingerprint),
+            rows(json!([
+                {"entity": 1, "components": {"Health": 10, "Name": "a"}},
+                {"entity": 3, "components": {"Health": 6}},
+                {"entity": 4, "components": {"Health": 1}},
+            ])),
+        );
+
+        assert_eq!(
+            changes.added.iter().map(|e| e.entity).collect::<Vec<_>>(),
+            [4]
+        );
+        assert_eq!(changes.removed, [2]);
+        assert_eq!(changes.changed.len(), 1);
+        let entry = changes.changed.first();
+        assert_eq!(
+            entry.map(|e| Value::Object(e.components.clone())),
+            Some(json!({"Health": 6}))
+        );
+        assert_eq!(
+            entry.map(|e| e.removed_components.clone()),
+            Some(vec!["Shield".to_string()])
+        );
+    }
+}
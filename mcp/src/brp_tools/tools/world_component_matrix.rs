@@ -0,0 +1,340 @@
+//! `world_component_matrix` tool - Which components each entity has, without their values
+//!
+//! Structural questions ("which of these lack a `Collider`?") only need presence, so this asks
+//! `world.query` for `has` flags instead of fetching component data. Without a list of component
+//! types, each requested entity's components are listed with `world.list_components` and the
+//! columns are the union of everything found.
+
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+
+use bevy_brp_mcp_macros::ParamStruct;
+use bevy_brp_mcp_macros::ResultStruct;
+use bevy_brp_mcp_macros::ToolFn;
+use futures::future::join_all;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+use serde_json::json;
+
+use super::world_query::BrpQuery;
+use super::world_query::BrpQueryFilter;
+use crate::brp_tools::BrpClient;
+use crate::brp_tools::Port;
+use crate::brp_tools::ResponseStatus;
+use crate::error::Error;
+use crate::error::Result;
+use crate::json_object::JsonObjectAccess;
+use crate::tool::BrpMethod;
+use crate::tool::HandlerContext;
+use crate::tool::HandlerResult;
+use crate::tool::ParameterName;
+use crate::tool::ToolFn;
+use crate::tool::ToolResult;
+
+/// Field of a `world.query` row holding the presence flags requested via `has`
+const QUERY_HAS_FIELD: &str = "has";
+
+/// Parameters for the `world_component_matrix` tool
+#[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
+pub struct ComponentMatrixParams {
+    /// Component types to check for (the matrix columns). Omit to use every component found on
+    /// the requested `entities`
+    #[serde(default)]
+    pub components: Vec<String>,
+
+    /// Entities to include (the matrix rows). Omit to include every entity matching `filter`
+    #[serde(default)]
+    pub entities: Vec<u64>,
+
+    /// Which entities to include when `entities` is omitted. Structure: {with: string[],
+    /// without: string[]}
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter: Option<BrpQueryFilter>,
+
+    /// The BRP port
+    #[serde(default)]
+    pub port: Port,
+}
+
+/// One entity's row - `has[i]` says whether it has `components[i]`
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct MatrixRow {
+    /// Entity ID
+    pub entity: u64,
+    /// Presence flag per column
+    pub has:    Vec<bool>,
+}
+
+/// Component presence per entity
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ComponentMatrix {
+    /// Component types, in column order
+    pub components:     Vec<String>,
+    /// Number of rows that have each component, in column order
+    pub present_counts: Vec<usize>,
+    /// One row per entity
+    pub rows:           Vec<MatrixRow>,
+}
+
+impl ComponentMatrix {
+    fn new(components: Vec<String>, rows: Vec<MatrixRow>) -> Self {
+        let present_counts = (0..components.len())
+            .map(|column| rows.iter().filter(|row| row.has[column]).count())
+            .collect();
+        Self {
+            components,
+            present_counts,
+            rows,
+        }
+    }
+}
+
+/// Result for the `world_component_matrix` tool
+#[derive(Debug, Clone, Serialize, Deserialize, ResultStruct)]
+pub struct ComponentMatrixResult {
+    /// The presence matrix
+    #[to_result]
+    matrix:           ComponentMatrix,
+    /// Number of rows
+    #[to_metadata]
+    entity_count:     usize,
+    /// Number of columns
+    #[to_metadata]
+    component_count:  usize,
+    /// Requested entities that don't exist (or don't match `filter`)
+    #[to_metadata(skip_if_none)]
+    missing_entities: Option<Vec<u64>>,
+    /// Message template for formatting responses
+    #[to_message(
+        message_template = "Built presence matrix for {entity_count} entities x {component_count} components"
+    )]
+    message_template: String,
+}
+
+#[derive(ToolFn)]
+#[tool_fn(params = "ComponentMatrixParams", output = "ComponentMatrixResult")]
+pub struct WorldComponentMatrix;
+
+async fn handle_impl(params: ComponentMatrixParams) -> Result<ComponentMatrixResult> {
+    let (matrix, missing_entities) = if params.components.is_empty() {
+        if params.entities.is_empty() {
+            return Err(
+                Error::invalid("parameters", "provide `components`, `entities`, or both").into(),
+            );
+        }
+        matrix_from_component_lists(&params.entities, params.port).await?
+    } else {
+        matrix_from_query(&params).await?
+    };
+
+    let entity_count = matrix.rows.len();
+    let component_count = matrix.components.len();
+    let missing_entities = (!missing_entities.is_empty()).then_some(missing_entities);
+
+    Ok(ComponentMatrixResult::new(
+        matrix,
+        entity_count,
+        component_count,
+        missing_entities,
+    ))
+}
+
+/// Ask `world.query` for `has` flags - one request regardless of the number of entities
+async fn matrix_from_query(params: &ComponentMatrixParams) -> Result<(ComponentMatrix, Vec<u64>)> {
+    let query = BrpQuery {
+        has: params.components.clone(),
+        ..BrpQuery::default()
+    };
+    let brp_params = json!({
+        ParameterName::Data: query,
+        ParameterName::Filter: params.filter.clone().unwrap_or_default(),
+    });
+
+    let client = BrpClient::new(BrpMethod::WorldQuery, params.port, Some(brp_params));
+    let rows = match client.execute_raw().await? {
+        ResponseStatus::Success(Some(Value::Array(rows))) => rows,
+        ResponseStatus::Success(_) => Vec::new(),
+        ResponseStatus::Error(err) => return Err(Error::tool_call_failed(err.get_message()).into()),
+    };
+
+    let matrix_rows = rows_from_query(&params.components, &rows);
+    let (matrix_rows, missing_entities) = if params.entities.is_empty() {
+        (matrix_rows, Vec::new())
+    } else {
+        select_requested(matrix_rows, &params.entities)
+    };
+
+    Ok((
+        ComponentMatrix::new(params.components.clone(), matrix_rows),
+        missing_entities,
+    ))
+}
+
+/// Turn `world.query` rows into matrix rows, treating an absent flag as "doesn't have it"
+fn rows_from_query(components: &[String], rows: &[Value]) -> Vec<MatrixRow> {
+    rows.iter()
+        .filter_map(|row| {
+            let entity = row
+                .get_field(ParameterName::Entity)
+                .and_then(Value::as_u64)?;
+            let flags = row.get(QUERY_HAS_FIELD);
+            let has = components
+                .iter()
+                .map(|component| {
+                    flags
+                        .and_then(|flags| flags.get(component))
+                        .and_then(Value::as_bool)
+                        .unwrap_or(false)
+                })
+                .collect();
+            Some(MatrixRow { entity, has })
+        })
+        .collect()
+}
+
+/// Keep only the requested entities, in the order they were requested, and report the ones
+/// the query didn't return
+fn select_requested(rows: Vec<MatrixRow>, entities: &[u64]) -> (Vec<MatrixRow>, Vec<u64>) {
+    let mut by_entity: HashMap<u64, MatrixRow> =
+        rows.into_iter().map(|row| (row.entity, row)).collect();
+    let mut requested_rows = Vec::with_capacity(entities.len());
+    let mut missing_entities = Vec::new();
+    for &entity in entities {
+        match by_entity.remove(&entity) {
+            Some(row) => requested_rows.push(row),
+            None => missing_entities.push(entity),
+        }
+    }
+    (requested_rows, missing_entities)
+}
+
+/// List each entity's components and use their union as the columns
+async fn matrix_from_component_lists(
+    entities: &[u64],
+    port: Port,
+) -> Result<(ComponentMatrix, Vec<u64>)> {
+    let lists = join_all(entities.iter().map(|&entity| async move {
+        let client = BrpClient::new(
+            BrpMethod::WorldListComponents,
+            port,
+            Some(json!({ ParameterName::Entity: entity })),
+        );
+        (entity, client.execute_raw().await)
+    }))
+    .await;
+
+    let mut found = Vec::new();
+    let mut missing_entities = Vec::new();
+    for (entity, response) in lists {
+        match response? {
+            ResponseStatus::Success(Some(Value::Array(types))) => {
+                let types: BTreeSet<String> = types
+                    .iter()
+                    .filter_map(|component| component.as_str().map(String::from))
+                    .collect();
+                found.push((entity, types));
+            },
+            // An entity that doesn't exist is reported, not fatal
+            ResponseStatus::Success(_) | ResponseStatus::Error(_) => missing_entities.push(entity),
+        }
+    }
+
+    Ok((matrix_from_found(found), missing_entities))
+}
+
+/// Build the matrix from each entity's component list, using their union as the columns
+fn matrix_from_found(found: Vec<(u64, BTreeSet<String>)>) -> ComponentMatrix {
+    let components: Vec<String> = found
+        .iter()
+        .flat_map(|(_, types)| types.iter().cloned())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    let rows = found
+        .into_iter()
+        .map(|(entity, types)| MatrixRow {
+            entity,
+            has: components
+                .iter()
+                .map(|component| types.contains(component))
+                .collect(),
+        })
+        .collect();
+
+    ComponentMatrix::new(components, rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(entity: u64, has: &[bool]) -> MatrixRow {
+        MatrixRow {
+            entity,
+            has: has.to_vec(),
+        }
+    }
+
+    #[test]
+    fn query_rows_default_missing_flags_to_absent() {
+        let components = vec!["game::Health".to_string(), "game::Collider".to_string()];
+        let rows = [
+            json!({ "entity": 1, "has": { "game::Health": true, "game::Collider": false } }),
+            json!({ "entity": 2, "has": { "game::Collider": true } }),
+            json!({ "entity": 3 }),
+            json!({ "has": { "game::Health": true } }),
+        ];
+
+        let matrix_rows = rows_from_query(&components, &rows);
+
+        let flags: Vec<(u64, Vec<bool>)> = matrix_rows
+            .into_iter()
+            .map(|row| (row.entity, row.has))
+            .collect();
+        assert_eq!(
+            flags,
+            vec![
+                (1, vec![true, false]),
+                (2, vec![false, true]),
+                (3, vec![false, false]),
+            ]
+        );
+    }
+
+    #[test]
+    fn requested_entities_keep_their_order_and_report_missing_ones() {
+        let rows = vec![row(10, &[true]), row(20, &[false]), row(30, &[true])];
+
+        let (selected, missing) = select_requested(rows, &[30, 99, 10, 30]);
+
+        let entities: Vec<u64> = selected.iter().map(|row| row.entity).collect();
+        assert_eq!(entities, vec![30, 10]);
+        assert_eq!(missing, vec![99, 30]);
+    }
+
+    #[test]
+    fn component_lists_become_a_union_of_sorted_columns() {
+        let found = vec![
+            (
+                1,
+                BTreeSet::from(["b::Sprite".to_string(), "a::Name".to_string()]),
+            ),
+            (
+                2,
+                BTreeSet::from(["a::Name".to_string(), "c::Collider".to_string()]),
+            ),
+        ];
+
+        let matrix = matrix_from_found(found);
+
+        assert_eq!(
+            matrix.components,
+            vec!["a::Name", "b::Sprite", "c::Collider"]
+        );
+        assert_eq!(matrix.rows[0].has, vec![true, true, false]);
+        assert_eq!(matrix.rows[1].has, vec![true, false, true]);
+        assert_eq!(matrix.present_counts, vec![2, 1, 1]);
+    }
+}
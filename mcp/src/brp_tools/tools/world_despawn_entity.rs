@@ -1,13 +1,25 @@
 //! `world.despawn_entity` tool - Despawn entities permanently
+//!
+//! With `preview: true` nothing is despawned - the result reports the descendants and
+//! relationships the despawn would affect.
 
 use bevy_brp_mcp_macros::ParamStruct;
 use bevy_brp_mcp_macros::ResultStruct;
+use bevy_brp_mcp_macros::ToolFn;
 use schemars::JsonSchema;
 use serde::Deserialize;
 use serde::Serialize;
 use serde_json::Value;
 
+use crate::brp_tools::BrpClient;
 use crate::brp_tools::Port;
+use crate::brp_tools::despawn_preview;
+use crate::error::Result;
+use crate::tool::BrpMethod;
+use crate::tool::HandlerContext;
+use crate::tool::HandlerResult;
+use crate::tool::ToolFn;
+use crate::tool::ToolResult;
 
 /// Parameters for the `world.despawn_entity` tool
 #[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
@@ -15,7 +27,22 @@ pub struct DespawnEntityParams {
     /// The entity ID to despawn
     pub entity: u64,
 
-    /// The BRP port (default: 15702)
+    /// If true, first archive the entity and its subtree to a file and return a `restore_token`
+    /// for `world_undo_despawn` (default: false)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub archive: Option<bool>,
+
+    /// If true, despawn nothing and report the descendants that would be despawned and the
+    /// relationships that would lose an end (default: false)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preview: Option<bool>,
+
+    /// Relationship components (fully-qualified type paths) whose links into or out of the
+    /// subtree `preview` reports
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub relationships: Option<Vec<String>>,
+
+    /// The BRP port
     #[serde(default)]
     pub port: Port,
 }
@@ -24,7 +51,8 @@ pub struct DespawnEntityParams {
 #[derive(Serialize, ResultStruct)]
 #[brp_result]
 pub struct DespawnEntityResult {
-    /// The raw BRP response data (empty for despawn)
+    /// The raw BRP response data - empty, or the `restore_token` and archive details when
+    /// `archive` was set, or what would be despawned when `preview` was set
     #[serde(skip_serializing_if = "Option::is_none")]
     #[to_result(skip_if_none)]
     result: Option<Value>,
@@ -33,3 +61,25 @@ pub struct DespawnEntityResult {
     #[to_message(message_template = "Despawned entity {entity}")]
     message_template: String,
 }
+
+#[derive(ToolFn)]
+#[tool_fn(params = "DespawnEntityParams", output = "DespawnEntityResult")]
+pub struct WorldDespawnEntity;
+
+async fn handle_impl(mut params: DespawnEntityParams) -> Result<DespawnEntityResult> {
+    let relationships = params.relationships.take().unwrap_or_default();
+    if params.preview.take() == Some(true) {
+        let preview =
+            despawn_preview::preview(params.port, &[params.entity], &relationships).await?;
+        return Ok(
+            DespawnEntityResult::new(Some(preview)).with_message_template(
+                "Previewed despawn of entity {entity} - nothing was despawned",
+            ),
+        );
+    }
+
+    let brp_params = BrpClient::prepare_params(&params)?;
+    BrpClient::new(BrpMethod::WorldDespawnEntity, params.port, brp_params)
+        .execute::<DespawnEntityResult>()
+        .await
+}
@@ -0,0 +1,323 @@
+//! `world_dump_resources` tool - Every registered resource with its value in one call
+//!
+//! Exploring resources otherwise takes a `world_list_resources` call followed by one
+//! `world_get_resources` call per resource. This reads the resources from the app's type
+//! registry, drops those outside the requested crates and fetches the rest a few at a time.
+//! Resources the registry shows can't be serialized are not requested, and those whose fetch
+//! fails - not inserted in the world, or the request itself failed - are reported with the error
+//! instead of failing the call.
+
+use std::collections::BTreeMap;
+use std::collections::HashSet;
+
+use bevy_brp_mcp_macros::ParamStruct;
+use bevy_brp_mcp_macros::ResultStruct;
+use bevy_brp_mcp_macros::ToolFn;
+use futures::StreamExt;
+use futures::stream;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Map;
+use serde_json::Value;
+use serde_json::json;
+
+use crate::brp_tools::BrpClient;
+use crate::brp_tools::Port;
+use crate::brp_tools::RequestPriority;
+use crate::brp_tools::ResponseStatus;
+use crate::brp_tools::brp_client::registry_schemas;
+use crate::brp_tools::brp_type_guide::TypeKind;
+use crate::error::Result;
+use crate::json_object::JsonObjectAccess;
+use crate::json_schema::SchemaField;
+use crate::tool::BrpMethod;
+use crate::tool::HandlerContext;
+use crate::tool::HandlerResult;
+use crate::tool::ParameterName;
+use crate::tool::ToolFn;
+use crate::tool::ToolResult;
+
+/// Field of a `world.get_resources` response holding the resource value
+const RESOURCE_VALUE_FIELD: &str = "value";
+
+/// Reflected trait marking a registered type as a resource
+const REFLECT_TRAIT_RESOURCE: &str = "Resource";
+
+/// Reflected trait an opaque type needs before BRP can serialize it
+const REFLECT_TRAIT_SERIALIZE: &str = "Serialize";
+
+/// Resources fetched at once - enough to hide latency without flooding the app's BRP queue
+const MAX_CONCURRENT_FETCHES: usize = 8;
+
+/// Deepest field nesting followed when checking that a resource can be serialized
+const MAX_SERIALIZABLE_DEPTH: usize = 32;
+
+/// Reason given for resources the registry shows can't be serialized
+const NOT_SERIALIZABLE: &str =
+    "not serializable - a field's type is opaque and registers no `Serialize`";
+
+/// Parameters for the `world_dump_resources` tool
+#[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
+pub struct DumpResourcesParams {
+    /// Only dump resources from these crates, e.g. `["my_game", "bevy_time"]` - omit for all
+    #[serde(default)]
+    pub include_crates: Vec<String>,
+
+    /// Skip resources from these crates, e.g. `["bevy_render"]` - applied after `include_crates`
+    #[serde(default)]
+    pub exclude_crates: Vec<String>,
+
+    /// The BRP port
+    #[serde(default)]
+    pub port: Port,
+}
+
+/// Result for the `world_dump_resources` tool
+#[derive(Debug, Clone, Serialize, Deserialize, ResultStruct)]
+pub struct DumpResourcesResult {
+    /// Value of each fetched resource, by type path
+    #[to_result]
+    resources:        BTreeMap<String, Value>,
+    /// Number of resources fetched
+    #[to_metadata]
+    resource_count:   usize,
+    /// Resources whose value couldn't be fetched, with the app's error
+    #[to_metadata(skip_if_none)]
+    unavailable:      Option<BTreeMap<String, String>>,
+    /// Number of registered resources left out by the crate filters
+    #[to_metadata]
+    filtered_out:     usize,
+    /// Message template for formatting responses
+    #[to_message(message_template = "Dumped {resource_count} resources")]
+    message_template: String,
+}
+
+#[derive(ToolFn)]
+#[tool_fn(params = "DumpResourcesParams", output = "DumpResourcesResult")]
+pub struct WorldDumpResources;
+
+async fn handle_impl(params: DumpResourcesParams) -> Result<DumpResourcesResult> {
+    let port = params.port;
+    let schemas = registry_schemas(port).await?;
+    let listed: Vec<&String> = schemas
+        .iter()
+        .filter(|(_, schema)| has_reflect_trait(schema, REFLECT_TRAIT_RESOURCE))
+        .map(|(type_path, _)| type_path)
+        .collect();
+
+    let mut unavailable = BTreeMap::new();
+    let mut selected = Vec::new();
+    let mut filtered_out = 0;
+    for type_path in listed {
+        if !is_selected(type_path, &params.include_crates, &params.exclude_crates) {
+            filtered_out += 1;
+        } else if is_serializable(&schemas, type_path) {
+            selected.push(type_path.clone());
+        } else {
+            unavailable.insert(type_path.clone(), NOT_SERIALIZABLE.to_string());
+        }
+    }
+
+    // A dump is many requests - let interactive calls to the app go first, and keep only a few
+    // of ours in flight so they don't crowd the app's BRP queue
+    let responses: Vec<_> = stream::iter(selected)
+        .map(|resource| async move {
+            let client = BrpClient::new(
+                BrpMethod::WorldGetResources,
+                port,
+                Some(json!({ ParameterName::Resource: resource })),
+            )
+            .with_priority(RequestPriority::Background);
+            (resource, client.execute_raw().await)
+        })
+        .buffer_unordered(MAX_CONCURRENT_FETCHES)
+        .collect()
+        .await;
+
+    let mut resources = BTreeMap::new();
+    for (resource, response) in responses {
+        match response {
+            Ok(ResponseStatus::Success(Some(Value::Object(mut response)))) => {
+                let value = response
+                    .remove(RESOURCE_VALUE_FIELD)
+                    .unwrap_or(Value::Object(response));
+                resources.insert(resource, value);
+            },
+            Ok(ResponseStatus::Success(Some(value))) => {
+                resources.insert(resource, value);
+            },
+            Ok(ResponseStatus::Success(None)) => {
+                unavailable.insert(resource, "the app returned no value".to_string());
+            },
+            Ok(ResponseStatus::Error(err)) => {
+                unavailable.insert(resource, err.get_message().to_string());
+            },
+            Err(report) => {
+                unavailable.insert(resource, report.current_context().to_string());
+            },
+        }
+    }
+
+    let resource_count = resources.len();
+    Ok(DumpResourcesResult::new(
+        resources,
+        resource_count,
+        (!unavailable.is_empty()).then_some(unavailable),
+        filtered_out,
+    ))
+}
+
+/// Whether a registry schema lists `reflect_trait` among its reflected traits
+fn has_reflect_trait(schema: &Value, reflect_trait: &str) -> bool {
+    schema
+        .get_field_array(SchemaField::ReflectTypes)
+        .is_some_and(|traits| {
+            traits
+                .iter()
+                .any(|name| name.as_str() == Some(reflect_trait))
+        })
+}
+
+/// Whether BRP can serialize a value of `type_path` - every opaque type it holds must register
+/// `Serialize`, since reflection can't look inside them
+fn is_serializable(schemas: &Map<String, Value>, type_path: &str) -> bool {
+    let mut checking = HashSet::new();
+    type_serializable(schemas, type_path, &mut checking, 0)
+}
+
+/// `is_serializable` for a type reached at `depth` - a type already being checked further up is
+/// taken as serializable, its other fields decide
+fn type_serializable<'a>(
+    schemas: &'a Map<String, Value>,
+    type_path: &'a str,
+    checking: &mut HashSet<&'a str>,
+    depth: usize,
+) -> bool {
+    if depth > MAX_SERIALIZABLE_DEPTH {
+        return false;
+    }
+    let Some(schema) = schemas.get(type_path) else {
+        return false;
+    };
+    if has_reflect_trait(schema, REFLECT_TRAIT_SERIALIZE) || checking.contains(type_path) {
+        return true;
+    }
+    if TypeKind::from_schema(schema) == TypeKind::Value {
+        return false;
+    }
+    checking.insert(type_path);
+    let serializable = field_types(schema).into_iter().all(|field| {
+        field.extract_field_type().is_some_and(|field_type| {
+            let Some((field_type, _)) = schemas.get_key_value(field_type.as_str()) else {
+                return false;
+            };
+            type_serializable(schemas, field_type, checking, depth + 1)
+        })
+    });
+    checking.remove(type_path);
+    serializable
+}
+
+/// The field schemas of a type's fields, elements, entries or variant payloads
+fn field_types(schema: &Value) -> Vec<&Value> {
+    let mut fields: Vec<&Value> = Vec::new();
+    if let Some(properties) = schema.get_properties() {
+        fields.extend(properties.values());
+    }
+    if let Some(items) = schema.get_field_array(SchemaField::PrefixItems) {
+        fields.extend(items);
+    }
+    for field in [
+        SchemaField::Items,
+        SchemaField::KeyType,
+        SchemaField::ValueType,
+    ] {
+        fields.extend(schema.get_field(field));
+    }
+    if let Some(variants) = schema.get_field_array(SchemaField::OneOf) {
+        fields.extend(variants.iter().flat_map(field_types));
+    }
+    fields
+}
+
+/// Whether a resource passes the crate filters - its crate is the first segment of its path
+fn is_selected(type_path: &str, include_crates: &[String], exclude_crates: &[String]) -> bool {
+    let crate_name = type_path.split("::").next().unwrap_or(type_path);
+    let included =
+        include_crates.is_empty() || include_crates.iter().any(|name| name == crate_name);
+    included && !exclude_crates.iter().any(|name| name == crate_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn filters_by_crate() {
+        let include = vec!["my_game".to_string(), "bevy_time".to_string()];
+        let exclude = vec!["bevy_time".to_string()];
+        assert!(is_selected("my_game::Score", &include, &exclude));
+        assert!(!is_selected(
+            "bevy_time::time::Time<bevy_time::real::Real>",
+            &include,
+            &exclude
+        ));
+        assert!(!is_selected(
+            "bevy_render::camera::ClearColor",
+            &include,
+            &[]
+        ));
+        assert!(is_selected(
+            "bevy_render::camera::ClearColor",
+            &[],
+            &exclude
+        ));
+    }
+
+    #[test]
+    fn opaque_fields_need_serialize() {
+        let schemas = json!({
+            "my_game::Score": {
+                "kind": "Struct",
+                "reflectTypes": ["Resource"],
+                "properties": {
+                    "value": {"type": {"$ref": "#/$defs/u32"}},
+                    "best": {"type": {"$ref": "#/$defs/core::option::Option<u32>"}}
+                }
+            },
+            "my_game::Timer": {
+                "kind": "TupleStruct",
+                "reflectTypes": ["Resource"],
+                "prefixItems": [{"type": {"$ref": "#/$defs/std::time::Instant"}}]
+            },
+            "my_game::Tree": {
+                "kind": "Struct",
+                "reflectTypes": ["Resource"],
+                "properties": {
+                    "children": {"type": {"$ref": "#/$defs/alloc::vec::Vec<my_game::Tree>"}}
+                }
+            },
+            "alloc::vec::Vec<my_game::Tree>": {
+                "kind": "List",
+                "items": {"type": {"$ref": "#/$defs/my_game::Tree"}}
+            },
+            "core::option::Option<u32>": {
+                "kind": "Enum",
+                "oneOf": [
+                    {"shortPath": "None"},
+                    {"shortPath": "Some", "prefixItems": [{"type": {"$ref": "#/$defs/u32"}}]}
+                ]
+            },
+            "u32": {"kind": "Value", "reflectTypes": ["Serialize", "Deserialize"]},
+            "std::time::Instant": {"kind": "Value", "reflectTypes": ["Default"]}
+        });
+        let Value::Object(schemas) = schemas else {
+            unreachable!("schemas are an object")
+        };
+        assert!(is_serializable(&schemas, "my_game::Score"));
+        assert!(!is_serializable(&schemas, "my_game::Timer"));
+        assert!(is_serializable(&schemas, "my_game::Tree"));
+        assert!(!is_serializable(&schemas, "my_game::Missing"));
+    }
+}
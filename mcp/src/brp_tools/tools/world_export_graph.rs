@@ -0,0 +1,460 @@
+//! `world_export_graph` tool - The entity hierarchy as DOT or Mermaid text
+//!
+//! One `world.query` fetches each entity's `ChildOf`, `Name` and any requested relationship
+//! components; parent links become hierarchy edges and entity IDs found inside the relationship
+//! components become labelled edges. The text can be rendered with `GraphViz` or any Mermaid
+//! viewer to get a diagram of the running scene.
+
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::fmt::Write as _;
+
+use bevy_brp_mcp_macros::ParamStruct;
+use bevy_brp_mcp_macros::ResultStruct;
+use bevy_brp_mcp_macros::ToolFn;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+use serde_json::json;
+use strum::Display;
+
+use super::world_query::BrpQuery;
+use super::world_query::BrpQueryFilter;
+use super::world_query::ComponentSelector;
+use crate::brp_tools::BrpClient;
+use crate::brp_tools::BrpTypeName;
+use crate::brp_tools::Port;
+use crate::brp_tools::ResponseStatus;
+use crate::error::Error;
+use crate::error::Result;
+use crate::json_object::JsonObjectAccess;
+use crate::tool::BrpMethod;
+use crate::tool::HandlerContext;
+use crate::tool::HandlerResult;
+use crate::tool::ParameterName;
+use crate::tool::ToolFn;
+use crate::tool::ToolResult;
+
+/// Component linking a child to its parent, under its current path
+pub const TYPE_CHILD_OF: &str = "bevy_ecs::hierarchy::ChildOf";
+
+/// Component holding an entity's name, under its current path
+pub const TYPE_NAME: &str = "bevy_ecs::name::Name";
+
+/// Field of a `world.query` row holding the requested component values
+pub const QUERY_COMPONENTS_FIELD: &str = "components";
+
+/// Text format of the exported graph
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema, Display,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum GraphFormat {
+    /// `GraphViz` DOT
+    #[default]
+    Dot,
+    /// Mermaid flowchart
+    Mermaid,
+}
+
+/// Parameters for the `world_export_graph` tool
+#[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
+pub struct ExportGraphParams {
+    /// Output format: `dot` (`GraphViz`, default) or `mermaid`
+    #[serde(default)]
+    pub format: GraphFormat,
+
+    /// Only export the subtree under this entity (inclusive)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub root: Option<u64>,
+
+    /// Maximum depth below the root(s) to include (default: unlimited)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_depth: Option<u32>,
+
+    /// Components whose values reference other entities, drawn as labelled edges from the
+    /// entity that has them (fully-qualified type paths)
+    #[serde(default)]
+    pub relationships: Vec<String>,
+
+    /// Which entities to consider. Structure: {with: string[], without: string[]}
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter: Option<BrpQueryFilter>,
+
+    /// The BRP port
+    #[serde(default)]
+    pub port: Port,
+}
+
+/// Result for the `world_export_graph` tool
+#[derive(Debug, Clone, Serialize, Deserialize, ResultStruct)]
+pub struct ExportGraphResult {
+    /// The graph as DOT or Mermaid text
+    #[to_result]
+    graph:              String,
+    /// Format of `graph`
+    #[to_metadata]
+    format:             String,
+    /// Number of entities in the graph
+    #[to_metadata]
+    node_count:         usize,
+    /// Number of hierarchy edges
+    #[to_metadata]
+    hierarchy_edges:    usize,
+    /// Number of relationship edges
+    #[to_metadata]
+    relationship_edges: usize,
+    /// Message template for formatting responses
+    #[to_message(
+        message_template = "Exported {node_count} entities with {hierarchy_edges} hierarchy and {relationship_edges} relationship edges as {format}"
+    )]
+    message_template:   String,
+}
+
+#[derive(ToolFn)]
+#[tool_fn(params = "ExportGraphParams", output = "ExportGraphResult")]
+pub struct WorldExportGraph;
+
+async fn handle_impl(params: ExportGraphParams) -> Result<ExportGraphResult> {
+    let scene = fetch_scene(params.port, params.filter, &params.relationships).await?;
+    let graph = scene.select(params.root, params.max_depth)?;
+    let text = match params.format {
+        GraphFormat::Dot => graph.to_dot(),
+        GraphFormat::Mermaid => graph.to_mermaid(),
+    };
+
+    Ok(ExportGraphResult::new(
+        text,
+        params.format.to_string(),
+        graph.nodes.len(),
+        graph.hierarchy.len(),
+        graph.relationships.len(),
+    ))
+}
+
+/// Every queried entity with its name, parent and relationship targets
+#[derive(Debug, Default)]
+pub struct Scene {
+    /// Label of every entity - its `Name`, or `Entity {id}`
+    pub names:         BTreeMap<u64, String>,
+    /// Parent of every entity that has one
+    pub parents:       BTreeMap<u64, u64>,
+    /// `(source, relationship short name, target)`
+    pub relationships: Vec<(u64, String, u64)>,
+}
+
+/// The part of a scene that is exported
+#[derive(Debug, Default)]
+struct Graph {
+    /// Entity and label, in ID order
+    nodes:         Vec<(u64, String)>,
+    /// `(parent, child)`
+    hierarchy:     Vec<(u64, u64)>,
+    /// `(source, label, target)`
+    relationships: Vec<(u64, String, u64)>,
+}
+
+/// Fetch the hierarchy of the entities matching `filter`, with the targets of the given
+/// relationship components
+pub async fn fetch_scene(
+    port: Port,
+    filter: Option<BrpQueryFilter>,
+    relationships: &[String],
+) -> Result<Scene> {
+    let child_of = with_aliases(TYPE_CHILD_OF);
+    let name = with_aliases(TYPE_NAME);
+    let option = child_of
+        .iter()
+        .chain(&name)
+        .chain(relationships)
+        .cloned()
+        .collect();
+    let query = BrpQuery {
+        option: ComponentSelector::Paths(option),
+        ..BrpQuery::default()
+    };
+    let brp_params = json!({
+        ParameterName::Data: query,
+        ParameterName::Filter: filter.unwrap_or_default(),
+    });
+
+    let client = BrpClient::new(BrpMethod::WorldQuery, port, Some(brp_params));
+    let rows = match client.execute_raw().await? {
+        ResponseStatus::Success(Some(Value::Array(rows))) => rows,
+        ResponseStatus::Success(_) => Vec::new(),
+        ResponseStatus::Error(err) => return Err(Error::tool_call_failed(err.get_message()).into()),
+    };
+
+    let mut scene = Scene::default();
+    for row in &rows {
+        let Some(entity) = row.get_field(ParameterName::Entity).and_then(Value::as_u64) else {
+            continue;
+        };
+        let components = row.get(QUERY_COMPONENTS_FIELD);
+        let component = |type_path: &String| components.and_then(|c| c.get(type_path.as_str()));
+
+        let label = name
+            .iter()
+            .find_map(|type_path| component(type_path).and_then(name_text))
+            .unwrap_or_else(|| format!("Entity {entity}"));
+        scene.names.insert(entity, label);
+
+        if let Some(parent) = child_of
+            .iter()
+            .find_map(|type_path| first_entity(component(type_path)?))
+        {
+            scene.parents.insert(entity, parent);
+        }
+
+        for relationship in relationships {
+            let Some(value) = component(relationship) else {
+                continue;
+            };
+            let mut targets = Vec::new();
+            collect_entities(value, &mut targets);
+            let label = BrpTypeName::from(relationship.as_str()).short_name();
+            scene.relationships.extend(
+                targets
+                    .into_iter()
+                    .map(|target| (entity, label.clone(), target)),
+            );
+        }
+    }
+    Ok(scene)
+}
+
+impl Scene {
+    /// Every entity below `root`, parents before their children
+    pub fn descendants(&self, root: u64) -> Vec<u64> {
+        let mut children: BTreeMap<u64, Vec<u64>> = BTreeMap::new();
+        for (&child, &parent) in &self.parents {
+            children.entry(parent).or_default().push(child);
+        }
+        let mut descendants = Vec::new();
+        let mut next = vec![root];
+        while !next.is_empty() {
+            next = next
+                .iter()
+                .flat_map(|entity| children.remove(entity).unwrap_or_default())
+                .collect();
+            descendants.extend(&next);
+        }
+        descendants
+    }
+
+    /// The subtree under `root` (or the whole forest) down to `max_depth`
+    fn select(&self, root: Option<u64>, max_depth: Option<u32>) -> Result<Graph> {
+        let mut children: BTreeMap<u64, Vec<u64>> = BTreeMap::new();
+        for (&child, &parent) in &self.parents {
+            children.entry(parent).or_default().push(child);
+        }
+
+        let roots: Vec<u64> = match root {
+            Some(root) if self.names.contains_key(&root) => vec![root],
+            Some(root) => {
+                return Err(Error::invalid(
+                    "root",
+                    format!("entity {root} was not found (or does not match `filter`)"),
+                )
+                .into());
+            },
+            // Entities whose parent isn't part of the queried set are roots too
+            None => self
+                .names
+                .keys()
+                .copied()
+                .filter(|entity| {
+                    self.parents
+                        .get(entity)
+                        .is_none_or(|parent| !self.names.contains_key(parent))
+                })
+                .collect(),
+        };
+
+        let mut included = BTreeSet::new();
+        let mut hierarchy = Vec::new();
+        let mut frontier: Vec<(u64, u32)> = roots.iter().map(|&root| (root, 0)).collect();
+        while let Some((entity, depth)) = frontier.pop() {
+            if !included.insert(entity) {
+                continue;
+            }
+            if max_depth.is_some_and(|max_depth| depth >= max_depth) {
+                continue;
+            }
+            for &child in children.get(&entity).into_iter().flatten() {
+                if self.names.contains_key(&child) {
+                    hierarchy.push((entity, child));
+                    frontier.push((child, depth + 1));
+                }
+            }
+        }
+        hierarchy.sort_unstable();
+
+        let relationships = self
+            .relationships
+            .iter()
+            .filter(|(source, _, target)| included.contains(source) && included.contains(target))
+            .cloned()
+            .collect();
+        let nodes = included
+            .into_iter()
+            .map(|entity| {
+                let label = self.names.get(&entity).cloned().unwrap_or_default();
+                (entity, label)
+            })
+            .collect();
+
+        Ok(Graph {
+            nodes,
+            hierarchy,
+            relationships,
+        })
+    }
+}
+
+impl Graph {
+    fn to_dot(&self) -> String {
+        let mut out = String::from("digraph scene {\n    node [shape=box];\n");
+        for (entity, label) in &self.nodes {
+            let _ = writeln!(
+                out,
+                "    e{entity} [label=\"{}\\n{entity}\"];",
+                escape(label)
+            );
+        }
+        for (parent, child) in &self.hierarchy {
+            let _ = writeln!(out, "    e{parent} -> e{child};");
+        }
+        for (source, label, target) in &self.relationships {
+            let _ = writeln!(
+                out,
+                "    e{source} -> e{target} [label=\"{}\", style=dashed];",
+                escape(label)
+            );
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    fn to_mermaid(&self) -> String {
+        let mut out = String::from("flowchart TD\n");
+        for (entity, label) in &self.nodes {
+            let _ = writeln!(
+                out,
+                "    e{entity}[\"{}<br/>{entity}\"]",
+                escape_mermaid(label)
+            );
+        }
+        for (parent, child) in &self.hierarchy {
+            let _ = writeln!(out, "    e{parent} --> e{child}");
+        }
+        for (source, label, target) in &self.relationships {
+            let _ = writeln!(
+                out,
+                "    e{source} -.->|{}| e{target}",
+                escape_mermaid(label)
+            );
+        }
+        out
+    }
+}
+
+/// `type_path` followed by the paths it had in other Bevy releases
+pub fn with_aliases(type_path: &str) -> Vec<String> {
+    let type_name = BrpTypeName::from(type_path);
+    std::iter::once(type_path.to_string())
+        .chain(
+            type_name
+                .aliases()
+                .iter()
+                .map(|alias| alias.as_str().to_string()),
+        )
+        .collect()
+}
+
+/// The text of a reflected `Name` - a plain string, or an object with a `name` field
+pub fn name_text(value: &Value) -> Option<String> {
+    value
+        .as_str()
+        .or_else(|| value.get("name")?.as_str())
+        .map(String::from)
+}
+
+/// The first entity ID in a reflected value such as `ChildOf(Entity)`
+pub fn first_entity(value: &Value) -> Option<u64> {
+    let mut entities = Vec::new();
+    collect_entities(value, &mut entities);
+    entities.first().copied()
+}
+
+/// Every integer in a reflected value - relationship components hold entity IDs as plain numbers
+fn collect_entities(value: &Value, entities: &mut Vec<u64>) {
+    match value {
+        Value::Number(n) => entities.extend(n.as_u64()),
+        Value::Array(items) => {
+            for item in items {
+                collect_entities(item, entities);
+            }
+        },
+        Value::Object(map) => {
+            for item in map.values() {
+                collect_entities(item, entities);
+            }
+        },
+        _ => {},
+    }
+}
+
+/// Escape quotes so DOT labels stay inside their quoted strings
+fn escape(label: &str) -> String { label.replace('\\', "\\\\").replace('"', "\\\"") }
+
+/// Replace characters Mermaid would read as label or edge syntax with entity codes
+fn escape_mermaid(label: &str) -> String {
+    label
+        .replace('"', "#quot;")
+        .replace('|', "#124;")
+        .replace('<', "#lt;")
+        .replace('>', "#gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scene() -> Scene {
+        Scene {
+            names:         BTreeMap::from([
+                (1, "Root".to_string()),
+                (2, "Arm".to_string()),
+                (3, "Hand".to_string()),
+                (4, "Target".to_string()),
+            ]),
+            parents:       BTreeMap::from([(2, 1), (3, 2)]),
+            relationships: vec![(3, "AimAt".to_string(), 4)],
+        }
+    }
+
+    #[test]
+    fn selects_subtree_to_depth() {
+        let graph = scene().select(Some(1), Some(1)).unwrap_or_default();
+        assert_eq!(
+            graph.nodes.iter().map(|(e, _)| *e).collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+        assert_eq!(graph.hierarchy, vec![(1, 2)]);
+        assert!(graph.relationships.is_empty());
+    }
+
+    #[test]
+    fn renders_dot_and_mermaid() {
+        let graph = scene().select(None, None).unwrap_or_default();
+        let dot = graph.to_dot();
+        assert!(dot.contains("e1 -> e2;"));
+        assert!(dot.contains("e3 -> e4 [label=\"AimAt\", style=dashed];"));
+        let mermaid = graph.to_mermaid();
+        assert!(mermaid.starts_with("flowchart TD\n"));
+        assert!(mermaid.contains("e3 -.->|AimAt| e4"));
+    }
+}
@@ -0,0 +1,241 @@
+//! `world_field_histogram` tool - Distribution of one numeric field across matching entities
+//!
+//! Distribution questions ("are any scales negative?", "what's the spread of z positions?")
+//! otherwise mean dumping every value into the conversation. One `world.query` fetches the
+//! component for all matching entities, and the field's values are reduced here to min, max,
+//! mean and bucketed counts - the values themselves never reach the response.
+
+use bevy_brp_mcp_macros::ParamStruct;
+use bevy_brp_mcp_macros::ResultStruct;
+use bevy_brp_mcp_macros::ToolFn;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+use serde_json::json;
+
+use super::brp_assert::value_at_path;
+use super::world_export_graph::QUERY_COMPONENTS_FIELD;
+use super::world_query::BrpQuery;
+use super::world_query::BrpQueryFilter;
+use crate::brp_tools::BrpClient;
+use crate::brp_tools::Port;
+use crate::brp_tools::ResponseStatus;
+use crate::error::Error;
+use crate::error::Result;
+use crate::tool::BrpMethod;
+use crate::tool::HandlerContext;
+use crate::tool::HandlerResult;
+use crate::tool::ParameterName;
+use crate::tool::ToolFn;
+use crate::tool::ToolResult;
+
+/// Buckets used when `buckets` is omitted
+const DEFAULT_BUCKETS: usize = 10;
+
+/// Parameters for the `world_field_histogram` tool
+#[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
+pub struct FieldHistogramParams {
+    /// Fully-qualified component type holding the field
+    pub component: String,
+
+    /// Reflection path to a numeric field (e.g. `.translation.2`, `.scale[0]`, `.0`); empty when
+    /// the component itself is a number
+    #[serde(default)]
+    pub path: String,
+
+    /// Which entities to include, besides requiring `component`. Structure: {with: string[],
+    /// without: string[]}
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter: Option<BrpQueryFilter>,
+
+    /// Number of equal-width buckets between the minimum and maximum (default: 10)
+    #[param(min = 1, max = 100)]
+    #[schemars(extend("default" = 10))]
+    pub buckets: Option<usize>,
+
+    /// The BRP port
+    #[serde(default)]
+    pub port: Port,
+}
+
+/// Count of values in `[lower, upper)` - the last bucket includes `upper`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Bucket {
+    /// Lower bound, inclusive
+    pub lower: f64,
+    /// Upper bound
+    pub upper: f64,
+    /// Number of values in the bucket
+    pub count: usize,
+}
+
+/// Summary of the field's values
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct FieldStats {
+    /// Smallest value
+    pub min:     f64,
+    /// Largest value
+    pub max:     f64,
+    /// Arithmetic mean
+    pub mean:    f64,
+    /// Counts per bucket, from `min` to `max`
+    pub buckets: Vec<Bucket>,
+}
+
+impl FieldStats {
+    /// Bucket `values` into `bucket_count` equal-width buckets - `None` when there are no values
+    #[allow(
+        clippy::cast_precision_loss,
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss
+    )]
+    fn from_values(values: &[f64], bucket_count: usize) -> Option<Self> {
+        let min = values.iter().copied().reduce(f64::min)?;
+        let max = values.iter().copied().reduce(f64::max)?;
+        let mean = values.iter().sum::<f64>() / values.len() as f64;
+
+        // All values equal - one bucket holds them all
+        let bucket_count = if max > min { bucket_count.max(1) } else { 1 };
+        let width = (max - min) / bucket_count as f64;
+        let mut buckets: Vec<Bucket> = (0..bucket_count)
+            .map(|index| Bucket {
+                lower: (index as f64).mul_add(width, min),
+                upper: if index + 1 == bucket_count {
+                    max
+                } else {
+                    ((index + 1) as f64).mul_add(width, min)
+                },
+                count: 0,
+            })
+            .collect();
+        for value in values {
+            let index = if width > 0.0 {
+                (((value - min) / width) as usize).min(bucket_count - 1)
+            } else {
+                0
+            };
+            if let Some(bucket) = buckets.get_mut(index) {
+                bucket.count += 1;
+            }
+        }
+
+        Some(Self {
+            min,
+            max,
+            mean,
+            buckets,
+        })
+    }
+}
+
+/// Result for the `world_field_histogram` tool
+#[derive(Debug, Clone, Serialize, Deserialize, ResultStruct)]
+pub struct FieldHistogramResult {
+    /// Min, max, mean and bucket counts - absent when no entity had a numeric value at `path`
+    #[to_result(skip_if_none)]
+    stats:            Option<FieldStats>,
+    /// Number of values summarized
+    #[to_metadata]
+    value_count:      usize,
+    /// Entities with the component whose value at `path` is missing or not a number
+    #[to_metadata]
+    skipped_count:    usize,
+    /// Message template for formatting responses
+    #[to_message]
+    message_template: Option<String>,
+}
+
+#[derive(ToolFn)]
+#[tool_fn(params = "FieldHistogramParams", output = "FieldHistogramResult")]
+pub struct WorldFieldHistogram;
+
+async fn handle_impl(params: FieldHistogramParams) -> Result<FieldHistogramResult> {
+    let query = BrpQuery {
+        components: vec![params.component.clone()],
+        ..BrpQuery::default()
+    };
+    let brp_params = json!({
+        ParameterName::Data: query,
+        ParameterName::Filter: params.filter.clone().unwrap_or_default(),
+    });
+    let client = BrpClient::new(BrpMethod::WorldQuery, params.port, Some(brp_params));
+    let rows = match client.execute_raw().await? {
+        ResponseStatus::Success(Some(Value::Array(rows))) => rows,
+        ResponseStatus::Success(_) => Vec::new(),
+        ResponseStatus::Error(err) => return Err(Error::tool_call_failed(err.get_message()).into()),
+    };
+
+    let values: Vec<f64> = rows
+        .iter()
+        .filter_map(|row| {
+            let component = row.get(QUERY_COMPONENTS_FIELD)?.get(&params.component)?;
+            value_at_path(component, &params.path)?.as_f64()
+        })
+        .collect();
+    let skipped_count = rows.len() - values.len();
+    let stats = FieldStats::from_values(&values, params.buckets.unwrap_or(DEFAULT_BUCKETS));
+
+    let field = format!("{}{}", params.component, params.path);
+    let message = stats.as_ref().map_or_else(
+        || format!("No numeric values found at {field} ({skipped_count} entities skipped)"),
+        |stats| {
+            format!(
+                "{} values of {field}: min {}, max {}, mean {}",
+                values.len(),
+                stats.min,
+                stats.max,
+                stats.mean
+            )
+        },
+    );
+
+    Ok(
+        FieldHistogramResult::new(stats, values.len(), skipped_count)
+            .with_message_template(message),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tool::ParamStruct;
+
+    #[test]
+    fn buckets_values_between_min_and_max() {
+        let Some(stats) = FieldStats::from_values(&[-1.0, 0.0, 0.5, 2.0, 3.0], 2) else {
+            unreachable!("values are not empty");
+        };
+        assert!((stats.mean - 0.9).abs() < 1e-9);
+        assert_eq!(
+            stats
+                .buckets
+                .iter()
+                .map(|bucket| (bucket.lower, bucket.upper, bucket.count))
+                .collect::<Vec<_>>(),
+            vec![(-1.0, 1.0, 3), (1.0, 3.0, 2)]
+        );
+
+        let Some(constant) = FieldStats::from_values(&[4.0, 4.0], 10) else {
+            unreachable!("values are not empty");
+        };
+        assert_eq!(constant.buckets.len(), 1);
+        assert_eq!(constant.buckets[0].count, 2);
+        assert_eq!(FieldStats::from_values(&[], 10), None);
+    }
+
+    #[test]
+    fn bucket_counts_outside_1_to_100_are_rejected() {
+        let params = |buckets| FieldHistogramParams {
+            component: "my_game::Health".to_string(),
+            path:      String::new(),
+            filter:    None,
+            buckets:   Some(buckets),
+            port:      Port::default(),
+        };
+        assert!(params(0).validate().is_err());
+        assert!(params(101).validate().is_err());
+        assert!(params(1).validate().is_ok());
+        assert!(params(100).validate().is_ok());
+    }
+}
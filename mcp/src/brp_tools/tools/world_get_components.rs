@@ -7,6 +7,8 @@ use serde::Deserialize;
 use serde::Serialize;
 use serde_json::Value;
 
+use crate::brp_tools::Corrector;
+use crate::brp_tools::FormatCorrections;
 use crate::brp_tools::Port;
 
 /// Parameters for the `world.get_components` tool
@@ -22,7 +24,12 @@ pub struct GetComponentsParams {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub strict: Option<bool>,
 
-    /// The BRP port (default: 15702)
+    /// Format correctors to run if the request fails with a format error, in pipeline order
+    /// (default: all) - an empty list turns format correction off
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub correctors: Option<Vec<Corrector>>,
+
+    /// The BRP port
     #[serde(default)]
     pub port: Port,
 }
@@ -44,6 +51,11 @@ pub struct GetComponentsResult {
     #[to_metadata(skip_if_none, result_operation = "count_errors")]
     error_count: Option<usize>,
 
+    /// How the request was corrected to succeed
+    #[serde(flatten)]
+    #[to_metadata(flatten)]
+    format_correction: FormatCorrections,
+
     /// Message template for formatting responses
     #[to_message(message_template = "Retrieved {component_count} components")]
     message_template: String,
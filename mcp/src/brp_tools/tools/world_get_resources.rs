@@ -7,6 +7,8 @@ use serde::Deserialize;
 use serde::Serialize;
 use serde_json::Value;
 
+use crate::brp_tools::Corrector;
+use crate::brp_tools::FormatCorrections;
 use crate::brp_tools::Port;
 
 /// Parameters for the `world.get_resources` tool
@@ -15,7 +17,12 @@ pub struct GetResourcesParams {
     /// The fully-qualified type name of the resource
     pub resource: String,
 
-    /// The BRP port (default: 15702)
+    /// Format correctors to run if the request fails with a format error, in pipeline order
+    /// (default: all) - an empty list turns format correction off
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub correctors: Option<Vec<Corrector>>,
+
+    /// The BRP port
     #[serde(default)]
     pub port: Port,
 }
@@ -29,6 +36,11 @@ pub struct GetResourcesResult {
     #[to_result(skip_if_none)]
     pub result: Option<Value>,
 
+    /// How the request was corrected to succeed
+    #[serde(flatten)]
+    #[to_metadata(flatten)]
+    pub format_correction: FormatCorrections,
+
     /// Message template for formatting responses
     #[to_message(message_template = "Retrieved {resource} resource")]
     pub message_template: String,
@@ -9,6 +9,8 @@ use serde::Deserialize;
 use serde::Serialize;
 use serde_json::Value;
 
+use crate::brp_tools::Corrector;
+use crate::brp_tools::FormatCorrections;
 use crate::brp_tools::Port;
 
 /// Parameters for the `world.insert_components` tool
@@ -20,7 +22,22 @@ pub struct InsertComponentsParams {
     /// Object containing component data to insert. Keys are component types, values are component
     pub components: HashMap<String, Value>,
 
-    /// The BRP port (default: 15702)
+    /// If true, first checks that the entity exists and the component types are registered,
+    /// returning a targeted error instead of the BRP failure (default: false)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub strict: Option<bool>,
+
+    /// If true, first checks the component values against the types' `registry.schema`, returning
+    /// every mismatch by field path instead of the BRP failure (default: false)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub validate: Option<bool>,
+
+    /// Format correctors to run if the request fails with a format error, in pipeline order
+    /// (default: all) - an empty list turns format correction off
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub correctors: Option<Vec<Corrector>>,
+
+    /// The BRP port
     #[serde(default)]
     pub port: Port,
 }
@@ -34,6 +51,11 @@ pub struct InsertComponentsResult {
     #[to_result(skip_if_none)]
     pub result: Option<Value>,
 
+    /// How the request was corrected to succeed
+    #[serde(flatten)]
+    #[to_metadata(flatten)]
+    pub format_correction: FormatCorrections,
+
     /// Message template for formatting responses
     #[to_message(message_template = "Inserted components into entity {entity}")]
     pub message_template: String,
@@ -0,0 +1,252 @@
+//! `world_join_query` tool - Run two queries and join their rows on entity ID
+//!
+//! A single `world.query` can only require, exclude or optionally fetch components, so questions
+//! like "enemies, with their `Health` if they have it and their `Target` only when it has a
+//! `Transform`" otherwise mean two large result lists the agent has to correlate itself. Both
+//! queries are sent together and joined here: an inner join keeps entities found by both, a left
+//! join keeps every entity of the left query and marks those the right query didn't return.
+
+use std::collections::HashMap;
+
+use bevy_brp_mcp_macros::ParamStruct;
+use bevy_brp_mcp_macros::ResultStruct;
+use bevy_brp_mcp_macros::ToolFn;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Map;
+use serde_json::Value;
+use serde_json::json;
+
+use super::world_query::BrpQuery;
+use super::world_query::BrpQueryFilter;
+use crate::brp_tools::BrpClient;
+use crate::brp_tools::Port;
+use crate::brp_tools::ResponseStatus;
+use crate::error::Error;
+use crate::error::Result;
+use crate::json_object::JsonObjectAccess;
+use crate::tool::BrpMethod;
+use crate::tool::HandlerContext;
+use crate::tool::HandlerResult;
+use crate::tool::ParameterName;
+use crate::tool::ToolFn;
+use crate::tool::ToolResult;
+
+/// Fields of a `world.query` row whose objects are merged across the two sides
+const MERGED_ROW_FIELDS: &[&str] = &["components", "has"];
+
+/// Field of a joined row saying whether the right query returned the entity
+const RIGHT_MATCHED_FIELD: &str = "right_matched";
+
+/// How the two queries' rows are combined
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum JoinKind {
+    /// Only entities both queries returned
+    #[default]
+    Inner,
+    /// Every entity the left query returned, with the right query's components when it returned
+    /// the entity too
+    Left,
+}
+
+/// One side of the join - the `data` and `filter` of a `world.query` request
+#[derive(Clone, Debug, Default, Deserialize, Serialize, JsonSchema)]
+pub struct JoinSide {
+    /// What component data to retrieve. Structure: {components: string[], option: "all" |
+    /// string[], has: string[]}
+    #[serde(default)]
+    pub data:   BrpQuery,
+    /// Which entities to query. Structure: {with: string[], without: string[]}
+    #[serde(default)]
+    pub filter: BrpQueryFilter,
+}
+
+/// Parameters for the `world_join_query` tool
+#[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
+pub struct JoinQueryParams {
+    /// The left query - with a left join, every entity it returns is kept
+    pub left: JoinSide,
+
+    /// The right query
+    pub right: JoinSide,
+
+    /// "inner" keeps entities both queries return, "left" keeps every entity of the left query
+    /// (default: "inner")
+    #[serde(default)]
+    pub join: JoinKind,
+
+    /// The BRP port
+    #[serde(default)]
+    pub port: Port,
+}
+
+/// Result for the `world_join_query` tool
+#[derive(Debug, Clone, Serialize, Deserialize, ResultStruct)]
+pub struct JoinQueryResult {
+    /// Joined rows in the left query's order - `components` and `has` combine both sides
+    #[to_result]
+    rows:             Vec<Value>,
+    /// Number of joined rows
+    #[to_metadata]
+    row_count:        usize,
+    /// Number of rows the left query returned
+    #[to_metadata]
+    left_count:       usize,
+    /// Number of rows the right query returned
+    #[to_metadata]
+    right_count:      usize,
+    /// Number of joined rows the right query also returned
+    #[to_metadata]
+    matched_count:    usize,
+    /// Message template for formatting responses
+    #[to_message]
+    message_template: Option<String>,
+}
+
+#[derive(ToolFn)]
+#[tool_fn(params = "JoinQueryParams", output = "JoinQueryResult")]
+pub struct WorldJoinQuery;
+
+async fn handle_impl(params: JoinQueryParams) -> Result<JoinQueryResult> {
+    let (left, right) = tokio::try_join!(
+        run_query(&params.left, params.port),
+        run_query(&params.right, params.port),
+    )?;
+    let (left_count, right_count) = (left.len(), right.len());
+
+    let rows = join_rows(left, right, params.join);
+    let matched_count = rows
+        .iter()
+        .filter(|row| {
+            row.get(RIGHT_MATCHED_FIELD)
+                .and_then(Value::as_bool)
+                .unwrap_or(true)
+        })
+        .count();
+    let row_count = rows.len();
+    let message = match params.join {
+        JoinKind::Inner => format!(
+            "Inner join matched {row_count} entities ({left_count} left, {right_count} right)"
+        ),
+        JoinKind::Left => format!(
+            "Left join returned {row_count} entities, {matched_count} matched by the right query"
+        ),
+    };
+
+    Ok(
+        JoinQueryResult::new(rows, row_count, left_count, right_count, matched_count)
+            .with_message_template(message),
+    )
+}
+
+/// Send one side's `world.query` and return its rows
+async fn run_query(side: &JoinSide, port: Port) -> Result<Vec<Value>> {
+    let brp_params = json!({
+        ParameterName::Data: side.data,
+        ParameterName::Filter: side.filter,
+    });
+    let client = BrpClient::new(BrpMethod::WorldQuery, port, Some(brp_params));
+    match client.execute_raw().await? {
+        ResponseStatus::Success(Some(Value::Array(rows))) => Ok(rows),
+        ResponseStatus::Success(_) => Ok(Vec::new()),
+        ResponseStatus::Error(err) => Err(Error::tool_call_failed(err.get_message()).into()),
+    }
+}
+
+/// Join `left` and `right` query rows on their entity ID, keeping the left rows' order
+///
+/// Left join rows carry `right_matched`; a component both sides returned keeps the right
+/// side's value, which was read second.
+fn join_rows(left: Vec<Value>, right: Vec<Value>, join: JoinKind) -> Vec<Value> {
+    let mut right_by_entity: HashMap<u64, Value> = right
+        .into_iter()
+        .filter_map(|row| Some((entity_of(&row)?, row)))
+        .collect();
+
+    left.into_iter()
+        .filter_map(|mut row| {
+            let matched = entity_of(&row).and_then(|entity| right_by_entity.remove(&entity));
+            if matched.is_none() && join == JoinKind::Inner {
+                return None;
+            }
+            let right_matched = matched.is_some();
+            if let (Some(row), Some(matched)) = (row.as_object_mut(), matched) {
+                merge_row(row, matched);
+            }
+            if join == JoinKind::Left
+                && let Some(row) = row.as_object_mut()
+            {
+                row.insert(RIGHT_MATCHED_FIELD.to_string(), Value::Bool(right_matched));
+            }
+            Some(row)
+        })
+        .collect()
+}
+
+/// Entity ID of a `world.query` row
+fn entity_of(row: &Value) -> Option<u64> {
+    row.get_field(ParameterName::Entity).and_then(Value::as_u64)
+}
+
+/// Merge the `components` and `has` objects of `matched` into `row`
+fn merge_row(row: &mut Map<String, Value>, matched: Value) {
+    let Value::Object(mut matched) = matched else {
+        return;
+    };
+    for field in MERGED_ROW_FIELDS {
+        let Some(Value::Object(from_right)) = matched.remove(*field) else {
+            continue;
+        };
+        match row.get_mut(*field) {
+            Some(Value::Object(from_left)) => from_left.extend(from_right),
+            _ => {
+                row.insert((*field).to_string(), Value::Object(from_right));
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rows() -> (Vec<Value>, Vec<Value>) {
+        let left = vec![
+            json!({ "entity": 1, "components": { "Enemy": {} } }),
+            json!({ "entity": 2, "components": { "Enemy": {} } }),
+        ];
+        let right = vec![
+            json!({ "entity": 2, "components": { "Health": 10 }, "has": { "Target": true } }),
+            json!({ "entity": 3, "components": { "Health": 5 } }),
+        ];
+        (left, right)
+    }
+
+    #[test]
+    fn inner_join_keeps_entities_both_queries_returned() {
+        let (left, right) = rows();
+        assert_eq!(
+            join_rows(left, right, JoinKind::Inner),
+            vec![json!({
+                "entity": 2,
+                "components": { "Enemy": {}, "Health": 10 },
+                "has": { "Target": true },
+            })]
+        );
+    }
+
+    #[test]
+    fn left_join_keeps_every_left_row_and_marks_matches() {
+        let (left, right) = rows();
+        let joined = join_rows(left, right, JoinKind::Left);
+        assert_eq!(
+            joined[0],
+            json!({ "entity": 1, "components": { "Enemy": {} }, "right_matched": false })
+        );
+        assert_eq!(joined[1][RIGHT_MATCHED_FIELD], json!(true));
+        assert_eq!(joined[1]["components"]["Health"], json!(10));
+        assert_eq!(joined.len(), 2);
+    }
+}
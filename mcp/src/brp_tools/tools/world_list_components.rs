@@ -8,6 +8,7 @@ use serde::Serialize;
 use serde_json::Value;
 
 use crate::brp_tools::Port;
+use crate::tool::OutputFormat;
 
 /// Parameters for the `world.list_components` tool
 #[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
@@ -16,7 +17,17 @@ pub struct ListComponentsParams {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub entity: Option<u64>,
 
-    /// The BRP port (default: 15702)
+    /// If true, always ask the app instead of answering from the short-lived read cache
+    /// (default: false)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub no_cache: Option<bool>,
+
+    /// Output format: "table" also renders the result as an aligned plain-text table in the
+    /// response message (default: "json")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<OutputFormat>,
+
+    /// The BRP port
     #[serde(default)]
     pub port: Port,
 }
@@ -8,11 +8,17 @@ use serde::Serialize;
 use serde_json::Value;
 
 use crate::brp_tools::Port;
+use crate::tool::OutputFormat;
 
 /// Parameters for the `world.list_resources` tool
 #[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
 pub struct ListResourcesParams {
-    /// The BRP port (default: 15702)
+    /// Output format: "table" also renders the result as an aligned plain-text table in the
+    /// response message (default: "json")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<OutputFormat>,
+
+    /// The BRP port
     #[serde(default)]
     pub port: Port,
 }
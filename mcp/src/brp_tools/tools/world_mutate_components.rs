@@ -12,6 +12,8 @@ use serde::de::MapAccess;
 use serde::de::Visitor;
 use serde_json::Value;
 
+use crate::brp_tools::Corrector;
+use crate::brp_tools::FormatCorrections;
 use crate::brp_tools::Port;
 
 /// Parameters for the `world.mutate_components` tool
@@ -23,14 +25,36 @@ pub struct MutateComponentsParams {
     /// The fully-qualified type name of the component to mutate
     pub component: String,
 
-    /// The new value for the mutation path
-    pub value: Value,
+    /// The new value for the mutation path - with `variant`, fields written over the variant's
+    /// example payload (optional)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<Value>,
+
+    /// Enum variant to switch the enum at `path` to - its payload is filled from the type guide's
+    /// example, so `value` is only needed for the fields you want to set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub variant: Option<String>,
 
     /// The path to the field within the component (e.g., 'translation.x')
     #[serde(default)]
     pub path: String,
 
-    /// The BRP port (default: 15702)
+    /// If true, first checks that the entity exists and has the component, returning a targeted
+    /// error instead of the BRP failure (default: false)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub strict: Option<bool>,
+
+    /// If true, first checks `value` against the `registry.schema` of the field at `path`,
+    /// returning every mismatch by field path instead of the BRP failure (default: false)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub validate: Option<bool>,
+
+    /// Format correctors to run if the request fails with a format error, in pipeline order
+    /// (default: all) - an empty list turns format correction off
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub correctors: Option<Vec<Corrector>>,
+
+    /// The BRP port
     #[serde(default)]
     pub port: Port,
 }
@@ -39,6 +63,7 @@ pub struct MutateComponentsParams {
 /// construct parameters for this tool. Created to provide an improved
 /// error message that hopefully allows the agent to correct itself.
 impl<'de> Deserialize<'de> for MutateComponentsParams {
+    #[allow(clippy::too_many_lines)]
     fn deserialize<D>(deserializer: D) -> core::result::Result<Self, D::Error>
     where
         D: serde::Deserializer<'de>,
@@ -49,7 +74,11 @@ impl<'de> Deserialize<'de> for MutateComponentsParams {
             Entity,
             Component,
             Value,
+            Variant,
             Path,
+            Strict,
+            Validate,
+            Correctors,
             Port,
         }
 
@@ -62,6 +91,7 @@ impl<'de> Deserialize<'de> for MutateComponentsParams {
                 formatter.write_str("struct MutateComponentsParams")
             }
 
+            #[allow(clippy::too_many_lines)]
             fn visit_map<V>(self, mut map: V) -> core::result::Result<Self::Value, V::Error>
             where
                 V: MapAccess<'de>,
@@ -69,7 +99,11 @@ impl<'de> Deserialize<'de> for MutateComponentsParams {
                 let mut entity: Option<u64> = None;
                 let mut component: Option<String> = None;
                 let mut value: Option<Value> = None;
+                let mut variant: Option<String> = None;
                 let mut path: Option<String> = None;
+                let mut strict: Option<bool> = None;
+                let mut validate: Option<bool> = None;
+                let mut correctors: Option<Vec<Corrector>> = None;
                 let mut port: Option<Port> = None;
 
                 while let Some(key) = map.next_key()? {
@@ -92,12 +126,36 @@ impl<'de> Deserialize<'de> for MutateComponentsParams {
                             }
                             value = Some(map.next_value()?);
                         },
+                        Field::Variant => {
+                            if variant.is_some() {
+                                return Err(Error::duplicate_field("variant"));
+                            }
+                            variant = Some(map.next_value()?);
+                        },
                         Field::Path => {
                             if path.is_some() {
                                 return Err(Error::duplicate_field("path"));
                             }
                             path = Some(map.next_value()?);
                         },
+                        Field::Strict => {
+                            if strict.is_some() {
+                                return Err(Error::duplicate_field("strict"));
+                            }
+                            strict = Some(map.next_value()?);
+                        },
+                        Field::Validate => {
+                            if validate.is_some() {
+                                return Err(Error::duplicate_field("validate"));
+                            }
+                            validate = Some(map.next_value()?);
+                        },
+                        Field::Correctors => {
+                            if correctors.is_some() {
+                                return Err(Error::duplicate_field("correctors"));
+                            }
+                            correctors = Some(map.next_value()?);
+                        },
                         Field::Port => {
                             if port.is_some() {
                                 return Err(Error::duplicate_field("port"));
@@ -107,14 +165,19 @@ impl<'de> Deserialize<'de> for MutateComponentsParams {
                     }
                 }
 
-                if let (Some(entity), Some(component), Some(value)) = (&entity, &component, &value)
+                if let (Some(entity), Some(component)) = (&entity, &component)
+                    && (value.is_some() || variant.is_some())
                 {
                     Ok(MutateComponentsParams {
-                        entity:    *entity,
+                        entity: *entity,
                         component: component.clone(),
-                        value:     value.clone(),
-                        path:      path.unwrap_or_default(),
-                        port:      port.unwrap_or_default(),
+                        value,
+                        variant,
+                        path: path.unwrap_or_default(),
+                        strict,
+                        validate,
+                        correctors,
+                        port: port.unwrap_or_default(),
                     })
                 } else {
                     // Collect missing required fields for better error message
@@ -125,22 +188,34 @@ impl<'de> Deserialize<'de> for MutateComponentsParams {
                     if component.is_none() {
                         missing.push("component");
                     }
-                    if value.is_none() {
+                    if value.is_none() && variant.is_none() {
                         missing.push("value");
                     }
 
                     Err(Error::custom(format!(
                         "Invalid parameter format for 'MutateComponentsParams': missing required \
                          fields: {}. All three parameters are required: entity (u64), component \
-                         (string), value (any JSON value). Optional: path (string, defaults to \
-                         empty), port (number, defaults to 15702)",
+                         (string), value (any JSON value) - value may be omitted when variant \
+                         (string) is given. Optional: path (string, defaults to empty), \
+                         strict (boolean), validate (boolean), correctors (array of strings), \
+                         port (number, defaults to 15702)",
                         missing.join(", ")
                     )))
                 }
             }
         }
 
-        const FIELDS: &[&str] = &["entity", "component", "value", "path", "port"];
+        const FIELDS: &[&str] = &[
+            "entity",
+            "component",
+            "value",
+            "variant",
+            "path",
+            "strict",
+            "validate",
+            "correctors",
+            "port",
+        ];
         deserializer.deserialize_struct("MutateComponentsParams", FIELDS, ParamsVisitor)
     }
 }
@@ -154,6 +229,11 @@ pub struct MutateComponentsResult {
     #[to_result(skip_if_none)]
     pub result: Option<Value>,
 
+    /// How the request was corrected to succeed
+    #[serde(flatten)]
+    #[to_metadata(flatten)]
+    pub format_correction: FormatCorrections,
+
     /// Message template for formatting responses
     #[to_message(message_template = "Mutated {component} for entity {entity}")]
     pub message_template: String,
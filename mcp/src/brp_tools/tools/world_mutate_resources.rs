@@ -7,6 +7,8 @@ use serde::Deserialize;
 use serde::Serialize;
 use serde_json::Value;
 
+use crate::brp_tools::Corrector;
+use crate::brp_tools::FormatCorrections;
 use crate::brp_tools::Port;
 
 /// Parameters for the `world.mutate_resources` tool
@@ -15,14 +17,31 @@ pub struct MutateResourcesParams {
     /// The fully-qualified type name of the resource to mutate
     pub resource: String,
 
-    /// The new value for the field.
-    pub value: Value,
+    /// The new value for the field - with `variant`, fields written over the variant's example
+    /// payload (optional)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<Value>,
+
+    /// Enum variant to switch the enum at `path` to - its payload is filled from the type guide's
+    /// example, so `value` is only needed for the fields you want to set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub variant: Option<String>,
 
     /// The mutation path to the field within the resource (e.g., 'settings.volume')
     #[serde(default)]
     pub path: String,
 
-    /// The BRP port (default: 15702)
+    /// If true, first checks that the resource exists in the world, returning a targeted error
+    /// instead of the BRP failure (default: false)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub strict: Option<bool>,
+
+    /// Format correctors to run if the request fails with a format error, in pipeline order
+    /// (default: all) - an empty list turns format correction off
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub correctors: Option<Vec<Corrector>>,
+
+    /// The BRP port
     #[serde(default)]
     pub port: Port,
 }
@@ -36,6 +55,11 @@ pub struct MutateResourcesResult {
     #[to_result(skip_if_none)]
     pub result: Option<Value>,
 
+    /// How the request was corrected to succeed
+    #[serde(flatten)]
+    #[to_metadata(flatten)]
+    pub format_correction: FormatCorrections,
+
     /// Message template for formatting responses
     #[to_message(message_template = "Mutated resource {resource}")]
     pub message_template: String,
@@ -7,7 +7,10 @@ use serde::Deserialize;
 use serde::Serialize;
 use serde_json::Value;
 
+use crate::brp_tools::Corrector;
+use crate::brp_tools::FormatCorrections;
 use crate::brp_tools::Port;
+use crate::tool::OutputFormat;
 
 /// Selector for optional components in a query (mirrors Bevy's `ComponentSelector`)
 #[derive(Clone, Debug, Serialize, JsonSchema)]
@@ -103,7 +106,22 @@ pub struct QueryParams {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub strict: Option<bool>,
 
-    /// The BRP port (default: 15702)
+    /// If true, component values shared by several entities are listed once in a
+    /// `shared_values` table and referenced as `{"$shared": index}` (default: false)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compact: Option<bool>,
+
+    /// Output format: "table" also renders the result as an aligned plain-text table in the
+    /// response message (default: "json")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<OutputFormat>,
+
+    /// Format correctors to run if the request fails with a format error, in pipeline order
+    /// (default: all) - an empty list turns format correction off
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub correctors: Option<Vec<Corrector>>,
+
+    /// The BRP port
     #[serde(default)]
     pub port: Port,
 }
@@ -125,7 +143,72 @@ pub struct QueryResult {
     #[to_metadata(result_operation = "count_query_components")]
     pub component_count: usize,
 
+    /// How the request was corrected to succeed
+    #[serde(flatten)]
+    #[to_metadata(flatten)]
+    pub format_correction: FormatCorrections,
+
     /// Message template for formatting responses
     #[to_message(message_template = "Found {entity_count} entities")]
     pub message_template: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::brp_tools::Corrector;
+    use crate::brp_tools::FormatCorrectionStatus;
+    use crate::brp_tools::ResultStructBrpExt;
+    use crate::tool::ResponseBuilder;
+    use crate::tool::ResultStruct;
+    use crate::tool::ToolName;
+
+    fn metadata_of(result: &QueryResult) -> Value {
+        let builder = ResponseBuilder::success(ToolName::WorldQuery.get_call_info());
+        let response = result
+            .add_response_fields(builder)
+            .unwrap_or_else(|error| unreachable!("fields should serialize: {error}"))
+            .build();
+        serde_json::to_value(response.metadata).unwrap_or_default()
+    }
+
+    #[test]
+    fn format_corrections_are_flattened_into_the_metadata() {
+        let result = <QueryResult as ResultStructBrpExt>::from_brp_client_response((
+            Some(json!([])),
+            Some(vec![(Corrector::ShortNames, json!({ "from": "Foo" }))]),
+            Some(FormatCorrectionStatus::Succeeded),
+        ))
+        .unwrap_or_else(|error| unreachable!("response should convert: {error}"));
+        let metadata = metadata_of(&result);
+        assert_eq!(metadata["format_corrections"], json!([{ "from": "Foo" }]));
+        assert_eq!(metadata["format_corrected"], json!("succeeded"));
+        assert_eq!(metadata["format_correctors"], json!(["short_names"]));
+
+        let uncorrected = <QueryResult as ResultStructBrpExt>::from_brp_client_response((
+            Some(json!([])),
+            None,
+            Some(FormatCorrectionStatus::NotAttempted),
+        ))
+        .unwrap_or_else(|error| unreachable!("response should convert: {error}"));
+        let metadata = metadata_of(&uncorrected);
+        assert!(metadata.get("format_corrections").is_none());
+        assert!(metadata.get("format_corrected").is_none());
+        assert!(metadata.get("format_correctors").is_none());
+    }
+
+    #[test]
+    fn output_schema_lists_the_flattened_fields() {
+        let mut generator = schemars::SchemaGenerator::default();
+        let fields: Vec<_> = QueryResult::output_schema_fields(&mut generator)
+            .into_iter()
+            .map(|field| field.field_name)
+            .collect();
+        assert!(fields.contains(&"format_corrections"));
+        assert!(fields.contains(&"format_corrected"));
+        assert!(fields.contains(&"format_correctors"));
+        assert!(!fields.contains(&"format_correction"));
+    }
+}
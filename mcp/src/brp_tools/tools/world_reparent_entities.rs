@@ -20,7 +20,7 @@ pub struct ReparentEntitiesParams {
     #[to_metadata(skip_if_none)]
     pub parent: Option<u64>,
 
-    /// The BRP port (default: 15702)
+    /// The BRP port
     #[serde(default)]
     pub port: Port,
 }
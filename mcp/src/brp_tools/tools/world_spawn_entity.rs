@@ -9,6 +9,9 @@ use serde::Deserialize;
 use serde::Serialize;
 use serde_json::Value;
 
+use crate::brp_tools::AutoName;
+use crate::brp_tools::Corrector;
+use crate::brp_tools::FormatCorrections;
 use crate::brp_tools::Port;
 
 /// Parameters for the `world.spawn_entity` tool
@@ -18,7 +21,23 @@ pub struct SpawnEntityParams {
     /// component data.
     pub components: HashMap<String, Value>,
 
-    /// The BRP port (default: 15702)
+    /// If true, first checks the component values against the types' `registry.schema`, returning
+    /// every mismatch by field path instead of the BRP failure (default: false)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub validate: Option<bool>,
+
+    /// Attach a generated unique `Name` to the entity and return it next to the entity ID:
+    /// `{"style": "counter", "prefix": "enemy"}` for `enemy_1`, `enemy_2`, ... or
+    /// `{"style": "words", "seed": 7}` for seeded `adjective-noun-n` names
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auto_name: Option<AutoName>,
+
+    /// Format correctors to run if the request fails with a format error, in pipeline order
+    /// (default: all) - an empty list turns format correction off
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub correctors: Option<Vec<Corrector>>,
+
+    /// The BRP port
     #[serde(default)]
     pub port: Port,
 }
@@ -27,7 +46,8 @@ pub struct SpawnEntityParams {
 #[derive(Serialize, ResultStruct)]
 #[brp_result(enhanced_errors = true)]
 pub struct SpawnEntityResult {
-    /// The raw BRP response data containing the new entity ID
+    /// The raw BRP response data containing the new entity ID, and its `name` when `auto_name`
+    /// was given
     #[serde(skip_serializing_if = "Option::is_none")]
     #[to_result(skip_if_none)]
     pub result: Option<Value>,
@@ -36,6 +56,11 @@ pub struct SpawnEntityResult {
     #[to_metadata(result_operation = "extract_entity")]
     pub entity: u64,
 
+    /// How the request was corrected to succeed
+    #[serde(flatten)]
+    #[to_metadata(flatten)]
+    pub format_correction: FormatCorrections,
+
     /// Message template for formatting responses
     #[to_message(message_template = "Spawned entity {entity}")]
     pub message_template: String,
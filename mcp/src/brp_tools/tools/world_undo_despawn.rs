@@ -0,0 +1,228 @@
+//! `world_undo_despawn` tool - Respawn an entity archived by `world_despawn_entity`
+//!
+//! Claims the archive written by a despawn with `archive: true` and spawns its entities again on
+//! the app they were despawned from, parents first, reattaching each child to its restored parent
+//! and the restored root to its original parent when that still exists. Components referring to
+//! archived entities are then rewritten to the restored IDs. The archive is deleted once any
+//! entity is back, so a token restores at most once.
+
+use std::collections::HashMap;
+
+use bevy_brp_mcp_macros::ParamStruct;
+use bevy_brp_mcp_macros::ResultStruct;
+use bevy_brp_mcp_macros::ToolFn;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Map;
+use serde_json::Value;
+use serde_json::json;
+
+use crate::brp_tools::BrpClient;
+use crate::brp_tools::Port;
+use crate::brp_tools::ResponseStatus;
+use crate::brp_tools::despawn_archive;
+use crate::brp_tools::despawn_archive::DespawnArchive;
+use crate::error::Error;
+use crate::error::Result;
+use crate::tool::BrpMethod;
+use crate::tool::HandlerContext;
+use crate::tool::HandlerResult;
+use crate::tool::ParameterName;
+use crate::tool::ToolFn;
+use crate::tool::ToolResult;
+
+/// Parameters for the `world_undo_despawn` tool
+#[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
+pub struct UndoDespawnParams {
+    /// The `restore_token` returned by `world_despawn_entity` called with `archive: true`
+    pub restore_token: String,
+}
+
+/// An archived entity and the entity it was restored as
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RestoredEntity {
+    /// Entity ID before the despawn
+    pub archived: u64,
+    /// Entity ID of the restored entity
+    pub entity:   u64,
+}
+
+/// Result for the `world_undo_despawn` tool
+#[derive(Debug, Clone, Serialize, Deserialize, ResultStruct)]
+pub struct UndoDespawnResult {
+    /// Restored entities, parents before their children
+    #[to_result]
+    entities:            Vec<RestoredEntity>,
+    /// Entity ID of the restored root
+    #[to_metadata]
+    entity:              u64,
+    /// Number of restored entities
+    #[to_metadata]
+    entity_count:        usize,
+    /// Components that weren't archived because the app couldn't serialize them
+    #[to_metadata]
+    skipped_components:  usize,
+    /// Components rewritten to refer to restored entities instead of archived ones
+    #[to_metadata]
+    remapped_components: usize,
+    /// Why entity references couldn't be rewritten - the components keep the archived IDs
+    #[to_metadata(skip_if_none)]
+    remap_errors:        Option<Vec<String>>,
+    /// The root's original parent, when it no longer exists and the root was restored without one
+    #[to_metadata(skip_if_none)]
+    missing_parent:      Option<u64>,
+    /// Message template for formatting responses
+    #[to_message(message_template = "Restored {entity_count} entities as entity {entity}")]
+    message_template:    String,
+}
+
+#[derive(ToolFn)]
+#[tool_fn(params = "UndoDespawnParams", output = "UndoDespawnResult")]
+pub struct WorldUndoDespawn;
+
+async fn handle_impl(params: UndoDespawnParams) -> Result<UndoDespawnResult> {
+    let claimed = despawn_archive::claim(&params.restore_token).await?;
+    let archive = &claimed.archive;
+    let port = Port(archive.port);
+
+    let mut restored: Vec<RestoredEntity> = Vec::with_capacity(archive.entities.len());
+    let mut restored_ids: HashMap<u64, u64> = HashMap::new();
+    for archived in &archive.entities {
+        let step = async {
+            let entity = spawn(port, &archived.components).await?;
+            if let Some(&parent) = archived.parent.and_then(|parent| restored_ids.get(&parent)) {
+                reparent(port, entity, parent).await?;
+            }
+            Ok::<_, String>(entity)
+        };
+        let entity = match step.await {
+            Ok(entity) => entity,
+            Err(message) => {
+                let message = format!("Restoring entity {} failed: {message}", archived.entity);
+                // Restoring again would duplicate the entities already back
+                if restored.is_empty() {
+                    claimed.put_back().await;
+                } else {
+                    claimed.discard().await;
+                }
+                return Err(Error::tool_call_failed_with_details(
+                    message,
+                    json!({ "restored": restored }),
+                )
+                .into());
+            },
+        };
+        restored_ids.insert(archived.entity, entity);
+        restored.push(RestoredEntity {
+            archived: archived.entity,
+            entity,
+        });
+    }
+
+    let root = restored_ids.get(&archive.root).copied();
+    let missing_parent = match (root, archive.parent) {
+        (Some(entity), Some(parent)) => reparent(port, entity, parent).await.err().map(|_| parent),
+        _ => None,
+    };
+    let (remapped_components, remap_errors) =
+        remap_entity_references(port, archive, &restored_ids).await;
+    let skipped_components = archive.entities.iter().map(|e| e.skipped.len()).sum();
+    claimed.discard().await;
+
+    let entity =
+        root.ok_or_else(|| Error::General("Despawn archive doesn't contain its root".to_string()))?;
+    let entity_count = restored.len();
+    Ok(UndoDespawnResult::new(
+        restored,
+        entity,
+        entity_count,
+        skipped_components,
+        remapped_components,
+        (!remap_errors.is_empty()).then_some(remap_errors),
+        missing_parent,
+    ))
+}
+
+/// Rewrite the restored components that refer to archived entities, returning how many were
+/// rewritten and what failed
+async fn remap_entity_references(
+    port: Port,
+    archive: &DespawnArchive,
+    restored_ids: &HashMap<u64, u64>,
+) -> (usize, Vec<String>) {
+    let remapped = match despawn_archive::remap_entity_references(port, archive, restored_ids).await
+    {
+        Ok(remapped) => remapped,
+        Err(report) => return (0, vec![report.current_context().to_string()]),
+    };
+    let mut count = 0;
+    let mut errors = Vec::new();
+    for (archived, components) in remapped {
+        let Some(&entity) = restored_ids.get(&archived) else {
+            continue;
+        };
+        let component_count = components.len();
+        match insert(port, entity, components).await {
+            Ok(()) => count += component_count,
+            Err(message) => errors.push(format!("entity {entity}: {message}")),
+        }
+    }
+    (count, errors)
+}
+
+/// Spawn an entity with `components`, returning its ID or the app's error
+async fn spawn(port: Port, components: &Map<String, Value>) -> std::result::Result<u64, String> {
+    let client = BrpClient::new(
+        BrpMethod::WorldSpawnEntity,
+        port,
+        Some(json!({ ParameterName::Components: components })),
+    );
+    match client.execute_raw().await {
+        Ok(ResponseStatus::Success(Some(result))) => result
+            .get(ParameterName::Entity.as_ref())
+            .and_then(Value::as_u64)
+            .ok_or_else(|| "the app returned no entity ID".to_string()),
+        Ok(ResponseStatus::Success(None)) => Err("the app returned no entity ID".to_string()),
+        Ok(ResponseStatus::Error(err)) => Err(err.get_message().to_string()),
+        Err(report) => Err(report.current_context().to_string()),
+    }
+}
+
+/// Overwrite components of `entity`, returning the app's error if it fails
+async fn insert(
+    port: Port,
+    entity: u64,
+    components: Map<String, Value>,
+) -> std::result::Result<(), String> {
+    let client = BrpClient::new(
+        BrpMethod::WorldInsertComponents,
+        port,
+        Some(json!({
+            ParameterName::Entity: entity,
+            ParameterName::Components: components,
+        })),
+    );
+    match client.execute_raw().await {
+        Ok(ResponseStatus::Success(_)) => Ok(()),
+        Ok(ResponseStatus::Error(err)) => Err(err.get_message().to_string()),
+        Err(report) => Err(report.current_context().to_string()),
+    }
+}
+
+/// Make `entity` a child of `parent`, returning the app's error if it fails
+async fn reparent(port: Port, entity: u64, parent: u64) -> std::result::Result<(), String> {
+    let client = BrpClient::new(
+        BrpMethod::WorldReparentEntities,
+        port,
+        Some(json!({
+            ParameterName::Entities: [entity],
+            ParameterName::Parent: parent,
+        })),
+    );
+    match client.execute_raw().await {
+        Ok(ResponseStatus::Success(_)) => Ok(()),
+        Ok(ResponseStatus::Error(err)) => Err(err.get_message().to_string()),
+        Err(report) => Err(report.current_context().to_string()),
+    }
+}
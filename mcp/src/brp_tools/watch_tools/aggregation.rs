@@ -0,0 +1,190 @@
+//! Rolling statistics over a numeric value in watch updates
+//!
+//! A watch on a value that changes every frame - a position, a timer - floods its log with
+//! updates an agent only wants the shape of. A watch started with `aggregate` logs no raw updates;
+//! it keeps the min, max, mean and rate of change of the number at `path` and logs them as
+//! `AGGREGATE_SUMMARY` once per interval, then starts a new window.
+
+use std::time::Duration;
+use std::time::Instant;
+
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+use serde_json::json;
+
+use crate::brp_tools::tools::brp_assert;
+use crate::error::Error;
+use crate::error::Result;
+
+/// Milliseconds between summaries unless `interval_ms` says otherwise
+const DEFAULT_INTERVAL_MS: u64 = 1000;
+
+/// A numeric value to summarize instead of logging every update
+///
+/// E.g. `{"path": ".components.my_game::Speed.0", "interval_ms": 1000}` - min, max, mean and rate
+/// of change are logged as `AGGREGATE_SUMMARY` once per interval.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, JsonSchema)]
+pub struct Aggregation {
+    /// Path to a number in the update payload, e.g. `.components.my_game::Speed.0`; a leading
+    /// `$` is allowed
+    pub path:        String,
+    /// Milliseconds between summaries (default: 1000)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schemars(extend("default" = DEFAULT_INTERVAL_MS))]
+    pub interval_ms: Option<u64>,
+}
+
+impl Aggregation {
+    /// Reject an aggregation that could never produce a summary
+    ///
+    /// # Errors
+    /// Returns an error if `path` is empty or `interval_ms` is zero.
+    pub fn validate(&self) -> Result<()> {
+        if self.path.trim().is_empty() {
+            return Err(Error::invalid("aggregate", "`path` cannot be empty").into());
+        }
+        if self.interval_ms == Some(0) {
+            return Err(Error::invalid("aggregate", "`interval_ms` must be at least 1").into());
+        }
+        Ok(())
+    }
+
+    /// Time between summaries
+    #[must_use]
+    pub fn interval(&self) -> Duration {
+        Duration::from_millis(self.interval_ms.unwrap_or(DEFAULT_INTERVAL_MS))
+    }
+}
+
+/// Samples seen since the last summary
+#[derive(Debug, Default)]
+struct Window {
+    samples: u64,
+    skipped: u64,
+    min:     f64,
+    max:     f64,
+    sum:     f64,
+    first:   Option<(f64, Instant)>,
+    last:    Option<(f64, Instant)>,
+}
+
+/// Accumulates the value at an aggregation's path between summaries
+#[derive(Debug)]
+pub struct Aggregator {
+    aggregation: Aggregation,
+    window:      Window,
+}
+
+impl Aggregator {
+    /// Start an empty window for `aggregation`
+    pub fn new(aggregation: Aggregation) -> Self {
+        Self {
+            aggregation,
+            window: Window::default(),
+        }
+    }
+
+    /// Time between summaries
+    pub fn interval(&self) -> Duration { self.aggregation.interval() }
+
+    /// Add the value at the path in `update`, received at `at` - updates without a number there
+    /// are counted as skipped
+    pub fn record(&mut self, update: &Value, at: Instant) {
+        let path = self
+            .aggregation
+            .path
+            .strip_prefix('$')
+            .unwrap_or(&self.aggregation.path);
+        let Some(value) = brp_assert::value_at_path(update, path).and_then(Value::as_f64) else {
+            self.window.skipped += 1;
+            return;
+        };
+        let window = &mut self.window;
+        if window.samples == 0 {
+            window.min = value;
+            window.max = value;
+            window.first = Some((value, at));
+        } else {
+            window.min = window.min.min(value);
+            window.max = window.max.max(value);
+        }
+        window.samples += 1;
+        window.sum += value;
+        window.last = Some((value, at));
+    }
+
+    /// Summarize the current window and start a new one - `None` when no update arrived since
+    /// the last summary
+    #[allow(clippy::cast_precision_loss)]
+    pub fn summarize(&mut self) -> Option<Value> {
+        let window = std::mem::take(&mut self.window);
+        if window.samples == 0 && window.skipped == 0 {
+            return None;
+        }
+        let (Some((first, first_at)), Some((last, last_at))) = (window.first, window.last) else {
+            return Some(json!({
+                "path": self.aggregation.path,
+                "samples": 0,
+                "skipped": window.skipped,
+            }));
+        };
+        let elapsed = last_at.duration_since(first_at).as_secs_f64();
+        let rate_per_sec = (elapsed > 0.0).then(|| (last - first) / elapsed);
+        Some(json!({
+            "path": self.aggregation.path,
+            "samples": window.samples,
+            "skipped": window.skipped,
+            "min": window.min,
+            "max": window.max,
+            "mean": window.sum / window.samples as f64,
+            "last": last,
+            "rate_per_sec": rate_per_sec,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn health(current: f64) -> Value {
+        json!({ "components": { "my_game::Health": { "current": current } } })
+    }
+
+    #[test]
+    fn summarizes_a_window_and_starts_a_new_one() {
+        let mut aggregator = Aggregator::new(Aggregation {
+            path:        "$.components.my_game::Health.current".to_string(),
+            interval_ms: None,
+        });
+        let start = Instant::now();
+        aggregator.record(&health(100.0), start);
+        aggregator.record(&health(80.0), start + Duration::from_millis(500));
+        aggregator.record(&health(90.0), start + Duration::from_secs(2));
+        aggregator.record(&json!({ "components": {} }), start + Duration::from_secs(2));
+
+        let Some(summary) = aggregator.summarize() else {
+            unreachable!("a window with samples is summarized");
+        };
+        assert_eq!(summary["samples"], 3);
+        assert_eq!(summary["skipped"], 1);
+        assert_eq!(summary["min"], 80.0);
+        assert_eq!(summary["max"], 100.0);
+        assert_eq!(summary["mean"], 90.0);
+        assert_eq!(summary["rate_per_sec"], -5.0);
+        assert!(aggregator.summarize().is_none());
+    }
+
+    #[test]
+    fn rejects_aggregations_without_a_path_or_interval() {
+        let aggregation = |path: &str, interval_ms| Aggregation {
+            path: path.to_string(),
+            interval_ms,
+        };
+        assert!(aggregation(".x", None).validate().is_ok());
+        assert!(aggregation(" ", None).validate().is_err());
+        assert!(aggregation(".x", Some(0)).validate().is_err());
+    }
+}
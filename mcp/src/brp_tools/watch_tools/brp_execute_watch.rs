@@ -0,0 +1,89 @@
+//! Start watching any streaming BRP method
+//!
+//! `brp_execute_watch` is to the watch tools what `brp_execute` is to the rest - it starts any
+//! `+watch`-style method, including a game's own streaming endpoints, under the watch manager so
+//! updates are logged like other watches and can be listed and stopped the same way.
+
+use bevy_brp_mcp_macros::ParamStruct;
+use bevy_brp_mcp_macros::ToolFn;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+
+use super::aggregation::Aggregation;
+use super::manager::WatchLabels;
+use super::stop_condition::StopCondition;
+use super::task::WatchOptions;
+use super::types::WatchStartResult;
+use crate::brp_tools::Port;
+use crate::error::Error;
+use crate::error::Result;
+use crate::tool::HandlerContext;
+use crate::tool::HandlerResult;
+use crate::tool::ToolFn;
+use crate::tool::ToolResult;
+
+#[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
+pub struct ExecuteWatchParams {
+    /// The streaming method to watch (e.g., `world.get_components+watch`, or a custom method
+    /// registered by the game)
+    pub method:    String,
+    /// Optional parameters for the method
+    pub params:    Option<Value>,
+    /// Optional name for the watch, shown by `brp_list_active_watches`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label:     Option<String>,
+    /// Optional group for the watch - `brp_stop_watch` with `group` stops every watch in it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group:     Option<String>,
+    /// Optional condition that ends the watch once an update meets it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop_when: Option<StopCondition>,
+    /// Optional number to summarize instead of logging every update
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub aggregate: Option<Aggregation>,
+    /// The BRP port
+    #[serde(default)]
+    pub port:      Port,
+}
+
+#[derive(ToolFn)]
+#[tool_fn(params = "ExecuteWatchParams", output = "WatchStartResult")]
+pub struct BrpExecuteWatch;
+
+async fn handle_impl(params: ExecuteWatchParams) -> Result<WatchStartResult> {
+    if params.method.trim().is_empty() {
+        return Err(Error::missing("method name").into());
+    }
+
+    let labels = WatchLabels {
+        label: params.label,
+        group: params.group,
+    };
+
+    let result = super::start_method_watch_task(
+        &params.method,
+        params.params,
+        params.port,
+        labels.clone(),
+        WatchOptions {
+            stop_when: params.stop_when,
+            aggregate: params.aggregate,
+        },
+    )
+    .await
+    .map_err(|e| {
+        super::wrap_watch_error(&format!("Failed to start {} watch", params.method), None, e)
+    });
+
+    match result {
+        Ok((watch_id, log_path)) => Ok(WatchStartResult::new(
+            watch_id,
+            log_path.to_string_lossy().to_string(),
+            labels.label,
+            labels.group,
+        )),
+        Err(e) => Err(Error::tool_call_failed(e.to_string()).into()),
+    }
+}
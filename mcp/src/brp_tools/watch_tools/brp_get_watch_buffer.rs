@@ -0,0 +1,109 @@
+//! Read a watch's buffered updates without going through its log file
+
+use bevy_brp_mcp_macros::ParamStruct;
+use bevy_brp_mcp_macros::ResultStruct;
+use bevy_brp_mcp_macros::ToolFn;
+use chrono::DateTime;
+use chrono::Local;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+
+use super::manager::WATCH_MANAGER;
+use super::update_buffer;
+use super::update_buffer::BufferedUpdate;
+use super::update_buffer::TakenUpdates;
+use crate::error::Error;
+use crate::error::Result;
+use crate::tool::HandlerContext;
+use crate::tool::HandlerResult;
+use crate::tool::ToolFn;
+use crate::tool::ToolResult;
+
+#[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
+pub struct GetWatchBufferParams {
+    /// The watch ID returned when the watch was started
+    pub watch_id:    u32,
+    /// Return at most this many updates, oldest first - omit for all
+    #[param(min = 1)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_entries: Option<usize>,
+    /// Only return updates logged after this RFC 3339 timestamp, e.g. the `timestamp` of the last
+    /// update already seen
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub since:       Option<String>,
+    /// Remove the returned updates from the buffer so the next call only sees newer ones
+    #[serde(default)]
+    pub clear:       bool,
+}
+
+/// Result from reading a watch's buffer
+#[derive(Debug, Clone, Serialize, Deserialize, ResultStruct)]
+pub struct GetWatchBufferResult {
+    /// Buffered updates, oldest first
+    #[to_result]
+    updates:      Vec<BufferedUpdate>,
+    /// Watch whose buffer was read
+    #[to_metadata]
+    watch_id:     u32,
+    /// Number of updates returned
+    #[to_metadata]
+    returned:     usize,
+    /// Matching updates left in the buffer by `max_entries`
+    #[to_metadata]
+    remaining:    usize,
+    /// Updates dropped because the buffer was full before they were read
+    #[to_metadata]
+    dropped:      u64,
+    /// Whether the watch is still running
+    #[to_metadata]
+    watch_active: bool,
+
+    /// Message template for formatting responses
+    #[to_message(message_template = "Read {returned} buffered updates of watch {watch_id}")]
+    message_template: String,
+}
+
+#[derive(ToolFn)]
+#[tool_fn(params = "GetWatchBufferParams", output = "GetWatchBufferResult")]
+pub struct BrpGetWatchBuffer;
+
+async fn handle_impl(params: GetWatchBufferParams) -> Result<GetWatchBufferResult> {
+    let watch_id = params.watch_id;
+    let since = params
+        .since
+        .as_deref()
+        .map(|since| {
+            DateTime::parse_from_rfc3339(since)
+                .map(|since| since.with_timezone(&Local))
+                .map_err(|e| Error::invalid("since", format!("not an RFC 3339 timestamp: {e}")))
+        })
+        .transpose()?;
+
+    let watch_active = WATCH_MANAGER
+        .lock()
+        .await
+        .active_watches
+        .contains_key(&watch_id);
+
+    let taken = match update_buffer::take(watch_id, params.max_entries, since, params.clear) {
+        Some(taken) => taken,
+        None if watch_active => TakenUpdates::default(),
+        None => {
+            return Err(Error::WatchOperation(format!(
+                "No buffered updates for watch {watch_id}: watch not found"
+            ))
+            .into());
+        },
+    };
+
+    let returned = taken.updates.len();
+    Ok(GetWatchBufferResult::new(
+        taken.updates,
+        watch_id,
+        returned,
+        taken.remaining,
+        taken.dropped,
+        watch_active,
+    ))
+}
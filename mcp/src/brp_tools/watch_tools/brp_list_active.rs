@@ -2,6 +2,7 @@
 
 use bevy_brp_mcp_macros::ResultStruct;
 use bevy_brp_mcp_macros::ToolFn;
+use schemars::JsonSchema;
 use serde::Deserialize;
 use serde::Serialize;
 
@@ -15,18 +16,28 @@ use crate::tool::ToolFn;
 use crate::tool::ToolResult;
 
 /// Individual watch information
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct WatchInfo {
     /// Watch ID
     pub watch_id:   u32,
-    /// Entity ID being watched
-    pub entity_id:  u64,
-    /// Type of watch (get/list)
+    /// Entity ID being watched - absent for method watches that aren't tied to an entity
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entity_id:  Option<u64>,
+    /// Type of watch (get/list, or the method name for `brp_execute_watch`)
     pub watch_type: String,
     /// Log file path
     pub log_path:   String,
     /// BRP port
     pub port:       Port,
+    /// Whether the app restarted since the watch started - its entity IDs belong to the
+    /// previous run, so restart the watch
+    pub stale:      bool,
+    /// Label given when the watch was started
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label:      Option<String>,
+    /// Group given when the watch was started
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group:      Option<String>,
 }
 
 /// Result from listing active watches
@@ -61,6 +72,9 @@ async fn handle_impl(_params: NoParams) -> Result<ListActiveWatchesResult> {
             watch_type: watch.watch_type.clone(),
             log_path:   watch.log_path.to_string_lossy().to_string(),
             port:       watch.port,
+            stale:      watch.stale,
+            label:      watch.labels.label.clone(),
+            group:      watch.labels.group.clone(),
         })
         .collect();
 
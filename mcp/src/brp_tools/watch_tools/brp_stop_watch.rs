@@ -1,4 +1,4 @@
-//! Stop an active watch
+//! Stop an active watch, or every watch in a group
 
 use bevy_brp_mcp_macros::ParamStruct;
 use bevy_brp_mcp_macros::ResultStruct;
@@ -17,20 +17,28 @@ use crate::tool::ToolResult;
 
 #[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
 pub struct StopWatchParams {
-    /// The watch ID returned from `bevy_start_entity_watch` or `bevy_start_list_watch`
-    pub watch_id: u32,
+    /// The watch ID returned from `bevy_start_entity_watch` or `bevy_start_list_watch` - omit
+    /// when stopping a `group`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub watch_id: Option<u32>,
+    /// Stop every watch started with this `group` instead of a single watch
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group:    Option<String>,
 }
 
 /// Result from stopping a watch operation
 #[derive(Debug, Clone, Serialize, Deserialize, ResultStruct)]
 pub struct StopWatchResult {
     /// Watch ID that was stopped
-    #[to_metadata]
-    watch_id: u32,
+    #[to_metadata(skip_if_none)]
+    watch_id:  Option<u32>,
+    /// IDs of the watches stopped with their group
+    #[to_metadata(skip_if_none)]
+    watch_ids: Option<Vec<u32>>,
 
     /// Message template for formatting responses
-    #[to_message(message_template = "Stopped watch {watch_id}")]
-    message_template: String,
+    #[to_message]
+    message_template: Option<String>,
 }
 
 #[derive(ToolFn)]
@@ -38,19 +46,76 @@ pub struct StopWatchResult {
 pub struct BrpStopWatch;
 
 async fn handle_impl(params: StopWatchParams) -> Result<StopWatchResult> {
-    // Stop the watch and release lock immediately
-    let result = {
-        let mut manager = WATCH_MANAGER.lock().await;
-        manager.stop_watch(params.watch_id)
-    };
-
-    // Convert result to our typed response
-    match result {
-        Ok(()) => Ok(StopWatchResult::new(params.watch_id)),
-        Err(e) => Err(Error::tool_call_failed(format!(
-            "Failed to stop watch {}: {e}",
-            params.watch_id
-        ))
-        .into()),
+    match (params.watch_id, params.group) {
+        (Some(watch_id), None) => {
+            // Stop the watch and release lock immediately
+            let result = {
+                let mut manager = WATCH_MANAGER.lock().await;
+                manager.stop_watch(watch_id)
+            };
+
+            // Convert result to our typed response
+            match result {
+                Ok(()) => Ok(StopWatchResult::new(Some(watch_id), None)
+                    .with_message_template(format!("Stopped watch {watch_id}"))),
+                Err(e) => Err(Error::tool_call_failed(format!(
+                    "Failed to stop watch {watch_id}: {e}"
+                ))
+                .into()),
+            }
+        },
+        (None, Some(group)) => {
+            let result = {
+                let mut manager = WATCH_MANAGER.lock().await;
+                manager.stop_group(&group)
+            };
+
+            match result {
+                Ok(watch_ids) => {
+                    let message = format!("Stopped {} watches in group '{group}'", watch_ids.len());
+                    Ok(StopWatchResult::new(None, Some(watch_ids)).with_message_template(message))
+                },
+                Err(e) => Err(Error::tool_call_failed(e.to_string()).into()),
+            }
+        },
+        (Some(_), Some(_)) => {
+            Err(Error::invalid("parameters", "pass either `watch_id` or `group`, not both").into())
+        },
+        (None, None) => Err(Error::missing("`watch_id` or `group` of the watches to stop").into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn stop_needs_exactly_one_of_watch_id_and_group() {
+        let both = StopWatchParams {
+            watch_id: Some(1),
+            group:    Some("player".to_string()),
+        };
+        let neither = StopWatchParams {
+            watch_id: None,
+            group:    None,
+        };
+
+        let both = handle_impl(both).await.err().map(|e| e.to_string());
+        let neither = handle_impl(neither).await.err().map(|e| e.to_string());
+
+        assert!(both.is_some_and(|e| e.contains("not both")));
+        assert!(neither.is_some_and(|e| e.contains("`watch_id` or `group`")));
+    }
+
+    #[tokio::test]
+    async fn stopping_an_empty_group_fails() {
+        let params = StopWatchParams {
+            watch_id: None,
+            group:    Some("brp_stop_watch_test_empty_group".to_string()),
+        };
+
+        let error = handle_impl(params).await.err().map(|e| e.to_string());
+
+        assert!(error.is_some_and(|e| e.contains("no active watches")));
     }
 }
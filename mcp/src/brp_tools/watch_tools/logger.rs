@@ -2,14 +2,20 @@
 
 use std::fmt::Write;
 use std::path::PathBuf;
+use std::sync::Mutex;
 
 use tokio::fs::OpenOptions;
 use tokio::io::AsyncWriteExt;
+use tokio::sync::Notify;
 use tokio::sync::mpsc;
 use tokio::sync::oneshot;
 use tracing::debug;
 use tracing::error;
 
+use super::aggregation::Aggregation;
+use super::aggregation::Aggregator;
+use super::stop_condition::StopCondition;
+use super::update_buffer;
 use crate::log_tools::TracingLevel;
 
 /// Log entry to be written
@@ -22,13 +28,20 @@ pub struct LogEntry {
 
 /// Buffered logger for watch updates
 pub struct BufferedWatchLogger {
+    watch_id:    u32,
     tx:          mpsc::Sender<LogEntry>,
     shutdown_tx: Option<oneshot::Sender<()>>,
+    /// Condition that ends the watch once an update meets it
+    stop_when:   Option<StopCondition>,
+    /// Notified when an update met `stop_when`
+    stop_signal: Notify,
+    /// Statistics logged in place of raw updates, for watches started with `aggregate`
+    aggregator:  Option<Mutex<Aggregator>>,
 }
 
 impl BufferedWatchLogger {
-    /// Create a new buffered logger and spawn the writer task
-    pub fn new(log_path: PathBuf) -> Self {
+    /// Create a new buffered logger for watch `watch_id` and spawn the writer task
+    pub fn new(watch_id: u32, log_path: PathBuf) -> Self {
         let (tx, rx) = mpsc::channel(1000); // Buffer up to 1000 messages
         let (shutdown_tx, shutdown_rx) = oneshot::channel();
 
@@ -40,27 +53,107 @@ impl BufferedWatchLogger {
         });
 
         Self {
+            watch_id,
             tx,
             shutdown_tx: Some(shutdown_tx),
+            stop_when: None,
+            stop_signal: Notify::new(),
+            aggregator: None,
         }
     }
 
-    /// Queue a log entry for writing (non-blocking)
+    /// End the watch once an update meets `stop_when`
+    pub fn with_stop_condition(mut self, stop_when: Option<StopCondition>) -> Self {
+        self.stop_when = stop_when;
+        self
+    }
+
+    /// Log periodic summaries of the number at `aggregate.path` instead of each update
+    pub fn with_aggregation(mut self, aggregate: Option<Aggregation>) -> Self {
+        self.aggregator = aggregate.map(|aggregate| Mutex::new(Aggregator::new(aggregate)));
+        self
+    }
+
+    /// Add `update` to the watch's aggregation - `false` for watches without one, whose updates
+    /// are logged as they arrive
+    pub fn aggregate(&self, update: &serde_json::Value) -> bool {
+        let Some(aggregator) = &self.aggregator else {
+            return false;
+        };
+        if let Ok(mut aggregator) = aggregator.lock() {
+            aggregator.record(update, std::time::Instant::now());
+        }
+        true
+    }
+
+    /// Log a summary every aggregation interval - never resolves, and never logs for watches
+    /// without an aggregation
+    pub async fn emit_summaries(&self) {
+        let Some(interval) = self
+            .aggregator
+            .as_ref()
+            .and_then(|aggregator| aggregator.lock().ok().map(|a| a.interval()))
+        else {
+            return std::future::pending().await;
+        };
+        loop {
+            tokio::time::sleep(interval).await;
+            self.write_summary().await;
+        }
+    }
+
+    /// Log a summary of the updates aggregated since the last one, if there were any
+    pub async fn write_summary(&self) {
+        let summary = self
+            .aggregator
+            .as_ref()
+            .and_then(|aggregator| aggregator.lock().ok()?.summarize());
+        if let Some(summary) = summary
+            && let Err(e) = self.write_update("AGGREGATE_SUMMARY", summary).await
+        {
+            error!("Failed to write aggregate summary to log: {}", e);
+        }
+    }
+
+    /// Whether `update` meets the watch's stop condition
+    pub fn meets_stop_condition(&self, update: &serde_json::Value) -> bool {
+        self.stop_when
+            .as_ref()
+            .is_some_and(|stop_when| stop_when.is_met(update))
+    }
+
+    /// Log the update that met the stop condition and signal the watch to end
+    pub async fn stop_on(&self, update: serde_json::Value) -> Result<(), String> {
+        let result = self
+            .write_update(
+                "STOP_CONDITION_MET",
+                serde_json::json!({
+                    "condition": self.stop_when,
+                    "update": update,
+                }),
+            )
+            .await;
+        self.stop_signal.notify_one();
+        result
+    }
+
+    /// Resolves once an update met the stop condition - never for watches without one
+    pub async fn stop_condition_met(&self) {
+        if self.stop_when.is_none() {
+            std::future::pending::<()>().await;
+        }
+        self.stop_signal.notified().await;
+    }
+
+    /// Queue a log entry for writing (non-blocking) and keep it in the watch's update buffer
     pub async fn write_update(
         &self,
         update_type: &str,
         data: serde_json::Value,
     ) -> Result<(), String> {
-        let entry = LogEntry {
-            update_type: update_type.to_string(),
-            data,
-            timestamp: chrono::Local::now(),
-        };
-
-        self.tx
-            .send(entry)
-            .await
-            .map_err(|_| "Logger channel closed".to_string())
+        let timestamp = chrono::Local::now();
+        update_buffer::push(self.watch_id, update_type, &data, timestamp);
+        self.send(update_type, data, timestamp).await
     }
 
     /// Queue a debug log entry for writing only if debug mode is enabled
@@ -73,21 +166,43 @@ impl BufferedWatchLogger {
             TracingLevel::get_current_tracing_level(),
             TracingLevel::Debug | TracingLevel::Trace
         ) {
-            self.write_update(update_type, data).await
+            self.send(update_type, data, chrono::Local::now()).await
         } else {
             Ok(())
         }
     }
 
-    /// Get the log file path for a watch (same as before)
-    pub fn get_watch_log_path(watch_id: u32, entity_id: u64, watch_type: &str) -> PathBuf {
+    /// Queue a log entry for the writer task
+    async fn send(
+        &self,
+        update_type: &str,
+        data: serde_json::Value,
+        timestamp: chrono::DateTime<chrono::Local>,
+    ) -> Result<(), String> {
+        let entry = LogEntry {
+            update_type: update_type.to_string(),
+            data,
+            timestamp,
+        };
+
+        self.tx
+            .send(entry)
+            .await
+            .map_err(|_| "Logger channel closed".to_string())
+    }
+
+    /// Get the log file path for a watch - the entity is left out for watches without one
+    pub fn get_watch_log_path(watch_id: u32, entity_id: Option<u64>, watch_type: &str) -> PathBuf {
         let timestamp = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
-            .map(|d| d.as_secs())
-            .unwrap_or(0);
+            .map_or(0, |d| d.as_secs());
 
-        let filename =
-            format!("bevy_brp_mcp_watch_{watch_id}_{watch_type}_{entity_id}_{timestamp}.log");
+        let filename = entity_id.map_or_else(
+            || format!("bevy_brp_mcp_watch_{watch_id}_{watch_type}_{timestamp}.log"),
+            |entity_id| {
+                format!("bevy_brp_mcp_watch_{watch_id}_{watch_type}_{entity_id}_{timestamp}.log")
+            },
+        );
 
         std::env::temp_dir().join(filename)
     }
@@ -10,6 +10,7 @@ use tokio::sync::Mutex;
 use tokio::task::JoinHandle;
 use tracing::info;
 
+use super::update_buffer;
 use crate::brp_tools::Port;
 use crate::error::Error;
 use crate::error::Result;
@@ -18,14 +19,33 @@ use crate::error::Result;
 pub static WATCH_MANAGER: std::sync::LazyLock<Arc<Mutex<WatchManager>>> =
     std::sync::LazyLock::new(|| Arc::new(Mutex::new(WatchManager::new())));
 
+/// Caller-chosen names for a watch, so it can be told apart from others and stopped with them
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WatchLabels {
+    /// Free-form name of the watch
+    pub label: Option<String>,
+    /// Group the watch belongs to - every watch in a group can be stopped at once
+    pub group: Option<String>,
+}
+
 /// Information about an active watch
 #[derive(Debug, Clone)]
 pub struct WatchInfo {
+    /// ID to stop the watch with
     pub watch_id:   u32,
-    pub entity_id:  u64,
+    /// Entity the watch follows, for entity watches
+    pub entity_id:  Option<u64>,
+    /// Kind of watch - `get`, `list` or the watched method
     pub watch_type: String,
+    /// File the watch logs its updates to
     pub log_path:   PathBuf,
+    /// Port of the watched app
     pub port:       Port,
+    /// Whether the app restarted since the watch started - its entity IDs belong to the
+    /// previous run
+    pub stale:      bool,
+    /// Label and group the watch was started with
+    pub labels:     WatchLabels,
 }
 
 /// Manager for watch subscriptions
@@ -38,6 +58,7 @@ pub struct WatchManager {
 
 impl WatchManager {
     /// Create a new watch manager
+    #[must_use]
     pub fn new() -> Self {
         Self {
             next_watch_id:  AtomicU32::new(1),
@@ -49,10 +70,14 @@ impl WatchManager {
     pub fn next_id(&self) -> u32 { self.next_watch_id.fetch_add(1, Ordering::SeqCst) }
 
     /// Stop a watch by ID
+    ///
+    /// # Errors
+    /// Returns an error if no watch with `watch_id` is running.
     pub fn stop_watch(&mut self, watch_id: u32) -> Result<()> {
         if let Some((info, handle)) = self.active_watches.remove(&watch_id) {
-            info!("Stopping watch {} for entity {}", watch_id, info.entity_id);
+            info!("Stopping {} watch {}", info.watch_type, watch_id);
             handle.abort();
+            update_buffer::discard(watch_id);
             Ok(())
         } else {
             Err(error_stack::Report::new(Error::WatchOperation(format!(
@@ -61,6 +86,29 @@ impl WatchManager {
         }
     }
 
+    /// Stop every watch in `group` - returns the IDs of the stopped watches, lowest first
+    ///
+    /// # Errors
+    /// Returns an error if no watch in `group` is running.
+    pub fn stop_group(&mut self, group: &str) -> Result<Vec<u32>> {
+        let mut watch_ids: Vec<u32> = self
+            .active_watches
+            .values()
+            .filter(|(info, _)| info.labels.group.as_deref() == Some(group))
+            .map(|(info, _)| info.watch_id)
+            .collect();
+        if watch_ids.is_empty() {
+            return Err(error_stack::Report::new(Error::WatchOperation(format!(
+                "Failed to stop group '{group}': no active watches in it"
+            ))));
+        }
+        watch_ids.sort_unstable();
+        for &watch_id in &watch_ids {
+            self.stop_watch(watch_id)?;
+        }
+        Ok(watch_ids)
+    }
+
     /// List all active watches
     pub fn list_active_watches(&self) -> Vec<WatchInfo> {
         self.active_watches
@@ -69,9 +117,136 @@ impl WatchManager {
             .collect()
     }
 
+    /// Mark every watch on `port` stale - returns the number of watches newly marked
+    pub fn mark_stale(&mut self, port: Port) -> usize {
+        let mut marked = 0;
+        for (info, _) in self.active_watches.values_mut() {
+            if info.port == port && !info.stale {
+                info.stale = true;
+                marked += 1;
+            }
+        }
+        marked
+    }
+
     /// Initialize the watch manager
     pub async fn initialize_watch_manager() {
         // Force initialization of the lazy static
         let _ = WATCH_MANAGER.lock().await;
     }
 }
+
+impl Default for WatchManager {
+    fn default() -> Self { Self::new() }
+}
+
+/// Mark every watch on `port` stale after the app behind it restarted
+pub async fn mark_watches_stale(port: Port) -> usize { WATCH_MANAGER.lock().await.mark_stale(port) }
+
+/// Stop the watch with `watch_id`
+///
+/// # Errors
+/// Returns an error if no watch with `watch_id` is running.
+pub async fn stop_watch(watch_id: u32) -> Result<()> {
+    WATCH_MANAGER.lock().await.stop_watch(watch_id)
+}
+
+/// The watches currently running
+pub async fn active_watches() -> Vec<WatchInfo> { WATCH_MANAGER.lock().await.list_active_watches() }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn watch_on(watch_id: u32, port: u16) -> (WatchInfo, JoinHandle<()>) {
+        let info = WatchInfo {
+            watch_id,
+            entity_id: None,
+            watch_type: "list".to_string(),
+            log_path: PathBuf::new(),
+            port: Port(port),
+            stale: false,
+            labels: WatchLabels::default(),
+        };
+        (info, tokio::spawn(async {}))
+    }
+
+    fn watch_in(watch_id: u32, group: Option<&str>) -> (WatchInfo, JoinHandle<()>) {
+        let (mut info, handle) = watch_on(watch_id, 15702);
+        info.labels = WatchLabels {
+            label: Some(format!("watch {watch_id}")),
+            group: group.map(str::to_string),
+        };
+        (info, handle)
+    }
+
+    #[tokio::test]
+    async fn mark_stale_counts_only_fresh_watches_on_the_port() {
+        let mut manager = WatchManager::new();
+        for (watch_id, port) in [(1, 15702), (2, 15702), (3, 15703)] {
+            manager
+                .active_watches
+                .insert(watch_id, watch_on(watch_id, port));
+        }
+
+        assert_eq!(manager.mark_stale(Port(15702)), 2);
+        assert_eq!(manager.mark_stale(Port(15702)), 0);
+        let stale: Vec<bool> = (1..=3)
+            .map(|watch_id| {
+                manager
+                    .active_watches
+                    .get(&watch_id)
+                    .is_some_and(|(info, _)| info.stale)
+            })
+            .collect();
+        assert_eq!(stale, [true, true, false]);
+    }
+
+    #[tokio::test]
+    async fn stop_group_stops_only_the_group_lowest_first() {
+        let mut manager = WatchManager::new();
+        for (watch_id, group) in [(3, Some("player")), (1, Some("player")), (2, Some("enemy"))] {
+            manager
+                .active_watches
+                .insert(watch_id, watch_in(watch_id, group));
+        }
+        manager.active_watches.insert(4, watch_in(4, None));
+
+        let stopped = manager
+            .stop_group("player")
+            .unwrap_or_else(|e| unreachable!("group has watches: {e}"));
+
+        assert_eq!(stopped, [1, 3]);
+        let mut remaining: Vec<u32> = manager.active_watches.keys().copied().collect();
+        remaining.sort_unstable();
+        assert_eq!(remaining, [2, 4]);
+    }
+
+    #[tokio::test]
+    async fn stop_group_without_watches_fails_and_keeps_the_rest() {
+        let mut manager = WatchManager::new();
+        manager.active_watches.insert(1, watch_in(1, Some("enemy")));
+
+        assert!(manager.stop_group("player").is_err());
+        assert_eq!(manager.active_watches.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn listed_watches_keep_their_labels() {
+        let mut manager = WatchManager::new();
+        manager
+            .active_watches
+            .insert(7, watch_in(7, Some("player")));
+
+        let listed = manager.list_active_watches();
+
+        assert_eq!(listed.len(), 1);
+        assert_eq!(
+            listed[0].labels,
+            WatchLabels {
+                label: Some("watch 7".to_string()),
+                group: Some("player".to_string()),
+            }
+        );
+    }
+}
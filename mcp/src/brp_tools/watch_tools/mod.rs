@@ -1,16 +1,37 @@
 // Watch module
 
+mod aggregation;
+mod brp_execute_watch;
+mod brp_get_watch_buffer;
 mod brp_list_active;
 mod brp_stop_watch;
 mod logger;
 mod manager;
+mod multiplex;
+mod stop_condition;
 mod task;
 mod types;
+mod update_buffer;
 mod world_get_components_watch;
 mod world_list_components_watch;
 
+pub use aggregation::Aggregation;
+pub use logger::BufferedWatchLogger;
+pub use manager::WatchInfo;
+pub use manager::WatchLabels;
+pub use manager::active_watches;
+pub use manager::mark_watches_stale;
+pub use manager::stop_watch;
+pub use multiplex::forget_multiwatch;
+pub use stop_condition::StopCondition;
+pub use task::WatchOptions;
+pub use task::start_background_watch_task;
 pub use task::start_entity_watch_task;
 pub use task::start_list_watch_task;
+pub use task::start_method_watch_task;
+pub use task::watch_component_list;
+pub use task::watch_entity;
+pub use task::watch_method;
 
 use crate::error::Error;
 
@@ -27,6 +48,10 @@ pub fn wrap_watch_error<E: std::fmt::Display>(
     Error::WatchOperation(message)
 }
 
+pub use brp_execute_watch::BrpExecuteWatch;
+pub use brp_execute_watch::ExecuteWatchParams;
+pub use brp_get_watch_buffer::BrpGetWatchBuffer;
+pub use brp_get_watch_buffer::GetWatchBufferParams;
 pub use brp_list_active::BrpListActiveWatches;
 pub use brp_stop_watch::BrpStopWatch;
 pub use brp_stop_watch::StopWatchParams;
@@ -0,0 +1,530 @@
+//! Watches multiplexed over one `brp_extras/multiwatch+watch` stream per app
+//!
+//! Each watch otherwise holds its own streaming connection to the app. When the app serves
+//! `brp_extras/multiwatch_subscribe`, component and list watches instead subscribe on a single
+//! stream per port, and a reader task hands each event to its watch by subscription ID - the
+//! watch ID. Apps without the method are remembered, and their watches keep their own
+//! connections.
+//!
+//! Each port has its own lock, held only while its stream is probed and opened, so watches on
+//! other ports never wait for a slow app. A watch that falls `SUBSCRIPTION_BUFFER` updates behind
+//! is ended with an error rather than stalling the reader - and every other watch on the stream.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::LazyLock;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+
+use futures::StreamExt;
+use serde_json::Value;
+use serde_json::json;
+use tokio::sync::Mutex;
+use tokio::sync::mpsc;
+use tokio::sync::mpsc::error::TrySendError;
+use tracing::debug;
+use tracing::info;
+use tracing::warn;
+
+use crate::brp_tools::BrpClient;
+use crate::brp_tools::JSON_RPC_ERROR_METHOD_NOT_FOUND;
+use crate::brp_tools::Port;
+use crate::brp_tools::ResponseStatus;
+
+/// Streaming method carrying the events of every subscription
+const MULTIWATCH_METHOD: &str = "brp_extras/multiwatch+watch";
+
+/// Method adding and removing the subscriptions of a stream
+const MULTIWATCH_SUBSCRIBE_METHOD: &str = "brp_extras/multiwatch_subscribe";
+
+/// Updates a watch can fall behind by before it is ended
+const SUBSCRIPTION_BUFFER: usize = 256;
+
+/// Maximum size of an unterminated stream line (10MB), as for a watch's own stream
+const MAX_LINE_SIZE: usize = 10 * 1024 * 1024;
+
+/// Content type of a streaming response - any other answer is a complete JSON-RPC response
+const EVENT_STREAM_CONTENT_TYPE: &str = "text/event-stream";
+
+/// Per-port multiwatch state, each behind its own lock
+static PORTS: LazyLock<std::sync::Mutex<HashMap<Port, Arc<Mutex<PortState>>>>> =
+    LazyLock::new(|| std::sync::Mutex::new(HashMap::new()));
+
+/// Streams opened by this server, numbered so a reopened stream never reuses an ID
+static NEXT_STREAM: AtomicU64 = AtomicU64::new(0);
+
+/// What is known about multiwatch on one port
+#[derive(Default)]
+enum PortState {
+    /// No stream is open - the next watch probes the app and opens one
+    #[default]
+    Closed,
+    /// The app doesn't serve multiwatch
+    Unsupported,
+    /// The stream is open
+    Open(Arc<Multiplexer>),
+}
+
+/// What the stream reported for one watch
+pub enum MultiplexedUpdate {
+    /// A change the watch method reported
+    Result(Value),
+    /// Why the watch failed - the app has dropped the subscription
+    Error(Value),
+    /// The watched entity was despawned - the app has dropped the subscription
+    Despawned(u64),
+}
+
+/// Where the reader hands one watch its updates
+struct Subscriber {
+    sender: mpsc::Sender<MultiplexedUpdate>,
+    /// Set when the watch fell too far behind and was dropped from the stream
+    lagged: Arc<AtomicBool>,
+}
+
+/// One app's stream and the watches subscribed on it
+struct Multiplexer {
+    stream_id:   String,
+    subscribers: std::sync::Mutex<HashMap<String, Subscriber>>,
+}
+
+impl Multiplexer {
+    fn new() -> Self {
+        Self {
+            stream_id:   format!(
+                "bevy_brp_mcp-{}-{}",
+                std::process::id(),
+                NEXT_STREAM.fetch_add(1, Ordering::Relaxed)
+            ),
+            subscribers: std::sync::Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Register `watch_id` for the stream's events, returning where they arrive
+    fn add_subscriber(
+        &self,
+        watch_id: &str,
+    ) -> (mpsc::Receiver<MultiplexedUpdate>, Arc<AtomicBool>) {
+        let (sender, receiver) = mpsc::channel(SUBSCRIPTION_BUFFER);
+        let lagged = Arc::new(AtomicBool::new(false));
+        if let Ok(mut subscribers) = self.subscribers.lock() {
+            subscribers.insert(
+                watch_id.to_string(),
+                Subscriber {
+                    sender,
+                    lagged: Arc::clone(&lagged),
+                },
+            );
+        }
+        (receiver, lagged)
+    }
+
+    fn remove_subscriber(&self, watch_id: &str) {
+        if let Ok(mut subscribers) = self.subscribers.lock() {
+            subscribers.remove(watch_id);
+        }
+    }
+
+    /// Drop every subscriber, ending their watches
+    fn clear_subscribers(&self) {
+        if let Ok(mut subscribers) = self.subscribers.lock() {
+            subscribers.clear();
+        }
+    }
+
+    /// Hand `update` to its watch without waiting - a watch too far behind is dropped instead
+    fn deliver(&self, watch_id: &str, update: MultiplexedUpdate) {
+        let Ok(mut subscribers) = self.subscribers.lock() else {
+            return;
+        };
+        let Some(subscriber) = subscribers.get(watch_id) else {
+            return;
+        };
+        match subscriber.sender.try_send(update) {
+            Ok(()) => return,
+            Err(TrySendError::Full(_)) => {
+                warn!("Multiplexed watch {watch_id} fell behind its stream and was ended");
+                subscriber.lagged.store(true, Ordering::Relaxed);
+            },
+            Err(TrySendError::Closed(_)) => {},
+        }
+        subscribers.remove(watch_id);
+    }
+
+    /// Hand `error` to every watch, which ends them
+    fn fail_all(&self, error: &Value) {
+        let watch_ids: Vec<String> = self
+            .subscribers
+            .lock()
+            .map(|subscribers| subscribers.keys().cloned().collect())
+            .unwrap_or_default();
+        for watch_id in watch_ids {
+            self.deliver(&watch_id, MultiplexedUpdate::Error(error.clone()));
+        }
+    }
+}
+
+/// A watch subscribed on its app's multiwatch stream - unsubscribed when dropped
+pub struct Subscription {
+    port:        Port,
+    watch_id:    String,
+    multiplexer: Arc<Multiplexer>,
+    updates:     mpsc::Receiver<MultiplexedUpdate>,
+    lagged:      Arc<AtomicBool>,
+}
+
+impl Subscription {
+    /// The next update for this watch - `None` once the stream has ended
+    ///
+    /// A watch dropped for falling behind gets the updates it was sent, then an error.
+    pub async fn next_update(&mut self) -> Option<MultiplexedUpdate> {
+        if let Some(update) = self.updates.recv().await {
+            return Some(update);
+        }
+        self.lagged.swap(false, Ordering::Relaxed).then(|| {
+            MultiplexedUpdate::Error(json!({
+                "message": format!(
+                    "The watch fell more than {SUBSCRIPTION_BUFFER} updates behind the app's \
+                     multiwatch stream and was ended"
+                ),
+            }))
+        })
+    }
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        self.multiplexer.remove_subscriber(&self.watch_id);
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        let params = json!({
+            "stream_id": self.multiplexer.stream_id,
+            "remove": [self.watch_id],
+        });
+        let port = self.port;
+        runtime.spawn(async move {
+            if let Err(e) = send_subscribe(port, params).await {
+                debug!("Failed to unsubscribe multiplexed watch on port {port}: {e}");
+            }
+        });
+    }
+}
+
+/// Why adding a subscription failed
+enum SubscribeFailure {
+    /// The app doesn't serve `multiwatch_subscribe`
+    NotServed,
+    /// Anything else - logged where it happened
+    Failed,
+}
+
+/// Subscribe watch `watch_id` to `method_name` on the app's multiwatch stream
+///
+/// Returns `None` when the app doesn't serve multiwatch or the subscription fails, so the watch
+/// opens its own connection instead.
+pub async fn subscribe(
+    port: Port,
+    watch_id: u32,
+    method_name: &str,
+    params: Value,
+) -> Option<Subscription> {
+    let watch_id = watch_id.to_string();
+    let state = port_state(port)?;
+
+    // Only the watch that opens the stream holds the port's lock across requests, so the watches
+    // arriving meanwhile subscribe on the stream it opens
+    let mut guard = state.lock().await;
+    let multiplexer = match &*guard {
+        PortState::Unsupported => return None,
+        PortState::Open(multiplexer) => {
+            let multiplexer = Arc::clone(multiplexer);
+            drop(guard);
+            return add_subscription(port, multiplexer, watch_id, method_name, params)
+                .await
+                .ok();
+        },
+        PortState::Closed => Arc::new(Multiplexer::new()),
+    };
+
+    let subscription = match add_subscription(
+        port,
+        Arc::clone(&multiplexer),
+        watch_id,
+        method_name,
+        params,
+    )
+    .await
+    {
+        Ok(subscription) => subscription,
+        Err(SubscribeFailure::NotServed) => {
+            debug!("App on port {port} doesn't serve multiwatch - watches use own streams");
+            *guard = PortState::Unsupported;
+            return None;
+        },
+        Err(SubscribeFailure::Failed) => return None,
+    };
+
+    let stream_params = json!({ "stream_id": multiplexer.stream_id });
+    let response = match BrpClient::execute_streaming_custom(
+        MULTIWATCH_METHOD.to_string(),
+        port,
+        Some(stream_params),
+    )
+    .await
+    {
+        Ok(response) => response,
+        Err(e) => {
+            warn!("Failed to open multiwatch stream on port {port}: {e}");
+            return None;
+        },
+    };
+    if !is_event_stream(&response) {
+        // The app answered with a complete JSON-RPC response - an error - instead of a stream
+        let body = response.text().await.unwrap_or_default();
+        warn!("App on port {port} refused the multiwatch stream: {body}");
+        return None;
+    }
+
+    info!(
+        "Opened multiwatch stream {} on port {port}",
+        multiplexer.stream_id
+    );
+    tokio::spawn(read_stream(port, Arc::clone(&multiplexer), response));
+    *guard = PortState::Open(multiplexer);
+    drop(guard);
+    Some(subscription)
+}
+
+/// Forget whether the app on `port` serves multiwatch, e.g. after it restarted
+pub async fn forget_multiwatch(port: Port) {
+    let Some(state) = PORTS.lock().ok().and_then(|mut ports| ports.remove(&port)) else {
+        return;
+    };
+    let forgotten = std::mem::take(&mut *state.lock().await);
+    if let PortState::Open(multiplexer) = forgotten {
+        multiplexer.clear_subscribers();
+    }
+}
+
+/// The lock of `port`'s multiwatch state, created on first use
+fn port_state(port: Port) -> Option<Arc<Mutex<PortState>>> {
+    PORTS
+        .lock()
+        .ok()
+        .map(|mut ports| Arc::clone(ports.entry(port).or_default()))
+}
+
+/// Add watch `watch_id` to `multiplexer`'s stream
+async fn add_subscription(
+    port: Port,
+    multiplexer: Arc<Multiplexer>,
+    watch_id: String,
+    method_name: &str,
+    params: Value,
+) -> Result<Subscription, SubscribeFailure> {
+    let (updates, lagged) = multiplexer.add_subscriber(&watch_id);
+    let subscribe_params = json!({
+        "stream_id": multiplexer.stream_id,
+        "add": [{ "id": watch_id, "method": method_name, "params": params }],
+    });
+    let failure = match send_subscribe(port, subscribe_params).await {
+        Ok(ResponseStatus::Success(_)) => {
+            return Ok(Subscription {
+                port,
+                watch_id,
+                multiplexer,
+                updates,
+                lagged,
+            });
+        },
+        Ok(ResponseStatus::Error(err)) if err.code == JSON_RPC_ERROR_METHOD_NOT_FOUND => {
+            SubscribeFailure::NotServed
+        },
+        Ok(ResponseStatus::Error(err)) => {
+            warn!(
+                "Failed to subscribe watch {watch_id} on port {port}: {}",
+                err.message
+            );
+            SubscribeFailure::Failed
+        },
+        Err(e) => {
+            debug!("Failed to subscribe watch {watch_id} on port {port}: {e}");
+            SubscribeFailure::Failed
+        },
+    };
+    multiplexer.remove_subscriber(&watch_id);
+    Err(failure)
+}
+
+/// Send a `multiwatch_subscribe` request
+async fn send_subscribe(port: Port, params: Value) -> crate::error::Result<ResponseStatus> {
+    BrpClient::execute_custom(MULTIWATCH_SUBSCRIBE_METHOD.to_string(), port, Some(params)).await
+}
+
+/// Whether `response` is a stream rather than a complete JSON-RPC response
+fn is_event_stream(response: &reqwest::Response) -> bool {
+    response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|content_type| content_type.to_str().ok())
+        .is_some_and(|content_type| content_type.starts_with(EVENT_STREAM_CONTENT_TYPE))
+}
+
+/// Hand the stream's events to their watches until the stream ends, then end every watch on it
+async fn read_stream(port: Port, multiplexer: Arc<Multiplexer>, response: reqwest::Response) {
+    let mut stream = response.bytes_stream();
+    let mut line_buffer = String::new();
+    'stream: while let Some(chunk) = stream.next().await {
+        let Ok(bytes) = chunk else {
+            break;
+        };
+        line_buffer.push_str(&String::from_utf8_lossy(&bytes));
+        while let Some(newline_pos) = line_buffer.find('\n') {
+            let line: String = line_buffer.drain(..=newline_pos).collect();
+            match parse_events(line.trim()) {
+                Ok(events) => {
+                    for (watch_id, update) in events {
+                        multiplexer.deliver(&watch_id, update);
+                    }
+                },
+                Err(error) => {
+                    warn!("Multiwatch stream on port {port} failed: {error}");
+                    multiplexer.fail_all(&error);
+                    break 'stream;
+                },
+            }
+        }
+        if line_buffer.len() > MAX_LINE_SIZE {
+            let error = json!({
+                "message": format!("Multiwatch stream line exceeded {MAX_LINE_SIZE} bytes"),
+            });
+            warn!("Multiwatch stream on port {port} failed: {error}");
+            multiplexer.fail_all(&error);
+            break;
+        }
+    }
+
+    info!(
+        "Multiwatch stream {} on port {port} ended",
+        multiplexer.stream_id
+    );
+    if let Some(state) = PORTS
+        .lock()
+        .ok()
+        .and_then(|ports| ports.get(&port).cloned())
+    {
+        let mut guard = state.lock().await;
+        if matches!(&*guard, PortState::Open(current) if Arc::ptr_eq(current, &multiplexer)) {
+            *guard = PortState::Closed;
+        }
+        drop(guard);
+    }
+    multiplexer.clear_subscribers();
+}
+
+/// The per-watch updates of one server-sent events line - the error of a failed stream
+fn parse_events(line: &str) -> Result<Vec<(String, MultiplexedUpdate)>, Value> {
+    let Some(data) = line
+        .strip_prefix("data: ")
+        .and_then(|json| serde_json::from_str::<Value>(json).ok())
+    else {
+        return Ok(Vec::new());
+    };
+    if let Some(error) = data.get("error") {
+        return Err(error.clone());
+    }
+    let Some(Value::Array(events)) = data.get("result").and_then(|result| result.get("events"))
+    else {
+        return Ok(Vec::new());
+    };
+    Ok(events
+        .iter()
+        .filter_map(|event| {
+            let watch_id = event.get("id")?.as_str()?.to_string();
+            if let Some(entity) = event.get("despawned").and_then(Value::as_u64) {
+                return Some((watch_id, MultiplexedUpdate::Despawned(entity)));
+            }
+            let update = match (event.get("result"), event.get("error")) {
+                (_, Some(error)) => MultiplexedUpdate::Error(error.clone()),
+                (Some(result), None) => MultiplexedUpdate::Result(result.clone()),
+                (None, None) => return None,
+            };
+            Some((watch_id, update))
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn events_are_split_by_subscription_id() {
+        let data = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "result": {
+                "stream_id": "bevy_brp_mcp-1-0",
+                "events": [
+                    { "id": "3", "result": { "components": {} } },
+                    { "id": "4", "error": { "code": -23401, "message": "gone" } },
+                    { "id": "5", "despawned": 4_294_967_298_u64 },
+                ],
+            },
+        });
+        let events = parse_events(&format!("data: {data}")).unwrap_or_default();
+        let ids: Vec<&str> = events.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(ids, vec!["3", "4", "5"]);
+        assert!(matches!(events[0].1, MultiplexedUpdate::Result(_)));
+        assert!(matches!(events[1].1, MultiplexedUpdate::Error(_)));
+        assert!(matches!(
+            events[2].1,
+            MultiplexedUpdate::Despawned(4_294_967_298)
+        ));
+        assert!(parse_events(": keep-alive").is_ok_and(|events| events.is_empty()));
+    }
+
+    #[test]
+    fn a_stream_level_error_fails_the_stream() {
+        let data = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "error": { "code": -32602, "message": "Invalid request format" },
+        });
+        let error = parse_events(&format!("data: {data}")).err();
+        assert_eq!(
+            error,
+            Some(json!({ "code": -32602, "message": "Invalid request format" }))
+        );
+    }
+
+    #[tokio::test]
+    async fn a_lagging_watch_is_ended_without_holding_up_the_others() {
+        let multiplexer = Multiplexer::new();
+        let (mut slow, slow_lagged) = multiplexer.add_subscriber("slow");
+        let (mut fast, _) = multiplexer.add_subscriber("fast");
+
+        for n in 0..=SUBSCRIPTION_BUFFER {
+            multiplexer.deliver("slow", MultiplexedUpdate::Result(json!(n)));
+            multiplexer.deliver("fast", MultiplexedUpdate::Result(json!(n)));
+            assert!(matches!(
+                fast.recv().await,
+                Some(MultiplexedUpdate::Result(_))
+            ));
+        }
+
+        assert!(slow_lagged.load(Ordering::Relaxed));
+        let mut delivered = 0;
+        while slow.recv().await.is_some() {
+            delivered += 1;
+        }
+        assert_eq!(delivered, SUBSCRIPTION_BUFFER);
+        multiplexer.deliver("fast", MultiplexedUpdate::Despawned(1));
+        assert!(matches!(
+            fast.recv().await,
+            Some(MultiplexedUpdate::Despawned(1))
+        ));
+    }
+}
@@ -0,0 +1,115 @@
+//! Conditions that end a watch on their own
+//!
+//! A watch started with `stop_when` checks every update against the condition. The first update
+//! that meets it is logged as `STOP_CONDITION_MET` and the watch ends, so an agent waiting for a
+//! value to be reached doesn't have to poll the stream.
+
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::brp_tools::tools::brp_assert;
+use crate::error::Error;
+use crate::error::Result;
+
+/// A predicate on a value in each watch update - met when every given check passes
+///
+/// E.g. `{"path": ".components.my_game::Health.current", "max": 0}` on a component watch, or
+/// `{"path": ".removed[0]", "equals": "my_game::Shield"}` on a component list watch - the update
+/// that meets it is logged as `STOP_CONDITION_MET`.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, JsonSchema)]
+pub struct StopCondition {
+    /// Path into the update payload, e.g. `.components.my_game::Health.current` or
+    /// `.added[0]`; a leading `$` is allowed
+    pub path:   String,
+    /// Stop when the value equals this (numbers compare by value, so `0` equals `0.0`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub equals: Option<Value>,
+    /// Stop when the value is a number of at least this
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min:    Option<f64>,
+    /// Stop when the value is a number of at most this
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max:    Option<f64>,
+}
+
+impl StopCondition {
+    /// Reject a condition that could never be checked
+    ///
+    /// # Errors
+    /// Returns an error if none of `equals`, `min` and `max` is given.
+    pub fn validate(&self) -> Result<()> {
+        if self.equals.is_none() && self.min.is_none() && self.max.is_none() {
+            return Err(Error::invalid(
+                "stop_when",
+                "give at least one of `equals`, `min` or `max`",
+            )
+            .into());
+        }
+        Ok(())
+    }
+
+    /// Whether `update` meets the condition - never when the path isn't in it
+    #[must_use]
+    pub fn is_met(&self, update: &Value) -> bool {
+        let path = self.path.strip_prefix('$').unwrap_or(&self.path);
+        let Some(value) = brp_assert::value_at_path(update, path) else {
+            return false;
+        };
+        if self
+            .equals
+            .as_ref()
+            .is_some_and(|expected| !brp_assert::values_equal(value, expected))
+        {
+            return false;
+        }
+        if self.min.is_none() && self.max.is_none() {
+            return true;
+        }
+        value
+            .as_f64()
+            .is_some_and(|number| brp_assert::in_range(number, self.min, self.max))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn condition(value: Value) -> StopCondition {
+        serde_json::from_value(value).unwrap_or_default()
+    }
+
+    #[test]
+    fn checks_the_value_at_the_path() {
+        let health_gone = condition(json!({
+            "path": "$.components.my_game::Health.current",
+            "max": 0,
+        }));
+        let update =
+            |current: f64| json!({ "components": { "my_game::Health": { "current": current } } });
+
+        assert!(!health_gone.is_met(&update(12.0)));
+        assert!(health_gone.is_met(&update(0.0)));
+        assert!(!health_gone.is_met(&json!({ "components": {} })));
+    }
+
+    #[test]
+    fn compares_equals_by_value() {
+        let despawned = condition(json!({ "path": ".removed[0]", "equals": "my_game::Enemy" }));
+        assert!(despawned.is_met(&json!({ "removed": ["my_game::Enemy"] })));
+        assert!(!despawned.is_met(&json!({ "removed": ["my_game::Player"] })));
+    }
+
+    #[test]
+    fn rejects_conditions_without_checks() {
+        assert!(
+            condition(json!({ "path": ".components" }))
+                .validate()
+                .is_err()
+        );
+    }
+}
@@ -9,13 +9,22 @@ use tracing::error;
 use tracing::info;
 use tracing::warn;
 
+use super::aggregation::Aggregation;
 use super::logger::BufferedWatchLogger;
 use super::manager::WATCH_MANAGER;
 use super::manager::WatchInfo;
+use super::manager::WatchLabels;
+use super::multiplex;
+use super::multiplex::MultiplexedUpdate;
+use super::multiplex::Subscription;
+use super::stop_condition::StopCondition;
+use super::update_buffer;
+use crate::brp_tools::BinaryResponseStream;
 use crate::brp_tools::BrpClient;
 use crate::brp_tools::Port;
 use crate::error::Error;
 use crate::error::Result;
+use crate::json_object::JsonObjectAccess;
 use crate::tool::BrpMethod;
 use crate::tool::ParameterName;
 
@@ -25,20 +34,46 @@ const MAX_CHUNK_SIZE: usize = 1024 * 1024;
 /// Maximum size for the total buffer when processing incomplete lines (10MB)
 const MAX_BUFFER_SIZE: usize = 10 * 1024 * 1024;
 
+/// The streaming method a watch subscribes to
+enum WatchMethod {
+    /// A method this server knows - sent with the name the app uses for it
+    Brp(BrpMethod),
+    /// Any other method, such as a game's own streaming endpoint - sent as-is
+    Custom(String),
+}
+
+/// How a watch treats its updates, beyond logging them
+#[derive(Debug, Clone, Default)]
+pub struct WatchOptions {
+    /// Condition that ends the watch once an update meets it
+    pub stop_when: Option<StopCondition>,
+    /// Number to summarize periodically instead of logging each update
+    pub aggregate: Option<Aggregation>,
+}
+
 /// Parameters for a watch connection
 struct WatchConnectionParams {
     watch_id:   u32,
-    entity_id:  u64,
+    entity_id:  Option<u64>,
     watch_type: String,
-    brp_method: BrpMethod,
+    method:     WatchMethod,
     params:     Value,
     port:       Port,
 }
 
+/// Describe what a watch observes for log messages - the entity, or the method for watches that
+/// aren't tied to one
+fn watch_target(entity_id: Option<u64>, watch_type: &str) -> String {
+    entity_id.map_or_else(
+        || format!("method {watch_type}"),
+        |entity_id| format!("entity {entity_id}"),
+    )
+}
+
 /// Process a single SSE line and log the update if valid
 async fn parse_sse_line(
     line: &str,
-    entity_id: u64,
+    entity_id: Option<u64>,
     watch_type: &str,
     logger: &BufferedWatchLogger,
 ) -> Result<()> {
@@ -61,8 +96,10 @@ async fn parse_sse_line(
     if let Some(json_str) = line.strip_prefix("data: ") {
         if let Ok(data) = serde_json::from_str::<Value>(json_str) {
             debug!(
-                "[{}] Received watch update for entity {}: {:?}",
-                watch_type, entity_id, data
+                "[{}] Received watch update for {}: {:?}",
+                watch_type,
+                watch_target(entity_id, watch_type),
+                data
             );
 
             // Log successful JSON parsing
@@ -127,15 +164,25 @@ async fn parse_sse_line(
     Ok(())
 }
 
-/// Log a watch update with error handling
+/// Log a watch update with error handling - or add it to the watch's aggregation - then end the
+/// watch if the update meets its stop condition
 async fn log_update(logger: &BufferedWatchLogger, result: Value) -> Result<()> {
-    if let Err(e) = logger.write_update("COMPONENT_UPDATE", result).await {
+    let stop = logger.meets_stop_condition(&result);
+    let triggering_update = stop.then(|| result.clone());
+    if !logger.aggregate(&result)
+        && let Err(e) = logger.write_update("COMPONENT_UPDATE", result).await
+    {
         error!("Failed to write watch update to log: {}", e);
         return Err(error_stack::Report::new(Error::failed_to(
             "write watch update to log",
             &e,
         )));
     }
+    if let Some(update) = triggering_update
+        && let Err(e) = logger.stop_on(update).await
+    {
+        error!("Failed to write stop condition to log: {}", e);
+    }
     Ok(())
 }
 
@@ -144,7 +191,7 @@ async fn process_chunk(
     bytes: &[u8],
     line_buffer: &mut String,
     total_buffer_size: &mut usize,
-    entity_id: u64,
+    entity_id: Option<u64>,
     watch_type: &str,
     logger: &BufferedWatchLogger,
 ) -> Result<()> {
@@ -252,7 +299,7 @@ async fn process_chunk(
 /// Handle stream error
 async fn handle_stream_error(
     error: reqwest::Error,
-    entity_id: u64,
+    entity_id: Option<u64>,
     watch_type: &str,
     logger: &BufferedWatchLogger,
     start_time: std::time::Instant,
@@ -282,7 +329,7 @@ async fn handle_stream_error(
 /// Log the first chunk of data for debugging
 async fn log_first_chunk(
     bytes: &[u8],
-    entity_id: u64,
+    entity_id: Option<u64>,
     watch_type: &str,
     logger: &BufferedWatchLogger,
 ) {
@@ -316,7 +363,7 @@ async fn log_first_chunk(
 #[allow(clippy::too_many_lines)]
 async fn process_watch_stream(
     response: reqwest::Response,
-    entity_id: u64,
+    entity_id: Option<u64>,
     watch_type: &str,
     logger: &BufferedWatchLogger,
     start_time: std::time::Instant,
@@ -408,8 +455,9 @@ async fn process_watch_stream(
         .await;
 
     info!(
-        "[{}] Watch stream ended for entity {}",
-        watch_type, entity_id
+        "[{}] Watch stream ended for {}",
+        watch_type,
+        watch_target(entity_id, watch_type)
     );
     Ok(())
 }
@@ -440,24 +488,33 @@ async fn handle_connection_error(
         .await;
 }
 
-/// Run the watch connection in a spawned task
-async fn run_watch_connection(conn_params: WatchConnectionParams, logger: BufferedWatchLogger) {
-    info!(
-        "Starting {} watch task for entity {} on port {}",
-        conn_params.watch_type, conn_params.entity_id, conn_params.port
-    );
-
-    // Track start time for timeout detection
-    let start_time = std::time::Instant::now();
-
-    // Create BRP client
-    let brp_client = BrpClient::new(
-        conn_params.brp_method,
-        conn_params.port,
-        Some(conn_params.params.clone()),
-    );
+/// Run a watch over an HTTP server-sent events stream
+async fn run_http_watch(
+    conn_params: &WatchConnectionParams,
+    logger: &BufferedWatchLogger,
+    start_time: std::time::Instant,
+) {
+    let stream = match &conn_params.method {
+        WatchMethod::Brp(brp_method) => {
+            BrpClient::new(
+                *brp_method,
+                conn_params.port,
+                Some(conn_params.params.clone()),
+            )
+            .execute_streaming()
+            .await
+        },
+        WatchMethod::Custom(method_name) => {
+            BrpClient::execute_streaming_custom(
+                method_name.clone(),
+                conn_params.port,
+                Some(conn_params.params.clone()),
+            )
+            .await
+        },
+    };
 
-    match brp_client.execute_streaming().await {
+    match stream {
         Ok(response) => {
             // Log initial HTTP response
             let _ = logger.write_debug_update(
@@ -477,7 +534,7 @@ async fn run_watch_connection(conn_params: WatchConnectionParams, logger: Buffer
                 response,
                 conn_params.entity_id,
                 &conn_params.watch_type,
-                &logger,
+                logger,
                 start_time,
             )
             .await
@@ -486,9 +543,177 @@ async fn run_watch_connection(conn_params: WatchConnectionParams, logger: Buffer
             }
         },
         Err(e) => {
-            handle_connection_error(e, &conn_params, &logger, start_time).await;
+            handle_connection_error(e, conn_params, logger, start_time).await;
         },
     }
+}
+
+/// Process a watch stream arriving over the binary transport
+async fn process_binary_watch_stream(
+    mut stream: BinaryResponseStream,
+    entity_id: Option<u64>,
+    watch_type: &str,
+    logger: &BufferedWatchLogger,
+) -> Result<()> {
+    while let Some(response) = stream.next_response().await? {
+        if let Some(result) = response.get("result") {
+            log_update(logger, result.clone()).await?;
+        } else {
+            debug!(
+                "[{}] No result in JSON-RPC response: {:?}",
+                watch_type, response
+            );
+        }
+    }
+
+    info!(
+        "[{}] Watch stream ended for {}",
+        watch_type,
+        watch_target(entity_id, watch_type)
+    );
+    Ok(())
+}
+
+/// Process the updates of a watch multiplexed over its app's multiwatch stream
+async fn process_multiplexed_watch(
+    mut subscription: Subscription,
+    entity_id: Option<u64>,
+    watch_type: &str,
+    logger: &BufferedWatchLogger,
+) -> Result<()> {
+    while let Some(update) = subscription.next_update().await {
+        match update {
+            MultiplexedUpdate::Result(result) => log_update(logger, result).await?,
+            MultiplexedUpdate::Error(error) => {
+                // The app dropped the subscription, as it ends a watch stream after an error
+                let _ = logger
+                    .write_update(
+                        "WATCH_ERROR",
+                        serde_json::json!({
+                            ParameterName::Entity: entity_id,
+                            "error": error,
+                            "timestamp": chrono::Local::now().to_rfc3339()
+                        }),
+                    )
+                    .await;
+                break;
+            },
+            MultiplexedUpdate::Despawned(entity) => {
+                // Reported by the app once the entity is gone, instead of a not-found error
+                let _ = logger
+                    .write_update(
+                        "ENTITY_DESPAWNED",
+                        serde_json::json!({
+                            ParameterName::Entity: entity,
+                            "timestamp": chrono::Local::now().to_rfc3339()
+                        }),
+                    )
+                    .await;
+                break;
+            },
+        }
+    }
+
+    info!(
+        "[{}] Watch stream ended for {}",
+        watch_type,
+        watch_target(entity_id, watch_type)
+    );
+    Ok(())
+}
+
+/// Receive the watch's updates over the best stream the app offers - its multiwatch stream,
+/// then the binary transport, then HTTP server-sent events
+async fn run_watch(
+    conn_params: &WatchConnectionParams,
+    logger: &BufferedWatchLogger,
+    start_time: std::time::Instant,
+) {
+    // Component and list watches share the app's multiwatch stream when it serves one
+    let multiplexed = match &conn_params.method {
+        WatchMethod::Brp(
+            brp_method @ (BrpMethod::WorldGetComponentsWatch | BrpMethod::WorldListComponentsWatch),
+        ) => {
+            multiplex::subscribe(
+                conn_params.port,
+                conn_params.watch_id,
+                brp_method.as_str(),
+                conn_params.params.clone(),
+            )
+            .await
+        },
+        _ => None,
+    };
+    if let Some(subscription) = multiplexed {
+        if let Err(e) = process_multiplexed_watch(
+            subscription,
+            conn_params.entity_id,
+            &conn_params.watch_type,
+            logger,
+        )
+        .await
+        {
+            error!("Watch stream processing failed: {}", e);
+        }
+        return;
+    }
+
+    let binary_stream = match &conn_params.method {
+        WatchMethod::Brp(brp_method) => {
+            BrpClient::new(
+                *brp_method,
+                conn_params.port,
+                Some(conn_params.params.clone()),
+            )
+            .execute_binary_streaming()
+            .await
+        },
+        WatchMethod::Custom(_) => None,
+    };
+    match binary_stream {
+        Some(stream) => {
+            if let Err(e) = process_binary_watch_stream(
+                stream,
+                conn_params.entity_id,
+                &conn_params.watch_type,
+                logger,
+            )
+            .await
+            {
+                error!("Watch stream processing failed: {}", e);
+            }
+        },
+        None => run_http_watch(conn_params, logger, start_time).await,
+    }
+}
+
+/// Run the watch connection in a spawned task
+async fn run_watch_connection(conn_params: WatchConnectionParams, logger: BufferedWatchLogger) {
+    let target = watch_target(conn_params.entity_id, &conn_params.watch_type);
+    info!(
+        "Starting {} watch task for {} on port {}",
+        conn_params.watch_type, target, conn_params.port
+    );
+
+    // Track start time for timeout detection
+    let start_time = std::time::Instant::now();
+    let watch = run_watch(&conn_params, &logger, start_time);
+
+    // End the watch early once an update meets its stop condition
+    let stop_condition_met = tokio::select! {
+        () = watch => false,
+        () = logger.stop_condition_met() => true,
+        () = logger.emit_summaries() => false,
+    };
+    if stop_condition_met {
+        info!(
+            "Watch {} for {} met its stop condition",
+            conn_params.watch_id, target
+        );
+    }
+
+    // Summarize what arrived since the last periodic summary
+    logger.write_summary().await;
 
     // Write final log entry
     let _ = logger
@@ -496,6 +721,7 @@ async fn run_watch_connection(conn_params: WatchConnectionParams, logger: Buffer
             "WATCH_ENDED",
             serde_json::json!({
                 ParameterName::Entity: conn_params.entity_id,
+                "stop_condition_met": stop_condition_met,
                 "timestamp": chrono::Local::now().to_rfc3339()
             }),
         )
@@ -509,14 +735,16 @@ async fn run_watch_connection(conn_params: WatchConnectionParams, logger: Buffer
             .remove(&conn_params.watch_id)
             .is_some()
         {
+            // Nothing can stop an ended watch, so its buffer would never be freed otherwise
+            update_buffer::discard(conn_params.watch_id);
             info!(
-                "Watch {} for entity {} automatically cleaned up after connection ended",
-                conn_params.watch_id, conn_params.entity_id
+                "Watch {} for {} automatically cleaned up after connection ended",
+                conn_params.watch_id, target
             );
         } else {
             warn!(
-                "Watch {} for entity {} attempted to clean up but was not found in active watches - possible phantom watch removal",
-                conn_params.watch_id, conn_params.entity_id
+                "Watch {} for {} attempted to clean up but was not found in active watches - possible phantom watch removal",
+                conn_params.watch_id, target
             );
         }
     }
@@ -524,30 +752,31 @@ async fn run_watch_connection(conn_params: WatchConnectionParams, logger: Buffer
 
 /// Generic function to start a watch task
 async fn start_watch_task(
-    entity_id: u64,
+    entity_id: Option<u64>,
     watch_type: &str,
-    brp_method: BrpMethod,
+    method: WatchMethod,
     params: Value,
     port: Port,
+    labels: WatchLabels,
+    options: WatchOptions,
 ) -> Result<(u32, PathBuf)> {
-    // Prepare all data that doesn't require the watch_id
-    let watch_type_owned = watch_type.to_string();
-    let brp_method_owned = brp_method;
-
-    // Perform all operations within a single lock to ensure atomicity
-    let mut manager = WATCH_MANAGER.lock().await;
-
-    // Generate ID while holding the lock
-    let watch_id = manager.next_id();
-
-    // Create log path and logger
-    let log_path = BufferedWatchLogger::get_watch_log_path(watch_id, entity_id, watch_type);
-    let logger = BufferedWatchLogger::new(log_path.clone());
+    if let Some(stop_when) = &options.stop_when {
+        stop_when.validate()?;
+    }
+    if let Some(aggregate) = &options.aggregate {
+        aggregate.validate()?;
+    }
 
     // Create initial log entry
     let log_data = match params.clone() {
         Value::Object(mut map) => {
             map.insert(String::from(ParameterName::Port), serde_json::json!(port));
+            if let Some(stop_when) = &options.stop_when {
+                map.insert("stop_when".to_string(), serde_json::json!(stop_when));
+            }
+            if let Some(aggregate) = &options.aggregate {
+                map.insert("aggregate".to_string(), serde_json::json!(aggregate));
+            }
             map.insert(
                 "timestamp".to_string(),
                 serde_json::json!(chrono::Local::now().to_rfc3339()),
@@ -561,27 +790,96 @@ async fn start_watch_task(
         }),
     };
 
+    let watch_type_owned = watch_type.to_string();
+    register_watch_task(
+        entity_id,
+        watch_type,
+        port,
+        labels,
+        options,
+        log_data,
+        move |watch_id, logger| {
+            run_watch_connection(
+                WatchConnectionParams {
+                    watch_id,
+                    entity_id,
+                    watch_type: watch_type_owned,
+                    method,
+                    params,
+                    port,
+                },
+                logger,
+            )
+        },
+    )
+    .await
+}
+
+/// Start a background task that isn't a BRP stream - such as the auto-relaunch watcher - under
+/// the watch manager, so it is logged, listed and stopped like any other watch
+///
+/// `task` receives the watch ID and the logger for its updates; `start_data` is logged as its
+/// `WATCH_STARTED` entry.
+pub async fn start_background_watch_task<F, Fut>(
+    watch_type: &str,
+    port: Port,
+    start_data: Value,
+    task: F,
+) -> Result<(u32, PathBuf)>
+where
+    F: FnOnce(u32, BufferedWatchLogger) -> Fut,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    register_watch_task(
+        None,
+        watch_type,
+        port,
+        WatchLabels::default(),
+        WatchOptions::default(),
+        start_data,
+        task,
+    )
+    .await
+}
+
+/// Log the start of a watch, spawn its task and register it with the watch manager
+async fn register_watch_task<F, Fut>(
+    entity_id: Option<u64>,
+    watch_type: &str,
+    port: Port,
+    labels: WatchLabels,
+    options: WatchOptions,
+    start_data: Value,
+    task: F,
+) -> Result<(u32, PathBuf)>
+where
+    F: FnOnce(u32, BufferedWatchLogger) -> Fut,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    // Perform all operations within a single lock to ensure atomicity
+    let mut manager = WATCH_MANAGER.lock().await;
+
+    // Generate ID while holding the lock
+    let watch_id = manager.next_id();
+
+    // Create log path and logger
+    let log_path = BufferedWatchLogger::get_watch_log_path(watch_id, entity_id, watch_type);
+    let logger = BufferedWatchLogger::new(watch_id, log_path.clone())
+        .with_stop_condition(options.stop_when)
+        .with_aggregation(options.aggregate);
+
     // If logging fails, we haven't registered anything yet
-    let log_result = logger.write_update("WATCH_STARTED", log_data).await;
+    let log_result = logger.write_update("WATCH_STARTED", start_data).await;
 
     if let Err(e) = log_result {
         return Err(error_stack::Report::new(Error::WatchOperation(format!(
-            "Failed to log initial entry for entity {entity_id}: {e}"
+            "Failed to log initial entry for {}: {e}",
+            watch_target(entity_id, watch_type)
         ))));
     }
 
     // Spawn task
-    let handle = tokio::spawn(run_watch_connection(
-        WatchConnectionParams {
-            watch_id,
-            entity_id,
-            watch_type: watch_type_owned,
-            brp_method: brp_method_owned,
-            params,
-            port,
-        },
-        logger,
-    ));
+    let handle = tokio::spawn(task(watch_id, logger));
 
     // Register immediately while still holding the lock
     manager.active_watches.insert(
@@ -593,6 +891,8 @@ async fn start_watch_task(
                 watch_type: watch_type.to_string(),
                 log_path: log_path.clone(),
                 port,
+                stale: false,
+                labels,
             },
             handle,
         ),
@@ -609,6 +909,8 @@ pub async fn start_entity_watch_task(
     entity_id: u64,
     components: Option<Vec<String>>,
     port: Port,
+    labels: WatchLabels,
+    options: WatchOptions,
 ) -> Result<(u32, PathBuf)> {
     // Validate components parameter
     let components = components.ok_or_else(|| {
@@ -629,27 +931,211 @@ pub async fn start_entity_watch_task(
     });
 
     start_watch_task(
-        entity_id,
+        Some(entity_id),
         "get",
-        BrpMethod::WorldGetComponentsWatch,
+        WatchMethod::Brp(BrpMethod::WorldGetComponentsWatch),
         params,
         port,
+        labels,
+        options,
     )
     .await
 }
 
 /// Start a background task for entity list watching
-pub async fn start_list_watch_task(entity_id: u64, port: Port) -> Result<(u32, PathBuf)> {
+///
+/// List updates name the components added and removed - there is no number to aggregate, so an
+/// `aggregate` option is rejected rather than left to summarize nothing.
+pub async fn start_list_watch_task(
+    entity_id: u64,
+    port: Port,
+    labels: WatchLabels,
+    options: WatchOptions,
+) -> Result<(u32, PathBuf)> {
+    if options.aggregate.is_some() {
+        return Err(Error::invalid(
+            "aggregate",
+            "component list watches have no number to summarize - aggregate a component watch",
+        )
+        .into());
+    }
     let params = serde_json::json!({
         "entity": entity_id
     });
 
     start_watch_task(
-        entity_id,
+        Some(entity_id),
         "list",
-        BrpMethod::WorldListComponentsWatch,
+        WatchMethod::Brp(BrpMethod::WorldListComponentsWatch),
         params,
         port,
+        labels,
+        options,
     )
     .await
 }
+
+/// Start a background task for any streaming method, such as a game's own `+watch` endpoint
+///
+/// Methods this server knows are sent with the name the app uses for them; anything else is sent
+/// as-is. The `entity` parameter, when present, is recorded like it is for entity watches.
+pub async fn start_method_watch_task(
+    method_name: &str,
+    params: Option<Value>,
+    port: Port,
+    labels: WatchLabels,
+    options: WatchOptions,
+) -> Result<(u32, PathBuf)> {
+    let params = params.unwrap_or_else(|| Value::Object(serde_json::Map::new()));
+    let entity_id = params
+        .get_field(ParameterName::Entity)
+        .and_then(Value::as_u64);
+    let method = BrpMethod::from_str(method_name).map_or_else(
+        || WatchMethod::Custom(method_name.to_string()),
+        WatchMethod::Brp,
+    );
+
+    // Method names become part of the log file name
+    let watch_type = method_name.replace(|c: char| !c.is_ascii_alphanumeric(), "_");
+
+    start_watch_task(
+        entity_id,
+        &watch_type,
+        method,
+        params,
+        port,
+        labels,
+        options,
+    )
+    .await
+}
+
+/// Watch `components` on `entity`, logging each change until the watch is stopped
+///
+/// Returns the watch ID, for `stop_watch`, and the path of the log file the updates are written
+/// to.
+///
+/// # Errors
+/// Returns an error if `components` is empty or the watch's log file cannot be created.
+pub async fn watch_entity(
+    entity: u64,
+    components: Vec<String>,
+    port: Port,
+    labels: WatchLabels,
+    options: WatchOptions,
+) -> Result<(u32, PathBuf)> {
+    start_entity_watch_task(entity, Some(components), port, labels, options).await
+}
+
+/// Watch which components `entity` has, logging each addition and removal until the watch is
+/// stopped
+///
+/// Returns the watch ID, for `stop_watch`, and the path of the log file.
+///
+/// # Errors
+/// Returns an error if `options` has an `aggregate`, or the watch's log file cannot be created.
+pub async fn watch_component_list(
+    entity: u64,
+    port: Port,
+    labels: WatchLabels,
+    options: WatchOptions,
+) -> Result<(u32, PathBuf)> {
+    start_list_watch_task(entity, port, labels, options).await
+}
+
+/// Watch any streaming method, such as a game's own `+watch` endpoint, logging each update until
+/// the watch is stopped
+///
+/// Returns the watch ID, for `stop_watch`, and the path of the log file.
+///
+/// # Errors
+/// Returns an error if the watch's log file cannot be created.
+pub async fn watch_method(
+    method_name: &str,
+    params: Option<Value>,
+    port: Port,
+    labels: WatchLabels,
+    options: WatchOptions,
+) -> Result<(u32, PathBuf)> {
+    start_method_watch_task(method_name, params, port, labels, options).await
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn health(current: f64) -> Value {
+        json!({ "components": { "my_game::Health": { "current": current } } })
+    }
+
+    /// Whether the watch was told to end within a short wait
+    async fn stopped(logger: &BufferedWatchLogger) -> bool {
+        tokio::time::timeout(Duration::from_millis(50), logger.stop_condition_met())
+            .await
+            .is_ok()
+    }
+
+    #[tokio::test]
+    async fn an_update_meeting_the_stop_condition_ends_the_watch() {
+        let watch_id = u32::MAX - 1;
+        let dir = TempDir::new().unwrap_or_else(|e| unreachable!("temp dir: {e}"));
+        let logger = BufferedWatchLogger::new(watch_id, dir.path().join("watch.log"))
+            .with_stop_condition(Some(StopCondition {
+                path: ".components.my_game::Health.current".to_string(),
+                max: Some(0.0),
+                ..StopCondition::default()
+            }));
+
+        assert!(log_update(&logger, health(12.0)).await.is_ok());
+        assert!(!stopped(&logger).await);
+        assert!(log_update(&logger, health(0.0)).await.is_ok());
+        assert!(stopped(&logger).await);
+
+        let updates = update_buffer::take(watch_id, None, None, true)
+            .map(|taken| taken.updates)
+            .unwrap_or_default();
+        update_buffer::discard(watch_id);
+        let types: Vec<&str> = updates
+            .iter()
+            .map(|update| update.update_type.as_str())
+            .collect();
+        assert_eq!(
+            types,
+            ["COMPONENT_UPDATE", "COMPONENT_UPDATE", "STOP_CONDITION_MET"]
+        );
+        assert_eq!(updates[2].data["update"], health(0.0));
+    }
+
+    #[tokio::test]
+    async fn watches_without_a_stop_condition_keep_running() {
+        let watch_id = u32::MAX - 2;
+        let dir = TempDir::new().unwrap_or_else(|e| unreachable!("temp dir: {e}"));
+        let logger = BufferedWatchLogger::new(watch_id, dir.path().join("watch.log"));
+
+        assert!(log_update(&logger, health(0.0)).await.is_ok());
+        assert!(!stopped(&logger).await);
+        update_buffer::discard(watch_id);
+    }
+
+    #[tokio::test]
+    async fn list_watches_reject_aggregation() {
+        let options = WatchOptions {
+            aggregate: Some(Aggregation {
+                path:        ".added".to_string(),
+                interval_ms: None,
+            }),
+            ..WatchOptions::default()
+        };
+        let result =
+            start_list_watch_task(42, Port::default(), WatchLabels::default(), options).await;
+        let Err(error) = result else {
+            unreachable!("aggregating a list watch should be rejected");
+        };
+        assert!(error.to_string().contains("aggregate"));
+    }
+}
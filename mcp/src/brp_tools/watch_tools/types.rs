@@ -15,6 +15,12 @@ pub struct WatchStartResult {
     /// Log path
     #[to_metadata]
     log_path: String,
+    /// Label given to the watch
+    #[to_metadata(skip_if_none)]
+    label:    Option<String>,
+    /// Group the watch belongs to
+    #[to_metadata(skip_if_none)]
+    group:    Option<String>,
 
     /// Message template for formatting responses
     #[to_message(message_template = "Started watch {watch_id}")]
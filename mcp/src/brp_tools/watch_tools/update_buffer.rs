@@ -0,0 +1,187 @@
+//! In-memory buffer of each watch's updates, for agents that poll instead of reading log files
+//!
+//! Every update a watch logs is also kept here until `brp_get_watch_buffer` takes it. Each watch
+//! keeps at most `MAX_BUFFERED_UPDATES`; when it is full the oldest update is dropped and
+//! counted. A watch's buffer is discarded when the watch is stopped with `brp_stop_watch` or ends
+//! on its own - its final updates stay in the watch's log file.
+
+use std::collections::HashMap;
+use std::collections::VecDeque;
+use std::sync::LazyLock;
+use std::sync::Mutex;
+
+use chrono::DateTime;
+use chrono::Local;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+
+/// Updates kept per watch at most - the oldest are dropped first
+pub const MAX_BUFFERED_UPDATES: usize = 1_000;
+
+/// An update logged by a watch
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct BufferedUpdate {
+    /// Position of the update among the watch's updates, starting at 1
+    pub sequence:    u64,
+    /// When the update was logged
+    pub timestamp:   String,
+    /// Kind of entry, e.g. `COMPONENT_UPDATE` or `WATCH_ENDED`
+    pub update_type: String,
+    /// The update as logged
+    pub data:        Value,
+    /// `timestamp` for comparing with the `since` filter
+    #[serde(skip)]
+    logged_at:       DateTime<Local>,
+}
+
+/// Updates of one watch not yet taken
+#[derive(Debug, Default)]
+struct WatchBuffer {
+    updates:       VecDeque<BufferedUpdate>,
+    next_sequence: u64,
+    /// Updates dropped because the buffer was full
+    dropped:       u64,
+}
+
+/// Updates taken from a watch's buffer
+#[derive(Debug, Clone, Default)]
+pub struct TakenUpdates {
+    /// Matching updates, oldest first
+    pub updates:   Vec<BufferedUpdate>,
+    /// Matching updates left out by `max_entries`
+    pub remaining: usize,
+    /// Updates dropped because the buffer was full
+    pub dropped:   u64,
+}
+
+/// Buffers per watch ID
+static BUFFERS: LazyLock<Mutex<HashMap<u32, WatchBuffer>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Keep an update logged by `watch_id`
+pub fn push(watch_id: u32, update_type: &str, data: &Value, logged_at: DateTime<Local>) {
+    let Ok(mut buffers) = BUFFERS.lock() else {
+        return;
+    };
+    let buffer = buffers.entry(watch_id).or_default();
+    buffer.next_sequence += 1;
+    if buffer.updates.len() >= MAX_BUFFERED_UPDATES {
+        buffer.updates.pop_front();
+        buffer.dropped += 1;
+    }
+    buffer.updates.push_back(BufferedUpdate {
+        sequence: buffer.next_sequence,
+        timestamp: logged_at.to_rfc3339(),
+        update_type: update_type.to_string(),
+        data: data.clone(),
+        logged_at,
+    });
+}
+
+/// The oldest `max_entries` updates of `watch_id` logged after `since`, removed from the buffer
+/// when `clear` is set - `None` when the watch never logged an update
+pub fn take(
+    watch_id: u32,
+    max_entries: Option<usize>,
+    since: Option<DateTime<Local>>,
+    clear: bool,
+) -> Option<TakenUpdates> {
+    let mut buffers = BUFFERS.lock().ok()?;
+    let taken = take_from(buffers.get_mut(&watch_id)?, max_entries, since, clear);
+    drop(buffers);
+    Some(taken)
+}
+
+/// Discard the buffer of a stopped watch
+pub fn discard(watch_id: u32) {
+    if let Ok(mut buffers) = BUFFERS.lock() {
+        buffers.remove(&watch_id);
+    }
+}
+
+fn take_from(
+    buffer: &mut WatchBuffer,
+    max_entries: Option<usize>,
+    since: Option<DateTime<Local>>,
+    clear: bool,
+) -> TakenUpdates {
+    let is_match = |update: &BufferedUpdate| since.is_none_or(|since| update.logged_at > since);
+    let matching = buffer
+        .updates
+        .iter()
+        .filter(|update| is_match(update))
+        .count();
+    let limit = max_entries.unwrap_or(matching).min(matching);
+
+    let mut updates = Vec::with_capacity(limit);
+    let mut kept = VecDeque::with_capacity(buffer.updates.len());
+    for update in buffer.updates.drain(..) {
+        if updates.len() < limit && is_match(&update) {
+            if !clear {
+                kept.push_back(update.clone());
+            }
+            updates.push(update);
+        } else {
+            kept.push_back(update);
+        }
+    }
+    buffer.updates = kept;
+
+    TakenUpdates {
+        updates,
+        remaining: matching - limit,
+        dropped: buffer.dropped,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeDelta;
+    use serde_json::json;
+
+    use super::*;
+
+    fn buffer_with(count: u64, start: DateTime<Local>) -> WatchBuffer {
+        let mut buffer = WatchBuffer::default();
+        for i in 0..count {
+            buffer.next_sequence += 1;
+            let logged_at = start + TimeDelta::seconds(i64::try_from(i).unwrap_or_default());
+            buffer.updates.push_back(BufferedUpdate {
+                sequence: buffer.next_sequence,
+                timestamp: logged_at.to_rfc3339(),
+                update_type: "COMPONENT_UPDATE".to_string(),
+                data: json!({ "n": i }),
+                logged_at,
+            });
+        }
+        buffer
+    }
+
+    #[test]
+    fn takes_oldest_matching_updates_and_clears_only_those() {
+        let start = Local::now();
+        let mut buffer = buffer_with(5, start);
+
+        let taken = take_from(&mut buffer, Some(2), Some(start), true);
+
+        let sequences: Vec<u64> = taken.updates.iter().map(|update| update.sequence).collect();
+        assert_eq!(sequences, vec![2, 3]);
+        assert_eq!(taken.remaining, 2);
+        let left: Vec<u64> = buffer
+            .updates
+            .iter()
+            .map(|update| update.sequence)
+            .collect();
+        assert_eq!(left, vec![1, 4, 5]);
+    }
+
+    #[test]
+    fn keeps_updates_without_clear() {
+        let mut buffer = buffer_with(3, Local::now());
+        let taken = take_from(&mut buffer, None, None, false);
+        assert_eq!(taken.updates.len(), 3);
+        assert_eq!(buffer.updates.len(), 3);
+    }
+}
@@ -6,6 +6,10 @@ use schemars::JsonSchema;
 use serde::Deserialize;
 use serde::Serialize;
 
+use super::aggregation::Aggregation;
+use super::manager::WatchLabels;
+use super::stop_condition::StopCondition;
+use super::task::WatchOptions;
 use super::types::WatchStartResult;
 use crate::brp_tools::Port;
 use crate::error::Error;
@@ -18,13 +22,25 @@ use crate::tool::ToolResult;
 #[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
 pub struct GetComponentsWatchParams {
     /// The entity ID to watch for component changes
-    pub entity: u64,
+    pub entity:    u64,
     /// Required array of component types to watch. Must contain at least one component. Without
     /// this, the watch will not detect any changes.
-    pub types:  Vec<String>,
-    /// The BRP port (default: 15702)
+    pub types:     Vec<String>,
+    /// Optional name for the watch, shown by `brp_list_active_watches`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label:     Option<String>,
+    /// Optional group for the watch - `brp_stop_watch` with `group` stops every watch in it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group:     Option<String>,
+    /// Optional condition that ends the watch once an update meets it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop_when: Option<StopCondition>,
+    /// Optional number to summarize instead of logging every update
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub aggregate: Option<Aggregation>,
+    /// The BRP port
     #[serde(default)]
-    pub port:   Port,
+    pub port:      Port,
 }
 
 #[derive(ToolFn)]
@@ -32,17 +48,31 @@ pub struct GetComponentsWatchParams {
 pub struct WorldGetComponentsWatch;
 
 async fn handle_impl(params: GetComponentsWatchParams) -> Result<WatchStartResult> {
+    let labels = WatchLabels {
+        label: params.label,
+        group: params.group,
+    };
+
     // Start the watch task
-    let result = super::start_entity_watch_task(params.entity, Some(params.types), params.port)
-        .await
-        .map_err(|e| {
-            super::wrap_watch_error("Failed to start entity watch", Some(params.entity), e)
-        });
+    let result = super::start_entity_watch_task(
+        params.entity,
+        Some(params.types),
+        params.port,
+        labels.clone(),
+        WatchOptions {
+            stop_when: params.stop_when,
+            aggregate: params.aggregate,
+        },
+    )
+    .await
+    .map_err(|e| super::wrap_watch_error("Failed to start entity watch", Some(params.entity), e));
 
     match result {
         Ok((watch_id, log_path)) => Ok(WatchStartResult::new(
             watch_id,
             log_path.to_string_lossy().to_string(),
+            labels.label,
+            labels.group,
         )),
         Err(e) => Err(Error::tool_call_failed(e.to_string()).into()),
     }
@@ -6,6 +6,9 @@ use schemars::JsonSchema;
 use serde::Deserialize;
 use serde::Serialize;
 
+use super::manager::WatchLabels;
+use super::stop_condition::StopCondition;
+use super::task::WatchOptions;
 use super::types::WatchStartResult;
 use crate::brp_tools::Port;
 use crate::error::Error;
@@ -18,10 +21,19 @@ use crate::tool::ToolResult;
 #[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
 pub struct ListComponentsWatchParams {
     /// The entity ID to watch for component list changes
-    pub entity: u64,
-    /// The BRP port (default: 15702)
+    pub entity:    u64,
+    /// Optional name for the watch, shown by `brp_list_active_watches`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label:     Option<String>,
+    /// Optional group for the watch - `brp_stop_watch` with `group` stops every watch in it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group:     Option<String>,
+    /// Optional condition that ends the watch once an update meets it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop_when: Option<StopCondition>,
+    /// The BRP port
     #[serde(default)]
-    pub port:   Port,
+    pub port:      Port,
 }
 
 #[derive(ToolFn)]
@@ -29,15 +41,30 @@ pub struct ListComponentsWatchParams {
 pub struct BevyListWatch;
 
 async fn handle_impl(params: ListComponentsWatchParams) -> Result<WatchStartResult> {
+    let labels = WatchLabels {
+        label: params.label,
+        group: params.group,
+    };
+
     // Start the watch task
-    let result = super::start_list_watch_task(params.entity, params.port)
-        .await
-        .map_err(|e| super::wrap_watch_error("Failed to start list watch", Some(params.entity), e));
+    let result = super::start_list_watch_task(
+        params.entity,
+        params.port,
+        labels.clone(),
+        WatchOptions {
+            stop_when: params.stop_when,
+            aggregate: None,
+        },
+    )
+    .await
+    .map_err(|e| super::wrap_watch_error("Failed to start list watch", Some(params.entity), e));
 
     match result {
         Ok((watch_id, log_path)) => Ok(WatchStartResult::new(
             watch_id,
             log_path.to_string_lossy().to_string(),
+            labels.label,
+            labels.group,
         )),
         Err(e) => Err(Error::tool_call_failed(e.to_string()).into()),
     }
@@ -13,65 +13,98 @@ const MSG_UNEXPECTED_PREFIX: &str = "Unexpected";
 /// Result type for the `bevy_brp_mcp` library
 pub type Result<T> = core::result::Result<T, Report<Error>>;
 
-// Internal error types for detailed error categorization
+/// Errors from the client and the tools, by category
+///
+/// Most variants carry the full message; build them with the constructors such as
+/// [`Error::failed_to`] and [`Error::invalid`] so messages read alike.
 #[derive(Error)]
 pub enum Error {
+    /// A BRP request couldn't be sent, or its response couldn't be read
     #[error("BRP communication failed: {0}")]
     BrpCommunication(String),
 
+    /// Reading or writing a file failed
     #[error("File operation failed: {0}")]
     FileOperation(String),
 
+    /// A file or path the call named doesn't exist
     #[error("File or path not found error: {0}")]
     FileOrPathNotFound(String),
 
+    /// Any other failure, with its message
     #[error("{0}")]
     General(String),
 
+    /// A parameter is missing or has an invalid value
     #[error("Invalid argument: {0}")]
     InvalidArgument(String),
 
+    /// The app or the server isn't in a state the call can run in
     #[error("Invalid state: {0}")]
     InvalidState(String),
 
+    /// The app answered with a JSON-RPC error
     #[error("JSON-RPC error: {0}")]
     JsonRpc(String),
 
+    /// Reading, writing or listing log files failed
     #[error("Log operation failed: {0}")]
     LogOperation(String),
 
+    /// A request to the MCP client failed
     #[error("MCP client communication failed: {0}")]
     McpClientCommunication(String),
 
+    /// A result is missing the message template it is formatted with
     #[error("Configuration error: {0}")]
     MissingMessageTemplate(String),
 
+    /// A call's arguments couldn't be deserialized into its parameters
     #[error("Unable to extract parameters: {0}")]
     ParameterExtraction(String),
 
+    /// Building, launching or stopping a process failed
     #[error("Process management error: {0}")]
     ProcessManagement(String),
 
+    /// A type schema couldn't be read or interpreted
     #[error("Schema processing error: {message}")]
     SchemaProcessing {
+        /// What went wrong
         message:   String,
+        /// Type whose schema was being processed
         type_name: Option<String>,
+        /// Step that failed
         operation: Option<String>,
+        /// Further details, such as the underlying error
         details:   Option<String>,
     },
 
-    #[error("Structured error")] // Generic message, the real message comes from the ResultStruct
-    Structured { result: Box<dyn ResultStruct> },
+    /// A failure reported with a full tool result - the message is generic, the real one comes
+    /// from the result
+    #[error("Structured error")]
+    Structured {
+        /// The result returned as the tool's error response
+        result: Box<dyn ResultStruct>,
+    },
 
+    /// The type isn't registered for reflection in the app
     #[error("Type not registered: {type_name}")]
-    TypeNotRegistered { type_name: String },
+    TypeNotRegistered {
+        /// Fully-qualified name of the type
+        type_name: String,
+    },
 
+    /// A tool call failed, with optional details for the response
     #[error("Tool call error: {message}")]
     ToolCall {
+        /// What went wrong
         message: String,
+        /// Structured details added to the error response
         details: Option<serde_json::Value>,
     },
 
+    /// Starting, stopping or reading a watch failed
     #[error("Watch operation failed: {0}")]
     WatchOperation(String),
 }
@@ -142,6 +175,7 @@ impl Error {
     }
 
     /// Create a "Missing X" error
+    #[must_use]
     pub fn missing(what: &str) -> Self {
         Self::InvalidArgument(format!("{MSG_MISSING_PREFIX} {what}"))
     }
@@ -8,6 +8,7 @@ use serde_json::Map;
 use serde_json::Value;
 
 use crate::brp_tools::BrpTypeName;
+use crate::json_schema::JsonSchemaType;
 use crate::json_schema::SchemaField;
 
 /// JSON Schema reference prefix for type definitions
@@ -111,54 +112,100 @@ impl JsonObjectAccess for Map<String, Value> {
     }
 }
 
-/// Coerce string values that look like numbers or booleans into their proper JSON types.
+/// Parse a string that looks like a number or boolean into that JSON type
+///
+/// Returns `None` for strings that are neither, so they stay strings.
+pub fn coerce_string(s: &str) -> Option<Value> {
+    // Try to parse as integer first (more specific)
+    if let Ok(n) = s.parse::<i64>() {
+        return Some(Value::Number(n.into()));
+    }
+    // Try to parse as float
+    if let Ok(f) = s.parse::<f64>() {
+        return serde_json::Number::from_f64(f).map(Value::Number);
+    }
+    // Try to parse as boolean
+    match s {
+        "true" => Some(Value::Bool(true)),
+        "false" => Some(Value::Bool(false)),
+        _ => None,
+    }
+}
+
+/// Coerce string arguments into numbers and booleans where the tool's input schema expects them.
 ///
 /// This is needed because MCP clients may serialize numeric values as strings
 /// (e.g., `"5"` instead of `5`), which causes deserialization errors when
 /// the target expects a numeric type like `f32`.
 ///
-/// This function recursively processes:
-/// - Strings that parse as integers → `Value::Number`
-/// - Strings that parse as floats → `Value::Number`
-/// - Strings "true"/"false" → `Value::Bool`
-/// - Arrays → recursively process each element
-/// - Objects → recursively process each value
+/// Only values whose property schema has `type` `integer`, `number` or `boolean` are converted -
+/// array elements follow the array's `items` schema. String parameters that merely look numeric
+/// (an app named `"123"`) and free-form JSON values are left untouched.
 ///
 /// # Example
-/// ```
+/// ```ignore
 /// use serde_json::json;
-/// let input = json!({"value": "5", "nested": {"x": "3.14", "flag": "true"}});
-/// let output = coerce_string_values(input);
-/// // output = {"value": 5, "nested": {"x": 3.14, "flag": true}}
+/// let schema = json!({"properties": {"port": {"type": "integer"}, "app_name": {"type": "string"}}});
+/// let input = json!({"port": "15702", "app_name": "123"});
+/// let output = coerce_string_values_by_schema(input, schema.as_object().unwrap());
+/// // output = {"port": 15702, "app_name": "123"}
 /// ```
-pub fn coerce_string_values(value: Value) -> Value {
-    match value {
-        Value::String(s) => {
-            // Try to parse as integer first (more specific)
-            if let Ok(n) = s.parse::<i64>() {
-                return Value::Number(n.into());
-            }
-            // Try to parse as float
-            if let Ok(f) = s.parse::<f64>() {
-                if let Some(n) = serde_json::Number::from_f64(f) {
-                    return Value::Number(n);
-                }
-            }
-            // Try to parse as boolean
-            match s.as_str() {
-                "true" => return Value::Bool(true),
-                "false" => return Value::Bool(false),
-                _ => {},
+pub fn coerce_string_values_by_schema(value: Value, input_schema: &Map<String, Value>) -> Value {
+    let Value::Object(args) = value else {
+        return value;
+    };
+    let properties = input_schema
+        .get_field(SchemaField::Properties)
+        .and_then(Value::as_object);
+    Value::Object(
+        args.into_iter()
+            .map(|(name, arg)| {
+                let arg = match properties.and_then(|properties| properties.get(&name)) {
+                    Some(property) => coerce_for_property(arg, property),
+                    None => arg,
+                };
+                (name, arg)
+            })
+            .collect(),
+    )
+}
+
+/// Coerce `value` to the scalar type `property` declares, recursing into array `items`
+fn coerce_for_property(value: Value, property: &Value) -> Value {
+    let schema_type = match property.get_field(SchemaField::Type) {
+        Some(Value::String(schema_type)) => Some(schema_type.as_str()),
+        // `["integer", "null"]` for optional parameters
+        Some(Value::Array(types)) => types
+            .iter()
+            .filter_map(Value::as_str)
+            .find(|schema_type| *schema_type != JsonSchemaType::Null.as_ref()),
+        _ => None,
+    }
+    .and_then(|schema_type| schema_type.parse::<JsonSchemaType>().ok());
+
+    match (schema_type, value) {
+        (Some(JsonSchemaType::Integer | JsonSchemaType::Number), Value::String(s)) => {
+            match coerce_string(&s) {
+                Some(number @ Value::Number(_)) => number,
+                _ => Value::String(s),
             }
-            // Keep as string if no conversion applies
-            Value::String(s)
         },
-        Value::Array(arr) => Value::Array(arr.into_iter().map(coerce_string_values).collect()),
-        Value::Object(obj) => {
-            Value::Object(obj.into_iter().map(|(k, v)| (k, coerce_string_values(v))).collect())
+        (Some(JsonSchemaType::Boolean), Value::String(s)) => match coerce_string(&s) {
+            Some(boolean @ Value::Bool(_)) => boolean,
+            _ => Value::String(s),
+        },
+        (Some(JsonSchemaType::Array), Value::Array(items)) => {
+            match property.get_field(SchemaField::Items) {
+                Some(item_schema) => Value::Array(
+                    items
+                        .into_iter()
+                        .map(|item| coerce_for_property(item, item_schema))
+                        .collect(),
+                ),
+                None => Value::Array(items),
+            }
         },
-        // Pass through other types unchanged
-        other => other,
+        (_, value) => value,
     }
 }
 
@@ -170,7 +217,7 @@ pub fn coerce_string_values(value: Value) -> Value {
 ///
 /// # Examples
 ///
-/// ```
+/// ```ignore
 /// use json_traits::IntoStrings;
 ///
 /// // Convert iterator of &str to Vec<String>
@@ -197,3 +244,60 @@ where
 {
     fn into_strings(self) -> Vec<String> { self.map(Into::into).collect() }
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn coerces_only_numeric_and_boolean_parameters() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "app_name": { "type": "string" },
+                "port": { "type": "integer" },
+                "verbose": { "type": "boolean" },
+                "entities": { "type": "array", "items": { "type": "integer" } },
+                "value": {},
+            },
+        });
+        let args = json!({
+            "app_name": "123",
+            "port": "15702",
+            "verbose": "true",
+            "entities": ["42", "player"],
+            "value": "5",
+        });
+
+        let coerced = coerce_string_values_by_schema(
+            args,
+            schema
+                .as_object()
+                .unwrap_or_else(|| unreachable!("schema is an object")),
+        );
+        assert_eq!(
+            coerced,
+            json!({
+                "app_name": "123",
+                "port": 15702,
+                "verbose": true,
+                "entities": [42, "player"],
+                "value": "5",
+            })
+        );
+    }
+
+    #[test]
+    fn leaves_unparseable_strings_alone() {
+        let schema = json!({ "properties": { "port": { "type": ["integer", "null"] } } });
+        let coerced = coerce_string_values_by_schema(
+            json!({ "port": "default" }),
+            schema
+                .as_object()
+                .unwrap_or_else(|| unreachable!("schema is an object")),
+        );
+        assert_eq!(coerced, json!({ "port": "default" }));
+    }
+}
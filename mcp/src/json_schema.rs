@@ -44,11 +44,15 @@ pub enum SchemaField {
     Const,
     /// The crate name field
     CrateName,
+    /// The default field for default values
+    Default,
     /// The $defs field for schema definitions
     #[strum(serialize = "$defs")]
     Defs,
     /// The description field
     Description,
+    /// The enum field for enumerated values
+    Enum,
     /// The items field for array types
     Items,
     /// Map Key
@@ -57,10 +61,16 @@ pub enum SchemaField {
     KeyType,
     /// The kind field for type categories
     Kind,
+    /// The maximum field for numeric types
+    Maximum,
+    /// The minimum field for numeric types
+    Minimum,
     /// The module path field
     ModulePath,
     /// The oneOf field for enum variants
     OneOf,
+    /// The pattern field for string types
+    Pattern,
     /// The prefixItems field for tuple types
     PrefixItems,
     /// The properties field for object types
@@ -0,0 +1,114 @@
+//! # Bevy BRP client
+//!
+//! The client layer of `bevy_brp_mcp`, for Rust tooling that drives Bevy apps over the Bevy
+//! Remote Protocol without going through MCP. Requests sent with [`BrpClient`] get the same
+//! handling the MCP tools get: format correction of rejected values, type guides attached to
+//! format errors, retries of transient failures and the per-port request queue.
+//!
+//! ```no_run
+//! use bevy_brp_client::BrpClient;
+//! use bevy_brp_client::BrpMethod;
+//! use bevy_brp_client::Port;
+//! use bevy_brp_client::methods::QueryResult;
+//! use serde_json::json;
+//!
+//! # async fn run() -> bevy_brp_client::Result<()> {
+//! let params = json!({
+//!     "data": { "components": ["bevy_transform::components::transform::Transform"] },
+//!     "filter": {},
+//! });
+//! let result = BrpClient::new(BrpMethod::WorldQuery, Port(15702), Some(params))
+//!     .execute::<QueryResult>()
+//!     .await?;
+//! println!("{}", serde_json::to_string_pretty(&result).unwrap_or_default());
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Results are read by serializing them, or use [`BrpClient::execute_raw`] for the untyped
+//! response. The [`methods`] module holds the typed parameters and results of the `world.*` and
+//! `rpc.*` methods, and [`watch`] starts and stops watches that log updates to files.
+//!
+//! The binary built from this crate is the `bevy_brp_mcp` MCP server.
+
+mod app_tools;
+mod brp_tools;
+mod error;
+mod json_object;
+mod json_schema;
+mod log_tools;
+mod mcp_service;
+mod session_tools;
+mod tool;
+
+pub use brp_tools::BrpClient;
+pub use brp_tools::Corrector;
+pub use brp_tools::FormatCorrectionStatus;
+pub use brp_tools::Port;
+pub use brp_tools::RequestPriority;
+pub use brp_tools::ResponseStatus;
+pub use error::Error;
+pub use error::Result;
+pub use tool::BrpMethod;
+
+/// Typed parameters and results of the BRP methods, for `BrpClient::prepare_params` and
+/// `BrpClient::execute`
+pub mod methods {
+    pub use crate::brp_tools::DespawnEntityParams;
+    pub use crate::brp_tools::DespawnEntityResult;
+    pub use crate::brp_tools::GetComponentsParams;
+    pub use crate::brp_tools::GetComponentsResult;
+    pub use crate::brp_tools::GetResourcesParams;
+    pub use crate::brp_tools::GetResourcesResult;
+    pub use crate::brp_tools::InsertComponentsParams;
+    pub use crate::brp_tools::InsertComponentsResult;
+    pub use crate::brp_tools::InsertResourcesParams;
+    pub use crate::brp_tools::InsertResourcesResult;
+    pub use crate::brp_tools::ListComponentsParams;
+    pub use crate::brp_tools::ListComponentsResult;
+    pub use crate::brp_tools::ListResourcesParams;
+    pub use crate::brp_tools::ListResourcesResult;
+    pub use crate::brp_tools::MutateComponentsParams;
+    pub use crate::brp_tools::MutateComponentsResult;
+    pub use crate::brp_tools::MutateResourcesParams;
+    pub use crate::brp_tools::MutateResourcesResult;
+    pub use crate::brp_tools::QueryParams;
+    pub use crate::brp_tools::QueryResult;
+    pub use crate::brp_tools::RemoveComponentsParams;
+    pub use crate::brp_tools::RemoveComponentsResult;
+    pub use crate::brp_tools::RemoveResourcesParams;
+    pub use crate::brp_tools::RemoveResourcesResult;
+    pub use crate::brp_tools::ReparentEntitiesParams;
+    pub use crate::brp_tools::ReparentEntitiesResult;
+    pub use crate::brp_tools::RpcDiscoverParams;
+    pub use crate::brp_tools::RpcDiscoverResult;
+    pub use crate::brp_tools::SpawnEntityParams;
+    pub use crate::brp_tools::SpawnEntityResult;
+}
+
+/// Watches that stream an app's updates to log files until stopped
+pub mod watch {
+    pub use crate::brp_tools::Aggregation;
+    pub use crate::brp_tools::StopCondition;
+    pub use crate::brp_tools::WatchInfo;
+    pub use crate::brp_tools::WatchLabels;
+    pub use crate::brp_tools::WatchOptions;
+    pub use crate::brp_tools::active_watches;
+    pub use crate::brp_tools::stop_watch;
+    pub use crate::brp_tools::watch_component_list;
+    pub use crate::brp_tools::watch_entity;
+    pub use crate::brp_tools::watch_method;
+}
+
+/// Entry points of the `bevy_brp_mcp` server binary
+#[doc(hidden)]
+pub mod server {
+    pub use crate::app_tools::start_watchdog;
+    pub use crate::brp_tools::WatchManager;
+    pub use crate::log_tools::TracingLevel;
+    pub use crate::mcp_service::McpService;
+    pub use crate::session_tools::RUN_TEST_SCRIPT_FLAG;
+    pub use crate::session_tools::catalog_path;
+    pub use crate::session_tools::run_test_script_headless;
+    pub use crate::session_tools::write_tool_catalog;
+}
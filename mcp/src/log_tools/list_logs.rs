@@ -120,7 +120,7 @@ fn list_log_files(
 }
 
 /// Individual log file entry
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct LogFileInfo {
     /// The filename
     pub filename:   String,
@@ -0,0 +1,182 @@
+//! `brp_list_screenshots` tool - List captures in the managed screenshot directory
+
+use std::cmp::Reverse;
+use std::fs;
+use std::path::Path;
+
+use bevy_brp_mcp_macros::ParamStruct;
+use bevy_brp_mcp_macros::ResultStruct;
+use bevy_brp_mcp_macros::ToolFn;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+
+use super::support;
+use crate::error::Error;
+use crate::error::Result;
+use crate::tool::HandlerContext;
+use crate::tool::HandlerResult;
+use crate::tool::ToolFn;
+use crate::tool::ToolResult;
+
+#[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
+pub struct ListScreenshotsParams {
+    /// Optional filter to list screenshots of a specific target only
+    #[to_metadata(skip_if_none)]
+    pub target_name: Option<String>,
+    /// Optional filter to list screenshots taken on a specific port only
+    #[to_metadata(skip_if_none)]
+    pub port:        Option<u16>,
+}
+
+/// A screenshot in the managed directory
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ScreenshotInfo {
+    /// Full path to the screenshot
+    pub path:     String,
+    /// Target the screenshot was taken of
+    pub target:   String,
+    /// BRP port of the instance
+    pub port:     u16,
+    /// When the screenshot was requested
+    pub taken_at: String,
+    /// Human-readable file size
+    pub size:     String,
+    /// Capture time in milliseconds since the epoch, for sorting
+    #[serde(skip)]
+    timestamp_ms: u128,
+}
+
+/// Result from listing screenshots
+#[derive(Debug, Clone, Serialize, Deserialize, ResultStruct)]
+pub struct ListScreenshotsResult {
+    /// Screenshots found, newest first
+    #[to_result]
+    screenshots:          Vec<ScreenshotInfo>,
+    /// Path to the managed screenshot directory
+    #[to_metadata]
+    screenshot_directory: String,
+    /// Screenshot count
+    #[to_metadata]
+    screenshot_count:     usize,
+    /// Message template for formatting responses
+    #[to_message(message_template = "Found {screenshot_count} screenshots")]
+    message_template:     String,
+}
+
+#[derive(ToolFn)]
+#[tool_fn(params = "ListScreenshotsParams", output = "ListScreenshotsResult")]
+pub struct ListScreenshots;
+
+#[allow(clippy::unused_async)]
+async fn handle_impl(params: ListScreenshotsParams) -> Result<ListScreenshotsResult> {
+    let directory = support::get_screenshot_directory();
+    let screenshots = list_screenshots(&directory, params.target_name.as_deref(), params.port)?;
+    Ok(ListScreenshotsResult::new(
+        screenshots.clone(),
+        directory.display().to_string(),
+        screenshots.len(),
+    ))
+}
+
+/// The managed screenshots in `directory` matching the filters, newest first
+fn list_screenshots(
+    directory: &Path,
+    target_filter: Option<&str>,
+    port_filter: Option<u16>,
+) -> Result<Vec<ScreenshotInfo>> {
+    // Nothing has been captured yet
+    if !directory.exists() {
+        return Ok(Vec::new());
+    }
+
+    let entries = fs::read_dir(directory)
+        .map_err(|e| Error::io_failed("read screenshot directory", directory, &e))?;
+
+    let mut screenshots: Vec<ScreenshotInfo> = entries
+        .filter_map(std::result::Result::ok)
+        .filter_map(|entry| {
+            let filename = entry.file_name().to_string_lossy().to_string();
+            let (target, port, timestamp) = support::parse_screenshot_filename(&filename)?;
+            if target_filter.is_some_and(|filter| filter != target)
+                || port_filter.is_some_and(|filter| filter != port)
+            {
+                return None;
+            }
+
+            let timestamp_ms = timestamp.parse::<u128>().unwrap_or(0);
+            let taken_at = i64::try_from(timestamp_ms)
+                .ok()
+                .and_then(chrono::DateTime::from_timestamp_millis)
+                .map(|time| {
+                    time.with_timezone(&chrono::Local)
+                        .format("%Y-%m-%d %H:%M:%S")
+                        .to_string()
+                })
+                .unwrap_or(timestamp);
+            let size = entry.metadata().map_or(0, |metadata| metadata.len());
+
+            Some(ScreenshotInfo {
+                path: entry.path().display().to_string(),
+                target,
+                port,
+                taken_at,
+                size: support::format_bytes(size),
+                timestamp_ms,
+            })
+        })
+        .collect();
+
+    screenshots.sort_by_key(|screenshot| Reverse(screenshot.timestamp_ms));
+    Ok(screenshots)
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn listed(directory: &Path, target: Option<&str>, port: Option<u16>) -> Vec<(String, u16)> {
+        list_screenshots(directory, target, port)
+            .unwrap_or_else(|e| unreachable!("listing should succeed: {e:?}"))
+            .into_iter()
+            .map(|screenshot| (screenshot.target, screenshot.port))
+            .collect()
+    }
+
+    #[test]
+    fn lists_managed_screenshots_newest_first_with_filters() {
+        let dir = TempDir::new().unwrap_or_else(|e| unreachable!("temp dir: {e}"));
+        for name in [
+            "space_game_port15702_1700000000000.png",
+            "space_game_port15703_1700000002000.png",
+            "my_app_port15702_1700000001000.png",
+            "notes.txt",
+            "space_game.png",
+        ] {
+            fs::write(dir.path().join(name), b"png")
+                .unwrap_or_else(|e| unreachable!("write {name}: {e}"));
+        }
+
+        assert_eq!(
+            listed(dir.path(), None, None),
+            vec![
+                ("space_game".to_string(), 15703),
+                ("my_app".to_string(), 15702),
+                ("space_game".to_string(), 15702),
+            ]
+        );
+        assert_eq!(
+            listed(dir.path(), Some("space_game"), Some(15702)),
+            vec![("space_game".to_string(), 15702)]
+        );
+        assert!(listed(dir.path(), Some("other"), None).is_empty());
+    }
+
+    #[test]
+    fn missing_directory_lists_nothing() {
+        let dir = TempDir::new().unwrap_or_else(|e| unreachable!("temp dir: {e}"));
+        assert!(listed(&dir.path().join("never_created"), None, None).is_empty());
+    }
+}
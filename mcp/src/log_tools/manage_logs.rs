@@ -0,0 +1,495 @@
+//! `brp_manage_logs` tool - Disk usage report and retention rules for log output
+//!
+//! Every launch, watch and stderr split leaves a file in the temp directory, and long-lived
+//! setups accumulate gigabytes of them. Files are grouped by the target that produced them and
+//! pruned by age, by a per-target keep-last count and by a total size cap, in that order. The
+//! logs of instances that are still running are reported but never deleted.
+
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::LazyLock;
+use std::time::Duration;
+use std::time::SystemTime;
+
+use bevy_brp_mcp_macros::ParamStruct;
+use bevy_brp_mcp_macros::ResultStruct;
+use bevy_brp_mcp_macros::ToolFn;
+use error_stack::ResultExt;
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+
+use super::support;
+use crate::app_tools::instance_registry;
+use crate::error::Error;
+use crate::error::Result;
+use crate::tool::HandlerContext;
+use crate::tool::HandlerResult;
+use crate::tool::ToolFn;
+use crate::tool::ToolResult;
+
+/// Target that watch logs are grouped under
+const WATCH_TARGET: &str = "watch";
+
+/// Target that files from `screenshot_dir` are grouped under
+const SCREENSHOT_TARGET: &str = "screenshots";
+
+/// Extensions of the files managed in `screenshot_dir`
+const SCREENSHOT_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "bmp"];
+
+/// Strips the port (and stderr marker) from an app log's name to get the target name
+static APP_LOG_TARGET_REGEX: LazyLock<Option<Regex>> =
+    LazyLock::new(|| Regex::new(r"^(.+?)(?:_stderr)?_port\d+$").ok());
+
+#[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
+pub struct ManageLogsParams {
+    /// Delete files older than this many seconds
+    #[to_metadata(skip_if_none)]
+    pub max_age_seconds: Option<u32>,
+    /// Keep only the newest N files of each target
+    #[to_metadata(skip_if_none)]
+    pub keep_last:       Option<u32>,
+    /// Delete the oldest files until the total is at most this many megabytes
+    #[to_metadata(skip_if_none)]
+    pub max_total_mb:    Option<u32>,
+    /// Other directory of screenshots to include, grouped as the `screenshots` target - it must be
+    /// inside the log directory (the managed screenshot directory is always included)
+    #[to_metadata(skip_if_none)]
+    pub screenshot_dir:  Option<String>,
+    /// Report what the rules would delete without deleting anything (default: false)
+    #[serde(default)]
+    pub dry_run:         bool,
+}
+
+/// Disk usage of one target's files
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct TargetUsage {
+    /// Target the files belong to
+    pub target:     String,
+    /// Number of files
+    pub file_count: usize,
+    /// Total size in bytes
+    pub bytes:      u64,
+    /// Human-readable total size
+    pub size:       String,
+}
+
+/// Result from managing log files
+#[derive(Debug, Clone, Serialize, Deserialize, ResultStruct)]
+pub struct ManageLogsResult {
+    /// Disk usage per target before any deletion, largest first
+    #[to_result]
+    usage:            Vec<TargetUsage>,
+    /// Files deleted, or that would be deleted in a dry run
+    #[to_metadata]
+    deleted_files:    Vec<String>,
+    /// Number of files deleted
+    #[to_metadata]
+    deleted_count:    usize,
+    /// Human-readable size of the deleted files
+    #[to_metadata]
+    freed:            String,
+    /// Human-readable size of all managed files before deletion
+    #[to_metadata]
+    total_size:       String,
+    /// Whether this was a dry run
+    #[to_metadata]
+    dry_run:          bool,
+    /// Message template for formatting responses
+    #[to_message]
+    message_template: Option<String>,
+}
+
+#[derive(ToolFn)]
+#[tool_fn(params = "ManageLogsParams", output = "ManageLogsResult")]
+pub struct ManageLogs;
+
+#[allow(clippy::unused_async)]
+async fn handle_impl(params: ManageLogsParams) -> Result<ManageLogsResult> {
+    let mut files = collect_log_files()?;
+    files.extend(collect_managed_screenshots()?);
+    if let Some(screenshot_dir) = &params.screenshot_dir {
+        let screenshot_dir = resolve_screenshot_dir(screenshot_dir)?;
+        if !is_managed_screenshot_dir(&screenshot_dir) {
+            files.extend(collect_screenshots(&screenshot_dir)?);
+        }
+    }
+
+    let rules = RetentionRules {
+        max_age:         params
+            .max_age_seconds
+            .map(|seconds| Duration::from_secs(u64::from(seconds))),
+        keep_last:       params
+            .keep_last
+            .map(|count| usize::try_from(count).unwrap_or(usize::MAX)),
+        max_total_bytes: params
+            .max_total_mb
+            .map(|megabytes| u64::from(megabytes) * 1024 * 1024),
+    };
+    let selected = select_for_deletion(&files, &rules, SystemTime::now());
+
+    let mut deleted_files = Vec::new();
+    let mut freed_bytes = 0;
+    for file in selected.iter().map(|&index| &files[index]) {
+        if params.dry_run || fs::remove_file(&file.path).is_ok() {
+            deleted_files.push(file.path.display().to_string());
+            freed_bytes += file.bytes;
+        }
+    }
+
+    let usage = usage_by_target(&files);
+    let total_bytes = files.iter().map(|file| file.bytes).sum();
+    let message = format!(
+        "Managed files use {} across {} targets; {} {} files ({})",
+        support::format_bytes(total_bytes),
+        usage.len(),
+        if params.dry_run {
+            "would delete"
+        } else {
+            "deleted"
+        },
+        deleted_files.len(),
+        support::format_bytes(freed_bytes)
+    );
+
+    Ok(ManageLogsResult::new(
+        usage,
+        deleted_files.clone(),
+        deleted_files.len(),
+        support::format_bytes(freed_bytes),
+        support::format_bytes(total_bytes),
+        params.dry_run,
+    )
+    .with_message_template(message))
+}
+
+/// A file subject to the retention rules
+#[derive(Debug, Clone)]
+struct ManagedFile {
+    path:     PathBuf,
+    target:   String,
+    bytes:    u64,
+    modified: SystemTime,
+    /// Whether a running instance still writes to the file - it is never deleted
+    in_use:   bool,
+}
+
+/// Retention rules - each one that is set can select files for deletion
+#[derive(Debug, Clone, Default)]
+struct RetentionRules {
+    max_age:         Option<Duration>,
+    keep_last:       Option<usize>,
+    max_total_bytes: Option<u64>,
+}
+
+/// All `bevy_brp_mcp` log files in the temp directory
+fn collect_log_files() -> Result<Vec<ManagedFile>> {
+    let entries =
+        support::iterate_log_files(|_| true).map_err(|e| Error::tool_call_failed(e.to_string()))?;
+    let running: BTreeSet<PathBuf> = instance_registry::live_instances()
+        .into_iter()
+        .flat_map(|instance| [Some(instance.log_file), instance.stderr_log_file])
+        .flatten()
+        .map(PathBuf::from)
+        .collect();
+
+    Ok(entries
+        .into_iter()
+        .map(|entry| ManagedFile {
+            target:   target_of(&entry.app_name),
+            bytes:    entry.metadata.len(),
+            modified: entry.metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+            in_use:   running.contains(&entry.path),
+            path:     entry.path,
+        })
+        .collect())
+}
+
+/// Screenshots in the managed screenshot directory, grouped by the target they were taken of
+fn collect_managed_screenshots() -> Result<Vec<ManagedFile>> {
+    let dir = support::get_screenshot_directory();
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    Ok(collect_screenshots(&dir)?
+        .into_iter()
+        .map(|mut file| {
+            let filename = file
+                .path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string());
+            if let Some((target, _, _)) =
+                filename.and_then(|name| support::parse_screenshot_filename(&name))
+            {
+                file.target = format!("{SCREENSHOT_TARGET}/{target}");
+            }
+            file
+        })
+        .collect())
+}
+
+/// The canonical path of `screenshot_dir`, refused unless it is a directory inside the log
+/// directory - the tool deletes what it finds there
+fn resolve_screenshot_dir(screenshot_dir: &str) -> Result<PathBuf> {
+    let log_dir = support::get_log_directory();
+    let requested = Path::new(screenshot_dir);
+    let dir = if requested.is_absolute() {
+        requested.to_path_buf()
+    } else {
+        log_dir.join(requested)
+    };
+
+    let (Ok(dir), Ok(log_dir)) = (dir.canonicalize(), log_dir.canonicalize()) else {
+        return Err(Error::missing(&format!("screenshot directory '{screenshot_dir}'")).into());
+    };
+    if !dir.starts_with(&log_dir) || !dir.is_dir() {
+        return Err(Error::invalid(
+            "screenshot_dir",
+            format!(
+                "'{screenshot_dir}' is not a directory inside the log directory {}",
+                log_dir.display()
+            ),
+        )
+        .into());
+    }
+    Ok(dir)
+}
+
+/// Whether `dir` is the managed screenshot directory, whose files are already included
+fn is_managed_screenshot_dir(dir: &Path) -> bool {
+    match (
+        dir.canonicalize(),
+        support::get_screenshot_directory().canonicalize(),
+    ) {
+        (Ok(dir), Ok(managed)) => dir == managed,
+        _ => false,
+    }
+}
+
+/// Image files directly inside `dir`
+fn collect_screenshots(dir: &Path) -> Result<Vec<ManagedFile>> {
+    let entries = fs::read_dir(dir)
+        .change_context(Error::FileOperation(
+            "Failed to read screenshot directory".to_string(),
+        ))
+        .attach(format!("Path: {}", dir.display()))?;
+
+    Ok(entries
+        .filter_map(std::result::Result::ok)
+        .filter(|entry| {
+            entry.path().extension().is_some_and(|extension| {
+                SCREENSHOT_EXTENSIONS
+                    .iter()
+                    .any(|image| extension.eq_ignore_ascii_case(image))
+            })
+        })
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok().filter(fs::Metadata::is_file)?;
+            Some(ManagedFile {
+                path:     entry.path(),
+                target:   SCREENSHOT_TARGET.to_string(),
+                bytes:    metadata.len(),
+                modified: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+                in_use:   false,
+            })
+        })
+        .collect())
+}
+
+/// Target a log belongs to, from the name part of its filename
+///
+/// App logs (`{target}_port{port}`, `{target}_stderr_port{port}`) group by target, and all
+/// watch logs group together.
+fn target_of(log_name: &str) -> String {
+    if log_name.starts_with("watch_") {
+        return WATCH_TARGET.to_string();
+    }
+    APP_LOG_TARGET_REGEX
+        .as_ref()
+        .and_then(|regex| regex.captures(log_name))
+        .and_then(|captures| captures.get(1))
+        .map_or_else(
+            || log_name.to_string(),
+            |target| target.as_str().to_string(),
+        )
+}
+
+/// Indices of the files the rules delete: anything past `max_age`, anything beyond the newest
+/// `keep_last` of its target, then the oldest remaining files until the total fits
+/// `max_total_bytes` - files in use are never selected, though they count towards both limits
+fn select_for_deletion(
+    files: &[ManagedFile],
+    rules: &RetentionRules,
+    now: SystemTime,
+) -> BTreeSet<usize> {
+    let mut selected = BTreeSet::new();
+
+    if let Some(max_age) = rules.max_age {
+        selected.extend(files.iter().enumerate().filter_map(|(index, file)| {
+            (!file.in_use
+                && now
+                    .duration_since(file.modified)
+                    .is_ok_and(|age| age > max_age))
+            .then_some(index)
+        }));
+    }
+
+    if let Some(keep_last) = rules.keep_last {
+        let mut by_target: BTreeMap<&str, Vec<usize>> = BTreeMap::new();
+        for (index, file) in files.iter().enumerate() {
+            by_target.entry(&file.target).or_default().push(index);
+        }
+        for mut indices in by_target.into_values() {
+            indices.sort_by_key(|&index| std::cmp::Reverse(files[index].modified));
+            selected.extend(
+                indices
+                    .into_iter()
+                    .skip(keep_last)
+                    .filter(|&index| !files[index].in_use),
+            );
+        }
+    }
+
+    if let Some(max_total_bytes) = rules.max_total_bytes {
+        let mut remaining: Vec<usize> = (0..files.len())
+            .filter(|index| !selected.contains(index))
+            .collect();
+        remaining.sort_by_key(|&index| files[index].modified);
+        let mut total: u64 = remaining.iter().map(|&index| files[index].bytes).sum();
+        for index in remaining.into_iter().filter(|&index| !files[index].in_use) {
+            if total <= max_total_bytes {
+                break;
+            }
+            total -= files[index].bytes;
+            selected.insert(index);
+        }
+    }
+
+    selected
+}
+
+/// File count and size per target, largest first
+fn usage_by_target(files: &[ManagedFile]) -> Vec<TargetUsage> {
+    let mut totals: BTreeMap<&str, (usize, u64)> = BTreeMap::new();
+    for file in files {
+        let (count, bytes) = totals.entry(&file.target).or_default();
+        *count += 1;
+        *bytes += file.bytes;
+    }
+
+    let mut usage: Vec<TargetUsage> = totals
+        .into_iter()
+        .map(|(target, (file_count, bytes))| TargetUsage {
+            target: target.to_string(),
+            file_count,
+            bytes,
+            size: support::format_bytes(bytes),
+        })
+        .collect();
+    usage.sort_by_key(|usage| Reverse(usage.bytes));
+    usage
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(target: &str, bytes: u64, age_seconds: u64, now: SystemTime) -> ManagedFile {
+        ManagedFile {
+            path: PathBuf::from(format!("/tmp/{target}_{age_seconds}.log")),
+            target: target.to_string(),
+            bytes,
+            modified: now - Duration::from_secs(age_seconds),
+            in_use: false,
+        }
+    }
+
+    #[test]
+    fn targets_group_app_stderr_and_watch_logs() {
+        assert_eq!(target_of("my_game_port15702"), "my_game");
+        assert_eq!(target_of("my_game_stderr_port15703"), "my_game");
+        assert_eq!(target_of("watch_3_get_42"), WATCH_TARGET);
+        assert_eq!(target_of("something_else"), "something_else");
+    }
+
+    #[test]
+    fn rules_select_old_surplus_and_oversized_files() {
+        let now = SystemTime::now();
+        let files = vec![
+            file("game", 100, 10, now),
+            file("game", 100, 20, now),
+            file("game", 100, 30, now),
+            file("editor", 100, 5, now),
+            file("editor", 100, 5_000, now),
+        ];
+
+        let by_age = RetentionRules {
+            max_age: Some(Duration::from_secs(1_000)),
+            ..RetentionRules::default()
+        };
+        assert_eq!(
+            select_for_deletion(&files, &by_age, now),
+            BTreeSet::from([4])
+        );
+
+        let keep_one = RetentionRules {
+            keep_last: Some(1),
+            ..RetentionRules::default()
+        };
+        assert_eq!(
+            select_for_deletion(&files, &keep_one, now),
+            BTreeSet::from([1, 2, 4])
+        );
+
+        let cap = RetentionRules {
+            max_total_bytes: Some(250),
+            ..RetentionRules::default()
+        };
+        assert_eq!(
+            select_for_deletion(&files, &cap, now),
+            BTreeSet::from([1, 2, 4])
+        );
+    }
+
+    #[test]
+    fn files_in_use_are_never_selected() {
+        let now = SystemTime::now();
+        let mut files = vec![
+            file("game", 100, 10, now),
+            file("game", 100, 20, now),
+            file("game", 100, 5_000, now),
+        ];
+        files[2].in_use = true;
+
+        let rules = RetentionRules {
+            max_age:         Some(Duration::from_secs(1_000)),
+            keep_last:       Some(1),
+            max_total_bytes: Some(0),
+        };
+        assert_eq!(
+            select_for_deletion(&files, &rules, now),
+            BTreeSet::from([0, 1])
+        );
+    }
+
+    #[test]
+    fn screenshot_dirs_outside_the_log_directory_are_refused() {
+        let outside = std::env::current_dir()
+            .unwrap_or_else(|e| unreachable!("the test has a working directory: {e}"));
+        assert!(resolve_screenshot_dir(&outside.display().to_string()).is_err());
+        assert!(resolve_screenshot_dir("../").is_err());
+
+        let inside =
+            tempfile::TempDir::new().unwrap_or_else(|e| unreachable!("temp dir is writable: {e}"));
+        assert_eq!(
+            resolve_screenshot_dir(&inside.path().display().to_string()).ok(),
+            inside.path().canonicalize().ok()
+        );
+    }
+}
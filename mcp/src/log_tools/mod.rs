@@ -5,7 +5,10 @@ mod delete_logs;
 mod get_trace_log_path;
 mod lazy_file_writer;
 mod list_logs;
+mod list_screenshots;
+mod manage_logs;
 mod read_log;
+mod search_log;
 #[cfg(feature = "mcp-debug")]
 mod set_tracing_level;
 mod support;
@@ -18,10 +21,18 @@ pub use delete_logs::DeleteLogsParams;
 pub use get_trace_log_path::GetTraceLogPath;
 pub use list_logs::ListLogs;
 pub use list_logs::ListLogsParams;
+pub use list_screenshots::ListScreenshots;
+pub use list_screenshots::ListScreenshotsParams;
+pub use manage_logs::ManageLogs;
+pub use manage_logs::ManageLogsParams;
 pub use read_log::ReadLog;
 pub use read_log::ReadLogParams;
+pub use search_log::SearchLog;
+pub use search_log::SearchLogParams;
 #[cfg(feature = "mcp-debug")]
 pub use set_tracing_level::SetTracingLevel;
 #[cfg(feature = "mcp-debug")]
 pub use set_tracing_level::SetTracingLevelParams;
+pub use support::get_log_directory;
+pub use support::screenshot_file_path;
 pub use tracing::TracingLevel;
@@ -1,6 +1,9 @@
 use std::fs::File;
 use std::io::BufRead;
 use std::io::BufReader;
+use std::io::Read;
+use std::io::Seek;
+use std::io::SeekFrom;
 use std::path::Path;
 
 use bevy_brp_mcp_macros::ParamStruct;
@@ -18,9 +21,13 @@ use crate::tool::HandlerResult;
 use crate::tool::ToolFn;
 use crate::tool::ToolResult;
 
+/// Bytes returned per call in offset mode when `max_bytes` is not given
+const DEFAULT_MAX_BYTES: u32 = 256 * 1024;
+
 #[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
 pub struct ReadLogParams {
     /// The log filename (e.g., `bevy_brp_mcp_myapp_1234567890.log`)
+    #[param(regex = r"^bevy_brp_mcp_.*\.log$")]
     pub filename:   String,
     /// Optional keyword to filter lines (case-insensitive)
     #[to_metadata(skip_if_none)]
@@ -28,6 +35,13 @@ pub struct ReadLogParams {
     /// Optional number of lines to read from the end of file
     #[to_metadata(skip_if_none)]
     pub tail_lines: Option<u32>,
+    /// Byte offset to read from - pass the previous call's `end_offset` to get only new output
+    #[to_metadata(skip_if_none)]
+    pub offset:     Option<u64>,
+    /// Maximum bytes to read in offset mode (default: 262144)
+    #[to_metadata(skip_if_none)]
+    #[param(min = 1)]
+    pub max_bytes:  Option<u32>,
 }
 
 /// Result from reading a log file
@@ -58,6 +72,9 @@ pub struct ReadLogResult {
     /// Whether tail mode was used
     #[to_metadata]
     tail_mode:           bool,
+    /// Byte offset the read ended at - the `offset` for the next incremental read
+    #[to_metadata]
+    end_offset:          u64,
     /// Message template for formatting responses
     #[to_message(message_template = "Read {lines_read} lines from {filename}")]
     message_template:    String,
@@ -82,10 +99,6 @@ async fn handle_impl(params: ReadLogParams) -> Result<ReadLogResult> {
 
     let filename = &params.filename;
     let keyword = params.keyword.as_deref();
-    // Validate filename format for security
-    if !support::is_valid_log_filename(filename) {
-        return Err(Error::invalid("filename", "only bevy_brp_mcp log files can be read").into());
-    }
 
     // Build full path
     let log_path = support::get_log_file_path(filename);
@@ -95,8 +108,20 @@ async fn handle_impl(params: ReadLogParams) -> Result<ReadLogResult> {
         return Err(Error::missing(&format!("log file '{filename}'")).into());
     }
 
-    // Read the log file
-    let (content, metadata) = read_log_file(&log_path, keyword, tail_lines)?;
+    // Read the log file, either whole or from the requested byte offset
+    let (content, metadata, end_offset) = if let Some(offset) = params.offset {
+        if tail_lines.is_some() {
+            return Err(
+                Error::invalid("tail_lines", "tail_lines cannot be combined with offset").into(),
+            );
+        }
+        let max_bytes = params.max_bytes.unwrap_or(DEFAULT_MAX_BYTES);
+        read_log_range(&log_path, keyword, offset, u64::from(max_bytes))?
+    } else {
+        let (content, metadata) = read_log_file(&log_path, keyword, tail_lines)?;
+        let end_offset = metadata.len();
+        (content, metadata, end_offset)
+    };
 
     Ok(ReadLogResult::new(
         params.filename,
@@ -107,6 +132,7 @@ async fn handle_impl(params: ReadLogParams) -> Result<ReadLogResult> {
         content,
         keyword.is_some(),
         tail_lines.is_some(),
+        end_offset,
     ))
 }
 
@@ -153,3 +179,78 @@ fn read_log_file(
     let content = final_lines.join("\n");
     Ok((content, metadata))
 }
+
+/// Read up to `max_bytes` from `offset`, returning the content and the offset it ended at
+///
+/// Every read ends after the last complete line - whether it was cut short by `max_bytes` or
+/// reached the end of the file - so a line still being written is returned whole by the next read.
+fn read_log_range(
+    path: &Path,
+    keyword: Option<&str>,
+    offset: u64,
+    max_bytes: u64,
+) -> Result<(String, std::fs::Metadata, u64)> {
+    let metadata =
+        std::fs::metadata(path).map_err(|e| Error::io_failed("get file metadata", path, &e))?;
+    if offset > metadata.len() {
+        return Err(Error::invalid(
+            "offset",
+            format!(
+                "offset {offset} is past the end of the file ({} bytes) - the log was \
+                 truncated or replaced, read again from 0",
+                metadata.len()
+            ),
+        )
+        .into());
+    }
+
+    let mut file = File::open(path).map_err(|e| Error::io_failed("open log file", path, &e))?;
+    file.seek(SeekFrom::Start(offset))
+        .map_err(|e| Error::io_failed("seek in log file", path, &e))?;
+    let mut buffer = Vec::new();
+    file.take(max_bytes)
+        .read_to_end(&mut buffer)
+        .map_err(|e| Error::io_failed("read log file", path, &e))?;
+
+    let reached_limit = buffer.len() as u64 == max_bytes;
+    buffer.truncate(complete_lines_len(&buffer, reached_limit));
+    let end_offset = offset + buffer.len() as u64;
+
+    let content = String::from_utf8_lossy(&buffer);
+    let content = keyword.map_or_else(
+        || content.to_string(),
+        |kw| {
+            let kw = kw.to_lowercase();
+            content
+                .lines()
+                .filter(|line| line.to_lowercase().contains(&kw))
+                .collect::<Vec<_>>()
+                .join("\n")
+        },
+    );
+    Ok((content, metadata, end_offset))
+}
+
+/// Length of `buffer` to return: up to the last newline - or, when the read hit its byte limit
+/// inside a single line longer than the limit, everything so the reader still makes progress
+fn complete_lines_len(buffer: &[u8], reached_limit: bool) -> usize {
+    let unfinished = if reached_limit { buffer.len() } else { 0 };
+    buffer
+        .iter()
+        .rposition(|&byte| byte == b'\n')
+        .map_or(unfinished, |newline| newline + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_end_on_a_line_boundary() {
+        assert_eq!(complete_lines_len(b"one\ntwo\nthr", true), 8);
+        assert_eq!(complete_lines_len(b"one\ntwo\nthr", false), 8);
+        assert_eq!(complete_lines_len(b"one\ntwo\n", false), 8);
+        assert_eq!(complete_lines_len(b"a very long line", true), 16);
+        assert_eq!(complete_lines_len(b"half a line", false), 0);
+    }
+}
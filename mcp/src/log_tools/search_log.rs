@@ -0,0 +1,281 @@
+//! `brp_search_log` tool - Regex search over a log file with context lines
+//!
+//! `brp_read_log` tails or keyword-filters a log, which misses an error that scrolled past
+//! mid-run and strips the lines around it. This searches the whole file and returns each match
+//! with its surrounding lines.
+
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::path::Path;
+use std::path::PathBuf;
+
+use bevy_brp_mcp_macros::ParamStruct;
+use bevy_brp_mcp_macros::ResultStruct;
+use bevy_brp_mcp_macros::ToolFn;
+use regex::Regex;
+use regex::RegexBuilder;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+
+use super::support;
+use crate::error::Error;
+use crate::error::Result;
+use crate::tool::HandlerContext;
+use crate::tool::HandlerResult;
+use crate::tool::ToolFn;
+use crate::tool::ToolResult;
+
+/// Matches returned when `max_matches` is not given
+const DEFAULT_MAX_MATCHES: u32 = 100;
+
+#[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
+pub struct SearchLogParams {
+    /// Log filename, or a full path inside the log directory (e.g. an instance's `log_file` from
+    /// a launch result)
+    pub filename:         String,
+    /// Regular expression to search for
+    pub pattern:          String,
+    /// Match case-insensitively (default: false)
+    #[serde(default)]
+    pub case_insensitive: bool,
+    /// Lines of context to include before each match (default: 0)
+    #[to_metadata(skip_if_none)]
+    #[param(max = 100)]
+    pub before:           Option<u32>,
+    /// Lines of context to include after each match (default: 0)
+    #[to_metadata(skip_if_none)]
+    #[param(max = 100)]
+    pub after:            Option<u32>,
+    /// Maximum number of matches to return (default: 100) - all matches are still counted
+    #[to_metadata(skip_if_none)]
+    #[param(min = 1)]
+    pub max_matches:      Option<u32>,
+}
+
+/// A matching line with its context
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct LogMatch {
+    /// 1-based line number of the match
+    pub line_number: usize,
+    /// The matching line
+    pub line:        String,
+    /// Lines immediately before the match
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub before:      Vec<String>,
+    /// Lines immediately after the match
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub after:       Vec<String>,
+}
+
+/// Result from searching a log file
+#[derive(Debug, Clone, Serialize, Deserialize, ResultStruct)]
+pub struct SearchLogResult {
+    /// Full path to the searched file
+    #[to_metadata]
+    file_path:        String,
+    /// Number of lines searched
+    #[to_metadata]
+    lines_searched:   usize,
+    /// Number of matching lines in the whole file
+    #[to_metadata]
+    total_matches:    usize,
+    /// Number of matches returned
+    #[to_metadata]
+    returned_matches: usize,
+    /// Whether matches beyond `max_matches` were left out
+    #[to_metadata]
+    truncated:        bool,
+    /// Matches with their context, in file order
+    #[to_result]
+    matches:          Vec<LogMatch>,
+    /// Message template for formatting responses
+    #[to_message(message_template = "Found {total_matches} matches in {file_path}")]
+    message_template: String,
+}
+
+#[derive(ToolFn)]
+#[tool_fn(params = "SearchLogParams", output = "SearchLogResult")]
+pub struct SearchLog;
+
+#[allow(clippy::unused_async)]
+async fn handle_impl(params: SearchLogParams) -> Result<SearchLogResult> {
+    let regex = RegexBuilder::new(&params.pattern)
+        .case_insensitive(params.case_insensitive)
+        .build()
+        .map_err(|e| Error::invalid("pattern", e))?;
+    let to_usize = |value: u32| usize::try_from(value).unwrap_or(usize::MAX);
+    let mut search = LogSearch::new(
+        regex,
+        params.before.map_or(0, to_usize),
+        params.after.map_or(0, to_usize),
+        to_usize(params.max_matches.unwrap_or(DEFAULT_MAX_MATCHES)),
+    );
+
+    let log_path = resolve_log_path(&params.filename)?;
+    let file =
+        File::open(&log_path).map_err(|e| Error::io_failed("open log file", &log_path, &e))?;
+    for line in BufReader::new(file).split(b'\n') {
+        let line = line.map_err(|e| Error::io_failed("read line from log", &log_path, &e))?;
+        // Game output isn't guaranteed to be UTF-8
+        let line = String::from_utf8_lossy(&line);
+        search.push(line.trim_end_matches('\r'));
+    }
+
+    let lines_searched = search.lines_searched;
+    let total_matches = search.total_matches;
+    let matches = search.finish();
+    Ok(SearchLogResult::new(
+        log_path.display().to_string(),
+        lines_searched,
+        total_matches,
+        matches.len(),
+        total_matches > matches.len(),
+        matches,
+    ))
+}
+
+/// Resolve `filename` to a file inside the log directory, rejecting anything outside it
+fn resolve_log_path(filename: &str) -> Result<PathBuf> {
+    let log_dir = support::get_log_directory();
+    let requested = Path::new(filename);
+    let path = if requested.is_absolute() {
+        requested.to_path_buf()
+    } else {
+        log_dir.join(requested)
+    };
+
+    let (Ok(path), Ok(log_dir)) = (path.canonicalize(), log_dir.canonicalize()) else {
+        return Err(Error::missing(&format!("log file '{filename}'")).into());
+    };
+    if !path.starts_with(&log_dir) || !path.is_file() {
+        return Err(Error::invalid(
+            "filename",
+            format!(
+                "'{filename}' is not a file in the log directory {}",
+                log_dir.display()
+            ),
+        )
+        .into());
+    }
+    Ok(path)
+}
+
+/// Streaming regex search that keeps the context lines around each match
+struct LogSearch {
+    regex:          Regex,
+    before:         usize,
+    after:          usize,
+    max_matches:    usize,
+    /// The most recent lines, for the next match's `before` context
+    recent:         VecDeque<String>,
+    /// Matches returned so far; the last ones may still be collecting `after` context
+    matches:        Vec<LogMatch>,
+    lines_searched: usize,
+    total_matches:  usize,
+}
+
+impl LogSearch {
+    const fn new(regex: Regex, before: usize, after: usize, max_matches: usize) -> Self {
+        Self {
+            regex,
+            before,
+            after,
+            max_matches,
+            recent: VecDeque::new(),
+            matches: Vec::new(),
+            lines_searched: 0,
+            total_matches: 0,
+        }
+    }
+
+    /// Search the next line of the file
+    fn push(&mut self, line: &str) {
+        self.lines_searched += 1;
+
+        // Earlier matches still short of their `after` context take this line
+        for pending in self.matches.iter_mut().rev() {
+            if pending.line_number + self.after < self.lines_searched {
+                break;
+            }
+            pending.after.push(line.to_string());
+        }
+
+        if self.regex.is_match(line) {
+            self.total_matches += 1;
+            if self.matches.len() < self.max_matches {
+                self.matches.push(LogMatch {
+                    line_number: self.lines_searched,
+                    line:        line.to_string(),
+                    before:      self.recent.iter().cloned().collect(),
+                    after:       Vec::new(),
+                });
+            }
+        }
+
+        if self.before > 0 {
+            if self.recent.len() == self.before {
+                self.recent.pop_front();
+            }
+            self.recent.push_back(line.to_string());
+        }
+    }
+
+    /// The matches found, in file order
+    fn finish(self) -> Vec<LogMatch> { self.matches }
+}
+
+#[cfg(test)]
+#[allow(clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    fn search(lines: &[&str], pattern: &str, before: usize, after: usize, max: usize) -> LogSearch {
+        let regex = Regex::new(pattern).expect("valid test pattern");
+        let mut search = LogSearch::new(regex, before, after, max);
+        for line in lines {
+            search.push(line);
+        }
+        search
+    }
+
+    #[test]
+    fn matches_carry_before_and_after_context() {
+        let lines = ["a", "b", "ERROR one", "c", "d", "ERROR two", "e"];
+        let matches = search(&lines, "^ERROR", 1, 1, 10).finish();
+
+        assert_eq!(
+            matches,
+            vec![
+                LogMatch {
+                    line_number: 3,
+                    line:        "ERROR one".to_string(),
+                    before:      vec!["b".to_string()],
+                    after:       vec!["c".to_string()],
+                },
+                LogMatch {
+                    line_number: 6,
+                    line:        "ERROR two".to_string(),
+                    before:      vec!["d".to_string()],
+                    after:       vec!["e".to_string()],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn context_is_cut_at_file_edges_and_matches_are_capped() {
+        let lines = ["panic", "x", "panic", "panic"];
+        let search = search(&lines, "panic", 2, 3, 2);
+
+        assert_eq!(search.total_matches, 3);
+        let matches = search.finish();
+        assert_eq!(matches.len(), 2);
+        assert!(matches[0].before.is_empty());
+        assert_eq!(matches[0].after, vec!["x", "panic", "panic"]);
+        assert_eq!(matches[1].before, vec!["panic", "x"]);
+        assert_eq!(matches[1].after, vec!["panic"]);
+    }
+}
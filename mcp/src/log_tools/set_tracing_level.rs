@@ -1,5 +1,3 @@
-use std::str::FromStr;
-
 use async_trait::async_trait;
 use bevy_brp_mcp_macros::ParamStruct;
 use bevy_brp_mcp_macros::ResultStruct;
@@ -8,14 +6,13 @@ use serde::Deserialize;
 use serde::Serialize;
 
 use super::tracing::TracingLevel;
-use crate::error::Error;
 use crate::error::Result;
 use crate::tool::ToolFn;
 
 #[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
 pub struct SetTracingLevelParams {
     /// Tracing level to set (error, warn, info, debug, trace)
-    pub level: String,
+    pub level: TracingLevel,
 }
 
 /// Result from setting the tracing level
@@ -40,20 +37,7 @@ impl ToolFn for SetTracingLevel {
     type Params = SetTracingLevelParams;
 
     async fn handle_impl(&self, params: SetTracingLevelParams) -> Result<SetTracingLevelResult> {
-        // Parse the tracing level
-        let tracing_level = match TracingLevel::from_str(&params.level) {
-            Ok(level) => level,
-            Err(e) => {
-                return Err(Error::invalid(
-                    "tracing level",
-                    format!(
-                        "{}: {e}. Valid levels are: error, warn, info, debug, trace",
-                        params.level
-                    ),
-                )
-                .into());
-            },
-        };
+        let tracing_level = params.level;
 
         // Update the tracing level
         TracingLevel::set_tracing_level(tracing_level);
@@ -11,11 +11,17 @@ use crate::error::Result;
 // Constants
 pub const LOG_PREFIX: &str = "bevy_brp_mcp_";
 pub const LOG_EXTENSION: &str = ".log";
+pub const SCREENSHOT_DIR_NAME: &str = "bevy_brp_mcp_screenshots";
+pub const SCREENSHOT_EXTENSION: &str = ".png";
 
 // Static regex for parsing app log filenames
 static APP_LOG_REGEX: LazyLock<Option<Regex>> =
     LazyLock::new(|| Regex::new(r"^bevy_brp_mcp_(.+?)_port\d+_(\d+)_\d+\.log$").ok());
 
+// Static regex for parsing managed screenshot filenames
+static SCREENSHOT_REGEX: LazyLock<Option<Regex>> =
+    LazyLock::new(|| Regex::new(r"^(.+?)_port(\d+)_(\d+)\.png$").ok());
+
 /// Validates if a filename follows the `bevy_brp_mcp` log naming convention
 pub fn is_valid_log_filename(filename: &str) -> bool {
     filename.starts_with(LOG_PREFIX) && filename.ends_with(LOG_EXTENSION)
@@ -101,6 +107,29 @@ pub fn get_log_directory() -> PathBuf { std::env::temp_dir() }
 /// Gets the full path for a log file given its filename
 pub fn get_log_file_path(filename: &str) -> PathBuf { get_log_directory().join(filename) }
 
+/// Gets the managed screenshot directory, next to the logs
+pub fn get_screenshot_directory() -> PathBuf { get_log_directory().join(SCREENSHOT_DIR_NAME) }
+
+/// Builds the managed path for a screenshot of `target` on `port` taken at `timestamp_ms`
+///
+/// Format: {`target`}_port{port}_{timestamp}.png
+pub fn screenshot_file_path(target: &str, port: u16, timestamp_ms: u128) -> PathBuf {
+    get_screenshot_directory().join(format!(
+        "{target}_port{port}_{timestamp_ms}{SCREENSHOT_EXTENSION}"
+    ))
+}
+
+/// Parses a managed screenshot filename into target, port and timestamp
+/// Returns `Some((target, port, timestamp_str))` if it follows the managed naming, `None` otherwise
+pub fn parse_screenshot_filename(filename: &str) -> Option<(String, u16, String)> {
+    let captures = SCREENSHOT_REGEX.as_ref()?.captures(filename)?;
+    Some((
+        captures.get(1)?.as_str().to_string(),
+        captures.get(2)?.as_str().parse().ok()?,
+        captures.get(3)?.as_str().to_string(),
+    ))
+}
+
 /// Represents a log file entry with metadata
 #[derive(Debug, Clone)]
 pub struct LogFileEntry {
@@ -220,3 +249,34 @@ where
 
     Ok(log_entries)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn screenshot_filenames_round_trip() {
+        let path = screenshot_file_path("my_game", 15702, 1_700_000_000_000);
+        let filename = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        assert_eq!(filename, "my_game_port15702_1700000000000.png");
+        assert_eq!(path.parent(), Some(get_screenshot_directory().as_path()));
+        assert_eq!(
+            parse_screenshot_filename(&filename),
+            Some(("my_game".to_string(), 15702, "1700000000000".to_string()))
+        );
+    }
+
+    #[test]
+    fn targets_may_contain_port_and_other_names_are_rejected() {
+        assert_eq!(
+            parse_screenshot_filename("game_port2_port15702_1.png"),
+            Some(("game_port2".to_string(), 15702, "1".to_string()))
+        );
+        assert_eq!(parse_screenshot_filename("capture.png"), None);
+        assert_eq!(parse_screenshot_filename("game_port15702_1.jpg"), None);
+        assert_eq!(parse_screenshot_filename("game_port99999_1.png"), None);
+    }
+}
@@ -2,6 +2,10 @@ use std::str::FromStr;
 use std::sync::atomic::AtomicU8;
 use std::sync::atomic::Ordering;
 
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Deserializer;
+use serde::Serialize;
 use tracing::Level;
 use tracing::Subscriber;
 use tracing_subscriber::Layer;
@@ -50,12 +54,18 @@ where
 }
 
 /// Represents tracing levels that can be set dynamically
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
 pub enum TracingLevel {
+    /// Only errors
     Error,
+    /// Errors and warnings (default)
     Warn,
+    /// General operational information
     Info,
+    /// Detailed diagnostics, including BRP request and response bodies
     Debug,
+    /// Everything
     Trace,
 }
 
@@ -76,6 +86,17 @@ impl FromStr for TracingLevel {
     }
 }
 
+/// Parses through `FromStr`, so levels are accepted in any case
+impl<'de> Deserialize<'de> for TracingLevel {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let level = String::deserialize(deserializer)?;
+        level.parse().map_err(serde::de::Error::custom)
+    }
+}
+
 impl TracingLevel {
     #[cfg(feature = "mcp-debug")]
     const fn as_u8(self) -> u8 {
@@ -88,7 +109,9 @@ impl TracingLevel {
         }
     }
 
+    /// Lowercase name of the level, as accepted by `FromStr`
     #[cfg(feature = "mcp-debug")]
+    #[must_use]
     pub const fn as_str(self) -> &'static str {
         match self {
             Self::Error => "error",
@@ -151,7 +174,27 @@ impl TracingLevel {
 
     /// Get the path to the trace log file
     /// Useful for testing and troubleshooting
+    #[must_use]
     pub fn get_trace_log_path() -> std::path::PathBuf {
         std::env::temp_dir().join("bevy_brp_mcp_trace.log")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn levels_parse_in_any_case() {
+        assert_eq!("WARN".parse::<TracingLevel>(), Ok(TracingLevel::Warn));
+        assert_eq!(
+            serde_json::from_value::<TracingLevel>(serde_json::json!("Debug")).ok(),
+            Some(TracingLevel::Debug)
+        );
+        assert!(serde_json::from_value::<TracingLevel>(serde_json::json!("verbose")).is_err());
+        assert_eq!(
+            serde_json::to_value(TracingLevel::Trace).ok(),
+            Some(serde_json::json!("trace"))
+        );
+    }
+}
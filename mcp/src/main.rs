@@ -7,35 +7,56 @@
 //! Bevy applications at runtime through a standardized MCP interface.
 
 use std::error::Error;
-
-use brp_tools::WatchManager;
-use log_tools::TracingLevel;
-use mcp_service::McpService;
+use std::process::ExitCode;
+
+use bevy_brp_client::server::McpService;
+use bevy_brp_client::server::RUN_TEST_SCRIPT_FLAG;
+use bevy_brp_client::server::TracingLevel;
+use bevy_brp_client::server::WatchManager;
+use bevy_brp_client::server::catalog_path;
+use bevy_brp_client::server::run_test_script_headless;
+use bevy_brp_client::server::start_watchdog;
+use bevy_brp_client::server::write_tool_catalog;
 use rmcp::ServiceExt;
 use rmcp::transport::stdio;
 
-mod app_tools;
-mod brp_tools;
-mod error;
-mod json_object;
-mod json_schema;
-mod log_tools;
-mod mcp_service;
-mod tool;
+/// Flag that writes the tool catalog - to the path after it, if given - and exits instead of
+/// serving
+const EXPORT_TOOL_CATALOG_FLAG: &str = "--export-tool-catalog";
 
 #[tokio::main]
-async fn main() -> Result<(), Box<dyn Error>> {
+async fn main() -> Result<ExitCode, Box<dyn Error>> {
     // Initialize file-based tracing with dynamic level management
     // Uses lazy file creation - file only created on first log write
     TracingLevel::init_file_tracing();
 
+    // Write the tool catalog for offline use without starting the server
+    let mut args = std::env::args().skip(1);
+    let mode = args.next();
+    if mode.as_deref() == Some(EXPORT_TOOL_CATALOG_FLAG) {
+        let path = catalog_path(args.next().as_deref());
+        let (tool_count, _) = write_tool_catalog(&path).map_err(|report| format!("{report:?}"))?;
+        println!("Exported {tool_count} tools to {}", path.display());
+        return Ok(ExitCode::SUCCESS);
+    }
+
     // Initialize the watch manager
     WatchManager::initialize_watch_manager().await;
 
+    // Run a test script headlessly for CI, exiting with how it went
+    if mode.as_deref() == Some(RUN_TEST_SCRIPT_FLAG) {
+        return Ok(run_test_script_headless(args).await);
+    }
+
     let service = McpService::new();
+    let notification_level = service.notification_level();
 
     let server = service.serve(stdio()).await?;
+
+    // Watch launched instances for hangs, reporting them to the client
+    start_watchdog(server.peer().clone(), notification_level);
+
     server.waiting().await?;
 
-    Ok(())
+    Ok(ExitCode::SUCCESS)
 }
@@ -3,34 +3,53 @@ use std::path::PathBuf;
 
 use itertools::Itertools;
 use rmcp::ErrorData as McpError;
-use rmcp::ServiceError;
 use rmcp::Peer;
 use rmcp::RoleServer;
 use rmcp::ServerHandler;
+use rmcp::ServiceError;
 use rmcp::model::CallToolRequestParam;
 use rmcp::model::CallToolResult;
 use rmcp::model::ErrorCode;
 use rmcp::model::ListToolsResult;
 use rmcp::model::PaginatedRequestParam;
 use rmcp::model::ServerCapabilities;
+use rmcp::model::SetLevelRequestParam;
 use rmcp::model::Tool;
 use rmcp::service::RequestContext;
 
+use crate::app_tools;
+use crate::app_tools::NotificationLevel;
+use crate::tool::ProgressReporter;
 use crate::tool::ToolDef;
 use crate::tool::ToolName;
 
+/// Whether the client supports MCP roots, which tools use as their search paths
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RootsSupport {
+    /// The client listed this many roots
+    Supported { roots: usize },
+    /// The client didn't declare roots when it initialized, or doesn't implement `roots/list`,
+    /// so the current directory is searched instead
+    Unsupported,
+}
+
 /// MCP service implementation for Bevy Remote Protocol integration.
 ///
 /// This service provides tools for interacting with Bevy applications through BRP,
 /// including entity manipulation, component management, and resource access.
 pub struct McpService {
     /// Tool definitions `HashMap` for O(1) lookup by name
-    tool_defs: HashMap<String, ToolDef>,
+    tool_defs:          HashMap<String, ToolDef>,
     /// Pre-converted MCP tools for list operations
-    tools:     Vec<Tool>,
+    tools:              Vec<Tool>,
+    /// Level set by the client's `logging/setLevel`, shared with the instance watchdog
+    notification_level: NotificationLevel,
 }
 
 impl McpService {
+    /// Create the service with every tool registered, picking up instances launched before a
+    /// server restart
+    #[must_use]
     pub fn new() -> Self {
         let all_defs = ToolName::get_all_tool_definitions();
 
@@ -54,13 +73,28 @@ impl McpService {
             })
             .collect();
 
-        Self { tool_defs, tools }
+        // Pick up instances launched before a server restart
+        app_tools::instance_registry::reload();
+
+        Self {
+            tool_defs,
+            tools,
+            notification_level: NotificationLevel::default(),
+        }
     }
 
+    /// The level the client sets with `logging/setLevel`, for the notifications it receives
+    #[must_use]
+    pub fn notification_level(&self) -> NotificationLevel { self.notification_level.clone() }
+
     /// Get tool definition by name with O(1) lookup
+    #[must_use]
     pub fn get_tool_def(&self, name: &str) -> Option<&ToolDef> { self.tool_defs.get(name) }
 
     /// List all MCP tools using pre-converted and sorted tools
+    ///
+    /// Extras tools stay listed whatever the apps serve - the list is shared by every port, and a
+    /// call to a method the app doesn't serve reports that `bevy_brp_extras` isn't present.
     fn list_mcp_tools(&self) -> ListToolsResult {
         ListToolsResult {
             meta:        None,
@@ -69,14 +103,26 @@ impl McpService {
         }
     }
 
-    /// Fetch roots from the client and return the search paths
+    /// Fetch roots from the client and return the search paths, with how the client supports
+    /// roots
+    ///
+    /// Clients that didn't declare the roots capability when they initialized aren't asked.
     ///
     /// # Errors
     /// Returns an error if the MCP client cannot be contacted or if the `list_roots` call fails.
     pub async fn fetch_roots_and_get_paths(
         &self,
         peer: Peer<RoleServer>,
-    ) -> Result<Vec<PathBuf>, McpError> {
+    ) -> Result<(Vec<PathBuf>, RootsSupport), McpError> {
+        let current_dir = || std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+        if peer
+            .peer_info()
+            .is_some_and(|info| info.capabilities.roots.is_none())
+        {
+            tracing::debug!("Client did not declare roots; falling back to current directory");
+            return Ok((vec![current_dir()], RootsSupport::Unsupported));
+        }
+
         // Fetch current roots from client
         tracing::debug!("Fetching current roots from client...");
 
@@ -108,7 +154,12 @@ impl McpService {
                     .collect();
 
                 tracing::debug!("Processed roots: {:?}", paths);
-                Ok(paths)
+                Ok((
+                    paths,
+                    RootsSupport::Supported {
+                        roots: result.roots.len(),
+                    },
+                ))
             },
             Err(e) => {
                 // Some clients may not implement list_roots; fall back to current dir on -32601
@@ -119,9 +170,10 @@ impl McpService {
                 );
 
                 if method_not_found {
-                    tracing::warn!("Client does not support list_roots (method not found); falling back to current directory");
-                    let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
-                    return Ok(vec![cwd]);
+                    tracing::warn!(
+                        "Client does not support list_roots (method not found); falling back to current directory"
+                    );
+                    return Ok((vec![current_dir()], RootsSupport::Unsupported));
                 }
 
                 tracing::error!("Failed to send roots/list request: {}", e);
@@ -134,10 +186,17 @@ impl McpService {
     }
 }
 
+impl Default for McpService {
+    fn default() -> Self { Self::new() }
+}
+
 impl ServerHandler for McpService {
     fn get_info(&self) -> rmcp::model::ServerInfo {
         rmcp::model::ServerInfo {
-            capabilities: ServerCapabilities::builder().enable_tools().build(),
+            capabilities: ServerCapabilities::builder()
+                .enable_logging()
+                .enable_tools()
+                .build(),
             ..Default::default()
         }
     }
@@ -150,18 +209,31 @@ impl ServerHandler for McpService {
         Ok(self.list_mcp_tools())
     }
 
+    async fn set_level(
+        &self,
+        request: SetLevelRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<(), McpError> {
+        self.notification_level.set(request.level);
+        Ok(())
+    }
+
     async fn call_tool(
         &self,
         request: CallToolRequestParam,
         context: RequestContext<RoleServer>,
     ) -> Result<CallToolResult, McpError> {
         // Fetch roots and get paths
-        let roots = self.fetch_roots_and_get_paths(context.peer.clone()).await?;
+        let (roots, roots_support) = self.fetch_roots_and_get_paths(context.peer.clone()).await?;
 
         let tool_def = self.get_tool_def(&request.name).ok_or_else(|| {
             McpError::invalid_params(format!("unknown tool: {}", request.name), None)
         })?;
 
-        tool_def.call_tool(request, roots).await
+        let progress = ProgressReporter::from_request_context(&context);
+
+        tool_def
+            .call_tool(request, roots, roots_support, progress)
+            .await
     }
 }
@@ -0,0 +1,107 @@
+//! `brp_acquire_control` tool - Take a named, time-boxed control lock on a port
+//!
+//! Acquiring again as the same owner renews the lock. Another owner's lock is reported as an
+//! error naming the holder and how long it has left.
+
+use std::time::Duration;
+
+use bevy_brp_mcp_macros::ParamStruct;
+use bevy_brp_mcp_macros::ResultStruct;
+use bevy_brp_mcp_macros::ToolFn;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+
+use super::control_lock;
+use super::control_lock::DEFAULT_LOCK_TTL;
+use crate::brp_tools::Port;
+use crate::error::Error;
+use crate::error::Result;
+use crate::tool::HandlerContext;
+use crate::tool::HandlerResult;
+use crate::tool::ToolFn;
+use crate::tool::ToolResult;
+
+#[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
+pub struct AcquireControlParams {
+    /// Name of the agent or person taking control, e.g. `level-editor-agent`
+    pub owner: String,
+
+    /// Seconds until the lock expires unless renewed by acquiring again (default: 300, max: 3600)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[param(min = 1, max = 3600)]
+    #[schemars(extend("default" = 300))]
+    pub ttl_seconds: Option<u64>,
+
+    /// If true, mutating tools aimed at the port are refused unless they pass this owner as
+    /// `control_owner`; otherwise the lock is advisory (default: false)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enforce: Option<bool>,
+
+    /// The BRP port to lock
+    #[serde(default)]
+    pub port: Port,
+}
+
+/// Result from acquiring a control lock
+#[derive(Debug, Clone, Serialize, Deserialize, ResultStruct)]
+pub struct AcquireControlResult {
+    /// Port the lock is on
+    #[to_metadata]
+    port:               u16,
+    /// Who holds the lock
+    #[to_metadata]
+    owner:              String,
+    /// Whether mutating tools from other owners are refused
+    #[to_metadata]
+    enforce:            bool,
+    /// Whether the owner already held the lock and renewed it
+    #[to_metadata]
+    renewed:            bool,
+    /// When the lock was first acquired
+    #[to_metadata]
+    acquired_at:        String,
+    /// Seconds until the lock expires
+    #[to_metadata]
+    expires_in_seconds: u64,
+    /// Message template for formatting responses
+    #[to_message(message_template = "'{owner}' controls port {port} for {expires_in_seconds}s")]
+    message_template:   String,
+}
+
+#[derive(ToolFn)]
+#[tool_fn(params = "AcquireControlParams", output = "AcquireControlResult")]
+pub struct AcquireControl;
+
+#[allow(clippy::unused_async)]
+async fn handle_impl(params: AcquireControlParams) -> Result<AcquireControlResult> {
+    let owner = params.owner.trim();
+    if owner.is_empty() {
+        return Err(Error::invalid("owner", "cannot be empty").into());
+    }
+    let ttl = params
+        .ttl_seconds
+        .map_or(DEFAULT_LOCK_TTL, Duration::from_secs);
+
+    let (lock, renewed) =
+        control_lock::acquire(params.port, owner, ttl, params.enforce.unwrap_or(false)).map_err(
+            |holder| {
+                Error::cannot(
+                    "acquire control",
+                    format!(
+                        "port {} is held by '{}' for another {}s",
+                        holder.port, holder.owner, holder.expires_in_seconds
+                    ),
+                )
+            },
+        )?;
+
+    Ok(AcquireControlResult::new(
+        lock.port,
+        lock.owner,
+        lock.enforce,
+        renewed,
+        lock.acquired_at,
+        lock.expires_in_seconds,
+    ))
+}
@@ -0,0 +1,370 @@
+//! Advisory control locks on ports, shared by everyone using this server
+//!
+//! When several agents - or an agent and a human - drive one app through the same server, their
+//! mutations can interleave. `brp_acquire_control` records a named owner for a port in this
+//! session's registry for a limited time, and `brp_release_control` ends it early. A lock is
+//! advisory unless it was acquired with `enforce: true`: then any mutating tool aimed at the port
+//! is refused unless the call carries `control_owner` naming the holder. Expired locks are
+//! dropped the next time the locks are read, and the holder of each lock is kept on the port's
+//! entry in the instance registry, so `brp_list_instances` shows who controls each app.
+
+use std::collections::HashMap;
+use std::sync::LazyLock;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use std::sync::PoisonError;
+use std::time::Duration;
+use std::time::Instant;
+
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Map;
+use serde_json::Value;
+use serde_json::json;
+
+use crate::app_tools::instance_registry;
+use crate::brp_tools::Port;
+use crate::tool::ParameterName;
+use crate::tool::ToolName;
+
+/// How long a lock is held unless `ttl_seconds` says otherwise
+pub const DEFAULT_LOCK_TTL: Duration = Duration::from_mins(5);
+
+/// Held locks by port
+static LOCKS: LazyLock<Mutex<ControlLocks>> = LazyLock::new(|| Mutex::new(ControlLocks::default()));
+
+/// A lock as reported to callers
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct ControlLockInfo {
+    /// Port the lock is on
+    pub port:               u16,
+    /// Who holds it
+    pub owner:              String,
+    /// Whether mutating tools from other owners are refused
+    pub enforce:            bool,
+    /// When it was first acquired
+    pub acquired_at:        String,
+    /// Seconds until it expires
+    pub expires_in_seconds: u64,
+}
+
+/// A held lock
+#[derive(Debug, Clone)]
+struct ControlLock {
+    owner:       String,
+    enforce:     bool,
+    acquired_at: String,
+    expires_at:  Instant,
+}
+
+impl ControlLock {
+    fn info(&self, port: Port, now: Instant) -> ControlLockInfo {
+        ControlLockInfo {
+            port:               port.0,
+            owner:              self.owner.clone(),
+            enforce:            self.enforce,
+            acquired_at:        self.acquired_at.clone(),
+            expires_in_seconds: self.expires_at.saturating_duration_since(now).as_secs(),
+        }
+    }
+}
+
+/// Outcome of releasing a lock
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Released {
+    /// The lock was released
+    Lock(ControlLockInfo),
+    /// Nobody held the port
+    NotHeld,
+    /// Another owner holds the port and `force` wasn't given
+    HeldByOther(ControlLockInfo),
+}
+
+/// The locks behind `LOCKS`, with the current time passed in
+#[derive(Debug, Default)]
+struct ControlLocks {
+    locks:   HashMap<Port, ControlLock>,
+    /// Owner changes not yet written to the instance registry
+    changes: Vec<(Port, Option<String>)>,
+}
+
+impl ControlLocks {
+    fn prune(&mut self, now: Instant) {
+        let changes = &mut self.changes;
+        self.locks.retain(|port, lock| {
+            let live = lock.expires_at > now;
+            if !live {
+                changes.push((*port, None));
+            }
+            live
+        });
+    }
+
+    /// Take or renew the lock on `port` - `Err` with the holder when another owner has it
+    fn acquire(
+        &mut self,
+        port: Port,
+        owner: &str,
+        ttl: Duration,
+        enforce: bool,
+        now: Instant,
+    ) -> Result<(ControlLockInfo, bool), ControlLockInfo> {
+        self.prune(now);
+        let renewed = match self.locks.get(&port) {
+            Some(lock) if lock.owner != owner => return Err(lock.info(port, now)),
+            Some(_) => true,
+            None => false,
+        };
+        let acquired_at = self.locks.get(&port).map_or_else(
+            || chrono::Local::now().to_rfc3339(),
+            |lock| lock.acquired_at.clone(),
+        );
+        let lock = ControlLock {
+            owner: owner.to_string(),
+            enforce,
+            acquired_at,
+            expires_at: now + ttl,
+        };
+        let info = lock.info(port, now);
+        if !renewed {
+            self.changes.push((port, Some(info.owner.clone())));
+        }
+        self.locks.insert(port, lock);
+        Ok((info, renewed))
+    }
+
+    fn release(&mut self, port: Port, owner: &str, force: bool, now: Instant) -> Released {
+        self.prune(now);
+        match self.locks.get(&port) {
+            None => Released::NotHeld,
+            Some(lock) if lock.owner != owner && !force => {
+                Released::HeldByOther(lock.info(port, now))
+            },
+            Some(_) => self.locks.remove(&port).map_or(Released::NotHeld, |lock| {
+                self.changes.push((port, None));
+                Released::Lock(lock.info(port, now))
+            }),
+        }
+    }
+
+    fn enforced_holder(&mut self, port: Port, now: Instant) -> Option<ControlLockInfo> {
+        self.prune(now);
+        self.locks
+            .get(&port)
+            .filter(|lock| lock.enforce)
+            .map(|lock| lock.info(port, now))
+    }
+}
+
+/// Take or renew the lock on `port` for `owner` - `Err` with the holder when another owner has it
+///
+/// The `bool` is whether `owner` already held the lock and renewed it.
+pub fn acquire(
+    port: Port,
+    owner: &str,
+    ttl: Duration,
+    enforce: bool,
+) -> Result<(ControlLockInfo, bool), ControlLockInfo> {
+    with_locks(|locks| locks.acquire(port, owner, ttl, enforce, Instant::now()))
+}
+
+/// Release `owner`'s lock on `port` - `force` releases another owner's lock
+pub fn release(port: Port, owner: &str, force: bool) -> Released {
+    with_locks(|locks| locks.release(port, owner, force, Instant::now()))
+}
+
+/// Drop expired locks, clearing their holders from the instance registry
+pub fn prune_expired() { with_locks(|locks| locks.prune(Instant::now())); }
+
+/// Run `f` on the locks, then record any owner changes in the instance registry
+///
+/// The registry is written after `LOCKS` is released, so the two mutexes are never held together.
+fn with_locks<R>(f: impl FnOnce(&mut ControlLocks) -> R) -> R {
+    let (result, changes) = {
+        let mut locks = locks();
+        let result = f(&mut locks);
+        (result, std::mem::take(&mut locks.changes))
+    };
+    for (port, owner) in changes {
+        instance_registry::set_control_owner(port.0, owner);
+    }
+    result
+}
+
+/// The locks - a panic while they were held leaves nothing half-updated, so poisoning is ignored
+fn locks() -> MutexGuard<'static, ControlLocks> {
+    LOCKS.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Remove `control_owner` from the request arguments and check a mutating call against the
+/// enforced lock on its port, so the field is never forwarded to BRP
+///
+/// `accepts_port` is only asked when an enforced lock exists. Returns `Err` with the details for
+/// a "port is locked" response when another owner holds an enforced lock on the call's port.
+pub fn check_control(
+    tool_name: ToolName,
+    mutates: bool,
+    accepts_port: impl FnOnce() -> bool,
+    arguments: Option<&mut Map<String, Value>>,
+) -> Result<(), Value> {
+    let (control_owner, port) = arguments.map_or((None, None), |args| {
+        (
+            args.remove(ParameterName::ControlOwner.as_ref()),
+            args.get(ParameterName::Port.as_ref()).cloned(),
+        )
+    });
+    if !mutates
+        || matches!(
+            tool_name,
+            ToolName::BrpAcquireControl | ToolName::BrpReleaseControl
+        )
+    {
+        return Ok(());
+    }
+
+    let port = port
+        .and_then(|port| serde_json::from_value::<Port>(port).ok())
+        .unwrap_or_else(Port::session_default);
+    let Some(holder) = with_locks(|locks| locks.enforced_holder(port, Instant::now())) else {
+        return Ok(());
+    };
+    let control_owner = control_owner.as_ref().and_then(Value::as_str);
+    if control_owner == Some(holder.owner.as_str()) || !accepts_port() {
+        return Ok(());
+    }
+
+    Err(json!({
+        "control_locked": true,
+        "tool": tool_name.to_string(),
+        "lock": holder,
+        "how_to_proceed": format!(
+            "Port {} is controlled by '{}' for another {} seconds. Pass `control_owner: \"{}\"` if \
+             you are that owner, wait for `{}`, or take over with `{}` and `force: true`",
+            holder.port,
+            holder.owner,
+            holder.expires_in_seconds,
+            holder.owner,
+            ToolName::BrpReleaseControl,
+            ToolName::BrpReleaseControl,
+        ),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TTL: Duration = Duration::from_mins(1);
+
+    #[test]
+    fn one_owner_holds_a_port_until_release_or_expiry() {
+        let mut locks = ControlLocks::default();
+        let port = Port(15702);
+        let now = Instant::now();
+
+        assert!(locks.acquire(port, "agent-a", TTL, true, now).is_ok());
+        assert_eq!(
+            locks
+                .acquire(port, "agent-b", TTL, false, now)
+                .map_err(|holder| holder.owner),
+            Err("agent-a".to_string())
+        );
+        assert!(matches!(
+            locks.acquire(port, "agent-a", TTL, true, now),
+            Ok((_, true))
+        ));
+        assert!(matches!(
+            locks.release(port, "agent-b", false, now),
+            Released::HeldByOther(_)
+        ));
+        assert!(locks.enforced_holder(port, now).is_some());
+
+        let later = now + TTL + Duration::from_secs(1);
+        assert!(locks.enforced_holder(port, later).is_none());
+        assert!(locks.acquire(port, "agent-b", TTL, false, later).is_ok());
+        assert!(matches!(
+            locks.release(port, "agent-b", false, later),
+            Released::Lock(_)
+        ));
+        assert_eq!(
+            locks.release(port, "agent-b", false, later),
+            Released::NotHeld
+        );
+    }
+
+    #[test]
+    fn owner_changes_are_queued_for_the_registry() {
+        let mut locks = ControlLocks::default();
+        let port = Port(15703);
+        let now = Instant::now();
+
+        assert!(locks.acquire(port, "agent-a", TTL, false, now).is_ok());
+        assert!(locks.acquire(port, "agent-a", TTL, false, now).is_ok());
+        assert_eq!(
+            std::mem::take(&mut locks.changes),
+            vec![(port, Some("agent-a".to_string()))]
+        );
+
+        locks.prune(now + TTL);
+        assert_eq!(std::mem::take(&mut locks.changes), vec![(port, None)]);
+
+        assert!(locks.acquire(port, "agent-b", TTL, false, now).is_ok());
+        assert!(matches!(
+            locks.release(port, "agent-b", false, now),
+            Released::Lock(_)
+        ));
+        assert_eq!(
+            locks.changes,
+            vec![(port, Some("agent-b".to_string())), (port, None)]
+        );
+    }
+
+    fn arguments(port: u16, control_owner: Option<&str>) -> Map<String, Value> {
+        let mut arguments = Map::new();
+        arguments.insert(ParameterName::Port.to_string(), json!(port));
+        if let Some(owner) = control_owner {
+            arguments.insert(ParameterName::ControlOwner.to_string(), json!(owner));
+        }
+        arguments
+    }
+
+    #[test]
+    fn check_control_refuses_other_owners_of_an_enforced_lock() {
+        // A port no other test locks, since `check_control` reads the shared registry
+        let port = 47_231;
+        assert!(acquire(Port(port), "agent-a", TTL, true).is_ok());
+
+        let mut args = arguments(port, None);
+        let refused = check_control(ToolName::WorldSpawnEntity, true, || true, Some(&mut args));
+        assert_eq!(
+            refused
+                .err()
+                .and_then(|details| details["lock"]["owner"].as_str().map(str::to_string)),
+            Some("agent-a".to_string())
+        );
+
+        let mut args = arguments(port, Some("agent-b"));
+        assert!(check_control(ToolName::WorldSpawnEntity, true, || true, Some(&mut args)).is_err());
+
+        let mut args = arguments(port, Some("agent-a"));
+        assert!(check_control(ToolName::WorldSpawnEntity, true, || true, Some(&mut args)).is_ok());
+        assert!(!args.contains_key(ParameterName::ControlOwner.as_ref()));
+
+        let mut args = arguments(port, Some("agent-b"));
+        assert!(check_control(ToolName::WorldQuery, false, || true, Some(&mut args)).is_ok());
+        assert!(!args.contains_key(ParameterName::ControlOwner.as_ref()));
+
+        let mut args = arguments(port, None);
+        assert!(check_control(ToolName::WorldSpawnEntity, true, || false, Some(&mut args)).is_ok());
+        let mut args = arguments(port, None);
+        assert!(check_control(ToolName::BrpReleaseControl, true, || true, Some(&mut args)).is_ok());
+
+        assert!(matches!(
+            release(Port(port), "agent-a", false),
+            Released::Lock(_)
+        ));
+        let mut args = arguments(port, None);
+        assert!(check_control(ToolName::WorldSpawnEntity, true, || true, Some(&mut args)).is_ok());
+    }
+}
@@ -0,0 +1,122 @@
+//! `brp_export_session` tool - Write the calls made this session to a file
+//!
+//! The transcript holds each recorded call's tool, arguments, target port, timing and abbreviated
+//! response, so a session can be attached to a bug report or replayed later.
+
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+
+use bevy_brp_mcp_macros::ParamStruct;
+use bevy_brp_mcp_macros::ResultStruct;
+use bevy_brp_mcp_macros::ToolFn;
+use error_stack::ResultExt;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+
+use super::recorder;
+use super::recorder::RecordedCall;
+use crate::error::Error;
+use crate::error::Result;
+use crate::log_tools::get_log_directory;
+use crate::tool::HandlerContext;
+use crate::tool::HandlerResult;
+use crate::tool::ToolFn;
+use crate::tool::ToolResult;
+
+/// Version of the transcript layout - bumped when fields change meaning
+pub const TRANSCRIPT_FORMAT_VERSION: u32 = 1;
+
+#[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
+pub struct ExportSessionParams {
+    /// File to write the transcript to (default: `bevy_brp_mcp_session_<timestamp>.json` in the
+    /// log directory; parent directories are created as needed)
+    #[to_metadata(skip_if_none)]
+    pub path: Option<String>,
+}
+
+/// A session transcript as written to disk
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SessionTranscript {
+    /// Layout version of this transcript
+    pub format_version: u32,
+    /// When the transcript was written
+    pub exported_at:    String,
+    /// Version of the MCP server that recorded the calls
+    pub server_version: String,
+    /// The recorded calls, oldest first
+    pub calls:          Vec<RecordedCall>,
+}
+
+/// Result from exporting the session transcript
+#[derive(Debug, Clone, Serialize, Deserialize, ResultStruct)]
+pub struct ExportSessionResult {
+    /// File the transcript was written to
+    #[to_metadata]
+    path:             String,
+    /// Number of calls in the transcript
+    #[to_metadata]
+    call_count:       usize,
+    /// Size of the written file in bytes
+    #[to_metadata]
+    bytes_written:    usize,
+    /// Message template for formatting responses
+    #[to_message(message_template = "Exported {call_count} tool calls to {path}")]
+    message_template: String,
+}
+
+#[derive(ToolFn)]
+#[tool_fn(params = "ExportSessionParams", output = "ExportSessionResult")]
+pub struct ExportSession;
+
+#[allow(clippy::unused_async)]
+async fn handle_impl(params: ExportSessionParams) -> Result<ExportSessionResult> {
+    let now = chrono::Local::now();
+    let path = params.path.map_or_else(
+        || {
+            get_log_directory().join(format!(
+                "bevy_brp_mcp_session_{}.json",
+                now.format("%Y%m%d_%H%M%S")
+            ))
+        },
+        PathBuf::from,
+    );
+
+    let transcript = SessionTranscript {
+        format_version: TRANSCRIPT_FORMAT_VERSION,
+        exported_at:    now.to_rfc3339(),
+        server_version: env!("CARGO_PKG_VERSION").to_string(),
+        calls:          recorder::recorded_calls(),
+    };
+    let bytes_written = write_transcript(&path, &transcript)?;
+
+    Ok(ExportSessionResult::new(
+        path.display().to_string(),
+        transcript.calls.len(),
+        bytes_written,
+    ))
+}
+
+/// Write `transcript` to `path` as pretty JSON, returning the number of bytes written
+fn write_transcript(path: &Path, transcript: &SessionTranscript) -> Result<usize> {
+    let json = serde_json::to_string_pretty(transcript).change_context(Error::General(
+        "Failed to serialize session transcript".to_string(),
+    ))?;
+
+    if let Some(parent) = path.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        fs::create_dir_all(parent).change_context(Error::FileOperation(format!(
+            "Failed to create directory {}",
+            parent.display()
+        )))?;
+    }
+
+    fs::write(path, &json).change_context(Error::FileOperation(format!(
+        "Failed to write session transcript to {}",
+        path.display()
+    )))?;
+
+    Ok(json.len())
+}
@@ -0,0 +1,207 @@
+//! `brp_export_tool_catalog` tool - Write every tool's schemas and annotations to a file
+//!
+//! Agents that build prompts offline, or clients that validate calls before sending them, need
+//! the tool list without a live `list_tools` round trip. The catalog holds every tool the server
+//! registers - including extras tools the default app may not serve - with its description,
+//! input and output schemas, annotations, and whether it is gated behind confirmation. Running
+//! the server with `--export-tool-catalog [path]` writes the same file and exits.
+
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+
+use bevy_brp_mcp_macros::ParamStruct;
+use bevy_brp_mcp_macros::ResultStruct;
+use bevy_brp_mcp_macros::ToolFn;
+use error_stack::ResultExt;
+use rmcp::model::Tool;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::error::Error;
+use crate::error::Result;
+use crate::log_tools::get_log_directory;
+use crate::tool::HandlerContext;
+use crate::tool::HandlerResult;
+use crate::tool::ToolFn;
+use crate::tool::ToolName;
+use crate::tool::ToolResult;
+
+/// Version of the catalog layout - bumped when fields change meaning
+pub const CATALOG_FORMAT_VERSION: u32 = 1;
+
+/// File name of the catalog in the log directory when no path is given
+const DEFAULT_CATALOG_FILENAME: &str = "bevy_brp_mcp_tool_catalog.json";
+
+#[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
+pub struct ExportToolCatalogParams {
+    /// File to write the catalog to (default: `bevy_brp_mcp_tool_catalog.json` in the log
+    /// directory; parent directories are created as needed)
+    #[to_metadata(skip_if_none)]
+    pub path: Option<String>,
+}
+
+/// One tool in the catalog
+#[derive(Debug, Clone, Serialize)]
+struct CatalogTool {
+    /// The tool as `list_tools` reports it - name, title, description, schemas, annotations
+    #[serde(flatten)]
+    tool:                  Tool,
+    /// Whether the tool changes app or server state
+    mutates:               bool,
+    /// Whether the tool needs confirmation when `BRP_MCP_REQUIRE_CONFIRMATION` is enabled
+    requires_confirmation: bool,
+}
+
+/// A tool catalog as written to disk
+#[derive(Debug, Clone, Serialize)]
+struct ToolCatalog {
+    /// Layout version of this catalog
+    format_version: u32,
+    /// When the catalog was written
+    exported_at:    String,
+    /// Version of the MCP server the tools belong to
+    server_version: String,
+    /// Number of tools in the catalog
+    tool_count:     usize,
+    /// The tools, sorted by name
+    tools:          Vec<CatalogTool>,
+}
+
+/// Result from exporting the tool catalog
+#[derive(Debug, Clone, Serialize, Deserialize, ResultStruct)]
+pub struct ExportToolCatalogResult {
+    /// File the catalog was written to
+    #[to_metadata]
+    path:             String,
+    /// Number of tools in the catalog
+    #[to_metadata]
+    tool_count:       usize,
+    /// Size of the written file in bytes
+    #[to_metadata]
+    bytes_written:    usize,
+    /// Message template for formatting responses
+    #[to_message(message_template = "Exported {tool_count} tools to {path}")]
+    message_template: String,
+}
+
+#[derive(ToolFn)]
+#[tool_fn(params = "ExportToolCatalogParams", output = "ExportToolCatalogResult")]
+pub struct ExportToolCatalog;
+
+#[allow(clippy::unused_async)]
+async fn handle_impl(params: ExportToolCatalogParams) -> Result<ExportToolCatalogResult> {
+    let path = catalog_path(params.path.as_deref());
+    let (tool_count, bytes_written) = write_tool_catalog(&path)?;
+    Ok(ExportToolCatalogResult::new(
+        path.display().to_string(),
+        tool_count,
+        bytes_written,
+    ))
+}
+
+/// Where to write the catalog - `path`, or the default file in the log directory
+pub fn catalog_path(path: Option<&str>) -> PathBuf {
+    path.map_or_else(
+        || get_log_directory().join(DEFAULT_CATALOG_FILENAME),
+        PathBuf::from,
+    )
+}
+
+/// Write the catalog of every registered tool to `path` as pretty JSON, returning the number of
+/// tools and bytes written
+///
+/// # Errors
+/// Returns an error if the catalog cannot be serialized or written to `path`.
+pub fn write_tool_catalog(path: &Path) -> Result<(usize, usize)> {
+    let mut tools: Vec<CatalogTool> = ToolName::get_all_tool_definitions()
+        .iter()
+        .map(|tool_def| CatalogTool {
+            tool:                  tool_def.to_tool(),
+            mutates:               tool_def.mutates(),
+            requires_confirmation: tool_def.tool_name.requires_confirmation(),
+        })
+        .collect();
+    tools.sort_by(|a, b| a.tool.name.cmp(&b.tool.name));
+
+    let catalog = ToolCatalog {
+        format_version: CATALOG_FORMAT_VERSION,
+        exported_at: chrono::Local::now().to_rfc3339(),
+        server_version: env!("CARGO_PKG_VERSION").to_string(),
+        tool_count: tools.len(),
+        tools,
+    };
+    let json = serde_json::to_string_pretty(&catalog).change_context(Error::General(
+        "Failed to serialize tool catalog".to_string(),
+    ))?;
+
+    if let Some(parent) = path.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        fs::create_dir_all(parent).change_context(Error::FileOperation(format!(
+            "Failed to create directory {}",
+            parent.display()
+        )))?;
+    }
+
+    fs::write(path, &json).change_context(Error::FileOperation(format!(
+        "Failed to write tool catalog to {}",
+        path.display()
+    )))?;
+
+    Ok((catalog.tool_count, json.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn writes_every_tool_sorted_with_its_flags() {
+        let dir = TempDir::new().unwrap_or_else(|e| unreachable!("temp dir: {e}"));
+        let path = dir.path().join("nested").join("catalog.json");
+
+        let (tool_count, bytes_written) = write_tool_catalog(&path)
+            .unwrap_or_else(|e| unreachable!("catalog should be written: {e:?}"));
+
+        let json = fs::read_to_string(&path).unwrap_or_else(|e| unreachable!("read: {e}"));
+        assert_eq!(json.len(), bytes_written);
+        let catalog: Value =
+            serde_json::from_str(&json).unwrap_or_else(|e| unreachable!("parse: {e}"));
+        assert_eq!(catalog["format_version"], CATALOG_FORMAT_VERSION);
+        assert_eq!(catalog["tool_count"], tool_count);
+        assert_eq!(tool_count, ToolName::get_all_tool_definitions().len());
+
+        let tools = catalog["tools"].as_array().map_or(&[][..], Vec::as_slice);
+        let names: Vec<&str> = tools
+            .iter()
+            .filter_map(|tool| tool["name"].as_str())
+            .collect();
+        assert_eq!(names.len(), tool_count);
+        assert!(names.windows(2).all(|pair| pair[0] <= pair[1]));
+
+        let tool = |name: &str| tools.iter().find(|tool| tool["name"] == name);
+        let catalog_tool = tool(ToolName::BrpExportToolCatalog.as_ref())
+            .unwrap_or_else(|| unreachable!("the catalog lists itself"));
+        assert!(catalog_tool["inputSchema"].is_object());
+        let list = tool(ToolName::WorldListComponents.as_ref())
+            .unwrap_or_else(|| unreachable!("world_list_components is registered"));
+        assert_eq!(list["mutates"], false);
+        let despawn = tool(ToolName::WorldDespawnEntity.as_ref())
+            .unwrap_or_else(|| unreachable!("world_despawn_entity is registered"));
+        assert_eq!(despawn["mutates"], true);
+    }
+
+    #[test]
+    fn defaults_to_the_log_directory() {
+        assert_eq!(
+            catalog_path(None),
+            get_log_directory().join(DEFAULT_CATALOG_FILENAME)
+        );
+        assert_eq!(catalog_path(Some("out.json")), PathBuf::from("out.json"));
+    }
+}
@@ -0,0 +1,28 @@
+// Session tools module
+
+mod acquire_control;
+pub mod control_lock;
+mod export_session;
+mod export_tool_catalog;
+pub mod recorder;
+mod release_control;
+mod replay_session;
+mod run_test_script;
+mod test_runner;
+
+pub use acquire_control::AcquireControl;
+pub use acquire_control::AcquireControlParams;
+pub use export_session::ExportSession;
+pub use export_session::ExportSessionParams;
+pub use export_tool_catalog::ExportToolCatalog;
+pub use export_tool_catalog::ExportToolCatalogParams;
+pub use export_tool_catalog::catalog_path;
+pub use export_tool_catalog::write_tool_catalog;
+pub use release_control::ReleaseControl;
+pub use release_control::ReleaseControlParams;
+pub use replay_session::ReplaySession;
+pub use replay_session::ReplaySessionParams;
+pub use run_test_script::RunTestScript;
+pub use run_test_script::RunTestScriptParams;
+pub use test_runner::RUN_TEST_SCRIPT_FLAG;
+pub use test_runner::run_test_script_headless;
@@ -0,0 +1,188 @@
+//! Recording of the tool calls made this session
+//!
+//! Every tool call is recorded with its arguments, an abbreviated response, when it started, how
+//! long it took and the BRP port it targeted, so the session can be exported for reproducibility
+//! and bug reports. Only the most recent `MAX_RECORDED_CALLS` calls are kept.
+
+use std::collections::VecDeque;
+use std::sync::LazyLock;
+use std::sync::Mutex;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+use chrono::DateTime;
+use chrono::Local;
+use rmcp::model::CallToolResult;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Map;
+use serde_json::Value;
+use serde_json::json;
+
+use crate::brp_tools::Port;
+use crate::tool::ParameterName;
+use crate::tool::ToolName;
+
+/// Calls kept at most - the oldest are dropped first
+pub const MAX_RECORDED_CALLS: usize = 1_000;
+
+/// Serialized size above which a response's `result` is replaced by a summary
+const MAX_RESULT_CHARS: usize = 2_000;
+
+/// Characters of an omitted `result` kept as a preview
+const RESULT_PREVIEW_CHARS: usize = 200;
+
+/// Tools whose calls are not recorded - exporting, replaying or scripting a session is not part of
+/// it
+///
+/// The steps of a replay or test script are recorded, as they are calls in their own right.
+const UNRECORDED_TOOLS: &[ToolName] = &[
+    ToolName::BrpExportSession,
+    ToolName::BrpReplaySession,
+    ToolName::BrpRunTestScript,
+];
+
+/// A tool call made this session
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RecordedCall {
+    /// Position of the call in the session, starting at 1
+    pub sequence:    u64,
+    /// When the call started
+    pub timestamp:   String,
+    /// Name of the tool called
+    pub tool:        String,
+    /// BRP port the call targeted - `None` for tools that don't talk to an app
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub port:        Option<u16>,
+    /// Arguments as the client sent them, without confirmation tokens
+    pub arguments:   Value,
+    /// `success` or `error`
+    pub status:      String,
+    /// How long the call took, in milliseconds
+    pub duration_ms: u64,
+    /// The response's message, metadata and - when small enough - result
+    pub response:    Value,
+}
+
+/// Calls recorded this session, oldest first
+static SESSION: LazyLock<Mutex<VecDeque<RecordedCall>>> =
+    LazyLock::new(|| Mutex::new(VecDeque::new()));
+
+/// Sequence number of the next recorded call
+static NEXT_SEQUENCE: AtomicU64 = AtomicU64::new(1);
+
+/// Record a finished tool call
+pub fn record_call(
+    tool_name: ToolName,
+    arguments: Option<&Map<String, Value>>,
+    response: &CallToolResult,
+    started: DateTime<Local>,
+    elapsed: Duration,
+) {
+    if UNRECORDED_TOOLS.contains(&tool_name) {
+        return;
+    }
+
+    let mut arguments = arguments.cloned().unwrap_or_default();
+    arguments.remove(ParameterName::ConfirmationToken.as_ref());
+
+    let port = arguments
+        .get(ParameterName::Port.as_ref())
+        .and_then(Value::as_u64)
+        .and_then(|port| u16::try_from(port).ok())
+        .or_else(|| tool_name.to_brp_method().map(|_| Port::session_default().0));
+
+    let call = RecordedCall {
+        sequence: NEXT_SEQUENCE.fetch_add(1, Ordering::Relaxed),
+        timestamp: started.to_rfc3339(),
+        tool: tool_name.to_string(),
+        port,
+        arguments: Value::Object(arguments),
+        status: if response.is_error == Some(true) {
+            "error".to_string()
+        } else {
+            "success".to_string()
+        },
+        duration_ms: u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX),
+        response: abbreviate_response(response.structured_content.as_ref()),
+    };
+
+    if let Ok(mut session) = SESSION.lock() {
+        while session.len() >= MAX_RECORDED_CALLS {
+            session.pop_front();
+        }
+        session.push_back(call);
+    }
+}
+
+/// The calls recorded this session, oldest first
+pub fn recorded_calls() -> Vec<RecordedCall> {
+    SESSION
+        .lock()
+        .map(|session| session.iter().cloned().collect())
+        .unwrap_or_default()
+}
+
+/// Keep a response's message, metadata and error details, and its result unless it is large
+fn abbreviate_response(response: Option<&Value>) -> Value {
+    let Some(Value::Object(response)) = response else {
+        return Value::Null;
+    };
+
+    let mut abbreviated: Map<String, Value> = ["message", "metadata", "error_info"]
+        .into_iter()
+        .filter_map(|field| Some((field.to_string(), response.get(field)?.clone())))
+        .collect();
+
+    if let Some(result) = response.get("result") {
+        let serialized = result.to_string();
+        let result = if serialized.chars().count() <= MAX_RESULT_CHARS {
+            result.clone()
+        } else {
+            json!({
+                "omitted": true,
+                "size_chars": serialized.chars().count(),
+                "preview": serialized.chars().take(RESULT_PREVIEW_CHARS).collect::<String>(),
+            })
+        };
+        abbreviated.insert("result".to_string(), result);
+    }
+
+    Value::Object(abbreviated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_small_results_and_summarizes_large_ones() {
+        let small = json!({
+            "status": "success",
+            "message": "Spawned entity 42",
+            "metadata": { "entity": 42 },
+            "result": { "entity": 42 },
+            "call_info": { "mcp_tool": "world_spawn_entity" },
+        });
+        assert_eq!(
+            abbreviate_response(Some(&small)),
+            json!({
+                "message": "Spawned entity 42",
+                "metadata": { "entity": 42 },
+                "result": { "entity": 42 },
+            })
+        );
+
+        let large = json!({ "result": "x".repeat(MAX_RESULT_CHARS * 2) });
+        let abbreviated = abbreviate_response(Some(&large));
+        assert_eq!(abbreviated["result"]["omitted"], json!(true));
+        assert_eq!(
+            abbreviated["result"]["preview"]
+                .as_str()
+                .map(|preview| preview.chars().count()),
+            Some(RESULT_PREVIEW_CHARS)
+        );
+    }
+}
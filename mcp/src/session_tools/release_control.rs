@@ -0,0 +1,75 @@
+//! `brp_release_control` tool - Release a control lock before it expires
+//!
+//! Only the holder can release a lock unless `force` is set, which lets a human take back a port
+//! from an agent that stopped responding.
+
+use bevy_brp_mcp_macros::ParamStruct;
+use bevy_brp_mcp_macros::ResultStruct;
+use bevy_brp_mcp_macros::ToolFn;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+
+use super::control_lock;
+use super::control_lock::Released;
+use crate::brp_tools::Port;
+use crate::error::Error;
+use crate::error::Result;
+use crate::tool::HandlerContext;
+use crate::tool::HandlerResult;
+use crate::tool::ToolFn;
+use crate::tool::ToolResult;
+
+#[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
+pub struct ReleaseControlParams {
+    /// Name the lock was acquired under
+    pub owner: String,
+
+    /// If true, release the lock even when another owner holds it (default: false)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub force: Option<bool>,
+
+    /// The BRP port to release
+    #[serde(default)]
+    pub port: Port,
+}
+
+/// Result from releasing a control lock
+#[derive(Debug, Clone, Serialize, Deserialize, ResultStruct)]
+pub struct ReleaseControlResult {
+    /// Port the lock was on
+    #[to_metadata]
+    port:             u16,
+    /// Whether a lock was released - false when nobody held the port
+    #[to_metadata]
+    released:         bool,
+    /// Who held the released lock
+    #[to_metadata(skip_if_none)]
+    previous_owner:   Option<String>,
+    /// Message template for formatting responses
+    #[to_message]
+    message_template: Option<String>,
+}
+
+#[derive(ToolFn)]
+#[tool_fn(params = "ReleaseControlParams", output = "ReleaseControlResult")]
+pub struct ReleaseControl;
+
+#[allow(clippy::literal_string_with_formatting_args, clippy::unused_async)]
+async fn handle_impl(params: ReleaseControlParams) -> Result<ReleaseControlResult> {
+    let port = params.port;
+    match control_lock::release(port, params.owner.trim(), params.force.unwrap_or(false)) {
+        Released::Lock(lock) => Ok(ReleaseControlResult::new(port.0, true, Some(lock.owner))
+            .with_message_template("Released control of port {port} held by '{previous_owner}'")),
+        Released::NotHeld => Ok(ReleaseControlResult::new(port.0, false, None)
+            .with_message_template("Port {port} was not locked")),
+        Released::HeldByOther(holder) => Err(Error::cannot(
+            "release control",
+            format!(
+                "port {} is held by '{}' - pass `force: true` to release it anyway",
+                holder.port, holder.owner
+            ),
+        )
+        .into()),
+    }
+}
@@ -0,0 +1,366 @@
+//! `brp_replay_session` tool - Re-run the calls of an exported session transcript
+//!
+//! Steps run in order through the same path as client calls, optionally against another port.
+//! Entities spawned during the recorded session get new IDs when replayed, so each step's recorded
+//! and replayed `entity` are paired up and later steps have recorded IDs in their `entity`,
+//! `entities` and `parent` arguments replaced by the replayed ones.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+use bevy_brp_mcp_macros::ParamStruct;
+use bevy_brp_mcp_macros::ResultStruct;
+use bevy_brp_mcp_macros::ToolFn;
+use error_stack::Report;
+use error_stack::ResultExt;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Map;
+use serde_json::Value;
+
+use super::export_session::SessionTranscript;
+use super::export_session::TRANSCRIPT_FORMAT_VERSION;
+use super::recorder::RecordedCall;
+use crate::brp_tools::Port;
+use crate::error::Error;
+use crate::error::Result;
+use crate::tool::HandlerContext;
+use crate::tool::HandlerResult;
+use crate::tool::ParameterName;
+use crate::tool::ToolFn;
+use crate::tool::ToolName;
+use crate::tool::ToolResult;
+
+/// Arguments holding entity IDs that are remapped between recording and replay
+const ENTITY_ARGUMENTS: &[ParameterName] = &[
+    ParameterName::Entity,
+    ParameterName::Entities,
+    ParameterName::Parent,
+];
+
+#[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
+pub struct ReplaySessionParams {
+    /// Transcript file written by `brp_export_session`
+    #[to_metadata]
+    pub path:            String,
+    /// Send every BRP step to this port instead of the port it was recorded against
+    #[to_metadata(skip_if_none)]
+    pub port:            Option<Port>,
+    /// Known recorded -> replayed entity IDs, keyed by the recorded ID (e.g. `{"4294967298":
+    /// 4294967310}`) - added to the IDs learned from spawns during the replay
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub entity_map:      Option<BTreeMap<String, u64>>,
+    /// Also replay steps that failed when they were recorded (default: false)
+    #[serde(default)]
+    pub include_failed:  bool,
+    /// Stop at the first step that fails and skip the rest (default: false)
+    #[serde(default)]
+    pub stop_on_failure: bool,
+}
+
+/// Outcome of one replayed step
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ReplayStep {
+    /// Sequence number of the step in the recorded session
+    pub sequence:        u64,
+    /// Tool the step called
+    pub tool:            String,
+    /// `success`, `error` or `skipped`
+    pub status:          String,
+    /// Status the step had when it was recorded
+    pub recorded_status: String,
+    /// Message of the replayed response, or why the step was skipped
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message:         Option<String>,
+    /// Port the step was sent to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub port:            Option<u16>,
+    /// How long the replayed step took, in milliseconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration_ms:     Option<u64>,
+}
+
+/// Result from replaying a session transcript
+#[derive(Debug, Clone, Serialize, Deserialize, ResultStruct)]
+pub struct ReplaySessionResult {
+    /// Outcome of each step, in recorded order
+    #[to_result]
+    steps:            Vec<ReplayStep>,
+    /// Number of steps in the transcript
+    #[to_metadata]
+    step_count:       usize,
+    /// Steps that succeeded
+    #[to_metadata]
+    succeeded:        usize,
+    /// Steps that failed
+    #[to_metadata]
+    failed:           usize,
+    /// Steps that were not run
+    #[to_metadata]
+    skipped:          usize,
+    /// Recorded -> replayed entity IDs used for substitution
+    #[to_metadata]
+    entity_map:       BTreeMap<String, u64>,
+    /// Message template for formatting responses
+    #[to_message(
+        message_template = "Replayed {step_count} steps: {succeeded} succeeded, {failed} failed, {skipped} skipped"
+    )]
+    message_template: String,
+}
+
+#[derive(ToolFn)]
+#[tool_fn(
+    params = "ReplaySessionParams",
+    output = "ReplaySessionResult",
+    with_context
+)]
+pub struct ReplaySession;
+
+async fn handle_impl(
+    ctx: HandlerContext,
+    params: ReplaySessionParams,
+) -> Result<ReplaySessionResult> {
+    let transcript = read_transcript(Path::new(&params.path))?;
+    let mut entity_map = seed_entity_map(params.entity_map.as_ref())?;
+
+    let total = transcript.calls.len();
+    let mut steps = Vec::with_capacity(total);
+    let mut stopped = false;
+
+    for (index, call) in transcript.calls.iter().enumerate() {
+        if let Some(progress) = ctx.progress() {
+            progress
+                .report(
+                    index,
+                    total,
+                    format!("Replaying step {} ({})", call.sequence, call.tool),
+                )
+                .await;
+        }
+
+        let skip_reason = if stopped {
+            Some("an earlier step failed".to_string())
+        } else if call.status != "success" && !params.include_failed {
+            Some("failed when recorded".to_string())
+        } else {
+            None
+        };
+        let tool_name = ToolName::from_str(&call.tool).ok();
+        let (Some(tool_name), None) = (tool_name, skip_reason.as_ref()) else {
+            steps.push(skipped_step(
+                call,
+                skip_reason.unwrap_or_else(|| format!("unknown tool `{}`", call.tool)),
+            ));
+            continue;
+        };
+
+        let step = replay_step(&ctx, tool_name, call, params.port, &mut entity_map).await;
+        stopped = params.stop_on_failure && step.status != "success";
+        steps.push(step);
+    }
+
+    let count = |status: &str| steps.iter().filter(|step| step.status == status).count();
+    let (succeeded, failed, skipped) = (count("success"), count("error"), count("skipped"));
+
+    Ok(ReplaySessionResult::new(
+        steps,
+        total,
+        succeeded,
+        failed,
+        skipped,
+        entity_map
+            .into_iter()
+            .map(|(recorded, replayed)| (recorded.to_string(), replayed))
+            .collect(),
+    ))
+}
+
+/// Run one recorded call and learn the entity it created, if any
+async fn replay_step(
+    ctx: &HandlerContext,
+    tool_name: ToolName,
+    call: &RecordedCall,
+    port: Option<Port>,
+    entity_map: &mut BTreeMap<u64, u64>,
+) -> ReplayStep {
+    let mut arguments = call.arguments.as_object().cloned().unwrap_or_default();
+    // Pin BRP steps to a port, so a changed session default doesn't redirect them
+    let port = call
+        .port
+        .map(|recorded| port.map_or(recorded, |port| port.0));
+    if let Some(port) = port {
+        arguments.insert(ParameterName::Port.to_string(), Value::from(port));
+    }
+    substitute_entities(&mut arguments, entity_map);
+
+    let mut request = ctx.request.clone();
+    request.name = <&'static str>::from(tool_name).into();
+    request.arguments = Some(arguments);
+
+    let started = std::time::Instant::now();
+    let response = tool_name
+        .to_tool_def()
+        .call_tool(request, ctx.roots.clone(), ctx.roots_support(), None)
+        .await;
+    let duration_ms = u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX);
+
+    let (status, message) = match response {
+        Ok(response) => {
+            let content = response.structured_content.unwrap_or_default();
+            if let (Some(recorded), Some(replayed)) =
+                (created_entity(&call.response), created_entity(&content))
+                && recorded != replayed
+            {
+                entity_map.insert(recorded, replayed);
+            }
+            let status = if response.is_error == Some(true) {
+                "error"
+            } else {
+                "success"
+            };
+            let message = content
+                .get("message")
+                .and_then(Value::as_str)
+                .map(String::from);
+            (status, message)
+        },
+        Err(e) => ("error", Some(e.message.to_string())),
+    };
+
+    ReplayStep {
+        sequence: call.sequence,
+        tool: call.tool.clone(),
+        status: status.to_string(),
+        recorded_status: call.status.clone(),
+        message,
+        port,
+        duration_ms: Some(duration_ms),
+    }
+}
+
+fn skipped_step(call: &RecordedCall, reason: String) -> ReplayStep {
+    ReplayStep {
+        sequence:        call.sequence,
+        tool:            call.tool.clone(),
+        status:          "skipped".to_string(),
+        recorded_status: call.status.clone(),
+        message:         Some(reason),
+        port:            None,
+        duration_ms:     None,
+    }
+}
+
+fn read_transcript(path: &Path) -> Result<SessionTranscript> {
+    let contents = fs::read_to_string(path)
+        .map_err(|e| Error::io_failed("read session transcript", path, &e))?;
+    let transcript: SessionTranscript = serde_json::from_str(&contents).change_context(
+        Error::FileOperation(format!("Invalid session transcript {}", path.display())),
+    )?;
+    if transcript.format_version > TRANSCRIPT_FORMAT_VERSION {
+        return Err(Error::invalid(
+            "session transcript",
+            format!(
+                "format version {} is newer than the supported version {TRANSCRIPT_FORMAT_VERSION}",
+                transcript.format_version
+            ),
+        )
+        .into());
+    }
+    Ok(transcript)
+}
+
+/// Parse the caller's `entity_map`, whose keys are recorded entity IDs written as strings
+fn seed_entity_map(entity_map: Option<&BTreeMap<String, u64>>) -> Result<BTreeMap<u64, u64>> {
+    entity_map
+        .into_iter()
+        .flatten()
+        .map(|(recorded, replayed)| {
+            recorded
+                .trim()
+                .parse::<u64>()
+                .map(|recorded| (recorded, *replayed))
+                .map_err(|e| {
+                    Report::new(Error::invalid("entity_map key", format!("{recorded}: {e}")))
+                })
+        })
+        .collect()
+}
+
+/// The entity a response reports in `metadata.entity` or `result.entity`
+fn created_entity(response: &Value) -> Option<u64> {
+    ["metadata", "result"]
+        .into_iter()
+        .find_map(|field| response.get(field)?.get("entity")?.as_u64())
+}
+
+/// Replace recorded entity IDs in the entity arguments with their replayed IDs
+fn substitute_entities(arguments: &mut Map<String, Value>, entity_map: &BTreeMap<u64, u64>) {
+    for name in ENTITY_ARGUMENTS {
+        if let Some(value) = arguments.get_mut(name.as_ref()) {
+            substitute_value(value, entity_map);
+        }
+    }
+}
+
+fn substitute_value(value: &mut Value, entity_map: &BTreeMap<u64, u64>) {
+    match value {
+        Value::Number(n) => {
+            if let Some(replayed) = n.as_u64().and_then(|id| entity_map.get(&id)) {
+                *value = Value::from(*replayed);
+            }
+        },
+        Value::Array(items) => {
+            for item in items {
+                substitute_value(item, entity_map);
+            }
+        },
+        _ => {},
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn substitutes_entity_arguments_only() {
+        let entity_map = BTreeMap::from([(4_294_967_298, 4_294_967_310), (7, 9)]);
+        let mut arguments = json!({
+            "entity": 4_294_967_298_u64,
+            "entities": [4_294_967_298_u64, 12],
+            "parent": 7,
+            "value": 7,
+        })
+        .as_object()
+        .cloned()
+        .unwrap_or_default();
+        substitute_entities(&mut arguments, &entity_map);
+        assert_eq!(
+            Value::Object(arguments),
+            json!({
+                "entity": 4_294_967_310_u64,
+                "entities": [4_294_967_310_u64, 12],
+                "parent": 9,
+                "value": 7,
+            })
+        );
+    }
+
+    #[test]
+    fn finds_created_entity_in_metadata_or_result() {
+        assert_eq!(
+            created_entity(&json!({ "metadata": { "entity": 42 } })),
+            Some(42)
+        );
+        assert_eq!(
+            created_entity(&json!({ "result": { "entity": 43 } })),
+            Some(43)
+        );
+        assert_eq!(created_entity(&json!({ "message": "ok" })), None);
+    }
+}
@@ -0,0 +1,598 @@
+//! `brp_run_test_script` tool - Run a YAML or JSON integration test script
+//!
+//! A script is a list of steps - launch a target, wait for a condition, send keys, assert,
+//! take screenshots, or call any other tool - followed by teardown steps that run even when a
+//! step failed. Every step goes through the same path as a client call, so a script exercises
+//! exactly what an agent would, and the report says which step passed, failed or was skipped.
+//! Without a `teardown` section the apps the script launched are shut down.
+
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Duration;
+use std::time::Instant;
+
+use bevy_brp_mcp_macros::ParamStruct;
+use bevy_brp_mcp_macros::ResultStruct;
+use bevy_brp_mcp_macros::ToolFn;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Map;
+use serde_json::Value;
+
+use crate::brp_tools::Port;
+use crate::error::Error;
+use crate::error::Result;
+use crate::tool::HandlerContext;
+use crate::tool::HandlerResult;
+use crate::tool::ParameterName;
+use crate::tool::ToolFn;
+use crate::tool::ToolName;
+use crate::tool::ToolResult;
+
+/// Step action that pauses the script instead of calling a tool
+const SLEEP_ACTION: &str = "sleep_ms";
+
+/// Step action calling the tool it names with the step's `args`
+const TOOL_ACTION: &str = "tool";
+
+/// Step field holding the arguments of a `tool` step
+const TOOL_ARGS_FIELD: &str = "args";
+
+/// Launch argument naming the target, which is also the app name `brp_shutdown` takes
+const TARGET_NAME_ARGUMENT: &str = "target_name";
+
+/// Phase of the script's own steps
+const STEPS_PHASE: &str = "steps";
+
+/// Phase of the steps run after them
+const TEARDOWN_PHASE: &str = "teardown";
+
+/// Status of a step that passed
+const PASSED: &str = "passed";
+
+/// Status of a step that failed
+pub const FAILED: &str = "failed";
+
+/// Status of a step skipped after a failure
+pub const SKIPPED: &str = "skipped";
+
+/// Actions that call a tool with the action's value as arguments
+const TOOL_ACTIONS: &[(&str, ToolName)] = &[
+    ("launch", ToolName::BrpLaunchBevyApp),
+    ("launch_example", ToolName::BrpLaunchBevyExample),
+    ("wait_for", ToolName::BrpWaitFor),
+    ("send_keys", ToolName::BrpExtrasSendKeys),
+    ("assert", ToolName::BrpAssert),
+    ("screenshot", ToolName::BrpExtrasScreenshot),
+    ("shutdown", ToolName::BrpShutdown),
+];
+
+#[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
+pub struct RunTestScriptParams {
+    /// The script as YAML or JSON text - provide this or `path`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub script: Option<String>,
+    /// Script file to read, absolute or relative to the first workspace root - provide this or
+    /// `script`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path:   Option<String>,
+    /// Port for steps that don't name one, overriding the script's `port`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub port:   Option<Port>,
+}
+
+/// A test script as written
+#[derive(Debug, Clone, Deserialize)]
+struct TestScript {
+    /// Name reported with the results
+    #[serde(default)]
+    name:            Option<String>,
+    /// Port for steps that don't name one
+    #[serde(default)]
+    port:            Option<u16>,
+    /// Skip the remaining steps after one fails - teardown still runs (default: true)
+    #[serde(default = "default_stop_on_failure")]
+    stop_on_failure: bool,
+    /// Steps to run in order
+    steps:           Vec<ScriptStep>,
+    /// Steps to run after `steps` whatever their outcome - omitted, the launched apps are shut
+    /// down
+    #[serde(default)]
+    teardown:        Option<Vec<ScriptStep>>,
+}
+
+const fn default_stop_on_failure() -> bool { true }
+
+/// One step of a script - a name and a single action
+#[derive(Debug, Clone, Deserialize)]
+struct ScriptStep {
+    /// Name reported with the step's outcome
+    #[serde(default)]
+    name:           Option<String>,
+    /// The step passes when its tool call fails and fails when it succeeds
+    #[serde(default)]
+    expect_failure: bool,
+    /// The action, e.g. `wait_for: {...}`
+    #[serde(flatten)]
+    action:         Map<String, Value>,
+}
+
+/// What a step does
+#[derive(Debug, Clone, PartialEq)]
+enum StepAction {
+    /// Pause the script
+    Sleep(Duration),
+    /// Call a tool with these arguments
+    Call {
+        /// Action as written in the script
+        action:    String,
+        /// Tool to call
+        tool_name: ToolName,
+        /// Arguments of the call
+        arguments: Map<String, Value>,
+    },
+}
+
+/// Outcome of one step
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct StepReport {
+    /// `steps` or `teardown`
+    pub phase:       String,
+    /// Position of the step within its phase
+    pub index:       usize,
+    /// Name the script gave the step
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name:        Option<String>,
+    /// Action of the step, e.g. `wait_for`
+    pub action:      String,
+    /// Tool the step called
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool:        Option<String>,
+    /// `passed`, `failed` or `skipped`
+    pub status:      String,
+    /// Message of the tool's response, or why the step failed or was skipped
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message:     Option<String>,
+    /// The tool's `result` when the step failed - e.g. the assertions that didn't hold
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details:     Option<Value>,
+    /// How long the step took, in milliseconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<u64>,
+}
+
+/// Result from running a test script
+#[derive(Debug, Clone, Serialize, Deserialize, ResultStruct)]
+pub struct RunTestScriptResult {
+    /// Outcome of each step and teardown step, in the order they ran
+    #[to_result]
+    steps:            Vec<StepReport>,
+    /// Name of the script
+    #[to_metadata(skip_if_none)]
+    script_name:      Option<String>,
+    /// Whether every step passed - teardown failures don't count
+    #[to_metadata]
+    passed:           bool,
+    /// Number of steps, not counting teardown
+    #[to_metadata]
+    step_count:       usize,
+    /// Steps that passed
+    #[to_metadata]
+    passed_count:     usize,
+    /// Steps that failed
+    #[to_metadata]
+    failed_count:     usize,
+    /// Steps skipped after a failure
+    #[to_metadata]
+    skipped_count:    usize,
+    /// Teardown steps that failed
+    #[to_metadata]
+    teardown_failed:  usize,
+    /// Total time the script took, in milliseconds
+    #[to_metadata]
+    duration_ms:      u64,
+    /// Message template for formatting responses
+    #[to_message(
+        message_template = "{passed_count} of {step_count} steps passed, {failed_count} failed, {skipped_count} skipped"
+    )]
+    message_template: String,
+}
+
+#[derive(ToolFn)]
+#[tool_fn(
+    params = "RunTestScriptParams",
+    output = "RunTestScriptResult",
+    with_context
+)]
+pub struct RunTestScript;
+
+async fn handle_impl(
+    ctx: HandlerContext,
+    params: RunTestScriptParams,
+) -> Result<RunTestScriptResult> {
+    let text = match (&params.script, &params.path) {
+        (Some(script), None) => script.clone(),
+        (None, Some(path)) => {
+            let path = ctx
+                .roots
+                .first()
+                .map_or_else(|| Path::new(path).to_path_buf(), |root| root.join(path));
+            fs::read_to_string(&path)
+                .map_err(|e| Error::io_failed("read test script", &path, &e))?
+        },
+        _ => {
+            return Err(
+                Error::invalid("script", "provide exactly one of `script` and `path`").into(),
+            );
+        },
+    };
+    let script = parse_script(&text)?;
+    let port = params.port.map(|port| port.0).or(script.port);
+
+    // Check every step before running any, so a typo doesn't strike halfway through
+    let steps = resolve_steps(&script.steps, port)?;
+    let teardown = script
+        .teardown
+        .as_ref()
+        .map(|teardown| resolve_steps(teardown, port))
+        .transpose()?;
+
+    let started = Instant::now();
+    let mut reports = Vec::with_capacity(steps.len());
+    let mut launched = Vec::new();
+    let mut failed = false;
+    for (index, (step, action)) in script.steps.iter().zip(&steps).enumerate() {
+        report_progress(&ctx, index, steps.len(), step, action).await;
+        if failed && script.stop_on_failure {
+            reports.push(skipped_report(index, step, action));
+            continue;
+        }
+        let report = run_step(&ctx, STEPS_PHASE, index, step, action).await;
+        if report.status == PASSED
+            && let Some(app) = launched_app(action)
+        {
+            launched.push(app);
+        }
+        failed |= report.status == FAILED;
+        reports.push(report);
+    }
+
+    // Shut down what the script launched unless it says how to tear down
+    let teardown: Vec<(ScriptStep, StepAction)> = match teardown {
+        Some(teardown) => {
+            let steps = script.teardown.unwrap_or_default();
+            steps.into_iter().zip(teardown).collect()
+        },
+        None => launched
+            .into_iter()
+            .map(|(app_name, port)| shutdown_step(app_name, port))
+            .collect(),
+    };
+    let mut teardown_failed = 0;
+    for (index, (step, action)) in teardown.iter().enumerate() {
+        report_progress(&ctx, index, teardown.len(), step, action).await;
+        let report = run_step(&ctx, TEARDOWN_PHASE, index, step, action).await;
+        if report.status == FAILED {
+            teardown_failed += 1;
+        }
+        reports.push(report);
+    }
+
+    let count = |status: &str| {
+        reports
+            .iter()
+            .filter(|report| report.phase == STEPS_PHASE && report.status == status)
+            .count()
+    };
+    let (passed_count, failed_count, skipped_count) =
+        (count(PASSED), count(FAILED), count(SKIPPED));
+    let step_count = steps.len();
+    Ok(RunTestScriptResult::new(
+        reports,
+        script.name,
+        passed_count == step_count,
+        step_count,
+        passed_count,
+        failed_count,
+        skipped_count,
+        teardown_failed,
+        u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX),
+    ))
+}
+
+/// Parse YAML or JSON - JSON is valid YAML
+fn parse_script(text: &str) -> Result<TestScript> {
+    serde_yaml::from_str(text).map_err(|e| Error::invalid("test script", e).into())
+}
+
+/// Turn each step into the action it runs, pinning tool calls to `port` when they don't name one
+fn resolve_steps(steps: &[ScriptStep], port: Option<u16>) -> Result<Vec<StepAction>> {
+    steps
+        .iter()
+        .enumerate()
+        .map(|(index, step)| {
+            resolve_step(step, port).map_err(|reason| {
+                let label = step
+                    .name
+                    .as_ref()
+                    .map_or_else(|| format!("step {index}"), |name| format!("step '{name}'"));
+                Error::invalid(&label, reason).into()
+            })
+        })
+        .collect()
+}
+
+fn resolve_step(step: &ScriptStep, port: Option<u16>) -> std::result::Result<StepAction, String> {
+    let mut fields = step.action.clone();
+    let arguments = fields.remove(TOOL_ARGS_FIELD);
+    let mut actions = fields.into_iter();
+    let (Some((action, value)), None) = (actions.next(), actions.next()) else {
+        return Err(format!(
+            "a step needs exactly one action - one of {}, `{SLEEP_ACTION}` or `{TOOL_ACTION}`",
+            TOOL_ACTIONS
+                .iter()
+                .map(|(action, _)| format!("`{action}`"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+    };
+
+    if action == SLEEP_ACTION {
+        let millis = value
+            .as_u64()
+            .ok_or_else(|| format!("`{SLEEP_ACTION}` must be a number of milliseconds"))?;
+        return Ok(StepAction::Sleep(Duration::from_millis(millis)));
+    }
+
+    let (tool_name, arguments) = if action == TOOL_ACTION {
+        let name = value
+            .as_str()
+            .ok_or_else(|| format!("`{TOOL_ACTION}` must name a tool"))?;
+        let tool_name = ToolName::from_str(name).map_err(|_| format!("unknown tool `{name}`"))?;
+        if tool_name == ToolName::BrpRunTestScript {
+            return Err("a test script can't run another test script".to_string());
+        }
+        (
+            tool_name,
+            arguments.unwrap_or_else(|| Value::Object(Map::new())),
+        )
+    } else {
+        let tool_name = TOOL_ACTIONS
+            .iter()
+            .find(|(name, _)| *name == action)
+            .map(|(_, tool_name)| *tool_name)
+            .ok_or_else(|| format!("unknown action `{action}`"))?;
+        (tool_name, value)
+    };
+    let Value::Object(mut arguments) = arguments else {
+        return Err(format!("the arguments of `{action}` must be an object"));
+    };
+    if let Some(port) = port
+        && accepts_port(tool_name)
+    {
+        arguments
+            .entry(ParameterName::Port.to_string())
+            .or_insert_with(|| Value::from(port));
+    }
+    Ok(StepAction::Call {
+        action,
+        tool_name,
+        arguments,
+    })
+}
+
+/// Whether the tool's input schema has a `port` parameter
+fn accepts_port(tool_name: ToolName) -> bool {
+    let tool = tool_name.to_tool_def().to_tool();
+    tool.input_schema
+        .get("properties")
+        .and_then(Value::as_object)
+        .is_some_and(|properties| properties.contains_key(ParameterName::Port.as_ref()))
+}
+
+/// The app name and port of a launch step, to shut it down in the default teardown
+fn launched_app(action: &StepAction) -> Option<(String, Option<Value>)> {
+    let StepAction::Call {
+        tool_name: ToolName::BrpLaunchBevyApp | ToolName::BrpLaunchBevyExample,
+        arguments,
+        ..
+    } = action
+    else {
+        return None;
+    };
+    let target_name = arguments.get(TARGET_NAME_ARGUMENT)?.as_str()?;
+    Some((
+        target_name.to_string(),
+        arguments.get(ParameterName::Port.as_ref()).cloned(),
+    ))
+}
+
+/// Teardown step shutting down an app the script launched
+fn shutdown_step(app_name: String, port: Option<Value>) -> (ScriptStep, StepAction) {
+    let step = ScriptStep {
+        name:           Some(format!("shut down {app_name}")),
+        expect_failure: false,
+        action:         Map::new(),
+    };
+    let mut arguments = Map::new();
+    arguments.insert(ParameterName::AppName.to_string(), Value::String(app_name));
+    if let Some(port) = port {
+        arguments.insert(ParameterName::Port.to_string(), port);
+    }
+    // The script launched the app, so shutting it down needs no confirmation from the caller
+    arguments.insert(ParameterName::Confirm.to_string(), Value::Bool(true));
+    let action = StepAction::Call {
+        action: "shutdown".to_string(),
+        tool_name: ToolName::BrpShutdown,
+        arguments,
+    };
+    (step, action)
+}
+
+async fn report_progress(
+    ctx: &HandlerContext,
+    completed: usize,
+    total: usize,
+    step: &ScriptStep,
+    action: &StepAction,
+) {
+    if let Some(progress) = ctx.progress() {
+        let label = step.name.clone().unwrap_or_else(|| action_name(action));
+        let message = format!("Running step {} of {total} ({label})", completed + 1);
+        progress.report(completed, total, message).await;
+    }
+}
+
+fn action_name(action: &StepAction) -> String {
+    match action {
+        StepAction::Sleep(_) => SLEEP_ACTION.to_string(),
+        StepAction::Call { action, .. } => action.clone(),
+    }
+}
+
+/// Run one step - a tool call passes unless it returns an error or reports `all_passed: false`
+async fn run_step(
+    ctx: &HandlerContext,
+    phase: &str,
+    index: usize,
+    step: &ScriptStep,
+    action: &StepAction,
+) -> StepReport {
+    let started = Instant::now();
+    let (tool, succeeded, message, details) = match action {
+        StepAction::Sleep(duration) => {
+            tokio::time::sleep(*duration).await;
+            (None, true, None, None)
+        },
+        StepAction::Call {
+            tool_name,
+            arguments,
+            ..
+        } => {
+            let mut request = ctx.request.clone();
+            request.name = <&'static str>::from(*tool_name).into();
+            request.arguments = Some(arguments.clone());
+            let response = tool_name
+                .to_tool_def()
+                .call_tool(request, ctx.roots.clone(), ctx.roots_support(), None)
+                .await;
+            let (succeeded, message, details) = match response {
+                Ok(response) => {
+                    let content = response.structured_content.unwrap_or_default();
+                    let all_passed = content
+                        .get("metadata")
+                        .and_then(|metadata| metadata.get("all_passed"))
+                        .and_then(Value::as_bool);
+                    let succeeded = response.is_error != Some(true) && all_passed != Some(false);
+                    let message = content
+                        .get("message")
+                        .and_then(Value::as_str)
+                        .map(String::from);
+                    (succeeded, message, content.get("result").cloned())
+                },
+                Err(e) => (false, Some(e.message.to_string()), None),
+            };
+            (Some(tool_name.to_string()), succeeded, message, details)
+        },
+    };
+
+    let passed = succeeded != step.expect_failure;
+    let message = if passed || !step.expect_failure {
+        message
+    } else {
+        Some("expected the step to fail, but it succeeded".to_string())
+    };
+    StepReport {
+        phase: phase.to_string(),
+        index,
+        name: step.name.clone(),
+        action: action_name(action),
+        tool,
+        status: if passed { PASSED } else { FAILED }.to_string(),
+        message,
+        details: if passed { None } else { details },
+        duration_ms: Some(u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX)),
+    }
+}
+
+fn skipped_report(index: usize, step: &ScriptStep, action: &StepAction) -> StepReport {
+    StepReport {
+        phase: STEPS_PHASE.to_string(),
+        index,
+        name: step.name.clone(),
+        action: action_name(action),
+        tool: None,
+        status: SKIPPED.to_string(),
+        message: Some("an earlier step failed".to_string()),
+        details: None,
+        duration_ms: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    const SCRIPT: &str = r"
+name: cube spins
+port: 15710
+steps:
+  - launch: { target_name: sandbox }
+  - name: cube exists
+    wait_for:
+      condition: { kind: count, filter: { with: [Spin] }, min: 1 }
+  - sleep_ms: 250
+  - tool: world_query
+    args: { data: { components: [Spin] } }
+";
+
+    #[test]
+    fn resolves_yaml_steps_to_tool_calls_on_the_script_port() {
+        let script = parse_script(SCRIPT);
+        let Ok(script) = script else {
+            unreachable!("script should parse: {script:?}");
+        };
+        assert!(script.stop_on_failure);
+        let steps = resolve_steps(&script.steps, script.port);
+        let Ok(steps) = steps else {
+            unreachable!("steps should resolve: {steps:?}");
+        };
+
+        assert_eq!(
+            steps.get(2),
+            Some(&StepAction::Sleep(Duration::from_millis(250)))
+        );
+        let Some(StepAction::Call {
+            tool_name,
+            arguments,
+            ..
+        }) = steps.get(3)
+        else {
+            unreachable!("fourth step should call a tool");
+        };
+        assert_eq!(*tool_name, ToolName::WorldQuery);
+        assert_eq!(arguments.get("port"), Some(&json!(15710)));
+        assert_eq!(
+            steps.first().and_then(launched_app),
+            Some(("sandbox".to_string(), Some(json!(15710))))
+        );
+    }
+
+    #[test]
+    fn rejects_steps_without_exactly_one_known_action() {
+        let step = |action: Value| ScriptStep {
+            name:           None,
+            expect_failure: false,
+            action:         action.as_object().cloned().unwrap_or_default(),
+        };
+        assert!(resolve_step(&step(json!({})), None).is_err());
+        assert!(resolve_step(&step(json!({ "assert": {}, "sleep_ms": 5 })), None).is_err());
+        assert!(resolve_step(&step(json!({ "click": {} })), None).is_err());
+        assert!(resolve_step(&step(json!({ "tool": "brp_run_test_script" })), None).is_err());
+        assert!(resolve_step(&step(json!({ "sleep_ms": "soon" })), None).is_err());
+    }
+}
@@ -0,0 +1,343 @@
+//! Headless test script runner - `bevy_brp_mcp --run-test-script <path>`
+//!
+//! CI has no MCP client to call `brp_run_test_script`, so the binary can run a script itself:
+//! the runner calls the tool in-process - launching, BRP calls, assertions and teardown all take
+//! the same path they take for an agent - prints the report as JSON on stdout, optionally writes
+//! it as `JUnit` XML for CI test reporting, and exits with a code saying how the script went.
+
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use rmcp::model::CallToolRequestParam;
+use serde_json::Value;
+use serde_json::json;
+
+use super::run_test_script::FAILED;
+use super::run_test_script::SKIPPED;
+use super::run_test_script::StepReport;
+use crate::mcp_service::RootsSupport;
+use crate::tool::ToolName;
+
+/// Flag that runs the test script at the path after it and exits instead of serving
+pub const RUN_TEST_SCRIPT_FLAG: &str = "--run-test-script";
+
+/// Runner flag writing the report as `JUnit` XML to the path after it
+const JUNIT_FLAG: &str = "--junit";
+
+/// Runner flag setting the port for steps that don't name one
+const PORT_FLAG: &str = "--port";
+
+/// Exit code when every step and teardown step passed
+const EXIT_PASSED: u8 = 0;
+
+/// Exit code when a step failed or was skipped
+const EXIT_STEPS_FAILED: u8 = 1;
+
+/// Exit code when the steps passed but a teardown step failed
+const EXIT_TEARDOWN_FAILED: u8 = 2;
+
+/// Exit code when the script couldn't run - bad arguments, or an unreadable or invalid script
+const EXIT_SCRIPT_ERROR: u8 = 3;
+
+/// What the runner was asked to do
+#[derive(Debug, PartialEq, Eq)]
+struct RunnerArgs {
+    script: PathBuf,
+    junit:  Option<PathBuf>,
+    port:   Option<u16>,
+}
+
+/// Run the test script named by `args` - the arguments after `--run-test-script` - and return
+/// the process exit code
+pub async fn run_test_script_headless(args: impl Iterator<Item = String>) -> ExitCode {
+    let args = match parse_args(args) {
+        Ok(args) => args,
+        Err(message) => {
+            eprintln!("{message}");
+            eprintln!(
+                "usage: bevy_brp_mcp {RUN_TEST_SCRIPT_FLAG} <script> [{JUNIT_FLAG} <file>] \
+                 [{PORT_FLAG} <port>]"
+            );
+            return ExitCode::from(EXIT_SCRIPT_ERROR);
+        },
+    };
+
+    // The report is printed in full - a large one must not be swapped for a file path
+    let mut arguments = json!({ "path": args.script, "max_response_tokens": 0 });
+    if let (Some(port), Value::Object(arguments)) = (args.port, &mut arguments) {
+        arguments.insert("port".to_string(), Value::from(port));
+    }
+    let request = serde_json::from_value::<CallToolRequestParam>(json!({
+        "name": ToolName::BrpRunTestScript.to_string(),
+        "arguments": arguments,
+    }));
+    let Ok(request) = request else {
+        eprintln!("Failed to build the brp_run_test_script call");
+        return ExitCode::from(EXIT_SCRIPT_ERROR);
+    };
+    let roots = std::env::current_dir()
+        .map(|dir| vec![dir])
+        .unwrap_or_default();
+    let response = match ToolName::BrpRunTestScript
+        .to_tool_def()
+        .call_tool(request, roots, RootsSupport::Unsupported, None)
+        .await
+    {
+        Ok(response) => response,
+        Err(e) => {
+            eprintln!("{}", e.message);
+            return ExitCode::from(EXIT_SCRIPT_ERROR);
+        },
+    };
+
+    let content = response.structured_content.unwrap_or_default();
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&content).unwrap_or_default()
+    );
+    if response.is_error == Some(true) {
+        return ExitCode::from(EXIT_SCRIPT_ERROR);
+    }
+
+    let steps: Vec<StepReport> = content
+        .get("result")
+        .cloned()
+        .and_then(|steps| serde_json::from_value(steps).ok())
+        .unwrap_or_default();
+    let metadata = content.get("metadata");
+    let field = |name: &str| metadata.and_then(|metadata| metadata.get(name));
+    if let Some(junit) = &args.junit {
+        let suite = field("script_name")
+            .and_then(Value::as_str)
+            .map(String::from)
+            .or_else(|| {
+                args.script
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().into_owned())
+            })
+            .unwrap_or_default();
+        let duration_ms = field("duration_ms").and_then(Value::as_u64).unwrap_or(0);
+        if let Err(message) = write_junit(junit, &suite, &steps, duration_ms) {
+            eprintln!("{message}");
+            return ExitCode::from(EXIT_SCRIPT_ERROR);
+        }
+    }
+
+    ExitCode::from(exit_code(metadata))
+}
+
+/// The exit code for a report with `metadata`
+fn exit_code(metadata: Option<&Value>) -> u8 {
+    let field = |name: &str| metadata.and_then(|metadata| metadata.get(name));
+    if field("passed").and_then(Value::as_bool) != Some(true) {
+        EXIT_STEPS_FAILED
+    } else if field("teardown_failed")
+        .and_then(Value::as_u64)
+        .unwrap_or(0)
+        > 0
+    {
+        EXIT_TEARDOWN_FAILED
+    } else {
+        EXIT_PASSED
+    }
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<RunnerArgs, String> {
+    let mut script = None;
+    let mut junit = None;
+    let mut port = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            JUNIT_FLAG => {
+                let path = args
+                    .next()
+                    .ok_or_else(|| format!("{JUNIT_FLAG} needs a file path"))?;
+                junit = Some(PathBuf::from(path));
+            },
+            PORT_FLAG => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| format!("{PORT_FLAG} needs a port"))?;
+                let parsed = value
+                    .parse()
+                    .map_err(|_| format!("{PORT_FLAG} needs a port, not `{value}`"))?;
+                port = Some(parsed);
+            },
+            _ if script.is_none() && !arg.starts_with("--") => script = Some(PathBuf::from(arg)),
+            _ => return Err(format!("Unexpected argument `{arg}`")),
+        }
+    }
+    let script = script.ok_or_else(|| format!("{RUN_TEST_SCRIPT_FLAG} needs a script path"))?;
+    Ok(RunnerArgs {
+        script,
+        junit,
+        port,
+    })
+}
+
+/// Write `steps` to `path` as a `JUnit` XML test suite, one test case per step
+fn write_junit(
+    path: &Path,
+    suite: &str,
+    steps: &[StepReport],
+    duration_ms: u64,
+) -> Result<(), String> {
+    if let Some(parent) = path.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create directory {}: {e}", parent.display()))?;
+    }
+    fs::write(path, junit_xml(suite, steps, duration_ms))
+        .map_err(|e| format!("Failed to write JUnit report to {}: {e}", path.display()))
+}
+
+#[allow(clippy::cast_precision_loss)] // durations are far below f64's exact integer range
+fn junit_xml(suite: &str, steps: &[StepReport], duration_ms: u64) -> String {
+    let count = |status: &str| steps.iter().filter(|step| step.status == status).count();
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<testsuites>\n");
+    let _ = writeln!(
+        xml,
+        "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" skipped=\"{}\" time=\"{:.3}\">",
+        escape_xml(suite),
+        steps.len(),
+        count(FAILED),
+        count(SKIPPED),
+        duration_ms as f64 / 1000.0
+    );
+    for step in steps {
+        let name = step.name.clone().unwrap_or_else(|| step.action.clone());
+        let time = step.duration_ms.unwrap_or(0) as f64 / 1000.0;
+        let _ = write!(
+            xml,
+            "    <testcase classname=\"{}.{}\" name=\"{}: {}\" time=\"{time:.3}\"",
+            escape_xml(suite),
+            step.phase,
+            step.index,
+            escape_xml(&name)
+        );
+        let message = escape_xml(step.message.as_deref().unwrap_or_default());
+        match step.status.as_str() {
+            FAILED => {
+                let details = step
+                    .details
+                    .as_ref()
+                    .map(|details| escape_xml(&details.to_string()))
+                    .unwrap_or_default();
+                let _ = writeln!(
+                    xml,
+                    ">\n      <failure message=\"{message}\">{details}</failure>\n    </testcase>"
+                );
+            },
+            SKIPPED => {
+                let _ = writeln!(
+                    xml,
+                    ">\n      <skipped message=\"{message}\"/>\n    </testcase>"
+                );
+            },
+            _ => {
+                let _ = writeln!(xml, "/>");
+            },
+        }
+    }
+    xml.push_str("  </testsuite>\n</testsuites>\n");
+    xml
+}
+
+fn escape_xml(text: &str) -> String {
+    text.chars()
+        .fold(String::with_capacity(text.len()), |mut escaped, c| {
+            match c {
+                '&' => escaped.push_str("&amp;"),
+                '<' => escaped.push_str("&lt;"),
+                '>' => escaped.push_str("&gt;"),
+                '"' => escaped.push_str("&quot;"),
+                '\'' => escaped.push_str("&apos;"),
+                c => escaped.push(c),
+            }
+            escaped
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(index: usize, status: &str, message: Option<&str>) -> StepReport {
+        StepReport {
+            phase: "steps".to_string(),
+            index,
+            name: None,
+            action: "assert".to_string(),
+            tool: Some("brp_assert".to_string()),
+            status: status.to_string(),
+            message: message.map(String::from),
+            details: None,
+            duration_ms: Some(1_500),
+        }
+    }
+
+    #[test]
+    fn junit_report_has_a_case_per_step() {
+        let steps = [
+            step(0, "passed", None),
+            step(1, FAILED, Some("count < 1 & \"spin\"")),
+            step(2, SKIPPED, Some("an earlier step failed")),
+        ];
+        let xml = junit_xml("cube <spins>", &steps, 4_000);
+
+        assert!(xml.contains(
+            "<testsuite name=\"cube &lt;spins&gt;\" tests=\"3\" failures=\"1\" skipped=\"1\" \
+             time=\"4.000\">"
+        ));
+        assert!(xml.contains("name=\"0: assert\" time=\"1.500\"/>"));
+        assert!(xml.contains("<failure message=\"count &lt; 1 &amp; &quot;spin&quot;\">"));
+        assert!(xml.contains("<skipped message=\"an earlier step failed\"/>"));
+    }
+
+    #[test]
+    fn exit_code_says_how_the_script_went() {
+        let metadata = |metadata: Value| exit_code(Some(&metadata));
+        assert_eq!(
+            metadata(json!({ "passed": true, "teardown_failed": 0 })),
+            EXIT_PASSED
+        );
+        assert_eq!(metadata(json!({ "passed": true })), EXIT_PASSED);
+        assert_eq!(
+            metadata(json!({ "passed": false, "teardown_failed": 1 })),
+            EXIT_STEPS_FAILED
+        );
+        assert_eq!(
+            metadata(json!({ "passed": true, "teardown_failed": 2 })),
+            EXIT_TEARDOWN_FAILED
+        );
+        // A report without a verdict never counts as a pass
+        assert_eq!(exit_code(None), EXIT_STEPS_FAILED);
+        assert_eq!(metadata(json!({ "passed": "yes" })), EXIT_STEPS_FAILED);
+    }
+
+    #[test]
+    fn runner_args_take_a_script_and_optional_flags() {
+        let args = |args: &[&str]| parse_args(args.iter().map(ToString::to_string));
+        assert_eq!(
+            args(&[
+                "ci/smoke.yaml",
+                "--junit",
+                "out/junit.xml",
+                "--port",
+                "15710"
+            ]),
+            Ok(RunnerArgs {
+                script: PathBuf::from("ci/smoke.yaml"),
+                junit:  Some(PathBuf::from("out/junit.xml")),
+                port:   Some(15710),
+            })
+        );
+        assert!(args(&[]).is_err());
+        assert!(args(&["smoke.yaml", "--port", "high"]).is_err());
+        assert!(args(&["smoke.yaml", "other.yaml"]).is_err());
+    }
+}
@@ -108,3 +108,27 @@ impl From<Annotation> for ToolAnnotations {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tool::ToolName;
+
+    #[test]
+    fn setting_the_clipboard_is_destructive() {
+        let annotation = ToolName::BrpExtrasClipboard.get_annotations();
+        assert_eq!(
+            annotation.environment_impact,
+            EnvironmentImpact::DestructiveIdempotent
+        );
+        assert_eq!(
+            annotation.domain_of_interaction,
+            DomainOfInteraction::OpenWorld
+        );
+
+        let tool_annotations = ToolAnnotations::from(annotation);
+        assert_eq!(tool_annotations.destructive_hint, Some(true));
+        assert_eq!(tool_annotations.idempotent_hint, Some(true));
+        assert_eq!(tool_annotations.open_world_hint, Some(true));
+    }
+}
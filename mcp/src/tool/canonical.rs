@@ -0,0 +1,159 @@
+//! Diff-friendly canonical responses
+//!
+//! Every tool accepts an optional `canonical` flag. With `canonical: true` the response is
+//! rewritten so two runs against the same app state produce identical JSON: object keys are
+//! sorted, floats are rounded to a fixed precision and whole floats are written as integers, and
+//! metadata that changes on every call - timestamps, durations, cache and retry details, process
+//! IDs - is dropped. Regression workflows can then diff saved outputs directly.
+
+use serde_json::Map;
+use serde_json::Number;
+use serde_json::Value;
+
+use super::ParameterName;
+use super::json_response::AnySchemaValue;
+use super::json_response::ToolCallJsonResponse;
+
+/// Decimal places floats are rounded to
+const FLOAT_DECIMALS: i32 = 6;
+
+/// Metadata fields that differ between otherwise identical calls
+const VOLATILE_METADATA_FIELDS: &[&str] = &[
+    "app_restarted",
+    "cache_age_ms",
+    "cache_hit",
+    "elapsed_ms",
+    "pid",
+    "pids",
+    "timestamp",
+    "transfer_sizes",
+    "transient_retries",
+];
+
+/// Remove `canonical` from the request arguments so tools never see it, returning whether the
+/// call asked for a canonical response
+pub fn take_canonical(arguments: Option<&mut Map<String, Value>>) -> bool {
+    arguments
+        .and_then(|args| args.remove(ParameterName::Canonical.as_ref()))
+        .is_some_and(|value| {
+            // Accept "true" too - some MCP clients serialize booleans as strings
+            value.as_bool() == Some(true) || value.as_str().is_some_and(|s| s.trim() == "true")
+        })
+}
+
+/// Rewrite `response` into its canonical form
+pub fn canonical_response(mut response: ToolCallJsonResponse) -> ToolCallJsonResponse {
+    if let Some(AnySchemaValue(metadata)) = response.metadata.take() {
+        let metadata = strip_volatile_fields(metadata);
+        response.metadata = match &metadata {
+            Value::Object(map) if map.is_empty() => None,
+            _ => Some(AnySchemaValue(canonical_value(metadata))),
+        };
+    }
+    for field in [
+        &mut response.parameters,
+        &mut response.result,
+        &mut response.error_info,
+        &mut response.brp_extras_debug_info,
+    ] {
+        if let Some(AnySchemaValue(value)) = field.take() {
+            *field = Some(AnySchemaValue(canonical_value(value)));
+        }
+    }
+    response
+}
+
+/// Drop volatile fields from the top level of the metadata
+fn strip_volatile_fields(metadata: Value) -> Value {
+    let Value::Object(map) = metadata else {
+        return metadata;
+    };
+    Value::Object(
+        map.into_iter()
+            .filter(|(key, _)| !is_volatile(key))
+            .collect(),
+    )
+}
+
+/// Whether a metadata field changes from call to call
+fn is_volatile(key: &str) -> bool {
+    VOLATILE_METADATA_FIELDS.contains(&key) || key.ends_with("_at") || key.ends_with("_timestamp")
+}
+
+/// Sort object keys and normalize floats, recursively
+fn canonical_value(value: Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<(String, Value)> = map.into_iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key, canonical_value(value)))
+                    .collect(),
+            )
+        },
+        Value::Array(items) => Value::Array(items.into_iter().map(canonical_value).collect()),
+        Value::Number(number) => Value::Number(canonical_number(number)),
+        other => other,
+    }
+}
+
+/// Round a float to `FLOAT_DECIMALS` places, writing it as an integer when nothing is left after
+/// the point - integers are left as they are
+#[allow(clippy::cast_possible_truncation)]
+fn canonical_number(number: Number) -> Number {
+    if number.is_i64() || number.is_u64() {
+        return number;
+    }
+    let Some(float) = number.as_f64() else {
+        return number;
+    };
+    let scale = 10_f64.powi(FLOAT_DECIMALS);
+    let rounded = (float * scale).round() / scale;
+    // `-0.0` and `0.0` are the same value
+    let rounded = if rounded == 0.0 { 0.0 } else { rounded };
+    if rounded.fract() == 0.0 && rounded.abs() < 2_f64.powi(53) {
+        return Number::from(rounded as i64);
+    }
+    Number::from_f64(rounded).unwrap_or(number)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn sorts_keys_and_normalizes_floats() {
+        let value = json!({
+            "z": [0.1 + 0.2, 2.0, -0.0, 7],
+            "a": {"y": 1.000_000_4, "b": 1.5}
+        });
+        let canonical = serde_json::to_string(&canonical_value(value)).unwrap_or_default();
+        assert_eq!(canonical, r#"{"a":{"b":1.5,"y":1},"z":[0.3,2,0,7]}"#);
+    }
+
+    #[test]
+    fn drops_volatile_metadata() {
+        let metadata = json!({
+            "entity_count": 3,
+            "cache_hit": true,
+            "cache_age_ms": 12,
+            "tripped_at": "2025-01-01T00:00:00Z",
+            "build_timestamp": "now"
+        });
+        assert_eq!(strip_volatile_fields(metadata), json!({"entity_count": 3}));
+    }
+
+    #[test]
+    fn takes_canonical_flag_from_arguments() {
+        let mut arguments = Map::new();
+        arguments.insert("canonical".to_string(), json!("true"));
+        arguments.insert("entity".to_string(), json!(5));
+        assert!(take_canonical(Some(&mut arguments)));
+        assert!(!arguments.contains_key("canonical"));
+        assert!(!take_canonical(Some(&mut arguments)));
+    }
+}
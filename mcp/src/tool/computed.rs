@@ -0,0 +1,57 @@
+//! Sources for computed result fields
+//!
+//! `#[computed(from = "field", operation = "...")]` on a `ResultStruct` field derives its value
+//! from another field when the result is constructed. The generated code calls these traits, so
+//! an operation works for any field type that implements the matching trait.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Field types that the `count` and `is_empty` operations can be applied to
+pub trait Countable {
+    /// Number of items held by the field
+    fn count(&self) -> usize;
+}
+
+impl<T> Countable for Vec<T> {
+    fn count(&self) -> usize { self.len() }
+}
+
+impl<K, V, S> Countable for HashMap<K, V, S> {
+    fn count(&self) -> usize { self.len() }
+}
+
+impl Countable for serde_json::Value {
+    fn count(&self) -> usize {
+        match self {
+            Self::Array(items) => items.len(),
+            Self::Object(map) => map.len(),
+            _ => 0,
+        }
+    }
+}
+
+impl<T: Countable> Countable for Option<T> {
+    fn count(&self) -> usize { self.as_ref().map_or(0, Countable::count) }
+}
+
+/// Field types that the `duration_ms` operation can be applied to
+pub trait DurationMillis {
+    /// Milliseconds type produced - optional durations produce optional milliseconds
+    type Millis;
+
+    /// The duration in whole milliseconds
+    fn duration_ms(&self) -> Self::Millis;
+}
+
+impl DurationMillis for Duration {
+    type Millis = u128;
+
+    fn duration_ms(&self) -> Self::Millis { self.as_millis() }
+}
+
+impl DurationMillis for Option<Duration> {
+    type Millis = Option<u128>;
+
+    fn duration_ms(&self) -> Self::Millis { self.map(|duration| duration.as_millis()) }
+}
@@ -0,0 +1,329 @@
+//! Confirmation gating for destructive tools
+//!
+//! When enabled via the `BRP_MCP_REQUIRE_CONFIRMATION` environment variable, tools that
+//! permanently remove state from a running app (despawn, remove, shutdown, relaunching on
+//! rebuild, which stops the running app, and command batches that despawn or remove - whether
+//! called directly or through `brp_execute`) refuse to run unless the caller either passes
+//! `confirm: true` or presents a single-use token obtained from `brp_request_confirmation`.
+//! Without either, the call returns a structured "confirmation required" error instead of
+//! touching the app.
+
+use std::collections::HashMap;
+use std::sync::LazyLock;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::Instant;
+
+use serde_json::Map;
+use serde_json::Value;
+use serde_json::json;
+
+use super::BrpMethod;
+use super::ParameterName;
+use super::tool_name::ToolName;
+
+/// Environment variable that turns confirmation gating on (`1`, `true`, `yes`, `on`)
+pub const REQUIRE_CONFIRMATION_ENV_VAR: &str = "BRP_MCP_REQUIRE_CONFIRMATION";
+
+/// Note appended to the description of every gated tool while gating is enabled
+pub const CONFIRMATION_NOTE: &str = include_str!("../../help_text/notes/confirmation.txt");
+
+/// How long a confirmation token stays valid after it is issued
+pub const CONFIRMATION_TOKEN_TTL: Duration = Duration::from_mins(1);
+
+/// Whether confirmation gating is enabled - read once at first use
+static CONFIRMATION_ENABLED: LazyLock<bool> = LazyLock::new(|| {
+    std::env::var(REQUIRE_CONFIRMATION_ENV_VAR).is_ok_and(|value| {
+        matches!(
+            value.trim().to_ascii_lowercase().as_str(),
+            "1" | "true" | "yes" | "on"
+        )
+    })
+});
+
+/// Outstanding confirmation tokens mapped to the tool they unlock and when they were issued
+static PENDING_CONFIRMATIONS: LazyLock<Mutex<HashMap<String, (ToolName, Instant)>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Returns true if destructive tools must be confirmed before they run
+pub fn is_confirmation_enabled() -> bool { *CONFIRMATION_ENABLED }
+
+/// Issue a single-use confirmation token for `tool_name`
+pub fn issue_confirmation_token(tool_name: ToolName) -> String {
+    let token = uuid::Uuid::new_v4().to_string();
+    if let Ok(mut pending) = PENDING_CONFIRMATIONS.lock() {
+        pending.retain(|_, (_, issued)| issued.elapsed() < CONFIRMATION_TOKEN_TTL);
+        pending.insert(token.clone(), (tool_name, Instant::now()));
+    }
+    token
+}
+
+/// Consume a token, returning true if it was issued for `tool_name` and has not expired
+fn consume_confirmation_token(token: &str, tool_name: ToolName) -> bool {
+    let Ok(mut pending) = PENDING_CONFIRMATIONS.lock() else {
+        return false;
+    };
+    match pending.remove(token) {
+        Some((issued_for, issued)) => {
+            issued_for == tool_name && issued.elapsed() < CONFIRMATION_TOKEN_TTL
+        },
+        None => false,
+    }
+}
+
+/// Whether the request is a despawn preview - other gated tools ignore `preview`, so it must not
+/// let them past the gate
+fn is_despawn_preview(tool_name: ToolName, args: &Map<String, Value>) -> bool {
+    tool_name == ToolName::WorldDespawnEntity
+        && args.get(ParameterName::Preview.as_ref()) == Some(&Value::Bool(true))
+}
+
+/// Whether the request is a command batch that removes nothing - only batches with a `despawn`
+/// or `remove` command, applied rather than previewed, are gated
+fn is_harmless_batch(tool_name: ToolName, args: &Map<String, Value>) -> bool {
+    tool_name == ToolName::BrpExtrasApplyCommands
+        && (args.get(ParameterName::Preview.as_ref()) == Some(&Value::Bool(true))
+            || !removes_in_batch(args))
+}
+
+/// Whether any command of a batch is a `despawn` or `remove`
+fn removes_in_batch(args: &Map<String, Value>) -> bool {
+    args.get("commands")
+        .and_then(Value::as_array)
+        .is_some_and(|commands| {
+            commands.iter().any(|command| {
+                matches!(
+                    command.get("command").and_then(Value::as_str),
+                    Some("despawn" | "remove")
+                )
+            })
+        })
+}
+
+/// Whether the request is a `brp_execute` of a method whose own tool would not be gated for the
+/// same parameters - `preview` is an MCP-side parameter, so BRP never sees it and it cannot let a
+/// raw despawn through
+fn is_harmless_execute(tool_name: ToolName, args: &Map<String, Value>) -> bool {
+    if tool_name != ToolName::BrpExecute {
+        return false;
+    }
+    let Some(method) = args
+        .get(ParameterName::Method.as_ref())
+        .and_then(Value::as_str)
+        .and_then(BrpMethod::from_str)
+    else {
+        return true;
+    };
+    match ToolName::from(method) {
+        ToolName::BrpExtrasApplyCommands => !args
+            .get(ParameterName::Params.as_ref())
+            .and_then(Value::as_object)
+            .is_some_and(removes_in_batch),
+        executed => !executed.requires_confirmation(),
+    }
+}
+
+/// Check the request arguments for a valid confirmation and strip the confirmation fields so
+/// they are never forwarded to BRP.
+///
+/// Returns `Err` with the details for a "confirmation required" response when the tool is gated
+/// and neither `confirm: true` nor a valid `confirmation_token` was provided. A despawn with
+/// `preview: true` changes nothing and needs no confirmation, nor does a command batch without a
+/// `despawn` or `remove` or a `brp_execute` of a method that is not gated.
+pub fn check_confirmation(
+    tool_name: ToolName,
+    arguments: Option<&mut Map<String, Value>>,
+) -> Result<(), Value> {
+    check_confirmation_when(is_confirmation_enabled(), tool_name, arguments)
+}
+
+/// [`check_confirmation`] with gating turned on or off by `enabled`
+fn check_confirmation_when(
+    enabled: bool,
+    tool_name: ToolName,
+    arguments: Option<&mut Map<String, Value>>,
+) -> Result<(), Value> {
+    let (confirm, token, previews) = arguments.map_or((None, None, false), |args| {
+        (
+            args.remove(ParameterName::Confirm.as_ref()),
+            args.remove(ParameterName::ConfirmationToken.as_ref()),
+            is_despawn_preview(tool_name, args)
+                || is_harmless_batch(tool_name, args)
+                || is_harmless_execute(tool_name, args),
+        )
+    });
+
+    if !enabled || !tool_name.requires_confirmation() || previews {
+        return Ok(());
+    }
+
+    let confirmed = matches!(confirm, Some(Value::Bool(true)))
+        || matches!(confirm.as_ref().and_then(Value::as_str), Some("true"));
+    if confirmed {
+        return Ok(());
+    }
+
+    if let Some(token) = token.as_ref().and_then(Value::as_str) {
+        if consume_confirmation_token(token, tool_name) {
+            return Ok(());
+        }
+        return Err(json!({
+            "confirmation_required": true,
+            "tool": tool_name.to_string(),
+            "reason": "confirmation_token is invalid, expired, or was issued for a different tool",
+            "how_to_confirm": confirmation_instructions(tool_name),
+        }));
+    }
+
+    Err(json!({
+        "confirmation_required": true,
+        "tool": tool_name.to_string(),
+        "reason": "this tool permanently removes state from the running app",
+        "how_to_confirm": confirmation_instructions(tool_name),
+    }))
+}
+
+fn confirmation_instructions(tool_name: ToolName) -> String {
+    format!(
+        "Re-run `{tool_name}` with `confirm: true`, or call `{}` with `tool: \"{tool_name}\"` and \
+         pass the returned `confirmation_token` (valid for {} seconds, single use)",
+        ToolName::BrpRequestConfirmation,
+        CONFIRMATION_TOKEN_TTL.as_secs()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_despawn_previews_skip_the_gate() {
+        let Value::Object(args) = json!({ "preview": true }) else {
+            unreachable!("arguments are an object");
+        };
+        assert!(is_despawn_preview(ToolName::WorldDespawnEntity, &args));
+        assert!(!is_despawn_preview(ToolName::BrpShutdown, &args));
+        assert!(!is_despawn_preview(ToolName::WorldRemoveComponents, &args));
+        assert!(!is_despawn_preview(ToolName::WorldRemoveResources, &args));
+
+        let Value::Object(args) = json!({ "preview": "true" }) else {
+            unreachable!("arguments are an object");
+        };
+        assert!(!is_despawn_preview(ToolName::WorldDespawnEntity, &args));
+    }
+
+    fn arguments(value: Value) -> Map<String, Value> {
+        let Value::Object(args) = value else {
+            unreachable!("arguments are an object");
+        };
+        args
+    }
+
+    #[test]
+    fn confirm_true_passes_and_is_stripped() {
+        for confirm in [json!(true), json!("true")] {
+            let mut args = arguments(json!({ "entity": 1, "confirm": confirm }));
+            assert!(check_confirmation_when(true, ToolName::BrpShutdown, Some(&mut args)).is_ok());
+            assert_eq!(Value::Object(args), json!({ "entity": 1 }));
+        }
+
+        let mut args = arguments(json!({ "confirm": false }));
+        let details = check_confirmation_when(true, ToolName::BrpShutdown, Some(&mut args))
+            .err()
+            .unwrap_or_else(|| unreachable!("confirm: false should be refused"));
+        assert_eq!(details["confirmation_required"], json!(true));
+        assert!(check_confirmation_when(true, ToolName::BrpShutdown, None).is_err());
+    }
+
+    #[test]
+    fn ungated_calls_pass_but_still_lose_the_confirmation_fields() {
+        let mut args = arguments(json!({ "confirmation_token": "stale" }));
+        assert!(check_confirmation_when(false, ToolName::BrpShutdown, Some(&mut args)).is_ok());
+        assert!(args.is_empty());
+        assert!(check_confirmation_when(true, ToolName::WorldQuery, None).is_ok());
+    }
+
+    #[test]
+    fn tokens_are_single_use_and_tool_specific() {
+        let token = issue_confirmation_token(ToolName::WorldDespawnEntity);
+        let mut args = arguments(json!({ "confirmation_token": token }));
+        assert!(check_confirmation_when(true, ToolName::BrpShutdown, Some(&mut args)).is_err());
+
+        let token = issue_confirmation_token(ToolName::WorldDespawnEntity);
+        let call = || arguments(json!({ "confirmation_token": token }));
+        let mut args = call();
+        assert!(
+            check_confirmation_when(true, ToolName::WorldDespawnEntity, Some(&mut args)).is_ok()
+        );
+        let mut args = call();
+        assert!(
+            check_confirmation_when(true, ToolName::WorldDespawnEntity, Some(&mut args)).is_err()
+        );
+    }
+
+    #[test]
+    fn command_batches_are_gated_only_when_they_despawn_or_remove() {
+        assert!(ToolName::BrpExtrasApplyCommands.requires_confirmation());
+        let check = |value: Value| {
+            let mut args = arguments(value);
+            check_confirmation_when(true, ToolName::BrpExtrasApplyCommands, Some(&mut args))
+        };
+
+        for command in ["despawn", "remove"] {
+            assert!(
+                check(json!({ "commands": [{ "command": "spawn" }, { "command": command }] }))
+                    .is_err()
+            );
+            assert!(
+                check(json!({ "commands": [{ "command": command }], "preview": true })).is_ok()
+            );
+            assert!(
+                check(json!({ "commands": [{ "command": command }], "confirm": true })).is_ok()
+            );
+        }
+        assert!(
+            check(json!({ "commands": [{ "command": "spawn" }, { "command": "insert" }] })).is_ok()
+        );
+        assert!(check_confirmation_when(true, ToolName::BrpExtrasApplyCommands, None).is_err());
+    }
+
+    #[test]
+    fn executing_a_gated_method_is_gated() {
+        assert!(ToolName::BrpExecute.requires_confirmation());
+        let check = |value: Value| {
+            let mut args = arguments(value);
+            check_confirmation_when(true, ToolName::BrpExecute, Some(&mut args))
+        };
+
+        for method in [
+            "world.despawn_entity",
+            "world.remove_components",
+            "world.remove_resources",
+        ] {
+            assert!(check(json!({ "method": method })).is_err());
+            assert!(check(json!({ "method": method, "params": { "preview": true } })).is_err());
+            assert!(check(json!({ "method": method, "confirm": true })).is_ok());
+        }
+        assert!(check(json!({ "method": "world.query", "params": {} })).is_ok());
+        assert!(
+            check(json!({
+                "method": "brp_extras/apply_commands",
+                "params": { "commands": [{ "command": "despawn" }] },
+            }))
+            .is_err()
+        );
+        assert!(
+            check(json!({
+                "method": "brp_extras/apply_commands",
+                "params": { "commands": [{ "command": "spawn" }] },
+            }))
+            .is_ok()
+        );
+    }
+
+    #[test]
+    fn relaunching_on_rebuild_is_gated() {
+        assert!(ToolName::BrpWatchAndRelaunch.requires_confirmation());
+        assert!(check_confirmation_when(true, ToolName::BrpWatchAndRelaunch, None).is_err());
+    }
+}
@@ -0,0 +1,190 @@
+//! Screenshots on error - what the app showed when a BRP tool call failed
+//!
+//! Gameplay-dependent failures - an entity missing because the level hadn't loaded yet, a UI
+//! node not on screen - are far quicker to diagnose with the frame in view. With
+//! `BRP_MCP_SCREENSHOT_ON_ERROR` set, a failed BRP tool call asks the app for a screenshot, saved
+//! to the managed screenshot directory, and attaches its path to the error details as
+//! `error_screenshot`. Only errors the app answered with get one - when the app couldn't be
+//! reached, didn't answer in time or the call failed before reaching it, the frame explains
+//! nothing. An app without `bevy_brp_extras` answers the screenshot request with an error, and
+//! the tool's error goes out unchanged.
+
+use std::sync::LazyLock;
+
+use serde_json::Map;
+use serde_json::Value;
+use serde_json::json;
+use tracing::debug;
+
+use super::ParameterName;
+use super::json_response::AnySchemaValue;
+use super::json_response::ToolCallJsonResponse;
+use super::tool_name::BrpMethod;
+use super::tool_name::ToolName;
+use crate::brp_tools::BrpClient;
+use crate::brp_tools::Port;
+use crate::brp_tools::ResponseStatus;
+use crate::brp_tools::with_managed_screenshot_path;
+use crate::error::Error;
+
+/// Environment variable that turns screenshots on error on (`1`, `true`, `yes`, `on`)
+pub const SCREENSHOT_ON_ERROR_ENV_VAR: &str = "BRP_MCP_SCREENSHOT_ON_ERROR";
+
+/// Error detail field holding the screenshot's path
+const ERROR_SCREENSHOT_FIELD: &str = "error_screenshot";
+
+/// Whether `BRP_MCP_SCREENSHOT_ON_ERROR` is set to a true value
+static SCREENSHOT_ON_ERROR: LazyLock<bool> = LazyLock::new(|| {
+    std::env::var(SCREENSHOT_ON_ERROR_ENV_VAR).is_ok_and(|value| is_enabled_value(&value))
+});
+
+fn is_enabled_value(value: &str) -> bool {
+    matches!(
+        value.trim().to_ascii_lowercase().as_str(),
+        "1" | "true" | "yes" | "on"
+    )
+}
+
+/// Whether a call of `tool_name` that failed with `error` gets a screenshot
+pub fn wanted(tool_name: ToolName, error: &Error) -> bool {
+    should_capture(*SCREENSHOT_ON_ERROR, tool_name, error)
+}
+
+fn should_capture(enabled: bool, tool_name: ToolName, error: &Error) -> bool {
+    // A failed screenshot won't be explained by another one
+    let calls_the_app =
+        tool_name.to_brp_method().is_some() && tool_name != ToolName::BrpExtrasScreenshot;
+    // Transport errors, timeouts and invalid arguments come from this side, not the app's frame
+    let app_answered = matches!(error, Error::ToolCall { .. } | Error::Structured { .. });
+    enabled && calls_the_app && app_answered
+}
+
+/// Capture a screenshot of the app a failed call with `arguments` was made against - call it
+/// when [`wanted`]
+///
+/// Returns the screenshot's path - `None` when the app couldn't take one.
+pub async fn capture(arguments: Option<&Map<String, Value>>) -> Option<String> {
+    let port = arguments
+        .and_then(|arguments| arguments.get(ParameterName::Port.as_ref()))
+        .and_then(|port| serde_json::from_value::<Port>(port.clone()).ok())
+        .unwrap_or_default();
+    let params = with_managed_screenshot_path(BrpMethod::BrpExtrasScreenshot, port, None)?;
+    let requested_path = params
+        .get(ParameterName::Path.as_ref())
+        .and_then(Value::as_str)
+        .map(String::from)?;
+
+    let client = BrpClient::new(BrpMethod::BrpExtrasScreenshot, port, Some(params));
+    match client.execute_raw().await {
+        Ok(ResponseStatus::Success(data)) => Some(
+            data.as_ref()
+                .and_then(|data| data.get(ParameterName::Path.as_ref()))
+                .and_then(Value::as_str)
+                .map_or(requested_path, String::from),
+        ),
+        Ok(ResponseStatus::Error(err)) => {
+            debug!(
+                "No screenshot on error from port {port}: {}",
+                err.get_message()
+            );
+            None
+        },
+        Err(report) => {
+            debug!("No screenshot on error from port {port}: {report}");
+            None
+        },
+    }
+}
+
+/// Add a screenshot's path to an error response's details, which sit in its metadata
+pub fn attach(mut response: ToolCallJsonResponse, path: String) -> ToolCallJsonResponse {
+    let metadata = response
+        .metadata
+        .get_or_insert_with(|| AnySchemaValue(Value::Object(Map::new())));
+    if let Value::Object(map) = &mut metadata.0 {
+        map.insert(ERROR_SCREENSHOT_FIELD.to_string(), json!(path));
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tool::response_builder::Response;
+
+    #[test]
+    fn the_env_var_turns_capture_on() {
+        for value in ["1", "true", " YES ", "on"] {
+            assert!(is_enabled_value(value), "{value}");
+        }
+        for value in ["", "0", "false", "off", "maybe"] {
+            assert!(!is_enabled_value(value), "{value}");
+        }
+
+        let app_error = Error::tool_call_failed("Entity 42 not found");
+        assert!(should_capture(
+            true,
+            ToolName::WorldGetComponents,
+            &app_error
+        ));
+        assert!(!should_capture(
+            false,
+            ToolName::WorldGetComponents,
+            &app_error
+        ));
+    }
+
+    #[test]
+    fn only_errors_the_app_answered_with_are_captured() {
+        let transport = Error::JsonRpc("HTTP request failed - Timeout".to_string());
+        assert!(!should_capture(
+            true,
+            ToolName::WorldGetComponents,
+            &transport
+        ));
+        let unreachable = Error::BrpCommunication("connection refused".to_string());
+        assert!(!should_capture(
+            true,
+            ToolName::WorldGetComponents,
+            &unreachable
+        ));
+        let invalid = Error::InvalidArgument("entity must be a number".to_string());
+        assert!(!should_capture(
+            true,
+            ToolName::WorldGetComponents,
+            &invalid
+        ));
+
+        let app_error = Error::tool_call_failed("Entity 42 not found");
+        assert!(!should_capture(
+            true,
+            ToolName::BrpExtrasScreenshot,
+            &app_error
+        ));
+        assert!(!should_capture(true, ToolName::BrpListBevyApps, &app_error));
+    }
+
+    #[test]
+    fn screenshot_path_joins_the_error_details() {
+        let details = json!({ "entity": 42 });
+        let response = Response::error_with_details(
+            "Entity 42 not found",
+            Some(&details),
+            ToolName::WorldGetComponents.get_call_info(),
+        );
+
+        let response = attach(
+            response,
+            "/tmp/screenshots/game_port15702_1.png".to_string(),
+        );
+
+        let Some(AnySchemaValue(details)) = response.metadata else {
+            unreachable!("the error response should have details");
+        };
+        assert_eq!(details["entity"], json!(42));
+        assert_eq!(
+            details[ERROR_SCREENSHOT_FIELD],
+            json!("/tmp/screenshots/game_port15702_1.png")
+        );
+    }
+}
@@ -5,6 +5,9 @@
 //! - Direct field access without JSON serialization
 //! - Automatic `CallInfo` generation
 
+use super::ResponseBuilder;
+use crate::error::Result;
+
 /// Specifies where a response field should be placed in the output JSON
 #[derive(Clone, Debug)]
 pub enum FieldPlacement {
@@ -16,6 +19,22 @@ pub enum FieldPlacement {
     ErrorInfo,
 }
 
+/// Schema of a single response field, used to build a tool's `outputSchema`
+///
+/// Generated by the `ResultStruct` derive macro for every `#[to_metadata]` and `#[to_result]`
+/// field.
+#[derive(Debug, Clone)]
+pub struct OutputSchemaField {
+    /// The name of the field (the key within `metadata`)
+    pub field_name: &'static str,
+    /// Where the field is placed (metadata or result)
+    pub placement:  FieldPlacement,
+    /// Metadata group the field is nested under, if any
+    pub group:      Option<&'static str>,
+    /// JSON schema for the field's type
+    pub schema:     schemars::Schema,
+}
+
 /// Information about where a field should be placed in the response
 ///
 /// Note: The `ParamStruct` and `ResultStruct` derive macros generate implementations that use
@@ -32,6 +51,8 @@ pub struct FieldPlacementInfo {
     pub source_path:  Option<&'static str>,
     /// Whether to skip this field if it's None
     pub skip_if_none: bool,
+    /// Metadata group the field is nested under (`#[to_metadata(group = "...")]`)
+    pub group:        Option<&'static str>,
 }
 
 /// Trait for types that have field placement information
@@ -42,3 +63,16 @@ pub trait HasFieldPlacement {
     /// Get the field placement information for this type
     fn field_placements() -> Vec<FieldPlacementInfo>;
 }
+
+/// A group of metadata fields a result struct flattens in with `#[to_metadata(flatten)]`, so
+/// fields shared by many results are declared once
+pub trait FlattenedMetadata {
+    /// Add the fields that are set to the response metadata
+    ///
+    /// # Errors
+    /// Returns an error if a field cannot be serialized.
+    fn add_metadata(&self, builder: ResponseBuilder) -> Result<ResponseBuilder>;
+
+    /// Schemas of the fields, for the tool's `outputSchema`
+    fn output_schema_fields(generator: &mut schemars::SchemaGenerator) -> Vec<OutputSchemaField>;
+}
@@ -1,75 +1,158 @@
 use std::fs;
 use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
 use std::time::SystemTime;
 use std::time::UNIX_EPOCH;
 
 use error_stack::ResultExt;
 use rmcp::model::CallToolRequestParam;
 use rmcp::model::CallToolResult;
+use serde_json::Map;
 use serde_json::Value;
 use serde_json::json;
 
+use super::canonical;
+use super::error_screenshot;
 use super::json_response::AnySchemaValue;
 use super::json_response::ToolCallJsonResponse;
+use super::strict_formats;
+use super::table_format;
+use super::table_format::OutputFormat;
+use super::verbosity;
+use crate::brp_tools::CallReport;
+use crate::brp_tools::compact_query_result;
 use crate::error::Error;
 use crate::error::Result;
+use crate::mcp_service::RootsSupport;
 use crate::tool::ParamStruct;
+use crate::tool::ParameterName;
+use crate::tool::ProgressReporter;
 use crate::tool::ResultStruct;
 use crate::tool::ToolDef;
+use crate::tool::ToolName;
 use crate::tool::ToolResult;
+use crate::tool::large_response;
 use crate::tool::large_response::CHARS_PER_TOKEN;
 use crate::tool::large_response::LargeResponseConfig;
+use crate::tool::redaction;
 use crate::tool::response_builder::Response;
 
+/// Metadata field counting the entries of a compact query's `shared_values` table
+const SHARED_VALUE_COUNT_FIELD: &str = "shared_value_count";
+
+/// Metadata field counting the values removed by redaction rules
+const REDACTED_VALUES_FIELD: &str = "redacted_values";
+
+/// Metadata field with the compressed and uncompressed sizes of compressed BRP responses
+const TRANSFER_SIZES_FIELD: &str = "transfer_sizes";
+
+/// Metadata field set when the call was answered from the read cache
+const CACHE_HIT_FIELD: &str = "cache_hit";
+
+/// Metadata field with the age of the cached response, in milliseconds
+const CACHE_AGE_MS_FIELD: &str = "cache_age_ms";
+
+/// Metadata field counting the requests sent again after a transient BRP error
+const TRANSIENT_RETRIES_FIELD: &str = "transient_retries";
+
+/// Metadata field describing an app restart noticed during the call
+const APP_RESTARTED_FIELD: &str = "app_restarted";
+
 /// Context passed to all handlers containing service, request, and MCP context
 #[derive(Clone)]
 pub struct HandlerContext {
     pub(super) tool_def: ToolDef,
     pub request:         CallToolRequestParam,
     pub roots:           Vec<PathBuf>,
+    roots_support:       RootsSupport,
+    progress:            Option<ProgressReporter>,
+    timeout:             Option<Duration>,
+    canonical:           bool,
+    max_response_tokens: Option<usize>,
 }
 
 impl HandlerContext {
     /// Create a new `HandlerContext`
+    #[allow(clippy::too_many_arguments)]
     pub(crate) const fn new(
         tool_def: ToolDef,
         request: CallToolRequestParam,
         roots: Vec<PathBuf>,
+        roots_support: RootsSupport,
+        progress: Option<ProgressReporter>,
+        timeout: Option<Duration>,
+        canonical: bool,
+        max_response_tokens: Option<usize>,
     ) -> Self {
         Self {
             tool_def,
             request,
             roots,
+            roots_support,
+            progress,
+            timeout,
+            canonical,
+            max_response_tokens,
         }
     }
 
+    /// How the client supports MCP roots, which `roots` came from
+    pub const fn roots_support(&self) -> RootsSupport { self.roots_support }
+
+    /// Progress reporter for this call - `None` unless the client sent a `progressToken`
+    pub const fn progress(&self) -> Option<&ProgressReporter> { self.progress.as_ref() }
+
+    /// Timeout enforced around this call's handler - from `timeout_ms` or the tool's default,
+    /// `None` when the call runs without one
+    pub const fn timeout(&self) -> Option<Duration> { self.timeout }
+
+    /// Whether the call asked for a canonical, diff-friendly response with `canonical: true`
+    pub const fn canonical(&self) -> bool { self.canonical }
+
     /// Get tool definition by looking up the request name in the service's tool registry
     ///
     /// # Errors
     /// Returns an error if the tool definition is not found.
     pub const fn tool_def(&self) -> &ToolDef { &self.tool_def }
 
+    /// The tool's input schema - `None` for tools without parameters
+    pub fn input_schema(&self) -> Option<Arc<Map<String, Value>>> {
+        self.tool_def
+            .parameters
+            .map(|build_parameters| build_parameters().build())
+    }
+
     /// Common parameter extraction methods (used by both BRP and local handlers)
+    ///
+    /// After deserializing, the values are checked against the struct's `#[param(...)]`
+    /// constraints so handlers receive parameters that already satisfy them.
     pub fn extract_parameter_values<T>(&self) -> Result<T>
     where
-        T: serde::de::DeserializeOwned,
+        T: ParamStruct,
     {
         // Get request arguments as JSON Value
-        // Special case: if T is unit type, use null instead of empty object
-        let args_value = if std::any::type_name::<T>() == "()" {
-            serde_json::Value::Null
-        } else {
-            let raw_args = self.request.arguments.as_ref().map_or_else(
-                || serde_json::Value::Object(serde_json::Map::new()),
-                |args| serde_json::Value::Object(args.clone()),
-            );
-            // Coerce string values that look like numbers/booleans to proper JSON types.
-            // This handles MCP clients that serialize numeric values as strings
-            // (e.g., "5" instead of 5), which would otherwise cause deserialization errors.
-            crate::json_object::coerce_string_values(raw_args)
+        let raw_args = self.request.arguments.as_ref().map_or_else(
+            || serde_json::Value::Object(serde_json::Map::new()),
+            |args| serde_json::Value::Object(args.clone()),
+        );
+        // Coerce strings to numbers/booleans where the input schema expects them.
+        // This handles MCP clients that serialize numeric values as strings
+        // (e.g., "5" instead of 5), which would otherwise cause deserialization errors,
+        // without touching string parameters that happen to look numeric.
+        let input_schema = self.input_schema();
+        let args_value = match input_schema {
+            Some(input_schema) => {
+                let args = strict_formats::coerce_unless_strict(raw_args, &input_schema);
+                // Calls that omit `port` go to the session default port
+                crate::brp_tools::Port::with_session_default(args, &input_schema)
+            },
+            None => raw_args,
         };
+        // Entity parameters may name an entity by an alias bound with `brp_alias_entity`
+        let args_value = crate::brp_tools::resolve_entity_aliases(args_value)?;
 
-        serde_json::from_value(args_value).map_err(|e| {
+        let params: T = serde_json::from_value(args_value).map_err(|e| {
             tracing::debug!("Serde deserialization error: {}", e);
 
             // Extract simplified type name (last component after ::)
@@ -87,7 +170,10 @@ impl HandlerContext {
                 .attach("Parameter validation failed")
                 .attach(format!("Full type path: {}", std::any::type_name::<T>()))
                 .attach(format!("Serde error details: {e}"))
-        })
+        })?;
+
+        params.validate()?;
+        Ok(params)
     }
 
     /// Get a field value from the request arguments
@@ -96,7 +182,16 @@ impl HandlerContext {
     }
 
     /// Format a tool result into a `CallToolResult`
-    pub fn format_result<T, P>(&self, tool_result: ToolResult<T, P>) -> CallToolResult
+    ///
+    /// `call_report` describes what the BRP client did for the call - compressed transfers, cache
+    /// hits, transient-error retries and app restarts are added to the metadata. A failed call's
+    /// `error_screenshot` - taken with `BRP_MCP_SCREENSHOT_ON_ERROR` set - goes in its details.
+    pub fn format_result<T, P>(
+        &self,
+        tool_result: ToolResult<T, P>,
+        call_report: CallReport,
+        error_screenshot: Option<String>,
+    ) -> CallToolResult
     where
         T: ResultStruct,
         P: ParamStruct,
@@ -118,9 +213,19 @@ impl HandlerContext {
                         },
                     };
 
+                let response = Self::add_call_report(response, call_report);
+                let response = self.redact_response(response);
+                // Compacted after redaction so redacted values are the ones shared
+                let response = self.compact_query_response(response);
+                // Rendered last so the table shows the redacted values
+                let response = self.table_format_response(response);
+
                 // Handle large response here with access to tool_name
                 match self.handle_large_response_if_needed(response) {
-                    Ok(processed) => processed.to_call_tool_result(),
+                    Ok(processed) => {
+                        verbosity::apply_verbosity(self.canonical_if_requested(processed))
+                            .to_call_tool_result()
+                    },
                     Err(e) => Response::error_message(
                         format!("Failed to process response: {}", e.current_context()),
                         call_info,
@@ -128,33 +233,39 @@ impl HandlerContext {
                     .to_call_tool_result(),
                 }
             },
-            Err(report) => match report.current_context() {
-                Error::Structured { result } => {
-                    // Create error response from structured result
-                    match Response::error(
-                        result.as_ref(),
-                        tool_result.params,
-                        call_info.clone(),
-                        self,
-                    ) {
-                        Ok(response) => response.to_call_tool_result(),
-                        Err(e) => Response::error_message(
-                            format!("Failed to create error response: {}", e.current_context()),
-                            call_info,
-                        )
-                        .to_call_tool_result(),
-                    }
-                },
-                Error::ToolCall { message, details } => {
-                    // Create error response with the error message and details
-                    Response::error_with_details(message, details.as_ref(), call_info)
-                        .to_call_tool_result()
-                },
-                _ => Response::error_message(
-                    format!("Internal error: {}", report.current_context()),
-                    call_info,
-                )
-                .to_call_tool_result(),
+            Err(report) => {
+                let response = match report.current_context() {
+                    Error::Structured { result } => {
+                        // Create error response from structured result
+                        match Response::error(
+                            result.as_ref(),
+                            tool_result.params,
+                            call_info.clone(),
+                            self,
+                        ) {
+                            Ok(response) => response,
+                            Err(e) => Response::error_message(
+                                format!("Failed to create error response: {}", e.current_context()),
+                                call_info,
+                            ),
+                        }
+                    },
+                    Error::ToolCall { message, details } => {
+                        // Create error response with the error message and details
+                        Response::error_with_details(message, details.as_ref(), call_info)
+                    },
+                    _ => Response::error_message(
+                        format!("Internal error: {}", report.current_context()),
+                        call_info,
+                    ),
+                };
+                // A failure right after a restart is most likely caused by it - keep the notice
+                let response = Self::add_call_report(response, call_report);
+                let response = match error_screenshot {
+                    Some(path) => error_screenshot::attach(response, path),
+                    None => response,
+                };
+                self.canonical_if_requested(response).to_call_tool_result()
             },
         }
     }
@@ -171,19 +282,148 @@ impl HandlerContext {
         .to_call_tool_result()
     }
 
-    /// Handle large responses if needed
+    /// Report compressed transfer sizes, read cache hits, retries and app restarts in the metadata
+    fn add_call_report(
+        mut response: ToolCallJsonResponse,
+        call_report: CallReport,
+    ) -> ToolCallJsonResponse {
+        if call_report == CallReport::default() {
+            return response;
+        }
+        let metadata = response
+            .metadata
+            .get_or_insert_with(|| AnySchemaValue(Value::Object(serde_json::Map::new())));
+        if let Value::Object(map) = &mut metadata.0 {
+            if let Some(transfer_sizes) = call_report.transfer_sizes {
+                map.insert(TRANSFER_SIZES_FIELD.to_string(), json!(transfer_sizes));
+            }
+            if let Some(cache_age) = call_report.cache_age {
+                map.insert(CACHE_HIT_FIELD.to_string(), json!(true));
+                map.insert(
+                    CACHE_AGE_MS_FIELD.to_string(),
+                    json!(u64::try_from(cache_age.as_millis()).unwrap_or(u64::MAX)),
+                );
+            }
+            if call_report.transient_retries > 0 {
+                map.insert(
+                    TRANSIENT_RETRIES_FIELD.to_string(),
+                    json!(call_report.transient_retries),
+                );
+            }
+            if let Some(restart) = call_report.app_restart {
+                map.insert(
+                    APP_RESTARTED_FIELD.to_string(),
+                    json!({
+                        "port": restart.port,
+                        "aliases_dropped": restart.aliases_dropped,
+                        "stale_watches": restart.stale_watches,
+                        "notice": format!(
+                            "The app on port {} restarted - entity IDs, aliases and cached type \
+                             information from its previous run were discarded",
+                            restart.port
+                        ),
+                    }),
+                );
+            }
+        }
+        response
+    }
+
+    /// Rewrite the response into its canonical form when called with `canonical: true`
+    fn canonical_if_requested(&self, response: ToolCallJsonResponse) -> ToolCallJsonResponse {
+        if self.canonical {
+            canonical::canonical_response(response)
+        } else {
+            response
+        }
+    }
+
+    /// Append the result as a plain-text table to the message when called with `format: "table"`
+    fn table_format_response(&self, mut response: ToolCallJsonResponse) -> ToolCallJsonResponse {
+        let format = self
+            .extract_optional_named_field(ParameterName::Format.as_ref())
+            .and_then(|value| serde_json::from_value::<OutputFormat>(value.clone()).ok());
+        if format != Some(OutputFormat::Table) {
+            return response;
+        }
+        let Some(AnySchemaValue(result)) = &response.result else {
+            return response;
+        };
+        if let Some(table) = table_format::render_table(self.tool_def.tool_name, result) {
+            // Terse responses have no message to put the table under
+            response.message = if response.message.is_empty() {
+                table
+            } else {
+                format!("{}\n\n{table}", response.message)
+            };
+        }
+        response
+    }
+
+    /// Factor shared component values out of a `world_query` result called with `compact: true`
+    fn compact_query_response(&self, mut response: ToolCallJsonResponse) -> ToolCallJsonResponse {
+        let compact = self
+            .extract_optional_named_field(ParameterName::Compact.as_ref())
+            .and_then(Value::as_bool)
+            == Some(true);
+        if self.tool_def.tool_name != ToolName::WorldQuery || !compact {
+            return response;
+        }
+        let Some(AnySchemaValue(result)) = &response.result else {
+            return response;
+        };
+        let Some((compacted, shared_count)) = compact_query_result(result) else {
+            return response;
+        };
+
+        response.result = Some(AnySchemaValue(compacted));
+        let metadata = response
+            .metadata
+            .get_or_insert_with(|| AnySchemaValue(Value::Object(serde_json::Map::new())));
+        if let Value::Object(map) = &mut metadata.0 {
+            map.insert(SHARED_VALUE_COUNT_FIELD.to_string(), json!(shared_count));
+        }
+        response
+    }
+
+    /// Apply the configured redaction rules to the result, counting redactions in the metadata
+    fn redact_response(&self, mut response: ToolCallJsonResponse) -> ToolCallJsonResponse {
+        let resource = self
+            .extract_optional_named_field(ParameterName::Resource.as_ref())
+            .and_then(Value::as_str);
+        let Some(AnySchemaValue(result)) = &mut response.result else {
+            return response;
+        };
+
+        let redacted = redaction::redact_result(result, resource);
+        if redacted > 0 {
+            let metadata = response
+                .metadata
+                .get_or_insert_with(|| AnySchemaValue(Value::Object(serde_json::Map::new())));
+            if let Value::Object(map) = &mut metadata.0 {
+                map.insert(REDACTED_VALUES_FIELD.to_string(), json!(redacted));
+            }
+        }
+        response
+    }
+
+    /// Save the result to a file when the response is over the call's or the server's token
+    /// budget, leaving a preview of its leading part that fits the budget
     fn handle_large_response_if_needed(
         &self,
         response: ToolCallJsonResponse,
     ) -> Result<ToolCallJsonResponse> {
-        let config = LargeResponseConfig::default();
+        let config = LargeResponseConfig::for_call(self.max_response_tokens);
+        let Some(max_tokens) = config.max_tokens else {
+            return Ok(response);
+        };
 
         // Check size and handle
         let response_json = serde_json::to_string(&response)
             .change_context(Error::General("Failed to serialize response".to_string()))?;
         let estimated_tokens = response_json.len() / CHARS_PER_TOKEN;
 
-        if estimated_tokens > config.max_tokens
+        if estimated_tokens > max_tokens
             && let Some(result_field) = &response.result
         {
             // Generate filename using self.tool_def.tool_name
@@ -209,13 +449,19 @@ impl HandlerContext {
                 filepath.display()
             )))?;
 
-            let mut modified_response = response;
-            modified_response.result = Some(AnySchemaValue(json!({
+            let mut saved = json!({
                 "saved_to_file": true,
                 "filepath": filepath.to_string_lossy(),
                 "instructions": "Use Read tool to examine, Grep to search, or jq commands to filter the data.",
-                "original_size_tokens": estimated_tokens
-            })));
+                "original_size_tokens": estimated_tokens,
+                "max_response_tokens": max_tokens
+            });
+            if let Some(preview) = large_response::preview(&result_field.0, max_tokens) {
+                saved["preview"] = preview;
+            }
+
+            let mut modified_response = response;
+            modified_response.result = Some(AnySchemaValue(saved));
 
             return Ok(modified_response);
         }
@@ -1,4 +1,12 @@
 use std::path::PathBuf;
+use std::sync::LazyLock;
+
+use serde_json::Map;
+use serde_json::Value;
+use serde_json::json;
+use tracing::debug;
+
+use super::ParameterName;
 
 // ============================================================================
 // LARGE RESPONSE TOKEN CALCULATION CONSTANTS
@@ -12,13 +20,34 @@ pub const CHARS_PER_TOKEN: usize = 4;
 /// (MCP seems to count tokens differently than our 4 chars/token estimate)
 pub const DEFAULT_MAX_RESPONSE_TOKENS: usize = 15_000;
 
+/// Environment variable setting the server's response token budget - `0` turns the limit off
+pub const MAX_RESPONSE_TOKENS_ENV_VAR: &str = "BRP_MCP_MAX_RESPONSE_TOKENS";
+
+/// Share of the token budget a saved response's preview may use
+const PREVIEW_BUDGET_DIVISOR: usize = 2;
+
+/// Server-wide token budget - read once at first use, `None` when responses are never saved
+static SERVER_MAX_TOKENS: LazyLock<Option<usize>> = LazyLock::new(|| {
+    let Ok(tokens) = std::env::var(MAX_RESPONSE_TOKENS_ENV_VAR) else {
+        return Some(DEFAULT_MAX_RESPONSE_TOKENS);
+    };
+    match tokens.trim().parse::<usize>() {
+        Ok(0) => None,
+        Ok(tokens) => Some(tokens),
+        Err(_) => {
+            debug!("Invalid {MAX_RESPONSE_TOKENS_ENV_VAR} value '{tokens}' - using the default");
+            Some(DEFAULT_MAX_RESPONSE_TOKENS)
+        },
+    }
+});
+
 /// Configuration for large response handling
 #[derive(Clone)]
 pub struct LargeResponseConfig {
     /// Prefix for generated filenames (e.g., "`brp_response`_", "`log_list`_")
     pub file_prefix: String,
-    /// Token limit for responses
-    pub max_tokens:  usize,
+    /// Token limit for responses - `None` returns every response in full
+    pub max_tokens:  Option<usize>,
     /// Directory for temporary files
     pub temp_dir:    PathBuf,
 }
@@ -27,8 +56,117 @@ impl Default for LargeResponseConfig {
     fn default() -> Self {
         Self {
             file_prefix: "mcp_response_".to_string(),
-            max_tokens:  DEFAULT_MAX_RESPONSE_TOKENS,
+            max_tokens:  *SERVER_MAX_TOKENS,
             temp_dir:    std::env::temp_dir(),
         }
     }
 }
+
+impl LargeResponseConfig {
+    /// The configuration for a call that asked for `max_response_tokens`, if it did
+    pub fn for_call(max_response_tokens: Option<usize>) -> Self {
+        let mut config = Self::default();
+        if let Some(tokens) = max_response_tokens {
+            config.max_tokens = (tokens > 0).then_some(tokens);
+        }
+        config
+    }
+}
+
+/// Remove `max_response_tokens` from the request arguments so tools never see it, returning the
+/// budget the call asked for - `Some(0)` for a full response, `None` when it asked for nothing.
+///
+/// Returns `Err` with the details for an "invalid parameter" response when the value is not a
+/// non-negative integer.
+pub fn take_max_response_tokens(
+    arguments: Option<&mut Map<String, Value>>,
+) -> Result<Option<usize>, Value> {
+    let requested =
+        arguments.and_then(|args| args.remove(ParameterName::MaxResponseTokens.as_ref()));
+    let Some(requested) = requested.filter(|value| !value.is_null()) else {
+        return Ok(None);
+    };
+
+    // Accept numeric strings too - some MCP clients serialize numbers as strings
+    requested
+        .as_u64()
+        .or_else(|| requested.as_str().and_then(|s| s.trim().parse().ok()))
+        .map(|tokens| Some(usize::try_from(tokens).unwrap_or(usize::MAX)))
+        .ok_or_else(|| {
+            json!({
+                "invalid_parameter": ParameterName::MaxResponseTokens.to_string(),
+                "value": requested,
+                "reason": "max_response_tokens must be a non-negative integer (0 returns the \
+                           full response)",
+            })
+        })
+}
+
+/// The leading part of a result too large to return, fitting a share of `max_tokens`
+///
+/// Arrays keep their first items and objects their first entries; `None` when not even one
+/// fits, or the result is neither.
+pub fn preview(result: &Value, max_tokens: usize) -> Option<Value> {
+    let budget = max_tokens * CHARS_PER_TOKEN / PREVIEW_BUDGET_DIVISOR;
+    let fits = |used: &mut usize, value: &Value| {
+        *used += value.to_string().len();
+        *used <= budget
+    };
+    let mut used = 0;
+    match result {
+        Value::Array(items) => {
+            let shown: Vec<&Value> = items
+                .iter()
+                .take_while(|item| fits(&mut used, item))
+                .collect();
+            (!shown.is_empty())
+                .then(|| json!({ "items": shown, "shown": shown.len(), "total": items.len() }))
+        },
+        Value::Object(entries) => {
+            let shown: Map<String, Value> = entries
+                .iter()
+                .take_while(|(key, value)| {
+                    used += key.len();
+                    fits(&mut used, value)
+                })
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect();
+            (!shown.is_empty())
+                .then(|| json!({ "entries": shown, "shown": shown.len(), "total": entries.len() }))
+        },
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn preview_keeps_leading_items_within_half_the_budget() {
+        let items: Vec<Value> = (0..100)
+            .map(|n| json!({ "entity": n, "name": "x" }))
+            .collect();
+        let preview = preview(&Value::Array(items), 24);
+
+        // 48 characters of budget fit the first two 23-character items
+        assert_eq!(preview.as_ref().map(|p| p["shown"].clone()), Some(json!(2)));
+        assert_eq!(preview.map(|p| p["total"].clone()), Some(json!(100)));
+        assert_eq!(super::preview(&json!([{"big": "x".repeat(100)}]), 20), None);
+    }
+
+    #[test]
+    fn per_call_budget_overrides_the_server_budget() {
+        let mut arguments = Map::new();
+        arguments.insert("max_response_tokens".to_string(), json!("0"));
+        let requested = take_max_response_tokens(Some(&mut arguments));
+
+        assert_eq!(requested, Ok(Some(0)));
+        assert!(arguments.is_empty());
+        assert_eq!(LargeResponseConfig::for_call(Some(0)).max_tokens, None);
+        assert_eq!(
+            LargeResponseConfig::for_call(Some(500)).max_tokens,
+            Some(500)
+        );
+    }
+}
@@ -1,26 +1,54 @@
 mod annotations;
+mod canonical;
+mod computed;
+mod confirmation;
+mod error_screenshot;
 mod field_placement;
 mod handler_context;
 mod json_response;
 mod large_response;
+mod output_schema;
+mod param_validation;
 mod parameters;
+mod progress;
+mod redaction;
 mod response_builder;
+mod strict_formats;
+mod table_format;
+mod timeout;
 mod tool_def;
 mod tool_name;
 mod types;
+mod verbosity;
 
 // exported for mcp_macros
+//
+pub use computed::Countable;
+pub use computed::DurationMillis;
+pub use confirmation::CONFIRMATION_TOKEN_TTL;
+pub use confirmation::issue_confirmation_token;
 pub use field_placement::FieldPlacement;
 pub use field_placement::FieldPlacementInfo;
+pub use field_placement::FlattenedMetadata;
 pub use field_placement::HasFieldPlacement;
-//
+pub use field_placement::OutputSchemaField;
 pub use handler_context::HandlerContext;
+pub use param_validation::ParamConstraint;
 pub use parameters::NoParams;
 pub use parameters::ParamStruct;
 pub use parameters::ParameterName;
+#[cfg(test)]
+pub use parameters::build_parameters_from;
+pub use progress::ProgressReporter;
 //
 // exported for mcp_macros
 pub use response_builder::ResponseBuilder;
+pub use strict_formats::configured_strict_formats;
+pub use strict_formats::set_session_strict_formats;
+pub use strict_formats::strict_formats;
+pub use strict_formats::with_strict_formats;
+pub use table_format::OutputFormat;
+pub use table_format::without_format_field;
 //
 pub use tool_def::ToolDef;
 //
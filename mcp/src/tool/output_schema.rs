@@ -0,0 +1,134 @@
+//! Per-tool MCP `outputSchema` generation
+//!
+//! Every tool responds with the same `ToolCallJsonResponse` envelope, but what goes into its
+//! `metadata` and `result` depends on the tool's `ResultStruct`. We start from the envelope's
+//! schema and replace those two properties with schemas built from the result struct's fields
+//! so clients can validate and destructure results without guessing.
+
+use std::sync::Arc;
+
+use rmcp::model::JsonObject;
+use schemars::generate::SchemaSettings;
+use serde_json::Map;
+use serde_json::Value;
+
+use super::field_placement::FieldPlacement;
+use super::json_response::ToolCallJsonResponse;
+use super::types::ResultStruct;
+use crate::json_object::JsonObjectAccess;
+use crate::json_schema::JsonSchemaType;
+use crate::json_schema::SchemaField;
+
+/// Build the `outputSchema` for a tool whose handler returns `R`
+pub fn output_schema_for<R: ResultStruct>() -> Arc<JsonObject> {
+    let mut settings = SchemaSettings::default();
+    settings.inline_subschemas = true;
+    let mut generator = settings.into_generator();
+
+    let fields = R::output_schema_fields(&mut generator);
+    let envelope = generator.into_root_schema_for::<ToolCallJsonResponse>();
+
+    let Ok(mut schema) = serde_json::to_value(envelope) else {
+        // Fallback to empty schema if serialization fails
+        return Arc::new(JsonObject::new());
+    };
+
+    let mut metadata_properties = Map::new();
+    let mut result_schema = None;
+    for field in fields {
+        let Ok(field_schema) = serde_json::to_value(field.schema) else {
+            continue;
+        };
+        match field.placement {
+            FieldPlacement::Metadata => match field.group {
+                Some(group) => insert_grouped_property(
+                    &mut metadata_properties,
+                    group,
+                    field.field_name,
+                    field_schema,
+                ),
+                None => {
+                    metadata_properties.insert(field.field_name.to_string(), field_schema);
+                },
+            },
+            // The result field's value becomes the whole `result`, so its name is not a key
+            FieldPlacement::Result => result_schema = Some(field_schema),
+            FieldPlacement::ErrorInfo => {},
+        }
+    }
+
+    if let Some(properties) = schema
+        .get_mut(SchemaField::Properties.as_ref())
+        .and_then(Value::as_object_mut)
+    {
+        // Error responses reuse `metadata` for error details, so no metadata field is required
+        // and additional properties stay allowed
+        if !metadata_properties.is_empty() {
+            let mut metadata = Map::new();
+            metadata.insert_field(SchemaField::Type.as_ref(), JsonSchemaType::Object);
+            metadata.insert_field(SchemaField::Properties.as_ref(), metadata_properties);
+            properties.insert_field("metadata", metadata);
+        }
+        if let Some(result_schema) = result_schema {
+            properties.insert_field("result", result_schema);
+        }
+    }
+
+    replace_true_schemas(&mut schema);
+
+    Arc::new(schema.as_object().cloned().unwrap_or_default())
+}
+
+/// Nest a grouped metadata field's schema under its group's object schema
+fn insert_grouped_property(
+    metadata_properties: &mut Map<String, Value>,
+    group: &str,
+    field_name: &str,
+    field_schema: Value,
+) {
+    let group_schema = metadata_properties.entry(group).or_insert_with(|| {
+        let mut group_schema = Map::new();
+        group_schema.insert_field(SchemaField::Type.as_ref(), JsonSchemaType::Object);
+        group_schema.insert_field(SchemaField::Properties.as_ref(), Map::new());
+        Value::Object(group_schema)
+    });
+    if let Some(properties) = group_schema
+        .get_mut(SchemaField::Properties.as_ref())
+        .and_then(Value::as_object_mut)
+    {
+        properties.insert(field_name.to_string(), field_schema);
+    }
+}
+
+/// Replace `true` subschemas (what schemars emits for `serde_json::Value`) with the equivalent
+/// `{}` - strict validators (like Gemini's) reject boolean schemas
+fn replace_true_schemas(schema: &mut Value) {
+    let Some(obj) = schema.as_object_mut() else {
+        return;
+    };
+
+    for (key, value) in obj.iter_mut() {
+        let is_schema_map = key == SchemaField::Properties.as_ref();
+        let is_schema =
+            key == SchemaField::Items.as_ref() || key == SchemaField::AdditionalProperties.as_ref();
+        let is_schema_list = key == SchemaField::AnyOf.as_ref()
+            || key == SchemaField::OneOf.as_ref()
+            || key == "allOf"
+            || key == SchemaField::PrefixItems.as_ref();
+
+        if is_schema_map && let Some(properties) = value.as_object_mut() {
+            properties.values_mut().for_each(replace_true_schema);
+        } else if is_schema {
+            replace_true_schema(value);
+        } else if is_schema_list && let Some(variants) = value.as_array_mut() {
+            variants.iter_mut().for_each(replace_true_schema);
+        }
+    }
+}
+
+fn replace_true_schema(schema: &mut Value) {
+    if matches!(schema, Value::Bool(true)) {
+        *schema = Value::Object(Map::new());
+    }
+    replace_true_schemas(schema);
+}
@@ -0,0 +1,264 @@
+//! Declarative parameter validation
+//!
+//! `#[param(min = .., max = .., regex = "..", one_of(..))]` on a `ParamStruct` field becomes a
+//! `ParamConstraint`. The same constraint is applied to the tool's input schema (as
+//! `minimum`/`maximum`/`pattern`/`enum`) and checked at runtime in
+//! `HandlerContext::extract_parameter_values`, so the advertised schema and the enforced rules
+//! can't drift apart.
+
+use std::collections::HashMap;
+use std::sync::LazyLock;
+use std::sync::Mutex;
+
+use regex::Regex;
+
+use crate::error::Error;
+use crate::error::Result;
+
+/// `#[param(regex)]` patterns, each compiled the first time a value is checked against it
+static PATTERNS: LazyLock<Mutex<HashMap<&'static str, Regex>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Whether `text` matches `pattern`, compiling the pattern only once
+fn matches_pattern(pattern: &'static str, text: &str) -> std::result::Result<bool, regex::Error> {
+    let mut patterns = PATTERNS
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    if let Some(regex) = patterns.get(pattern) {
+        return Ok(regex.is_match(text));
+    }
+    let regex = Regex::new(pattern)?;
+    let matched = regex.is_match(text);
+    patterns.insert(pattern, regex);
+    drop(patterns);
+    Ok(matched)
+}
+
+/// Constraint declared with `#[param(...)]` on a `ParamStruct` field
+#[derive(Debug, Clone, Copy)]
+pub struct ParamConstraint {
+    /// Name of the constrained field
+    pub field_name: &'static str,
+    /// Inclusive lower bound for integer values
+    pub minimum:    Option<i64>,
+    /// Inclusive upper bound for integer values
+    pub maximum:    Option<i64>,
+    /// Regex string values must match
+    pub pattern:    Option<&'static str>,
+    /// Allowed string values (empty means unrestricted)
+    pub one_of:     &'static [&'static str],
+}
+
+impl ParamConstraint {
+    /// Check every scalar in `value` against this constraint
+    pub fn validate(&self, value: &impl ParamValue) -> Result<()> {
+        let mut violation = None;
+        value.visit_scalars(&mut |scalar| {
+            if violation.is_none() {
+                violation = self.check(scalar);
+            }
+        });
+
+        violation.map_or(Ok(()), |reason| {
+            Err(Error::ParameterExtraction(format!(
+                "Invalid value for '{}': {reason}",
+                self.field_name
+            ))
+            .into())
+        })
+    }
+
+    fn check(&self, scalar: ParamScalar<'_>) -> Option<String> {
+        match scalar {
+            ParamScalar::Integer(value) => {
+                if let Some(minimum) = self.minimum
+                    && value < i128::from(minimum)
+                {
+                    return Some(format!("{value} is less than the minimum of {minimum}"));
+                }
+                if let Some(maximum) = self.maximum
+                    && value > i128::from(maximum)
+                {
+                    return Some(format!("{value} is greater than the maximum of {maximum}"));
+                }
+                None
+            },
+            ParamScalar::Text(text) => {
+                if !self.one_of.is_empty() && !self.one_of.contains(&text) {
+                    return Some(format!(
+                        "'{text}' is not one of: {}",
+                        self.one_of.join(", ")
+                    ));
+                }
+                let pattern = self.pattern?;
+                match matches_pattern(pattern, text) {
+                    Ok(true) => None,
+                    Ok(false) => Some(format!("'{text}' does not match the pattern '{pattern}'")),
+                    Err(e) => Some(format!("invalid pattern '{pattern}': {e}")),
+                }
+            },
+        }
+    }
+}
+
+/// A single value of a constrained field
+#[derive(Debug, Clone, Copy)]
+pub enum ParamScalar<'a> {
+    /// Integer value, checked against `min`/`max`
+    Integer(i128),
+    /// String value, checked against `regex`/`one_of`
+    Text(&'a str),
+}
+
+/// Field types that can carry `#[param(...)]` constraints
+///
+/// `Option` fields visit nothing when absent and collections visit each element, so a
+/// constraint always applies to the individual values a caller supplied.
+pub trait ParamValue {
+    /// Call `visit` with each scalar value held by this field
+    fn visit_scalars(&self, visit: &mut dyn FnMut(ParamScalar<'_>));
+}
+
+macro_rules! impl_integer_param_value {
+    ($($ty:ty),*) => {
+        $(
+            impl ParamValue for $ty {
+                fn visit_scalars(&self, visit: &mut dyn FnMut(ParamScalar<'_>)) {
+                    visit(ParamScalar::Integer(i128::from(*self)));
+                }
+            }
+        )*
+    };
+}
+
+impl_integer_param_value!(u8, u16, u32, u64, i32, i64);
+
+impl ParamValue for usize {
+    fn visit_scalars(&self, visit: &mut dyn FnMut(ParamScalar<'_>)) {
+        if let Ok(value) = i128::try_from(*self) {
+            visit(ParamScalar::Integer(value));
+        }
+    }
+}
+
+impl ParamValue for String {
+    fn visit_scalars(&self, visit: &mut dyn FnMut(ParamScalar<'_>)) {
+        visit(ParamScalar::Text(self));
+    }
+}
+
+impl<T: ParamValue> ParamValue for Option<T> {
+    fn visit_scalars(&self, visit: &mut dyn FnMut(ParamScalar<'_>)) {
+        if let Some(value) = self {
+            value.visit_scalars(visit);
+        }
+    }
+}
+
+impl<T: ParamValue> ParamValue for Vec<T> {
+    fn visit_scalars(&self, visit: &mut dyn FnMut(ParamScalar<'_>)) {
+        for value in self {
+            value.visit_scalars(visit);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_brp_mcp_macros::ParamStruct;
+    use serde::Deserialize;
+    use serde::Serialize;
+
+    use super::*;
+    use crate::tool::ParamStruct;
+
+    const fn constraint() -> ParamConstraint {
+        ParamConstraint {
+            field_name: "field",
+            minimum:    None,
+            maximum:    None,
+            pattern:    None,
+            one_of:     &[],
+        }
+    }
+
+    #[test]
+    fn range_applies_to_present_values_only() {
+        let range = ParamConstraint {
+            minimum: Some(1),
+            maximum: Some(64),
+            ..constraint()
+        };
+        assert!(range.validate(&32_u32).is_ok());
+        assert!(range.validate(&None::<u32>).is_ok());
+        assert!(range.validate(&0_u32).is_err());
+        assert!(range.validate(&Some(65_u32)).is_err());
+        assert!(range.validate(&vec![1_u32, 100]).is_err());
+    }
+
+    #[test]
+    fn text_checks_one_of_and_pattern() {
+        let one_of = ParamConstraint {
+            one_of: &["debug", "release"],
+            ..constraint()
+        };
+        assert!(one_of.validate(&"release".to_string()).is_ok());
+        assert!(one_of.validate(&"fast".to_string()).is_err());
+
+        let pattern = ParamConstraint {
+            pattern: Some(r"^bevy_brp_mcp_.*\.log$"),
+            ..constraint()
+        };
+        assert!(
+            pattern
+                .validate(&"bevy_brp_mcp_app.log".to_string())
+                .is_ok()
+        );
+        assert!(pattern.validate(&"../etc/passwd".to_string()).is_err());
+        // The second check reuses the compiled pattern
+        assert!(
+            pattern
+                .validate(&"bevy_brp_mcp_other.log".to_string())
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn invalid_patterns_are_reported() {
+        let broken = ParamConstraint {
+            pattern: Some("("),
+            ..constraint()
+        };
+        let Err(error) = broken.validate(&"text".to_string()) else {
+            unreachable!("an invalid pattern should fail validation");
+        };
+        assert!(error.to_string().contains("invalid pattern"));
+    }
+
+    #[derive(Serialize, Deserialize, ParamStruct)]
+    struct RenamedParams {
+        #[serde(rename = "frames")]
+        #[param(min = 1)]
+        delay_frames: u32,
+        #[param(one_of("fast", "slow"))]
+        r#type:       String,
+    }
+
+    #[test]
+    fn violations_name_the_parameter_callers_send() {
+        let names: Vec<&str> = RenamedParams::param_constraints()
+            .iter()
+            .map(|constraint| constraint.field_name)
+            .collect();
+        assert_eq!(names, ["frames", "type"]);
+
+        let params = RenamedParams {
+            delay_frames: 0,
+            r#type:       "fast".to_string(),
+        };
+        let Err(error) = params.validate() else {
+            unreachable!("zero frames is below the minimum");
+        };
+        assert!(error.to_string().contains("'frames'"));
+    }
+}
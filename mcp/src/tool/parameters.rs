@@ -13,6 +13,9 @@ use serde_json::Value;
 use strum::Display;
 use strum::EnumString;
 
+use super::param_validation::ParamConstraint;
+use crate::brp_tools::Port;
+use crate::error::Result;
 use crate::json_object::IntoStrings;
 use crate::json_object::JsonObjectAccess;
 use crate::json_schema::JsonSchemaType;
@@ -25,8 +28,19 @@ use crate::json_schema::SchemaField;
 /// in the `ToolFn` trait.
 ///
 /// The trait is automatically implemented by the `ParamStruct` derive macro
-/// for parameter structs.
-pub trait ParamStruct: Send + Sync + serde::Serialize + serde::de::DeserializeOwned {}
+/// for parameter structs, including the `#[param(...)]` field constraints.
+pub trait ParamStruct: Send + Sync + serde::Serialize + serde::de::DeserializeOwned {
+    /// Constraints declared with `#[param(...)]` on the struct's fields
+    fn param_constraints() -> Vec<ParamConstraint>
+    where
+        Self: Sized,
+    {
+        Vec::new()
+    }
+
+    /// Check field values against their `#[param(...)]` constraints
+    fn validate(&self) -> Result<()> { Ok(()) }
+}
 
 /// Shared parameter struct for tools that have no parameters
 #[derive(Clone, Deserialize, Serialize, JsonSchema, ParamStruct)]
@@ -53,10 +67,26 @@ pub struct NoParams {
 pub enum ParameterName {
     /// Application name
     AppName,
+    /// Archive flag for despawns
+    Archive,
+    /// Generated `Name` for spawned entities
+    AutoName,
+    /// Canonical response flag for diffing outputs across runs
+    Canonical,
+    /// Compact output flag for queries
+    Compact,
     /// Component type for mutations
     Component,
     /// Components parameter for operations
     Components,
+    /// Explicit confirmation flag for destructive operations
+    Confirm,
+    /// Single-use token from `brp_request_confirmation`
+    ConfirmationToken,
+    /// Owner of the control lock on the target port, for calls the lock is enforced on
+    ControlOwner,
+    /// Format correctors to run when a request fails with a format error
+    Correctors,
     /// Data parameter for queries
     Data,
     /// Duration in milliseconds
@@ -73,14 +103,24 @@ pub enum ParameterName {
     Filename,
     /// Filter parameter for queries
     Filter,
+    /// Output format for query and list results
+    Format,
+    /// Number of instances to launch
+    InstanceCount,
+    /// Per-instance launch overrides
+    Instances,
     /// Keys array for input simulation
     Keys,
     /// Keyword for filtering
     Keyword,
     /// Tracing level
     Level,
+    /// Token budget for one response before its result is saved to a file
+    MaxResponseTokens,
     /// Method name for dynamic execution
     Method,
+    /// Bypass flag for the read cache
+    NoCache,
     /// Age threshold in seconds
     OlderThanSeconds,
     /// Parameters for dynamic method execution
@@ -91,18 +131,30 @@ pub enum ParameterName {
     Path,
     /// Port number for connections
     Port,
+    /// Named launch preset
+    Preset,
+    /// Report what a despawn would affect instead of despawning
+    Preview,
     /// Build profile (debug/release)
     Profile,
     /// Resource type name parameter
     Resource,
     /// Strict mode flag for queries
     Strict,
+    /// Per-call override of the session's strict formats setting
+    StrictFormats,
     /// Number of lines to tail
     TailLines,
+    /// Per-call handler timeout in milliseconds
+    TimeoutMs,
     /// Types parameter for discovery
     Types,
+    /// Schema validation flag for spawn, insert and mutate
+    Validate,
     /// Value for mutations and inserts
     Value,
+    /// Enum variant to switch a mutation path to
+    Variant,
     /// Verbose output flag
     Verbose,
     /// Watch ID for stopping watches
@@ -122,14 +174,18 @@ pub enum ParameterName {
 enum ParameterType {
     /// A string field
     String,
-    /// A numeric field (typically u64)
+    /// A floating point field
     Number,
+    /// An integer field
+    Integer,
     /// A boolean field
     Boolean,
     /// An array of strings
     StringArray,
     /// An array of numbers
     NumberArray,
+    /// An array of integers (e.g. entity IDs)
+    IntegerArray,
     /// An object field
     Object,
     /// Any JSON value (object, array, etc.)
@@ -160,6 +216,33 @@ impl ParameterBuilder {
         self
     }
 
+    /// Add a string property restricted to a fixed set of values
+    ///
+    /// A single allowed value is emitted as `const`, several as `enum`.
+    pub fn add_enum_property(
+        mut self,
+        name: &str,
+        description: &str,
+        values: Vec<Value>,
+        required: bool,
+    ) -> Self {
+        let mut prop = Map::new();
+        prop.insert_field(SchemaField::Type.as_ref(), JsonSchemaType::String);
+        if let [value] = values.as_slice() {
+            prop.insert_field(SchemaField::Const.as_ref(), value.clone());
+        } else {
+            prop.insert_field(SchemaField::Enum.as_ref(), values);
+        }
+        prop.insert_field(SchemaField::Description.as_ref(), description);
+        self.properties.insert_field(name, prop);
+
+        if required {
+            self.required.push(name.to_string());
+        }
+
+        self
+    }
+
     /// Add a string array property to the schema
     pub fn add_string_array_property(
         mut self,
@@ -222,6 +305,94 @@ impl ParameterBuilder {
         self
     }
 
+    /// Add an integer property to the schema
+    pub fn add_integer_property(mut self, name: &str, description: &str, required: bool) -> Self {
+        let mut prop = Map::new();
+        prop.insert_field("type", JsonSchemaType::Integer);
+        prop.insert_field("description", description);
+        self.properties.insert_field(name, prop);
+
+        if required {
+            self.required.push(name.to_string());
+        }
+
+        self
+    }
+
+    /// Add an integer array property to the schema
+    pub fn add_integer_array_property(
+        mut self,
+        name: &str,
+        description: &str,
+        required: bool,
+    ) -> Self {
+        let mut prop = Map::new();
+        prop.insert_field("type", JsonSchemaType::Array);
+
+        let mut items = Map::new();
+        items.insert_field("type", JsonSchemaType::Integer);
+        prop.insert_field("items", items);
+
+        prop.insert_field("description", description);
+        self.properties.insert_field(name, prop);
+
+        if required {
+            self.required.push(name.to_string());
+        }
+
+        self
+    }
+
+    /// Set the `default` of a previously added property
+    pub fn with_default(mut self, name: &str, default: Value) -> Self {
+        if let Some(prop) = self.properties.get_mut(name).and_then(Value::as_object_mut) {
+            prop.insert_field(SchemaField::Default.as_ref(), default);
+        }
+        self
+    }
+
+    /// Constrain a previously added numeric property to `minimum..=maximum`
+    ///
+    /// For array properties the range applies to the items.
+    pub fn with_range(
+        mut self,
+        name: &str,
+        minimum: Option<Value>,
+        maximum: Option<Value>,
+    ) -> Self {
+        if let Some(prop) = self.properties.get_mut(name).and_then(Value::as_object_mut) {
+            insert_range(prop, minimum, maximum);
+        }
+        self
+    }
+
+    /// Apply a `#[param(...)]` constraint to a previously added property
+    ///
+    /// For array properties the constraint applies to the items.
+    pub fn with_constraint(mut self, constraint: &ParamConstraint) -> Self {
+        let Some(prop) = self
+            .properties
+            .get_mut(constraint.field_name)
+            .and_then(Value::as_object_mut)
+        else {
+            return self;
+        };
+
+        if let Some(minimum) = constraint.minimum {
+            insert_constraint(prop, SchemaField::Minimum, Value::from(minimum));
+        }
+        if let Some(maximum) = constraint.maximum {
+            insert_constraint(prop, SchemaField::Maximum, Value::from(maximum));
+        }
+        if let Some(pattern) = constraint.pattern {
+            insert_constraint(prop, SchemaField::Pattern, Value::from(pattern));
+        }
+        if !constraint.one_of.is_empty() {
+            insert_constraint(prop, SchemaField::Enum, Value::from(constraint.one_of));
+        }
+        self
+    }
+
     /// Add a boolean property to the schema
     pub fn add_boolean_property(mut self, name: &str, description: &str, required: bool) -> Self {
         let mut prop = Map::new();
@@ -293,6 +464,25 @@ impl ParameterBuilder {
     }
 }
 
+/// Insert `minimum`/`maximum` into a property, or into its `items` if it is an array
+fn insert_range(prop: &mut Map<String, Value>, minimum: Option<Value>, maximum: Option<Value>) {
+    if let Some(minimum) = minimum {
+        insert_constraint(prop, SchemaField::Minimum, minimum);
+    }
+    if let Some(maximum) = maximum {
+        insert_constraint(prop, SchemaField::Maximum, maximum);
+    }
+}
+
+/// Insert a constraint keyword into a property, or into its `items` if it is an array
+fn insert_constraint(prop: &mut Map<String, Value>, field: SchemaField, value: Value) {
+    if let Some(Value::Object(items)) = prop.get_mut(SchemaField::Items.as_ref()) {
+        insert_constraint(items, field, value);
+        return;
+    }
+    prop.insert_field(field.as_ref(), value);
+}
+
 /// Handle array type schemas and determine the array element type
 fn handle_array_type(obj: &Map<String, Value>) -> ParameterType {
     obj.get_field(SchemaField::Items)
@@ -301,9 +491,8 @@ fn handle_array_type(obj: &Map<String, Value>) -> ParameterType {
         .and_then(|item_type| item_type.as_str())
         .map_or(ParameterType::Any, |item_type_str| match item_type_str {
             s if s == JsonSchemaType::String.as_ref() => ParameterType::StringArray,
-            s if s == JsonSchemaType::Integer.as_ref() || s == JsonSchemaType::Number.as_ref() => {
-                ParameterType::NumberArray
-            },
+            s if s == JsonSchemaType::Integer.as_ref() => ParameterType::IntegerArray,
+            s if s == JsonSchemaType::Number.as_ref() => ParameterType::NumberArray,
             _ => ParameterType::Any,
         })
 }
@@ -312,9 +501,8 @@ fn handle_array_type(obj: &Map<String, Value>) -> ParameterType {
 fn handle_string_type(type_str: &str, obj: &Map<String, Value>) -> ParameterType {
     match type_str {
         s if s == JsonSchemaType::String.as_ref() => ParameterType::String,
-        s if s == JsonSchemaType::Integer.as_ref() || s == JsonSchemaType::Number.as_ref() => {
-            ParameterType::Number
-        },
+        s if s == JsonSchemaType::Integer.as_ref() => ParameterType::Integer,
+        s if s == JsonSchemaType::Number.as_ref() => ParameterType::Number,
         s if s == JsonSchemaType::Boolean.as_ref() => ParameterType::Boolean,
         s if s == JsonSchemaType::Object.as_ref() => ParameterType::Object,
         s if s == JsonSchemaType::Array.as_ref() => handle_array_type(obj),
@@ -333,12 +521,8 @@ fn handle_type_array(types: &[Value]) -> ParameterType {
     if non_null_types.len() == 1 {
         match non_null_types.first() {
             Some(&s) if s == JsonSchemaType::String.as_ref() => ParameterType::String,
-            Some(&s)
-                if s == JsonSchemaType::Integer.as_ref()
-                    || s == JsonSchemaType::Number.as_ref() =>
-            {
-                ParameterType::Number
-            },
+            Some(&s) if s == JsonSchemaType::Integer.as_ref() => ParameterType::Integer,
+            Some(&s) if s == JsonSchemaType::Number.as_ref() => ParameterType::Number,
             Some(&s) if s == JsonSchemaType::Boolean.as_ref() => ParameterType::Boolean,
             _ => ParameterType::Any,
         }
@@ -409,6 +593,92 @@ fn handle_any_of_schema(any_of: &[Value]) -> ParameterType {
     ParameterType::Any
 }
 
+/// Resolve a `#/$defs/...` reference, returning the value unchanged if it isn't a reference
+fn resolve_ref<'a>(value: &'a Value, defs: Option<&'a Value>) -> &'a Value {
+    value
+        .as_object()
+        .and_then(|o| o.get_field(SchemaField::Ref))
+        .and_then(|r| r.as_str())
+        .and_then(|ref_path| {
+            ref_path.strip_prefix("#/$defs/").and_then(|type_name| {
+                defs.and_then(|d| d.as_object())
+                    .and_then(|d| d.get(type_name))
+            })
+        })
+        .unwrap_or(value)
+}
+
+/// Extract the allowed string values of a schema generated from a Rust enum with unit variants
+///
+/// Handles the three shapes schemars produces: a plain `enum` list, a `oneOf` of `const`
+/// variants (when variants carry doc comments), and an `anyOf` wrapping either of those with
+/// `null` (for `Option<Enum>`).
+fn extract_enum_values(value: &Value, defs: Option<&Value>) -> Option<Vec<Value>> {
+    let obj = resolve_ref(value, defs).as_object()?;
+
+    if let Some(values) = obj.get_field(SchemaField::Enum).and_then(|e| e.as_array()) {
+        let strings: Vec<Value> = values.iter().filter(|v| v.is_string()).cloned().collect();
+        return (!strings.is_empty()).then_some(strings);
+    }
+
+    if let Some(one_of) = obj.get_field(SchemaField::OneOf).and_then(|v| v.as_array()) {
+        return one_of
+            .iter()
+            .map(|variant| {
+                let variant = variant.as_object()?;
+                variant
+                    .get_field(SchemaField::Const)
+                    .filter(|c| c.is_string())
+                    .cloned()
+                    .or_else(|| {
+                        match variant
+                            .get_field(SchemaField::Enum)
+                            .and_then(|e| e.as_array())?
+                            .as_slice()
+                        {
+                            [single @ Value::String(_)] => Some(single.clone()),
+                            _ => None,
+                        }
+                    })
+            })
+            .collect();
+    }
+
+    if let Some(any_of) = obj.get_field(SchemaField::AnyOf).and_then(|v| v.as_array()) {
+        let mut non_null = any_of.iter().filter(|variant| {
+            variant
+                .as_object()
+                .and_then(|v| v.get_field(SchemaField::Type))
+                .and_then(|t| t.as_str())
+                .is_none_or(|t| t != JsonSchemaType::Null.as_ref())
+        });
+        if let (Some(variant), None) = (non_null.next(), non_null.next()) {
+            return extract_enum_values(variant, defs);
+        }
+    }
+
+    None
+}
+
+/// Extract `minimum`/`maximum` from a numeric schema, or from its `items` for arrays
+///
+/// These come from the Rust type (e.g. `u32` has a minimum of 0) or from a manual `JsonSchema`
+/// impl (e.g. `Port`) - a field's `#[param(min = .., max = ..)]` is added by `with_constraint`.
+fn extract_numeric_range(value: &Value) -> (Option<Value>, Option<Value>) {
+    let Some(obj) = value.as_object() else {
+        return (None, None);
+    };
+    let bounds_of = |obj: &Map<String, Value>| {
+        (
+            obj.get_field(SchemaField::Minimum).cloned(),
+            obj.get_field(SchemaField::Maximum).cloned(),
+        )
+    };
+    obj.get_field(SchemaField::Items)
+        .and_then(Value::as_object)
+        .map_or_else(|| bounds_of(obj), bounds_of)
+}
+
 fn map_schema_type_to_parameter_type(schema: &Schema) -> ParameterType {
     let Some(obj) = schema.as_object() else {
         return ParameterType::Any;
@@ -456,7 +726,7 @@ fn map_schema_type_to_parameter_type(schema: &Schema) -> ParameterType {
 /// Build parameters from a `JsonSchema` type directly into a `ParameterBuilder`
 /// All tools with parameters derive `JsonSchema` making it possible for us
 /// to build the parameters from the schema
-pub fn build_parameters_from<T: JsonSchema>() -> ParameterBuilder {
+pub fn build_parameters_from<T: JsonSchema + ParamStruct>() -> ParameterBuilder {
     let schema = schemars::schema_for!(T);
     let mut builder = ParameterBuilder::new();
 
@@ -492,41 +762,73 @@ pub fn build_parameters_from<T: JsonSchema>() -> ParameterBuilder {
 
     for (field_name, field_value) in properties {
         let required = required_fields.contains(field_name);
+        builder = add_field(builder, field_name, field_value, defs, required);
+    }
 
-        // Resolve $ref if present
-        let resolved_value = field_value
-            .as_object()
-            .and_then(|o| o.get_field(SchemaField::Ref))
-            .and_then(|r| r.as_str())
-            .and_then(|ref_path| {
-                ref_path.strip_prefix("#/$defs/").and_then(|type_name| {
-                    defs.and_then(|d| d.as_object())
-                        .and_then(|d| d.get(type_name))
-                })
-            })
-            .unwrap_or(field_value);
+    // `#[param(...)]` constraints are enforced at runtime, so advertise them too
+    for constraint in T::param_constraints() {
+        builder = builder.with_constraint(&constraint);
+    }
 
-        // Convert the resolved JSON value to a Schema for processing
-        let field_schema = if let Value::Object(obj) = resolved_value {
-            Schema::from(obj.clone())
-        } else if let Value::Bool(b) = resolved_value {
-            Schema::from(*b)
-        } else {
-            continue; // Skip non-schema values
-        };
-        let param_type = map_schema_type_to_parameter_type(&field_schema);
+    builder
+}
 
-        // Extract description from schema if available
-        let description = resolved_value
-            .as_object()
-            .and_then(|obj| obj.get_field(SchemaField::Description))
-            .and_then(|d| d.as_str())
-            .unwrap_or(field_name.as_str());
+/// The `description` of a schema, if it has one
+fn description_of(schema: &Value) -> Option<&str> {
+    schema
+        .as_object()
+        .and_then(|obj| obj.get_field(SchemaField::Description))
+        .and_then(Value::as_str)
+}
+
+/// Whether a property's schema is a `Port`
+fn references_port(schema: &Value) -> bool {
+    schema
+        .as_object()
+        .and_then(|obj| obj.get_field(SchemaField::Ref))
+        .and_then(Value::as_str)
+        .and_then(|ref_path| ref_path.strip_prefix("#/$defs/"))
+        .is_some_and(|type_name| type_name == Port::schema_name())
+}
+
+/// Add a single property from a struct's JSON schema to the builder
+fn add_field(
+    builder: ParameterBuilder,
+    field_name: &str,
+    field_value: &Value,
+    defs: Option<&Value>,
+    required: bool,
+) -> ParameterBuilder {
+    // Resolve $ref if present
+    let resolved_value = resolve_ref(field_value, defs);
+
+    // Convert the resolved JSON value to a Schema for processing
+    let field_schema = if let Value::Object(obj) = resolved_value {
+        Schema::from(obj.clone())
+    } else if let Value::Bool(b) = resolved_value {
+        Schema::from(*b)
+    } else {
+        return builder; // Skip non-schema values
+    };
+    let param_type = map_schema_type_to_parameter_type(&field_schema);
+
+    // Extract description from schema if available - the field's own doc comment sits next to
+    // a `$ref`, so prefer it over the referenced type's
+    let description = description_of(field_value)
+        .or_else(|| description_of(resolved_value))
+        .unwrap_or(field_name);
 
+    // Enums with unit variants become constrained strings rather than plain strings
+    let mut builder = if let Some(values) = extract_enum_values(field_value, defs) {
+        builder.add_enum_property(field_name, description, values, required)
+    } else {
         // Add to builder based on type
-        builder = match param_type {
+        match param_type {
             ParameterType::String => builder.add_string_property(field_name, description, required),
             ParameterType::Number => builder.add_number_property(field_name, description, required),
+            ParameterType::Integer => {
+                builder.add_integer_property(field_name, description, required)
+            },
             ParameterType::Boolean => {
                 builder.add_boolean_property(field_name, description, required)
             },
@@ -536,9 +838,35 @@ pub fn build_parameters_from<T: JsonSchema>() -> ParameterBuilder {
             ParameterType::NumberArray => {
                 builder.add_number_array_property(field_name, description, required)
             },
+            ParameterType::IntegerArray => {
+                builder.add_integer_array_property(field_name, description, required)
+            },
             ParameterType::Object => builder.add_object_property(field_name, description, required),
             ParameterType::Any => builder.add_any_property(field_name, description, required),
-        };
+        }
+    };
+
+    if matches!(
+        param_type,
+        ParameterType::Number
+            | ParameterType::Integer
+            | ParameterType::NumberArray
+            | ParameterType::IntegerArray
+    ) {
+        let (minimum, maximum) = extract_numeric_range(resolved_value);
+        builder = builder.with_range(field_name, minimum, maximum);
+    }
+
+    // Surface serde defaults (`#[serde(default)]`) so clients can display and prefill them -
+    // except a port's, which would override the session default port the call gets when it
+    // omits `port`
+    if let Some(default) = field_value
+        .as_object()
+        .and_then(|obj| obj.get_field(SchemaField::Default))
+        .filter(|default| !default.is_null())
+        .filter(|_| !references_port(field_value))
+    {
+        builder = builder.with_default(field_name, default.clone());
     }
 
     builder
@@ -547,3 +875,75 @@ pub fn build_parameters_from<T: JsonSchema>() -> ParameterBuilder {
 impl From<ParameterName> for String {
     fn from(param: ParameterName) -> Self { param.as_ref().to_string() }
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::app_tools::LaunchBevyBinaryParams;
+    use crate::brp_tools::ScreenshotParams;
+    use crate::brp_tools::SendKeysParams;
+
+    /// The schema `T`'s parameter `name` is advertised with
+    fn property<T: JsonSchema + ParamStruct>(name: &str) -> Value {
+        build_parameters_from::<T>()
+            .build()
+            .get("properties")
+            .and_then(|properties| properties.get(name))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// `type`, `minimum` and `maximum` of a property
+    fn range_of(property: &Value) -> (Value, Value, Value) {
+        let field = |name: &str| property.get(name).cloned().unwrap_or_default();
+        (field("type"), field("minimum"), field("maximum"))
+    }
+
+    #[test]
+    fn integer_parameters_carry_their_range() {
+        assert_eq!(
+            range_of(&property::<LaunchBevyBinaryParams>("port")),
+            (json!("integer"), json!(1024), json!(65534))
+        );
+        assert_eq!(
+            range_of(&property::<LaunchBevyBinaryParams>("instance_count")),
+            (json!("integer"), json!(1), json!(100))
+        );
+        assert_eq!(
+            range_of(&property::<SendKeysParams>("duration_ms")),
+            (json!("integer"), json!(0), json!(60_000))
+        );
+        assert_eq!(
+            range_of(&property::<ScreenshotParams>("delay_frames")),
+            (json!("integer"), json!(0), json!(600))
+        );
+    }
+
+    #[test]
+    fn ports_advertise_no_default_and_keep_the_field_description() {
+        let port = property::<LaunchBevyBinaryParams>("port");
+
+        assert_eq!(port.get("default"), None);
+        assert_eq!(port.get("description"), Some(&json!("The BRP port")));
+        assert_eq!(
+            property::<LaunchBevyBinaryParams>("instance_count").get("default"),
+            Some(&json!(1))
+        );
+    }
+
+    #[test]
+    fn param_bounds_are_enforced_when_extracted() {
+        let screenshot = |delay_frames: u32| ScreenshotParams {
+            path:                None,
+            width:               None,
+            height:              None,
+            delay_frames:        Some(delay_frames),
+            wait_for_completion: false,
+            port:                crate::brp_tools::Port::default(),
+        };
+        assert!(screenshot(600).validate().is_ok());
+        assert!(screenshot(601).validate().is_err());
+    }
+}
@@ -0,0 +1,45 @@
+//! MCP progress notifications for long-running tool calls
+//!
+//! A client opts in by sending a `progressToken` in the request's `_meta`. Tools that do a lot of
+//! work (like `brp_all_type_guides`) report progress through the `ProgressReporter` on their
+//! `HandlerContext` so the client can show progress and keep its request alive.
+
+use rmcp::Peer;
+use rmcp::RoleServer;
+use rmcp::model::ProgressNotificationParam;
+use rmcp::model::ProgressToken;
+use rmcp::service::RequestContext;
+use tracing::debug;
+
+/// Sends progress notifications for a single tool call
+#[derive(Clone)]
+pub struct ProgressReporter {
+    peer:  Peer<RoleServer>,
+    token: ProgressToken,
+}
+
+impl ProgressReporter {
+    /// Create a reporter if the client asked for progress on this request
+    pub fn from_request_context(context: &RequestContext<RoleServer>) -> Option<Self> {
+        context.meta.get_progress_token().map(|token| Self {
+            peer: context.peer.clone(),
+            token,
+        })
+    }
+
+    /// Report that `completed` of `total` units of work are done
+    ///
+    /// Failures are only logged - progress is informational and must never fail the tool call.
+    #[allow(clippy::cast_precision_loss)] // counts are far below f64's exact integer range
+    pub async fn report(&self, completed: usize, total: usize, message: impl Into<String>) {
+        let notification = ProgressNotificationParam {
+            progress_token: self.token.clone(),
+            progress:       completed as f64,
+            total:          Some(total as f64),
+            message:        Some(message.into()),
+        };
+        if let Err(e) = self.peer.notify_progress(notification).await {
+            debug!("Failed to send progress notification: {e}");
+        }
+    }
+}
@@ -0,0 +1,232 @@
+//! Redaction of oversized component and resource values in tool responses
+//!
+//! A single huge component - mesh data, an image buffer, an internal blob - can use up a whole
+//! token budget. Rules in the JSON file named by `BRP_MCP_REDACTION_RULES` omit or truncate the
+//! values of chosen types, or of fields within them, wherever they appear in a tool's result.
+//! Removed data is replaced by a string marker saying how much was dropped.
+
+use std::fs;
+use std::sync::LazyLock;
+
+use serde::Deserialize;
+use serde_json::Map;
+use serde_json::Value;
+use tracing::warn;
+
+/// Environment variable naming the JSON file of redaction rules
+pub const REDACTION_RULES_ENV_VAR: &str = "BRP_MCP_REDACTION_RULES";
+
+/// Characters kept by a `truncate` rule without `max_chars`
+const DEFAULT_MAX_CHARS: usize = 256;
+
+/// What a rule does to a matching value
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RedactionAction {
+    /// Replace the value with a marker
+    Omit,
+    /// Keep the first `max_chars` characters of the value, as a string, followed by a marker
+    #[default]
+    Truncate,
+}
+
+/// A redaction rule from the rules file
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RedactionRule {
+    /// Component or resource type - a full type path, or a short name matching any type path
+    /// that ends in it
+    #[serde(rename = "type")]
+    pub type_name: String,
+    /// JSON pointer to a field within the value, e.g. `/data/positions` - omit to redact the
+    /// whole value
+    #[serde(default)]
+    pub path:      Option<String>,
+    /// What to do with the value
+    #[serde(default)]
+    pub action:    RedactionAction,
+    /// Characters kept by `truncate` (default: 256)
+    #[serde(default)]
+    pub max_chars: Option<usize>,
+}
+
+impl RedactionRule {
+    fn matches(&self, type_path: &str) -> bool {
+        if self.type_name.contains("::") {
+            return type_path == self.type_name;
+        }
+        let base = type_path.split('<').next().unwrap_or(type_path);
+        base.rsplit("::").next() == Some(self.type_name.as_str())
+    }
+
+    /// Apply the rule to a matching value, returning whether anything was removed
+    fn apply(&self, value: &mut Value) -> bool {
+        let target = match &self.path {
+            Some(path) => match value.pointer_mut(path) {
+                Some(target) => target,
+                None => return false,
+            },
+            None => value,
+        };
+
+        let text = match &*target {
+            Value::String(text) => text.clone(),
+            other => other.to_string(),
+        };
+        let total = text.chars().count();
+        match self.action {
+            RedactionAction::Omit => {
+                *target = Value::String(format!("[redacted: {total} chars omitted]"));
+                true
+            },
+            RedactionAction::Truncate => {
+                let max_chars = self.max_chars.unwrap_or(DEFAULT_MAX_CHARS);
+                if total <= max_chars {
+                    return false;
+                }
+                let kept: String = text.chars().take(max_chars).collect();
+                *target = Value::String(format!(
+                    "{kept}... [redacted: {} of {total} chars truncated]",
+                    total - max_chars
+                ));
+                true
+            },
+        }
+    }
+}
+
+/// Rules from the rules file - read once at first use, empty when the variable is unset
+static REDACTION_RULES: LazyLock<Vec<RedactionRule>> = LazyLock::new(|| {
+    let Ok(path) = std::env::var(REDACTION_RULES_ENV_VAR) else {
+        return Vec::new();
+    };
+    let rules = fs::read_to_string(&path)
+        .map_err(|e| e.to_string())
+        .and_then(|contents| serde_json::from_str(&contents).map_err(|e| e.to_string()));
+    rules.unwrap_or_else(|e| {
+        warn!("Ignoring redaction rules in {path}: {e}");
+        Vec::new()
+    })
+});
+
+/// Redact `result` by the configured rules, returning the number of values redacted
+///
+/// `resource` is the resource type a `world.get_resources` call asked for - its value is
+/// returned under `value` rather than keyed by its type path.
+pub fn redact_result(result: &mut Value, resource: Option<&str>) -> usize {
+    redact_with_rules(result, resource, &REDACTION_RULES)
+}
+
+fn redact_with_rules(result: &mut Value, resource: Option<&str>, rules: &[RedactionRule]) -> usize {
+    if rules.is_empty() {
+        return 0;
+    }
+
+    let mut redacted = 0;
+    if let Some(resource) = resource
+        && let Some(value) = result.get_mut("value")
+    {
+        redacted += apply_matching(resource, value, rules);
+    }
+    redacted + redact_keyed_values(result, rules)
+}
+
+/// Redact values keyed by a matching type path, anywhere in `value`
+fn redact_keyed_values(value: &mut Value, rules: &[RedactionRule]) -> usize {
+    match value {
+        Value::Object(map) => redact_map(map, rules),
+        Value::Array(items) => items
+            .iter_mut()
+            .map(|item| redact_keyed_values(item, rules))
+            .sum(),
+        _ => 0,
+    }
+}
+
+fn redact_map(map: &mut Map<String, Value>, rules: &[RedactionRule]) -> usize {
+    map.iter_mut()
+        .map(|(key, child)| {
+            let redacted = apply_matching(key, child, rules);
+            if redacted > 0 {
+                redacted
+            } else {
+                redact_keyed_values(child, rules)
+            }
+        })
+        .sum()
+}
+
+fn apply_matching(type_path: &str, value: &mut Value, rules: &[RedactionRule]) -> usize {
+    rules
+        .iter()
+        .filter(|rule| rule.matches(type_path))
+        .filter(|rule| rule.apply(value))
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn rules(rules: &Value) -> Vec<RedactionRule> {
+        serde_json::from_value(rules.clone()).unwrap_or_default()
+    }
+
+    #[test]
+    fn omits_and_truncates_matching_components_in_query_results() {
+        let rules = rules(&json!([
+            {"type": "Mesh3d", "action": "omit"},
+            {"type": "my_game::Terrain", "path": "/heights", "max_chars": 5}
+        ]));
+        let mut result = json!([{
+            "entity": 1,
+            "components": {
+                "bevy_mesh::components::Mesh3d": {"handle": "x".repeat(40)},
+                "my_game::Terrain": {"heights": [1, 2, 3, 4, 5], "name": "hills"},
+                "my_game::OtherTerrain": {"heights": [1, 2, 3, 4, 5]}
+            }
+        }]);
+
+        let redacted = redact_with_rules(&mut result, None, &rules);
+
+        assert_eq!(redacted, 2);
+        let components = &result[0]["components"];
+        assert_eq!(
+            components["bevy_mesh::components::Mesh3d"],
+            "[redacted: 53 chars omitted]"
+        );
+        assert_eq!(
+            components["my_game::Terrain"]["heights"],
+            "[1,2,... [redacted: 6 of 11 chars truncated]"
+        );
+        assert_eq!(components["my_game::Terrain"]["name"], "hills");
+        assert_eq!(
+            components["my_game::OtherTerrain"]["heights"],
+            json!([1, 2, 3, 4, 5])
+        );
+    }
+
+    #[test]
+    fn redacts_resource_values_and_leaves_short_values_alone() {
+        let rules = rules(&json!([{"type": "my_game::Blob", "max_chars": 100}]));
+
+        let mut short = json!({"value": [1, 2, 3]});
+        assert_eq!(
+            redact_with_rules(&mut short, Some("my_game::Blob"), &rules),
+            0
+        );
+
+        let mut long = json!({"value": "y".repeat(150)});
+        assert_eq!(
+            redact_with_rules(&mut long, Some("my_game::Blob"), &rules),
+            1
+        );
+        assert!(
+            long["value"]
+                .as_str()
+                .is_some_and(|text| text.ends_with("[redacted: 50 of 150 chars truncated]"))
+        );
+    }
+}
@@ -8,6 +8,8 @@ use super::field_placement::FieldPlacement;
 use super::json_response::ResponseStatus;
 use super::json_response::ToolCallJsonResponse;
 use super::tool_name::CallInfo;
+use super::verbosity;
+use super::verbosity::Verbosity;
 use crate::error::Error;
 use crate::error::Result;
 
@@ -148,6 +150,41 @@ impl ResponseBuilder {
         }
     }
 
+    /// Add a field to a nested object within metadata, creating the group object if needed
+    pub fn add_metadata_field_to_group(
+        mut self,
+        group: &str,
+        key: &str,
+        value: impl Serialize,
+    ) -> Result<Self> {
+        use error_stack::ResultExt;
+
+        use super::json_response::AnySchemaValue;
+
+        let value_json = serde_json::to_value(value)
+            .change_context(Error::General(format!("Failed to serialize field '{key}'")))?;
+
+        // Skip fields marked for nullable skipping
+        if let Value::String(s) = &value_json
+            && s == "__SKIP_NULL_FIELD__"
+        {
+            return Ok(self);
+        }
+
+        let metadata = self
+            .metadata
+            .get_or_insert_with(|| AnySchemaValue(Value::Object(serde_json::Map::new())));
+        if let Value::Object(map) = &mut metadata.0
+            && let Value::Object(group_map) = map
+                .entry(group)
+                .or_insert_with(|| Value::Object(serde_json::Map::new()))
+        {
+            group_map.insert(key.to_string(), value_json);
+        }
+
+        Ok(self)
+    }
+
     /// Add a field to the specified location (metadata or result object)
     pub fn add_field_to(
         mut self,
@@ -307,7 +344,19 @@ impl ResponseBuilder {
         // Perform template substitution
         let template_str = result.get_message_template()?;
         tracing::debug!("Template before substitution: '{}'", template_str);
-        let message = Self::substitute_dynamic_template(template_str, &self, handler_context);
+        let message = match verbosity::verbosity() {
+            // Terse successes carry only their structured fields
+            Verbosity::Terse if matches!(self.status, ResponseStatus::Success) => String::new(),
+            Verbosity::Verbose => verbosity::with_unmentioned_metadata(
+                Self::substitute_dynamic_template(template_str, &self, handler_context),
+                &Self::parse_template_placeholders(template_str),
+                self.metadata(),
+                Self::value_to_string,
+            ),
+            Verbosity::Terse | Verbosity::Normal => {
+                Self::substitute_dynamic_template(template_str, &self, handler_context)
+            },
+        };
         tracing::debug!("Template after substitution: '{}'", message);
         self = self.message(message);
 
@@ -379,9 +428,14 @@ impl ResponseBuilder {
             }
         }
 
-        // Then check metadata
-        if let Some(Value::Object(metadata)) = builder.metadata()
-            && let Some(value) = metadata.get(placeholder)
+        // Then check metadata - dotted placeholders (`{timing.launch_duration_ms}`) reach into
+        // grouped metadata
+        if let Some(metadata @ Value::Object(_)) = builder.metadata()
+            && let Some(value) = metadata.get(placeholder).or_else(|| {
+                placeholder
+                    .split('.')
+                    .try_fold(metadata, |value, key| value.get(key))
+            })
         {
             return Some(Self::value_to_string(value));
         }
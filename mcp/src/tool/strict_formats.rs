@@ -0,0 +1,154 @@
+//! Strict formats - no silent coercion of what the caller sent
+//!
+//! By default the server patches over malformed input: tool arguments sent as strings are
+//! coerced to the numbers and booleans their schema declares, and BRP requests rejected for a
+//! format error are retried through the format-correction pipeline. That hides the failures a
+//! library developer validating their own payload generation needs to see. With strict formats
+//! on, arguments are deserialized as sent and BRP format errors are returned as they are.
+//!
+//! The session setting starts as `BRP_MCP_STRICT_FORMATS` and is changed with
+//! `brp_set_strict_formats`; any tool call can override it with `strict_formats`.
+
+use std::future::Future;
+use std::sync::LazyLock;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+
+use serde_json::Map;
+use serde_json::Value;
+use serde_json::json;
+
+use super::ParameterName;
+
+/// Environment variable that turns strict formats on for the session (`1`, `true`, `yes`, `on`)
+pub const STRICT_FORMATS_ENV_VAR: &str = "BRP_MCP_STRICT_FORMATS";
+
+/// Strict formats setting for calls that don't pass `strict_formats`
+static SESSION_STRICT_FORMATS: LazyLock<AtomicBool> =
+    LazyLock::new(|| AtomicBool::new(configured_strict_formats()));
+
+tokio::task_local! {
+    /// `strict_formats` of the tool call running on this task
+    static CALL_STRICT_FORMATS: bool;
+}
+
+/// The startup setting - whether `BRP_MCP_STRICT_FORMATS` is set to a true value
+pub fn configured_strict_formats() -> bool {
+    std::env::var(STRICT_FORMATS_ENV_VAR).is_ok_and(|value| {
+        matches!(
+            value.trim().to_ascii_lowercase().as_str(),
+            "1" | "true" | "yes" | "on"
+        )
+    })
+}
+
+/// Change the session setting, returning the previous one
+pub fn set_session_strict_formats(strict: bool) -> bool {
+    SESSION_STRICT_FORMATS.swap(strict, Ordering::Relaxed)
+}
+
+/// Whether the running tool call - or, outside one, the session - uses strict formats
+pub fn strict_formats() -> bool {
+    CALL_STRICT_FORMATS
+        .try_with(|strict| *strict)
+        .unwrap_or_else(|_| SESSION_STRICT_FORMATS.load(Ordering::Relaxed))
+}
+
+/// Remove `strict_formats` from the request arguments so tools never see it, returning the
+/// call's setting - `None` when it didn't pass one
+///
+/// Anything but a boolean (or `"true"`/`"false"`) is rejected with the error details, rather
+/// than silently falling back to the session setting.
+pub fn take_strict_formats(
+    arguments: Option<&mut Map<String, Value>>,
+) -> Result<Option<bool>, Value> {
+    let requested = arguments.and_then(|args| args.remove(ParameterName::StrictFormats.as_ref()));
+    let Some(requested) = requested.filter(|value| !value.is_null()) else {
+        return Ok(None);
+    };
+    // Read before any coercion runs, so accept "true" and "false" too
+    requested
+        .as_bool()
+        .or_else(|| requested.as_str().and_then(|s| s.trim().parse().ok()))
+        .map(Some)
+        .ok_or_else(|| {
+            json!({
+                "invalid_parameter": ParameterName::StrictFormats.to_string(),
+                "value": requested,
+                "reason": "strict_formats must be true or false",
+            })
+        })
+}
+
+/// Coerce string arguments to the numbers and booleans `input_schema` declares - unless the
+/// running call uses strict formats, when the arguments are returned exactly as sent
+pub fn coerce_unless_strict(arguments: Value, input_schema: &Map<String, Value>) -> Value {
+    if strict_formats() {
+        arguments
+    } else {
+        crate::json_object::coerce_string_values_by_schema(arguments, input_schema)
+    }
+}
+
+/// Run a tool call with strict formats set to `strict`, or to the session setting when `None`
+pub async fn with_strict_formats<F: Future>(strict: Option<bool>, future: F) -> F::Output {
+    let strict = strict.unwrap_or_else(|| SESSION_STRICT_FORMATS.load(Ordering::Relaxed));
+    CALL_STRICT_FORMATS.scope(strict, future).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn takes_the_per_call_setting_out_of_the_arguments() {
+        let Value::Object(mut args) =
+            json!({ "entity": 42, "strict_formats": "true", "port": 15702 })
+        else {
+            unreachable!("literal is an object");
+        };
+        assert_eq!(take_strict_formats(Some(&mut args)), Ok(Some(true)));
+        assert!(!args.contains_key("strict_formats"));
+        assert_eq!(take_strict_formats(Some(&mut args)), Ok(None));
+
+        args.insert("strict_formats".to_string(), json!(false));
+        assert_eq!(take_strict_formats(Some(&mut args)), Ok(Some(false)));
+        assert_eq!(take_strict_formats(None), Ok(None));
+    }
+
+    #[test]
+    fn rejects_settings_that_are_not_booleans() {
+        for value in [json!(1), json!("yes"), json!({ "on": true })] {
+            let Value::Object(mut args) = json!({ "strict_formats": value.clone() }) else {
+                unreachable!("literal is an object");
+            };
+            let details = take_strict_formats(Some(&mut args))
+                .err()
+                .unwrap_or_else(|| unreachable!("{value} should be rejected"));
+            assert_eq!(details["invalid_parameter"], "strict_formats");
+            assert_eq!(details["value"], value);
+        }
+    }
+
+    #[tokio::test]
+    async fn strict_formats_skip_coercion() {
+        let Value::Object(schema) = json!({
+            "properties": { "entity": { "type": "integer" }, "name": { "type": "string" } }
+        }) else {
+            unreachable!("literal is an object");
+        };
+        let args = json!({ "entity": "42", "name": "7" });
+
+        let strict = with_strict_formats(Some(true), async {
+            coerce_unless_strict(args.clone(), &schema)
+        })
+        .await;
+        assert_eq!(strict, args);
+
+        let lenient = with_strict_formats(Some(false), async {
+            coerce_unless_strict(args.clone(), &schema)
+        })
+        .await;
+        assert_eq!(lenient, json!({ "entity": 42, "name": "7" }));
+    }
+}
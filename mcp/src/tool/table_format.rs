@@ -0,0 +1,236 @@
+//! `format: "table"` output mode for the query and list tools
+//!
+//! Raw JSON is what agents want, but a person following an agent's session needs to skim what it
+//! found. With `format: "table"`, `world_query`, `world_list_components` and
+//! `world_list_resources` also render their result as an aligned plain-text table in the response
+//! message. The structured result is returned unchanged alongside it.
+
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+
+use super::BrpMethod;
+use super::ParameterName;
+use super::ToolName;
+use crate::brp_tools::BrpTypeName;
+
+/// Rows rendered at most - the rest are counted below the table
+const MAX_ROWS: usize = 200;
+
+/// Characters of a cell kept before it is cut with `…`
+const MAX_CELL_CHARS: usize = 40;
+
+/// Full type path of Bevy's `Name` component, shown in its own column
+const NAME_COMPONENT: &str = "bevy_ecs::name::Name";
+
+/// How a tool's result is presented in the response message
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputFormat {
+    /// The usual one-line summary
+    #[default]
+    Json,
+    /// The summary followed by the result as an aligned plain-text table
+    Table,
+}
+
+/// Remove `format` from a query or list request - BRP doesn't know it
+///
+/// Returns `None` when the request has no `format` field, so it can be sent unchanged.
+pub fn without_format_field(method: BrpMethod, params: Option<&Value>) -> Option<Value> {
+    if !matches!(
+        method,
+        BrpMethod::WorldQuery | BrpMethod::WorldListComponents | BrpMethod::WorldListResources
+    ) {
+        return None;
+    }
+    let mut params = params.and_then(Value::as_object).cloned()?;
+    params.remove(ParameterName::Format.as_ref())?;
+    Some(Value::Object(params))
+}
+
+/// Render `result` of `tool_name` as a table - `None` for tools and results without a table form
+pub fn render_table(tool_name: ToolName, result: &Value) -> Option<String> {
+    let rows = result.as_array()?;
+    match tool_name {
+        ToolName::WorldQuery => Some(query_table(rows)),
+        ToolName::WorldListComponents | ToolName::WorldListResources => Some(type_list_table(rows)),
+        _ => None,
+    }
+}
+
+/// One row per entity: its ID, its `Name`, then a column per component and `has` check
+fn query_table(entities: &[Value]) -> String {
+    let mut columns: Vec<String> = Vec::new();
+    for entity in entities {
+        for section in ["components", "has"] {
+            let Some(fields) = entity.get(section).and_then(Value::as_object) else {
+                continue;
+            };
+            for type_path in fields.keys() {
+                if type_path != NAME_COMPONENT && !columns.contains(type_path) {
+                    columns.push(type_path.clone());
+                }
+            }
+        }
+    }
+
+    let mut header = vec!["entity".to_string(), "name".to_string()];
+    header.extend(
+        columns
+            .iter()
+            .map(|type_path| BrpTypeName::from(type_path.as_str()).short_name()),
+    );
+    let rows = entities
+        .iter()
+        .map(|entity| {
+            let cell = |type_path: &str| {
+                ["components", "has"]
+                    .iter()
+                    .find_map(|section| entity.get(section)?.get(type_path))
+                    .map(cell_text)
+                    .unwrap_or_default()
+            };
+            let mut row = vec![
+                entity.get("entity").map(cell_text).unwrap_or_default(),
+                cell(NAME_COMPONENT),
+            ];
+            row.extend(columns.iter().map(|type_path| cell(type_path)));
+            row
+        })
+        .collect();
+    render(&header, rows)
+}
+
+/// One row per type: its short name and full path, sorted by short name
+fn type_list_table(type_paths: &[Value]) -> String {
+    let mut rows: Vec<Vec<String>> = type_paths
+        .iter()
+        .filter_map(Value::as_str)
+        .map(|type_path| {
+            vec![
+                BrpTypeName::from(type_path).short_name(),
+                type_path.to_string(),
+            ]
+        })
+        .collect();
+    rows.sort();
+    render(&["type".to_string(), "type_path".to_string()], rows)
+}
+
+/// Compact text of a cell value - strings unquoted, everything else as JSON, cut to fit
+fn cell_text(value: &Value) -> String {
+    let text = match value {
+        Value::String(text) => text.clone(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    };
+    let text = text.replace(['\n', '\r', '\t'], " ");
+    if text.chars().count() > MAX_CELL_CHARS {
+        let kept: String = text.chars().take(MAX_CELL_CHARS - 1).collect();
+        format!("{kept}…")
+    } else {
+        text
+    }
+}
+
+/// Lay out `header` and `rows` as space-aligned columns under a dashed rule
+fn render(header: &[String], rows: Vec<Vec<String>>) -> String {
+    let row_count = rows.len();
+    let rows: Vec<Vec<String>> = rows.into_iter().take(MAX_ROWS).collect();
+    let widths: Vec<usize> = (0..header.len())
+        .map(|column| {
+            std::iter::once(&header[column])
+                .chain(rows.iter().filter_map(|row| row.get(column)))
+                .map(|cell| cell.chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    let line = |cells: &[String]| {
+        cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{cell:<width$}"))
+            .collect::<Vec<_>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
+    let rule: Vec<String> = widths.iter().map(|width| "-".repeat(*width)).collect();
+
+    let mut lines = vec![line(header), line(&rule)];
+    lines.extend(rows.iter().map(|row| line(row)));
+    if row_count > MAX_ROWS {
+        lines.push(format!("… {} more rows", row_count - MAX_ROWS));
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn query_table_aligns_entities_names_and_components() {
+        let result = json!([
+            {
+                "entity": 4_294_967_298_u64,
+                "components": {
+                    "bevy_ecs::name::Name": "Player",
+                    "my_game::Health": 100
+                }
+            },
+            {
+                "entity": 12,
+                "components": {"my_game::Health": 7},
+                "has": {"my_game::Boss": true}
+            }
+        ]);
+        let table = render_table(ToolName::WorldQuery, &result);
+        assert_eq!(
+            table.as_deref(),
+            Some(
+                "entity      name    Health  Boss\n\
+                 ----------  ------  ------  ----\n\
+                 4294967298  Player  100\n\
+                 12                  7       true"
+            )
+        );
+    }
+
+    #[test]
+    fn list_table_sorts_by_short_name() {
+        let result = json!([
+            "my_game::Health",
+            "bevy_transform::components::transform::Transform"
+        ]);
+        let table = render_table(ToolName::WorldListComponents, &result);
+        assert_eq!(
+            table.as_deref(),
+            Some(
+                "type       type_path\n\
+                 ---------  ------------------------------------------------\n\
+                 Health     my_game::Health\n\
+                 Transform  bevy_transform::components::transform::Transform"
+            )
+        );
+    }
+
+    #[test]
+    fn format_is_stripped_only_from_table_capable_methods() {
+        let params = json!({"data": {}, "format": "table"});
+        assert_eq!(
+            without_format_field(BrpMethod::WorldQuery, Some(&params)),
+            Some(json!({"data": {}}))
+        );
+        assert_eq!(
+            without_format_field(BrpMethod::WorldGetComponents, Some(&params)),
+            None
+        );
+    }
+}
@@ -0,0 +1,123 @@
+//! Per-call timeouts for tool handlers
+//!
+//! Every tool accepts an optional `timeout_ms` parameter that bounds how long its handler may
+//! run. Calls that omit it use the tool's default - generous for builds and whole-registry
+//! operations, shorter for everything else. `timeout_ms: 0` disables the timeout for one call.
+//! When the handler does not finish in time it is dropped and the call returns a structured
+//! "timed out" error instead of leaving the client waiting.
+
+use std::time::Duration;
+
+use serde_json::Map;
+use serde_json::Value;
+use serde_json::json;
+
+use super::ParameterName;
+use super::tool_name::ToolName;
+
+/// Default timeout for tools without a specific one
+pub const DEFAULT_TOOL_TIMEOUT: Duration = Duration::from_mins(1);
+
+/// Default timeout for tools that build the app or walk the whole type registry
+pub const LONG_TOOL_TIMEOUT: Duration = Duration::from_mins(10);
+
+/// Largest `timeout_ms` a caller may request (one hour)
+pub const MAX_TIMEOUT_MS: u64 = 3_600_000;
+
+/// Remove `timeout_ms` from the request arguments and resolve the timeout for the call so the
+/// field is never forwarded to BRP.
+///
+/// Returns `Ok(None)` when the call should run without a timeout, and `Err` with the details for
+/// an "invalid timeout" response when `timeout_ms` is not an integer in `0..=MAX_TIMEOUT_MS`.
+pub fn take_timeout(
+    tool_name: ToolName,
+    arguments: Option<&mut Map<String, Value>>,
+) -> Result<Option<Duration>, Value> {
+    let requested = arguments.and_then(|args| args.remove(ParameterName::TimeoutMs.as_ref()));
+    let Some(requested) = requested.filter(|value| !value.is_null()) else {
+        return Ok(tool_name.default_timeout());
+    };
+
+    // Accept numeric strings too - some MCP clients serialize numbers as strings
+    let timeout_ms = requested
+        .as_u64()
+        .or_else(|| requested.as_str().and_then(|s| s.trim().parse().ok()))
+        .filter(|ms| *ms <= MAX_TIMEOUT_MS)
+        .ok_or_else(|| {
+            json!({
+                "invalid_parameter": ParameterName::TimeoutMs.to_string(),
+                "value": requested,
+                "reason": format!(
+                    "timeout_ms must be an integer between 0 and {MAX_TIMEOUT_MS} (0 disables the timeout)"
+                ),
+            })
+        })?;
+
+    Ok((timeout_ms > 0).then(|| Duration::from_millis(timeout_ms)))
+}
+
+/// Details for the error returned when a handler exceeds its timeout
+pub fn timeout_details(tool_name: ToolName, timeout: Duration) -> Value {
+    json!({
+        "timed_out": true,
+        "tool": tool_name.to_string(),
+        "timeout_ms": u64::try_from(timeout.as_millis()).unwrap_or(u64::MAX),
+        "hint": "Re-run with a larger `timeout_ms`, or `timeout_ms: 0` to wait without a limit",
+    })
+}
+
+#[cfg(test)]
+#[allow(clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    fn take(value: Option<Value>) -> Result<Option<Duration>, Value> {
+        let mut arguments = Map::new();
+        if let Some(value) = value {
+            arguments.insert(ParameterName::TimeoutMs.to_string(), value);
+        }
+        let timeout = take_timeout(ToolName::WorldQuery, Some(&mut arguments));
+        assert!(!arguments.contains_key(ParameterName::TimeoutMs.as_ref()));
+        timeout
+    }
+
+    #[test]
+    fn missing_or_null_value_uses_the_tool_default() {
+        assert_eq!(take(None), Ok(Some(DEFAULT_TOOL_TIMEOUT)));
+        assert_eq!(take(Some(Value::Null)), Ok(Some(DEFAULT_TOOL_TIMEOUT)));
+        assert_eq!(
+            take_timeout(ToolName::BrpLaunchBevyApp, None),
+            Ok(Some(LONG_TOOL_TIMEOUT))
+        );
+        assert_eq!(take_timeout(ToolName::BrpNewBevyExample, None), Ok(None));
+    }
+
+    #[test]
+    fn zero_disables_the_timeout() {
+        assert_eq!(take(Some(json!(0))), Ok(None));
+        assert_eq!(take(Some(json!("0"))), Ok(None));
+    }
+
+    #[test]
+    fn integers_and_numeric_strings_set_the_timeout() {
+        assert_eq!(take(Some(json!(250))), Ok(Some(Duration::from_millis(250))));
+        assert_eq!(
+            take(Some(json!(" 1500 "))),
+            Ok(Some(Duration::from_millis(1500)))
+        );
+    }
+
+    #[test]
+    fn rejects_values_that_are_not_an_integer_in_range() {
+        for value in [
+            json!(1.5),
+            json!(-1),
+            json!("soon"),
+            json!(true),
+            json!(MAX_TIMEOUT_MS + 1),
+        ] {
+            let details = take(Some(value.clone())).expect_err("invalid timeout_ms");
+            assert_eq!(details["value"], value);
+        }
+    }
+}
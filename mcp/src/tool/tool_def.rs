@@ -1,19 +1,33 @@
 //! Unified tool definition that can handle both BRP and Local tools
 
+use std::borrow::Cow;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Instant;
 
 use rmcp::ErrorData;
 use rmcp::model::CallToolRequestParam;
 use rmcp::model::CallToolResult;
-use schemars::generate::SchemaSettings;
 
 use super::HandlerContext;
+use super::ParameterName;
+use super::ProgressReporter;
 use super::annotations::Annotation;
-use super::json_response::ToolCallJsonResponse;
+use super::annotations::EnvironmentImpact;
+use super::canonical;
+use super::confirmation;
+use super::large_response;
 use super::parameters::ParameterBuilder;
+use super::response_builder::Response;
+use super::strict_formats;
+use super::timeout;
 use super::tool_name::ToolName;
 use super::types::ErasedToolFn;
+use super::with_strict_formats;
+use crate::brp_tools;
+use crate::mcp_service::RootsSupport;
+use crate::session_tools::control_lock;
+use crate::session_tools::recorder;
 
 /// Unified tool definition that can handle both BRP and Local tools
 #[derive(Clone)]
@@ -35,40 +49,252 @@ impl ToolDef {
         &self,
         request: CallToolRequestParam,
         roots: Vec<PathBuf>,
+        roots_support: RootsSupport,
+        progress: Option<ProgressReporter>,
     ) -> std::result::Result<CallToolResult, ErrorData> {
+        // Record the call as the client made it, before any arguments are stripped
+        let arguments = request.arguments.clone();
+        let started_at = chrono::Local::now();
+        let started = Instant::now();
+
+        let result = self.run_tool(request, roots, roots_support, progress).await;
+
+        if let Ok(response) = &result {
+            recorder::record_call(
+                self.tool_name,
+                arguments.as_ref(),
+                response,
+                started_at,
+                started.elapsed(),
+            );
+        }
+        result
+    }
+
+    async fn run_tool(
+        &self,
+        mut request: CallToolRequestParam,
+        roots: Vec<PathBuf>,
+        roots_support: RootsSupport,
+        progress: Option<ProgressReporter>,
+    ) -> std::result::Result<CallToolResult, ErrorData> {
+        // Mutating tools may not touch a port another owner holds an enforced control lock on -
+        // checked first so a refused call doesn't use up a confirmation token
+        if let Err(details) = control_lock::check_control(
+            self.tool_name,
+            self.mutates(),
+            || self.accepts(ParameterName::Port),
+            request.arguments.as_mut(),
+        ) {
+            return Ok(Response::error_with_details(
+                format!(
+                    "`{}` refused: its port is locked by another owner",
+                    self.tool_name
+                ),
+                Some(&details),
+                self.tool_name.get_call_info(),
+            )
+            .to_call_tool_result());
+        }
+
+        // Destructive tools may need an explicit confirmation before they run
+        if let Err(details) =
+            confirmation::check_confirmation(self.tool_name, request.arguments.as_mut())
+        {
+            return Ok(Response::error_with_details(
+                format!("Confirmation required before running `{}`", self.tool_name),
+                Some(&details),
+                self.tool_name.get_call_info(),
+            )
+            .to_call_tool_result());
+        }
+
+        let timeout = match timeout::take_timeout(self.tool_name, request.arguments.as_mut()) {
+            Ok(timeout) => timeout,
+            Err(details) => {
+                return Ok(Response::error_with_details(
+                    format!("Invalid `timeout_ms` for `{}`", self.tool_name),
+                    Some(&details),
+                    self.tool_name.get_call_info(),
+                )
+                .to_call_tool_result());
+            },
+        };
+
+        let canonical = canonical::take_canonical(request.arguments.as_mut());
+
+        let strict_formats = match strict_formats::take_strict_formats(request.arguments.as_mut()) {
+            Ok(strict_formats) => strict_formats,
+            Err(details) => {
+                return Ok(Response::error_with_details(
+                    format!("Invalid `strict_formats` for `{}`", self.tool_name),
+                    Some(&details),
+                    self.tool_name.get_call_info(),
+                )
+                .to_call_tool_result());
+            },
+        };
+
+        let max_response_tokens =
+            match large_response::take_max_response_tokens(request.arguments.as_mut()) {
+                Ok(max_response_tokens) => max_response_tokens,
+                Err(details) => {
+                    return Ok(Response::error_with_details(
+                        format!("Invalid `max_response_tokens` for `{}`", self.tool_name),
+                        Some(&details),
+                        self.tool_name.get_call_info(),
+                    )
+                    .to_call_tool_result());
+                },
+            };
+
         // Create HandlerContext - all tools use the same context
-        let ctx = HandlerContext::new(self.clone(), request, roots);
+        let ctx = HandlerContext::new(
+            self.clone(),
+            request,
+            roots,
+            roots_support,
+            progress,
+            timeout,
+            canonical,
+            max_response_tokens,
+        );
 
         // Tools now always return CallToolResult - errors are already formatted as responses
-        Ok(self.handler.call_erased(ctx).await)
+        let call = with_strict_formats(strict_formats, self.handler.call_erased(ctx));
+        let Some(timeout) = timeout else {
+            return Ok(call.await);
+        };
+        Ok(tokio::time::timeout(timeout, call)
+            .await
+            .unwrap_or_else(|_| {
+                Response::error_with_details(
+                    format!(
+                        "`{}` timed out after {} ms",
+                        self.tool_name,
+                        timeout.as_millis()
+                    ),
+                    Some(&timeout::timeout_details(self.tool_name, timeout)),
+                    self.tool_name.get_call_info(),
+                )
+                .to_call_tool_result()
+            }))
     }
 
-    /// Generate unified output schema from the actual `ToolCallJsonResponse` struct
-    fn generate_output_schema() -> Arc<rmcp::model::JsonObject> {
-        let mut settings = SchemaSettings::default();
-        settings.inline_subschemas = true;
-        let generator = settings.into_generator();
-        let schema = generator.into_root_schema_for::<ToolCallJsonResponse>();
+    /// Whether the tool changes state, which an enforced control lock guards against
+    pub fn mutates(&self) -> bool {
+        self.annotations.environment_impact != EnvironmentImpact::ReadOnly
+    }
 
-        let Ok(schema_value) = serde_json::to_value(schema) else {
-            // Fallback to empty schema if serialization fails
-            return Arc::new(rmcp::model::JsonObject::new());
-        };
+    /// Whether the tool takes `parameter` - a tool taking a `port` is subject to the control lock
+    /// on that port
+    fn accepts(&self, parameter: ParameterName) -> bool {
+        self.parameters.is_some_and(|builder_fn| {
+            builder_fn()
+                .build()
+                .get("properties")
+                .and_then(serde_json::Value::as_object)
+                .is_some_and(|properties| properties.contains_key(parameter.as_ref()))
+        })
+    }
 
-        let schema_object = schema_value
-            .as_object()
-            .map_or_else(rmcp::model::JsonObject::new, Clone::clone);
+    /// The tool's help text followed by the notes it shares with other tools - `gated` adds the
+    /// confirmation note
+    fn description(&self, gated: bool) -> Cow<'static, str> {
+        let accepts_colors = self
+            .tool_name
+            .to_brp_method()
+            .is_some_and(brp_tools::accepts_color_shorthand);
+        let notes: Vec<&str> = [
+            (accepts_colors, brp_tools::COLOR_SHORTHAND_NOTE),
+            (
+                self.accepts(ParameterName::Correctors),
+                brp_tools::FORMAT_CORRECTION_NOTE,
+            ),
+            (gated, confirmation::CONFIRMATION_NOTE),
+        ]
+        .into_iter()
+        .filter_map(|(applies, note)| applies.then_some(note.trim_end()))
+        .collect();
 
-        Arc::new(schema_object)
+        let description = self.tool_name.description();
+        if notes.is_empty() {
+            return Cow::Borrowed(description);
+        }
+        Cow::Owned(format!(
+            "{}\n\n{}",
+            description.trim_end(),
+            notes.join("\n")
+        ))
     }
 
     /// Convert to MCP Tool for registration
     pub fn to_tool(&self) -> rmcp::model::Tool {
         // Build parameters using the provided builder function, or create empty builder
-        let builder = self
+        let mut builder = self
             .parameters
             .map_or_else(ParameterBuilder::new, |builder_fn| builder_fn());
 
+        // Gated tools advertise the confirmation parameters alongside their own
+        let gated =
+            confirmation::is_confirmation_enabled() && self.tool_name.requires_confirmation();
+        if gated {
+            builder = builder
+                .add_boolean_property(
+                    ParameterName::Confirm.as_ref(),
+                    "Set to true to confirm this destructive operation",
+                    false,
+                )
+                .add_string_property(
+                    ParameterName::ConfirmationToken.as_ref(),
+                    "Single-use token from brp_request_confirmation (alternative to confirm)",
+                    false,
+                );
+        }
+
+        // Mutating tools can name the owner of an enforced control lock on their port
+        if self.mutates() {
+            builder = builder.add_string_property(
+                ParameterName::ControlOwner.as_ref(),
+                "Owner of the control lock on the target port (see brp_acquire_control); \
+                 required when the lock is enforced",
+                false,
+            );
+        }
+
+        // Every tool accepts a per-call timeout for its handler
+        builder = builder.add_integer_property(
+            ParameterName::TimeoutMs.as_ref(),
+            "Maximum time in milliseconds the call may run before returning a timeout error \
+             (0 disables the timeout; defaults depend on the tool)",
+            false,
+        );
+
+        // ... and can ask for a response that diffs cleanly across runs
+        builder = builder.add_boolean_property(
+            ParameterName::Canonical.as_ref(),
+            "Sort object keys, round floats and drop volatile metadata (timestamps, durations, \
+             cache details) so outputs can be diffed across runs (default: false)",
+            false,
+        );
+
+        // ... and can turn off coercion and format correction of what it sends
+        builder = builder.add_boolean_property(
+            ParameterName::StrictFormats.as_ref(),
+            "Send arguments and BRP payloads exactly as given: no string-to-number coercion and \
+             no format correction, so format errors come back unpatched (default: the session \
+             setting, see brp_set_strict_formats)",
+            false,
+        );
+
+        // ... and can tighten or lift the token budget its response is held to
+        builder = builder.add_integer_property(
+            ParameterName::MaxResponseTokens.as_ref(),
+            "Estimated tokens the response may use before its result is saved to a file and \
+             replaced by a preview (0 returns the full response; default: the server's budget)",
+            false,
+        );
+
         // Enhance title with category prefix and optional method name
         let enhanced_annotations = {
             let mut enhanced = self.annotations.clone();
@@ -87,12 +313,45 @@ impl ToolDef {
         rmcp::model::Tool {
             name:          <&'static str>::from(self.tool_name).into(),
             title:         Some(self.tool_name.short_title()),
-            description:   Some(self.tool_name.description().into()),
+            description:   Some(self.description(gated)),
             input_schema:  builder.build(),
-            output_schema: Some(Self::generate_output_schema()),
+            output_schema: Some(self.handler.output_schema()),
             annotations:   Some(enhanced_annotations.into()),
             icons:         None,
             meta:          None,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shared_notes_are_appended_to_the_tools_they_apply_to() {
+        let spawn = ToolName::WorldSpawnEntity.to_tool_def();
+        let description = spawn.description(false);
+        assert!(description.contains(brp_tools::COLOR_SHORTHAND_NOTE.trim_end()));
+        assert!(description.ends_with(brp_tools::FORMAT_CORRECTION_NOTE.trim_end()));
+        assert_eq!(description.matches("Colors:").count(), 1);
+        assert_eq!(description.matches("Format correction:").count(), 1);
+        assert!(
+            spawn
+                .description(true)
+                .ends_with(confirmation::CONFIRMATION_NOTE.trim_end())
+        );
+
+        let query = ToolName::WorldQuery.to_tool_def();
+        assert!(
+            query
+                .description(false)
+                .ends_with(brp_tools::FORMAT_CORRECTION_NOTE.trim_end())
+        );
+
+        let list = ToolName::WorldListComponents.to_tool_def();
+        assert_eq!(
+            list.description(false),
+            ToolName::WorldListComponents.description()
+        );
+    }
+}
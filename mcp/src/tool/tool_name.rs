@@ -4,6 +4,7 @@
 //! It provides a single source of truth for all tool-related constants.
 
 use std::sync::Arc;
+use std::time::Duration;
 
 use bevy_brp_mcp_macros::BrpTools;
 use bevy_brp_mcp_macros::ToolDescription;
@@ -18,34 +19,80 @@ use strum::IntoStaticStr;
 
 use super::ToolDef;
 use super::annotations::Annotation;
+use super::annotations::DomainOfInteraction;
 use super::annotations::EnvironmentImpact;
 use super::annotations::ToolCategory;
 use super::parameters;
+use super::timeout::DEFAULT_TOOL_TIMEOUT;
+use super::timeout::LONG_TOOL_TIMEOUT;
 use super::types::ErasedToolFn;
+use crate::app_tools::BootstrapSession;
+use crate::app_tools::BootstrapSessionParams;
+use crate::app_tools::Diagnose;
+use crate::app_tools::DiagnoseParams;
 use crate::app_tools::LaunchBevyBinaryParams;
+use crate::app_tools::LaunchGroup;
+use crate::app_tools::LaunchGroupParams;
 use crate::app_tools::ListBevyApps;
 use crate::app_tools::ListBevyExamples;
 use crate::app_tools::ListBrpApps;
+use crate::app_tools::ListInstances;
+use crate::app_tools::ListTargetsParams;
+use crate::app_tools::NetworkProxyConfigure;
+use crate::app_tools::NetworkProxyConfigureParams;
+use crate::app_tools::NetworkProxyStart;
+use crate::app_tools::NetworkProxyStartParams;
+use crate::app_tools::NetworkProxyStop;
+use crate::app_tools::NetworkProxyStopParams;
+use crate::app_tools::NewBevyExample;
+use crate::app_tools::NewBevyExampleParams;
+use crate::app_tools::SelfTest;
+use crate::app_tools::SetupCheck;
+use crate::app_tools::SetupCheckParams;
 use crate::app_tools::Shutdown;
 use crate::app_tools::ShutdownParams;
 use crate::app_tools::Status;
 use crate::app_tools::StatusParams;
+use crate::app_tools::WatchAndRelaunch;
+use crate::app_tools::WatchAndRelaunchParams;
 use crate::app_tools::{self};
 // Import special tools that aren't generated by the macro
 // Import parameter and result types so they're in scope for the macro
 use crate::brp_tools::{
-    AllTypeGuidesParams, BevyListWatch, BrpAllTypeGuides, BrpExecute, BrpListActiveWatches,
-    BrpStopWatch, BrpTypeGuide, DespawnEntityParams, DespawnEntityResult, ExecuteParams,
-    GetComponentsParams, GetComponentsResult, GetComponentsWatchParams, GetResourcesParams,
-    GetResourcesResult, InsertComponentsParams, InsertComponentsResult, InsertResourcesParams,
-    InsertResourcesResult, ListComponentsParams, ListComponentsResult, ListComponentsWatchParams,
-    ListResourcesParams, ListResourcesResult, MutateComponentsParams, MutateComponentsResult,
-    MutateResourcesParams, MutateResourcesResult, QueryParams, QueryResult, RegistrySchemaParams,
-    RegistrySchemaResult, RemoveComponentsParams, RemoveComponentsResult, RemoveResourcesParams,
-    RemoveResourcesResult, ReparentEntitiesParams, ReparentEntitiesResult, RpcDiscoverParams,
-    RpcDiscoverResult, ScreenshotParams, ScreenshotResult, SendKeysParams, SendKeysResult,
+    AliasEntityParams, AllTypeGuidesParams, AppInfoParams, AppInfoResult, ApplyCommandsParams,
+    AssertParams, BevyListWatch, BrpAliasEntity, BrpAllTypeGuides, BrpAssert,
+    BrpClearTypeGuideCache, BrpExecute, BrpExecuteWatch, BrpExtrasApplyCommands,
+    BrpExtrasCollectFrameStats, BrpExtrasQueryStream, BrpExtrasReadPixels, BrpExtrasScreenshot,
+    BrpExtrasTrackArchetypeMoves, BrpFuzzComponent, BrpGetWatchBuffer, BrpListActiveWatches,
+    BrpListCorrections, BrpMapEntities, BrpRefreshTypeCache, BrpRequestConfirmation,
+    BrpResetCircuit, BrpSetDefaultPort, BrpSetStrictFormats, BrpStopWatch, BrpTypeGuide,
+    BrpWaitFor, BrpWaitForIdle, CapabilitiesParams, CapabilitiesResult, ChangesSinceParams,
+    ClearTypeGuideCacheParams, ClipboardParams, ClipboardResult, CollectFrameStatsParams,
+    ComponentMatrixParams, DespawnEntityParams, DeterminismParams, DeterminismResult,
+    DumpResourcesParams, EnsureRegisteredParams, EnsureRegisteredResult, ExecuteParams,
+    ExecuteWatchParams, ExportGraphParams, FieldHistogramParams, FindTaggedParams,
+    FindTaggedResult, FuzzComponentParams, GetComponentsParams, GetComponentsResult,
+    GetComponentsWatchParams, GetHistoryParams, GetHistoryResult, GetInteractionStateParams,
+    GetInteractionStateResult, GetResourcesParams, GetResourcesResult, GetUiTextParams,
+    GetUiTextResult, GetWatchBufferParams, GizmoConfigParams, GizmoConfigResult, GrabSelection,
+    GrabSelectionParams, InsertComponentsParams, InsertComponentsResult, InsertResourcesParams,
+    InsertResourcesResult, JoinQueryParams, ListComponentsParams, ListComponentsResult,
+    ListComponentsWatchParams, ListCorrectionsParams, ListMonitorsParams, ListMonitorsResult,
+    ListResourcesParams, ListResourcesResult, MapEntitiesParams, MaterialParams, MaterialResult,
+    MutateComponentsParams, MutateComponentsResult, MutateResourcesParams, MutateResourcesResult,
+    PinEntityParams, PinEntityResult, QueryParams, QueryResult, QueryStreamParams,
+    ReadPixelsParams, RefreshTypeCacheParams, RegistrySchema, RegistrySchemaParams,
+    RemoveComponentsParams, RemoveComponentsResult, RemoveResourcesParams, RemoveResourcesResult,
+    ReparentEntitiesParams, ReparentEntitiesResult, RequestConfirmationParams, ResetCircuitParams,
+    RpcDiscoverParams, RpcDiscoverResult, ScheduleReportParams, ScheduleReportResult,
+    ScreenshotParams, ScreenshotStatusParams, ScreenshotStatusResult, SendKeysParams,
+    SendKeysResult, SetDefaultPortParams, SetStrictFormatsParams, SetWindowParams, SetWindowResult,
     SetWindowTitleParams, SetWindowTitleResult, SpawnEntityParams, SpawnEntityResult,
-    StopWatchParams, TypeGuideParams, WorldGetComponentsWatch, GrabSelection, GrabSelectionParams,
+    StopWatchParams, TagEntityParams, TagEntityResult, TimeConfigParams, TimeConfigResult,
+    TrackArchetypeMovesParams, TrackEntityParams, TrackEntityResult, TypeGuideParams,
+    UndoDespawnParams, WaitForIdleParams, WaitForParams, WorldChangesSince, WorldComponentMatrix,
+    WorldDespawnEntity, WorldDumpResources, WorldExportGraph, WorldFieldHistogram,
+    WorldGetComponentsWatch, WorldJoinQuery, WorldUndoDespawn,
 };
 use crate::log_tools::DeleteLogs;
 use crate::log_tools::DeleteLogsParams;
@@ -53,12 +100,30 @@ use crate::log_tools::DeleteLogsParams;
 use crate::log_tools::GetTraceLogPath;
 use crate::log_tools::ListLogs;
 use crate::log_tools::ListLogsParams;
+use crate::log_tools::ListScreenshots;
+use crate::log_tools::ListScreenshotsParams;
+use crate::log_tools::ManageLogs;
+use crate::log_tools::ManageLogsParams;
 use crate::log_tools::ReadLog;
 use crate::log_tools::ReadLogParams;
+use crate::log_tools::SearchLog;
+use crate::log_tools::SearchLogParams;
 #[cfg(feature = "mcp-debug")]
 use crate::log_tools::SetTracingLevel;
 #[cfg(feature = "mcp-debug")]
 use crate::log_tools::SetTracingLevelParams;
+use crate::session_tools::AcquireControl;
+use crate::session_tools::AcquireControlParams;
+use crate::session_tools::ExportSession;
+use crate::session_tools::ExportSessionParams;
+use crate::session_tools::ExportToolCatalog;
+use crate::session_tools::ExportToolCatalogParams;
+use crate::session_tools::ReleaseControl;
+use crate::session_tools::ReleaseControlParams;
+use crate::session_tools::ReplaySession;
+use crate::session_tools::ReplaySessionParams;
+use crate::session_tools::RunTestScript;
+use crate::session_tools::RunTestScriptParams;
 
 /// Call information for tracking tool execution
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
@@ -125,12 +190,10 @@ pub enum ToolName {
     )]
     WorldGetComponents,
     /// `world_despawn_entity` - Despawns entities permanently
-    #[brp_tool(
-        brp_method = "world.despawn_entity",
-        params = "DespawnEntityParams",
-        result = "DespawnEntityResult"
-    )]
+    #[brp_tool(brp_method = "world.despawn_entity")]
     WorldDespawnEntity,
+    /// `world_undo_despawn` - Respawn an entity archived by `world_despawn_entity`
+    WorldUndoDespawn,
     /// `world_insert_components` - Insert or replace components on entities
     #[brp_tool(
         brp_method = "world.insert_components",
@@ -159,6 +222,8 @@ pub enum ToolName {
         result = "GetResourcesResult"
     )]
     WorldGetResources,
+    /// `world_dump_resources` - Every registered resource with its value
+    WorldDumpResources,
     /// `world_insert_resources` - Insert or update resources
     #[brp_tool(
         brp_method = "world.insert_resources",
@@ -202,6 +267,16 @@ pub enum ToolName {
         result = "QueryResult"
     )]
     WorldQuery,
+    /// `world_changes_since` - Entities whose queried components changed since a checkpoint
+    WorldChangesSince,
+    /// `world_component_matrix` - Component presence per entity, without values
+    WorldComponentMatrix,
+    /// `world_join_query` - Two queries joined on entity ID
+    WorldJoinQuery,
+    /// `world_field_histogram` - Distribution of a numeric field across matching entities
+    WorldFieldHistogram,
+    /// `world_export_graph` - Entity hierarchy and relationships as DOT or Mermaid text
+    WorldExportGraph,
     /// `world_spawn_entity` - Spawn entities with components
     #[brp_tool(
         brp_method = "world.spawn_entity",
@@ -209,12 +284,8 @@ pub enum ToolName {
         result = "SpawnEntityResult"
     )]
     WorldSpawnEntity,
-    /// `registry_schema` - Get type schemas
-    #[brp_tool(
-        brp_method = "registry.schema",
-        params = "RegistrySchemaParams",
-        result = "RegistrySchemaResult"
-    )]
+    /// `registry_schema` - Get type schemas, optionally exported to a file
+    #[brp_tool(brp_method = "registry.schema")]
     RegistrySchema,
 
     /// `grab_selection` - Read latest grab/selection output for coding agents
@@ -240,12 +311,15 @@ pub enum ToolName {
 
     // BRP Extras Tools
     /// `brp_extras_screenshot` - Capture screenshots
+    #[brp_tool(brp_method = "brp_extras/screenshot")]
+    BrpExtrasScreenshot,
+    /// `brp_extras_screenshot_status` - Check whether a screenshot has been saved
     #[brp_tool(
-        brp_method = "brp_extras/screenshot",
-        params = "ScreenshotParams",
-        result = "ScreenshotResult"
+        brp_method = "brp_extras/screenshot_status",
+        params = "ScreenshotStatusParams",
+        result = "ScreenshotStatusResult"
     )]
-    BrpExtrasScreenshot,
+    BrpExtrasScreenshotStatus,
     /// `brp_extras_send_keys` - Send keyboard input
     #[brp_tool(
         brp_method = "brp_extras/send_keys",
@@ -260,12 +334,157 @@ pub enum ToolName {
         result = "SetWindowTitleResult"
     )]
     BrpExtrasSetWindowTitle,
+    /// `brp_extras_set_window` - Change window decorations, level, focus and minimized state
+    #[brp_tool(
+        brp_method = "brp_extras/set_window",
+        params = "SetWindowParams",
+        result = "SetWindowResult"
+    )]
+    BrpExtrasSetWindow,
+    /// `brp_extras_tag_entity` - Bookmark an entity with a tag stored in the app
+    #[brp_tool(
+        brp_method = "brp_extras/tag_entity",
+        params = "TagEntityParams",
+        result = "TagEntityResult"
+    )]
+    BrpExtrasTagEntity,
+    /// `brp_extras_find_tagged` - Find entities by tag
+    #[brp_tool(
+        brp_method = "brp_extras/find_tagged",
+        params = "FindTaggedParams",
+        result = "FindTaggedResult"
+    )]
+    BrpExtrasFindTagged,
+    /// `brp_extras_list_monitors` - List connected displays
+    #[brp_tool(
+        brp_method = "brp_extras/list_monitors",
+        params = "ListMonitorsParams",
+        result = "ListMonitorsResult"
+    )]
+    BrpExtrasListMonitors,
+    /// `brp_extras_time_config` - Read or change fixed timestep and time configuration
+    #[brp_tool(
+        brp_method = "brp_extras/time_config",
+        params = "TimeConfigParams",
+        result = "TimeConfigResult"
+    )]
+    BrpExtrasTimeConfig,
+    /// `brp_extras_gizmo_config` - Read or change gizmo configuration
+    #[brp_tool(
+        brp_method = "brp_extras/gizmo_config",
+        params = "GizmoConfigParams",
+        result = "GizmoConfigResult"
+    )]
+    BrpExtrasGizmoConfig,
+    /// `brp_extras_material` - Read or change an entity's material
+    #[brp_tool(
+        brp_method = "brp_extras/material",
+        params = "MaterialParams",
+        result = "MaterialResult"
+    )]
+    BrpExtrasMaterial,
+    /// `brp_extras_collect_frame_stats` - Frame-time histogram over a time window
+    #[brp_tool(brp_method = "brp_extras/collect_frame_stats")]
+    BrpExtrasCollectFrameStats,
+    /// `brp_extras_track_archetype_moves` - Entities that change archetype most often
+    #[brp_tool(brp_method = "brp_extras/track_archetype_moves")]
+    BrpExtrasTrackArchetypeMoves,
+    /// `brp_extras_query_stream` - Query a huge world in chunks, inline or to a file
+    #[brp_tool(brp_method = "brp_extras/query_stream+watch")]
+    BrpExtrasQueryStream,
+    /// `brp_extras_schedule_report` - Report system ambiguities and execution order
+    #[brp_tool(
+        brp_method = "brp_extras/schedule_report",
+        params = "ScheduleReportParams",
+        result = "ScheduleReportResult"
+    )]
+    BrpExtrasScheduleReport,
+    /// `brp_extras_determinism` - Set the RNG seed and fixed frame delta for reproducible runs
+    #[brp_tool(
+        brp_method = "brp_extras/determinism",
+        params = "DeterminismParams",
+        result = "DeterminismResult"
+    )]
+    BrpExtrasDeterminism,
+    /// `brp_extras_pin_entity` - Protect an entity from despawning while it is inspected
+    #[brp_tool(
+        brp_method = "brp_extras/pin_entity",
+        params = "PinEntityParams",
+        result = "PinEntityResult"
+    )]
+    BrpExtrasPinEntity,
+    /// `brp_extras_read_pixels` - Sample pixel colors of the rendered frame
+    #[brp_tool(brp_method = "brp_extras/read_pixels")]
+    BrpExtrasReadPixels,
+    /// `brp_extras_get_ui_text` - Collect UI text with screen rects and hierarchy paths
+    #[brp_tool(
+        brp_method = "brp_extras/get_ui_text",
+        params = "GetUiTextParams",
+        result = "GetUiTextResult"
+    )]
+    BrpExtrasGetUiText,
+    /// `brp_extras_get_interaction_state` - Report keyboard focus, UI interactions and hovers
+    #[brp_tool(
+        brp_method = "brp_extras/get_interaction_state",
+        params = "GetInteractionStateParams",
+        result = "GetInteractionStateResult"
+    )]
+    BrpExtrasGetInteractionState,
+    /// `brp_extras_clipboard` - Read and set the system clipboard
+    #[brp_tool(
+        brp_method = "brp_extras/clipboard",
+        params = "ClipboardParams",
+        result = "ClipboardResult"
+    )]
+    BrpExtrasClipboard,
+    /// `brp_extras_track_entity` - Record component changes of an entity
+    #[brp_tool(
+        brp_method = "brp_extras/track_entity",
+        params = "TrackEntityParams",
+        result = "TrackEntityResult"
+    )]
+    BrpExtrasTrackEntity,
+    /// `brp_extras_get_history` - Read recorded component changes of tracked entities
+    #[brp_tool(
+        brp_method = "brp_extras/get_history",
+        params = "GetHistoryParams",
+        result = "GetHistoryResult"
+    )]
+    BrpExtrasGetHistory,
+    /// `brp_extras_apply_commands` - Apply a batch of ECS commands atomically
+    #[brp_tool(brp_method = "brp_extras/apply_commands")]
+    BrpExtrasApplyCommands,
+    /// `brp_extras_app_info` - Report the app's plugins and build info
+    #[brp_tool(
+        brp_method = "brp_extras/app_info",
+        params = "AppInfoParams",
+        result = "AppInfoResult"
+    )]
+    BrpExtrasAppInfo,
+    /// `brp_extras_ensure_registered` - Report which types are missing reflection registration
+    #[brp_tool(
+        brp_method = "brp_extras/ensure_registered",
+        params = "EnsureRegisteredParams",
+        result = "EnsureRegisteredResult"
+    )]
+    BrpExtrasEnsureRegistered,
+    /// `brp_extras_capabilities` - Report the plugin sections and methods the app serves
+    #[brp_tool(
+        brp_method = "brp_extras/capabilities",
+        params = "CapabilitiesParams",
+        result = "CapabilitiesResult"
+    )]
+    BrpExtrasCapabilities,
 
     // BRP Watch Assist Tools
+    /// `brp_execute_watch` - Watch any streaming BRP method
+    BrpExecuteWatch,
     /// `brp_stop_watch` - Stop active watch subscriptions
     BrpStopWatch,
     /// `brp_list_active_watches` - List active watch subscriptions
     BrpListActiveWatches,
+    /// `brp_get_watch_buffer` - Read buffered updates of a watch
+    BrpGetWatchBuffer,
 
     // Application Management Tools
     /// `brp_list_bevy_apps` - List Bevy apps in workspace
@@ -274,23 +493,87 @@ pub enum ToolName {
     BrpListBevyExamples,
     /// `brp_list_brp_apps` - List BRP-enabled Bevy apps
     BrpListBrpApps,
+    /// `brp_list_instances` - List running instances launched by this server
+    BrpListInstances,
     /// `brp_launch_bevy_app` - Launch Bevy applications
     BrpLaunchBevyApp,
     /// `brp_launch_bevy_example` - Launch Bevy examples
     BrpLaunchBevyExample,
+    /// `brp_launch_group` - Launch several different Bevy targets together
+    BrpLaunchGroup,
+    /// `brp_bootstrap_session` - Launch a preset, wait for BRP, load a scene, seed and snapshot
+    BrpBootstrapSession,
+    /// `brp_new_bevy_example` - Scaffold and build a minimal Bevy app with `BrpExtrasPlugin`
+    BrpNewBevyExample,
+    /// `brp_watch_and_relaunch` - Rebuild and relaunch a Bevy app when its sources change
+    BrpWatchAndRelaunch,
+    /// `brp_network_proxy_start` - Start a proxy simulating latency, jitter and packet loss
+    BrpNetworkProxyStart,
+    /// `brp_network_proxy_configure` - Change a running network proxy's conditions
+    BrpNetworkProxyConfigure,
+    /// `brp_network_proxy_stop` - Stop network proxies and report their counts
+    BrpNetworkProxyStop,
     /// `brp_shutdown` - Shutdown running Bevy applications
     #[brp_tool(brp_method = "brp_extras/shutdown")]
     BrpShutdown,
     /// `brp_status` - Check if Bevy app is running with BRP
     BrpStatus,
+    /// `brp_diagnose` - Staged connection checklist for a BRP port
+    BrpDiagnose,
+    /// `brp_self_test` - Check the MCP server's own subsystems before the first workflow
+    BrpSelfTest,
+    /// `brp_setup_check` - Check a target's dependencies and sources for BRP plugins
+    BrpSetupCheck,
+    /// `brp_request_confirmation` - Get a token that unlocks a destructive tool
+    BrpRequestConfirmation,
+    /// `brp_set_default_port` - Set the port used when BRP tool calls omit `port`
+    BrpSetDefaultPort,
+    /// `brp_set_strict_formats` - Turn silent coercion and format correction off or on
+    BrpSetStrictFormats,
+    /// `brp_reset_circuit` - Close the circuit breaker for a port that failed repeatedly
+    BrpResetCircuit,
+    /// `brp_list_corrections` - List the format corrections applied this session
+    BrpListCorrections,
+    /// `brp_alias_entity` - Bind a readable name to an entity ID for this session
+    BrpAliasEntity,
+    /// `brp_assert` - Evaluate declarative assertions against the live world
+    BrpAssert,
+    /// `brp_wait_for` - Poll the world until a condition holds or a timeout elapses
+    BrpWaitFor,
+    /// `brp_wait_for_idle` - Wait until the app stops changing for a number of frames
+    #[brp_tool(brp_method = "brp_extras/activity")]
+    BrpWaitForIdle,
+    /// `brp_map_entities` - Match entity IDs from before an app restart to the new IDs
+    BrpMapEntities,
+    /// `brp_fuzz_component` - Generate random valid values for a component and optionally apply
+    /// them
+    BrpFuzzComponent,
 
     // Log Management Tools
     /// `brp_list_logs` - List `bevy_brp_mcp` log files
     BrpListLogs,
     /// `brp_read_log` - Read `bevy_brp_mcp` log file contents
     BrpReadLog,
+    /// `brp_search_log` - Regex search of a log file with context lines
+    BrpSearchLog,
     /// `brp_delete_logs` - Delete `bevy_brp_mcp` log files
     BrpDeleteLogs,
+    /// `brp_manage_logs` - Report log disk usage and apply retention rules
+    BrpManageLogs,
+    /// `brp_list_screenshots` - List screenshots in the managed screenshot directory
+    BrpListScreenshots,
+    /// `brp_export_session` - Write the tool calls made this session to a file
+    BrpExportSession,
+    /// `brp_export_tool_catalog` - Write every tool's schemas and annotations to a file
+    BrpExportToolCatalog,
+    /// `brp_replay_session` - Re-run the calls of an exported session transcript
+    BrpReplaySession,
+    /// `brp_run_test_script` - Run a YAML or JSON integration test script
+    BrpRunTestScript,
+    /// `brp_acquire_control` - Take a time-boxed control lock on a port
+    BrpAcquireControl,
+    /// `brp_release_control` - Release a control lock on a port
+    BrpReleaseControl,
     /// `brp_get_trace_log_path` - Get trace log path
     #[cfg(feature = "mcp-debug")]
     BrpGetTraceLogPath,
@@ -303,6 +586,10 @@ pub enum ToolName {
     BrpTypeGuide,
     /// `brp_all_type_guides` - Get type guides for all registered types
     BrpAllTypeGuides,
+    /// `brp_clear_type_guide_cache` - Invalidate cached type guides
+    BrpClearTypeGuideCache,
+    /// `brp_refresh_type_cache` - Rebuild the short type name index for an app
+    BrpRefreshTypeCache,
 }
 
 impl ToolName {
@@ -337,6 +624,11 @@ impl ToolName {
                 ToolCategory::Entity,
                 EnvironmentImpact::DestructiveIdempotent,
             ),
+            Self::WorldUndoDespawn => Annotation::new(
+                "Undo Entity Despawn",
+                ToolCategory::Entity,
+                EnvironmentImpact::AdditiveNonIdempotent,
+            ),
             Self::WorldGetComponents => Annotation::new(
                 "Get Component Data",
                 ToolCategory::Component,
@@ -347,6 +639,11 @@ impl ToolName {
                 ToolCategory::Resource,
                 EnvironmentImpact::ReadOnly,
             ),
+            Self::WorldDumpResources => Annotation::new(
+                "Dump All Resources",
+                ToolCategory::Resource,
+                EnvironmentImpact::ReadOnly,
+            ),
             Self::WorldInsertComponents => Annotation::new(
                 "Insert Components",
                 ToolCategory::Component,
@@ -382,6 +679,31 @@ impl ToolName {
                 ToolCategory::Component,
                 EnvironmentImpact::ReadOnly,
             ),
+            Self::WorldChangesSince => Annotation::new(
+                "Changes Since Checkpoint",
+                ToolCategory::Component,
+                EnvironmentImpact::ReadOnly,
+            ),
+            Self::WorldComponentMatrix => Annotation::new(
+                "Component Presence Matrix",
+                ToolCategory::Component,
+                EnvironmentImpact::ReadOnly,
+            ),
+            Self::WorldJoinQuery => Annotation::new(
+                "Join Queries",
+                ToolCategory::Component,
+                EnvironmentImpact::ReadOnly,
+            ),
+            Self::WorldFieldHistogram => Annotation::new(
+                "Field Histogram",
+                ToolCategory::Component,
+                EnvironmentImpact::ReadOnly,
+            ),
+            Self::WorldExportGraph => Annotation::new(
+                "Export Scene Graph",
+                ToolCategory::Entity,
+                EnvironmentImpact::ReadOnly,
+            ),
             Self::RegistrySchema => Annotation::new(
                 "Get Type Schemas from Registry",
                 ToolCategory::Discovery,
@@ -427,6 +749,11 @@ impl ToolName {
                 ToolCategory::Extras,
                 EnvironmentImpact::AdditiveNonIdempotent,
             ),
+            Self::BrpExtrasScreenshotStatus => Annotation::new(
+                "Screenshot Status",
+                ToolCategory::Extras,
+                EnvironmentImpact::ReadOnly,
+            ),
             Self::BrpExtrasSendKeys => Annotation::new(
                 "Send Keys",
                 ToolCategory::Extras,
@@ -437,6 +764,123 @@ impl ToolName {
                 ToolCategory::Extras,
                 EnvironmentImpact::AdditiveIdempotent,
             ),
+            Self::BrpExtrasSetWindow => Annotation::new(
+                "Set Window State",
+                ToolCategory::Extras,
+                EnvironmentImpact::AdditiveIdempotent,
+            ),
+            Self::BrpExtrasTagEntity => Annotation::new(
+                "Tag Entity",
+                ToolCategory::Extras,
+                EnvironmentImpact::AdditiveIdempotent,
+            ),
+            Self::BrpExtrasFindTagged => Annotation::new(
+                "Find Tagged Entities",
+                ToolCategory::Extras,
+                EnvironmentImpact::ReadOnly,
+            ),
+            Self::BrpExtrasListMonitors => Annotation::new(
+                "List Monitors",
+                ToolCategory::Extras,
+                EnvironmentImpact::ReadOnly,
+            ),
+            Self::BrpExtrasTimeConfig => Annotation::new(
+                "Time Configuration",
+                ToolCategory::Extras,
+                EnvironmentImpact::AdditiveIdempotent,
+            ),
+            Self::BrpExtrasGizmoConfig => Annotation::new(
+                "Gizmo Configuration",
+                ToolCategory::Extras,
+                EnvironmentImpact::AdditiveIdempotent,
+            ),
+            Self::BrpExtrasMaterial => Annotation::new(
+                "Material",
+                ToolCategory::Extras,
+                EnvironmentImpact::AdditiveIdempotent,
+            ),
+            Self::BrpExtrasCollectFrameStats => Annotation::new(
+                "Collect Frame Stats",
+                ToolCategory::Extras,
+                EnvironmentImpact::ReadOnly,
+            ),
+            Self::BrpExtrasTrackArchetypeMoves => Annotation::new(
+                "Track Archetype Moves",
+                ToolCategory::Extras,
+                EnvironmentImpact::ReadOnly,
+            ),
+            Self::BrpExtrasQueryStream => Annotation::new(
+                "Stream Query Results",
+                ToolCategory::Extras,
+                EnvironmentImpact::ReadOnly,
+            ),
+            Self::BrpExtrasScheduleReport => Annotation::new(
+                "Schedule Report",
+                ToolCategory::Extras,
+                EnvironmentImpact::ReadOnly,
+            ),
+            Self::BrpExtrasDeterminism => Annotation::new(
+                "Deterministic Run Mode",
+                ToolCategory::Extras,
+                EnvironmentImpact::AdditiveIdempotent,
+            ),
+            Self::BrpExtrasPinEntity => Annotation::new(
+                "Pin Entity",
+                ToolCategory::Extras,
+                EnvironmentImpact::AdditiveIdempotent,
+            ),
+            Self::BrpExtrasReadPixels => Annotation::new(
+                "Read Pixels",
+                ToolCategory::Extras,
+                EnvironmentImpact::ReadOnly,
+            ),
+            Self::BrpExtrasGetUiText => Annotation::new(
+                "Get UI Text",
+                ToolCategory::Extras,
+                EnvironmentImpact::ReadOnly,
+            ),
+            Self::BrpExtrasGetInteractionState => Annotation::new(
+                "Get Interaction State",
+                ToolCategory::Extras,
+                EnvironmentImpact::ReadOnly,
+            ),
+            // Setting the clipboard replaces what the user had copied
+            Self::BrpExtrasClipboard => Annotation::new(
+                "Clipboard",
+                ToolCategory::Extras,
+                EnvironmentImpact::DestructiveIdempotent,
+            )
+            .with_domain(DomainOfInteraction::OpenWorld),
+            Self::BrpExtrasTrackEntity => Annotation::new(
+                "Track Entity Changes",
+                ToolCategory::Extras,
+                EnvironmentImpact::AdditiveIdempotent,
+            ),
+            Self::BrpExtrasGetHistory => Annotation::new(
+                "Get Change History",
+                ToolCategory::Extras,
+                EnvironmentImpact::ReadOnly,
+            ),
+            Self::BrpExtrasApplyCommands => Annotation::new(
+                "Apply Commands",
+                ToolCategory::Extras,
+                EnvironmentImpact::DestructiveNonIdempotent,
+            ),
+            Self::BrpExtrasAppInfo => Annotation::new(
+                "App Info",
+                ToolCategory::Extras,
+                EnvironmentImpact::ReadOnly,
+            ),
+            Self::BrpExtrasEnsureRegistered => Annotation::new(
+                "Ensure Registered",
+                ToolCategory::Extras,
+                EnvironmentImpact::ReadOnly,
+            ),
+            Self::BrpExtrasCapabilities => Annotation::new(
+                "Extras Capabilities",
+                ToolCategory::Extras,
+                EnvironmentImpact::ReadOnly,
+            ),
             Self::WorldGetComponentsWatch => Annotation::new(
                 "Watch Component Changes",
                 ToolCategory::WatchMonitoring,
@@ -452,6 +896,46 @@ impl ToolName {
                 ToolCategory::Logging,
                 EnvironmentImpact::DestructiveIdempotent,
             ),
+            Self::BrpManageLogs => Annotation::new(
+                "Manage Log Retention",
+                ToolCategory::Logging,
+                EnvironmentImpact::DestructiveIdempotent,
+            ),
+            Self::BrpListScreenshots => Annotation::new(
+                "List Screenshots",
+                ToolCategory::Logging,
+                EnvironmentImpact::ReadOnly,
+            ),
+            Self::BrpExportSession => Annotation::new(
+                "Export Session Transcript",
+                ToolCategory::Logging,
+                EnvironmentImpact::AdditiveNonIdempotent,
+            ),
+            Self::BrpExportToolCatalog => Annotation::new(
+                "Export Tool Catalog",
+                ToolCategory::Logging,
+                EnvironmentImpact::AdditiveIdempotent,
+            ),
+            Self::BrpReplaySession => Annotation::new(
+                "Replay Session Transcript",
+                ToolCategory::Logging,
+                EnvironmentImpact::AdditiveNonIdempotent,
+            ),
+            Self::BrpRunTestScript => Annotation::new(
+                "Run Test Script",
+                ToolCategory::App,
+                EnvironmentImpact::AdditiveNonIdempotent,
+            ),
+            Self::BrpAcquireControl => Annotation::new(
+                "Acquire Control Lock",
+                ToolCategory::App,
+                EnvironmentImpact::AdditiveIdempotent,
+            ),
+            Self::BrpReleaseControl => Annotation::new(
+                "Release Control Lock",
+                ToolCategory::App,
+                EnvironmentImpact::DestructiveIdempotent,
+            ),
             #[cfg(feature = "mcp-debug")]
             Self::BrpGetTraceLogPath => Annotation::new(
                 "Get Trace Log Path",
@@ -468,6 +952,21 @@ impl ToolName {
                 ToolCategory::App,
                 EnvironmentImpact::AdditiveNonIdempotent,
             ),
+            Self::BrpLaunchGroup => Annotation::new(
+                "Launch Bevy Target Group",
+                ToolCategory::App,
+                EnvironmentImpact::AdditiveNonIdempotent,
+            ),
+            Self::BrpBootstrapSession => Annotation::new(
+                "Bootstrap Session",
+                ToolCategory::App,
+                EnvironmentImpact::AdditiveNonIdempotent,
+            ),
+            Self::BrpNewBevyExample => Annotation::new(
+                "Scaffold Bevy App",
+                ToolCategory::App,
+                EnvironmentImpact::AdditiveNonIdempotent,
+            ),
             Self::BrpListBevyApps => Annotation::new(
                 "List Bevy Apps",
                 ToolCategory::App,
@@ -483,16 +982,31 @@ impl ToolName {
                 ToolCategory::App,
                 EnvironmentImpact::ReadOnly,
             ),
+            Self::BrpListInstances => Annotation::new(
+                "List Launched Instances",
+                ToolCategory::App,
+                EnvironmentImpact::ReadOnly,
+            ),
             Self::BrpListActiveWatches => Annotation::new(
                 "List Active Watches",
                 ToolCategory::WatchMonitoring,
                 EnvironmentImpact::ReadOnly,
             ),
+            Self::BrpExecuteWatch => Annotation::new(
+                "Watch Streaming Method",
+                ToolCategory::WatchMonitoring,
+                EnvironmentImpact::AdditiveNonIdempotent,
+            ),
             Self::BrpStopWatch => Annotation::new(
                 "Stop Watch",
                 ToolCategory::WatchMonitoring,
                 EnvironmentImpact::DestructiveIdempotent,
             ),
+            Self::BrpGetWatchBuffer => Annotation::new(
+                "Get Watch Buffer",
+                ToolCategory::WatchMonitoring,
+                EnvironmentImpact::ReadOnly,
+            ),
             Self::BrpListLogs => Annotation::new(
                 "List Log Files",
                 ToolCategory::Logging,
@@ -503,6 +1017,11 @@ impl ToolName {
                 ToolCategory::Logging,
                 EnvironmentImpact::ReadOnly,
             ),
+            Self::BrpSearchLog => Annotation::new(
+                "Search Log File",
+                ToolCategory::Logging,
+                EnvironmentImpact::ReadOnly,
+            ),
             #[cfg(feature = "mcp-debug")]
             Self::BrpSetTracingLevel => Annotation::new(
                 "Set Tracing Level",
@@ -514,11 +1033,101 @@ impl ToolName {
                 ToolCategory::App,
                 EnvironmentImpact::ReadOnly,
             ),
+            Self::BrpDiagnose => Annotation::new(
+                "Diagnose BRP Connection",
+                ToolCategory::App,
+                EnvironmentImpact::ReadOnly,
+            ),
+            Self::BrpSelfTest => Annotation::new(
+                "Run Server Self-Test",
+                ToolCategory::App,
+                EnvironmentImpact::ReadOnly,
+            ),
+            Self::BrpSetupCheck => Annotation::new(
+                "Check BRP Setup",
+                ToolCategory::App,
+                EnvironmentImpact::AdditiveIdempotent,
+            ),
             Self::BrpShutdown => Annotation::new(
                 "Shutdown Bevy App",
                 ToolCategory::App,
                 EnvironmentImpact::DestructiveIdempotent,
             ),
+            Self::BrpWatchAndRelaunch => Annotation::new(
+                "Watch and Relaunch",
+                ToolCategory::App,
+                EnvironmentImpact::AdditiveNonIdempotent,
+            ),
+            Self::BrpNetworkProxyStart => Annotation::new(
+                "Start Network Proxy",
+                ToolCategory::App,
+                EnvironmentImpact::AdditiveNonIdempotent,
+            ),
+            Self::BrpNetworkProxyConfigure => Annotation::new(
+                "Configure Network Proxy",
+                ToolCategory::App,
+                EnvironmentImpact::AdditiveIdempotent,
+            ),
+            Self::BrpNetworkProxyStop => Annotation::new(
+                "Stop Network Proxy",
+                ToolCategory::App,
+                EnvironmentImpact::DestructiveIdempotent,
+            ),
+            Self::BrpRequestConfirmation => Annotation::new(
+                "Request Destructive Operation Confirmation",
+                ToolCategory::App,
+                EnvironmentImpact::AdditiveNonIdempotent,
+            ),
+            Self::BrpSetDefaultPort => Annotation::new(
+                "Set Default BRP Port",
+                ToolCategory::App,
+                EnvironmentImpact::AdditiveIdempotent,
+            ),
+            Self::BrpSetStrictFormats => Annotation::new(
+                "Set Strict Formats",
+                ToolCategory::App,
+                EnvironmentImpact::AdditiveIdempotent,
+            ),
+            Self::BrpResetCircuit => Annotation::new(
+                "Reset BRP Circuit Breaker",
+                ToolCategory::App,
+                EnvironmentImpact::AdditiveIdempotent,
+            ),
+            Self::BrpListCorrections => Annotation::new(
+                "List Format Corrections",
+                ToolCategory::App,
+                EnvironmentImpact::DestructiveNonIdempotent,
+            ),
+            Self::BrpAliasEntity => Annotation::new(
+                "Alias Entity",
+                ToolCategory::Entity,
+                EnvironmentImpact::AdditiveIdempotent,
+            ),
+            Self::BrpAssert => Annotation::new(
+                "Assert World State",
+                ToolCategory::Component,
+                EnvironmentImpact::ReadOnly,
+            ),
+            Self::BrpWaitFor => Annotation::new(
+                "Wait for World State",
+                ToolCategory::Component,
+                EnvironmentImpact::ReadOnly,
+            ),
+            Self::BrpWaitForIdle => Annotation::new(
+                "Wait for Idle",
+                ToolCategory::Extras,
+                EnvironmentImpact::ReadOnly,
+            ),
+            Self::BrpMapEntities => Annotation::new(
+                "Map Entities Across Restarts",
+                ToolCategory::Entity,
+                EnvironmentImpact::ReadOnly,
+            ),
+            Self::BrpFuzzComponent => Annotation::new(
+                "Fuzz Component Values",
+                ToolCategory::Component,
+                EnvironmentImpact::AdditiveIdempotent,
+            ),
             Self::BrpTypeGuide => Annotation::new(
                 "Type guide for components and resources",
                 ToolCategory::Discovery,
@@ -529,6 +1138,16 @@ impl ToolName {
                 ToolCategory::Discovery,
                 EnvironmentImpact::ReadOnly,
             ),
+            Self::BrpClearTypeGuideCache => Annotation::new(
+                "Clear cached type guides",
+                ToolCategory::Discovery,
+                EnvironmentImpact::DestructiveIdempotent,
+            ),
+            Self::BrpRefreshTypeCache => Annotation::new(
+                "Refresh short type name cache",
+                ToolCategory::Discovery,
+                EnvironmentImpact::AdditiveIdempotent,
+            ),
         }
     }
 
@@ -544,12 +1163,16 @@ impl ToolName {
             Self::WorldDespawnEntity => {
                 Some(parameters::build_parameters_from::<DespawnEntityParams>)
             },
+            Self::WorldUndoDespawn => Some(parameters::build_parameters_from::<UndoDespawnParams>),
             Self::WorldGetComponents => {
                 Some(parameters::build_parameters_from::<GetComponentsParams>)
             },
             Self::WorldGetResources => {
                 Some(parameters::build_parameters_from::<GetResourcesParams>)
             },
+            Self::WorldDumpResources => {
+                Some(parameters::build_parameters_from::<DumpResourcesParams>)
+            },
             Self::WorldInsertComponents => {
                 Some(parameters::build_parameters_from::<InsertComponentsParams>)
             },
@@ -569,6 +1192,17 @@ impl ToolName {
                 Some(parameters::build_parameters_from::<MutateResourcesParams>)
             },
             Self::WorldQuery => Some(parameters::build_parameters_from::<QueryParams>),
+            Self::WorldChangesSince => {
+                Some(parameters::build_parameters_from::<ChangesSinceParams>)
+            },
+            Self::WorldComponentMatrix => {
+                Some(parameters::build_parameters_from::<ComponentMatrixParams>)
+            },
+            Self::WorldJoinQuery => Some(parameters::build_parameters_from::<JoinQueryParams>),
+            Self::WorldFieldHistogram => {
+                Some(parameters::build_parameters_from::<FieldHistogramParams>)
+            },
+            Self::WorldExportGraph => Some(parameters::build_parameters_from::<ExportGraphParams>),
             Self::RegistrySchema => Some(parameters::build_parameters_from::<RegistrySchemaParams>),
             Self::GrabSelection => Some(parameters::build_parameters_from::<GrabSelectionParams>),
             Self::WorldRemoveComponents => {
@@ -586,10 +1220,68 @@ impl ToolName {
             Self::BrpExtrasScreenshot => {
                 Some(parameters::build_parameters_from::<ScreenshotParams>)
             },
+            Self::BrpExtrasScreenshotStatus => {
+                Some(parameters::build_parameters_from::<ScreenshotStatusParams>)
+            },
             Self::BrpExtrasSendKeys => Some(parameters::build_parameters_from::<SendKeysParams>),
             Self::BrpExtrasSetWindowTitle => {
                 Some(parameters::build_parameters_from::<SetWindowTitleParams>)
             },
+            Self::BrpExtrasSetWindow => Some(parameters::build_parameters_from::<SetWindowParams>),
+            Self::BrpExtrasTagEntity => Some(parameters::build_parameters_from::<TagEntityParams>),
+            Self::BrpExtrasFindTagged => {
+                Some(parameters::build_parameters_from::<FindTaggedParams>)
+            },
+            Self::BrpExtrasListMonitors => {
+                Some(parameters::build_parameters_from::<ListMonitorsParams>)
+            },
+            Self::BrpExtrasTimeConfig => {
+                Some(parameters::build_parameters_from::<TimeConfigParams>)
+            },
+            Self::BrpExtrasGizmoConfig => {
+                Some(parameters::build_parameters_from::<GizmoConfigParams>)
+            },
+            Self::BrpExtrasMaterial => Some(parameters::build_parameters_from::<MaterialParams>),
+            Self::BrpExtrasCollectFrameStats => {
+                Some(parameters::build_parameters_from::<CollectFrameStatsParams>)
+            },
+            Self::BrpExtrasTrackArchetypeMoves => {
+                Some(parameters::build_parameters_from::<TrackArchetypeMovesParams>)
+            },
+            Self::BrpExtrasQueryStream => {
+                Some(parameters::build_parameters_from::<QueryStreamParams>)
+            },
+            Self::BrpExtrasScheduleReport => {
+                Some(parameters::build_parameters_from::<ScheduleReportParams>)
+            },
+            Self::BrpExtrasDeterminism => {
+                Some(parameters::build_parameters_from::<DeterminismParams>)
+            },
+            Self::BrpExtrasPinEntity => Some(parameters::build_parameters_from::<PinEntityParams>),
+            Self::BrpExtrasReadPixels => {
+                Some(parameters::build_parameters_from::<ReadPixelsParams>)
+            },
+            Self::BrpExtrasGetUiText => Some(parameters::build_parameters_from::<GetUiTextParams>),
+            Self::BrpExtrasGetInteractionState => {
+                Some(parameters::build_parameters_from::<GetInteractionStateParams>)
+            },
+            Self::BrpExtrasClipboard => Some(parameters::build_parameters_from::<ClipboardParams>),
+            Self::BrpExtrasTrackEntity => {
+                Some(parameters::build_parameters_from::<TrackEntityParams>)
+            },
+            Self::BrpExtrasGetHistory => {
+                Some(parameters::build_parameters_from::<GetHistoryParams>)
+            },
+            Self::BrpExtrasApplyCommands => {
+                Some(parameters::build_parameters_from::<ApplyCommandsParams>)
+            },
+            Self::BrpExtrasAppInfo => Some(parameters::build_parameters_from::<AppInfoParams>),
+            Self::BrpExtrasEnsureRegistered => {
+                Some(parameters::build_parameters_from::<EnsureRegisteredParams>)
+            },
+            Self::BrpExtrasCapabilities => {
+                Some(parameters::build_parameters_from::<CapabilitiesParams>)
+            },
             Self::WorldGetComponentsWatch => {
                 Some(parameters::build_parameters_from::<GetComponentsWatchParams>)
             },
@@ -597,32 +1289,107 @@ impl ToolName {
                 Some(parameters::build_parameters_from::<ListComponentsWatchParams>)
             },
             Self::BrpDeleteLogs => Some(parameters::build_parameters_from::<DeleteLogsParams>),
+            Self::BrpManageLogs => Some(parameters::build_parameters_from::<ManageLogsParams>),
+            Self::BrpListScreenshots => {
+                Some(parameters::build_parameters_from::<ListScreenshotsParams>)
+            },
+            Self::BrpExportSession => {
+                Some(parameters::build_parameters_from::<ExportSessionParams>)
+            },
+            Self::BrpExportToolCatalog => {
+                Some(parameters::build_parameters_from::<ExportToolCatalogParams>)
+            },
+            Self::BrpReplaySession => {
+                Some(parameters::build_parameters_from::<ReplaySessionParams>)
+            },
+            Self::BrpRunTestScript => {
+                Some(parameters::build_parameters_from::<RunTestScriptParams>)
+            },
+            Self::BrpAcquireControl => {
+                Some(parameters::build_parameters_from::<AcquireControlParams>)
+            },
+            Self::BrpReleaseControl => {
+                Some(parameters::build_parameters_from::<ReleaseControlParams>)
+            },
 
             // this lot has no parametrers
             #[cfg(feature = "mcp-debug")]
             Self::BrpGetTraceLogPath => None,
-            Self::BrpListBevyApps
-            | Self::BrpListBevyExamples
-            | Self::BrpListBrpApps
-            | Self::BrpListActiveWatches => None,
+            Self::BrpListInstances | Self::BrpListActiveWatches | Self::BrpSelfTest => None,
 
             // and thest of these app and watch tools do have parameters
+            Self::BrpListBevyApps | Self::BrpListBevyExamples | Self::BrpListBrpApps => {
+                Some(parameters::build_parameters_from::<ListTargetsParams>)
+            },
             Self::BrpLaunchBevyApp | Self::BrpLaunchBevyExample => {
                 Some(parameters::build_parameters_from::<LaunchBevyBinaryParams>)
             },
+            Self::BrpLaunchGroup => Some(parameters::build_parameters_from::<LaunchGroupParams>),
+            Self::BrpBootstrapSession => {
+                Some(parameters::build_parameters_from::<BootstrapSessionParams>)
+            },
+            Self::BrpNewBevyExample => {
+                Some(parameters::build_parameters_from::<NewBevyExampleParams>)
+            },
+            Self::BrpWatchAndRelaunch => {
+                Some(parameters::build_parameters_from::<WatchAndRelaunchParams>)
+            },
+            Self::BrpNetworkProxyStart => {
+                Some(parameters::build_parameters_from::<NetworkProxyStartParams>)
+            },
+            Self::BrpNetworkProxyConfigure => {
+                Some(parameters::build_parameters_from::<NetworkProxyConfigureParams>)
+            },
+            Self::BrpNetworkProxyStop => {
+                Some(parameters::build_parameters_from::<NetworkProxyStopParams>)
+            },
+            Self::BrpExecuteWatch => Some(parameters::build_parameters_from::<ExecuteWatchParams>),
             Self::BrpStopWatch => Some(parameters::build_parameters_from::<StopWatchParams>),
+            Self::BrpGetWatchBuffer => {
+                Some(parameters::build_parameters_from::<GetWatchBufferParams>)
+            },
             Self::BrpListLogs => Some(parameters::build_parameters_from::<ListLogsParams>),
             Self::BrpReadLog => Some(parameters::build_parameters_from::<ReadLogParams>),
+            Self::BrpSearchLog => Some(parameters::build_parameters_from::<SearchLogParams>),
             #[cfg(feature = "mcp-debug")]
             Self::BrpSetTracingLevel => {
                 Some(parameters::build_parameters_from::<SetTracingLevelParams>)
             },
             Self::BrpStatus => Some(parameters::build_parameters_from::<StatusParams>),
+            Self::BrpDiagnose => Some(parameters::build_parameters_from::<DiagnoseParams>),
+            Self::BrpSetupCheck => Some(parameters::build_parameters_from::<SetupCheckParams>),
             Self::BrpShutdown => Some(parameters::build_parameters_from::<ShutdownParams>),
+            Self::BrpRequestConfirmation => {
+                Some(parameters::build_parameters_from::<RequestConfirmationParams>)
+            },
+            Self::BrpSetDefaultPort => {
+                Some(parameters::build_parameters_from::<SetDefaultPortParams>)
+            },
+            Self::BrpSetStrictFormats => {
+                Some(parameters::build_parameters_from::<SetStrictFormatsParams>)
+            },
+            Self::BrpResetCircuit => Some(parameters::build_parameters_from::<ResetCircuitParams>),
+            Self::BrpListCorrections => {
+                Some(parameters::build_parameters_from::<ListCorrectionsParams>)
+            },
+            Self::BrpAliasEntity => Some(parameters::build_parameters_from::<AliasEntityParams>),
+            Self::BrpAssert => Some(parameters::build_parameters_from::<AssertParams>),
+            Self::BrpWaitFor => Some(parameters::build_parameters_from::<WaitForParams>),
+            Self::BrpWaitForIdle => Some(parameters::build_parameters_from::<WaitForIdleParams>),
+            Self::BrpMapEntities => Some(parameters::build_parameters_from::<MapEntitiesParams>),
+            Self::BrpFuzzComponent => {
+                Some(parameters::build_parameters_from::<FuzzComponentParams>)
+            },
             Self::BrpTypeGuide => Some(parameters::build_parameters_from::<TypeGuideParams>),
             Self::BrpAllTypeGuides => {
                 Some(parameters::build_parameters_from::<AllTypeGuidesParams>)
             },
+            Self::BrpClearTypeGuideCache => {
+                Some(parameters::build_parameters_from::<ClearTypeGuideCacheParams>)
+            },
+            Self::BrpRefreshTypeCache => {
+                Some(parameters::build_parameters_from::<RefreshTypeCacheParams>)
+            },
         }
     }
 
@@ -632,8 +1399,10 @@ impl ToolName {
         match self {
             // BRP tools generated by the macro
             Self::WorldDespawnEntity => Arc::new(WorldDespawnEntity),
+            Self::WorldUndoDespawn => Arc::new(WorldUndoDespawn),
             Self::WorldGetComponents => Arc::new(WorldGetComponents),
             Self::WorldGetResources => Arc::new(WorldGetResources),
+            Self::WorldDumpResources => Arc::new(WorldDumpResources),
             Self::WorldInsertComponents => Arc::new(WorldInsertComponents),
             Self::WorldInsertResources => Arc::new(WorldInsertResources),
             Self::WorldListComponents => Arc::new(WorldListComponents),
@@ -641,6 +1410,11 @@ impl ToolName {
             Self::WorldMutateComponents => Arc::new(WorldMutateComponents),
             Self::WorldMutateResources => Arc::new(WorldMutateResources),
             Self::WorldQuery => Arc::new(WorldQuery),
+            Self::WorldChangesSince => Arc::new(WorldChangesSince),
+            Self::WorldComponentMatrix => Arc::new(WorldComponentMatrix),
+            Self::WorldJoinQuery => Arc::new(WorldJoinQuery),
+            Self::WorldFieldHistogram => Arc::new(WorldFieldHistogram),
+            Self::WorldExportGraph => Arc::new(WorldExportGraph),
             Self::RegistrySchema => Arc::new(RegistrySchema),
             Self::GrabSelection => Arc::new(GrabSelection),
             Self::WorldRemoveComponents => Arc::new(WorldRemoveComponents),
@@ -649,33 +1423,92 @@ impl ToolName {
             Self::RpcDiscover => Arc::new(RpcDiscover),
             Self::WorldSpawnEntity => Arc::new(WorldSpawnEntity),
             Self::BrpExtrasScreenshot => Arc::new(BrpExtrasScreenshot),
+            Self::BrpExtrasScreenshotStatus => Arc::new(BrpExtrasScreenshotStatus),
             Self::BrpExtrasSendKeys => Arc::new(BrpExtrasSendKeys),
             Self::BrpExtrasSetWindowTitle => Arc::new(BrpExtrasSetWindowTitle),
+            Self::BrpExtrasSetWindow => Arc::new(BrpExtrasSetWindow),
+            Self::BrpExtrasTagEntity => Arc::new(BrpExtrasTagEntity),
+            Self::BrpExtrasFindTagged => Arc::new(BrpExtrasFindTagged),
+            Self::BrpExtrasListMonitors => Arc::new(BrpExtrasListMonitors),
+            Self::BrpExtrasTimeConfig => Arc::new(BrpExtrasTimeConfig),
+            Self::BrpExtrasGizmoConfig => Arc::new(BrpExtrasGizmoConfig),
+            Self::BrpExtrasMaterial => Arc::new(BrpExtrasMaterial),
+            Self::BrpExtrasCollectFrameStats => Arc::new(BrpExtrasCollectFrameStats),
+            Self::BrpExtrasTrackArchetypeMoves => Arc::new(BrpExtrasTrackArchetypeMoves),
+            Self::BrpExtrasQueryStream => Arc::new(BrpExtrasQueryStream),
+            Self::BrpExtrasScheduleReport => Arc::new(BrpExtrasScheduleReport),
+            Self::BrpExtrasDeterminism => Arc::new(BrpExtrasDeterminism),
+            Self::BrpExtrasPinEntity => Arc::new(BrpExtrasPinEntity),
+            Self::BrpExtrasReadPixels => Arc::new(BrpExtrasReadPixels),
+            Self::BrpExtrasGetUiText => Arc::new(BrpExtrasGetUiText),
+            Self::BrpExtrasGetInteractionState => Arc::new(BrpExtrasGetInteractionState),
+            Self::BrpExtrasClipboard => Arc::new(BrpExtrasClipboard),
+            Self::BrpExtrasTrackEntity => Arc::new(BrpExtrasTrackEntity),
+            Self::BrpExtrasGetHistory => Arc::new(BrpExtrasGetHistory),
+            Self::BrpExtrasApplyCommands => Arc::new(BrpExtrasApplyCommands),
+            Self::BrpExtrasAppInfo => Arc::new(BrpExtrasAppInfo),
+            Self::BrpExtrasEnsureRegistered => Arc::new(BrpExtrasEnsureRegistered),
+            Self::BrpExtrasCapabilities => Arc::new(BrpExtrasCapabilities),
 
             // Special tools with their own implementations
             Self::BrpExecute => Arc::new(BrpExecute),
             Self::WorldGetComponentsWatch => Arc::new(WorldGetComponentsWatch),
             Self::WorldListComponentsWatch => Arc::new(BevyListWatch),
             Self::BrpListActiveWatches => Arc::new(BrpListActiveWatches),
+            Self::BrpExecuteWatch => Arc::new(BrpExecuteWatch),
             Self::BrpStopWatch => Arc::new(BrpStopWatch),
+            Self::BrpGetWatchBuffer => Arc::new(BrpGetWatchBuffer),
             Self::BrpTypeGuide => Arc::new(BrpTypeGuide),
             Self::BrpAllTypeGuides => Arc::new(BrpAllTypeGuides),
+            Self::BrpClearTypeGuideCache => Arc::new(BrpClearTypeGuideCache),
+            Self::BrpRefreshTypeCache => Arc::new(BrpRefreshTypeCache),
 
             // App tools
             Self::BrpDeleteLogs => Arc::new(DeleteLogs),
+            Self::BrpManageLogs => Arc::new(ManageLogs),
+            Self::BrpListScreenshots => Arc::new(ListScreenshots),
+            Self::BrpExportSession => Arc::new(ExportSession),
+            Self::BrpExportToolCatalog => Arc::new(ExportToolCatalog),
+            Self::BrpReplaySession => Arc::new(ReplaySession),
+            Self::BrpRunTestScript => Arc::new(RunTestScript),
+            Self::BrpAcquireControl => Arc::new(AcquireControl),
+            Self::BrpReleaseControl => Arc::new(ReleaseControl),
             #[cfg(feature = "mcp-debug")]
             Self::BrpGetTraceLogPath => Arc::new(GetTraceLogPath),
             Self::BrpLaunchBevyApp => Arc::new(app_tools::create_launch_bevy_app_handler()),
             Self::BrpLaunchBevyExample => Arc::new(app_tools::create_launch_bevy_example_handler()),
+            Self::BrpLaunchGroup => Arc::new(LaunchGroup),
+            Self::BrpBootstrapSession => Arc::new(BootstrapSession),
+            Self::BrpNewBevyExample => Arc::new(NewBevyExample),
+            Self::BrpWatchAndRelaunch => Arc::new(WatchAndRelaunch),
+            Self::BrpNetworkProxyStart => Arc::new(NetworkProxyStart),
+            Self::BrpNetworkProxyConfigure => Arc::new(NetworkProxyConfigure),
+            Self::BrpNetworkProxyStop => Arc::new(NetworkProxyStop),
             Self::BrpListBevyApps => Arc::new(ListBevyApps),
             Self::BrpListBevyExamples => Arc::new(ListBevyExamples),
             Self::BrpListBrpApps => Arc::new(ListBrpApps),
+            Self::BrpListInstances => Arc::new(ListInstances),
             Self::BrpListLogs => Arc::new(ListLogs),
             Self::BrpReadLog => Arc::new(ReadLog),
+            Self::BrpSearchLog => Arc::new(SearchLog),
             #[cfg(feature = "mcp-debug")]
             Self::BrpSetTracingLevel => Arc::new(SetTracingLevel),
             Self::BrpStatus => Arc::new(Status),
+            Self::BrpDiagnose => Arc::new(Diagnose),
+            Self::BrpSelfTest => Arc::new(SelfTest),
+            Self::BrpSetupCheck => Arc::new(SetupCheck),
             Self::BrpShutdown => Arc::new(Shutdown),
+            Self::BrpRequestConfirmation => Arc::new(BrpRequestConfirmation),
+            Self::BrpSetDefaultPort => Arc::new(BrpSetDefaultPort),
+            Self::BrpSetStrictFormats => Arc::new(BrpSetStrictFormats),
+            Self::BrpResetCircuit => Arc::new(BrpResetCircuit),
+            Self::BrpListCorrections => Arc::new(BrpListCorrections),
+            Self::BrpAliasEntity => Arc::new(BrpAliasEntity),
+            Self::BrpAssert => Arc::new(BrpAssert),
+            Self::BrpWaitFor => Arc::new(BrpWaitFor),
+            Self::BrpWaitForIdle => Arc::new(BrpWaitForIdle),
+            Self::BrpMapEntities => Arc::new(BrpMapEntities),
+            Self::BrpFuzzComponent => Arc::new(BrpFuzzComponent),
         }
     }
 
@@ -696,6 +1529,51 @@ impl ToolName {
         Self::iter().map(Self::to_tool_def).collect()
     }
 
+    /// Whether this tool permanently removes state from or stops a running app and is therefore
+    /// gated behind confirmation when `BRP_MCP_REQUIRE_CONFIRMATION` is enabled - command batches
+    /// are gated only when they despawn or remove, and `brp_execute` only when its method is gated
+    pub const fn requires_confirmation(self) -> bool {
+        matches!(
+            self,
+            Self::WorldDespawnEntity
+                | Self::WorldRemoveComponents
+                | Self::WorldRemoveResources
+                | Self::BrpShutdown
+                | Self::BrpWatchAndRelaunch
+                | Self::BrpExtrasApplyCommands
+                | Self::BrpExecute
+        )
+    }
+
+    /// Timeout applied to this tool's handler when the call omits `timeout_ms`
+    ///
+    /// Builds and whole-registry operations get a longer default. Frame-stat collection,
+    /// `brp_wait_for` and `brp_wait_for_idle` bound themselves by their own `duration_ms` and
+    /// `max_wait_ms`, archetype tracking and query streams give up once the app stops running
+    /// frames, and a session replay, test script or session bootstrap is bounded by the timeouts
+    /// of its steps, so none of them has a framework timeout by default.
+    pub const fn default_timeout(self) -> Option<Duration> {
+        match self {
+            Self::BrpLaunchBevyApp
+            | Self::BrpLaunchBevyExample
+            | Self::BrpLaunchGroup
+            | Self::BrpWatchAndRelaunch
+            | Self::BrpAllTypeGuides
+            | Self::BrpRefreshTypeCache => Some(LONG_TOOL_TIMEOUT),
+            // A scaffold's first build compiles Bevy from scratch, beyond any fixed limit
+            Self::BrpNewBevyExample
+            | Self::BrpExtrasCollectFrameStats
+            | Self::BrpExtrasTrackArchetypeMoves
+            | Self::BrpExtrasQueryStream
+            | Self::BrpWaitFor
+            | Self::BrpWaitForIdle
+            | Self::BrpReplaySession
+            | Self::BrpRunTestScript
+            | Self::BrpBootstrapSession => None,
+            _ => Some(DEFAULT_TOOL_TIMEOUT),
+        }
+    }
+
     /// Get a short human-readable title for this tool
     /// Extracted from the annotation data we already have
     pub fn short_title(self) -> String { self.get_annotations().title }
@@ -17,12 +17,19 @@
 
 use std::future::Future;
 use std::pin::Pin;
+use std::sync::Arc;
 
 use async_trait::async_trait;
 use rmcp::model::CallToolResult;
+use rmcp::model::JsonObject;
+use schemars::SchemaGenerator;
 
+use super::error_screenshot;
+use super::field_placement::OutputSchemaField;
 use super::handler_context::HandlerContext;
+use super::output_schema;
 use super::response_builder::ResponseBuilder;
+use crate::brp_tools::with_call_report;
 use crate::error::Result;
 use crate::tool::ParamStruct;
 
@@ -52,7 +59,7 @@ pub struct ToolResult<T, P = ()> {
 /// context is needed):
 ///
 /// ## Most tools (no context needed):
-/// ```rust
+/// ```ignore
 /// impl ToolFn for MyTool {
 ///     type Output = MyResult;
 ///     type Params = MyParams;
@@ -64,7 +71,7 @@ pub struct ToolResult<T, P = ()> {
 /// ```
 ///
 /// ## Context-needing tools (e.g., list tools that need workspace roots):
-/// ```rust
+/// ```ignore
 /// impl ToolFn for ListTool {
 ///     type Output = ListResult;
 ///     type Params = NoParams;
@@ -133,6 +140,9 @@ pub trait ErasedToolFn: Send + Sync {
         &'a self,
         ctx: HandlerContext,
     ) -> Pin<Box<dyn Future<Output = CallToolResult> + Send + 'a>>;
+
+    /// MCP `outputSchema` describing this tool's structured result
+    fn output_schema(&self) -> Arc<JsonObject>;
 }
 
 /// Blanket implementation to convert typed `ToolFn`s to erased ones
@@ -144,13 +154,25 @@ impl<T: ToolFn> ErasedToolFn for T {
         Box::pin(async move {
             // we're making a judgement call that we passed a reference to call()
 
-            let result = self.call(ctx.clone()).await;
+            let (result, call_report) = with_call_report(self.call(ctx.clone())).await;
             match result {
-                Ok(tool_result) => ctx.format_result(tool_result),
+                Ok(tool_result) => {
+                    let wants_screenshot = tool_result.result.as_ref().is_err_and(|report| {
+                        error_screenshot::wanted(ctx.tool_def().tool_name, report.current_context())
+                    });
+                    let error_screenshot = if wants_screenshot {
+                        error_screenshot::capture(ctx.request.arguments.as_ref()).await
+                    } else {
+                        None
+                    };
+                    ctx.format_result(tool_result, call_report, error_screenshot)
+                },
                 Err(e) => ctx.format_framework_error(e),
             }
         })
     }
+
+    fn output_schema(&self) -> Arc<JsonObject> { output_schema::output_schema_for::<T::Output>() }
 }
 
 /// Trait for types that can be used as results
@@ -184,4 +206,9 @@ pub trait ResultStruct: Send + Sync {
 
     /// Get the message template for this response
     fn get_message_template(&self) -> Result<&str>;
+
+    /// Schemas for the fields placed in `metadata` and `result`
+    fn output_schema_fields(generator: &mut SchemaGenerator) -> Vec<OutputSchemaField>
+    where
+        Self: Sized;
 }
@@ -0,0 +1,162 @@
+//! Server-wide verbosity of response prose
+//!
+//! Every response carries a `message` rendered from its `ResultStruct` template, and some
+//! metadata carries explanations for a human reader. Token-constrained deployments pay for that
+//! prose on every call, so `BRP_MCP_VERBOSITY` picks how much of it is sent:
+//!
+//! - `terse`: message templates of successful responses aren't rendered, and the echoed
+//!   `parameters` and explanatory metadata fields are dropped - only the structured result and
+//!   metadata remain (plus a table asked for with `format: "table"`). Error responses are
+//!   unchanged, since their message and details say what went wrong and how to recover.
+//! - `normal` (the default): responses as the tools build them.
+//! - `verbose`: the message also lists the metadata fields its template doesn't mention.
+
+use std::sync::LazyLock;
+
+use serde_json::Value;
+use strum::Display;
+use strum::EnumString;
+use tracing::debug;
+
+use super::json_response::AnySchemaValue;
+use super::json_response::ResponseStatus;
+use super::json_response::ToolCallJsonResponse;
+
+/// Environment variable choosing the verbosity (`terse`, `normal` or `verbose`)
+pub const VERBOSITY_ENV_VAR: &str = "BRP_MCP_VERBOSITY";
+
+/// Metadata fields that explain rather than report, dropped from terse responses
+const NARRATIVE_FIELDS: &[&str] = &["instructions", "notice"];
+
+/// How much prose responses carry
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Display, EnumString)]
+#[strum(serialize_all = "snake_case", ascii_case_insensitive)]
+pub enum Verbosity {
+    /// Structured fields only on success
+    Terse,
+    /// Messages as the tools write them
+    #[default]
+    Normal,
+    /// Messages followed by the metadata they don't mention
+    Verbose,
+}
+
+/// Server-wide verbosity - read once at first use
+static VERBOSITY: LazyLock<Verbosity> = LazyLock::new(|| {
+    let Ok(value) = std::env::var(VERBOSITY_ENV_VAR) else {
+        return Verbosity::default();
+    };
+    value.trim().parse().unwrap_or_else(|_| {
+        debug!("Invalid {VERBOSITY_ENV_VAR} value '{value}' - using normal verbosity");
+        Verbosity::default()
+    })
+});
+
+/// The server's verbosity
+pub fn verbosity() -> Verbosity { *VERBOSITY }
+
+/// `message` followed by the metadata fields not in `mentioned`, for verbose responses
+pub fn with_unmentioned_metadata(
+    message: String,
+    mentioned: &[String],
+    metadata: Option<&Value>,
+    value_to_string: impl Fn(&Value) -> String,
+) -> String {
+    let Some(Value::Object(metadata)) = metadata else {
+        return message;
+    };
+    let details: Vec<String> = metadata
+        .iter()
+        .filter(|(key, _)| !mentioned.iter().any(|mentioned| mentioned == *key))
+        .map(|(key, value)| format!("{key}: {}", value_to_string(value)))
+        .collect();
+    if details.is_empty() {
+        message
+    } else {
+        format!("{message} ({})", details.join(", "))
+    }
+}
+
+/// Strip the echoed parameters and narrative metadata from a finished response when the server
+/// is terse - its message was never rendered
+pub fn apply_verbosity(response: ToolCallJsonResponse) -> ToolCallJsonResponse {
+    match verbosity() {
+        Verbosity::Terse => terse_response(response),
+        Verbosity::Normal | Verbosity::Verbose => response,
+    }
+}
+
+/// Drop the echoed parameters and narrative metadata of a successful response
+fn terse_response(mut response: ToolCallJsonResponse) -> ToolCallJsonResponse {
+    if !matches!(response.status, ResponseStatus::Success) {
+        return response;
+    }
+    response.parameters = None;
+    if let Some(AnySchemaValue(metadata)) = response.metadata.as_mut() {
+        strip_narrative_fields(metadata);
+    }
+    response
+}
+
+/// Remove narrative string fields at any depth
+fn strip_narrative_fields(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            map.retain(|key, value| {
+                !(NARRATIVE_FIELDS.contains(&key.as_str()) && value.is_string())
+            });
+            map.values_mut().for_each(strip_narrative_fields);
+        },
+        Value::Array(items) => items.iter_mut().for_each(strip_narrative_fields),
+        _ => {},
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::tool::ToolName;
+
+    #[test]
+    fn terse_keeps_only_structured_fields_of_successes() {
+        let response = ToolCallJsonResponse {
+            status:                ResponseStatus::Success,
+            message:               String::new(),
+            call_info:             ToolName::WorldQuery.get_call_info(),
+            metadata:              Some(AnySchemaValue(json!({
+                "entity_count": 2,
+                "app_restarted": { "port": 15702, "notice": "The app restarted" },
+            }))),
+            parameters:            Some(AnySchemaValue(json!({ "entity": 42 }))),
+            result:                Some(AnySchemaValue(json!([1, 2]))),
+            error_info:            None,
+            brp_extras_debug_info: None,
+        };
+        let terse = terse_response(response);
+        assert!(terse.parameters.is_none());
+        assert_eq!(
+            terse.metadata.map(|AnySchemaValue(metadata)| metadata),
+            Some(json!({ "entity_count": 2, "app_restarted": { "port": 15702 } }))
+        );
+        assert_eq!(
+            "Verbose".parse::<Verbosity>().ok(),
+            Some(Verbosity::Verbose)
+        );
+    }
+
+    #[test]
+    fn verbose_lists_metadata_the_template_leaves_out() {
+        let metadata = json!({ "entity_count": 2, "cache_hit": true });
+        assert_eq!(
+            with_unmentioned_metadata(
+                "Found 2 entities".to_string(),
+                &["entity_count".to_string()],
+                Some(&metadata),
+                ToString::to_string,
+            ),
+            "Found 2 entities (cache_hit: true)"
+        );
+    }
+}
@@ -201,6 +201,11 @@ pub fn derive_brp_tools_impl(input: TokenStream) -> TokenStream {
         }
 
         /// Enum containing only tool variants that have BRP methods
+        ///
+        /// Each variant is named after the tool that calls the method, and `as_str` gives the
+        /// method's name. The variants are left undocumented so the schema stays a plain list of
+        /// method names.
+        #[allow(missing_docs)]
         #[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
         pub enum BrpMethod {
             #(#brp_method_variants,)*
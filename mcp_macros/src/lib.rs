@@ -77,15 +77,28 @@ pub fn derive_brp_tools(input: TokenStream) -> TokenStream {
 /// struct GetParams {
 ///     pub entity: u64,
 ///
+///     #[param(min = 1, max = 64)]
+///     pub depth: Option<u32>,
+///
+///     #[param(one_of("debug", "release"))]
+///     pub profile: String,
+///
 ///     #[to_call_info]
 ///     pub port: Port,
 /// }
 /// ```
 ///
+/// # Field Constraints
+///
+/// `#[param(...)]` accepts `min = ..` / `max = ..` (inclusive integer bounds), `regex = "..."`
+/// and `one_of("..", ..)`. Each constraint is added to the tool's input schema and checked when
+/// the parameters are extracted. `Option` fields are only checked when present, and `Vec` fields
+/// check every element.
+///
 /// This will generate implementations for:
 /// - `HasFieldPlacement` - provides field placement information
-/// - `ResponseData` - for building MCP responses
-#[proc_macro_derive(ParamStruct, attributes(to_metadata, to_call_info))]
+/// - `ParamStruct` - provides the field constraints and their runtime validation
+#[proc_macro_derive(ParamStruct, attributes(to_metadata, to_call_info, param))]
 pub fn derive_param_struct(input: TokenStream) -> TokenStream {
     param_struct::derive_param_struct_impl(input)
 }
@@ -113,6 +126,19 @@ pub fn derive_param_struct(input: TokenStream) -> TokenStream {
 ///   - `#[to_message]`
 ///   - Usage: `MyResult::new(...).with_message_template("required message")`
 ///
+/// # Grouped Metadata
+///
+/// `#[to_metadata(group = "timing")]` nests the field under `metadata.timing` instead of at the
+/// top level of `metadata`. Message templates reach grouped fields with dotted placeholders
+/// (`{timing.launch_duration_ms}`).
+///
+/// # Computed Fields
+///
+/// `#[computed(from = "instances", operation = "count")]` derives a field from another field
+/// when the result is constructed, so it is not a `::new()` parameter. Supported operations are
+/// `count`, `is_empty` and `duration_ms`. Without `from`, `#[brp_result]` structs compute the
+/// field from the BRP response value.
+///
 /// # Example
 ///
 /// ```ignore
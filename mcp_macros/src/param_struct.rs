@@ -7,10 +7,116 @@ use proc_macro::TokenStream;
 use quote::quote;
 use syn::Data;
 use syn::DeriveInput;
+use syn::ext::IdentExt;
 use syn::parse_macro_input;
 
 use crate::shared::extract_field_data;
 
+/// Constraints parsed from a field's `#[param(...)]` attribute
+#[derive(Default)]
+struct ParamAttrs {
+    min:    Option<syn::Expr>,
+    max:    Option<syn::Expr>,
+    regex:  Option<syn::LitStr>,
+    one_of: Vec<syn::LitStr>,
+}
+
+/// Parse `#[param(min = .., max = .., regex = "..", one_of("a", "b"))]` on a field
+fn parse_param_attr(field: &syn::Field) -> syn::Result<Option<ParamAttrs>> {
+    let Some(attr) = field
+        .attrs
+        .iter()
+        .find(|attr| attr.path().is_ident("param"))
+    else {
+        return Ok(None);
+    };
+
+    let mut param_attrs = ParamAttrs::default();
+    attr.parse_nested_meta(|meta| {
+        if meta.path.is_ident("min") {
+            param_attrs.min = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("max") {
+            param_attrs.max = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("regex") {
+            param_attrs.regex = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("one_of") {
+            let content;
+            syn::parenthesized!(content in meta.input);
+            param_attrs.one_of = content
+                .parse_terminated(<syn::LitStr as syn::parse::Parse>::parse, syn::Token![,])?
+                .into_iter()
+                .collect();
+        } else {
+            return Err(meta.error("unsupported param attribute"));
+        }
+        Ok(())
+    })?;
+
+    Ok(Some(param_attrs))
+}
+
+/// Name callers send the field under - its `#[serde(rename)]` (or `rename(deserialize)`) if it has
+/// one, otherwise the field name without any `r#` prefix
+fn serde_name(field: &syn::Field, ident: &syn::Ident) -> String {
+    let mut name = ident.unraw().to_string();
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("serde"))
+    {
+        // Other serde options are skipped - serde itself reports any that are malformed
+        let _ = attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename") {
+                if meta.input.peek(syn::Token![=]) {
+                    name = meta.value()?.parse::<syn::LitStr>()?.value();
+                } else {
+                    meta.parse_nested_meta(|direction| {
+                        let renamed: syn::LitStr = direction.value()?.parse()?;
+                        if direction.path.is_ident("deserialize") {
+                            name = renamed.value();
+                        }
+                        Ok(())
+                    })?;
+                }
+            } else if meta.input.peek(syn::Token![=]) {
+                meta.value()?.parse::<syn::Expr>()?;
+            } else if meta.input.peek(syn::token::Paren) {
+                let _options;
+                syn::parenthesized!(_options in meta.input);
+            }
+            Ok(())
+        });
+    }
+    name
+}
+
+/// Build the `ParamConstraint` expression for a field's `#[param(...)]` attribute
+fn constraint_tokens(field_name: &str, attrs: &ParamAttrs) -> proc_macro2::TokenStream {
+    let minimum = attrs
+        .min
+        .as_ref()
+        .map_or_else(|| quote! { None }, |min| quote! { Some(#min) });
+    let maximum = attrs
+        .max
+        .as_ref()
+        .map_or_else(|| quote! { None }, |max| quote! { Some(#max) });
+    let pattern = attrs
+        .regex
+        .as_ref()
+        .map_or_else(|| quote! { None }, |regex| quote! { Some(#regex) });
+    let one_of = &attrs.one_of;
+
+    quote! {
+        crate::tool::ParamConstraint {
+            field_name: #field_name,
+            minimum:    #minimum,
+            maximum:    #maximum,
+            pattern:    #pattern,
+            one_of:     &[#(#one_of),*],
+        }
+    }
+}
+
 /// Implementation of the ParamStruct derive macro
 pub fn derive_param_struct_impl(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
@@ -36,6 +142,25 @@ pub fn derive_param_struct_impl(input: TokenStream) -> TokenStream {
 
     let field_placements = extraction_result.field_placements;
 
+    // Collect `#[param(...)]` constraints - each one feeds both the schema and `validate`
+    let mut constraints = Vec::new();
+    let mut validations = Vec::new();
+    for field in &fields {
+        let Some(field_ident) = &field.ident else {
+            continue;
+        };
+        let attrs = match parse_param_attr(field) {
+            Ok(Some(attrs)) => attrs,
+            Ok(None) => continue,
+            Err(error) => return TokenStream::from(error.to_compile_error()),
+        };
+        let constraint = constraint_tokens(&serde_name(field, field_ident), &attrs);
+        validations.push(quote! {
+            #constraint.validate(&self.#field_ident)?;
+        });
+        constraints.push(constraint);
+    }
+
     // Generate the trait implementations
     let expanded = quote! {
         impl crate::tool::HasFieldPlacement for #struct_name {
@@ -46,7 +171,18 @@ pub fn derive_param_struct_impl(input: TokenStream) -> TokenStream {
             }
         }
 
-        impl crate::tool::ParamStruct for #struct_name {}
+        impl crate::tool::ParamStruct for #struct_name {
+            fn param_constraints() -> Vec<crate::tool::ParamConstraint> {
+                vec![
+                    #(#constraints,)*
+                ]
+            }
+
+            fn validate(&self) -> crate::error::Result<()> {
+                #(#validations)*
+                Ok(())
+            }
+        }
     };
 
     TokenStream::from(expanded)
@@ -85,6 +85,7 @@ pub fn derive_result_struct_impl(input: TokenStream) -> TokenStream {
 
     let field_placements = extraction_result.field_placements;
     let response_data_fields = extraction_result.response_data_fields;
+    let output_schema_fields = extraction_result.output_schema_fields;
     let regular_fields = extraction_result.regular_fields;
     let computed_fields = extraction_result.computed_fields;
     let message_template_field = extraction_result.message_template_field;
@@ -161,7 +162,7 @@ pub fn derive_result_struct_impl(input: TokenStream) -> TokenStream {
             impl crate::brp_tools::ResultStructBrpExt for #struct_name {
                 type Args = (
                     Option<serde_json::Value>,
-                    Option<Vec<serde_json::Value>>,
+                    Option<Vec<(crate::brp_tools::Corrector, serde_json::Value)>>,
                     Option<crate::brp_tools::FormatCorrectionStatus>,
                 );
 
@@ -194,6 +195,15 @@ pub fn derive_result_struct_impl(input: TokenStream) -> TokenStream {
             fn get_message_template(&self) -> crate::error::Result<&str> {
                 #get_template_impl
             }
+
+            #[allow(unused_variables, unused_mut)]
+            fn output_schema_fields(
+                generator: &mut schemars::SchemaGenerator,
+            ) -> Vec<crate::tool::OutputSchemaField> {
+                let mut fields = Vec::new();
+                #(fields.extend(#output_schema_fields);)*
+                fields
+            }
         }
 
         #from_brp_client_response_impl
@@ -257,9 +267,16 @@ fn generate_message_template_provider(
             }
         }
 
+        // Fields computed from other fields are evaluated before the fields are moved
+        let computed_lets = generate_field_computations(computed_fields, |from| quote! { #from });
+
         // Handle computed fields with default values
         for computed in computed_fields {
             let field_name = &computed.field_name;
+            if !computed.is_from_result() {
+                field_initializers.push(quote! { #field_name });
+                continue;
+            }
             // Provide default values for computed fields
             let default_value = match computed.operation.as_str() {
                 "count"
@@ -334,9 +351,16 @@ fn generate_message_template_provider(
                 }
             }
 
+            let builder_computed_lets =
+                generate_field_computations(computed_fields, |from| quote! { self.#from });
+
             // Add computed field initializers
             for computed in computed_fields {
                 let field_name = &computed.field_name;
+                if !computed.is_from_result() {
+                    builder_to_struct_initializers.push(quote! { #field_name });
+                    continue;
+                }
                 let default_value = match computed.operation.as_str() {
                     "count"
                     | "count_type_info"
@@ -368,6 +392,7 @@ fn generate_message_template_provider(
                 impl #builder_name {
                     /// Set the message template and build the final result
                     pub fn with_message_template(self, template: impl Into<String>) -> #struct_name {
+                        #(#builder_computed_lets)*
                         #struct_name {
                             #(#builder_to_struct_initializers,)*
                             #field_name: Some(template.into()),
@@ -398,6 +423,7 @@ fn generate_message_template_provider(
                     /// Create a new instance with default message template
                     #[allow(clippy::too_many_arguments)]
                     pub fn new(#(#constructor_params),*) -> Self {
+                        #(#computed_lets)*
                         Self {
                             #(#field_initializers,)*
                         }
@@ -416,6 +442,33 @@ fn generate_message_template_provider(
     }
 }
 
+/// Generate `let` bindings for fields computed from other fields of the struct
+///
+/// `source` maps the `from` field name to the expression holding its value (a constructor
+/// parameter or a builder field).
+fn generate_field_computations(
+    computed_fields: &[ComputedField],
+    source: impl Fn(&syn::Ident) -> proc_macro2::TokenStream,
+) -> Vec<proc_macro2::TokenStream> {
+    computed_fields
+        .iter()
+        .filter(|computed| !computed.is_from_result())
+        .map(|computed| {
+            let field_name = &computed.field_name;
+            let from = source(&syn::Ident::new(&computed.from_field, field_name.span()));
+            let computation = match computed.operation.as_str() {
+                "count" => quote! { crate::tool::Countable::count(&#from) },
+                "duration_ms" => quote! { crate::tool::DurationMillis::duration_ms(&#from) },
+                "is_empty" => quote! { crate::tool::Countable::count(&#from) == 0 },
+                other => panic!(
+                    "Unknown computed operation for #[computed(from = ..)] on {field_name}: {other}"
+                ),
+            };
+            quote! { let #field_name = #computation; }
+        })
+        .collect()
+}
+
 /// Generate from_brp_client_response method
 fn generate_from_brp_client_response(
     struct_name: &syn::Ident,
@@ -431,12 +484,24 @@ fn generate_from_brp_client_response(
 
         if field_name == "result" && type_str.contains("Option < Value >") {
             field_initializers.push(quote! { result: value.clone() });
+        } else if type_str == "FormatCorrections" {
+            field_initializers.push(quote! {
+                #field_name: crate::brp_tools::FormatCorrections::new(
+                    format_corrections.clone(),
+                    format_corrected.clone(),
+                )
+            });
         } else if field_name == "format_corrections" {
             field_initializers.push(quote! {
                 format_corrections: if format_corrections.as_ref().map_or(true, |v| v.is_empty()) {
                     None
                 } else {
-                    format_corrections.clone()
+                    format_corrections.as_ref().map(|corrections| {
+                        corrections
+                            .iter()
+                            .map(|(_, correction)| correction.clone())
+                            .collect()
+                    })
                 }
             });
         } else if field_name == "format_corrected" {
@@ -495,6 +560,12 @@ fn generate_from_brp_client_response(
         let from_field = &computed.from_field;
         let operation = &computed.operation;
 
+        if !computed.is_from_result() {
+            panic!(
+                "#[computed(from = \"{from_field}\")] on {field_name} is not supported with #[brp_result] - BRP results are computed from the response value"
+            );
+        }
+
         // Map field names to parameter names
         let source = if from_field == "result" {
             quote! { value }
@@ -693,7 +764,7 @@ fn generate_from_brp_client_response(
     // Always use 3-parameter signature
     let params = quote! {
         value: Option<serde_json::Value>,
-        format_corrections: Option<Vec<serde_json::Value>>,
+        format_corrections: Option<Vec<(crate::brp_tools::Corrector, serde_json::Value)>>,
         format_corrected: Option<crate::brp_tools::FormatCorrectionStatus>,
     };
 
@@ -14,6 +14,11 @@ pub struct ComputedField {
     pub operation:  String,
 }
 
+impl ComputedField {
+    /// Whether this field is computed from the BRP response value (rather than another field)
+    pub fn is_from_result(&self) -> bool { self.from_field == "result" }
+}
+
 /// Parse placement attribute arguments
 pub fn parse_placement_attr(
     attr: &Attribute,
@@ -21,6 +26,8 @@ pub fn parse_placement_attr(
     field_type: &mut Option<String>,
     skip_if_none: &mut bool,
     result_operation: &mut Option<String>,
+    group: &mut Option<String>,
+    flatten: &mut bool,
 ) {
     let _ = attr.parse_nested_meta(|meta| {
         if meta.path.is_ident("from") {
@@ -41,6 +48,14 @@ pub fn parse_placement_attr(
             let s: syn::LitStr = value.parse()?;
             *result_operation = Some(s.value());
             Ok(())
+        } else if meta.path.is_ident("group") {
+            let value = meta.value()?;
+            let s: syn::LitStr = value.parse()?;
+            *group = Some(s.value());
+            Ok(())
+        } else if meta.path.is_ident("flatten") {
+            *flatten = true;
+            Ok(())
         } else {
             Err(meta.error("unsupported attribute"))
         }
@@ -48,13 +63,25 @@ pub fn parse_placement_attr(
 }
 
 /// Parse computed attribute arguments
-pub fn parse_computed_attr(attr: &Attribute, result_operation: &mut Option<String>) {
+///
+/// `from` names the struct field the value is computed from - without it the operation is
+/// applied to the BRP response value.
+pub fn parse_computed_attr(
+    attr: &Attribute,
+    result_operation: &mut Option<String>,
+    from_field: &mut Option<String>,
+) {
     let _ = attr.parse_nested_meta(|meta| {
         if meta.path.is_ident("operation") {
             let value = meta.value()?;
             let s: syn::LitStr = value.parse()?;
             *result_operation = Some(s.value());
             Ok(())
+        } else if meta.path.is_ident("from") {
+            let value = meta.value()?;
+            let s: syn::LitStr = value.parse()?;
+            *from_field = Some(s.value());
+            Ok(())
         } else {
             Err(meta.error("unsupported computed attribute"))
         }
@@ -84,21 +111,31 @@ pub fn generate_response_data_field(
     field_type: &Type,
     placement: &TokenStream,
     skip_if_none: bool,
+    group: Option<&str>,
 ) -> TokenStream {
     let field_name_str = field_name.to_string();
     let type_str = quote!(#field_type).to_string();
 
+    // Grouped metadata fields are nested under their group's object
+    let add_field = |value: TokenStream| match group {
+        Some(group) => quote! {
+            builder = builder.add_metadata_field_to_group(#group, #field_name_str, #value)?;
+        },
+        None => quote! {
+            builder = builder.add_field_to(#field_name_str, #value, #placement)?;
+        },
+    };
+
     // Handle Option types with skip_if_none
     if type_str.starts_with("Option <") && skip_if_none {
+        let add_field = add_field(quote! { val });
         quote! {
             if let Some(val) = &self.#field_name {
-                builder = builder.add_field_to(#field_name_str, val, #placement)?;
+                #add_field
             }
         }
     } else {
-        quote! {
-            builder = builder.add_field_to(#field_name_str, &self.#field_name, #placement)?;
-        }
+        add_field(quote! { &self.#field_name })
     }
 }
 
@@ -106,6 +143,7 @@ pub fn generate_response_data_field(
 pub fn extract_field_data(fields: &[&Field]) -> FieldExtractionResult {
     let mut field_placements = Vec::new();
     let mut response_data_fields = Vec::new();
+    let mut output_schema_fields = Vec::new();
     let mut computed_fields = Vec::new();
     let mut regular_fields = Vec::new();
     let mut message_template_field: Option<(Ident, Option<String>)> = None;
@@ -120,7 +158,11 @@ pub fn extract_field_data(fields: &[&Field]) -> FieldExtractionResult {
         let mut field_type_override = None;
         let mut skip_if_none = false;
         let mut is_computed = false;
+        let mut is_error_info = false;
         let mut result_operation = None;
+        let mut computed_from = None;
+        let mut group = None;
+        let mut flatten = false;
 
         for attr in &field.attrs {
             if attr.path().is_ident("to_metadata") {
@@ -131,6 +173,8 @@ pub fn extract_field_data(fields: &[&Field]) -> FieldExtractionResult {
                     &mut field_type_override,
                     &mut skip_if_none,
                     &mut result_operation,
+                    &mut group,
+                    &mut flatten,
                 );
             } else if attr.path().is_ident("to_result") {
                 placement = Some(quote! { crate::tool::FieldPlacement::Result });
@@ -140,22 +184,27 @@ pub fn extract_field_data(fields: &[&Field]) -> FieldExtractionResult {
                     &mut field_type_override,
                     &mut skip_if_none,
                     &mut result_operation,
+                    &mut group,
+                    &mut flatten,
                 );
             } else if attr.path().is_ident("to_error_info") {
                 placement = Some(quote! { crate::tool::FieldPlacement::ErrorInfo });
+                is_error_info = true;
                 parse_placement_attr(
                     attr,
                     &mut source_path,
                     &mut field_type_override,
                     &mut skip_if_none,
                     &mut result_operation,
+                    &mut group,
+                    &mut flatten,
                 );
             } else if attr.path().is_ident("to_call_info") {
                 // Skip fields marked with to_call_info as we no longer need them
                 continue;
             } else if attr.path().is_ident("computed") {
                 is_computed = true;
-                parse_computed_attr(attr, &mut result_operation);
+                parse_computed_attr(attr, &mut result_operation, &mut computed_from);
             } else if attr.path().is_ident("to_message") {
                 let template = parse_to_message_attr(attr);
                 message_template_field = Some((field_name.clone(), template));
@@ -173,7 +222,7 @@ pub fn extract_field_data(fields: &[&Field]) -> FieldExtractionResult {
             if let Some(operation) = result_operation {
                 computed_fields.push(ComputedField {
                     field_name: field_name.clone(),
-                    from_field: "result".to_string(), // Always operate on result
+                    from_field: computed_from.unwrap_or_else(|| "result".to_string()),
                     operation,
                 });
             }
@@ -181,14 +230,34 @@ pub fn extract_field_data(fields: &[&Field]) -> FieldExtractionResult {
             regular_fields.push((field_name.clone(), field_type.clone()));
         }
 
+        // A flattened field adds its own metadata fields - `FlattenedMetadata` places and
+        // describes them
+        if flatten {
+            response_data_fields.push(quote! {
+                builder = crate::tool::FlattenedMetadata::add_metadata(&self.#field_name, builder)?;
+            });
+            output_schema_fields.push(quote! {
+                <#field_type as crate::tool::FlattenedMetadata>::output_schema_fields(generator)
+            });
+            continue;
+        }
+
         // Only add placement info if there's a placement attribute
         if let Some(placement) = &placement {
             let field_name_str = field_name.to_string();
 
+            if group.is_some() && !field.attrs.iter().any(|a| a.path().is_ident("to_metadata")) {
+                panic!("`group` is only supported on #[to_metadata] fields ({field_name_str})");
+            }
+
             let source_path_token = source_path
                 .as_ref()
                 .map(|s| quote! { Some(#s) })
                 .unwrap_or_else(|| quote! { None });
+            let group_token = group
+                .as_ref()
+                .map(|g| quote! { Some(#g) })
+                .unwrap_or_else(|| quote! { None });
 
             field_placements.push(quote! {
                 crate::tool::FieldPlacementInfo {
@@ -196,6 +265,7 @@ pub fn extract_field_data(fields: &[&Field]) -> FieldExtractionResult {
                     placement: #placement,
                     source_path: #source_path_token,
                     skip_if_none: #skip_if_none,
+                    group: #group_token,
                 }
             });
 
@@ -204,13 +274,27 @@ pub fn extract_field_data(fields: &[&Field]) -> FieldExtractionResult {
                 field_type,
                 placement,
                 skip_if_none,
+                group.as_deref(),
             ));
+
+            // Only successful output (metadata and result) is described by the output schema
+            if !is_error_info {
+                output_schema_fields.push(quote! {
+                    [crate::tool::OutputSchemaField {
+                        field_name: #field_name_str,
+                        placement: #placement,
+                        group: #group_token,
+                        schema: generator.subschema_for::<#field_type>(),
+                    }]
+                });
+            }
         }
     }
 
     FieldExtractionResult {
         field_placements,
         response_data_fields,
+        output_schema_fields,
         computed_fields,
         regular_fields,
         message_template_field,
@@ -220,6 +304,7 @@ pub fn extract_field_data(fields: &[&Field]) -> FieldExtractionResult {
 pub struct FieldExtractionResult {
     pub field_placements:       Vec<TokenStream>,
     pub response_data_fields:   Vec<TokenStream>,
+    pub output_schema_fields:   Vec<TokenStream>,
     pub computed_fields:        Vec<ComputedField>,
     pub regular_fields:         Vec<(Ident, Type)>,
     pub message_template_field: Option<(Ident, Option<String>)>,